@@ -0,0 +1,47 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use openvm_circuit::arch::VmConfig;
+use openvm_sdk::config::SdkVmConfig;
+use openvm_stark_sdk::p3_baby_bear::BabyBear;
+use rustc_hash::FxHashMap;
+
+/// Compares opcode dispatch through the executor jump table with the hash map lookup it
+/// replaced, for a config with many extensions.
+fn benchmark_function(c: &mut Criterion) {
+    let config = SdkVmConfig::builder()
+        .system(Default::default())
+        .rv32i(Default::default())
+        .rv32m(Default::default())
+        .io(Default::default())
+        .keccak(Default::default())
+        .native(Default::default())
+        .bigint(Default::default())
+        .build();
+    let chip_complex = VmConfig::<BabyBear>::create_chip_complex(&config).unwrap();
+    let inventory = &chip_complex.inventory;
+    let opcodes: Vec<_> = inventory.opcodes().collect();
+    let hash_map: FxHashMap<_, _> = opcodes
+        .iter()
+        .enumerate()
+        .map(|(id, &opcode)| (opcode, id))
+        .collect();
+
+    let mut group = c.benchmark_group("dispatch");
+    group.bench_function("jump_table", |b| {
+        b.iter(|| {
+            for opcode in &opcodes {
+                black_box(inventory.get_executor(black_box(*opcode)));
+            }
+        })
+    });
+    group.bench_function("hash_map", |b| {
+        b.iter(|| {
+            for opcode in &opcodes {
+                black_box(hash_map.get(black_box(opcode)));
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, benchmark_function);
+criterion_main!(benches);
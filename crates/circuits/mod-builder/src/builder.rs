@@ -1,7 +1,13 @@
-use std::{cell::RefCell, ops::Deref, rc::Rc};
+use std::{
+    cell::RefCell,
+    cmp::{max, min},
+    convert::identity,
+    ops::{Add, Deref, Mul},
+    rc::Rc,
+};
 
 use num_bigint_dig::{BigInt, BigUint, Sign};
-use num_traits::Zero;
+use num_traits::{FromPrimitive, One, Zero};
 use openvm_circuit_primitives::{
     bigint::{
         check_carry_mod_to_zero::{CheckCarryModToZeroCols, CheckCarryModToZeroSubAir},
@@ -17,16 +23,38 @@ use openvm_stark_backend::{
     p3_air::{Air, AirBuilder, BaseAir},
     p3_field::{AbstractField, Field, PrimeField64},
     p3_matrix::Matrix,
+    p3_util::log2_ceil_usize,
     rap::{BaseAirWithPublicValues, PartitionedBaseAir},
 };
+use serde::{Deserialize, Serialize};
 
 use super::{FieldVariable, SymbolicExpr};
 
-#[derive(Clone)]
+#[derive(Clone, Default)]
 pub struct ExprBuilderConfig {
     pub modulus: BigUint,
     pub num_limbs: usize,
     pub limb_bits: usize,
+    pub reduction: ReductionStrategy,
+}
+
+/// How a constraint `expr = 0 mod p` is reduced before it is checked as `expr - q * p = 0`.
+///
+/// With `r = 2^(limb_bits * num_limbs) mod p`, the limbs of `expr` above `num_limbs` can be
+/// folded back by multiplying them with the limbs of `r`. For pseudo-Mersenne primes such as
+/// secp256k1's or 2^255 - 19, `r` only has a few small limbs, so the folded expression needs a much
+/// shorter quotient and fewer carries, at the cost of larger carries.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReductionStrategy {
+    /// Fold a constraint only if the folded carries fit in the range checker and the folded
+    /// constraint takes fewer columns.
+    #[default]
+    Auto,
+    /// Never fold, the quotient covers the whole expression.
+    Generic,
+    /// Fold every constraint that has limbs above `num_limbs`. Panics if the folded carries don't
+    /// fit in the range checker.
+    PseudoMersenne,
 }
 
 impl ExprBuilderConfig {
@@ -58,6 +86,12 @@ pub struct ExprBuilder {
     // The max bits to range check.
     pub range_checker_bits: usize,
 
+    pub reduction: ReductionStrategy,
+    /// Balanced limbs of 2^(limb_bits * num_limbs) mod p, used to fold the high limbs of a constraint.
+    pub fold_limbs: Vec<isize>,
+    // Whether each constraint is folded before the quotient is taken.
+    pub folded: Vec<bool>,
+
     // The number of limbs of the quotient for each constraint.
     pub q_limbs: Vec<usize>,
     // The number of limbs of the carries for each constraint.
@@ -85,6 +119,8 @@ pub struct ExprBuilder {
 impl ExprBuilder {
     pub fn new(config: ExprBuilderConfig, range_checker_bits: usize) -> Self {
         let prime_bigint = BigInt::from_biguint(Sign::Plus, config.modulus.clone());
+        let fold_constant =
+            (BigUint::one() << (config.limb_bits * config.num_limbs)) % &config.modulus;
         Self {
             prime: config.modulus.clone(),
             prime_bigint,
@@ -94,6 +130,9 @@ impl ExprBuilder {
             limb_bits: config.limb_bits,
             num_limbs: config.num_limbs,
             range_checker_bits,
+            reduction: config.reduction,
+            fold_limbs: big_uint_to_balanced_limbs(&fold_constant, config.limb_bits),
+            folded: vec![],
             num_variables: 0,
             constants: vec![],
             q_limbs: vec![],
@@ -160,6 +199,7 @@ impl ExprBuilder {
         self.computes.push(SymbolicExpr::Input(0));
         self.q_limbs.push(0);
         self.carry_limbs.push(0);
+        self.folded.push(false);
         (
             self.num_variables - 1,
             SymbolicExpr::Var(self.num_variables - 1),
@@ -187,11 +227,63 @@ impl ExprBuilder {
     }
 
     pub fn set_constraint(&mut self, index: usize, constraint: SymbolicExpr) {
-        let (q_limbs, carry_limbs) =
+        let (mut q_limbs, mut carry_limbs) =
             constraint.constraint_limbs(&self.prime, self.limb_bits, self.num_limbs);
+        let folded = match (self.reduction, self.folded_constraint_limbs(&constraint)) {
+            (ReductionStrategy::Auto, Some((folded_q_limbs, folded_carry_limbs, carry_bits))) => {
+                carry_bits <= self.range_checker_bits
+                    && folded_q_limbs + folded_carry_limbs < q_limbs + carry_limbs
+            }
+            (ReductionStrategy::PseudoMersenne, Some((_, _, carry_bits))) => {
+                assert!(
+                    carry_bits <= self.range_checker_bits,
+                    "folded carries need {} bits, the range checker only has {}",
+                    carry_bits,
+                    self.range_checker_bits
+                );
+                true
+            }
+            _ => false,
+        };
+        if folded {
+            (q_limbs, carry_limbs, _) = self.folded_constraint_limbs(&constraint).unwrap();
+        }
         self.constraints[index] = constraint;
         self.q_limbs[index] = q_limbs;
         self.carry_limbs[index] = carry_limbs;
+        self.folded[index] = folded;
+    }
+
+    // The number of limbs of q and of the carries, and the carry bits, of a constraint whose limbs
+    // above num_limbs are folded. None if folding doesn't shorten the constraint.
+    fn folded_constraint_limbs(&self, constraint: &SymbolicExpr) -> Option<(usize, usize, usize)> {
+        let expr_limbs = constraint.expr_limbs(self.num_limbs);
+        if expr_limbs <= self.num_limbs || self.fold_limbs.len() >= self.num_limbs {
+            return None;
+        }
+        let folded_limbs = max(
+            self.num_limbs,
+            expr_limbs - self.num_limbs + self.fold_limbs.len() - 1,
+        );
+        let fold_abs: usize = self.fold_limbs.iter().map(|r| r.unsigned_abs()).sum();
+        let limb_max_abs =
+            constraint.constraint_limb_max_abs(self.limb_bits, self.num_limbs) * (1 + fold_abs);
+
+        // Every limb is at most limb_max_abs, so |expr| <= limb_max_abs * sum(2^(limb_bits * i)).
+        let max_abs = BigUint::from_usize(limb_max_abs).unwrap()
+            * ((BigUint::one() << (self.limb_bits * folded_limbs)) - BigUint::one())
+            / BigUint::from_usize((1 << self.limb_bits) - 1).unwrap();
+        let max_q_abs = (max_abs + &self.prime - BigUint::one()) / &self.prime;
+        let q_limbs = max_q_abs.bits().div_ceil(self.limb_bits);
+        let p_limbs = self.prime_limbs.len();
+        let carry_limbs = max(folded_limbs, q_limbs + p_limbs - 1);
+
+        // The bound of expr - q * p as computed in CheckCarryModToZeroSubAir.
+        let limb_max_abs = limb_max_abs
+            + (1 << self.limb_bits) * ((1 << self.limb_bits) - 1) * min(q_limbs, p_limbs);
+        let (_, carry_bits) =
+            get_carry_max_abs_and_bits(log2_ceil_usize(limb_max_abs), self.limb_bits);
+        Some((q_limbs, carry_limbs, carry_bits))
     }
 
     pub fn set_compute(&mut self, index: usize, compute: SymbolicExpr) {
@@ -322,8 +414,16 @@ impl<AB: InteractionBuilder> SubAir<AB> for FieldExpr {
             builder.assert_bool(*flag);
         }
         for i in 0..self.constraints.len() {
-            let expr = self.constraints[i]
+            let mut expr = self.constraints[i]
                 .evaluate_overflow_expr::<AB>(&inputs, &vars, &constants, &flags);
+            if self.folded[i] {
+                expr = fold_high_limbs(
+                    expr,
+                    &self.fold_limbs,
+                    self.num_limbs,
+                    SymbolicExpr::isize_to_expr::<AB>,
+                );
+            }
             self.check_carry_mod_to_zero.eval(
                 builder,
                 (
@@ -419,9 +519,19 @@ impl<F: PrimeField64> TraceSubRowGenerator<F> for FieldExpr {
         }
         // We need to have all variables computed first because, e.g. constraints[2] might need variables[3].
         for i in 0..self.constraints.len() {
+            let mut expr = self.constraints[i].evaluate_overflow_isize(
+                &input_overflow,
+                &vars_overflow,
+                &constants,
+                &flags,
+            );
             // expr = q * p
-            let expr_bigint =
-                self.constraints[i].evaluate_bigint(&input_bigint, &vars_bigint, &flags);
+            let expr_bigint = if self.folded[i] {
+                expr = fold_high_limbs(expr, &self.fold_limbs, self.num_limbs, identity);
+                overflow_to_big_int(&expr, limb_bits)
+            } else {
+                self.constraints[i].evaluate_bigint(&input_bigint, &vars_bigint, &flags)
+            };
             let q = &expr_bigint / &self.prime_bigint;
             // If this is not true then the evaluated constraint is not divisible by p.
             debug_assert_eq!(expr_bigint, &q * &self.prime_bigint);
@@ -432,12 +542,6 @@ impl<F: PrimeField64> TraceSubRowGenerator<F> for FieldExpr {
             }
             let q_overflow = OverflowInt::from_canonical_signed_limbs(q_limbs.clone(), limb_bits);
             // compute carries of (expr - q * p)
-            let expr = self.constraints[i].evaluate_overflow_isize(
-                &input_overflow,
-                &vars_overflow,
-                &constants,
-                &flags,
-            );
             let expr = expr - q_overflow * prime_overflow.clone();
             let carries = expr.calculate_carries(limb_bits);
             assert_eq!(carries.len(), self.carry_limbs[i]); // If this fails, the carry limbs estimate is wrong.
@@ -557,3 +661,61 @@ fn load_overflow<AB: AirBuilder>(
     }
     result
 }
+
+// Folds the limbs of `expr` at index num_limbs and above onto the lower limbs, multiplied by
+// `fold_limbs`. The result is congruent to `expr` modulo p.
+fn fold_high_limbs<T>(
+    expr: OverflowInt<T>,
+    fold_limbs: &[isize],
+    num_limbs: usize,
+    convert: fn(isize) -> T,
+) -> OverflowInt<T>
+where
+    T: Add<Output = T> + Mul<Output = T> + Clone + Default,
+{
+    let (low, high) = expr.limbs().split_at(num_limbs);
+    let mut limbs = low.to_vec();
+    limbs.resize(
+        max(num_limbs, high.len() + fold_limbs.len() - 1),
+        T::default(),
+    );
+    for (i, limb) in high.iter().enumerate() {
+        for (j, &r) in fold_limbs.iter().enumerate() {
+            if r != 0 {
+                limbs[i + j] = limbs[i + j].clone() + limb.clone() * convert(r);
+            }
+        }
+    }
+    let fold_abs: usize = fold_limbs.iter().map(|r| r.unsigned_abs()).sum();
+    let limb_max_abs = expr.limb_max_abs() * (1 + fold_abs);
+    OverflowInt::from_computed_limbs(limbs, limb_max_abs, log2_ceil_usize(limb_max_abs))
+}
+
+fn overflow_to_big_int(expr: &OverflowInt<isize>, limb_bits: usize) -> BigInt {
+    expr.limbs()
+        .iter()
+        .rev()
+        .fold(BigInt::zero(), |acc, &limb| {
+            (acc << limb_bits) + BigInt::from_isize(limb).unwrap()
+        })
+}
+
+// Limbs in (-2^(limb_bits - 1), 2^(limb_bits - 1)], which keeps the folded limbs small.
+fn big_uint_to_balanced_limbs(x: &BigUint, limb_bits: usize) -> Vec<isize> {
+    let mut limbs: Vec<isize> = big_uint_to_limbs(x, limb_bits)
+        .into_iter()
+        .map(|limb| limb as isize)
+        .collect();
+    let mut i = 0;
+    while i < limbs.len() {
+        if limbs[i] > 1 << (limb_bits - 1) {
+            limbs[i] -= 1 << limb_bits;
+            if i + 1 == limbs.len() {
+                limbs.push(0);
+            }
+            limbs[i + 1] += 1;
+        }
+        i += 1;
+    }
+    limbs
+}
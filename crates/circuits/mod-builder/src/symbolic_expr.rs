@@ -426,7 +426,7 @@ impl SymbolicExpr {
         }
     }
 
-    fn isize_to_expr<AB: AirBuilder>(s: isize) -> AB::Expr {
+    pub(crate) fn isize_to_expr<AB: AirBuilder>(s: isize) -> AB::Expr {
         if s >= 0 {
             AB::Expr::from_canonical_usize(s as usize)
         } else {
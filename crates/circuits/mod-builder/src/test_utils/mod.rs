@@ -32,6 +32,7 @@ pub fn setup(prime: &BigUint) -> (Arc<VariableRangeCheckerChip>, Rc<RefCell<Expr
         modulus: prime.clone(),
         limb_bits: LIMB_BITS,
         num_limbs: 32,
+        ..Default::default()
     };
     let builder = ExprBuilder::new(config, range_checker.range_max_bits());
     (range_checker, Rc::new(RefCell::new(builder)))
@@ -1,7 +1,11 @@
-use std::{cell::RefCell, rc::Rc};
+use std::{cell::RefCell, rc::Rc, sync::Arc};
 
 use num_bigint_dig::BigUint;
-use openvm_circuit_primitives::{bigint::utils::*, TraceSubRowGenerator};
+use openvm_circuit_primitives::{
+    bigint::utils::*,
+    var_range::{VariableRangeCheckerBus, VariableRangeCheckerChip},
+    TraceSubRowGenerator,
+};
 use openvm_stark_backend::{
     p3_air::BaseAir, p3_field::AbstractField, p3_matrix::dense::RowMajorMatrix,
 };
@@ -10,7 +14,10 @@ use openvm_stark_sdk::{
     p3_baby_bear::BabyBear,
 };
 
-use crate::{test_utils::*, ExprBuilder, FieldExpr, FieldExprCols, FieldVariable, SymbolicExpr};
+use crate::{
+    test_utils::*, ExprBuilder, ExprBuilderConfig, FieldExpr, FieldExprCols, FieldVariable,
+    ReductionStrategy, SymbolicExpr,
+};
 
 const LIMB_BITS: usize = 8;
 
@@ -387,3 +394,79 @@ fn test_symbolic_limbs_mul() {
     let expected_carry = 63;
     test_symbolic_limbs(expr, expected_q, expected_carry);
 }
+
+// Multiplies two inputs modulo the Mersenne prime 2^61 - 1 with 8 limbs, for which
+// 2^64 = 8 mod p so the high limbs of the product fold onto the low ones.
+fn test_mul_with_reduction(reduction: ReductionStrategy, expected_width: usize) {
+    let prime = (BigUint::from(1u32) << 61) - BigUint::from(1u32);
+    let range_checker = Arc::new(VariableRangeCheckerChip::new(VariableRangeCheckerBus::new(
+        1, 17,
+    )));
+    let config = ExprBuilderConfig {
+        modulus: prime.clone(),
+        num_limbs: 8,
+        limb_bits: LIMB_BITS,
+        reduction,
+    };
+    let builder = ExprBuilder::new(config, range_checker.range_max_bits());
+    let builder = Rc::new(RefCell::new(builder));
+
+    let mut x1 = ExprBuilder::new_input(builder.clone());
+    let mut x2 = ExprBuilder::new_input(builder.clone());
+    let mut x3 = &mut x1 * &mut x2;
+    x3.save();
+    let builder = builder.borrow().clone();
+    assert_eq!(builder.fold_limbs, vec![8]);
+
+    let expr = FieldExpr::new(builder, range_checker.bus(), false);
+    let width = BaseAir::<BabyBear>::width(&expr);
+    assert_eq!(width, expected_width);
+
+    let x = generate_random_biguint(&prime);
+    let y = generate_random_biguint(&prime);
+    let expected = (&x * &y) % prime;
+    let inputs = vec![x, y];
+
+    let mut row = BabyBear::zero_vec(width);
+    expr.generate_subrow((&range_checker, inputs, vec![]), &mut row);
+    let FieldExprCols { vars, .. } = expr.load_vars(&row);
+    let generated = evaluate_biguint(&vars[0], LIMB_BITS);
+    assert_eq!(generated, expected);
+
+    let trace = RowMajorMatrix::new(row, width);
+    let range_trace = range_checker.generate_trace();
+
+    BabyBearBlake3Engine::run_simple_test_no_pis_fast(
+        any_rap_arc_vec![expr, range_checker.air],
+        vec![trace, range_trace],
+    )
+    .expect("Verification failed");
+}
+
+#[test]
+fn test_mul_generic_reduction() {
+    // 3 * 8 limbs of inputs and variable, 8 limbs of q and 15 carries, is_valid.
+    test_mul_with_reduction(ReductionStrategy::Generic, 48);
+}
+
+#[test]
+fn test_mul_auto_reduction() {
+    // The folded constraint only needs 3 limbs of q and 10 carries.
+    test_mul_with_reduction(ReductionStrategy::Auto, 38);
+    test_mul_with_reduction(ReductionStrategy::PseudoMersenne, 38);
+}
+
+#[test]
+fn test_auto_reduction_secp256k1() {
+    // Folding by 2^256 mod p = 2^32 + 977 needs carries wider than the range checker.
+    let prime = secp256k1_coord_prime();
+    let (_, builder) = setup(&prime);
+    let mut x1 = ExprBuilder::new_input(builder.clone());
+    let mut x2 = ExprBuilder::new_input(builder.clone());
+    let mut x3 = &mut x1 * &mut x2;
+    x3.save();
+    let builder = builder.borrow();
+    assert_eq!(builder.fold_limbs, vec![-47, 4, 0, 0, 1]);
+    assert_eq!(builder.folded, vec![false]);
+    assert_eq!(builder.q_limbs, vec![32]);
+}
@@ -141,6 +141,19 @@ impl VariableRangeCheckerChip {
         val_atomic.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
     }
 
+    /// Same as [Self::add_count], but records `mult` range checks of `value` at once.
+    pub fn add_count_mult(&self, value: u32, max_bits: usize, mult: u32) {
+        let idx = (1 << max_bits) + (value as usize);
+        assert!(
+            idx < self.count.len(),
+            "range exceeded: {} >= {}",
+            idx,
+            self.count.len()
+        );
+        let val_atomic = &self.count[idx];
+        val_atomic.fetch_add(mult, std::sync::atomic::Ordering::Relaxed);
+    }
+
     pub fn clear(&self) {
         for i in 0..self.count.len() {
             self.count[i].store(0, std::sync::atomic::Ordering::Relaxed);
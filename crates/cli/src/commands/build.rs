@@ -5,8 +5,8 @@ use eyre::Result;
 use openvm_build::{
     build_guest_package, find_unique_executable, get_package, GuestOptions, TargetFilter,
 };
-use openvm_sdk::{fs::write_exe_to_file, Sdk};
-use openvm_transpiler::{elf::Elf, openvm_platform::memory::MEM_SIZE};
+use openvm_sdk::{commit::AppExecutionCommit, fs::write_exe_with_commit_to_file, Sdk};
+use openvm_transpiler::{elf::Elf, openvm_platform::memory::MEM_SIZE, trim::reachable_pcs};
 
 use crate::{
     default::{DEFAULT_APP_CONFIG_PATH, DEFAULT_APP_EXE_PATH, DEFAULT_MANIFEST_DIR},
@@ -65,6 +65,13 @@ pub struct BuildArgs {
 
     #[arg(long, default_value = "release", help = "Build profile")]
     pub profile: String,
+
+    #[arg(
+        long,
+        default_value = "false",
+        help = "Removes instructions which are statically unreachable from the transpiled program"
+    )]
+    pub trim_unreachable: bool,
 }
 
 #[derive(Clone, clap::Args)]
@@ -119,12 +126,22 @@ pub(crate) fn build(build_args: &BuildArgs) -> Result<Option<PathBuf>> {
         println!("[openvm] Transpiling the package...");
         let output_path = &build_args.exe_output;
         let app_config = read_config_toml_or_default(&build_args.config)?;
-        let transpiler = app_config.app_vm_config.transpiler();
 
         let data = read(elf_path.clone())?;
         let elf = Elf::decode(&data, MEM_SIZE as u32)?;
-        let exe = Sdk.transpile(elf, transpiler)?;
-        write_exe_to_file(exe, output_path)?;
+        // Fails if the configured moduli differ from the ones declared by the guest
+        let app_vm_config = app_config.app_vm_config.with_moduli_from_elf(&elf)?;
+        let reachable = build_args.trim_unreachable.then(|| reachable_pcs(&elf));
+        let mut exe = Sdk.transpile(elf, app_vm_config.transpiler())?;
+        if let Some(reachable) = reachable {
+            let removed = exe.program.retain_pcs(|pc| reachable.contains(&pc));
+            println!("[openvm] Removed {removed} unreachable instructions");
+        }
+        // Record the exe commit so that `prove` can check that it proves the same exe
+        let committed_exe =
+            Sdk.commit_app_exe(app_config.app_fri_params.fri_params, exe.clone())?;
+        let exe_commit = AppExecutionCommit::compute_exe_commit(&app_vm_config, &committed_exe);
+        write_exe_with_commit_to_file(exe, exe_commit, output_path)?;
 
         println!(
             "[openvm] Successfully transpiled to {}",
@@ -7,17 +7,18 @@ use openvm_sdk::{
     commit::AppExecutionCommit,
     config::SdkVmConfig,
     fs::{
-        read_agg_pk_from_file, read_app_pk_from_file, read_exe_from_file, write_app_proof_to_file,
-        write_evm_proof_to_file,
+        read_agg_pk_from_file, read_app_pk_from_file, read_exe_and_commit_from_file,
+        write_app_proof_to_file, write_evm_proof_to_file,
     },
     keygen::AppProvingKey,
+    prover::CommittedExeCache,
     NonRootCommittedExe, Sdk, StdIn,
 };
 
 use crate::{
     default::{
         DEFAULT_AGG_PK_PATH, DEFAULT_APP_EXE_PATH, DEFAULT_APP_PK_PATH, DEFAULT_APP_PROOF_PATH,
-        DEFAULT_EVM_PROOF_PATH, DEFAULT_PARAMS_DIR,
+        DEFAULT_COMMITTED_EXE_CACHE_DIR, DEFAULT_EVM_PROOF_PATH, DEFAULT_PARAMS_DIR,
     },
     util::{read_to_stdin, Input},
 };
@@ -102,14 +103,23 @@ impl ProveCmd {
         StdIn,
     )> {
         let app_pk: Arc<AppProvingKey<SdkVmConfig>> = Arc::new(read_app_pk_from_file(app_pk)?);
-        let app_exe = read_exe_from_file(exe)?;
-        let committed_exe = Sdk.commit_app_exe(app_pk.app_fri_params(), app_exe)?;
+        let (app_exe, expected_exe_commit) = read_exe_and_commit_from_file(exe)?;
+        let committed_exe = Sdk.commit_app_exe_cached(
+            app_pk.app_fri_params(),
+            app_exe,
+            &CommittedExeCache::new(DEFAULT_COMMITTED_EXE_CACHE_DIR),
+        )?;
 
         let commits = AppExecutionCommit::compute(
             &app_pk.app_vm_pk.vm_config,
             &committed_exe,
             &app_pk.leaf_committed_exe,
         );
+        if expected_exe_commit.is_some_and(|commit| commit != commits.exe_commit) {
+            eyre::bail!(
+                "exe commit does not match the one recorded at build time; was the exe built with a different config?"
+            );
+        }
         println!("app_pk commit: {:?}", commits.app_config_commit_to_bn254());
         println!("exe commit: {:?}", commits.exe_commit_to_bn254());
 
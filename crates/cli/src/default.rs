@@ -6,6 +6,7 @@ pub const DEFAULT_MANIFEST_DIR: &str = ".";
 pub const DEFAULT_AGG_PK_PATH: &str = concat!(env!("HOME"), "/.openvm/agg.pk");
 pub const DEFAULT_VERIFIER_PATH: &str = concat!(env!("HOME"), "/.openvm/verifier.sol");
 pub const DEFAULT_PARAMS_DIR: &str = concat!(env!("HOME"), "/.openvm/params/");
+pub const DEFAULT_COMMITTED_EXE_CACHE_DIR: &str = concat!(env!("HOME"), "/.openvm/committed-exes/");
 
 pub const DEFAULT_APP_CONFIG_PATH: &str = "./openvm.toml";
 pub const DEFAULT_APP_EXE_PATH: &str = "./openvm/app.vmexe";
@@ -53,16 +53,28 @@ impl AppExecutionCommit<F> {
         assert!(
             app_exe.exe.program.max_num_public_values <= app_vm_config.system().num_public_values
         );
-        let hasher = vm_poseidon2_hasher();
-        let memory_dimensions = app_vm_config.system().memory_config.memory_dimensions();
-        let app_program_commit: [F; DIGEST_SIZE] =
-            app_exe.committed_program.prover_data.commit.into();
         let leaf_verifier_program_commit: [F; DIGEST_SIZE] = leaf_vm_verifier_exe
             .committed_program
             .prover_data
             .commit
             .into();
 
+        Self {
+            leaf_vm_verifier_commit: leaf_verifier_program_commit,
+            exe_commit: Self::compute_exe_commit(app_vm_config, app_exe),
+        }
+    }
+
+    /// Computes only [AppExecutionCommit::exe_commit], which does not depend on the leaf verifier.
+    pub fn compute_exe_commit<VC: VmConfig<F>>(
+        app_vm_config: &VC,
+        app_exe: &NonRootCommittedExe,
+    ) -> [F; DIGEST_SIZE] {
+        let hasher = vm_poseidon2_hasher();
+        let memory_dimensions = app_vm_config.system().memory_config.memory_dimensions();
+        let app_program_commit: [F; DIGEST_SIZE] =
+            app_exe.committed_program.prover_data.commit.into();
+
         let init_memory_commit = MemoryNode::tree_from_memory(
             memory_dimensions,
             &memory_image_to_equipartition(app_exe.exe.init_memory.clone()),
@@ -75,12 +87,7 @@ impl AppExecutionCommit<F> {
         let init_memory_hash = hasher.hash(&init_memory_commit);
         let pc_start_hash = hasher.hash(&padded_pc_start);
         let compress_1 = hasher.compress(&app_hash, &init_memory_hash);
-        let user_commit = hasher.compress(&compress_1, &pc_start_hash);
-
-        Self {
-            leaf_vm_verifier_commit: leaf_verifier_program_commit,
-            exe_commit: user_commit,
-        }
+        hasher.compress(&compress_1, &pc_start_hash)
     }
 
     pub fn app_config_commit_to_bn254(&self) -> Bn254Fr {
@@ -1,5 +1,6 @@
 use bon::Builder;
 use derive_more::derive::From;
+use eyre::bail;
 use openvm_algebra_circuit::{
     Fp2Extension, Fp2ExtensionExecutor, Fp2ExtensionPeriphery, ModularExtension,
     ModularExtensionExecutor, ModularExtensionPeriphery,
@@ -7,6 +8,8 @@ use openvm_algebra_circuit::{
 use openvm_algebra_transpiler::{Fp2TranspilerExtension, ModularTranspilerExtension};
 use openvm_bigint_circuit::{Int256, Int256Executor, Int256Periphery};
 use openvm_bigint_transpiler::Int256TranspilerExtension;
+use openvm_blake_circuit::{Blake, BlakeExecutor, BlakePeriphery};
+use openvm_blake_transpiler::BlakeTranspilerExtension;
 use openvm_circuit::{
     arch::{
         SystemConfig, SystemExecutor, SystemPeriphery, VmChipComplex, VmConfig, VmInventoryError,
@@ -25,15 +28,25 @@ use openvm_pairing_circuit::{
     PairingExtension, PairingExtensionExecutor, PairingExtensionPeriphery,
 };
 use openvm_pairing_transpiler::PairingTranspilerExtension;
+use openvm_poseidon2_circuit::{Rv32Poseidon2, Rv32Poseidon2Executor, Rv32Poseidon2Periphery};
+use openvm_poseidon2_transpiler::Poseidon2TranspilerExtension;
+use openvm_ripemd160_circuit::{Ripemd160, Ripemd160Executor, Ripemd160Periphery};
+use openvm_ripemd160_transpiler::Ripemd160TranspilerExtension;
 use openvm_rv32im_circuit::{
-    Rv32I, Rv32IExecutor, Rv32IPeriphery, Rv32Io, Rv32IoExecutor, Rv32IoPeriphery, Rv32M,
-    Rv32MExecutor, Rv32MPeriphery,
+    Rv32A, Rv32AExecutor, Rv32APeriphery, Rv32F, Rv32FExecutor, Rv32FPeriphery, Rv32I,
+    Rv32IExecutor, Rv32IPeriphery, Rv32Io, Rv32IoExecutor, Rv32IoPeriphery, Rv32M, Rv32MExecutor,
+    Rv32MPeriphery, Rv32Zba, Rv32ZbaExecutor, Rv32ZbaPeriphery, Rv32Zbb, Rv32ZbbExecutor,
+    Rv32ZbbPeriphery, Rv32Zicsr, Rv32ZicsrExecutor, Rv32ZicsrPeriphery,
 };
 use openvm_rv32im_transpiler::{
-    Rv32ITranspilerExtension, Rv32IoTranspilerExtension, Rv32MTranspilerExtension,
+    Rv32ATranspilerExtension, Rv32FTranspilerExtension, Rv32ITranspilerExtension,
+    Rv32IoTranspilerExtension, Rv32MFusedTranspilerExtension, Rv32MTranspilerExtension,
+    Rv32ZbaTranspilerExtension, Rv32ZbbTranspilerExtension, Rv32ZicsrTranspilerExtension,
 };
+use openvm_sha2_circuit::{Sha2, Sha2Executor, Sha2Periphery};
+use openvm_sha2_transpiler::Sha2TranspilerExtension;
 use openvm_stark_backend::p3_field::PrimeField32;
-use openvm_transpiler::transpiler::Transpiler;
+use openvm_transpiler::{elf::Elf, transpiler::Transpiler};
 use serde::{Deserialize, Serialize};
 
 use crate::F;
@@ -45,7 +58,16 @@ pub struct SdkVmConfig {
 
     pub rv32i: Option<UnitStruct>,
     pub io: Option<UnitStruct>,
+    pub zicsr: Option<UnitStruct>,
+    pub zba: Option<UnitStruct>,
+    pub zbb: Option<UnitStruct>,
+    pub rv32a: Option<UnitStruct>,
+    pub rv32f: Option<UnitStruct>,
     pub keccak: Option<UnitStruct>,
+    pub sha2: Option<UnitStruct>,
+    pub blake: Option<UnitStruct>,
+    pub ripemd160: Option<UnitStruct>,
+    pub poseidon2: Option<UnitStruct>,
     pub native: Option<UnitStruct>,
 
     pub rv32m: Option<Rv32M>,
@@ -65,8 +87,26 @@ pub enum SdkVmConfigExecutor<F: PrimeField32> {
     #[any_enum]
     Io(Rv32IoExecutor<F>),
     #[any_enum]
+    Zicsr(Rv32ZicsrExecutor<F>),
+    #[any_enum]
+    Zba(Rv32ZbaExecutor<F>),
+    #[any_enum]
+    Zbb(Rv32ZbbExecutor<F>),
+    #[any_enum]
+    Rv32a(Rv32AExecutor<F>),
+    #[any_enum]
+    Rv32f(Rv32FExecutor<F>),
+    #[any_enum]
     Keccak(Keccak256Executor<F>),
     #[any_enum]
+    Sha2(Sha2Executor<F>),
+    #[any_enum]
+    Blake(BlakeExecutor<F>),
+    #[any_enum]
+    Ripemd160(Ripemd160Executor<F>),
+    #[any_enum]
+    Poseidon2(Rv32Poseidon2Executor<F>),
+    #[any_enum]
     Native(NativeExecutor<F>),
     #[any_enum]
     Rv32m(Rv32MExecutor<F>),
@@ -91,8 +131,26 @@ pub enum SdkVmConfigPeriphery<F: PrimeField32> {
     #[any_enum]
     Io(Rv32IoPeriphery<F>),
     #[any_enum]
+    Zicsr(Rv32ZicsrPeriphery<F>),
+    #[any_enum]
+    Zba(Rv32ZbaPeriphery<F>),
+    #[any_enum]
+    Zbb(Rv32ZbbPeriphery<F>),
+    #[any_enum]
+    Rv32a(Rv32APeriphery<F>),
+    #[any_enum]
+    Rv32f(Rv32FPeriphery<F>),
+    #[any_enum]
     Keccak(Keccak256Periphery<F>),
     #[any_enum]
+    Sha2(Sha2Periphery<F>),
+    #[any_enum]
+    Blake(BlakePeriphery<F>),
+    #[any_enum]
+    Ripemd160(Ripemd160Periphery<F>),
+    #[any_enum]
+    Poseidon2(Rv32Poseidon2Periphery<F>),
+    #[any_enum]
     Native(NativePeriphery<F>),
     #[any_enum]
     Rv32m(Rv32MPeriphery<F>),
@@ -117,11 +175,42 @@ impl SdkVmConfig {
         if self.io.is_some() {
             transpiler = transpiler.with_extension(Rv32IoTranspilerExtension);
         }
+        if self.zicsr.is_some() {
+            transpiler = transpiler.with_extension(Rv32ZicsrTranspilerExtension);
+        }
+        if self.zba.is_some() {
+            transpiler = transpiler.with_extension(Rv32ZbaTranspilerExtension);
+        }
+        if self.zbb.is_some() {
+            transpiler = transpiler.with_extension(Rv32ZbbTranspilerExtension);
+        }
+        if self.rv32a.is_some() {
+            transpiler = transpiler.with_extension(Rv32ATranspilerExtension);
+        }
+        if self.rv32f.is_some() {
+            transpiler = transpiler.with_extension(Rv32FTranspilerExtension);
+        }
         if self.keccak.is_some() {
             transpiler = transpiler.with_extension(Keccak256TranspilerExtension);
         }
-        if self.rv32m.is_some() {
-            transpiler = transpiler.with_extension(Rv32MTranspilerExtension);
+        if self.sha2.is_some() {
+            transpiler = transpiler.with_extension(Sha2TranspilerExtension);
+        }
+        if self.blake.is_some() {
+            transpiler = transpiler.with_extension(BlakeTranspilerExtension);
+        }
+        if self.ripemd160.is_some() {
+            transpiler = transpiler.with_extension(Ripemd160TranspilerExtension);
+        }
+        if self.poseidon2.is_some() {
+            transpiler = transpiler.with_extension(Poseidon2TranspilerExtension);
+        }
+        if let Some(rv32m) = &self.rv32m {
+            if rv32m.fuse_mulh_mul {
+                transpiler = transpiler.with_extension(Rv32MFusedTranspilerExtension);
+            } else {
+                transpiler = transpiler.with_extension(Rv32MTranspilerExtension);
+            }
         }
         if self.bigint.is_some() {
             transpiler = transpiler.with_extension(Int256TranspilerExtension);
@@ -140,6 +229,29 @@ impl SdkVmConfig {
         }
         transpiler
     }
+
+    /// Configures the modular extension with the moduli that the guest declared with
+    /// `moduli_init!`, as recorded in `elf`. Fails if the modular extension is already
+    /// configured with different moduli, because the guest addresses the chips by modulus index.
+    pub fn with_moduli_from_elf(mut self, elf: &Elf) -> eyre::Result<Self> {
+        if elf.supported_moduli.is_empty() {
+            return Ok(self);
+        }
+        match &self.modular {
+            Some(modular) if modular.supported_modulus != elf.supported_moduli => {
+                bail!(
+                    "Configured moduli {:?} do not match the moduli {:?} declared by the guest",
+                    modular.supported_modulus,
+                    elf.supported_moduli
+                );
+            }
+            Some(_) => {}
+            None => {
+                self.modular = Some(ModularExtension::new(elf.supported_moduli.clone()));
+            }
+        }
+        Ok(self)
+    }
 }
 
 impl<F: PrimeField32> VmConfig<F> for SdkVmConfig {
@@ -165,9 +277,36 @@ impl<F: PrimeField32> VmConfig<F> for SdkVmConfig {
         if self.io.is_some() {
             complex = complex.extend(&Rv32Io)?;
         }
+        if self.zicsr.is_some() {
+            complex = complex.extend(&Rv32Zicsr)?;
+        }
+        if self.zba.is_some() {
+            complex = complex.extend(&Rv32Zba)?;
+        }
+        if self.zbb.is_some() {
+            complex = complex.extend(&Rv32Zbb)?;
+        }
+        if self.rv32a.is_some() {
+            complex = complex.extend(&Rv32A)?;
+        }
+        if self.rv32f.is_some() {
+            complex = complex.extend(&Rv32F)?;
+        }
         if self.keccak.is_some() {
             complex = complex.extend(&Keccak256)?;
         }
+        if self.sha2.is_some() {
+            complex = complex.extend(&Sha2)?;
+        }
+        if self.blake.is_some() {
+            complex = complex.extend(&Blake)?;
+        }
+        if self.ripemd160.is_some() {
+            complex = complex.extend(&Ripemd160)?;
+        }
+        if self.poseidon2.is_some() {
+            complex = complex.extend(&Rv32Poseidon2)?;
+        }
         if self.native.is_some() {
             complex = complex.extend(&Native)?;
         }
@@ -245,12 +384,66 @@ impl From<Rv32Io> for UnitStruct {
     }
 }
 
+impl From<Rv32Zicsr> for UnitStruct {
+    fn from(_: Rv32Zicsr) -> Self {
+        UnitStruct {}
+    }
+}
+
+impl From<Rv32Zba> for UnitStruct {
+    fn from(_: Rv32Zba) -> Self {
+        UnitStruct {}
+    }
+}
+
+impl From<Rv32Zbb> for UnitStruct {
+    fn from(_: Rv32Zbb) -> Self {
+        UnitStruct {}
+    }
+}
+
+impl From<Rv32A> for UnitStruct {
+    fn from(_: Rv32A) -> Self {
+        UnitStruct {}
+    }
+}
+
+impl From<Rv32F> for UnitStruct {
+    fn from(_: Rv32F) -> Self {
+        UnitStruct {}
+    }
+}
+
 impl From<Keccak256> for UnitStruct {
     fn from(_: Keccak256) -> Self {
         UnitStruct {}
     }
 }
 
+impl From<Sha2> for UnitStruct {
+    fn from(_: Sha2) -> Self {
+        UnitStruct {}
+    }
+}
+
+impl From<Blake> for UnitStruct {
+    fn from(_: Blake) -> Self {
+        UnitStruct {}
+    }
+}
+
+impl From<Ripemd160> for UnitStruct {
+    fn from(_: Ripemd160) -> Self {
+        UnitStruct {}
+    }
+}
+
+impl From<Rv32Poseidon2> for UnitStruct {
+    fn from(_: Rv32Poseidon2) -> Self {
+        UnitStruct {}
+    }
+}
+
 impl From<Native> for UnitStruct {
     fn from(_: Native) -> Self {
         UnitStruct {}
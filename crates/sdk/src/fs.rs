@@ -3,9 +3,14 @@ use std::{
     path::Path,
 };
 
-use eyre::Result;
-use openvm_circuit::arch::{instructions::exe::VmExe, VmConfig};
+use eyre::{bail, Result};
+use openvm_circuit::arch::{
+    instructions::exe::{MemoryImage, VmExe},
+    VmConfig,
+};
+use openvm_native_compiler::ir::DIGEST_SIZE;
 use openvm_native_recursion::halo2::{wrapper::EvmVerifier, EvmProof};
+use openvm_stark_backend::p3_field::{AbstractField, PrimeField32};
 use serde::{de::DeserializeOwned, Serialize};
 
 use crate::{
@@ -14,12 +19,145 @@ use crate::{
     F, SC,
 };
 
+/// Magic bytes at the start of every file in the versioned artifact format.
+pub const ARTIFACT_MAGIC: [u8; 4] = *b"OVMA";
+/// Version of the artifact format. Files of other versions are rejected.
+pub const ARTIFACT_VERSION: u16 = 1;
+const ARTIFACT_HEADER_LEN: usize = 8 + 32 + 4 * DIGEST_SIZE;
+const ZSTD_LEVEL: i32 = 3;
+
+/// What a file in the versioned artifact format contains.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ArtifactKind {
+    Exe = 0,
+    MemoryImage = 1,
+    /// A [NonRootCommittedExe](crate::NonRootCommittedExe), see
+    /// [CommittedExeCache](crate::prover::CommittedExeCache).
+    CommittedExe = 2,
+}
+
+/// Header of the versioned artifact format. A file is laid out as
+/// - [ARTIFACT_MAGIC], then the version as a little-endian `u16`, the [ArtifactKind] byte and a
+///   byte which is 1 if `exe_commit` is present,
+/// - the 32 byte blake3 hash of the payload,
+/// - `exe_commit`, as little-endian `u32`s, or zeros if absent,
+/// - the zstd-compressed bitcode encoding of the payload.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ArtifactHeader {
+    pub version: u16,
+    pub kind: ArtifactKind,
+    /// blake3 hash of the uncompressed payload.
+    pub content_hash: [u8; 32],
+    /// For exes, the exe commit of
+    /// [AppExecutionCommit::compute_exe_commit](crate::commit::AppExecutionCommit::compute_exe_commit),
+    /// if it was known when the exe was written.
+    pub exe_commit: Option<[F; DIGEST_SIZE]>,
+}
+
+/// Encodes `data` in the versioned artifact format.
+pub fn encode_artifact<T: Serialize>(
+    kind: ArtifactKind,
+    data: &T,
+    exe_commit: Option<[F; DIGEST_SIZE]>,
+) -> Result<Vec<u8>> {
+    let payload = bitcode::serialize(data)?;
+    let mut bytes = Vec::with_capacity(ARTIFACT_HEADER_LEN + payload.len() / 2);
+    bytes.extend_from_slice(&ARTIFACT_MAGIC);
+    bytes.extend_from_slice(&ARTIFACT_VERSION.to_le_bytes());
+    bytes.push(kind as u8);
+    bytes.push(exe_commit.is_some() as u8);
+    bytes.extend_from_slice(blake3::hash(&payload).as_bytes());
+    for x in exe_commit.unwrap_or([F::ZERO; DIGEST_SIZE]) {
+        bytes.extend_from_slice(&x.as_canonical_u32().to_le_bytes());
+    }
+    zstd::stream::copy_encode(payload.as_slice(), &mut bytes, ZSTD_LEVEL)?;
+    Ok(bytes)
+}
+
+/// Decodes a value of the given `kind` in the versioned artifact format, checking its version and
+/// content hash.
+pub fn decode_artifact<T: DeserializeOwned>(
+    kind: ArtifactKind,
+    bytes: &[u8],
+) -> Result<(ArtifactHeader, T)> {
+    if bytes.len() < ARTIFACT_HEADER_LEN || bytes[..4] != ARTIFACT_MAGIC {
+        bail!("Not an OpenVM artifact");
+    }
+    let version = u16::from_le_bytes([bytes[4], bytes[5]]);
+    if version != ARTIFACT_VERSION {
+        bail!("Unsupported artifact version {version}, expected {ARTIFACT_VERSION}");
+    }
+    if bytes[6] != kind as u8 {
+        bail!(
+            "Expected an artifact of kind {kind:?}, found kind {}",
+            bytes[6]
+        );
+    }
+    let content_hash: [u8; 32] = bytes[8..40].try_into().unwrap();
+    let exe_commit = match bytes[7] {
+        0 => None,
+        1 => Some(std::array::from_fn(|i| {
+            let word = bytes[40 + 4 * i..44 + 4 * i].try_into().unwrap();
+            F::from_canonical_u32(u32::from_le_bytes(word))
+        })),
+        flag => bail!("Invalid exe commit flag {flag}"),
+    };
+
+    let payload = zstd::stream::decode_all(&bytes[ARTIFACT_HEADER_LEN..])?;
+    if *blake3::hash(&payload).as_bytes() != content_hash {
+        bail!("Artifact content hash mismatch");
+    }
+    let header = ArtifactHeader {
+        version,
+        kind,
+        content_hash,
+        exe_commit,
+    };
+    Ok((header, bitcode::deserialize(&payload)?))
+}
+
 pub fn read_exe_from_file<P: AsRef<Path>>(path: P) -> Result<VmExe<F>> {
-    read_from_file_bitcode(path)
+    Ok(read_exe_and_commit_from_file(path)?.0)
+}
+
+/// Reads an exe along with the exe commit it was written with, if any.
+pub fn read_exe_and_commit_from_file<P: AsRef<Path>>(
+    path: P,
+) -> Result<(VmExe<F>, Option<[F; DIGEST_SIZE]>)> {
+    let (header, exe) = decode_artifact(ArtifactKind::Exe, &read(path)?)?;
+    Ok((exe, header.exe_commit))
 }
 
 pub fn write_exe_to_file<P: AsRef<Path>>(exe: VmExe<F>, path: P) -> Result<()> {
-    write_to_file_bitcode(path, exe)
+    write_to_file_bytes(path, encode_artifact(ArtifactKind::Exe, &exe, None)?)
+}
+
+/// Writes an exe along with its exe commit, which readers can check the exe against once they
+/// have committed it.
+pub fn write_exe_with_commit_to_file<P: AsRef<Path>>(
+    exe: VmExe<F>,
+    exe_commit: [F; DIGEST_SIZE],
+    path: P,
+) -> Result<()> {
+    write_to_file_bytes(
+        path,
+        encode_artifact(ArtifactKind::Exe, &exe, Some(exe_commit))?,
+    )
+}
+
+pub fn read_memory_image_from_file<P: AsRef<Path>>(path: P) -> Result<MemoryImage<F>> {
+    Ok(decode_artifact(ArtifactKind::MemoryImage, &read(path)?)?.1)
+}
+
+pub fn write_memory_image_to_file<P: AsRef<Path>>(
+    memory_image: &MemoryImage<F>,
+    path: P,
+) -> Result<()> {
+    write_to_file_bytes(
+        path,
+        encode_artifact(ArtifactKind::MemoryImage, memory_image, None)?,
+    )
 }
 
 pub fn read_app_pk_from_file<VC: VmConfig<F>, P: AsRef<Path>>(
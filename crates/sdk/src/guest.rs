@@ -0,0 +1,196 @@
+use std::{
+    env,
+    fs::read,
+    path::{Path, PathBuf},
+};
+
+use eyre::Result;
+use openvm_build::{
+    build_guest_package, find_unique_executable, get_package, GuestOptions, TargetFilter,
+};
+use openvm_circuit::arch::instructions::exe::VmExe;
+use openvm_transpiler::{
+    elf::Elf, openvm_platform::memory::MEM_SIZE, transpiler::Transpiler, trim::reachable_pcs,
+    FromElf,
+};
+use tracing::info;
+
+use crate::{config::SdkVmConfig, F};
+
+/// Builds a guest crate for the OpenVM target and turns it into a [VmExe].
+///
+/// Cargo is invoked with the guest target, linker arguments and `build-std` flags set up by
+/// `openvm-build`, so callers only need to describe what to build:
+/// ```ignore
+/// let exe = GuestBuilder::new("path/to/guest")
+///     .features(["std"])
+///     .bin("fibonacci")
+///     .reproducible(true)
+///     .build(transpiler)?;
+/// ```
+#[derive(Clone, Default)]
+pub struct GuestBuilder {
+    pkg_dir: PathBuf,
+    guest_opts: GuestOptions,
+    target_filter: Option<TargetFilter>,
+    reproducible: bool,
+    trim_unreachable: bool,
+}
+
+impl GuestBuilder {
+    pub fn new(pkg_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            pkg_dir: pkg_dir.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Start from existing [GuestOptions]. Options set on the builder afterwards are added to them.
+    pub fn with_guest_options(mut self, guest_opts: GuestOptions) -> Self {
+        self.guest_opts = guest_opts;
+        self
+    }
+
+    pub fn with_target_filter(mut self, target_filter: Option<TargetFilter>) -> Self {
+        self.target_filter = target_filter;
+        self
+    }
+
+    pub fn features<S: AsRef<str>>(mut self, features: impl IntoIterator<Item = S>) -> Self {
+        self.guest_opts = self.guest_opts.with_features(features);
+        self
+    }
+
+    /// Cargo profile to build with. Defaults to `release`, or `dev` if `OPENVM_BUILD_DEBUG` is set.
+    pub fn profile(mut self, profile: impl Into<String>) -> Self {
+        self.guest_opts = self.guest_opts.with_profile(profile.into());
+        self
+    }
+
+    pub fn target_dir(mut self, target_dir: impl AsRef<Path>) -> Self {
+        self.guest_opts = self.guest_opts.with_target_dir(target_dir);
+        self
+    }
+
+    /// Extra arguments to pass to `cargo build`.
+    pub fn cargo_args<S: AsRef<str>>(mut self, args: impl IntoIterator<Item = S>) -> Self {
+        self.guest_opts = self.guest_opts.with_options(args);
+        self
+    }
+
+    /// Extra flags to pass to rustc, in addition to the ones required by the guest target.
+    pub fn rustc_flags<S: AsRef<str>>(mut self, flags: impl IntoIterator<Item = S>) -> Self {
+        self.guest_opts = self.guest_opts.with_rustc_flags(flags);
+        self
+    }
+
+    /// Build only the binary target `name`.
+    pub fn bin(self, name: impl Into<String>) -> Self {
+        self.target(name, "bin")
+    }
+
+    /// Build only the example target `name`.
+    pub fn example(self, name: impl Into<String>) -> Self {
+        self.target(name, "example")
+    }
+
+    /// When set, the build uses the versions pinned in `Cargo.lock`, a single codegen unit, and
+    /// strips machine-specific paths from the ELF, so that the same sources produce the same ELF
+    /// on any machine with the same toolchain.
+    pub fn reproducible(mut self, reproducible: bool) -> Self {
+        self.reproducible = reproducible;
+        self
+    }
+
+    /// When set, instructions which are statically unreachable are removed from the transpiled
+    /// program. See [reachable_pcs] for the limits of the analysis.
+    pub fn trim_unreachable(mut self, trim_unreachable: bool) -> Self {
+        self.trim_unreachable = trim_unreachable;
+        self
+    }
+
+    pub fn build_elf(&self) -> Result<Elf> {
+        let guest_opts = self.resolved_guest_options();
+        let pkg = get_package(&self.pkg_dir);
+        let target_dir = match build_guest_package(&pkg, &guest_opts, None, &self.target_filter) {
+            Ok(target_dir) => target_dir,
+            Err(Some(code)) => {
+                return Err(eyre::eyre!("Failed to build guest: code = {}", code));
+            }
+            Err(None) => {
+                return Err(eyre::eyre!(
+                    "Failed to build guest (OPENVM_SKIP_BUILD is set)"
+                ));
+            }
+        };
+
+        let elf_path = find_unique_executable(&self.pkg_dir, target_dir, &self.target_filter)?;
+        let data = read(&elf_path)?;
+        Elf::decode(&data, MEM_SIZE as u32)
+    }
+
+    /// Builds the guest and transpiles it with `transpiler`.
+    pub fn build(&self, transpiler: Transpiler<F>) -> Result<VmExe<F>> {
+        let elf = self.build_elf()?;
+        self.transpile(elf, transpiler)
+    }
+
+    /// Builds the guest and transpiles it with the transpiler of `vm_config`. The moduli declared
+    /// by the guest are checked against, or added to, the config, which is returned alongside the
+    /// exe.
+    pub fn build_for(&self, vm_config: SdkVmConfig) -> Result<(VmExe<F>, SdkVmConfig)> {
+        let elf = self.build_elf()?;
+        let vm_config = vm_config.with_moduli_from_elf(&elf)?;
+        let exe = self.transpile(elf, vm_config.transpiler())?;
+        Ok((exe, vm_config))
+    }
+
+    fn transpile(&self, elf: Elf, transpiler: Transpiler<F>) -> Result<VmExe<F>> {
+        let reachable = self.trim_unreachable.then(|| reachable_pcs(&elf));
+        let mut exe = VmExe::from_elf(elf, transpiler)?;
+        if let Some(reachable) = reachable {
+            let removed = exe.program.retain_pcs(|pc| reachable.contains(&pc));
+            info!("removed {removed} unreachable instructions");
+        }
+        Ok(exe)
+    }
+
+    fn target(mut self, name: impl Into<String>, kind: &str) -> Self {
+        self.target_filter = Some(TargetFilter {
+            name: name.into(),
+            kind: kind.to_string(),
+        });
+        self
+    }
+
+    fn resolved_guest_options(&self) -> GuestOptions {
+        if !self.reproducible {
+            return self.guest_opts.clone();
+        }
+        let mut remaps = vec![(self.pkg_dir.clone(), "/guest".to_string())];
+        let home = env::var_os("HOME").map(PathBuf::from);
+        let cargo_home = env::var_os("CARGO_HOME")
+            .map(PathBuf::from)
+            .or_else(|| home.as_ref().map(|home| home.join(".cargo")));
+        let rustup_home = env::var_os("RUSTUP_HOME")
+            .map(PathBuf::from)
+            .or_else(|| home.as_ref().map(|home| home.join(".rustup")));
+        remaps.extend(cargo_home.map(|dir| (dir, "/cargo".to_string())));
+        remaps.extend(rustup_home.map(|dir| (dir, "/rustup".to_string())));
+
+        let mut rustc_flags = vec!["-C".to_string(), "codegen-units=1".to_string()];
+        for (from, to) in remaps {
+            // Canonicalize so that the prefix matches the absolute paths cargo passes to rustc.
+            let from = from.canonicalize().unwrap_or(from);
+            rustc_flags.push(format!("--remap-path-prefix={}={to}", from.display()));
+        }
+        let mut guest_opts = self.guest_opts.clone().with_rustc_flags(rustc_flags);
+        // `cargo_command` already passes `--locked` when `OPENVM_BUILD_LOCKED` is set.
+        if env::var("OPENVM_BUILD_LOCKED").is_err()
+            && !guest_opts.options.iter().any(|opt| opt == "--locked")
+        {
+            guest_opts = guest_opts.with_options(["--locked"]);
+        }
+        guest_opts
+    }
+}
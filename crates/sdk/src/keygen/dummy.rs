@@ -49,7 +49,7 @@ pub(super) fn compute_root_proof_heights(
     root_exe: VmExe<F>,
     dummy_internal_proof: &Proof<SC>,
 ) -> (Vec<usize>, VmComplexTraceHeights) {
-    let num_user_public_values = root_vm_config.system.num_public_values - 2 * DIGEST_SIZE;
+    let num_user_public_values = root_vm_config.system.num_public_values - 3 * DIGEST_SIZE;
     let root_input = RootVmVerifierInput {
         proofs: vec![dummy_internal_proof.clone()],
         public_values: vec![F::ZERO; num_user_public_values],
@@ -92,6 +92,11 @@ where
                     <= config.app_fri_params.fri_params.max_constraint_degree()
             );
             assert!(config.app_vm_config.system().continuation_enabled);
+            // The aggregation verifiers do not check the carry-over values between segments.
+            assert!(
+                config.app_vm_config.system().carry_over.is_empty(),
+                "carry-over values are not supported by the SDK"
+            );
             VmProvingKey {
                 fri_params: config.app_fri_params.fri_params,
                 vm_config: config.app_vm_config.clone(),
@@ -258,7 +263,7 @@ impl AggStarkProvingKey {
             .vm_config
             .system
             .num_public_values
-            - (2 * DIGEST_SIZE)
+            - (3 * DIGEST_SIZE)
     }
 }
 
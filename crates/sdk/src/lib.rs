@@ -1,14 +1,12 @@
 extern crate core;
 
-use std::{fs::read, panic::catch_unwind, path::Path, sync::Arc};
+use std::{panic::catch_unwind, path::Path, sync::Arc};
 
 use commit::commit_app_exe;
 use config::AppConfig;
 use eyre::Result;
 use keygen::{AppProvingKey, AppVerifyingKey};
-use openvm_build::{
-    build_guest_package, find_unique_executable, get_package, GuestOptions, TargetFilter,
-};
+use openvm_build::{GuestOptions, TargetFilter};
 use openvm_circuit::{
     arch::{instructions::exe::VmExe, ExecutionError, VmConfig, VmExecutor},
     system::{memory::tree::public_values::extract_public_values, program::trace::VmCommittedExe},
@@ -34,7 +32,6 @@ use openvm_stark_sdk::{
 };
 use openvm_transpiler::{
     elf::Elf,
-    openvm_platform::memory::MEM_SIZE,
     transpiler::{Transpiler, TranspilerError},
     FromElf,
 };
@@ -51,11 +48,13 @@ pub mod verifier;
 mod stdin;
 pub use stdin::*;
 pub mod fs;
+mod guest;
+pub use guest::*;
 
 use crate::{
     config::AggConfig,
     keygen::AggProvingKey,
-    prover::{AppProver, ContinuationProver},
+    prover::{AppProver, CommittedExeCache, ContinuationProver},
 };
 
 pub(crate) type SC = BabyBearPoseidon2Config;
@@ -73,22 +72,10 @@ impl Sdk {
         pkg_dir: P,
         target_filter: &Option<TargetFilter>,
     ) -> Result<Elf> {
-        let pkg = get_package(pkg_dir.as_ref());
-        let target_dir = match build_guest_package(&pkg, &guest_opts, None, target_filter) {
-            Ok(target_dir) => target_dir,
-            Err(Some(code)) => {
-                return Err(eyre::eyre!("Failed to build guest: code = {}", code));
-            }
-            Err(None) => {
-                return Err(eyre::eyre!(
-                    "Failed to build guest (OPENVM_SKIP_BUILD is set)"
-                ));
-            }
-        };
-
-        let elf_path = find_unique_executable(pkg_dir, target_dir, target_filter)?;
-        let data = read(&elf_path)?;
-        Elf::decode(&data, MEM_SIZE as u32)
+        GuestBuilder::new(pkg_dir.as_ref())
+            .with_guest_options(guest_opts)
+            .with_target_filter(target_filter.clone())
+            .build_elf()
     }
 
     pub fn transpile(
@@ -128,6 +115,17 @@ impl Sdk {
         Ok(committed_exe)
     }
 
+    /// Same as [Sdk::commit_app_exe], but reuses the committed exe from `cache` if the same exe
+    /// was already committed with the same parameters.
+    pub fn commit_app_exe_cached(
+        &self,
+        app_fri_params: FriParameters,
+        exe: VmExe<F>,
+        cache: &CommittedExeCache,
+    ) -> Result<Arc<NonRootCommittedExe>> {
+        cache.get_or_commit(app_fri_params, exe)
+    }
+
     pub fn app_keygen<VC: VmConfig<F>>(&self, config: AppConfig<VC>) -> Result<AppProvingKey<VC>>
     where
         VC::Executor: Chip<SC>,
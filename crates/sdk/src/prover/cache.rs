@@ -0,0 +1,77 @@
+use std::{
+    fs::{read, rename},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use eyre::Result;
+use openvm_circuit::arch::instructions::exe::VmExe;
+use openvm_stark_sdk::config::FriParameters;
+use tracing::{info, warn};
+
+use crate::{
+    commit::commit_app_exe,
+    fs::{decode_artifact, encode_artifact, write_to_file_bytes, ArtifactKind},
+    NonRootCommittedExe, F,
+};
+
+/// Disk-backed cache of committed app exes, so that proving the same guest again skips committing
+/// its program. Each entry is keyed by the content hash of the exe and the hash of the FRI
+/// parameters it was committed with.
+#[derive(Clone, Debug)]
+pub struct CommittedExeCache {
+    dir: PathBuf,
+}
+
+impl CommittedExeCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Returns the committed exe from the cache, or commits it and stores it in the cache.
+    /// Entries which cannot be read, e.g. because they were written by another version, are
+    /// recomputed and overwritten.
+    pub fn get_or_commit(
+        &self,
+        app_fri_params: FriParameters,
+        exe: VmExe<F>,
+    ) -> Result<Arc<NonRootCommittedExe>> {
+        let path = self.entry_path(&app_fri_params, &exe)?;
+        if path.exists() {
+            match read(&path)
+                .map_err(eyre::Error::from)
+                .and_then(|bytes| decode_artifact(ArtifactKind::CommittedExe, &bytes))
+            {
+                Ok((_, committed_exe)) => {
+                    info!("loaded committed exe from {}", path.display());
+                    return Ok(Arc::new(committed_exe));
+                }
+                Err(err) => warn!("ignoring cached committed exe {}: {err}", path.display()),
+            }
+        }
+
+        let committed_exe = commit_app_exe(app_fri_params, exe);
+        // Written to a temporary file first so that concurrent readers never see a partial entry.
+        let tmp_path = path.with_extension("tmp");
+        write_to_file_bytes(
+            &tmp_path,
+            encode_artifact(ArtifactKind::CommittedExe, committed_exe.as_ref(), None)?,
+        )?;
+        rename(&tmp_path, &path)?;
+        Ok(committed_exe)
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn entry_path(&self, app_fri_params: &FriParameters, exe: &VmExe<F>) -> Result<PathBuf> {
+        let exe_hash = blake3::hash(&bitcode::serialize(exe)?);
+        let config_hash = blake3::hash(&bitcode::serialize(app_fri_params)?);
+        Ok(self.dir.join(format!(
+            "{}-{}.committed",
+            exe_hash.to_hex(),
+            &config_hash.to_hex()[..16]
+        )))
+    }
+}
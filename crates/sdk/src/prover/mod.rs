@@ -10,6 +10,8 @@ mod agg;
 pub use agg::*;
 mod app;
 pub use app::*;
+mod cache;
+pub use cache::*;
 use openvm_native_recursion::halo2::utils::Halo2ParamsReader;
 
 mod halo2;
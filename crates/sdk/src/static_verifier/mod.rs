@@ -48,14 +48,14 @@ impl RootVerifierProvingKey {
 
     pub fn generate_dummy_root_proof(&self, dummy_internal_proof: Proof<SC>) -> Proof<RootSC> {
         let prover = RootVerifierLocalProver::new(self.clone());
-        // 2 * DIGEST_SIZE for exe_commit and leaf_commit
+        // 3 * DIGEST_SIZE for exe_commit, leaf_commit and kv_store_root
         let num_public_values = prover
             .root_verifier_pk
             .vm_pk
             .vm_config
             .system
             .num_public_values
-            - 2 * DIGEST_SIZE;
+            - 3 * DIGEST_SIZE;
         SingleSegmentVmProver::prove(
             &prover,
             RootVmVerifierInput {
@@ -120,11 +120,13 @@ fn build_static_verifier_operations(
         let pvs = RootVmVerifierPvs::from_flatten(public_values);
         let exe_commit = compress_babybear_var_to_bn254(&mut builder, pvs.exe_commit);
         let leaf_commit = compress_babybear_var_to_bn254(&mut builder, pvs.leaf_verifier_commit);
-        let num_public_values = 2 + pvs.public_values.len();
+        let kv_store_root = compress_babybear_var_to_bn254(&mut builder, pvs.kv_store_root);
+        let num_public_values = 3 + pvs.public_values.len();
         builder.static_commit_public_value(0, exe_commit);
         builder.static_commit_public_value(1, leaf_commit);
+        builder.static_commit_public_value(2, kv_store_root);
         for (i, x) in pvs.public_values.into_iter().enumerate() {
-            builder.static_commit_public_value(i + 2, x);
+            builder.static_commit_public_value(i + 3, x);
         }
         builder.cycle_tracker_end("VerifierProgram");
         num_public_values
@@ -11,7 +11,10 @@ use openvm_native_compiler::{ir::Config, prelude::*};
 use openvm_native_recursion::{digest::DigestVariable, vars::StarkProofVariable};
 use openvm_stark_sdk::openvm_stark_backend::p3_field::AbstractField;
 
-use crate::verifier::internal::types::InternalVmVerifierPvs;
+use crate::verifier::{
+    internal::types::InternalVmVerifierPvs,
+    utils::{assign_array_to_slice, eq_felt_slice},
+};
 
 pub mod non_leaf;
 pub mod types;
@@ -90,6 +93,38 @@ pub fn assert_or_assign_memory_pvs<C: Config>(
     builder.assign(&dst.final_root, proof_pvs.final_root);
 }
 
+/// Merges the key-value store root of a proof into `dst`. A zero root means that no lookup was
+/// made, and all other roots must agree.
+pub fn assert_or_assign_kv_store_root<C: Config>(
+    builder: &mut Builder<C>,
+    dst: &[Felt<C::F>; DIGEST_SIZE],
+    proof_idx: RVar<C::N>,
+    proof_root: &[Felt<C::F>; DIGEST_SIZE],
+) {
+    let zero: [Felt<C::F>; DIGEST_SIZE] = array::from_fn(|_| builder.eval(C::F::ZERO));
+    builder.if_eq(proof_idx, RVar::zero()).then_or_else(
+        |builder| {
+            builder.assign(dst, *proof_root);
+        },
+        |builder| {
+            let dst_is_zero = eq_felt_slice(builder, dst, &zero);
+            builder.if_eq(dst_is_zero, RVar::one()).then_or_else(
+                |builder| {
+                    builder.assign(dst, *proof_root);
+                },
+                |builder| {
+                    let proof_root_is_zero = eq_felt_slice(builder, proof_root, &zero);
+                    builder
+                        .if_eq(proof_root_is_zero, RVar::zero())
+                        .then(|builder| {
+                            builder.assert_eq::<[_; DIGEST_SIZE]>(*dst, *proof_root);
+                        });
+                },
+            );
+        },
+    );
+}
+
 pub fn get_program_commit<C: Config>(
     builder: &mut Builder<C>,
     proof: &StarkProofVariable<C>,
@@ -139,6 +174,27 @@ pub fn get_memory_pvs<C: Config>(
     }
 }
 
+/// Returns the root exposed by the key-value store AIR of an app VM proof, or zero if the proof
+/// has no trace for the AIR, which happens when the segment made no lookup.
+pub fn get_kv_store_root<C: Config>(
+    builder: &mut Builder<C>,
+    proof: &StarkProofVariable<C>,
+    kv_store_air_id: usize,
+) -> [Felt<C::F>; DIGEST_SIZE] {
+    let root: [Felt<C::F>; DIGEST_SIZE] = array::from_fn(|_| builder.eval(C::F::ZERO));
+    builder
+        .range(0, proof.per_air.len())
+        .for_each(|i, builder| {
+            let air_proof = builder.get(&proof.per_air, i);
+            builder
+                .if_eq(air_proof.air_id, RVar::from(kv_store_air_id))
+                .then(|builder| {
+                    assign_array_to_slice(builder, &root, &air_proof.public_values, 0);
+                });
+        });
+    root
+}
+
 /// Asserts that a single segment VM  exits successfully.
 pub fn assert_single_segment_vm_exit_successfully<C: Config>(
     builder: &mut Builder<C>,
@@ -10,9 +10,9 @@ use openvm_stark_sdk::openvm_stark_backend::p3_field::PrimeField32;
 
 use crate::verifier::{
     common::{
-        assert_or_assign_connector_pvs, assert_or_assign_memory_pvs,
-        assert_required_air_for_agg_vm_present, assert_single_segment_vm_exit_successfully,
-        get_program_commit, types::VmVerifierPvs,
+        assert_or_assign_connector_pvs, assert_or_assign_kv_store_root,
+        assert_or_assign_memory_pvs, assert_required_air_for_agg_vm_present,
+        assert_single_segment_vm_exit_successfully, get_program_commit, types::VmVerifierPvs,
     },
     internal::types::InternalVmVerifierPvs,
     utils::{assign_array_to_slice, eq_felt_slice},
@@ -80,6 +80,12 @@ impl<C: Config> NonLeafVerifierVariables<C> {
                 i,
                 &proof_vm_pvs.vm_verifier_pvs.memory,
             );
+            assert_or_assign_kv_store_root(
+                builder,
+                &pvs.kv_store_root,
+                i,
+                &proof_vm_pvs.vm_verifier_pvs.kv_store_root,
+            );
             // This is only needed when `is_terminate` but branching here won't save much, so we
             // always assign it.
             builder.assign(
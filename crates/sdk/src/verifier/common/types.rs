@@ -26,6 +26,9 @@ pub struct VmVerifierPvs<T> {
     /// The merkle root of all public values. This is only meaningful when the last segment is
     /// aggregated by this circuit.
     pub public_values_commit: [T; DIGEST_SIZE],
+    /// The root of the key-value store that the lookups of all the segments this circuit
+    /// aggregates were proven against, or zero if none of them looked a key up.
+    pub kv_store_root: [T; DIGEST_SIZE],
 }
 
 impl<F: PrimeField32> VmVerifierPvs<Felt<F>> {
@@ -43,6 +46,7 @@ impl<F: PrimeField32> VmVerifierPvs<Felt<F>> {
                 final_root: array::from_fn(|_| builder.uninit()),
             },
             public_values_commit: array::from_fn(|_| builder.uninit()),
+            kv_store_root: array::from_fn(|_| builder.uninit()),
         }
     }
 }
@@ -1,3 +1,5 @@
+use std::array;
+
 use openvm_circuit::{
     arch::{instructions::program::Program, SystemConfig},
     system::memory::tree::public_values::PUBLIC_VALUES_ADDRESS_SPACE_OFFSET,
@@ -19,9 +21,9 @@ use openvm_stark_sdk::{
 use crate::{
     verifier::{
         common::{
-            assert_or_assign_connector_pvs, assert_or_assign_memory_pvs,
-            assert_required_air_for_app_vm_present, get_connector_pvs, get_memory_pvs,
-            get_program_commit, types::VmVerifierPvs,
+            assert_or_assign_connector_pvs, assert_or_assign_kv_store_root,
+            assert_or_assign_memory_pvs, assert_required_air_for_app_vm_present, get_connector_pvs,
+            get_kv_store_root, get_memory_pvs, get_program_commit, types::VmVerifierPvs,
         },
         leaf::types::UserPublicValuesRootProof,
         utils::VariableP2Compressor,
@@ -45,6 +47,9 @@ impl LeafVmVerifierConfig {
         app_vm_vk: &MultiStarkVerifyingKey<BabyBearPoseidon2Config>,
     ) -> Program<F> {
         let m_advice = new_from_inner_multi_vk(app_vm_vk);
+        let kv_store_air_id = self
+            .app_system_config
+            .kv_store_air_id(app_vm_vk.per_air.len());
         let mut builder = Builder::<C>::default();
 
         {
@@ -83,6 +88,17 @@ impl LeafVmVerifierConfig {
 
                 let proof_memory_pvs = get_memory_pvs(builder, &proof);
                 assert_or_assign_memory_pvs(builder, &pvs.memory, i, &proof_memory_pvs);
+
+                let proof_kv_store_root: [Felt<F>; DIGEST_SIZE] = match kv_store_air_id {
+                    Some(air_id) => get_kv_store_root(builder, &proof, air_id),
+                    None => array::from_fn(|_| builder.eval(F::ZERO)),
+                };
+                assert_or_assign_kv_store_root(
+                    builder,
+                    &pvs.kv_store_root,
+                    i,
+                    &proof_kv_store_root,
+                );
             });
             builder.cycle_tracker_end("VerifyProofs");
             builder.cycle_tracker_start("ExtractPublicValuesCommit");
@@ -27,8 +27,8 @@ impl AggStarkConfig {
     }
     pub fn root_verifier_vm_config(&self) -> NativeConfig {
         NativeConfig::aggregation(
-            // app_commit + leaf_verifier_commit + public_values
-            DIGEST_SIZE * 2 + self.max_num_user_public_values,
+            // app_commit + leaf_verifier_commit + kv_store_root + public_values
+            DIGEST_SIZE * 3 + self.max_num_user_public_values,
             SBOX_SIZE.min(self.root_fri_params.max_constraint_degree()),
         )
     }
@@ -97,6 +97,7 @@ impl RootVmVerifierConfig {
                     merged_pvs.connector.initial_pc,
                 ),
                 leaf_verifier_commit: expected_leaf_commit,
+                kv_store_root: merged_pvs.kv_store_root,
                 public_values: public_values_vec,
             };
             pvs.flatten()
@@ -19,6 +19,9 @@ pub struct RootVmVerifierPvs<T> {
     pub exe_commit: [T; DIGEST_SIZE],
     /// The commitment of the leaf verifier program, which commits the VM config of App VM.
     pub leaf_verifier_commit: [T; DIGEST_SIZE],
+    /// The root of the key-value store that the lookups of the App VM execution were proven
+    /// against, or zero if it made no lookup.
+    pub kv_store_root: [T; DIGEST_SIZE],
     /// Raw public values from App VM execution.
     pub public_values: Vec<T>,
 }
@@ -42,6 +45,7 @@ impl<F: PrimeField32> RootVmVerifierPvs<Felt<F>> {
         Self {
             exe_commit: array::from_fn(|_| builder.uninit()),
             leaf_verifier_commit: array::from_fn(|_| builder.uninit()),
+            kv_store_root: array::from_fn(|_| builder.uninit()),
             public_values: (0..num_public_values).map(|_| builder.uninit()).collect(),
         }
     }
@@ -51,16 +55,21 @@ impl<F: Copy> RootVmVerifierPvs<F> {
     pub fn flatten(self) -> Vec<F> {
         let mut ret = self.exe_commit.to_vec();
         ret.extend(self.leaf_verifier_commit);
+        ret.extend(self.kv_store_root);
         ret.extend(self.public_values);
         ret
     }
     pub fn from_flatten(flatten: Vec<F>) -> Self {
         let exe_commit = flatten[..DIGEST_SIZE].try_into().unwrap();
         let leaf_verifier_commit = flatten[DIGEST_SIZE..2 * DIGEST_SIZE].try_into().unwrap();
-        let public_values = flatten[2 * DIGEST_SIZE..].to_vec();
+        let kv_store_root = flatten[2 * DIGEST_SIZE..3 * DIGEST_SIZE]
+            .try_into()
+            .unwrap();
+        let public_values = flatten[3 * DIGEST_SIZE..].to_vec();
         Self {
             exe_commit,
             leaf_verifier_commit,
+            kv_store_root,
             public_values,
         }
     }
@@ -3,8 +3,8 @@ use std::{borrow::Borrow, path::PathBuf, sync::Arc};
 use openvm_build::GuestOptions;
 use openvm_circuit::{
     arch::{
-        hasher::poseidon2::vm_poseidon2_hasher, ExecutionError, SingleSegmentVmExecutor,
-        SystemConfig, VmConfig, VmExecutor,
+        hasher::poseidon2::vm_poseidon2_hasher, instructions::exe::VmExe, ExecutionError,
+        SingleSegmentVmExecutor, SystemConfig, VmConfig, VmExecutor,
     },
     system::{memory::tree::public_values::UserPublicValuesProof, program::trace::VmCommittedExe},
 };
@@ -13,13 +13,16 @@ use openvm_native_compiler::{conversion::CompilerOptions, prelude::*};
 use openvm_native_recursion::{halo2::utils::CacheHalo2ParamsReader, types::InnerConfig};
 use openvm_rv32im_transpiler::{Rv32ITranspilerExtension, Rv32MTranspilerExtension};
 use openvm_sdk::{
+    commit::AppExecutionCommit,
     config::{AggConfig, AggStarkConfig, AppConfig, Halo2Config},
+    fs::{decode_artifact, encode_artifact, ArtifactKind},
     keygen::AppProvingKey,
+    prover::CommittedExeCache,
     verifier::{
         common::types::VmVerifierPvs,
         leaf::types::{LeafVmVerifierInput, UserPublicValuesRootProof},
     },
-    Sdk, StdIn,
+    GuestBuilder, Sdk, StdIn,
 };
 use openvm_stark_sdk::{
     config::{
@@ -211,6 +214,8 @@ fn test_public_values_and_leaf_verification() {
             leaf_vm_pvs.public_values_commit,
             pv_root_proof.public_values_commit
         );
+        // The app VM has no key-value store.
+        assert_eq!(leaf_vm_pvs.kv_store_root, [F::ZERO; DIGEST_SIZE]);
     }
 
     // Failure: The public value root proof has a wrong public values commit.
@@ -299,3 +304,74 @@ fn test_sdk_guest_build_and_transpile() {
         .with_extension(Rv32MTranspilerExtension);
     let _exe = sdk.transpile(one, transpiler).unwrap();
 }
+
+#[test]
+fn test_guest_builder() {
+    let mut pkg_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).to_path_buf();
+    pkg_dir.push("example");
+    // The example is not checked in with a `Cargo.lock`, so it cannot be built `--locked`.
+    let builder = GuestBuilder::new(&pkg_dir).profile("release");
+    let one = builder.build_elf().unwrap();
+    let two = Sdk
+        .build(GuestOptions::default(), &pkg_dir, &Default::default())
+        .unwrap();
+    assert_eq!(one.instructions, two.instructions);
+    let transpiler = || {
+        Transpiler::<F>::default()
+            .with_extension(Rv32ITranspilerExtension)
+            .with_extension(Rv32MTranspilerExtension)
+    };
+    let exe = builder.build(transpiler()).unwrap();
+    let one = Sdk.transpile(one, transpiler()).unwrap();
+    assert_eq!(exe.pc_start, one.pc_start);
+    assert_eq!(exe.init_memory, one.init_memory);
+}
+
+#[test]
+fn test_exe_artifact_roundtrip() {
+    let app_log_blowup = 1;
+    let app_config = small_test_app_config(app_log_blowup);
+    let app_committed_exe = app_committed_exe_for_test(app_log_blowup);
+    let exe_commit =
+        AppExecutionCommit::compute_exe_commit(&app_config.app_vm_config, &app_committed_exe);
+
+    let mut bytes =
+        encode_artifact(ArtifactKind::Exe, &app_committed_exe.exe, Some(exe_commit)).unwrap();
+    let (header, exe) = decode_artifact::<VmExe<F>>(ArtifactKind::Exe, &bytes).unwrap();
+    assert_eq!(header.exe_commit, Some(exe_commit));
+    let recommitted_exe = Sdk
+        .commit_app_exe(app_config.app_fri_params.fri_params, exe)
+        .unwrap();
+    assert_eq!(
+        AppExecutionCommit::compute_exe_commit(&app_config.app_vm_config, &recommitted_exe),
+        exe_commit
+    );
+
+    assert!(decode_artifact::<VmExe<F>>(ArtifactKind::MemoryImage, &bytes).is_err());
+    // Corrupt the content hash
+    bytes[8] ^= 1;
+    assert!(decode_artifact::<VmExe<F>>(ArtifactKind::Exe, &bytes).is_err());
+}
+
+#[test]
+fn test_committed_exe_cache() {
+    let app_log_blowup = 1;
+    let fri_params = standard_fri_params_with_100_bits_conjectured_security(app_log_blowup);
+    let exe = app_committed_exe_for_test(app_log_blowup).exe.clone();
+    let cache_dir = tempfile::tempdir().unwrap();
+    let cache = CommittedExeCache::new(cache_dir.path());
+
+    let committed = Sdk
+        .commit_app_exe_cached(fri_params, exe.clone(), &cache)
+        .unwrap();
+    assert_eq!(std::fs::read_dir(cache_dir.path()).unwrap().count(), 1);
+    let cached = Sdk
+        .commit_app_exe_cached(fri_params, exe.clone(), &cache)
+        .unwrap();
+    assert_eq!(committed.get_program_commit(), cached.get_program_commit());
+
+    // Different parameters get a separate entry
+    let fri_params = standard_fri_params_with_100_bits_conjectured_security(app_log_blowup + 1);
+    Sdk.commit_app_exe_cached(fri_params, exe, &cache).unwrap();
+    assert_eq!(std::fs::read_dir(cache_dir.path()).unwrap().count(), 2);
+}
@@ -349,7 +349,7 @@ pub fn build_guest_package(
 }
 
 /// A filter for selecting a target from a package.
-#[derive(Default)]
+#[derive(Clone, Debug, Default)]
 pub struct TargetFilter {
     /// The target name to match.
     pub name: String,
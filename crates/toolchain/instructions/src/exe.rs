@@ -1,4 +1,4 @@
-use std::collections::BTreeMap;
+use std::{collections::BTreeMap, fmt};
 
 use openvm_stark_backend::p3_field::Field;
 use serde::{Deserialize, Serialize};
@@ -9,6 +9,10 @@ use crate::program::Program;
 pub type MemoryImage<F> = BTreeMap<(u32, u32), F>;
 /// Stores the starting address, end address, and name of a set of function.
 pub type FnBounds = BTreeMap<u32, FnBound>;
+/// Map from the pc of the first instruction of each row of the guest's DWARF line table to its
+/// source location. The location of a pc is that of the closest row at or below it, see
+/// [source_location].
+pub type SourceLines = BTreeMap<u32, SourceLocation>;
 
 /// Executable program for OpenVM.
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
@@ -25,6 +29,9 @@ pub struct VmExe<F> {
     pub init_memory: MemoryImage<F>,
     /// Starting + ending bounds for each function.
     pub fn_bounds: FnBounds,
+    /// Source line of each pc, if the ELF had DWARF line info.
+    #[serde(default)]
+    pub source_lines: SourceLines,
 }
 
 impl<F> VmExe<F> {
@@ -34,6 +41,7 @@ impl<F> VmExe<F> {
             pc_start: 0,
             init_memory: BTreeMap::new(),
             fn_bounds: Default::default(),
+            source_lines: Default::default(),
         }
     }
     pub fn with_pc_start(mut self, pc_start: u32) -> Self {
@@ -58,3 +66,23 @@ pub struct FnBound {
     pub end: u32,
     pub name: String,
 }
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceLocation {
+    pub file: String,
+    pub line: u32,
+}
+
+impl fmt::Display for SourceLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.file, self.line)
+    }
+}
+
+/// Returns the source location of `pc` in `source_lines`, if any.
+pub fn source_location(source_lines: &SourceLines, pc: u32) -> Option<&SourceLocation> {
+    source_lines
+        .range(..=pc)
+        .next_back()
+        .map(|(_, location)| location)
+}
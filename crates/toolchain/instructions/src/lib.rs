@@ -88,6 +88,16 @@ pub enum PublishOpcode {
     PUBLISH,
 }
 
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, EnumCount, EnumIter, FromRepr, UsizeOpcode,
+)]
+#[opcode_offset = 0x121]
+#[repr(usize)]
+pub enum KvStoreOpcode {
+    /// `[a]_d <- value of key [b]_e` in the key-value store of the system.
+    LOOKUP,
+}
+
 #[derive(
     Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, EnumCount, EnumIter, FromRepr, UsizeOpcode,
 )]
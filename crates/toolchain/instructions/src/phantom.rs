@@ -15,4 +15,14 @@ pub enum SysPhantom {
     CtStart,
     /// End tracing
     CtEnd,
+    /// Puts the performance counters of the current segment in front of the hint stream, each as
+    /// 4 little-endian bytes of a saturated `u32`: cycles executed in the segment, cycles executed
+    /// in all segments, timestamp, maximum trace height and the maximum segment length. Hints
+    /// already in the stream are read after the counters. The counters are only constrained as
+    /// hints.
+    HintPerfCounters,
+    /// Forwards a UTF-8 message from guest memory to the host log. Operands `a` and `b` point to
+    /// the message pointer and length, in the register address space of the system config's
+    /// `GuestLogConfig`. The message is unconstrained, so it is for diagnostics only.
+    DebugLog,
 }
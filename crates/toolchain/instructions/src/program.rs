@@ -58,6 +58,29 @@ impl<F: Field> Program<F> {
         }
     }
 
+    /// Like [Self::new_without_debug_infos], but `None` entries leave a gap in the program, e.g.
+    /// for data words in the middle of the instructions.
+    pub fn new_without_debug_infos_with_option(
+        instructions: &[Option<Instruction<F>>],
+        step: u32,
+        pc_base: u32,
+        max_num_public_values: usize,
+    ) -> Self {
+        assert!(
+            instructions.is_empty()
+                || pc_base + (instructions.len() as u32 - 1) * step <= MAX_ALLOWED_PC
+        );
+        Self {
+            instructions_and_debug_infos: instructions
+                .iter()
+                .map(|instruction| instruction.clone().map(|instruction| (instruction, None)))
+                .collect(),
+            step,
+            pc_base,
+            max_num_public_values,
+        }
+    }
+
     /// We assume that pc_start = pc_base = 0 everywhere except the RISC-V programs, until we need otherwise
     /// We use [DEFAULT_PC_STEP] for consistency with RISC-V
     pub fn from_instructions_and_debug_infos(
@@ -148,6 +171,23 @@ impl<F: Field> Program<F> {
             .flatten()
     }
 
+    /// Removes the instructions whose pc does not satisfy `keep`, so that they no longer occupy
+    /// rows of the program trace. Returns the number of instructions removed.
+    pub fn retain_pcs(&mut self, mut keep: impl FnMut(u32) -> bool) -> usize {
+        let mut removed = 0;
+        for (index, entry) in self.instructions_and_debug_infos.iter_mut().enumerate() {
+            let pc = self.pc_base + self.step * index as u32;
+            if entry.is_some() && !keep(pc) {
+                *entry = None;
+                removed += 1;
+            }
+        }
+        while matches!(self.instructions_and_debug_infos.last(), Some(None)) {
+            self.instructions_and_debug_infos.pop();
+        }
+        removed
+    }
+
     pub fn push_instruction_and_debug_info(
         &mut self,
         instruction: Instruction<F>,
@@ -87,7 +87,7 @@ mod tests {
     use alloc::vec;
 
     use super::*;
-    use crate::io::read_vec;
+    use crate::io::{read_page, read_vec, HintPage, HINT_PAGE_BYTES};
 
     #[test]
     fn test_read_hints() {
@@ -98,4 +98,16 @@ mod tests {
         assert_eq!(read_n_bytes(8), vec![4, 0, 0, 0, 1, 2, 3, 4]);
         assert_eq!(read_vec(), vec![1, 2, 3, 4]);
     }
+
+    #[test]
+    fn test_read_page() {
+        let bytes: Vec<u8> = (0..HINT_PAGE_BYTES + 4).map(|i| i as u8).collect();
+        set_hints(vec![bytes.clone()]);
+        hint_input();
+        assert_eq!(read_u32(), bytes.len() as u32);
+        let mut page = HintPage::default();
+        read_page(&mut page);
+        assert_eq!(page.0[..], bytes[..HINT_PAGE_BYTES]);
+        assert_eq!(read_u32(), u32::from_le_bytes([0, 1, 2, 3]));
+    }
 }
@@ -7,6 +7,7 @@ use core::fmt::Write;
 
 #[cfg(target_os = "zkvm")]
 use openvm_rv32im_guest::{hint_input, hint_store_u32};
+pub use openvm_rv32im_guest::{HintPage, HINT_PAGE_BYTES};
 use serde::de::DeserializeOwned;
 
 #[cfg(not(target_os = "zkvm"))]
@@ -21,6 +22,14 @@ pub fn read_vec() -> Vec<u8> {
     read_vec_by_len(read_u32() as usize)
 }
 
+/// Read `size: u32` and then `size` bytes of the next input of input stream `stream` into a
+/// vector. Key-value stores look the input up by `key`, other streams ignore it.
+#[cfg(target_os = "zkvm")]
+pub fn read_vec_from(stream: u32, key: &[u8]) -> Vec<u8> {
+    openvm_rv32im_guest::hint_input_from(stream, key);
+    read_vec_by_len(read_u32() as usize)
+}
+
 /// Read the next vec and deserialize it into a type `T`.
 pub fn read<T: DeserializeOwned>() -> T {
     let reader = read::Reader::new();
@@ -83,6 +92,47 @@ pub(crate) fn read_vec_by_len(len: usize) -> Vec<u8> {
     }
 }
 
+/// Read the next [HINT_PAGE_BYTES] bytes from the hint stream into `page`, with a single
+/// instruction on the zkVM.
+pub fn read_page(page: &mut HintPage) {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        openvm_rv32im_guest::hint_load_page(page);
+    }
+    #[cfg(not(target_os = "zkvm"))]
+    page.0.copy_from_slice(&read_n_bytes(HINT_PAGE_BYTES));
+}
+
+/// Performance counters of the current segment, see [read_perf_counters]. Counters that do not
+/// fit in a `u32` saturate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PerfCounters {
+    /// Cycles executed in the current segment
+    pub segment_cycles: u32,
+    /// Cycles executed in all segments so far
+    pub total_cycles: u32,
+    pub timestamp: u32,
+    /// Height of the tallest trace of the segment so far
+    pub max_trace_height: u32,
+    /// Maximum trace height before the segment is split
+    pub max_segment_len: u32,
+}
+
+/// Read the performance counters of the current segment, e.g. to choose between a precompile and
+/// a software path based on the remaining segment budget. The counters are only constrained as
+/// hints, so a program must not rely on them for correctness.
+#[cfg(target_os = "zkvm")]
+pub fn read_perf_counters() -> PerfCounters {
+    openvm_rv32im_guest::hint_perf_counters();
+    PerfCounters {
+        segment_cycles: read_u32(),
+        total_cycles: read_u32(),
+        timestamp: read_u32(),
+        max_trace_height: read_u32(),
+        max_segment_len: read_u32(),
+    }
+}
+
 /// Publish `x` as the `index`-th u32 output.
 #[allow(unused_variables)]
 pub fn reveal(x: u32, index: usize) {
@@ -102,9 +152,14 @@ pub fn print<S: AsRef<str>>(s: S) {
     openvm_rv32im_guest::print_str_from_bytes(s.as_ref().as_bytes());
 }
 
+/// Log a UTF-8 string as one line of the host log, annotated with the cycle count, for debugging
+/// purposes.
+#[allow(unused_variables)]
 pub fn println<S: AsRef<str>>(s: S) {
-    print(s);
-    print("\n");
+    #[cfg(all(not(target_os = "zkvm"), feature = "std"))]
+    println!("{}", s.as_ref());
+    #[cfg(target_os = "zkvm")]
+    openvm_rv32im_guest::log_str_from_bytes(s.as_ref().as_bytes());
 }
 
 /// A no-alloc writer to print to stdout on host machine for debugging purposes.
@@ -5,7 +5,7 @@
 /// operations in the same way: there is no operating system and even the standard library should be
 /// directly handled with intrinsics.
 use openvm_platform::{fileno::*, memory::sys_alloc_aligned, rust_rt::terminate, WORD_SIZE};
-use openvm_rv32im_guest::raw_print_str_from_bytes;
+use openvm_rv32im_guest::{raw_log_str_from_bytes, raw_print_str_from_bytes};
 
 const DIGEST_WORDS: usize = 8;
 
@@ -91,7 +91,7 @@ unsafe extern "C" fn sys_panic(msg_ptr: *const u8, len: usize) -> ! {
 /// `msg_ptr` must be aligned and dereferenceable.
 #[no_mangle]
 pub unsafe extern "C" fn sys_log(msg_ptr: *const u8, len: usize) {
-    raw_print_str_from_bytes(msg_ptr, len);
+    raw_log_str_from_bytes(msg_ptr, len);
 }
 
 /// Cycle count
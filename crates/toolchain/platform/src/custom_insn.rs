@@ -55,6 +55,16 @@ macro_rules! custom_insn_r {
             ), opcode = const $opcode, funct3 = const $funct3, funct7 = const $funct7, rd = out(reg) $rd)
         }
     };
+    ($opcode:expr, $funct3:expr, $funct7:expr, $rd:literal, $rs1:expr, $rs2:literal) => {
+        unsafe {
+            core::arch::asm!(concat!(
+                ".insn r {opcode}, {funct3}, {funct7}, ",
+                $rd,
+                ", {rs1}, ",
+                $rs2,
+            ), opcode = const $opcode, funct3 = const $funct3, funct7 = const $funct7, rs1 = in(reg) $rs1)
+        }
+    };
     ($opcode:expr, $funct3:expr, $funct7:expr, $rd:expr, $rs1:expr, $rs2:literal) => {
         // Note: rd = in(reg) because we expect rd to be a pointer
         unsafe {
@@ -22,7 +22,7 @@ use openvm_circuit::{
 };
 use openvm_circuit_primitives_derive::{Chip, ChipUsageGetter};
 use openvm_ecc_guest::k256::{SECP256K1_MODULUS, SECP256K1_ORDER};
-use openvm_instructions::exe::VmExe;
+use openvm_instructions::{exe::VmExe, instruction::Instruction, VmOpcode};
 use openvm_platform::memory::MEM_SIZE;
 use openvm_rv32im_circuit::{
     Rv32I, Rv32IExecutor, Rv32IPeriphery, Rv32ImConfig, Rv32Io, Rv32IoExecutor, Rv32IoPeriphery,
@@ -33,7 +33,11 @@ use openvm_rv32im_transpiler::{
 };
 use openvm_stark_backend::p3_field::PrimeField32;
 use openvm_stark_sdk::p3_baby_bear::BabyBear;
-use openvm_transpiler::{elf::Elf, transpiler::Transpiler, FromElf};
+use openvm_transpiler::{
+    elf::Elf,
+    transpiler::{Transpiler, TranspilerError},
+    EncodingRange, FromElf,
+};
 use serde::{Deserialize, Serialize};
 use test_case::test_case;
 
@@ -77,6 +81,71 @@ fn test_generate_program(elf_path: &str) -> Result<()> {
     Ok(())
 }
 
+const CUSTOM_2_OPCODE: u8 = 0x5b;
+
+fn custom_2_encoding() -> EncodingRange {
+    EncodingRange::opcode(CUSTOM_2_OPCODE)
+        .with_funct3(0)
+        .with_funct7(0..=3)
+}
+
+#[test]
+fn test_custom_encoding() {
+    let transpiler = Transpiler::<F>::default()
+        .with_extension(Rv32MTranspilerExtension)
+        .with_custom_encoding(custom_2_encoding(), |insn| {
+            let funct7 = (insn >> 25) as usize;
+            Some(Instruction::from_usize(
+                VmOpcode::from_usize(0x900 + funct7),
+                [0, 0, 0, 1, 2],
+            ))
+        });
+
+    let insn = |funct7: u32| CUSTOM_2_OPCODE as u32 | (funct7 << 25);
+    let program = transpiler.transpile(&[insn(0), insn(3)]).unwrap();
+    assert_eq!(program[0].opcode, VmOpcode::from_usize(0x900));
+    assert_eq!(program[1].opcode, VmOpcode::from_usize(0x903));
+    assert!(matches!(
+        transpiler.transpile(&[insn(4)]),
+        Err(TranspilerError::ParseError(_))
+    ));
+}
+
+#[test]
+#[should_panic(expected = "overlapping encodings")]
+fn test_custom_encoding_overlap() {
+    let _ = Transpiler::<F>::default()
+        .with_custom_encoding(custom_2_encoding(), |_| None)
+        .with_custom_encoding(
+            EncodingRange::opcode(CUSTOM_2_OPCODE).with_funct7(3..=5),
+            |_| None,
+        );
+}
+
+#[test]
+fn test_transpile_words() {
+    let transpiler = Transpiler::<F>::default().with_custom_encoding(custom_2_encoding(), |insn| {
+        Some(Instruction::from_usize(
+            VmOpcode::from_usize(0x900 + (insn >> 25) as usize),
+            [0, 0, 0, 1, 2],
+        ))
+    });
+
+    let insn = |funct7: u32| CUSTOM_2_OPCODE as u32 | (funct7 << 25);
+    let program = transpiler
+        .transpile_words(&[insn(0), insn(4), insn(3)])
+        .unwrap();
+    assert_eq!(
+        program[0].as_ref().unwrap().opcode,
+        VmOpcode::from_usize(0x900)
+    );
+    assert!(program[1].is_none());
+    assert_eq!(
+        program[2].as_ref().unwrap().opcode,
+        VmOpcode::from_usize(0x903)
+    );
+}
+
 #[test_case("tests/data/rv32im-exp-from-as")]
 #[test_case("tests/data/rv32im-fib-from-as")]
 fn test_rv32im_runtime(elf_path: &str) -> Result<()> {
@@ -3,23 +3,34 @@
 use std::{cmp::min, collections::BTreeMap, fmt::Debug};
 
 use elf::{
-    abi::{EM_RISCV, ET_EXEC, PF_X, PT_LOAD},
+    abi::{
+        EM_RISCV, ET_DYN, ET_EXEC, PF_X, PT_LOAD, R_RISCV_32, R_RISCV_64, R_RISCV_JUMP_SLOT,
+        R_RISCV_NONE, R_RISCV_RELATIVE, SHF_ALLOC, SHN_ABS, SHT_REL, SHT_RELA, STT_FUNC,
+    },
     endian::LittleEndian,
     file::Class,
+    segment::ProgramHeader,
     ElfBytes,
 };
 use eyre::{self, bail, ContextCompat};
-#[cfg(feature = "function-span")]
-use openvm_instructions::exe::FnBound;
-use openvm_instructions::exe::FnBounds;
-use openvm_platform::WORD_SIZE;
+use num_bigint_dig::BigUint;
+use openvm_instructions::exe::{FnBound, FnBounds, SourceLines};
+use openvm_platform::{memory::TEXT_START, WORD_SIZE};
 
 pub const ELF_DEFAULT_MAX_NUM_PUBLIC_VALUES: usize = 32;
 
-/// RISC-V 32IM ELF (Executable and Linkable Format) File.
+/// Name of the ELF section where guest macros record metadata about the program, such as the
+/// moduli declared by `moduli_init!`.
+pub const OPENVM_SECTION_NAME: &str = ".openvm";
+/// Kind of a record in the `.openvm` section for a modulus. The record is the kind byte, the
+/// modulus index byte, the number of bytes of the modulus as a little-endian `u32`, and the
+/// little-endian bytes of the modulus.
+pub const OPENVM_SECTION_MODULUS_KIND: u8 = 1;
+
+/// RISC-V 32IM or 64IM ELF (Executable and Linkable Format) File.
 ///
-/// This file represents a binary in the ELF format, specifically the RISC-V 32IM architecture
-/// with the following extensions:
+/// This file represents a binary in the ELF format, specifically the RISC-V 32IM or 64IM
+/// architecture with the following extensions:
 ///
 /// - Base Integer Instruction Set (I)
 /// - Integer Multiplication and Division (M)
@@ -38,8 +49,14 @@ pub struct Elf {
     /// The upper bound of the number of public values the program would publish.
     /// TODO: read from project config.
     pub(crate) max_num_public_values: usize,
-    /// Debug info for spanning benchmark metrics by function.
+    /// Bounds of the functions in the symbol table, for spanning metrics by function.
     pub(crate) fn_bounds: FnBounds,
+    /// Source line of each pc, from the DWARF line table. Empty unless the `dwarf` feature is
+    /// enabled.
+    pub(crate) source_lines: SourceLines,
+    /// The moduli declared by the guest with `moduli_init!`, ordered by their index. The
+    /// modular extension of the VM must support exactly these moduli in this order.
+    pub supported_moduli: Vec<BigUint>,
 }
 
 impl Elf {
@@ -50,6 +67,8 @@ impl Elf {
         pc_base: u32,
         memory_image: BTreeMap<u32, u32>,
         fn_bounds: FnBounds,
+        source_lines: SourceLines,
+        supported_moduli: Vec<BigUint>,
     ) -> Self {
         Self {
             instructions,
@@ -58,12 +77,17 @@ impl Elf {
             memory_image,
             max_num_public_values: ELF_DEFAULT_MAX_NUM_PUBLIC_VALUES,
             fn_bounds,
+            source_lines,
+            supported_moduli,
         }
     }
 
     /// Parse the ELF file into a vector of 32-bit encoded instructions and the first memory
     /// address.
     ///
+    /// Position-independent executables (`ET_DYN`) are loaded so that their lowest segment is at
+    /// or above [TEXT_START], and their dynamic relocations are applied to the memory image.
+    ///
     /// # Errors
     ///
     /// This function may return an error if the ELF is not valid.
@@ -77,43 +101,47 @@ impl Elf {
             .map_err(|err| eyre::eyre!("Elf parse error: {err}"))?;
 
         // Some sanity checks to make sure that the ELF file is valid.
-        if elf.ehdr.class != Class::ELF32 {
-            bail!("Not a 32-bit ELF");
+        // 64-bit ELFs are accepted for RV64 guests, as long as all addresses fit in 32 bits.
+        if elf.ehdr.class != Class::ELF32 && elf.ehdr.class != Class::ELF64 {
+            bail!("Not a 32-bit or 64-bit ELF");
         } else if elf.ehdr.e_machine != EM_RISCV {
             bail!("Invalid machine type, must be RISC-V");
-        } else if elf.ehdr.e_type != ET_EXEC {
-            bail!("Invalid ELF type, must be executable");
+        } else if elf.ehdr.e_type != ET_EXEC && elf.ehdr.e_type != ET_DYN {
+            bail!("Invalid ELF type, must be executable or position-independent executable");
         }
 
-        #[cfg(not(feature = "function-span"))]
-        let fn_bounds = Default::default();
-
-        #[cfg(feature = "function-span")]
-        let mut fn_bounds = FnBounds::new();
-        #[cfg(feature = "function-span")]
-        {
-            if let Some((symtab, stringtab)) = elf.symbol_table()? {
-                for symbol in symtab.iter() {
-                    if symbol.st_symtype() == elf::abi::STT_FUNC {
-                        fn_bounds.insert(
-                            symbol.st_value as u32,
-                            FnBound {
-                                start: symbol.st_value as u32,
-                                end: (symbol.st_value + symbol.st_size - (WORD_SIZE as u64)) as u32,
-                                name: stringtab.get(symbol.st_name as usize).unwrap().to_string(),
-                            },
-                        );
-                    }
-                }
-            } else {
-                println!("No symbol table found");
-            }
+        // Get the loadable segments of the ELF file.
+        let segments = elf
+            .segments()
+            .ok_or_else(|| eyre::eyre!("Missing segment table"))?;
+        if segments.len() > 256 {
+            bail!("Too many program headers");
         }
+        let segments: Vec<ProgramHeader> =
+            segments.iter().filter(|x| x.p_type == PT_LOAD).collect();
+
+        let load_bias = if elf.ehdr.e_type == ET_DYN {
+            pie_load_bias(&segments)?
+        } else {
+            0
+        };
+
+        let fn_bounds = decode_fn_bounds(&elf, load_bias)?;
+        #[cfg(feature = "dwarf")]
+        let source_lines = decode_source_lines(&elf, load_bias)?;
+        #[cfg(not(feature = "dwarf"))]
+        let source_lines = Default::default();
+
+        let supported_moduli = match elf.section_header_by_name(OPENVM_SECTION_NAME)? {
+            Some(shdr) => {
+                let (data, _) = elf.section_data(&shdr)?;
+                decode_openvm_section(data)?
+            }
+            None => Vec::new(),
+        };
 
         // Get the entrypoint of the ELF file as an u32.
-        let entry: u32 = elf
-            .ehdr
-            .e_entry
+        let entry: u32 = (elf.ehdr.e_entry + u64::from(load_bias))
             .try_into()
             .map_err(|err| eyre::eyre!("e_entry was larger than 32 bits. {err}"))?;
 
@@ -122,19 +150,12 @@ impl Elf {
             bail!("Invalid entrypoint");
         }
 
-        // Get the segments of the ELF file.
-        let segments = elf
-            .segments()
-            .ok_or_else(|| eyre::eyre!("Missing segment table"))?;
-        if segments.len() > 256 {
-            bail!("Too many program headers");
-        }
-
-        let mut instructions: Vec<u32> = Vec::new();
+        // The executable segments, as (virtual address, file size) pairs. Their instructions are
+        // read from the memory image once relocations have been applied.
+        let mut executable_segments = Vec::new();
         let mut base_address = u32::MAX;
 
-        // Only read segments that are executable instructions that are also PT_LOAD.
-        for segment in segments.iter().filter(|x| x.p_type == PT_LOAD) {
+        for segment in &segments {
             // Get the file size of the segment as an u32.
             let file_size: u32 = segment.p_filesz.try_into()?;
             if file_size >= max_mem {
@@ -148,15 +169,16 @@ impl Elf {
             }
 
             // Get the virtual address of the segment as an u32.
-            let vaddr: u32 = segment.p_vaddr.try_into()?;
+            let vaddr: u32 = (segment.p_vaddr + u64::from(load_bias)).try_into()?;
             if vaddr % WORD_SIZE as u32 != 0 {
                 bail!("vaddr {vaddr:08x} is unaligned");
             }
 
             // If the virtual address is less than the first memory address, then update the first
             // memory address.
-            if (segment.p_flags & PF_X) != 0 && base_address > vaddr {
-                base_address = vaddr;
+            if (segment.p_flags & PF_X) != 0 {
+                base_address = base_address.min(vaddr);
+                executable_segments.push((vaddr, min(file_size, mem_size)));
             }
 
             // Get the offset to the segment.
@@ -188,18 +210,306 @@ impl Elf {
                     word |= u32::from(*byte) << (j * 8);
                 }
                 image.insert(addr, word);
-                if (segment.p_flags & PF_X) != 0 {
-                    instructions.push(word);
-                }
             }
         }
 
+        apply_dynamic_relocations(&elf, load_bias, &mut image)?;
+
+        let instructions = executable_segments
+            .into_iter()
+            .flat_map(|(vaddr, file_size)| {
+                (0..file_size).step_by(WORD_SIZE).map(move |i| vaddr + i)
+            })
+            .map(|addr| image[&addr])
+            .collect();
+
         Ok(Elf::new(
             instructions,
             entry,
             base_address,
             image,
             fn_bounds,
+            source_lines,
+            supported_moduli,
         ))
     }
 }
+
+/// Reads the bounds of every function in the symbol table, if there is one.
+fn decode_fn_bounds(elf: &ElfBytes<LittleEndian>, load_bias: u32) -> eyre::Result<FnBounds> {
+    let mut fn_bounds = FnBounds::new();
+    let Some((symtab, strtab)) = elf.symbol_table()? else {
+        return Ok(fn_bounds);
+    };
+    for symbol in symtab.iter() {
+        if symbol.st_symtype() != STT_FUNC || symbol.st_size == 0 || symbol.is_undefined() {
+            continue;
+        }
+        let start: u32 = (symbol.st_value + u64::from(load_bias)).try_into()?;
+        fn_bounds.insert(
+            start,
+            FnBound {
+                start,
+                end: start + symbol.st_size as u32 - WORD_SIZE as u32,
+                name: strtab.get(symbol.st_name as usize)?.to_string(),
+            },
+        );
+    }
+    Ok(fn_bounds)
+}
+
+/// Reads the DWARF line table, if there is one, mapping the pc of each row to its file and line.
+#[cfg(feature = "dwarf")]
+fn decode_source_lines(elf: &ElfBytes<LittleEndian>, load_bias: u32) -> eyre::Result<SourceLines> {
+    use openvm_instructions::exe::SourceLocation;
+
+    let sections = gimli::DwarfSections::load(|id| -> eyre::Result<&[u8]> {
+        Ok(match elf.section_header_by_name(id.name())? {
+            Some(shdr) => elf.section_data(&shdr)?.0,
+            None => &[],
+        })
+    })?;
+    let dwarf = sections.borrow(|section| gimli::EndianSlice::new(section, gimli::LittleEndian));
+
+    let mut source_lines = SourceLines::new();
+    let mut units = dwarf.units();
+    while let Some(header) = units.next()? {
+        let unit = dwarf.unit(header)?;
+        let Some(program) = unit.line_program.clone() else {
+            continue;
+        };
+        let mut rows = program.rows();
+        while let Some((header, row)) = rows.next_row()? {
+            if row.end_sequence() {
+                continue;
+            }
+            let Some(file) = row.file(header) else {
+                continue;
+            };
+            let mut path = match file.directory(header) {
+                Some(dir) => format!("{}/", dwarf.attr_string(&unit, dir)?.to_string_lossy()),
+                None => String::new(),
+            };
+            path.push_str(
+                &dwarf
+                    .attr_string(&unit, file.path_name())?
+                    .to_string_lossy(),
+            );
+            let pc: u32 = (row.address() + u64::from(load_bias)).try_into()?;
+            source_lines.insert(
+                pc,
+                SourceLocation {
+                    file: path,
+                    line: row.line().map_or(0, |line| line.get() as u32),
+                },
+            );
+        }
+    }
+    Ok(source_lines)
+}
+
+/// Returns the offset at which a position-independent executable is loaded: the smallest
+/// multiple of the largest segment alignment which puts every segment at or above [TEXT_START].
+fn pie_load_bias(segments: &[ProgramHeader]) -> eyre::Result<u32> {
+    let min_vaddr = segments
+        .iter()
+        .map(|segment| segment.p_vaddr)
+        .min()
+        .context("No loadable segments")?;
+    let align = segments
+        .iter()
+        .map(|segment| segment.p_align)
+        .max()
+        .unwrap_or(1)
+        .max(1);
+    let bias = u64::from(TEXT_START)
+        .saturating_sub(min_vaddr)
+        .next_multiple_of(align);
+    Ok(bias.try_into()?)
+}
+
+/// Applies the dynamic relocations in the allocated `SHT_RELA` sections, which is how
+/// position-independent executables fill in absolute addresses (e.g. GOT entries) once the load
+/// address is known. There is no dynamic linker, so every symbol they refer to must be defined
+/// in the ELF itself.
+fn apply_dynamic_relocations(
+    elf: &ElfBytes<LittleEndian>,
+    load_bias: u32,
+    image: &mut BTreeMap<u32, u32>,
+) -> eyre::Result<()> {
+    let Some(section_headers) = elf.section_headers() else {
+        return Ok(());
+    };
+    let dynamic_symbols = elf.dynamic_symbol_table()?;
+    let symbol_value = |index: u32| -> eyre::Result<u64> {
+        let (symtab, strtab) = dynamic_symbols
+            .as_ref()
+            .context("Symbol relocation without a dynamic symbol table")?;
+        let symbol = symtab.get(index as usize)?;
+        if symbol.is_undefined() {
+            bail!(
+                "Relocation against undefined symbol {}",
+                strtab.get(symbol.st_name as usize)?
+            );
+        }
+        Ok(if symbol.st_shndx == SHN_ABS {
+            symbol.st_value
+        } else {
+            symbol.st_value + u64::from(load_bias)
+        })
+    };
+
+    for shdr in section_headers
+        .iter()
+        .filter(|shdr| shdr.sh_flags & SHF_ALLOC as u64 != 0)
+    {
+        if shdr.sh_type == SHT_REL {
+            bail!("SHT_REL relocations are not supported");
+        }
+        if shdr.sh_type != SHT_RELA {
+            continue;
+        }
+        for rela in elf.section_data_as_relas(&shdr)? {
+            let (base, addend) = match rela.r_type {
+                R_RISCV_NONE => continue,
+                R_RISCV_RELATIVE => (u64::from(load_bias), rela.r_addend),
+                R_RISCV_32 | R_RISCV_64 => (symbol_value(rela.r_sym)?, rela.r_addend),
+                R_RISCV_JUMP_SLOT => (symbol_value(rela.r_sym)?, 0),
+                r_type => bail!("Unsupported relocation type {r_type}"),
+            };
+            let addr: u32 = (rela.r_offset + u64::from(load_bias)).try_into()?;
+            let value: u32 = base
+                .checked_add_signed(addend)
+                .and_then(|value| value.try_into().ok())
+                .with_context(|| format!("Relocated value at 0x{addr:08x} exceeds 32 bits"))?;
+            if addr % WORD_SIZE as u32 != 0 {
+                bail!("Relocation at 0x{addr:08x} is unaligned");
+            }
+            // Relocations of 64-bit ELFs other than R_RISCV_32 write a doubleword.
+            let num_words = if elf.ehdr.class == Class::ELF64 && rela.r_type != R_RISCV_32 {
+                2
+            } else {
+                1
+            };
+            for (i, word) in [value, 0].into_iter().take(num_words).enumerate() {
+                let word_addr = addr + (i * WORD_SIZE) as u32;
+                *image.get_mut(&word_addr).with_context(|| {
+                    format!("Relocation at 0x{word_addr:08x} is outside of the loaded segments")
+                })? = word;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Decodes the records of the `.openvm` section, which the linker concatenates in an
+/// arbitrary order. Returns the moduli ordered by their index.
+fn decode_openvm_section(mut data: &[u8]) -> eyre::Result<Vec<BigUint>> {
+    let mut moduli = BTreeMap::new();
+    while let Some((&kind, rest)) = data.split_first() {
+        match kind {
+            OPENVM_SECTION_MODULUS_KIND => {
+                if rest.len() < 5 {
+                    bail!("Truncated modulus record in the {OPENVM_SECTION_NAME} section");
+                }
+                let mod_idx = rest[0];
+                let len = u32::from_le_bytes(rest[1..5].try_into().unwrap()) as usize;
+                let bytes = rest[5..].get(..len).with_context(|| {
+                    format!("Truncated modulus record in the {OPENVM_SECTION_NAME} section")
+                })?;
+                if moduli
+                    .insert(mod_idx, BigUint::from_bytes_le(bytes))
+                    .is_some()
+                {
+                    bail!("Modulus index {mod_idx} is declared twice");
+                }
+                data = &rest[5 + len..];
+            }
+            _ => bail!("Unknown record kind {kind} in the {OPENVM_SECTION_NAME} section"),
+        }
+    }
+    if moduli.keys().copied().ne(0..moduli.len() as u8) {
+        bail!("Modulus indices are not contiguous from 0");
+    }
+    Ok(moduli.into_values().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use openvm_platform::memory::MEM_SIZE;
+
+    use super::*;
+
+    const ENTRY: u32 = 0x54;
+    const GOT_ENTRY: u32 = 0x58;
+    const RELA_DYN: u32 = 0x5c;
+    const SHSTRTAB: u32 = 0x68;
+    const SECTION_HEADERS: u32 = 0x80;
+
+    /// A minimal RV32 position-independent executable linked at address 0, with a single
+    /// loadable segment and one `R_RISCV_RELATIVE` relocation pointing a GOT entry at the entry
+    /// point.
+    fn pie_elf() -> Vec<u8> {
+        fn push(data: &mut Vec<u8>, words: &[u32], size: usize) {
+            for word in words {
+                data.extend_from_slice(&word.to_le_bytes()[..size]);
+            }
+        }
+
+        let mut data = Vec::new();
+        // ELF header
+        push(&mut data, &[0x464c457f, 0x00010101, 0, 0], 4);
+        push(&mut data, &[ET_DYN as u32, EM_RISCV as u32], 2);
+        push(&mut data, &[1, ENTRY, 52, SECTION_HEADERS, 0], 4);
+        push(&mut data, &[52, 32, 1, 40, 3, 2], 2);
+        // Program header
+        push(
+            &mut data,
+            &[PT_LOAD, 0, 0, 0, SHSTRTAB, SHSTRTAB, 0b111, 0x1000],
+            4,
+        );
+        // Entry point (`ecall`) and the GOT entry
+        push(&mut data, &[0x00000073, 0], 4);
+        // .rela.dyn
+        push(&mut data, &[GOT_ENTRY, R_RISCV_RELATIVE, ENTRY], 4);
+        // .shstrtab
+        data.extend_from_slice(b"\0.rela.dyn\0.shstrtab\0\0\0\0");
+        // Section headers: null, .rela.dyn, .shstrtab
+        push(&mut data, &[0; 10], 4);
+        push(
+            &mut data,
+            &[
+                1,
+                SHT_RELA,
+                SHF_ALLOC as u32,
+                RELA_DYN,
+                RELA_DYN,
+                12,
+                0,
+                0,
+                4,
+                12,
+            ],
+            4,
+        );
+        push(
+            &mut data,
+            &[11, elf::abi::SHT_STRTAB, 0, 0, SHSTRTAB, 21, 0, 0, 1, 0],
+            4,
+        );
+        data
+    }
+
+    #[test]
+    fn test_decode_pie() {
+        let elf = Elf::decode(&pie_elf(), MEM_SIZE as u32).unwrap();
+        let bias = 0x20_1000;
+        assert_eq!(elf.pc_start, bias + ENTRY);
+        assert_eq!(elf.pc_base, bias);
+        assert_eq!(elf.memory_image[&(bias + GOT_ENTRY)], bias + ENTRY);
+        assert_eq!(
+            elf.instructions[(GOT_ENTRY / WORD_SIZE as u32) as usize],
+            bias + ENTRY
+        );
+    }
+}
@@ -1,14 +1,79 @@
+use std::ops::RangeInclusive;
+
 use openvm_instructions::instruction::Instruction;
 
 /// Trait to add custom RISC-V instruction transpilation to OpenVM instruction format.
 /// RISC-V instructions always come in 32-bit chunks.
 /// An important feature is that multiple 32-bit RISC-V instructions can be transpiled into a single OpenVM instruction.
 /// See `process_custom` for details.
+///
+/// Extensions are registered with [`Transpiler::with_extension`](crate::transpiler::Transpiler::with_extension).
+/// An extension may declare the encodings it handles via [`encodings`](Self::encodings), in which case
+/// the transpiler only offers it instructions whose first word falls in one of those ranges. This lets
+/// third-party extensions claim a slice of the custom opcode space (e.g. a `funct7` range under a shared
+/// `opcode`/`funct3`) without having to coordinate with every other registered extension.
 pub trait TranspilerExtension<F> {
     /// The `instruction_stream` provides a view of the remaining RISC-V instructions to be processed,
-    /// presented as 32-bit chunks. The [`CustomInstructionProcessor`] should determine if it knows how to transpile
+    /// presented as 32-bit chunks. The [`TranspilerExtension`] should determine if it knows how to transpile
     /// the next contiguous section of RISC-V instructions into an [`Instruction`].
     /// It returns `None` if it cannot transpile. Otherwise it returns `(instruction, how_many_u32s)` to indicate that
     /// `instruction_stream[..how_many_u32s]` should be transpiled into `instruction`.
     fn process_custom(&self, instruction_stream: &[u32]) -> Option<(Instruction<F>, usize)>;
+
+    /// The encodings of the first instruction word this extension may transpile.
+    /// `None` (the default) means `process_custom` is tried on every instruction.
+    fn encodings(&self) -> Option<Vec<EncodingRange>> {
+        None
+    }
+}
+
+/// A set of 32-bit RISC-V instruction words, selected by `opcode` and optionally by `funct3` and a
+/// range of `funct7` values.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EncodingRange {
+    pub opcode: u8,
+    pub funct3: Option<u8>,
+    pub funct7: Option<RangeInclusive<u8>>,
+}
+
+impl EncodingRange {
+    pub const fn opcode(opcode: u8) -> Self {
+        Self {
+            opcode,
+            funct3: None,
+            funct7: None,
+        }
+    }
+
+    pub fn with_funct3(mut self, funct3: u8) -> Self {
+        self.funct3 = Some(funct3);
+        self
+    }
+
+    pub fn with_funct7(mut self, funct7: RangeInclusive<u8>) -> Self {
+        self.funct7 = Some(funct7);
+        self
+    }
+
+    pub fn contains(&self, instruction_u32: u32) -> bool {
+        let opcode = (instruction_u32 & 0x7f) as u8;
+        let funct3 = ((instruction_u32 >> 12) & 0b111) as u8;
+        let funct7 = (instruction_u32 >> 25) as u8;
+        opcode == self.opcode
+            && self.funct3.map_or(true, |f| f == funct3)
+            && self.funct7.as_ref().map_or(true, |r| r.contains(&funct7))
+    }
+
+    /// Whether some instruction word is contained in both `self` and `other`.
+    pub fn overlaps(&self, other: &Self) -> bool {
+        self.opcode == other.opcode
+            && match (self.funct3, other.funct3) {
+                (Some(a), Some(b)) => a == b,
+                _ => true,
+            }
+            && match (&self.funct7, &other.funct7) {
+                (Some(a), Some(b)) => a.start() <= b.end() && b.start() <= a.end(),
+                _ => true,
+            }
+    }
 }
@@ -13,10 +13,11 @@ use crate::util::elf_memory_image_to_openvm_memory_image;
 
 pub mod elf;
 pub mod transpiler;
+pub mod trim;
 pub mod util;
 
 mod extension;
-pub use extension::TranspilerExtension;
+pub use extension::{EncodingRange, TranspilerExtension};
 
 pub trait FromElf {
     type ElfContext;
@@ -28,8 +29,10 @@ pub trait FromElf {
 impl<F: PrimeField32> FromElf for VmExe<F> {
     type ElfContext = Transpiler<F>;
     fn from_elf(elf: Elf, transpiler: Self::ElfContext) -> Result<Self, TranspilerError> {
-        let instructions = transpiler.transpile(&elf.instructions)?;
-        let program = Program::new_without_debug_infos(
+        // Words which cannot be transpiled, such as read-only data placed in the text segment by
+        // the linker, stay in the memory image but not in the program.
+        let instructions = transpiler.transpile_words(&elf.instructions)?;
+        let program = Program::new_without_debug_infos_with_option(
             &instructions,
             DEFAULT_PC_STEP,
             elf.pc_base,
@@ -42,6 +45,7 @@ impl<F: PrimeField32> FromElf for VmExe<F> {
             pc_start: elf.pc_start,
             init_memory,
             fn_bounds: elf.fn_bounds,
+            source_lines: elf.source_lines,
         })
     }
 }
@@ -4,12 +4,14 @@ use openvm_instructions::instruction::Instruction;
 use openvm_stark_backend::p3_field::PrimeField32;
 use thiserror::Error;
 
-use crate::TranspilerExtension;
+use crate::{EncodingRange, TranspilerExtension};
 
 /// Collection of [`TranspilerExtension`]s.
 /// The transpiler can be configured to transpile any ELF in 32-bit chunks.
 pub struct Transpiler<F> {
     processors: Vec<Rc<dyn TranspilerExtension<F>>>,
+    /// The declared [`TranspilerExtension::encodings`] of each processor.
+    encodings: Vec<Option<Vec<EncodingRange>>>,
 }
 
 impl<F: PrimeField32> Default for Transpiler<F> {
@@ -28,25 +30,58 @@ pub enum TranspilerError {
 
 impl<F: PrimeField32> Transpiler<F> {
     pub fn new() -> Self {
-        Self { processors: vec![] }
+        Self {
+            processors: vec![],
+            encodings: vec![],
+        }
     }
 
-    pub fn with_processor(self, proc: Rc<dyn TranspilerExtension<F>>) -> Self {
-        let mut procs = self.processors;
-        procs.push(proc);
-        Self { processors: procs }
+    /// Registers a processor.
+    ///
+    /// Panics if the processor declares [`encodings`](TranspilerExtension::encodings) that overlap
+    /// the declared encodings of an already registered processor.
+    pub fn with_processor(mut self, proc: Rc<dyn TranspilerExtension<F>>) -> Self {
+        let encodings = proc.encodings();
+        if let Some(new_ranges) = &encodings {
+            for existing in self.encodings.iter().flatten().flatten() {
+                for range in new_ranges {
+                    assert!(
+                        !existing.overlaps(range),
+                        "overlapping encodings {existing:?} and {range:?}"
+                    );
+                }
+            }
+        }
+        self.processors.push(proc);
+        self.encodings.push(encodings);
+        self
     }
 
     pub fn with_extension<T: TranspilerExtension<F> + 'static>(self, ext: T) -> Self {
         self.with_processor(Rc::new(ext))
     }
 
+    /// Registers a single-word custom instruction encoding without defining a new
+    /// [`TranspilerExtension`]. `f` is only called on words contained in `range`.
+    pub fn with_custom_encoding(
+        self,
+        range: EncodingRange,
+        f: impl Fn(u32) -> Option<Instruction<F>> + 'static,
+    ) -> Self {
+        self.with_extension(CustomEncoding {
+            range,
+            f: Box::new(f),
+        })
+    }
+
     /// Iterates over a sequence of 32-bit RISC-V instructions `instructions_u32`. The iterator
     /// applies every processor in the [`Transpiler`] to determine if one of them knows how to transpile
     /// the current instruction (and possibly a contiguous section of following instructions).
     /// If so, it advances the iterator by the amount specified by the processor.
-    /// The transpiler will panic if two different processors claim to know how to transpile the same instruction
-    /// to avoid ambiguity.
+    /// Processors which declare [`TranspilerExtension::encodings`] are only applied to instructions
+    /// within those encodings.
+    /// The transpiler will return an error if two different processors claim to know how to transpile the same
+    /// instruction to avoid ambiguity.
     pub fn transpile(
         &self,
         instructions_u32: &[u32],
@@ -54,22 +89,82 @@ impl<F: PrimeField32> Transpiler<F> {
         let mut instructions = Vec::new();
         let mut ptr = 0;
         while ptr < instructions_u32.len() {
-            let mut options = self
-                .processors
-                .iter()
-                .map(|proc| proc.process_custom(&instructions_u32[ptr..]))
-                .filter(|opt| opt.is_some())
-                .collect::<Vec<_>>();
-            if options.is_empty() {
-                return Err(TranspilerError::ParseError(instructions_u32[ptr]));
-            }
-            if options.len() > 1 {
-                return Err(TranspilerError::AmbiguousNextInstruction);
-            }
-            let (instruction, advance) = options.pop().unwrap().unwrap();
+            let (instruction, advance) = self
+                .transpile_next(&instructions_u32[ptr..])?
+                .ok_or(TranspilerError::ParseError(instructions_u32[ptr]))?;
             instructions.push(instruction);
             ptr += advance;
         }
         Ok(instructions)
     }
+
+    /// Like [`Transpiler::transpile`], but for a stream which mixes instructions with data: a word
+    /// which no processor accepts is treated as data instead of failing the transpilation. Returns
+    /// one entry per word, which is `None` for data and for the words consumed by a preceding
+    /// multi-word instruction.
+    pub fn transpile_words(
+        &self,
+        instructions_u32: &[u32],
+    ) -> Result<Vec<Option<Instruction<F>>>, TranspilerError> {
+        let mut instructions = Vec::with_capacity(instructions_u32.len());
+        let mut ptr = 0;
+        while ptr < instructions_u32.len() {
+            match self.transpile_next(&instructions_u32[ptr..])? {
+                Some((instruction, advance)) => {
+                    instructions.push(Some(instruction));
+                    instructions.extend((1..advance).map(|_| None));
+                    ptr += advance;
+                }
+                None => {
+                    instructions.push(None);
+                    ptr += 1;
+                }
+            }
+        }
+        Ok(instructions)
+    }
+
+    /// Transpiles the instruction at the start of `instructions_u32`, returning it with the number
+    /// of words it consumes, or `None` if no processor accepts it.
+    fn transpile_next(
+        &self,
+        instructions_u32: &[u32],
+    ) -> Result<Option<(Instruction<F>, usize)>, TranspilerError> {
+        let mut options = self
+            .processors
+            .iter()
+            .zip(&self.encodings)
+            .filter(|(_, encodings)| {
+                encodings.as_ref().map_or(true, |ranges| {
+                    ranges
+                        .iter()
+                        .any(|range| range.contains(instructions_u32[0]))
+                })
+            })
+            .filter_map(|(proc, _)| proc.process_custom(instructions_u32))
+            .collect::<Vec<_>>();
+        if options.len() > 1 {
+            return Err(TranspilerError::AmbiguousNextInstruction);
+        }
+        Ok(options.pop())
+    }
+}
+
+struct CustomEncoding<F> {
+    range: EncodingRange,
+    f: Box<dyn Fn(u32) -> Option<Instruction<F>>>,
+}
+
+impl<F> TranspilerExtension<F> for CustomEncoding<F> {
+    fn process_custom(&self, instruction_stream: &[u32]) -> Option<(Instruction<F>, usize)> {
+        let instruction_u32 = *instruction_stream.first()?;
+        if !self.range.contains(instruction_u32) {
+            return None;
+        }
+        (self.f)(instruction_u32).map(|instruction| (instruction, 1))
+    }
+
+    fn encodings(&self) -> Option<Vec<EncodingRange>> {
+        Some(vec![self.range.clone()])
+    }
 }
@@ -0,0 +1,162 @@
+//! Dead-code elimination for transpiled programs.
+//!
+//! Instructions that are never executed still take up rows in the program trace and are part of
+//! the program commitment. [reachable_pcs] finds the instructions of an [Elf] which may be
+//! executed, and [Program::retain_pcs](openvm_instructions::program::Program::retain_pcs) removes
+//! the others from the transpiled program. Instead of the static analysis, the set of PCs may also
+//! come from a recorded execution, e.g. the keys of a PC profile with a bucket size of
+//! [DEFAULT_PC_STEP].
+//!
+//! Trimming never makes a program prove something it otherwise would not: executing a removed
+//! instruction fails with `ExecutionError::PcNotFound`.
+
+use std::collections::BTreeSet;
+
+use openvm_instructions::program::DEFAULT_PC_STEP;
+use rrs_lib::instruction_formats::{BType, IType, JType, UType};
+
+use crate::elf::Elf;
+
+const OPCODE_OP_IMM: u32 = 0b0010011;
+const OPCODE_LUI: u32 = 0b0110111;
+const OPCODE_AUIPC: u32 = 0b0010111;
+const OPCODE_STORE: u32 = 0b0100011;
+const OPCODE_BRANCH: u32 = 0b1100011;
+const OPCODE_JALR: u32 = 0b1100111;
+const OPCODE_JAL: u32 = 0b1101111;
+
+/// How many instructions after a `lui` or `auipc` are searched for the `addi` or `jalr` which
+/// completes the address.
+const ADDRESS_LOOKAHEAD: u32 = 8;
+
+/// Returns the PCs of the instructions of `elf` which may be executed.
+///
+/// The analysis follows the control flow from the entry point. The targets of indirect jumps are
+/// over-approximated by every code address which the program materializes, namely
+/// - code addresses stored in the initial memory image, such as function pointers, vtables and
+///   jump tables,
+/// - code addresses built by a `lui` or `auipc` in reachable code, together with the `addi` or
+///   `jalr` which uses them.
+///
+/// Code addresses which are computed in any other way are missed, in which case the trimmed
+/// program fails when it jumps to them. Such programs should be trimmed with a recorded execution
+/// instead.
+pub fn reachable_pcs(elf: &Elf) -> BTreeSet<u32> {
+    let word_at = |pc: u32| {
+        let offset = pc.checked_sub(elf.pc_base)?;
+        if offset % DEFAULT_PC_STEP != 0 {
+            return None;
+        }
+        elf.instructions
+            .get((offset / DEFAULT_PC_STEP) as usize)
+            .copied()
+    };
+
+    let mut worklist: Vec<u32> = vec![elf.pc_start];
+    worklist.extend(elf.memory_image.values().copied());
+    let mut reachable = BTreeSet::new();
+    while let Some(pc) = worklist.pop() {
+        let Some(insn) = word_at(pc) else {
+            continue;
+        };
+        if !reachable.insert(pc) {
+            continue;
+        }
+        let next_pc = pc.wrapping_add(DEFAULT_PC_STEP);
+        match insn & 0x7f {
+            OPCODE_JAL => {
+                let dec_insn = JType::new(insn);
+                worklist.push(pc.wrapping_add(dec_insn.imm as u32));
+                // A call returns to the next instruction, a plain jump does not.
+                if dec_insn.rd != 0 {
+                    worklist.push(next_pc);
+                }
+            }
+            OPCODE_JALR => {
+                // The target is covered by the materialized code addresses.
+                if IType::new(insn).rd != 0 {
+                    worklist.push(next_pc);
+                }
+            }
+            OPCODE_BRANCH => {
+                worklist.push(pc.wrapping_add(BType::new(insn).imm as u32));
+                worklist.push(next_pc);
+            }
+            OPCODE_LUI | OPCODE_AUIPC => {
+                let dec_insn = UType::new(insn);
+                let mut address = dec_insn.imm as u32;
+                if insn & 0x7f == OPCODE_AUIPC {
+                    address = address.wrapping_add(pc);
+                }
+                worklist.push(address);
+                worklist.extend(completed_addresses(&word_at, next_pc, dec_insn.rd, address));
+                worklist.push(next_pc);
+            }
+            _ => worklist.push(next_pc),
+        }
+    }
+    reachable
+}
+
+/// The addresses `address + imm` of the `addi` and `jalr` instructions from `pc` onwards which
+/// read register `rd` before it is overwritten.
+fn completed_addresses(
+    word_at: &impl Fn(u32) -> Option<u32>,
+    pc: u32,
+    rd: usize,
+    address: u32,
+) -> Vec<u32> {
+    let mut addresses = Vec::new();
+    for i in 0..ADDRESS_LOOKAHEAD {
+        let Some(insn) = word_at(pc.wrapping_add(i * DEFAULT_PC_STEP)) else {
+            break;
+        };
+        let opcode = insn & 0x7f;
+        let dec_insn = IType::new(insn);
+        let is_addi = opcode == OPCODE_OP_IMM && dec_insn.funct3 == 0;
+        if (is_addi || opcode == OPCODE_JALR) && dec_insn.rs1 == rd {
+            addresses.push(address.wrapping_add(dec_insn.imm as u32));
+        }
+        let writes_rd = !matches!(opcode, OPCODE_STORE | OPCODE_BRANCH) && dec_insn.rd == rd;
+        if writes_rd || matches!(opcode, OPCODE_JAL | OPCODE_JALR | OPCODE_BRANCH) {
+            break;
+        }
+    }
+    addresses
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+
+    #[test]
+    fn test_reachable_pcs() {
+        let instructions = vec![
+            0x00001537, // 0x1000: lui a0, 0x1
+            0x01c50513, // 0x1004: addi a0, a0, 28
+            0x00c000ef, // 0x1008: jal ra, 0x1014
+            0x0000006f, // 0x100c: j 0x100c
+            0x00000013, // 0x1010: nop (after a jump)
+            0x00008067, // 0x1014: ret
+            0x00000013, // 0x1018: nop (never referenced)
+            0x00008067, // 0x101c: ret (address built by lui/addi)
+            0x00008067, // 0x1020: ret (address stored in memory)
+        ];
+        let memory_image = BTreeMap::from([(0x2000, 0x1020)]);
+        let elf = Elf::new(
+            instructions,
+            0x1000,
+            0x1000,
+            memory_image,
+            Default::default(),
+            Default::default(),
+            vec![],
+        );
+        assert_eq!(
+            reachable_pcs(&elf),
+            BTreeSet::from([0x1000, 0x1004, 0x1008, 0x100c, 0x1014, 0x101c, 0x1020])
+        );
+    }
+}
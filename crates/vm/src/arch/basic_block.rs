@@ -0,0 +1,102 @@
+use std::sync::Arc;
+
+use openvm_instructions::{instruction::Instruction, program::Program, SystemOpcode, VmOpcode};
+use rustc_hash::FxHashMap;
+
+use super::{ExecutorId, VmInventory};
+
+/// Maximum number of instructions in a [BasicBlock]. Blocks starting at different pcs may
+/// overlap, so this bounds the memory used by a [BasicBlockCache].
+pub const MAX_BASIC_BLOCK_LEN: usize = 64;
+
+/// An instruction of a [BasicBlock], with its executor already looked up.
+#[derive(Clone, Debug)]
+pub(super) struct DecodedInstruction<F> {
+    pub pc_index: usize,
+    pub instruction: Instruction<F>,
+    pub executor_id: ExecutorId,
+}
+
+/// Consecutive instructions that only need their executor to run, so that they can be executed
+/// without the per-instruction dispatch of
+/// [ExecutionSegment::execute_from_pc](super::ExecutionSegment::execute_from_pc).
+///
+/// A block ends before TERMINATE, PHANTOM, instructions with debug info and opcodes without an
+/// executor in the inventory, which all need the handling of the segment. Since branch opcodes
+/// are not known to the VM, a block does not end at them: execution leaves the block as soon as
+/// an instruction does not continue at the next pc.
+#[derive(Clone, Debug, Default)]
+pub(super) struct BasicBlock<F> {
+    pub instructions: Vec<DecodedInstruction<F>>,
+}
+
+impl<F: Clone> BasicBlock<F> {
+    fn decode<E, P>(
+        pc: u32,
+        program: &Program<F>,
+        program_len: usize,
+        inventory: &VmInventory<E, P>,
+    ) -> Self {
+        // Unaligned pcs and pcs out of the program are left to the error handling of the
+        // segment.
+        let Some(start) = pc
+            .checked_sub(program.pc_base)
+            .filter(|offset| offset % program.step == 0)
+            .map(|offset| (offset / program.step) as usize)
+        else {
+            return Self::default();
+        };
+        let terminate = VmOpcode::with_default_offset(SystemOpcode::TERMINATE);
+        let phantom = VmOpcode::with_default_offset(SystemOpcode::PHANTOM);
+
+        let mut instructions = Vec::new();
+        for pc_index in start..program_len.min(start + MAX_BASIC_BLOCK_LEN) {
+            let Some((instruction, None)) = program.get_instruction_and_debug_info(pc_index) else {
+                break;
+            };
+            if instruction.opcode == terminate || instruction.opcode == phantom {
+                break;
+            }
+            let Some(executor_id) = inventory.executor_id(instruction.opcode) else {
+                break;
+            };
+            instructions.push(DecodedInstruction {
+                pc_index,
+                instruction,
+                executor_id,
+            });
+        }
+        Self { instructions }
+    }
+}
+
+/// The [BasicBlock]s of a program by start pc. Blocks are decoded the first time execution
+/// reaches their start, since the targets of indirect jumps are only known at runtime.
+pub(super) struct BasicBlockCache<F> {
+    blocks: FxHashMap<u32, Arc<BasicBlock<F>>>,
+}
+
+impl<F> Default for BasicBlockCache<F> {
+    fn default() -> Self {
+        Self {
+            blocks: FxHashMap::default(),
+        }
+    }
+}
+
+impl<F: Clone> BasicBlockCache<F> {
+    /// The block starting at `pc`, which is empty if the instruction at `pc` cannot be part of
+    /// a block.
+    pub fn get_or_decode<E, P>(
+        &mut self,
+        pc: u32,
+        program: &Program<F>,
+        program_len: usize,
+        inventory: &VmInventory<E, P>,
+    ) -> Arc<BasicBlock<F>> {
+        self.blocks
+            .entry(pc)
+            .or_insert_with(|| Arc::new(BasicBlock::decode(pc, program, program_len, inventory)))
+            .clone()
+    }
+}
@@ -13,10 +13,13 @@ pub use super::testing::{
     POSEIDON2_DIRECT_BUS, RANGE_TUPLE_CHECKER_BUS, READ_INSTRUCTION_BUS,
 };
 use super::{
-    AnyEnum, InstructionExecutor, SystemComplex, SystemExecutor, SystemPeriphery, VmChipComplex,
-    VmInventoryError, PUBLIC_VALUES_AIR_ID,
+    AnyEnum, InstructionExecutor, SegmentationLimit, SystemComplex, SystemExecutor,
+    SystemPeriphery, VmChipComplex, VmInventoryError, PUBLIC_VALUES_AIR_ID,
+};
+use crate::system::memory::{
+    offline_checker::MAX_CLK_BITS, tree::public_values::PUBLIC_VALUES_ADDRESS_SPACE_OFFSET,
+    BOUNDARY_AIR_OFFSET, MAX_READ_ONLY_ADDRESS_SPACES,
 };
-use crate::system::memory::BOUNDARY_AIR_OFFSET;
 
 const DEFAULT_MAX_SEGMENT_LEN: usize = (1 << 22) - 100;
 // sbox is decomposed to have this max degree for Poseidon2. We set to 3 so quotient_degree = 2
@@ -30,8 +33,9 @@ pub fn vm_poseidon2_config<F: PrimeField32>() -> Poseidon2Config<F> {
 }
 
 pub trait VmConfig<F: PrimeField32>: Clone + Serialize + DeserializeOwned {
-    type Executor: InstructionExecutor<F> + AnyEnum + ChipUsageGetter;
-    type Periphery: AnyEnum + ChipUsageGetter;
+    // `Send` so that the traces of different chips can be generated in parallel.
+    type Executor: InstructionExecutor<F> + AnyEnum + ChipUsageGetter + Send;
+    type Periphery: AnyEnum + ChipUsageGetter + Send;
 
     /// Must contain system config
     fn system(&self) -> &SystemConfig;
@@ -40,9 +44,57 @@ pub trait VmConfig<F: PrimeField32>: Clone + Serialize + DeserializeOwned {
     fn create_chip_complex(
         &self,
     ) -> Result<VmChipComplex<F, Self::Executor, Self::Periphery>, VmInventoryError>;
+
+    /// Checks that the system and memory configuration are consistent and that the extensions
+    /// do not claim the same opcodes, phantom discriminants or bus indices.
+    ///
+    /// This builds the chip complex, so conflicts that would otherwise only surface during
+    /// execution or trace generation are reported upfront.
+    fn validate(&self) -> Result<(), VmConfigError> {
+        self.system().validate()?;
+        let complex = self.create_chip_complex()?;
+        complex.check_bus_allocation()
+    }
+}
+
+/// Pointers are range checked with [MemoryConfig::decomp] limbs, and must fit in a field element.
+const MAX_POINTER_MAX_BITS: usize = 29;
+
+#[derive(thiserror::Error, Debug)]
+pub enum VmConfigError {
+    #[error("as_offset must be positive since address space 0 is reserved for immediates")]
+    ImmediateAddressSpace,
+    #[error("pointer_max_bits is {pointer_max_bits} but at most {max} is supported")]
+    PointerMaxBits { pointer_max_bits: usize, max: usize },
+    #[error("clk_max_bits is {clk_max_bits} but at most {max} is supported")]
+    ClkMaxBits { clk_max_bits: usize, max: usize },
+    #[error("max_access_adapter_n {0} is not a power of two")]
+    AccessAdapterSize(usize),
+    #[error("Address space {address_space} is out of range")]
+    AddressSpaceOutOfRange { address_space: u32 },
+    #[error("Address space {address_space} has more pointer bits than pointer_max_bits")]
+    AddressSpacePointerBits { address_space: u32 },
+    #[error("Word size of address space {address_space} is not a power of two")]
+    WordSize { address_space: u32 },
+    #[error("Address space {address_space} is declared more than once")]
+    DuplicateAddressSpace { address_space: u32 },
+    #[error("{count} address spaces are read-only but at most {max} are supported")]
+    ReadOnlyAddressSpaces { count: usize, max: usize },
+    #[error("Public values address space is out of range of as_height")]
+    PublicValuesAddressSpace,
+    #[error("max_segment_len and max_segment_instructions must be positive")]
+    ZeroSegmentLen,
+    #[error("Carry-over value {name} is declared more than once or is empty")]
+    InvalidCarryOver { name: String },
+    #[error("Key-value store of depth {depth} requires continuations and a depth in 1..=32")]
+    InvalidKvStore { depth: usize },
+    #[error("Bus index {bus} of a system chip is not allocated to the system")]
+    BusCollision { bus: usize },
+    #[error(transparent)]
+    Inventory(#[from] VmInventoryError),
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone, new, Copy)]
+#[derive(Debug, Serialize, Deserialize, Clone, new)]
 pub struct MemoryConfig {
     /// The maximum height of the address space. This means the trie has `as_height` layers for searching the address space. The allowed address spaces are those in the range `[as_offset, as_offset + 2^as_height)` where `as_offset` is currently fixed to `1` to not allow address space `0` in memory.
     pub as_height: usize,
@@ -52,8 +104,150 @@ pub struct MemoryConfig {
     pub clk_max_bits: usize,
     /// Limb size used by the range checker
     pub decomp: usize,
-    /// Maximum N AccessAdapter AIR to support.
+    /// Maximum N AccessAdapter AIR to support. Must be a power of two, since access adapters only
+    /// split and merge aligned power-of-two blocks.
     pub max_access_adapter_n: usize,
+    /// Descriptors of address spaces with custom semantics. Address spaces without a descriptor
+    /// use [AddressSpaceDescriptor::unrestricted].
+    #[new(default)]
+    #[serde(default)]
+    pub address_spaces: Vec<AddressSpaceDescriptor>,
+}
+
+impl MemoryConfig {
+    /// Declares an address space, replacing any previous descriptor for the same address space.
+    ///
+    /// Panics if the address space is out of range, if its pointer bits exceed
+    /// [MemoryConfig::pointer_max_bits], or if its word size is not a power of two.
+    pub fn with_address_space(mut self, descriptor: AddressSpaceDescriptor) -> Self {
+        let address_space = descriptor.address_space;
+        assert!(
+            address_space >= self.as_offset
+                && address_space - self.as_offset < (1 << self.as_height),
+            "address space {address_space} is out of range"
+        );
+        assert!(
+            descriptor.pointer_max_bits <= self.pointer_max_bits,
+            "address space {address_space} has more than {} pointer bits",
+            self.pointer_max_bits
+        );
+        assert!(
+            descriptor.word_size.is_power_of_two(),
+            "word size of address space {address_space} is not a power of two"
+        );
+        self.address_spaces
+            .retain(|d| d.address_space != address_space);
+        self.address_spaces.push(descriptor);
+        self
+    }
+
+    /// Checks that the configuration is consistent, see [VmConfig::validate].
+    pub fn validate(&self) -> Result<(), VmConfigError> {
+        if self.as_offset == 0 {
+            return Err(VmConfigError::ImmediateAddressSpace);
+        }
+        if self.pointer_max_bits > MAX_POINTER_MAX_BITS {
+            return Err(VmConfigError::PointerMaxBits {
+                pointer_max_bits: self.pointer_max_bits,
+                max: MAX_POINTER_MAX_BITS,
+            });
+        }
+        if self.clk_max_bits > MAX_CLK_BITS {
+            return Err(VmConfigError::ClkMaxBits {
+                clk_max_bits: self.clk_max_bits,
+                max: MAX_CLK_BITS,
+            });
+        }
+        if !self.max_access_adapter_n.is_power_of_two() {
+            return Err(VmConfigError::AccessAdapterSize(self.max_access_adapter_n));
+        }
+        for (i, descriptor) in self.address_spaces.iter().enumerate() {
+            let address_space = descriptor.address_space;
+            if address_space < self.as_offset
+                || address_space - self.as_offset >= (1 << self.as_height)
+            {
+                return Err(VmConfigError::AddressSpaceOutOfRange { address_space });
+            }
+            if descriptor.pointer_max_bits > self.pointer_max_bits {
+                return Err(VmConfigError::AddressSpacePointerBits { address_space });
+            }
+            if !descriptor.word_size.is_power_of_two() {
+                return Err(VmConfigError::WordSize { address_space });
+            }
+            if self.address_spaces[..i]
+                .iter()
+                .any(|d| d.address_space == address_space)
+            {
+                return Err(VmConfigError::DuplicateAddressSpace { address_space });
+            }
+        }
+        let num_read_only = self.read_only_address_spaces().len();
+        if num_read_only > MAX_READ_ONLY_ADDRESS_SPACES {
+            return Err(VmConfigError::ReadOnlyAddressSpaces {
+                count: num_read_only,
+                max: MAX_READ_ONLY_ADDRESS_SPACES,
+            });
+        }
+        Ok(())
+    }
+
+    /// Returns the descriptor of `address_space`.
+    pub fn address_space(&self, address_space: u32) -> AddressSpaceDescriptor {
+        self.address_spaces
+            .iter()
+            .find(|d| d.address_space == address_space)
+            .copied()
+            .unwrap_or_else(|| AddressSpaceDescriptor::unrestricted(address_space, self))
+    }
+
+    /// The address spaces declared read-only.
+    pub fn read_only_address_spaces(&self) -> Vec<u32> {
+        self.address_spaces
+            .iter()
+            .filter(|d| d.read_only)
+            .map(|d| d.address_space)
+            .collect()
+    }
+
+    /// Number of bits of the largest allowed address space, `as_offset + 2^as_height - 1`.
+    pub fn addr_space_max_bits(&self) -> usize {
+        let max_addr_space = self.as_offset as u64 + (1 << self.as_height) - 1;
+        (u64::BITS - max_addr_space.leading_zeros()) as usize
+    }
+}
+
+/// Describes how an address space may be accessed.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub struct AddressSpaceDescriptor {
+    pub address_space: u32,
+    /// Every access must cover a multiple of this many cells.
+    pub word_size: usize,
+    /// Pointers into the address space must be less than `2^pointer_max_bits`.
+    pub pointer_max_bits: usize,
+    /// Whether the address space can only be read once initialized, e.g. program ROM or lookup
+    /// tables. A write to it fails execution with
+    /// [ExecutionError::ReadOnlyWrite](crate::arch::ExecutionError::ReadOnlyWrite), while the
+    /// initial memory may still be set or injected.
+    ///
+    /// With persistent memory, the boundary chip constrains the final values of every touched
+    /// chunk of the address space to equal its initial values. Volatile memory has no committed
+    /// initial memory, so there is nothing to constrain. At most
+    /// [MAX_READ_ONLY_ADDRESS_SPACES] address spaces can be read-only.
+    #[serde(default)]
+    pub read_only: bool,
+}
+
+impl AddressSpaceDescriptor {
+    /// The descriptor of an undeclared address space: word size `1`, the global pointer bound and
+    /// writable.
+    pub fn unrestricted(address_space: u32, memory_config: &MemoryConfig) -> Self {
+        Self {
+            address_space,
+            word_size: 1,
+            pointer_max_bits: memory_config.pointer_max_bits,
+            read_only: false,
+        }
+    }
 }
 
 impl Default for MemoryConfig {
@@ -86,9 +280,80 @@ pub struct SystemConfig {
     pub num_public_values: usize,
     /// When continuations are enabled, a heuristic used to determine when to segment execution.
     pub max_segment_len: usize,
+    /// Additional limits to segment execution at, on top of `max_segment_len`. A segment is cut
+    /// as soon as any limit is exceeded.
+    #[serde(default)]
+    pub segmentation_limits: Vec<SegmentationLimit>,
     /// Whether to collect metrics.
     /// **Warning**: this slows down the runtime.
     pub collect_metrics: bool,
+    /// If set, metric collection also attributes cycles and trace cells to buckets of this many
+    /// bytes of program counter, for hot-spot reports. Has no effect unless `collect_metrics` is
+    /// true.
+    #[serde(default)]
+    pub pc_profile_bucket_size: Option<u32>,
+    /// If set, execution fails with [ExecutionError::BudgetExceeded](super::ExecutionError)
+    /// once more than this many instructions were executed, over all segments.
+    #[serde(default)]
+    pub max_cycles: Option<u64>,
+    /// If set, execution fails with [ExecutionError::BudgetExceeded](super::ExecutionError)
+    /// once the traces of all segments have more than this many cells. Checked every few
+    /// instructions only, so the traces may slightly overshoot the budget.
+    #[serde(default)]
+    pub max_trace_cells: Option<usize>,
+    /// If set, execution fails with
+    /// [ExecutionError::InstructionLimitExceeded](super::ExecutionError) instead of executing
+    /// more than this many instructions in one segment. Unlike `max_segment_len`, this also
+    /// applies without continuations.
+    #[serde(default)]
+    pub max_segment_instructions: Option<u64>,
+    /// Whether to record an execution timeline in Chrome trace-event format. See
+    /// [TraceEventRecorder](crate::metrics::TraceEventRecorder).
+    #[serde(default)]
+    pub collect_trace_events: bool,
+    /// Values that each segment imports from the previous segment and exports to the next one,
+    /// in addition to the pc and the memory. See [Self::with_carry_over].
+    #[serde(default)]
+    pub carry_over: Vec<CarryOverValue>,
+    /// Where guest log messages are read from. See [Self::with_guest_log].
+    #[serde(default)]
+    pub guest_log: GuestLogConfig,
+    /// If set, the VM has a key-value store chip for Merkle trees of this depth. See
+    /// [Self::with_kv_store].
+    #[serde(default)]
+    pub kv_store_depth: Option<usize>,
+}
+
+/// A named vector of field elements carried over between segments, e.g. a running transcript
+/// hash.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CarryOverValue {
+    pub name: String,
+    pub len: usize,
+}
+
+/// Memory layout of the [SysPhantom::DebugLog](openvm_instructions::SysPhantom::DebugLog)
+/// instruction, which guests use to log messages to the host.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GuestLogConfig {
+    /// If false, log instructions are executed as no-ops.
+    pub enabled: bool,
+    /// The message pointer and length are stored little-endian in the 4 byte cells at the
+    /// operands of the instruction.
+    pub register_address_space: u32,
+    /// The message, with one byte per cell.
+    pub memory_address_space: u32,
+}
+
+impl Default for GuestLogConfig {
+    /// The layout of the RV32IM extension.
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            register_address_space: 1,
+            memory_address_space: 2,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -109,7 +374,16 @@ impl SystemConfig {
             memory_config,
             num_public_values,
             max_segment_len: DEFAULT_MAX_SEGMENT_LEN,
+            segmentation_limits: vec![],
             collect_metrics: false,
+            pc_profile_bucket_size: None,
+            max_cycles: None,
+            max_trace_cells: None,
+            max_segment_instructions: None,
+            collect_trace_events: false,
+            carry_over: vec![],
+            guest_log: GuestLogConfig::default(),
+            kv_store_depth: None,
         }
     }
 
@@ -138,6 +412,19 @@ impl SystemConfig {
         self
     }
 
+    pub fn with_segmentation_limit(mut self, limit: SegmentationLimit) -> Self {
+        self.segmentation_limits.push(limit);
+        self
+    }
+
+    /// The limits execution is segmented at: `max_segment_len` followed by the additional
+    /// `segmentation_limits`.
+    pub fn segmentation_strategy(&self) -> Vec<SegmentationLimit> {
+        let mut limits = vec![SegmentationLimit::MaxHeight(self.max_segment_len)];
+        limits.extend_from_slice(&self.segmentation_limits);
+        limits
+    }
+
     pub fn with_metric_collection(mut self) -> Self {
         self.collect_metrics = true;
         self
@@ -148,6 +435,122 @@ impl SystemConfig {
         self
     }
 
+    /// Enables metric collection with a PC profile in buckets of `bucket_size` bytes. Use a
+    /// bucket size of [DEFAULT_PC_STEP](openvm_instructions::program::DEFAULT_PC_STEP) for a
+    /// per-instruction profile.
+    pub fn with_pc_profile(mut self, bucket_size: u32) -> Self {
+        assert!(bucket_size > 0, "PC profile bucket size must be positive");
+        self.collect_metrics = true;
+        self.pc_profile_bucket_size = Some(bucket_size);
+        self
+    }
+
+    pub fn with_max_cycles(mut self, max_cycles: u64) -> Self {
+        self.max_cycles = Some(max_cycles);
+        self
+    }
+
+    pub fn with_max_trace_cells(mut self, max_trace_cells: usize) -> Self {
+        self.max_trace_cells = Some(max_trace_cells);
+        self
+    }
+
+    pub fn with_max_segment_instructions(mut self, max_segment_instructions: u64) -> Self {
+        self.max_segment_instructions = Some(max_segment_instructions);
+        self
+    }
+
+    pub fn with_trace_events(mut self) -> Self {
+        self.collect_trace_events = true;
+        self
+    }
+
+    /// Reads guest log messages with the layout of `guest_log`. Messages are passed to the
+    /// logger of the [VmExecutor](super::VmExecutor), or to `tracing` if it has none.
+    pub fn with_guest_log(mut self, guest_log: GuestLogConfig) -> Self {
+        self.guest_log = guest_log;
+        self
+    }
+
+    pub fn without_guest_log(mut self) -> Self {
+        self.guest_log.enabled = false;
+        self
+    }
+
+    /// Adds the [KvStoreChip](crate::system::kv_store::KvStoreChip), which looks values up in
+    /// the [KvStore](crate::system::kv_store::KvStore) of depth `depth` provided with the
+    /// [Streams](super::Streams). Requires continuations, as the Merkle proofs are checked by the
+    /// Poseidon2 chip.
+    pub fn with_kv_store(mut self, depth: usize) -> Self {
+        self.kv_store_depth = Some(depth);
+        self
+    }
+
+    /// Declares a carry-over value of `len` field elements.
+    ///
+    /// The connector chip exposes the values at the start and at the end of each segment as
+    /// public values, after the [VmConnectorPvs](crate::system::connector::VmConnectorPvs), and
+    /// sends and receives them on the [CarryOverBus](crate::system::connector::CarryOverBus).
+    /// Chips change the values during execution through
+    /// [CarryOverValues](crate::system::connector::CarryOverValues) and the bus. Verifiers must
+    /// check that the initial values of the first segment are zero, and that the initial values of
+    /// every other segment are the final values of the previous one, as they do for the pc.
+    /// [VirtualMachine::verify](super::VirtualMachine::verify) does, but the SDK aggregation
+    /// verifiers do not, so the SDK rejects configurations with carry-over values.
+    pub fn with_carry_over(mut self, name: impl Into<String>, len: usize) -> Self {
+        self.carry_over.push(CarryOverValue {
+            name: name.into(),
+            len,
+        });
+        self
+    }
+
+    /// Index of the first element of carry-over value `name` among all carry-over elements.
+    pub fn carry_over_offset(&self, name: &str) -> Option<usize> {
+        let mut offset = 0;
+        for value in &self.carry_over {
+            if value.name == name {
+                return Some(offset);
+            }
+            offset += value.len;
+        }
+        None
+    }
+
+    /// Total number of carry-over field elements.
+    pub fn num_carry_over(&self) -> usize {
+        self.carry_over.iter().map(|value| value.len).sum()
+    }
+
+    /// Checks that the configuration is consistent, see [VmConfig::validate].
+    pub fn validate(&self) -> Result<(), VmConfigError> {
+        self.memory_config.validate()?;
+        if self.max_segment_len == 0 || self.max_segment_instructions == Some(0) {
+            return Err(VmConfigError::ZeroSegmentLen);
+        }
+        for (i, value) in self.carry_over.iter().enumerate() {
+            if value.len == 0 || self.carry_over[..i].iter().any(|v| v.name == value.name) {
+                return Err(VmConfigError::InvalidCarryOver {
+                    name: value.name.clone(),
+                });
+            }
+        }
+        if let Some(depth) = self.kv_store_depth {
+            if !self.continuation_enabled || !(1..=32).contains(&depth) {
+                return Err(VmConfigError::InvalidKvStore { depth });
+            }
+        }
+        // With continuations, public values are stored in address space
+        // `as_offset + PUBLIC_VALUES_ADDRESS_SPACE_OFFSET`.
+        if self.continuation_enabled
+            && self.num_public_values > 0
+            && PUBLIC_VALUES_ADDRESS_SPACE_OFFSET >= 1 << self.memory_config.as_height
+        {
+            return Err(VmConfigError::PublicValuesAddressSpace);
+        }
+        Ok(())
+    }
+
     pub fn has_public_values_chip(&self) -> bool {
         !self.continuation_enabled && self.num_public_values > 0
     }
@@ -161,6 +564,15 @@ impl SystemConfig {
         ret += BOUNDARY_AIR_OFFSET;
         ret
     }
+
+    /// Returns the AIR ID of the key-value store AIR in a VM with `num_airs` AIRs, such as the
+    /// AIRs of a verifying key, or `None` if the key-value store is not enabled.
+    pub fn kv_store_air_id(&self, num_airs: usize) -> Option<usize> {
+        // Non-system AIRs are ordered by reverse insertion. The key-value store chip is added
+        // right after the Poseidon2 periphery chip, so its AIR is only followed by the Poseidon2
+        // and range checker AIRs.
+        self.kv_store_depth.map(|_| num_airs - 3)
+    }
 }
 
 impl Default for SystemConfig {
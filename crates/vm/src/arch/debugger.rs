@@ -0,0 +1,91 @@
+use std::sync::Arc;
+
+use openvm_stark_backend::p3_field::PrimeField32;
+use parking_lot::Mutex;
+
+use super::ProgressAction;
+use crate::system::memory::MemoryController;
+
+/// Inspects and controls guest execution instruction by instruction, e.g. a
+/// [GdbServer](super::GdbServer).
+pub trait Debugger<F>: Send {
+    /// Called before the instruction at `pc` is executed. Execution stops with
+    /// [ExecutionError::Aborted](super::ExecutionError::Aborted) if this returns
+    /// [ProgressAction::Abort].
+    fn on_instruction(&mut self, pc: u32, memory: &MemoryController<F>) -> ProgressAction;
+
+    /// Called when the program terminated with `exit_code`.
+    fn on_terminate(&mut self, exit_code: u32);
+}
+
+pub type SharedDebugger<F> = Arc<Mutex<dyn Debugger<F>>>;
+
+/// Address space of the RV32IM registers: register `i` is stored little-endian in the 4 byte
+/// cells at pointer `4 * i`.
+const RV32_REGISTER_AS: u32 = 1;
+const RV32_NUM_REGISTERS: usize = 32;
+
+/// Read-only view of the architectural state between two instructions, passed to
+/// inspection hooks, see [VmExecutor::set_inspection_hook](super::VmExecutor::set_inspection_hook).
+///
+/// Reads go around the memory controller's bookkeeping, so they are cheap and do not affect
+/// the traces.
+pub struct ArchState<'a, F> {
+    segment_idx: usize,
+    pc: u32,
+    memory: &'a MemoryController<F>,
+}
+
+impl<'a, F: PrimeField32> ArchState<'a, F> {
+    pub fn new(segment_idx: usize, pc: u32, memory: &'a MemoryController<F>) -> Self {
+        Self {
+            segment_idx,
+            pc,
+            memory,
+        }
+    }
+
+    pub fn segment_idx(&self) -> usize {
+        self.segment_idx
+    }
+
+    /// The pc of the next instruction.
+    pub fn pc(&self) -> u32 {
+        self.pc
+    }
+
+    pub fn timestamp(&self) -> u32 {
+        self.memory.timestamp()
+    }
+
+    /// The registers of an RV32IM guest.
+    pub fn registers(&self) -> [u32; RV32_NUM_REGISTERS] {
+        std::array::from_fn(|i| {
+            let bytes = self
+                .memory
+                .unsafe_read::<4>(
+                    F::from_canonical_u32(RV32_REGISTER_AS),
+                    F::from_canonical_usize(4 * i),
+                )
+                .map(|byte| byte.as_canonical_u32() as u8);
+            u32::from_le_bytes(bytes)
+        })
+    }
+
+    /// The `len` cells at `[ptr, ptr + len)` of address space `address_space`. Cells which were
+    /// never written are zero.
+    pub fn read_memory(&self, address_space: u32, ptr: u32, len: usize) -> Vec<F> {
+        let address_space = F::from_canonical_u32(address_space);
+        (0..len as u32)
+            .map(|i| {
+                self.memory
+                    .unsafe_read_cell(address_space, F::from_canonical_u32(ptr + i))
+            })
+            .collect()
+    }
+}
+
+/// Called on the [ArchState] every few instructions, e.g. to check guest invariants. Execution
+/// stops with [ExecutionError::InspectionFailed](super::ExecutionError::InspectionFailed) if it
+/// returns an error.
+pub type InspectionHook<F> = Arc<Mutex<dyn FnMut(&ArchState<F>) -> eyre::Result<()> + Send>>;
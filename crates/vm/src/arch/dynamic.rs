@@ -0,0 +1,76 @@
+use std::{ops::Range, sync::Arc};
+
+use openvm_instructions::VmOpcode;
+use parking_lot::Mutex;
+use rustc_hash::FxHashMap;
+
+use super::{InstructionExecutor, VmInventoryError};
+
+/// An [InstructionExecutor] registered at runtime with
+/// [VmExecutor::register_executor](super::VmExecutor::register_executor).
+pub type DynamicExecutor<F> = Arc<Mutex<Box<dyn InstructionExecutor<F> + Send>>>;
+
+/// Executors registered at runtime for ranges of opcodes, on top of the executors of the
+/// [VmConfig](super::VmConfig) extensions. This allows prototyping new instructions without
+/// writing an extension.
+///
+/// Dynamic executors only take part in execution: they have no AIR, so segments that used them
+/// cannot be proven.
+pub struct DynamicExecutors<F> {
+    lookup: FxHashMap<VmOpcode, usize>,
+    executors: Vec<DynamicExecutor<F>>,
+}
+
+impl<F> DynamicExecutors<F> {
+    /// Registers `executor` for all opcodes in `opcodes`.
+    pub fn register(
+        &mut self,
+        opcodes: Range<usize>,
+        executor: DynamicExecutor<F>,
+    ) -> Result<(), VmInventoryError> {
+        if let Some(opcode) = opcodes
+            .clone()
+            .map(VmOpcode::from_usize)
+            .find(|opcode| self.lookup.contains_key(opcode))
+        {
+            return Err(VmInventoryError::OpcodeExists { opcode });
+        }
+        let id = self.executors.len();
+        self.executors.push(executor);
+        self.lookup
+            .extend(opcodes.map(|opcode| (VmOpcode::from_usize(opcode), id)));
+        Ok(())
+    }
+
+    pub fn get(&self, opcode: &VmOpcode) -> Option<&DynamicExecutor<F>> {
+        let id = self.lookup.get(opcode)?;
+        self.executors.get(*id)
+    }
+
+    /// All opcodes with a dynamic executor.
+    pub fn opcodes(&self) -> impl Iterator<Item = VmOpcode> + '_ {
+        self.lookup.keys().copied()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.executors.is_empty()
+    }
+}
+
+impl<F> Default for DynamicExecutors<F> {
+    fn default() -> Self {
+        Self {
+            lookup: FxHashMap::default(),
+            executors: Vec::new(),
+        }
+    }
+}
+
+impl<F> Clone for DynamicExecutors<F> {
+    fn clone(&self) -> Self {
+        Self {
+            lookup: self.lookup.clone(),
+            executors: self.executors.clone(),
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use std::{cell::RefCell, rc::Rc};
+use std::{cell::RefCell, rc::Rc, time::Duration};
 
 use openvm_circuit_primitives_derive::AlignedBorrow;
 use openvm_instructions::{
@@ -7,8 +7,11 @@ use openvm_instructions::{
 use openvm_stark_backend::{interaction::InteractionBuilder, p3_field::AbstractField};
 use thiserror::Error;
 
-use super::Streams;
-use crate::system::{memory::MemoryController, program::ProgramBus};
+use super::{ExecutionBudgetUsage, Streams};
+use crate::{
+    metrics::VmMetrics,
+    system::{memory::MemoryController, program::ProgramBus},
+};
 
 pub type Result<T> = std::result::Result<T, ExecutionError>;
 
@@ -34,6 +37,16 @@ pub enum ExecutionError {
     DisabledOperation { pc: u32, opcode: VmOpcode },
     #[error("at pc = {pc}")]
     HintOutOfBounds { pc: u32 },
+    #[error("at pc {pc}, division by zero")]
+    DivisionByZero { pc: u32 },
+    #[error("at pc {pc}, signed division overflow")]
+    DivisionOverflow { pc: u32 },
+    #[error("at pc {pc}, memory access at address {address} is not aligned to {alignment} bytes")]
+    MisalignedMemoryAccess {
+        pc: u32,
+        address: u32,
+        alignment: u32,
+    },
     #[error("at pc {pc}, tried to publish into index {public_value_index} when num_public_values = {num_public_values}")]
     PublicValueIndexOutOfBounds {
         pc: u32,
@@ -58,6 +71,61 @@ pub enum ExecutionError {
         discriminant: PhantomDiscriminant,
         inner: eyre::Error,
     },
+    #[error("at pc {pc}, execution diverged from the replayed execution log")]
+    ReplayDivergence { pc: u32 },
+    #[error("at pc {pc}, execution aborted by the progress callback")]
+    Aborted { pc: u32 },
+    #[error("at pc {pc}, inspection hook failed: {inner}")]
+    InspectionFailed { pc: u32, inner: eyre::Error },
+    #[error("at pc {pc}, execution exceeded its budget after {} cycles and {} trace cells", .usage.cycles, .usage.trace_cells)]
+    BudgetExceeded {
+        pc: u32,
+        usage: ExecutionBudgetUsage,
+        /// Metrics collected up to the abort. Empty unless metric collection is enabled.
+        metrics: Box<VmMetrics>,
+    },
+    #[error("at pc {pc}, the segment reached its limit of {limit} instructions")]
+    InstructionLimitExceeded { pc: u32, limit: u64 },
+    #[error("at pc {pc}, the hint provider did not answer within {timeout:?}")]
+    HintTimeout { pc: u32, timeout: Duration },
+    #[error("at pc {pc}, no key-value store of depth {depth} was provided")]
+    KvStoreUnavailable { pc: u32, depth: usize },
+    #[error("at pc {pc}, key {key} is not in the key-value store")]
+    KvStoreKeyNotFound { pc: u32, key: u32 },
+    #[error("at pc {pc}, write to [{address_space}:{pointer}] in a read-only address space")]
+    ReadOnlyWrite {
+        pc: u32,
+        address_space: u32,
+        pointer: u32,
+    },
+}
+impl ExecutionError {
+    /// The pc of the instruction at which execution failed.
+    pub fn pc(&self) -> u32 {
+        match self {
+            Self::Fail { pc, .. }
+            | Self::PcNotFound { pc, .. }
+            | Self::PcOutOfBounds { pc, .. }
+            | Self::DisabledOperation { pc, .. }
+            | Self::HintOutOfBounds { pc, .. }
+            | Self::DivisionByZero { pc, .. }
+            | Self::DivisionOverflow { pc, .. }
+            | Self::MisalignedMemoryAccess { pc, .. }
+            | Self::PublicValueIndexOutOfBounds { pc, .. }
+            | Self::PublicValueNotEqual { pc, .. }
+            | Self::PhantomNotFound { pc, .. }
+            | Self::Phantom { pc, .. }
+            | Self::ReplayDivergence { pc, .. }
+            | Self::Aborted { pc, .. }
+            | Self::InspectionFailed { pc, .. }
+            | Self::BudgetExceeded { pc, .. }
+            | Self::InstructionLimitExceeded { pc, .. }
+            | Self::HintTimeout { pc, .. }
+            | Self::KvStoreUnavailable { pc, .. }
+            | Self::KvStoreKeyNotFound { pc, .. }
+            | Self::ReadOnlyWrite { pc, .. } => *pc,
+        }
+    }
 }
 
 pub trait InstructionExecutor<F> {
@@ -290,7 +358,7 @@ impl<T: AbstractField> From<(u32, Option<T>)> for PcIncOrSet<T> {
 ///
 /// Phantom sub-instructions are only allowed to use operands
 /// `a,b` and `c_upper = c.as_canonical_u32() >> 16`.
-pub trait PhantomSubExecutor<F> {
+pub trait PhantomSubExecutor<F>: Send {
     fn phantom_execute(
         &mut self,
         memory: &MemoryController<F>,
@@ -300,4 +368,10 @@ pub trait PhantomSubExecutor<F> {
         b: F,
         c_upper: u16,
     ) -> eyre::Result<()>;
+
+    /// Name of the sub-executor, listed by
+    /// [VmChipComplex::phantom_sub_executors](super::VmChipComplex::phantom_sub_executors).
+    fn name(&self) -> String {
+        std::any::type_name::<Self>().to_string()
+    }
 }
@@ -0,0 +1,47 @@
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+
+/// Effect of a phantom instruction executed by a phantom sub-executor.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound(serialize = "F: Serialize", deserialize = "F: Deserialize<'de>"))]
+pub struct PhantomRecord<F> {
+    pub pc: u32,
+    /// The hint stream after the instruction, or `None` if the instruction left it unchanged.
+    pub hint_stream: Option<VecDeque<F>>,
+}
+
+/// The host-side effects of a run, in order: the hints computed by phantom sub-executors, which
+/// may have consumed inputs or queried a [HintProvider](super::HintProvider).
+///
+/// Phantom instructions cannot write memory, so everything else a run does is determined by the
+/// program and its initial memory. Replaying the log with [Streams::replay](super::Streams::replay)
+/// therefore re-executes the run, e.g. to generate traces on another machine, without running
+/// any host hint logic again.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound(serialize = "F: Serialize", deserialize = "F: Deserialize<'de>"))]
+pub struct ExecutionLog<F> {
+    pub phantoms: Vec<PhantomRecord<F>>,
+}
+
+impl<F> Default for ExecutionLog<F> {
+    fn default() -> Self {
+        Self {
+            phantoms: Vec::new(),
+        }
+    }
+}
+
+/// Whether the [Streams](super::Streams) of a run record or replay an [ExecutionLog].
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(bound(serialize = "F: Serialize", deserialize = "F: Deserialize<'de>"))]
+pub enum ExecutionLogMode<F> {
+    #[default]
+    Off,
+    Record(ExecutionLog<F>),
+    Replay {
+        log: ExecutionLog<F>,
+        /// Index of the next phantom record to replay.
+        next: usize,
+    },
+}
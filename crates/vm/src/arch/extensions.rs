@@ -1,5 +1,6 @@
-use std::{any::Any, cell::RefCell, iter::once, rc::Rc, sync::Arc};
+use std::{any::Any, cell::RefCell, iter::once, sync::Arc};
 
+use atomic_refcell::AtomicRefCell;
 use derive_more::derive::From;
 use getset::Getters;
 use openvm_circuit_derive::{AnyEnum, InstructionExecutor};
@@ -9,13 +10,15 @@ use openvm_circuit_primitives::{
 };
 use openvm_circuit_primitives_derive::{Chip, ChipUsageGetter};
 use openvm_instructions::{
-    program::Program, PhantomDiscriminant, PublishOpcode, SystemOpcode, UsizeOpcode, VmOpcode,
+    program::Program, KvStoreOpcode, PhantomDiscriminant, PublishOpcode, SystemOpcode, UsizeOpcode,
+    VmOpcode,
 };
 use openvm_stark_backend::{
     config::{Domain, StarkGenericConfig},
     p3_commit::PolynomialSpace,
     p3_field::{AbstractField, PrimeField32},
     p3_matrix::Matrix,
+    p3_maybe_rayon::prelude::*,
     prover::types::{AirProofInput, CommittedTraceData, ProofInput},
     rap::AnyRap,
     Chip, ChipUsageGetter,
@@ -25,11 +28,12 @@ use rustc_hash::FxHashMap;
 use serde::{Deserialize, Serialize};
 
 use super::{
-    vm_poseidon2_config, ExecutionBus, InstructionExecutor, PhantomSubExecutor, Streams,
-    SystemConfig, SystemTraceHeights,
+    hasher::HasherChip, vm_poseidon2_config, ExecutionBus, InstructionExecutor, PhantomSubExecutor,
+    Streams, SystemConfig, SystemTraceHeights, VmConfigError,
 };
 use crate::system::{
-    connector::VmConnectorChip,
+    connector::{CarryOverBus, CarryOverValues, VmConnectorChip},
+    kv_store::{core::KvStoreCoreChip, KvStoreChip},
     memory::{
         merkle::{DirectCompressionBus, MemoryMerkleBus},
         offline_checker::MemoryBus,
@@ -37,7 +41,7 @@ use crate::system::{
         MERKLE_AIR_OFFSET,
     },
     native_adapter::NativeAdapterChip,
-    phantom::PhantomChip,
+    phantom::{PhantomChip, PhantomSubExecutorInfo},
     poseidon2::Poseidon2PeripheryChip,
     program::{ProgramBus, ProgramChip},
     public_values::{core::PublicValuesCoreChip, PublicValuesChip},
@@ -57,10 +61,8 @@ pub const BOUNDARY_AIR_ID: usize = PUBLIC_VALUES_AIR_ID + 1 + BOUNDARY_AIR_OFFSE
 /// Merkle AIR commits start/final memory states.
 pub const MERKLE_AIR_ID: usize = CONNECTOR_AIR_ID + 1 + MERKLE_AIR_OFFSET;
 
-const EXECUTION_BUS: ExecutionBus = ExecutionBus(0);
-const MEMORY_BUS: MemoryBus = MemoryBus(1);
-const PROGRAM_BUS: ProgramBus = ProgramBus(2);
-const RANGE_CHECKER_BUS: usize = 3;
+/// Owner recorded by the [BusAllocator] for buses of the system chips.
+pub const SYSTEM_BUS_OWNER: &str = "System";
 
 /// Configuration for a processor extension.
 ///
@@ -91,13 +93,45 @@ pub struct SystemPort<F> {
     pub memory_controller: MemoryControllerRef<F>,
 }
 
+/// Hands out unique bus indices to the system and to extensions, so that composing extensions
+/// never reuses a bus. The owner of every index is recorded for debugging.
+#[derive(Clone, Debug, Default)]
+pub struct BusAllocator {
+    /// Owner of each allocated bus index.
+    owners: Vec<String>,
+}
+
+impl BusAllocator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allocates the next unused bus index to `owner`.
+    pub fn new_bus_idx(&mut self, owner: &str) -> usize {
+        self.owners.push(owner.to_string());
+        self.owners.len() - 1
+    }
+
+    /// Allocated bus indices are in range `[0, num_buses)`.
+    pub fn num_buses(&self) -> usize {
+        self.owners.len()
+    }
+
+    /// Returns the owner of `bus`, if it was allocated.
+    pub fn owner(&self, bus: usize) -> Option<&str> {
+        self.owners.get(bus).map(String::as_str)
+    }
+}
+
 /// Builder for processing unit. Processing units extend an existing system unit.
 pub struct VmInventoryBuilder<'a, F: PrimeField32> {
     system_config: &'a SystemConfig,
     system: &'a SystemBase<F>,
     streams: &'a Arc<Mutex<Streams<F>>>,
-    /// Bus indices are in range [0, bus_idx_max)
-    bus_idx_max: usize,
+    bus_allocator: BusAllocator,
+    /// Owner recorded for buses allocated with [VmInventoryBuilder::new_bus_idx] and for phantom
+    /// sub-executors.
+    bus_owner: String,
     /// Chips that are already included in the chipset and may be used
     /// as dependencies. The order should be that depended-on chips are ordered
     /// **before** their dependents.
@@ -109,13 +143,14 @@ impl<'a, F: PrimeField32> VmInventoryBuilder<'a, F> {
         system_config: &'a SystemConfig,
         system: &'a SystemBase<F>,
         streams: &'a Arc<Mutex<Streams<F>>>,
-        bus_idx_max: usize,
+        bus_allocator: BusAllocator,
     ) -> Self {
         Self {
             system_config,
             system,
             streams,
-            bus_idx_max,
+            bus_allocator,
+            bus_owner: "Extension".to_string(),
             chips: Vec::new(),
         }
     }
@@ -140,10 +175,13 @@ impl<'a, F: PrimeField32> VmInventoryBuilder<'a, F> {
         }
     }
 
+    /// Allocates a bus index that is not used by the system or by any other extension.
     pub fn new_bus_idx(&mut self) -> usize {
-        let idx = self.bus_idx_max;
-        self.bus_idx_max += 1;
-        idx
+        self.bus_allocator.new_bus_idx(&self.bus_owner)
+    }
+
+    pub fn bus_allocator(&self) -> &BusAllocator {
+        &self.bus_allocator
     }
 
     /// Looks through built chips to see if there exists any of type `C` by downcasting.
@@ -157,6 +195,10 @@ impl<'a, F: PrimeField32> VmInventoryBuilder<'a, F> {
             .collect()
     }
 
+    /// Registers `phantom_sub` to execute the phantom instructions with discriminant
+    /// `discriminant`, which must not be used by another extension or by a
+    /// [SysPhantom](openvm_instructions::SysPhantom).
+    ///
     /// The generic `F` must match that of the `PhantomChip<F>`.
     pub fn add_phantom_sub_executor<PE: PhantomSubExecutor<F> + 'static>(
         &self,
@@ -165,12 +207,9 @@ impl<'a, F: PrimeField32> VmInventoryBuilder<'a, F> {
     ) -> Result<(), VmInventoryError> {
         let chip_ref: &RefCell<PhantomChip<F>> =
             self.find_chip().first().expect("PhantomChip always exists");
-        let mut chip = chip_ref.borrow_mut();
-        let existing = chip.add_sub_executor(phantom_sub, discriminant);
-        if existing.is_some() {
-            return Err(VmInventoryError::PhantomSubExecutorExists { discriminant });
-        }
-        Ok(())
+        chip_ref
+            .borrow_mut()
+            .add_sub_executor(phantom_sub, discriminant, &self.bus_owner)
     }
 
     /// Shareable streams. Clone to get a shared mutable reference.
@@ -185,8 +224,12 @@ impl<'a, F: PrimeField32> VmInventoryBuilder<'a, F> {
 
 #[derive(Clone, Debug)]
 pub struct VmInventory<E, P> {
-    /// Lookup table to executor ID. We store executors separately due to mutable borrow issues.
-    instruction_lookup: FxHashMap<VmOpcode, ExecutorId>,
+    /// Jump table from opcode to executor ID, indexed by [VmOpcode::as_usize]. We store
+    /// executors separately due to mutable borrow issues.
+    ///
+    /// Opcodes are small and dense (each extension claims a contiguous range from its offset), so
+    /// dispatching through a table is cheaper than hashing on every instruction.
+    executor_table: Vec<Option<ExecutorId>>,
     executors: Vec<E>,
     pub(super) periphery: Vec<P>,
     /// Order of insertion. The reverse of this will be the order the chips are destroyed
@@ -205,7 +248,7 @@ pub struct VmComplexTraceHeights {
     pub inventory: VmInventoryTraceHeights,
 }
 
-type ExecutorId = usize;
+pub(super) type ExecutorId = usize;
 
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ChipId {
@@ -217,10 +260,19 @@ pub enum ChipId {
 pub enum VmInventoryError {
     #[error("Opcode {opcode} already owned by executor id {id}")]
     ExecutorExists { opcode: VmOpcode, id: ExecutorId },
-    #[error("Phantom discriminant {} already has sub-executor", .discriminant.0)]
-    PhantomSubExecutorExists { discriminant: PhantomDiscriminant },
+    #[error("Opcode {opcode} already has an executor")]
+    OpcodeExists { opcode: VmOpcode },
+    #[error("Phantom discriminant {} already has sub-executor from {extension}", .discriminant.0)]
+    PhantomSubExecutorExists {
+        discriminant: PhantomDiscriminant,
+        extension: String,
+    },
+    #[error("Phantom discriminant {} is reserved for system phantom instructions", .discriminant.0)]
+    PhantomDiscriminantReserved { discriminant: PhantomDiscriminant },
     #[error("Chip {name} not found")]
     ChipNotFound { name: String },
+    #[error("Invalid config for {extension}: {reason}")]
+    InvalidConfig { extension: String, reason: String },
 }
 
 impl<E, P> Default for VmInventory<E, P> {
@@ -232,7 +284,7 @@ impl<E, P> Default for VmInventory<E, P> {
 impl<E, P> VmInventory<E, P> {
     pub fn new() -> Self {
         Self {
-            instruction_lookup: FxHashMap::default(),
+            executor_table: Vec::new(),
             executors: Vec::new(),
             periphery: Vec::new(),
             insertion_order: Vec::new(),
@@ -245,7 +297,7 @@ impl<E, P> VmInventory<E, P> {
         P: Into<P2>,
     {
         VmInventory {
-            instruction_lookup: self.instruction_lookup,
+            executor_table: self.executor_table,
             executors: self.executors.into_iter().map(|e| e.into()).collect(),
             periphery: self.periphery.into_iter().map(|p| p.into()).collect(),
             insertion_order: self.insertion_order,
@@ -256,9 +308,12 @@ impl<E, P> VmInventory<E, P> {
     pub fn append(&mut self, mut other: VmInventory<E, P>) -> Result<(), VmInventoryError> {
         let num_executors = self.executors.len();
         let num_periphery = self.periphery.len();
-        for (opcode, mut id) in other.instruction_lookup.into_iter() {
-            id += num_executors;
-            if let Some(old_id) = self.instruction_lookup.insert(opcode, id) {
+        for (opcode, id) in other.executor_table.into_iter().enumerate() {
+            let Some(id) = id else {
+                continue;
+            };
+            let opcode = VmOpcode::from_usize(opcode);
+            if let Some(old_id) = self.set_executor_id(opcode, id + num_executors) {
                 return Err(VmInventoryError::ExecutorExists { opcode, id: old_id });
             }
         }
@@ -283,23 +338,37 @@ impl<E, P> VmInventory<E, P> {
         opcodes: impl IntoIterator<Item = VmOpcode>,
     ) -> Result<(), VmInventoryError> {
         let opcodes: Vec<_> = opcodes.into_iter().collect();
-        for opcode in &opcodes {
-            if let Some(id) = self.instruction_lookup.get(opcode) {
-                return Err(VmInventoryError::ExecutorExists {
-                    opcode: *opcode,
-                    id: *id,
-                });
+        for &opcode in &opcodes {
+            if let Some(id) = self.executor_id(opcode) {
+                return Err(VmInventoryError::ExecutorExists { opcode, id });
             }
         }
         let id = self.executors.len();
         self.executors.push(executor.into());
         self.insertion_order.push(ChipId::Executor(id));
         for opcode in opcodes {
-            self.instruction_lookup.insert(opcode, id);
+            self.set_executor_id(opcode, id);
         }
         Ok(())
     }
 
+    pub(super) fn executor_id(&self, opcode: VmOpcode) -> Option<ExecutorId> {
+        self.executor_table
+            .get(opcode.as_usize())
+            .copied()
+            .flatten()
+    }
+
+    /// Points `opcode` to executor `id`, growing the jump table as needed. Returns the executor
+    /// previously owning `opcode`, if any.
+    fn set_executor_id(&mut self, opcode: VmOpcode, id: ExecutorId) -> Option<ExecutorId> {
+        let index = opcode.as_usize();
+        if index >= self.executor_table.len() {
+            self.executor_table.resize(index + 1, None);
+        }
+        self.executor_table[index].replace(id)
+    }
+
     pub fn add_periphery_chip(&mut self, periphery_chip: impl Into<P>) {
         let id = self.periphery.len();
         self.periphery.push(periphery_chip.into());
@@ -307,13 +376,27 @@ impl<E, P> VmInventory<E, P> {
     }
 
     pub fn get_executor(&self, opcode: VmOpcode) -> Option<&E> {
-        let id = self.instruction_lookup.get(&opcode)?;
-        self.executors.get(*id)
+        let id = self.executor_id(opcode)?;
+        self.executors.get(id)
     }
 
     pub fn get_mut_executor(&mut self, opcode: &VmOpcode) -> Option<&mut E> {
-        let id = self.instruction_lookup.get(opcode)?;
-        self.executors.get_mut(*id)
+        let id = self.executor_id(*opcode)?;
+        self.executors.get_mut(id)
+    }
+
+    /// The executor with `id`, as returned by `executor_id`.
+    pub(super) fn get_mut_executor_by_id(&mut self, id: ExecutorId) -> Option<&mut E> {
+        self.executors.get_mut(id)
+    }
+
+    /// All opcodes with an executor, in increasing order.
+    pub fn opcodes(&self) -> impl Iterator<Item = VmOpcode> + '_ {
+        self.executor_table
+            .iter()
+            .enumerate()
+            .filter(|(_, id)| id.is_some())
+            .map(|(opcode, _)| VmOpcode::from_usize(opcode))
     }
 
     pub fn executors(&self) -> &[E] {
@@ -424,8 +507,7 @@ pub struct VmChipComplex<F: PrimeField32, E, P> {
     overridden_inventory_heights: Option<VmInventoryTraceHeights>,
 
     streams: Arc<Mutex<Streams<F>>>,
-    /// System buses use indices [0, bus_idx_max)
-    bus_idx_max: usize,
+    bus_allocator: BusAllocator,
 }
 
 /// The base [VmChipComplex] with only system chips.
@@ -442,6 +524,9 @@ pub struct SystemBase<F> {
     pub program_chip: ProgramChip<F>,
 
     range_checker_bus: VariableRangeCheckerBus,
+    memory_bus: MemoryBus,
+    program_bus: ProgramBus,
+    execution_bus: ExecutionBus,
 }
 
 impl<F: PrimeField32> SystemBase<F> {
@@ -450,15 +535,20 @@ impl<F: PrimeField32> SystemBase<F> {
     }
 
     pub fn memory_bus(&self) -> MemoryBus {
-        MEMORY_BUS
+        self.memory_bus
     }
 
     pub fn program_bus(&self) -> ProgramBus {
-        PROGRAM_BUS
+        self.program_bus
     }
 
     pub fn execution_bus(&self) -> ExecutionBus {
-        EXECUTION_BUS
+        self.execution_bus
+    }
+
+    /// The carry-over values declared in the [SystemConfig], for the chips updating them.
+    pub fn carry_over(&self) -> &CarryOverValues<F> {
+        self.connector_chip.carry_over()
     }
 
     /// Return trace heights of SystemBase. Usually this is for aggregation and not useful for
@@ -485,6 +575,7 @@ impl<F: PrimeField32> SystemBase<F> {
 pub enum SystemExecutor<F: PrimeField32> {
     PublicValues(PublicValuesChip<F>),
     Phantom(RefCell<PhantomChip<F>>),
+    KvStore(KvStoreChip<F>),
 }
 
 #[derive(ChipUsageGetter, Chip, AnyEnum, From)]
@@ -495,38 +586,52 @@ pub enum SystemPeriphery<F: PrimeField32> {
 
 impl<F: PrimeField32> SystemComplex<F> {
     pub fn new(config: SystemConfig) -> Self {
-        let range_bus =
-            VariableRangeCheckerBus::new(RANGE_CHECKER_BUS, config.memory_config.decomp);
-        let mut bus_idx_max = RANGE_CHECKER_BUS;
+        let mut bus_allocator = BusAllocator::new();
+        let execution_bus = ExecutionBus(bus_allocator.new_bus_idx(SYSTEM_BUS_OWNER));
+        let memory_bus = MemoryBus(bus_allocator.new_bus_idx(SYSTEM_BUS_OWNER));
+        let program_bus = ProgramBus(bus_allocator.new_bus_idx(SYSTEM_BUS_OWNER));
+        let range_bus = VariableRangeCheckerBus::new(
+            bus_allocator.new_bus_idx(SYSTEM_BUS_OWNER),
+            config.memory_config.decomp,
+        );
 
         let range_checker = Arc::new(VariableRangeCheckerChip::new(range_bus));
         let memory_controller = if config.continuation_enabled {
-            bus_idx_max += 2;
+            let merkle_bus = MemoryMerkleBus(bus_allocator.new_bus_idx(SYSTEM_BUS_OWNER));
+            let compression_bus = DirectCompressionBus(bus_allocator.new_bus_idx(SYSTEM_BUS_OWNER));
             MemoryController::with_persistent_memory(
-                MEMORY_BUS,
-                config.memory_config,
+                memory_bus,
+                config.memory_config.clone(),
                 range_checker.clone(),
-                MemoryMerkleBus(bus_idx_max - 2),
-                DirectCompressionBus(bus_idx_max - 1),
+                merkle_bus,
+                compression_bus,
                 Equipartition::<F, CHUNK>::new(),
             )
         } else {
             MemoryController::with_volatile_memory(
-                MEMORY_BUS,
-                config.memory_config,
+                memory_bus,
+                config.memory_config.clone(),
                 range_checker.clone(),
             )
         };
-        let memory_controller = Rc::new(RefCell::new(memory_controller));
-        let program_chip = ProgramChip::new(PROGRAM_BUS);
-        let connector_chip = VmConnectorChip::new(EXECUTION_BUS, PROGRAM_BUS);
+        let memory_controller = Arc::new(AtomicRefCell::new(memory_controller));
+        let program_chip = ProgramChip::new(program_bus);
+        let num_carry_over = config.num_carry_over();
+        let carry_over_bus =
+            (num_carry_over > 0).then(|| CarryOverBus(bus_allocator.new_bus_idx(SYSTEM_BUS_OWNER)));
+        let connector_chip = VmConnectorChip::new(
+            execution_bus,
+            program_bus,
+            CarryOverValues::new(carry_over_bus, num_carry_over),
+        );
 
+        let streams = Arc::new(Mutex::new(Streams::default()));
         let mut inventory = VmInventory::new();
         // PublicValuesChip is required when num_public_values > 0 in single segment mode.
         if config.has_public_values_chip() {
             assert_eq!(inventory.executors().len(), Self::PV_EXECUTOR_IDX);
             let chip = PublicValuesChip::new(
-                NativeAdapterChip::new(EXECUTION_BUS, PROGRAM_BUS, memory_controller.clone()),
+                NativeAdapterChip::new(execution_bus, program_bus, memory_controller.clone()),
                 PublicValuesCoreChip::new(
                     config.num_public_values,
                     PublishOpcode::default_offset(),
@@ -560,11 +665,32 @@ impl<F: PrimeField32> SystemComplex<F> {
             );
             inventory.add_periphery_chip(chip);
         }
-        let streams = Arc::new(Mutex::new(Streams::default()));
+        // The key-value store requires continuations, so it never coexists with PublicValuesChip.
+        if let Some(depth) = config.kv_store_depth {
+            assert_eq!(inventory.executors().len(), Self::KV_STORE_EXECUTOR_IDX);
+            let compression_bus = memory_controller
+                .borrow()
+                .interface_chip
+                .compression_bus()
+                .expect("key-value store requires continuations");
+            let chip = KvStoreChip::new(
+                NativeAdapterChip::new(execution_bus, program_bus, memory_controller.clone()),
+                KvStoreCoreChip::new(
+                    compression_bus,
+                    depth,
+                    KvStoreOpcode::default_offset(),
+                    streams.clone(),
+                ),
+                memory_controller.clone(),
+            );
+            inventory
+                .add_executor(chip, [VmOpcode::with_default_offset(KvStoreOpcode::LOOKUP)])
+                .unwrap();
+        }
         let phantom_opcode = VmOpcode::with_default_offset(SystemOpcode::PHANTOM);
         let mut phantom_chip = PhantomChip::new(
-            EXECUTION_BUS,
-            PROGRAM_BUS,
+            execution_bus,
+            program_bus,
             memory_controller.clone(),
             SystemOpcode::default_offset(),
         );
@@ -579,13 +705,16 @@ impl<F: PrimeField32> SystemComplex<F> {
             memory_controller,
             range_checker_chip: range_checker,
             range_checker_bus: range_bus,
+            memory_bus,
+            program_bus,
+            execution_bus,
         };
 
         Self {
             config,
             base,
             inventory,
-            bus_idx_max,
+            bus_allocator,
             streams,
             overridden_inventory_heights: None,
         }
@@ -597,15 +726,21 @@ impl<F: PrimeField32, E, P> VmChipComplex<F, E, P> {
     pub(super) const PV_EXECUTOR_IDX: ExecutorId = 0;
     /// **If** internal poseidon2 chip exists, then its periphery index is 0.
     pub(super) const POSEIDON2_PERIPHERY_IDX: usize = 0;
+    /// **If** key-value store chip exists, then its executor index is 0.
+    pub(super) const KV_STORE_EXECUTOR_IDX: ExecutorId = 0;
 
-    // @dev: Remember to update self.bus_idx_max after dropping this!
+    // @dev: Remember to update self.bus_allocator after dropping this!
     pub fn inventory_builder(&self) -> VmInventoryBuilder<F>
     where
         E: AnyEnum,
         P: AnyEnum,
     {
-        let mut builder =
-            VmInventoryBuilder::new(&self.config, &self.base, &self.streams, self.bus_idx_max);
+        let mut builder = VmInventoryBuilder::new(
+            &self.config,
+            &self.base,
+            &self.streams,
+            self.bus_allocator.clone(),
+        );
         // Add range checker for convenience, the other system base chips aren't included - they can be accessed directly from builder
         builder.add_chip(&self.base.range_checker_chip);
         for chip in self.inventory.executors() {
@@ -632,8 +767,9 @@ impl<F: PrimeField32, E, P> VmChipComplex<F, E, P> {
         Ext::Periphery: Into<P3>,
     {
         let mut builder = self.inventory_builder();
+        builder.bus_owner = std::any::type_name::<Ext>().to_string();
         let inventory_ext = config.build(&mut builder)?;
-        self.bus_idx_max = builder.bus_idx_max;
+        self.bus_allocator = builder.bus_allocator;
         let mut ext_complex = self.transmute();
         ext_complex.append(inventory_ext.transmute())?;
         Ok(ext_complex)
@@ -648,12 +784,43 @@ impl<F: PrimeField32, E, P> VmChipComplex<F, E, P> {
             config: self.config,
             base: self.base,
             inventory: self.inventory.transmute(),
-            bus_idx_max: self.bus_idx_max,
+            bus_allocator: self.bus_allocator,
             streams: self.streams,
             overridden_inventory_heights: self.overridden_inventory_heights,
         }
     }
 
+    pub fn bus_allocator(&self) -> &BusAllocator {
+        &self.bus_allocator
+    }
+
+    /// Bus indices used by the system chips.
+    pub fn system_bus_indices(&self) -> Vec<usize> {
+        let memory_controller = self.memory_controller().borrow();
+        let interface = &memory_controller.interface_chip;
+        [
+            self.base.execution_bus.0,
+            self.base.memory_bus.0,
+            self.base.program_bus.0,
+            self.base.range_checker_bus.index,
+        ]
+        .into_iter()
+        .chain(interface.merkle_bus().map(|bus| bus.0))
+        .chain(interface.compression_bus().map(|bus| bus.0))
+        .collect()
+    }
+
+    /// Fails if a bus used by the system chips was not allocated to the system by the
+    /// [BusAllocator], i.e. it may be shared with an extension.
+    pub fn check_bus_allocation(&self) -> Result<(), VmConfigError> {
+        for bus in self.system_bus_indices() {
+            if self.bus_allocator.owner(bus) != Some(SYSTEM_BUS_OWNER) {
+                return Err(VmConfigError::BusCollision { bus });
+            }
+        }
+        Ok(())
+    }
+
     /// Appends `other` to the current inventory.
     /// This means `self` comes earlier in the dependency chain.
     pub fn append(&mut self, other: VmInventory<E, P>) -> Result<(), VmInventoryError> {
@@ -676,6 +843,10 @@ impl<F: PrimeField32, E, P> VmChipComplex<F, E, P> {
         &mut self.base.connector_chip
     }
 
+    pub fn carry_over(&self) -> &CarryOverValues<F> {
+        self.base.carry_over()
+    }
+
     pub fn memory_controller(&self) -> &MemoryControllerRef<F> {
         &self.base.memory_controller
     }
@@ -692,6 +863,20 @@ impl<F: PrimeField32, E, P> VmChipComplex<F, E, P> {
         chip.as_any_kind().downcast_ref()
     }
 
+    /// The phantom sub-executors registered by the extensions, by discriminant.
+    pub fn phantom_sub_executors(&self) -> Vec<PhantomSubExecutorInfo>
+    where
+        E: AnyEnum,
+    {
+        self.inventory
+            .executors()
+            .iter()
+            .find_map(|chip| chip.as_any_kind().downcast_ref::<RefCell<PhantomChip<F>>>())
+            .expect("PhantomChip always exists")
+            .borrow()
+            .sub_executors()
+    }
+
     pub fn poseidon2_chip(&self) -> Option<&Poseidon2PeripheryChip<F>>
     where
         P: AnyEnum,
@@ -714,6 +899,29 @@ impl<F: PrimeField32, E, P> VmChipComplex<F, E, P> {
         chip.as_any_kind_mut().downcast_mut()
     }
 
+    /// Records the compressions of the key-value store lookups in the Poseidon2 chip, which
+    /// proves them. Must be called before generating the traces.
+    pub(crate) fn finalize_kv_store(&mut self)
+    where
+        E: AnyEnum,
+        P: AnyEnum,
+    {
+        if self.config.kv_store_depth.is_none() {
+            return;
+        }
+        let chip: &KvStoreChip<F> = self.inventory.executors[Self::KV_STORE_EXECUTOR_IDX]
+            .as_any_kind()
+            .downcast_ref()
+            .expect("key-value store chip has the wrong type");
+        let compressions = chip.core.take_compressions();
+        let poseidon2 = self
+            .poseidon2_chip_mut()
+            .expect("key-value store requires the Poseidon2 chip");
+        for (left, right) in compressions {
+            poseidon2.compress_and_record(&left, &right);
+        }
+    }
+
     pub(crate) fn set_program(&mut self, program: Program<F>) {
         self.base.program_chip.set_program(program);
     }
@@ -723,6 +931,15 @@ impl<F: PrimeField32, E, P> VmChipComplex<F, E, P> {
         *self.streams.lock() = streams;
     }
 
+    /// Puts `hints` in front of the hint stream, so they are read next, before any hints
+    /// already in the stream.
+    pub(crate) fn prepend_hints(&mut self, hints: Vec<F>) {
+        let hint_stream = &mut self.streams.lock().hint_stream;
+        for hint in hints.into_iter().rev() {
+            hint_stream.push_front(hint);
+        }
+    }
+
     /// This should **only** be called after segment execution has finished.
     pub(super) fn take_streams(&mut self) -> Streams<F> {
         std::mem::take(&mut self.streams.lock())
@@ -916,8 +1133,8 @@ impl<F: PrimeField32, E, P> VmChipComplex<F, E, P> {
     ) -> ProofInput<SC>
     where
         Domain<SC>: PolynomialSpace<Val = F>,
-        E: Chip<SC>,
-        P: Chip<SC>,
+        E: Chip<SC> + Send,
+        P: Chip<SC> + Send,
     {
         let has_pv_chip = self.public_values_chip_idx().is_some();
         // ATTENTION: The order of AIR proof input generation MUST be consistent with `airs`.
@@ -939,25 +1156,36 @@ impl<F: PrimeField32, E, P> VmChipComplex<F, E, P> {
         // Go through all chips in inventory in reverse order they were added (to resolve dependencies)
         // Important Note: for air_id ordering reasons, we want to generate_air_proof_input for
         // public values and memory chips **last** but include them into the `builder` **first**.
+        let overridden_height = |chip_id: ChipId| {
+            self.overridden_inventory_heights
+                .as_ref()
+                .and_then(|overridden_heights| overridden_heights.chips.get(&chip_id).copied())
+        };
+        // Executors only depend on periphery chips, whose lookup counters are atomic, so their
+        // traces are generated in parallel before any periphery trace.
+        let executors: Vec<_> = self
+            .inventory
+            .executors
+            .into_iter()
+            .enumerate()
+            .map(|(id, chip)| (chip, overridden_height(ChipId::Executor(id))))
+            .collect();
+        let mut executor_inputs: Vec<_> = executors
+            .into_par_iter()
+            .map(|(chip, height)| Some(generate_air_proof_input(chip, height)))
+            .collect();
+        let mut periphery: Vec<_> = self.inventory.periphery.into_iter().map(Some).collect();
+
         let mut public_values_input = None;
         let mut insertion_order = self.inventory.insertion_order;
         insertion_order.reverse();
         let mut non_sys_inputs = Vec::with_capacity(insertion_order.len());
         for chip_id in insertion_order {
-            let mut height = None;
-            if let Some(overridden_heights) = self.overridden_inventory_heights.as_ref() {
-                height = overridden_heights.chips.get(&chip_id).copied();
-            }
             let air_proof_input = match chip_id {
-                ChipId::Executor(id) => {
-                    let chip = self.inventory.executors.pop().unwrap();
-                    assert_eq!(id, self.inventory.executors.len());
-                    generate_air_proof_input(chip, height)
-                }
+                ChipId::Executor(id) => executor_inputs[id].take().unwrap(),
                 ChipId::Periphery(id) => {
-                    let chip = self.inventory.periphery.pop().unwrap();
-                    assert_eq!(id, self.inventory.periphery.len());
-                    generate_air_proof_input(chip, height)
+                    let chip = periphery[id].take().unwrap();
+                    generate_air_proof_input(chip, overridden_height(chip_id))
                 }
             };
             if has_pv_chip && chip_id == ChipId::Executor(Self::PV_EXECUTOR_IDX) {
@@ -974,7 +1202,7 @@ impl<F: PrimeField32, E, P> VmChipComplex<F, E, P> {
         // System: Memory Controller
         {
             // memory
-            let memory_controller = Rc::try_unwrap(memory_controller)
+            let memory_controller = Arc::try_unwrap(memory_controller)
                 .expect("other chips still hold a reference to memory chip")
                 .into_inner();
 
@@ -0,0 +1,279 @@
+use std::{
+    collections::BTreeSet,
+    io::{self, Read, Write},
+    net::{TcpListener, TcpStream, ToSocketAddrs},
+};
+
+use openvm_stark_backend::p3_field::PrimeField32;
+
+use super::{Debugger, ProgressAction};
+use crate::system::memory::MemoryController;
+
+/// Number of general purpose registers reported to the debugger. The pc follows them.
+const NUM_REGISTERS: usize = 32;
+/// Signal reported to the debugger when execution stops at a breakpoint or after a step.
+const SIGTRAP: u8 = 5;
+
+/// Where a [GdbServer] finds the guest registers and memory.
+#[derive(Clone, Copy, Debug)]
+pub struct GdbTarget {
+    /// Register `i` is stored little-endian in the 4 byte cells at pointer `4 * i`.
+    pub register_address_space: u32,
+    /// Main memory, with one byte per cell.
+    pub memory_address_space: u32,
+}
+
+impl Default for GdbTarget {
+    /// The layout of the RV32IM extension.
+    fn default() -> Self {
+        Self {
+            register_address_space: 1,
+            memory_address_space: 2,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum RunState {
+    /// Stop before the next instruction.
+    Step,
+    /// Stop at the next breakpoint.
+    Continue,
+    /// The debugger detached or the connection was lost.
+    Detached,
+}
+
+/// [Debugger] speaking the gdb remote serial protocol, to debug RISC-V guests with gdb or lldb
+/// instead of qemu:
+///
+/// ```text
+/// (gdb) set architecture riscv:rv32
+/// (gdb) target remote localhost:9001
+/// ```
+///
+/// Supports breakpoints on the pc, single stepping, and reading registers and memory. Execution
+/// stops before the first instruction, until the debugger continues it.
+pub struct GdbServer<S = TcpStream> {
+    stream: S,
+    target: GdbTarget,
+    breakpoints: BTreeSet<u32>,
+    state: RunState,
+    /// The first stop is only reported when the debugger asks for it with `?`.
+    stopped_before: bool,
+}
+
+impl GdbServer<TcpStream> {
+    /// Waits for a debugger to connect on `addr`.
+    pub fn listen(addr: impl ToSocketAddrs) -> io::Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        tracing::info!("Waiting for gdb on {}", listener.local_addr()?);
+        let (stream, peer) = listener.accept()?;
+        tracing::info!("gdb connected from {peer}");
+        Ok(Self::new(stream))
+    }
+}
+
+impl<S: Read + Write> GdbServer<S> {
+    pub fn new(stream: S) -> Self {
+        Self {
+            stream,
+            target: GdbTarget::default(),
+            breakpoints: BTreeSet::new(),
+            state: RunState::Step,
+            stopped_before: false,
+        }
+    }
+
+    pub fn with_target(mut self, target: GdbTarget) -> Self {
+        self.target = target;
+        self
+    }
+
+    /// Reads the next packet, acknowledging it. Acknowledgements sent by the debugger are skipped.
+    fn read_packet(&mut self) -> io::Result<String> {
+        let mut byte = [0u8; 1];
+        loop {
+            loop {
+                self.stream.read_exact(&mut byte)?;
+                if byte[0] == b'$' {
+                    break;
+                }
+            }
+            let mut data = vec![];
+            loop {
+                self.stream.read_exact(&mut byte)?;
+                if byte[0] == b'#' {
+                    break;
+                }
+                data.push(byte[0]);
+            }
+            let mut checksum_hex = [0u8; 2];
+            self.stream.read_exact(&mut checksum_hex)?;
+            let expected = std::str::from_utf8(&checksum_hex)
+                .ok()
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+            if expected == Some(checksum(&data)) {
+                self.stream.write_all(b"+")?;
+                return Ok(String::from_utf8_lossy(&data).into_owned());
+            }
+            self.stream.write_all(b"-")?;
+        }
+    }
+
+    fn write_packet(&mut self, data: &str) -> io::Result<()> {
+        write!(self.stream, "${data}#{:02x}", checksum(data.as_bytes()))?;
+        self.stream.flush()
+    }
+
+    /// Serves the debugger until it resumes or kills execution.
+    fn stop<F: PrimeField32>(
+        &mut self,
+        pc: u32,
+        memory: &MemoryController<F>,
+    ) -> io::Result<ProgressAction> {
+        if self.stopped_before {
+            self.write_packet(&format!("S{SIGTRAP:02x}"))?;
+        }
+        self.stopped_before = true;
+        loop {
+            let packet = self.read_packet()?;
+            let reply = if packet == "?" {
+                format!("S{SIGTRAP:02x}")
+            } else if packet == "g" {
+                (0..=NUM_REGISTERS)
+                    .map(|reg| hex(&self.register(pc, memory, reg)))
+                    .collect()
+            } else if let Some(reg) = packet.strip_prefix('p') {
+                match usize::from_str_radix(reg, 16) {
+                    Ok(reg) if reg <= NUM_REGISTERS => hex(&self.register(pc, memory, reg)),
+                    _ => "E01".to_string(),
+                }
+            } else if let Some(args) = packet.strip_prefix('m') {
+                self.read_memory(memory, args)
+                    .unwrap_or_else(|| "E01".to_string())
+            } else if let Some(args) = packet.strip_prefix("Z0,") {
+                match parse_breakpoint(args) {
+                    Some(addr) => {
+                        self.breakpoints.insert(addr);
+                        "OK".to_string()
+                    }
+                    None => "E01".to_string(),
+                }
+            } else if let Some(args) = packet.strip_prefix("z0,") {
+                match parse_breakpoint(args) {
+                    Some(addr) => {
+                        self.breakpoints.remove(&addr);
+                        "OK".to_string()
+                    }
+                    None => "E01".to_string(),
+                }
+            } else if packet.starts_with('c') {
+                self.state = RunState::Continue;
+                return Ok(ProgressAction::Continue);
+            } else if packet.starts_with('s') {
+                self.state = RunState::Step;
+                return Ok(ProgressAction::Continue);
+            } else if packet == "k" {
+                return Ok(ProgressAction::Abort);
+            } else if packet.starts_with('D') {
+                self.write_packet("OK")?;
+                self.state = RunState::Detached;
+                return Ok(ProgressAction::Continue);
+            } else if packet.starts_with("qSupported") {
+                "PacketSize=4000;swbreak+".to_string()
+            } else if packet == "qAttached" {
+                "1".to_string()
+            } else if packet.starts_with('H') {
+                "OK".to_string()
+            } else {
+                // An empty reply tells the debugger that the packet is not supported.
+                String::new()
+            };
+            self.write_packet(&reply)?;
+        }
+    }
+
+    /// Value of register `reg`, where register [NUM_REGISTERS] is the pc.
+    fn register<F: PrimeField32>(
+        &self,
+        pc: u32,
+        memory: &MemoryController<F>,
+        reg: usize,
+    ) -> [u8; 4] {
+        if reg == NUM_REGISTERS {
+            return pc.to_le_bytes();
+        }
+        memory
+            .unsafe_read::<4>(
+                F::from_canonical_u32(self.target.register_address_space),
+                F::from_canonical_usize(4 * reg),
+            )
+            .map(|byte| byte.as_canonical_u32() as u8)
+    }
+
+    /// Handles the `addr,len` arguments of an `m` packet.
+    fn read_memory<F: PrimeField32>(
+        &self,
+        memory: &MemoryController<F>,
+        args: &str,
+    ) -> Option<String> {
+        let (addr, len) = args.split_once(',')?;
+        let addr = u32::from_str_radix(addr, 16).ok()?;
+        let len = u32::from_str_radix(len, 16).ok()?;
+        let end = addr.checked_add(len)?;
+        if end as u64 > 1 << memory.mem_config.pointer_max_bits {
+            return None;
+        }
+        let address_space = F::from_canonical_u32(self.target.memory_address_space);
+        let bytes: Vec<u8> = (addr..end)
+            .map(|ptr| {
+                memory
+                    .unsafe_read_cell(address_space, F::from_canonical_u32(ptr))
+                    .as_canonical_u32() as u8
+            })
+            .collect();
+        Some(hex(&bytes))
+    }
+}
+
+impl<F: PrimeField32, S: Read + Write + Send> Debugger<F> for GdbServer<S> {
+    fn on_instruction(&mut self, pc: u32, memory: &MemoryController<F>) -> ProgressAction {
+        match self.state {
+            RunState::Detached => return ProgressAction::Continue,
+            RunState::Continue if !self.breakpoints.contains(&pc) => {
+                return ProgressAction::Continue
+            }
+            _ => {}
+        }
+        self.stop(pc, memory).unwrap_or_else(|err| {
+            tracing::warn!("gdb connection lost: {err}");
+            self.state = RunState::Detached;
+            ProgressAction::Continue
+        })
+    }
+
+    fn on_terminate(&mut self, exit_code: u32) {
+        if self.state == RunState::Detached {
+            return;
+        }
+        // The protocol only has room for 8-bit exit statuses.
+        if let Err(err) = self.write_packet(&format!("W{:02x}", exit_code as u8)) {
+            tracing::warn!("gdb connection lost: {err}");
+        }
+        self.state = RunState::Detached;
+    }
+}
+
+fn checksum(data: &[u8]) -> u8 {
+    data.iter().fold(0u8, |sum, &byte| sum.wrapping_add(byte))
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// Parses the `addr,kind` arguments of a `Z0` or `z0` packet.
+fn parse_breakpoint(args: &str) -> Option<u32> {
+    let (addr, _kind) = args.split_once(',')?;
+    u32::from_str_radix(addr, 16).ok()
+}
@@ -0,0 +1,124 @@
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+
+/// Computes the inputs of the guest program on demand, e.g. from a database or over RPC,
+/// instead of providing them all upfront in [Streams::input_stream](super::Streams).
+pub trait HintProvider<F>: Send {
+    /// Returns the input with index `hint_id` in the run, or `None` once there is no more input.
+    /// `payload` is data the guest sent along with the request; input hints have none.
+    fn next_hint(&mut self, hint_id: usize, payload: &[F]) -> Option<Vec<F>>;
+}
+
+pub type SharedHintProvider<F> = Arc<Mutex<dyn HintProvider<F>>>;
+
+impl<F, P> HintProvider<F> for P
+where
+    P: FnMut(usize, &[F]) -> Option<Vec<F>> + Send,
+{
+    fn next_hint(&mut self, hint_id: usize, payload: &[F]) -> Option<Vec<F>> {
+        self(hint_id, payload)
+    }
+}
+
+#[cfg(feature = "async-hints")]
+pub use self::async_hints::*;
+
+#[cfg(feature = "async-hints")]
+mod async_hints {
+    use async_trait::async_trait;
+    use tokio::runtime::Handle;
+
+    use super::HintProvider;
+
+    /// Asynchronous version of [HintProvider], for hosts that compute hints with async code.
+    #[async_trait]
+    pub trait AsyncHintProvider<F: Send + Sync>: Send {
+        async fn next_hint(&mut self, hint_id: usize, payload: &[F]) -> Option<Vec<F>>;
+    }
+
+    /// Runs an [AsyncHintProvider] on a tokio runtime, blocking execution until each hint is
+    /// ready.
+    ///
+    /// Execution must not run on a thread of the runtime itself, e.g. use
+    /// `tokio::task::spawn_blocking`, since blocking on the runtime from within it panics.
+    pub struct BlockingHintProvider<P> {
+        provider: P,
+        handle: Handle,
+    }
+
+    impl<P> BlockingHintProvider<P> {
+        pub fn new(provider: P, handle: Handle) -> Self {
+            Self { provider, handle }
+        }
+    }
+
+    impl<F: Send + Sync, P: AsyncHintProvider<F>> HintProvider<F> for BlockingHintProvider<P> {
+        fn next_hint(&mut self, hint_id: usize, payload: &[F]) -> Option<Vec<F>> {
+            self.handle
+                .block_on(self.provider.next_hint(hint_id, payload))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{thread, time::Duration};
+
+    use crate::arch::{Streams, STDIN_STREAM};
+
+    #[test]
+    fn test_hint_provider() {
+        let mut streams =
+            Streams::new(vec![vec![7u32]]).with_hint_provider(|hint_id: usize, _: &[u32]| {
+                (hint_id < 3).then(|| vec![hint_id as u32])
+            });
+        let inputs: Vec<_> = std::iter::from_fn(|| streams.next_input(&[])).collect();
+        assert_eq!(inputs, vec![vec![7], vec![1], vec![2]]);
+        assert_eq!(streams.num_inputs_read, 3);
+    }
+
+    #[test]
+    fn test_named_streams() {
+        let mut streams = Streams::new(vec![vec![1u32]])
+            .with_named_stream("witness", vec![vec![2], vec![3]])
+            .with_key_value_store("kv", [(vec![4], vec![5])]);
+        let witness = streams.stream_index("witness").unwrap();
+        let kv = streams.stream_index("kv").unwrap();
+        assert_eq!(
+            (streams.stream_index(STDIN_STREAM), witness, kv),
+            (Some(0), 1, 2)
+        );
+
+        assert_eq!(streams.next_input_from(witness, &[]), Some(vec![2]));
+        assert_eq!(streams.next_input_from(kv, &[4]), Some(vec![5]));
+        assert_eq!(streams.next_input_from(kv, &[4]), Some(vec![5]));
+        assert_eq!(streams.next_input_from(kv, &[6]), None);
+        assert_eq!(streams.next_input_from(0, &[]), Some(vec![1]));
+        assert_eq!(streams.next_input_from(3, &[]), None);
+
+        let offsets = streams.read_offsets();
+        assert_eq!(offsets[STDIN_STREAM], 1);
+        assert_eq!(offsets["witness"], 1);
+        assert_eq!(offsets["kv"], 2);
+    }
+
+    #[test]
+    fn test_hint_timeout() {
+        let timeout = Duration::from_millis(50);
+        let mut streams = Streams::new(vec![])
+            .with_hint_provider(|hint_id: usize, _: &[u32]| {
+                if hint_id > 0 {
+                    thread::sleep(Duration::from_secs(10));
+                }
+                Some(vec![hint_id as u32])
+            })
+            .with_hint_timeout(timeout);
+        assert_eq!(streams.next_input(&[]), Some(vec![0]));
+        assert_eq!(streams.take_hint_timeout(), None);
+        assert_eq!(streams.next_input(&[]), None);
+        assert_eq!(streams.take_hint_timeout(), Some(timeout));
+        assert_eq!(streams.take_hint_timeout(), None);
+        assert_eq!(streams.num_inputs_read, 1);
+    }
+}
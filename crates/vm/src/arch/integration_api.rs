@@ -1,5 +1,6 @@
-use std::{array::from_fn, borrow::Borrow, cell::RefCell, marker::PhantomData, sync::Arc};
+use std::{array::from_fn, borrow::Borrow, marker::PhantomData, sync::Arc};
 
+use atomic_refcell::AtomicRefCell;
 use openvm_circuit_primitives::utils::next_power_of_two_or_zero;
 use openvm_circuit_primitives_derive::AlignedBorrow;
 use openvm_instructions::instruction::Instruction;
@@ -281,7 +282,7 @@ where
         let width = core_width + adapter_width;
         let mut values = Val::<SC>::zero_vec(height * width);
 
-        let memory_aux_cols_factory = RefCell::borrow(&self.memory).aux_cols_factory();
+        let memory_aux_cols_factory = AtomicRefCell::borrow(&self.memory).aux_cols_factory();
         // This zip only goes through records.
         // The padding rows between records.len()..height are filled with zeros.
         values
@@ -1,13 +1,27 @@
+/// Pre-decoded basic blocks for faster execution
+mod basic_block;
 mod config;
+/// Hooks to inspect and control execution instruction by instruction
+mod debugger;
+/// Instruction executors registered at runtime
+mod dynamic;
 /// Instruction execution traits and types.
 /// Execution bus and interface.
 mod execution;
+/// Recording and replaying the host-side effects of a run
+mod execution_log;
 /// Traits and builders to compose collections of chips into a virtual machine.
 mod extensions;
+/// gdb remote serial protocol server
+mod gdb;
+/// Host-side providers of guest inputs
+mod hints;
 /// Traits and wrappers to facilitate VM chip integration
 mod integration_api;
 /// Runtime execution and segmentation
 pub mod segment;
+/// Strategies deciding when to cut a segment
+mod segmentation;
 /// Top level [VirtualMachine] constructor and API.
 pub mod vm;
 
@@ -18,9 +32,16 @@ pub mod hasher;
 #[cfg(any(test, feature = "test-utils"))]
 pub mod testing;
 
+pub use basic_block::*;
 pub use config::*;
+pub use debugger::*;
+pub use dynamic::*;
 pub use execution::*;
+pub use execution_log::*;
 pub use extensions::*;
+pub use gdb::*;
+pub use hints::*;
 pub use integration_api::*;
 pub use segment::*;
+pub use segmentation::*;
 pub use vm::*;
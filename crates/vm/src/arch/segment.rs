@@ -1,7 +1,13 @@
+use std::sync::Arc;
+
 use backtrace::Backtrace;
 #[cfg(feature = "function-span")]
 use openvm_instructions::exe::FnBound;
-use openvm_instructions::{exe::FnBounds, instruction::DebugInfo, program::Program};
+use openvm_instructions::{
+    exe::FnBounds,
+    instruction::{DebugInfo, Instruction},
+    program::Program,
+};
 use openvm_stark_backend::{
     config::{Domain, StarkGenericConfig},
     p3_commit::PolynomialSpace,
@@ -9,17 +15,19 @@ use openvm_stark_backend::{
     prover::types::{CommittedTraceData, ProofInput},
     Chip,
 };
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
 
 use super::{
-    AnyEnum, ExecutionError, Streams, SystemConfig, VmChipComplex, VmComplexTraceHeights, VmConfig,
+    AnyEnum, ArchState, BasicBlockCache, DynamicExecutors, ExecutionError, InspectionHook,
+    SegmentationCtx, SegmentationStrategy, SharedDebugger, Streams, SystemConfig, VmChipComplex,
+    VmComplexTraceHeights, VmConfig, VmMemoryState,
 };
-#[cfg(feature = "bench-metrics")]
-use crate::metrics::VmMetrics;
 use crate::{
     arch::{instructions::*, ExecutionState, InstructionExecutor},
-    metrics::cycle_tracker::CycleTracker,
+    metrics::{cycle_tracker::CycleTracker, TraceEventRecorder, VmMetrics},
     system::{
-        memory::{Equipartition, CHUNK},
+        memory::{access_log::MemoryAccessEntry, OpType},
         poseidon2::Poseidon2PeripheryChip,
     },
 };
@@ -27,6 +35,48 @@ use crate::{
 /// Check segment every 100 instructions.
 const SEGMENT_CHECK_INTERVAL: usize = 100;
 
+/// Snapshot of a running [ExecutionSegment], passed to its progress callback.
+#[derive(Clone, Debug)]
+pub struct ExecutionProgress {
+    pub segment_idx: usize,
+    /// The pc of the next instruction.
+    pub pc: u32,
+    pub timestamp: u32,
+    /// Number of instructions executed in this segment so far.
+    pub instructions_retired: u64,
+    /// Current trace height of each AIR, in the order of `ExecutionSegment::air_names`.
+    pub trace_heights: Vec<usize>,
+}
+
+/// Resources used by an execution, counted against the budget of the [SystemConfig].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExecutionBudgetUsage {
+    pub cycles: u64,
+    pub trace_cells: usize,
+}
+
+/// Returned by a progress callback to decide whether execution goes on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProgressAction {
+    Continue,
+    /// Stop execution with [ExecutionError::Aborted].
+    Abort,
+}
+
+pub type ProgressCallback = Box<dyn FnMut(&ExecutionProgress) -> ProgressAction + Send>;
+
+/// A message the guest logged with [SysPhantom::DebugLog].
+#[derive(Clone, Debug)]
+pub struct GuestLogRecord {
+    pub segment_idx: usize,
+    pub pc: u32,
+    /// Number of instructions executed before the log instruction, over all segments.
+    pub cycle: u64,
+    pub message: String,
+}
+
+pub type GuestLogger = Arc<Mutex<dyn FnMut(&GuestLogRecord) + Send>>;
+
 pub struct ExecutionSegment<F, VC>
 where
     F: PrimeField32,
@@ -34,18 +84,43 @@ where
 {
     pub chip_complex: VmChipComplex<F, VC::Executor, VC::Periphery>,
 
-    pub final_memory: Option<Equipartition<F, CHUNK>>,
+    pub final_memory: Option<VmMemoryState<F>>,
 
     /// Metric collection tools. Only collected when `config.collect_metrics` is true.
     pub cycle_tracker: CycleTracker,
+    /// Execution timeline. Only recorded when `config.collect_trace_events` is true.
+    pub trace_events: Option<TraceEventRecorder>,
     #[cfg(feature = "bench-metrics")]
     pub(crate) collected_metrics: VmMetrics,
 
-    #[allow(dead_code)]
     pub(crate) fn_bounds: FnBounds,
 
     pub air_names: Vec<String>,
     pub since_last_segment_check: usize,
+    /// Index of this segment in the run, used to label its metrics.
+    pub segment_idx: usize,
+    /// Resources used by the previous segments of the run and, once executed, by this one.
+    pub budget_usage: ExecutionBudgetUsage,
+    /// See [Self::set_progress_callback].
+    progress_callback: Option<(u64, ProgressCallback)>,
+    /// See [Self::set_inspection_hook].
+    inspection_hook: Option<(u64, InspectionHook<F>)>,
+    /// Decides when to cut the segment. Defaults to the limits of the system config.
+    segmentation_strategy: Box<dyn SegmentationStrategy>,
+    /// See [Self::set_execute_only].
+    execute_only: bool,
+    /// Message of the TERMINATE instruction that ended the program, if it had one.
+    pub exit_message: Option<String>,
+    /// See [Self::set_dynamic_executors].
+    dynamic_executors: DynamicExecutors<F>,
+    /// Number of instructions executed by `dynamic_executors`.
+    dynamic_instructions: u64,
+    /// See [Self::set_debugger].
+    debugger: Option<SharedDebugger<F>>,
+    /// See [Self::set_guest_logger].
+    guest_logger: Option<GuestLogger>,
+    /// See [Self::set_basic_block_execution].
+    basic_blocks: Option<BasicBlockCache<F>>,
 }
 
 pub struct ExecutionSegmentState {
@@ -53,13 +128,39 @@ pub struct ExecutionSegmentState {
     pub is_terminated: bool,
 }
 
+/// One instruction executed by [ExecutionSegment::step].
+#[derive(Clone, Debug)]
+pub struct StepRecord<F> {
+    pub pc: u32,
+    /// Timestamp before the instruction.
+    pub timestamp: u32,
+    pub opcode: VmOpcode,
+    /// Operands `a` to `g` of the instruction.
+    pub operands: [F; 7],
+    pub next_pc: u32,
+    /// Set if the instruction is TERMINATE.
+    pub exit_code: Option<u32>,
+    /// Reads and writes of the instruction in order, including register accesses.
+    pub memory_accesses: Vec<MemoryAccessEntry<F>>,
+}
+
+impl<F> StepRecord<F> {
+    /// Writes of the instruction to `address_space`, e.g. the register address space to get the
+    /// register writes.
+    pub fn writes_to(&self, address_space: u32) -> impl Iterator<Item = &MemoryAccessEntry<F>> {
+        self.memory_accesses.iter().filter(move |access| {
+            access.op == OpType::Write && access.address_space == address_space
+        })
+    }
+}
+
 impl<F: PrimeField32, VC: VmConfig<F>> ExecutionSegment<F, VC> {
     /// Creates a new execution segment from a program and initial state, using parent VM config
     pub fn new(
         config: &VC,
         program: Program<F>,
         init_streams: Streams<F>,
-        initial_memory: Option<Equipartition<F, CHUNK>>,
+        initial_memory: Option<VmMemoryState<F>>,
         fn_bounds: FnBounds,
     ) -> Self {
         let mut chip_complex = config.create_chip_complex().unwrap();
@@ -83,14 +184,98 @@ impl<F: PrimeField32, VC: VmConfig<F>> ExecutionSegment<F, VC> {
             chip_complex,
             final_memory: None,
             cycle_tracker: CycleTracker::new(),
+            trace_events: config
+                .system()
+                .collect_trace_events
+                .then(TraceEventRecorder::new),
             #[cfg(feature = "bench-metrics")]
             collected_metrics: Default::default(),
             fn_bounds,
             air_names,
             since_last_segment_check: 0,
+            segment_idx: 0,
+            budget_usage: ExecutionBudgetUsage::default(),
+            progress_callback: None,
+            inspection_hook: None,
+            segmentation_strategy: Box::new(config.system().segmentation_strategy()),
+            execute_only: false,
+            exit_message: None,
+            dynamic_executors: DynamicExecutors::default(),
+            dynamic_instructions: 0,
+            debugger: None,
+            guest_logger: None,
+            basic_blocks: None,
         }
     }
 
+    /// Lets `debugger` inspect and control execution before every instruction.
+    pub fn set_debugger(&mut self, debugger: SharedDebugger<F>) {
+        self.debugger = Some(debugger);
+    }
+
+    /// Passes the messages logged by the guest to `logger` instead of `tracing`.
+    pub fn set_guest_logger(&mut self, logger: GuestLogger) {
+        self.guest_logger = Some(logger);
+    }
+
+    /// Executes the opcodes of `dynamic_executors` with them, in addition to the executors of the
+    /// chip complex. The segment cannot be proved if any of them was used.
+    pub fn set_dynamic_executors(&mut self, dynamic_executors: DynamicExecutors<F>) {
+        self.dynamic_executors = dynamic_executors;
+    }
+
+    /// Runs the segment only to compute its results: no metrics or trace events are collected,
+    /// and instead of being finalized, the memory is copied into `final_memory` as is. The
+    /// segment cannot be proved afterwards.
+    pub fn set_execute_only(&mut self) {
+        self.execute_only = true;
+        self.trace_events = None;
+    }
+
+    /// Executes straight-line code a basic block at a time, with the instructions and their
+    /// executors decoded once per block instead of once per instruction. The records, and so
+    /// the traces and segmentation, are the same as when executing instruction by instruction.
+    ///
+    /// Instructions are still dispatched one by one while a debugger, progress callback,
+    /// inspection hook, trace events or metrics need to observe each of them.
+    pub fn set_basic_block_execution(&mut self) {
+        self.basic_blocks = Some(BasicBlockCache::default());
+    }
+
+    /// Replaces the segmentation limits of the system config with a custom strategy. Only has an
+    /// effect when continuations are enabled.
+    pub fn set_segmentation_strategy(&mut self, strategy: impl SegmentationStrategy + 'static) {
+        self.segmentation_strategy = Box::new(strategy);
+    }
+
+    /// Calls `callback` every `interval` instructions during execution, e.g. to drive a progress
+    /// bar. Execution stops with [ExecutionError::Aborted] if the callback returns
+    /// [ProgressAction::Abort].
+    pub fn set_progress_callback(
+        &mut self,
+        interval: u64,
+        callback: impl FnMut(&ExecutionProgress) -> ProgressAction + Send + 'static,
+    ) {
+        assert!(interval > 0, "progress interval must be positive");
+        self.progress_callback = Some((interval, Box::new(callback)));
+    }
+
+    pub fn clear_progress_callback(&mut self) {
+        self.progress_callback = None;
+    }
+
+    /// Calls `hook` every `interval` instructions of the segment, after the instruction.
+    pub fn set_inspection_hook(&mut self, interval: u64, hook: InspectionHook<F>) {
+        assert!(interval > 0, "inspection interval must be positive");
+        self.inspection_hook = Some((interval, hook));
+    }
+
+    /// Metrics collected so far in this segment. Use [VmMetrics::merge] to aggregate segments.
+    #[cfg(feature = "bench-metrics")]
+    pub fn metrics(&self) -> &VmMetrics {
+        &self.collected_metrics
+    }
+
     pub fn system_config(&self) -> &SystemConfig {
         self.chip_complex.config()
     }
@@ -110,7 +295,7 @@ impl<F: PrimeField32, VC: VmConfig<F>> ExecutionSegment<F, VC> {
         let mut timestamp = self.chip_complex.memory_controller().borrow().timestamp();
 
         #[cfg(feature = "bench-metrics")]
-        let collect_metrics = self.system_config().collect_metrics;
+        let collect_metrics = self.system_config().collect_metrics && !self.execute_only;
         // The backtrace for the previous instruction, if any.
         let mut prev_backtrace: Option<Backtrace> = None;
 
@@ -123,8 +308,38 @@ impl<F: PrimeField32, VC: VmConfig<F>> ExecutionSegment<F, VC> {
             .begin(ExecutionState::new(pc, timestamp));
 
         let mut did_terminate = false;
+        let mut instructions_retired = 0u64;
+
+        let use_basic_blocks = self.basic_blocks.is_some()
+            && self.debugger.is_none()
+            && self.trace_events.is_none()
+            && self.progress_callback.is_none()
+            && self.inspection_hook.is_none();
+        #[cfg(feature = "bench-metrics")]
+        let use_basic_blocks = use_basic_blocks && !collect_metrics;
+        #[cfg(feature = "function-span")]
+        let use_basic_blocks = use_basic_blocks && self.fn_bounds.is_empty();
 
         loop {
+            if use_basic_blocks {
+                let (state, segment_ended) = self.execute_basic_block(
+                    ExecutionState::new(pc, timestamp),
+                    &mut instructions_retired,
+                )?;
+                // Every executed instruction advances the timestamp.
+                let executed = state.timestamp != timestamp;
+                pc = state.pc;
+                timestamp = state.timestamp;
+                if segment_ended {
+                    break;
+                }
+                if executed {
+                    // Instructions in blocks have no debug info, hence no backtrace.
+                    prev_backtrace = None;
+                    continue;
+                }
+            }
+
             let (instruction, debug_info) =
                 self.chip_complex.program_chip_mut().get_instruction(pc)?;
             tracing::trace!("pc: {pc:#x} | time: {timestamp} | {:?}", instruction);
@@ -144,16 +359,48 @@ impl<F: PrimeField32, VC: VmConfig<F>> ExecutionSegment<F, VC> {
             } else {
                 vec![]
             };
+            #[cfg(feature = "bench-metrics")]
+            let prev_access_counts = self
+                .chip_complex
+                .memory_controller()
+                .borrow()
+                .access_counts();
+
+            if let Some(debugger) = &self.debugger {
+                let memory_controller = self.chip_complex.memory_controller().borrow();
+                if debugger.lock().on_instruction(pc, &memory_controller) == ProgressAction::Abort {
+                    return Err(ExecutionError::Aborted { pc });
+                }
+            }
 
             if opcode == VmOpcode::with_default_offset(SystemOpcode::TERMINATE) {
                 did_terminate = true;
-                self.chip_complex.connector_chip_mut().end(
+                if let Some(debugger) = &self.debugger {
+                    debugger
+                        .lock()
+                        .on_terminate(instruction.c.as_canonical_u32());
+                }
+                let [message_ptr, message_len, message_address_space] =
+                    [instruction.a, instruction.b, instruction.d].map(|x| x.as_canonical_u32());
+                let connector = self.chip_complex.connector_chip_mut();
+                connector.end(
                     ExecutionState::new(pc, timestamp),
                     Some(instruction.c.as_canonical_u32()),
                 );
+                connector.set_exit_message(message_ptr, message_len, message_address_space);
+                if message_address_space != 0 {
+                    self.exit_message =
+                        Some(self.read_guest_str(message_address_space, message_ptr, message_len));
+                }
                 break;
             }
 
+            if let Some(limit) = self.system_config().max_segment_instructions {
+                if instructions_retired >= limit {
+                    return Err(ExecutionError::InstructionLimitExceeded { pc, limit });
+                }
+            }
+
             // Some phantom instruction handling is more convenient to do here than in PhantomChip.
             if opcode == VmOpcode::with_default_offset(SystemOpcode::PHANTOM) {
                 // Note: the discriminant is the lower 16 bits of the c operand.
@@ -184,6 +431,13 @@ impl<F: PrimeField32, VC: VmConfig<F>> ExecutionSegment<F, VC> {
                             dsl_instr.clone().unwrap_or("CT-Default".to_string())[3..].to_string(),
                         )
                     }
+                    Some(SysPhantom::HintPerfCounters) => {
+                        let counters = self.perf_counters(instructions_retired, timestamp);
+                        self.chip_complex.prepend_hints(counters);
+                    }
+                    Some(SysPhantom::DebugLog) => {
+                        self.guest_log(pc, instruction.a, instruction.b, instructions_retired);
+                    }
                     _ => {}
                 }
             }
@@ -196,7 +450,7 @@ impl<F: PrimeField32, VC: VmConfig<F>> ExecutionSegment<F, VC> {
                     .range(..=pc)
                     .next_back()
                     .map(|(_, func)| (*func).clone())
-                    .unwrap();
+                    .unwrap_or_default();
                 if pc == current_fn.start {
                     self.cycle_tracker.start(current_fn.name.clone());
                 } else {
@@ -204,27 +458,30 @@ impl<F: PrimeField32, VC: VmConfig<F>> ExecutionSegment<F, VC> {
                 }
             };
 
+            if let Some(trace_events) = &mut self.trace_events {
+                if let Some((_, func)) = self.fn_bounds.range(..=pc).next_back() {
+                    if pc <= func.end {
+                        let cycle = self.budget_usage.cycles + instructions_retired;
+                        trace_events.enter_function(&func.name, cycle);
+                    }
+                }
+            }
+
             #[cfg(feature = "bench-metrics")]
             let mut opcode_name = None;
-            if let Some(executor) = self.chip_complex.inventory.get_mut_executor(&opcode) {
-                let next_state = InstructionExecutor::execute(
-                    executor,
-                    instruction,
-                    ExecutionState::new(pc, timestamp),
-                )?;
-                assert!(next_state.timestamp > timestamp);
-                #[cfg(feature = "bench-metrics")]
-                {
-                    metrics::counter!("total_cycles").increment(1u64);
-                    if collect_metrics {
-                        opcode_name = Some(executor.get_opcode_name(opcode.as_usize()));
-                    }
+            #[cfg(feature = "bench-metrics")]
+            let prev_pc = pc;
+            let next_state = self.execute_instruction(pc, timestamp, instruction)?;
+            #[cfg(feature = "bench-metrics")]
+            {
+                metrics::counter!("total_cycles", "segment" => self.segment_idx.to_string())
+                    .increment(1u64);
+                if collect_metrics {
+                    opcode_name = self.opcode_name(opcode);
                 }
-                pc = next_state.pc;
-                timestamp = next_state.timestamp;
-            } else {
-                return Err(ExecutionError::DisabledOperation { pc, opcode });
-            };
+            }
+            pc = next_state.pc;
+            timestamp = next_state.timestamp;
 
             #[cfg(feature = "bench-metrics")]
             if collect_metrics {
@@ -233,7 +490,52 @@ impl<F: PrimeField32, VC: VmConfig<F>> ExecutionSegment<F, VC> {
                 let opcode_name = opcode_name.unwrap_or(opcode.to_string());
                 let key = (dsl_instr.clone(), opcode_name.clone());
                 self.cycle_tracker.increment_opcode(&key);
-                *self.collected_metrics.counts.entry(key).or_insert(0) += 1;
+                *self
+                    .collected_metrics
+                    .counts
+                    .entry(key.clone())
+                    .or_insert(0) += 1;
+
+                let access_counts = self
+                    .chip_complex
+                    .memory_controller()
+                    .borrow()
+                    .access_counts();
+                for (metric, now_value, prev_value) in [
+                    (
+                        &mut self.collected_metrics.memory_cells_read,
+                        access_counts.cells_read,
+                        prev_access_counts.cells_read,
+                    ),
+                    (
+                        &mut self.collected_metrics.memory_cells_written,
+                        access_counts.cells_written,
+                        prev_access_counts.cells_written,
+                    ),
+                    (
+                        &mut self.collected_metrics.access_adapter_rows,
+                        access_counts.access_adapter_rows,
+                        prev_access_counts.access_adapter_rows,
+                    ),
+                ] {
+                    if now_value != prev_value {
+                        *metric.entry(key.clone()).or_insert(0) += now_value - prev_value;
+                    }
+                }
+
+                let pc_profile_bucket_size = self.system_config().pc_profile_bucket_size;
+                if let Some(bucket_size) = pc_profile_bucket_size {
+                    let trace_cells = itertools::izip!(&now_trace_cells, &prev_trace_cells)
+                        .map(|(now_value, prev_value)| now_value - prev_value)
+                        .sum::<usize>();
+                    let entry = self
+                        .collected_metrics
+                        .pc_profile
+                        .entry(prev_pc / bucket_size * bucket_size)
+                        .or_default();
+                    entry.cycles += 1;
+                    entry.trace_cells += trace_cells;
+                }
 
                 for (air_name, now_value, &prev_value) in
                     itertools::izip!(&self.air_names, now_trace_cells, &prev_trace_cells)
@@ -247,7 +549,31 @@ impl<F: PrimeField32, VC: VmConfig<F>> ExecutionSegment<F, VC> {
                     }
                 }
             }
-            if self.should_segment() {
+            instructions_retired += 1;
+            if let Some((interval, callback)) = &mut self.progress_callback {
+                if instructions_retired % *interval == 0 {
+                    let progress = ExecutionProgress {
+                        segment_idx: self.segment_idx,
+                        pc,
+                        timestamp,
+                        instructions_retired,
+                        trace_heights: self.chip_complex.current_trace_heights(),
+                    };
+                    if callback(&progress) == ProgressAction::Abort {
+                        return Err(ExecutionError::Aborted { pc });
+                    }
+                }
+            }
+            if let Some((interval, hook)) = &self.inspection_hook {
+                if instructions_retired % *interval == 0 {
+                    let memory = self.chip_complex.memory_controller().borrow();
+                    let state = ArchState::new(self.segment_idx, pc, &memory);
+                    (hook.lock())(&state)
+                        .map_err(|inner| ExecutionError::InspectionFailed { pc, inner })?;
+                }
+            }
+            self.check_budget(pc, instructions_retired)?;
+            if self.should_segment(instructions_retired) {
                 self.chip_complex
                     .connector_chip_mut()
                     .end(ExecutionState::new(pc, timestamp), None);
@@ -255,7 +581,15 @@ impl<F: PrimeField32, VC: VmConfig<F>> ExecutionSegment<F, VC> {
             }
         }
         // Finalize memory.
-        {
+        if self.execute_only {
+            let memory_image = self
+                .chip_complex
+                .memory_controller()
+                .borrow()
+                .memory_image();
+            self.final_memory = Some(Arc::new(memory_image));
+        } else {
+            self.chip_complex.finalize_kv_store();
             // Need some partial borrows, so code is ugly:
             let mut memory_controller = self.chip_complex.base.memory_controller.borrow_mut();
             self.final_memory = if self.system_config().continuation_enabled {
@@ -280,18 +614,342 @@ impl<F: PrimeField32, VC: VmConfig<F>> ExecutionSegment<F, VC> {
         if collect_metrics {
             self.collected_metrics.chip_heights =
                 itertools::izip!(self.air_names.clone(), self.current_trace_heights()).collect();
+            self.collected_metrics.chip_cells =
+                itertools::izip!(self.air_names.clone(), self.current_trace_cells()).collect();
 
-            self.collected_metrics.emit();
-            metrics::counter!("total_cells_used")
+            self.collected_metrics.emit(self.segment_idx);
+            self.cycle_tracker.emit_span_stats();
+            metrics::counter!("total_cells_used", "segment" => self.segment_idx.to_string())
                 .absolute(self.current_trace_cells().into_iter().sum::<usize>() as u64);
         }
 
+        if let Some(trace_events) = &mut self.trace_events {
+            let start = self.budget_usage.cycles;
+            let end = start + instructions_retired;
+            trace_events.exit_function(end);
+            trace_events.span(
+                "segment",
+                format!("segment {}", self.segment_idx),
+                start,
+                end,
+            );
+            trace_events.instant("finalize", "memory finalized", end);
+        }
+        self.budget_usage.cycles += instructions_retired;
+        self.budget_usage.trace_cells += self.current_trace_cells().into_iter().sum::<usize>();
+
         Ok(ExecutionSegmentState {
             pc,
             is_terminated: did_terminate,
         })
     }
 
+    /// The hints for [SysPhantom::HintPerfCounters].
+    fn perf_counters(&self, instructions_retired: u64, timestamp: u32) -> Vec<F> {
+        let max_trace_height = self
+            .current_trace_heights()
+            .into_iter()
+            .max()
+            .unwrap_or_default();
+        [
+            instructions_retired,
+            self.budget_usage.cycles + instructions_retired,
+            timestamp as u64,
+            max_trace_height as u64,
+            self.system_config().max_segment_len as u64,
+        ]
+        .into_iter()
+        .flat_map(|counter| u32::try_from(counter).unwrap_or(u32::MAX).to_le_bytes())
+        .map(F::from_canonical_u8)
+        .collect()
+    }
+
+    /// Handles [SysPhantom::DebugLog] at `pc`, where `a` and `b` point to the message pointer
+    /// and length.
+    fn guest_log(&self, pc: u32, a: F, b: F, instructions_retired: u64) {
+        let config = self.system_config().guest_log;
+        if !config.enabled {
+            return;
+        }
+        let (ptr, len) = {
+            let memory_controller = self.chip_complex.memory_controller().borrow();
+            let register_address_space = F::from_canonical_u32(config.register_address_space);
+            let [ptr, len] = [a, b].map(|pointer| {
+                u32::from_le_bytes(
+                    memory_controller
+                        .unsafe_read::<4>(register_address_space, pointer)
+                        .map(|byte| byte.as_canonical_u32() as u8),
+                )
+            });
+            (ptr, len)
+        };
+        let pointer_max_bits = self.system_config().memory_config.pointer_max_bits;
+        if ptr
+            .checked_add(len)
+            .is_none_or(|end| end as u64 > 1 << pointer_max_bits)
+        {
+            tracing::warn!("pc: {pc:#x} | guest log message out of bounds: {ptr:#x}+{len}");
+            return;
+        }
+        let record = GuestLogRecord {
+            segment_idx: self.segment_idx,
+            pc,
+            cycle: self.budget_usage.cycles + instructions_retired,
+            message: self.read_guest_str(config.memory_address_space, ptr, len),
+        };
+        match &self.guest_logger {
+            Some(logger) => (logger.lock())(&record),
+            None => tracing::info!(
+                target: "openvm::guest",
+                segment = record.segment_idx,
+                cycle = record.cycle,
+                "{}",
+                record.message
+            ),
+        }
+    }
+
+    /// Reads a string of `len` bytes, one per cell, e.g. an exit message. The string is
+    /// unconstrained, so it is for diagnostics only.
+    fn read_guest_str(&self, address_space: u32, ptr: u32, len: u32) -> String {
+        let memory_controller = self.chip_complex.memory_controller().borrow();
+        let bytes: Vec<u8> = (ptr..ptr + len)
+            .map(|ptr| {
+                memory_controller
+                    .unsafe_read_cell(
+                        F::from_canonical_u32(address_space),
+                        F::from_canonical_u32(ptr),
+                    )
+                    .as_canonical_u32() as u8
+            })
+            .collect();
+        String::from_utf8_lossy(&bytes).into_owned()
+    }
+
+    /// Fails if the run so far, including `instructions_retired` instructions of this segment,
+    /// exceeds the budget of the system config.
+    fn check_budget(&self, pc: u32, instructions_retired: u64) -> Result<(), ExecutionError> {
+        let config = self.system_config();
+        let cycles = self.budget_usage.cycles + instructions_retired;
+        let cycles_exceeded = config.max_cycles.is_some_and(|max| cycles > max);
+        // Counting trace cells is expensive, so it is done at the same interval as the
+        // segmentation check.
+        let check_trace_cells = config.max_trace_cells.is_some()
+            && instructions_retired % SEGMENT_CHECK_INTERVAL as u64 == 0;
+        if !cycles_exceeded && !check_trace_cells {
+            return Ok(());
+        }
+
+        let usage = ExecutionBudgetUsage {
+            cycles,
+            trace_cells: self.budget_usage.trace_cells
+                + self.current_trace_cells().into_iter().sum::<usize>(),
+        };
+        if cycles_exceeded
+            || config
+                .max_trace_cells
+                .is_some_and(|max| usage.trace_cells > max)
+        {
+            #[cfg(feature = "bench-metrics")]
+            let metrics = self.collected_metrics.clone();
+            #[cfg(not(feature = "bench-metrics"))]
+            let metrics = VmMetrics::default();
+            return Err(ExecutionError::BudgetExceeded {
+                pc,
+                usage,
+                metrics: Box::new(metrics),
+            });
+        }
+        Ok(())
+    }
+
+    /// Executes `instruction` at `pc` with the executor of its opcode.
+    fn execute_instruction(
+        &mut self,
+        pc: u32,
+        timestamp: u32,
+        instruction: Instruction<F>,
+    ) -> Result<ExecutionState<u32>, ExecutionError> {
+        let opcode = instruction.opcode;
+        let from_state = ExecutionState::new(pc, timestamp);
+        let next_state =
+            if let Some(executor) = self.chip_complex.inventory.get_mut_executor(&opcode) {
+                InstructionExecutor::execute(executor, instruction, from_state)?
+            } else if let Some(executor) = self.dynamic_executors.get(&opcode) {
+                let next_state = executor.lock().execute(instruction, from_state)?;
+                self.dynamic_instructions += 1;
+                next_state
+            } else {
+                return Err(ExecutionError::DisabledOperation { pc, opcode });
+            };
+        assert!(next_state.timestamp > timestamp);
+        self.check_read_only_writes(pc)?;
+        Ok(next_state)
+    }
+
+    /// Fails if the instruction at `pc` wrote to a read-only address space.
+    fn check_read_only_writes(&self, pc: u32) -> Result<(), ExecutionError> {
+        let write = self
+            .chip_complex
+            .memory_controller()
+            .borrow_mut()
+            .take_read_only_write();
+        match write {
+            Some((address_space, pointer)) => Err(ExecutionError::ReadOnlyWrite {
+                pc,
+                address_space,
+                pointer,
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Executes the basic block starting at `from_state`, until its end, an instruction that
+    /// does not continue at the next pc, or the end of the segment. Returns the state after the
+    /// executed instructions and whether the segment ended. Nothing is executed if the
+    /// instruction at the pc cannot be part of a block.
+    fn execute_basic_block(
+        &mut self,
+        from_state: ExecutionState<u32>,
+        instructions_retired: &mut u64,
+    ) -> Result<(ExecutionState<u32>, bool), ExecutionError> {
+        let program_chip = self.chip_complex.program_chip();
+        let step = program_chip.program.step;
+        let block = self.basic_blocks.as_mut().unwrap().get_or_decode(
+            from_state.pc,
+            &program_chip.program,
+            program_chip.true_program_length,
+            &self.chip_complex.inventory,
+        );
+
+        let mut state = from_state;
+        for decoded in &block.instructions {
+            if let Some(limit) = self.system_config().max_segment_instructions {
+                if *instructions_retired >= limit {
+                    return Err(ExecutionError::InstructionLimitExceeded {
+                        pc: state.pc,
+                        limit,
+                    });
+                }
+            }
+            self.chip_complex.program_chip_mut().execution_frequencies[decoded.pc_index] += 1;
+            let executor = self
+                .chip_complex
+                .inventory
+                .get_mut_executor_by_id(decoded.executor_id)
+                .unwrap();
+            let next_state =
+                InstructionExecutor::execute(executor, decoded.instruction.clone(), state)?;
+            assert!(next_state.timestamp > state.timestamp);
+            self.check_read_only_writes(state.pc)?;
+            #[cfg(feature = "bench-metrics")]
+            metrics::counter!("total_cycles", "segment" => self.segment_idx.to_string())
+                .increment(1u64);
+
+            let jumped = next_state.pc != state.pc + step;
+            state = next_state;
+            *instructions_retired += 1;
+            self.check_budget(state.pc, *instructions_retired)?;
+            if self.should_segment(*instructions_retired) {
+                self.chip_complex.connector_chip_mut().end(state, None);
+                return Ok((state, true));
+            }
+            if jumped {
+                break;
+            }
+        }
+        Ok((state, false))
+    }
+
+    #[cfg(feature = "bench-metrics")]
+    fn opcode_name(&self, opcode: VmOpcode) -> Option<String> {
+        if let Some(executor) = self.chip_complex.inventory.get_executor(opcode) {
+            return Some(executor.get_opcode_name(opcode.as_usize()));
+        }
+        self.dynamic_executors
+            .get(&opcode)
+            .map(|executor| executor.lock().get_opcode_name(opcode.as_usize()))
+    }
+
+    /// Executes the single instruction at `pc` and returns what it did. TERMINATE is not
+    /// executed: it only ends the program, so its record has no memory accesses and an
+    /// `exit_code`.
+    ///
+    /// Meant for debuggers and differential testing. Unlike [Self::execute_from_pc], stepping
+    /// does not update the connector chip or finalize memory, so the segment cannot be proven
+    /// afterwards.
+    pub fn step(&mut self, pc: u32) -> Result<StepRecord<F>, ExecutionError> {
+        let (instruction, _) = self.chip_complex.program_chip_mut().get_instruction(pc)?;
+        let memory_controller = self.chip_complex.memory_controller().clone();
+        let timestamp = memory_controller.borrow().timestamp();
+        let Instruction {
+            opcode,
+            a,
+            b,
+            c,
+            d,
+            e,
+            f,
+            g,
+        } = instruction;
+        let mut record = StepRecord {
+            pc,
+            timestamp,
+            opcode,
+            operands: [a, b, c, d, e, f, g],
+            next_pc: pc,
+            exit_code: None,
+            memory_accesses: vec![],
+        };
+        if opcode == VmOpcode::with_default_offset(SystemOpcode::TERMINATE) {
+            record.exit_code = Some(c.as_canonical_u32());
+            return Ok(record);
+        }
+        if opcode == VmOpcode::with_default_offset(SystemOpcode::PHANTOM)
+            && SysPhantom::from_repr(c.as_canonical_u32() as u16) == Some(SysPhantom::DebugPanic)
+        {
+            return Err(ExecutionError::Fail { pc });
+        }
+
+        // Record the accesses of the instruction, keeping any access log that was already
+        // enabled.
+        let log_start = memory_controller.borrow().access_log().map(|log| log.len());
+        memory_controller.borrow_mut().enable_access_log();
+        let next_state = self.execute_instruction(pc, timestamp, instruction);
+        let mut memory_controller = memory_controller.borrow_mut();
+        record.memory_accesses = match log_start {
+            Some(start) => memory_controller.access_log().unwrap().entries[start..].to_vec(),
+            None => memory_controller.take_access_log().unwrap().entries,
+        };
+        record.next_pc = next_state?.pc;
+        Ok(record)
+    }
+
+    /// Steps from `pc` until the pc is `until_pc` or the program terminates. At least one
+    /// instruction is executed. See [Self::step].
+    pub fn run_until(
+        &mut self,
+        mut pc: u32,
+        until_pc: u32,
+    ) -> Result<ExecutionSegmentState, ExecutionError> {
+        loop {
+            let record = self.step(pc)?;
+            if record.exit_code.is_some() {
+                return Ok(ExecutionSegmentState {
+                    pc,
+                    is_terminated: true,
+                });
+            }
+            pc = record.next_pc;
+            if pc == until_pc {
+                return Ok(ExecutionSegmentState {
+                    pc,
+                    is_terminated: false,
+                });
+            }
+        }
+    }
+
     /// Generate ProofInput to prove the segment. Should be called after ::execute
     pub fn generate_proof_input<SC: StarkGenericConfig>(
         self,
@@ -302,6 +960,10 @@ impl<F: PrimeField32, VC: VmConfig<F>> ExecutionSegment<F, VC> {
         VC::Executor: Chip<SC>,
         VC::Periphery: Chip<SC>,
     {
+        assert_eq!(
+            self.dynamic_instructions, 0,
+            "instructions of dynamic executors cannot be proven"
+        );
         #[cfg(feature = "bench-metrics")]
         let start = std::time::Instant::now();
 
@@ -316,26 +978,21 @@ impl<F: PrimeField32, VC: VmConfig<F>> ExecutionSegment<F, VC> {
     /// Returns bool of whether to switch to next segment or not. This is called every clock cycle inside of Core trace generation.
     ///
     /// Default config: switch if any runtime chip height exceeds 1<<20 - 100
-    fn should_segment(&mut self) -> bool {
+    fn should_segment(&mut self, instructions_retired: u64) -> bool {
         // Avoid checking segment too often.
         if self.since_last_segment_check != SEGMENT_CHECK_INTERVAL {
             self.since_last_segment_check += 1;
             return false;
         }
         self.since_last_segment_check = 0;
-        let heights = self.chip_complex.dynamic_trace_heights();
-        for (i, height) in heights.enumerate() {
-            if height > self.system_config().max_segment_len {
-                tracing::info!(
-                    "Should segment because chip {} has height {}",
-                    self.air_names[i],
-                    height
-                );
-                return true;
-            }
-        }
-
-        false
+        let trace_heights: Vec<_> = self.chip_complex.dynamic_trace_heights().collect();
+        let trace_cells = self.current_trace_cells();
+        self.segmentation_strategy.should_segment(&SegmentationCtx {
+            air_names: &self.air_names,
+            trace_heights: &trace_heights,
+            trace_cells: &trace_cells,
+            cycles: instructions_retired,
+        })
     }
 
     pub fn current_trace_cells(&self) -> Vec<usize> {
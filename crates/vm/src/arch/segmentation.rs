@@ -0,0 +1,109 @@
+use std::fmt::Debug;
+
+use serde::{Deserialize, Serialize};
+
+/// State of an [ExecutionSegment](super::ExecutionSegment) passed to a [SegmentationStrategy].
+#[derive(Clone, Copy, Debug)]
+pub struct SegmentationCtx<'a> {
+    pub air_names: &'a [String],
+    /// Current trace height of each AIR, in the order of `air_names`. AIRs with constant heights
+    /// are reported as 0, since they do not grow with execution.
+    pub trace_heights: &'a [usize],
+    /// Current main trace cells of each AIR, in the order of `air_names`.
+    pub trace_cells: &'a [usize],
+    /// Number of instructions executed in the segment so far.
+    pub cycles: u64,
+}
+
+/// Decides when an execution segment is cut. Checked every few instructions.
+pub trait SegmentationStrategy: Debug + Send + Sync {
+    fn should_segment(&self, ctx: &SegmentationCtx) -> bool;
+}
+
+/// Built-in segmentation strategies, selectable in the
+/// [SystemConfig](super::SystemConfig::segmentation_limits).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SegmentationLimit {
+    /// Cut when any trace is higher than this.
+    MaxHeight(usize),
+    /// Cut when all traces together have more cells than this.
+    MaxCells(usize),
+    /// Cut after this many instructions.
+    MaxCycles(u64),
+}
+
+impl SegmentationStrategy for SegmentationLimit {
+    fn should_segment(&self, ctx: &SegmentationCtx) -> bool {
+        match *self {
+            Self::MaxHeight(max_height) => {
+                if let Some((air_name, height)) = ctx
+                    .air_names
+                    .iter()
+                    .zip(ctx.trace_heights)
+                    .find(|(_, &height)| height > max_height)
+                {
+                    tracing::info!("Should segment because chip {air_name} has height {height}");
+                    return true;
+                }
+                false
+            }
+            Self::MaxCells(max_cells) => {
+                let cells: usize = ctx.trace_cells.iter().sum();
+                if cells > max_cells {
+                    tracing::info!("Should segment because traces have {cells} cells");
+                    return true;
+                }
+                false
+            }
+            Self::MaxCycles(max_cycles) => {
+                if ctx.cycles > max_cycles {
+                    tracing::info!("Should segment after {} cycles", ctx.cycles);
+                    return true;
+                }
+                false
+            }
+        }
+    }
+}
+
+/// Cuts as soon as any of the strategies does.
+impl<S: SegmentationStrategy> SegmentationStrategy for Vec<S> {
+    fn should_segment(&self, ctx: &SegmentationCtx) -> bool {
+        self.iter().any(|strategy| strategy.should_segment(ctx))
+    }
+}
+
+impl SegmentationStrategy for Box<dyn SegmentationStrategy> {
+    fn should_segment(&self, ctx: &SegmentationCtx) -> bool {
+        self.as_ref().should_segment(ctx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SegmentationCtx, SegmentationLimit, SegmentationStrategy};
+
+    #[test]
+    fn test_segmentation_limits() {
+        let air_names = ["A".to_string(), "B".to_string()];
+        let ctx = SegmentationCtx {
+            air_names: &air_names,
+            trace_heights: &[10, 20],
+            trace_cells: &[100, 400],
+            cycles: 30,
+        };
+        assert!(!SegmentationLimit::MaxHeight(20).should_segment(&ctx));
+        assert!(SegmentationLimit::MaxHeight(19).should_segment(&ctx));
+        assert!(!SegmentationLimit::MaxCells(500).should_segment(&ctx));
+        assert!(SegmentationLimit::MaxCells(499).should_segment(&ctx));
+        assert!(!SegmentationLimit::MaxCycles(30).should_segment(&ctx));
+        assert!(SegmentationLimit::MaxCycles(29).should_segment(&ctx));
+
+        let limits = vec![
+            SegmentationLimit::MaxHeight(20),
+            SegmentationLimit::MaxCycles(29),
+        ];
+        assert!(limits.should_segment(&ctx));
+        assert!(!limits[..1].to_vec().should_segment(&ctx));
+    }
+}
@@ -0,0 +1,310 @@
+//! Deterministic fuzzing of the memory subsystem.
+//!
+//! A seed expands to a sequence of [MemoryFuzzOp]s, which are executed on a fresh volatile
+//! [MemoryController]. The memory AIRs are then proven together with one [MemoryDummyAir] per
+//! block size that sends the accesses to the [MemoryBus]. Failing sequences are shrunk to a
+//! minimal failing sequence before being reported.
+
+use std::{
+    any::Any,
+    borrow::BorrowMut,
+    collections::BTreeMap,
+    fmt,
+    mem::size_of,
+    panic::{catch_unwind, AssertUnwindSafe},
+    sync::Arc,
+};
+
+use openvm_circuit_primitives::var_range::{VariableRangeCheckerBus, VariableRangeCheckerChip};
+use openvm_stark_backend::{
+    config::{StarkGenericConfig, Val},
+    interaction::InteractionType,
+    p3_field::{AbstractField, PrimeField32},
+    p3_matrix::dense::RowMajorMatrix,
+    prover::types::AirProofInput,
+    verifier::VerificationError,
+    Chip,
+};
+use openvm_stark_sdk::{
+    config::baby_bear_poseidon2::BabyBearPoseidon2Engine, engine::StarkFriEngine,
+    p3_baby_bear::BabyBear,
+};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+use super::air::{DummyMemoryInteractionCols, MemoryDummyAir};
+use crate::{
+    arch::{MemoryConfig, MEMORY_BUS},
+    system::{
+        memory::{
+            offline_checker::{MemoryBus, MemoryBusInteraction},
+            MemoryAddress, MemoryController, OpType,
+        },
+        poseidon2::Poseidon2PeripheryChip,
+    },
+};
+
+/// Block sizes the harness can execute.
+pub const FUZZ_BLOCK_SIZES: [usize; 6] = [1, 2, 4, 8, 16, 32];
+
+const RANGE_CHECKER_BUS: usize = 4;
+
+/// A single memory access of a fuzzing case.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MemoryFuzzOp {
+    pub op_type: OpType,
+    pub address_space: u32,
+    /// Aligned to `block_size`.
+    pub pointer: u32,
+    pub block_size: usize,
+    /// Seed of the written values. Unused for reads.
+    pub value: u32,
+}
+
+#[derive(Clone, Debug)]
+pub struct MemoryFuzzConfig {
+    pub num_ops: usize,
+    pub address_spaces: Vec<u32>,
+    /// Must be a subset of [FUZZ_BLOCK_SIZES].
+    pub block_sizes: Vec<usize>,
+    /// Pointers are drawn below this bound. A small bound makes accesses of different block sizes
+    /// overlap, which exercises the access adapters.
+    pub pointer_range: u32,
+}
+
+impl Default for MemoryFuzzConfig {
+    fn default() -> Self {
+        Self {
+            num_ops: 64,
+            address_spaces: vec![1, 2],
+            block_sizes: FUZZ_BLOCK_SIZES.to_vec(),
+            pointer_range: 256,
+        }
+    }
+}
+
+/// Why a fuzzing case failed.
+#[derive(Debug)]
+pub enum MemoryFuzzFailure {
+    /// Execution or trace generation panicked.
+    Panic(String),
+    Verification(VerificationError),
+}
+
+impl fmt::Display for MemoryFuzzFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Panic(msg) => write!(f, "panicked: {msg}"),
+            Self::Verification(err) => write!(f, "verification failed: {err:?}"),
+        }
+    }
+}
+
+/// Expands `seed` into a sequence of memory accesses. The same seed and config always give the
+/// same sequence.
+pub fn generate_memory_ops(config: &MemoryFuzzConfig, seed: u64) -> Vec<MemoryFuzzOp> {
+    assert!(
+        config
+            .block_sizes
+            .iter()
+            .all(|n| FUZZ_BLOCK_SIZES.contains(n)),
+        "unsupported block size in {:?}",
+        config.block_sizes
+    );
+    let mut rng = StdRng::seed_from_u64(seed);
+    (0..config.num_ops)
+        .map(|_| {
+            let block_size = *config.block_sizes.choose(&mut rng).unwrap();
+            let pointer_range = config.pointer_range.max(block_size as u32);
+            MemoryFuzzOp {
+                op_type: if rng.gen_bool(0.5) {
+                    OpType::Read
+                } else {
+                    OpType::Write
+                },
+                address_space: *config.address_spaces.choose(&mut rng).unwrap(),
+                pointer: rng.gen_range(0..pointer_range) / block_size as u32 * block_size as u32,
+                block_size,
+                value: rng.gen(),
+            }
+        })
+        .collect()
+}
+
+/// Executes `ops` on a fresh volatile memory controller and proves the memory AIRs.
+pub fn check_memory_ops(ops: &[MemoryFuzzOp]) -> Result<(), MemoryFuzzFailure> {
+    match catch_unwind(AssertUnwindSafe(|| prove_memory_ops(ops))) {
+        Ok(result) => result.map_err(MemoryFuzzFailure::Verification),
+        Err(payload) => Err(MemoryFuzzFailure::Panic(panic_message(payload))),
+    }
+}
+
+/// Removes as many accesses from `ops` as possible while `fails` still holds, then zeroes the
+/// written values where possible.
+pub fn shrink_memory_ops(
+    mut ops: Vec<MemoryFuzzOp>,
+    mut fails: impl FnMut(&[MemoryFuzzOp]) -> bool,
+) -> Vec<MemoryFuzzOp> {
+    let mut chunk_len = ops.len().div_ceil(2);
+    while chunk_len > 0 {
+        let mut start = 0;
+        while start < ops.len() {
+            let end = (start + chunk_len).min(ops.len());
+            let candidate: Vec<_> = ops[..start].iter().chain(&ops[end..]).copied().collect();
+            if fails(&candidate) {
+                ops = candidate;
+            } else {
+                start = end;
+            }
+        }
+        chunk_len /= 2;
+    }
+    for i in 0..ops.len() {
+        if ops[i].value != 0 {
+            let mut candidate = ops.clone();
+            candidate[i].value = 0;
+            if fails(&candidate) {
+                ops = candidate;
+            }
+        }
+    }
+    ops
+}
+
+/// Runs the case of each seed, and panics with the shrunk accesses of the first failing case.
+pub fn fuzz_memory(config: &MemoryFuzzConfig, seeds: impl IntoIterator<Item = u64>) {
+    for seed in seeds {
+        let ops = generate_memory_ops(config, seed);
+        if let Err(failure) = check_memory_ops(&ops) {
+            let ops = shrink_memory_ops(ops, |ops| check_memory_ops(ops).is_err());
+            panic!(
+                "memory fuzzing failed for seed {seed}: {failure}\nshrunk to {} accesses: {ops:#?}",
+                ops.len()
+            );
+        }
+    }
+}
+
+fn prove_memory_ops(ops: &[MemoryFuzzOp]) -> Result<(), VerificationError> {
+    let mem_config = MemoryConfig::new(2, 1, 29, 29, 17, 64);
+    let range_checker = Arc::new(VariableRangeCheckerChip::new(VariableRangeCheckerBus::new(
+        RANGE_CHECKER_BUS,
+        mem_config.decomp,
+    )));
+    let memory_bus = MemoryBus(MEMORY_BUS);
+    let mut memory_controller =
+        MemoryController::with_volatile_memory(memory_bus, mem_config, range_checker.clone());
+
+    let mut records = BTreeMap::<usize, Vec<MemoryBusInteraction<BabyBear>>>::new();
+    for op in ops {
+        let interactions = match op.block_size {
+            1 => execute_op::<1>(&mut memory_controller, op),
+            2 => execute_op::<2>(&mut memory_controller, op),
+            4 => execute_op::<4>(&mut memory_controller, op),
+            8 => execute_op::<8>(&mut memory_controller, op),
+            16 => execute_op::<16>(&mut memory_controller, op),
+            32 => execute_op::<32>(&mut memory_controller, op),
+            n => panic!("unsupported block size {n}"),
+        };
+        records
+            .entry(op.block_size)
+            .or_default()
+            .extend(interactions);
+    }
+    memory_controller.finalize(None::<&mut Poseidon2PeripheryChip<BabyBear>>);
+
+    let mut air_proof_inputs: Vec<_> = records
+        .into_iter()
+        .map(|(block_size, records)| match block_size {
+            1 => dummy_air_proof_input::<_, 1>(memory_bus, records),
+            2 => dummy_air_proof_input::<_, 2>(memory_bus, records),
+            4 => dummy_air_proof_input::<_, 4>(memory_bus, records),
+            8 => dummy_air_proof_input::<_, 8>(memory_bus, records),
+            16 => dummy_air_proof_input::<_, 16>(memory_bus, records),
+            32 => dummy_air_proof_input::<_, 32>(memory_bus, records),
+            n => panic!("unsupported block size {n}"),
+        })
+        .collect();
+    air_proof_inputs.extend(
+        memory_controller
+            .generate_air_proof_inputs()
+            .into_iter()
+            .filter(|api| api.main_trace_height() > 0),
+    );
+    air_proof_inputs.push(range_checker.generate_air_proof_input());
+
+    BabyBearPoseidon2Engine::run_test_fast(air_proof_inputs).map(|_| ())
+}
+
+/// Executes `op` and returns the bus interactions that balance the controller's records.
+fn execute_op<const N: usize>(
+    memory_controller: &mut MemoryController<BabyBear>,
+    op: &MemoryFuzzOp,
+) -> [MemoryBusInteraction<BabyBear>; 2] {
+    let bus = memory_controller.memory_bus;
+    let [address_space, pointer] = [op.address_space, op.pointer].map(BabyBear::from_canonical_u32);
+    let address = MemoryAddress::new(address_space, pointer);
+    let (prev_data, data, prev_timestamp, timestamp) = match op.op_type {
+        OpType::Read => {
+            let read = memory_controller.read::<N>(address_space, pointer);
+            (read.data, read.data, read.prev_timestamp, read.timestamp)
+        }
+        OpType::Write => {
+            let data = std::array::from_fn(|i| {
+                BabyBear::from_canonical_u32(op.value.wrapping_add(i as u32) % (1 << 30))
+            });
+            let write = memory_controller.write::<N>(address_space, pointer, data);
+            (
+                write.prev_data,
+                write.data,
+                write.prev_timestamp,
+                write.timestamp,
+            )
+        }
+    };
+    [
+        bus.receive(
+            address,
+            prev_data.to_vec(),
+            BabyBear::from_canonical_u32(prev_timestamp),
+        ),
+        bus.send(
+            address,
+            data.to_vec(),
+            BabyBear::from_canonical_u32(timestamp),
+        ),
+    ]
+}
+
+fn dummy_air_proof_input<SC: StarkGenericConfig, const N: usize>(
+    bus: MemoryBus,
+    records: Vec<MemoryBusInteraction<Val<SC>>>,
+) -> AirProofInput<SC>
+where
+    Val<SC>: PrimeField32,
+{
+    let width = size_of::<DummyMemoryInteractionCols<u8, N>>();
+    let height = records.len().next_power_of_two();
+    let mut values = Val::<SC>::zero_vec(height * width);
+    for (row, record) in values.chunks_mut(width).zip(records) {
+        let row: &mut DummyMemoryInteractionCols<Val<SC>, N> = row.borrow_mut();
+        row.address = record.address;
+        row.data = record.data.try_into().unwrap();
+        row.timestamp = record.timestamp;
+        row.count = match record.interaction_type {
+            InteractionType::Send => Val::<SC>::ONE,
+            InteractionType::Receive => -Val::<SC>::ONE,
+        };
+    }
+    AirProofInput::simple_no_pis(
+        Arc::new(MemoryDummyAir::<N>::new(bus)),
+        RowMajorMatrix::new(values, width),
+    )
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|msg| msg.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string())
+}
@@ -1,6 +1,7 @@
-use std::{array::from_fn, borrow::BorrowMut as _, cell::RefCell, mem::size_of, sync::Arc};
+use std::{array::from_fn, borrow::BorrowMut as _, mem::size_of, sync::Arc};
 
 use air::{DummyMemoryInteractionCols, MemoryDummyAir};
+use atomic_refcell::AtomicRefCell;
 use openvm_stark_backend::{
     config::{StarkGenericConfig, Val},
     interaction::InteractionType,
@@ -18,6 +19,7 @@ use crate::system::memory::{
 };
 
 pub mod air;
+pub mod fuzz;
 
 const WORD_SIZE: usize = 1;
 
@@ -47,7 +49,7 @@ impl<F: PrimeField32> MemoryTester<F> {
     pub fn read_cell(&mut self, address_space: usize, pointer: usize) -> F {
         let [addr_space, pointer] = [address_space, pointer].map(F::from_canonical_usize);
         // core::BorrowMut confuses compiler
-        let read = RefCell::borrow_mut(&self.controller).read_cell(addr_space, pointer);
+        let read = AtomicRefCell::borrow_mut(&self.controller).read_cell(addr_space, pointer);
         let address = MemoryAddress::new(addr_space, pointer);
         self.records.push(self.bus.receive(
             address,
@@ -64,7 +66,8 @@ impl<F: PrimeField32> MemoryTester<F> {
 
     pub fn write_cell(&mut self, address_space: usize, pointer: usize, value: F) {
         let [addr_space, pointer] = [address_space, pointer].map(F::from_canonical_usize);
-        let write = RefCell::borrow_mut(&self.controller).write_cell(addr_space, pointer, value);
+        let write =
+            AtomicRefCell::borrow_mut(&self.controller).write_cell(addr_space, pointer, value);
         let address = MemoryAddress::new(addr_space, pointer);
         self.records.push(self.bus.receive(
             address,
@@ -1,5 +1,6 @@
-use std::{cell::RefCell, rc::Rc, sync::Arc};
+use std::sync::Arc;
 
+use atomic_refcell::AtomicRefCell;
 use openvm_circuit_primitives::var_range::{VariableRangeCheckerBus, VariableRangeCheckerChip};
 use openvm_instructions::instruction::Instruction;
 use openvm_stark_backend::{
@@ -225,7 +226,7 @@ impl<F: PrimeField32> Default for VmChipTestBuilder<F> {
             range_checker,
         );
         Self {
-            memory: MemoryTester::new(Rc::new(RefCell::new(memory_controller))),
+            memory: MemoryTester::new(Arc::new(AtomicRefCell::new(memory_controller))),
             execution: ExecutionTester::new(ExecutionBus(EXECUTION_BUS)),
             program: ProgramTester::new(ProgramBus(READ_INSTRUCTION_BUS)),
             rng: StdRng::seed_from_u64(0),
@@ -272,7 +273,7 @@ where
             let range_checker = memory_controller.borrow().range_checker.clone();
             self = self.load(memory_tester); // dummy memory interactions
             {
-                let air_proof_inputs = Rc::try_unwrap(memory_controller)
+                let air_proof_inputs = Arc::try_unwrap(memory_controller)
                     .unwrap()
                     .into_inner()
                     .generate_air_proof_inputs();
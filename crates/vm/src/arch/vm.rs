@@ -1,35 +1,114 @@
-use std::{borrow::Borrow, collections::VecDeque, marker::PhantomData, mem, sync::Arc};
+use std::{
+    borrow::Borrow,
+    collections::{BTreeMap, VecDeque},
+    fmt::{self, Debug},
+    marker::PhantomData,
+    mem,
+    ops::Range,
+    sync::{mpsc, Arc},
+    thread,
+    time::Duration,
+};
 
-use openvm_instructions::exe::VmExe;
+use openvm_instructions::{
+    exe::{source_location, MemoryImage, SourceLines, VmExe},
+    VmOpcode,
+};
 use openvm_stark_backend::{
-    config::{Domain, StarkGenericConfig, Val},
+    config::{Com, Domain, StarkGenericConfig, Val},
     engine::StarkEngine,
     keygen::types::{MultiStarkProvingKey, MultiStarkVerifyingKey},
     p3_commit::PolynomialSpace,
-    p3_field::PrimeField32,
+    p3_field::{AbstractField, PrimeField32},
     prover::types::{CommittedTraceData, Proof, ProofInput},
     verifier::VerificationError,
     Chip,
 };
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-use super::{ExecutionError, VmComplexTraceHeights, VmConfig, CONNECTOR_AIR_ID, MERKLE_AIR_ID};
+use super::{
+    ArchState, Debugger, DynamicExecutors, ExecutionBudgetUsage, ExecutionError, ExecutionLog,
+    ExecutionLogMode, GuestLogRecord, GuestLogger, HintProvider, InspectionHook,
+    InstructionExecutor, PhantomRecord, SharedDebugger, SharedHintProvider, VmComplexTraceHeights,
+    VmConfig, VmConfigError, VmInventoryError, CONNECTOR_AIR_ID, MERKLE_AIR_ID,
+    PROGRAM_CACHED_TRACE_INDEX,
+};
 use crate::{
-    arch::segment::ExecutionSegment,
+    arch::{hasher::poseidon2::vm_poseidon2_hasher, segment::ExecutionSegment},
+    metrics::{cycle_tracker::CycleTracker, TraceEventRecorder},
     system::{
         connector::{VmConnectorPvs, DEFAULT_SUSPEND_EXIT_CODE},
-        memory::{memory_image_to_equipartition, merkle::MemoryMerklePvs, Equipartition, CHUNK},
+        kv_store::KvStore,
+        memory::{
+            memory_image_to_equipartition,
+            merkle::MemoryMerklePvs,
+            tree::{
+                proof::MemoryRegionProof, public_values::PUBLIC_VALUES_ADDRESS_SPACE_OFFSET,
+                MemoryNode,
+            },
+            Equipartition, CHUNK,
+        },
         program::trace::VmCommittedExe,
     },
 };
 
-/// VM memory state for continuations.
-pub type VmMemoryState<F> = Equipartition<F, CHUNK>;
+/// VM memory state for continuations. Shared between consecutive segments rather than copied.
+pub type VmMemoryState<F> = Arc<Equipartition<F, CHUNK>>;
+
+/// Name of the main [Streams::input_stream], which is input stream 0.
+pub const STDIN_STREAM: &str = "stdin";
+
+/// An input stream besides the main input stream, see [Streams::with_named_stream].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound(serialize = "F: Serialize", deserialize = "F: Deserialize<'de>"))]
+pub struct NamedStream<F> {
+    pub name: String,
+    pub source: StreamSource<F>,
+    /// Number of inputs read from the stream so far.
+    pub num_read: usize,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound(serialize = "F: Serialize", deserialize = "F: Deserialize<'de>"))]
+pub enum StreamSource<F> {
+    /// Inputs read in order, e.g. a witness stream.
+    Queue(VecDeque<Vec<F>>),
+    /// Inputs looked up by the key the guest sends along with each request. Reads do not consume
+    /// the entries.
+    KeyValue(Vec<(Vec<F>, Vec<F>)>),
+}
 
-#[derive(Clone, Default, Debug)]
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(bound(serialize = "F: Serialize", deserialize = "F: Deserialize<'de>"))]
 pub struct Streams<F> {
     pub input_stream: VecDeque<Vec<F>>,
     pub hint_stream: VecDeque<F>,
+    /// Provides the inputs requested once `input_stream` is exhausted. Not serialized, so it must
+    /// be set again when resuming from a [VmCheckpoint].
+    #[serde(skip)]
+    pub hint_provider: Option<SharedHintProvider<F>>,
+    /// See [Self::with_hint_timeout]. Not serialized either.
+    #[serde(skip)]
+    pub hint_timeout: Option<Duration>,
+    /// Set when the `hint_provider` did not answer within `hint_timeout`.
+    #[serde(skip)]
+    hint_timed_out: bool,
+    /// Number of inputs read so far, used as the hint ID of the next request to the
+    /// `hint_provider`.
+    #[serde(default)]
+    pub num_inputs_read: usize,
+    /// See [Self::with_execution_log] and [Self::replay].
+    #[serde(default)]
+    pub execution_log: ExecutionLogMode<F>,
+    /// Input streams `1..` of the guest, see [Self::with_named_stream].
+    #[serde(default)]
+    pub named_streams: Vec<NamedStream<F>>,
+    /// The store looked up by [KvStoreOpcode::LOOKUP](openvm_instructions::KvStoreOpcode), see
+    /// [SystemConfig::with_kv_store](super::SystemConfig::with_kv_store).
+    #[serde(default)]
+    pub kv_store: Option<KvStore<F>>,
 }
 
 impl<F> Streams<F> {
@@ -37,7 +116,241 @@ impl<F> Streams<F> {
         Self {
             input_stream: input_stream.into(),
             hint_stream: VecDeque::default(),
+            hint_provider: None,
+            hint_timeout: None,
+            hint_timed_out: false,
+            num_inputs_read: 0,
+            execution_log: ExecutionLogMode::Off,
+            named_streams: vec![],
+            kv_store: None,
+        }
+    }
+
+    pub fn with_kv_store(mut self, kv_store: KvStore<F>) -> Self {
+        self.kv_store = Some(kv_store);
+        self
+    }
+
+    /// Adds an input stream whose inputs the guest reads in order, e.g. a witness stream next to
+    /// the main input stream. Streams are addressed by the guest with their index, see
+    /// [Self::stream_index].
+    pub fn with_named_stream(
+        self,
+        name: impl Into<String>,
+        inputs: impl Into<VecDeque<Vec<F>>>,
+    ) -> Self {
+        self.with_stream_source(name.into(), StreamSource::Queue(inputs.into()))
+    }
+
+    /// Adds an input stream answering each request with the value of the key sent by the guest,
+    /// or with the end of the input if there is no such key.
+    pub fn with_key_value_store(
+        self,
+        name: impl Into<String>,
+        entries: impl IntoIterator<Item = (Vec<F>, Vec<F>)>,
+    ) -> Self {
+        self.with_stream_source(
+            name.into(),
+            StreamSource::KeyValue(entries.into_iter().collect()),
+        )
+    }
+
+    fn with_stream_source(mut self, name: String, source: StreamSource<F>) -> Self {
+        assert!(
+            self.stream_index(&name).is_none(),
+            "input stream {name} already exists"
+        );
+        self.named_streams.push(NamedStream {
+            name,
+            source,
+            num_read: 0,
+        });
+        self
+    }
+
+    /// Index of input stream `name`. The main input stream [STDIN_STREAM] is stream 0, followed
+    /// by the named streams in the order they were added.
+    pub fn stream_index(&self, name: &str) -> Option<usize> {
+        if name == STDIN_STREAM {
+            return Some(0);
+        }
+        self.named_streams
+            .iter()
+            .position(|stream| stream.name == name)
+            .map(|idx| idx + 1)
+    }
+
+    pub fn num_streams(&self) -> usize {
+        1 + self.named_streams.len()
+    }
+
+    /// Number of inputs read so far from each input stream, by name, e.g. to audit which inputs
+    /// a run consumed.
+    pub fn read_offsets(&self) -> BTreeMap<String, usize> {
+        self.named_streams
+            .iter()
+            .map(|stream| (stream.name.clone(), stream.num_read))
+            .chain([(STDIN_STREAM.to_string(), self.num_inputs_read)])
+            .collect()
+    }
+
+    /// Records the host-side effects of the run into an [ExecutionLog], see
+    /// [Self::take_execution_log].
+    pub fn with_execution_log(mut self) -> Self {
+        self.execution_log = ExecutionLogMode::Record(ExecutionLog::default());
+        self
+    }
+
+    /// Streams that replay `log` instead of computing hints, without any input.
+    pub fn replay(log: ExecutionLog<F>) -> Self {
+        Self {
+            execution_log: ExecutionLogMode::Replay { log, next: 0 },
+            ..Self::new(VecDeque::new())
+        }
+    }
+
+    /// In replay mode, applies the recorded effects of the phantom instruction at `pc` and
+    /// returns true, so that its sub-executor is not run. Returns false otherwise.
+    pub(crate) fn replay_phantom(&mut self, pc: u32) -> Result<bool, ExecutionError>
+    where
+        F: Clone,
+    {
+        let ExecutionLogMode::Replay { log, next } = &mut self.execution_log else {
+            return Ok(false);
+        };
+        let record = log
+            .phantoms
+            .get(*next)
+            .filter(|record| record.pc == pc)
+            .ok_or(ExecutionError::ReplayDivergence { pc })?;
+        if let Some(hint_stream) = &record.hint_stream {
+            self.hint_stream = hint_stream.clone();
+        }
+        *next += 1;
+        Ok(true)
+    }
+
+    /// In record mode, records the effects of the phantom instruction at `pc`, which found the
+    /// hint stream `prev_hint_stream`.
+    pub(crate) fn record_phantom(&mut self, pc: u32, prev_hint_stream: &VecDeque<F>)
+    where
+        F: Clone + PartialEq,
+    {
+        if let ExecutionLogMode::Record(log) = &mut self.execution_log {
+            let hint_stream =
+                (self.hint_stream != *prev_hint_stream).then(|| self.hint_stream.clone());
+            log.phantoms.push(PhantomRecord { pc, hint_stream });
+        }
+    }
+
+    pub(crate) fn is_recording(&self) -> bool {
+        matches!(self.execution_log, ExecutionLogMode::Record(_))
+    }
+
+    /// Returns the recorded execution log and stops recording.
+    pub fn take_execution_log(&mut self) -> Option<ExecutionLog<F>> {
+        match mem::take(&mut self.execution_log) {
+            ExecutionLogMode::Record(log) => Some(log),
+            mode => {
+                self.execution_log = mode;
+                None
+            }
+        }
+    }
+
+    pub fn with_hint_provider(mut self, provider: impl HintProvider<F> + 'static) -> Self {
+        self.hint_provider = Some(Arc::new(Mutex::new(provider)));
+        self
+    }
+
+    /// Fails execution with [ExecutionError::HintTimeout] if the `hint_provider` blocks for
+    /// longer than `timeout` on a request. The provider then runs on a separate thread for each
+    /// request, and a provider that timed out is left running in the background.
+    pub fn with_hint_timeout(mut self, timeout: Duration) -> Self {
+        self.hint_timeout = Some(timeout);
+        self
+    }
+
+    /// Returns the next input of the guest: the front of `input_stream` or, once it is
+    /// exhausted, the next hint of the `hint_provider`. `None` at the end of the input, or if
+    /// the provider timed out, see [Self::take_hint_timeout].
+    pub fn next_input(&mut self, payload: &[F]) -> Option<Vec<F>>
+    where
+        F: Clone + Send + 'static,
+    {
+        let input = match self.input_stream.pop_front() {
+            Some(input) => input,
+            None => self.request_hint(payload)?,
+        };
+        self.num_inputs_read += 1;
+        Some(input)
+    }
+
+    /// Returns the next input of input stream `stream`, see [Self::stream_index]. For key-value
+    /// stores, `payload` is the key. `None` at the end of the stream or if there is no such
+    /// stream.
+    pub fn next_input_from(&mut self, stream: usize, payload: &[F]) -> Option<Vec<F>>
+    where
+        F: Clone + PartialEq + Send + 'static,
+    {
+        if stream == 0 {
+            return self.next_input(payload);
         }
+        let named = self.named_streams.get_mut(stream - 1)?;
+        let input = match &mut named.source {
+            StreamSource::Queue(inputs) => inputs.pop_front(),
+            StreamSource::KeyValue(entries) => entries
+                .iter()
+                .find(|(key, _)| key.as_slice() == payload)
+                .map(|(_, value)| value.clone()),
+        }?;
+        named.num_read += 1;
+        Some(input)
+    }
+
+    fn request_hint(&mut self, payload: &[F]) -> Option<Vec<F>>
+    where
+        F: Clone + Send + 'static,
+    {
+        let provider = self.hint_provider.as_ref()?;
+        let hint_id = self.num_inputs_read;
+        let Some(timeout) = self.hint_timeout else {
+            return provider.lock().next_hint(hint_id, payload);
+        };
+        let (sender, receiver) = mpsc::channel();
+        let provider = provider.clone();
+        let payload = payload.to_vec();
+        thread::spawn(move || {
+            // The receiver is gone if the request timed out.
+            let _ = sender.send(provider.lock().next_hint(hint_id, &payload));
+        });
+        receiver.recv_timeout(timeout).unwrap_or_else(|_| {
+            self.hint_timed_out = true;
+            None
+        })
+    }
+
+    /// Returns the hint timeout if the last request to the `hint_provider` timed out, and
+    /// clears that state.
+    pub fn take_hint_timeout(&mut self) -> Option<Duration> {
+        mem::take(&mut self.hint_timed_out)
+            .then_some(self.hint_timeout)
+            .flatten()
+    }
+}
+
+impl<F: Debug> Debug for Streams<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Streams")
+            .field("input_stream", &self.input_stream)
+            .field("hint_stream", &self.hint_stream)
+            .field("has_hint_provider", &self.hint_provider.is_some())
+            .field("hint_timeout", &self.hint_timeout)
+            .field("num_inputs_read", &self.num_inputs_read)
+            .field("execution_log", &self.execution_log)
+            .field("named_streams", &self.named_streams)
+            .field("has_kv_store", &self.kv_store.is_some())
+            .finish()
     }
 }
 
@@ -53,9 +366,76 @@ impl<F> From<Vec<Vec<F>>> for Streams<F> {
     }
 }
 
+/// State of a run at a segment boundary, from which [VmExecutor::execute_segment] resumes
+/// execution, possibly in another process after a serialization round trip. This way the
+/// segments of a long execution can be executed and traced on different machines.
+///
+/// Registers are part of the memory. Timestamps restart at every segment, so they are not part
+/// of the state.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound(serialize = "F: Serialize", deserialize = "F: Deserialize<'de>"))]
+pub struct VmCheckpoint<F> {
+    /// Index of the segment to execute next.
+    pub segment_idx: usize,
+    pub pc: u32,
+    #[serde(with = "memory_state_serde")]
+    pub memory: VmMemoryState<F>,
+    pub streams: Streams<F>,
+    /// Resources used by the previous segments.
+    pub budget_usage: ExecutionBudgetUsage,
+    /// The carry-over values, see
+    /// [SystemConfig::with_carry_over](super::SystemConfig::with_carry_over). All zero at the
+    /// start of the program.
+    #[serde(default)]
+    pub carry_over: Vec<F>,
+    /// Merkle tree of `memory`, carried over when resuming in the same process so that the next
+    /// segment does not rehash the whole memory. Not serialized.
+    #[serde(skip)]
+    memory_tree: Option<MemoryNode<CHUNK, F>>,
+    #[serde(skip)]
+    cycle_tracker: CycleTracker,
+    #[serde(skip)]
+    trace_events: Option<TraceEventRecorder>,
+}
+
+/// Serializes the memory state as a sequence of entries, so that formats with string-only map
+/// keys can represent it.
+mod memory_state_serde {
+    use std::sync::Arc;
+
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::VmMemoryState;
+    use crate::system::memory::CHUNK;
+
+    pub fn serialize<F: Serialize, S: Serializer>(
+        memory: &VmMemoryState<F>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(memory.iter())
+    }
+
+    pub fn deserialize<'de, F: Deserialize<'de>, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<VmMemoryState<F>, D::Error> {
+        let entries = Vec::<((u32, u32), [F; CHUNK])>::deserialize(deserializer)?;
+        Ok(Arc::new(entries.into_iter().collect()))
+    }
+}
+
 pub struct VmExecutor<F, VC> {
     pub config: VC,
     pub overridden_heights: Option<VmComplexTraceHeights>,
+    /// See [Self::register_executor].
+    dynamic_executors: DynamicExecutors<F>,
+    /// See [Self::set_debugger].
+    debugger: Option<SharedDebugger<F>>,
+    /// See [Self::set_guest_logger].
+    guest_logger: Option<GuestLogger>,
+    /// See [Self::set_inspection_hook].
+    inspection_hook: Option<(u64, InspectionHook<F>)>,
+    /// See [Self::set_basic_block_execution].
+    basic_block_execution: bool,
     _marker: PhantomData<F>,
 }
 
@@ -66,6 +446,49 @@ pub enum ExitCode {
     Suspended = -1, // Continuations
 }
 
+/// How a program terminated, as returned by [VmExecutor::execute_with_result].
+#[derive(Clone, Debug)]
+pub struct ExecutionResult<F> {
+    /// The exit code of the TERMINATE instruction. See [ExitCode].
+    pub exit_code: u32,
+    /// The exit message of the TERMINATE instruction, e.g. a panic message, if it had one.
+    pub exit_message: Option<String>,
+    /// All user public values. None means the public value is not set.
+    pub public_values: Vec<Option<F>>,
+    /// Final memory, when continuations are enabled.
+    pub final_memory: Option<VmMemoryState<F>>,
+    /// Number of inputs the program read from each input stream, see [Streams::read_offsets].
+    pub stream_offsets: BTreeMap<String, usize>,
+}
+
+/// Logs the source location of the pc at which execution failed, if the exe has line info.
+fn log_error_source(source_lines: &SourceLines, err: &ExecutionError) {
+    if let Some(location) = source_location(source_lines, err.pc()) {
+        tracing::error!("{err} ({location})");
+    }
+}
+
+impl<F> ExecutionResult<F> {
+    pub fn is_success(&self) -> bool {
+        self.exit_code == ExitCode::Success as u32
+    }
+}
+
+/// Results of [VmExecutor::execute_only].
+#[derive(Clone, Debug)]
+pub struct ExecuteOnlyResult<F> {
+    /// Values of the initialized or written memory cells, in chunks. Unlike the final memory of
+    /// [VmExecutor::execute], chunks that were only read are left out.
+    pub final_memory: VmMemoryState<F>,
+    pub exit_code: u32,
+    /// Number of segments a proving run would split the execution into.
+    pub num_segments: usize,
+    /// Number of instructions executed.
+    pub cycles: u64,
+    /// Input and hints left unread at termination.
+    pub streams: Streams<F>,
+}
+
 pub struct VmExecutorResult<SC: StarkGenericConfig> {
     pub per_segment: Vec<ProofInput<SC>>,
     /// When VM is running on persistent mode, public values are stored in a special memory space.
@@ -84,6 +507,12 @@ where
         Self::new_with_overridden_trace_heights(config, None)
     }
 
+    /// Create a new VM executor after checking the config with [VmConfig::validate].
+    pub fn try_new(config: VC) -> Result<Self, VmConfigError> {
+        config.validate()?;
+        Ok(Self::new(config))
+    }
+
     pub fn set_override_trace_heights(&mut self, overridden_heights: VmComplexTraceHeights) {
         self.overridden_heights = Some(overridden_heights);
     }
@@ -95,78 +524,243 @@ where
         Self {
             config,
             overridden_heights,
+            dynamic_executors: DynamicExecutors::default(),
+            debugger: None,
+            guest_logger: None,
+            inspection_hook: None,
+            basic_block_execution: false,
             _marker: Default::default(),
         }
     }
 
+    /// Lets `debugger` inspect and control execution before every instruction of every segment,
+    /// e.g. a [GdbServer](super::GdbServer).
+    pub fn set_debugger(&mut self, debugger: impl Debugger<F> + 'static) {
+        self.debugger = Some(Arc::new(Mutex::new(debugger)));
+    }
+
+    /// Passes the messages the guest logs, e.g. with `println!`, to `logger` instead of
+    /// `tracing`. See [GuestLogConfig](super::GuestLogConfig).
+    pub fn set_guest_logger(&mut self, logger: impl FnMut(&GuestLogRecord) + Send + 'static) {
+        self.guest_logger = Some(Arc::new(Mutex::new(logger)));
+    }
+
+    /// Calls `hook` with the [ArchState] every `interval` instructions of each segment, e.g. to
+    /// check guest invariants. Execution stops with [ExecutionError::InspectionFailed] if `hook`
+    /// fails.
+    pub fn set_inspection_hook(
+        &mut self,
+        interval: u64,
+        hook: impl FnMut(&ArchState<F>) -> eyre::Result<()> + Send + 'static,
+    ) {
+        assert!(interval > 0, "inspection interval must be positive");
+        self.inspection_hook = Some((interval, Arc::new(Mutex::new(hook))));
+    }
+
+    /// Executes straight-line code a basic block at a time in every segment, which speeds up
+    /// execution without changing the records. See
+    /// [ExecutionSegment::set_basic_block_execution].
+    pub fn set_basic_block_execution(&mut self, enabled: bool) {
+        self.basic_block_execution = enabled;
+    }
+
+    /// Registers `executor` to execute the opcodes in `opcodes`, without adding an extension to
+    /// the config. Fails if any of the opcodes already has an executor.
+    ///
+    /// Intended for prototyping: the executor has no AIR, so executions that use it can only be
+    /// run with [Self::execute] and similar methods, not proven.
+    pub fn register_executor(
+        &mut self,
+        opcodes: Range<usize>,
+        executor: Box<dyn InstructionExecutor<F> + Send>,
+    ) -> Result<(), VmInventoryError> {
+        let chip_complex = self.config.create_chip_complex()?;
+        if let Some(opcode) = opcodes
+            .clone()
+            .map(VmOpcode::from_usize)
+            .find(|opcode| chip_complex.inventory.get_executor(*opcode).is_some())
+        {
+            return Err(VmInventoryError::OpcodeExists { opcode });
+        }
+        self.dynamic_executors
+            .register(opcodes, Arc::new(Mutex::new(executor)))
+    }
+
     pub fn continuation_enabled(&self) -> bool {
         self.config.system().continuation_enabled
     }
 
-    pub fn execute_segments(
+    /// Returns the checkpoint at the start of `exe`, to execute it segment by segment with
+    /// [Self::execute_segment].
+    pub fn initial_checkpoint(
         &self,
-        exe: impl Into<VmExe<F>>,
+        exe: &VmExe<F>,
         input: impl Into<Streams<F>>,
-    ) -> Result<Vec<ExecutionSegment<F, VC>>, ExecutionError> {
-        #[cfg(feature = "bench-metrics")]
-        let start = std::time::Instant::now();
+    ) -> VmCheckpoint<F> {
+        self.initial_checkpoint_impl(exe.init_memory.clone(), exe.pc_start, input.into())
+    }
 
-        let exe = exe.into();
-        let streams = input.into();
-        let mut segments = vec![];
+    fn initial_checkpoint_impl(
+        &self,
+        init_memory: MemoryImage<F>,
+        pc_start: u32,
+        streams: Streams<F>,
+    ) -> VmCheckpoint<F> {
+        // The first segment hashes its initial memory on the fly, see `execute_segment_impl`.
+        VmCheckpoint {
+            segment_idx: 0,
+            pc: pc_start,
+            memory: Arc::new(memory_image_to_equipartition(init_memory)),
+            streams,
+            budget_usage: ExecutionBudgetUsage::default(),
+            carry_over: vec![F::ZERO; self.config.system().num_carry_over()],
+            memory_tree: None,
+            cycle_tracker: CycleTracker::new(),
+            trace_events: None,
+        }
+    }
+
+    /// Executes the segment of `exe` starting at `checkpoint`. Returns the executed segment and,
+    /// unless the program terminated, the checkpoint of the next segment.
+    pub fn execute_segment(
+        &self,
+        exe: &VmExe<F>,
+        checkpoint: VmCheckpoint<F>,
+    ) -> Result<(ExecutionSegment<F, VC>, Option<VmCheckpoint<F>>), ExecutionError> {
+        self.execute_segment_impl(exe, checkpoint, false)
+    }
+
+    fn execute_segment_impl(
+        &self,
+        exe: &VmExe<F>,
+        checkpoint: VmCheckpoint<F>,
+        execute_only: bool,
+    ) -> Result<(ExecutionSegment<F, VC>, Option<VmCheckpoint<F>>), ExecutionError> {
+        let VmCheckpoint {
+            segment_idx,
+            pc,
+            memory,
+            streams,
+            budget_usage,
+            carry_over,
+            memory_tree,
+            cycle_tracker,
+            trace_events,
+        } = checkpoint;
         let mut segment = ExecutionSegment::new(
             &self.config,
             exe.program.clone(),
             streams,
-            Some(memory_image_to_equipartition(exe.init_memory)),
+            Some(memory),
             exe.fn_bounds.clone(),
         );
+        segment.segment_idx = segment_idx;
+        segment.budget_usage = budget_usage;
+        segment.chip_complex.carry_over().set_values(carry_over);
+        segment.cycle_tracker = cycle_tracker;
+        if trace_events.is_some() {
+            segment.trace_events = trace_events;
+        }
+        // Without a tree, e.g. after deserialization, the initial memory is rehashed from scratch.
+        if let Some(memory_tree) = memory_tree {
+            segment
+                .chip_complex
+                .memory_controller()
+                .borrow_mut()
+                .set_initial_memory_tree(memory_tree);
+        }
         if let Some(overridden_heights) = self.overridden_heights.as_ref() {
             segment.set_override_trace_heights(overridden_heights.clone());
         }
-        let mut pc = exe.pc_start;
+        segment.set_dynamic_executors(self.dynamic_executors.clone());
+        if let Some(debugger) = &self.debugger {
+            segment.set_debugger(debugger.clone());
+        }
+        if let Some((interval, hook)) = &self.inspection_hook {
+            segment.set_inspection_hook(*interval, hook.clone());
+        }
+        if let Some(guest_logger) = &self.guest_logger {
+            segment.set_guest_logger(guest_logger.clone());
+        }
+        if execute_only {
+            segment.set_execute_only();
+        }
+        if self.basic_block_execution {
+            segment.set_basic_block_execution();
+        }
 
-        loop {
-            let state = tracing::info_span!("execute_segment", segment = segments.len())
-                .in_scope(|| segment.execute_from_pc(pc))?;
-            pc = state.pc;
+        let state = tracing::info_span!("execute_segment", segment = segment_idx)
+            .in_scope(|| segment.execute_from_pc(pc))
+            .inspect_err(|err| log_error_source(&exe.source_lines, err))?;
+        if state.is_terminated {
+            return Ok((segment, None));
+        }
 
-            if state.is_terminated {
-                break;
-            }
+        assert!(
+            self.continuation_enabled(),
+            "multiple segments require to enable continuations"
+        );
 
-            assert!(
-                self.continuation_enabled(),
-                "multiple segments require to enable continuations"
-            );
+        assert_eq!(
+            state.pc,
+            segment.chip_complex.connector_chip().boundary_states[1]
+                .unwrap()
+                .pc
+        );
 
-            assert_eq!(
-                pc,
-                segment.chip_complex.connector_chip().boundary_states[1]
-                    .unwrap()
-                    .pc
-            );
+        let memory = mem::take(&mut segment.final_memory)
+            .expect("final memory should be set in continuations segment");
+        // The memory tree is carried over between segments so that each segment only rehashes
+        // the paths to the chunks it touched. A segment without an initial tree does not build
+        // one, so the tree is only built here, once the execution needs another segment.
+        let memory_tree = (!execute_only).then(|| {
+            segment
+                .chip_complex
+                .memory_controller()
+                .borrow_mut()
+                .take_final_memory_tree()
+                .unwrap_or_else(|| {
+                    MemoryNode::tree_from_memory(
+                        self.config.system().memory_config.memory_dimensions(),
+                        &memory,
+                        &vm_poseidon2_hasher(),
+                    )
+                })
+        });
+        let next = VmCheckpoint {
+            segment_idx: segment_idx + 1,
+            pc: state.pc,
+            memory,
+            streams: segment.chip_complex.take_streams(),
+            budget_usage: segment.budget_usage,
+            carry_over: segment.chip_complex.carry_over().values(),
+            memory_tree,
+            cycle_tracker: mem::take(&mut segment.cycle_tracker),
+            trace_events: segment.trace_events.take(),
+        };
+        Ok((segment, Some(next)))
+    }
 
-            let cycle_tracker = mem::take(&mut segment.cycle_tracker);
-            let final_memory = mem::take(&mut segment.final_memory)
-                .expect("final memory should be set in continuations segment");
-            let streams = segment.chip_complex.take_streams();
+    pub fn execute_segments(
+        &self,
+        exe: impl Into<VmExe<F>>,
+        input: impl Into<Streams<F>>,
+    ) -> Result<Vec<ExecutionSegment<F, VC>>, ExecutionError> {
+        #[cfg(feature = "bench-metrics")]
+        let start = std::time::Instant::now();
 
+        let mut exe = exe.into();
+        let mut checkpoint = Some(self.initial_checkpoint_impl(
+            mem::take(&mut exe.init_memory),
+            exe.pc_start,
+            input.into(),
+        ));
+        let mut segments = vec![];
+        while let Some(current) = checkpoint {
+            let (segment, next) = self.execute_segment(&exe, current)?;
             segments.push(segment);
-
-            segment = ExecutionSegment::new(
-                &self.config,
-                exe.program.clone(),
-                streams,
-                Some(final_memory),
-                exe.fn_bounds.clone(),
-            );
-            if let Some(overridden_heights) = self.overridden_heights.as_ref() {
-                segment.set_override_trace_heights(overridden_heights.clone());
-            }
-            segment.cycle_tracker = cycle_tracker;
+            checkpoint = next;
         }
-        segments.push(segment);
         tracing::debug!("Number of continuation segments: {}", segments.len());
         #[cfg(feature = "bench-metrics")]
         metrics::gauge!("execute_time_ms").set(start.elapsed().as_millis() as f64);
@@ -176,24 +770,116 @@ where
         Ok(segments)
     }
 
-    pub fn execute(
+    /// Executes `exe` only to compute its results, e.g. its outputs, the hints left in its
+    /// streams or its number of segments.
+    ///
+    /// Faster than [Self::execute_segments]: no metrics or trace events are collected, memory is
+    /// neither finalized nor hashed, and each segment is dropped when it ends instead of being
+    /// kept for trace generation. Chips still keep records within a segment, since their trace
+    /// heights decide where segments end.
+    pub fn execute_only(
         &self,
         exe: impl Into<VmExe<F>>,
         input: impl Into<Streams<F>>,
-    ) -> Result<Option<VmMemoryState<F>>, ExecutionError> {
-        let mut results = self.execute_segments(exe, input)?;
-        let last = results.last_mut().unwrap();
-        let final_memory = mem::take(&mut last.final_memory);
+    ) -> Result<ExecuteOnlyResult<F>, ExecutionError> {
+        let mut exe = exe.into();
+        let mut checkpoint = self.initial_checkpoint_impl(
+            mem::take(&mut exe.init_memory),
+            exe.pc_start,
+            input.into(),
+        );
+        loop {
+            let (mut segment, next) = self.execute_segment_impl(&exe, checkpoint, true)?;
+            if let Some(next) = next {
+                checkpoint = next;
+                continue;
+            }
+            let end_state = segment.chip_complex.connector_chip().boundary_states[1]
+                .expect("end state must be set");
+            return Ok(ExecuteOnlyResult {
+                final_memory: segment
+                    .final_memory
+                    .take()
+                    .expect("final memory is set in execute-only mode"),
+                exit_code: end_state.exit_code,
+                num_segments: segment.segment_idx + 1,
+                cycles: segment.budget_usage.cycles,
+                streams: segment.chip_complex.take_streams(),
+            });
+        }
+    }
+
+    /// Executes `exe` only to record its [ExecutionLog]. Passing the log to
+    /// [Streams::replay] as the input of this executor, e.g. on another machine, reproduces the
+    /// run without computing any hints.
+    pub fn record_execution_log(
+        &self,
+        exe: impl Into<VmExe<F>>,
+        input: impl Into<Streams<F>>,
+    ) -> Result<ExecutionLog<F>, ExecutionError> {
+        let input = input.into().with_execution_log();
+        let mut result = self.execute_only(exe, input)?;
+        Ok(result
+            .streams
+            .take_execution_log()
+            .expect("execution log is recorded"))
+    }
+
+    /// Executes `exe` until it terminates and returns how it exited. Unlike [Self::execute], a
+    /// non-zero exit code, e.g. from a guest panic, is not an error.
+    pub fn execute_with_result(
+        &self,
+        exe: impl Into<VmExe<F>>,
+        input: impl Into<Streams<F>>,
+    ) -> Result<ExecutionResult<F>, ExecutionError> {
+        let mut segments = self.execute_segments(exe, input)?;
+        let last = segments.last_mut().unwrap();
         let end_state =
             last.chip_complex.connector_chip().boundary_states[1].expect("end state must be set");
-        // TODO[jpw]: add these as execution errors
         assert_eq!(end_state.is_terminate, 1, "program must terminate");
-        assert_eq!(
-            end_state.exit_code,
-            ExitCode::Success as u32,
-            "program did not exit successfully"
+        let final_memory = mem::take(&mut last.final_memory);
+        let public_values = match &final_memory {
+            Some(final_memory) => {
+                let system_config = self.config.system();
+                let address_space =
+                    system_config.memory_config.as_offset + PUBLIC_VALUES_ADDRESS_SPACE_OFFSET;
+                (0..system_config.num_public_values as u32)
+                    .map(|i| {
+                        final_memory
+                            .get(&(address_space, i / CHUNK as u32))
+                            .map(|chunk| chunk[i as usize % CHUNK])
+                    })
+                    .collect()
+            }
+            None => last
+                .chip_complex
+                .public_values_chip()
+                .map(|pv_chip| pv_chip.core.get_custom_public_values())
+                .unwrap_or_default(),
+        };
+        Ok(ExecutionResult {
+            exit_code: end_state.exit_code,
+            exit_message: last.exit_message.take(),
+            public_values,
+            final_memory,
+            stream_offsets: last.chip_complex.take_streams().read_offsets(),
+        })
+    }
+
+    pub fn execute(
+        &self,
+        exe: impl Into<VmExe<F>>,
+        input: impl Into<Streams<F>>,
+    ) -> Result<Option<VmMemoryState<F>>, ExecutionError> {
+        let result = self.execute_with_result(exe, input)?;
+        // TODO[jpw]: add these as execution errors
+        assert!(
+            result.is_success(),
+            "program did not exit successfully: exit code {}, message {:?}",
+            result.exit_code,
+            result.exit_message
         );
-        Ok(final_memory)
+        Ok(result.final_memory)
     }
 
     pub fn execute_and_generate<SC: StarkGenericConfig>(
@@ -356,7 +1042,9 @@ where
         if let Some(overridden_heights) = self.overridden_heights.as_ref() {
             segment.set_override_trace_heights(overridden_heights.clone());
         }
-        segment.execute_from_pc(pc_start)?;
+        segment
+            .execute_from_pc(pc_start)
+            .inspect_err(|err| log_error_source(&exe.source_lines, err))?;
 
         #[cfg(feature = "bench-metrics")]
         metrics::gauge!("execute_time_ms").set(start.elapsed().as_millis() as f64);
@@ -375,6 +1063,30 @@ pub enum VmVerificationError {
     #[error("initial memory root mismatch")]
     InitialMemoryRootMismatch,
 
+    #[error("no segment proofs")]
+    NoSegmentProofs,
+
+    #[error("entry pc mismatch (expected: {expected}, actual: {actual})")]
+    EntryPcMismatch { expected: u32, actual: u32 },
+
+    #[error("initial memory root of the first segment does not match the executable")]
+    EntryMemoryMismatch,
+
+    #[error("memory region proof does not match the final memory root")]
+    MemoryRegionMismatch,
+
+    #[error("program commitment of segment {segment} does not match the executable")]
+    ProgramCommitMismatch { segment: usize },
+
+    #[error("initial carry-over value {index} mismatch")]
+    InitialCarryOverMismatch { index: usize },
+
+    #[error("initial carry-over value {index} of the first segment is not zero")]
+    NonZeroInitialCarryOver { index: usize },
+
+    #[error("key-value store root mismatch between segments")]
+    KvStoreRootMismatch,
+
     #[error("is terminate mismatch (expected: {expected}, actual: {actual})")]
     IsTerminateMismatch { expected: bool, actual: bool },
 
@@ -521,7 +1233,7 @@ where
         Val<SC>: PrimeField32,
     {
         if self.config().system().continuation_enabled {
-            self.verify_segments(vk, proofs)
+            self.verify_segments(vk, proofs).map(|_| ())
         } else {
             assert_eq!(proofs.len(), 1);
             self.verify_single(vk, &proofs.into_iter().next().unwrap())
@@ -529,17 +1241,122 @@ where
         }
     }
 
-    /// Verify segment proofs with boundary condition checks for continuation between segments
-    fn verify_segments(
+    /// Like [Self::verify], and also checks that the proofs are of `exe`: every segment ran its
+    /// committed program, and the execution started at its `pc_start` and, with continuations,
+    /// from its initial memory.
+    pub fn verify_exe(
         &self,
         vk: &MultiStarkVerifyingKey<SC>,
         proofs: Vec<Proof<SC>>,
+        exe: &VmCommittedExe<SC>,
     ) -> Result<(), VmVerificationError>
+    where
+        Com<SC>: PartialEq,
+    {
+        let program_commit = exe.get_program_commit();
+        for (segment, proof) in proofs.iter().enumerate() {
+            if proof.commitments.main_trace[PROGRAM_CACHED_TRACE_INDEX] != program_commit {
+                return Err(VmVerificationError::ProgramCommitMismatch { segment });
+            }
+        }
+        let exe = &exe.exe;
+        let first = proofs.first().ok_or(VmVerificationError::NoSegmentProofs)?;
+        for air_proof_data in &first.per_air {
+            let pvs = &air_proof_data.public_values;
+            if air_proof_data.air_id == CONNECTOR_AIR_ID {
+                let pvs: &VmConnectorPvs<_> = pvs[..VmConnectorPvs::<u8>::width()].borrow();
+                if pvs.initial_pc != F::from_canonical_u32(exe.pc_start) {
+                    return Err(VmVerificationError::EntryPcMismatch {
+                        expected: exe.pc_start,
+                        actual: pvs.initial_pc.as_canonical_u32(),
+                    });
+                }
+            } else if self.config().system().continuation_enabled
+                && air_proof_data.air_id == MERKLE_AIR_ID
+            {
+                let pvs: &MemoryMerklePvs<_, CHUNK> = pvs.as_slice().borrow();
+                let initial_root = MemoryNode::tree_from_memory(
+                    self.config().system().memory_config.memory_dimensions(),
+                    &memory_image_to_equipartition(exe.init_memory.clone()),
+                    &vm_poseidon2_hasher(),
+                )
+                .hash();
+                if pvs.initial_root != initial_root {
+                    return Err(VmVerificationError::EntryMemoryMismatch);
+                }
+            }
+        }
+        self.verify(vk, proofs)
+    }
+
+    /// Checks that `region` holds the final contents of its memory region, against the final
+    /// memory root that the last segment exposes as a public value of the Merkle AIR. This is how
+    /// a verifier trusts an output region that the guest left in memory instead of copying it into
+    /// the public values address space. The proofs themselves must be checked with [Self::verify]
+    /// or [Self::verify_exe].
+    pub fn verify_region(
+        &self,
+        proofs: &[Proof<SC>],
+        region: &MemoryRegionProof<CHUNK, F>,
+    ) -> Result<(), VmVerificationError> {
+        assert!(
+            self.config().system().continuation_enabled,
+            "memory region proofs require continuations"
+        );
+        let last = proofs.last().ok_or(VmVerificationError::NoSegmentProofs)?;
+        let final_root = last
+            .per_air
+            .iter()
+            .find(|air_proof_data| air_proof_data.air_id == MERKLE_AIR_ID)
+            .map(|air_proof_data| {
+                let pvs: &MemoryMerklePvs<_, CHUNK> =
+                    air_proof_data.public_values.as_slice().borrow();
+                pvs.final_root
+            })
+            .ok_or(VmVerificationError::MemoryRegionMismatch)?;
+        let memory_dimensions = self.config().system().memory_config.memory_dimensions();
+        if !region.verify(memory_dimensions, &final_root, &vm_poseidon2_hasher()) {
+            return Err(VmVerificationError::MemoryRegionMismatch);
+        }
+        Ok(())
+    }
+
+    /// Like [Self::verify] with continuations, and also returns the root of the
+    /// [KvStore](crate::system::kv_store::KvStore) that the lookups of all segments were proven
+    /// against, or `None` if no segment looked a key up. The caller must check the root against
+    /// its own commitment to the store.
+    pub fn verify_with_kv_store_root(
+        &self,
+        vk: &MultiStarkVerifyingKey<SC>,
+        proofs: Vec<Proof<SC>>,
+    ) -> Result<Option<Vec<Val<SC>>>, VmVerificationError>
+    where
+        Val<SC>: PrimeField32,
+    {
+        assert!(
+            self.config().system().continuation_enabled,
+            "the key-value store requires continuations"
+        );
+        self.verify_segments(vk, proofs)
+    }
+
+    /// Verify segment proofs with boundary condition checks for continuation between segments.
+    /// Returns the root exposed by the key-value store AIR, if any segment has a trace for it.
+    fn verify_segments(
+        &self,
+        vk: &MultiStarkVerifyingKey<SC>,
+        proofs: Vec<Proof<SC>>,
+    ) -> Result<Option<Vec<Val<SC>>>, VmVerificationError>
     where
         Val<SC>: PrimeField32,
     {
         let mut prev_final_memory_root = None;
         let mut prev_final_pc = None;
+        let mut prev_final_carry_over = vec![];
+        let kv_store_air_id = self.config().system().kv_store_air_id(vk.per_air.len());
+        // The key-value store AIR exposes the root of the store. Segments without lookups have no
+        // trace for it, or expose a zero root.
+        let mut kv_store_root: Option<Vec<Val<SC>>> = None;
 
         for (i, proof) in proofs.iter().enumerate() {
             let res = self.engine.verify(vk, proof);
@@ -554,7 +1371,10 @@ where
                 let air_vk = &vk.per_air[air_proof_data.air_id];
 
                 if air_proof_data.air_id == CONNECTOR_AIR_ID {
-                    let pvs: &VmConnectorPvs<_> = pvs.as_slice().borrow();
+                    let (pvs, carry_over) = pvs.split_at(VmConnectorPvs::<u8>::width());
+                    let pvs: &VmConnectorPvs<_> = pvs.borrow();
+                    let (initial_carry_over, final_carry_over) =
+                        carry_over.split_at(carry_over.len() / 2);
 
                     if i != 0 {
                         // Check initial pc matches the previous final pc.
@@ -564,10 +1384,21 @@ where
                                 prev_final: prev_final_pc.unwrap().as_canonical_u32(),
                             });
                         }
+                        // Check initial carry-over values match the previous final ones.
+                        if let Some(index) = (0..initial_carry_over.len())
+                            .find(|&j| initial_carry_over[j] != prev_final_carry_over[j])
+                        {
+                            return Err(VmVerificationError::InitialCarryOverMismatch { index });
+                        }
                     } else {
                         // TODO: Fetch initial pc from program
+                        // Carry-over values start at zero.
+                        if let Some(index) = initial_carry_over.iter().position(|v| !v.is_zero()) {
+                            return Err(VmVerificationError::NonZeroInitialCarryOver { index });
+                        }
                     }
                     prev_final_pc = Some(pvs.final_pc);
+                    prev_final_carry_over = final_carry_over.to_vec();
 
                     let expected_is_terminate = i == proofs.len() - 1;
                     if pvs.is_terminate != Val::<SC>::from_bool(expected_is_terminate) {
@@ -596,6 +1427,12 @@ where
                         return Err(VmVerificationError::InitialMemoryRootMismatch);
                     }
                     prev_final_memory_root = Some(pvs.final_root);
+                } else if Some(air_proof_data.air_id) == kv_store_air_id {
+                    if pvs.iter().any(|value| !value.is_zero())
+                        && kv_store_root.get_or_insert_with(|| pvs.clone()) != pvs
+                    {
+                        return Err(VmVerificationError::KvStoreRootMismatch);
+                    }
                 } else {
                     if !pvs.is_empty() {
                         return Err(VmVerificationError::UnexpectedPvs {
@@ -612,6 +1449,6 @@ where
                 }
             }
         }
-        Ok(())
+        Ok(kv_store_root)
     }
 }
@@ -0,0 +1,66 @@
+use serde::{Deserialize, Serialize};
+
+use super::VmMetrics;
+
+/// Access adapter overhead of one opcode, see [VmMetrics::adapter_overhead].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AdapterOverheadEntry {
+    pub dsl_ir: Option<String>,
+    pub opcode: String,
+    pub frequency: usize,
+    pub access_adapter_rows: usize,
+    /// Access adapter rows generated per execution of the opcode.
+    pub rows_per_execution: f64,
+}
+
+impl VmMetrics {
+    /// Returns the opcodes that generated more than `threshold` access adapter rows per
+    /// execution, most expensive first.
+    ///
+    /// Adapter rows come from splitting or merging memory blocks when an access uses a different
+    /// block size than the previous access to the same cells, so a high overhead usually points
+    /// to a chip whose block size does not match that of its neighbors.
+    pub fn adapter_overhead(&self, threshold: f64) -> Vec<AdapterOverheadEntry> {
+        let mut entries: Vec<_> = self
+            .access_adapter_rows
+            .iter()
+            .filter_map(|(key, &access_adapter_rows)| {
+                let frequency = self.counts.get(key).copied().unwrap_or_default();
+                let rows_per_execution = access_adapter_rows as f64 / frequency.max(1) as f64;
+                (rows_per_execution > threshold).then(|| AdapterOverheadEntry {
+                    dsl_ir: key.0.clone(),
+                    opcode: key.1.clone(),
+                    frequency,
+                    access_adapter_rows,
+                    rows_per_execution,
+                })
+            })
+            .collect();
+        entries.sort_by(|a, b| b.rows_per_execution.total_cmp(&a.rows_per_execution));
+        entries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::metrics::VmMetrics;
+
+    #[test]
+    fn test_adapter_overhead() {
+        let mut metrics = VmMetrics::default();
+        for (opcode, frequency, rows) in [("LOADW", 10, 5), ("HINT", 2, 8), ("ADD", 4, 30)] {
+            let key = (None, opcode.to_string());
+            metrics.counts.insert(key.clone(), frequency);
+            metrics.access_adapter_rows.insert(key, rows);
+        }
+
+        let entries = metrics.adapter_overhead(1.0);
+        assert_eq!(
+            entries
+                .iter()
+                .map(|e| (e.opcode.as_str(), e.rows_per_execution))
+                .collect::<Vec<_>>(),
+            vec![("ADD", 7.5), ("HINT", 4.0)]
+        );
+    }
+}
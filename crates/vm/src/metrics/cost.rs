@@ -0,0 +1,99 @@
+use serde::{Deserialize, Serialize};
+
+use super::VmMetrics;
+
+/// Estimated cost of proving one segment.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProverCostEstimate {
+    pub proving_time_ms: f64,
+    pub peak_memory_bytes: usize,
+}
+
+/// Converts the trace sizes of one segment into an estimated proving cost, to compare guest
+/// programs without proving them.
+pub trait ProverCostModel {
+    fn estimate(&self, metrics: &VmMetrics) -> ProverCostEstimate;
+}
+
+/// Cost model of the BabyBear FRI backend, linear in the number of cells of the low-degree
+/// extensions of the traces.
+///
+/// The defaults are rough figures for a multi-core machine and the default FRI parameters;
+/// measure a few segments on the target hardware and adjust the constants to get comparable
+/// absolute numbers. Relative comparisons between guest programs do not depend on them.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FriCostModel {
+    pub log_blowup: usize,
+    /// Proving time per cell of the low-degree extension of a main trace.
+    pub ns_per_lde_cell: f64,
+    /// Time independent of the trace sizes.
+    pub overhead_ms: f64,
+    /// Peak memory per cell of the low-degree extension of a main trace. Accounts for the
+    /// after-challenge traces and the quotient, which are over the extension field.
+    pub bytes_per_lde_cell: f64,
+}
+
+impl Default for FriCostModel {
+    fn default() -> Self {
+        Self {
+            log_blowup: 1,
+            ns_per_lde_cell: 10.0,
+            overhead_ms: 100.0,
+            bytes_per_lde_cell: 24.0,
+        }
+    }
+}
+
+impl FriCostModel {
+    /// Number of cells of the low-degree extensions of the main traces, with each trace padded to
+    /// a power-of-two height.
+    pub fn lde_cells(&self, metrics: &VmMetrics) -> usize {
+        metrics
+            .chip_heights
+            .iter()
+            .zip(&metrics.chip_cells)
+            .filter(|((_, height), _)| *height > 0)
+            .map(|((_, height), (_, cells))| {
+                let width = cells / height;
+                width * height.next_power_of_two()
+            })
+            .sum::<usize>()
+            << self.log_blowup
+    }
+}
+
+impl ProverCostModel for FriCostModel {
+    fn estimate(&self, metrics: &VmMetrics) -> ProverCostEstimate {
+        let lde_cells = self.lde_cells(metrics);
+        ProverCostEstimate {
+            proving_time_ms: self.overhead_ms + lde_cells as f64 * self.ns_per_lde_cell / 1e6,
+            peak_memory_bytes: (lde_cells as f64 * self.bytes_per_lde_cell) as usize,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FriCostModel, ProverCostModel};
+    use crate::metrics::VmMetrics;
+
+    #[test]
+    fn test_fri_cost_model() {
+        let metrics = VmMetrics {
+            chip_heights: vec![("A".to_string(), 3), ("B".to_string(), 0)],
+            chip_cells: vec![("A".to_string(), 30), ("B".to_string(), 0)],
+            ..Default::default()
+        };
+        let model = FriCostModel {
+            log_blowup: 2,
+            ns_per_lde_cell: 1e3,
+            overhead_ms: 1.0,
+            bytes_per_lde_cell: 2.0,
+        };
+        // Width 10, padded to height 4, blown up by 4.
+        assert_eq!(model.lde_cells(&metrics), 160);
+        let estimate = model.estimate(&metrics);
+        assert!((estimate.proving_time_ms - 1.16).abs() < 1e-9);
+        assert_eq!(estimate.peak_memory_bytes, 320);
+    }
+}
@@ -1,7 +1,32 @@
+use std::{
+    collections::BTreeMap,
+    io::{self, Write},
+};
+
+use serde::{Deserialize, Serialize};
+
+/// Totals of one span of the [CycleTracker], over all times it was entered with the same parent
+/// spans. Inclusive totals include nested spans, exclusive totals do not.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpanStats {
+    /// Number of times the span was started.
+    pub calls: usize,
+    pub inclusive_cycles: usize,
+    pub exclusive_cycles: usize,
+    pub inclusive_cells: usize,
+    pub exclusive_cells: usize,
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct CycleTracker {
     /// Stack of span names, with most recent at the end
     stack: Vec<String>,
+    /// Maps folded stack, with the opcode as the innermost frame, to number of cycles
+    folded_cycles: BTreeMap<String, usize>,
+    /// Maps folded stack, with the opcode as the innermost frame, to number of trace cells used
+    folded_cells: BTreeMap<String, usize>,
+    /// Maps full name of span to its totals
+    spans: BTreeMap<String, SpanStats>,
 }
 
 impl CycleTracker {
@@ -13,6 +38,7 @@ impl CycleTracker {
     /// If a span already exists for the given name, it ends the existing span and pushes a new one to the vec.
     pub fn start(&mut self, name: String) {
         self.stack.push(name);
+        self.spans.entry(self.get_full_name()).or_default().calls += 1;
     }
 
     /// Ends the cycle tracker span for the given name.
@@ -31,6 +57,77 @@ impl CycleTracker {
     pub fn get_full_name(&self) -> String {
         self.stack.join(";")
     }
+
+    /// Adds `cycles` to the current stack with `opcode` as the innermost frame.
+    pub fn record_cycles(&mut self, opcode: &str, cycles: usize) {
+        let key = self.folded_key(opcode);
+        *self.folded_cycles.entry(key).or_insert(0) += cycles;
+        self.attribute(|stats, exclusive| {
+            stats.inclusive_cycles += cycles;
+            if exclusive {
+                stats.exclusive_cycles += cycles;
+            }
+        });
+    }
+
+    /// Adds `cells` to the current stack with `opcode` as the innermost frame.
+    pub fn record_cells(&mut self, opcode: &str, cells: usize) {
+        let key = self.folded_key(opcode);
+        *self.folded_cells.entry(key).or_insert(0) += cells;
+        self.attribute(|stats, exclusive| {
+            stats.inclusive_cells += cells;
+            if exclusive {
+                stats.exclusive_cells += cells;
+            }
+        });
+    }
+
+    /// Returns the totals of every span entered so far, keyed by full name. See
+    /// [Self::get_full_name].
+    pub fn span_stats(&self) -> &BTreeMap<String, SpanStats> {
+        &self.spans
+    }
+
+    /// Calls `f` on the totals of every span on the stack, with `exclusive` set for the innermost
+    /// one.
+    fn attribute(&mut self, mut f: impl FnMut(&mut SpanStats, bool)) {
+        let mut full_name = String::new();
+        for (depth, name) in self.stack.iter().enumerate() {
+            if depth > 0 {
+                full_name.push(';');
+            }
+            full_name.push_str(name);
+            let stats = self.spans.entry(full_name.clone()).or_default();
+            f(stats, depth + 1 == self.stack.len());
+        }
+    }
+
+    /// Writes the recorded cycles in folded-stack format, one `stack weight` line per stack,
+    /// as consumed by `inferno-flamegraph` and `flamegraph.pl`.
+    pub fn write_folded_cycles(&self, writer: impl Write) -> io::Result<()> {
+        write_folded(&self.folded_cycles, writer)
+    }
+
+    /// Writes the recorded trace cells in folded-stack format. See [Self::write_folded_cycles].
+    pub fn write_folded_cells(&self, writer: impl Write) -> io::Result<()> {
+        write_folded(&self.folded_cells, writer)
+    }
+
+    fn folded_key(&self, opcode: &str) -> String {
+        self.stack
+            .iter()
+            .map(String::as_str)
+            .chain([opcode])
+            .collect::<Vec<_>>()
+            .join(";")
+    }
+}
+
+fn write_folded(stacks: &BTreeMap<String, usize>, mut writer: impl Write) -> io::Result<()> {
+    for (stack, weight) in stacks {
+        writeln!(writer, "{stack} {weight}")?;
+    }
+    Ok(())
 }
 
 #[cfg(feature = "bench-metrics")]
@@ -40,7 +137,20 @@ mod emit {
     use super::CycleTracker;
 
     impl CycleTracker {
-        pub fn increment_opcode(&self, (dsl_ir, opcode): &(Option<String>, String)) {
+        /// Emits the totals of every span as counters labeled with the full span name.
+        pub fn emit_span_stats(&self) {
+            for (span, stats) in self.span_stats() {
+                let labels = [("cycle_tracker_span", span.clone())];
+                counter!("span_calls", &labels).absolute(stats.calls as u64);
+                counter!("span_inclusive_cycles", &labels).absolute(stats.inclusive_cycles as u64);
+                counter!("span_exclusive_cycles", &labels).absolute(stats.exclusive_cycles as u64);
+                counter!("span_inclusive_cells", &labels).absolute(stats.inclusive_cells as u64);
+                counter!("span_exclusive_cells", &labels).absolute(stats.exclusive_cells as u64);
+            }
+        }
+
+        pub fn increment_opcode(&mut self, (dsl_ir, opcode): &(Option<String>, String)) {
+            self.record_cycles(opcode, 1);
             let labels = [
                 ("opcode", opcode.clone()),
                 ("dsl_ir", dsl_ir.clone().unwrap_or_default()),
@@ -50,13 +160,14 @@ mod emit {
         }
 
         pub fn increment_cells_used(
-            &self,
+            &mut self,
             (dsl_ir, opcode, air_name): &(Option<String>, String, String),
             trace_cells_used: usize,
         ) {
             if trace_cells_used == 0 {
                 return;
             }
+            self.record_cells(opcode, trace_cells_used);
             let labels = [
                 ("air_name", air_name.clone()),
                 ("opcode", opcode.clone()),
@@ -67,3 +178,58 @@ mod emit {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::CycleTracker;
+
+    #[test]
+    fn test_folded_stacks() {
+        let mut tracker = CycleTracker::new();
+        tracker.record_cycles("ADD", 1);
+        tracker.start("main".to_string());
+        tracker.record_cycles("ADD", 2);
+        tracker.start("hash".to_string());
+        tracker.record_cycles("KECCAK", 5);
+        tracker.record_cells("KECCAK", 100);
+        tracker.end("hash".to_string());
+        tracker.record_cycles("ADD", 1);
+
+        let mut cycles = Vec::new();
+        tracker.write_folded_cycles(&mut cycles).unwrap();
+        assert_eq!(
+            String::from_utf8(cycles).unwrap(),
+            "ADD 1\nmain;ADD 3\nmain;hash;KECCAK 5\n"
+        );
+
+        let mut cells = Vec::new();
+        tracker.write_folded_cells(&mut cells).unwrap();
+        assert_eq!(String::from_utf8(cells).unwrap(), "main;hash;KECCAK 100\n");
+    }
+
+    #[test]
+    fn test_nested_span_stats() {
+        let mut tracker = CycleTracker::new();
+        tracker.start("main".to_string());
+        tracker.record_cycles("ADD", 2);
+        for _ in 0..2 {
+            tracker.start("hash".to_string());
+            tracker.record_cycles("KECCAK", 5);
+            tracker.record_cells("KECCAK", 100);
+            tracker.end("hash".to_string());
+        }
+        tracker.record_cells("ADD", 10);
+        tracker.end("main".to_string());
+        tracker.record_cycles("ADD", 1);
+
+        let main = tracker.span_stats()["main"];
+        assert_eq!(main.calls, 1);
+        assert_eq!((main.inclusive_cycles, main.exclusive_cycles), (12, 2));
+        assert_eq!((main.inclusive_cells, main.exclusive_cells), (210, 10));
+        let hash = tracker.span_stats()["main;hash"];
+        assert_eq!(hash.calls, 2);
+        assert_eq!((hash.inclusive_cycles, hash.exclusive_cycles), (10, 10));
+        assert_eq!((hash.inclusive_cells, hash.exclusive_cells), (200, 200));
+        assert_eq!(tracker.span_stats().len(), 2);
+    }
+}
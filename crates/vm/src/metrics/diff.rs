@@ -0,0 +1,222 @@
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Write},
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::VmMetrics;
+
+/// A value of a baseline run and of the current run.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MetricDelta {
+    pub baseline: usize,
+    pub current: usize,
+}
+
+impl MetricDelta {
+    pub fn delta(&self) -> i64 {
+        self.current as i64 - self.baseline as i64
+    }
+
+    /// Change relative to the baseline, in percent. `None` if the baseline is zero.
+    pub fn percent_change(&self) -> Option<f64> {
+        (self.baseline != 0).then(|| self.delta() as f64 * 100.0 / self.baseline as f64)
+    }
+
+    pub fn is_changed(&self) -> bool {
+        self.baseline != self.current
+    }
+}
+
+/// Comparison of two [VmMetrics], as returned by [VmMetrics::diff]. Every key present in either
+/// run is included, with missing values counted as zero.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MetricsDiff {
+    /// Keyed by chip name.
+    pub chip_heights: BTreeMap<String, MetricDelta>,
+    /// Keyed by chip name.
+    pub chip_cells: BTreeMap<String, MetricDelta>,
+    /// Execution counts, keyed by (dsl_ir, opcode).
+    pub opcodes: BTreeMap<(Option<String>, String), MetricDelta>,
+    /// Trace cells, keyed by (dsl_ir, opcode, air_name).
+    pub trace_cells: BTreeMap<(Option<String>, String, String), MetricDelta>,
+}
+
+impl VmMetrics {
+    /// Compares these metrics against those of a `baseline` run.
+    pub fn diff(&self, baseline: &Self) -> MetricsDiff {
+        MetricsDiff {
+            chip_heights: diff_maps(
+                baseline.chip_heights.iter().cloned().collect(),
+                self.chip_heights.iter().cloned().collect(),
+            ),
+            chip_cells: diff_maps(
+                baseline.chip_cells.iter().cloned().collect(),
+                self.chip_cells.iter().cloned().collect(),
+            ),
+            opcodes: diff_maps(baseline.counts.clone(), self.counts.clone()),
+            trace_cells: diff_maps(baseline.trace_cells.clone(), self.trace_cells.clone()),
+        }
+    }
+}
+
+fn diff_maps<K: Ord>(
+    baseline: BTreeMap<K, usize>,
+    current: BTreeMap<K, usize>,
+) -> BTreeMap<K, MetricDelta> {
+    let mut diff: BTreeMap<K, MetricDelta> = baseline
+        .into_iter()
+        .map(|(key, baseline)| {
+            (
+                key,
+                MetricDelta {
+                    baseline,
+                    current: 0,
+                },
+            )
+        })
+        .collect();
+    for (key, current) in current {
+        diff.entry(key).or_default().current = current;
+    }
+    diff
+}
+
+impl MetricsDiff {
+    /// Renders the changed entries as markdown tables, one per kind of metric. Kinds without
+    /// changes are omitted.
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        write_table(
+            &mut out,
+            "Chip heights",
+            &["chip"],
+            self.chip_heights
+                .iter()
+                .map(|(chip, delta)| (vec![chip.as_str()], delta)),
+        );
+        write_table(
+            &mut out,
+            "Chip cells",
+            &["chip"],
+            self.chip_cells
+                .iter()
+                .map(|(chip, delta)| (vec![chip.as_str()], delta)),
+        );
+        write_table(
+            &mut out,
+            "Opcode frequency",
+            &["dsl_ir", "opcode"],
+            self.opcodes.iter().map(|((dsl_ir, opcode), delta)| {
+                (
+                    vec![dsl_ir.as_deref().unwrap_or_default(), opcode.as_str()],
+                    delta,
+                )
+            }),
+        );
+        write_table(
+            &mut out,
+            "Trace cells",
+            &["dsl_ir", "opcode", "air"],
+            self.trace_cells
+                .iter()
+                .map(|((dsl_ir, opcode, air_name), delta)| {
+                    (
+                        vec![
+                            dsl_ir.as_deref().unwrap_or_default(),
+                            opcode.as_str(),
+                            air_name.as_str(),
+                        ],
+                        delta,
+                    )
+                }),
+        );
+        out
+    }
+}
+
+impl fmt::Display for MetricsDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_markdown())
+    }
+}
+
+fn write_table<'a>(
+    out: &mut String,
+    title: &str,
+    key_columns: &[&str],
+    rows: impl Iterator<Item = (Vec<&'a str>, &'a MetricDelta)>,
+) {
+    let mut rows = rows.filter(|(_, delta)| delta.is_changed()).peekable();
+    if rows.peek().is_none() {
+        return;
+    }
+    writeln!(out, "### {title}\n").unwrap();
+    writeln!(
+        out,
+        "| {} | baseline | current | delta | change |",
+        key_columns.join(" | ")
+    )
+    .unwrap();
+    writeln!(out, "|{}", "---|".repeat(key_columns.len() + 4)).unwrap();
+    for (keys, delta) in rows {
+        let change = delta
+            .percent_change()
+            .map_or_else(|| "new".to_string(), |pct| format!("{pct:+.2}%"));
+        writeln!(
+            out,
+            "| {} | {} | {} | {:+} | {change} |",
+            keys.join(" | "),
+            delta.baseline,
+            delta.current,
+            delta.delta()
+        )
+        .unwrap();
+    }
+    out.push('\n');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MetricDelta;
+    use crate::metrics::VmMetrics;
+
+    #[test]
+    fn test_metrics_diff() {
+        let add = (None, "ADD".to_string());
+        let mul = (None, "MUL".to_string());
+        let mut baseline = VmMetrics {
+            chip_heights: vec![("ProgramChip".to_string(), 8)],
+            ..Default::default()
+        };
+        baseline.counts.insert(add.clone(), 4);
+        let mut current = VmMetrics {
+            chip_heights: vec![("ProgramChip".to_string(), 8)],
+            ..Default::default()
+        };
+        current.counts.insert(add.clone(), 5);
+        current.counts.insert(mul.clone(), 2);
+
+        let diff = current.diff(&baseline);
+        assert!(!diff.chip_heights["ProgramChip"].is_changed());
+        assert_eq!(
+            diff.opcodes[&add],
+            MetricDelta {
+                baseline: 4,
+                current: 5
+            }
+        );
+        assert_eq!(diff.opcodes[&add].percent_change(), Some(25.0));
+        assert_eq!(diff.opcodes[&mul].percent_change(), None);
+
+        assert_eq!(
+            diff.to_markdown(),
+            "### Opcode frequency\n\n\
+             | dsl_ir | opcode | baseline | current | delta | change |\n\
+             |---|---|---|---|---|---|\n\
+             |  | ADD | 4 | 5 | +1 | +25.00% |\n\
+             |  | MUL | 0 | 2 | +2 | new |\n\n"
+        );
+    }
+}
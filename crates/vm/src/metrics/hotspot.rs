@@ -0,0 +1,121 @@
+use openvm_instructions::exe::{source_location, FnBounds, SourceLines, SourceLocation};
+use serde::{Deserialize, Serialize};
+
+use super::VmMetrics;
+
+/// Cost attributed to one PC bucket. See
+/// [SystemConfig::with_pc_profile](crate::arch::SystemConfig::with_pc_profile).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PcProfileEntry {
+    pub cycles: usize,
+    pub trace_cells: usize,
+}
+
+/// What [VmMetrics::hotspots] ranks PC buckets by.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HotspotWeight {
+    Cycles,
+    TraceCells,
+}
+
+/// One entry of [VmMetrics::hotspots].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PcHotspot {
+    /// First PC of the bucket.
+    pub pc: u32,
+    /// Name of the function containing `pc`, if known.
+    pub function: Option<String>,
+    /// Source location of `pc`, if known.
+    pub source: Option<SourceLocation>,
+    pub cycles: usize,
+    pub trace_cells: usize,
+}
+
+impl VmMetrics {
+    /// Returns the `n` PC buckets with the highest `weight`, heaviest first, with the function
+    /// containing each bucket looked up in `fn_bounds` and its source location in `source_lines`.
+    /// Empty unless the PC profile was enabled.
+    pub fn hotspots(
+        &self,
+        fn_bounds: &FnBounds,
+        source_lines: &SourceLines,
+        n: usize,
+        weight: HotspotWeight,
+    ) -> Vec<PcHotspot> {
+        let mut buckets: Vec<_> = self.pc_profile.iter().collect();
+        buckets.sort_by_key(|&(&pc, entry)| {
+            let weight = match weight {
+                HotspotWeight::Cycles => entry.cycles,
+                HotspotWeight::TraceCells => entry.trace_cells,
+            };
+            (std::cmp::Reverse(weight), pc)
+        });
+        buckets
+            .into_iter()
+            .take(n)
+            .map(|(&pc, entry)| PcHotspot {
+                pc,
+                function: fn_bounds
+                    .range(..=pc)
+                    .next_back()
+                    .filter(|(_, bound)| pc <= bound.end)
+                    .map(|(_, bound)| bound.name.clone()),
+                source: source_location(source_lines, pc).cloned(),
+                cycles: entry.cycles,
+                trace_cells: entry.trace_cells,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use openvm_instructions::exe::{FnBound, FnBounds, SourceLines, SourceLocation};
+
+    use super::{HotspotWeight, PcProfileEntry};
+    use crate::metrics::VmMetrics;
+
+    #[test]
+    fn test_hotspots() {
+        let mut metrics = VmMetrics::default();
+        for (pc, cycles, trace_cells) in [(0, 10, 5), (8, 3, 100), (64, 7, 7)] {
+            metrics.pc_profile.insert(
+                pc,
+                PcProfileEntry {
+                    cycles,
+                    trace_cells,
+                },
+            );
+        }
+        let fn_bounds = FnBounds::from([(
+            0,
+            FnBound {
+                start: 0,
+                end: 12,
+                name: "main".to_string(),
+            },
+        )]);
+        let source_lines = SourceLines::from([(
+            8,
+            SourceLocation {
+                file: "src/main.rs".to_string(),
+                line: 3,
+            },
+        )]);
+
+        let hotspots = metrics.hotspots(&fn_bounds, &source_lines, 2, HotspotWeight::Cycles);
+        assert_eq!(
+            hotspots.iter().map(|h| h.pc).collect::<Vec<_>>(),
+            vec![0, 64]
+        );
+        assert_eq!(hotspots[0].function.as_deref(), Some("main"));
+        assert_eq!(hotspots[1].function, None);
+        assert_eq!(hotspots[0].source, None);
+        assert_eq!(hotspots[1].source, source_lines.get(&8).cloned());
+
+        let hotspots = metrics.hotspots(&fn_bounds, &source_lines, 1, HotspotWeight::TraceCells);
+        assert_eq!(hotspots[0].pc, 8);
+        assert_eq!(hotspots[0].function.as_deref(), Some("main"));
+        assert_eq!(hotspots[0].source, source_lines.get(&8).cloned());
+    }
+}
@@ -2,15 +2,75 @@ use std::collections::BTreeMap;
 
 use serde::{Deserialize, Serialize};
 
+mod adapter;
+mod cost;
 pub mod cycle_tracker;
+mod diff;
+mod hotspot;
+mod report;
+mod trace_event;
+
+pub use adapter::*;
+pub use cost::*;
+pub use diff::*;
+pub use hotspot::*;
+pub use report::*;
+pub use trace_event::*;
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct VmMetrics {
     pub chip_heights: Vec<(String, usize)>,
+    /// Main trace cells of each chip, in the same order as `chip_heights`
+    #[serde(default)]
+    pub chip_cells: Vec<(String, usize)>,
     /// Maps (dsl_ir, opcode) to number of times opcode was executed
     pub counts: BTreeMap<(Option<String>, String), usize>,
     /// Maps (dsl_ir, opcode, air_name) to number of trace cells generated by opcode
     pub trace_cells: BTreeMap<(Option<String>, String, String), usize>,
+    /// Maps (dsl_ir, opcode) to number of memory cells read by opcode
+    pub memory_cells_read: BTreeMap<(Option<String>, String), usize>,
+    /// Maps (dsl_ir, opcode) to number of memory cells written by opcode
+    pub memory_cells_written: BTreeMap<(Option<String>, String), usize>,
+    /// Maps (dsl_ir, opcode) to number of access adapter rows generated by opcode
+    pub access_adapter_rows: BTreeMap<(Option<String>, String), usize>,
+    /// Maps first pc of a bucket to cycles and trace cells of the instructions in it. Only
+    /// collected when [SystemConfig::pc_profile_bucket_size](crate::arch::SystemConfig) is set
+    #[serde(default)]
+    pub pc_profile: BTreeMap<u32, PcProfileEntry>,
+}
+
+impl VmMetrics {
+    /// Adds the metrics of `other` to `self`, e.g. to aggregate the metrics of all segments of a
+    /// run. Chip heights and cells are summed by chip name.
+    pub fn merge(&mut self, other: &Self) {
+        merge_named(&mut self.chip_heights, &other.chip_heights);
+        merge_named(&mut self.chip_cells, &other.chip_cells);
+        merge_counts(&mut self.counts, &other.counts);
+        merge_counts(&mut self.trace_cells, &other.trace_cells);
+        merge_counts(&mut self.memory_cells_read, &other.memory_cells_read);
+        merge_counts(&mut self.memory_cells_written, &other.memory_cells_written);
+        merge_counts(&mut self.access_adapter_rows, &other.access_adapter_rows);
+        for (&pc, entry) in &other.pc_profile {
+            let total = self.pc_profile.entry(pc).or_default();
+            total.cycles += entry.cycles;
+            total.trace_cells += entry.trace_cells;
+        }
+    }
+}
+
+fn merge_named(into: &mut Vec<(String, usize)>, from: &[(String, usize)]) {
+    for (name, value) in from {
+        match into.iter_mut().find(|(n, _)| n == name) {
+            Some((_, total)) => *total += value,
+            None => into.push((name.clone(), *value)),
+        }
+    }
+}
+
+fn merge_counts<K: Clone + Ord>(into: &mut BTreeMap<K, usize>, from: &BTreeMap<K, usize>) {
+    for (key, value) in from {
+        *into.entry(key.clone()).or_insert(0) += value;
+    }
 }
 
 #[cfg(feature = "bench-metrics")]
@@ -20,14 +80,18 @@ mod emit {
     use super::VmMetrics;
 
     impl VmMetrics {
-        pub fn emit(&self) {
+        /// Emits the metrics as counters labeled with `segment`, the index of the segment they
+        /// were collected in.
+        pub fn emit(&self, segment: usize) {
+            let segment = segment.to_string();
             for (name, value) in self.chip_heights.iter() {
-                let labels = [("chip_name", name.clone())];
+                let labels = [("segment", segment.clone()), ("chip_name", name.clone())];
                 counter!("rows_used", &labels).absolute(*value as u64);
             }
 
             for ((dsl_ir, opcode), value) in self.counts.iter() {
                 let labels = [
+                    ("segment", segment.clone()),
                     ("dsl_ir", dsl_ir.clone().unwrap_or_else(String::new)),
                     ("opcode", opcode.clone()),
                 ];
@@ -36,12 +100,63 @@ mod emit {
 
             for ((dsl_ir, opcode, air_name), value) in self.trace_cells.iter() {
                 let labels = [
+                    ("segment", segment.clone()),
                     ("dsl_ir", dsl_ir.clone().unwrap_or_else(String::new)),
                     ("opcode", opcode.clone()),
                     ("air_name", air_name.clone()),
                 ];
                 counter!("cells_used", &labels).absolute(*value as u64);
             }
+
+            for (name, values) in [
+                ("memory_cells_read", &self.memory_cells_read),
+                ("memory_cells_written", &self.memory_cells_written),
+                ("access_adapter_rows", &self.access_adapter_rows),
+            ] {
+                for ((dsl_ir, opcode), value) in values.iter() {
+                    let labels = [
+                        ("segment", segment.clone()),
+                        ("dsl_ir", dsl_ir.clone().unwrap_or_else(String::new)),
+                        ("opcode", opcode.clone()),
+                    ];
+                    counter!(name, &labels).absolute(*value as u64);
+                }
+            }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::VmMetrics;
+
+    #[test]
+    fn test_metrics_merge() {
+        let key = (None, "ADD".to_string());
+        let mut first = VmMetrics {
+            chip_heights: vec![("ProgramChip".to_string(), 8)],
+            ..Default::default()
+        };
+        first.counts.insert(key.clone(), 3);
+        let mut second = VmMetrics {
+            chip_heights: vec![
+                ("ProgramChip".to_string(), 4),
+                ("ConnectorChip".to_string(), 2),
+            ],
+            ..Default::default()
+        };
+        second.counts.insert(key.clone(), 2);
+        second.memory_cells_read.insert(key.clone(), 5);
+
+        first.merge(&second);
+        assert_eq!(
+            first.chip_heights,
+            vec![
+                ("ProgramChip".to_string(), 12),
+                ("ConnectorChip".to_string(), 2)
+            ]
+        );
+        assert_eq!(first.counts[&key], 5);
+        assert_eq!(first.memory_cells_read[&key], 5);
+    }
+}
@@ -0,0 +1,187 @@
+use std::{
+    collections::BTreeMap,
+    io::{self, Write},
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::VmMetrics;
+
+/// Height of one chip's trace.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChipHeightEntry {
+    pub chip_name: String,
+    pub height: usize,
+}
+
+/// Execution and memory counts of one opcode.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OpcodeMetricsEntry {
+    pub dsl_ir: Option<String>,
+    pub opcode: String,
+    pub frequency: usize,
+    pub memory_cells_read: usize,
+    pub memory_cells_written: usize,
+    pub access_adapter_rows: usize,
+}
+
+/// Trace cells generated in one AIR by one opcode.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CellsUsedEntry {
+    pub dsl_ir: Option<String>,
+    pub opcode: String,
+    pub air_name: String,
+    pub cells: usize,
+}
+
+/// A snapshot of [VmMetrics] that can be serialized, so runs can be compared without a metrics
+/// exporter. Entries are sorted by their keys, so reports of the same program are stable.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VmMetricsReport {
+    pub chip_heights: Vec<ChipHeightEntry>,
+    pub opcodes: Vec<OpcodeMetricsEntry>,
+    pub cells_used: Vec<CellsUsedEntry>,
+}
+
+impl VmMetrics {
+    pub fn to_report(&self) -> VmMetricsReport {
+        let chip_heights = self
+            .chip_heights
+            .iter()
+            .map(|(chip_name, height)| ChipHeightEntry {
+                chip_name: chip_name.clone(),
+                height: *height,
+            })
+            .collect();
+
+        let mut opcodes = BTreeMap::new();
+        for (key, &value) in &self.counts {
+            opcode_entry(&mut opcodes, key).frequency = value;
+        }
+        for (key, &value) in &self.memory_cells_read {
+            opcode_entry(&mut opcodes, key).memory_cells_read = value;
+        }
+        for (key, &value) in &self.memory_cells_written {
+            opcode_entry(&mut opcodes, key).memory_cells_written = value;
+        }
+        for (key, &value) in &self.access_adapter_rows {
+            opcode_entry(&mut opcodes, key).access_adapter_rows = value;
+        }
+
+        let cells_used = self
+            .trace_cells
+            .iter()
+            .map(|((dsl_ir, opcode, air_name), &cells)| CellsUsedEntry {
+                dsl_ir: dsl_ir.clone(),
+                opcode: opcode.clone(),
+                air_name: air_name.clone(),
+                cells,
+            })
+            .collect();
+
+        VmMetricsReport {
+            chip_heights,
+            opcodes: opcodes.into_values().collect(),
+            cells_used,
+        }
+    }
+}
+
+fn opcode_entry<'a>(
+    opcodes: &'a mut BTreeMap<(Option<String>, String), OpcodeMetricsEntry>,
+    (dsl_ir, opcode): &(Option<String>, String),
+) -> &'a mut OpcodeMetricsEntry {
+    opcodes
+        .entry((dsl_ir.clone(), opcode.clone()))
+        .or_insert_with(|| OpcodeMetricsEntry {
+            dsl_ir: dsl_ir.clone(),
+            opcode: opcode.clone(),
+            ..Default::default()
+        })
+}
+
+impl VmMetricsReport {
+    /// Writes the report as CSV in long format, one value per row, with header
+    /// `metric,chip_name,dsl_ir,opcode,air_name,value`. Columns that do not apply to a metric are
+    /// left empty.
+    pub fn write_csv(&self, mut writer: impl Write) -> io::Result<()> {
+        writeln!(writer, "metric,chip_name,dsl_ir,opcode,air_name,value")?;
+        for entry in &self.chip_heights {
+            write_csv_row(
+                &mut writer,
+                ["rows_used", &entry.chip_name, "", "", ""],
+                entry.height,
+            )?;
+        }
+        for entry in &self.opcodes {
+            let dsl_ir = entry.dsl_ir.as_deref().unwrap_or_default();
+            for (metric, value) in [
+                ("frequency", entry.frequency),
+                ("memory_cells_read", entry.memory_cells_read),
+                ("memory_cells_written", entry.memory_cells_written),
+                ("access_adapter_rows", entry.access_adapter_rows),
+            ] {
+                write_csv_row(&mut writer, [metric, "", dsl_ir, &entry.opcode, ""], value)?;
+            }
+        }
+        for entry in &self.cells_used {
+            write_csv_row(
+                &mut writer,
+                [
+                    "cells_used",
+                    "",
+                    entry.dsl_ir.as_deref().unwrap_or_default(),
+                    &entry.opcode,
+                    &entry.air_name,
+                ],
+                entry.cells,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn write_csv_row(writer: &mut impl Write, fields: [&str; 5], value: usize) -> io::Result<()> {
+    for field in fields {
+        if field.contains([',', '"', '\n', '\r']) {
+            write!(writer, "\"{}\",", field.replace('"', "\"\""))?;
+        } else {
+            write!(writer, "{field},")?;
+        }
+    }
+    writeln!(writer, "{value}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::VmMetrics;
+
+    #[test]
+    fn test_metrics_report_csv() {
+        let mut metrics = VmMetrics {
+            chip_heights: vec![("ProgramChip".to_string(), 8)],
+            ..Default::default()
+        };
+        let key = (Some("a, \"b\"".to_string()), "ADD".to_string());
+        metrics.counts.insert(key.clone(), 3);
+        metrics.memory_cells_read.insert(key.clone(), 6);
+        metrics
+            .trace_cells
+            .insert((key.0, key.1, "AddAir".to_string()), 30);
+
+        let report = metrics.to_report();
+        assert_eq!(report.opcodes.len(), 1);
+        assert_eq!(report.opcodes[0].frequency, 3);
+        assert_eq!(report.opcodes[0].memory_cells_read, 6);
+        assert_eq!(report.opcodes[0].memory_cells_written, 0);
+
+        let mut csv = Vec::new();
+        report.write_csv(&mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        let lines: Vec<_> = csv.lines().collect();
+        assert_eq!(lines.len(), 1 + 1 + 4 + 1);
+        assert_eq!(lines[1], "rows_used,ProgramChip,,,,8");
+        assert_eq!(lines[2], "frequency,,\"a, \"\"b\"\"\",ADD,,3");
+        assert_eq!(lines[6], "cells_used,,\"a, \"\"b\"\"\",ADD,AddAir,30");
+    }
+}
@@ -0,0 +1,127 @@
+use std::io::{self, Write};
+
+use serde::{Deserialize, Serialize};
+
+/// One event in the [Chrome trace-event format](https://docs.google.com/document/d/1CvAClvFfyA5R-PhYUmn5OOQtYMH4h6I0nSsKchNAySU),
+/// which Perfetto and `chrome://tracing` can display.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraceEvent {
+    pub name: String,
+    #[serde(rename = "cat")]
+    pub category: String,
+    /// `X` for a complete event with a duration, `i` for an instant event.
+    #[serde(rename = "ph")]
+    pub phase: String,
+    /// Start of the event, in cycles.
+    pub ts: u64,
+    /// Duration of a complete event, in cycles.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dur: Option<u64>,
+    pub pid: u32,
+    pub tid: u32,
+}
+
+/// Records an execution timeline of function spans, segments and finalization phases, with
+/// timestamps in cycles over all segments of the run.
+#[derive(Clone, Debug, Default)]
+pub struct TraceEventRecorder {
+    events: Vec<TraceEvent>,
+    /// Name and start cycle of the function being executed.
+    current_fn: Option<(String, u64)>,
+}
+
+impl TraceEventRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn events(&self) -> &[TraceEvent] {
+        &self.events
+    }
+
+    /// Records a span of `category` from cycle `start` to cycle `end`.
+    pub fn span(&mut self, category: &str, name: impl Into<String>, start: u64, end: u64) {
+        self.events.push(TraceEvent {
+            name: name.into(),
+            category: category.to_string(),
+            phase: "X".to_string(),
+            ts: start,
+            dur: Some(end - start),
+            pid: 0,
+            tid: 0,
+        });
+    }
+
+    /// Records an event of `category` without duration at `cycle`.
+    pub fn instant(&mut self, category: &str, name: impl Into<String>, cycle: u64) {
+        self.events.push(TraceEvent {
+            name: name.into(),
+            category: category.to_string(),
+            phase: "i".to_string(),
+            ts: cycle,
+            dur: None,
+            pid: 0,
+            tid: 0,
+        });
+    }
+
+    /// Notes that the function `name` is executing at `cycle`, closing the span of the previous
+    /// function if it is a different one.
+    pub fn enter_function(&mut self, name: &str, cycle: u64) {
+        if self
+            .current_fn
+            .as_ref()
+            .is_some_and(|(current, _)| current == name)
+        {
+            return;
+        }
+        self.exit_function(cycle);
+        self.current_fn = Some((name.to_string(), cycle));
+    }
+
+    /// Closes the span of the function being executed, if any.
+    pub fn exit_function(&mut self, cycle: u64) {
+        if let Some((name, start)) = self.current_fn.take() {
+            self.span("function", name, start, cycle);
+        }
+    }
+
+    /// Writes the events as a JSON trace file, e.g. `trace.json`. Timestamps are cycles, which
+    /// viewers display as microseconds.
+    pub fn write_json(&self, writer: impl Write) -> io::Result<()> {
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct TraceFile<'a> {
+            trace_events: &'a [TraceEvent],
+        }
+        serde_json::to_writer(
+            writer,
+            &TraceFile {
+                trace_events: &self.events,
+            },
+        )
+        .map_err(io::Error::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TraceEventRecorder;
+
+    #[test]
+    fn test_trace_events() {
+        let mut recorder = TraceEventRecorder::new();
+        recorder.enter_function("main", 0);
+        recorder.enter_function("main", 3);
+        recorder.enter_function("memcpy", 5);
+        recorder.exit_function(9);
+        recorder.instant("segment", "finalize memory", 9);
+
+        let mut json = Vec::new();
+        recorder.write_json(&mut json).unwrap();
+        assert_eq!(
+            String::from_utf8(json).unwrap(),
+            r#"{"traceEvents":[{"name":"main","cat":"function","ph":"X","ts":0,"dur":5,"pid":0,"tid":0},{"name":"memcpy","cat":"function","ph":"X","ts":5,"dur":4,"pid":0,"tid":0},{"name":"finalize memory","cat":"segment","ph":"i","ts":9,"pid":0,"tid":0}]}"#
+        );
+    }
+}
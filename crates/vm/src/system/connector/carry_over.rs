@@ -0,0 +1,127 @@
+use std::sync::Arc;
+
+use openvm_stark_backend::{interaction::InteractionBuilder, p3_field::AbstractField};
+use parking_lot::Mutex;
+
+/// Bus through which chips update the carry-over values of a segment, see
+/// [SystemConfig::with_carry_over](crate::arch::SystemConfig::with_carry_over).
+///
+/// The connector sends each element with its initial value and receives it with its final
+/// value, so the updates in between must chain the two. The bus does not order the updates:
+/// the update function must make cycles infeasible, as a hash chain does.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CarryOverBus(pub usize);
+
+impl CarryOverBus {
+    /// Replaces `old` by `new` as the value of carry-over element `index`.
+    pub fn update<AB: InteractionBuilder>(
+        &self,
+        builder: &mut AB,
+        index: impl Into<AB::Expr>,
+        old: impl Into<AB::Expr>,
+        new: impl Into<AB::Expr>,
+        count: impl Into<AB::Expr>,
+    ) {
+        let index = index.into();
+        let count = count.into();
+        builder.push_receive(self.0, [index.clone(), old.into()], count.clone());
+        builder.push_send(self.0, [index, new.into()], count);
+    }
+
+    pub(super) fn send<AB: InteractionBuilder>(
+        &self,
+        builder: &mut AB,
+        index: usize,
+        value: impl Into<AB::Expr>,
+        count: impl Into<AB::Expr>,
+    ) {
+        builder.push_send(
+            self.0,
+            [AB::Expr::from_canonical_usize(index), value.into()],
+            count,
+        );
+    }
+
+    pub(super) fn receive<AB: InteractionBuilder>(
+        &self,
+        builder: &mut AB,
+        index: usize,
+        value: impl Into<AB::Expr>,
+        count: impl Into<AB::Expr>,
+    ) {
+        builder.push_receive(
+            self.0,
+            [AB::Expr::from_canonical_usize(index), value.into()],
+            count,
+        );
+    }
+}
+
+/// The current carry-over values of a segment, shared between the connector chip, which records
+/// them at the segment boundaries, and the chips updating them during execution.
+#[derive(Clone, Debug)]
+pub struct CarryOverValues<F> {
+    bus: Option<CarryOverBus>,
+    values: Arc<Mutex<Vec<F>>>,
+}
+
+impl<F: AbstractField> CarryOverValues<F> {
+    /// `len` elements, all zero. The bus must be set if `len` is positive.
+    pub fn new(bus: Option<CarryOverBus>, len: usize) -> Self {
+        assert_eq!(
+            bus.is_some(),
+            len > 0,
+            "carry-over bus must be set iff len > 0"
+        );
+        Self {
+            bus,
+            values: Arc::new(Mutex::new(vec![F::ZERO; len])),
+        }
+    }
+}
+
+impl<F: Clone> CarryOverValues<F> {
+    /// The bus to update the values through. Panics if the system config declares no carry-over
+    /// values.
+    pub fn bus(&self) -> CarryOverBus {
+        self.bus
+            .expect("the system config declares no carry-over values")
+    }
+
+    pub(super) fn try_bus(&self) -> Option<CarryOverBus> {
+        self.bus
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn get(&self, index: usize) -> F {
+        self.values.lock()[index].clone()
+    }
+
+    /// Sets element `index` to `value` and returns the previous value. A chip calling this must
+    /// record both values for its [CarryOverBus::update] interaction.
+    pub fn update(&self, index: usize, value: F) -> F {
+        std::mem::replace(&mut self.values.lock()[index], value)
+    }
+
+    pub fn values(&self) -> Vec<F> {
+        self.values.lock().clone()
+    }
+
+    /// Overwrites all values, e.g. with the final values of the previous segment.
+    pub fn set_values(&self, values: Vec<F>) {
+        let mut current = self.values.lock();
+        assert_eq!(
+            values.len(),
+            current.len(),
+            "wrong number of carry-over values"
+        );
+        *current = values;
+    }
+}
@@ -1,6 +1,5 @@
 use std::{
     borrow::{Borrow, BorrowMut},
-    marker::PhantomData,
     sync::Arc,
 };
 
@@ -22,9 +21,12 @@ use crate::{
     system::program::ProgramBus,
 };
 
+mod carry_over;
 #[cfg(test)]
 mod tests;
 
+pub use carry_over::*;
+
 /// When a program hasn't terminated. There is no constraints on the exit code.
 /// But we will use this value when generating the proof.
 pub const DEFAULT_SUSPEND_EXIT_CODE: u32 = 42;
@@ -33,6 +35,12 @@ pub const DEFAULT_SUSPEND_EXIT_CODE: u32 = 42;
 pub struct VmConnectorAir {
     pub execution_bus: ExecutionBus,
     pub program_bus: ProgramBus,
+    /// Set if there are carry-over values.
+    pub carry_over_bus: Option<CarryOverBus>,
+    /// Number of carry-over field elements. The trace has a column per element after the
+    /// [ConnectorCols], and the public values are followed by the initial and then the final
+    /// values of the elements.
+    pub num_carry_over: usize,
 }
 
 #[derive(Debug, Clone, Copy, AlignedBorrow)]
@@ -52,13 +60,13 @@ pub struct VmConnectorPvs<F> {
 
 impl<F: Field> BaseAirWithPublicValues<F> for VmConnectorAir {
     fn num_public_values(&self) -> usize {
-        VmConnectorPvs::<F>::width()
+        VmConnectorPvs::<F>::width() + 2 * self.num_carry_over
     }
 }
 impl<F: Field> PartitionedBaseAir<F> for VmConnectorAir {}
 impl<F: Field> BaseAir<F> for VmConnectorAir {
     fn width(&self) -> usize {
-        4
+        ConnectorCols::<F>::width() + self.num_carry_over
     }
 
     fn preprocessed_trace(&self) -> Option<RowMajorMatrix<F>> {
@@ -73,6 +81,11 @@ pub struct ConnectorCols<T> {
     pub timestamp: T,
     pub is_terminate: T,
     pub exit_code: T,
+    /// Operands `a`, `b` and `d` of the TERMINATE instruction: pointer, length and address
+    /// space of an exit message. The address space is 0 if there is no message.
+    pub message_ptr: T,
+    pub message_len: T,
+    pub message_address_space: T,
 }
 
 impl<T: Copy> ConnectorCols<T> {
@@ -82,11 +95,22 @@ impl<T: Copy> ConnectorCols<T> {
             timestamp: f(self.timestamp),
             is_terminate: f(self.is_terminate),
             exit_code: f(self.exit_code),
+            message_ptr: f(self.message_ptr),
+            message_len: f(self.message_len),
+            message_address_space: f(self.message_address_space),
         }
     }
 
-    fn flatten(&self) -> [T; 4] {
-        [self.pc, self.timestamp, self.is_terminate, self.exit_code]
+    fn flatten(&self) -> [T; 7] {
+        [
+            self.pc,
+            self.timestamp,
+            self.is_terminate,
+            self.exit_code,
+            self.message_ptr,
+            self.message_len,
+            self.message_address_space,
+        ]
     }
 }
 
@@ -97,15 +121,21 @@ impl<AB: InteractionBuilder + PairBuilder + AirBuilderWithPublicValues> Air<AB>
         let prep_local = preprocessed.row_slice(0);
         let (begin, end) = (main.row_slice(0), main.row_slice(1));
 
-        let begin: &ConnectorCols<AB::Var> = (*begin).borrow();
-        let end: &ConnectorCols<AB::Var> = (*end).borrow();
+        let width = ConnectorCols::<AB::Var>::width();
+        let (begin, begin_carry_over) = begin.split_at(width);
+        let (end, end_carry_over) = end.split_at(width);
+        let begin: &ConnectorCols<AB::Var> = begin.borrow();
+        let end: &ConnectorCols<AB::Var> = end.borrow();
 
+        let public_values = builder.public_values().to_vec();
+        let (pvs, carry_over_pvs) = public_values.split_at(VmConnectorPvs::<AB::Var>::width());
         let &VmConnectorPvs {
             initial_pc,
             final_pc,
             exit_code,
             is_terminate,
-        } = builder.public_values().borrow();
+        } = pvs.borrow();
+        let (initial_carry_over, final_carry_over) = carry_over_pvs.split_at(self.num_carry_over);
 
         builder.when_transition().assert_eq(begin.pc, initial_pc);
         builder.when_transition().assert_eq(end.pc, final_pc);
@@ -127,9 +157,32 @@ impl<AB: InteractionBuilder + PairBuilder + AirBuilderWithPublicValues> Air<AB>
             builder,
             end.pc,
             AB::Expr::from_canonical_usize(TERMINATE.with_default_offset()),
-            [AB::Expr::ZERO, AB::Expr::ZERO, end.exit_code.into()],
+            [
+                end.message_ptr.into(),
+                end.message_len.into(),
+                end.exit_code.into(),
+                end.message_address_space.into(),
+            ],
             (AB::Expr::ONE - prep_local[0]) * end.is_terminate,
         );
+
+        // Each segment starts from the initial carry-over values and ends with the final ones;
+        // the chips updating them in between must balance the bus.
+        for index in 0..self.num_carry_over {
+            builder
+                .when_transition()
+                .assert_eq(begin_carry_over[index], initial_carry_over[index]);
+            builder
+                .when_transition()
+                .assert_eq(end_carry_over[index], final_carry_over[index]);
+        }
+        if let Some(carry_over_bus) = self.carry_over_bus {
+            for index in 0..self.num_carry_over {
+                let count = AB::Expr::ONE - prep_local[0];
+                carry_over_bus.send(builder, index, begin_carry_over[index], count.clone());
+                carry_over_bus.receive(builder, index, end_carry_over[index], count);
+            }
+        }
     }
 }
 
@@ -137,38 +190,70 @@ impl<AB: InteractionBuilder + PairBuilder + AirBuilderWithPublicValues> Air<AB>
 pub struct VmConnectorChip<F> {
     pub air: VmConnectorAir,
     pub boundary_states: [Option<ConnectorCols<u32>>; 2],
-    _marker: PhantomData<F>,
+    /// The carry-over values at the beginning and at the end of the segment.
+    pub boundary_carry_over: [Vec<F>; 2],
+    carry_over: CarryOverValues<F>,
 }
 
 impl<F: PrimeField32> VmConnectorChip<F> {
-    pub fn new(execution_bus: ExecutionBus, program_bus: ProgramBus) -> Self {
+    pub fn new(
+        execution_bus: ExecutionBus,
+        program_bus: ProgramBus,
+        carry_over: CarryOverValues<F>,
+    ) -> Self {
         Self {
             air: VmConnectorAir {
                 execution_bus,
                 program_bus,
+                carry_over_bus: carry_over.try_bus(),
+                num_carry_over: carry_over.len(),
             },
             boundary_states: [None, None],
-            _marker: PhantomData,
+            boundary_carry_over: [vec![], vec![]],
+            carry_over,
         }
     }
 
+    pub fn carry_over(&self) -> &CarryOverValues<F> {
+        &self.carry_over
+    }
+
     pub fn begin(&mut self, state: ExecutionState<u32>) {
+        self.boundary_carry_over[0] = self.carry_over.values();
         self.boundary_states[0] = Some(ConnectorCols {
             pc: state.pc,
             timestamp: state.timestamp,
             is_terminate: 0,
             exit_code: 0,
+            message_ptr: 0,
+            message_len: 0,
+            message_address_space: 0,
         });
     }
 
     pub fn end(&mut self, state: ExecutionState<u32>, exit_code: Option<u32>) {
+        self.boundary_carry_over[1] = self.carry_over.values();
         self.boundary_states[1] = Some(ConnectorCols {
             pc: state.pc,
             timestamp: state.timestamp,
             is_terminate: exit_code.is_some() as u32,
             exit_code: exit_code.unwrap_or(DEFAULT_SUSPEND_EXIT_CODE),
+            message_ptr: 0,
+            message_len: 0,
+            message_address_space: 0,
         });
     }
+
+    /// Records the exit message operands of the TERMINATE instruction. Must be called after
+    /// [Self::end].
+    pub fn set_exit_message(&mut self, ptr: u32, len: u32, address_space: u32) {
+        let end = self.boundary_states[1]
+            .as_mut()
+            .expect("end state must be set");
+        end.message_ptr = ptr;
+        end.message_len = len;
+        end.message_address_space = address_space;
+    }
 }
 
 impl<SC> Chip<SC> for VmConnectorChip<Val<SC>>
@@ -181,13 +266,21 @@ where
     }
 
     fn generate_air_proof_input(self) -> AirProofInput<SC> {
+        let width = self.trace_width();
         let [initial_state, final_state] = self
             .boundary_states
             .map(|state| state.unwrap().map(Val::<SC>::from_canonical_u32));
+        let [initial_carry_over, final_carry_over] = self.boundary_carry_over;
 
         let trace = RowMajorMatrix::new(
-            [initial_state.flatten(), final_state.flatten()].concat(),
-            self.trace_width(),
+            [
+                &initial_state.flatten()[..],
+                &initial_carry_over[..],
+                &final_state.flatten()[..],
+                &final_carry_over[..],
+            ]
+            .concat(),
+            width,
         );
 
         let mut public_values = Val::<SC>::zero_vec(VmConnectorPvs::<Val<SC>>::width());
@@ -197,6 +290,8 @@ where
             exit_code: final_state.exit_code,
             is_terminate: final_state.is_terminate,
         };
+        public_values.extend(initial_carry_over);
+        public_values.extend(final_carry_over);
         AirProofInput::simple(Arc::new(self.air), trace, public_values)
     }
 }
@@ -215,6 +310,6 @@ impl<F: PrimeField32> ChipUsageGetter for VmConnectorChip<F> {
     }
 
     fn trace_width(&self) -> usize {
-        4
+        ConnectorCols::<F>::width() + self.air.num_carry_over
     }
 }
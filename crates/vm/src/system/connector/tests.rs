@@ -19,7 +19,7 @@ use openvm_stark_sdk::{
     p3_baby_bear::BabyBear,
 };
 
-use super::VmConnectorPvs;
+use super::{ConnectorCols, VmConnectorPvs};
 use crate::{
     arch::{SingleSegmentVmExecutor, SystemConfig, VirtualMachine, CONNECTOR_AIR_ID},
     system::program::trace::VmCommittedExe,
@@ -29,17 +29,31 @@ type F = BabyBear;
 #[test]
 fn test_vm_connector_happy_path() {
     let exit_code = 1789;
-    test_impl(true, exit_code, |air_proof_input| {
+    test_impl(true, exit_code, [0; 3], |air_proof_input| {
         let pvs: &VmConnectorPvs<F> = air_proof_input.raw.public_values.as_slice().borrow();
         assert_eq!(pvs.is_terminate, F::ONE);
         assert_eq!(pvs.exit_code, F::from_canonical_u32(exit_code));
     });
 }
 
+#[test]
+fn test_vm_connector_exit_message() {
+    test_impl(true, 1, [16, 0, 4], |_| {});
+}
+
+#[test]
+fn test_vm_connector_wrong_exit_message() {
+    test_impl(false, 1, [16, 0, 4], |air_proof_input| {
+        let trace = air_proof_input.raw.common_main.as_mut().unwrap();
+        let end: &mut ConnectorCols<F> = trace.row_mut(1).borrow_mut();
+        end.message_ptr = F::from_canonical_u32(17);
+    });
+}
+
 #[test]
 fn test_vm_connector_wrong_exit_code() {
     let exit_code = 1789;
-    test_impl(false, exit_code, |air_proof_input| {
+    test_impl(false, exit_code, [0; 3], |air_proof_input| {
         let pvs: &mut VmConnectorPvs<F> = air_proof_input
             .raw
             .public_values
@@ -52,7 +66,7 @@ fn test_vm_connector_wrong_exit_code() {
 #[test]
 fn test_vm_connector_wrong_is_terminate() {
     let exit_code = 1789;
-    test_impl(false, exit_code, |air_proof_input| {
+    test_impl(false, exit_code, [0; 3], |air_proof_input| {
         let pvs: &mut VmConnectorPvs<F> = air_proof_input
             .raw
             .public_values
@@ -65,6 +79,7 @@ fn test_vm_connector_wrong_is_terminate() {
 fn test_impl(
     should_pass: bool,
     exit_code: u32,
+    [message_ptr, message_len, message_address_space]: [isize; 3],
     f: impl FnOnce(&mut AirProofInput<BabyBearPoseidon2Config>),
 ) {
     let vm_config = SystemConfig::default();
@@ -76,10 +91,10 @@ fn test_impl(
     {
         let instructions = vec![Instruction::from_isize(
             VmOpcode::with_default_offset(TERMINATE),
-            0,
-            0,
+            message_ptr,
+            message_len,
             exit_code as isize,
-            0,
+            message_address_space,
             0,
         )];
 
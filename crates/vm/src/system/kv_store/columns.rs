@@ -0,0 +1,28 @@
+use openvm_circuit_primitives_derive::AlignedBorrow;
+
+use crate::system::memory::CHUNK;
+
+/// The first columns of a [KvStoreCoreAir](super::core::KvStoreCoreAir) row, followed by
+/// [KvStoreLevelCols] for each level of the tree.
+#[repr(C)]
+#[derive(AlignedBorrow)]
+pub struct KvStoreCoreCols<T> {
+    pub is_valid: T,
+    pub key: T,
+    pub value: T,
+    /// Hash of the pair.
+    pub leaf: [T; CHUNK],
+}
+
+/// One compression on the path from the leaf to the root.
+#[repr(C)]
+#[derive(AlignedBorrow)]
+pub struct KvStoreLevelCols<T> {
+    /// Whether the node on the path is the right child of `parent`.
+    pub is_right: T,
+    pub sibling: [T; CHUNK],
+    /// The left child of `parent`: the sibling if `is_right`, else the node on the path. The
+    /// right child is the other one.
+    pub left: [T; CHUNK],
+    pub parent: [T; CHUNK],
+}
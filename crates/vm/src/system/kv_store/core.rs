@@ -0,0 +1,288 @@
+use std::{
+    borrow::{Borrow, BorrowMut},
+    sync::Arc,
+};
+
+use openvm_circuit_primitives::utils::not;
+use openvm_instructions::{instruction::Instruction, KvStoreOpcode, UsizeOpcode};
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    p3_air::{AirBuilder, AirBuilderWithPublicValues, BaseAir},
+    p3_field::{AbstractField, Field, PrimeField32},
+    rap::BaseAirWithPublicValues,
+};
+use parking_lot::Mutex;
+
+use super::{
+    columns::{KvStoreCoreCols, KvStoreLevelCols},
+    leaf_input, KvStoreProofStep,
+};
+use crate::{
+    arch::{
+        hasher::{
+            poseidon2::{vm_poseidon2_hasher, Poseidon2Hasher},
+            Hasher,
+        },
+        AdapterAirContext, AdapterRuntimeContext, BasicAdapterInterface, ExecutionError,
+        MinimalInstruction, Result, Streams, VmAdapterInterface, VmCoreAir, VmCoreChip,
+    },
+    system::memory::{merkle::DirectCompressionBus, CHUNK},
+};
+
+pub(crate) type AdapterInterface<F> = BasicAdapterInterface<F, MinimalInstruction<F>, 1, 1, 1, 1>;
+pub(crate) type AdapterInterfaceReads<F> = <AdapterInterface<F> as VmAdapterInterface<F>>::Reads;
+
+/// Proves that the key read and the value written by each lookup are a leaf of the Merkle tree
+/// whose root is the public value, with one row per lookup.
+#[derive(Clone, Debug)]
+pub struct KvStoreCoreAir {
+    pub compression_bus: DirectCompressionBus,
+    /// Depth of the Merkle tree.
+    pub depth: usize,
+    offset: usize,
+}
+
+impl KvStoreCoreAir {
+    pub fn new(compression_bus: DirectCompressionBus, depth: usize, offset: usize) -> Self {
+        Self {
+            compression_bus,
+            depth,
+            offset,
+        }
+    }
+}
+
+impl<F: Field> BaseAir<F> for KvStoreCoreAir {
+    fn width(&self) -> usize {
+        KvStoreCoreCols::<F>::width() + self.depth * KvStoreLevelCols::<F>::width()
+    }
+}
+
+impl<F: Field> BaseAirWithPublicValues<F> for KvStoreCoreAir {
+    /// The root of the tree.
+    fn num_public_values(&self) -> usize {
+        CHUNK
+    }
+}
+
+impl<AB: InteractionBuilder + AirBuilderWithPublicValues> VmCoreAir<AB, AdapterInterface<AB::Expr>>
+    for KvStoreCoreAir
+{
+    fn eval(
+        &self,
+        builder: &mut AB,
+        local_core: &[AB::Var],
+        _from_pc: AB::Var,
+    ) -> AdapterAirContext<AB::Expr, AdapterInterface<AB::Expr>> {
+        let (local, levels) = local_core.split_at(KvStoreCoreCols::<AB::Var>::width());
+        let cols: &KvStoreCoreCols<_> = local.borrow();
+        builder.assert_bool(cols.is_valid);
+
+        let leaf_input = [cols.key, cols.value]
+            .into_iter()
+            .map(Into::into)
+            .chain(std::iter::repeat(AB::Expr::ZERO))
+            .take(CHUNK);
+        builder.push_send(
+            self.compression_bus.0,
+            leaf_input
+                .chain(std::iter::repeat(AB::Expr::ZERO).take(CHUNK))
+                .chain(cols.leaf.map(Into::into)),
+            cols.is_valid,
+        );
+
+        let mut node = cols.leaf.map(Into::into);
+        for level in levels.chunks_exact(KvStoreLevelCols::<AB::Var>::width()) {
+            let level: &KvStoreLevelCols<_> = level.borrow();
+            builder.assert_bool(level.is_right);
+            for i in 0..CHUNK {
+                builder.assert_eq(
+                    level.left[i],
+                    node[i].clone() + level.is_right * (level.sibling[i] - node[i].clone()),
+                );
+            }
+            let right: [AB::Expr; CHUNK] =
+                std::array::from_fn(|i| node[i].clone() + level.sibling[i] - level.left[i]);
+            builder.push_send(
+                self.compression_bus.0,
+                level
+                    .left
+                    .map(Into::into)
+                    .into_iter()
+                    .chain(right)
+                    .chain(level.parent.map(Into::into)),
+                cols.is_valid,
+            );
+            node = level.parent.map(Into::into);
+        }
+
+        let root: Vec<AB::Expr> = builder.public_values()[..CHUNK]
+            .iter()
+            .map(|&value| value.into())
+            .collect();
+        // Lookups fill the trace from the first row, so a segment without lookups has an invalid
+        // first row, and its root is constrained to zero. Proving a lookup against the zero root
+        // would take a Poseidon2 preimage of it.
+        let mut when_first_row = builder.when_first_row();
+        let mut when_no_lookup = when_first_row.when(not::<AB::Expr>(cols.is_valid));
+        for root in &root {
+            when_no_lookup.assert_zero(root.clone());
+        }
+        let mut when_valid = builder.when(cols.is_valid);
+        for (node, root) in node.into_iter().zip(root) {
+            when_valid.assert_eq(node, root);
+        }
+
+        AdapterAirContext {
+            to_pc: None,
+            reads: [[cols.key.into()]],
+            writes: [[cols.value.into()]],
+            instruction: MinimalInstruction {
+                is_valid: cols.is_valid.into(),
+                opcode: AB::Expr::from_canonical_usize(
+                    KvStoreOpcode::LOOKUP.as_usize() + self.offset,
+                ),
+            },
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct KvStoreRecord<F> {
+    key: F,
+    value: F,
+    leaf: [F; CHUNK],
+    proof: Vec<KvStoreProofStep<F>>,
+    /// The parent computed at each step of the proof.
+    parents: Vec<[F; CHUNK]>,
+}
+
+/// Looks the values up in the [KvStore](super::KvStore) of the [Streams].
+pub struct KvStoreCoreChip<F: PrimeField32> {
+    air: KvStoreCoreAir,
+    streams: Arc<Mutex<Streams<F>>>,
+    hasher: Poseidon2Hasher<F>,
+    /// The root of the store, once a lookup was made.
+    root: Mutex<Option<[F; CHUNK]>>,
+    /// Inputs of the compressions proved by the Poseidon2 periphery chip, see
+    /// [Self::take_compressions].
+    compressions: Mutex<Vec<([F; CHUNK], [F; CHUNK])>>,
+}
+
+impl<F: PrimeField32> KvStoreCoreChip<F> {
+    pub fn new(
+        compression_bus: DirectCompressionBus,
+        depth: usize,
+        offset: usize,
+        streams: Arc<Mutex<Streams<F>>>,
+    ) -> Self {
+        Self {
+            air: KvStoreCoreAir::new(compression_bus, depth, offset),
+            streams,
+            hasher: vm_poseidon2_hasher(),
+            root: Mutex::new(None),
+            compressions: Mutex::new(vec![]),
+        }
+    }
+
+    /// The compressions of all lookups so far, to be recorded in the Poseidon2 periphery chip.
+    pub fn take_compressions(&self) -> Vec<([F; CHUNK], [F; CHUNK])> {
+        std::mem::take(&mut *self.compressions.lock())
+    }
+}
+
+impl<F: PrimeField32> VmCoreChip<F, AdapterInterface<F>> for KvStoreCoreChip<F> {
+    type Record = KvStoreRecord<F>;
+    type Air = KvStoreCoreAir;
+
+    #[allow(clippy::type_complexity)]
+    fn execute_instruction(
+        &self,
+        _instruction: &Instruction<F>,
+        from_pc: u32,
+        reads: AdapterInterfaceReads<F>,
+    ) -> Result<(AdapterRuntimeContext<F, AdapterInterface<F>>, Self::Record)> {
+        let [[key]] = reads;
+        let streams = self.streams.lock();
+        let store = streams
+            .kv_store
+            .as_ref()
+            .filter(|store| store.depth() == self.air.depth)
+            .ok_or(ExecutionError::KvStoreUnavailable {
+                pc: from_pc,
+                depth: self.air.depth,
+            })?;
+        let (value, proof) = store
+            .lookup(key)
+            .ok_or(ExecutionError::KvStoreKeyNotFound {
+                pc: from_pc,
+                key: key.as_canonical_u32(),
+            })?;
+        *self.root.lock() = Some(store.root());
+        drop(streams);
+
+        let mut compressions = self.compressions.lock();
+        let input = leaf_input(key, value);
+        let leaf = self.hasher.hash(&input);
+        compressions.push((input, [F::ZERO; CHUNK]));
+        let mut node = leaf;
+        let parents = proof
+            .iter()
+            .map(|step| {
+                let (left, right) = if step.is_right {
+                    (step.sibling, node)
+                } else {
+                    (node, step.sibling)
+                };
+                node = self.hasher.compress(&left, &right);
+                compressions.push((left, right));
+                node
+            })
+            .collect();
+
+        let record = KvStoreRecord {
+            key,
+            value,
+            leaf,
+            proof,
+            parents,
+        };
+        Ok((AdapterRuntimeContext::without_pc([[value]]), record))
+    }
+
+    fn get_opcode_name(&self, opcode: usize) -> String {
+        format!("{:?}", KvStoreOpcode::from_usize(opcode - self.air.offset))
+    }
+
+    fn generate_trace_row(&self, row_slice: &mut [F], record: Self::Record) {
+        let (row, levels) = row_slice.split_at_mut(KvStoreCoreCols::<F>::width());
+        let cols: &mut KvStoreCoreCols<F> = row.borrow_mut();
+        cols.is_valid = F::ONE;
+        cols.key = record.key;
+        cols.value = record.value;
+        cols.leaf = record.leaf;
+
+        let mut node = record.leaf;
+        for ((level, step), parent) in levels
+            .chunks_exact_mut(KvStoreLevelCols::<F>::width())
+            .zip(record.proof)
+            .zip(record.parents)
+        {
+            let level: &mut KvStoreLevelCols<F> = level.borrow_mut();
+            level.is_right = F::from_bool(step.is_right);
+            level.sibling = step.sibling;
+            level.left = if step.is_right { step.sibling } else { node };
+            level.parent = parent;
+            node = parent;
+        }
+    }
+
+    /// The root of the store, or zero if no lookup was made.
+    fn generate_public_values(&self) -> Vec<F> {
+        self.root.lock().unwrap_or([F::ZERO; CHUNK]).to_vec()
+    }
+
+    fn air(&self) -> &Self::Air {
+        &self.air
+    }
+}
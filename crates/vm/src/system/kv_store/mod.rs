@@ -0,0 +1,132 @@
+use openvm_stark_backend::p3_field::PrimeField32;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    arch::{
+        hasher::{poseidon2::vm_poseidon2_hasher, Hasher},
+        VmAirWrapper, VmChipWrapper,
+    },
+    system::{
+        kv_store::core::{KvStoreCoreAir, KvStoreCoreChip},
+        memory::CHUNK,
+        native_adapter::{NativeAdapterAir, NativeAdapterChip},
+    },
+};
+
+mod columns;
+pub mod core;
+
+#[cfg(test)]
+mod tests;
+
+pub type KvStoreAir = VmAirWrapper<NativeAdapterAir<1, 1>, KvStoreCoreAir>;
+pub type KvStoreChip<F> = VmChipWrapper<F, NativeAdapterChip<F, 1, 1>, KvStoreCoreChip<F>>;
+
+/// Host-provided key-value pairs, committed to by the root of a Merkle tree of depth `depth`
+/// whose leaf `i` is the hash of the `i`-th pair in key order. Missing leaves are zero.
+///
+/// Guests look values up with [KvStoreOpcode::LOOKUP](openvm_instructions::KvStoreOpcode).
+/// The values are hinted by the host, but each lookup proves the membership of its pair in the
+/// tree, whose root is a public value of the [KvStoreAir], or zero in segments without lookups.
+/// The verifier must check the root against its commitment to the store. The SDK aggregation
+/// checks that all segments agree on the root, and exposes it as a public value of the root
+/// verifier.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound(serialize = "F: Serialize", deserialize = "F: Deserialize<'de>"))]
+pub struct KvStore<F> {
+    depth: usize,
+    /// Sorted by key.
+    entries: Vec<(F, F)>,
+    /// The nodes of the tree by level, from the leaves to the root. Each level stops at its last
+    /// non-empty node.
+    levels: Vec<Vec<[F; CHUNK]>>,
+    /// The root of an empty subtree of each height.
+    empty_roots: Vec<[F; CHUNK]>,
+}
+
+/// One level of a [KvStore] Merkle proof, from the leaf up.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KvStoreProofStep<F> {
+    pub sibling: [F; CHUNK],
+    /// Whether the node on the path is the right child of its parent.
+    pub is_right: bool,
+}
+
+impl<F: PrimeField32> KvStore<F> {
+    /// Panics if a key appears twice or if the pairs do not fit in a tree of depth `depth`.
+    pub fn new(depth: usize, entries: impl IntoIterator<Item = (F, F)>) -> Self {
+        let mut entries: Vec<_> = entries.into_iter().collect();
+        entries.sort_by_key(|&(key, _)| key);
+        assert!(
+            entries.windows(2).all(|pair| pair[0].0 != pair[1].0),
+            "duplicate key in key-value store"
+        );
+        assert!(
+            depth < usize::BITS as usize && entries.len() <= 1 << depth,
+            "too many key-value pairs for depth {depth}"
+        );
+
+        let hasher = vm_poseidon2_hasher();
+        let mut empty_roots = vec![[F::ZERO; CHUNK]];
+        let mut levels = vec![entries
+            .iter()
+            .map(|&(key, value)| hasher.hash(&leaf_input(key, value)))
+            .collect::<Vec<_>>()];
+        for height in 0..depth {
+            let empty = empty_roots[height];
+            let nodes = &levels[height];
+            let parents = nodes
+                .chunks(2)
+                .map(|pair| hasher.compress(&pair[0], pair.get(1).unwrap_or(&empty)))
+                .collect();
+            levels.push(parents);
+            empty_roots.push(hasher.compress(&empty, &empty));
+        }
+        Self {
+            depth,
+            entries,
+            levels,
+            empty_roots,
+        }
+    }
+
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    pub fn root(&self) -> [F; CHUNK] {
+        self.levels[self.depth]
+            .first()
+            .copied()
+            .unwrap_or(self.empty_roots[self.depth])
+    }
+
+    /// The value of `key` and the Merkle proof of the pair, or `None` if there is no such key.
+    pub fn lookup(&self, key: F) -> Option<(F, Vec<KvStoreProofStep<F>>)> {
+        let index = self
+            .entries
+            .binary_search_by_key(&key, |&(key, _)| key)
+            .ok()?;
+        let proof = (0..self.depth)
+            .map(|height| {
+                let node = index >> height;
+                KvStoreProofStep {
+                    sibling: self.levels[height]
+                        .get(node ^ 1)
+                        .copied()
+                        .unwrap_or(self.empty_roots[height]),
+                    is_right: node & 1 == 1,
+                }
+            })
+            .collect();
+        Some((self.entries[index].1, proof))
+    }
+}
+
+/// The leaf of a pair is the hash of this chunk.
+pub(crate) fn leaf_input<F: PrimeField32>(key: F, value: F) -> [F; CHUNK] {
+    let mut input = [F::ZERO; CHUNK];
+    input[0] = key;
+    input[1] = value;
+    input
+}
@@ -0,0 +1,85 @@
+use openvm_stark_backend::p3_field::AbstractField;
+use openvm_stark_sdk::p3_baby_bear::BabyBear;
+
+use super::{leaf_input, KvStore};
+use crate::{
+    arch::{
+        hasher::{poseidon2::vm_poseidon2_hasher, Hasher},
+        SystemConfig, VmConfig,
+    },
+    system::memory::CHUNK,
+};
+
+type F = BabyBear;
+
+fn store(depth: usize, num_entries: u32) -> KvStore<F> {
+    KvStore::new(
+        depth,
+        (0..num_entries).rev().map(|i| {
+            (
+                F::from_canonical_u32(3 * i + 1),
+                F::from_canonical_u32(i * i),
+            )
+        }),
+    )
+}
+
+#[test]
+fn test_kv_store_lookup_proves_root() {
+    let hasher = vm_poseidon2_hasher();
+    for (depth, num_entries) in [(1, 1), (1, 2), (3, 5), (4, 16)] {
+        let store = store(depth, num_entries);
+        for i in 0..num_entries {
+            let key = F::from_canonical_u32(3 * i + 1);
+            let (value, proof) = store.lookup(key).unwrap();
+            assert_eq!(value, F::from_canonical_u32(i * i));
+            assert_eq!(proof.len(), depth);
+
+            let mut node = hasher.hash(&leaf_input(key, value));
+            for step in proof {
+                node = if step.is_right {
+                    hasher.compress(&step.sibling, &node)
+                } else {
+                    hasher.compress(&node, &step.sibling)
+                };
+            }
+            assert_eq!(node, store.root());
+        }
+        assert!(store.lookup(F::ZERO).is_none());
+    }
+}
+
+#[test]
+fn test_kv_store_empty_root() {
+    let hasher = vm_poseidon2_hasher();
+    let empty = store(2, 0);
+    let leaf = [F::ZERO; CHUNK];
+    let parent = hasher.compress(&leaf, &leaf);
+    assert_eq!(empty.root(), hasher.compress(&parent, &parent));
+    assert_ne!(store(2, 1).root(), empty.root());
+}
+
+#[test]
+#[should_panic(expected = "duplicate key")]
+fn test_kv_store_duplicate_key() {
+    KvStore::new(2, [(F::ONE, F::ONE), (F::ONE, F::TWO)]);
+}
+
+#[test]
+#[should_panic(expected = "too many key-value pairs")]
+fn test_kv_store_too_many_pairs() {
+    store(2, 5);
+}
+
+#[test]
+fn test_kv_store_air_id() {
+    let config = SystemConfig::default()
+        .with_continuations()
+        .with_kv_store(2);
+    let chip_complex = VmConfig::<F>::create_chip_complex(&config).unwrap();
+    let air_id = config.kv_store_air_id(chip_complex.num_airs()).unwrap();
+    assert!(chip_complex.air_names()[air_id].contains("KvStoreCoreAir"));
+
+    let config = SystemConfig::default().with_continuations();
+    assert_eq!(config.kv_store_air_id(10), None);
+}
@@ -35,12 +35,24 @@ pub struct AccessAdapterInventory<F> {
 }
 
 impl<F> AccessAdapterInventory<F> {
+    /// Creates access adapters for every block size `2, 4, ..., max_access_adapter_n`.
+    ///
+    /// Each adapter proves that an aligned block of size `N` is the concatenation of its two
+    /// aligned halves, so block sizes are restricted to powers of two. Accesses of other widths,
+    /// such as 3 or 12 cells, should be issued as consecutive power-of-two blocks, e.g. with
+    /// [MemoryController::read_range](crate::system::memory::MemoryController::read_range).
+    ///
+    /// Panics if `max_access_adapter_n` is not a power of two.
     pub fn new(
         range_checker: Arc<VariableRangeCheckerChip>,
         memory_bus: MemoryBus,
         clk_max_bits: usize,
         max_access_adapter_n: usize,
     ) -> Self {
+        assert!(
+            max_access_adapter_n.is_power_of_two(),
+            "max_access_adapter_n must be a power of two, got {max_access_adapter_n}"
+        );
         let rc = range_checker;
         let mb = memory_bus;
         let cmb = clk_max_bits;
@@ -75,6 +87,17 @@ impl<F> AccessAdapterInventory<F> {
         debug_assert!(chip.n() == n);
         chip.add_record(record);
     }
+    /// Drops all but the first `lens[i]` records of the `i`-th access adapter chip.
+    pub fn truncate_records(&mut self, lens: &[usize]) {
+        assert_eq!(lens.len(), self.chips.len());
+        for (chip, &len) in self.chips.iter_mut().zip(lens) {
+            chip.truncate_records(len);
+        }
+    }
+    /// Iterates over the pending records of all access adapter chips.
+    pub fn records(&self) -> impl Iterator<Item = &AccessAdapterRecord<F>> {
+        self.chips.iter().flat_map(|chip| chip.records())
+    }
     pub fn get_heights(&self) -> Vec<usize> {
         self.chips
             .iter()
@@ -111,6 +134,17 @@ impl<F> AccessAdapterInventory<F> {
             .map(|chip| chip.generate_air_proof_input())
             .collect()
     }
+    /// Generates the traces of all access adapter chips in parallel, in the same order as
+    /// [Self::airs].
+    pub fn generate_traces(self) -> Vec<RowMajorMatrix<F>>
+    where
+        F: PrimeField32,
+    {
+        self.chips
+            .into_par_iter()
+            .map(|chip| chip.generate_trace())
+            .collect()
+    }
 
     fn create_access_adapter_chip<const N: usize>(
         range_checker: Arc<VariableRangeCheckerChip>,
@@ -152,6 +186,8 @@ pub struct AccessAdapterRecord<T> {
 pub trait GenericAccessAdapterChipTrait<F> {
     fn set_override_trace_heights(&mut self, overridden_height: usize);
     fn add_record(&mut self, record: AccessAdapterRecord<F>);
+    fn truncate_records(&mut self, len: usize);
+    fn records(&self) -> &[AccessAdapterRecord<F>];
     fn n(&self) -> usize;
     fn generate_trace(self) -> RowMajorMatrix<F>
     where
@@ -219,6 +255,12 @@ impl<F, const N: usize> GenericAccessAdapterChipTrait<F> for AccessAdapterChip<F
     fn add_record(&mut self, record: AccessAdapterRecord<F>) {
         self.records.push(record);
     }
+    fn truncate_records(&mut self, len: usize) {
+        self.records.truncate(len);
+    }
+    fn records(&self) -> &[AccessAdapterRecord<F>] {
+        &self.records
+    }
     fn n(&self) -> usize {
         N
     }
@@ -0,0 +1,226 @@
+use openvm_stark_backend::p3_field::PrimeField32;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::{watchpoint::WatchpointHit, MemoryController};
+use crate::system::memory::OpType;
+
+/// A single `read` or `write` issued to the [MemoryController].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemoryAccessEntry<F> {
+    pub op: OpType,
+    pub address_space: u32,
+    pub pointer: u32,
+    /// The timestamp at which the access happened.
+    pub timestamp: u32,
+    /// The data read or written. Its length is the block size of the access.
+    pub data: Vec<F>,
+}
+
+/// An ordered log of every access made through [MemoryController::read] and
+/// [MemoryController::write] while recording was enabled.
+///
+/// The log can be serialized, replayed against a fresh controller with
+/// [MemoryController::replay_access_log], or compared against the log of another run.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemoryAccessLog<F> {
+    pub entries: Vec<MemoryAccessEntry<F>>,
+}
+
+impl<F> Default for MemoryAccessLog<F> {
+    fn default() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+}
+
+impl<F: PartialEq> MemoryAccessLog<F> {
+    pub fn push(&mut self, entry: MemoryAccessEntry<F>) {
+        self.entries.push(entry);
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the index of the first entry where `self` and `other` differ, or `None` if the
+    /// logs are identical.
+    pub fn first_divergence(&self, other: &Self) -> Option<usize> {
+        let common = self.entries.len().min(other.entries.len());
+        (0..common)
+            .find(|&i| self.entries[i] != other.entries[i])
+            .or((self.entries.len() != other.entries.len()).then_some(common))
+    }
+}
+
+/// Running totals of the memory traffic of a [MemoryController], as reported by
+/// [MemoryController::access_counts]. Reads of address space 0 are immediates and are not counted.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemoryAccessCounts {
+    pub cells_read: usize,
+    pub cells_written: usize,
+    /// Rows of all access adapter traces generated so far.
+    pub access_adapter_rows: usize,
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum MemoryReplayError {
+    #[error("entry {index}: timestamp {timestamp} is before current timestamp {current}")]
+    TimestampRegression {
+        index: usize,
+        timestamp: u32,
+        current: u32,
+    },
+    #[error("entry {index}: unsupported block size {len}")]
+    UnsupportedBlockSize { index: usize, len: usize },
+    #[error("entry {index}: read data does not match the recorded data")]
+    ReadMismatch { index: usize },
+}
+
+/// Binds `$N` to a `const usize` equal to `$len` for each block size supported by
+/// [MemoryController::read] and [MemoryController::write], then evaluates `$body`.
+macro_rules! with_block_size {
+    ($len:expr, $N:ident, $body:block, $unsupported:expr) => {
+        match $len {
+            1 => {
+                const $N: usize = 1;
+                $body
+            }
+            2 => {
+                const $N: usize = 2;
+                $body
+            }
+            4 => {
+                const $N: usize = 4;
+                $body
+            }
+            8 => {
+                const $N: usize = 8;
+                $body
+            }
+            16 => {
+                const $N: usize = 16;
+                $body
+            }
+            32 => {
+                const $N: usize = 32;
+                $body
+            }
+            64 => {
+                const $N: usize = 64;
+                $body
+            }
+            _ => $unsupported,
+        }
+    };
+}
+
+impl<F: PrimeField32, const CHUNK_SIZE: usize> MemoryController<F, CHUNK_SIZE> {
+    /// Starts recording every `read` and `write` into an access log. Does nothing if recording
+    /// is already enabled.
+    pub fn enable_access_log(&mut self) {
+        self.access_log.get_or_insert_with(MemoryAccessLog::default);
+    }
+
+    /// Stops recording and returns the access log recorded so far, if any.
+    pub fn take_access_log(&mut self) -> Option<MemoryAccessLog<F>> {
+        self.access_log.take()
+    }
+
+    pub fn access_log(&self) -> Option<&MemoryAccessLog<F>> {
+        self.access_log.as_ref()
+    }
+
+    /// Returns the number of cells read and written and the access adapter rows generated since
+    /// the controller was created. The counts are always kept, independent of the access log.
+    pub fn access_counts(&self) -> MemoryAccessCounts {
+        MemoryAccessCounts {
+            access_adapter_rows: self.access_adapters.get_heights().into_iter().sum(),
+            ..self.access_counts
+        }
+    }
+
+    /// Counts an access, records it in the access log, if enabled, and notifies any watchpoints it
+    /// hits.
+    pub(super) fn log_access(
+        &mut self,
+        op: OpType,
+        address_space: u32,
+        pointer: u32,
+        timestamp: u32,
+        data: &[F],
+    ) {
+        if address_space != 0 {
+            match op {
+                OpType::Read => self.access_counts.cells_read += data.len(),
+                OpType::Write => self.access_counts.cells_written += data.len(),
+            }
+        }
+        self.watchpoints.notify(&WatchpointHit {
+            op,
+            address_space,
+            pointer,
+            timestamp,
+            data,
+        });
+        if let Some(log) = &mut self.access_log {
+            log.push(MemoryAccessEntry {
+                op,
+                address_space,
+                pointer,
+                timestamp,
+                data: data.to_vec(),
+            });
+        }
+    }
+
+    /// Re-executes the accesses in `log` against this controller, advancing the timestamp to
+    /// match each recorded access. Reads are checked against the recorded data.
+    ///
+    /// This is intended to be called on a freshly constructed controller with the same
+    /// configuration and initial memory as the one that produced the log.
+    pub fn replay_access_log(&mut self, log: &MemoryAccessLog<F>) -> Result<(), MemoryReplayError> {
+        for (index, entry) in log.entries.iter().enumerate() {
+            let current = self.timestamp();
+            if entry.timestamp < current {
+                return Err(MemoryReplayError::TimestampRegression {
+                    index,
+                    timestamp: entry.timestamp,
+                    current,
+                });
+            }
+            self.increase_timestamp_to(entry.timestamp);
+
+            let address_space = F::from_canonical_u32(entry.address_space);
+            let pointer = F::from_canonical_u32(entry.pointer);
+            with_block_size!(
+                entry.data.len(),
+                N,
+                {
+                    match entry.op {
+                        OpType::Read => {
+                            let record = self.read::<N>(address_space, pointer);
+                            if record.data[..] != entry.data[..] {
+                                return Err(MemoryReplayError::ReadMismatch { index });
+                            }
+                        }
+                        OpType::Write => {
+                            let data: [F; N] = entry.data[..].try_into().unwrap();
+                            self.write(address_space, pointer, data);
+                        }
+                    }
+                },
+                return Err(MemoryReplayError::UnsupportedBlockSize {
+                    index,
+                    len: entry.data.len(),
+                })
+            );
+        }
+        Ok(())
+    }
+}
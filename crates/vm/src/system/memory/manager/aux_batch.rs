@@ -0,0 +1,119 @@
+use openvm_circuit_primitives::assert_less_than::LessThanAuxCols;
+use openvm_stark_backend::{
+    p3_field::PrimeField32,
+    p3_maybe_rayon::prelude::{IntoParallelRefIterator, ParallelIterator},
+};
+use rustc_hash::FxHashMap;
+
+use super::MemoryAuxColsFactory;
+use crate::system::memory::{
+    offline_checker::{MemoryBaseAuxCols, MemoryReadAuxCols, MemoryWriteAuxCols, AUX_LEN},
+    MemoryReadRecord, MemoryWriteRecord, OpType,
+};
+
+/// Range checks `(value, max_bits)` made while decomposing one timestamp difference.
+type LimbChecks = [(u32, usize); AUX_LEN];
+
+impl<F: PrimeField32> MemoryAuxColsFactory<F> {
+    /// Batched version of [Self::make_read_aux_cols]: makes the auxiliary columns of all `reads`,
+    /// in order, in one parallel pass.
+    ///
+    /// Unlike the per-record functions, the range checks are tallied locally and added to the
+    /// shared range checker once per distinct value, which avoids contention on its counters when
+    /// chips generate their traces in parallel.
+    pub fn make_read_aux_cols_batch<const N: usize>(
+        &self,
+        reads: &[MemoryReadRecord<F, N>],
+    ) -> Vec<MemoryReadAuxCols<F, N>> {
+        let (cols, checks): (Vec<_>, Vec<_>) = reads
+            .par_iter()
+            .map(|read| {
+                assert!(
+                    !read.address_space.is_zero(),
+                    "cannot make `MemoryReadAuxCols` for address space 0"
+                );
+                let (base, checks) =
+                    self.decompose_timestamp_lt(read.prev_timestamp, read.timestamp);
+                self.check_aux_cols(
+                    OpType::Read,
+                    read.address_space,
+                    read.pointer,
+                    read.timestamp,
+                    &read.data,
+                    &base,
+                    &[],
+                );
+                (MemoryReadAuxCols::from_base(base), checks)
+            })
+            .unzip();
+        self.add_range_checks(checks);
+        cols
+    }
+
+    /// Batched version of [Self::make_write_aux_cols]. See [Self::make_read_aux_cols_batch].
+    pub fn make_write_aux_cols_batch<const N: usize>(
+        &self,
+        writes: &[MemoryWriteRecord<F, N>],
+    ) -> Vec<MemoryWriteAuxCols<F, N>> {
+        let (cols, checks): (Vec<_>, Vec<_>) = writes
+            .par_iter()
+            .map(|write| {
+                let (base, checks) =
+                    self.decompose_timestamp_lt(write.prev_timestamp, write.timestamp);
+                self.check_aux_cols(
+                    OpType::Write,
+                    write.address_space,
+                    write.pointer,
+                    write.timestamp,
+                    &write.data,
+                    &base,
+                    &write.prev_data,
+                );
+                (MemoryWriteAuxCols::from_base(base, write.prev_data), checks)
+            })
+            .unzip();
+        self.add_range_checks(checks);
+        cols
+    }
+
+    /// Makes the base auxiliary columns like [Self::generate_base_aux], but returns the range
+    /// checks instead of adding them to the range checker.
+    fn decompose_timestamp_lt(
+        &self,
+        prev_timestamp: u32,
+        timestamp: u32,
+    ) -> (MemoryBaseAuxCols<F>, LimbChecks) {
+        debug_assert!(prev_timestamp < timestamp);
+        debug_assert_eq!(self.timestamp_lt_air.decomp_limbs, AUX_LEN);
+        let range_max_bits = self.range_checker.range_max_bits();
+        let mask = (1 << range_max_bits) - 1;
+
+        let mut value = timestamp - prev_timestamp - 1;
+        let mut bits_remaining = self.timestamp_lt_air.max_bits;
+        let mut decomp = [F::ZERO; AUX_LEN];
+        let mut checks = [(0, 0); AUX_LEN];
+        for (limb, check) in decomp.iter_mut().zip(checks.iter_mut()) {
+            let limb_u32 = value & mask;
+            *limb = F::from_canonical_u32(limb_u32);
+            *check = (limb_u32, bits_remaining.min(range_max_bits));
+            value >>= range_max_bits;
+            bits_remaining = bits_remaining.saturating_sub(range_max_bits);
+        }
+        debug_assert_eq!(value, 0);
+        let base = MemoryBaseAuxCols::new(
+            F::from_canonical_u32(prev_timestamp),
+            LessThanAuxCols::new(decomp),
+        );
+        (base, checks)
+    }
+
+    fn add_range_checks(&self, checks: Vec<LimbChecks>) {
+        let mut counts = FxHashMap::<(u32, usize), u32>::default();
+        for check in checks.into_iter().flatten() {
+            *counts.entry(check).or_default() += 1;
+        }
+        for ((value, max_bits), mult) in counts {
+            self.range_checker.add_count_mult(value, max_bits, mult);
+        }
+    }
+}
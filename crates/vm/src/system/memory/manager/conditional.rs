@@ -0,0 +1,56 @@
+use openvm_stark_backend::p3_field::PrimeField32;
+
+use super::{MemoryAuxColsFactory, MemoryController};
+use crate::system::memory::{
+    offline_checker::{MemoryReadAuxCols, MemoryWriteAuxCols},
+    MemoryReadRecord, MemoryWriteRecord,
+};
+
+impl<F: PrimeField32, const CHUNK_SIZE: usize> MemoryController<F, CHUNK_SIZE> {
+    /// Reads like [Self::read] if `condition` is true. Otherwise nothing is accessed and the
+    /// timestamp is not incremented, matching
+    /// [MemoryBridge::read_conditional](crate::system::memory::offline_checker::MemoryBridge::read_conditional).
+    pub fn read_conditional<const N: usize>(
+        &mut self,
+        address_space: F,
+        pointer: F,
+        condition: bool,
+    ) -> Option<MemoryReadRecord<F, N>> {
+        condition.then(|| self.read(address_space, pointer))
+    }
+
+    /// Writes like [Self::write] if `condition` is true. See [Self::read_conditional].
+    pub fn write_conditional<const N: usize>(
+        &mut self,
+        address_space: F,
+        pointer: F,
+        data: [F; N],
+        condition: bool,
+    ) -> Option<MemoryWriteRecord<F, N>> {
+        condition.then(|| self.write(address_space, pointer, data))
+    }
+}
+
+impl<F: PrimeField32> MemoryAuxColsFactory<F> {
+    /// Auxiliary columns of a read returned by [MemoryController::read_conditional], or
+    /// [MemoryReadAuxCols::disabled] if the read was skipped.
+    pub fn make_read_aux_cols_conditional<const N: usize>(
+        &self,
+        read: Option<MemoryReadRecord<F, N>>,
+    ) -> MemoryReadAuxCols<F, N> {
+        read.map_or_else(MemoryReadAuxCols::disabled, |read| {
+            self.make_read_aux_cols(read)
+        })
+    }
+
+    /// Auxiliary columns of a write returned by [MemoryController::write_conditional], or
+    /// [MemoryWriteAuxCols::disabled] if the write was skipped.
+    pub fn make_write_aux_cols_conditional<const N: usize>(
+        &self,
+        write: Option<MemoryWriteRecord<F, N>>,
+    ) -> MemoryWriteAuxCols<F, N> {
+        write.map_or_else(MemoryWriteAuxCols::disabled, |write| {
+            self.make_write_aux_cols(write)
+        })
+    }
+}
@@ -0,0 +1,446 @@
+use std::{cmp::max, sync::Arc};
+
+use openvm_stark_backend::p3_field::PrimeField32;
+use parking_lot::Mutex;
+use rustc_hash::FxHashMap;
+use thiserror::Error;
+
+use super::{
+    memory::INITIAL_TIMESTAMP, MemoryAuxColsFactory, MemoryController, MemoryReadRecord,
+    MemoryWriteRecord,
+};
+use crate::system::memory::{
+    adapter::{AccessAdapterRecord, AccessAdapterRecordKind},
+    offline_checker::{MemoryBaseAuxCols, AUX_LEN},
+    OpType,
+};
+
+/// The first record that violated memory consistency, as found by
+/// [MemoryController::check_consistency].
+#[derive(Error, Clone, Debug, PartialEq, Eq)]
+pub enum MemoryConsistencyError {
+    #[error(
+        "access #{index} ({op:?} of [{address_space}:{pointer}] at timestamp {timestamp}): \
+         timestamp is not after the timestamp {prev_timestamp} of the previous access"
+    )]
+    TimestampNotIncreasing {
+        index: usize,
+        op: OpType,
+        address_space: u32,
+        pointer: u32,
+        timestamp: u32,
+        prev_timestamp: u32,
+    },
+    #[error(
+        "access #{index} ({op:?} of [{address_space}:{pointer}] at timestamp {timestamp}): \
+         the record has prev_timestamp {actual}, but cell [{address_space}:{cell}] was last \
+         accessed at timestamp {expected}"
+    )]
+    StalePrevTimestamp {
+        index: usize,
+        op: OpType,
+        address_space: u32,
+        pointer: u32,
+        timestamp: u32,
+        cell: u32,
+        expected: u32,
+        actual: u32,
+    },
+    /// The data of a read or the `prev_data` of a write differs from the value of the cell.
+    #[error(
+        "access #{index} ({op:?} of [{address_space}:{pointer}] at timestamp {timestamp}): \
+         the record has {actual} for cell [{address_space}:{cell}], but the access at timestamp \
+         {prev_timestamp} left {expected}"
+    )]
+    StaleValue {
+        index: usize,
+        op: OpType,
+        address_space: u32,
+        pointer: u32,
+        timestamp: u32,
+        cell: u32,
+        expected: u32,
+        actual: u32,
+        prev_timestamp: u32,
+    },
+    #[error(
+        "access adapter record ({kind:?} of [{address_space}:{pointer}..{pointer}+{len}] at \
+         timestamp {timestamp}) does not match the cells it covers"
+    )]
+    AdapterRecordMismatch {
+        kind: AccessAdapterRecordKind,
+        address_space: u32,
+        pointer: u32,
+        len: usize,
+        timestamp: u32,
+    },
+    /// Auxiliary columns were made for an access that was not replayed, or `column` of them
+    /// does not match the replayed access.
+    #[error(
+        "auxiliary columns of the {op:?} of [{address_space}:{pointer}] at timestamp \
+         {timestamp}: {column} does not match the replayed access"
+    )]
+    AuxColsMismatch {
+        op: OpType,
+        address_space: u32,
+        pointer: u32,
+        timestamp: u32,
+        column: &'static str,
+    },
+}
+
+/// An access as replayed by the [ConsistencyChecker], with values as canonical `u32`s.
+#[derive(Clone, Debug)]
+struct ReplayedAccess {
+    op: OpType,
+    address_space: u32,
+    pointer: u32,
+    prev_timestamp: u32,
+    data: Vec<u32>,
+    /// Empty for reads.
+    prev_data: Vec<u32>,
+}
+
+/// Software offline memory checker. Replays the access and access adapter records produced by
+/// the controller against a shadow memory of its own, which keeps the value and timestamp of
+/// every cell, and records the first record that contradicts it.
+#[derive(Clone, Debug, Default)]
+pub(super) struct ConsistencyChecker {
+    num_accesses: usize,
+    last_timestamp: Option<u32>,
+    /// `(address_space, pointer) -> (value, timestamp)`. Cells not in the map hold zero at
+    /// [INITIAL_TIMESTAMP].
+    cells: FxHashMap<(u32, u32), (u32, u32)>,
+    /// The replayed accesses by timestamp, against which auxiliary columns are checked.
+    accesses: FxHashMap<u32, ReplayedAccess>,
+    first_violation: Option<MemoryConsistencyError>,
+}
+
+impl ConsistencyChecker {
+    pub(super) fn replay_read<F: PrimeField32, const N: usize>(
+        &mut self,
+        record: &MemoryReadRecord<F, N>,
+    ) {
+        self.replay_access(
+            OpType::Read,
+            record.address_space.as_canonical_u32(),
+            record.pointer.as_canonical_u32(),
+            record.timestamp,
+            record.prev_timestamp,
+            &record.data,
+            &[],
+        );
+    }
+
+    pub(super) fn replay_write<F: PrimeField32, const N: usize>(
+        &mut self,
+        record: &MemoryWriteRecord<F, N>,
+    ) {
+        self.replay_access(
+            OpType::Write,
+            record.address_space.as_canonical_u32(),
+            record.pointer.as_canonical_u32(),
+            record.timestamp,
+            record.prev_timestamp,
+            &record.data,
+            &record.prev_data,
+        );
+    }
+
+    /// Replays access adapter records in the order they were made. A split requires the block to
+    /// hold its data at its timestamp; a merge requires each half to hold its data at its own
+    /// timestamp, and moves the whole block to the later one.
+    pub(super) fn replay_adapter_records<F: PrimeField32>(
+        &mut self,
+        records: &[AccessAdapterRecord<F>],
+    ) {
+        for record in records {
+            let address_space = record.address_space.as_canonical_u32();
+            let pointer = record.start_index.as_canonical_u32();
+            let len = record.data.len();
+            let consistent = record.data.iter().enumerate().all(|(i, value)| {
+                let expected_timestamp = match record.kind {
+                    AccessAdapterRecordKind::Split => record.timestamp,
+                    AccessAdapterRecordKind::Merge {
+                        left_timestamp,
+                        right_timestamp,
+                    } => {
+                        if i < len / 2 {
+                            left_timestamp
+                        } else {
+                            right_timestamp
+                        }
+                    }
+                };
+                self.cell(address_space, pointer + i as u32)
+                    == (value.as_canonical_u32(), expected_timestamp)
+            });
+            let consistent = consistent
+                && match record.kind {
+                    AccessAdapterRecordKind::Split => true,
+                    AccessAdapterRecordKind::Merge {
+                        left_timestamp,
+                        right_timestamp,
+                    } => record.timestamp == max(left_timestamp, right_timestamp),
+                };
+            if !consistent {
+                self.report(MemoryConsistencyError::AdapterRecordMismatch {
+                    kind: record.kind.clone(),
+                    address_space,
+                    pointer,
+                    len,
+                    timestamp: record.timestamp,
+                });
+            }
+            if matches!(record.kind, AccessAdapterRecordKind::Merge { .. }) {
+                for i in 0..len as u32 {
+                    let (value, _) = self.cell(address_space, pointer + i);
+                    self.cells
+                        .insert((address_space, pointer + i), (value, record.timestamp));
+                }
+            }
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn replay_access<F: PrimeField32>(
+        &mut self,
+        op: OpType,
+        address_space: u32,
+        pointer: u32,
+        timestamp: u32,
+        prev_timestamp: u32,
+        data: &[F],
+        prev_data: &[F],
+    ) {
+        let index = self.num_accesses;
+        self.num_accesses += 1;
+        if let Some(last_timestamp) = self.last_timestamp {
+            if timestamp <= last_timestamp {
+                self.report(MemoryConsistencyError::TimestampNotIncreasing {
+                    index,
+                    op,
+                    address_space,
+                    pointer,
+                    timestamp,
+                    prev_timestamp: last_timestamp,
+                });
+            }
+        }
+        self.last_timestamp = Some(timestamp);
+
+        let data: Vec<u32> = data.iter().map(|value| value.as_canonical_u32()).collect();
+        let prev_data: Vec<u32> = prev_data
+            .iter()
+            .map(|value| value.as_canonical_u32())
+            .collect();
+        // Reads of address space 0 are immediates, not memory accesses.
+        if address_space != 0 {
+            for (i, &value) in data.iter().enumerate() {
+                let cell = pointer + i as u32;
+                let (cell_value, cell_timestamp) = self.cell(address_space, cell);
+                if cell_timestamp != prev_timestamp {
+                    self.report(MemoryConsistencyError::StalePrevTimestamp {
+                        index,
+                        op,
+                        address_space,
+                        pointer,
+                        timestamp,
+                        cell,
+                        expected: cell_timestamp,
+                        actual: prev_timestamp,
+                    });
+                }
+                let actual = match op {
+                    OpType::Read => value,
+                    OpType::Write => prev_data[i],
+                };
+                if cell_value != actual {
+                    self.report(MemoryConsistencyError::StaleValue {
+                        index,
+                        op,
+                        address_space,
+                        pointer,
+                        timestamp,
+                        cell,
+                        expected: cell_value,
+                        actual,
+                        prev_timestamp: cell_timestamp,
+                    });
+                }
+                self.cells.insert((address_space, cell), (value, timestamp));
+            }
+        }
+        self.accesses.insert(
+            timestamp,
+            ReplayedAccess {
+                op,
+                address_space,
+                pointer,
+                prev_timestamp,
+                data,
+                prev_data,
+            },
+        );
+    }
+
+    /// Checks the flattened base auxiliary columns and the `prev_data` (empty for reads) made for
+    /// the access at `timestamp` against the replayed access.
+    #[allow(clippy::too_many_arguments)]
+    fn check_aux_cols(
+        &mut self,
+        op: OpType,
+        address_space: u32,
+        pointer: u32,
+        timestamp: u32,
+        data: &[u32],
+        base_aux: &[u32],
+        prev_data: &[u32],
+        range_max_bits: usize,
+    ) {
+        let column = match self.accesses.get(&timestamp) {
+            Some(access)
+                if access.op == op
+                    && access.address_space == address_space
+                    && access.pointer == pointer
+                    && access.data == data =>
+            {
+                let lower_decomp = &base_aux[1..1 + AUX_LEN];
+                let diff = lower_decomp
+                    .iter()
+                    .rev()
+                    .fold(0u64, |acc, &limb| (acc << range_max_bits) + limb as u64);
+                if base_aux[0] != access.prev_timestamp {
+                    Some("prev_timestamp")
+                } else if lower_decomp.iter().any(|&limb| limb >> range_max_bits != 0)
+                    || diff + 1 + access.prev_timestamp as u64 != timestamp as u64
+                {
+                    Some("clk_lt_aux")
+                } else if access.prev_data != prev_data {
+                    Some("prev_data")
+                } else {
+                    None
+                }
+            }
+            _ => Some("record"),
+        };
+        if let Some(column) = column {
+            self.report(MemoryConsistencyError::AuxColsMismatch {
+                op,
+                address_space,
+                pointer,
+                timestamp,
+                column,
+            });
+        }
+    }
+
+    /// Records values written outside of the constrained accesses, which later reads may observe.
+    /// The timestamps of the cells are unchanged.
+    pub(super) fn overwrite<F: PrimeField32>(
+        &mut self,
+        address_space: u32,
+        pointer: u32,
+        values: &[F],
+    ) {
+        for (i, value) in values.iter().enumerate() {
+            let (_, timestamp) = self.cell(address_space, pointer + i as u32);
+            self.cells.insert(
+                (address_space, pointer + i as u32),
+                (value.as_canonical_u32(), timestamp),
+            );
+        }
+    }
+
+    fn cell(&self, address_space: u32, pointer: u32) -> (u32, u32) {
+        self.cells
+            .get(&(address_space, pointer))
+            .copied()
+            .unwrap_or((0, INITIAL_TIMESTAMP))
+    }
+
+    fn report(&mut self, violation: MemoryConsistencyError) {
+        self.first_violation.get_or_insert(violation);
+    }
+}
+
+impl<F: PrimeField32, const CHUNK_SIZE: usize> MemoryController<F, CHUNK_SIZE> {
+    /// Starts checking every subsequent record in software: the records of reads, writes and
+    /// access adapters are replayed against a shadow memory, which starts out as the current
+    /// memory, and the auxiliary columns the chips make from the records are checked against the
+    /// replayed accesses. Does nothing if checking is already enabled.
+    ///
+    /// Timestamps must strictly increase, every record must agree with the value and timestamp
+    /// the previous access left in each cell, and the auxiliary columns must agree with the
+    /// record of their access. Inconsistencies otherwise only surface as unbalanced memory bus
+    /// interactions when the proof is verified. While checking is enabled,
+    /// [Self::finalize] and [Self::generate_air_proof_inputs] panic with a report of the first
+    /// violating record. With the `memory-self-check` feature, checking is enabled on
+    /// construction.
+    ///
+    /// Panics if called after the first access.
+    pub fn enable_consistency_check(&mut self) {
+        if self.consistency_checker.is_none() {
+            assert!(
+                self.timestamp() <= INITIAL_TIMESTAMP + 1,
+                "Cannot enable the consistency check after the first access"
+            );
+            self.consistency_checker = Some(Arc::new(Mutex::new(self.new_consistency_checker())));
+        }
+    }
+
+    /// Returns the first record that violated consistency since checking was enabled. Always
+    /// succeeds if checking is disabled.
+    pub fn check_consistency(&self) -> Result<(), MemoryConsistencyError> {
+        match self
+            .consistency_checker
+            .as_ref()
+            .and_then(|checker| checker.lock().first_violation.clone())
+        {
+            Some(violation) => Err(violation),
+            None => Ok(()),
+        }
+    }
+
+    /// A checker whose shadow memory holds the current values of the memory. Timestamps are only
+    /// correct before the first access.
+    pub(super) fn new_consistency_checker(&self) -> ConsistencyChecker {
+        let mut checker = ConsistencyChecker::default();
+        for (&(address_space, pointer), values) in &self.memory.equipartition::<1>() {
+            checker.overwrite(address_space, pointer, values);
+        }
+        checker
+    }
+}
+
+impl<F: PrimeField32> MemoryAuxColsFactory<F> {
+    /// Checks the auxiliary columns made for the access at `timestamp` against the access replayed
+    /// by the consistency checker, if checking is enabled. `prev_data` is empty for reads.
+    #[allow(clippy::too_many_arguments)]
+    pub(super) fn check_aux_cols(
+        &self,
+        op: OpType,
+        address_space: F,
+        pointer: F,
+        timestamp: u32,
+        data: &[F],
+        base: &MemoryBaseAuxCols<F>,
+        prev_data: &[F],
+    ) {
+        if let Some(checker) = &self.consistency_checker {
+            let canonical = |values: &[F]| -> Vec<u32> {
+                values.iter().map(|v| v.as_canonical_u32()).collect()
+            };
+            checker.lock().check_aux_cols(
+                op,
+                address_space.as_canonical_u32(),
+                pointer.as_canonical_u32(),
+                timestamp,
+                &canonical(data),
+                &canonical(&base.flatten()),
+                &canonical(prev_data),
+                self.range_checker.range_max_bits(),
+            );
+        }
+    }
+}
@@ -28,9 +28,22 @@ impl MemoryDimensions {
 
 impl MemoryConfig {
     pub fn memory_dimensions(&self) -> MemoryDimensions {
+        self.memory_dimensions_for_chunk(CHUNK)
+    }
+
+    /// Dimensions of the persistent memory Merkle tree when each leaf holds `chunk` cells.
+    ///
+    /// Panics if `chunk` is not a power of two or does not fit in an address space.
+    pub fn memory_dimensions_for_chunk(&self, chunk: usize) -> MemoryDimensions {
+        let chunk_bits = log2_strict_usize(chunk);
+        assert!(
+            chunk_bits <= self.pointer_max_bits,
+            "chunk size {chunk} exceeds address space size 2^{}",
+            self.pointer_max_bits
+        );
         MemoryDimensions {
             as_height: self.as_height,
-            address_height: self.pointer_max_bits - log2_strict_usize(CHUNK),
+            address_height: self.pointer_max_bits - chunk_bits,
             as_offset: self.as_offset,
         }
     }
@@ -0,0 +1,112 @@
+use std::collections::BTreeMap;
+
+use openvm_stark_backend::{p3_field::PrimeField32, ChipUsageGetter};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    MemoryController, MemoryInterface, MemoryTraceHeights, PersistentMemoryTraceHeights,
+    VolatileMemoryTraceHeights,
+};
+
+/// Counts of memory accesses by block size, for [MemoryController::estimate_trace_heights].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemoryAccessProfile {
+    /// Block size -> number of reads of that size.
+    pub reads: BTreeMap<usize, usize>,
+    /// Block size -> number of writes of that size.
+    pub writes: BTreeMap<usize, usize>,
+    /// Number of distinct cells touched by the accesses, if known. Otherwise every access is
+    /// assumed to touch new cells.
+    pub touched_cells: Option<usize>,
+}
+
+impl MemoryAccessProfile {
+    pub fn add_reads(&mut self, block_size: usize, count: usize) -> &mut Self {
+        *self.reads.entry(block_size).or_default() += count;
+        self
+    }
+
+    pub fn add_writes(&mut self, block_size: usize, count: usize) -> &mut Self {
+        *self.writes.entry(block_size).or_default() += count;
+        self
+    }
+
+    fn accesses(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.reads
+            .iter()
+            .chain(self.writes.iter())
+            .map(|(&block_size, &count)| (block_size, count))
+    }
+
+    fn touched_cells(&self) -> usize {
+        self.touched_cells.unwrap_or_else(|| {
+            self.accesses()
+                .map(|(block_size, count)| block_size * count)
+                .sum()
+        })
+    }
+}
+
+impl<F: PrimeField32, const CHUNK_SIZE: usize> MemoryController<F, CHUNK_SIZE> {
+    /// Upper bounds on the memory trace heights, before rounding, after the accesses of `profile`
+    /// are made on top of the accesses so far and memory is finalized.
+    ///
+    /// Nothing is executed, so segmentation can check whether the next accesses fit in the
+    /// current segment. The bounds assume the worst case for every access: each access of size
+    /// `N` may merge its cells up from single cells and split a block of the largest adapter size
+    /// down to `N`, and finalization may split or merge every touched cell back to the
+    /// boundary block size.
+    pub fn estimate_trace_heights(&self, profile: &MemoryAccessProfile) -> MemoryTraceHeights {
+        let touched_cells = profile.touched_cells();
+        let access_adapters = self
+            .access_adapters
+            .get_heights()
+            .into_iter()
+            .enumerate()
+            .map(|(i, height)| {
+                let adapter_n = 1 << (i + 1);
+                let accesses: usize = profile
+                    .accesses()
+                    .map(|(block_size, count)| {
+                        let records = if adapter_n <= block_size {
+                            block_size / adapter_n
+                        } else {
+                            1
+                        };
+                        records * count
+                    })
+                    .sum();
+                height + accesses + touched_cells.div_ceil(adapter_n)
+            })
+            .collect();
+
+        match &self.interface_chip {
+            MemoryInterface::Volatile { boundary_chip } => {
+                MemoryTraceHeights::Volatile(VolatileMemoryTraceHeights {
+                    boundary: boundary_chip.current_trace_height() + touched_cells,
+                    access_adapters,
+                })
+            }
+            MemoryInterface::Persistent {
+                boundary_chip,
+                merkle_chip,
+                ..
+            } => {
+                let touched_labels = boundary_chip.current_trace_height() / 2 + touched_cells;
+                let overall_height = self
+                    .mem_config
+                    .memory_dimensions_for_chunk(CHUNK_SIZE)
+                    .overall_height();
+                // Each level of the tree has at most one touched node per touched leaf.
+                let nonleaves: usize = (0..overall_height)
+                    .map(|level| touched_labels.max(1).min(1 << level))
+                    .sum();
+                MemoryTraceHeights::Persistent(PersistentMemoryTraceHeights {
+                    boundary: 2 * touched_labels,
+                    merkle: merkle_chip.current_trace_height().max(2 * nonleaves),
+                    access_adapters,
+                })
+            }
+        }
+    }
+}
@@ -0,0 +1,31 @@
+use std::collections::VecDeque;
+
+use openvm_stark_backend::p3_field::PrimeField32;
+
+use super::MemoryController;
+use crate::system::memory::MemoryWriteRangeRecord;
+
+impl<F: PrimeField32, const CHUNK_SIZE: usize> MemoryController<F, CHUNK_SIZE> {
+    /// Pops the next `len` values of `hint_stream` and writes them at `pointer` as consecutive
+    /// writes of blocks of size `N`, one per timestamp.
+    ///
+    /// This is the memory side of loading a whole page of hints at once: with `N` equal to the
+    /// chunk size, the page takes one write per chunk instead of one hint store per word. The
+    /// values are unconstrained, so the calling chip constrains each block like a hint store.
+    ///
+    /// Returns `None`, leaving the stream and memory untouched, if the stream has fewer than
+    /// `len` values. Panics if `len` is not a multiple of `N`.
+    pub fn write_hints<const N: usize>(
+        &mut self,
+        address_space: F,
+        pointer: F,
+        len: usize,
+        hint_stream: &mut VecDeque<F>,
+    ) -> Option<MemoryWriteRangeRecord<F, N>> {
+        if hint_stream.len() < len {
+            return None;
+        }
+        let data = hint_stream.drain(..len).collect();
+        Some(self.write_range(address_space, pointer, data))
+    }
+}
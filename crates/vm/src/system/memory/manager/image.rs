@@ -0,0 +1,305 @@
+use std::io::{self, Read, Write};
+
+use openvm_stark_backend::p3_field::PrimeField32;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::{Equipartition, TimestampedEquipartition, TimestampedValues};
+
+/// Version of the [MemoryPartitionImage] formats. Bumped on every incompatible change.
+pub const MEMORY_PARTITION_FORMAT_VERSION: u32 = 1;
+
+const MAGIC: [u8; 4] = *b"OVMP";
+
+/// A versioned, self-describing copy of an [Equipartition] or [TimestampedEquipartition].
+///
+/// The image can be persisted with serde or with the compact binary format of
+/// [Self::write_binary], so that e.g. the final memory of one run can be loaded as the initial
+/// memory of a later run in another process.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemoryPartitionImage<F> {
+    pub version: u32,
+    /// The number of cells in each block, `N` of the partition.
+    pub block_size: usize,
+    /// Whether the blocks carry timestamps, i.e. the image holds a [TimestampedEquipartition].
+    pub timestamped: bool,
+    /// Blocks sorted by `(address_space, label)`.
+    pub blocks: Vec<PartitionBlock<F>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PartitionBlock<F> {
+    pub address_space: u32,
+    pub label: u32,
+    /// Zero if the image is not timestamped.
+    pub timestamp: u32,
+    pub values: Vec<F>,
+}
+
+#[derive(Error, Debug)]
+pub enum MemoryPartitionImageError {
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+    #[error("not a memory partition image")]
+    BadMagic,
+    #[error("unsupported format version {0}, expected {MEMORY_PARTITION_FORMAT_VERSION}")]
+    UnsupportedVersion(u32),
+    #[error("image has block size {actual}, expected {expected}")]
+    BlockSizeMismatch { expected: usize, actual: usize },
+    #[error("block ({address_space}, {label}) has {len} values, expected {block_size}")]
+    BlockLength {
+        address_space: u32,
+        label: u32,
+        len: usize,
+        block_size: usize,
+    },
+    #[error("image has no timestamps")]
+    NotTimestamped,
+    #[error("value {0} is not a canonical field element")]
+    NonCanonical(u32),
+}
+
+impl<F: PrimeField32> MemoryPartitionImage<F> {
+    pub fn from_equipartition<const N: usize>(memory: &Equipartition<F, N>) -> Self {
+        Self {
+            version: MEMORY_PARTITION_FORMAT_VERSION,
+            block_size: N,
+            timestamped: false,
+            blocks: memory
+                .iter()
+                .map(|(&(address_space, label), values)| PartitionBlock {
+                    address_space,
+                    label,
+                    timestamp: 0,
+                    values: values.to_vec(),
+                })
+                .collect(),
+        }
+    }
+
+    pub fn from_timestamped_equipartition<const N: usize>(
+        memory: &TimestampedEquipartition<F, N>,
+    ) -> Self {
+        Self {
+            version: MEMORY_PARTITION_FORMAT_VERSION,
+            block_size: N,
+            timestamped: true,
+            blocks: memory
+                .iter()
+                .map(|(&(address_space, label), block)| PartitionBlock {
+                    address_space,
+                    label,
+                    timestamp: block.timestamp,
+                    values: block.values.to_vec(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Converts the image back into an equipartition, dropping timestamps if there are any.
+    pub fn into_equipartition<const N: usize>(
+        self,
+    ) -> Result<Equipartition<F, N>, MemoryPartitionImageError> {
+        self.validate::<N>()?;
+        Ok(self
+            .blocks
+            .into_iter()
+            .map(|block| {
+                let values = block.values.try_into().unwrap();
+                ((block.address_space, block.label), values)
+            })
+            .collect())
+    }
+
+    pub fn into_timestamped_equipartition<const N: usize>(
+        self,
+    ) -> Result<TimestampedEquipartition<F, N>, MemoryPartitionImageError> {
+        self.validate::<N>()?;
+        if !self.timestamped {
+            return Err(MemoryPartitionImageError::NotTimestamped);
+        }
+        Ok(self
+            .blocks
+            .into_iter()
+            .map(|block| {
+                let values = block.values.try_into().unwrap();
+                (
+                    (block.address_space, block.label),
+                    TimestampedValues {
+                        timestamp: block.timestamp,
+                        values,
+                    },
+                )
+            })
+            .collect())
+    }
+
+    fn validate<const N: usize>(&self) -> Result<(), MemoryPartitionImageError> {
+        if self.version != MEMORY_PARTITION_FORMAT_VERSION {
+            return Err(MemoryPartitionImageError::UnsupportedVersion(self.version));
+        }
+        if self.block_size != N {
+            return Err(MemoryPartitionImageError::BlockSizeMismatch {
+                expected: N,
+                actual: self.block_size,
+            });
+        }
+        match self.blocks.iter().find(|block| block.values.len() != N) {
+            Some(block) => Err(MemoryPartitionImageError::BlockLength {
+                address_space: block.address_space,
+                label: block.label,
+                len: block.values.len(),
+                block_size: N,
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Writes the image in a compact little-endian binary format: a header with the magic bytes,
+    /// the version, the block size, the timestamp flag and the number of blocks, followed by
+    /// each block as its address space, label, timestamp (only if timestamped) and values.
+    pub fn write_binary(&self, mut writer: impl Write) -> io::Result<()> {
+        writer.write_all(&MAGIC)?;
+        writer.write_all(&self.version.to_le_bytes())?;
+        writer.write_all(&(self.block_size as u32).to_le_bytes())?;
+        writer.write_all(&[self.timestamped as u8])?;
+        writer.write_all(&(self.blocks.len() as u64).to_le_bytes())?;
+        for block in &self.blocks {
+            assert_eq!(block.values.len(), self.block_size);
+            writer.write_all(&block.address_space.to_le_bytes())?;
+            writer.write_all(&block.label.to_le_bytes())?;
+            if self.timestamped {
+                writer.write_all(&block.timestamp.to_le_bytes())?;
+            }
+            for value in &block.values {
+                writer.write_all(&value.as_canonical_u32().to_le_bytes())?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads an image written by [Self::write_binary].
+    pub fn read_binary(mut reader: impl Read) -> Result<Self, MemoryPartitionImageError> {
+        let mut magic = [0u8; 4];
+        reader.read_exact(&mut magic)?;
+        if magic != MAGIC {
+            return Err(MemoryPartitionImageError::BadMagic);
+        }
+        let version = read_u32(&mut reader)?;
+        if version != MEMORY_PARTITION_FORMAT_VERSION {
+            return Err(MemoryPartitionImageError::UnsupportedVersion(version));
+        }
+        let block_size = read_u32(&mut reader)? as usize;
+        let mut timestamped = [0u8; 1];
+        reader.read_exact(&mut timestamped)?;
+        let timestamped = timestamped[0] != 0;
+        let mut num_blocks = [0u8; 8];
+        reader.read_exact(&mut num_blocks)?;
+        let num_blocks = u64::from_le_bytes(num_blocks);
+
+        let mut blocks = Vec::new();
+        for _ in 0..num_blocks {
+            let address_space = read_u32(&mut reader)?;
+            let label = read_u32(&mut reader)?;
+            let timestamp = if timestamped {
+                read_u32(&mut reader)?
+            } else {
+                0
+            };
+            let values = (0..block_size)
+                .map(|_| {
+                    let value = read_u32(&mut reader)?;
+                    if value >= F::ORDER_U32 {
+                        return Err(MemoryPartitionImageError::NonCanonical(value));
+                    }
+                    Ok(F::from_canonical_u32(value))
+                })
+                .collect::<Result<_, _>>()?;
+            blocks.push(PartitionBlock {
+                address_space,
+                label,
+                timestamp,
+                values,
+            });
+        }
+        Ok(Self {
+            version,
+            block_size,
+            timestamped,
+            blocks,
+        })
+    }
+}
+
+fn read_u32(reader: &mut impl Read) -> io::Result<u32> {
+    let mut bytes = [0u8; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use openvm_stark_backend::p3_field::AbstractField;
+    use openvm_stark_sdk::p3_baby_bear::BabyBear;
+
+    use super::{MemoryPartitionImage, MemoryPartitionImageError};
+    use crate::system::memory::{Equipartition, TimestampedEquipartition, TimestampedValues};
+
+    type F = BabyBear;
+
+    #[test]
+    fn test_memory_partition_image_roundtrip() {
+        let memory: TimestampedEquipartition<F, 4> = [
+            ((1, 0), 5, [1, 2, 3, 4]),
+            ((1, 7), 9, [0, 0, 0, 8]),
+            ((3, 2), 1, [F::ORDER_U32 - 1, 0, 1, 0]),
+        ]
+        .into_iter()
+        .map(|(label, timestamp, values)| {
+            let values = values.map(F::from_canonical_u32);
+            (label, TimestampedValues { timestamp, values })
+        })
+        .collect();
+
+        let image = MemoryPartitionImage::from_timestamped_equipartition(&memory);
+        let mut bytes = Vec::new();
+        image.write_binary(&mut bytes).unwrap();
+        let decoded = MemoryPartitionImage::<F>::read_binary(&bytes[..]).unwrap();
+        assert_eq!(decoded, image);
+        assert_eq!(
+            decoded
+                .clone()
+                .into_timestamped_equipartition::<4>()
+                .unwrap(),
+            memory
+        );
+
+        let values: Equipartition<F, 4> = memory
+            .iter()
+            .map(|(&label, block)| (label, block.values))
+            .collect();
+        assert_eq!(decoded.into_equipartition::<4>().unwrap(), values);
+
+        let image = MemoryPartitionImage::from_equipartition(&values);
+        let mut bytes = Vec::new();
+        image.write_binary(&mut bytes).unwrap();
+        let decoded = MemoryPartitionImage::<F>::read_binary(&bytes[..]).unwrap();
+        assert!(matches!(
+            decoded.clone().into_timestamped_equipartition::<4>(),
+            Err(MemoryPartitionImageError::NotTimestamped)
+        ));
+        assert!(matches!(
+            decoded.into_equipartition::<8>(),
+            Err(MemoryPartitionImageError::BlockSizeMismatch {
+                expected: 8,
+                actual: 4
+            })
+        ));
+
+        bytes[4] += 1;
+        assert!(matches!(
+            MemoryPartitionImage::<F>::read_binary(&bytes[..]),
+            Err(MemoryPartitionImageError::UnsupportedVersion(2))
+        ));
+    }
+}
@@ -1,26 +1,33 @@
+use std::sync::Arc;
+
 use openvm_stark_backend::p3_field::PrimeField32;
+use rustc_hash::FxHashSet;
 
 use crate::system::memory::{
-    merkle::{DirectCompressionBus, MemoryMerkleChip},
+    merkle::{DirectCompressionBus, MemoryMerkleBus, MemoryMerkleChip},
     persistent::PersistentBoundaryChip,
-    volatile::VolatileBoundaryChip,
+    tree::MemoryNode,
+    volatile::{TouchedAddresses, VolatileBoundaryChip},
     Equipartition, CHUNK,
 };
 
 #[allow(clippy::large_enum_variant)]
 #[derive(Debug)]
-pub enum MemoryInterface<F> {
+pub enum MemoryInterface<F, const CHUNK_SIZE: usize = CHUNK> {
     Volatile {
         boundary_chip: VolatileBoundaryChip<F>,
     },
     Persistent {
-        boundary_chip: PersistentBoundaryChip<F, CHUNK>,
-        merkle_chip: MemoryMerkleChip<CHUNK, F>,
-        initial_memory: Equipartition<F, CHUNK>,
+        boundary_chip: PersistentBoundaryChip<F, CHUNK_SIZE>,
+        merkle_chip: MemoryMerkleChip<CHUNK_SIZE, F>,
+        initial_memory: Arc<Equipartition<F, CHUNK_SIZE>>,
+        /// Merkle tree of `initial_memory`, if known. See
+        /// [super::MemoryController::set_initial_memory_tree].
+        initial_tree: Option<MemoryNode<CHUNK_SIZE, F>>,
     },
 }
 
-impl<F: PrimeField32> MemoryInterface<F> {
+impl<F: PrimeField32, const CHUNK_SIZE: usize> MemoryInterface<F, CHUNK_SIZE> {
     pub fn touch_address(&mut self, addr_space: u32, pointer: u32) {
         match self {
             MemoryInterface::Volatile { boundary_chip } => {
@@ -37,6 +44,83 @@ impl<F: PrimeField32> MemoryInterface<F> {
         }
     }
 
+    /// Touches every address in `[pointer, pointer + len)`. In persistent mode, each chunk
+    /// overlapping the range is touched once.
+    pub fn touch_range(&mut self, addr_space: u32, pointer: u32, len: u32) {
+        if len == 0 {
+            return;
+        }
+        match self {
+            MemoryInterface::Volatile { boundary_chip } => {
+                boundary_chip.touch_range(addr_space, pointer, len);
+            }
+            MemoryInterface::Persistent {
+                boundary_chip,
+                merkle_chip,
+                ..
+            } => {
+                let chunk = CHUNK_SIZE as u32;
+                let start = pointer - pointer % chunk;
+                for ptr in (start..pointer + len).step_by(CHUNK_SIZE) {
+                    boundary_chip.touch_address(addr_space, ptr);
+                    merkle_chip.touch_address(addr_space, ptr);
+                }
+            }
+        }
+    }
+
+    pub(super) fn save_touched(&self) -> TouchedSnapshot {
+        match self {
+            MemoryInterface::Volatile { boundary_chip } => {
+                TouchedSnapshot::Volatile(boundary_chip.touched_addresses().clone())
+            }
+            MemoryInterface::Persistent {
+                boundary_chip,
+                merkle_chip,
+                ..
+            } => {
+                let (touched_nodes, num_touched_nonleaves) = merkle_chip.touched_nodes();
+                TouchedSnapshot::Persistent {
+                    touched_labels: boundary_chip.touched_labels().clone(),
+                    touched_nodes: touched_nodes.clone(),
+                    num_touched_nonleaves,
+                }
+            }
+        }
+    }
+
+    pub(super) fn restore_touched(&mut self, snapshot: TouchedSnapshot) {
+        match (self, snapshot) {
+            (
+                MemoryInterface::Volatile { boundary_chip },
+                TouchedSnapshot::Volatile(touched_addresses),
+            ) => boundary_chip.set_touched_addresses(touched_addresses),
+            (
+                MemoryInterface::Persistent {
+                    boundary_chip,
+                    merkle_chip,
+                    ..
+                },
+                TouchedSnapshot::Persistent {
+                    touched_labels,
+                    touched_nodes,
+                    num_touched_nonleaves,
+                },
+            ) => {
+                boundary_chip.set_touched_labels(touched_labels);
+                merkle_chip.set_touched_nodes(touched_nodes, num_touched_nonleaves);
+            }
+            _ => panic!("Snapshot does not match the memory interface"),
+        }
+    }
+
+    pub fn merkle_bus(&self) -> Option<MemoryMerkleBus> {
+        match self {
+            MemoryInterface::Volatile { .. } => None,
+            MemoryInterface::Persistent { merkle_chip, .. } => Some(merkle_chip.air.merkle_bus),
+        }
+    }
+
     pub fn compression_bus(&self) -> Option<DirectCompressionBus> {
         match self {
             MemoryInterface::Volatile { .. } => None,
@@ -46,3 +130,14 @@ impl<F: PrimeField32> MemoryInterface<F> {
         }
     }
 }
+
+/// The addresses touched by a [MemoryInterface], saved by [MemoryInterface::save_touched].
+#[derive(Clone, Debug)]
+pub(super) enum TouchedSnapshot {
+    Volatile(TouchedAddresses),
+    Persistent {
+        touched_labels: FxHashSet<(u32, u32)>,
+        touched_nodes: FxHashSet<(usize, u32, u32)>,
+        num_touched_nonleaves: usize,
+    },
+}
@@ -1,8 +1,11 @@
-use std::{array, cmp::max, fmt::Debug};
+use std::{array, cmp::max, collections::BTreeMap, fmt::Debug};
 
-use openvm_stark_backend::p3_field::PrimeField32;
-use rustc_hash::{FxHashMap, FxHashSet};
+use openvm_stark_backend::{
+    p3_field::PrimeField32,
+    p3_maybe_rayon::prelude::{IntoParallelRefIterator, ParallelIterator, ParallelSliceMut},
+};
 
+use super::paged::PagedMap;
 use crate::system::memory::{
     adapter::{AccessAdapterRecord, AccessAdapterRecordKind},
     Equipartition, TimestampedEquipartition, TimestampedValues,
@@ -45,6 +48,76 @@ impl<T: Copy> MemoryReadRecord<T, 1> {
     }
 }
 
+/// Represents a contiguous range of memory reads, split into blocks of size `N` that are read at
+/// consecutive timestamps.
+/// Each block can be used to generate a [MemoryReadAuxCols].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MemoryReadRangeRecord<T, const N: usize> {
+    pub address_space: T,
+    pub pointer: T,
+    /// The timestamp of the first block. Block `i` is read at `start_timestamp + i`.
+    pub start_timestamp: u32,
+    pub prev_timestamps: Vec<u32>,
+    pub data: Vec<T>,
+}
+
+impl<F: PrimeField32, const N: usize> MemoryReadRangeRecord<F, N> {
+    pub fn num_blocks(&self) -> usize {
+        self.prev_timestamps.len()
+    }
+
+    /// Returns the record of the `i`-th block read.
+    pub fn block(&self, i: usize) -> MemoryReadRecord<F, N> {
+        MemoryReadRecord {
+            address_space: self.address_space,
+            pointer: self.pointer + F::from_canonical_usize(i * N),
+            timestamp: self.start_timestamp + i as u32,
+            prev_timestamp: self.prev_timestamps[i],
+            data: array::from_fn(|j| self.data[i * N + j]),
+        }
+    }
+
+    pub fn blocks(&self) -> impl Iterator<Item = MemoryReadRecord<F, N>> + '_ {
+        (0..self.num_blocks()).map(|i| self.block(i))
+    }
+}
+
+/// Represents a contiguous range of memory writes, split into blocks of size `N` that are written
+/// at consecutive timestamps.
+/// Each block can be used to generate a [MemoryWriteAuxCols].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MemoryWriteRangeRecord<T, const N: usize> {
+    pub address_space: T,
+    pub pointer: T,
+    /// The timestamp of the first block. Block `i` is written at `start_timestamp + i`.
+    pub start_timestamp: u32,
+    pub prev_timestamps: Vec<u32>,
+    pub data: Vec<T>,
+    pub prev_data: Vec<T>,
+}
+
+impl<F: PrimeField32, const N: usize> MemoryWriteRangeRecord<F, N> {
+    pub fn num_blocks(&self) -> usize {
+        self.prev_timestamps.len()
+    }
+
+    /// Returns the record of the `i`-th block written.
+    pub fn block(&self, i: usize) -> MemoryWriteRecord<F, N> {
+        MemoryWriteRecord {
+            address_space: self.address_space,
+            pointer: self.pointer + F::from_canonical_usize(i * N),
+            timestamp: self.start_timestamp + i as u32,
+            prev_timestamp: self.prev_timestamps[i],
+            data: array::from_fn(|j| self.data[i * N + j]),
+            prev_data: array::from_fn(|j| self.prev_data[i * N + j]),
+        }
+    }
+
+    pub fn blocks(&self) -> impl Iterator<Item = MemoryWriteRecord<F, N>> + '_ {
+        (0..self.num_blocks()).map(|i| self.block(i))
+    }
+}
+
 pub const INITIAL_TIMESTAMP: u32 = 0;
 
 /// (address_space, pointer)
@@ -58,10 +131,12 @@ struct BlockData {
 }
 
 /// A partition of data into blocks where each block has size a power of two.
-#[derive(Debug)]
+///
+/// Cloning is cheap: the underlying pages are shared until one of the copies writes to them.
+#[derive(Clone, Debug)]
 pub struct Memory<F> {
-    block_data: FxHashMap<Address, BlockData>,
-    data: FxHashMap<Address, F>,
+    block_data: PagedMap<BlockData>,
+    data: PagedMap<F>,
     initial_block_size: usize,
     timestamp: u32,
 }
@@ -73,8 +148,8 @@ impl<F: PrimeField32> Memory<F> {
     pub fn new<const N: usize>(initial_memory: &Equipartition<F, N>) -> Self {
         assert!(N.is_power_of_two());
 
-        let mut block_data = FxHashMap::default();
-        let mut data = FxHashMap::default();
+        let mut block_data = PagedMap::default();
+        let mut data = PagedMap::default();
         for (&(address_space, block_idx), values) in initial_memory {
             let pointer = block_idx * N as u32;
             let block = BlockData {
@@ -168,41 +243,147 @@ impl<F: PrimeField32> Memory<F> {
         (record, adapter_records)
     }
 
+    /// Writes `values` starting at the specified address space and start index, as a sequence of
+    /// blocks of size `N` at consecutive timestamps.
+    ///
+    /// Panics if the length of `values` is not a multiple of `N`.
+    pub fn write_range<const N: usize>(
+        &mut self,
+        address_space: u32,
+        pointer: u32,
+        values: Vec<F>,
+    ) -> (MemoryWriteRangeRecord<F, N>, Vec<AccessAdapterRecord<F>>) {
+        assert!(N.is_power_of_two());
+        assert_eq!(
+            values.len() % N,
+            0,
+            "range length must be a multiple of {N}"
+        );
+
+        let mut adapter_records = vec![];
+        let start_timestamp = self.timestamp;
+        let mut prev_timestamps = Vec::with_capacity(values.len() / N);
+        let mut prev_data = Vec::with_capacity(values.len());
+        for (i, block) in values.chunks_exact(N).enumerate() {
+            let block_pointer = pointer + (i * N) as u32;
+            let prev_timestamp = self.access_updating_timestamp(
+                address_space,
+                block_pointer,
+                N,
+                &mut adapter_records,
+            );
+            debug_assert!(prev_timestamp < self.timestamp);
+            prev_timestamps.push(prev_timestamp);
+            prev_data.extend(block.iter().enumerate().map(|(j, &value)| {
+                self.data
+                    .insert((address_space, block_pointer + j as u32), value)
+                    .unwrap_or(F::ZERO)
+            }));
+            self.increment_timestamp();
+        }
+
+        let record = MemoryWriteRangeRecord {
+            address_space: F::from_canonical_u32(address_space),
+            pointer: F::from_canonical_u32(pointer),
+            start_timestamp,
+            prev_timestamps,
+            data: values,
+            prev_data,
+        };
+        (record, adapter_records)
+    }
+
+    /// Reads `len` values starting at the specified address space and start index, as a sequence
+    /// of blocks of size `N` at consecutive timestamps.
+    ///
+    /// Panics if `len` is not a multiple of `N`.
+    pub fn read_range<const N: usize>(
+        &mut self,
+        address_space: u32,
+        pointer: u32,
+        len: usize,
+    ) -> (MemoryReadRangeRecord<F, N>, Vec<AccessAdapterRecord<F>>) {
+        assert!(N.is_power_of_two());
+        assert_eq!(len % N, 0, "range length must be a multiple of {N}");
+
+        let mut adapter_records = vec![];
+        let start_timestamp = self.timestamp;
+        let prev_timestamps = (0..len / N)
+            .map(|i| {
+                let prev_timestamp = self.access_updating_timestamp(
+                    address_space,
+                    pointer + (i * N) as u32,
+                    N,
+                    &mut adapter_records,
+                );
+                debug_assert!(prev_timestamp < self.timestamp);
+                self.increment_timestamp();
+                prev_timestamp
+            })
+            .collect();
+
+        let record = MemoryReadRangeRecord {
+            address_space: F::from_canonical_u32(address_space),
+            pointer: F::from_canonical_u32(pointer),
+            start_timestamp,
+            prev_timestamps,
+            data: self.range_vec(address_space, pointer, len),
+        };
+        (record, adapter_records)
+    }
+
     pub fn finalize<const N: usize>(
         &mut self,
     ) -> (TimestampedEquipartition<F, N>, Vec<AccessAdapterRecord<F>>) {
         let mut adapter_records = vec![];
 
         // First make sure the partition we maintain in self.block_data is an equipartition.
-        // Grab all aligned pointers that need to be re-accessed.
-        let to_access: FxHashSet<_> = self
+        // Grab all aligned pointers that need to be re-accessed, sorted by address space and
+        // pointer so that the partition can be built in parallel and merged in order.
+        let mut to_access: Vec<_> = self
             .block_data
             .keys()
-            .map(|&(address_space, pointer)| (address_space, (pointer / N as u32) * N as u32))
+            .map(|(address_space, pointer)| (address_space, (pointer / N as u32) * N as u32))
             .collect();
-
-        for &(address_space, pointer) in to_access.iter() {
+        to_access.par_sort_unstable();
+        to_access.dedup();
+
+        let misaligned: Vec<_> = to_access
+            .par_iter()
+            .filter(|&&(address_space, pointer)| {
+                let block = self.block_data.get(&(address_space, pointer)).unwrap();
+                block.pointer != pointer || block.size != N
+            })
+            .copied()
+            .collect();
+        // Accesses mutate the partition, so they cannot run in parallel.
+        for (address_space, pointer) in misaligned {
+            // An earlier access may already have aligned this block.
             let block = self.block_data.get(&(address_space, pointer)).unwrap();
             if block.pointer != pointer || block.size != N {
                 self.access(address_space, pointer, N, &mut adapter_records);
             }
         }
 
-        let mut equipartition = TimestampedEquipartition::<F, N>::new();
-        for (address_space, pointer) in to_access {
-            let block = self.block_data.get(&(address_space, pointer)).unwrap();
-
-            debug_assert_eq!(block.pointer % N as u32, 0);
-            debug_assert_eq!(block.size, N);
-
-            equipartition.insert(
-                (address_space, pointer / N as u32),
-                TimestampedValues {
-                    timestamp: block.timestamp,
-                    values: self.range_array::<N>(address_space, pointer),
-                },
-            );
-        }
+        let blocks: Vec<_> = to_access
+            .par_iter()
+            .map(|&(address_space, pointer)| {
+                let block = self.block_data.get(&(address_space, pointer)).unwrap();
+
+                debug_assert_eq!(block.pointer % N as u32, 0);
+                debug_assert_eq!(block.size, N);
+
+                (
+                    (address_space, pointer / N as u32),
+                    TimestampedValues {
+                        timestamp: block.timestamp,
+                        values: self.range_array::<N>(address_space, pointer),
+                    },
+                )
+            })
+            .collect();
+        // The blocks are sorted, so this builds the map in linear time.
+        let equipartition: TimestampedEquipartition<F, N> = blocks.into_iter().collect();
 
         (equipartition, adapter_records)
     }
@@ -287,8 +468,9 @@ impl<F: PrimeField32> Memory<F> {
         for i in 0..size as u32 {
             let block = self
                 .block_data
-                .entry((address_space, pointer + i))
-                .or_insert_with(|| Self::initial_block_data(pointer + i, self.initial_block_size));
+                .get_or_insert_with((address_space, pointer + i), || {
+                    Self::initial_block_data(pointer + i, self.initial_block_size)
+                });
             debug_assert!(i == 0 || prev_timestamp == Some(block.timestamp));
             prev_timestamp = Some(block.timestamp);
             block.timestamp = self.timestamp;
@@ -393,6 +575,50 @@ impl<F: PrimeField32> Memory<F> {
         }
     }
 
+    /// Overwrites the values starting at the specified address space and start index, without
+    /// updating the block partition or timestamps and without creating any records.
+    pub fn unsafe_write(&mut self, address_space: u32, pointer: u32, values: &[F]) {
+        for (i, &value) in values.iter().enumerate() {
+            self.data.insert((address_space, pointer + i as u32), value);
+        }
+    }
+
+    /// Returns whether the cell has never been accessed, i.e. it still lies in its initial block
+    /// with the initial timestamp and no access adapter record mentions it.
+    pub fn is_initial(&self, address_space: u32, pointer: u32) -> bool {
+        let initial_block = Self::initial_block_data(pointer, self.initial_block_size);
+        self.block_data
+            .get(&(address_space, pointer))
+            .map_or(true, |block| *block == initial_block)
+    }
+
+    /// Approximate heap bytes used by the values and the block partition, keyed by address
+    /// space.
+    pub fn page_bytes(&self) -> impl Iterator<Item = (u32, usize)> + '_ {
+        self.data.page_bytes().chain(self.block_data.page_bytes())
+    }
+
+    /// Returns every cell of `address_space` that was initialized or accessed, keyed by pointer.
+    pub fn address_space_cells(&self, address_space: u32) -> BTreeMap<u32, F> {
+        self.block_data
+            .iter_address_space(address_space)
+            .map(|(pointer, _)| (pointer, self.get(address_space, pointer)))
+            .chain(self.data.iter_address_space(address_space))
+            .collect()
+    }
+
+    /// Returns the values of all cells that were initialized or written, grouped into blocks of
+    /// `N` cells. Cells of a block that have no value are zero.
+    pub fn equipartition<const N: usize>(&self) -> Equipartition<F, N> {
+        let mut partition = Equipartition::new();
+        for (address_space, pointer) in self.data.keys() {
+            partition
+                .entry((address_space, pointer / N as u32))
+                .or_insert([F::ZERO; N])[pointer as usize % N] = self.get(address_space, pointer);
+        }
+        partition
+    }
+
     pub fn get(&self, address_space: u32, pointer: u32) -> F {
         *self.data.get(&(address_space, pointer)).unwrap_or(&F::ZERO)
     }
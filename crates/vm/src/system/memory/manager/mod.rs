@@ -1,15 +1,17 @@
 use std::{
     array::{self, from_fn},
-    cell::RefCell,
     collections::BTreeMap,
     iter,
     marker::PhantomData,
-    rc::Rc,
     sync::Arc,
 };
 
+use atomic_refcell::AtomicRefCell;
 use getset::Getters;
-pub use memory::{MemoryReadRecord, MemoryWriteRecord};
+use itertools::Itertools;
+pub use memory::{
+    MemoryReadRangeRecord, MemoryReadRecord, MemoryWriteRangeRecord, MemoryWriteRecord,
+};
 use openvm_circuit_primitives::{
     assert_less_than::{AssertLtSubAir, LessThanAuxCols},
     is_zero::IsZeroSubAir,
@@ -22,37 +24,81 @@ use openvm_stark_backend::{
     config::{Domain, StarkGenericConfig},
     p3_commit::PolynomialSpace,
     p3_field::PrimeField32,
-    p3_maybe_rayon::prelude::{IntoParallelIterator, ParallelIterator},
-    p3_util::log2_strict_usize,
+    p3_maybe_rayon::prelude::{
+        join, IntoParallelIterator, IntoParallelRefIterator, ParallelIterator,
+    },
     prover::types::AirProofInput,
     rap::AnyRap,
     Chip, ChipUsageGetter,
 };
+use parking_lot::Mutex;
+use rustc_hash::FxHashMap;
 use serde::{Deserialize, Serialize};
 
-use self::interface::MemoryInterface;
+use self::{
+    access_log::{MemoryAccessCounts, MemoryAccessLog},
+    consistency::ConsistencyChecker,
+    interface::MemoryInterface,
+    watchpoint::Watchpoints,
+};
 use super::{merkle::DirectCompressionBus, volatile::VolatileBoundaryChip};
 use crate::{
-    arch::{hasher::HasherChip, MemoryConfig},
+    arch::{
+        hasher::{Hasher, HasherChip},
+        AddressSpaceDescriptor, MemoryConfig,
+    },
     system::memory::offline_checker::{
-        MemoryBridge, MemoryBus, MemoryReadAuxCols, MemoryReadOrImmediateAuxCols,
-        MemoryWriteAuxCols, AUX_LEN,
+        MemoryBaseAuxCols, MemoryBridge, MemoryBus, MemoryReadAuxCols,
+        MemoryReadOrImmediateAuxCols, MemoryWriteAuxCols, AUX_LEN,
     },
 };
 
+pub mod access_log;
+mod aux_batch;
+mod conditional;
+mod consistency;
 pub mod dimensions;
+mod estimate;
+mod hint;
+mod image;
 mod interface;
 pub(super) mod memory;
+mod paged;
+mod snapshot;
+mod usage;
+mod watchpoint;
+mod wide;
+
+pub use consistency::MemoryConsistencyError;
+pub use estimate::MemoryAccessProfile;
+pub use image::{
+    MemoryPartitionImage, MemoryPartitionImageError, PartitionBlock,
+    MEMORY_PARTITION_FORMAT_VERSION,
+};
+pub use snapshot::MemorySnapshot;
+pub use usage::{AddressSpaceUsage, MemoryUsage};
+pub use watchpoint::{WatchpointHit, WatchpointId};
+pub use wide::{u64_from_limbs, u64_to_limbs, U64LimbError, U64_LIMB_BITS, U64_NUM_LIMBS};
 
 use crate::system::memory::{
     adapter::AccessAdapterInventory,
-    dimensions::MemoryDimensions,
     manager::memory::{Memory, INITIAL_TIMESTAMP},
     merkle::{MemoryMerkleBus, MemoryMerkleChip},
     persistent::PersistentBoundaryChip,
-    tree::MemoryNode,
+    tree::{
+        proof::{
+            region_values, AddressSpaceOutOfRange, AddressSpaceRootProof, MemoryMerkleProof,
+            MemoryRegionProof,
+        },
+        MemoryNode,
+    },
 };
 
+/// The default number of cells in a leaf of the persistent memory Merkle tree.
+///
+/// [MemoryController] is generic over the chunk size, which trades Merkle tree depth against the
+/// width of the persistent boundary trace. The chunk size must equal the digest width of the
+/// [HasherChip] used at finalization.
 pub const CHUNK: usize = 8;
 /// The offset of the Merkle AIR in AIRs of MemoryController.
 pub const MERKLE_AIR_OFFSET: usize = 1;
@@ -65,7 +111,7 @@ pub struct TimestampedValues<T, const N: usize> {
     pub values: [T; N],
 }
 
-pub type MemoryControllerRef<F> = Rc<RefCell<MemoryController<F>>>;
+pub type MemoryControllerRef<F> = Arc<AtomicRefCell<MemoryController<F>>>;
 
 /// An equipartition of memory, with timestamps and values.
 ///
@@ -85,12 +131,14 @@ pub type TimestampedEquipartition<F, const N: usize> =
 pub type Equipartition<F, const N: usize> = BTreeMap<(u32, u32), [F; N]>;
 
 #[derive(Debug, Getters)]
-pub struct MemoryController<F> {
+pub struct MemoryController<F, const CHUNK_SIZE: usize = CHUNK> {
     pub memory_bus: MemoryBus,
-    pub interface_chip: MemoryInterface<F>,
+    pub interface_chip: MemoryInterface<F, CHUNK_SIZE>,
 
     #[getset(get = "pub")]
     pub(crate) mem_config: MemoryConfig,
+    // The declared address spaces of `mem_config`, indexed by address space.
+    address_spaces: FxHashMap<u32, AddressSpaceDescriptor>,
     pub range_checker: Arc<VariableRangeCheckerChip>,
     // Store separately to avoid smart pointer reference each time
     range_checker_bus: VariableRangeCheckerBus,
@@ -101,24 +149,40 @@ pub struct MemoryController<F> {
     access_adapters: AccessAdapterInventory<F>,
 
     // Filled during finalization.
-    final_state: Option<FinalState<F>>,
+    final_state: Option<FinalState<F, CHUNK_SIZE>>,
+
+    // Records every read and write when enabled. See [MemoryController::enable_access_log].
+    access_log: Option<MemoryAccessLog<F>>,
+    // See [MemoryController::access_counts].
+    access_counts: MemoryAccessCounts,
+
+    // Debugging hooks. See [MemoryController::add_watchpoint].
+    watchpoints: Watchpoints<F>,
+
+    // Software memory checker, shared with the aux columns factories. See
+    // [MemoryController::enable_consistency_check].
+    consistency_checker: Option<Arc<Mutex<ConsistencyChecker>>>,
+
+    // First write to a read-only address space. See [MemoryController::take_read_only_write].
+    read_only_write: Option<(u32, u32)>,
 }
 
 #[allow(clippy::large_enum_variant)]
 #[derive(Debug)]
-enum FinalState<F> {
+enum FinalState<F, const CHUNK_SIZE: usize> {
     Volatile(VolatileFinalState<F>),
-    #[allow(dead_code)]
-    Persistent(PersistentFinalState<F>),
+    Persistent(PersistentFinalState<F, CHUNK_SIZE>),
 }
 #[derive(Debug, Default)]
 struct VolatileFinalState<F> {
     _marker: PhantomData<F>,
 }
-#[allow(dead_code)]
 #[derive(Debug)]
-struct PersistentFinalState<F> {
-    final_memory: Equipartition<F, CHUNK>,
+struct PersistentFinalState<F, const CHUNK_SIZE: usize> {
+    /// Shared with the caller of [MemoryController::finalize].
+    final_memory: Arc<Equipartition<F, CHUNK_SIZE>>,
+    /// Only computed when the initial memory tree was provided.
+    final_tree: Option<MemoryNode<CHUNK_SIZE, F>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -212,25 +276,18 @@ impl PersistentMemoryTraceHeights {
 }
 
 impl<F: PrimeField32> MemoryController<F> {
-    pub fn continuation_enabled(&self) -> bool {
-        match &self.interface_chip {
-            MemoryInterface::Volatile { .. } => false,
-            MemoryInterface::Persistent { .. } => true,
-        }
-    }
     pub fn with_volatile_memory(
         memory_bus: MemoryBus,
         mem_config: MemoryConfig,
         range_checker: Arc<VariableRangeCheckerChip>,
     ) -> Self {
         let range_checker_bus = range_checker.bus();
-        Self {
+        let mut controller = Self {
             memory_bus,
-            mem_config,
             interface_chip: MemoryInterface::Volatile {
                 boundary_chip: VolatileBoundaryChip::new(
                     memory_bus,
-                    mem_config.as_height,
+                    mem_config.addr_space_max_bits(),
                     mem_config.pointer_max_bits,
                     range_checker.clone(),
                 ),
@@ -242,9 +299,29 @@ impl<F: PrimeField32> MemoryController<F> {
                 mem_config.clk_max_bits,
                 mem_config.max_access_adapter_n,
             ),
+            address_spaces: index_address_spaces(&mem_config),
+            mem_config,
             range_checker,
             range_checker_bus,
             final_state: None,
+            access_log: None,
+            access_counts: MemoryAccessCounts::default(),
+            watchpoints: Watchpoints::default(),
+            consistency_checker: None,
+            read_only_write: None,
+        };
+        if cfg!(feature = "memory-self-check") {
+            controller.enable_consistency_check();
+        }
+        controller
+    }
+}
+
+impl<F: PrimeField32, const CHUNK_SIZE: usize> MemoryController<F, CHUNK_SIZE> {
+    pub fn continuation_enabled(&self) -> bool {
+        match &self.interface_chip {
+            MemoryInterface::Volatile { .. } => false,
+            MemoryInterface::Persistent { .. } => true,
         }
     }
 
@@ -254,13 +331,9 @@ impl<F: PrimeField32> MemoryController<F> {
         range_checker: Arc<VariableRangeCheckerChip>,
         merkle_bus: MemoryMerkleBus,
         compression_bus: DirectCompressionBus,
-        initial_memory: Equipartition<F, CHUNK>,
+        initial_memory: Equipartition<F, CHUNK_SIZE>,
     ) -> Self {
-        let memory_dims = MemoryDimensions {
-            as_height: mem_config.as_height,
-            address_height: mem_config.pointer_max_bits - log2_strict_usize(CHUNK),
-            as_offset: 1,
-        };
+        let memory_dims = mem_config.memory_dimensions_for_chunk(CHUNK_SIZE);
         let memory = Memory::new(&initial_memory);
         let range_checker_bus = range_checker.bus();
         let interface_chip = MemoryInterface::Persistent {
@@ -269,13 +342,14 @@ impl<F: PrimeField32> MemoryController<F> {
                 memory_bus,
                 merkle_bus,
                 compression_bus,
+                mem_config.read_only_address_spaces(),
             ),
             merkle_chip: MemoryMerkleChip::new(memory_dims, merkle_bus, compression_bus),
-            initial_memory,
+            initial_memory: Arc::new(initial_memory),
+            initial_tree: None,
         };
-        Self {
+        let mut controller = Self {
             memory_bus,
-            mem_config,
             interface_chip,
             memory,
             access_adapters: AccessAdapterInventory::new(
@@ -284,10 +358,21 @@ impl<F: PrimeField32> MemoryController<F> {
                 mem_config.clk_max_bits,
                 mem_config.max_access_adapter_n,
             ),
+            address_spaces: index_address_spaces(&mem_config),
+            mem_config,
             range_checker,
             range_checker_bus,
             final_state: None,
+            access_log: None,
+            access_counts: MemoryAccessCounts::default(),
+            watchpoints: Watchpoints::default(),
+            consistency_checker: None,
+            read_only_write: None,
+        };
+        if cfg!(feature = "memory-self-check") {
+            controller.enable_consistency_check();
         }
+        controller
     }
 
     pub fn set_override_trace_heights(&mut self, overridden_heights: MemoryTraceHeights) {
@@ -316,7 +401,10 @@ impl<F: PrimeField32> MemoryController<F> {
         }
     }
 
-    pub fn set_initial_memory(&mut self, memory: Equipartition<F, CHUNK>) {
+    /// Sets the initial memory. The memory may be shared, e.g. the final memory returned by
+    /// [Self::finalize] of the previous segment, in which case it is not copied.
+    pub fn set_initial_memory(&mut self, memory: impl Into<Arc<Equipartition<F, CHUNK_SIZE>>>) {
+        let memory = memory.into();
         if self.timestamp() > INITIAL_TIMESTAMP + 1 {
             panic!("Cannot set initial memory after first timestamp");
         }
@@ -326,13 +414,48 @@ impl<F: PrimeField32> MemoryController<F> {
                     panic!("Cannot set initial memory for volatile memory");
                 }
             }
-            MemoryInterface::Persistent { initial_memory, .. } => {
+            MemoryInterface::Persistent {
+                initial_memory,
+                initial_tree,
+                ..
+            } => {
                 *initial_memory = memory;
-                self.memory = Memory::new(initial_memory);
+                *initial_tree = None;
+                self.memory = Memory::new(&**initial_memory);
+                if let Some(checker) = &self.consistency_checker {
+                    *checker.lock() = self.new_consistency_checker();
+                }
             }
         }
     }
 
+    /// Sets the Merkle tree of the initial memory, which must be the tree of the memory passed to
+    /// the last call of [Self::set_initial_memory]. With continuations, this is typically the
+    /// tree returned by [Self::take_final_memory_tree] of the previous segment.
+    ///
+    /// When the initial tree is set, finalization only rehashes the paths to touched chunks and
+    /// keeps the final tree for the next segment. Otherwise the initial memory is rehashed from
+    /// scratch and no final tree is kept.
+    pub fn set_initial_memory_tree(&mut self, tree: MemoryNode<CHUNK_SIZE, F>) {
+        match &mut self.interface_chip {
+            MemoryInterface::Volatile { .. } => {
+                panic!("Cannot set initial memory tree for volatile memory");
+            }
+            MemoryInterface::Persistent { initial_tree, .. } => {
+                *initial_tree = Some(tree);
+            }
+        }
+    }
+
+    /// Takes the Merkle tree of the final memory, if it was computed during finalization. See
+    /// [Self::set_initial_memory_tree].
+    pub fn take_final_memory_tree(&mut self) -> Option<MemoryNode<CHUNK_SIZE, F>> {
+        match &mut self.final_state {
+            Some(FinalState::Persistent(final_state)) => final_state.final_tree.take(),
+            _ => None,
+        }
+    }
+
     pub fn memory_bridge(&self) -> MemoryBridge {
         MemoryBridge::new(
             self.memory_bus,
@@ -348,35 +471,46 @@ impl<F: PrimeField32> MemoryController<F> {
     pub fn read<const N: usize>(&mut self, address_space: F, pointer: F) -> MemoryReadRecord<F, N> {
         let address_space_u32 = address_space.as_canonical_u32();
         let ptr_u32 = pointer.as_canonical_u32();
-        assert!(
-            address_space == F::ZERO || ptr_u32 < (1 << self.mem_config.pointer_max_bits),
-            "memory out of bounds: {ptr_u32:?}",
-        );
-
         if address_space == F::ZERO {
             assert_eq!(N, 1, "cannot batch read from address space 0");
 
             let timestamp = self.timestamp();
             self.memory.increment_timestamp();
+            self.log_access(OpType::Read, 0, ptr_u32, timestamp, &[pointer]);
 
-            return MemoryReadRecord {
+            let record = MemoryReadRecord {
                 address_space,
                 pointer,
                 timestamp,
                 prev_timestamp: 0,
                 data: array::from_fn(|_| pointer),
             };
+            if let Some(checker) = &self.consistency_checker {
+                checker.lock().replay_read(&record);
+            }
+            return record;
         }
+        self.check_access(address_space_u32, ptr_u32, N);
 
         let (record, adapter_records) = self.memory.read::<N>(address_space_u32, ptr_u32);
+        self.log_access(
+            OpType::Read,
+            address_space_u32,
+            ptr_u32,
+            record.timestamp,
+            &record.data,
+        );
+        if let Some(checker) = &self.consistency_checker {
+            let mut checker = checker.lock();
+            checker.replay_adapter_records(&adapter_records);
+            checker.replay_read(&record);
+        }
         for record in adapter_records {
             self.access_adapters.add_record(record);
         }
 
-        for i in 0..N as u32 {
-            self.interface_chip
-                .touch_address(address_space_u32, ptr_u32 + i);
-        }
+        self.interface_chip
+            .touch_range(address_space_u32, ptr_u32, N as u32);
 
         record
     }
@@ -397,6 +531,76 @@ impl<F: PrimeField32> MemoryController<F> {
         from_fn(|i| self.memory.get(addr_space, ptr + i as u32))
     }
 
+    /// Writes a word directly to memory without updating internal state.
+    ///
+    /// The write is unconstrained: no records are created, so the memory traces do not account
+    /// for it. The resulting proof is only valid if the written cells are overwritten by a
+    /// constrained write before they are next read, or are never accessed again. To add values
+    /// that are committed as part of the initial memory, use [Self::inject_initial_memory].
+    pub fn unsafe_write_cell(&mut self, addr_space: F, ptr: F, data: F) {
+        self.unsafe_write(addr_space, ptr, [data]);
+    }
+
+    /// Writes a word directly to memory without updating internal state.
+    ///
+    /// See [Self::unsafe_write_cell].
+    pub fn unsafe_write<const N: usize>(&mut self, addr_space: F, ptr: F, data: [F; N]) {
+        let (addr_space, ptr) = (addr_space.as_canonical_u32(), ptr.as_canonical_u32());
+        self.memory.unsafe_write(addr_space, ptr, &data);
+        if let Some(checker) = &self.consistency_checker {
+            checker.lock().overwrite(addr_space, ptr, &data);
+        }
+    }
+
+    /// Injects `values` into the initial memory starting at `(address_space, pointer)`.
+    ///
+    /// This lets the host materialize memory lazily, e.g. large read-only inputs, after the
+    /// controller was created. Injected values are treated exactly like values passed to
+    /// [Self::set_initial_memory]: they are committed to by the boundary chip and are part of the
+    /// initial Merkle root, so the verifier must expect the same injected values. Any initial
+    /// memory tree set by [Self::set_initial_memory_tree] is dropped, since it no longer matches.
+    ///
+    /// Panics if the memory is volatile, if finalization already happened, or if any chunk
+    /// overlapping the injected range has already been accessed.
+    pub fn inject_initial_memory(&mut self, address_space: u32, pointer: u32, values: &[F]) {
+        assert!(
+            self.final_state.is_none(),
+            "Cannot inject memory after finalization"
+        );
+        let len = values.len() as u32;
+        let chunk = CHUNK_SIZE as u32;
+        let start = pointer - pointer % chunk;
+        let end = (pointer + len).next_multiple_of(chunk);
+        assert!(
+            (start..end).all(|ptr| self.memory.is_initial(address_space, ptr)),
+            "Cannot inject memory into chunks that were already accessed"
+        );
+        match &mut self.interface_chip {
+            MemoryInterface::Volatile { .. } => {
+                panic!("Cannot inject initial memory for volatile memory");
+            }
+            MemoryInterface::Persistent {
+                initial_memory,
+                initial_tree,
+                ..
+            } => {
+                // Copies the initial memory if it is shared.
+                let initial_memory = Arc::make_mut(initial_memory);
+                for (i, &value) in values.iter().enumerate() {
+                    let ptr = pointer + i as u32;
+                    let label = (address_space, ptr / chunk);
+                    initial_memory.entry(label).or_insert([F::ZERO; CHUNK_SIZE])
+                        [(ptr % chunk) as usize] = value;
+                }
+                *initial_tree = None;
+            }
+        }
+        self.memory.unsafe_write(address_space, pointer, values);
+        if let Some(checker) = &self.consistency_checker {
+            checker.lock().overwrite(address_space, pointer, values);
+        }
+    }
+
     pub fn write_cell(&mut self, address_space: F, pointer: F, data: F) -> MemoryWriteRecord<F, 1> {
         self.write(address_space, pointer, [data])
     }
@@ -410,29 +614,185 @@ impl<F: PrimeField32> MemoryController<F> {
         assert_ne!(address_space, F::ZERO);
         let address_space_u32 = address_space.as_canonical_u32();
         let ptr_u32 = pointer.as_canonical_u32();
-        assert!(
-            ptr_u32 < (1 << self.mem_config.pointer_max_bits),
-            "memory out of bounds: {ptr_u32:?}",
-        );
+        self.check_access(address_space_u32, ptr_u32, N);
+        self.check_writable(address_space_u32, ptr_u32);
 
         let (record, adapter_records) = self.memory.write(address_space_u32, ptr_u32, data);
+        self.log_access(
+            OpType::Write,
+            address_space_u32,
+            ptr_u32,
+            record.timestamp,
+            &record.data,
+        );
+        if let Some(checker) = &self.consistency_checker {
+            let mut checker = checker.lock();
+            checker.replay_adapter_records(&adapter_records);
+            checker.replay_write(&record);
+        }
         for record in adapter_records {
             self.access_adapters.add_record(record);
         }
 
-        for i in 0..N as u32 {
-            self.interface_chip
-                .touch_address(address_space_u32, ptr_u32 + i);
+        self.interface_chip
+            .touch_range(address_space_u32, ptr_u32, N as u32);
+
+        record
+    }
+
+    /// Reads `len` cells starting at `pointer` as consecutive reads of blocks of size `N`, one
+    /// per timestamp. Equivalent to calling [Self::read] once per block, but returns a single
+    /// record for the whole range.
+    ///
+    /// Panics if `len` is not a multiple of `N`.
+    pub fn read_range<const N: usize>(
+        &mut self,
+        address_space: F,
+        pointer: F,
+        len: usize,
+    ) -> MemoryReadRangeRecord<F, N> {
+        assert_ne!(
+            address_space,
+            F::ZERO,
+            "cannot range read from address space 0"
+        );
+        let address_space_u32 = address_space.as_canonical_u32();
+        let ptr_u32 = pointer.as_canonical_u32();
+        self.check_range_access(address_space_u32, ptr_u32, len, N);
+
+        let (record, adapter_records) =
+            self.memory.read_range::<N>(address_space_u32, ptr_u32, len);
+        for block in record.blocks() {
+            self.log_access(
+                OpType::Read,
+                address_space_u32,
+                block.pointer.as_canonical_u32(),
+                block.timestamp,
+                &block.data,
+            );
+        }
+        if let Some(checker) = &self.consistency_checker {
+            // The adapter records of each block only cover cells of that block and later ones.
+            let mut checker = checker.lock();
+            checker.replay_adapter_records(&adapter_records);
+            for block in record.blocks() {
+                checker.replay_read(&block);
+            }
+        }
+        for record in adapter_records {
+            self.access_adapters.add_record(record);
         }
+        self.interface_chip
+            .touch_range(address_space_u32, ptr_u32, len as u32);
 
         record
     }
 
+    /// Writes `data` starting at `pointer` as consecutive writes of blocks of size `N`, one per
+    /// timestamp. Equivalent to calling [Self::write] once per block, but returns a single
+    /// record for the whole range.
+    ///
+    /// Panics if the length of `data` is not a multiple of `N`.
+    pub fn write_range<const N: usize>(
+        &mut self,
+        address_space: F,
+        pointer: F,
+        data: Vec<F>,
+    ) -> MemoryWriteRangeRecord<F, N> {
+        assert_ne!(address_space, F::ZERO);
+        let address_space_u32 = address_space.as_canonical_u32();
+        let ptr_u32 = pointer.as_canonical_u32();
+        let len = data.len();
+        self.check_range_access(address_space_u32, ptr_u32, len, N);
+        self.check_writable(address_space_u32, ptr_u32);
+
+        let (record, adapter_records) =
+            self.memory
+                .write_range::<N>(address_space_u32, ptr_u32, data);
+        for block in record.blocks() {
+            self.log_access(
+                OpType::Write,
+                address_space_u32,
+                block.pointer.as_canonical_u32(),
+                block.timestamp,
+                &block.data,
+            );
+        }
+        if let Some(checker) = &self.consistency_checker {
+            // The adapter records of each block only cover cells of that block and later ones.
+            let mut checker = checker.lock();
+            checker.replay_adapter_records(&adapter_records);
+            for block in record.blocks() {
+                checker.replay_write(&block);
+            }
+        }
+        for record in adapter_records {
+            self.access_adapters.add_record(record);
+        }
+        self.interface_chip
+            .touch_range(address_space_u32, ptr_u32, len as u32);
+
+        record
+    }
+
+    /// Returns the descriptor of `address_space`, see [MemoryConfig::address_space].
+    fn address_space(&self, address_space: u32) -> AddressSpaceDescriptor {
+        self.address_spaces
+            .get(&address_space)
+            .copied()
+            .unwrap_or_else(|| {
+                AddressSpaceDescriptor::unrestricted(address_space, &self.mem_config)
+            })
+    }
+
+    /// Panics if an access of `len` cells at `pointer` is not allowed by the descriptor of
+    /// `address_space` in the memory config.
+    fn check_access(&self, address_space: u32, pointer: u32, len: usize) {
+        let descriptor = self.address_space(address_space);
+        assert!(
+            pointer < (1 << descriptor.pointer_max_bits),
+            "memory out of bounds: {pointer:?}",
+        );
+        assert_eq!(
+            len % descriptor.word_size,
+            0,
+            "access of {len} cells is not a multiple of the word size of address space {address_space}",
+        );
+    }
+
+    /// Records a write at `pointer` if `address_space` is read-only in the memory config. The
+    /// write still happens so that the records stay consistent, and the segment fails once the
+    /// instruction returns, see [Self::take_read_only_write].
+    fn check_writable(&mut self, address_space: u32, pointer: u32) {
+        if self.read_only_write.is_none() && self.address_space(address_space).read_only {
+            self.read_only_write = Some((address_space, pointer));
+        }
+    }
+
+    /// Returns and clears the `(address_space, pointer)` of the first write to a read-only
+    /// address space since the last call, if any.
+    pub fn take_read_only_write(&mut self) -> Option<(u32, u32)> {
+        self.read_only_write.take()
+    }
+
+    /// Checks each block of size `block_size` in a range access of `len` cells at `pointer`.
+    fn check_range_access(&self, address_space: u32, pointer: u32, len: usize, block_size: usize) {
+        self.check_access(address_space, pointer, block_size);
+        if len > block_size {
+            self.check_access(
+                address_space,
+                pointer + (len - block_size) as u32,
+                block_size,
+            );
+        }
+    }
+
     pub fn aux_cols_factory(&self) -> MemoryAuxColsFactory<F> {
         let range_bus = self.range_checker.bus();
         MemoryAuxColsFactory {
             range_checker: self.range_checker.clone(),
             timestamp_lt_air: AssertLtSubAir::new(range_bus, self.mem_config.clk_max_bits),
+            consistency_checker: self.consistency_checker.clone(),
             _marker: Default::default(),
         }
     }
@@ -454,14 +814,32 @@ impl<F: PrimeField32> MemoryController<F> {
         self.memory.timestamp()
     }
 
-    /// Returns the final memory state if persistent.
+    /// Returns the current values of all initialized or written cells, in chunks. Unlike
+    /// [Self::finalize], this creates no records and hashes nothing, so the result is not
+    /// committed to by any trace.
+    pub fn memory_image(&self) -> Equipartition<F, CHUNK_SIZE> {
+        self.memory.equipartition()
+    }
+
+    /// Returns the final memory state if persistent. The returned memory is shared with the
+    /// controller rather than copied, and can be passed to [Self::set_initial_memory] of the
+    /// next segment as is.
     pub fn finalize(
         &mut self,
-        hasher: Option<&mut impl HasherChip<CHUNK, F>>,
-    ) -> Option<Equipartition<F, CHUNK>> {
+        hasher: Option<&mut impl HasherChip<CHUNK_SIZE, F>>,
+    ) -> Option<Arc<Equipartition<F, CHUNK_SIZE>>> {
         if self.final_state.is_some() {
             panic!("Cannot finalize more than once");
         }
+        if let Err(violation) = self.check_consistency() {
+            panic!("Memory consistency check failed: {violation}");
+        }
+        let clk_max_bits = self.mem_config.clk_max_bits;
+        assert!(
+            self.timestamp() <= 1 << clk_max_bits,
+            "timestamp {} exceeds clk_max_bits = {clk_max_bits}; the segment must be split",
+            self.timestamp()
+        );
 
         let (records, final_memory) = match &mut self.interface_chip {
             MemoryInterface::Volatile { boundary_chip } => {
@@ -474,28 +852,36 @@ impl<F: PrimeField32> MemoryController<F> {
                 merkle_chip,
                 boundary_chip,
                 initial_memory,
+                initial_tree,
             } => {
                 let hasher = hasher.unwrap();
 
-                let (final_partition, records) = self.memory.finalize::<CHUNK>();
-                boundary_chip.finalize(initial_memory, &final_partition, hasher);
-                let final_memory_values = final_partition
-                    .into_par_iter()
-                    .map(|(key, value)| (key, value.values))
-                    .collect();
-                let initial_node = MemoryNode::tree_from_memory(
-                    merkle_chip.air.memory_dimensions,
-                    initial_memory,
-                    hasher,
+                let (final_partition, records) = self.memory.finalize::<CHUNK_SIZE>();
+                boundary_chip.finalize(&**initial_memory, &final_partition, hasher);
+                let final_memory_values: Arc<Equipartition<F, CHUNK_SIZE>> = Arc::new(
+                    final_partition
+                        .par_iter()
+                        .map(|(&key, value)| (key, value.values))
+                        .collect(),
                 );
-                merkle_chip.finalize(&initial_node, &final_memory_values, hasher);
+                let final_tree = if let Some(initial_tree) = initial_tree.take() {
+                    Some(merkle_chip.finalize(&initial_tree, &final_memory_values, hasher))
+                } else {
+                    // Hash the initial memory on the fly instead of building the whole initial
+                    // tree.
+                    merkle_chip.finalize_streaming(&**initial_memory, &final_partition, hasher);
+                    None
+                };
                 self.final_state = Some(FinalState::Persistent(PersistentFinalState {
                     final_memory: final_memory_values.clone(),
+                    final_tree,
                 }));
-                // FIXME: avoid clone here.
                 (records, Some(final_memory_values))
             }
         };
+        if let Some(checker) = &self.consistency_checker {
+            checker.lock().replay_adapter_records(&records);
+        }
         for record in records {
             self.access_adapters.add_record(record);
         }
@@ -503,34 +889,221 @@ impl<F: PrimeField32> MemoryController<F> {
         final_memory
     }
 
+    /// Returns a Merkle proof of the chunk `(address_space, label)` in the final memory, which
+    /// can be checked against the final memory root with [MemoryMerkleProof::verify].
+    ///
+    /// Panics if the memory is volatile or has not been finalized.
+    pub fn open(
+        &self,
+        address_space: u32,
+        label: u32,
+        hasher: &impl Hasher<CHUNK_SIZE, F>,
+    ) -> MemoryMerkleProof<CHUNK_SIZE, F> {
+        match &self.final_state {
+            Some(FinalState::Persistent(PersistentFinalState {
+                final_memory,
+                final_tree,
+            })) => {
+                let memory_dimensions = self.mem_config.memory_dimensions_for_chunk(CHUNK_SIZE);
+                let label = (address_space, label);
+                match final_tree {
+                    Some(final_tree) => MemoryMerkleProof::from_tree(
+                        memory_dimensions,
+                        final_tree,
+                        label,
+                        *final_memory.get(&label).unwrap_or(&[F::ZERO; CHUNK_SIZE]),
+                    ),
+                    None => MemoryMerkleProof::compute(
+                        memory_dimensions,
+                        &**final_memory,
+                        hasher,
+                        label,
+                    ),
+                }
+            }
+            _ => panic!("Merkle proofs are only available after finalizing persistent memory"),
+        }
+    }
+
+    /// Returns a proof of the root of `address_space` in the initial memory, which can be checked
+    /// against the initial memory root with [AddressSpaceRootProof::verify], or an error if the
+    /// memory tree has no subtree for `address_space`.
+    ///
+    /// Panics if the memory is volatile.
+    pub fn open_initial_address_space(
+        &self,
+        address_space: u32,
+        hasher: &impl Hasher<CHUNK_SIZE, F>,
+    ) -> Result<AddressSpaceRootProof<CHUNK_SIZE, F>, AddressSpaceOutOfRange> {
+        let memory_dimensions = self.mem_config.memory_dimensions_for_chunk(CHUNK_SIZE);
+        match &self.interface_chip {
+            MemoryInterface::Persistent {
+                initial_tree: Some(initial_tree),
+                ..
+            } => AddressSpaceRootProof::from_tree(memory_dimensions, initial_tree, address_space),
+            MemoryInterface::Persistent { initial_memory, .. } => AddressSpaceRootProof::compute(
+                memory_dimensions,
+                &**initial_memory,
+                hasher,
+                address_space,
+            ),
+            MemoryInterface::Volatile { .. } => {
+                panic!("Address space roots are only available for persistent memory")
+            }
+        }
+    }
+
+    /// Returns a proof of the root of `address_space` in the final memory, which can be checked
+    /// against the final memory root with [AddressSpaceRootProof::verify]. Comparing it with
+    /// [Self::open_initial_address_space] shows whether the segment modified the address space.
+    /// Returns an error if the memory tree has no subtree for `address_space`.
+    ///
+    /// Panics if the memory is volatile or has not been finalized.
+    pub fn open_address_space(
+        &self,
+        address_space: u32,
+        hasher: &impl Hasher<CHUNK_SIZE, F>,
+    ) -> Result<AddressSpaceRootProof<CHUNK_SIZE, F>, AddressSpaceOutOfRange> {
+        match &self.final_state {
+            Some(FinalState::Persistent(PersistentFinalState {
+                final_memory,
+                final_tree,
+            })) => {
+                let memory_dimensions = self.mem_config.memory_dimensions_for_chunk(CHUNK_SIZE);
+                match final_tree {
+                    Some(final_tree) => AddressSpaceRootProof::from_tree(
+                        memory_dimensions,
+                        final_tree,
+                        address_space,
+                    ),
+                    None => AddressSpaceRootProof::compute(
+                        memory_dimensions,
+                        &**final_memory,
+                        hasher,
+                        address_space,
+                    ),
+                }
+            }
+            _ => panic!("Merkle proofs are only available after finalizing persistent memory"),
+        }
+    }
+
+    /// Returns a proof of the final contents of the `len` cells starting at
+    /// `(address_space, pointer)`, which can be checked against the final memory root with
+    /// [MemoryRegionProof::verify], or against segment proofs with
+    /// [VirtualMachine::verify_region](crate::arch::VirtualMachine::verify_region). This commits
+    /// to an output region without copying it into the public values address space.
+    ///
+    /// Panics if the memory is volatile or has not been finalized, or if the region is not
+    /// aligned as described in [MemoryRegionProof].
+    pub fn open_region(
+        &self,
+        address_space: u32,
+        pointer: u32,
+        len: usize,
+        hasher: &impl Hasher<CHUNK_SIZE, F>,
+    ) -> MemoryRegionProof<CHUNK_SIZE, F> {
+        match &self.final_state {
+            Some(FinalState::Persistent(PersistentFinalState {
+                final_memory,
+                final_tree,
+            })) => {
+                let memory_dimensions = self.mem_config.memory_dimensions_for_chunk(CHUNK_SIZE);
+                match final_tree {
+                    Some(final_tree) => MemoryRegionProof::from_tree(
+                        memory_dimensions,
+                        final_tree,
+                        address_space,
+                        pointer,
+                        region_values(&**final_memory, address_space, pointer, len),
+                    ),
+                    None => MemoryRegionProof::compute(
+                        memory_dimensions,
+                        &**final_memory,
+                        hasher,
+                        address_space,
+                        pointer,
+                        len,
+                    ),
+                }
+            }
+            _ => panic!("Merkle proofs are only available after finalizing persistent memory"),
+        }
+    }
+
+    /// Returns the final contents of `address_space`, keyed by pointer. Only cells that were part
+    /// of the initial memory or were written or accessed are included; all other cells are zero.
+    ///
+    /// Panics if called before finalization.
+    pub fn dump(&self, address_space: u32) -> BTreeMap<u32, F> {
+        assert!(
+            self.final_state.is_some(),
+            "Memory dumps are only available after finalization"
+        );
+        self.memory.address_space_cells(address_space)
+    }
+
+    /// Returns the final contents of `address_spaces` as a [MemoryImage], the format of the
+    /// initial memory of an executable, so it can be serialized or compared against the memory
+    /// of a native execution. See [Self::dump].
+    pub fn dump_image(&self, address_spaces: impl IntoIterator<Item = u32>) -> MemoryImage<F> {
+        address_spaces
+            .into_iter()
+            .flat_map(|address_space| {
+                self.dump(address_space)
+                    .into_iter()
+                    .map(move |(pointer, value)| ((address_space, pointer), value))
+            })
+            .collect()
+    }
+
+    /// Generates the memory traces. The boundary, Merkle and access adapter traces are
+    /// independent, so they are generated in parallel when the `parallel` feature is enabled.
+    ///
+    /// The traces of all other chips must have been generated, so that their auxiliary columns
+    /// are covered by the consistency check, if enabled.
     pub fn generate_air_proof_inputs<SC: StarkGenericConfig>(self) -> Vec<AirProofInput<SC>>
     where
         Domain<SC>: PolynomialSpace<Val = F>,
     {
-        let mut ret = Vec::new();
-
+        if let Err(violation) = self.check_consistency() {
+            panic!("Memory consistency check failed: {violation}");
+        }
+        let airs = self.airs::<SC>();
         let Self {
             interface_chip,
             access_adapters,
             ..
         } = self;
-        match interface_chip {
-            MemoryInterface::Volatile { boundary_chip } => {
-                ret.push(boundary_chip.generate_air_proof_input());
-            }
-            MemoryInterface::Persistent {
-                merkle_chip,
-                boundary_chip,
-                ..
-            } => {
-                debug_assert_eq!(ret.len(), BOUNDARY_AIR_OFFSET);
-                ret.push(boundary_chip.generate_air_proof_input());
-                debug_assert_eq!(ret.len(), MERKLE_AIR_OFFSET);
-                ret.push(merkle_chip.generate_air_proof_input());
-            }
-        }
-        ret.extend(access_adapters.generate_air_proof_inputs());
-        ret
+        let (mut traces, access_adapter_traces) = join(
+            || match interface_chip {
+                MemoryInterface::Volatile { boundary_chip } => {
+                    vec![(boundary_chip.generate_trace(), vec![])]
+                }
+                MemoryInterface::Persistent {
+                    merkle_chip,
+                    boundary_chip,
+                    ..
+                } => {
+                    let (boundary_trace, merkle_trace) = join(
+                        || boundary_chip.generate_trace(),
+                        || merkle_chip.generate_trace(),
+                    );
+                    // Order must match `BOUNDARY_AIR_OFFSET` and `MERKLE_AIR_OFFSET`.
+                    vec![(boundary_trace, vec![]), merkle_trace]
+                }
+            },
+            || access_adapters.generate_traces(),
+        );
+        traces.extend(
+            access_adapter_traces
+                .into_iter()
+                .map(|trace| (trace, vec![])),
+        );
+        airs.into_iter()
+            .zip_eq(traces)
+            .map(|(air, (trace, pvs))| AirProofInput::simple(air, trace, pvs))
+            .collect()
     }
 
     pub fn airs<SC: StarkGenericConfig>(&self) -> Vec<Arc<dyn AnyRap<SC>>>
@@ -646,6 +1219,8 @@ impl<F: PrimeField32> MemoryController<F> {
 pub struct MemoryAuxColsFactory<T> {
     range_checker: Arc<VariableRangeCheckerChip>,
     timestamp_lt_air: AssertLtSubAir,
+    // See [MemoryController::enable_consistency_check].
+    consistency_checker: Option<Arc<Mutex<ConsistencyChecker>>>,
     _marker: PhantomData<T>,
 }
 
@@ -659,10 +1234,17 @@ impl<F: PrimeField32> MemoryAuxColsFactory<F> {
             !read.address_space.is_zero(),
             "cannot make `MemoryReadAuxCols` for address space 0"
         );
-        MemoryReadAuxCols::new(
-            read.prev_timestamp,
-            self.generate_timestamp_lt_cols(read.prev_timestamp, read.timestamp),
-        )
+        let base = self.generate_base_aux(read.prev_timestamp, read.timestamp);
+        self.check_aux_cols(
+            OpType::Read,
+            read.address_space,
+            read.pointer,
+            read.timestamp,
+            &read.data,
+            &base,
+            &[],
+        );
+        MemoryReadAuxCols::from_base(base)
     }
 
     pub fn make_read_or_immediate_aux_cols(
@@ -672,43 +1254,77 @@ impl<F: PrimeField32> MemoryAuxColsFactory<F> {
         let mut inv = F::ZERO;
         let mut is_zero = F::ZERO;
         IsZeroSubAir.generate_subrow(read.address_space, (&mut inv, &mut is_zero));
-        let timestamp_lt_cols =
-            self.generate_timestamp_lt_cols(read.prev_timestamp, read.timestamp);
-
-        MemoryReadOrImmediateAuxCols::new(
-            F::from_canonical_u32(read.prev_timestamp),
-            is_zero,
-            inv,
-            timestamp_lt_cols,
-        )
+        let base = self.generate_base_aux(read.prev_timestamp, read.timestamp);
+        self.check_aux_cols(
+            OpType::Read,
+            read.address_space,
+            read.pointer,
+            read.timestamp,
+            &read.data,
+            &base,
+            &[],
+        );
+
+        MemoryReadOrImmediateAuxCols::new(base, is_zero, inv)
     }
 
     pub fn make_write_aux_cols<const N: usize>(
         &self,
         write: MemoryWriteRecord<F, N>,
     ) -> MemoryWriteAuxCols<F, N> {
-        MemoryWriteAuxCols::new(
-            write.prev_data,
-            F::from_canonical_u32(write.prev_timestamp),
-            self.generate_timestamp_lt_cols(write.prev_timestamp, write.timestamp),
-        )
+        let base = self.generate_base_aux(write.prev_timestamp, write.timestamp);
+        self.check_aux_cols(
+            OpType::Write,
+            write.address_space,
+            write.pointer,
+            write.timestamp,
+            &write.data,
+            &base,
+            &write.prev_data,
+        );
+        MemoryWriteAuxCols::from_base(base, write.prev_data)
+    }
+
+    /// Makes the auxiliary columns of each block read in `read`, in order.
+    pub fn make_read_range_aux_cols<const N: usize>(
+        &self,
+        read: &MemoryReadRangeRecord<F, N>,
+    ) -> Vec<MemoryReadAuxCols<F, N>> {
+        self.make_read_aux_cols_batch(&read.blocks().collect::<Vec<_>>())
     }
 
-    fn generate_timestamp_lt_cols(
+    /// Makes the auxiliary columns of each block written in `write`, in order.
+    pub fn make_write_range_aux_cols<const N: usize>(
         &self,
-        prev_timestamp: u32,
-        timestamp: u32,
-    ) -> LessThanAuxCols<F, AUX_LEN> {
+        write: &MemoryWriteRangeRecord<F, N>,
+    ) -> Vec<MemoryWriteAuxCols<F, N>> {
+        self.make_write_aux_cols_batch(&write.blocks().collect::<Vec<_>>())
+    }
+
+    fn generate_base_aux(&self, prev_timestamp: u32, timestamp: u32) -> MemoryBaseAuxCols<F> {
         debug_assert!(prev_timestamp < timestamp);
         let mut decomp = [F::ZERO; AUX_LEN];
         self.timestamp_lt_air.generate_subrow(
             (&self.range_checker, prev_timestamp, timestamp),
             &mut decomp,
         );
-        LessThanAuxCols::new(decomp)
+        MemoryBaseAuxCols::new(
+            F::from_canonical_u32(prev_timestamp),
+            LessThanAuxCols::new(decomp),
+        )
     }
 }
 
+/// Indexes the declared address spaces of `mem_config` by address space, so that the descriptor
+/// of an access does not take a scan of [MemoryConfig::address_spaces].
+fn index_address_spaces(mem_config: &MemoryConfig) -> FxHashMap<u32, AddressSpaceDescriptor> {
+    mem_config
+        .address_spaces
+        .iter()
+        .map(|descriptor| (descriptor.address_space, *descriptor))
+        .collect()
+}
+
 pub fn memory_image_to_equipartition<F: PrimeField32, const N: usize>(
     memory_image: MemoryImage<F>,
 ) -> Equipartition<F, N> {
@@ -723,17 +1339,32 @@ pub fn memory_image_to_equipartition<F: PrimeField32, const N: usize>(
 
 #[cfg(test)]
 mod tests {
-    use std::sync::Arc;
+    use std::{
+        array,
+        collections::{BTreeMap, VecDeque},
+        sync::Arc,
+    };
 
     use openvm_circuit_primitives::var_range::{VariableRangeCheckerBus, VariableRangeCheckerChip};
     use openvm_stark_backend::p3_field::AbstractField;
     use openvm_stark_sdk::p3_baby_bear::BabyBear;
+    use parking_lot::Mutex;
     use rand::{prelude::SliceRandom, thread_rng, Rng};
 
-    use super::MemoryController;
+    use super::{
+        u64_from_limbs, u64_to_limbs, MemoryAccessProfile, MemoryAuxColsFactory,
+        MemoryConsistencyError, MemoryController, MemoryReadRecord, MemoryWriteRecord,
+        U64LimbError,
+    };
     use crate::{
-        arch::{MemoryConfig, MEMORY_BUS},
-        system::memory::offline_checker::MemoryBus,
+        arch::{AddressSpaceDescriptor, MemoryConfig, MEMORY_BUS},
+        system::{
+            memory::{
+                offline_checker::{MemoryBus, MemoryReadAuxCols},
+                OpType,
+            },
+            poseidon2::Poseidon2PeripheryChip,
+        },
     };
 
     const RANGE_CHECKER_BUS: usize = 3;
@@ -749,7 +1380,7 @@ mod tests {
 
         let mut memory_controller = MemoryController::with_volatile_memory(
             memory_bus,
-            memory_config,
+            memory_config.clone(),
             range_checker.clone(),
         );
 
@@ -772,4 +1403,622 @@ mod tests {
             .iter()
             .all(|&h| h == 0));
     }
+
+    #[test]
+    fn test_access_log_replay() {
+        type F = BabyBear;
+
+        let memory_bus = MemoryBus(MEMORY_BUS);
+        let memory_config = MemoryConfig::default();
+        let range_bus = VariableRangeCheckerBus::new(RANGE_CHECKER_BUS, memory_config.decomp);
+        let range_checker = Arc::new(VariableRangeCheckerChip::new(range_bus));
+        let new_controller = || {
+            MemoryController::<F>::with_volatile_memory(
+                memory_bus,
+                memory_config.clone(),
+                range_checker.clone(),
+            )
+        };
+
+        let mut memory_controller = new_controller();
+        memory_controller.enable_access_log();
+
+        let mut rng = thread_rng();
+        for _ in 0..1000 {
+            let address_space = F::from_canonical_u32(*[1, 2].choose(&mut rng).unwrap());
+            let pointer = F::from_canonical_u32(rng.gen_range(0..1 << 10) * 4);
+
+            if rng.gen_bool(0.5) {
+                let data = [F::from_canonical_u32(rng.gen_range(0..1 << 30)); 4];
+                memory_controller.write(address_space, pointer, data);
+            } else {
+                memory_controller.read::<1>(address_space, pointer);
+            }
+            memory_controller.increment_timestamp_by(rng.gen_range(0..3));
+        }
+        let log = memory_controller.take_access_log().unwrap();
+        assert_eq!(log.len(), 1000);
+
+        let mut replayed = new_controller();
+        replayed.enable_access_log();
+        replayed.replay_access_log(&log).unwrap();
+        assert_eq!(
+            replayed.take_access_log().unwrap().first_divergence(&log),
+            None
+        );
+        assert_eq!(
+            replayed.get_memory_trace_heights(),
+            memory_controller.get_memory_trace_heights()
+        );
+    }
+
+    #[test]
+    fn test_range_access_matches_block_access() {
+        type F = BabyBear;
+
+        let memory_bus = MemoryBus(MEMORY_BUS);
+        let memory_config = MemoryConfig::default();
+        let range_bus = VariableRangeCheckerBus::new(RANGE_CHECKER_BUS, memory_config.decomp);
+        let range_checker = Arc::new(VariableRangeCheckerChip::new(range_bus));
+        let new_controller = || {
+            MemoryController::<F>::with_volatile_memory(
+                memory_bus,
+                memory_config.clone(),
+                range_checker.clone(),
+            )
+        };
+
+        let mut rng = thread_rng();
+        let one = F::ONE;
+        let pointer = F::from_canonical_u32(12);
+        let data: Vec<F> = (0..32)
+            .map(|_| F::from_canonical_u32(rng.gen_range(0..1 << 30)))
+            .collect();
+
+        let mut range_controller = new_controller();
+        range_controller.write(one, F::from_canonical_u32(16), [F::ONE; 8]);
+        let write_range = range_controller.write_range::<4>(one, pointer, data.clone());
+        let read_range = range_controller.read_range::<2>(one, pointer, 32);
+
+        let mut block_controller = new_controller();
+        block_controller.write(one, F::from_canonical_u32(16), [F::ONE; 8]);
+        for (i, block) in data.chunks_exact(4).enumerate() {
+            let record = block_controller.write::<4>(
+                one,
+                pointer + F::from_canonical_usize(4 * i),
+                block.try_into().unwrap(),
+            );
+            assert_eq!(write_range.block(i), record);
+        }
+        for i in 0..16 {
+            let record = block_controller.read::<2>(one, pointer + F::from_canonical_usize(2 * i));
+            assert_eq!(read_range.block(i), record);
+        }
+
+        assert_eq!(read_range.data, data);
+        assert_eq!(range_controller.timestamp(), block_controller.timestamp());
+        assert_eq!(
+            range_controller.get_memory_trace_heights(),
+            block_controller.get_memory_trace_heights()
+        );
+    }
+
+    #[test]
+    fn test_watchpoints() {
+        type F = BabyBear;
+
+        let memory_bus = MemoryBus(MEMORY_BUS);
+        let memory_config = MemoryConfig::default();
+        let range_bus = VariableRangeCheckerBus::new(RANGE_CHECKER_BUS, memory_config.decomp);
+        let range_checker = Arc::new(VariableRangeCheckerChip::new(range_bus));
+
+        let mut memory_controller =
+            MemoryController::<F>::with_volatile_memory(memory_bus, memory_config, range_checker);
+
+        let hits = Arc::new(Mutex::new(Vec::new()));
+        let id = memory_controller.add_watchpoint(1, 8..12, {
+            let hits = hits.clone();
+            move |hit| hits.lock().push((hit.op, hit.pointer, hit.data.to_vec()))
+        });
+
+        let one = F::ONE;
+        let two = F::TWO;
+        memory_controller.write(one, F::from_canonical_u32(4), [F::ONE; 4]);
+        memory_controller.write(two, F::from_canonical_u32(8), [F::ONE; 4]);
+        memory_controller.write(one, F::from_canonical_u32(8), [F::TWO; 8]);
+        memory_controller.read::<4>(one, F::from_canonical_u32(12));
+        memory_controller.read::<1>(one, F::from_canonical_u32(11));
+        assert_eq!(
+            *hits.lock(),
+            vec![
+                (OpType::Write, 8, vec![F::TWO; 8]),
+                (OpType::Read, 11, vec![F::TWO]),
+            ]
+        );
+
+        assert!(memory_controller.remove_watchpoint(id));
+        assert!(!memory_controller.remove_watchpoint(id));
+        memory_controller.read::<1>(one, F::from_canonical_u32(8));
+        assert_eq!(hits.lock().len(), 2);
+    }
+
+    #[test]
+    fn test_consistency_check() {
+        type F = BabyBear;
+
+        let memory_bus = MemoryBus(MEMORY_BUS);
+        let memory_config = MemoryConfig::default();
+        let range_bus = VariableRangeCheckerBus::new(RANGE_CHECKER_BUS, memory_config.decomp);
+        let new_controller = || {
+            let range_checker = Arc::new(VariableRangeCheckerChip::new(range_bus));
+            let mut memory_controller = MemoryController::<F>::with_volatile_memory(
+                memory_bus,
+                memory_config.clone(),
+                range_checker,
+            );
+            memory_controller.enable_consistency_check();
+            memory_controller
+        };
+        let one = F::ONE;
+
+        let mut memory_controller = new_controller();
+        let write = memory_controller.write(one, F::from_canonical_u32(4), [F::ONE; 4]);
+        memory_controller.unsafe_write_cell(one, F::from_canonical_u32(5), F::TWO);
+        // Splits the block written above.
+        let read = memory_controller.read::<2>(one, F::from_canonical_u32(4));
+        let immediate = memory_controller.read::<1>(F::ZERO, F::from_canonical_u32(7));
+        let aux_factory = memory_controller.aux_cols_factory();
+        aux_factory.make_write_aux_cols(write);
+        aux_factory.make_read_aux_cols(read);
+        aux_factory.make_read_or_immediate_aux_cols(immediate);
+        assert_eq!(memory_controller.check_consistency(), Ok(()));
+
+        // A chip that changes its record before making the aux columns.
+        aux_factory.make_read_aux_cols(MemoryReadRecord {
+            prev_timestamp: read.prev_timestamp - 1,
+            ..read
+        });
+        assert_eq!(
+            memory_controller.check_consistency(),
+            Err(MemoryConsistencyError::AuxColsMismatch {
+                op: OpType::Read,
+                address_space: 1,
+                pointer: 4,
+                timestamp: read.timestamp,
+                column: "prev_timestamp",
+            })
+        );
+
+        // A memory model that returns stale data.
+        let memory_controller = new_controller();
+        let checker = memory_controller.consistency_checker.as_ref().unwrap();
+        checker.lock().replay_write(&MemoryWriteRecord {
+            address_space: one,
+            pointer: F::from_canonical_u32(4),
+            timestamp: 1,
+            prev_timestamp: 0,
+            data: [F::ONE; 4],
+            prev_data: [F::ZERO; 4],
+        });
+        checker.lock().replay_read(&MemoryReadRecord {
+            address_space: one,
+            pointer: F::from_canonical_u32(6),
+            timestamp: 2,
+            prev_timestamp: 1,
+            data: [F::ONE, F::TWO],
+        });
+        assert_eq!(
+            memory_controller.check_consistency(),
+            Err(MemoryConsistencyError::StaleValue {
+                index: 1,
+                op: OpType::Read,
+                address_space: 1,
+                pointer: 6,
+                timestamp: 2,
+                cell: 7,
+                expected: 1,
+                actual: 2,
+                prev_timestamp: 1,
+            })
+        );
+    }
+
+    #[test]
+    fn test_dump() {
+        type F = BabyBear;
+
+        let memory_bus = MemoryBus(MEMORY_BUS);
+        let memory_config = MemoryConfig::default();
+        let range_bus = VariableRangeCheckerBus::new(RANGE_CHECKER_BUS, memory_config.decomp);
+        let range_checker = Arc::new(VariableRangeCheckerChip::new(range_bus));
+
+        let mut memory_controller =
+            MemoryController::<F>::with_volatile_memory(memory_bus, memory_config, range_checker);
+
+        let values = [1, 2, 3, 4].map(F::from_canonical_u32);
+        memory_controller.write(F::ONE, F::from_canonical_u32(2048), values);
+        memory_controller.write(F::TWO, F::from_canonical_u32(4), [F::ONE; 2]);
+        memory_controller.read::<1>(F::TWO, F::from_canonical_u32(7));
+        memory_controller.finalize(None::<&mut Poseidon2PeripheryChip<F>>);
+
+        assert_eq!(
+            memory_controller.dump(1),
+            (2048..).zip(values).collect::<BTreeMap<_, _>>()
+        );
+        assert_eq!(
+            memory_controller.dump_image([2, 3]),
+            [((2, 4), F::ONE), ((2, 5), F::ONE), ((2, 7), F::ZERO)]
+                .into_iter()
+                .collect()
+        );
+    }
+
+    #[test]
+    fn test_u64_limbs() {
+        type F = BabyBear;
+
+        let mut rng = thread_rng();
+        for _ in 0..16 {
+            let value: u64 = rng.gen();
+            assert_eq!(u64_from_limbs(&u64_to_limbs::<F>(value)), Ok(value));
+        }
+        assert_eq!(
+            u64_to_limbs::<F>(0x0102_0304_0506_0708),
+            [8, 7, 6, 5, 4, 3, 2, 1].map(F::from_canonical_u32)
+        );
+
+        let mut limbs = [F::ZERO; 8];
+        limbs[2] = F::from_canonical_u32(256);
+        assert_eq!(
+            u64_from_limbs(&limbs),
+            Err(U64LimbError {
+                index: 2,
+                limb: 256
+            })
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "exceeds clk_max_bits")]
+    fn test_timestamp_overflow() {
+        type F = BabyBear;
+
+        let memory_bus = MemoryBus(MEMORY_BUS);
+        let memory_config = MemoryConfig::new(2, 1, 10, 10, 6, 64);
+        let range_bus = VariableRangeCheckerBus::new(RANGE_CHECKER_BUS, memory_config.decomp);
+        let range_checker = Arc::new(VariableRangeCheckerChip::new(range_bus));
+
+        let mut memory_controller =
+            MemoryController::<F>::with_volatile_memory(memory_bus, memory_config, range_checker);
+        memory_controller.increment_timestamp_by(1 << 10);
+        memory_controller.finalize(None::<&mut Poseidon2PeripheryChip<F>>);
+    }
+
+    #[test]
+    fn test_write_hints() {
+        type F = BabyBear;
+
+        let memory_bus = MemoryBus(MEMORY_BUS);
+        let memory_config = MemoryConfig::default();
+        let range_bus = VariableRangeCheckerBus::new(RANGE_CHECKER_BUS, memory_config.decomp);
+        let range_checker = Arc::new(VariableRangeCheckerChip::new(range_bus));
+
+        let mut memory_controller =
+            MemoryController::<F>::with_volatile_memory(memory_bus, memory_config, range_checker);
+
+        let mut hint_stream: VecDeque<F> = (0..20).map(F::from_canonical_u32).collect();
+        let timestamp = memory_controller.timestamp();
+        let record = memory_controller
+            .write_hints::<8>(F::TWO, F::ZERO, 16, &mut hint_stream)
+            .unwrap();
+        assert_eq!(record.num_blocks(), 2);
+        assert_eq!(memory_controller.timestamp(), timestamp + 2);
+        assert_eq!(hint_stream.len(), 4);
+        let read = memory_controller.read::<8>(F::TWO, F::from_canonical_u32(8));
+        assert_eq!(
+            read.data,
+            array::from_fn(|i| F::from_canonical_usize(8 + i))
+        );
+
+        assert!(memory_controller
+            .write_hints::<8>(F::TWO, F::ZERO, 8, &mut hint_stream)
+            .is_none());
+        assert_eq!(hint_stream.len(), 4);
+    }
+
+    #[test]
+    fn test_estimate_trace_heights() {
+        type F = BabyBear;
+
+        let memory_bus = MemoryBus(MEMORY_BUS);
+        let memory_config = MemoryConfig::default();
+        let range_bus = VariableRangeCheckerBus::new(RANGE_CHECKER_BUS, memory_config.decomp);
+        let range_checker = Arc::new(VariableRangeCheckerChip::new(range_bus));
+
+        let mut memory_controller =
+            MemoryController::<F>::with_volatile_memory(memory_bus, memory_config, range_checker);
+        memory_controller.write(F::TWO, F::ZERO, [F::ONE; 4]);
+
+        let mut profile = MemoryAccessProfile::default();
+        profile.add_writes(1, 16).add_reads(4, 8).add_reads(8, 2);
+        let estimate = memory_controller.estimate_trace_heights(&profile);
+
+        let mut rng = thread_rng();
+        for _ in 0..16 {
+            let pointer = F::from_canonical_u32(rng.gen_range(0..32));
+            memory_controller.write(F::TWO, pointer, [F::ONE]);
+        }
+        for _ in 0..8 {
+            let pointer = F::from_canonical_u32(rng.gen_range(0..8) * 4);
+            memory_controller.read::<4>(F::TWO, pointer);
+        }
+        for _ in 0..2 {
+            let pointer = F::from_canonical_u32(rng.gen_range(0..4) * 8);
+            memory_controller.read::<8>(F::TWO, pointer);
+        }
+        memory_controller.finalize(None::<&mut Poseidon2PeripheryChip<F>>);
+
+        let actual = memory_controller.get_memory_trace_heights();
+        for (actual, estimate) in actual.flatten().into_iter().zip(estimate.flatten()) {
+            assert!(actual <= estimate, "{actual} > {estimate}");
+        }
+    }
+
+    #[test]
+    fn test_conditional_access() {
+        type F = BabyBear;
+
+        let memory_bus = MemoryBus(MEMORY_BUS);
+        let memory_config = MemoryConfig::default();
+        let range_bus = VariableRangeCheckerBus::new(RANGE_CHECKER_BUS, memory_config.decomp);
+        let range_checker = Arc::new(VariableRangeCheckerChip::new(range_bus));
+
+        let mut memory_controller =
+            MemoryController::<F>::with_volatile_memory(memory_bus, memory_config, range_checker);
+        let aux_factory = memory_controller.aux_cols_factory();
+
+        let data = [F::ONE, F::TWO, F::ZERO, F::ONE];
+        let timestamp = memory_controller.timestamp();
+        assert!(memory_controller
+            .write_conditional(F::TWO, F::ZERO, data, false)
+            .is_none());
+        assert!(memory_controller
+            .read_conditional::<4>(F::TWO, F::ZERO, false)
+            .is_none());
+        assert_eq!(memory_controller.timestamp(), timestamp);
+        assert_eq!(
+            format!(
+                "{:?}",
+                aux_factory.make_read_aux_cols_conditional::<4>(None)
+            ),
+            format!("{:?}", MemoryReadAuxCols::<F, 4>::disabled())
+        );
+
+        let write = memory_controller
+            .write_conditional(F::TWO, F::ZERO, data, true)
+            .unwrap();
+        assert_eq!(write.timestamp, timestamp);
+        let read = memory_controller
+            .read_conditional::<4>(F::TWO, F::ZERO, true)
+            .unwrap();
+        assert_eq!(read.data, data);
+        assert_eq!(read.prev_timestamp, timestamp);
+        assert_eq!(memory_controller.timestamp(), timestamp + 2);
+        assert_ne!(
+            format!(
+                "{:?}",
+                aux_factory.make_read_aux_cols_conditional(Some(read))
+            ),
+            format!("{:?}", MemoryReadAuxCols::<F, 4>::disabled())
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "not a multiple of the word size")]
+    fn test_address_space_word_size() {
+        type F = BabyBear;
+
+        let memory_bus = MemoryBus(MEMORY_BUS);
+        let memory_config = MemoryConfig::default().with_address_space(AddressSpaceDescriptor {
+            address_space: 2,
+            word_size: 4,
+            pointer_max_bits: 16,
+            read_only: false,
+        });
+        let range_bus = VariableRangeCheckerBus::new(RANGE_CHECKER_BUS, memory_config.decomp);
+        let range_checker = Arc::new(VariableRangeCheckerChip::new(range_bus));
+
+        let mut memory_controller =
+            MemoryController::<F>::with_volatile_memory(memory_bus, memory_config, range_checker);
+
+        memory_controller.write(F::TWO, F::ZERO, [F::ONE; 4]);
+        memory_controller.read::<1>(F::ONE, F::from_canonical_u32(1 << 20));
+        memory_controller.read::<1>(F::TWO, F::ZERO);
+    }
+
+    #[test]
+    fn test_read_only_address_space() {
+        type F = BabyBear;
+
+        let memory_bus = MemoryBus(MEMORY_BUS);
+        let memory_config = MemoryConfig::default().with_address_space(AddressSpaceDescriptor {
+            read_only: true,
+            ..AddressSpaceDescriptor::unrestricted(2, &MemoryConfig::default())
+        });
+        let range_bus = VariableRangeCheckerBus::new(RANGE_CHECKER_BUS, memory_config.decomp);
+        let range_checker = Arc::new(VariableRangeCheckerChip::new(range_bus));
+
+        let mut memory_controller =
+            MemoryController::<F>::with_volatile_memory(memory_bus, memory_config, range_checker);
+
+        memory_controller.write(F::ONE, F::ZERO, [F::ONE; 4]);
+        memory_controller.read::<4>(F::TWO, F::ZERO);
+        assert_eq!(memory_controller.take_read_only_write(), None);
+
+        memory_controller.write(F::TWO, F::from_canonical_u32(8), [F::ONE; 4]);
+        memory_controller.write(F::TWO, F::ZERO, [F::ONE; 4]);
+        assert_eq!(memory_controller.take_read_only_write(), Some((2, 8)));
+        assert_eq!(memory_controller.take_read_only_write(), None);
+    }
+
+    #[test]
+    fn test_memory_usage() {
+        type F = BabyBear;
+
+        let memory_bus = MemoryBus(MEMORY_BUS);
+        let memory_config = MemoryConfig::default();
+        let range_bus = VariableRangeCheckerBus::new(RANGE_CHECKER_BUS, memory_config.decomp);
+        let range_checker = Arc::new(VariableRangeCheckerChip::new(range_bus));
+
+        let mut memory_controller =
+            MemoryController::<F>::with_volatile_memory(memory_bus, memory_config, range_checker);
+        assert_eq!(memory_controller.memory_usage().total_bytes(), 0);
+
+        memory_controller.write(F::ONE, F::ZERO, [F::ONE; 1]);
+        memory_controller.write(F::TWO, F::ZERO, [F::ONE; 4]);
+        memory_controller.write(F::TWO, F::from_canonical_u32(1 << 20), [F::ONE; 4]);
+
+        let usage = memory_controller.memory_usage();
+        assert_eq!(
+            usage.address_spaces.keys().copied().collect::<Vec<_>>(),
+            [1, 2]
+        );
+        let [as1, as2] = [1, 2].map(|address_space| usage.address_spaces[&address_space]);
+        assert!(as2.memory_bytes > as1.memory_bytes);
+        assert!(as2.touched_bytes > as1.touched_bytes);
+        assert_eq!(as1.access_adapter_record_bytes, 0);
+        assert!(as2.access_adapter_record_bytes > 0);
+        assert_eq!(usage.shared_bytes, 0);
+        assert_eq!(usage.total_bytes(), as1.total_bytes() + as2.total_bytes());
+    }
+
+    #[test]
+    fn test_snapshot_restore() {
+        type F = BabyBear;
+
+        let memory_bus = MemoryBus(MEMORY_BUS);
+        let memory_config = MemoryConfig::default();
+        let range_bus = VariableRangeCheckerBus::new(RANGE_CHECKER_BUS, memory_config.decomp);
+        let range_checker = Arc::new(VariableRangeCheckerChip::new(range_bus));
+
+        let mut memory_controller =
+            MemoryController::<F>::with_volatile_memory(memory_bus, memory_config, range_checker);
+
+        let one = F::ONE;
+        let values = [1, 2, 3, 4].map(F::from_canonical_u32);
+        memory_controller.write(one, F::ZERO, values);
+
+        let snapshot = memory_controller.snapshot();
+        let heights = memory_controller.get_memory_trace_heights();
+        let timestamp = memory_controller.timestamp();
+
+        memory_controller.write(one, F::ZERO, [F::ZERO; 2]);
+        memory_controller.write(one, F::from_canonical_u32(1 << 20), [F::ONE; 8]);
+        assert_ne!(memory_controller.get_memory_trace_heights(), heights);
+
+        memory_controller.restore(snapshot);
+        assert_eq!(memory_controller.timestamp(), timestamp);
+        assert_eq!(memory_controller.get_memory_trace_heights(), heights);
+        assert_eq!(memory_controller.unsafe_read::<4>(one, F::ZERO), values);
+        assert_eq!(
+            memory_controller.unsafe_read::<8>(one, F::from_canonical_u32(1 << 20)),
+            [F::ZERO; 8]
+        );
+        assert_eq!(memory_controller.read::<4>(one, F::ZERO).data, values);
+    }
+
+    #[test]
+    fn test_batched_aux_cols_match_eager() {
+        type F = BabyBear;
+
+        let memory_bus = MemoryBus(MEMORY_BUS);
+        let memory_config = MemoryConfig::default();
+        let range_bus = VariableRangeCheckerBus::new(RANGE_CHECKER_BUS, memory_config.decomp);
+        let range_checker = Arc::new(VariableRangeCheckerChip::new(range_bus));
+
+        let mut memory_controller = MemoryController::with_volatile_memory(
+            memory_bus,
+            memory_config.clone(),
+            range_checker.clone(),
+        );
+
+        let mut rng = thread_rng();
+        let mut reads = Vec::new();
+        let mut writes = Vec::new();
+        for _ in 0..200 {
+            let pointer = rng.gen_range(0..64) * 4;
+            if rng.gen_bool(0.5) {
+                let data = F::from_canonical_u32(rng.gen_range(0..1 << 20));
+                writes.push(memory_controller.write(
+                    F::ONE,
+                    F::from_canonical_u32(pointer),
+                    [data; 4],
+                ));
+            } else {
+                reads.push(memory_controller.read::<4>(F::TWO, F::from_canonical_u32(pointer)));
+            }
+            memory_controller.increment_timestamp_by(rng.gen_range(1..1 << 20));
+        }
+
+        let eager_range_checker = Arc::new(VariableRangeCheckerChip::new(range_bus));
+        let batch_range_checker = Arc::new(VariableRangeCheckerChip::new(range_bus));
+        let eager = MemoryAuxColsFactory::<F> {
+            range_checker: eager_range_checker.clone(),
+            ..memory_controller.aux_cols_factory()
+        };
+        let batch = MemoryAuxColsFactory::<F> {
+            range_checker: batch_range_checker.clone(),
+            ..memory_controller.aux_cols_factory()
+        };
+
+        // The aux columns do not implement `PartialEq`.
+        assert_eq!(
+            format!("{:?}", batch.make_read_aux_cols_batch(&reads)),
+            format!(
+                "{:?}",
+                reads
+                    .iter()
+                    .map(|&read| eager.make_read_aux_cols(read))
+                    .collect::<Vec<_>>()
+            )
+        );
+        assert_eq!(
+            format!("{:?}", batch.make_write_aux_cols_batch(&writes)),
+            format!(
+                "{:?}",
+                writes
+                    .iter()
+                    .map(|&write| eager.make_write_aux_cols(write))
+                    .collect::<Vec<_>>()
+            )
+        );
+        assert_eq!(
+            batch_range_checker.generate_trace::<F>(),
+            eager_range_checker.generate_trace::<F>()
+        );
+    }
+
+    #[test]
+    fn test_access_counts() {
+        type F = BabyBear;
+
+        let memory_bus = MemoryBus(MEMORY_BUS);
+        let memory_config = MemoryConfig::default();
+        let range_bus = VariableRangeCheckerBus::new(RANGE_CHECKER_BUS, memory_config.decomp);
+        let range_checker = Arc::new(VariableRangeCheckerChip::new(range_bus));
+
+        let mut memory_controller =
+            MemoryController::<F>::with_volatile_memory(memory_bus, memory_config, range_checker);
+        assert_eq!(memory_controller.access_counts(), Default::default());
+
+        memory_controller.write(F::ONE, F::ZERO, [F::ONE; 4]);
+        memory_controller.read::<1>(F::ONE, F::ZERO);
+        memory_controller.read::<1>(F::ZERO, F::from_canonical_u32(5));
+
+        let counts = memory_controller.access_counts();
+        assert_eq!(counts.cells_read, 1);
+        assert_eq!(counts.cells_written, 4);
+        // Splitting the written block for the read of size 1 goes through the access adapters.
+        assert!(counts.access_adapter_rows > 0);
+    }
 }
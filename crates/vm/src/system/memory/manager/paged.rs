@@ -0,0 +1,109 @@
+use std::{mem::size_of, sync::Arc};
+
+use rustc_hash::FxHashMap;
+
+/// log2 of the number of cells in a page.
+pub const PAGE_BITS: usize = 10;
+/// The number of cells in a page.
+pub const PAGE_SIZE: usize = 1 << PAGE_BITS;
+
+type Page<T> = Vec<Option<T>>;
+
+/// A sparse map from `(address_space, pointer)` to values, stored in fixed-size pages.
+///
+/// Pages are reference counted, so cloning the map costs one pointer copy per page, and a page is
+/// only copied when it is first written to after the clone.
+#[derive(Clone, Debug)]
+pub struct PagedMap<T> {
+    pages: FxHashMap<(u32, u32), Arc<Page<T>>>,
+}
+
+impl<T> Default for PagedMap<T> {
+    fn default() -> Self {
+        Self {
+            pages: FxHashMap::default(),
+        }
+    }
+}
+
+impl<T: Copy> PagedMap<T> {
+    /// Splits `(address_space, pointer)` into a page key and an offset within the page.
+    #[inline(always)]
+    fn locate(&(address_space, pointer): &(u32, u32)) -> ((u32, u32), usize) {
+        (
+            (address_space, pointer >> PAGE_BITS),
+            pointer as usize & (PAGE_SIZE - 1),
+        )
+    }
+
+    fn page_mut(&mut self, key: (u32, u32)) -> &mut Page<T> {
+        let page = self
+            .pages
+            .entry(key)
+            .or_insert_with(|| Arc::new(vec![None; PAGE_SIZE]));
+        Arc::make_mut(page)
+    }
+
+    pub fn get(&self, address: &(u32, u32)) -> Option<&T> {
+        let (key, offset) = Self::locate(address);
+        self.pages.get(&key)?[offset].as_ref()
+    }
+
+    /// Inserts `value` at `address`, returning the previous value if there was one.
+    pub fn insert(&mut self, address: (u32, u32), value: T) -> Option<T> {
+        let (key, offset) = Self::locate(&address);
+        self.page_mut(key)[offset].replace(value)
+    }
+
+    /// Returns a mutable reference to the value at `address`, inserting the result of `default`
+    /// if there is no value.
+    pub fn get_or_insert_with(
+        &mut self,
+        address: (u32, u32),
+        default: impl FnOnce() -> T,
+    ) -> &mut T {
+        let (key, offset) = Self::locate(&address);
+        self.page_mut(key)[offset].get_or_insert_with(default)
+    }
+
+    /// Approximate heap bytes used by each allocated page, keyed by address space. Pages shared
+    /// with clones are counted in full.
+    pub fn page_bytes(&self) -> impl Iterator<Item = (u32, usize)> + '_ {
+        let page_bytes = size_of::<((u32, u32), Arc<Page<T>>)>()
+            + 2 * size_of::<usize>()
+            + size_of::<Page<T>>()
+            + PAGE_SIZE * size_of::<Option<T>>();
+        self.pages
+            .keys()
+            .map(move |&(address_space, _)| (address_space, page_bytes))
+    }
+
+    /// Iterates over all addresses with a value, in no particular order.
+    pub fn keys(&self) -> impl Iterator<Item = (u32, u32)> + '_ {
+        self.pages
+            .iter()
+            .flat_map(|(&(address_space, page), values)| {
+                let base = page << PAGE_BITS;
+                values
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, value)| value.is_some())
+                    .map(move |(offset, _)| (address_space, base + offset as u32))
+            })
+    }
+
+    /// Iterates over the pointers with a value in `address_space` and their values, in no
+    /// particular order.
+    pub fn iter_address_space(&self, address_space: u32) -> impl Iterator<Item = (u32, T)> + '_ {
+        self.pages
+            .iter()
+            .filter(move |&(&(page_address_space, _), _)| page_address_space == address_space)
+            .flat_map(|(&(_, page), values)| {
+                let base = page << PAGE_BITS;
+                values
+                    .iter()
+                    .enumerate()
+                    .filter_map(move |(offset, value)| Some((base + offset as u32, (*value)?)))
+            })
+    }
+}
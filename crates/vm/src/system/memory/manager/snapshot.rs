@@ -0,0 +1,80 @@
+use openvm_stark_backend::p3_field::PrimeField32;
+
+use super::{
+    consistency::ConsistencyChecker, interface::TouchedSnapshot, memory::Memory, MemoryController,
+};
+
+/// A saved copy of the state of a [MemoryController], created by [MemoryController::snapshot].
+///
+/// The memory contents are shared copy-on-write with the controller, so taking a snapshot costs
+/// one pointer copy per page plus a copy of the touched-address sets.
+#[derive(Clone, Debug)]
+pub struct MemorySnapshot<F> {
+    memory: Memory<F>,
+    touched: TouchedSnapshot,
+    num_adapter_records: Vec<usize>,
+    access_log_len: Option<usize>,
+    consistency_checker: Option<ConsistencyChecker>,
+}
+
+impl<F: PrimeField32> MemorySnapshot<F> {
+    /// The timestamp of the controller when the snapshot was taken.
+    pub fn timestamp(&self) -> u32 {
+        self.memory.timestamp()
+    }
+}
+
+impl<F: PrimeField32, const CHUNK_SIZE: usize> MemoryController<F, CHUNK_SIZE> {
+    /// Captures the memory contents, timestamp, touched addresses and pending access adapter
+    /// records so they can later be restored with [MemoryController::restore].
+    ///
+    /// Panics if called after finalization.
+    pub fn snapshot(&self) -> MemorySnapshot<F> {
+        assert!(
+            self.final_state.is_none(),
+            "Cannot snapshot after finalization"
+        );
+        MemorySnapshot {
+            memory: self.memory.clone(),
+            touched: self.interface_chip.save_touched(),
+            num_adapter_records: self.access_adapters.get_heights(),
+            access_log_len: self.access_log.as_ref().map(|log| log.len()),
+            consistency_checker: self
+                .consistency_checker
+                .as_ref()
+                .map(|checker| checker.lock().clone()),
+        }
+    }
+
+    /// Rolls the controller back to the state captured by `snapshot`. All accesses made since
+    /// the snapshot are discarded and will not appear in the memory traces.
+    ///
+    /// The snapshot must have been taken from this controller, and no snapshot taken after
+    /// `snapshot` may be restored afterwards. Records held by other chips are not rolled back.
+    pub fn restore(&mut self, snapshot: MemorySnapshot<F>) {
+        assert!(
+            self.final_state.is_none(),
+            "Cannot restore after finalization"
+        );
+        let MemorySnapshot {
+            memory,
+            touched,
+            num_adapter_records,
+            access_log_len,
+            consistency_checker,
+        } = snapshot;
+        self.memory = memory;
+        self.interface_chip.restore_touched(touched);
+        self.access_adapters.truncate_records(&num_adapter_records);
+        if let (Some(log), Some(len)) = (&mut self.access_log, access_log_len) {
+            log.entries.truncate(len);
+        }
+        match (&self.consistency_checker, consistency_checker) {
+            (Some(checker), Some(saved)) => *checker.lock() = saved,
+            // Checking was enabled after the snapshot, which was therefore taken before the first
+            // access.
+            (Some(checker), None) => *checker.lock() = self.new_consistency_checker(),
+            (None, _) => {}
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use std::{collections::BTreeMap, mem::size_of};
+
+use openvm_stark_backend::p3_field::PrimeField32;
+use serde::{Deserialize, Serialize};
+
+use super::{interface::MemoryInterface, MemoryController};
+use crate::system::memory::adapter::AccessAdapterRecord;
+
+/// Estimated host memory used by the state of one address space, in bytes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AddressSpaceUsage {
+    /// Pages holding the memory values and the block partition.
+    pub memory_bytes: usize,
+    /// Pending access adapter records.
+    pub access_adapter_record_bytes: usize,
+    /// Touched addresses tracked by the boundary chip and, in persistent mode, touched Merkle
+    /// nodes below the root of the address space.
+    pub touched_bytes: usize,
+}
+
+impl AddressSpaceUsage {
+    pub fn total_bytes(&self) -> usize {
+        self.memory_bytes + self.access_adapter_record_bytes + self.touched_bytes
+    }
+}
+
+/// Estimated host memory used by a [MemoryController], in bytes, as reported by
+/// [MemoryController::memory_usage].
+///
+/// The estimates count the heap allocations of the underlying collections but ignore allocator
+/// overhead and spare capacity, so they are a lower bound on the actual usage.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemoryUsage {
+    pub address_spaces: BTreeMap<u32, AddressSpaceUsage>,
+    /// Touched Merkle nodes above the roots of the individual address spaces.
+    pub shared_bytes: usize,
+}
+
+impl MemoryUsage {
+    pub fn total_bytes(&self) -> usize {
+        self.address_spaces
+            .values()
+            .map(AddressSpaceUsage::total_bytes)
+            .sum::<usize>()
+            + self.shared_bytes
+    }
+
+    fn address_space(&mut self, address_space: u32) -> &mut AddressSpaceUsage {
+        self.address_spaces.entry(address_space).or_default()
+    }
+}
+
+/// Approximate bytes used by one entry of a hash set or map: the entry plus one control byte.
+fn hash_set_entry_bytes<T>() -> usize {
+    size_of::<T>() + 1
+}
+
+impl<F: PrimeField32, const CHUNK_SIZE: usize> MemoryController<F, CHUNK_SIZE> {
+    /// Estimates how much host memory the memory contents, the pending access adapter records and
+    /// the touched-address sets currently use, broken down per address space.
+    ///
+    /// Pages shared with a [super::MemorySnapshot] are counted in full.
+    ///
+    /// Panics if called after finalization.
+    pub fn memory_usage(&self) -> MemoryUsage {
+        assert!(
+            self.final_state.is_none(),
+            "Cannot report memory usage after finalization"
+        );
+        let mut usage = MemoryUsage::default();
+
+        for (address_space, bytes) in self.memory.page_bytes() {
+            usage.address_space(address_space).memory_bytes += bytes;
+        }
+
+        for record in self.access_adapters.records() {
+            usage
+                .address_space(record.address_space.as_canonical_u32())
+                .access_adapter_record_bytes +=
+                size_of::<AccessAdapterRecord<F>>() + record.data.len() * size_of::<F>();
+        }
+
+        match &self.interface_chip {
+            MemoryInterface::Volatile { boundary_chip } => {
+                for address_space in boundary_chip.touched_addresses().page_address_spaces() {
+                    usage.address_space(address_space).touched_bytes +=
+                        hash_set_entry_bytes::<((u32, u32), u64)>();
+                }
+            }
+            MemoryInterface::Persistent {
+                boundary_chip,
+                merkle_chip,
+                ..
+            } => {
+                for &(address_space, _) in boundary_chip.touched_labels() {
+                    usage.address_space(address_space).touched_bytes +=
+                        hash_set_entry_bytes::<(u32, u32)>();
+                }
+                let memory_dimensions = merkle_chip.air.memory_dimensions;
+                let (touched_nodes, _) = merkle_chip.touched_nodes();
+                for &(height, as_label, _) in touched_nodes {
+                    let bytes = hash_set_entry_bytes::<(usize, u32, u32)>();
+                    if height <= memory_dimensions.address_height {
+                        usage
+                            .address_space(as_label + memory_dimensions.as_offset)
+                            .touched_bytes += bytes;
+                    } else {
+                        usage.shared_bytes += bytes;
+                    }
+                }
+            }
+        }
+
+        usage
+    }
+}
@@ -0,0 +1,109 @@
+use std::{fmt, ops::Range};
+
+use openvm_stark_backend::p3_field::PrimeField32;
+
+use super::MemoryController;
+use crate::system::memory::OpType;
+
+/// Identifies a watchpoint registered with [MemoryController::add_watchpoint].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct WatchpointId(usize);
+
+/// A memory access that overlapped a watched range.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WatchpointHit<'a, F> {
+    pub op: OpType,
+    pub address_space: u32,
+    /// The start of the accessed block, which may lie outside the watched range.
+    pub pointer: u32,
+    pub timestamp: u32,
+    /// The data read or written by the whole access.
+    pub data: &'a [F],
+}
+
+type Callback<F> = Box<dyn FnMut(&WatchpointHit<F>) + Send + Sync>;
+
+struct Watchpoint<F> {
+    id: WatchpointId,
+    address_space: u32,
+    ptr_range: Range<u32>,
+    callback: Callback<F>,
+}
+
+/// The watchpoints registered on a [MemoryController].
+pub(super) struct Watchpoints<F> {
+    next_id: usize,
+    watchpoints: Vec<Watchpoint<F>>,
+}
+
+impl<F> Default for Watchpoints<F> {
+    fn default() -> Self {
+        Self {
+            next_id: 0,
+            watchpoints: Vec::new(),
+        }
+    }
+}
+
+impl<F> fmt::Debug for Watchpoints<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(
+                self.watchpoints
+                    .iter()
+                    .map(|w| (w.id, w.address_space, w.ptr_range.clone())),
+            )
+            .finish()
+    }
+}
+
+impl<F> Watchpoints<F> {
+    pub(super) fn notify(&mut self, hit: &WatchpointHit<F>) {
+        let start = hit.pointer;
+        let end = start + hit.data.len() as u32;
+        for watchpoint in &mut self.watchpoints {
+            if watchpoint.address_space == hit.address_space
+                && watchpoint.ptr_range.start < end
+                && start < watchpoint.ptr_range.end
+            {
+                (watchpoint.callback)(hit);
+            }
+        }
+    }
+}
+
+impl<F: PrimeField32, const CHUNK_SIZE: usize> MemoryController<F, CHUNK_SIZE> {
+    /// Registers `callback` to be called on every read or write through the controller that
+    /// touches at least one cell of `ptr_range` in `address_space`. Reads of address space `0`
+    /// (immediates) are reported as well.
+    ///
+    /// Callbacks fire after the access has been applied, in the order they were registered.
+    pub fn add_watchpoint(
+        &mut self,
+        address_space: u32,
+        ptr_range: Range<u32>,
+        callback: impl FnMut(&WatchpointHit<F>) + Send + Sync + 'static,
+    ) -> WatchpointId {
+        let id = WatchpointId(self.watchpoints.next_id);
+        self.watchpoints.next_id += 1;
+        self.watchpoints.watchpoints.push(Watchpoint {
+            id,
+            address_space,
+            ptr_range,
+            callback: Box::new(callback),
+        });
+        id
+    }
+
+    /// Removes a watchpoint. Returns `false` if it was already removed.
+    pub fn remove_watchpoint(&mut self, id: WatchpointId) -> bool {
+        let watchpoints = &mut self.watchpoints.watchpoints;
+        let len = watchpoints.len();
+        watchpoints.retain(|w| w.id != id);
+        watchpoints.len() != len
+    }
+
+    pub fn clear_watchpoints(&mut self) {
+        self.watchpoints.watchpoints.clear();
+    }
+}
@@ -0,0 +1,41 @@
+use std::array;
+
+use openvm_stark_backend::p3_field::PrimeField32;
+use thiserror::Error;
+
+// The memory itself only holds field elements. These helpers fix one layout of 64-bit words in
+// cells, so that 64-bit chips agree on it, and leave the range checks of the limbs to the chips.
+// Words are read and written as ordinary blocks of [U64_NUM_LIMBS] cells.
+
+/// Number of cells holding a 64-bit word.
+pub const U64_NUM_LIMBS: usize = 8;
+/// Number of bits of each cell of a 64-bit word.
+pub const U64_LIMB_BITS: usize = 8;
+
+/// Splits `value` into little-endian byte limbs.
+pub fn u64_to_limbs<F: PrimeField32>(value: u64) -> [F; U64_NUM_LIMBS] {
+    array::from_fn(|i| F::from_canonical_u32((value >> (U64_LIMB_BITS * i)) as u32 & 0xff))
+}
+
+/// A cell of a 64-bit word which does not fit in [U64_LIMB_BITS] bits.
+#[derive(Error, Clone, Copy, Debug, PartialEq, Eq)]
+#[error("limb {index} of a 64-bit word is {limb}, which is not a {U64_LIMB_BITS}-bit limb")]
+pub struct U64LimbError {
+    pub index: usize,
+    pub limb: u32,
+}
+
+/// Recombines little-endian byte limbs into a 64-bit word.
+pub fn u64_from_limbs<F: PrimeField32>(limbs: &[F; U64_NUM_LIMBS]) -> Result<u64, U64LimbError> {
+    limbs
+        .iter()
+        .enumerate()
+        .rev()
+        .try_fold(0, |acc, (index, limb)| {
+            let limb = limb.as_canonical_u32();
+            if limb >= 1 << U64_LIMB_BITS {
+                return Err(U64LimbError { index, limb });
+            }
+            Ok((acc << U64_LIMB_BITS) | limb as u64)
+        })
+}
@@ -1,6 +1,6 @@
 use openvm_circuit_primitives_derive::AlignedBorrow;
 
-#[derive(Debug, AlignedBorrow)]
+#[derive(Debug, PartialEq, Eq, AlignedBorrow)]
 #[repr(C)]
 pub struct MemoryMerkleCols<T, const CHUNK: usize> {
     // `expand_direction` =  1 corresponds to initial memory state
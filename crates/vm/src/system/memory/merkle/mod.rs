@@ -12,7 +12,7 @@ pub use bus::*;
 pub use columns::*;
 
 #[cfg(test)]
-mod tests;
+pub(super) mod tests;
 
 #[derive(Debug)]
 pub struct MemoryMerkleChip<const CHUNK: usize, F> {
@@ -70,6 +70,20 @@ impl<const CHUNK: usize, F: PrimeField32> MemoryMerkleChip<CHUNK, F> {
         }
     }
 
+    /// Returns the touched nodes and the number of touched non-leaf nodes among them.
+    pub(crate) fn touched_nodes(&self) -> (&FxHashSet<(usize, u32, u32)>, usize) {
+        (&self.touched_nodes, self.num_touched_nonleaves)
+    }
+
+    pub(crate) fn set_touched_nodes(
+        &mut self,
+        touched_nodes: FxHashSet<(usize, u32, u32)>,
+        num_touched_nonleaves: usize,
+    ) {
+        self.touched_nodes = touched_nodes;
+        self.num_touched_nonleaves = num_touched_nonleaves;
+    }
+
     pub fn touch_address(&mut self, address_space: u32, address: u32) {
         self.touch_node(
             0,
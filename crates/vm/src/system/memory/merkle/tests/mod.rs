@@ -25,11 +25,11 @@ use crate::{
             MemoryMerkleBus, MemoryMerkleChip,
         },
         tree::MemoryNode,
-        Equipartition,
+        Equipartition, TimestampedValues,
     },
 };
 
-mod util;
+pub(in crate::system::memory) mod util;
 
 const DEFAULT_CHUNK: usize = 8;
 const COMPRESSION_BUS: DirectCompressionBus = DirectCompressionBus(POSEIDON2_DIRECT_BUS);
@@ -77,12 +77,43 @@ fn test<const CHUNK: usize>(
         }
     }
 
+    let mut streaming_chip =
+        MemoryMerkleChip::<CHUNK, _>::new(memory_dimensions, merkle_bus, COMPRESSION_BUS);
+    for &(address_space, label) in touched_labels.iter() {
+        streaming_chip.touch_address(address_space, label * CHUNK as u32);
+    }
+
     println!("trace height = {}", chip.current_trace_height());
     chip.finalize(&initial_tree, final_memory, &mut hash_test_chip);
     assert_eq!(
         chip.final_state.as_ref().unwrap().final_root,
         final_tree_check.hash()
     );
+
+    let timestamped_final_memory = final_memory
+        .iter()
+        .map(|(&label, &values)| {
+            (
+                label,
+                TimestampedValues {
+                    timestamp: 0,
+                    values,
+                },
+            )
+        })
+        .collect();
+    streaming_chip.finalize_streaming(
+        initial_memory,
+        &timestamped_final_memory,
+        &mut HashTestChip::new(),
+    );
+    let (state, streaming_state) = (
+        chip.final_state.as_ref().unwrap(),
+        streaming_chip.final_state.unwrap(),
+    );
+    assert_eq!(streaming_state.init_root, state.init_root);
+    assert_eq!(streaming_state.final_root, state.final_root);
+    assert_eq!(streaming_state.rows, state.rows);
     let chip_api = chip.generate_air_proof_input();
 
     let dummy_interaction_air = DummyInteractionAir::new(4 + CHUNK, true, merkle_bus.0);
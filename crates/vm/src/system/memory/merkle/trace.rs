@@ -1,4 +1,4 @@
-use std::{borrow::BorrowMut, cmp::Reverse, sync::Arc};
+use std::{borrow::BorrowMut, cmp::Reverse, collections::BTreeMap, sync::Arc};
 
 use openvm_stark_backend::{
     config::{StarkGenericConfig, Val},
@@ -11,22 +11,26 @@ use openvm_stark_backend::{
 use rustc_hash::FxHashSet;
 
 use crate::{
-    arch::hasher::HasherChip,
+    arch::hasher::{Hasher, HasherChip},
     system::memory::{
         manager::dimensions::MemoryDimensions,
         merkle::{FinalState, MemoryMerkleChip, MemoryMerkleCols},
         tree::MemoryNode::{self, NonLeaf},
-        Equipartition,
+        Equipartition, TimestampedEquipartition,
     },
 };
 
 impl<const CHUNK: usize, F: PrimeField32> MemoryMerkleChip<CHUNK, F> {
+    /// Generates the trace rows from the initial memory tree and returns the final memory tree.
+    ///
+    /// Only paths to touched leaves are rehashed; untouched subtrees are shared with
+    /// `initial_tree`, so the returned tree can be used as the initial tree of the next segment.
     pub fn finalize(
         &mut self,
         initial_tree: &MemoryNode<CHUNK, F>,
         final_memory: &Equipartition<F, CHUNK>,
         hasher: &mut impl HasherChip<CHUNK, F>,
-    ) {
+    ) -> MemoryNode<CHUNK, F> {
         assert!(self.final_state.is_none(), "Merkle chip already finalized");
         // there needs to be a touched node with `height_section` = 0
         // shouldn't be a leaf because
@@ -54,19 +58,57 @@ impl<const CHUNK: usize, F: PrimeField32> MemoryMerkleChip<CHUNK, F> {
             init_root: initial_tree.hash(),
             final_root: final_tree.hash(),
         });
+        final_tree
     }
-}
 
-impl<const CHUNK: usize, SC: StarkGenericConfig> Chip<SC> for MemoryMerkleChip<CHUNK, Val<SC>>
-where
-    Val<SC>: PrimeField32,
-{
-    fn air(&self) -> Arc<dyn AnyRap<SC>> {
-        Arc::new(self.air.clone())
+    /// Same as [Self::finalize], but without materializing the initial memory tree.
+    ///
+    /// Only the paths to touched leaves are visited. The roots of untouched subtrees are hashed
+    /// on the fly from `initial_memory` and immediately discarded, so peak memory scales with the
+    /// number of touched nodes rather than with the size of the initial memory.
+    pub fn finalize_streaming(
+        &mut self,
+        initial_memory: &Equipartition<F, CHUNK>,
+        final_memory: &TimestampedEquipartition<F, CHUNK>,
+        hasher: &mut impl HasherChip<CHUNK, F>,
+    ) {
+        assert!(self.final_state.is_none(), "Merkle chip already finalized");
+        // See `finalize`.
+        if self.touched_nodes.len() == 1 {
+            self.touch_node(1, 0, 0);
+        }
+
+        let memory_dimensions = self.air.memory_dimensions;
+        let mut zero_hashes = vec![hasher.hash(&[F::ZERO; CHUNK])];
+        for height in 1..=memory_dimensions.overall_height() {
+            let child = zero_hashes[height - 1];
+            zero_hashes.push(hasher.compress(&child, &child));
+        }
+
+        let mut rows = vec![];
+        let mut helper = StreamingTreeHelper {
+            memory_dimensions,
+            initial_memory: initial_memory
+                .iter()
+                .map(|(&label, values)| (memory_dimensions.label_to_index(label), values))
+                .collect(),
+            zero_hashes,
+            final_memory,
+            touched_nodes: &self.touched_nodes,
+            trace_rows: &mut rows,
+        };
+        let (init_root, final_root) =
+            helper.recur(memory_dimensions.overall_height(), 0, 0, hasher);
+        self.final_state = Some(FinalState {
+            rows,
+            init_root,
+            final_root,
+        });
     }
 
-    fn generate_air_proof_input(self) -> AirProofInput<SC> {
-        let air = Arc::new(self.air);
+    /// Generates the trace matrix together with the public values, which are the initial root
+    /// followed by the final root.
+    pub fn generate_trace(self) -> (RowMajorMatrix<F>, Vec<F>) {
         assert!(
             self.final_state.is_some(),
             "Merkle chip must finalize before trace generation"
@@ -81,7 +123,7 @@ where
         // TODO: do we only need find all height == 0 instead of sorting?
         rows.sort_by_key(|row| Reverse(row.parent_height));
 
-        let width = MemoryMerkleCols::<Val<SC>, CHUNK>::width();
+        let width = MemoryMerkleCols::<F, CHUNK>::width();
         let mut height = rows.len().next_power_of_two();
         if let Some(mut oh) = self.overridden_height {
             oh = oh.next_power_of_two();
@@ -91,7 +133,7 @@ where
             );
             height = oh;
         }
-        let mut trace = Val::<SC>::zero_vec(width * height);
+        let mut trace = F::zero_vec(width * height);
 
         for (trace_row, row) in trace.chunks_exact_mut(width).zip(rows) {
             *trace_row.borrow_mut() = row;
@@ -99,6 +141,21 @@ where
 
         let trace = RowMajorMatrix::new(trace, width);
         let pvs = init_root.into_iter().chain(final_root).collect();
+        (trace, pvs)
+    }
+}
+
+impl<const CHUNK: usize, SC: StarkGenericConfig> Chip<SC> for MemoryMerkleChip<CHUNK, Val<SC>>
+where
+    Val<SC>: PrimeField32,
+{
+    fn air(&self) -> Arc<dyn AnyRap<SC>> {
+        Arc::new(self.air.clone())
+    }
+
+    fn generate_air_proof_input(self) -> AirProofInput<SC> {
+        let air = Arc::new(self.air.clone());
+        let (trace, pvs) = self.generate_trace();
         AirProofInput::simple(air, trace, pvs)
     }
 }
@@ -219,29 +276,165 @@ impl<const CHUNK: usize, F: PrimeField32> TreeHelper<'_, CHUNK, F> {
         node: &MemoryNode<CHUNK, F>,
         direction_changes: Option<[bool; 2]>,
     ) {
-        let [left_direction_change, right_direction_change] =
-            direction_changes.unwrap_or([false; 2]);
         let cols = if let NonLeaf { hash, left, right } = node {
-            MemoryMerkleCols {
-                expand_direction: if direction_changes.is_none() {
-                    F::ONE
-                } else {
-                    F::NEG_ONE
-                },
-                height_section: F::from_bool(parent_height > self.memory_dimensions.address_height),
-                parent_height: F::from_canonical_usize(parent_height),
-                is_root: F::from_bool(parent_height == self.memory_dimensions.overall_height()),
-                parent_as_label: F::from_canonical_u32(as_label),
-                parent_address_label: F::from_canonical_u32(address_label),
-                parent_hash: *hash,
-                left_child_hash: left.hash(),
-                right_child_hash: right.hash(),
-                left_direction_different: F::from_bool(left_direction_change),
-                right_direction_different: F::from_bool(right_direction_change),
-            }
+            merkle_row(
+                self.memory_dimensions,
+                parent_height,
+                as_label,
+                address_label,
+                [*hash, left.hash(), right.hash()],
+                direction_changes,
+            )
         } else {
             panic!("trace_rows expects node = {:?} to be NonLeaf", node);
         };
         self.trace_rows.push(cols);
     }
 }
+
+struct StreamingTreeHelper<'a, const CHUNK: usize, F: PrimeField32> {
+    memory_dimensions: MemoryDimensions,
+    /// Initial memory keyed by leaf index in the tree.
+    initial_memory: BTreeMap<u64, &'a [F; CHUNK]>,
+    /// `zero_hashes[h]` is the root of a subtree of height `h` with all leaves zero.
+    zero_hashes: Vec<[F; CHUNK]>,
+    final_memory: &'a TimestampedEquipartition<F, CHUNK>,
+    touched_nodes: &'a FxHashSet<(usize, u32, u32)>,
+    trace_rows: &'a mut Vec<MemoryMerkleCols<F, CHUNK>>,
+}
+
+impl<const CHUNK: usize, F: PrimeField32> StreamingTreeHelper<'_, CHUNK, F> {
+    /// Index of the leftmost leaf of the subtree rooted at the given node.
+    fn first_leaf_index(&self, height: usize, as_label: u32, address_label: u32) -> u64 {
+        let address_height = self.memory_dimensions.address_height;
+        if height >= address_height {
+            (as_label as u64) << height
+        } else {
+            ((as_label as u64) << address_height) + ((address_label as u64) << height)
+        }
+    }
+
+    /// Root of an untouched subtree of the initial memory, computed without recording hashes.
+    fn initial_hash(&self, height: usize, from: u64, hasher: &impl Hasher<CHUNK, F>) -> [F; CHUNK] {
+        if self
+            .initial_memory
+            .range(from..from + (1 << height))
+            .next()
+            .is_none()
+        {
+            self.zero_hashes[height]
+        } else if height == 0 {
+            hasher.hash(self.initial_memory[&from])
+        } else {
+            let midpoint = from + (1 << (height - 1));
+            hasher.compress(
+                &self.initial_hash(height - 1, from, hasher),
+                &self.initial_hash(height - 1, midpoint, hasher),
+            )
+        }
+    }
+
+    /// Returns the initial and final hashes of a touched node, adding trace rows for every
+    /// touched non-leaf node in its subtree.
+    fn recur(
+        &mut self,
+        height: usize,
+        as_label: u32,
+        address_label: u32,
+        hasher: &mut impl HasherChip<CHUNK, F>,
+    ) -> ([F; CHUNK], [F; CHUNK]) {
+        if height == 0 {
+            let address_space = as_label + self.memory_dimensions.as_offset;
+            let initial_hash = self.initial_hash(
+                0,
+                self.first_leaf_index(0, as_label, address_label),
+                &*hasher,
+            );
+            let final_values = self
+                .final_memory
+                .get(&(address_space, address_label))
+                .map_or([F::ZERO; CHUNK], |v| v.values);
+            return (initial_hash, hasher.hash(&final_values));
+        }
+
+        let is_as_section = height > self.memory_dimensions.address_height;
+        let children = if is_as_section {
+            [
+                (2 * as_label, address_label),
+                (2 * as_label + 1, address_label),
+            ]
+        } else {
+            [
+                (as_label, 2 * address_label),
+                (as_label, 2 * address_label + 1),
+            ]
+        };
+
+        let [left, right] = children.map(|(child_as_label, child_address_label)| {
+            if self
+                .touched_nodes
+                .contains(&(height - 1, child_as_label, child_address_label))
+            {
+                let (initial, fin) =
+                    self.recur(height - 1, child_as_label, child_address_label, hasher);
+                (initial, fin, false)
+            } else {
+                let from = self.first_leaf_index(height - 1, child_as_label, child_address_label);
+                let hash = self.initial_hash(height - 1, from, &*hasher);
+                (hash, hash, true)
+            }
+        });
+        let (left_initial, left_final, left_is_final) = left;
+        let (right_initial, right_final, right_is_final) = right;
+
+        let initial_hash = hasher.compress_and_record(&left_initial, &right_initial);
+        let final_hash = hasher.compress_and_record(&left_final, &right_final);
+        self.trace_rows.push(merkle_row(
+            self.memory_dimensions,
+            height,
+            as_label,
+            address_label,
+            [initial_hash, left_initial, right_initial],
+            None,
+        ));
+        self.trace_rows.push(merkle_row(
+            self.memory_dimensions,
+            height,
+            as_label,
+            address_label,
+            [final_hash, left_final, right_final],
+            Some([left_is_final, right_is_final]),
+        ));
+        (initial_hash, final_hash)
+    }
+}
+
+/// `hashes` are the parent, left child and right child hashes, in that order.
+fn merkle_row<const CHUNK: usize, F: PrimeField32>(
+    memory_dimensions: MemoryDimensions,
+    parent_height: usize,
+    as_label: u32,
+    address_label: u32,
+    hashes: [[F; CHUNK]; 3],
+    direction_changes: Option<[bool; 2]>,
+) -> MemoryMerkleCols<F, CHUNK> {
+    let [parent_hash, left_child_hash, right_child_hash] = hashes;
+    let [left_direction_change, right_direction_change] = direction_changes.unwrap_or([false; 2]);
+    MemoryMerkleCols {
+        expand_direction: if direction_changes.is_none() {
+            F::ONE
+        } else {
+            F::NEG_ONE
+        },
+        height_section: F::from_bool(parent_height > memory_dimensions.address_height),
+        parent_height: F::from_canonical_usize(parent_height),
+        is_root: F::from_bool(parent_height == memory_dimensions.overall_height()),
+        parent_as_label: F::from_canonical_u32(as_label),
+        parent_address_label: F::from_canonical_u32(address_label),
+        parent_hash,
+        left_child_hash,
+        right_child_hash,
+        left_direction_different: F::from_bool(left_direction_change),
+        right_direction_different: F::from_bool(right_direction_change),
+    }
+}
@@ -1,4 +1,5 @@
 use openvm_circuit_primitives_derive::AlignedBorrow;
+use serde::{Deserialize, Serialize};
 
 mod adapter;
 mod manager;
@@ -11,8 +12,10 @@ pub mod tree;
 mod volatile;
 
 pub use manager::*;
+pub use persistent::MAX_READ_ONLY_ADDRESS_SPACES;
+pub use volatile::{VolatileBoundaryChip, VolatileBoundaryEntry};
 
-#[derive(PartialEq, Copy, Clone, Debug, Eq)]
+#[derive(PartialEq, Copy, Clone, Debug, Eq, Serialize, Deserialize)]
 pub enum OpType {
     Read = 0,
     Write = 1,
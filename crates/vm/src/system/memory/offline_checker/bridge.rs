@@ -23,6 +23,14 @@ use crate::system::memory::{
 ///         in MemoryOfflineChecker (or whenever AssertLtSubAir is used)
 pub(crate) const AUX_LEN: usize = 2;
 
+/// Largest supported `clk_max_bits`.
+///
+/// Timestamps are single field elements on the memory bus, and every timestamp must be checked
+/// to be greater than the previous one without wrapping around the field. Wider timestamps would
+/// need a multi-limb timestamp on the memory bus, which every chip accessing memory would have to
+/// send, so executions with more memory accesses must be split into segments instead.
+pub const MAX_CLK_BITS: usize = 29;
+
 /// The [MemoryBridge] is used within AIR evaluation functions to constrain logical memory operations (read/write).
 /// It adds all necessary constraints and interactions.
 #[derive(Clone, Copy, Debug)]
@@ -32,6 +40,9 @@ pub struct MemoryBridge {
 
 impl MemoryBridge {
     /// Create a new [MemoryBridge] with the provided offline_checker.
+    ///
+    /// Panics if `clk_max_bits` exceeds [MAX_CLK_BITS], or if timestamp differences do not
+    /// decompose into exactly `AUX_LEN` limbs of the range checker.
     pub fn new(
         memory_bus: MemoryBus,
         clk_max_bits: usize,
@@ -95,6 +106,45 @@ impl MemoryBridge {
             aux,
         }
     }
+
+    /// Prepare a logical memory read that only happens when the boolean `condition` is true.
+    ///
+    /// The read consumes a timestamp only when it happens, matching
+    /// [MemoryController::read_conditional](crate::system::memory::MemoryController::read_conditional),
+    /// so [ConditionalMemoryReadOperation::eval] returns the timestamp increment to add to the
+    /// timestamp of the next access. The auxiliary columns of a skipped read should be
+    /// [MemoryReadAuxCols::disabled].
+    #[must_use]
+    pub fn read_conditional<'a, T, V, const N: usize>(
+        &self,
+        address: MemoryAddress<impl Into<T>, impl Into<T>>,
+        data: [impl Into<T>; N],
+        timestamp: impl Into<T>,
+        aux: &'a MemoryReadAuxCols<V, N>,
+        condition: impl Into<T>,
+    ) -> ConditionalMemoryReadOperation<'a, T, V, N> {
+        ConditionalMemoryReadOperation {
+            op: self.read(address, data, timestamp, aux),
+            condition: condition.into(),
+        }
+    }
+
+    /// Prepare a logical memory write that only happens when the boolean `condition` is true.
+    /// See [Self::read_conditional].
+    #[must_use]
+    pub fn write_conditional<'a, T, V, const N: usize>(
+        &self,
+        address: MemoryAddress<impl Into<T>, impl Into<T>>,
+        data: [impl Into<T>; N],
+        timestamp: impl Into<T>,
+        aux: &'a MemoryWriteAuxCols<V, N>,
+        condition: impl Into<T>,
+    ) -> ConditionalMemoryWriteOperation<'a, T, V, N> {
+        ConditionalMemoryWriteOperation {
+            op: self.write(address, data, timestamp, aux),
+            condition: condition.into(),
+        }
+    }
 }
 
 /// Constraints and interactions for a logical memory read of `(address, data)` at time `timestamp`.
@@ -256,6 +306,58 @@ impl<T: AbstractField, V: Copy + Into<T>, const N: usize> MemoryWriteOperation<'
     }
 }
 
+/// A [MemoryReadOperation] that only happens when `condition` is true.
+pub struct ConditionalMemoryReadOperation<'a, T, V, const N: usize> {
+    op: MemoryReadOperation<'a, T, V, N>,
+    condition: T,
+}
+
+/// The max degree of constraints is that of [MemoryReadOperation] with
+/// `deg(enabled) + deg(condition)` in place of `deg(enabled)`.
+impl<F: AbstractField, V: Copy + Into<F>, const N: usize>
+    ConditionalMemoryReadOperation<'_, F, V, N>
+{
+    /// Evaluate constraints and send/receive interactions, and constrain `condition` to be
+    /// boolean when `enabled`. Returns the timestamp increment of the read, i.e. `condition`.
+    pub fn eval<AB>(self, builder: &mut AB, enabled: impl Into<AB::Expr>) -> F
+    where
+        AB: InteractionBuilder<Var = V, Expr = F>,
+    {
+        let enabled = enabled.into();
+        builder
+            .when(enabled.clone())
+            .assert_bool(self.condition.clone());
+        self.op.eval(builder, enabled * self.condition.clone());
+        self.condition
+    }
+}
+
+/// A [MemoryWriteOperation] that only happens when `condition` is true.
+pub struct ConditionalMemoryWriteOperation<'a, T, V, const N: usize> {
+    op: MemoryWriteOperation<'a, T, V, N>,
+    condition: T,
+}
+
+/// The max degree of constraints is that of [MemoryWriteOperation] with
+/// `deg(enabled) + deg(condition)` in place of `deg(enabled)`.
+impl<T: AbstractField, V: Copy + Into<T>, const N: usize>
+    ConditionalMemoryWriteOperation<'_, T, V, N>
+{
+    /// Evaluate constraints and send/receive interactions, and constrain `condition` to be
+    /// boolean when `enabled`. Returns the timestamp increment of the write, i.e. `condition`.
+    pub fn eval<AB>(self, builder: &mut AB, enabled: impl Into<AB::Expr>) -> T
+    where
+        AB: InteractionBuilder<Var = V, Expr = T>,
+    {
+        let enabled = enabled.into();
+        builder
+            .when(enabled.clone())
+            .assert_bool(self.condition.clone());
+        self.op.eval(builder, enabled * self.condition.clone());
+        self.condition
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 struct MemoryOfflineChecker {
     memory_bus: MemoryBus,
@@ -264,6 +366,16 @@ struct MemoryOfflineChecker {
 
 impl MemoryOfflineChecker {
     fn new(memory_bus: MemoryBus, clk_max_bits: usize, range_bus: VariableRangeCheckerBus) -> Self {
+        assert!(
+            clk_max_bits <= MAX_CLK_BITS,
+            "clk_max_bits {clk_max_bits} exceeds the maximum of {MAX_CLK_BITS}"
+        );
+        assert_eq!(
+            clk_max_bits.div_ceil(range_bus.range_max_bits),
+            AUX_LEN,
+            "{clk_max_bits}-bit timestamps must decompose into {AUX_LEN} limbs of {} bits",
+            range_bus.range_max_bits
+        );
         Self {
             memory_bus,
             timestamp_lt_air: AssertLtSubAir::new(range_bus, clk_max_bits),
@@ -5,7 +5,7 @@ use std::{array, borrow::Borrow, iter};
 
 use openvm_circuit_primitives::is_less_than::LessThanAuxCols;
 use openvm_circuit_primitives_derive::AlignedBorrow;
-use openvm_stark_backend::p3_field::{AbstractField, PrimeField32};
+use openvm_stark_backend::p3_field::AbstractField;
 
 use crate::system::memory::offline_checker::bridge::AUX_LEN;
 
@@ -21,6 +21,15 @@ pub struct MemoryBaseAuxCols<T> {
     pub(super) clk_lt_aux: LessThanAuxCols<T, AUX_LEN>,
 }
 
+impl<T> MemoryBaseAuxCols<T> {
+    pub fn new(prev_timestamp: T, clk_lt_aux: LessThanAuxCols<T, AUX_LEN>) -> Self {
+        Self {
+            prev_timestamp,
+            clk_lt_aux,
+        }
+    }
+}
+
 impl<T: Clone> MemoryBaseAuxCols<T> {
     /// TODO[arayi]: Since we have AlignedBorrow, should remove all from_slice, from_iterator, and flatten in a future PR.
     pub fn from_slice(slc: &[T]) -> Self {
@@ -51,18 +60,6 @@ pub struct MemoryWriteAuxCols<T, const N: usize> {
     pub prev_data: [T; N],
 }
 
-impl<const N: usize, T> MemoryWriteAuxCols<T, N> {
-    pub fn new(prev_data: [T; N], prev_timestamp: T, lt_aux: LessThanAuxCols<T, AUX_LEN>) -> Self {
-        Self {
-            base: MemoryBaseAuxCols {
-                prev_timestamp,
-                clk_lt_aux: lt_aux,
-            },
-            prev_data,
-        }
-    }
-}
-
 impl<const N: usize, T: Clone> MemoryWriteAuxCols<T, N> {
     pub fn from_slice(slc: &[T]) -> Self {
         let width = MemoryBaseAuxCols::<T>::width();
@@ -114,14 +111,9 @@ pub struct MemoryReadAuxCols<T, const N: usize> {
     pub(super) base: MemoryBaseAuxCols<T>,
 }
 
-impl<const N: usize, F: PrimeField32> MemoryReadAuxCols<F, N> {
-    pub fn new(prev_timestamp: u32, clk_lt_aux: LessThanAuxCols<F, AUX_LEN>) -> Self {
-        Self {
-            base: MemoryBaseAuxCols {
-                prev_timestamp: F::from_canonical_u32(prev_timestamp),
-                clk_lt_aux,
-            },
-        }
+impl<const N: usize, T> MemoryReadAuxCols<T, N> {
+    pub fn from_base(base: MemoryBaseAuxCols<T>) -> Self {
+        Self { base }
     }
 }
 
@@ -231,17 +223,9 @@ pub struct MemoryReadOrImmediateAuxCols<T> {
 }
 
 impl<T> MemoryReadOrImmediateAuxCols<T> {
-    pub fn new(
-        prev_timestamp: T,
-        is_immediate: T,
-        is_zero_aux: T,
-        clk_lt_aux: LessThanAuxCols<T, AUX_LEN>,
-    ) -> Self {
+    pub fn new(base: MemoryBaseAuxCols<T>, is_immediate: T, is_zero_aux: T) -> Self {
         Self {
-            base: MemoryBaseAuxCols {
-                prev_timestamp,
-                clk_lt_aux,
-            },
+            base,
             is_immediate,
             is_zero_aux,
         }
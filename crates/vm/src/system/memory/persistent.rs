@@ -11,7 +11,7 @@ use openvm_stark_backend::{
     config::{StarkGenericConfig, Val},
     interaction::InteractionBuilder,
     p3_air::{Air, BaseAir},
-    p3_field::{AbstractField, PrimeField32},
+    p3_field::{AbstractField, Field, PrimeField32},
     p3_matrix::{dense::RowMajorMatrix, Matrix},
     p3_maybe_rayon::prelude::{IntoParallelIterator, ParallelIterator, ParallelSliceMut},
     prover::types::AirProofInput,
@@ -43,10 +43,22 @@ pub struct PersistentBoundaryCols<T, const CHUNK: usize> {
     pub values: [T; CHUNK],
     pub hash: [T; CHUNK],
     pub timestamp: T,
+    /// Non-zero if `address_space` is read-only, in which case an initial row must be followed by
+    /// the final row of the same chunk, with the same values.
+    pub read_only: T,
+    /// Inverse of the product of `address_space - a` over the read-only address spaces `a`, if
+    /// `address_space` is not one of them.
+    pub read_write_inv: T,
 }
 
+/// Largest number of read-only address spaces supported by [PersistentBoundaryAir], which keeps
+/// the constraint detecting them of degree at most 3.
+pub const MAX_READ_ONLY_ADDRESS_SPACES: usize = 2;
+
 /// Imposes the following constraints:
 /// - `expand_direction` should be -1, 0, 1
+/// - the initial row of a chunk in a read-only address space is followed by the final row of the
+///   chunk, with the same values
 ///
 /// Sends the following interactions:
 /// - if `expand_direction` is 1, sends `[0, 0, address_space_label, leaf_label]` to `merkle_bus`.
@@ -57,6 +69,32 @@ pub struct PersistentBoundaryAir<const CHUNK: usize> {
     pub memory_bus: MemoryBus,
     pub merkle_bus: MemoryMerkleBus,
     pub compression_bus: DirectCompressionBus,
+    /// At most [MAX_READ_ONLY_ADDRESS_SPACES] address spaces whose final memory must equal their
+    /// initial memory.
+    pub read_only_address_spaces: Vec<u32>,
+}
+
+impl<const CHUNK: usize> PersistentBoundaryAir<CHUNK> {
+    /// The product of `address_space - a` over the read-only address spaces `a`, which is zero
+    /// exactly when `address_space` is read-only.
+    fn read_write_factor<E: AbstractField>(&self, address_space: E) -> E {
+        self.read_only_address_spaces
+            .iter()
+            .fold(E::ONE, |acc, &a| {
+                acc * (address_space.clone() - E::from_canonical_u32(a))
+            })
+    }
+
+    /// The values of [PersistentBoundaryCols::read_only] and
+    /// [PersistentBoundaryCols::read_write_inv] for a row of `address_space`.
+    fn read_only_cols<F: Field>(&self, address_space: u32) -> (F, F) {
+        let factor = self.read_write_factor(F::from_canonical_u32(address_space));
+        if factor.is_zero() {
+            (F::ONE, F::ZERO)
+        } else {
+            (F::ZERO, factor.inverse())
+        }
+    }
 }
 
 impl<const CHUNK: usize, F> BaseAir<F> for PersistentBoundaryAir<CHUNK> {
@@ -71,8 +109,9 @@ impl<const CHUNK: usize, F> PartitionedBaseAir<F> for PersistentBoundaryAir<CHUN
 impl<const CHUNK: usize, AB: InteractionBuilder> Air<AB> for PersistentBoundaryAir<CHUNK> {
     fn eval(&self, builder: &mut AB) {
         let main = builder.main();
-        let local = main.row_slice(0);
+        let (local, next) = (main.row_slice(0), main.row_slice(1));
         let local: &PersistentBoundaryCols<AB::Var, CHUNK> = (*local).borrow();
+        let next: &PersistentBoundaryCols<AB::Var, CHUNK> = (*next).borrow();
 
         // `direction` should be -1, 0, 1
         builder.assert_eq(
@@ -80,6 +119,25 @@ impl<const CHUNK: usize, AB: InteractionBuilder> Air<AB> for PersistentBoundaryA
             local.expand_direction * local.expand_direction * local.expand_direction,
         );
 
+        if !self.read_only_address_spaces.is_empty() {
+            // `read_only` is non-zero whenever `address_space` is read-only
+            builder.assert_eq(
+                self.read_write_factor::<AB::Expr>(local.address_space.into())
+                    * local.read_write_inv,
+                AB::Expr::ONE - local.read_only,
+            );
+            // Each chunk has a single final row, since it is a leaf of the final memory tree, so
+            // the final values of a read-only chunk are its initial values.
+            let mut when_read_only_initial =
+                builder.when(local.read_only * (AB::Expr::ONE + local.expand_direction));
+            when_read_only_initial.assert_eq(next.expand_direction, AB::F::NEG_ONE);
+            when_read_only_initial.assert_eq(next.address_space, local.address_space);
+            when_read_only_initial.assert_eq(next.leaf_label, local.leaf_label);
+            for i in 0..CHUNK {
+                when_read_only_initial.assert_eq(next.values[i], local.values[i]);
+            }
+        }
+
         // TODO[zach]: Make bus interface.
         // Interactions.
         let mut expand_fields = vec![
@@ -173,13 +231,16 @@ impl<const CHUNK: usize, F: PrimeField32> PersistentBoundaryChip<F, CHUNK> {
         memory_bus: MemoryBus,
         merkle_bus: MemoryMerkleBus,
         compression_bus: DirectCompressionBus,
+        read_only_address_spaces: Vec<u32>,
     ) -> Self {
+        assert!(read_only_address_spaces.len() <= MAX_READ_ONLY_ADDRESS_SPACES);
         Self {
             air: PersistentBoundaryAir {
                 memory_dims: memory_dimensions,
                 memory_bus,
                 merkle_bus,
                 compression_bus,
+                read_only_address_spaces,
             },
             touched_labels: Default::default(),
             overridden_height: None,
@@ -195,6 +256,18 @@ impl<const CHUNK: usize, F: PrimeField32> PersistentBoundaryChip<F, CHUNK> {
         self.touched_labels.touch(address_space, label);
     }
 
+    /// Panics if called after finalization.
+    pub(crate) fn touched_labels(&self) -> &FxHashSet<(u32, u32)> {
+        match &self.touched_labels {
+            TouchedLabels::Running(touched_labels) => touched_labels,
+            _ => panic!("Touched labels are not available after finalization"),
+        }
+    }
+
+    pub(crate) fn set_touched_labels(&mut self, touched_labels: FxHashSet<(u32, u32)>) {
+        self.touched_labels = TouchedLabels::Running(touched_labels);
+    }
+
     pub fn finalize(
         &mut self,
         initial_memory: &Equipartition<F, CHUNK>,
@@ -235,6 +308,67 @@ impl<const CHUNK: usize, F: PrimeField32> PersistentBoundaryChip<F, CHUNK> {
             _ => panic!("Cannot finalize after finalization"),
         }
     }
+
+    pub fn generate_trace(self) -> RowMajorMatrix<F> {
+        let width = PersistentBoundaryCols::<F, CHUNK>::width();
+        // Boundary AIR should always present in order to fix the AIR ID of merkle AIR.
+        let mut height = (2 * self.touched_labels.len()).next_power_of_two();
+        if let Some(mut oh) = self.overridden_height {
+            oh = oh.next_power_of_two();
+            assert!(
+                oh >= height,
+                "Overridden height is less than the required height"
+            );
+            height = oh;
+        }
+        let mut rows = F::zero_vec(height * width);
+
+        let touched_labels = match self.touched_labels {
+            TouchedLabels::Final(touched_labels) => touched_labels,
+            _ => panic!("Cannot generate trace before finalization"),
+        };
+
+        let num_rows = 2 * touched_labels.len();
+        let air = &self.air;
+        rows.par_chunks_mut(2 * width)
+            .zip(touched_labels.into_par_iter())
+            .for_each(|(row, touched_label)| {
+                let (read_only, read_write_inv) = air.read_only_cols(touched_label.address_space);
+                let (initial_row, final_row) = row.split_at_mut(width);
+                *initial_row.borrow_mut() = PersistentBoundaryCols {
+                    expand_direction: F::ONE,
+                    address_space: F::from_canonical_u32(touched_label.address_space),
+                    leaf_label: F::from_canonical_u32(touched_label.label),
+                    values: touched_label.init_values,
+                    hash: touched_label.init_hash,
+                    timestamp: if touched_label.init_exists {
+                        F::from_canonical_u32(INITIAL_TIMESTAMP)
+                    } else {
+                        F::ZERO
+                    },
+                    read_only,
+                    read_write_inv,
+                };
+
+                *final_row.borrow_mut() = PersistentBoundaryCols {
+                    expand_direction: F::NEG_ONE,
+                    address_space: F::from_canonical_u32(touched_label.address_space),
+                    leaf_label: F::from_canonical_u32(touched_label.label),
+                    values: touched_label.final_values,
+                    hash: touched_label.final_hash,
+                    timestamp: F::from_canonical_u32(touched_label.final_timestamp),
+                    read_only,
+                    read_write_inv,
+                };
+            });
+        // Padding rows are in address space 0, which is never read-only
+        let (_, padding_inv) = air.read_only_cols::<F>(0);
+        rows.par_chunks_mut(width).skip(num_rows).for_each(|row| {
+            let row: &mut PersistentBoundaryCols<F, CHUNK> = row.borrow_mut();
+            row.read_write_inv = padding_inv;
+        });
+        RowMajorMatrix::new(rows, width)
+    }
 }
 
 impl<const CHUNK: usize, SC: StarkGenericConfig> Chip<SC> for PersistentBoundaryChip<Val<SC>, CHUNK>
@@ -246,55 +380,8 @@ where
     }
 
     fn generate_air_proof_input(self) -> AirProofInput<SC> {
-        let air = Arc::new(self.air);
-        let trace = {
-            let width = PersistentBoundaryCols::<Val<SC>, CHUNK>::width();
-            // Boundary AIR should always present in order to fix the AIR ID of merkle AIR.
-            let mut height = (2 * self.touched_labels.len()).next_power_of_two();
-            if let Some(mut oh) = self.overridden_height {
-                oh = oh.next_power_of_two();
-                assert!(
-                    oh >= height,
-                    "Overridden height is less than the required height"
-                );
-                height = oh;
-            }
-            let mut rows = Val::<SC>::zero_vec(height * width);
-
-            let touched_labels = match self.touched_labels {
-                TouchedLabels::Final(touched_labels) => touched_labels,
-                _ => panic!("Cannot generate trace before finalization"),
-            };
-
-            rows.par_chunks_mut(2 * width)
-                .zip(touched_labels.into_par_iter())
-                .for_each(|(row, touched_label)| {
-                    let (initial_row, final_row) = row.split_at_mut(width);
-                    *initial_row.borrow_mut() = PersistentBoundaryCols {
-                        expand_direction: Val::<SC>::ONE,
-                        address_space: Val::<SC>::from_canonical_u32(touched_label.address_space),
-                        leaf_label: Val::<SC>::from_canonical_u32(touched_label.label),
-                        values: touched_label.init_values,
-                        hash: touched_label.init_hash,
-                        timestamp: if touched_label.init_exists {
-                            Val::<SC>::from_canonical_u32(INITIAL_TIMESTAMP)
-                        } else {
-                            Val::<SC>::ZERO
-                        },
-                    };
-
-                    *final_row.borrow_mut() = PersistentBoundaryCols {
-                        expand_direction: Val::<SC>::NEG_ONE,
-                        address_space: Val::<SC>::from_canonical_u32(touched_label.address_space),
-                        leaf_label: Val::<SC>::from_canonical_u32(touched_label.label),
-                        values: touched_label.final_values,
-                        hash: touched_label.final_hash,
-                        timestamp: Val::<SC>::from_canonical_u32(touched_label.final_timestamp),
-                    };
-                });
-            RowMajorMatrix::new(rows, width)
-        };
-        AirProofInput::simple_no_pis(air, trace)
+        let air = Arc::new(self.air.clone());
+        AirProofInput::simple_no_pis(air, self.generate_trace())
     }
 }
 
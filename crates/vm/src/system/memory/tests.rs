@@ -27,19 +27,25 @@ use rand::{
 };
 
 use super::{
-    merkle::DirectCompressionBus, Equipartition, MemoryAuxColsFactory, MemoryController,
-    MemoryReadRecord,
+    merkle::{tests::util::HashTestChip, DirectCompressionBus},
+    Equipartition, MemoryAuxColsFactory, MemoryController, MemoryReadRecord, CHUNK,
 };
 use crate::{
     arch::{
-        testing::memory::gen_pointer, MemoryConfig, MEMORY_BUS, MEMORY_MERKLE_BUS,
-        POSEIDON2_DIRECT_BUS,
+        testing::memory::{
+            fuzz::{
+                fuzz_memory, generate_memory_ops, shrink_memory_ops, MemoryFuzzConfig, MemoryFuzzOp,
+            },
+            gen_pointer,
+        },
+        MemoryConfig, MEMORY_BUS, MEMORY_MERKLE_BUS, POSEIDON2_DIRECT_BUS,
     },
     system::{
         memory::{
             merkle::MemoryMerkleBus,
             offline_checker::{MemoryBridge, MemoryBus, MemoryReadAuxCols, MemoryWriteAuxCols},
-            MemoryAddress, MemoryWriteRecord,
+            tree::MemoryNode,
+            MemoryAddress, MemoryWriteRecord, OpType,
         },
         poseidon2::Poseidon2PeripheryChip,
     },
@@ -268,7 +274,7 @@ fn test_memory_controller_persistent() {
         range_checker.clone(),
         merkle_bus,
         compression_bus,
-        Equipartition::new(),
+        Equipartition::<_, CHUNK>::new(),
     );
     let aux_factory = memory_controller.aux_cols_factory();
 
@@ -295,8 +301,191 @@ fn test_memory_controller_persistent() {
     BabyBearPoseidon2Engine::run_test_fast(air_proof_inputs).expect("Verification failed");
 }
 
-fn make_random_accesses<F: PrimeField32>(
-    memory_controller: &mut MemoryController<F>,
+#[test]
+fn test_memory_controller_persistent_injected_memory() {
+    let memory_bus = MemoryBus(MEMORY_BUS);
+    let merkle_bus = MemoryMerkleBus(MEMORY_MERKLE_BUS);
+    let compression_bus = DirectCompressionBus(POSEIDON2_DIRECT_BUS);
+    let memory_config = MemoryConfig::default();
+    let range_bus = VariableRangeCheckerBus::new(RANGE_CHECKER_BUS, memory_config.decomp);
+    let range_checker = Arc::new(VariableRangeCheckerChip::new(range_bus));
+
+    let mut memory_controller = MemoryController::with_persistent_memory(
+        memory_bus,
+        memory_config,
+        range_checker.clone(),
+        merkle_bus,
+        compression_bus,
+        Equipartition::<_, CHUNK>::new(),
+    );
+    let aux_factory = memory_controller.aux_cols_factory();
+
+    let mut rng = create_seeded_rng();
+    let injected = (0..100)
+        .map(|_| BabyBear::from_canonical_u32(rng.gen_range(0..1 << 30)))
+        .collect_vec();
+    memory_controller.inject_initial_memory(2, 1003, &injected);
+
+    let record = memory_controller.read::<4>(BabyBear::TWO, BabyBear::from_canonical_u32(1004));
+    assert_eq!(record.data[..], injected[1..5]);
+    let mut records = vec![Record::Read4(record)];
+    records.extend(make_random_accesses(&mut memory_controller, &mut rng));
+    let memory_requester_trace = generate_trace(records, aux_factory);
+
+    let memory_requester_air = MemoryRequesterAir {
+        memory_bridge: memory_controller.memory_bridge(),
+    };
+
+    let mut poseidon_chip =
+        Poseidon2PeripheryChip::new(Poseidon2Config::default(), POSEIDON2_DIRECT_BUS, 3);
+
+    memory_controller.finalize(Some(&mut poseidon_chip));
+    let mut air_proof_inputs = memory_controller.generate_air_proof_inputs();
+    air_proof_inputs.push(AirProofInput::simple_no_pis(
+        Arc::new(memory_requester_air),
+        memory_requester_trace,
+    ));
+    air_proof_inputs.push(poseidon_chip.generate_air_proof_input());
+    air_proof_inputs.push(range_checker.generate_air_proof_input());
+
+    BabyBearPoseidon2Engine::run_test_fast(air_proof_inputs).expect("Verification failed");
+}
+
+#[test]
+fn test_memory_controller_persistent_chunk_4() {
+    const CHUNK_SIZE: usize = 4;
+
+    let memory_bus = MemoryBus(MEMORY_BUS);
+    let merkle_bus = MemoryMerkleBus(MEMORY_MERKLE_BUS);
+    let compression_bus = DirectCompressionBus(POSEIDON2_DIRECT_BUS);
+    let memory_config = MemoryConfig::default();
+    let range_bus = VariableRangeCheckerBus::new(RANGE_CHECKER_BUS, memory_config.decomp);
+    let range_checker = Arc::new(VariableRangeCheckerChip::new(range_bus));
+
+    let mut memory_controller = MemoryController::with_persistent_memory(
+        memory_bus,
+        memory_config.clone(),
+        range_checker.clone(),
+        merkle_bus,
+        compression_bus,
+        Equipartition::<_, CHUNK_SIZE>::new(),
+    );
+    let aux_factory = memory_controller.aux_cols_factory();
+
+    let mut rng = create_seeded_rng();
+    let records = make_random_accesses(&mut memory_controller, &mut rng);
+    let memory_requester_trace = generate_trace(records, aux_factory);
+
+    let memory_requester_air = MemoryRequesterAir {
+        memory_bridge: memory_controller.memory_bridge(),
+    };
+
+    let mut hash_chip = HashTestChip::<CHUNK_SIZE, BabyBear>::new();
+
+    let final_memory = memory_controller.finalize(Some(&mut hash_chip)).unwrap();
+
+    let memory_dimensions = memory_config.memory_dimensions_for_chunk(CHUNK_SIZE);
+    let root = MemoryNode::tree_from_memory(memory_dimensions, &final_memory, &hash_chip).hash();
+    for (&label, values) in final_memory.iter().take(16) {
+        let proof = memory_controller.open(label.0, label.1, &hash_chip);
+        assert_eq!(&proof.values, values);
+        assert!(proof.verify(memory_dimensions, &root, &hash_chip));
+
+        let pointer = label.1 * CHUNK_SIZE as u32 / 16 * 16;
+        let region = memory_controller.open_region(label.0, pointer, 16, &hash_chip);
+        assert!(region.verify(memory_dimensions, &root, &hash_chip));
+    }
+    let mut air_proof_inputs = memory_controller.generate_air_proof_inputs();
+    air_proof_inputs.push(AirProofInput::simple_no_pis(
+        Arc::new(memory_requester_air),
+        memory_requester_trace,
+    ));
+    air_proof_inputs.push(hash_chip.generate_air_proof_input());
+    air_proof_inputs.push(range_checker.generate_air_proof_input());
+
+    BabyBearPoseidon2Engine::run_test_fast(air_proof_inputs).expect("Verification failed");
+}
+
+#[test]
+fn test_memory_controller_persistent_incremental_tree() {
+    const CHUNK_SIZE: usize = 4;
+
+    let memory_bus = MemoryBus(MEMORY_BUS);
+    let merkle_bus = MemoryMerkleBus(MEMORY_MERKLE_BUS);
+    let compression_bus = DirectCompressionBus(POSEIDON2_DIRECT_BUS);
+    let memory_config = MemoryConfig::default();
+    let memory_dimensions = memory_config.memory_dimensions_for_chunk(CHUNK_SIZE);
+    let range_bus = VariableRangeCheckerBus::new(RANGE_CHECKER_BUS, memory_config.decomp);
+
+    let mut rng = create_seeded_rng();
+    let mut initial_memory = Arc::new(Equipartition::<_, CHUNK_SIZE>::new());
+    let hasher = HashTestChip::<CHUNK_SIZE, BabyBear>::new();
+    let mut initial_tree =
+        MemoryNode::tree_from_memory(memory_dimensions, &initial_memory, &hasher);
+    // Run two segments, carrying the memory tree over from the first to the second.
+    for _ in 0..2 {
+        let range_checker = Arc::new(VariableRangeCheckerChip::new(range_bus));
+        let mut memory_controller = MemoryController::with_persistent_memory(
+            memory_bus,
+            memory_config.clone(),
+            range_checker.clone(),
+            merkle_bus,
+            compression_bus,
+            Equipartition::<_, CHUNK_SIZE>::new(),
+        );
+        memory_controller.set_initial_memory(initial_memory);
+        memory_controller.set_initial_memory_tree(initial_tree);
+        let aux_factory = memory_controller.aux_cols_factory();
+        let records = make_random_accesses(&mut memory_controller, &mut rng);
+        let memory_requester_trace = generate_trace(records, aux_factory);
+        let memory_requester_air = MemoryRequesterAir {
+            memory_bridge: memory_controller.memory_bridge(),
+        };
+
+        let mut hash_chip = HashTestChip::new();
+        initial_memory = memory_controller.finalize(Some(&mut hash_chip)).unwrap();
+        // The final memory is shared with the controller, not copied.
+        assert_eq!(Arc::strong_count(&initial_memory), 2);
+        initial_tree = memory_controller.take_final_memory_tree().unwrap();
+        assert_eq!(
+            initial_tree,
+            MemoryNode::tree_from_memory(memory_dimensions, &initial_memory, &hasher)
+        );
+
+        let mut air_proof_inputs = memory_controller.generate_air_proof_inputs();
+        air_proof_inputs.push(AirProofInput::simple_no_pis(
+            Arc::new(memory_requester_air),
+            memory_requester_trace,
+        ));
+        air_proof_inputs.push(hash_chip.generate_air_proof_input());
+        air_proof_inputs.push(range_checker.generate_air_proof_input());
+
+        BabyBearPoseidon2Engine::run_test_fast(air_proof_inputs).expect("Verification failed");
+    }
+}
+
+#[test]
+fn test_memory_fuzz() {
+    fuzz_memory(&MemoryFuzzConfig::default(), 0..4);
+}
+
+#[test]
+fn test_memory_fuzz_shrink() {
+    let ops = generate_memory_ops(&MemoryFuzzConfig::default(), 0);
+    let fails = |ops: &[MemoryFuzzOp]| {
+        ops.iter()
+            .any(|op| op.op_type == OpType::Write && op.address_space == 2)
+    };
+    assert!(fails(&ops));
+
+    let shrunk = shrink_memory_ops(ops, fails);
+    assert_eq!(shrunk.len(), 1);
+    assert_eq!(shrunk[0].value, 0);
+    assert!(fails(&shrunk));
+}
+
+fn make_random_accesses<F: PrimeField32, const CHUNK_SIZE: usize>(
+    memory_controller: &mut MemoryController<F, CHUNK_SIZE>,
     mut rng: &mut StdRng,
 ) -> Vec<Record<F>> {
     (0..1024)
@@ -1,3 +1,4 @@
+pub mod proof;
 pub mod public_values;
 
 use std::{collections::BTreeMap, sync::Arc};
@@ -12,7 +13,7 @@ use crate::{
 };
 
 #[derive(Clone, Debug, PartialEq)]
-pub enum MemoryNode<const CHUNK: usize, F: PrimeField32> {
+pub enum MemoryNode<const CHUNK: usize, F> {
     Leaf {
         values: [F; CHUNK],
     },
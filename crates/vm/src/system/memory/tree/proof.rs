@@ -0,0 +1,492 @@
+use openvm_stark_backend::p3_field::PrimeField32;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    arch::hasher::Hasher,
+    system::memory::{dimensions::MemoryDimensions, tree::MemoryNode, Equipartition},
+};
+
+/// Merkle proof that a chunk of memory has given values in a memory state.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "F: Serialize, [F; CHUNK]: Serialize",
+    deserialize = "F: Deserialize<'de>, [F; CHUNK]: Deserialize<'de>"
+))]
+pub struct MemoryMerkleProof<const CHUNK: usize, F> {
+    pub address_space: u32,
+    /// The block id of the chunk, i.e. its pointer divided by `CHUNK`.
+    pub label: u32,
+    /// The values of the chunk.
+    pub values: [F; CHUNK],
+    /// Path from the leaf to the root in the format of (`bit`, `hash`)
+    /// `bit`: If `bit` is true, the node on the path is the right child, otherwise the left child.
+    /// `hash`: Hash of the sibling node.
+    pub siblings: Vec<(bool, [F; CHUNK])>,
+}
+
+impl<const CHUNK: usize, F: PrimeField32> MemoryMerkleProof<CHUNK, F> {
+    /// Computes the proof of the chunk `(address_space, label)` in `memory`.
+    pub fn compute(
+        memory_dimensions: MemoryDimensions,
+        memory: &Equipartition<F, CHUNK>,
+        hasher: &impl Hasher<CHUNK, F>,
+        label: (u32, u32),
+    ) -> Self {
+        let root = MemoryNode::tree_from_memory(memory_dimensions, memory, hasher);
+        let values = *memory.get(&label).unwrap_or(&[F::ZERO; CHUNK]);
+        Self::from_tree(memory_dimensions, &root, label, values)
+    }
+
+    /// Extracts the proof of the chunk `(address_space, label)`, which holds `values`, from the
+    /// memory tree rooted at `root`.
+    pub fn from_tree(
+        memory_dimensions: MemoryDimensions,
+        root: &MemoryNode<CHUNK, F>,
+        (address_space, label): (u32, u32),
+        values: [F; CHUNK],
+    ) -> Self {
+        let index = memory_dimensions.label_to_index((address_space, label));
+        let (_, siblings) = open_path(root, index, memory_dimensions.overall_height());
+        Self {
+            address_space,
+            label,
+            values,
+            siblings,
+        }
+    }
+
+    /// Recomputes the root of the memory tree from the values and the siblings.
+    pub fn root(&self, hasher: &impl Hasher<CHUNK, F>) -> [F; CHUNK] {
+        path_root(hasher.hash(&self.values), &self.siblings, hasher)
+    }
+
+    /// Returns whether the proof shows that the chunk `(address_space, label)` has `values` in
+    /// the memory state with root `root`.
+    pub fn verify(
+        &self,
+        memory_dimensions: MemoryDimensions,
+        root: &[F; CHUNK],
+        hasher: &impl Hasher<CHUNK, F>,
+    ) -> bool {
+        if self.siblings.len() != memory_dimensions.overall_height()
+            || as_label(memory_dimensions, self.address_space).is_none()
+            || self.label as u64 >= 1 << memory_dimensions.address_height
+        {
+            return false;
+        }
+        let index = memory_dimensions.label_to_index((self.address_space, self.label));
+        path_matches(&self.siblings, index) && self.root(hasher) == *root
+    }
+}
+
+/// Merkle proof that the subtree of one address space has a given root in a memory state.
+///
+/// The memory tree commits to each address space under its own subtree, so comparing the
+/// address space roots opened against the initial and the final memory root of a segment shows
+/// whether that address space (e.g. a read-only program image) changed, without revealing the
+/// rest of the memory.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "F: Serialize, [F; CHUNK]: Serialize",
+    deserialize = "F: Deserialize<'de>, [F; CHUNK]: Deserialize<'de>"
+))]
+pub struct AddressSpaceRootProof<const CHUNK: usize, F> {
+    pub address_space: u32,
+    /// Root of the subtree of the address space.
+    pub address_space_root: [F; CHUNK],
+    /// Path from the address space root to the memory root, in the same format as
+    /// [MemoryMerkleProof::siblings].
+    pub siblings: Vec<(bool, [F; CHUNK])>,
+}
+
+impl<const CHUNK: usize, F: PrimeField32> AddressSpaceRootProof<CHUNK, F> {
+    /// Computes the proof of `address_space` in `memory`, or returns an error if the memory tree
+    /// has no subtree for `address_space`.
+    pub fn compute(
+        memory_dimensions: MemoryDimensions,
+        memory: &Equipartition<F, CHUNK>,
+        hasher: &impl Hasher<CHUNK, F>,
+        address_space: u32,
+    ) -> Result<Self, AddressSpaceOutOfRange> {
+        as_label(memory_dimensions, address_space).ok_or(AddressSpaceOutOfRange(address_space))?;
+        let root = MemoryNode::tree_from_memory(memory_dimensions, memory, hasher);
+        Self::from_tree(memory_dimensions, &root, address_space)
+    }
+
+    /// Extracts the proof of `address_space` from the memory tree rooted at `root`, or returns an
+    /// error if the memory tree has no subtree for `address_space`.
+    pub fn from_tree(
+        memory_dimensions: MemoryDimensions,
+        root: &MemoryNode<CHUNK, F>,
+        address_space: u32,
+    ) -> Result<Self, AddressSpaceOutOfRange> {
+        let as_label = as_label(memory_dimensions, address_space)
+            .ok_or(AddressSpaceOutOfRange(address_space))?;
+        let (node, siblings) = open_path(root, as_label as u64, memory_dimensions.as_height);
+        Ok(Self {
+            address_space,
+            address_space_root: node.hash(),
+            siblings,
+        })
+    }
+
+    /// Recomputes the memory root from the address space root and the siblings.
+    pub fn memory_root(&self, hasher: &impl Hasher<CHUNK, F>) -> [F; CHUNK] {
+        path_root(self.address_space_root, &self.siblings, hasher)
+    }
+
+    /// Returns whether the proof shows that `address_space` has root `address_space_root` in the
+    /// memory state with root `memory_root`.
+    pub fn verify(
+        &self,
+        memory_dimensions: MemoryDimensions,
+        memory_root: &[F; CHUNK],
+        hasher: &impl Hasher<CHUNK, F>,
+    ) -> bool {
+        let Some(as_label) = as_label(memory_dimensions, self.address_space) else {
+            return false;
+        };
+        self.siblings.len() == memory_dimensions.as_height
+            && path_matches(&self.siblings, as_label as u64)
+            && self.memory_root(hasher) == *memory_root
+    }
+}
+
+/// Merkle proof that an aligned region of memory has given values in a memory state.
+///
+/// The region covers a whole subtree of the memory tree: its length is a power of two multiple of
+/// `CHUNK` and its start is a multiple of its length. Its commitment, [Self::region_commit], is
+/// the root of that subtree, computed the same way as [Hasher::merkle_root], so a verifier that
+/// trusts the final memory root (a public value of the Merkle AIR) can trust the contents of the
+/// region without the guest copying them into the public values address space.
+///
+/// The region is chosen by the host when opening the proof, not pinned by the guest: the guest
+/// only has to leave its output at an aligned location agreed on with the verifier, and no chip
+/// hashes the region during execution.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "F: Serialize, [F; CHUNK]: Serialize",
+    deserialize = "F: Deserialize<'de>, [F; CHUNK]: Deserialize<'de>"
+))]
+pub struct MemoryRegionProof<const CHUNK: usize, F> {
+    pub address_space: u32,
+    /// The first pointer of the region.
+    pub pointer: u32,
+    /// The values of the region.
+    pub values: Vec<F>,
+    /// Path from the root of the region to the memory root, in the same format as
+    /// [MemoryMerkleProof::siblings].
+    pub siblings: Vec<(bool, [F; CHUNK])>,
+}
+
+impl<const CHUNK: usize, F: PrimeField32> MemoryRegionProof<CHUNK, F> {
+    /// Computes the proof of the `len` cells starting at `(address_space, pointer)` in `memory`.
+    ///
+    /// Panics if the region is not aligned as described in [MemoryRegionProof].
+    pub fn compute(
+        memory_dimensions: MemoryDimensions,
+        memory: &Equipartition<F, CHUNK>,
+        hasher: &impl Hasher<CHUNK, F>,
+        address_space: u32,
+        pointer: u32,
+        len: usize,
+    ) -> Self {
+        let root = MemoryNode::tree_from_memory(memory_dimensions, memory, hasher);
+        let values = region_values(memory, address_space, pointer, len);
+        Self::from_tree(memory_dimensions, &root, address_space, pointer, values)
+    }
+
+    /// Extracts the proof of the region starting at `(address_space, pointer)`, which holds
+    /// `values`, from the memory tree rooted at `root`.
+    ///
+    /// Panics if the region is not aligned as described in [MemoryRegionProof].
+    pub fn from_tree(
+        memory_dimensions: MemoryDimensions,
+        root: &MemoryNode<CHUNK, F>,
+        address_space: u32,
+        pointer: u32,
+        values: Vec<F>,
+    ) -> Self {
+        let region_height = region_height::<CHUNK>(memory_dimensions, pointer, values.len())
+            .expect("memory region must be an aligned power of two number of chunks");
+        let index = memory_dimensions.label_to_index((address_space, pointer / CHUNK as u32))
+            >> region_height;
+        let (_, siblings) = open_path(
+            root,
+            index,
+            memory_dimensions.overall_height() - region_height,
+        );
+        Self {
+            address_space,
+            pointer,
+            values,
+            siblings,
+        }
+    }
+
+    /// The root of the subtree holding the region.
+    pub fn region_commit(&self, hasher: &impl Hasher<CHUNK, F>) -> [F; CHUNK] {
+        hasher.merkle_root(&self.values)
+    }
+
+    /// Recomputes the memory root from the values and the siblings.
+    pub fn root(&self, hasher: &impl Hasher<CHUNK, F>) -> [F; CHUNK] {
+        path_root(self.region_commit(hasher), &self.siblings, hasher)
+    }
+
+    /// Returns whether the proof shows that the region has `values` in the memory state with root
+    /// `root`.
+    pub fn verify(
+        &self,
+        memory_dimensions: MemoryDimensions,
+        root: &[F; CHUNK],
+        hasher: &impl Hasher<CHUNK, F>,
+    ) -> bool {
+        let Some(region_height) =
+            region_height::<CHUNK>(memory_dimensions, self.pointer, self.values.len())
+        else {
+            return false;
+        };
+        if self.siblings.len() != memory_dimensions.overall_height() - region_height
+            || as_label(memory_dimensions, self.address_space).is_none()
+        {
+            return false;
+        }
+        let index = memory_dimensions
+            .label_to_index((self.address_space, self.pointer / CHUNK as u32))
+            >> region_height;
+        path_matches(&self.siblings, index) && self.root(hasher) == *root
+    }
+}
+
+/// An address space that has no subtree in the memory tree.
+#[derive(Error, Clone, Copy, Debug, PartialEq, Eq)]
+#[error("address space {0} is not in the memory tree")]
+pub struct AddressSpaceOutOfRange(pub u32);
+
+/// Returns the index of the subtree of `address_space` among the address space subtrees, or
+/// `None` if the memory tree has no subtree for it.
+fn as_label(memory_dimensions: MemoryDimensions, address_space: u32) -> Option<u32> {
+    address_space
+        .checked_sub(memory_dimensions.as_offset)
+        .filter(|&as_label| (as_label as u64) < 1 << memory_dimensions.as_height)
+}
+
+/// Walks `depth` levels down from `root` to the node with index `index` at that depth. Returns
+/// the node and the siblings on the path, from the bottom up, in the format of
+/// [MemoryMerkleProof::siblings].
+fn open_path<const CHUNK: usize, F: PrimeField32>(
+    root: &MemoryNode<CHUNK, F>,
+    index: u64,
+    depth: usize,
+) -> (&MemoryNode<CHUNK, F>, Vec<(bool, [F; CHUNK])>) {
+    let mut curr_node = root;
+    let mut siblings = Vec::with_capacity(depth);
+    for height in (0..depth).rev() {
+        if let MemoryNode::NonLeaf { left, right, .. } = curr_node {
+            if (index >> height) & 1 == 1 {
+                curr_node = right.as_ref();
+                siblings.push((true, left.hash()));
+            } else {
+                curr_node = left.as_ref();
+                siblings.push((false, right.hash()));
+            }
+        } else {
+            unreachable!()
+        }
+    }
+    siblings.reverse();
+    (curr_node, siblings)
+}
+
+/// Recomputes the root from the hash of `node` and the siblings on its path.
+fn path_root<const CHUNK: usize, F: PrimeField32>(
+    node: [F; CHUNK],
+    siblings: &[(bool, [F; CHUNK])],
+    hasher: &impl Hasher<CHUNK, F>,
+) -> [F; CHUNK] {
+    siblings.iter().fold(node, |node, (is_right, sibling)| {
+        if *is_right {
+            hasher.compress(sibling, &node)
+        } else {
+            hasher.compress(&node, sibling)
+        }
+    })
+}
+
+/// Returns whether the directions in `siblings` lead to the node with index `index`.
+fn path_matches<const CHUNK: usize, F>(siblings: &[(bool, [F; CHUNK])], index: u64) -> bool {
+    siblings
+        .iter()
+        .enumerate()
+        .all(|(height, (is_right, _))| *is_right == ((index >> height) & 1 == 1))
+}
+
+/// Returns the height of the subtree covering the region of `len` cells starting at `pointer`,
+/// or `None` if the region is not a whole subtree inside one address space.
+fn region_height<const CHUNK: usize>(
+    memory_dimensions: MemoryDimensions,
+    pointer: u32,
+    len: usize,
+) -> Option<usize> {
+    let num_chunks = len / CHUNK;
+    if len % CHUNK != 0 || !num_chunks.is_power_of_two() || pointer as usize % len != 0 {
+        return None;
+    }
+    let height = num_chunks.trailing_zeros() as usize;
+    (height <= memory_dimensions.address_height
+        && (pointer / CHUNK as u32) as u64 >> memory_dimensions.address_height == 0)
+        .then_some(height)
+}
+
+/// The `len` cells starting at `(address_space, pointer)` in `memory`, which must be aligned to
+/// `CHUNK`.
+pub(crate) fn region_values<const CHUNK: usize, F: PrimeField32>(
+    memory: &Equipartition<F, CHUNK>,
+    address_space: u32,
+    pointer: u32,
+    len: usize,
+) -> Vec<F> {
+    let first_label = pointer / CHUNK as u32;
+    (first_label..first_label + (len / CHUNK) as u32)
+        .flat_map(|label| {
+            *memory
+                .get(&(address_space, label))
+                .unwrap_or(&[F::ZERO; CHUNK])
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use openvm_instructions::exe::MemoryImage;
+    use openvm_stark_backend::p3_field::AbstractField;
+    use openvm_stark_sdk::p3_baby_bear::BabyBear;
+
+    use super::{
+        AddressSpaceOutOfRange, AddressSpaceRootProof, MemoryMerkleProof, MemoryRegionProof,
+    };
+    use crate::{
+        arch::{hasher::poseidon2::vm_poseidon2_hasher, SystemConfig},
+        system::memory::{memory_image_to_equipartition, tree::MemoryNode, CHUNK},
+    };
+
+    type F = BabyBear;
+    #[test]
+    fn test_memory_merkle_proof() {
+        let mut vm_config = SystemConfig::default();
+        vm_config.memory_config.as_height = 2;
+        vm_config.memory_config.pointer_max_bits = 6;
+        let memory_dimensions = vm_config.memory_config.memory_dimensions();
+        let memory: MemoryImage<F> = [
+            ((1, 3), F::ONE),
+            ((2, 17), F::TWO),
+            ((4, 63), F::from_canonical_u32(5)),
+        ]
+        .into_iter()
+        .collect();
+        let memory = memory_image_to_equipartition(memory);
+        let hasher = vm_poseidon2_hasher();
+        let root = MemoryNode::tree_from_memory(memory_dimensions, &memory, &hasher).hash();
+
+        for label in [(1, 0), (2, 2), (4, 7), (3, 5)] {
+            let proof =
+                MemoryMerkleProof::<CHUNK, F>::compute(memory_dimensions, &memory, &hasher, label);
+            assert!(proof.verify(memory_dimensions, &root, &hasher));
+
+            let mut forged = proof.clone();
+            forged.values[0] += F::ONE;
+            assert!(!forged.verify(memory_dimensions, &root, &hasher));
+
+            let mut moved = proof;
+            moved.label ^= 1;
+            assert!(!moved.verify(memory_dimensions, &root, &hasher));
+        }
+    }
+    #[test]
+    fn test_address_space_root_proof() {
+        let mut vm_config = SystemConfig::default();
+        vm_config.memory_config.as_height = 2;
+        vm_config.memory_config.pointer_max_bits = 6;
+        let memory_dimensions = vm_config.memory_config.memory_dimensions();
+        let hasher = vm_poseidon2_hasher();
+
+        let initial: MemoryImage<F> = [((1, 3), F::ONE), ((2, 17), F::TWO)].into_iter().collect();
+        let mut final_memory = initial.clone();
+        final_memory.insert((2, 17), F::from_canonical_u32(7));
+        let [initial, final_memory] =
+            [initial, final_memory].map(memory_image_to_equipartition::<F, CHUNK>);
+        let [initial_root, final_root] = [&initial, &final_memory]
+            .map(|memory| MemoryNode::tree_from_memory(memory_dimensions, memory, &hasher).hash());
+
+        for address_space in 1..=4 {
+            let [initial_proof, final_proof] = [&initial, &final_memory].map(|memory| {
+                AddressSpaceRootProof::<CHUNK, F>::compute(
+                    memory_dimensions,
+                    memory,
+                    &hasher,
+                    address_space,
+                )
+                .unwrap()
+            });
+            assert!(initial_proof.verify(memory_dimensions, &initial_root, &hasher));
+            assert!(final_proof.verify(memory_dimensions, &final_root, &hasher));
+            assert!(!initial_proof.verify(memory_dimensions, &final_root, &hasher));
+            assert_eq!(
+                initial_proof.address_space_root == final_proof.address_space_root,
+                address_space != 2
+            );
+        }
+        for address_space in [0, 5] {
+            assert_eq!(
+                AddressSpaceRootProof::<CHUNK, F>::compute(
+                    memory_dimensions,
+                    &initial,
+                    &hasher,
+                    address_space
+                ),
+                Err(AddressSpaceOutOfRange(address_space))
+            );
+        }
+    }
+    #[test]
+    fn test_memory_region_proof() {
+        let mut vm_config = SystemConfig::default();
+        vm_config.memory_config.as_height = 2;
+        vm_config.memory_config.pointer_max_bits = 6;
+        let memory_dimensions = vm_config.memory_config.memory_dimensions();
+        let memory: MemoryImage<F> = (0..64)
+            .map(|ptr| ((3, ptr), F::from_canonical_u32(ptr + 1)))
+            .collect();
+        let memory = memory_image_to_equipartition(memory);
+        let hasher = vm_poseidon2_hasher();
+        let root = MemoryNode::tree_from_memory(memory_dimensions, &memory, &hasher).hash();
+
+        for (pointer, len) in [(0, CHUNK), (16, 16), (32, 32), (0, 64)] {
+            let proof = MemoryRegionProof::<CHUNK, F>::compute(
+                memory_dimensions,
+                &memory,
+                &hasher,
+                3,
+                pointer,
+                len,
+            );
+            assert_eq!(
+                proof.values,
+                (pointer + 1..pointer + 1 + len as u32)
+                    .map(F::from_canonical_u32)
+                    .collect::<Vec<_>>()
+            );
+            assert!(proof.verify(memory_dimensions, &root, &hasher));
+
+            let mut forged = proof.clone();
+            forged.values[len - 1] += F::ONE;
+            assert!(!forged.verify(memory_dimensions, &root, &hasher));
+
+            let mut misaligned = proof;
+            misaligned.pointer += CHUNK as u32;
+            assert!(!misaligned.verify(memory_dimensions, &root, &hasher));
+        }
+    }
+}
@@ -1,8 +1,10 @@
 use std::{
+    array,
     borrow::{Borrow, BorrowMut},
     sync::Arc,
 };
 
+use itertools::Itertools;
 use openvm_circuit_primitives::{
     is_less_than_array::{
         IsLtArrayAuxCols, IsLtArrayIo, IsLtArraySubAir, IsLtArrayWhenTransitionAir,
@@ -23,9 +25,9 @@ use openvm_stark_backend::{
     rap::{AnyRap, BaseAirWithPublicValues, PartitionedBaseAir},
     Chip, ChipUsageGetter,
 };
-use rustc_hash::FxHashSet;
+use rustc_hash::FxHashMap;
 
-use super::TimestampedEquipartition;
+use super::{TimestampedEquipartition, TimestampedValues};
 use crate::system::memory::{
     offline_checker::{MemoryBus, AUX_LEN},
     MemoryAddress,
@@ -37,6 +39,28 @@ mod tests;
 /// Address stored as address space, pointer
 const ADDR_ELTS: usize = 2;
 
+/// Number of limbs each of the address space and the pointer is decomposed into by the wide
+/// layout, see [VolatileBoundaryAir::new_wide].
+const WIDE_ADDR_LIMBS: usize = 3;
+/// Address stored as the limbs of the address space followed by the limbs of the pointer.
+const WIDE_ADDR_ELTS: usize = 2 * WIDE_ADDR_LIMBS;
+/// Limbs have at most `range_max_bits` bits, so their comparison needs a single limb.
+const WIDE_ADDR_LT_AUX_LEN: usize = 1;
+
+/// Largest number of bits of an address space or pointer supported by the default layout of
+/// [VolatileBoundaryAir], the width of the address spaces of the default memory configuration.
+///
+/// Addresses are compared by range checking the decomposition of `y - x - 1 + 2^max_bits` into
+/// `AUX_LEN` limbs.
+pub const VOLATILE_ADDRESS_MAX_BITS: usize = 30;
+
+/// Largest number of bits of an address space or pointer supported by the wide layout of
+/// [VolatileBoundaryAir].
+pub const WIDE_VOLATILE_ADDRESS_MAX_BITS: usize = 48;
+
+/// The final value and timestamp of one touched address, as `((address_space, pointer), value)`.
+pub type VolatileBoundaryEntry<F> = ((u32, u32), TimestampedValues<F, 1>);
+
 #[repr(C)]
 #[derive(Clone, Copy, Debug, AlignedBorrow)]
 pub struct VolatileBoundaryCols<T> {
@@ -52,34 +76,181 @@ pub struct VolatileBoundaryCols<T> {
     pub addr_lt_aux: IsLtArrayAuxCols<T, ADDR_ELTS, AUX_LEN>,
 }
 
+/// Columns of the wide layout, see [VolatileBoundaryAir::new_wide].
+#[repr(C)]
+#[derive(Clone, Copy, Debug, AlignedBorrow)]
+pub struct WideVolatileBoundaryCols<T> {
+    /// Little-endian limbs of `range_max_bits` bits each.
+    pub addr_space_limbs: [T; WIDE_ADDR_LIMBS],
+    /// Little-endian limbs of `range_max_bits` bits each.
+    pub pointer_limbs: [T; WIDE_ADDR_LIMBS],
+
+    pub initial_data: T,
+    pub final_data: T,
+    pub final_timestamp: T,
+
+    /// Boolean. `1` if a non-padding row with a valid touched address, `0` if it is a padding row.
+    pub is_valid: T,
+    pub addr_lt_aux: IsLtArrayAuxCols<T, WIDE_ADDR_ELTS, WIDE_ADDR_LT_AUX_LEN>,
+}
+
 #[derive(Clone, Debug)]
 pub struct VolatileBoundaryAir {
     pub memory_bus: MemoryBus,
-    pub addr_lt_air: IsLtArrayWhenTransitionAir<ADDR_ELTS>,
+    pub layout: VolatileAddressLayout,
+}
+
+/// How [VolatileBoundaryAir] stores and compares addresses.
+#[derive(Clone, Debug)]
+pub enum VolatileAddressLayout {
+    /// The address space and the pointer are single columns. Used by default.
+    Narrow {
+        addr_lt_air: IsLtArrayWhenTransitionAir<ADDR_ELTS>,
+    },
+    /// The address space and the pointer are each decomposed into range checked limbs, which
+    /// also bounds them by their maximum number of bits.
+    Wide {
+        range_bus: VariableRangeCheckerBus,
+        addr_space_max_bits: usize,
+        pointer_max_bits: usize,
+        addr_lt_air: IsLtArrayWhenTransitionAir<WIDE_ADDR_ELTS>,
+    },
 }
 
 impl VolatileBoundaryAir {
+    /// Panics if `addr_space_max_bits` or `pointer_max_bits` exceeds
+    /// [VOLATILE_ADDRESS_MAX_BITS], or if the range checker is too narrow to decompose addresses
+    /// into `AUX_LEN` limbs.
     pub fn new(
         memory_bus: MemoryBus,
         addr_space_max_bits: usize,
         pointer_max_bits: usize,
         range_bus: VariableRangeCheckerBus,
     ) -> Self {
+        let max_bits = addr_space_max_bits.max(pointer_max_bits);
+        assert!(
+            max_bits <= VOLATILE_ADDRESS_MAX_BITS,
+            "Volatile memory supports addresses of at most {VOLATILE_ADDRESS_MAX_BITS} bits, got {max_bits}"
+        );
+        assert!(
+            max_bits.div_ceil(range_bus.range_max_bits) <= AUX_LEN,
+            "Range checker with {} bits cannot decompose {max_bits}-bit addresses into {AUX_LEN} limbs",
+            range_bus.range_max_bits
+        );
+        let addr_lt_air = IsLtArraySubAir::<ADDR_ELTS>::new(range_bus, max_bits).when_transition();
+        Self {
+            memory_bus,
+            layout: VolatileAddressLayout::Narrow { addr_lt_air },
+        }
+    }
+
+    /// Like [Self::new], but with the wide layout, for address spaces or pointers of up to
+    /// [WIDE_VOLATILE_ADDRESS_MAX_BITS] bits. The memory bus still carries each of them as a
+    /// single field element, so the field must be wider than the addresses, see
+    /// [VolatileBoundaryChip::new_wide].
+    ///
+    /// Panics if `addr_space_max_bits` or `pointer_max_bits` exceeds
+    /// [WIDE_VOLATILE_ADDRESS_MAX_BITS], or if the range checker is too narrow to decompose
+    /// addresses into `WIDE_ADDR_LIMBS` limbs.
+    pub fn new_wide(
+        memory_bus: MemoryBus,
+        addr_space_max_bits: usize,
+        pointer_max_bits: usize,
+        range_bus: VariableRangeCheckerBus,
+    ) -> Self {
+        let max_bits = addr_space_max_bits.max(pointer_max_bits);
+        assert!(
+            max_bits <= WIDE_VOLATILE_ADDRESS_MAX_BITS,
+            "Volatile memory supports addresses of at most {WIDE_VOLATILE_ADDRESS_MAX_BITS} bits, got {max_bits}"
+        );
+        assert!(
+            max_bits <= WIDE_ADDR_LIMBS * range_bus.range_max_bits,
+            "Range checker with {} bits cannot decompose {max_bits}-bit addresses into {WIDE_ADDR_LIMBS} limbs",
+            range_bus.range_max_bits
+        );
         let addr_lt_air =
-            IsLtArraySubAir::<ADDR_ELTS>::new(range_bus, addr_space_max_bits.max(pointer_max_bits))
+            IsLtArraySubAir::<WIDE_ADDR_ELTS>::new(range_bus, range_bus.range_max_bits)
                 .when_transition();
         Self {
             memory_bus,
-            addr_lt_air,
+            layout: VolatileAddressLayout::Wide {
+                range_bus,
+                addr_space_max_bits,
+                pointer_max_bits,
+                addr_lt_air,
+            },
         }
     }
+
+    /// Writes the initial and final values of the address of a non-padding row to the memory
+    /// bus, and constrains the non-padding rows to be at the top.
+    #[allow(clippy::too_many_arguments)]
+    fn eval_boundary<AB: InteractionBuilder>(
+        &self,
+        builder: &mut AB,
+        address: MemoryAddress<AB::Expr, AB::Expr>,
+        initial_data: AB::Var,
+        final_data: AB::Var,
+        final_timestamp: AB::Var,
+        is_valid: AB::Var,
+        next_is_valid: AB::Var,
+    ) {
+        builder.assert_bool(is_valid);
+
+        // Ensuring all non-padding rows are at the bottom
+        builder
+            .when_transition()
+            .assert_one(implies(next_is_valid, is_valid));
+
+        // Write the initial memory values at initial timestamps
+        self.memory_bus
+            .send(address.clone(), vec![initial_data], AB::Expr::ZERO)
+            .eval(builder, is_valid);
+
+        // Read the final memory values at last timestamps when written to
+        self.memory_bus
+            .receive(address, vec![final_data], final_timestamp)
+            .eval(builder, is_valid);
+    }
+}
+
+/// Number of bits of limb `i` of a value with at most `max_bits` bits, split into limbs of
+/// `limb_size` bits.
+fn limb_bits(limb_size: usize, max_bits: usize, i: usize) -> usize {
+    max_bits.saturating_sub(i * limb_size).min(limb_size)
+}
+
+/// Splits `value` into its little-endian limbs of `limb_size` bits.
+fn split(limb_size: usize, value: u32) -> [u32; WIDE_ADDR_LIMBS] {
+    array::from_fn(|i| {
+        value
+            .checked_shr((i * limb_size) as u32)
+            .map_or(0, |limb| limb & ((1 << limb_size) - 1))
+    })
+}
+
+/// The address limbs in the order in which addresses are compared, most significant first.
+fn lt_limbs<T: Clone>(
+    addr_space_limbs: &[T; WIDE_ADDR_LIMBS],
+    pointer_limbs: &[T; WIDE_ADDR_LIMBS],
+) -> [T; WIDE_ADDR_ELTS] {
+    array::from_fn(|i| {
+        if i < WIDE_ADDR_LIMBS {
+            addr_space_limbs[WIDE_ADDR_LIMBS - 1 - i].clone()
+        } else {
+            pointer_limbs[WIDE_ADDR_ELTS - 1 - i].clone()
+        }
+    })
 }
 
 impl<F: Field> BaseAirWithPublicValues<F> for VolatileBoundaryAir {}
 impl<F: Field> PartitionedBaseAir<F> for VolatileBoundaryAir {}
 impl<F: Field> BaseAir<F> for VolatileBoundaryAir {
     fn width(&self) -> usize {
-        VolatileBoundaryCols::<F>::width()
+        match self.layout {
+            VolatileAddressLayout::Narrow { .. } => VolatileBoundaryCols::<F>::width(),
+            VolatileAddressLayout::Wide { .. } => WideVolatileBoundaryCols::<F>::width(),
+        }
     }
 }
 
@@ -88,55 +259,151 @@ impl<AB: InteractionBuilder> Air<AB> for VolatileBoundaryAir {
         let main = builder.main();
 
         let [local, next] = [0, 1].map(|i| main.row_slice(i));
-        let local: &VolatileBoundaryCols<_> = (*local).borrow();
-        let next: &VolatileBoundaryCols<_> = (*next).borrow();
+        match &self.layout {
+            VolatileAddressLayout::Narrow { addr_lt_air } => {
+                let local: &VolatileBoundaryCols<_> = (*local).borrow();
+                let next: &VolatileBoundaryCols<_> = (*next).borrow();
 
-        builder.assert_bool(local.is_valid);
+                // Assert local addr < next addr when next.is_valid
+                // This ensures the addresses in non-padding rows are all sorted
+                let lt_io = IsLtArrayIo {
+                    x: [local.addr_space, local.pointer].map(Into::into),
+                    y: [next.addr_space, next.pointer].map(Into::into),
+                    out: AB::Expr::ONE,
+                    count: next.is_valid.into(),
+                };
+                // N.B.: this will do range checks (but not other constraints) on the last row if the first row has is_valid = 1 due to wraparound
+                addr_lt_air.eval(builder, (lt_io, (&local.addr_lt_aux).into()));
 
-        // Ensuring all non-padding rows are at the bottom
-        builder
-            .when_transition()
-            .assert_one(implies(next.is_valid, local.is_valid));
-
-        // Assert local addr < next addr when next.is_valid
-        // This ensures the addresses in non-padding rows are all sorted
-        let lt_io = IsLtArrayIo {
-            x: [local.addr_space, local.pointer].map(Into::into),
-            y: [next.addr_space, next.pointer].map(Into::into),
-            out: AB::Expr::ONE,
-            count: next.is_valid.into(),
-        };
-        // N.B.: this will do range checks (but not other constraints) on the last row if the first row has is_valid = 1 due to wraparound
-        self.addr_lt_air
-            .eval(builder, (lt_io, (&local.addr_lt_aux).into()));
+                self.eval_boundary(
+                    builder,
+                    MemoryAddress::new(local.addr_space.into(), local.pointer.into()),
+                    local.initial_data,
+                    local.final_data,
+                    local.final_timestamp,
+                    local.is_valid,
+                    next.is_valid,
+                );
+            }
+            VolatileAddressLayout::Wide {
+                range_bus,
+                addr_space_max_bits,
+                pointer_max_bits,
+                addr_lt_air,
+            } => {
+                let local: &WideVolatileBoundaryCols<_> = (*local).borrow();
+                let next: &WideVolatileBoundaryCols<_> = (*next).borrow();
 
-        // Write the initial memory values at initial timestamps
-        self.memory_bus
-            .send(
-                MemoryAddress::new(local.addr_space, local.pointer),
-                vec![local.initial_data],
-                AB::Expr::ZERO,
-            )
-            .eval(builder, local.is_valid);
+                // Range check the address limbs, which also bounds the address space and pointer
+                // by their maximum number of bits
+                for (limbs, max_bits) in [
+                    (&local.addr_space_limbs, *addr_space_max_bits),
+                    (&local.pointer_limbs, *pointer_max_bits),
+                ] {
+                    for (i, &limb) in limbs.iter().enumerate() {
+                        range_bus
+                            .range_check(limb, limb_bits(range_bus.range_max_bits, max_bits, i))
+                            .eval(builder, local.is_valid);
+                    }
+                }
+                let compose = |limbs: &[AB::Var; WIDE_ADDR_LIMBS]| {
+                    limbs.iter().rev().fold(AB::Expr::ZERO, |acc, &limb| {
+                        acc * AB::Expr::from_canonical_usize(1 << range_bus.range_max_bits)
+                            + limb.into()
+                    })
+                };
 
-        // Read the final memory values at last timestamps when written to
-        self.memory_bus
-            .receive(
-                MemoryAddress::new(local.addr_space, local.pointer),
-                vec![local.final_data],
-                local.final_timestamp,
-            )
-            .eval(builder, local.is_valid);
+                // Assert local addr < next addr when next.is_valid
+                // This ensures the addresses in non-padding rows are all sorted
+                let lt_io = IsLtArrayIo {
+                    x: lt_limbs(&local.addr_space_limbs, &local.pointer_limbs).map(Into::into),
+                    y: lt_limbs(&next.addr_space_limbs, &next.pointer_limbs).map(Into::into),
+                    out: AB::Expr::ONE,
+                    count: next.is_valid.into(),
+                };
+                // N.B.: this will do range checks (but not other constraints) on the last row if the first row has is_valid = 1 due to wraparound
+                addr_lt_air.eval(builder, (lt_io, (&local.addr_lt_aux).into()));
+
+                self.eval_boundary(
+                    builder,
+                    MemoryAddress::new(
+                        compose(&local.addr_space_limbs),
+                        compose(&local.pointer_limbs),
+                    ),
+                    local.initial_data,
+                    local.final_data,
+                    local.final_timestamp,
+                    local.is_valid,
+                    next.is_valid,
+                );
+            }
+        }
+    }
+}
+
+/// Log2 of the number of addresses tracked by one page of [TouchedAddresses].
+const TOUCHED_PAGE_BITS: u32 = 6;
+
+/// Set of touched addresses, stored as bitmasks over aligned pages of `1 << TOUCHED_PAGE_BITS`
+/// pointers so that touching a contiguous range costs one update per page instead of one per
+/// address.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct TouchedAddresses {
+    /// Maps `(address_space, pointer >> TOUCHED_PAGE_BITS)` to the bitmask of touched pointers
+    /// in that page.
+    pages: FxHashMap<(u32, u32), u64>,
+    len: usize,
+}
+
+impl TouchedAddresses {
+    pub(crate) fn touch_range(&mut self, address_space: u32, pointer: u32, len: u32) {
+        let page_size = 1 << TOUCHED_PAGE_BITS;
+        let end = pointer + len;
+        let mut ptr = pointer;
+        while ptr < end {
+            let offset = ptr % page_size;
+            let count = (page_size - offset).min(end - ptr);
+            let mask = (u64::MAX >> (64 - count)) << offset;
+            let page = self
+                .pages
+                .entry((address_space, ptr >> TOUCHED_PAGE_BITS))
+                .or_default();
+            self.len += (mask & !*page).count_ones() as usize;
+            *page |= mask;
+            ptr += count;
+        }
+    }
+
+    /// Number of touched addresses.
+    pub(crate) fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Iterates over the touched addresses, expanding each page.
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (u32, u32)> + '_ {
+        self.pages
+            .iter()
+            .flat_map(|(&(address_space, page), &mask)| {
+                (0..1 << TOUCHED_PAGE_BITS)
+                    .filter(move |offset| (mask >> offset) & 1 == 1)
+                    .map(move |offset| (address_space, (page << TOUCHED_PAGE_BITS) + offset))
+            })
+    }
+
+    /// Iterates over the address spaces of the tracked pages, one item per page.
+    pub(crate) fn page_address_spaces(&self) -> impl Iterator<Item = u32> + '_ {
+        self.pages.keys().map(|&(address_space, _)| address_space)
     }
 }
 
 #[derive(Debug)]
 pub struct VolatileBoundaryChip<F> {
     pub air: VolatileBoundaryAir,
-    touched_addresses: FxHashSet<(u32, u32)>,
+    touched_addresses: TouchedAddresses,
     range_checker: Arc<VariableRangeCheckerChip>,
     overridden_height: Option<usize>,
-    final_memory: Option<TimestampedEquipartition<F, 1>>,
+    /// Sorted by address.
+    final_memory: Option<Vec<VolatileBoundaryEntry<F>>>,
 }
 
 impl<F: Field> VolatileBoundaryChip<F> {
@@ -154,7 +421,7 @@ impl<F: Field> VolatileBoundaryChip<F> {
                 pointer_max_bits,
                 range_bus,
             ),
-            touched_addresses: FxHashSet::default(),
+            touched_addresses: TouchedAddresses::default(),
             range_checker,
             overridden_height: None,
             final_memory: None,
@@ -162,38 +429,106 @@ impl<F: Field> VolatileBoundaryChip<F> {
     }
 
     pub fn touch_address(&mut self, addr_space: u32, pointer: u32) {
-        self.touched_addresses.insert((addr_space, pointer));
+        self.touched_addresses.touch_range(addr_space, pointer, 1);
+    }
+
+    /// Touches every address in `[pointer, pointer + len)`.
+    pub fn touch_range(&mut self, addr_space: u32, pointer: u32, len: u32) {
+        self.touched_addresses.touch_range(addr_space, pointer, len);
     }
 
     pub fn all_addresses(&self) -> Vec<(u32, u32)> {
-        self.touched_addresses.iter().cloned().collect()
+        self.touched_addresses.iter().collect()
+    }
+
+    pub(crate) fn touched_addresses(&self) -> &TouchedAddresses {
+        &self.touched_addresses
+    }
+
+    pub(crate) fn set_touched_addresses(&mut self, touched_addresses: TouchedAddresses) {
+        self.touched_addresses = touched_addresses;
     }
 }
 
 impl<F: PrimeField32> VolatileBoundaryChip<F> {
+    /// Like [Self::new], but with the wide layout of [VolatileBoundaryAir::new_wide], which range
+    /// checks every touched address and supports addresses of more than
+    /// [VOLATILE_ADDRESS_MAX_BITS] bits.
+    ///
+    /// Panics if the addresses do not fit in a field element, since the memory bus identifies
+    /// addresses by their values in the field.
+    pub fn new_wide(
+        memory_bus: MemoryBus,
+        addr_space_max_bits: usize,
+        pointer_max_bits: usize,
+        range_checker: Arc<VariableRangeCheckerChip>,
+    ) -> Self {
+        let max_bits = addr_space_max_bits.max(pointer_max_bits);
+        assert!(
+            max_bits < F::bits(),
+            "{max_bits}-bit addresses do not fit in a {}-bit field element",
+            F::bits()
+        );
+        let range_bus = range_checker.bus();
+        Self {
+            air: VolatileBoundaryAir::new_wide(
+                memory_bus,
+                addr_space_max_bits,
+                pointer_max_bits,
+                range_bus,
+            ),
+            touched_addresses: TouchedAddresses::default(),
+            range_checker,
+            overridden_height: None,
+            final_memory: None,
+        }
+    }
+
     pub fn set_overridden_height(&mut self, overridden_height: usize) {
         self.overridden_height = Some(overridden_height);
     }
     /// Volatile memory requires the starting and final memory to be in equipartition with block size `1`.
     /// When block size is `1`, then the `label` is the same as the address pointer.
     pub fn finalize(&mut self, final_memory: TimestampedEquipartition<F, 1>) {
-        self.final_memory = Some(final_memory);
+        self.final_memory = Some(final_memory.into_par_iter().collect());
     }
-}
 
-impl<SC: StarkGenericConfig> Chip<SC> for VolatileBoundaryChip<Val<SC>>
-where
-    Val<SC>: PrimeField32,
-{
-    fn air(&self) -> Arc<dyn AnyRap<SC>> {
-        Arc::new(self.air.clone())
+    /// Like [Self::finalize], but takes the final memory as runs that are each sorted by
+    /// address, e.g. one run per address space, and merges them instead of sorting everything.
+    ///
+    /// Panics if a run is not sorted or if an address appears more than once.
+    pub fn finalize_sorted_runs(&mut self, runs: Vec<Vec<VolatileBoundaryEntry<F>>>) {
+        let merged = runs.into_iter().kmerge_by(|(a, _), (b, _)| a < b).collect();
+        self.set_sorted_final_memory(merged);
     }
 
-    fn generate_air_proof_input(self) -> AirProofInput<SC> {
+    /// Like [Self::finalize], but takes the final memory in any order and sorts it with `sort`,
+    /// so that very large traces can use an external or parallel sort, for example
+    /// `|entries| entries.par_sort_unstable_by_key(|&(address, _)| address)`.
+    ///
+    /// Panics if the entries are not sorted by address after `sort`, or if an address appears
+    /// more than once.
+    pub fn finalize_unsorted(
+        &mut self,
+        mut final_memory: Vec<VolatileBoundaryEntry<F>>,
+        sort: impl FnOnce(&mut [VolatileBoundaryEntry<F>]),
+    ) {
+        sort(&mut final_memory);
+        self.set_sorted_final_memory(final_memory);
+    }
+
+    fn set_sorted_final_memory(&mut self, final_memory: Vec<VolatileBoundaryEntry<F>>) {
+        assert!(
+            final_memory.par_windows(2).all(|w| w[0].0 < w[1].0),
+            "final memory is not sorted by address or has duplicate addresses"
+        );
+        self.final_memory = Some(final_memory);
+    }
+
+    pub fn generate_trace(self) -> RowMajorMatrix<F> {
         // Volatile memory requires the starting and final memory to be in equipartition with block size `1`.
         // When block size is `1`, then the `label` is the same as the address pointer.
         let width = self.trace_width();
-        let air = Arc::new(self.air);
         let final_memory = self
             .final_memory
             .expect("Trace generation should be called after finalize");
@@ -208,59 +543,136 @@ where
         };
         let trace_height = trace_height.next_power_of_two();
 
-        // Collect into Vec to sort from BTreeMap and also so we can look at adjacent entries
-        let sorted_final_memory: Vec<_> = final_memory.into_par_iter().collect();
-        let memory_len = sorted_final_memory.len();
-
-        let mut rows = Val::<SC>::zero_vec(trace_height * width);
-        rows.par_chunks_mut(width)
-            .zip(sorted_final_memory.par_iter())
-            .enumerate()
-            .for_each(|(i, (row, ((addr_space, ptr), timestamped_values)))| {
-                // `pointer` is the same as `label` since the equipartition has block size 1
-                let [data] = timestamped_values.values;
-                let row: &mut VolatileBoundaryCols<_> = row.borrow_mut();
-                row.addr_space = Val::<SC>::from_canonical_u32(*addr_space);
-                row.pointer = Val::<SC>::from_canonical_u32(*ptr);
-                row.initial_data = Val::<SC>::ZERO;
-                row.final_data = data;
-                row.final_timestamp = Val::<SC>::from_canonical_u32(timestamped_values.timestamp);
-                row.is_valid = Val::<SC>::ONE;
-
-                // If next.is_valid == 1:
-                if i != memory_len - 1 {
-                    let (next_addr_space, next_ptr) = sorted_final_memory[i + 1].0;
-                    let mut out = Val::<SC>::ZERO;
-                    air.addr_lt_air.0.generate_subrow(
+        let memory_len = final_memory.len();
+        let range_checker = &self.range_checker;
+
+        let mut rows = F::zero_vec(trace_height * width);
+        match &self.air.layout {
+            VolatileAddressLayout::Narrow { addr_lt_air } => {
+                rows.par_chunks_mut(width)
+                    .zip(final_memory.par_iter())
+                    .enumerate()
+                    .for_each(|(i, (row, ((addr_space, ptr), timestamped_values)))| {
+                        // `pointer` is the same as `label` since the equipartition has block size 1
+                        let [data] = timestamped_values.values;
+                        let row: &mut VolatileBoundaryCols<_> = row.borrow_mut();
+                        row.addr_space = F::from_canonical_u32(*addr_space);
+                        row.pointer = F::from_canonical_u32(*ptr);
+                        row.initial_data = F::ZERO;
+                        row.final_data = data;
+                        row.final_timestamp = F::from_canonical_u32(timestamped_values.timestamp);
+                        row.is_valid = F::ONE;
+
+                        // If next.is_valid == 1:
+                        if i != memory_len - 1 {
+                            let (next_addr_space, next_ptr) = final_memory[i + 1].0;
+                            let mut out = F::ZERO;
+                            addr_lt_air.0.generate_subrow(
+                                (
+                                    range_checker,
+                                    &[row.addr_space, row.pointer],
+                                    &[
+                                        F::from_canonical_u32(next_addr_space),
+                                        F::from_canonical_u32(next_ptr),
+                                    ],
+                                ),
+                                ((&mut row.addr_lt_aux).into(), &mut out),
+                            );
+                            debug_assert_eq!(out, F::ONE, "Addresses are not sorted");
+                        }
+                    });
+                // Always do a dummy range check on the last row due to wraparound
+                if memory_len > 0 {
+                    let mut out = F::ZERO;
+                    let row: &mut VolatileBoundaryCols<_> =
+                        rows[width * (trace_height - 1)..].borrow_mut();
+                    addr_lt_air.0.generate_subrow(
+                        (range_checker, &[F::ZERO; ADDR_ELTS], &[F::ZERO; ADDR_ELTS]),
+                        ((&mut row.addr_lt_aux).into(), &mut out),
+                    );
+                }
+            }
+            VolatileAddressLayout::Wide {
+                range_bus,
+                addr_space_max_bits,
+                pointer_max_bits,
+                addr_lt_air,
+            } => {
+                let limb_size = range_bus.range_max_bits;
+                rows.par_chunks_mut(width)
+                    .zip(final_memory.par_iter())
+                    .enumerate()
+                    .for_each(|(i, (row, ((addr_space, ptr), timestamped_values)))| {
+                        let [data] = timestamped_values.values;
+                        let row: &mut WideVolatileBoundaryCols<_> = row.borrow_mut();
+                        for (cols, value, max_bits) in [
+                            (&mut row.addr_space_limbs, *addr_space, *addr_space_max_bits),
+                            (&mut row.pointer_limbs, *ptr, *pointer_max_bits),
+                        ] {
+                            for (i, (col, limb)) in
+                                cols.iter_mut().zip(split(limb_size, value)).enumerate()
+                            {
+                                range_checker.add_count(limb, limb_bits(limb_size, max_bits, i));
+                                *col = F::from_canonical_u32(limb);
+                            }
+                        }
+                        row.initial_data = F::ZERO;
+                        row.final_data = data;
+                        row.final_timestamp = F::from_canonical_u32(timestamped_values.timestamp);
+                        row.is_valid = F::ONE;
+
+                        // If next.is_valid == 1:
+                        if i != memory_len - 1 {
+                            let (next_addr_space, next_ptr) = final_memory[i + 1].0;
+                            let next_limbs = lt_limbs(
+                                &split(limb_size, next_addr_space),
+                                &split(limb_size, next_ptr),
+                            )
+                            .map(F::from_canonical_u32);
+                            let mut out = F::ZERO;
+                            addr_lt_air.0.generate_subrow(
+                                (
+                                    range_checker,
+                                    &lt_limbs(&row.addr_space_limbs, &row.pointer_limbs),
+                                    &next_limbs,
+                                ),
+                                ((&mut row.addr_lt_aux).into(), &mut out),
+                            );
+                            debug_assert_eq!(out, F::ONE, "Addresses are not sorted");
+                        }
+                    });
+                // Always do a dummy range check on the last row due to wraparound
+                if memory_len > 0 {
+                    let mut out = F::ZERO;
+                    let row: &mut WideVolatileBoundaryCols<_> =
+                        rows[width * (trace_height - 1)..].borrow_mut();
+                    addr_lt_air.0.generate_subrow(
                         (
-                            &self.range_checker,
-                            &[row.addr_space, row.pointer],
-                            &[
-                                Val::<SC>::from_canonical_u32(next_addr_space),
-                                Val::<SC>::from_canonical_u32(next_ptr),
-                            ],
+                            range_checker,
+                            &[F::ZERO; WIDE_ADDR_ELTS],
+                            &[F::ZERO; WIDE_ADDR_ELTS],
                         ),
                         ((&mut row.addr_lt_aux).into(), &mut out),
                     );
-                    debug_assert_eq!(out, Val::<SC>::ONE, "Addresses are not sorted");
                 }
-            });
-        // Always do a dummy range check on the last row due to wraparound
-        if memory_len > 0 {
-            let mut out = Val::<SC>::ZERO;
-            let row: &mut VolatileBoundaryCols<_> = rows[width * (trace_height - 1)..].borrow_mut();
-            air.addr_lt_air.0.generate_subrow(
-                (
-                    &self.range_checker,
-                    &[Val::<SC>::ZERO, Val::<SC>::ZERO],
-                    &[Val::<SC>::ZERO, Val::<SC>::ZERO],
-                ),
-                ((&mut row.addr_lt_aux).into(), &mut out),
-            );
+            }
         }
 
-        let trace = RowMajorMatrix::new(rows, width);
-        AirProofInput::simple_no_pis(air, trace)
+        RowMajorMatrix::new(rows, width)
+    }
+}
+
+impl<SC: StarkGenericConfig> Chip<SC> for VolatileBoundaryChip<Val<SC>>
+where
+    Val<SC>: PrimeField32,
+{
+    fn air(&self) -> Arc<dyn AnyRap<SC>> {
+        Arc::new(self.air.clone())
+    }
+
+    fn generate_air_proof_input(self) -> AirProofInput<SC> {
+        let air = Arc::new(self.air.clone());
+        AirProofInput::simple_no_pis(air, self.generate_trace())
     }
 }
 
@@ -274,6 +686,6 @@ impl<F: PrimeField32> ChipUsageGetter for VolatileBoundaryChip<F> {
     }
 
     fn trace_width(&self) -> usize {
-        VolatileBoundaryCols::<F>::width()
+        BaseAir::<F>::width(&self.air)
     }
 }
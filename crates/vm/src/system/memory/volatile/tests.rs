@@ -2,7 +2,9 @@ use std::{collections::HashSet, iter, sync::Arc};
 
 use openvm_circuit_primitives::var_range::{VariableRangeCheckerBus, VariableRangeCheckerChip};
 use openvm_stark_backend::{
-    p3_field::AbstractField, p3_matrix::dense::RowMajorMatrix, prover::types::AirProofInput, Chip,
+    p3_field::AbstractField, p3_matrix::dense::RowMajorMatrix,
+    p3_maybe_rayon::prelude::ParallelSliceMut, prover::types::AirProofInput,
+    utils::disable_debug_builder, verifier::VerificationError, Chip,
 };
 use openvm_stark_sdk::{
     config::baby_bear_poseidon2::{BabyBearPoseidon2Config, BabyBearPoseidon2Engine},
@@ -11,12 +13,13 @@ use openvm_stark_sdk::{
     p3_baby_bear::BabyBear,
     utils::create_seeded_rng,
 };
-use rand::Rng;
+use rand::{seq::SliceRandom, Rng};
 use test_log::test;
 
 use crate::system::memory::{
-    offline_checker::MemoryBus, volatile::VolatileBoundaryChip, TimestampedEquipartition,
-    TimestampedValues,
+    offline_checker::MemoryBus,
+    volatile::{TouchedAddresses, VolatileBoundaryChip},
+    TimestampedEquipartition, TimestampedValues,
 };
 
 type Val = BabyBear;
@@ -131,3 +134,220 @@ fn boundary_air_test() {
     ])
     .expect("Verification failed");
 }
+
+#[test]
+fn touched_addresses_test() {
+    let mut rng = create_seeded_rng();
+
+    let mut touched = TouchedAddresses::default();
+    let mut expected = HashSet::new();
+    for _ in 0..100 {
+        let addr_space = rng.gen_range(1..4);
+        let pointer = rng.gen_range(0..1000);
+        let len = rng.gen_range(0..200);
+        touched.touch_range(addr_space, pointer, len);
+        expected.extend((pointer..pointer + len).map(|ptr| (addr_space, ptr)));
+        assert_eq!(touched.len(), expected.len());
+    }
+    assert_eq!(touched.iter().collect::<HashSet<_>>(), expected);
+}
+
+#[test]
+#[should_panic(expected = "Volatile memory supports addresses of at most 30 bits")]
+fn boundary_air_pointer_too_wide_test() {
+    let range_bus = VariableRangeCheckerBus::new(3, 17);
+    let range_checker = Arc::new(VariableRangeCheckerChip::new(range_bus));
+    VolatileBoundaryChip::<Val>::new(MemoryBus(1), 2, 31, range_checker);
+}
+
+#[test]
+#[should_panic(expected = "31-bit addresses do not fit in a 31-bit field element")]
+fn boundary_air_wide_pointer_too_wide_test() {
+    let range_bus = VariableRangeCheckerBus::new(3, 17);
+    let range_checker = Arc::new(VariableRangeCheckerChip::new(range_bus));
+    VolatileBoundaryChip::<Val>::new_wide(MemoryBus(1), 2, 31, range_checker);
+}
+
+#[test]
+fn boundary_air_wide_test() {
+    const MEMORY_BUS: usize = 1;
+    const POINTER_MAX_BITS: usize = 30;
+    let memory_bus = MemoryBus(MEMORY_BUS);
+    let range_bus = VariableRangeCheckerBus::new(3, 10);
+    let range_checker = Arc::new(VariableRangeCheckerChip::new(range_bus));
+    let mut boundary_chip =
+        VolatileBoundaryChip::new_wide(memory_bus, 2, POINTER_MAX_BITS, range_checker.clone());
+
+    let addresses = [
+        (1, 5),
+        (1, 1 << 20),
+        (2, 3),
+        (2, (1 << POINTER_MAX_BITS) - 1),
+    ];
+    let final_memory: TimestampedEquipartition<Val, 1> = addresses
+        .iter()
+        .map(|&address| {
+            let values = TimestampedValues {
+                values: [Val::ONE],
+                timestamp: 1,
+            };
+            (address, values)
+        })
+        .collect();
+    let memory_trace = |data: Val, timestamp: Val| {
+        let values = addresses
+            .iter()
+            .flat_map(|&(addr_space, pointer)| {
+                [
+                    Val::ONE,
+                    Val::from_canonical_u32(addr_space),
+                    Val::from_canonical_u32(pointer),
+                    data,
+                    timestamp,
+                    Val::ONE,
+                ]
+            })
+            .collect();
+        RowMajorMatrix::new(values, 6)
+    };
+
+    boundary_chip.finalize(final_memory);
+    BabyBearPoseidon2Engine::run_test_fast(vec![
+        boundary_chip.generate_air_proof_input(),
+        range_checker.generate_air_proof_input(),
+        AirProofInput::simple_no_pis(
+            Arc::new(DummyInteractionAir::new(5, false, MEMORY_BUS)),
+            memory_trace(Val::ZERO, Val::ZERO),
+        ),
+        AirProofInput::simple_no_pis(
+            Arc::new(DummyInteractionAir::new(5, true, MEMORY_BUS)),
+            memory_trace(Val::ONE, Val::ONE),
+        ),
+    ])
+    .expect("Verification failed");
+}
+
+#[test]
+fn boundary_air_pointer_out_of_range_test() {
+    const MEMORY_BUS: usize = 1;
+    const POINTER_MAX_BITS: usize = 10;
+    let memory_bus = MemoryBus(MEMORY_BUS);
+    let range_bus = VariableRangeCheckerBus::new(3, 8);
+    let range_checker = Arc::new(VariableRangeCheckerChip::new(range_bus));
+    // Only the wide layout range checks the addresses themselves.
+    let mut boundary_chip =
+        VolatileBoundaryChip::new_wide(memory_bus, 2, POINTER_MAX_BITS, range_checker.clone());
+
+    // The second pointer does not fit in `POINTER_MAX_BITS` bits.
+    let addresses = [(1, 5), (1, 1 << POINTER_MAX_BITS)];
+    let final_memory: TimestampedEquipartition<Val, 1> = addresses
+        .iter()
+        .map(|&address| {
+            let values = TimestampedValues {
+                values: [Val::ONE],
+                timestamp: 1,
+            };
+            (address, values)
+        })
+        .collect();
+    let memory_trace = |final_values: bool| {
+        let values = addresses
+            .iter()
+            .flat_map(|&(addr_space, pointer)| {
+                let (data, timestamp) = if final_values {
+                    (Val::ONE, Val::ONE)
+                } else {
+                    (Val::ZERO, Val::ZERO)
+                };
+                [
+                    Val::ONE,
+                    Val::from_canonical_u32(addr_space),
+                    Val::from_canonical_u32(pointer),
+                    data,
+                    timestamp,
+                    Val::ONE,
+                ]
+            })
+            .collect();
+        RowMajorMatrix::new(values, 6)
+    };
+
+    boundary_chip.finalize(final_memory);
+    let boundary_api: AirProofInput<BabyBearPoseidon2Config> =
+        boundary_chip.generate_air_proof_input();
+    disable_debug_builder();
+    assert_eq!(
+        BabyBearPoseidon2Engine::run_test_fast(vec![
+            boundary_api,
+            range_checker.generate_air_proof_input(),
+            AirProofInput::simple_no_pis(
+                Arc::new(DummyInteractionAir::new(5, false, MEMORY_BUS)),
+                memory_trace(false),
+            ),
+            AirProofInput::simple_no_pis(
+                Arc::new(DummyInteractionAir::new(5, true, MEMORY_BUS)),
+                memory_trace(true),
+            ),
+        ])
+        .err(),
+        Some(VerificationError::ChallengePhaseError)
+    );
+}
+
+#[test]
+fn boundary_sorted_runs_test() {
+    let mut rng = create_seeded_rng();
+    let range_bus = VariableRangeCheckerBus::new(3, 8);
+    let new_chip = || {
+        let range_checker = Arc::new(VariableRangeCheckerChip::new(range_bus));
+        VolatileBoundaryChip::<Val>::new(MemoryBus(1), 2, 10, range_checker)
+    };
+
+    let final_memory: TimestampedEquipartition<Val, 1> = (0..64)
+        .map(|_| {
+            let address = (rng.gen_range(1..4), rng.gen_range(0..1 << 10));
+            let values = TimestampedValues {
+                values: [Val::from_canonical_u32(rng.gen_range(0..1 << 10))],
+                timestamp: rng.gen_range(1..1 << 10),
+            };
+            (address, values)
+        })
+        .collect();
+
+    let mut chip = new_chip();
+    chip.finalize(final_memory.clone());
+    let expected = chip.generate_trace().values;
+
+    let mut runs = vec![vec![]; 4];
+    for (&address, &values) in &final_memory {
+        runs[address.0 as usize].push((address, values));
+    }
+    runs.reverse();
+    let mut chip = new_chip();
+    chip.finalize_sorted_runs(runs);
+    assert_eq!(chip.generate_trace().values, expected);
+
+    let mut entries: Vec<_> = final_memory.into_iter().collect();
+    entries.shuffle(&mut rng);
+    let mut chip = new_chip();
+    chip.finalize_unsorted(entries, |entries| {
+        entries.par_sort_unstable_by_key(|&(address, _)| address)
+    });
+    assert_eq!(chip.generate_trace().values, expected);
+}
+
+#[test]
+#[should_panic(expected = "not sorted")]
+fn boundary_unsorted_run_test() {
+    let range_bus = VariableRangeCheckerBus::new(3, 8);
+    let range_checker = Arc::new(VariableRangeCheckerChip::new(range_bus));
+    let mut chip = VolatileBoundaryChip::<Val>::new(MemoryBus(1), 2, 10, range_checker);
+    let entry = |pointer| {
+        let values = TimestampedValues {
+            values: [Val::ONE],
+            timestamp: 1,
+        };
+        ((1, pointer), values)
+    };
+    chip.finalize_sorted_runs(vec![vec![entry(2), entry(1)]]);
+}
@@ -1,4 +1,6 @@
 pub mod connector;
+/// Chip to look up values in a key-value store committed to by a public Merkle root.
+pub mod kv_store;
 pub mod memory;
 pub mod native_adapter;
 /// Chip to handle phantom instructions.
@@ -1,9 +1,9 @@
 use std::{
     borrow::{Borrow, BorrowMut},
-    cell::RefCell,
     marker::PhantomData,
 };
 
+use atomic_refcell::AtomicRefCell;
 use openvm_circuit::{
     arch::{
         AdapterAirContext, AdapterRuntimeContext, BasicAdapterInterface, ExecutionBridge,
@@ -42,7 +42,7 @@ impl<F: PrimeField32, const R: usize, const W: usize> NativeAdapterChip<F, R, W>
         program_bus: ProgramBus,
         memory_controller: MemoryControllerRef<F>,
     ) -> Self {
-        let memory_controller = RefCell::borrow(&memory_controller);
+        let memory_controller = AtomicRefCell::borrow(&memory_controller);
         let memory_bridge = memory_controller.memory_bridge();
         Self {
             air: NativeAdapterAir {
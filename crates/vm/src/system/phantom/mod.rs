@@ -1,9 +1,10 @@
 use std::{
     borrow::{Borrow, BorrowMut},
-    cell::RefCell,
+    collections::hash_map::Entry,
     sync::{Arc, OnceLock},
 };
 
+use atomic_refcell::AtomicRefCell;
 use openvm_circuit_primitives_derive::AlignedBorrow;
 use openvm_instructions::{
     instruction::Instruction, program::DEFAULT_PC_STEP, PhantomDiscriminant, SysPhantom,
@@ -26,7 +27,7 @@ use rustc_hash::FxHashMap;
 use crate::{
     arch::{
         ExecutionBridge, ExecutionBus, ExecutionError, ExecutionState, InstructionExecutor,
-        PcIncOrSet, PhantomSubExecutor, Streams,
+        PcIncOrSet, PhantomSubExecutor, Streams, VmInventoryError,
     },
     system::{memory::MemoryControllerRef, program::ProgramBus},
 };
@@ -84,12 +85,28 @@ impl<AB: AirBuilder + InteractionBuilder> Air<AB> for PhantomAir {
     }
 }
 
+/// A phantom sub-executor registered with the [PhantomChip].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PhantomSubExecutorInfo {
+    pub discriminant: PhantomDiscriminant,
+    /// See [PhantomSubExecutor::name].
+    pub name: String,
+    /// The extension which registered the sub-executor.
+    pub extension: String,
+}
+
+struct RegisteredSubExecutor<F> {
+    executor: Box<dyn PhantomSubExecutor<F>>,
+    name: String,
+    extension: String,
+}
+
 pub struct PhantomChip<F> {
     pub air: PhantomAir,
     pub rows: Vec<PhantomCols<F>>,
     memory: MemoryControllerRef<F>,
     streams: OnceLock<Arc<Mutex<Streams<F>>>>,
-    phantom_executors: FxHashMap<PhantomDiscriminant, Box<dyn PhantomSubExecutor<F>>>,
+    phantom_executors: FxHashMap<PhantomDiscriminant, RegisteredSubExecutor<F>>,
 }
 
 impl<F> PhantomChip<F> {
@@ -117,13 +134,46 @@ impl<F> PhantomChip<F> {
         }
     }
 
+    /// Registers `sub_executor` for `discriminant` on behalf of `extension`. Fails without
+    /// replacing anything if the discriminant is taken or belongs to a [SysPhantom].
     pub(crate) fn add_sub_executor<P: PhantomSubExecutor<F> + 'static>(
         &mut self,
         sub_executor: P,
         discriminant: PhantomDiscriminant,
-    ) -> Option<Box<dyn PhantomSubExecutor<F>>> {
-        self.phantom_executors
-            .insert(discriminant, Box::new(sub_executor))
+        extension: &str,
+    ) -> Result<(), VmInventoryError> {
+        if SysPhantom::from_repr(discriminant.0).is_some() {
+            return Err(VmInventoryError::PhantomDiscriminantReserved { discriminant });
+        }
+        match self.phantom_executors.entry(discriminant) {
+            Entry::Occupied(entry) => Err(VmInventoryError::PhantomSubExecutorExists {
+                discriminant,
+                extension: entry.get().extension.clone(),
+            }),
+            Entry::Vacant(entry) => {
+                entry.insert(RegisteredSubExecutor {
+                    name: sub_executor.name(),
+                    executor: Box::new(sub_executor),
+                    extension: extension.to_string(),
+                });
+                Ok(())
+            }
+        }
+    }
+
+    /// All registered sub-executors, by discriminant.
+    pub fn sub_executors(&self) -> Vec<PhantomSubExecutorInfo> {
+        let mut infos: Vec<_> = self
+            .phantom_executors
+            .iter()
+            .map(|(&discriminant, registered)| PhantomSubExecutorInfo {
+                discriminant,
+                name: registered.name.clone(),
+                extension: registered.extension.clone(),
+            })
+            .collect();
+        infos.sort_by_key(|info| info.discriminant.0);
+        infos
     }
 }
 
@@ -143,30 +193,43 @@ impl<F: PrimeField32> InstructionExecutor<F> for PhantomChip<F> {
         // If not a system phantom sub-instruction (which is handled in
         // ExecutionSegment), look for a phantom sub-executor to handle it.
         if SysPhantom::from_repr(discriminant.0).is_none() {
-            let sub_executor = self
+            let sub_executor = &mut self
                 .phantom_executors
                 .get_mut(&discriminant)
                 .ok_or_else(|| ExecutionError::PhantomNotFound {
                     pc: from_state.pc,
                     discriminant,
-                })?;
-            let memory = RefCell::borrow(&self.memory);
+                })?
+                .executor;
+            let memory = AtomicRefCell::borrow(&self.memory);
             let mut streams = self.streams.get().unwrap().lock();
-            sub_executor
-                .as_mut()
-                .phantom_execute(
-                    &memory,
-                    &mut streams,
-                    discriminant,
-                    a,
-                    b,
-                    (c_u32 >> 16) as u16,
-                )
-                .map_err(|e| ExecutionError::Phantom {
-                    pc: from_state.pc,
-                    discriminant,
-                    inner: e,
-                })?;
+            if !streams.replay_phantom(from_state.pc)? {
+                let prev_hint_stream = streams.is_recording().then(|| streams.hint_stream.clone());
+                sub_executor
+                    .as_mut()
+                    .phantom_execute(
+                        &memory,
+                        &mut streams,
+                        discriminant,
+                        a,
+                        b,
+                        (c_u32 >> 16) as u16,
+                    )
+                    .map_err(|e| match streams.take_hint_timeout() {
+                        Some(timeout) => ExecutionError::HintTimeout {
+                            pc: from_state.pc,
+                            timeout,
+                        },
+                        None => ExecutionError::Phantom {
+                            pc: from_state.pc,
+                            discriminant,
+                            inner: e,
+                        },
+                    })?;
+                if let Some(prev_hint_stream) = prev_hint_stream {
+                    streams.record_phantom(from_state.pc, &prev_hint_stream);
+                }
+            }
         }
 
         self.rows.push(PhantomCols {
@@ -175,7 +238,7 @@ impl<F: PrimeField32> InstructionExecutor<F> for PhantomChip<F> {
             timestamp: F::from_canonical_u32(from_state.timestamp),
             is_valid: F::ONE,
         });
-        RefCell::borrow_mut(&self.memory).increment_timestamp();
+        AtomicRefCell::borrow_mut(&self.memory).increment_timestamp();
         Ok(ExecutionState::new(
             from_state.pc + DEFAULT_PC_STEP,
             from_state.timestamp + 1,
@@ -1,18 +1,27 @@
-use std::{collections::BTreeMap, sync::Arc};
+use std::{
+    array,
+    collections::BTreeMap,
+    io,
+    sync::{Arc, Mutex},
+};
 
 use derive_more::derive::From;
 use openvm_circuit::{
     arch::{
         hasher::{poseidon2::vm_poseidon2_hasher, Hasher},
-        ChipId, ExitCode, MemoryConfig, SingleSegmentVmExecutor, SystemConfig, SystemExecutor,
-        SystemPeriphery, SystemTraceHeights, VirtualMachine, VmChipComplex, VmComplexTraceHeights,
-        VmConfig, VmInventoryError, VmInventoryTraceHeights,
+        AddressSpaceDescriptor, ChipId, ExecutionError, ExecutionLog, ExecutionSegment,
+        ExecutionState, ExitCode, GdbServer, GuestLogConfig, InstructionExecutor, MemoryConfig,
+        PhantomSubExecutor, ProgressAction, SegmentationLimit, SingleSegmentVmExecutor, Streams,
+        SystemConfig, SystemExecutor, SystemPeriphery, SystemTraceHeights, VirtualMachine,
+        VmCheckpoint, VmChipComplex, VmComplexTraceHeights, VmConfig, VmConfigError, VmExecutor,
+        VmInventoryError, VmInventoryTraceHeights, VmVerificationError, CONNECTOR_AIR_ID,
+        SYSTEM_BUS_OWNER,
     },
     derive::{AnyEnum, InstructionExecutor, VmConfig},
     system::{
         memory::{
-            tree::public_values::UserPublicValuesProof, MemoryTraceHeights,
-            VolatileMemoryTraceHeights, CHUNK,
+            tree::{proof::MemoryRegionProof, public_values::UserPublicValuesProof},
+            MemoryController, MemoryTraceHeights, VolatileMemoryTraceHeights, CHUNK,
         },
         program::trace::VmCommittedExe,
     },
@@ -362,6 +371,7 @@ fn test_vm_initial_memory() {
         pc_start: 0,
         init_memory,
         fn_bounds: Default::default(),
+        source_lines: Default::default(),
     };
     air_test(config, exe);
 }
@@ -431,6 +441,155 @@ fn test_vm_1_persistent() {
         .expect("Verification failed");
 }
 
+#[test]
+fn test_vm_entry_point() {
+    let engine = BabyBearPoseidon2Engine::new(FriParameters::standard_fast());
+    let config = NativeConfig {
+        system: SystemConfig::new(3, MemoryConfig::new(1, 1, 16, 10, 6, 64), 0),
+        native: Default::default(),
+    }
+    .with_continuations();
+
+    let vm = VirtualMachine::new(engine, config);
+    let pk = vm.keygen();
+
+    let instructions = vec![
+        // Skipped: execution starts at the next instruction.
+        Instruction::from_isize(VmOpcode::with_default_offset(TERMINATE), 0, 0, 0, 0, 0),
+        Instruction::large_from_isize(VmOpcode::with_default_offset(SUB), 0, 0, 1, 1, 1, 0, 0),
+        Instruction::from_isize(
+            VmOpcode::with_default_offset(NativeBranchEqualOpcode(BNE)),
+            0,
+            0,
+            -(DEFAULT_PC_STEP as isize),
+            1,
+            0,
+        ),
+        Instruction::from_isize(VmOpcode::with_default_offset(TERMINATE), 0, 0, 0, 0, 0),
+    ];
+    // Cell 0 is the counter, set through the initial memory.
+    let exe = VmExe::new(Program::from_instructions(&instructions))
+        .with_pc_start(DEFAULT_PC_STEP)
+        .with_init_memory(BTreeMap::from([((1, 0), BabyBear::from_canonical_u32(6))]));
+    let committed_exe = vm.commit_exe(exe);
+
+    let result = vm
+        .execute_and_generate_with_cached_program(committed_exe.clone(), vec![])
+        .unwrap();
+    let final_memory = result.final_memory.clone().unwrap();
+    let proofs = vm.prove(&pk, result);
+    let with_exe = |f: fn(&mut VmExe<BabyBear>)| {
+        let mut committed_exe = (*committed_exe).clone();
+        f(&mut committed_exe.exe);
+        committed_exe
+    };
+    assert!(matches!(
+        vm.verify_exe(
+            &pk.get_vk(),
+            proofs.clone(),
+            &with_exe(|exe| exe.pc_start = 0)
+        ),
+        Err(VmVerificationError::EntryPcMismatch {
+            expected: 0,
+            actual: DEFAULT_PC_STEP,
+        })
+    ));
+    assert!(matches!(
+        vm.verify_exe(
+            &pk.get_vk(),
+            proofs.clone(),
+            &with_exe(|exe| {
+                exe.init_memory
+                    .insert((1, 0), BabyBear::from_canonical_u32(7));
+            })
+        ),
+        Err(VmVerificationError::EntryMemoryMismatch)
+    ));
+    // Same entry point and memory, but a program that skips the loop.
+    let mut other_instructions = instructions.clone();
+    other_instructions[2] =
+        Instruction::from_isize(VmOpcode::with_default_offset(TERMINATE), 0, 0, 0, 0, 0);
+    let mut other_exe = committed_exe.exe.clone();
+    other_exe.program = Program::from_instructions(&other_instructions);
+    assert!(matches!(
+        vm.verify_exe(&pk.get_vk(), proofs.clone(), &vm.commit_exe(other_exe)),
+        Err(VmVerificationError::ProgramCommitMismatch { segment: 0 })
+    ));
+    assert!(matches!(
+        vm.verify_exe(&pk.get_vk(), vec![], &committed_exe),
+        Err(VmVerificationError::NoSegmentProofs)
+    ));
+    // The loop counts the cell down to zero.
+    let mut region = MemoryRegionProof::<CHUNK, _>::compute(
+        vm.config().system.memory_config.memory_dimensions(),
+        &final_memory,
+        &vm_poseidon2_hasher(),
+        1,
+        0,
+        CHUNK,
+    );
+    assert_eq!(region.values, vec![BabyBear::ZERO; CHUNK]);
+    vm.verify_region(&proofs, &region)
+        .expect("Region verification failed");
+    region.values[0] = BabyBear::ONE;
+    assert!(matches!(
+        vm.verify_region(&proofs, &region),
+        Err(VmVerificationError::MemoryRegionMismatch)
+    ));
+    vm.verify_exe(&pk.get_vk(), proofs, &committed_exe)
+        .expect("Verification failed");
+}
+
+#[test]
+fn test_vm_initial_carry_over() {
+    let engine = BabyBearPoseidon2Engine::new(FriParameters::standard_fast());
+    let config = NativeConfig {
+        system: SystemConfig::new(3, MemoryConfig::new(1, 1, 16, 10, 6, 64), 0)
+            .with_carry_over("transcript", 1),
+        native: Default::default(),
+    }
+    .with_continuations();
+
+    let vm = VirtualMachine::new(engine, config);
+    let pk = vm.keygen();
+
+    let program = Program::from_instructions(&[Instruction::from_isize(
+        VmOpcode::with_default_offset(TERMINATE),
+        0,
+        0,
+        0,
+        0,
+        0,
+    )]);
+
+    let result = vm.execute_and_generate(program.clone(), vec![]).unwrap();
+    let proofs = vm.prove(&pk, result);
+    vm.verify(&pk.get_vk(), proofs)
+        .expect("Verification failed");
+
+    // Start the first segment from a non-zero carry-over value. The trace and the public values
+    // agree, so only the verifier's check on the first segment catches it.
+    let mut result = vm.execute_and_generate(program, vec![]).unwrap();
+    let (_, connector) = result.per_segment[0]
+        .per_air
+        .iter_mut()
+        .find(|(air_id, _)| *air_id == CONNECTOR_AIR_ID)
+        .unwrap();
+    let tampered = BabyBear::from_canonical_u32(7);
+    // The carry-over value is the last column of both rows and the last two public values.
+    let trace = connector.raw.common_main.as_mut().unwrap();
+    for row in 0..2 {
+        *trace.row_mut(row).last_mut().unwrap() = tampered;
+    }
+    let num_pvs = connector.raw.public_values.len();
+    connector.raw.public_values[num_pvs - 2..].fill(tampered);
+    let proofs = vm.prove(&pk, result);
+    assert!(matches!(
+        vm.verify(&pk.get_vk(), proofs),
+        Err(VmVerificationError::NonZeroInitialCarryOver { index: 0 })
+    ));
+}
+
 #[test]
 fn test_vm_continuations() {
     let n = 200000;
@@ -588,6 +747,634 @@ fn test_vm_fibonacci_old() {
     air_test(NativeConfig::default(), program);
 }
 
+/// Counts from 0 to `n` in a loop: 2 stores, then `n` iterations of branch, add and jump, then the
+/// final branch, for `3 * n + 3` instructions.
+fn counter_program(n: isize) -> Program<BabyBear> {
+    let instructions = vec![
+        Instruction::from_isize(VmOpcode::with_default_offset(STOREW), n, 0, 0, 0, 1),
+        Instruction::from_isize(VmOpcode::with_default_offset(STOREW), 0, 0, 1, 0, 1),
+        Instruction::from_isize(
+            VmOpcode::with_default_offset(NativeBranchEqualOpcode(BEQ)),
+            1,
+            0,
+            3 * DEFAULT_PC_STEP as isize,
+            1,
+            1,
+        ),
+        Instruction::large_from_isize(VmOpcode::with_default_offset(ADD), 1, 1, 1, 1, 1, 0, 0),
+        Instruction::from_isize(
+            VmOpcode::with_default_offset(JAL),
+            2,
+            -2 * DEFAULT_PC_STEP as isize,
+            0,
+            1,
+            0,
+        ),
+        Instruction::from_isize(VmOpcode::with_default_offset(TERMINATE), 0, 0, 0, 0, 0),
+    ];
+    Program::from_instructions(&instructions)
+}
+
+#[test]
+fn test_vm_progress_callback() {
+    let program = counter_program(9);
+    let config = NativeConfig::default();
+
+    let progress = Arc::new(Mutex::new(vec![]));
+    let mut segment = ExecutionSegment::new(
+        &config,
+        program.clone(),
+        vec![].into(),
+        None,
+        Default::default(),
+    );
+    let recorded = progress.clone();
+    segment.set_progress_callback(5, move |progress| {
+        recorded.lock().unwrap().push(progress.instructions_retired);
+        ProgressAction::Continue
+    });
+    assert!(segment.execute_from_pc(0).unwrap().is_terminated);
+    assert_eq!(*progress.lock().unwrap(), vec![5, 10, 15, 20, 25, 30]);
+
+    let mut segment =
+        ExecutionSegment::new(&config, program, vec![].into(), None, Default::default());
+    segment.set_progress_callback(5, |_| ProgressAction::Abort);
+    assert!(matches!(
+        segment.execute_from_pc(0),
+        Err(ExecutionError::Aborted { .. })
+    ));
+}
+
+#[test]
+fn test_vm_step() {
+    let config = NativeConfig::default();
+    let mut segment = ExecutionSegment::new(
+        &config,
+        counter_program(9),
+        vec![].into(),
+        None,
+        Default::default(),
+    );
+    let record = segment.step(0).unwrap();
+    assert_eq!(record.opcode, VmOpcode::with_default_offset(STOREW));
+    assert_eq!(record.operands[0], BabyBear::from_canonical_u32(9));
+    assert_eq!(record.next_pc, DEFAULT_PC_STEP);
+    let writes: Vec<_> = record.writes_to(1).collect();
+    assert_eq!(writes.len(), 1);
+    assert_eq!(writes[0].data, vec![BabyBear::from_canonical_u32(9)]);
+
+    let terminate_pc = 5 * DEFAULT_PC_STEP;
+    let state = segment.run_until(record.next_pc, terminate_pc).unwrap();
+    assert_eq!(state.pc, terminate_pc);
+    assert!(!state.is_terminated);
+    let record = segment.step(terminate_pc).unwrap();
+    assert_eq!(record.exit_code, Some(0));
+    assert!(record.memory_accesses.is_empty());
+}
+
+#[test]
+fn test_vm_cycle_budget() {
+    let mut config = NativeConfig::default();
+    config.system = config.system.with_max_cycles(10);
+    let mut segment = ExecutionSegment::new(
+        &config,
+        counter_program(9),
+        vec![].into(),
+        None,
+        Default::default(),
+    );
+    match segment.execute_from_pc(0) {
+        Err(ExecutionError::BudgetExceeded { usage, .. }) => assert_eq!(usage.cycles, 11),
+        res => panic!(
+            "expected the cycle budget to be exceeded, got {:?}",
+            res.err()
+        ),
+    }
+
+    config.system = config.system.with_max_cycles(30);
+    let mut segment = ExecutionSegment::new(
+        &config,
+        counter_program(9),
+        vec![].into(),
+        None,
+        Default::default(),
+    );
+    assert!(segment.execute_from_pc(0).unwrap().is_terminated);
+    assert_eq!(segment.budget_usage.cycles, 30);
+}
+
+#[test]
+fn test_vm_inspection_hook() {
+    let mut executor = VmExecutor::<BabyBear, _>::new(NativeConfig::default());
+    let inspected = Arc::new(Mutex::new(vec![]));
+    let recorded = inspected.clone();
+    executor.set_inspection_hook(4, move |state| {
+        let [n, counter] = state.read_memory(1, 0, 2)[..] else {
+            unreachable!()
+        };
+        eyre::ensure!(counter.as_canonical_u32() <= n.as_canonical_u32());
+        recorded
+            .lock()
+            .unwrap()
+            .push((state.pc(), state.timestamp(), counter));
+        Ok(())
+    });
+    executor.execute(counter_program(9), vec![]).unwrap();
+    // The counter program executes 30 instructions before TERMINATE.
+    let inspected = inspected.lock().unwrap();
+    assert_eq!(inspected.len(), 7);
+    assert!(inspected.windows(2).all(|w| w[0].1 < w[1].1));
+    assert_eq!(inspected.last().unwrap().2, BabyBear::from_canonical_u32(9));
+
+    executor.set_inspection_hook(10, |state| {
+        eyre::ensure!(
+            state.read_memory(1, 1, 1)[0] == BabyBear::ZERO,
+            "counter moved"
+        );
+        Ok(())
+    });
+    assert!(matches!(
+        executor.execute(counter_program(9), vec![]),
+        Err(ExecutionError::InspectionFailed { .. })
+    ));
+}
+
+#[test]
+fn test_vm_instruction_limit() {
+    let mut config = NativeConfig::default();
+    config.system = config.system.with_max_segment_instructions(10);
+    let mut segment = ExecutionSegment::new(
+        &config,
+        counter_program(9),
+        vec![].into(),
+        None,
+        Default::default(),
+    );
+    assert!(matches!(
+        segment.execute_from_pc(0),
+        Err(ExecutionError::InstructionLimitExceeded { limit: 10, .. })
+    ));
+
+    // The counter program executes 30 instructions before TERMINATE.
+    config.system.max_segment_instructions = Some(30);
+    let mut segment = ExecutionSegment::new(
+        &config,
+        counter_program(9),
+        vec![].into(),
+        None,
+        Default::default(),
+    );
+    assert!(segment.execute_from_pc(0).unwrap().is_terminated);
+}
+
+#[test]
+fn test_vm_read_only_write() {
+    let mut config = NativeConfig::default();
+    let descriptor = AddressSpaceDescriptor {
+        read_only: true,
+        ..AddressSpaceDescriptor::unrestricted(1, &config.system.memory_config)
+    };
+    config.system.memory_config = config.system.memory_config.with_address_space(descriptor);
+    let mut segment = ExecutionSegment::new(
+        &config,
+        counter_program(9),
+        vec![].into(),
+        None,
+        Default::default(),
+    );
+    // The first instruction stores to [0]_1
+    assert!(matches!(
+        segment.execute_from_pc(0),
+        Err(ExecutionError::ReadOnlyWrite {
+            pc: 0,
+            address_space: 1,
+            pointer: 0,
+        })
+    ));
+}
+
+#[test]
+fn test_vm_checkpoint_resume() {
+    let exe = VmExe::new(counter_program(1000));
+    let mut config = NativeConfig::default().with_continuations();
+    config.system = config
+        .system
+        .with_segmentation_limit(SegmentationLimit::MaxCycles(500));
+    let executor = VmExecutor::<BabyBear, _>::new(config);
+    let expected_memory = executor.execute(exe.clone(), vec![]).unwrap().unwrap();
+
+    let mut checkpoint = Some(executor.initial_checkpoint(&exe, vec![]));
+    let mut num_segments = 0;
+    let mut final_memory = None;
+    while let Some(current) = checkpoint {
+        // Resume from the serialized state, as a different process would.
+        let current: VmCheckpoint<BabyBear> =
+            serde_json::from_str(&serde_json::to_string(&current).unwrap()).unwrap();
+        assert_eq!(current.segment_idx, num_segments);
+        let (mut segment, next) = executor.execute_segment(&exe, current).unwrap();
+        num_segments += 1;
+        final_memory = segment.final_memory.take();
+        checkpoint = next;
+    }
+    assert!(num_segments > 1);
+    assert_eq!(final_memory.unwrap(), expected_memory);
+}
+
+#[test]
+fn test_vm_execute_only() {
+    let exe = VmExe::new(counter_program(1000));
+    let mut config = NativeConfig::default().with_continuations();
+    config.system = config
+        .system
+        .with_segmentation_limit(SegmentationLimit::MaxCycles(500));
+    let executor = VmExecutor::<BabyBear, _>::new(config);
+    let segments = executor.execute_segments(exe.clone(), vec![]).unwrap();
+    let expected_memory = segments.last().unwrap().final_memory.clone().unwrap();
+
+    let result = executor.execute_only(exe, vec![]).unwrap();
+    assert_eq!(result.exit_code, ExitCode::Success as u32);
+    assert_eq!(result.num_segments, segments.len());
+    assert_eq!(result.cycles, 3003);
+    assert_eq!(result.final_memory[&(1, 0)], expected_memory[&(1, 0)]);
+}
+
+#[test]
+fn test_vm_basic_block_execution() {
+    let exe = VmExe::new(counter_program(1000));
+    let mut config = NativeConfig::default().with_continuations();
+    config.system = config
+        .system
+        .with_segmentation_limit(SegmentationLimit::MaxCycles(500));
+    let mut executor = VmExecutor::<BabyBear, _>::new(config);
+    let expected = executor.execute_segments(exe.clone(), vec![]).unwrap();
+
+    executor.set_basic_block_execution(true);
+    let segments = executor.execute_segments(exe, vec![]).unwrap();
+    assert_eq!(segments.len(), expected.len());
+    for (segment, expected) in segments.iter().zip(&expected) {
+        assert_eq!(
+            segment.current_trace_heights(),
+            expected.current_trace_heights()
+        );
+        assert_eq!(
+            segment.chip_complex.program_chip().execution_frequencies,
+            expected.chip_complex.program_chip().execution_frequencies
+        );
+        assert_eq!(segment.final_memory, expected.final_memory);
+    }
+}
+
+struct CountingExecutor(Arc<Mutex<usize>>);
+
+impl InstructionExecutor<BabyBear> for CountingExecutor {
+    fn execute(
+        &mut self,
+        _instruction: Instruction<BabyBear>,
+        from_state: ExecutionState<u32>,
+    ) -> openvm_circuit::arch::Result<ExecutionState<u32>> {
+        *self.0.lock().unwrap() += 1;
+        Ok(ExecutionState::new(
+            from_state.pc + DEFAULT_PC_STEP,
+            from_state.timestamp + 1,
+        ))
+    }
+
+    fn get_opcode_name(&self, _opcode: usize) -> String {
+        "COUNT".to_string()
+    }
+}
+
+struct NopSubEx;
+
+impl PhantomSubExecutor<BabyBear> for NopSubEx {
+    fn phantom_execute(
+        &mut self,
+        _: &MemoryController<BabyBear>,
+        _: &mut Streams<BabyBear>,
+        _: PhantomDiscriminant,
+        _: BabyBear,
+        _: BabyBear,
+        _: u16,
+    ) -> eyre::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_vm_phantom_registry() {
+    let chip_complex = NativeConfig::default().create_chip_complex().unwrap();
+    let sub_executors = chip_complex.phantom_sub_executors();
+    let discriminants: Vec<_> = sub_executors
+        .iter()
+        .map(|info| info.discriminant.0)
+        .collect();
+    assert_eq!(
+        discriminants,
+        [
+            NativePhantom::Print as u16,
+            NativePhantom::HintInput as u16,
+            NativePhantom::HintBits as u16,
+        ]
+    );
+    assert!(sub_executors
+        .iter()
+        .all(|info| info.extension == std::any::type_name::<Native>()));
+
+    let builder = chip_complex.inventory_builder();
+    let taken = PhantomDiscriminant(NativePhantom::Print as u16);
+    assert!(matches!(
+        builder.add_phantom_sub_executor(NopSubEx, taken),
+        Err(VmInventoryError::PhantomSubExecutorExists { discriminant, .. }) if discriminant == taken
+    ));
+    assert!(matches!(
+        builder.add_phantom_sub_executor(NopSubEx, PhantomDiscriminant(SysPhantom::Nop as u16)),
+        Err(VmInventoryError::PhantomDiscriminantReserved { .. })
+    ));
+    let free = PhantomDiscriminant(0x7000);
+    builder.add_phantom_sub_executor(NopSubEx, free).unwrap();
+    drop(builder);
+
+    let info = chip_complex.phantom_sub_executors().pop().unwrap();
+    assert_eq!(info.discriminant, free);
+    assert!(info.name.ends_with("NopSubEx"));
+    assert!(chip_complex
+        .phantom_sub_executors()
+        .iter()
+        .any(|info| info.discriminant == taken && info.name.contains("NativePrintSubEx")));
+}
+
+#[test]
+fn test_vm_dynamic_executor() {
+    const COUNT_OPCODE: usize = 0x1000;
+    let program = Program::from_instructions(&[
+        Instruction::from_isize(VmOpcode::from_usize(COUNT_OPCODE), 0, 0, 0, 0, 0),
+        Instruction::from_isize(VmOpcode::from_usize(COUNT_OPCODE + 1), 0, 0, 0, 0, 0),
+        Instruction::from_isize(VmOpcode::with_default_offset(TERMINATE), 0, 0, 0, 0, 0),
+    ]);
+    let mut executor = VmExecutor::<BabyBear, _>::new(NativeConfig::default());
+    let count = Arc::new(Mutex::new(0));
+    executor
+        .register_executor(
+            COUNT_OPCODE..COUNT_OPCODE + 2,
+            Box::new(CountingExecutor(count.clone())),
+        )
+        .unwrap();
+    assert!(matches!(
+        executor.register_executor(
+            COUNT_OPCODE + 1..COUNT_OPCODE + 3,
+            Box::new(CountingExecutor(count.clone())),
+        ),
+        Err(VmInventoryError::OpcodeExists { .. })
+    ));
+    let terminate = VmOpcode::with_default_offset(TERMINATE).as_usize();
+    assert!(matches!(
+        executor.register_executor(
+            terminate..terminate + 1,
+            Box::new(CountingExecutor(count.clone())),
+        ),
+        Err(VmInventoryError::OpcodeExists { .. })
+    ));
+
+    executor.execute(program, vec![]).unwrap();
+    assert_eq!(*count.lock().unwrap(), 2);
+}
+
+/// Replays the packets a debugger would send, recording the replies of the server.
+struct ScriptedStream {
+    input: io::Cursor<Vec<u8>>,
+    output: Arc<Mutex<Vec<u8>>>,
+}
+
+impl io::Read for ScriptedStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        io::Read::read(&mut self.input, buf)
+    }
+}
+
+impl io::Write for ScriptedStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.output.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn gdb_packet(data: &str) -> String {
+    let checksum = data.bytes().fold(0u8, |sum, byte| sum.wrapping_add(byte));
+    format!("${data}#{checksum:02x}")
+}
+
+#[test]
+fn test_vm_gdb_server() {
+    let program = counter_program(9);
+    let add_pc = 3 * DEFAULT_PC_STEP;
+    let script: String = [
+        "?".to_string(),
+        format!("Z0,{add_pc:x},4"),
+        "c".to_string(),
+        "p20".to_string(),
+        format!("z0,{add_pc:x},4"),
+        "c".to_string(),
+    ]
+    .iter()
+    .map(|packet| format!("+{}", gdb_packet(packet)))
+    .collect();
+    let output = Arc::new(Mutex::new(vec![]));
+    let stream = ScriptedStream {
+        input: io::Cursor::new(script.into_bytes()),
+        output: output.clone(),
+    };
+
+    let mut executor = VmExecutor::<BabyBear, _>::new(NativeConfig::default());
+    executor.set_debugger(GdbServer::new(stream));
+    executor.execute(program, vec![]).unwrap();
+
+    // Every packet is acknowledged with `+`. Resuming has no reply, but the next stop and the
+    // exit are reported.
+    let expected = [
+        format!("+{}", gdb_packet("S05")),
+        format!("+{}", gdb_packet("OK")),
+        format!("+{}", gdb_packet("S05")),
+        format!("+{}", gdb_packet(&hex::encode(add_pc.to_le_bytes()))),
+        format!("+{}", gdb_packet("OK")),
+        format!("+{}", gdb_packet("W00")),
+    ]
+    .concat();
+    let output = String::from_utf8(output.lock().unwrap().clone()).unwrap();
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn test_vm_exit_message() {
+    let program = Program::from_instructions(&[
+        Instruction::from_isize(
+            VmOpcode::with_default_offset(STOREW),
+            'h' as isize,
+            0,
+            0,
+            0,
+            1,
+        ),
+        Instruction::from_isize(
+            VmOpcode::with_default_offset(STOREW),
+            'i' as isize,
+            1,
+            0,
+            0,
+            1,
+        ),
+        Instruction::from_isize(
+            VmOpcode::with_default_offset(TERMINATE),
+            0,
+            2,
+            ExitCode::Error as isize,
+            1,
+            0,
+        ),
+    ]);
+    let executor = VmExecutor::<BabyBear, _>::new(NativeConfig::default());
+    let result = executor.execute_with_result(program, vec![]).unwrap();
+    assert!(!result.is_success());
+    assert_eq!(result.exit_code, ExitCode::Error as u32);
+    assert_eq!(result.exit_message.as_deref(), Some("hi"));
+}
+
+#[test]
+fn test_vm_guest_log() {
+    // The message pointer and length are stored little-endian in cells 0 and 4, the message at
+    // cell 8.
+    let instructions: Vec<_> = [(0, 8), (4, 2), (8, 'h' as isize), (9, 'i' as isize)]
+        .into_iter()
+        .map(|(ptr, value)| {
+            Instruction::from_isize(VmOpcode::with_default_offset(STOREW), value, ptr, 0, 0, 1)
+        })
+        .chain([
+            Instruction::phantom(
+                PhantomDiscriminant(SysPhantom::DebugLog as u16),
+                BabyBear::ZERO,
+                BabyBear::from_canonical_u32(4),
+                0,
+            ),
+            Instruction::from_isize(VmOpcode::with_default_offset(TERMINATE), 0, 0, 0, 0, 0),
+        ])
+        .collect();
+    let program = Program::from_instructions(&instructions);
+
+    let mut config = NativeConfig::default();
+    config.system = config.system.with_guest_log(GuestLogConfig {
+        enabled: true,
+        register_address_space: 1,
+        memory_address_space: 1,
+    });
+    let records = Arc::new(Mutex::new(vec![]));
+    let mut executor = VmExecutor::<BabyBear, _>::new(config.clone());
+    let logged = records.clone();
+    executor.set_guest_logger(move |record| logged.lock().unwrap().push(record.clone()));
+    executor.execute(program.clone(), vec![]).unwrap();
+    let records = records.lock().unwrap();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].message, "hi");
+    assert_eq!(records[0].cycle, 4);
+    assert_eq!(records[0].pc, 4 * DEFAULT_PC_STEP);
+
+    let mut executor = VmExecutor::<BabyBear, _>::new(NativeConfig {
+        system: config.system.without_guest_log(),
+        ..config
+    });
+    executor.set_guest_logger(|_| panic!("guest log is disabled"));
+    executor.execute(program, vec![]).unwrap();
+}
+
+#[test]
+fn test_vm_hint_perf_counters() {
+    type F = BabyBear;
+    let program = Program::from_instructions(&[
+        Instruction::phantom(
+            PhantomDiscriminant(NativePhantom::HintInput as u16),
+            F::ZERO,
+            F::ZERO,
+            0,
+        ),
+        Instruction::phantom(
+            PhantomDiscriminant(SysPhantom::HintPerfCounters as u16),
+            F::ZERO,
+            F::ZERO,
+            0,
+        ),
+        Instruction::from_isize(VmOpcode::with_default_offset(TERMINATE), 0, 0, 0, 0, 0),
+    ]);
+
+    let mut config = NativeConfig::default();
+    config.system = config.system.with_max_segment_len(1 << 20);
+    let executor = VmExecutor::<F, _>::new(config);
+    let input = vec![vec![F::from_canonical_u32(7), F::from_canonical_u32(8)]];
+    let result = executor.execute_only(program, input).unwrap();
+
+    // The counters are read before the hints of the input, which are kept.
+    let hints: Vec<u32> = result
+        .streams
+        .hint_stream
+        .iter()
+        .map(|hint| hint.as_canonical_u32())
+        .collect();
+    let counter = |i: usize| {
+        u32::from_le_bytes(array::from_fn(|j| {
+            hints[4 * i + j]
+                .try_into()
+                .expect("counters are hinted as bytes")
+        }))
+    };
+    assert_eq!(hints.len(), 5 * 4 + 3);
+    assert_eq!(counter(0), 1);
+    assert_eq!(counter(1), 1);
+    assert!(counter(2) > 0);
+    assert_eq!(counter(4), 1 << 20);
+    assert_eq!(hints[5 * 4..], [2, 7, 8]);
+}
+
+#[test]
+fn test_vm_config_validation() {
+    let config = NativeConfig::default();
+    assert!(VmConfig::<BabyBear>::validate(&config).is_ok());
+    assert!(VmExecutor::<BabyBear, _>::try_new(config.clone()).is_ok());
+
+    let mut config = NativeConfig::default();
+    config.system.memory_config.max_access_adapter_n = 48;
+    assert!(matches!(
+        VmExecutor::<BabyBear, _>::try_new(config),
+        Err(VmConfigError::AccessAdapterSize(48))
+    ));
+
+    let mut config = NativeConfig::default();
+    let descriptor = AddressSpaceDescriptor::unrestricted(2, &config.system.memory_config);
+    config.system.memory_config.address_spaces = vec![descriptor, descriptor];
+    assert!(matches!(
+        VmConfig::<BabyBear>::validate(&config),
+        Err(VmConfigError::DuplicateAddressSpace { address_space: 2 })
+    ));
+
+    let mut config = NativeConfig::default();
+    config.system = config.system.with_continuations();
+    config.system.memory_config.as_height = 1;
+    assert!(matches!(
+        VmConfig::<BabyBear>::validate(&config),
+        Err(VmConfigError::PublicValuesAddressSpace)
+    ));
+
+    let mut config = NativeConfig::default();
+    config.system = config
+        .system
+        .with_carry_over("transcript", 2)
+        .with_carry_over("transcript", 1);
+    assert!(matches!(
+        VmConfig::<BabyBear>::validate(&config),
+        Err(VmConfigError::InvalidCarryOver { name }) if name == "transcript"
+    ));
+}
+
 #[test]
 fn test_vm_fibonacci_old_cycle_tracker() {
     // NOTE: Instructions commented until cycle tracker instructions are not counted as additional assembly Instructions
@@ -728,8 +1515,7 @@ fn test_vm_field_extension_arithmetic_persistent() {
     air_test(config, program);
 }
 
-#[test]
-fn test_vm_hint() {
+fn hint_program() -> Program<BabyBear> {
     let instructions = vec![
         Instruction::from_isize(VmOpcode::with_default_offset(STOREW), 0, 0, 16, 0, 1),
         Instruction::large_from_isize(
@@ -788,8 +1574,12 @@ fn test_vm_hint() {
         ),
         Instruction::from_isize(VmOpcode::with_default_offset(TERMINATE), 0, 0, 0, 0, 0),
     ];
+    Program::from_instructions(&instructions)
+}
 
-    let program = Program::from_instructions(&instructions);
+#[test]
+fn test_vm_hint() {
+    let program = hint_program();
 
     type F = BabyBear;
 
@@ -798,6 +1588,34 @@ fn test_vm_hint() {
     air_test_with_min_segments(config, program, input_stream, 1);
 }
 
+#[test]
+fn test_vm_execution_log_replay() {
+    type F = BabyBear;
+    let exe = VmExe::new(hint_program());
+    let executor = VmExecutor::<F, _>::new(NativeConfig::default());
+    let input_stream: Vec<Vec<F>> = vec![vec![F::TWO]];
+    let expected = executor
+        .execute_only(exe.clone(), input_stream.clone())
+        .unwrap();
+
+    let log = executor
+        .record_execution_log(exe.clone(), input_stream)
+        .unwrap();
+    assert_eq!(log.phantoms.len(), 1);
+    let log: ExecutionLog<F> = serde_json::from_str(&serde_json::to_string(&log).unwrap()).unwrap();
+    // The replay has no input, the hints come from the log.
+    let replayed = executor
+        .execute_only(exe.clone(), Streams::replay(log))
+        .unwrap();
+    assert_eq!(replayed.final_memory, expected.final_memory);
+    assert_eq!(replayed.cycles, expected.cycles);
+
+    assert!(matches!(
+        executor.execute_only(exe, Streams::replay(ExecutionLog::default())),
+        Err(ExecutionError::ReplayDivergence { .. })
+    ));
+}
+
 #[test]
 fn test_vm_compress_poseidon2_as2() {
     let mut rng = create_seeded_rng();
@@ -993,6 +1811,27 @@ impl Default for NativeKeccakConfig {
     }
 }
 
+#[test]
+fn test_vm_bus_allocation() {
+    let config = NativeKeccakConfig::default();
+    let complex = VmConfig::<BabyBear>::create_chip_complex(&config).unwrap();
+    let bus_allocator = complex.bus_allocator();
+    let system_buses = complex.system_bus_indices();
+    // Execution, memory, program and range checker buses, plus merkle and compression buses for
+    // continuations.
+    assert_eq!(system_buses.len(), 6);
+    for bus in system_buses {
+        assert_eq!(bus_allocator.owner(bus), Some(SYSTEM_BUS_OWNER));
+    }
+    // Keccak256 allocates the bitwise operation lookup bus.
+    let last_bus = bus_allocator.num_buses() - 1;
+    assert!(bus_allocator
+        .owner(last_bus)
+        .unwrap()
+        .ends_with("Keccak256"));
+    assert!(complex.check_bus_allocation().is_ok());
+}
+
 #[test]
 fn test_vm_keccak() {
     let inputs = [
@@ -84,11 +84,13 @@ impl<F: PrimeField32> VmExtension<F> for Fp2Extension {
                 modulus: modulus.clone(),
                 num_limbs: 32,
                 limb_bits: 8,
+                ..Default::default()
             };
             let config48 = ExprBuilderConfig {
                 modulus: modulus.clone(),
                 num_limbs: 48,
                 limb_bits: 8,
+                ..Default::default()
             };
             let adapter_chip_32 = Rv32VecHeapAdapterChip::new(
                 execution_bus,
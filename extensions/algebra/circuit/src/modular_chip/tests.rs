@@ -4,7 +4,8 @@ use num_bigint_dig::BigUint;
 use num_traits::Zero;
 use openvm_algebra_transpiler::Rv32ModularArithmeticOpcode;
 use openvm_circuit::arch::{
-    instructions::UsizeOpcode, testing::VmChipTestBuilder, VmChipWrapper, BITWISE_OP_LOOKUP_BUS,
+    instructions::UsizeOpcode, testing::VmChipTestBuilder, VmChipWrapper, VmConfig,
+    VmInventoryError, BITWISE_OP_LOOKUP_BUS,
 };
 use openvm_circuit_primitives::{
     bigint::utils::{
@@ -15,7 +16,7 @@ use openvm_circuit_primitives::{
 use openvm_instructions::{instruction::Instruction, riscv::RV32_CELL_BITS, VmOpcode};
 use openvm_mod_circuit_builder::{
     test_utils::{biguint_to_limbs, generate_field_element},
-    ExprBuilderConfig,
+    ExprBuilderConfig, ReductionStrategy,
 };
 use openvm_pairing_guest::bls12_381::BLS12_381_MODULUS;
 use openvm_rv32_adapters::{
@@ -29,6 +30,7 @@ use rand::Rng;
 use super::{
     ModularAddSubCoreChip, ModularIsEqualChip, ModularIsEqualCoreChip, ModularMulDivCoreChip,
 };
+use crate::{ModularExtension, Rv32ModularConfig};
 
 const NUM_LIMBS: usize = 32;
 const LIMB_BITS: usize = 8;
@@ -38,6 +40,21 @@ type F = BabyBear;
 const ADD_LOCAL: usize = Rv32ModularArithmeticOpcode::ADD as usize;
 const MUL_LOCAL: usize = Rv32ModularArithmeticOpcode::MUL as usize;
 
+#[test]
+fn test_more_reduction_strategies_than_moduli() {
+    let config = Rv32ModularConfig {
+        modular: ModularExtension {
+            supported_modulus: vec![secp256k1_coord_prime()],
+            reduction_strategies: vec![ReductionStrategy::Auto, ReductionStrategy::Generic],
+        },
+        ..Rv32ModularConfig::new(vec![])
+    };
+    assert!(matches!(
+        VmConfig::<F>::create_chip_complex(&config),
+        Err(VmInventoryError::InvalidConfig { .. })
+    ));
+}
+
 #[test]
 fn test_coord_addsub() {
     let opcode_offset = 0;
@@ -58,6 +75,7 @@ fn test_addsub(opcode_offset: usize, modulus: BigUint) {
         modulus: modulus.clone(),
         num_limbs: NUM_LIMBS,
         limb_bits: LIMB_BITS,
+        ..Default::default()
     };
     let core = ModularAddSubCoreChip::new(
         config,
@@ -187,6 +205,7 @@ fn test_muldiv(opcode_offset: usize, modulus: BigUint) {
         modulus: modulus.clone(),
         num_limbs: NUM_LIMBS,
         limb_bits: LIMB_BITS,
+        ..Default::default()
     };
     let core = ModularMulDivCoreChip::new(
         config,
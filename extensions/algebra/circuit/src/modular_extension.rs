@@ -14,7 +14,7 @@ use openvm_circuit_primitives::bitwise_op_lookup::{
 };
 use openvm_circuit_primitives_derive::{Chip, ChipUsageGetter};
 use openvm_instructions::{UsizeOpcode, VmOpcode};
-use openvm_mod_circuit_builder::ExprBuilderConfig;
+use openvm_mod_circuit_builder::{ExprBuilderConfig, ReductionStrategy};
 use openvm_rv32_adapters::{Rv32IsEqualModAdapterChip, Rv32VecHeapAdapterChip};
 use openvm_stark_backend::p3_field::PrimeField32;
 use serde::{Deserialize, Serialize};
@@ -31,6 +31,12 @@ use crate::modular_chip::{
 pub struct ModularExtension {
     #[serde_as(as = "Vec<DisplayFromStr>")]
     pub supported_modulus: Vec<BigUint>,
+    /// The reduction strategy of the chips of each modulus, in the same order as
+    /// `supported_modulus`. Moduli without an entry use [ReductionStrategy::Auto]. Building the
+    /// extension fails if there are more strategies than moduli.
+    #[new(default)]
+    #[serde(default)]
+    pub reduction_strategies: Vec<ReductionStrategy>,
 }
 
 #[derive(ChipUsageGetter, Chip, InstructionExecutor, AnyEnum, From)]
@@ -60,6 +66,16 @@ impl<F: PrimeField32> VmExtension<F> for ModularExtension {
         &self,
         builder: &mut VmInventoryBuilder<F>,
     ) -> Result<VmInventory<Self::Executor, Self::Periphery>, VmInventoryError> {
+        if self.reduction_strategies.len() > self.supported_modulus.len() {
+            return Err(VmInventoryError::InvalidConfig {
+                extension: std::any::type_name::<Self>().to_string(),
+                reason: format!(
+                    "{} reduction strategies for {} moduli",
+                    self.reduction_strategies.len(),
+                    self.supported_modulus.len()
+                ),
+            });
+        }
         let mut inventory = VmInventory::new();
         let SystemPort {
             execution_bus,
@@ -91,16 +107,23 @@ impl<F: PrimeField32> VmExtension<F> for ModularExtension {
             let bytes = modulus.bits().div_ceil(8);
             let class_offset = Rv32ModularArithmeticOpcode::default_offset()
                 + i * Rv32ModularArithmeticOpcode::COUNT;
+            let reduction = self
+                .reduction_strategies
+                .get(i)
+                .copied()
+                .unwrap_or_default();
 
             let config32 = ExprBuilderConfig {
                 modulus: modulus.clone(),
                 num_limbs: 32,
                 limb_bits: 8,
+                reduction,
             };
             let config48 = ExprBuilderConfig {
                 modulus: modulus.clone(),
                 num_limbs: 48,
                 limb_bits: 8,
+                reduction,
             };
             let adapter_chip_32 = Rv32VecHeapAdapterChip::new(
                 execution_bus,
@@ -21,6 +21,12 @@ mod tests {
     #[test]
     fn test_moduli_setup() -> Result<()> {
         let elf = build_example_program_at_path(get_programs_dir!(), "moduli_setup")?;
+        let moduli = ["4002409555221667393417789825735904156556882819939007885332058136124031650490837864442687629129015664037894272559787", "1000000000000000003", "2305843009213693951"]
+            .map(|s| BigUint::from_str(s).unwrap());
+        // The moduli declared by `moduli_init!` are recorded in the ELF
+        assert_eq!(elf.supported_moduli, moduli);
+        let config = Rv32ModularConfig::new(elf.supported_moduli.clone());
+
         let openvm_exe = VmExe::from_elf(
             elf,
             Transpiler::<F>::default()
@@ -29,10 +35,6 @@ mod tests {
                 .with_extension(Rv32IoTranspilerExtension)
                 .with_extension(ModularTranspilerExtension),
         )?;
-
-        let moduli = ["4002409555221667393417789825735904156556882819939007885332058136124031650490837864442687629129015664037894272559787", "1000000000000000003", "2305843009213693951"]
-            .map(|s| BigUint::from_str(s).unwrap());
-        let config = Rv32ModularConfig::new(moduli.to_vec());
         air_test(config, openvm_exe);
         Ok(())
     }
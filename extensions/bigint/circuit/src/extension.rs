@@ -3,7 +3,8 @@ use std::sync::Arc;
 use derive_more::derive::From;
 use openvm_bigint_transpiler::{
     Rv32BaseAlu256Opcode, Rv32BranchEqual256Opcode, Rv32BranchLessThan256Opcode,
-    Rv32LessThan256Opcode, Rv32Mul256Opcode, Rv32Shift256Opcode,
+    Rv32DivRem256Opcode, Rv32LessThan256Opcode, Rv32Mul256Opcode, Rv32MulMod256Opcode,
+    Rv32Shift256Opcode,
 };
 use openvm_circuit::{
     arch::{
@@ -68,8 +69,9 @@ impl Default for Int256 {
     }
 }
 
+// Division needs carries of up to 2 * 32 bytes.
 fn default_range_tuple_checker_sizes() -> [u32; 2] {
-    [1 << 8, 32 * (1 << 8)]
+    [1 << 8, 2 * 32 * (1 << 8)]
 }
 
 #[derive(ChipUsageGetter, Chip, InstructionExecutor, From, AnyEnum)]
@@ -79,13 +81,15 @@ pub enum Int256Executor<F: PrimeField32> {
     BranchEqual256(Rv32BranchEqual256Chip<F>),
     BranchLessThan256(Rv32BranchLessThan256Chip<F>),
     Multiplication256(Rv32Multiplication256Chip<F>),
+    DivRem256(Rv32DivRem256Chip<F>),
+    MulMod256(Rv32MulMod256Chip<F>),
     Shift256(Rv32Shift256Chip<F>),
 }
 
 #[derive(From, ChipUsageGetter, Chip, AnyEnum)]
 pub enum Int256Periphery<F: PrimeField32> {
     BitwiseOperationLookup(Arc<BitwiseOperationLookupChip<8>>),
-    /// Only needed for multiplication extension
+    /// Only needed for multiplication, division and modular multiplication
     RangeTupleChecker(Arc<RangeTupleCheckerChip<2>>),
     Phantom(PhantomChip<F>),
 }
@@ -209,7 +213,10 @@ impl<F: PrimeField32> VmExtension<F> for Int256 {
                 memory_controller.clone(),
                 bitwise_lu_chip.clone(),
             ),
-            MultiplicationCoreChip::new(range_tuple_chip, Rv32Mul256Opcode::default_offset()),
+            MultiplicationCoreChip::new(
+                range_tuple_chip.clone(),
+                Rv32Mul256Opcode::default_offset(),
+            ),
             memory_controller.clone(),
         );
         inventory.add_executor(
@@ -217,6 +224,44 @@ impl<F: PrimeField32> VmExtension<F> for Int256 {
             Rv32Mul256Opcode::iter().map(VmOpcode::with_default_offset),
         )?;
 
+        let divrem_chip = Rv32DivRem256Chip::new(
+            Rv32HeapAdapterChip::new(
+                execution_bus,
+                program_bus,
+                memory_controller.clone(),
+                bitwise_lu_chip.clone(),
+            ),
+            DivRemCoreChip::new(
+                bitwise_lu_chip.clone(),
+                range_tuple_chip.clone(),
+                Rv32DivRem256Opcode::default_offset(),
+            ),
+            memory_controller.clone(),
+        );
+        inventory.add_executor(
+            divrem_chip,
+            Rv32DivRem256Opcode::iter().map(VmOpcode::with_default_offset),
+        )?;
+
+        let mul_mod_chip = Rv32MulMod256Chip::new(
+            Rv32VecHeapTwoReadsAdapterChip::new(
+                execution_bus,
+                program_bus,
+                memory_controller.clone(),
+                bitwise_lu_chip.clone(),
+            ),
+            MulModCoreChip::new(
+                bitwise_lu_chip.clone(),
+                range_tuple_chip,
+                Rv32MulMod256Opcode::default_offset(),
+            ),
+            memory_controller.clone(),
+        );
+        inventory.add_executor(
+            mul_mod_chip,
+            [Rv32MulMod256Opcode::MULMOD].map(VmOpcode::with_default_offset),
+        )?;
+
         let shift_chip = Rv32Shift256Chip::new(
             Rv32HeapAdapterChip::new(
                 execution_bus,
@@ -0,0 +1,424 @@
+use std::{
+    array,
+    borrow::{Borrow, BorrowMut},
+    sync::Arc,
+};
+
+use num_bigint::BigUint;
+use num_integer::Integer;
+use openvm_bigint_transpiler::Rv32MulMod256Opcode;
+use openvm_circuit::arch::{
+    AdapterAirContext, AdapterRuntimeContext, MinimalInstruction, Result, VmAdapterInterface,
+    VmCoreAir, VmCoreChip,
+};
+use openvm_circuit_primitives::{
+    bitwise_op_lookup::{BitwiseOperationLookupBus, BitwiseOperationLookupChip},
+    range_tuple::{RangeTupleCheckerBus, RangeTupleCheckerChip},
+    utils::not,
+};
+use openvm_circuit_primitives_derive::AlignedBorrow;
+use openvm_instructions::{instruction::Instruction, UsizeOpcode};
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    p3_air::{AirBuilder, BaseAir},
+    p3_field::{AbstractField, Field, PrimeField32},
+    rap::BaseAirWithPublicValues,
+};
+
+/// Columns for `a = b * c mod n`. The product `b * c` and the quotient `q` take twice as many
+/// limbs as the inputs.
+#[repr(C)]
+#[derive(AlignedBorrow)]
+pub struct MulModCoreCols<T, const NUM_LIMBS: usize, const LIMB_BITS: usize> {
+    pub a: [T; NUM_LIMBS],
+    pub b: [T; NUM_LIMBS],
+    pub c: [T; NUM_LIMBS],
+    pub n: [T; NUM_LIMBS],
+
+    // b * c = prod = q * n + a, with 0 <= a < n unless n is zero.
+    pub prod_lo: [T; NUM_LIMBS],
+    pub prod_hi: [T; NUM_LIMBS],
+    pub q_lo: [T; NUM_LIMBS],
+    pub q_hi: [T; NUM_LIMBS],
+
+    // The result is zero when n is zero.
+    pub zero_modulus: T,
+
+    // Auxiliary columns to constrain that a < n.
+    pub lt_marker: [T; NUM_LIMBS],
+    pub lt_diff: T,
+
+    pub is_valid: T,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct MulModCoreAir<const NUM_LIMBS: usize, const LIMB_BITS: usize> {
+    pub bitwise_lookup_bus: BitwiseOperationLookupBus,
+    pub range_tuple_bus: RangeTupleCheckerBus<2>,
+    offset: usize,
+}
+
+impl<F: Field, const NUM_LIMBS: usize, const LIMB_BITS: usize> BaseAir<F>
+    for MulModCoreAir<NUM_LIMBS, LIMB_BITS>
+{
+    fn width(&self) -> usize {
+        MulModCoreCols::<F, NUM_LIMBS, LIMB_BITS>::width()
+    }
+}
+impl<F: Field, const NUM_LIMBS: usize, const LIMB_BITS: usize> BaseAirWithPublicValues<F>
+    for MulModCoreAir<NUM_LIMBS, LIMB_BITS>
+{
+}
+
+impl<AB, I, const NUM_LIMBS: usize, const LIMB_BITS: usize> VmCoreAir<AB, I>
+    for MulModCoreAir<NUM_LIMBS, LIMB_BITS>
+where
+    AB: InteractionBuilder,
+    I: VmAdapterInterface<AB::Expr>,
+    I::Reads: From<([[AB::Expr; NUM_LIMBS]; 1], [[AB::Expr; NUM_LIMBS]; 2])>,
+    I::Writes: From<[[AB::Expr; NUM_LIMBS]; 1]>,
+    I::ProcessedInstruction: From<MinimalInstruction<AB::Expr>>,
+{
+    fn eval(
+        &self,
+        builder: &mut AB,
+        local_core: &[AB::Var],
+        _from_pc: AB::Var,
+    ) -> AdapterAirContext<AB::Expr, I> {
+        let cols: &MulModCoreCols<_, NUM_LIMBS, LIMB_BITS> = local_core.borrow();
+        builder.assert_bool(cols.is_valid);
+        builder.assert_bool(cols.zero_modulus);
+        builder.when(cols.zero_modulus).assert_one(cols.is_valid);
+
+        let a = &cols.a;
+        let b = &cols.b;
+        let c = &cols.c;
+        let n = &cols.n;
+        let prod: Vec<AB::Var> = cols.prod_lo.iter().chain(&cols.prod_hi).copied().collect();
+        let q: Vec<AB::Var> = cols.q_lo.iter().chain(&cols.q_hi).copied().collect();
+        let carry_divide = AB::F::from_canonical_u32(1 << LIMB_BITS).inverse();
+
+        // Constrain that prod = b * c and range check each limb of prod. The product of two
+        // NUM_LIMBS limb numbers fits in 2 * NUM_LIMBS limbs, so the last carry is zero.
+        let mut carry = AB::Expr::ZERO;
+        for i in 0..2 * NUM_LIMBS {
+            let expected_limb = (i.saturating_sub(NUM_LIMBS - 1)..NUM_LIMBS.min(i + 1))
+                .fold(carry, |acc, k| acc + (b[k] * c[i - k]));
+            carry = (expected_limb - prod[i]) * carry_divide;
+            self.range_tuple_bus
+                .send(vec![prod[i].into(), carry.clone()])
+                .eval(builder, cols.is_valid);
+        }
+        builder.assert_zero(carry);
+
+        // Constrain that q * n + a = prod when n is non-zero, and q * n + a = 0 otherwise, and
+        // range check each limb of q. All limbs of q * n past 2 * NUM_LIMBS must be zero, which
+        // holds exactly when each convolution term is zero since all limbs are in range.
+        let mut carry = AB::Expr::ZERO;
+        for i in 0..2 * NUM_LIMBS {
+            let expected_limb = (i.saturating_sub(NUM_LIMBS - 1)..=i)
+                .fold(carry, |acc, k| acc + (q[k] * n[i - k]))
+                + if i < NUM_LIMBS {
+                    a[i].into()
+                } else {
+                    AB::Expr::ZERO
+                };
+            carry = (expected_limb - prod[i] * not::<AB::Expr>(cols.zero_modulus)) * carry_divide;
+            self.range_tuple_bus
+                .send(vec![q[i].into(), carry.clone()])
+                .eval(builder, cols.is_valid);
+        }
+        builder.assert_zero(carry);
+        for i in 2 * NUM_LIMBS..3 * NUM_LIMBS - 1 {
+            builder.assert_zero(
+                ((i + 1 - NUM_LIMBS)..2 * NUM_LIMBS)
+                    .fold(AB::Expr::ZERO, |acc, k| acc + (q[k] * n[i - k])),
+            );
+        }
+
+        // Range check each limb of a, which is written to memory.
+        for i in 0..NUM_LIMBS / 2 {
+            self.bitwise_lookup_bus
+                .send_range(a[2 * i], a[2 * i + 1])
+                .eval(builder, cols.is_valid);
+        }
+
+        let mut when_zero_modulus = builder.when(cols.zero_modulus);
+        for i in 0..NUM_LIMBS {
+            when_zero_modulus.assert_zero(n[i]);
+            when_zero_modulus.assert_zero(a[i]);
+        }
+
+        // Constrain that a < n when n is non-zero by finding the most significant limb where
+        // they differ.
+        let marker = &cols.lt_marker;
+        let mut prefix_sum: AB::Expr = cols.zero_modulus.into();
+
+        for i in (0..NUM_LIMBS).rev() {
+            let diff = n[i] - a[i];
+            prefix_sum += marker[i].into();
+            builder.assert_bool(marker[i]);
+            builder.assert_zero(not::<AB::Expr>(prefix_sum.clone()) * diff.clone());
+            builder.when(marker[i]).assert_eq(cols.lt_diff, diff);
+        }
+
+        builder.when(cols.is_valid).assert_one(prefix_sum);
+        self.bitwise_lookup_bus
+            .send_range(cols.lt_diff - AB::Expr::ONE, AB::F::ZERO)
+            .eval(builder, cols.is_valid - cols.zero_modulus);
+
+        let expected_opcode =
+            AB::Expr::from_canonical_usize(Rv32MulMod256Opcode::MULMOD as usize + self.offset);
+
+        AdapterAirContext {
+            to_pc: None,
+            reads: (
+                [cols.b.map(Into::into)],
+                [cols.c.map(Into::into), cols.n.map(Into::into)],
+            )
+                .into(),
+            writes: [cols.a.map(Into::into)].into(),
+            instruction: MinimalInstruction {
+                is_valid: cols.is_valid.into(),
+                opcode: expected_opcode,
+            }
+            .into(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct MulModCoreChip<const NUM_LIMBS: usize, const LIMB_BITS: usize> {
+    pub air: MulModCoreAir<NUM_LIMBS, LIMB_BITS>,
+    pub bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<LIMB_BITS>>,
+    pub range_tuple_chip: Arc<RangeTupleCheckerChip<2>>,
+}
+
+impl<const NUM_LIMBS: usize, const LIMB_BITS: usize> MulModCoreChip<NUM_LIMBS, LIMB_BITS> {
+    pub fn new(
+        bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<LIMB_BITS>>,
+        range_tuple_chip: Arc<RangeTupleCheckerChip<2>>,
+        offset: usize,
+    ) -> Self {
+        // The RangeTupleChecker is used to range check (prod[i], carry[i]) and (q[i], carry[i])
+        // pairs where 0 <= i < 2 * NUM_LIMBS. Each carry is at most the sum of NUM_LIMBS
+        // products of two limbs plus a limb, shifted down by LIMB_BITS.
+        debug_assert!(
+            range_tuple_chip.sizes()[0] == 1 << LIMB_BITS,
+            "First element of RangeTupleChecker must have size {}",
+            1 << LIMB_BITS
+        );
+        debug_assert!(
+            range_tuple_chip.sizes()[1] >= (1 << LIMB_BITS) * NUM_LIMBS as u32,
+            "Second element of RangeTupleChecker must have size of at least {}",
+            (1 << LIMB_BITS) * NUM_LIMBS as u32
+        );
+
+        Self {
+            air: MulModCoreAir {
+                bitwise_lookup_bus: bitwise_lookup_chip.bus(),
+                range_tuple_bus: *range_tuple_chip.bus(),
+                offset,
+            },
+            bitwise_lookup_chip,
+            range_tuple_chip,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct MulModCoreRecord<T, const NUM_LIMBS: usize, const LIMB_BITS: usize> {
+    pub a: [T; NUM_LIMBS],
+    pub b: [T; NUM_LIMBS],
+    pub c: [T; NUM_LIMBS],
+    pub n: [T; NUM_LIMBS],
+    pub prod: [[T; NUM_LIMBS]; 2],
+    pub q: [[T; NUM_LIMBS]; 2],
+    pub zero_modulus: bool,
+    pub lt_diff_val: T,
+    pub lt_diff_idx: usize,
+}
+
+impl<F: PrimeField32, I: VmAdapterInterface<F>, const NUM_LIMBS: usize, const LIMB_BITS: usize>
+    VmCoreChip<F, I> for MulModCoreChip<NUM_LIMBS, LIMB_BITS>
+where
+    I::Reads: Into<([[F; NUM_LIMBS]; 1], [[F; NUM_LIMBS]; 2])>,
+    I::Writes: From<[[F; NUM_LIMBS]; 1]>,
+{
+    type Record = MulModCoreRecord<F, NUM_LIMBS, LIMB_BITS>;
+    type Air = MulModCoreAir<NUM_LIMBS, LIMB_BITS>;
+
+    #[allow(clippy::type_complexity)]
+    fn execute_instruction(
+        &self,
+        instruction: &Instruction<F>,
+        _from_pc: u32,
+        reads: I::Reads,
+    ) -> Result<(AdapterRuntimeContext<F, I>, Self::Record)> {
+        let Instruction { opcode, .. } = instruction;
+        assert_eq!(
+            Rv32MulMod256Opcode::from_usize(opcode.local_opcode_idx(self.air.offset)),
+            Rv32MulMod256Opcode::MULMOD
+        );
+
+        let ([b], [c, n]) = reads.into();
+        let b_u32 = b.map(|x| x.as_canonical_u32());
+        let c_u32 = c.map(|x| x.as_canonical_u32());
+        let n_u32 = n.map(|x| x.as_canonical_u32());
+        let zero_modulus = n_u32.iter().all(|&x| x == 0);
+        let (a, prod, q) = run_mul_mod::<NUM_LIMBS, LIMB_BITS>(&b_u32, &c_u32, &n_u32);
+
+        let prod_flat = prod.concat();
+        let q_flat = q.concat();
+        let prod_carries =
+            run_mul_carries::<NUM_LIMBS, LIMB_BITS>(&b_u32, &c_u32, &[0; NUM_LIMBS], &prod_flat);
+        let q_target = if zero_modulus {
+            vec![0; 2 * NUM_LIMBS]
+        } else {
+            prod_flat.clone()
+        };
+        let q_carries = run_mul_carries::<NUM_LIMBS, LIMB_BITS>(&q_flat, &n_u32, &a, &q_target);
+        for i in 0..2 * NUM_LIMBS {
+            self.range_tuple_chip
+                .add_count(&[prod_flat[i], prod_carries[i]]);
+            self.range_tuple_chip.add_count(&[q_flat[i], q_carries[i]]);
+        }
+        for i in 0..NUM_LIMBS / 2 {
+            self.bitwise_lookup_chip
+                .request_range(a[2 * i], a[2 * i + 1]);
+        }
+
+        let (lt_diff_idx, lt_diff_val) = if zero_modulus {
+            (NUM_LIMBS, 0)
+        } else {
+            let idx = (0..NUM_LIMBS).rev().find(|&i| a[i] != n_u32[i]).unwrap();
+            let val = n_u32[idx] - a[idx];
+            self.bitwise_lookup_chip.request_range(val - 1, 0);
+            (idx, val)
+        };
+
+        let a = a.map(F::from_canonical_u32);
+        let output = AdapterRuntimeContext::without_pc([a]);
+        let record = MulModCoreRecord {
+            a,
+            b,
+            c,
+            n,
+            prod: prod.map(|limbs| limbs.map(F::from_canonical_u32)),
+            q: q.map(|limbs| limbs.map(F::from_canonical_u32)),
+            zero_modulus,
+            lt_diff_val: F::from_canonical_u32(lt_diff_val),
+            lt_diff_idx,
+        };
+
+        Ok((output, record))
+    }
+
+    fn get_opcode_name(&self, opcode: usize) -> String {
+        format!(
+            "{:?}",
+            Rv32MulMod256Opcode::from_usize(opcode - self.air.offset)
+        )
+    }
+
+    fn generate_trace_row(&self, row_slice: &mut [F], record: Self::Record) {
+        let row_slice: &mut MulModCoreCols<_, NUM_LIMBS, LIMB_BITS> = row_slice.borrow_mut();
+        row_slice.a = record.a;
+        row_slice.b = record.b;
+        row_slice.c = record.c;
+        row_slice.n = record.n;
+        [row_slice.prod_lo, row_slice.prod_hi] = record.prod;
+        [row_slice.q_lo, row_slice.q_hi] = record.q;
+        row_slice.zero_modulus = F::from_bool(record.zero_modulus);
+        row_slice.lt_marker = array::from_fn(|i| F::from_bool(i == record.lt_diff_idx));
+        row_slice.lt_diff = record.lt_diff_val;
+        row_slice.is_valid = F::ONE;
+    }
+
+    fn air(&self) -> &Self::Air {
+        &self.air
+    }
+}
+
+// Returns (x * y mod n, x * y, x * y / n), where the product and the quotient are split into
+// their low and high NUM_LIMBS limbs. The remainder and the quotient are zero when n is zero.
+#[allow(clippy::type_complexity)]
+pub(crate) fn run_mul_mod<const NUM_LIMBS: usize, const LIMB_BITS: usize>(
+    x: &[u32; NUM_LIMBS],
+    y: &[u32; NUM_LIMBS],
+    n: &[u32; NUM_LIMBS],
+) -> (
+    [u32; NUM_LIMBS],
+    [[u32; NUM_LIMBS]; 2],
+    [[u32; NUM_LIMBS]; 2],
+) {
+    let prod =
+        limbs_to_biguint::<NUM_LIMBS, LIMB_BITS>(x) * limbs_to_biguint::<NUM_LIMBS, LIMB_BITS>(y);
+    let n = limbs_to_biguint::<NUM_LIMBS, LIMB_BITS>(n);
+    let (q, r) = if n == BigUint::from(0u32) {
+        (BigUint::from(0u32), BigUint::from(0u32))
+    } else {
+        prod.div_rem(&n)
+    };
+    (
+        biguint_to_limbs::<NUM_LIMBS, LIMB_BITS>(&r),
+        biguint_to_double_limbs::<NUM_LIMBS, LIMB_BITS>(&prod),
+        biguint_to_double_limbs::<NUM_LIMBS, LIMB_BITS>(&q),
+    )
+}
+
+// Returns the carries of x * y + z - target, one per limb of target. Limbs of x * y + z past
+// the limbs of target are ignored.
+fn run_mul_carries<const NUM_LIMBS: usize, const LIMB_BITS: usize>(
+    x: &[u32],
+    y: &[u32; NUM_LIMBS],
+    z: &[u32; NUM_LIMBS],
+    target: &[u32],
+) -> Vec<u32> {
+    let mut carry = vec![0; target.len()];
+    for i in 0..target.len() {
+        let mut val = if i > 0 { carry[i - 1] } else { 0 };
+        for k in i.saturating_sub(NUM_LIMBS - 1)..x.len().min(i + 1) {
+            val += x[k] * y[i - k];
+        }
+        if i < NUM_LIMBS {
+            val += z[i];
+        }
+        carry[i] = (val - target[i]) >> LIMB_BITS;
+    }
+    carry
+}
+
+fn limbs_to_biguint<const NUM_LIMBS: usize, const LIMB_BITS: usize>(
+    x: &[u32; NUM_LIMBS],
+) -> BigUint {
+    let base = BigUint::from(1u32 << LIMB_BITS);
+    x.iter()
+        .rev()
+        .fold(BigUint::from(0u32), |acc, &limb| acc * &base + limb)
+}
+
+fn biguint_to_limbs<const NUM_LIMBS: usize, const LIMB_BITS: usize>(
+    x: &BigUint,
+) -> [u32; NUM_LIMBS] {
+    let mut res = [0; NUM_LIMBS];
+    let mut x = x.clone();
+    let base = BigUint::from(1u32 << LIMB_BITS);
+    for limb in res.iter_mut() {
+        let (quot, rem) = x.div_rem(&base);
+        *limb = rem.iter_u32_digits().next().unwrap_or(0);
+        x = quot;
+    }
+    debug_assert_eq!(x, BigUint::from(0u32));
+    res
+}
+
+fn biguint_to_double_limbs<const NUM_LIMBS: usize, const LIMB_BITS: usize>(
+    x: &BigUint,
+) -> [[u32; NUM_LIMBS]; 2] {
+    let shift = NUM_LIMBS * LIMB_BITS;
+    [
+        biguint_to_limbs::<NUM_LIMBS, LIMB_BITS>(&(x % (BigUint::from(1u32) << shift))),
+        biguint_to_limbs::<NUM_LIMBS, LIMB_BITS>(&(x >> shift)),
+    ]
+}
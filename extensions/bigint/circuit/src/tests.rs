@@ -1,5 +1,6 @@
 use std::sync::Arc;
 
+use openvm_bigint_transpiler::Rv32MulMod256Opcode;
 use openvm_circuit::{
     arch::{
         testing::VmChipTestBuilder, InstructionExecutor, BITWISE_OP_LOOKUP_BUS,
@@ -14,22 +15,24 @@ use openvm_circuit_primitives::{
 use openvm_instructions::{program::PC_BITS, riscv::RV32_CELL_BITS, UsizeOpcode};
 use openvm_rv32_adapters::{
     rv32_heap_branch_default, rv32_write_heap_default, Rv32HeapAdapterChip,
-    Rv32HeapBranchAdapterChip,
+    Rv32HeapBranchAdapterChip, Rv32VecHeapTwoReadsAdapterChip,
 };
 use openvm_rv32im_circuit::{
-    adapters::{INT256_NUM_LIMBS, RV_B_TYPE_IMM_BITS},
-    BaseAluCoreChip, BranchEqualCoreChip, BranchLessThanCoreChip, LessThanCoreChip,
+    adapters::{compose, INT256_NUM_LIMBS, RV_B_TYPE_IMM_BITS},
+    BaseAluCoreChip, BranchEqualCoreChip, BranchLessThanCoreChip, DivRemCoreChip, LessThanCoreChip,
     MultiplicationCoreChip, ShiftCoreChip,
 };
 use openvm_rv32im_transpiler::{
-    BaseAluOpcode, BranchEqualOpcode, BranchLessThanOpcode, LessThanOpcode, MulOpcode, ShiftOpcode,
+    BaseAluOpcode, BranchEqualOpcode, BranchLessThanOpcode, DivRemOpcode, LessThanOpcode,
+    MulOpcode, ShiftOpcode,
 };
 use openvm_stark_backend::p3_field::{AbstractField, PrimeField32};
 use openvm_stark_sdk::{p3_baby_bear::BabyBear, utils::create_seeded_rng};
 use rand::Rng;
 
 use super::{
-    Rv32BaseAlu256Chip, Rv32BranchEqual256Chip, Rv32BranchLessThan256Chip, Rv32LessThan256Chip,
+    run_mul_mod, MulModCoreChip, Rv32BaseAlu256Chip, Rv32BranchEqual256Chip,
+    Rv32BranchLessThan256Chip, Rv32DivRem256Chip, Rv32LessThan256Chip, Rv32MulMod256Chip,
     Rv32Multiplication256Chip, Rv32Shift256Chip,
 };
 
@@ -212,6 +215,131 @@ fn mul_256_rand_test() {
     run_mul_256_rand_test(24);
 }
 
+fn run_divrem_256_rand_test(opcode: DivRemOpcode, num_ops: usize) {
+    let range_tuple_bus = RangeTupleCheckerBus::new(
+        RANGE_TUPLE_CHECKER_BUS,
+        [
+            1 << RV32_CELL_BITS,
+            (2 * INT256_NUM_LIMBS * (1 << RV32_CELL_BITS)) as u32,
+        ],
+    );
+    let range_tuple_checker = Arc::new(RangeTupleCheckerChip::new(range_tuple_bus));
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = Arc::new(BitwiseOperationLookupChip::<RV32_CELL_BITS>::new(
+        bitwise_bus,
+    ));
+
+    let mut tester = VmChipTestBuilder::default();
+    let mut chip = Rv32DivRem256Chip::<F>::new(
+        Rv32HeapAdapterChip::<F, 2, INT256_NUM_LIMBS, INT256_NUM_LIMBS>::new(
+            tester.execution_bus(),
+            tester.program_bus(),
+            tester.memory_controller(),
+            bitwise_chip.clone(),
+        ),
+        DivRemCoreChip::new(bitwise_chip.clone(), range_tuple_checker.clone(), 0),
+        tester.memory_controller(),
+    );
+
+    run_int_256_rand_execute(opcode as usize, num_ops, &mut chip, &mut tester, None);
+    let tester = tester
+        .build()
+        .load(chip)
+        .load(range_tuple_checker)
+        .load(bitwise_chip)
+        .finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
+#[test]
+fn divrem_256_div_rand_test() {
+    run_divrem_256_rand_test(DivRemOpcode::DIV, 12);
+}
+
+#[test]
+fn divrem_256_divu_rand_test() {
+    run_divrem_256_rand_test(DivRemOpcode::DIVU, 12);
+}
+
+#[test]
+fn divrem_256_rem_rand_test() {
+    run_divrem_256_rand_test(DivRemOpcode::REM, 12);
+}
+
+#[test]
+fn divrem_256_remu_rand_test() {
+    run_divrem_256_rand_test(DivRemOpcode::REMU, 12);
+}
+
+fn run_mul_mod_256_rand_test(num_ops: usize) {
+    let range_tuple_bus = RangeTupleCheckerBus::new(
+        RANGE_TUPLE_CHECKER_BUS,
+        [
+            1 << RV32_CELL_BITS,
+            (INT256_NUM_LIMBS * (1 << RV32_CELL_BITS)) as u32,
+        ],
+    );
+    let range_tuple_checker = Arc::new(RangeTupleCheckerChip::new(range_tuple_bus));
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = Arc::new(BitwiseOperationLookupChip::<RV32_CELL_BITS>::new(
+        bitwise_bus,
+    ));
+
+    let mut tester = VmChipTestBuilder::default();
+    let mut chip = Rv32MulMod256Chip::<F>::new(
+        Rv32VecHeapTwoReadsAdapterChip::<F, 1, 2, 1, INT256_NUM_LIMBS, INT256_NUM_LIMBS>::new(
+            tester.execution_bus(),
+            tester.program_bus(),
+            tester.memory_controller(),
+            bitwise_chip.clone(),
+        ),
+        MulModCoreChip::new(bitwise_chip.clone(), range_tuple_checker.clone(), 0),
+        tester.memory_controller(),
+    );
+
+    let mut rng = create_seeded_rng();
+    let mut small_modulus = [0; INT256_NUM_LIMBS];
+    small_modulus[0] = rng.gen_range(1..(1 << RV32_CELL_BITS));
+    let mut moduli = vec![[0; INT256_NUM_LIMBS], small_modulus];
+    for _ in 0..num_ops {
+        moduli.push(generate_long_number::<INT256_NUM_LIMBS, RV32_CELL_BITS>(
+            &mut rng,
+        ));
+    }
+
+    for n in moduli {
+        let b = generate_long_number::<INT256_NUM_LIMBS, RV32_CELL_BITS>(&mut rng);
+        let c = generate_long_number::<INT256_NUM_LIMBS, RV32_CELL_BITS>(&mut rng);
+        let instruction = rv32_write_heap_default(
+            &mut tester,
+            vec![b.map(F::from_canonical_u32)],
+            vec![c.map(F::from_canonical_u32), n.map(F::from_canonical_u32)],
+            Rv32MulMod256Opcode::MULMOD as usize,
+        );
+        tester.execute(&mut chip, instruction.clone());
+
+        let (a, _, _) = run_mul_mod::<INT256_NUM_LIMBS, RV32_CELL_BITS>(&b, &c, &n);
+        let rd = compose(tester.read::<4>(1, instruction.a.as_canonical_u32() as usize));
+        assert_eq!(
+            tester.read::<INT256_NUM_LIMBS>(2, rd as usize),
+            a.map(F::from_canonical_u32)
+        );
+    }
+
+    let tester = tester
+        .build()
+        .load(chip)
+        .load(range_tuple_checker)
+        .load(bitwise_chip)
+        .finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
+#[test]
+fn mul_mod_256_rand_test() {
+    run_mul_mod_256_rand_test(12);
+}
+
 fn run_shift_256_rand_test(opcode: ShiftOpcode, num_ops: usize) {
     let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
     let bitwise_chip = Arc::new(BitwiseOperationLookupChip::<RV32_CELL_BITS>::new(
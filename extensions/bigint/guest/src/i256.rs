@@ -1,13 +1,13 @@
 use core::{
     cmp::{Eq, Ord, Ordering, PartialEq, PartialOrd},
     ops::{
-        Add, AddAssign, BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Mul,
-        MulAssign, Shl, ShlAssign, Shr, ShrAssign, Sub, SubAssign,
+        Add, AddAssign, BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Div,
+        DivAssign, Mul, MulAssign, Rem, RemAssign, Shl, ShlAssign, Shr, ShrAssign, Sub, SubAssign,
     },
 };
 
 #[cfg(not(target_os = "zkvm"))]
-use {super::bigint_to_limbs, num_bigint_dig::BigInt};
+use {super::bigint_to_limbs, num_bigint_dig::BigInt, num_traits::Zero};
 #[cfg(target_os = "zkvm")]
 use {
     super::{Int256Funct7, BEQ256_FUNCT3, INT256_FUNCT3, OPCODE},
@@ -125,6 +125,32 @@ impl_bin_op!(
     |lhs: &I256, rhs: &I256| -> I256 {I256::from_bigint(&(lhs.as_bigint() * rhs.as_bigint()))}
 );
 
+impl_bin_op!(
+    I256,
+    Div,
+    DivAssign,
+    div,
+    div_assign,
+    OPCODE,
+    INT256_FUNCT3,
+    Int256Funct7::Div as u8,
+    /=,
+    |lhs: &I256, rhs: &I256| -> I256 {if rhs.as_bigint().is_zero() {I256::from_i8(-1)} else {I256::from_bigint(&(lhs.as_bigint() / rhs.as_bigint()))}}
+);
+
+impl_bin_op!(
+    I256,
+    Rem,
+    RemAssign,
+    rem,
+    rem_assign,
+    OPCODE,
+    INT256_FUNCT3,
+    Int256Funct7::Rem as u8,
+    %=,
+    |lhs: &I256, rhs: &I256| -> I256 {if rhs.as_bigint().is_zero() {lhs.clone()} else {I256::from_bigint(&(lhs.as_bigint() % rhs.as_bigint()))}}
+);
+
 impl_bin_op!(
     I256,
     BitXor,
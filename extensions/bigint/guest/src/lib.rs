@@ -31,6 +31,13 @@ pub enum Int256Funct7 {
     Slt,
     Sltu,
     Mul,
+    Div,
+    Divu,
+    Rem,
+    Remu,
+    /// `rd = rs1 * rs2[0] mod rs2[1]`, where `rs2` points to the multiplier followed by the
+    /// modulus.
+    MulMod,
 }
 
 #[cfg(all(feature = "export-intrinsics", target_os = "zkvm"))]
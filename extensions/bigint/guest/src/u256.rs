@@ -1,8 +1,8 @@
 use core::{
     cmp::{Eq, Ord, Ordering, PartialEq, PartialOrd},
     ops::{
-        Add, AddAssign, BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Mul,
-        MulAssign, Shl, ShlAssign, Shr, ShrAssign, Sub, SubAssign,
+        Add, AddAssign, BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Div,
+        DivAssign, Mul, MulAssign, Rem, RemAssign, Shl, ShlAssign, Shr, ShrAssign, Sub, SubAssign,
     },
 };
 
@@ -13,7 +13,11 @@ use {
     openvm_platform::custom_insn_r,
 };
 #[cfg(not(target_os = "zkvm"))]
-use {num_bigint_dig::BigUint, num_traits::One, openvm::utils::biguint_to_limbs};
+use {
+    num_bigint_dig::BigUint,
+    num_traits::{One, Zero},
+    openvm::utils::biguint_to_limbs,
+};
 
 use crate::impl_bin_op;
 
@@ -75,6 +79,33 @@ impl U256 {
     pub fn as_le_bytes(&self) -> &[u8; 32] {
         &self.limbs
     }
+
+    /// Returns `self * rhs mod modulus` without overflowing, or zero if `modulus` is zero.
+    pub fn mulmod(&self, rhs: &Self, modulus: &Self) -> Self {
+        #[cfg(target_os = "zkvm")]
+        {
+            // The instruction reads the multiplier and the modulus consecutively from rs2.
+            let operands = [rhs.clone(), modulus.clone()];
+            let mut uninit: MaybeUninit<Self> = MaybeUninit::uninit();
+            custom_insn_r!(
+                OPCODE,
+                INT256_FUNCT3,
+                Int256Funct7::MulMod as u8,
+                uninit.as_mut_ptr(),
+                self as *const Self,
+                operands.as_ptr()
+            );
+            unsafe { uninit.assume_init() }
+        }
+        #[cfg(not(target_os = "zkvm"))]
+        {
+            let modulus = modulus.as_biguint();
+            if modulus.is_zero() {
+                return Self::ZERO;
+            }
+            Self::from_biguint(&(self.as_biguint() * rhs.as_biguint() % modulus))
+        }
+    }
 }
 
 impl_bin_op!(
@@ -116,6 +147,32 @@ impl_bin_op!(
     |lhs: &U256, rhs: &U256| -> U256 {U256::from_biguint(&(lhs.as_biguint() * rhs.as_biguint()))}
 );
 
+impl_bin_op!(
+    U256,
+    Div,
+    DivAssign,
+    div,
+    div_assign,
+    OPCODE,
+    INT256_FUNCT3,
+    Int256Funct7::Divu as u8,
+    /=,
+    |lhs: &U256, rhs: &U256| -> U256 {if rhs.as_biguint().is_zero() {U256::MAX} else {U256::from_biguint(&(lhs.as_biguint() / rhs.as_biguint()))}}
+);
+
+impl_bin_op!(
+    U256,
+    Rem,
+    RemAssign,
+    rem,
+    rem_assign,
+    OPCODE,
+    INT256_FUNCT3,
+    Int256Funct7::Remu as u8,
+    %=,
+    |lhs: &U256, rhs: &U256| -> U256 {if rhs.as_biguint().is_zero() {lhs.clone()} else {U256::from_biguint(&(lhs.as_biguint() % rhs.as_biguint()))}}
+);
+
 impl_bin_op!(
     U256,
     BitXor,
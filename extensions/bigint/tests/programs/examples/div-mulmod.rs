@@ -0,0 +1,28 @@
+#![cfg_attr(not(feature = "std"), no_main)]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+openvm::entry!(main);
+use openvm_bigint_guest::{I256, U256};
+
+pub fn main() {
+    let a = U256::from_u64(1_000_000_007);
+    let b = U256::from_u32(97);
+    let q = &a / &b;
+    let r = &a % &b;
+    assert_eq!(&(&q * &b) + &r, a);
+    assert!(r < b);
+
+    // Division by zero follows the RISC-V conventions.
+    assert_eq!(&a / &U256::ZERO, U256::MAX);
+    assert_eq!(&a % &U256::ZERO, a);
+
+    let x = I256::from_i32(-100);
+    let y = I256::from_i32(7);
+    assert_eq!(&x / &y, I256::from_i32(-14));
+    assert_eq!(&x % &y, I256::from_i32(-2));
+
+    let m = U256::from_u32(1_000_003);
+    let p = a.mulmod(&b, &m);
+    assert_eq!(p, &(&a * &b) % &m);
+    assert_eq!(a.mulmod(&b, &U256::ZERO), U256::ZERO);
+}
@@ -45,4 +45,20 @@ mod tests {
         air_test(config, openvm_exe);
         Ok(())
     }
+
+    #[test]
+    fn test_div_mulmod() -> Result<()> {
+        let elf = build_example_program_at_path(get_programs_dir!(), "div-mulmod")?;
+        let openvm_exe = VmExe::from_elf(
+            elf,
+            Transpiler::<F>::default()
+                .with_extension(Rv32ITranspilerExtension)
+                .with_extension(Rv32MTranspilerExtension)
+                .with_extension(Rv32IoTranspilerExtension)
+                .with_extension(Int256TranspilerExtension),
+        )?;
+        let config = Int256Rv32Config::default();
+        air_test(config, openvm_exe);
+        Ok(())
+    }
 }
@@ -5,12 +5,13 @@ use openvm_instructions::{
 };
 use openvm_instructions_derive::UsizeOpcode;
 use openvm_rv32im_transpiler::{
-    BaseAluOpcode, BranchEqualOpcode, BranchLessThanOpcode, LessThanOpcode, MulOpcode, ShiftOpcode,
+    BaseAluOpcode, BranchEqualOpcode, BranchLessThanOpcode, DivRemOpcode, LessThanOpcode,
+    MulOpcode, ShiftOpcode,
 };
 use openvm_stark_backend::p3_field::PrimeField32;
 use openvm_transpiler::{util::from_r_type, TranspilerExtension};
 use rrs_lib::instruction_formats::{BType, RType};
-use strum::IntoEnumIterator;
+use strum::{EnumCount, EnumIter, FromRepr, IntoEnumIterator};
 
 // =================================================================================================
 // Intrinsics: 256-bit Integers
@@ -76,6 +77,28 @@ impl Rv32Mul256Opcode {
     }
 }
 
+#[derive(Copy, Clone, Debug, UsizeOpcode)]
+#[opcode_offset = 0x454]
+pub struct Rv32DivRem256Opcode(pub DivRemOpcode);
+
+impl Rv32DivRem256Opcode {
+    pub fn iter() -> impl Iterator<Item = Self> {
+        DivRemOpcode::iter().map(Self)
+    }
+}
+
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, EnumCount, EnumIter, FromRepr, UsizeOpcode,
+)]
+#[opcode_offset = 0x458]
+#[repr(usize)]
+#[allow(non_camel_case_types)]
+pub enum Rv32MulMod256Opcode {
+    /// `rd = rs1 * rs2[0] mod rs2[1]`, where `rs2` points to two consecutive 256-bit integers.
+    /// The result is zero when the modulus is zero.
+    MULMOD,
+}
+
 #[derive(Default)]
 pub struct Int256TranspilerExtension;
 
@@ -132,6 +155,21 @@ impl<F: PrimeField32> TranspilerExtension<F> for Int256TranspilerExtension {
                     Some(Int256Funct7::Mul) => {
                         MulOpcode::MUL as usize + Rv32Mul256Opcode::default_offset()
                     }
+                    Some(Int256Funct7::Div) => {
+                        DivRemOpcode::DIV as usize + Rv32DivRem256Opcode::default_offset()
+                    }
+                    Some(Int256Funct7::Divu) => {
+                        DivRemOpcode::DIVU as usize + Rv32DivRem256Opcode::default_offset()
+                    }
+                    Some(Int256Funct7::Rem) => {
+                        DivRemOpcode::REM as usize + Rv32DivRem256Opcode::default_offset()
+                    }
+                    Some(Int256Funct7::Remu) => {
+                        DivRemOpcode::REMU as usize + Rv32DivRem256Opcode::default_offset()
+                    }
+                    Some(Int256Funct7::MulMod) => {
+                        Rv32MulMod256Opcode::MULMOD as usize + Rv32MulMod256Opcode::default_offset()
+                    }
                     _ => unimplemented!(),
                 };
                 Some(from_r_type(global_opcode, 2, &dec_insn))
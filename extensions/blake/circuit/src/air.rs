@@ -0,0 +1,504 @@
+use std::{array::from_fn, borrow::Borrow, iter::once};
+
+use openvm_circuit::{
+    arch::{ExecutionBridge, ExecutionState},
+    system::memory::{offline_checker::MemoryBridge, MemoryAddress},
+};
+use openvm_circuit_primitives::{bitwise_op_lookup::BitwiseOperationLookupBus, utils::not};
+use openvm_instructions::riscv::{
+    RV32_CELL_BITS, RV32_MEMORY_AS, RV32_REGISTER_AS, RV32_REGISTER_NUM_LIMBS,
+};
+use openvm_rv32im_circuit::adapters::abstract_compose;
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    p3_air::{Air, AirBuilder, BaseAir},
+    p3_field::AbstractField,
+    p3_matrix::Matrix,
+    rap::{BaseAirWithPublicValues, PartitionedBaseAir},
+};
+
+use super::{
+    blake_timestamp_change,
+    columns::BlakeVmCols,
+    utils::{g_message_indices, next_slot},
+    BlakeVariant, BLAKE_BLOCK_WORDS, BLAKE_PARAM_WORDS, BLAKE_REGISTER_READS, BLAKE_STATE_WORDS,
+    BLAKE_WORD_SIZE,
+};
+
+/// The AIR is generic over the number of bits `W`, `u16` limbs `L`, bytes `B` and 4-byte memory
+/// accesses `A` of a word, which must match the word size of `variant`.
+#[derive(Clone, Copy, Debug, derive_new::new)]
+pub struct BlakeVmAir<const W: usize, const L: usize, const B: usize, const A: usize> {
+    pub execution_bridge: ExecutionBridge,
+    pub memory_bridge: MemoryBridge,
+    /// Bus to send 8-bit range checks to.
+    pub bitwise_lookup_bus: BitwiseOperationLookupBus,
+    /// Maximum number of bits allowed for an address pointer
+    pub ptr_max_bits: usize,
+    pub variant: BlakeVariant,
+    pub(super) offset: usize,
+}
+
+impl<F, const W: usize, const L: usize, const B: usize, const A: usize> BaseAirWithPublicValues<F>
+    for BlakeVmAir<W, L, B, A>
+{
+}
+impl<F, const W: usize, const L: usize, const B: usize, const A: usize> PartitionedBaseAir<F>
+    for BlakeVmAir<W, L, B, A>
+{
+}
+impl<F, const W: usize, const L: usize, const B: usize, const A: usize> BaseAir<F>
+    for BlakeVmAir<W, L, B, A>
+{
+    fn width(&self) -> usize {
+        BlakeVmCols::<F, W, L, B, A>::width()
+    }
+}
+
+impl<AB: InteractionBuilder, const W: usize, const L: usize, const B: usize, const A: usize> Air<AB>
+    for BlakeVmAir<W, L, B, A>
+{
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let (local, next) = (main.row_slice(0), main.row_slice(1));
+        let local: &BlakeVmCols<AB::Var, W, L, B, A> = (*local).borrow();
+        let next: &BlakeVmCols<AB::Var, W, L, B, A> = (*next).borrow();
+
+        self.eval_flags(builder, local, next);
+        self.eval_round(builder, local, next);
+        self.eval_digest(builder, local);
+        self.eval_instruction(builder, local);
+    }
+}
+
+impl<const W: usize, const L: usize, const B: usize, const A: usize> BlakeVmAir<W, L, B, A> {
+    /// Constrain the rows of each block to be the half-rounds in order followed by the digest
+    /// row. Rows without flags are dummy rows and may only be followed by the first half-round
+    /// of a block.
+    #[inline]
+    pub fn eval_flags<AB: AirBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &BlakeVmCols<AB::Var, W, L, B, A>,
+        next: &BlakeVmCols<AB::Var, W, L, B, A>,
+    ) {
+        let flags = &local.flags;
+        for &flag in flags
+            .half_round_flags
+            .iter()
+            .chain(once(&flags.is_digest_row))
+        {
+            builder.assert_bool(flag);
+        }
+        let num_half_rounds = 2 * self.variant.rounds();
+        for &flag in &flags.half_round_flags[num_half_rounds..] {
+            builder.assert_zero(flag);
+        }
+        let is_enabled: AB::Expr = flags.is_enabled();
+        builder.assert_bool(is_enabled.clone());
+        let is_round: AB::Expr = flags.is_round();
+
+        let mut transition_builder = builder.when_transition();
+        for h in 0..num_half_rounds {
+            let next_flag = if h + 1 < num_half_rounds {
+                next.flags.half_round_flags[h + 1]
+            } else {
+                next.flags.is_digest_row
+            };
+            transition_builder
+                .when(flags.half_round_flags[h])
+                .assert_one(next_flag);
+        }
+        transition_builder
+            .when(not::<AB::Expr>(is_round.clone()))
+            .assert_eq(
+                next.flags.is_enabled::<AB::Expr>(),
+                next.flags.half_round_flags[0],
+            );
+
+        builder
+            .when_first_row()
+            .assert_eq(is_enabled, flags.half_round_flags[0]);
+        // Every block is complete
+        builder.when_last_row().assert_zero(is_round);
+    }
+
+    /// Constrain the four `G` functions of a half-round, and the state of the next row to be
+    /// their outputs.
+    ///
+    /// The XORs and rotations are computed on bits, and the additions are done on `u16` limbs
+    /// with carries. Adding three words carries at most 2 out of each limb, which is the sum
+    /// of two booleans, so no range checks are needed.
+    #[inline]
+    pub fn eval_round<AB: InteractionBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &BlakeVmCols<AB::Var, W, L, B, A>,
+        next: &BlakeVmCols<AB::Var, W, L, B, A>,
+    ) {
+        let round = &local.round;
+        let flags = &local.flags;
+        let is_round: AB::Expr = flags.is_round();
+        let is_column_round: AB::Expr = flags.is_column_round();
+        let is_diagonal_round: AB::Expr = flags.is_diagonal_round();
+        let num_half_rounds = 2 * self.variant.rounds();
+        let [r1, r2, r3, r4] = self.variant.rotations();
+
+        for g in &round.g {
+            for &bit in [g.a1, g.d1, g.c1, g.b1, g.a2, g.c2]
+                .iter()
+                .flatten()
+                .chain(g.carry_a1.iter().flatten())
+                .chain(g.carry_a2.iter().flatten())
+                .chain(&g.carry_c1)
+                .chain(&g.carry_c2)
+            {
+                builder.assert_bool(bit);
+            }
+        }
+        for &bit in round.state.iter().flatten().flatten() {
+            builder.assert_bool(bit);
+        }
+
+        let rotr_xor = |x: &[AB::Var; W], y: &[AB::Var; W], n: usize| -> [AB::Expr; W] {
+            from_fn(|j| xor::<AB::Expr>(x[(j + n) % W], y[(j + n) % W]))
+        };
+        let two_16 = AB::Expr::from_canonical_u32(1 << 16);
+        let message_limb = |word: usize, k: usize| -> AB::Expr {
+            let bytes = &local.instruction.message[word];
+            bytes[2 * k] + bytes[2 * k + 1] * AB::F::from_canonical_u32(1 << 8)
+        };
+
+        for (i, g) in round.g.iter().enumerate() {
+            let mut round_builder = builder.when(is_round.clone());
+            let [a, b, c, d] = from_fn(|role| &round.state[role][i]);
+            // The message words `x` and `y` selected by the half-round flags. Degree 2.
+            let [x, y] = from_fn(|n| -> [AB::Expr; L] {
+                from_fn(|k| {
+                    flags.half_round_flags[..num_half_rounds]
+                        .iter()
+                        .enumerate()
+                        .fold(AB::Expr::ZERO, |acc, (h, &flag)| {
+                            acc + message_limb(g_message_indices(self.variant, h, i)[n], k) * flag
+                        })
+                })
+            });
+            let d2 = rotr_xor(&g.d1, &g.a2, r3);
+            let b2 = rotr_xor(&g.b1, &g.c2, r4);
+
+            for (bit, expected) in g.d1.iter().zip(rotr_xor(d, &g.a1, r1)) {
+                round_builder.assert_eq(*bit, expected);
+            }
+            for (bit, expected) in g.b1.iter().zip(rotr_xor(b, &g.c1, r2)) {
+                round_builder.assert_eq(*bit, expected);
+            }
+
+            for k in 0..L {
+                let carry_in3 = |carries: &[[AB::Var; 2]; L]| -> AB::Expr {
+                    if k == 0 {
+                        AB::Expr::ZERO
+                    } else {
+                        carries[k - 1][0] + carries[k - 1][1]
+                    }
+                };
+                let carry_in2 = |carries: &[AB::Var; L]| -> AB::Expr {
+                    if k == 0 {
+                        AB::Expr::ZERO
+                    } else {
+                        carries[k - 1].into()
+                    }
+                };
+                round_builder.assert_eq(
+                    compose_limb::<AB::Expr>(a, k)
+                        + compose_limb::<AB::Expr>(b, k)
+                        + x[k].clone()
+                        + carry_in3(&g.carry_a1),
+                    compose_limb::<AB::Expr>(&g.a1, k)
+                        + (g.carry_a1[k][0] + g.carry_a1[k][1]) * two_16.clone(),
+                );
+                round_builder.assert_eq(
+                    compose_limb::<AB::Expr>(c, k)
+                        + compose_limb::<AB::Expr>(&g.d1, k)
+                        + carry_in2(&g.carry_c1),
+                    compose_limb::<AB::Expr>(&g.c1, k) + g.carry_c1[k] * two_16.clone(),
+                );
+                round_builder.assert_eq(
+                    compose_limb::<AB::Expr>(&g.a1, k)
+                        + compose_limb::<AB::Expr>(&g.b1, k)
+                        + y[k].clone()
+                        + carry_in3(&g.carry_a2),
+                    compose_limb::<AB::Expr>(&g.a2, k)
+                        + (g.carry_a2[k][0] + g.carry_a2[k][1]) * two_16.clone(),
+                );
+                round_builder.assert_eq(
+                    compose_limb::<AB::Expr>(&g.c1, k)
+                        + compose_limb::<AB::Expr>(&d2, k)
+                        + carry_in2(&g.carry_c2),
+                    compose_limb::<AB::Expr>(&g.c2, k) + g.carry_c2[k] * two_16.clone(),
+                );
+            }
+
+            // The outputs move to the slots of the next half-round, or of the digest row
+            let outputs: [[AB::Expr; W]; 4] = [g.a2.map(Into::into), b2, g.c2.map(Into::into), d2];
+            for (role, output) in outputs.into_iter().enumerate() {
+                for (is_half_round, h) in [(&is_column_round, 0), (&is_diagonal_round, 1)] {
+                    let next_word = &next.round.state[role][next_slot(h, i, role)];
+                    for (&next_bit, bit) in next_word.iter().zip(output.iter()) {
+                        builder
+                            .when(is_half_round.clone())
+                            .assert_eq(next_bit, bit.clone());
+                    }
+                }
+            }
+        }
+        local
+            .instruction
+            .assert_eq(&mut builder.when(is_round), next.instruction);
+
+        // The state of the first half-round is the chaining value, the first half of the IV,
+        // and the parameters masked by `params_mask`
+        let mut first_round_builder = builder.when(flags.half_round_flags[0]);
+        let iv = self.variant.iv();
+        for i in 0..4 {
+            for j in 0..W {
+                first_round_builder
+                    .assert_eq(round.state[0][i][j], local.instruction.prev_state[i][j]);
+                first_round_builder
+                    .assert_eq(round.state[1][i][j], local.instruction.prev_state[4 + i][j]);
+                first_round_builder.assert_eq(
+                    round.state[2][i][j],
+                    AB::F::from_canonical_u64((iv[i] >> j) & 1),
+                );
+            }
+        }
+    }
+
+    /// Constrain the output words on the digest row.
+    #[inline]
+    pub fn eval_digest<AB: InteractionBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &BlakeVmCols<AB::Var, W, L, B, A>,
+    ) {
+        let output = &local.digest.output;
+        for &bit in output.iter().flatten() {
+            builder.assert_bool(bit);
+        }
+
+        let v = |j: usize| &local.round.state[j / 4][j % 4];
+        let prev_state = &local.instruction.prev_state;
+        let mut digest_builder = builder.when(local.flags.is_digest_row);
+        for i in 0..BLAKE_STATE_WORDS {
+            let second = match self.variant {
+                BlakeVariant::Blake2b => &output[i],
+                BlakeVariant::Blake3 => v(i + 8),
+            };
+            for j in 0..W {
+                digest_builder.assert_eq(output[i][j], xor::<AB::Expr>(v(i)[j], v(i + 8)[j]));
+                digest_builder.assert_eq(
+                    output[i + 8][j],
+                    xor::<AB::Expr>(second[j], prev_state[i][j]),
+                );
+            }
+        }
+    }
+
+    /// Receive the instruction, read the pointers, the chaining value, the block and the
+    /// parameters on the first half-round, and write the output on the digest row.
+    pub fn eval_instruction<AB: InteractionBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &BlakeVmCols<AB::Var, W, L, B, A>,
+    ) {
+        let instruction = local.instruction;
+        let is_first_round = local.flags.half_round_flags[0];
+        let is_digest_row = local.flags.is_digest_row;
+
+        self.execution_bridge
+            .execute_and_increment_pc(
+                AB::Expr::from_canonical_usize(self.variant.local_opcode() as usize + self.offset),
+                [
+                    instruction.rd_ptr.into(),
+                    instruction.rs1_ptr.into(),
+                    instruction.rs2_ptr.into(),
+                    AB::Expr::from_canonical_u32(RV32_REGISTER_AS),
+                    AB::Expr::from_canonical_u32(RV32_MEMORY_AS),
+                ],
+                ExecutionState::new(instruction.pc, instruction.start_timestamp),
+                AB::Expr::from_canonical_usize(blake_timestamp_change(A)),
+            )
+            .eval(builder, is_first_round);
+
+        for (i, (reg_ptr, value)) in [
+            (instruction.rd_ptr, instruction.state_ptr),
+            (instruction.rs1_ptr, instruction.block_ptr),
+            (instruction.rs2_ptr, instruction.params_ptr),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            self.memory_bridge
+                .read(
+                    MemoryAddress::new(AB::F::from_canonical_u32(RV32_REGISTER_AS), reg_ptr),
+                    value,
+                    instruction.start_timestamp + AB::F::from_canonical_usize(i),
+                    &local.mem_oc.register_aux[i],
+                )
+                .eval(builder, is_first_round);
+        }
+        // Range check that the pointers are less than 2^ptr_max_bits
+        let limb_shift = AB::F::from_canonical_usize(
+            1 << (RV32_CELL_BITS * RV32_REGISTER_NUM_LIMBS - self.ptr_max_bits),
+        );
+        self.bitwise_lookup_bus
+            .send_range(
+                instruction.state_ptr[RV32_REGISTER_NUM_LIMBS - 1] * limb_shift,
+                instruction.block_ptr[RV32_REGISTER_NUM_LIMBS - 1] * limb_shift,
+            )
+            .eval(builder, is_first_round);
+        self.bitwise_lookup_bus
+            .send_range(
+                instruction.params_ptr[RV32_REGISTER_NUM_LIMBS - 1] * limb_shift,
+                AB::Expr::ZERO,
+            )
+            .eval(builder, is_first_round);
+        for pair in instruction
+            .message
+            .iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .chunks_exact(2)
+        {
+            self.bitwise_lookup_bus
+                .send_range(*pair[0], *pair[1])
+                .eval(builder, is_first_round);
+        }
+
+        let state_ptr = abstract_compose::<AB::Expr, _>(instruction.state_ptr);
+        let block_ptr = abstract_compose::<AB::Expr, _>(instruction.block_ptr);
+        let params_ptr = abstract_compose::<AB::Expr, _>(instruction.params_ptr);
+        let state_read_timestamp =
+            instruction.start_timestamp + AB::F::from_canonical_usize(BLAKE_REGISTER_READS);
+        let block_read_timestamp =
+            state_read_timestamp.clone() + AB::F::from_canonical_usize(BLAKE_STATE_WORDS * A);
+        let params_read_timestamp =
+            block_read_timestamp.clone() + AB::F::from_canonical_usize(BLAKE_BLOCK_WORDS * A);
+        let state_write_timestamp =
+            params_read_timestamp.clone() + AB::F::from_canonical_usize(BLAKE_PARAM_WORDS * A);
+
+        // The parameters are the last row of the initial state with the mask removed
+        let params_mask = self.variant.params_mask();
+        let params: Vec<[AB::Expr; W]> = (0..BLAKE_PARAM_WORDS)
+            .map(|i| {
+                from_fn(|j| {
+                    let bit = local.round.state[3][i][j];
+                    if (params_mask[i] >> j) & 1 == 1 {
+                        not::<AB::Expr>(bit)
+                    } else {
+                        bit.into()
+                    }
+                })
+            })
+            .collect();
+        let reads = instruction
+            .prev_state
+            .iter()
+            .map(|word| {
+                (0..B)
+                    .map(|m| compose_byte::<AB::Expr>(word, m))
+                    .collect::<Vec<_>>()
+            })
+            .zip(&local.mem_oc.state_reads)
+            .map(|(bytes, aux)| (state_ptr.clone(), state_read_timestamp.clone(), bytes, aux))
+            .enumerate()
+            .chain(
+                instruction
+                    .message
+                    .iter()
+                    .map(|word| word.iter().map(|&byte| byte.into()).collect::<Vec<_>>())
+                    .zip(&local.mem_oc.block_reads)
+                    .map(|(bytes, aux)| {
+                        (block_ptr.clone(), block_read_timestamp.clone(), bytes, aux)
+                    })
+                    .enumerate(),
+            )
+            .chain(
+                params
+                    .iter()
+                    .map(|word| {
+                        (0..B)
+                            .map(|m| compose_byte::<AB::Expr>(word, m))
+                            .collect::<Vec<_>>()
+                    })
+                    .zip(&local.mem_oc.params_reads)
+                    .map(|(bytes, aux)| {
+                        (
+                            params_ptr.clone(),
+                            params_read_timestamp.clone(),
+                            bytes,
+                            aux,
+                        )
+                    })
+                    .enumerate(),
+            );
+        for (w, (ptr, timestamp, bytes, aux)) in reads {
+            for (a, (access, aux)) in bytes.chunks_exact(BLAKE_WORD_SIZE).zip(aux).enumerate() {
+                self.memory_bridge
+                    .read(
+                        MemoryAddress::new(
+                            AB::F::from_canonical_u32(RV32_MEMORY_AS),
+                            ptr.clone() + AB::F::from_canonical_usize(w * B + a * BLAKE_WORD_SIZE),
+                        ),
+                        from_fn::<_, BLAKE_WORD_SIZE, _>(|j| access[j].clone()),
+                        timestamp.clone() + AB::F::from_canonical_usize(w * A + a),
+                        aux,
+                    )
+                    .eval(builder, is_first_round);
+            }
+        }
+
+        let output_bytes: Vec<AB::Expr> = local.digest.output[self.variant.output_words()]
+            .iter()
+            .flat_map(|word| (0..B).map(|m| compose_byte::<AB::Expr>(word, m)))
+            .collect();
+        for (i, (access, aux)) in output_bytes
+            .chunks_exact(BLAKE_WORD_SIZE)
+            .zip(&local.mem_oc.state_writes)
+            .enumerate()
+        {
+            self.memory_bridge
+                .write(
+                    MemoryAddress::new(
+                        AB::F::from_canonical_u32(RV32_MEMORY_AS),
+                        state_ptr.clone() + AB::F::from_canonical_usize(i * BLAKE_WORD_SIZE),
+                    ),
+                    from_fn::<_, BLAKE_WORD_SIZE, _>(|j| access[j].clone()),
+                    state_write_timestamp.clone() + AB::F::from_canonical_usize(i),
+                    aux,
+                )
+                .eval(builder, is_digest_row);
+        }
+    }
+}
+
+/// The `k`-th `u16` limb of a word given as bits, least significant bit first.
+fn compose_limb<E: AbstractField>(bits: &[impl Into<E> + Clone], k: usize) -> E {
+    compose_bits(&bits[16 * k..16 * (k + 1)])
+}
+
+/// The `m`-th byte of a word given as bits, least significant bit first.
+fn compose_byte<E: AbstractField>(bits: &[impl Into<E> + Clone], m: usize) -> E {
+    compose_bits(&bits[8 * m..8 * (m + 1)])
+}
+
+fn compose_bits<E: AbstractField>(bits: &[impl Into<E> + Clone]) -> E {
+    bits.iter()
+        .rev()
+        .fold(E::ZERO, |acc, bit| acc.double() + bit.clone().into())
+}
+
+/// `x ^ y` for booleans. Degree 2.
+fn xor<E: AbstractField>(x: impl Into<E>, y: impl Into<E>) -> E {
+    let (x, y) = (x.into(), y.into());
+    x.clone() + y.clone() - (x * y).double()
+}
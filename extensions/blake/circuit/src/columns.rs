@@ -0,0 +1,168 @@
+use openvm_circuit::system::memory::offline_checker::{MemoryReadAuxCols, MemoryWriteAuxCols};
+use openvm_circuit_primitives::utils::assert_array_eq;
+use openvm_circuit_primitives_derive::AlignedBorrow;
+use openvm_instructions::riscv::RV32_REGISTER_NUM_LIMBS;
+use openvm_stark_backend::{p3_air::AirBuilder, p3_field::AbstractField};
+
+use super::{
+    BLAKE_BLOCK_WORDS, BLAKE_MAX_HALF_ROUNDS, BLAKE_OUTPUT_ACCESSES, BLAKE_PARAM_WORDS,
+    BLAKE_REGISTER_READS, BLAKE_STATE_WORDS, BLAKE_WORD_SIZE,
+};
+
+/// Each block takes [rows_per_block](super::BlakeVariant::rows_per_block) rows: one row per
+/// half-round, alternating between the columns and the diagonals of the state, followed by a
+/// digest row. The columns are generic over the number of bits `W`, `u16` limbs `L`, bytes `B`
+/// and 4-byte memory accesses `A` of a word.
+#[repr(C)]
+#[derive(Debug, AlignedBorrow)]
+pub struct BlakeVmCols<T, const W: usize, const L: usize, const B: usize, const A: usize> {
+    pub flags: BlakeFlagCols<T>,
+    /// Columns for instruction interface and register access
+    pub instruction: BlakeInstructionCols<T, W, B>,
+    pub round: BlakeRoundCols<T, W, L>,
+    pub digest: BlakeDigestCols<T, W>,
+    /// Auxiliary columns for offline memory checking
+    pub mem_oc: BlakeMemoryCols<T, A>,
+}
+
+/// At most one flag is set on each row. No flag is set on dummy rows.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, AlignedBorrow)]
+pub struct BlakeFlagCols<T> {
+    /// `half_round_flags[h]` is set on the row of half-round `h`. Only the first
+    /// `2 * rounds` flags are used.
+    pub half_round_flags: [T; BLAKE_MAX_HALF_ROUNDS],
+    pub is_digest_row: T,
+}
+
+/// Columns for instruction parsing. They are the same on all rows of a block.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, AlignedBorrow)]
+pub struct BlakeInstructionCols<T, const W: usize, const B: usize> {
+    /// Program counter
+    pub pc: T,
+    /// The timestamp of the first register read
+    pub start_timestamp: T,
+    /// Pointer to address space 1 `rd` register
+    pub rd_ptr: T,
+    /// Pointer to address space 1 `rs1` register
+    pub rs1_ptr: T,
+    /// Pointer to address space 1 `rs2` register
+    pub rs2_ptr: T,
+    /// `state_ptr <- [rd_ptr:4]_1`
+    pub state_ptr: [T; RV32_REGISTER_NUM_LIMBS],
+    /// `block_ptr <- [rs1_ptr:4]_1`
+    pub block_ptr: [T; RV32_REGISTER_NUM_LIMBS],
+    /// `params_ptr <- [rs2_ptr:4]_1`
+    pub params_ptr: [T; RV32_REGISTER_NUM_LIMBS],
+    /// Bits of the chaining value before the compression
+    pub prev_state: [[T; W]; BLAKE_STATE_WORDS],
+    /// Little-endian bytes of the message words. Range checked to 8 bits.
+    pub message: [[T; B]; BLAKE_BLOCK_WORDS],
+}
+
+/// Bits are little-endian, i.e., least significant bit first.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, AlignedBorrow)]
+pub struct BlakeRoundCols<T, const W: usize, const L: usize> {
+    /// Bits of the state before this half-round, where `state[role][i]` is the input `role`
+    /// of the `i`-th `G` function. See [BlakeState](super::utils::BlakeState).
+    pub state: [[[T; W]; 4]; 4],
+    pub g: [BlakeGCols<T, W, L>; 4],
+}
+
+/// One `G` function:
+/// ```text
+/// a1 = a + b + x     d1 = (d ^ a1) >>> r1    c1 = c + d1     b1 = (b ^ c1) >>> r2
+/// a2 = a1 + b1 + y   d2 = (d1 ^ a2) >>> r3   c2 = c1 + d2    b2 = (b1 ^ c2) >>> r4
+/// ```
+/// The outputs `d2` and `b2` are not stored here but in the state of the next row.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, AlignedBorrow)]
+pub struct BlakeGCols<T, const W: usize, const L: usize> {
+    pub a1: [T; W],
+    pub d1: [T; W],
+    pub c1: [T; W],
+    pub b1: [T; W],
+    pub a2: [T; W],
+    pub c2: [T; W],
+    /// Carries of the `u16` limb additions of three words, each as a sum of two booleans
+    pub carry_a1: [[T; 2]; L],
+    pub carry_a2: [[T; 2]; L],
+    /// Boolean carries of the `u16` limb additions of two words
+    pub carry_c1: [T; L],
+    pub carry_c2: [T; L],
+}
+
+/// Only used on the digest row.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, AlignedBorrow)]
+pub struct BlakeDigestCols<T, const W: usize> {
+    /// Bits of the 16 output words
+    pub output: [[T; W]; 16],
+}
+
+#[repr(C)]
+#[derive(Clone, Debug, AlignedBorrow)]
+pub struct BlakeMemoryCols<T, const A: usize> {
+    /// The memory columns other than the writes are only used on the first half-round
+    pub register_aux: [MemoryReadAuxCols<T, RV32_REGISTER_NUM_LIMBS>; BLAKE_REGISTER_READS],
+    pub state_reads: [[MemoryReadAuxCols<T, BLAKE_WORD_SIZE>; A]; BLAKE_STATE_WORDS],
+    pub block_reads: [[MemoryReadAuxCols<T, BLAKE_WORD_SIZE>; A]; BLAKE_BLOCK_WORDS],
+    pub params_reads: [[MemoryReadAuxCols<T, BLAKE_WORD_SIZE>; A]; BLAKE_PARAM_WORDS],
+    /// Only used on the digest row
+    pub state_writes: [MemoryWriteAuxCols<T, BLAKE_WORD_SIZE>; BLAKE_OUTPUT_ACCESSES],
+}
+
+impl<T: Copy, const W: usize, const B: usize> BlakeInstructionCols<T, W, B> {
+    pub fn assert_eq<AB: AirBuilder>(&self, builder: &mut AB, other: Self)
+    where
+        T: Into<AB::Expr>,
+    {
+        builder.assert_eq(self.pc, other.pc);
+        builder.assert_eq(self.start_timestamp, other.start_timestamp);
+        builder.assert_eq(self.rd_ptr, other.rd_ptr);
+        builder.assert_eq(self.rs1_ptr, other.rs1_ptr);
+        builder.assert_eq(self.rs2_ptr, other.rs2_ptr);
+        assert_array_eq(builder, self.state_ptr, other.state_ptr);
+        assert_array_eq(builder, self.block_ptr, other.block_ptr);
+        assert_array_eq(builder, self.params_ptr, other.params_ptr);
+        for (word, other_word) in self.prev_state.into_iter().zip(other.prev_state) {
+            assert_array_eq(builder, word, other_word);
+        }
+        for (word, other_word) in self.message.into_iter().zip(other.message) {
+            assert_array_eq(builder, word, other_word);
+        }
+    }
+}
+
+impl<T: Copy> BlakeFlagCols<T> {
+    /// Whether this row is a half-round row. Degree 1.
+    pub fn is_round<E: AbstractField + From<T>>(&self) -> E {
+        self.half_round_flags
+            .iter()
+            .fold(E::ZERO, |acc, &flag| acc + flag.into())
+    }
+
+    /// Whether this row is a half-round on the columns of the state. Degree 1.
+    pub fn is_column_round<E: AbstractField + From<T>>(&self) -> E {
+        self.half_round_flags
+            .iter()
+            .step_by(2)
+            .fold(E::ZERO, |acc, &flag| acc + flag.into())
+    }
+
+    /// Whether this row is a half-round on the diagonals of the state. Degree 1.
+    pub fn is_diagonal_round<E: AbstractField + From<T>>(&self) -> E {
+        self.half_round_flags
+            .iter()
+            .skip(1)
+            .step_by(2)
+            .fold(E::ZERO, |acc, &flag| acc + flag.into())
+    }
+
+    /// Whether this row belongs to a block. Degree 1.
+    pub fn is_enabled<E: AbstractField + From<T>>(&self) -> E {
+        self.is_round::<E>() + self.is_digest_row.into()
+    }
+}
@@ -0,0 +1,126 @@
+use std::sync::Arc;
+
+use derive_more::derive::From;
+use openvm_blake_transpiler::Rv32BlakeOpcode;
+use openvm_circuit::{
+    arch::{
+        SystemConfig, SystemExecutor, SystemPeriphery, SystemPort, VmChipComplex, VmConfig,
+        VmExtension, VmInventory, VmInventoryBuilder, VmInventoryError,
+    },
+    system::phantom::PhantomChip,
+};
+use openvm_circuit_derive::{AnyEnum, InstructionExecutor, VmConfig};
+use openvm_circuit_primitives::bitwise_op_lookup::{
+    BitwiseOperationLookupBus, BitwiseOperationLookupChip,
+};
+use openvm_circuit_primitives_derive::{Chip, ChipUsageGetter};
+use openvm_instructions::*;
+use openvm_rv32im_circuit::{
+    Rv32I, Rv32IExecutor, Rv32IPeriphery, Rv32Io, Rv32IoExecutor, Rv32IoPeriphery, Rv32M,
+    Rv32MExecutor, Rv32MPeriphery,
+};
+use openvm_stark_backend::p3_field::PrimeField32;
+use serde::{Deserialize, Serialize};
+
+use crate::*;
+
+#[derive(Clone, Debug, VmConfig, derive_new::new, Serialize, Deserialize)]
+pub struct BlakeRv32Config {
+    #[system]
+    pub system: SystemConfig,
+    #[extension]
+    pub rv32i: Rv32I,
+    #[extension]
+    pub rv32m: Rv32M,
+    #[extension]
+    pub io: Rv32Io,
+    #[extension]
+    pub blake: Blake,
+}
+
+impl Default for BlakeRv32Config {
+    fn default() -> Self {
+        Self {
+            system: SystemConfig::default().with_continuations(),
+            rv32i: Rv32I,
+            rv32m: Rv32M::default(),
+            io: Rv32Io,
+            blake: Blake,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub struct Blake;
+
+#[derive(ChipUsageGetter, Chip, InstructionExecutor, From, AnyEnum)]
+pub enum BlakeExecutor<F: PrimeField32> {
+    Blake2b(Blake2bVmChip<F>),
+    Blake3(Blake3VmChip<F>),
+}
+
+#[derive(From, ChipUsageGetter, Chip, AnyEnum)]
+pub enum BlakePeriphery<F: PrimeField32> {
+    BitwiseOperationLookup(Arc<BitwiseOperationLookupChip<8>>),
+    Phantom(PhantomChip<F>),
+}
+
+impl<F: PrimeField32> VmExtension<F> for Blake {
+    type Executor = BlakeExecutor<F>;
+    type Periphery = BlakePeriphery<F>;
+
+    fn build(
+        &self,
+        builder: &mut VmInventoryBuilder<F>,
+    ) -> Result<VmInventory<Self::Executor, Self::Periphery>, VmInventoryError> {
+        let mut inventory = VmInventory::new();
+        let SystemPort {
+            execution_bus,
+            program_bus,
+            memory_controller,
+        } = builder.system_port();
+        let bitwise_lu_chip = if let Some(chip) = builder
+            .find_chip::<Arc<BitwiseOperationLookupChip<8>>>()
+            .first()
+        {
+            Arc::clone(chip)
+        } else {
+            let bitwise_lu_bus = BitwiseOperationLookupBus::new(builder.new_bus_idx());
+            let chip = Arc::new(BitwiseOperationLookupChip::new(bitwise_lu_bus));
+            inventory.add_periphery_chip(chip.clone());
+            chip
+        };
+
+        let blake2b_chip = Blake2bVmChip::new(
+            execution_bus,
+            program_bus,
+            memory_controller.clone(),
+            bitwise_lu_chip.clone(),
+            BlakeVariant::Blake2b,
+            Rv32BlakeOpcode::default_offset(),
+        );
+        inventory.add_executor(
+            blake2b_chip,
+            [VmOpcode::with_default_offset(
+                Rv32BlakeOpcode::BLAKE2B_COMPRESS,
+            )],
+        )?;
+
+        let blake3_chip = Blake3VmChip::new(
+            execution_bus,
+            program_bus,
+            memory_controller,
+            bitwise_lu_chip,
+            BlakeVariant::Blake3,
+            Rv32BlakeOpcode::default_offset(),
+        );
+        inventory.add_executor(
+            blake3_chip,
+            [VmOpcode::with_default_offset(
+                Rv32BlakeOpcode::BLAKE3_COMPRESS,
+            )],
+        )?;
+
+        Ok(inventory)
+    }
+}
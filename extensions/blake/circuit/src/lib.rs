@@ -0,0 +1,326 @@
+//! Blake2b and Blake3 compression of one message block into a state in VM memory. Both
+//! compression functions run the same `G` function on the columns and diagonals of a 4x4 state,
+//! so they share one AIR which is generic over the word size, with the rotations, the message
+//! schedule and the output layout chosen by [BlakeVariant].
+use std::{array::from_fn, ops::Range, sync::Arc};
+
+use openvm_blake_guest::{BLAKE2B_IV, BLAKE2B_SIGMA, BLAKE3_IV, BLAKE3_MSG_PERMUTATION};
+use openvm_blake_transpiler::Rv32BlakeOpcode;
+use openvm_circuit::{
+    arch::{ExecutionBridge, ExecutionBus, ExecutionError, ExecutionState, InstructionExecutor},
+    system::{
+        memory::{MemoryControllerRef, MemoryReadRecord, MemoryWriteRecord},
+        program::ProgramBus,
+    },
+};
+use openvm_circuit_primitives::bitwise_op_lookup::BitwiseOperationLookupChip;
+use openvm_instructions::{
+    instruction::Instruction, program::DEFAULT_PC_STEP, riscv::RV32_REGISTER_NUM_LIMBS, UsizeOpcode,
+};
+use openvm_rv32im_circuit::adapters::read_rv32_register;
+use openvm_stark_backend::p3_field::PrimeField32;
+
+pub mod air;
+pub mod columns;
+pub mod trace;
+pub mod utils;
+
+mod extension;
+pub use extension::*;
+
+#[cfg(test)]
+mod tests;
+
+pub use air::BlakeVmAir;
+use utils::blake_compress;
+
+// ==== Constants for register/memory adapter ====
+/// Register reads to get the state, block and parameter pointers
+const BLAKE_REGISTER_READS: usize = 3;
+/// Number of cells to read/write in a single memory access
+const BLAKE_WORD_SIZE: usize = 4;
+/// Memory accesses to write the output, which is 64 bytes for both variants
+const BLAKE_OUTPUT_ACCESSES: usize = 16;
+
+// ==== Do not change these constants! ====
+/// Number of words of the chaining value read from the state.
+pub const BLAKE_STATE_WORDS: usize = 8;
+/// Number of words in a message block.
+pub const BLAKE_BLOCK_WORDS: usize = 16;
+/// Number of parameter words: the counter, and the finalization flags or block length and
+/// domain flags.
+pub const BLAKE_PARAM_WORDS: usize = 4;
+/// Maximum number of half-rounds of a compression, which is for Blake2b.
+pub const BLAKE_MAX_HALF_ROUNDS: usize = 24;
+
+/// Blake2b with 64-bit words: 4 `u16` limbs, 8 bytes and 2 memory accesses per word.
+pub type Blake2bVmChip<F> = BlakeVmChip<F, 64, 4, 8, 2>;
+/// Blake3 with 32-bit words: 2 `u16` limbs, 4 bytes and 1 memory access per word.
+pub type Blake3VmChip<F> = BlakeVmChip<F, 32, 2, 4, 1>;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BlakeVariant {
+    Blake2b,
+    Blake3,
+}
+
+impl BlakeVariant {
+    pub fn local_opcode(&self) -> Rv32BlakeOpcode {
+        match self {
+            BlakeVariant::Blake2b => Rv32BlakeOpcode::BLAKE2B_COMPRESS,
+            BlakeVariant::Blake3 => Rv32BlakeOpcode::BLAKE3_COMPRESS,
+        }
+    }
+
+    pub fn word_bits(&self) -> usize {
+        match self {
+            BlakeVariant::Blake2b => 64,
+            BlakeVariant::Blake3 => 32,
+        }
+    }
+
+    pub fn rounds(&self) -> usize {
+        match self {
+            BlakeVariant::Blake2b => 12,
+            BlakeVariant::Blake3 => 7,
+        }
+    }
+
+    /// Number of trace rows per block: one per half-round and a digest row.
+    pub fn rows_per_block(&self) -> usize {
+        2 * self.rounds() + 1
+    }
+
+    /// The right rotations of the `G` function.
+    pub fn rotations(&self) -> [usize; 4] {
+        match self {
+            BlakeVariant::Blake2b => [32, 24, 16, 63],
+            BlakeVariant::Blake3 => [16, 12, 8, 7],
+        }
+    }
+
+    pub fn iv(&self) -> [u64; 8] {
+        match self {
+            BlakeVariant::Blake2b => BLAKE2B_IV,
+            BlakeVariant::Blake3 => BLAKE3_IV.map(|word| word as u64),
+        }
+    }
+
+    /// The constants XOR-ed with the parameters to get `v[12..16]`.
+    pub fn params_mask(&self) -> [u64; BLAKE_PARAM_WORDS] {
+        match self {
+            BlakeVariant::Blake2b => from_fn(|i| BLAKE2B_IV[BLAKE_PARAM_WORDS + i]),
+            BlakeVariant::Blake3 => [0; BLAKE_PARAM_WORDS],
+        }
+    }
+
+    /// The message word indices used by round `r`, in the order they are consumed.
+    pub fn message_schedule(&self, r: usize) -> [usize; BLAKE_BLOCK_WORDS] {
+        match self {
+            BlakeVariant::Blake2b => BLAKE2B_SIGMA[r % BLAKE2B_SIGMA.len()],
+            BlakeVariant::Blake3 => (0..r).fold(from_fn(|i| i), |schedule, _| {
+                BLAKE3_MSG_PERMUTATION.map(|i| schedule[i])
+            }),
+        }
+    }
+
+    /// The output words written to the state: the new chaining value for Blake2b and the full
+    /// 16-word output for Blake3.
+    pub fn output_words(&self) -> Range<usize> {
+        match self {
+            BlakeVariant::Blake2b => 8..16,
+            BlakeVariant::Blake3 => 0..16,
+        }
+    }
+}
+
+/// The chip is generic over the number of bits `W`, `u16` limbs `L`, bytes `B` and 4-byte
+/// memory accesses `A` of a word. Use [Blake2bVmChip] or [Blake3VmChip].
+#[derive(Debug)]
+pub struct BlakeVmChip<
+    F: PrimeField32,
+    const W: usize,
+    const L: usize,
+    const B: usize,
+    const A: usize,
+> {
+    pub air: BlakeVmAir<W, L, B, A>,
+    /// IO and memory data necessary for each opcode call
+    pub records: Vec<BlakeRecord<F, A>>,
+    pub memory_controller: MemoryControllerRef<F>,
+    pub bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<8>>,
+}
+
+#[derive(Clone, Debug)]
+pub struct BlakeRecord<F, const A: usize> {
+    pub from_state: ExecutionState<u32>,
+    pub state_ptr_read: MemoryReadRecord<F, RV32_REGISTER_NUM_LIMBS>,
+    pub block_ptr_read: MemoryReadRecord<F, RV32_REGISTER_NUM_LIMBS>,
+    pub params_ptr_read: MemoryReadRecord<F, RV32_REGISTER_NUM_LIMBS>,
+    pub state_reads: [[MemoryReadRecord<F, BLAKE_WORD_SIZE>; A]; BLAKE_STATE_WORDS],
+    pub block_reads: [[MemoryReadRecord<F, BLAKE_WORD_SIZE>; A]; BLAKE_BLOCK_WORDS],
+    pub params_reads: [[MemoryReadRecord<F, BLAKE_WORD_SIZE>; A]; BLAKE_PARAM_WORDS],
+    pub state_writes: [MemoryWriteRecord<F, BLAKE_WORD_SIZE>; BLAKE_OUTPUT_ACCESSES],
+}
+
+impl<F: PrimeField32, const W: usize, const L: usize, const B: usize, const A: usize>
+    BlakeVmChip<F, W, L, B, A>
+{
+    pub fn new(
+        execution_bus: ExecutionBus,
+        program_bus: ProgramBus,
+        memory_controller: MemoryControllerRef<F>,
+        bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<8>>,
+        variant: BlakeVariant,
+        offset: usize,
+    ) -> Self {
+        assert_eq!(variant.word_bits(), W);
+        assert!(L * 16 == W && B * 8 == W && A * 8 * BLAKE_WORD_SIZE == W);
+        let ptr_max_bits = memory_controller.borrow().mem_config().pointer_max_bits;
+        let memory_bridge = memory_controller.borrow().memory_bridge();
+        Self {
+            air: BlakeVmAir::new(
+                ExecutionBridge::new(execution_bus, program_bus),
+                memory_bridge,
+                bitwise_lookup_chip.bus(),
+                ptr_max_bits,
+                variant,
+                offset,
+            ),
+            records: Vec::new(),
+            memory_controller,
+            bitwise_lookup_chip,
+        }
+    }
+}
+
+impl<F: PrimeField32, const W: usize, const L: usize, const B: usize, const A: usize>
+    InstructionExecutor<F> for BlakeVmChip<F, W, L, B, A>
+{
+    fn execute(
+        &mut self,
+        instruction: Instruction<F>,
+        from_state: ExecutionState<u32>,
+    ) -> Result<ExecutionState<u32>, ExecutionError> {
+        let Instruction {
+            opcode,
+            a,
+            b,
+            c,
+            d,
+            e,
+            ..
+        } = instruction;
+        let variant = self.air.variant;
+        let local_opcode = Rv32BlakeOpcode::from_usize(opcode.local_opcode_idx(self.air.offset));
+        debug_assert_eq!(local_opcode, variant.local_opcode());
+
+        let mut memory = self.memory_controller.borrow_mut();
+        debug_assert_eq!(from_state.timestamp, memory.timestamp());
+
+        let (state_ptr_read, state_ptr) = read_rv32_register(&mut memory, d, a);
+        let (block_ptr_read, block_ptr) = read_rv32_register(&mut memory, d, b);
+        let (params_ptr_read, params_ptr) = read_rv32_register(&mut memory, d, c);
+        let (state_ptr, block_ptr, params_ptr) =
+            (state_ptr as usize, block_ptr as usize, params_ptr as usize);
+        for (ptr, len) in [
+            (state_ptr, BLAKE_OUTPUT_ACCESSES * BLAKE_WORD_SIZE),
+            (block_ptr, BLAKE_BLOCK_WORDS * B),
+            (params_ptr, BLAKE_PARAM_WORDS * B),
+        ] {
+            assert!(ptr + len <= (1 << self.air.ptr_max_bits));
+        }
+
+        let mut read_word = |ptr: usize| -> [MemoryReadRecord<F, BLAKE_WORD_SIZE>; A] {
+            from_fn(|i| {
+                memory
+                    .read::<BLAKE_WORD_SIZE>(e, F::from_canonical_usize(ptr + i * BLAKE_WORD_SIZE))
+            })
+        };
+        let state_reads: [_; BLAKE_STATE_WORDS] = from_fn(|i| read_word(state_ptr + i * B));
+        let block_reads: [_; BLAKE_BLOCK_WORDS] = from_fn(|i| read_word(block_ptr + i * B));
+        let params_reads: [_; BLAKE_PARAM_WORDS] = from_fn(|i| read_word(params_ptr + i * B));
+
+        let output = blake_compress(
+            variant,
+            state_reads.map(|reads| word_value(&reads)),
+            block_reads.map(|reads| word_value(&reads)),
+            params_reads.map(|reads| word_value(&reads)),
+        );
+        tracing::trace!("[runtime] blake compress output: {:x?}", output);
+
+        let output_bytes: Vec<u8> = output[variant.output_words()]
+            .iter()
+            .flat_map(|word| word.to_le_bytes().into_iter().take(B))
+            .collect();
+        let state_writes: [_; BLAKE_OUTPUT_ACCESSES] = from_fn(|i| {
+            memory.write::<BLAKE_WORD_SIZE>(
+                e,
+                F::from_canonical_usize(state_ptr + i * BLAKE_WORD_SIZE),
+                from_fn(|j| F::from_canonical_u8(output_bytes[i * BLAKE_WORD_SIZE + j])),
+            )
+        });
+
+        self.records.push(BlakeRecord {
+            from_state,
+            state_ptr_read,
+            block_ptr_read,
+            params_ptr_read,
+            state_reads,
+            block_reads,
+            params_reads,
+            state_writes,
+        });
+
+        // NOTE: Check this is consistent with the timestamp change in BlakeVmAir
+        let to_timestamp = from_state.timestamp + blake_timestamp_change(A) as u32;
+        debug_assert_eq!(to_timestamp, memory.timestamp());
+
+        Ok(ExecutionState {
+            pc: from_state.pc + DEFAULT_PC_STEP,
+            timestamp: to_timestamp,
+        })
+    }
+
+    fn get_opcode_name(&self, _: usize) -> String {
+        format!("{:?}", self.air.variant.local_opcode())
+    }
+}
+
+/// Amount to advance the timestamp by after execution of one compression instruction, where
+/// each word takes `word_accesses` memory accesses.
+const fn blake_timestamp_change(word_accesses: usize) -> usize {
+    BLAKE_REGISTER_READS
+        + (BLAKE_STATE_WORDS + BLAKE_BLOCK_WORDS + BLAKE_PARAM_WORDS) * word_accesses
+        + BLAKE_OUTPUT_ACCESSES
+}
+
+/// The little-endian word read by `reads`.
+fn word_value<F: PrimeField32>(reads: &[MemoryReadRecord<F, BLAKE_WORD_SIZE>]) -> u64 {
+    reads
+        .iter()
+        .flat_map(|read| read.data)
+        .enumerate()
+        .fold(0, |acc, (i, byte)| {
+            let byte: u8 = byte
+                .as_canonical_u32()
+                .try_into()
+                .expect("Memory cell not a byte");
+            acc | (byte as u64) << (8 * i)
+        })
+}
+
+impl<F: PrimeField32, const A: usize> BlakeRecord<F, A> {
+    /// The chaining value before the compression.
+    pub fn prev_state(&self) -> [u64; BLAKE_STATE_WORDS] {
+        self.state_reads.map(|reads| word_value(&reads))
+    }
+
+    pub fn message(&self) -> [u64; BLAKE_BLOCK_WORDS] {
+        self.block_reads.map(|reads| word_value(&reads))
+    }
+
+    pub fn params(&self) -> [u64; BLAKE_PARAM_WORDS] {
+        self.params_reads.map(|reads| word_value(&reads))
+    }
+}
@@ -0,0 +1,207 @@
+use std::{borrow::BorrowMut, sync::Arc};
+
+use openvm_blake_guest::{
+    blake2b, blake2b_compress, blake3, blake3_compress, BLAKE2B_BLOCK_BYTES, BLAKE3_BLOCK_BYTES,
+};
+use openvm_blake_transpiler::Rv32BlakeOpcode;
+use openvm_circuit::arch::{
+    testing::{VmChipTestBuilder, VmChipTester},
+    BITWISE_OP_LOOKUP_BUS,
+};
+use openvm_circuit_primitives::bitwise_op_lookup::{
+    BitwiseOperationLookupBus, BitwiseOperationLookupChip,
+};
+use openvm_instructions::{instruction::Instruction, UsizeOpcode, VmOpcode};
+use openvm_stark_backend::{
+    p3_field::AbstractField, utils::disable_debug_builder, verifier::VerificationError,
+};
+use openvm_stark_sdk::{
+    config::baby_bear_blake3::BabyBearBlake3Config, p3_baby_bear::BabyBear,
+    utils::create_seeded_rng,
+};
+use rand::Rng;
+
+use super::{
+    columns::BlakeVmCols, BlakeVariant, BlakeVmChip, BLAKE_OUTPUT_ACCESSES, BLAKE_WORD_SIZE,
+};
+
+type F = BabyBear;
+
+const STATE_BYTES: usize = BLAKE_OUTPUT_ACCESSES * BLAKE_WORD_SIZE;
+const PARAMS_BYTES: usize = 32;
+
+/// Compresses each input with the chip in place in memory and checks the written state against
+/// `expected`, which is given the state, block and parameter bytes.
+fn build_blake_test<const W: usize, const L: usize, const B: usize, const A: usize>(
+    variant: BlakeVariant,
+    inputs: Vec<(Vec<u8>, Vec<u8>, Vec<u8>)>,
+    expected: impl Fn(&[u8], &[u8], &[u8]) -> Vec<u8>,
+) -> VmChipTester<BabyBearBlake3Config> {
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = Arc::new(BitwiseOperationLookupChip::<8>::new(bitwise_bus));
+
+    let mut tester = VmChipTestBuilder::default();
+    let mut chip = BlakeVmChip::<F, W, L, B, A>::new(
+        tester.execution_bus(),
+        tester.program_bus(),
+        tester.memory_controller(),
+        bitwise_chip.clone(),
+        variant,
+        Rv32BlakeOpcode::default_offset(),
+    );
+
+    let [a, b, c, d, e] = [4, 8, 12, 1, 2];
+    for (k, (state, block, params)) in inputs.into_iter().enumerate() {
+        let state_ptr = k * (STATE_BYTES + 16 * B + PARAMS_BYTES);
+        let block_ptr = state_ptr + STATE_BYTES;
+        let params_ptr = block_ptr + 16 * B;
+        for (reg, ptr) in [(a, state_ptr), (b, block_ptr), (c, params_ptr)] {
+            tester.write(d, reg, (ptr as u32).to_le_bytes().map(F::from_canonical_u8));
+        }
+        for (ptr, bytes) in [
+            (state_ptr, &state),
+            (block_ptr, &block),
+            (params_ptr, &params),
+        ] {
+            for (i, &byte) in bytes.iter().enumerate() {
+                tester.write_cell(e, ptr + i, F::from_canonical_u8(byte));
+            }
+        }
+
+        tester.execute(
+            &mut chip,
+            Instruction::from_isize(
+                VmOpcode::with_default_offset(variant.local_opcode()),
+                a as isize,
+                b as isize,
+                c as isize,
+                d as isize,
+                e as isize,
+            ),
+        );
+
+        for (i, byte) in expected(&state, &block, &params).into_iter().enumerate() {
+            assert_eq!(
+                tester.read_cell(e, state_ptr + i),
+                F::from_canonical_u8(byte)
+            );
+        }
+    }
+    tester.build().load(chip).load(bitwise_chip).finalize()
+}
+
+fn expected_blake2b(state: &[u8], block: &[u8], params: &[u8]) -> Vec<u8> {
+    let mut h: [u64; 8] =
+        std::array::from_fn(|i| u64::from_le_bytes(state[8 * i..8 * (i + 1)].try_into().unwrap()));
+    let params: [u64; 4] =
+        std::array::from_fn(|i| u64::from_le_bytes(params[8 * i..8 * (i + 1)].try_into().unwrap()));
+    let t = params[0] as u128 | (params[1] as u128) << 64;
+    blake2b_compress(&mut h, block.try_into().unwrap(), t, params[2] != 0);
+    h.iter().flat_map(|word| word.to_le_bytes()).collect()
+}
+
+fn expected_blake3(state: &[u8], block: &[u8], params: &[u8]) -> Vec<u8> {
+    let mut state: [u32; 16] =
+        std::array::from_fn(|i| u32::from_le_bytes(state[4 * i..4 * (i + 1)].try_into().unwrap()));
+    let params: [u32; 4] =
+        std::array::from_fn(|i| u32::from_le_bytes(params[4 * i..4 * (i + 1)].try_into().unwrap()));
+    let counter = params[0] as u64 | (params[1] as u64) << 32;
+    blake3_compress(
+        &mut state,
+        block.try_into().unwrap(),
+        counter,
+        params[2],
+        params[3],
+    );
+    state.iter().flat_map(|word| word.to_le_bytes()).collect()
+}
+
+fn rand_blake2b_inputs(num: usize) -> Vec<(Vec<u8>, Vec<u8>, Vec<u8>)> {
+    let mut rng = create_seeded_rng();
+    (0..num)
+        .map(|i| {
+            let mut state = vec![0u8; STATE_BYTES];
+            rng.fill(&mut state[..]);
+            let mut block = vec![0u8; BLAKE2B_BLOCK_BYTES];
+            rng.fill(&mut block[..]);
+            // The finalization flag is either all zeros or all ones
+            let last = if i % 2 == 0 { u64::MAX } else { 0 };
+            let params = [rng.gen(), rng.gen(), last, 0u64];
+            (
+                state,
+                block,
+                params.iter().flat_map(|p| p.to_le_bytes()).collect(),
+            )
+        })
+        .collect()
+}
+
+fn rand_blake3_inputs(num: usize) -> Vec<(Vec<u8>, Vec<u8>, Vec<u8>)> {
+    let mut rng = create_seeded_rng();
+    (0..num)
+        .map(|_| {
+            let mut state = vec![0u8; STATE_BYTES];
+            rng.fill(&mut state[..]);
+            let mut block = vec![0u8; BLAKE3_BLOCK_BYTES];
+            rng.fill(&mut block[..]);
+            let mut params = vec![0u8; 16];
+            rng.fill(&mut params[..]);
+            (state, block, params)
+        })
+        .collect()
+}
+
+#[test]
+fn rand_blake2b_test() {
+    let tester = build_blake_test::<64, 4, 8, 2>(
+        BlakeVariant::Blake2b,
+        rand_blake2b_inputs(3),
+        expected_blake2b,
+    );
+    tester.simple_test().expect("Verification failed");
+}
+
+#[test]
+fn rand_blake3_test() {
+    let tester = build_blake_test::<32, 2, 4, 1>(
+        BlakeVariant::Blake3,
+        rand_blake3_inputs(3),
+        expected_blake3,
+    );
+    tester.simple_test().expect("Verification failed");
+}
+
+#[test]
+fn blake_empty_message_test() {
+    assert_eq!(
+        hex::encode(blake2b(b"")),
+        "786a02f742015903c6c6fd852552d272912f4740e15847618a86e217f71f5419\
+         d25e1031afee585313896444934eb04b903a685b1448b755d56f701afe9be2ce"
+    );
+    assert_eq!(
+        hex::encode(blake3(b"")),
+        "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262"
+    );
+}
+
+#[test]
+fn negative_blake3_test() {
+    let mut tester = build_blake_test::<32, 2, 4, 1>(
+        BlakeVariant::Blake3,
+        rand_blake3_inputs(1),
+        expected_blake3,
+    );
+
+    // Flip a bit of the output, which is written to memory
+    let blake_trace = tester.air_proof_inputs[2].raw.common_main.as_mut().unwrap();
+    let digest_row: &mut BlakeVmCols<F, 32, 2, 4, 1> = blake_trace
+        .row_mut(BlakeVariant::Blake3.rows_per_block() - 1)
+        .borrow_mut();
+    digest_row.digest.output[0][0] = F::ONE - digest_row.digest.output[0][0];
+
+    disable_debug_builder();
+    assert_eq!(
+        tester.simple_test().err(),
+        Some(VerificationError::OodEvaluationMismatch)
+    );
+}
@@ -0,0 +1,183 @@
+use std::{array::from_fn, borrow::BorrowMut, sync::Arc};
+
+use openvm_circuit_primitives::utils::next_power_of_two_or_zero;
+use openvm_instructions::riscv::{RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS};
+use openvm_stark_backend::{
+    config::{StarkGenericConfig, Val},
+    p3_air::BaseAir,
+    p3_field::{AbstractField, PrimeField32},
+    p3_matrix::dense::RowMajorMatrix,
+    p3_maybe_rayon::prelude::*,
+    prover::types::AirProofInput,
+    rap::{get_air_name, AnyRap},
+    Chip, ChipUsageGetter,
+};
+
+use super::{
+    columns::{BlakeGCols, BlakeInstructionCols, BlakeVmCols},
+    utils::*,
+    BlakeVmChip,
+};
+
+impl<SC: StarkGenericConfig, const W: usize, const L: usize, const B: usize, const A: usize>
+    Chip<SC> for BlakeVmChip<Val<SC>, W, L, B, A>
+where
+    Val<SC>: PrimeField32,
+{
+    fn air(&self) -> Arc<dyn AnyRap<SC>> {
+        Arc::new(self.air)
+    }
+
+    fn generate_air_proof_input(self) -> AirProofInput<SC> {
+        let air = self.air();
+        let variant = self.air.variant;
+        let trace_width = self.trace_width();
+        let num_rows = next_power_of_two_or_zero(self.current_trace_height());
+        // Rows after the last block are dummy rows with no flags set.
+        let mut trace =
+            RowMajorMatrix::new(Val::<SC>::zero_vec(num_rows * trace_width), trace_width);
+
+        let aux_cols_factory = self.memory_controller.borrow().aux_cols_factory();
+        let limb_shift_bits = RV32_CELL_BITS * RV32_REGISTER_NUM_LIMBS - self.air.ptr_max_bits;
+        let block_width = trace_width * variant.rows_per_block();
+
+        trace.values[..self.records.len() * block_width]
+            .par_chunks_mut(block_width)
+            .zip(self.records.par_iter())
+            .for_each(|(rows, record)| {
+                let prev_state = record.prev_state();
+                let message = record.message();
+                let instruction = BlakeInstructionCols {
+                    pc: Val::<SC>::from_canonical_u32(record.from_state.pc),
+                    start_timestamp: Val::<SC>::from_canonical_u32(record.from_state.timestamp),
+                    rd_ptr: record.state_ptr_read.pointer,
+                    rs1_ptr: record.block_ptr_read.pointer,
+                    rs2_ptr: record.params_ptr_read.pointer,
+                    state_ptr: record.state_ptr_read.data,
+                    block_ptr: record.block_ptr_read.data,
+                    params_ptr: record.params_ptr_read.data,
+                    prev_state: prev_state.map(word_to_bits),
+                    message: message.map(word_to_bytes),
+                };
+                let block_rows = blake_rows(variant, prev_state, message, record.params());
+
+                for (h, (row, (state, gs))) in rows
+                    .chunks_exact_mut(trace_width)
+                    .zip(&block_rows)
+                    .enumerate()
+                {
+                    let cols: &mut BlakeVmCols<Val<SC>, W, L, B, A> = row.borrow_mut();
+                    cols.instruction = instruction;
+                    cols.round.state = state.map(|words| words.map(word_to_bits));
+
+                    if h + 1 < variant.rows_per_block() {
+                        cols.flags.half_round_flags[h] = Val::<SC>::ONE;
+                        for (i, (g_cols, g)) in cols.round.g.iter_mut().zip(gs).enumerate() {
+                            let [a, b, c, _] = from_fn(|role| state[role][i]);
+                            let [x, y] = g_message_indices(variant, h, i).map(|j| message[j]);
+                            generate_g_cols(g_cols, g, a, b, c, x, y);
+                        }
+                    } else {
+                        cols.flags.is_digest_row = Val::<SC>::ONE;
+                        cols.digest.output =
+                            blake_output(variant, prev_state, state).map(word_to_bits);
+                        for (aux, write) in cols
+                            .mem_oc
+                            .state_writes
+                            .iter_mut()
+                            .zip(&record.state_writes)
+                        {
+                            *aux = aux_cols_factory.make_write_aux_cols(*write);
+                        }
+                    }
+                }
+
+                let first_row: &mut BlakeVmCols<Val<SC>, W, L, B, A> =
+                    rows[..trace_width].borrow_mut();
+                first_row.mem_oc.register_aux = [
+                    record.state_ptr_read,
+                    record.block_ptr_read,
+                    record.params_ptr_read,
+                ]
+                .map(|read| aux_cols_factory.make_read_aux_cols(read));
+                let mem_oc = &mut first_row.mem_oc;
+                for (aux, reads) in mem_oc
+                    .state_reads
+                    .iter_mut()
+                    .chain(mem_oc.block_reads.iter_mut())
+                    .chain(mem_oc.params_reads.iter_mut())
+                    .zip(
+                        record
+                            .state_reads
+                            .iter()
+                            .chain(&record.block_reads)
+                            .chain(&record.params_reads),
+                    )
+                {
+                    *aux = reads.map(|read| aux_cols_factory.make_read_aux_cols(read));
+                }
+
+                self.bitwise_lookup_chip.request_range(
+                    record.state_ptr_read.data[RV32_REGISTER_NUM_LIMBS - 1].as_canonical_u32()
+                        << limb_shift_bits,
+                    record.block_ptr_read.data[RV32_REGISTER_NUM_LIMBS - 1].as_canonical_u32()
+                        << limb_shift_bits,
+                );
+                self.bitwise_lookup_chip.request_range(
+                    record.params_ptr_read.data[RV32_REGISTER_NUM_LIMBS - 1].as_canonical_u32()
+                        << limb_shift_bits,
+                    0,
+                );
+                let message_bytes: Vec<u32> = message
+                    .iter()
+                    .flat_map(|word| (0..B).map(move |m| ((word >> (8 * m)) & 0xff) as u32))
+                    .collect();
+                for pair in message_bytes.chunks_exact(2) {
+                    self.bitwise_lookup_chip.request_range(pair[0], pair[1]);
+                }
+            });
+
+        AirProofInput::simple_no_pis(air, trace)
+    }
+}
+
+/// Fills the bits of the intermediate values of a `G` function with inputs `a, b, c` and message
+/// words `x, y`, and the carries of its additions.
+fn generate_g_cols<F: PrimeField32, const W: usize, const L: usize>(
+    g_cols: &mut BlakeGCols<F, W, L>,
+    g: &GValues,
+    a: u64,
+    b: u64,
+    c: u64,
+    x: u64,
+    y: u64,
+) {
+    g_cols.a1 = word_to_bits(g.a1);
+    g_cols.d1 = word_to_bits(g.d1);
+    g_cols.c1 = word_to_bits(g.c1);
+    g_cols.b1 = word_to_bits(g.b1);
+    g_cols.a2 = word_to_bits(g.a2);
+    g_cols.c2 = word_to_bits(g.c2);
+    // A carry of up to 2 is split into two booleans
+    let split_carry = |carry: u64| [carry >= 1, carry >= 2].map(F::from_bool);
+    g_cols.carry_a1 = u16_limb_carries::<L>(&[a, b, x]).map(split_carry);
+    g_cols.carry_a2 = u16_limb_carries::<L>(&[g.a1, g.b1, y]).map(split_carry);
+    g_cols.carry_c1 = u16_limb_carries::<L>(&[c, g.d1]).map(F::from_canonical_u64);
+    g_cols.carry_c2 = u16_limb_carries::<L>(&[g.c1, g.d2]).map(F::from_canonical_u64);
+}
+
+impl<F: PrimeField32, const W: usize, const L: usize, const B: usize, const A: usize>
+    ChipUsageGetter for BlakeVmChip<F, W, L, B, A>
+{
+    fn air_name(&self) -> String {
+        get_air_name(&self.air)
+    }
+
+    fn current_trace_height(&self) -> usize {
+        self.records.len() * self.air.variant.rows_per_block()
+    }
+
+    fn trace_width(&self) -> usize {
+        BaseAir::<F>::width(&self.air)
+    }
+}
@@ -0,0 +1,174 @@
+use std::array::from_fn;
+
+use openvm_stark_backend::p3_field::AbstractField;
+
+use crate::{BlakeVariant, BLAKE_BLOCK_WORDS, BLAKE_PARAM_WORDS, BLAKE_STATE_WORDS};
+
+/// The state of a row: `state[role][i]` is the input `role` (`a, b, c, d` in order) of the `i`-th
+/// `G` function of the half-round. On column half-rounds and on the digest row this is
+/// `v[4 * role + i]`, and on diagonal half-rounds it is `v[4 * role + (i + role) % 4]`.
+pub type BlakeState = [[u64; 4]; 4];
+
+/// The intermediate values of one `G` function.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct GValues {
+    pub a1: u64,
+    pub d1: u64,
+    pub c1: u64,
+    pub b1: u64,
+    pub a2: u64,
+    pub d2: u64,
+    pub c2: u64,
+    pub b2: u64,
+}
+
+/// The `G` function on `[a, b, c, d]` with the message words `x` and `y`.
+pub fn g(variant: BlakeVariant, [a, b, c, d]: [u64; 4], x: u64, y: u64) -> GValues {
+    let bits = variant.word_bits();
+    let [r1, r2, r3, r4] = variant.rotations();
+    let add = |x: u64, y: u64| x.wrapping_add(y) & word_mask(bits);
+    let rotr = |x: u64, n: usize| rotate_right(x, n, bits);
+    let a1 = add(add(a, b), x);
+    let d1 = rotr(d ^ a1, r1);
+    let c1 = add(c, d1);
+    let b1 = rotr(b ^ c1, r2);
+    let a2 = add(add(a1, b1), y);
+    let d2 = rotr(d1 ^ a2, r3);
+    let c2 = add(c1, d2);
+    let b2 = rotr(b1 ^ c2, r4);
+    GValues {
+        a1,
+        d1,
+        c1,
+        b1,
+        a2,
+        d2,
+        c2,
+        b2,
+    }
+}
+
+/// The indices of the message words `x` and `y` of the `i`-th `G` function of half-round `h`.
+pub fn g_message_indices(variant: BlakeVariant, h: usize, i: usize) -> [usize; 2] {
+    let schedule = variant.message_schedule(h / 2);
+    let j = 2 * (i + 4 * (h % 2));
+    [schedule[j], schedule[j + 1]]
+}
+
+/// The slot in the next row's state of the output `role` of the `i`-th `G` function of
+/// half-round `h`.
+pub fn next_slot(h: usize, i: usize, role: usize) -> usize {
+    if h % 2 == 0 {
+        (i + 4 - role) % 4
+    } else {
+        (i + role) % 4
+    }
+}
+
+/// The state of the first half-round: the chaining value, the first half of the IV, and the
+/// parameters XOR-ed with the second half of the IV for Blake2b.
+pub fn initial_state(
+    variant: BlakeVariant,
+    prev_state: [u64; BLAKE_STATE_WORDS],
+    params: [u64; BLAKE_PARAM_WORDS],
+) -> BlakeState {
+    let iv = variant.iv();
+    let mask = variant.params_mask();
+    [
+        from_fn(|i| prev_state[i]),
+        from_fn(|i| prev_state[4 + i]),
+        from_fn(|i| iv[i]),
+        from_fn(|i| params[i] ^ mask[i]),
+    ]
+}
+
+/// The state of each row of a block, and the `G` values of each half-round. The digest row has
+/// default `G` values.
+pub fn blake_rows(
+    variant: BlakeVariant,
+    prev_state: [u64; BLAKE_STATE_WORDS],
+    message: [u64; BLAKE_BLOCK_WORDS],
+    params: [u64; BLAKE_PARAM_WORDS],
+) -> Vec<(BlakeState, [GValues; 4])> {
+    let mut rows = Vec::with_capacity(variant.rows_per_block());
+    let mut state = initial_state(variant, prev_state, params);
+    for h in 0..2 * variant.rounds() {
+        let gs: [GValues; 4] = from_fn(|i| {
+            let [x, y] = g_message_indices(variant, h, i);
+            g(
+                variant,
+                from_fn(|role| state[role][i]),
+                message[x],
+                message[y],
+            )
+        });
+        let mut next_state = [[0; 4]; 4];
+        for (i, g) in gs.iter().enumerate() {
+            for (role, value) in [g.a2, g.b2, g.c2, g.d2].into_iter().enumerate() {
+                next_state[role][next_slot(h, i, role)] = value;
+            }
+        }
+        rows.push((state, gs));
+        state = next_state;
+    }
+    rows.push((state, Default::default()));
+    rows
+}
+
+/// The 16 output words given the final state: `v[i] ^ v[i + 8]` for `i < 8` followed by the
+/// new chaining value for Blake2b, or by `v[i + 8] ^ h[i]` for Blake3.
+pub fn blake_output(
+    variant: BlakeVariant,
+    prev_state: [u64; BLAKE_STATE_WORDS],
+    state: &BlakeState,
+) -> [u64; 16] {
+    let v = |j: usize| state[j / 4][j % 4];
+    let mut output = [0; 16];
+    for i in 0..8 {
+        output[i] = v(i) ^ v(i + 8);
+        output[i + 8] = match variant {
+            BlakeVariant::Blake2b => output[i] ^ prev_state[i],
+            BlakeVariant::Blake3 => v(i + 8) ^ prev_state[i],
+        };
+    }
+    output
+}
+
+/// The compression function on words of the variant's size.
+pub fn blake_compress(
+    variant: BlakeVariant,
+    prev_state: [u64; BLAKE_STATE_WORDS],
+    message: [u64; BLAKE_BLOCK_WORDS],
+    params: [u64; BLAKE_PARAM_WORDS],
+) -> [u64; 16] {
+    let rows = blake_rows(variant, prev_state, message, params);
+    blake_output(variant, prev_state, &rows.last().unwrap().0)
+}
+
+/// The number of carries out of each `u16` limb when adding `words` limb by limb.
+pub fn u16_limb_carries<const L: usize>(words: &[u64]) -> [u64; L] {
+    let mut carry = 0;
+    from_fn(|i| {
+        let limb_sum: u64 = words.iter().map(|word| (word >> (16 * i)) & 0xffff).sum();
+        carry = (limb_sum + carry) >> 16;
+        carry
+    })
+}
+
+/// The bits of `x`, least significant bit first.
+pub fn word_to_bits<F: AbstractField, const W: usize>(x: u64) -> [F; W] {
+    from_fn(|i| F::from_canonical_u64((x >> i) & 1))
+}
+
+/// The bytes of `x`, least significant byte first.
+pub fn word_to_bytes<F: AbstractField, const B: usize>(x: u64) -> [F; B] {
+    from_fn(|i| F::from_canonical_u64((x >> (8 * i)) & 0xff))
+}
+
+fn word_mask(bits: usize) -> u64 {
+    u64::MAX >> (64 - bits)
+}
+
+fn rotate_right(x: u64, n: usize, bits: usize) -> u64 {
+    ((x >> n) | (x << (bits - n))) & word_mask(bits)
+}
@@ -0,0 +1,163 @@
+use crate::{
+    blake2b_compress, blake3_compress, BLAKE2B_BLOCK_BYTES, BLAKE2B_IV, BLAKE3_BLOCK_BYTES,
+    BLAKE3_IV,
+};
+
+/// Number of bytes in a Blake3 chunk, the leaf of the hash tree.
+pub const BLAKE3_CHUNK_BYTES: usize = 1024;
+
+const CHUNK_START: u32 = 1 << 0;
+const CHUNK_END: u32 = 1 << 1;
+const PARENT: u32 = 1 << 2;
+const ROOT: u32 = 1 << 3;
+
+/// Maximum depth of the Blake3 hash tree, enough for inputs of up to `2^64` bytes.
+const BLAKE3_MAX_DEPTH: usize = 54;
+
+/// Unkeyed Blake2b with a 64-byte digest.
+pub fn blake2b(input: &[u8]) -> [u8; 64] {
+    let mut output = [0u8; 64];
+    set_blake2b(input, &mut output);
+    output
+}
+
+/// Sets `output` to the unkeyed Blake2b hash of `input` with a 64-byte digest.
+pub fn set_blake2b(input: &[u8], output: &mut [u8; 64]) {
+    let mut h = BLAKE2B_IV;
+    // Parameter block: digest length 64, no key, fanout 1, depth 1
+    h[0] ^= 0x0101_0000 ^ 64;
+    // The empty input is hashed as one block of zeros
+    let num_blocks = input.len().div_ceil(BLAKE2B_BLOCK_BYTES).max(1);
+    for i in 0..num_blocks {
+        let start = i * BLAKE2B_BLOCK_BYTES;
+        let end = input.len().min(start + BLAKE2B_BLOCK_BYTES);
+        let mut block = [0u8; BLAKE2B_BLOCK_BYTES];
+        block[..end - start].copy_from_slice(&input[start..end]);
+        blake2b_compress(&mut h, &block, end as u128, i == num_blocks - 1);
+    }
+    for (out, word) in output.chunks_exact_mut(8).zip(h) {
+        out.copy_from_slice(&word.to_le_bytes());
+    }
+}
+
+/// Blake3 with a 32-byte digest.
+pub fn blake3(input: &[u8]) -> [u8; 32] {
+    let mut output = [0u8; 32];
+    set_blake3(input, &mut output);
+    output
+}
+
+/// Sets `output` to the Blake3 hash of `input` with a 32-byte digest.
+pub fn set_blake3(input: &[u8], output: &mut [u8; 32]) {
+    // Chaining values of the complete left subtrees, from the largest one
+    let mut cv_stack = [[0u32; 8]; BLAKE3_MAX_DEPTH];
+    let mut stack_len = 0;
+
+    let num_chunks = input.len().div_ceil(BLAKE3_CHUNK_BYTES).max(1);
+    for (i, chunk) in input
+        .chunks(BLAKE3_CHUNK_BYTES)
+        .take(num_chunks - 1)
+        .enumerate()
+    {
+        let mut cv = chunk_output(chunk, i as u64).chaining_value();
+        // Merge one subtree for each trailing zero of the number of chunks so far
+        let mut total_chunks = i + 1;
+        while total_chunks & 1 == 0 {
+            stack_len -= 1;
+            cv = parent_output(&cv_stack[stack_len], &cv).chaining_value();
+            total_chunks >>= 1;
+        }
+        cv_stack[stack_len] = cv;
+        stack_len += 1;
+    }
+
+    let last_chunk = &input[(num_chunks - 1) * BLAKE3_CHUNK_BYTES..];
+    let mut node = chunk_output(last_chunk, (num_chunks - 1) as u64);
+    while stack_len > 0 {
+        stack_len -= 1;
+        node = parent_output(&cv_stack[stack_len], &node.chaining_value());
+    }
+    node.flags |= ROOT;
+    for (out, word) in output.chunks_exact_mut(4).zip(node.compress()) {
+        out.copy_from_slice(&word.to_le_bytes());
+    }
+}
+
+/// The inputs of a Blake3 compression which may be the root of the tree.
+struct Blake3Output {
+    cv: [u32; 8],
+    block: [u8; BLAKE3_BLOCK_BYTES],
+    counter: u64,
+    block_len: u32,
+    flags: u32,
+}
+
+impl Blake3Output {
+    fn compress(&self) -> [u32; 16] {
+        let mut state = [0u32; 16];
+        state[..8].copy_from_slice(&self.cv);
+        blake3_compress(
+            &mut state,
+            &self.block,
+            self.counter,
+            self.block_len,
+            self.flags,
+        );
+        state
+    }
+
+    fn chaining_value(&self) -> [u32; 8] {
+        self.compress()[..8].try_into().unwrap()
+    }
+}
+
+/// Compresses all but the last block of the chunk.
+fn chunk_output(chunk: &[u8], chunk_counter: u64) -> Blake3Output {
+    let mut cv = BLAKE3_IV;
+    let num_blocks = chunk.len().div_ceil(BLAKE3_BLOCK_BYTES).max(1);
+    for (i, bytes) in chunk
+        .chunks_exact(BLAKE3_BLOCK_BYTES)
+        .take(num_blocks - 1)
+        .enumerate()
+    {
+        let mut state = [0u32; 16];
+        state[..8].copy_from_slice(&cv);
+        let flags = if i == 0 { CHUNK_START } else { 0 };
+        blake3_compress(
+            &mut state,
+            bytes.try_into().unwrap(),
+            chunk_counter,
+            BLAKE3_BLOCK_BYTES as u32,
+            flags,
+        );
+        cv = state[..8].try_into().unwrap();
+    }
+
+    let last_block = &chunk[(num_blocks - 1) * BLAKE3_BLOCK_BYTES..];
+    let mut block = [0u8; BLAKE3_BLOCK_BYTES];
+    block[..last_block.len()].copy_from_slice(last_block);
+    Blake3Output {
+        cv,
+        block,
+        counter: chunk_counter,
+        block_len: last_block.len() as u32,
+        flags: (if num_blocks == 1 { CHUNK_START } else { 0 }) | CHUNK_END,
+    }
+}
+
+fn parent_output(left_cv: &[u32; 8], right_cv: &[u32; 8]) -> Blake3Output {
+    let mut block = [0u8; BLAKE3_BLOCK_BYTES];
+    for (out, word) in block
+        .chunks_exact_mut(4)
+        .zip(left_cv.iter().chain(right_cv))
+    {
+        out.copy_from_slice(&word.to_le_bytes());
+    }
+    Blake3Output {
+        cv: BLAKE3_IV,
+        block,
+        counter: 0,
+        block_len: BLAKE3_BLOCK_BYTES as u32,
+        flags: PARENT,
+    }
+}
@@ -0,0 +1,219 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+mod hash;
+pub use hash::*;
+
+/// This is custom-1 defined in RISC-V spec document
+pub const OPCODE: u8 = 0x2b;
+pub const BLAKE_FUNCT3: u8 = 0b110;
+pub const BLAKE2B_COMPRESS_FUNCT7: u8 = 0x0;
+pub const BLAKE3_COMPRESS_FUNCT7: u8 = 0x1;
+
+/// Number of bytes in a Blake2b message block.
+pub const BLAKE2B_BLOCK_BYTES: usize = 128;
+/// Number of bytes in a Blake3 message block.
+pub const BLAKE3_BLOCK_BYTES: usize = 64;
+
+/// Initialization vector of Blake2b.
+pub const BLAKE2B_IV: [u64; 8] = [
+    0x6a09e667f3bcc908,
+    0xbb67ae8584caa73b,
+    0x3c6ef372fe94f82b,
+    0xa54ff53a5f1d36f1,
+    0x510e527fade682d1,
+    0x9b05688c2b3e6c1f,
+    0x1f83d9abfb41bd6b,
+    0x5be0cd19137e2179,
+];
+
+/// Initialization vector of Blake3.
+pub const BLAKE3_IV: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+/// Message word permutations of Blake2b. Round `r` uses `BLAKE2B_SIGMA[r % 10]`.
+pub const BLAKE2B_SIGMA: [[usize; 16]; 10] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
+    [11, 8, 12, 0, 5, 2, 15, 13, 10, 14, 3, 6, 7, 1, 9, 4],
+    [7, 9, 3, 1, 13, 12, 11, 14, 2, 6, 5, 10, 4, 0, 15, 8],
+    [9, 0, 5, 7, 2, 4, 10, 15, 14, 1, 11, 12, 6, 8, 3, 13],
+    [2, 12, 6, 10, 0, 11, 8, 3, 4, 13, 7, 5, 15, 14, 1, 9],
+    [12, 5, 1, 15, 14, 13, 4, 10, 0, 7, 6, 3, 9, 2, 8, 11],
+    [13, 11, 7, 14, 12, 1, 3, 9, 5, 0, 15, 4, 8, 6, 2, 10],
+    [6, 15, 14, 9, 11, 3, 0, 8, 12, 2, 13, 7, 1, 4, 10, 5],
+    [10, 2, 8, 4, 7, 6, 1, 5, 15, 11, 9, 14, 3, 12, 13, 0],
+];
+
+/// Permutation applied to the Blake3 message words after each round.
+pub const BLAKE3_MSG_PERMUTATION: [usize; 16] =
+    [2, 6, 3, 10, 7, 0, 4, 13, 1, 11, 12, 5, 9, 14, 15, 8];
+
+/// The Blake2b compression function `F`. Updates `h` with the message `block`, where `t` is
+/// the number of bytes hashed so far including this block and `last` is set on the last block.
+#[inline(always)]
+pub fn blake2b_compress(h: &mut [u64; 8], block: &[u8; BLAKE2B_BLOCK_BYTES], t: u128, last: bool) {
+    let params = [
+        t as u64,
+        (t >> 64) as u64,
+        if last { u64::MAX } else { 0 },
+        0,
+    ];
+    #[cfg(not(target_os = "zkvm"))]
+    {
+        let mut m = [0u64; 16];
+        for (m, chunk) in m.iter_mut().zip(block.chunks_exact(8)) {
+            *m = u64::from_le_bytes(chunk.try_into().unwrap());
+        }
+        let mut v = [0u64; 16];
+        v[..8].copy_from_slice(h);
+        v[8..].copy_from_slice(&BLAKE2B_IV);
+        for (v, p) in v[12..].iter_mut().zip(params) {
+            *v ^= p;
+        }
+        for sigma in BLAKE2B_SIGMA.iter().cycle().take(12) {
+            round(
+                &mut v,
+                |i| m[sigma[i]],
+                |x, n| x.rotate_right(n),
+                [32, 24, 16, 63],
+            );
+        }
+        for i in 0..8 {
+            h[i] ^= v[i] ^ v[i + 8];
+        }
+    }
+    #[cfg(target_os = "zkvm")]
+    native_blake2b_compress(h.as_mut_ptr(), block.as_ptr(), params.as_ptr());
+}
+
+/// The Blake3 compression function. The chaining value is read from `state[..8]`, and `state`
+/// is overwritten with the full 16-word output.
+#[inline(always)]
+pub fn blake3_compress(
+    state: &mut [u32; 16],
+    block: &[u8; BLAKE3_BLOCK_BYTES],
+    counter: u64,
+    block_len: u32,
+    flags: u32,
+) {
+    let params = [counter as u32, (counter >> 32) as u32, block_len, flags];
+    #[cfg(not(target_os = "zkvm"))]
+    {
+        let mut m = [0u32; 16];
+        for (m, chunk) in m.iter_mut().zip(block.chunks_exact(4)) {
+            *m = u32::from_le_bytes(chunk.try_into().unwrap());
+        }
+        let mut v = [0u32; 16];
+        v[..8].copy_from_slice(&state[..8]);
+        v[8..12].copy_from_slice(&BLAKE3_IV[..4]);
+        v[12..].copy_from_slice(&params);
+        for _ in 0..7 {
+            round(&mut v, |i| m[i], |x, n| x.rotate_right(n), [16, 12, 8, 7]);
+            m = BLAKE3_MSG_PERMUTATION.map(|i| m[i]);
+        }
+        for i in 0..8 {
+            state[i + 8] = v[i + 8] ^ state[i];
+            state[i] = v[i] ^ v[i + 8];
+        }
+    }
+    #[cfg(target_os = "zkvm")]
+    native_blake3_compress(state.as_mut_ptr(), block.as_ptr(), params.as_ptr());
+}
+
+/// One round of Blake2b or Blake3: the `G` function on the columns and then on the diagonals
+/// of `v`, with the message words `msg(0), ..., msg(15)` in order.
+#[cfg(not(target_os = "zkvm"))]
+#[inline(always)]
+fn round<T: Copy + WrappingAdd + core::ops::BitXor<Output = T>>(
+    v: &mut [T; 16],
+    msg: impl Fn(usize) -> T,
+    rotr: impl Fn(T, u32) -> T,
+    rotations: [u32; 4],
+) {
+    const INDICES: [[usize; 4]; 8] = [
+        [0, 4, 8, 12],
+        [1, 5, 9, 13],
+        [2, 6, 10, 14],
+        [3, 7, 11, 15],
+        [0, 5, 10, 15],
+        [1, 6, 11, 12],
+        [2, 7, 8, 13],
+        [3, 4, 9, 14],
+    ];
+    for (i, [a, b, c, d]) in INDICES.into_iter().enumerate() {
+        v[a] = v[a].wrapping_add(v[b]).wrapping_add(msg(2 * i));
+        v[d] = rotr(v[d] ^ v[a], rotations[0]);
+        v[c] = v[c].wrapping_add(v[d]);
+        v[b] = rotr(v[b] ^ v[c], rotations[1]);
+        v[a] = v[a].wrapping_add(v[b]).wrapping_add(msg(2 * i + 1));
+        v[d] = rotr(v[d] ^ v[a], rotations[2]);
+        v[c] = v[c].wrapping_add(v[d]);
+        v[b] = rotr(v[b] ^ v[c], rotations[3]);
+    }
+}
+
+#[cfg(not(target_os = "zkvm"))]
+trait WrappingAdd {
+    fn wrapping_add(self, rhs: Self) -> Self;
+}
+
+#[cfg(not(target_os = "zkvm"))]
+impl WrappingAdd for u64 {
+    fn wrapping_add(self, rhs: Self) -> Self {
+        u64::wrapping_add(self, rhs)
+    }
+}
+
+#[cfg(not(target_os = "zkvm"))]
+impl WrappingAdd for u32 {
+    fn wrapping_add(self, rhs: Self) -> Self {
+        u32::wrapping_add(self, rhs)
+    }
+}
+
+/// Native hook for the Blake2b compression function.
+///
+/// # Safety
+///
+/// The VM updates `h` in place.
+/// - `h` must point to 8 little-endian `u64` words.
+/// - `block` must point to a 128-byte message block.
+/// - `params` must point to the 4 little-endian `u64` words `[t_lo, t_hi, f0, f1]`.
+#[cfg(target_os = "zkvm")]
+#[inline(always)]
+#[no_mangle]
+extern "C" fn native_blake2b_compress(h: *mut u64, block: *const u8, params: *const u64) {
+    openvm_platform::custom_insn_r!(
+        OPCODE,
+        BLAKE_FUNCT3,
+        BLAKE2B_COMPRESS_FUNCT7,
+        h,
+        block,
+        params
+    );
+}
+
+/// Native hook for the Blake3 compression function.
+///
+/// # Safety
+///
+/// The VM reads the chaining value from the first 8 words of `state` and writes the 16-word
+/// output to `state`.
+/// - `state` must point to 16 little-endian `u32` words.
+/// - `block` must point to a 64-byte message block.
+/// - `params` must point to the 4 little-endian `u32` words
+///   `[counter_lo, counter_hi, block_len, flags]`.
+#[cfg(target_os = "zkvm")]
+#[inline(always)]
+#[no_mangle]
+extern "C" fn native_blake3_compress(state: *mut u32, block: *const u8, params: *const u32) {
+    openvm_platform::custom_insn_r!(
+        OPCODE,
+        BLAKE_FUNCT3,
+        BLAKE3_COMPRESS_FUNCT7,
+        state,
+        block,
+        params
+    );
+}
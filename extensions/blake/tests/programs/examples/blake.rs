@@ -0,0 +1,42 @@
+#![cfg_attr(not(feature = "std"), no_main)]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use core::hint::black_box;
+
+use hex::FromHex;
+use openvm_blake_guest::{blake2b, blake3};
+
+openvm::entry!(main);
+
+pub fn main() {
+    // Spans several Blake2b blocks and several Blake3 chunks
+    let long_input: Vec<u8> = (0..3000u32).map(|i| (i % 251) as u8).collect();
+    let test_vectors = [
+        (
+            &b""[..],
+            "786a02f742015903c6c6fd852552d272912f4740e15847618a86e217f71f5419d25e1031afee585313896444934eb04b903a685b1448b755d56f701afe9be2ce",
+            "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262",
+        ),
+        (
+            &b"abc"[..],
+            "ba80a53f981c4d0d6a2797b69f12f6e94c212f14685ac4b74b12bb6fdbffa2d17d87c5392aab792dc252d5de4533cc9518d38aa8dbf1925ab92386edd4009923",
+            "6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85",
+        ),
+        (
+            &long_input[..],
+            "ce12518dcb627261263eb7e13284e1c423254b97c8d26f78a4efb5fd2000d75399518e3883cf19ef7ce7d674bda64f55bfced2151e838af10ca05ecc1862afff",
+            "5fade288bf27444bee55ba2babb98c3c922c1e84c2e445e7d1f6da24756f5060",
+        ),
+    ];
+    for (input, expected_blake2b, expected_blake3) in test_vectors.iter() {
+        let input = black_box(*input);
+        let expected_blake2b = Vec::from_hex(expected_blake2b).unwrap();
+        let expected_blake3 = Vec::from_hex(expected_blake3).unwrap();
+        if blake2b(input) != *expected_blake2b || blake3(input) != *expected_blake3 {
+            panic!();
+        }
+    }
+}
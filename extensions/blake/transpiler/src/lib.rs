@@ -0,0 +1,53 @@
+use openvm_blake_guest::{BLAKE2B_COMPRESS_FUNCT7, BLAKE3_COMPRESS_FUNCT7, BLAKE_FUNCT3, OPCODE};
+use openvm_instructions::{instruction::Instruction, UsizeOpcode};
+use openvm_instructions_derive::UsizeOpcode;
+use openvm_stark_backend::p3_field::PrimeField32;
+use openvm_transpiler::{util::from_r_type, EncodingRange, TranspilerExtension};
+use rrs_lib::instruction_formats::RType;
+use strum::{EnumCount, EnumIter, FromRepr};
+
+/// Compression of the block at `[rs1]` with the parameters at `[rs2]` into the state at `[rd]`.
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, EnumCount, EnumIter, FromRepr, UsizeOpcode,
+)]
+#[opcode_offset = 0x328]
+#[repr(usize)]
+#[allow(non_camel_case_types)]
+pub enum Rv32BlakeOpcode {
+    BLAKE2B_COMPRESS,
+    BLAKE3_COMPRESS,
+}
+
+#[derive(Default)]
+pub struct BlakeTranspilerExtension;
+
+impl<F: PrimeField32> TranspilerExtension<F> for BlakeTranspilerExtension {
+    fn process_custom(&self, instruction_stream: &[u32]) -> Option<(Instruction<F>, usize)> {
+        if instruction_stream.is_empty() {
+            return None;
+        }
+        let instruction_u32 = instruction_stream[0];
+        let opcode = (instruction_u32 & 0x7f) as u8;
+        let funct3 = ((instruction_u32 >> 12) & 0b111) as u8;
+
+        if (opcode, funct3) != (OPCODE, BLAKE_FUNCT3) {
+            return None;
+        }
+        let dec_insn = RType::new(instruction_u32);
+        let local_opcode = match dec_insn.funct7 as u8 {
+            BLAKE2B_COMPRESS_FUNCT7 => Rv32BlakeOpcode::BLAKE2B_COMPRESS,
+            BLAKE3_COMPRESS_FUNCT7 => Rv32BlakeOpcode::BLAKE3_COMPRESS,
+            _ => return None,
+        };
+        let instruction = from_r_type(local_opcode.with_default_offset(), 2, &dec_insn);
+        Some((instruction, 1))
+    }
+
+    fn encodings(&self) -> Option<Vec<EncodingRange>> {
+        Some(vec![EncodingRange::opcode(OPCODE)
+            .with_funct3(BLAKE_FUNCT3)
+            .with_funct7(
+                BLAKE2B_COMPRESS_FUNCT7..=BLAKE3_COMPRESS_FUNCT7,
+            )])
+    }
+}
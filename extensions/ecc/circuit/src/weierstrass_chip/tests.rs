@@ -87,6 +87,7 @@ fn test_add_ne() {
         modulus: secp256k1_coord_prime(),
         num_limbs: NUM_LIMBS,
         limb_bits: LIMB_BITS,
+        ..Default::default()
     };
     let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
     let bitwise_chip = Arc::new(BitwiseOperationLookupChip::<RV32_CELL_BITS>::new(
@@ -159,6 +160,7 @@ fn test_double() {
         modulus: secp256k1_coord_prime(),
         num_limbs: NUM_LIMBS,
         limb_bits: LIMB_BITS,
+        ..Default::default()
     };
     let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
     let bitwise_chip = Arc::new(BitwiseOperationLookupChip::<RV32_CELL_BITS>::new(
@@ -222,6 +224,7 @@ fn test_p256_double() {
         modulus: secp256r1_coord_prime(),
         num_limbs: NUM_LIMBS,
         limb_bits: LIMB_BITS,
+        ..Default::default()
     };
     let a = BigUint::from_str_radix(
         "ffffffff00000001000000000000000000000000fffffffffffffffffffffffc",
@@ -108,11 +108,13 @@ impl<F: PrimeField32> VmExtension<F> for WeierstrassExtension {
                 modulus: curve.modulus.clone(),
                 num_limbs: 32,
                 limb_bits: 8,
+                ..Default::default()
             };
             let config48 = ExprBuilderConfig {
                 modulus: curve.modulus.clone(),
                 num_limbs: 48,
                 limb_bits: 8,
+                ..Default::default()
             };
             // TODO: Better support for different limb sizes. Currently only 32 or 48 limbs are supported.
             if bytes <= 32 {
@@ -195,6 +197,10 @@ impl<F: PrimeField32> VmExtension<F> for WeierstrassExtension {
             phantom::DecompressHintSubEx::new(self.supported_curves.clone()),
             PhantomDiscriminant(EccPhantom::HintDecompress as u16),
         )?;
+        builder.add_phantom_sub_executor(
+            phantom::EcdsaHintSubEx::new(self.supported_curves.clone()),
+            PhantomDiscriminant(EccPhantom::HintEcdsa as u16),
+        )?;
 
         Ok(inventory)
     }
@@ -206,7 +212,7 @@ pub(crate) mod phantom {
     use eyre::bail;
     use num_bigint_dig::BigUint;
     use num_integer::Integer;
-    use num_traits::One;
+    use num_traits::{One, Zero};
     use openvm_circuit::{
         arch::{PhantomSubExecutor, Streams},
         system::memory::MemoryController,
@@ -232,55 +238,132 @@ pub(crate) mod phantom {
             b: F,
             c_upper: u16,
         ) -> eyre::Result<()> {
-            let c_idx = c_upper as usize;
-            if c_idx >= self.supported_curves.len() {
-                bail!(
-                    "Curve index {c_idx} out of range: {} supported curves",
-                    self.supported_curves.len()
-                );
-            }
-            let curve = &self.supported_curves[c_idx];
+            let curve = get_curve(&self.supported_curves, c_upper)?;
             let modulus_mod_4 = BigUint::from(3u8) & curve.modulus.clone();
             if modulus_mod_4 != BigUint::from(3u8) {
                 bail!("Currently only supporting curves with modulus congruent to 3 mod 4.");
                 // TODO: Tonelli-Shanks algorithm
             }
             let rs1 = unsafe_read_rv32_register(memory, a);
-            // TODO: Better support for different limb sizes
-            let num_limbs: usize = if curve.modulus.bits().div_ceil(8) <= 32 {
-                32
-            } else if curve.modulus.bits().div_ceil(8) <= 48 {
-                48
-            } else {
-                bail!("Modulus too large")
-            };
-            let mut x_limbs: Vec<u8> = Vec::with_capacity(num_limbs);
-            for i in 0..num_limbs {
-                let limb = memory.unsafe_read_cell(
-                    F::from_canonical_u32(RV32_MEMORY_AS),
-                    F::from_canonical_u32(rs1 + i as u32),
-                );
-                x_limbs.push(limb.as_canonical_u32() as u8);
-            }
-            let x = BigUint::from_bytes_le(&x_limbs);
+            let num_limbs = get_num_limbs(&curve.modulus)?;
+            let x = read_biguint(memory, rs1, num_limbs);
             let rs2 = unsafe_read_rv32_register(memory, b);
             let rec_id = memory.unsafe_read_cell(
                 F::from_canonical_u32(RV32_MEMORY_AS),
                 F::from_canonical_u32(rs2),
             );
             let y = decompress_point(x, rec_id.as_canonical_u32() & 1 == 1, curve);
-            let y_bytes = y
-                .to_bytes_le()
-                .into_iter()
-                .map(F::from_canonical_u8)
-                .chain(repeat(F::ZERO))
-                .take(num_limbs)
+            streams.hint_stream = biguint_to_limbs(&y, num_limbs).collect();
+            Ok(())
+        }
+    }
+
+    /// Hints `R = s^-1 (z G + r Q)` and a short `(a, b)` with `a = b r s^-1` modulo the
+    /// scalar modulus for hinted ECDSA verification. The points `[G, Q]` are read from `[rs1]`
+    /// and the scalars `[z, r, s]` from `[rs2]`.
+    #[derive(derive_new::new)]
+    pub struct EcdsaHintSubEx {
+        pub supported_curves: Vec<CurveConfig>,
+    }
+
+    impl<F: PrimeField32> PhantomSubExecutor<F> for EcdsaHintSubEx {
+        fn phantom_execute(
+            &mut self,
+            memory: &MemoryController<F>,
+            streams: &mut Streams<F>,
+            _: PhantomDiscriminant,
+            a: F,
+            b: F,
+            c_upper: u16,
+        ) -> eyre::Result<()> {
+            let curve = get_curve(&self.supported_curves, c_upper)?;
+            let num_limbs = get_num_limbs(&curve.modulus)?;
+            let scalar_limbs = get_num_limbs(&curve.scalar)?;
+
+            let rs1 = unsafe_read_rv32_register(memory, a);
+            let [gx, gy, qx, qy] = std::array::from_fn(|i| {
+                read_biguint(memory, rs1 + (i * num_limbs) as u32, num_limbs) % &curve.modulus
+            });
+            let rs2 = unsafe_read_rv32_register(memory, b);
+            let [z, r, s] = std::array::from_fn(|i| {
+                read_biguint(memory, rs2 + (i * scalar_limbs) as u32, scalar_limbs) % &curve.scalar
+            });
+
+            let n = &curve.scalar;
+            let (point, a, b, b_is_negative) = if s.is_zero() {
+                (None, BigUint::zero(), BigUint::zero(), false)
+            } else {
+                let s_inv = s.modpow(&(n - BigUint::from(2u8)), n);
+                let u1 = (&z * &s_inv) % n;
+                let u2 = (&r * &s_inv) % n;
+                let point = ec_add(
+                    ec_mul(&u1, Some((gx, gy)), curve),
+                    ec_mul(&u2, Some((qx, qy)), curve),
+                    curve,
+                );
+                let (a, b, b_is_negative) = half_gcd(n, u2, 4 * scalar_limbs);
+                (point, a, b, b_is_negative)
+            };
+            let (x, y) = point.unwrap_or((BigUint::zero(), BigUint::zero()));
+            streams.hint_stream = biguint_to_limbs(&x, num_limbs)
+                .chain(biguint_to_limbs(&y, num_limbs))
+                .chain(biguint_to_limbs(&a, scalar_limbs))
+                .chain(biguint_to_limbs(&b, scalar_limbs))
+                .chain([F::from_bool(b_is_negative), F::ZERO, F::ZERO, F::ZERO])
                 .collect();
-            streams.hint_stream = y_bytes;
             Ok(())
         }
     }
 
+    fn get_curve(supported_curves: &[CurveConfig], c_upper: u16) -> eyre::Result<&CurveConfig> {
+        let c_idx = c_upper as usize;
+        if c_idx >= supported_curves.len() {
+            bail!(
+                "Curve index {c_idx} out of range: {} supported curves",
+                supported_curves.len()
+            );
+        }
+        Ok(&supported_curves[c_idx])
+    }
+
+    // TODO: Better support for different limb sizes
+    fn get_num_limbs(modulus: &BigUint) -> eyre::Result<usize> {
+        let num_bytes = modulus.bits().div_ceil(8);
+        if num_bytes <= 32 {
+            Ok(32)
+        } else if num_bytes <= 48 {
+            Ok(48)
+        } else {
+            bail!("Modulus too large")
+        }
+    }
+
+    fn read_biguint<F: PrimeField32>(
+        memory: &MemoryController<F>,
+        ptr: u32,
+        num_limbs: usize,
+    ) -> BigUint {
+        let limbs: Vec<u8> = (0..num_limbs)
+            .map(|i| {
+                memory
+                    .unsafe_read_cell(
+                        F::from_canonical_u32(RV32_MEMORY_AS),
+                        F::from_canonical_u32(ptr + i as u32),
+                    )
+                    .as_canonical_u32() as u8
+            })
+            .collect();
+        BigUint::from_bytes_le(&limbs)
+    }
+
+    fn biguint_to_limbs<F: PrimeField32>(x: &BigUint, num_limbs: usize) -> impl Iterator<Item = F> {
+        x.to_bytes_le()
+            .into_iter()
+            .map(F::from_canonical_u8)
+            .chain(repeat(F::ZERO))
+            .take(num_limbs)
+    }
+
     fn decompress_point(x: BigUint, is_y_odd: bool, curve: &CurveConfig) -> BigUint {
         let alpha = ((&x * &x * &x) + (&x * &curve.a) + &curve.b) % &curve.modulus;
         let beta = mod_sqrt(alpha, &curve.modulus);
@@ -295,4 +378,65 @@ pub(crate) mod phantom {
         let exponent = (modulus + BigUint::one()) >> 2;
         x.modpow(&exponent, modulus)
     }
+
+    /// Affine point, where `None` is the identity.
+    type EcPoint = Option<(BigUint, BigUint)>;
+
+    fn ec_add(p1: EcPoint, p2: EcPoint, curve: &CurveConfig) -> EcPoint {
+        let ((x1, y1), (x2, y2)) = match (p1, p2) {
+            (None, p) | (p, None) => return p,
+            (Some(p1), Some(p2)) => (p1, p2),
+        };
+        let p = &curve.modulus;
+        let lambda = if x1 == x2 {
+            if (&y1 + &y2) % p == BigUint::zero() {
+                return None;
+            }
+            let num = (BigUint::from(3u8) * &x1 * &x1 + &curve.a) % p;
+            num * mod_inverse(&(BigUint::from(2u8) * &y1), p) % p
+        } else {
+            let num = (&y2 + p - &y1) % p;
+            num * mod_inverse(&((&x2 + p - &x1) % p), p) % p
+        };
+        let x3 = (&lambda * &lambda + BigUint::from(2u8) * p - &x1 - &x2) % p;
+        let y3 = (lambda * ((&x1 + p - &x3) % p) + p - &y1) % p;
+        Some((x3, y3))
+    }
+
+    fn ec_mul(k: &BigUint, point: EcPoint, curve: &CurveConfig) -> EcPoint {
+        let mut res = None;
+        for byte in k.to_bytes_be() {
+            for i in (0..8).rev() {
+                res = ec_add(res.clone(), res, curve);
+                if (byte >> i) & 1 == 1 {
+                    res = ec_add(res, point.clone(), curve);
+                }
+            }
+        }
+        res
+    }
+
+    /// Inverse modulo a prime `p`.
+    fn mod_inverse(x: &BigUint, p: &BigUint) -> BigUint {
+        x.modpow(&(p - BigUint::from(2u8)), p)
+    }
+
+    /// Runs the extended Euclidean algorithm on `(n, u)` until the remainder fits in
+    /// `half_bits` bits. Returns `(a, |b|, b < 0)` with `a = b u` modulo `n`, where both `a`
+    /// and `|b|` fit in `half_bits` bits when `n < 2^(2 half_bits)`.
+    fn half_gcd(n: &BigUint, u: BigUint, half_bits: usize) -> (BigUint, BigUint, bool) {
+        let bound = BigUint::one() << half_bits;
+        // Remainders and the magnitudes of their coefficients, whose signs alternate.
+        let (mut r0, mut r1) = (n.clone(), u);
+        let (mut t0, mut t1) = (BigUint::zero(), BigUint::one());
+        let mut is_negative = false;
+        while r1 >= bound {
+            let (q, rem) = r0.div_rem(&r1);
+            let t2 = &t0 + &q * &t1;
+            (r0, r1) = (r1, rem);
+            (t0, t1) = (t1, t2);
+            is_negative = !is_negative;
+        }
+        (r1, t1, is_negative)
+    }
 }
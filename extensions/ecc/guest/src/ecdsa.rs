@@ -1,11 +1,15 @@
+use alloc::{boxed::Box, vec::Vec};
 use core::ops::{Add, AddAssign, Mul};
 
-use ecdsa::{self, hazmat::bits2field, Error, RecoveryId, Result};
-use elliptic_curve::PrimeCurve;
+use ecdsa::{
+    self, hazmat::bits2field, signature::hazmat::PrehashVerifier, Error, RecoveryId, Result,
+    Signature, SignatureSize,
+};
+use elliptic_curve::{generic_array::ArrayLength, PrimeCurve};
 use openvm_algebra_guest::{DivUnsafe, IntMod, Reduce};
 
 use crate::{
-    weierstrass::{IntrinsicCurve, WeierstrassPoint},
+    weierstrass::{CachedMulTable, IntrinsicCurve, WeierstrassPoint},
     CyclicGroup, Group,
 };
 
@@ -31,7 +35,65 @@ impl<C: IntrinsicCurve> PublicKey<C> {
     }
 }
 
+/// Curves whose ECDSA signatures can be verified with [VerifyingKey::verify_prehashed_hinted].
+pub trait HintedEcdsaCurve: IntrinsicCurve {
+    /// `2^(4 * Scalar::NUM_LIMBS) * GENERATOR`, i.e., the generator multiplied by 2 to the
+    /// power of half of the scalar bits.
+    const GENERATOR_HALF_SHIFT: Self::Point;
+}
+
 impl<C: IntrinsicCurve> VerifyingKey<C> {
+    /// Initialize from an affine point. Fails if the point is the identity.
+    pub fn from_affine(point: <C as IntrinsicCurve>::Point) -> Result<Self> {
+        if point.is_identity() {
+            return Err(Error::new());
+        }
+        Ok(Self {
+            inner: PublicKey { point },
+        })
+    }
+
+    /// Initialize from a SEC1-encoded public key, either compressed or uncompressed, with
+    /// big-endian coordinates.
+    ///
+    /// ## Panics
+    /// If the key is compressed and the point cannot be decompressed. See
+    /// [WeierstrassPoint::decompress].
+    pub fn from_sec1_bytes(bytes: &[u8]) -> Result<Self>
+    where
+        for<'a> &'a Coordinate<C>: Mul<&'a Coordinate<C>, Output = Coordinate<C>>,
+    {
+        let num_limbs = Coordinate::<C>::NUM_LIMBS;
+        let (&tag, coords) = bytes.split_first().ok_or_else(Error::new)?;
+        let point = match tag {
+            0x02 | 0x03 if coords.len() == num_limbs => {
+                let x = Coordinate::<C>::from_be_bytes(coords);
+                x.assert_unique();
+                C::Point::decompress(x, &(tag & 1))
+            }
+            0x04 if coords.len() == 2 * num_limbs => {
+                let x = Coordinate::<C>::from_be_bytes(&coords[..num_limbs]);
+                let y = Coordinate::<C>::from_be_bytes(&coords[num_limbs..]);
+                x.assert_unique();
+                y.assert_unique();
+                C::Point::from_xy_nonidentity(x, y).ok_or_else(Error::new)?
+            }
+            _ => return Err(Error::new()),
+        };
+        Self::from_affine(point)
+    }
+
+    /// Serialize as a compressed SEC1-encoded public key with a big-endian `x` coordinate.
+    pub fn to_sec1_bytes(&self) -> Box<[u8]> {
+        let (x, y) = (self.inner.point.x(), self.inner.point.y());
+        y.assert_unique();
+        let tag = 0x02 | (y.as_le_bytes()[0] & 1);
+        let mut bytes = Vec::with_capacity(1 + Coordinate::<C>::NUM_LIMBS);
+        bytes.push(tag);
+        bytes.extend_from_slice(x.to_be_bytes().as_ref());
+        bytes.into_boxed_slice()
+    }
+
     pub fn as_affine(&self) -> &<C as IntrinsicCurve>::Point {
         &self.inner.point
     }
@@ -57,22 +119,7 @@ where
         for<'a> &'a C::Point: Add<&'a C::Point, Output = C::Point>,
         for<'a> &'a Coordinate<C>: Mul<&'a Coordinate<C>, Output = Coordinate<C>>,
     {
-        // This should get compiled out:
-        assert!(Scalar::<C>::NUM_LIMBS <= Coordinate::<C>::NUM_LIMBS);
-        // IntMod limbs are currently always bytes
-        assert_eq!(sig.len(), <C as IntrinsicCurve>::Scalar::NUM_LIMBS * 2);
-        // Signature is default encoded in big endian bytes
-        let (r_be, s_be) = sig.split_at(<C as IntrinsicCurve>::Scalar::NUM_LIMBS);
-        // Note: Scalar internally stores using little endian
-        let r = Scalar::<C>::from_be_bytes(r_be);
-        let s = Scalar::<C>::from_be_bytes(s_be);
-        // The PartialEq implementation of Scalar: IntMod will constrain `r, s`
-        // are in the canonical unique form (i.e., less than the modulus).
-        assert_ne!(r, Scalar::<C>::ZERO);
-        assert_ne!(s, Scalar::<C>::ZERO);
-
-        // TODO: don't use bits2field from ::ecdsa
-        let z = Scalar::<C>::from_be_bytes(bits2field::<C>(prehash).unwrap().as_ref());
+        let (z, r, s) = parse_prehashed::<C>(prehash, sig);
 
         // `r` is in the Scalar field, we now possibly add C::ORDER to it to get `x`
         // in the Coordinate field.
@@ -95,6 +142,26 @@ where
         VerifyingKey { inner: public_key }
     }
 
+    /// Recovers the verifying key from the signature `sig` of `prehash` like
+    /// [Self::recover_from_prehash_noverify], and verifies the signature with it.
+    ///
+    /// ## Panics
+    /// If the public key cannot be recovered from the given input.
+    pub fn recover_from_prehash(
+        prehash: &[u8],
+        sig: &[u8],
+        recovery_id: RecoveryId,
+    ) -> Result<VerifyingKey<C>>
+    where
+        for<'a> &'a C::Point: Add<&'a C::Point, Output = C::Point>,
+        for<'a> &'a Coordinate<C>: Mul<&'a Coordinate<C>, Output = Coordinate<C>>,
+        for<'a> &'a Scalar<C>: DivUnsafe<&'a Scalar<C>, Output = Scalar<C>>,
+    {
+        let key = Self::recover_from_prehash_noverify(prehash, sig, recovery_id);
+        key.clone().verify_prehashed(prehash, sig)?;
+        Ok(key)
+    }
+
     // Ref: https://docs.rs/ecdsa/latest/src/ecdsa/hazmat.rs.html#270
     #[allow(non_snake_case)]
     pub fn verify_prehashed(self, prehash: &[u8], sig: &[u8]) -> Result<()>
@@ -102,24 +169,7 @@ where
         for<'a> &'a C::Point: Add<&'a C::Point, Output = C::Point>,
         for<'a> &'a Scalar<C>: DivUnsafe<&'a Scalar<C>, Output = Scalar<C>>,
     {
-        // This should get compiled out:
-        assert!(Scalar::<C>::NUM_LIMBS <= Coordinate::<C>::NUM_LIMBS);
-        // IntMod limbs are currently always bytes
-        assert_eq!(sig.len(), Scalar::<C>::NUM_LIMBS * 2);
-        // Signature is default encoded in big endian bytes
-        let (r_be, s_be) = sig.split_at(<C as IntrinsicCurve>::Scalar::NUM_LIMBS);
-        // Note: Scalar internally stores using little endian
-        let r = Scalar::<C>::from_be_bytes(r_be);
-        let s = Scalar::<C>::from_be_bytes(s_be);
-        // The PartialEq implementation of Scalar: IntMod will constrain `r, s`
-        // are in the canonical unique form (i.e., less than the modulus).
-        assert_ne!(r, Scalar::<C>::ZERO);
-        assert_ne!(s, Scalar::<C>::ZERO);
-
-        // TODO: don't use bits2field from ::ecdsa
-        let z = <C as IntrinsicCurve>::Scalar::from_be_bytes(
-            bits2field::<C>(prehash).unwrap().as_ref(),
-        );
+        let (z, r, s) = parse_prehashed::<C>(prehash, sig);
 
         let u1 = z.div_unsafe(&s);
         let u2 = (&r).div_unsafe(&s);
@@ -140,3 +190,133 @@ where
         }
     }
 }
+
+impl<C> VerifyingKey<C>
+where
+    C: PrimeCurve + HintedEcdsaCurve,
+{
+    /// Verifies the signature `sig` of `prehash` like [Self::verify_prehashed], but with the
+    /// scalar inversion, multiplications and point additions done by the host. The host hints
+    /// the point `R = u1 G + u2 Q` together with scalars `a, b` of half the size of the scalar
+    /// field such that `a = b u2`, and the guest checks `b R = (b u1) G + a Q`. Splitting
+    /// `b u1` in halves, this is a multi-scalar multiplication with half-size scalars, which
+    /// takes half as many doublings as computing `R`.
+    ///
+    /// Ref: <https://eprint.iacr.org/2020/454>
+    #[allow(non_snake_case)]
+    pub fn verify_prehashed_hinted(&self, prehash: &[u8], sig: &[u8]) -> Result<()>
+    where
+        for<'a> &'a Coordinate<C>: Mul<&'a Coordinate<C>, Output = Coordinate<C>>,
+    {
+        let (z, r, s) = parse_prehashed::<C>(prehash, sig);
+        let num_limbs = Scalar::<C>::NUM_LIMBS;
+        let half_limbs = num_limbs / 2;
+        let coord_limbs = Coordinate::<C>::NUM_LIMBS;
+
+        let G = C::Point::GENERATOR;
+        let Q = self.inner.point.clone();
+        let scalars = [z.as_le_bytes(), r.as_le_bytes(), s.as_le_bytes()].concat();
+        // Hints are stored a word at a time, so the buffer must be word aligned
+        let mut hint_words = vec![0u32; (2 * coord_limbs + 2 * num_limbs) / 4 + 1];
+        C::Point::hint_ecdsa(&[G.clone(), Q.clone()], &scalars, &mut hint_words);
+        let hint: Vec<u8> = hint_words
+            .into_iter()
+            .flat_map(|word| word.to_le_bytes())
+            .collect();
+        let (x_bytes, hint) = hint.split_at(coord_limbs);
+        let (y_bytes, hint) = hint.split_at(coord_limbs);
+        let (a_bytes, hint) = hint.split_at(num_limbs);
+        let (b_bytes, b_sign) = hint.split_at(num_limbs);
+
+        let x = Coordinate::<C>::from_le_bytes(x_bytes);
+        // Must assert unique so the reduction of `x` is well defined
+        x.assert_unique();
+        if Scalar::<C>::reduce_le_bytes(x.as_le_bytes()) != r {
+            return Err(Error::new());
+        }
+        let y = Coordinate::<C>::from_le_bytes(y_bytes);
+        let R = C::Point::from_xy_nonidentity(x, y).ok_or_else(Error::new)?;
+
+        // Only the lower halves of `a` and `|b|` are used in the multiplication below
+        if a_bytes[half_limbs..]
+            .iter()
+            .chain(&b_bytes[half_limbs..])
+            .any(|&byte| byte != 0)
+        {
+            return Err(Error::new());
+        }
+        let b_is_negative = b_sign[0] != 0;
+        let a = Scalar::<C>::from_le_bytes(a_bytes);
+        let mut b = Scalar::<C>::from_le_bytes(b_bytes);
+        if b_is_negative {
+            b = -b;
+        }
+        // Since the group has prime order, `b != 0` makes `b R = (b u1) G + a Q` equivalent to
+        // `R = u1 G + u2 Q`
+        if b == Scalar::<C>::ZERO || a * &s != b.clone() * &r {
+            return Err(Error::new());
+        }
+        let c = (b * &z).div_unsafe(&s);
+        let (c_lo, c_hi) = c.as_le_bytes().split_at(half_limbs);
+
+        // (b u1) G + a Q - b R
+        let bases = [
+            G,
+            C::GENERATOR_HALF_SHIFT,
+            Q,
+            if b_is_negative { R } else { -R },
+        ];
+        let table = CachedMulTable::<C>::new_with_prime_order(&bases, 4);
+        let sum = table.windowed_mul_le_bytes(&[
+            c_lo,
+            c_hi,
+            &a_bytes[..half_limbs],
+            &b_bytes[..half_limbs],
+        ]);
+        if sum.is_identity() {
+            Ok(())
+        } else {
+            Err(Error::new())
+        }
+    }
+}
+
+impl<C> PrehashVerifier<Signature<C>> for VerifyingKey<C>
+where
+    C: PrimeCurve + HintedEcdsaCurve,
+    SignatureSize<C>: ArrayLength<u8>,
+    for<'a> &'a Coordinate<C>: Mul<&'a Coordinate<C>, Output = Coordinate<C>>,
+{
+    fn verify_prehash(&self, prehash: &[u8], signature: &Signature<C>) -> Result<()> {
+        self.verify_prehashed_hinted(prehash, &signature.to_bytes())
+    }
+}
+
+/// Parses the big-endian scalars `r, s` of the signature `sig`, and the scalar `z` of the
+/// message digest `prehash`. Returns `(z, r, s)`.
+///
+/// ## Panics
+/// If `r` or `s` is zero or not less than the scalar modulus.
+fn parse_prehashed<C>(prehash: &[u8], sig: &[u8]) -> (Scalar<C>, Scalar<C>, Scalar<C>)
+where
+    C: PrimeCurve + IntrinsicCurve,
+{
+    // This should get compiled out:
+    assert!(Scalar::<C>::NUM_LIMBS <= Coordinate::<C>::NUM_LIMBS);
+    // IntMod limbs are currently always bytes
+    assert_eq!(sig.len(), Scalar::<C>::NUM_LIMBS * 2);
+    // Signature is default encoded in big endian bytes
+    let (r_be, s_be) = sig.split_at(<C as IntrinsicCurve>::Scalar::NUM_LIMBS);
+    // Note: Scalar internally stores using little endian
+    let r = Scalar::<C>::from_be_bytes(r_be);
+    let s = Scalar::<C>::from_be_bytes(s_be);
+    // The PartialEq implementation of Scalar: IntMod will constrain `r, s`
+    // are in the canonical unique form (i.e., less than the modulus).
+    assert_ne!(r, Scalar::<C>::ZERO);
+    assert_ne!(s, Scalar::<C>::ZERO);
+
+    // TODO: don't use bits2field from ::ecdsa
+    let z =
+        <C as IntrinsicCurve>::Scalar::from_be_bytes(bits2field::<C>(prehash).unwrap().as_ref());
+    (z, r, s)
+}
@@ -0,0 +1,7 @@
+//! Drop-in replacements for the verification side of `k256::ecdsa`. Signatures are verified
+//! with [VerifyingKey::verify_prehashed_hinted](crate::ecdsa::VerifyingKey::verify_prehashed_hinted)
+//! through the [PrehashVerifier](::ecdsa::signature::hazmat::PrehashVerifier) trait.
+pub use ::k256::ecdsa::{Error, RecoveryId, Signature};
+
+/// ECDSA/secp256k1 verification key (i.e. public key).
+pub type VerifyingKey = crate::ecdsa::VerifyingKey<::k256::Secp256k1>;
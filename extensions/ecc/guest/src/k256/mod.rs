@@ -8,7 +8,13 @@ use num_bigint_dig::BigUint;
 use openvm_algebra_guest::IntMod;
 
 use super::group::{CyclicGroup, Group};
-use crate::weierstrass::{CachedMulTable, IntrinsicCurve};
+use crate::{
+    ecdsa::HintedEcdsaCurve,
+    weierstrass::{CachedMulTable, IntrinsicCurve},
+};
+
+/// ECDSA over secp256k1 with an API compatible with `k256::ecdsa`.
+pub mod ecdsa;
 
 #[cfg(not(target_os = "zkvm"))]
 lazy_static! {
@@ -75,3 +81,14 @@ impl IntrinsicCurve for k256::Secp256k1 {
         }
     }
 }
+
+impl HintedEcdsaCurve for k256::Secp256k1 {
+    const GENERATOR_HALF_SHIFT: Self::Point = Secp256k1Point {
+        x: Secp256k1Coord::from_const_bytes(hex!(
+            "DAC0C49E4C447B1B35A33E7278568CE82E161F98ADC13992335F3BF6D2B9688F"
+        )),
+        y: Secp256k1Coord::from_const_bytes(hex!(
+            "82FF1F5079BF3CF2FD0B5195FE2CEABB5D21BEB6C2901DDE863906BA2D9F2A66"
+        )),
+    };
+}
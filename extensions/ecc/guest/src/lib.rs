@@ -38,6 +38,7 @@ pub enum SwBaseFunct7 {
     SwDouble,
     SwSetup,
     HintDecompress,
+    HintEcdsa,
 }
 
 impl SwBaseFunct7 {
@@ -89,6 +89,18 @@ pub trait WeierstrassPoint: Group {
     /// This is only a hint, and the returned `y` does not guarantee any of the above properties.
     /// They must be checked separately. Normal users should use `decompress` directly.
     fn hint_decompress(x: &Self::Coordinate, rec_id: &u8) -> Self::Coordinate;
+
+    /// Given `points = [G, Q]` and the little-endian scalars `[z, r, s]` concatenated in
+    /// `scalars`, fills the little-endian words of `hint` with the point `R = s^-1 (z G + r Q)`,
+    /// followed by `a` and `|b|` as scalars and a word which is 1 if and only if `b` is
+    /// negative, where `a = b r s^-1` modulo the scalar modulus and `a, |b|` fit in half of the
+    /// scalar bytes. The point `R` is the identity if it does not exist.
+    ///
+    /// This is only a hint, and the hinted values do not guarantee any of the above properties.
+    /// They must be checked separately. Normal users should use
+    /// [VerifyingKey::verify_prehashed_hinted](crate::ecdsa::VerifyingKey::verify_prehashed_hinted)
+    /// directly.
+    fn hint_ecdsa(points: &[Self; 2], scalars: &[u8], hint: &mut [u32]);
 }
 
 /// A trait for elliptic curves that bridges the openvm types and external types with CurveArithmetic etc.
@@ -182,16 +194,27 @@ impl<'a, C: IntrinsicCurve> CachedMulTable<'a, C> {
     /// For implementation simplicity, currently only implemented when
     /// `window_bits` divides 8 (number of bits in a byte).
     pub fn windowed_mul(&self, scalars: &[C::Scalar]) -> C::Point {
+        let scalars: Vec<&[u8]> = scalars.iter().map(|scalar| scalar.as_le_bytes()).collect();
+        self.windowed_mul_le_bytes(&scalars)
+    }
+
+    /// Computes `sum scalars[i] * bases[i]`, where the scalars are given as little-endian
+    /// bytes of the same length, which may be shorter than `C::Scalar::NUM_LIMBS`.
+    ///
+    /// Same restrictions on `window_bits` as [Self::windowed_mul].
+    pub fn windowed_mul_le_bytes(&self, scalars: &[&[u8]]) -> C::Point {
         assert_eq!(8 % self.window_bits, 0);
         assert_eq!(scalars.len(), self.bases.len());
+        let num_limbs = scalars.first().map_or(0, |scalar| scalar.len());
+        assert!(scalars.iter().all(|scalar| scalar.len() == num_limbs));
         let windows_per_byte = 8 / self.window_bits;
 
-        let num_windows = C::Scalar::NUM_LIMBS * windows_per_byte;
+        let num_windows = num_limbs * windows_per_byte;
         let mask = (1u8 << self.window_bits) - 1;
 
         // The current byte index (little endian) at the current step of the
         // windowed method, across all scalars.
-        let mut limb_idx = C::Scalar::NUM_LIMBS;
+        let mut limb_idx = num_limbs;
         // The current bit (little endian) within the current byte of the windowed
         // method. The window will look at bits `bit_idx..bit_idx + window_bits`.
         // bit_idx will always be in range [0, 8)
@@ -213,7 +236,7 @@ impl<'a, C: IntrinsicCurve> CachedMulTable<'a, C> {
                 }
             }
             for (base_idx, scalar) in scalars.iter().enumerate() {
-                let scalar = (scalar.as_le_bytes()[limb_idx] >> bit_idx) & mask;
+                let scalar = (scalar[limb_idx] >> bit_idx) & mask;
                 let summand = self.get_multiple(base_idx, scalar as usize);
                 // handles identity
                 res.add_assign(summand);
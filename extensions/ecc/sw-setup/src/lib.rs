@@ -75,12 +75,14 @@ pub fn sw_declare(input: TokenStream) -> TokenStream {
         create_extern_func!(sw_add_ne_extern_func);
         create_extern_func!(sw_double_extern_func);
         create_extern_func!(hint_decompress_extern_func);
+        create_extern_func!(hint_ecdsa_extern_func);
 
         let result = TokenStream::from(quote::quote_spanned! { span.into() =>
             extern "C" {
                 fn #sw_add_ne_extern_func(rd: usize, rs1: usize, rs2: usize);
                 fn #sw_double_extern_func(rd: usize, rs1: usize);
                 fn #hint_decompress_extern_func(rs1: usize, rs2: usize);
+                fn #hint_ecdsa_extern_func(rs1: usize, rs2: usize);
             }
 
             #[derive(Eq, PartialEq, Clone, Debug, serde::Serialize, serde::Deserialize)]
@@ -267,6 +269,26 @@ pub fn sw_declare(input: TokenStream) -> TokenStream {
                         }
                     }
                 }
+
+                fn hint_ecdsa(points: &[Self; 2], scalars: &[u8], hint: &mut [u32]) {
+                    #[cfg(not(target_os = "zkvm"))]
+                    {
+                        unimplemented!()
+                    }
+                    #[cfg(target_os = "zkvm")]
+                    {
+                        use openvm::platform as openvm_platform; // needed for hint_store_u32!
+
+                        unsafe {
+                            #hint_ecdsa_extern_func(points.as_ptr() as usize, scalars.as_ptr() as usize);
+                            let mut ptr = hint.as_mut_ptr() as *mut u8;
+                            for _ in 0..hint.len() {
+                                openvm_rv32im_guest::hint_store_u32!(ptr, 0);
+                                ptr = ptr.add(4);
+                            }
+                        }
+                    }
+                }
             }
 
             impl Group for #struct_name {
@@ -454,6 +476,8 @@ pub fn sw_init(input: TokenStream) -> TokenStream {
             &format!("hint_decompress_extern_func_{}", str_path),
             span.into(),
         );
+        let hint_ecdsa_extern_func =
+            syn::Ident::new(&format!("hint_ecdsa_extern_func_{}", str_path), span.into());
         externs.push(quote::quote_spanned! { span.into() =>
             #[no_mangle]
             extern "C" fn #add_ne_extern_func(rd: usize, rs1: usize, rs2: usize) {
@@ -495,6 +519,21 @@ pub fn sw_init(input: TokenStream) -> TokenStream {
                     );
                 }
             }
+
+            #[no_mangle]
+            extern "C" fn #hint_ecdsa_extern_func(rs1: usize, rs2: usize) {
+                unsafe {
+                    core::arch::asm!(
+                        ".insn r {opcode}, {funct3}, {funct7}, x0, {rs1}, {rs2}",
+                        opcode = const OPCODE,
+                        funct3 = const SW_FUNCT3 as usize,
+                        funct7 = const SwBaseFunct7::HintEcdsa as usize + #ec_idx
+                            * (SwBaseFunct7::SHORT_WEIERSTRASS_MAX_KINDS as usize),
+                        rs1 = in(reg) rs1,
+                        rs2 = in(reg) rs2
+                    );
+                }
+            }
         });
 
         let setup_function = syn::Ident::new(&format!("setup_sw_{}", str_path), span.into());
@@ -3,18 +3,18 @@
 
 use core::{hint::black_box, ptr::slice_from_raw_parts};
 
+use hex_literal::hex;
+use k256::{
+    ecdsa::{self, signature::hazmat::PrehashVerifier, RecoveryId},
+    Secp256k1,
+};
 use openvm_ecc_guest::{
     algebra::IntMod,
     ecdsa::VerifyingKey,
-    k256::{Secp256k1Coord, Secp256k1Point},
+    k256::{ecdsa as k256_ecdsa, Secp256k1Coord, Secp256k1Point},
     weierstrass::WeierstrassPoint,
 };
 use openvm_keccak256_guest::keccak256;
-use hex_literal::hex;
-use k256::{
-    ecdsa::{self, RecoveryId},
-    Secp256k1,
-};
 openvm::entry!(main);
 
 openvm_algebra_moduli_setup::moduli_init! {
@@ -58,4 +58,22 @@ pub fn main() {
     recovered_key
         .verify_prehashed(&prehash, &signature)
         .unwrap();
+
+    // Same verification with the `k256::ecdsa` compatible API, which uses hints
+    let verifying_key = k256_ecdsa::VerifyingKey::from_sec1_bytes(&hex!(
+        "0200866db99873b09fc2fb1e3ba549b156e96d1a567e3284f5f0e859a83320cb8b"
+    ))
+    .unwrap();
+    let signature = k256_ecdsa::Signature::from_slice(&signature).unwrap();
+    verifying_key.verify_prehash(&prehash, &signature).unwrap();
+    assert_eq!(
+        verifying_key.to_sec1_bytes().as_ref(),
+        expected_key.compress().as_bytes()
+    );
+
+    // A signature of a different message must not verify
+    let other_prehash = keccak256(black_box(b"another message"));
+    assert!(verifying_key
+        .verify_prehash(&other_prehash, &signature)
+        .is_err());
 }
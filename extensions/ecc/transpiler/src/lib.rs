@@ -26,6 +26,7 @@ pub enum Rv32WeierstrassOpcode {
 #[repr(u16)]
 pub enum EccPhantom {
     HintDecompress = 0x40,
+    HintEcdsa = 0x41,
 }
 
 #[derive(Default)]
@@ -57,11 +58,16 @@ impl<F: PrimeField32> TranspilerExtension<F> for EccTranspilerExtension {
             let curve_idx =
                 ((dec_insn.funct7 as u8) / SwBaseFunct7::SHORT_WEIERSTRASS_MAX_KINDS) as usize;
             let curve_idx_shift = curve_idx * Rv32WeierstrassOpcode::COUNT;
-            if let Some(SwBaseFunct7::HintDecompress) = SwBaseFunct7::from_repr(base_funct7) {
+            let phantom = match SwBaseFunct7::from_repr(base_funct7) {
+                Some(SwBaseFunct7::HintDecompress) => Some(EccPhantom::HintDecompress),
+                Some(SwBaseFunct7::HintEcdsa) => Some(EccPhantom::HintEcdsa),
+                _ => None,
+            };
+            if let Some(phantom) = phantom {
                 assert_eq!(dec_insn.rd, 0);
                 return Some((
                     Instruction::phantom(
-                        PhantomDiscriminant(EccPhantom::HintDecompress as u16),
+                        PhantomDiscriminant(phantom as u16),
                         F::from_canonical_usize(RV32_REGISTER_NUM_LIMBS * dec_insn.rs1),
                         F::from_canonical_usize(RV32_REGISTER_NUM_LIMBS * dec_insn.rs2),
                         curve_idx as u16,
@@ -81,12 +81,12 @@ impl<AB: InteractionBuilder> Air<AB> for KeccakVmAir {
         self.constrain_absorb(builder, local, next);
         let start_read_timestamp = self.eval_instruction(builder, local, &mem.register_aux);
         let start_write_timestamp =
-            self.constrain_input_read(builder, local, start_read_timestamp, &mem.absorb_reads);
+            self.constrain_input_read(builder, local, start_read_timestamp, mem.absorb_reads());
         self.constrain_output_write(
             builder,
             local,
             start_write_timestamp.clone(),
-            &mem.digest_writes,
+            mem.digest_writes(),
         );
 
         self.constrain_block_transition(builder, local, next, start_write_timestamp);
@@ -1,4 +1,7 @@
-use core::mem::size_of;
+use core::{
+    borrow::{Borrow, BorrowMut},
+    mem::size_of,
+};
 
 use openvm_circuit::system::memory::offline_checker::{MemoryReadAuxCols, MemoryWriteAuxCols};
 use openvm_circuit_primitives::utils::assert_array_eq;
@@ -97,8 +100,11 @@ pub struct KeccakSpongeCols<T> {
 #[derive(Clone, Debug, AlignedBorrow)]
 pub struct KeccakMemoryCols<T> {
     pub register_aux: [MemoryReadAuxCols<T, RV32_REGISTER_NUM_LIMBS>; KECCAK_REGISTER_READS],
-    pub absorb_reads: [MemoryReadAuxCols<T, KECCAK_WORD_SIZE>; KECCAK_ABSORB_READS],
-    pub digest_writes: [MemoryWriteAuxCols<T, KECCAK_WORD_SIZE>; KECCAK_DIGEST_WRITES],
+    /// The input block is only read on the first round and the digest is only written on the
+    /// last round, so the aux columns of the absorb reads and of the digest writes share these
+    /// cells. Use [KeccakMemoryCols::absorb_reads] and [KeccakMemoryCols::digest_writes] to
+    /// access them.
+    pub block_aux: [T; NUM_KECCAK_BLOCK_AUX_COLS],
     /// The input bytes are batch read in blocks of [KECCAK_WORD_SIZE] bytes. However
     /// if the input length is not a multiple of [KECCAK_WORD_SIZE], we read into
     /// `partial_block` more bytes than we need. On the other hand `block_bytes` expects
@@ -108,6 +114,52 @@ pub struct KeccakMemoryCols<T> {
     pub partial_block: [T; KECCAK_WORD_SIZE - 1],
 }
 
+/// Aux columns of the absorb reads, which are only used on the first round of a block.
+#[repr(C)]
+#[derive(Clone, Debug, AlignedBorrow)]
+pub struct KeccakAbsorbReadAuxCols<T> {
+    pub reads: [MemoryReadAuxCols<T, KECCAK_WORD_SIZE>; KECCAK_ABSORB_READS],
+}
+
+/// Aux columns of the digest writes, which are only used on the last round of a block.
+#[repr(C)]
+#[derive(Clone, Debug, AlignedBorrow)]
+pub struct KeccakDigestWriteAuxCols<T> {
+    pub writes: [MemoryWriteAuxCols<T, KECCAK_WORD_SIZE>; KECCAK_DIGEST_WRITES],
+}
+
+impl<T> KeccakMemoryCols<T> {
+    pub fn absorb_reads(&self) -> &[MemoryReadAuxCols<T, KECCAK_WORD_SIZE>; KECCAK_ABSORB_READS] {
+        let cols: &KeccakAbsorbReadAuxCols<T> =
+            self.block_aux[..NUM_KECCAK_ABSORB_READ_AUX_COLS].borrow();
+        &cols.reads
+    }
+
+    pub fn absorb_reads_mut(
+        &mut self,
+    ) -> &mut [MemoryReadAuxCols<T, KECCAK_WORD_SIZE>; KECCAK_ABSORB_READS] {
+        let cols: &mut KeccakAbsorbReadAuxCols<T> =
+            self.block_aux[..NUM_KECCAK_ABSORB_READ_AUX_COLS].borrow_mut();
+        &mut cols.reads
+    }
+
+    pub fn digest_writes(
+        &self,
+    ) -> &[MemoryWriteAuxCols<T, KECCAK_WORD_SIZE>; KECCAK_DIGEST_WRITES] {
+        let cols: &KeccakDigestWriteAuxCols<T> =
+            self.block_aux[..NUM_KECCAK_DIGEST_WRITE_AUX_COLS].borrow();
+        &cols.writes
+    }
+
+    pub fn digest_writes_mut(
+        &mut self,
+    ) -> &mut [MemoryWriteAuxCols<T, KECCAK_WORD_SIZE>; KECCAK_DIGEST_WRITES] {
+        let cols: &mut KeccakDigestWriteAuxCols<T> =
+            self.block_aux[..NUM_KECCAK_DIGEST_WRITE_AUX_COLS].borrow_mut();
+        &mut cols.writes
+    }
+}
+
 impl<T: Copy> KeccakVmCols<T> {
     pub const fn remaining_len(&self) -> T {
         self.instruction.remaining_len
@@ -153,3 +205,11 @@ pub const NUM_KECCAK_VM_COLS: usize = size_of::<KeccakVmCols<u8>>();
 pub const NUM_KECCAK_INSTRUCTION_COLS: usize = size_of::<KeccakInstructionCols<u8>>();
 pub const NUM_KECCAK_SPONGE_COLS: usize = size_of::<KeccakSpongeCols<u8>>();
 pub const NUM_KECCAK_MEMORY_COLS: usize = size_of::<KeccakMemoryCols<u8>>();
+pub const NUM_KECCAK_ABSORB_READ_AUX_COLS: usize = size_of::<KeccakAbsorbReadAuxCols<u8>>();
+pub const NUM_KECCAK_DIGEST_WRITE_AUX_COLS: usize = size_of::<KeccakDigestWriteAuxCols<u8>>();
+pub const NUM_KECCAK_BLOCK_AUX_COLS: usize =
+    if NUM_KECCAK_ABSORB_READ_AUX_COLS > NUM_KECCAK_DIGEST_WRITE_AUX_COLS {
+        NUM_KECCAK_ABSORB_READ_AUX_COLS
+    } else {
+        NUM_KECCAK_DIGEST_WRITE_AUX_COLS
+    };
@@ -10,6 +10,7 @@ use openvm_circuit_derive::{AnyEnum, InstructionExecutor, VmConfig};
 use openvm_circuit_primitives::bitwise_op_lookup::BitwiseOperationLookupBus;
 use openvm_circuit_primitives_derive::{Chip, ChipUsageGetter};
 use openvm_instructions::*;
+use openvm_keccak256_transpiler::Rv32KeccakfOpcode;
 use openvm_rv32im_circuit::{
     Rv32I, Rv32IExecutor, Rv32IPeriphery, Rv32Io, Rv32IoExecutor, Rv32IoPeriphery, Rv32M,
     Rv32MExecutor, Rv32MPeriphery,
@@ -52,6 +53,7 @@ pub struct Keccak256;
 #[derive(ChipUsageGetter, Chip, InstructionExecutor, From, AnyEnum)]
 pub enum Keccak256Executor<F: PrimeField32> {
     Keccak256(KeccakVmChip<F>),
+    Keccakf(KeccakfVmChip<F>),
 }
 
 #[derive(From, ChipUsageGetter, Chip, AnyEnum)]
@@ -89,8 +91,8 @@ impl<F: PrimeField32> VmExtension<F> for Keccak256 {
         let keccak_chip = KeccakVmChip::new(
             execution_bus,
             program_bus,
-            memory_controller,
-            bitwise_lu_chip,
+            memory_controller.clone(),
+            bitwise_lu_chip.clone(),
             Rv32KeccakOpcode::default_offset(),
         );
         inventory.add_executor(
@@ -98,6 +100,18 @@ impl<F: PrimeField32> VmExtension<F> for Keccak256 {
             Rv32KeccakOpcode::iter().map(VmOpcode::with_default_offset),
         )?;
 
+        let keccakf_chip = KeccakfVmChip::new(
+            execution_bus,
+            program_bus,
+            memory_controller,
+            bitwise_lu_chip,
+            Rv32KeccakfOpcode::default_offset(),
+        );
+        inventory.add_executor(
+            keccakf_chip,
+            Rv32KeccakfOpcode::iter().map(VmOpcode::with_default_offset),
+        )?;
+
         Ok(inventory)
     }
 }
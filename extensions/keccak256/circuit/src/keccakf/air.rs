@@ -0,0 +1,210 @@
+use std::{array::from_fn, borrow::Borrow};
+
+use itertools::izip;
+use openvm_circuit::{
+    arch::{ExecutionBridge, ExecutionState},
+    system::memory::{offline_checker::MemoryBridge, MemoryAddress},
+};
+use openvm_circuit_primitives::{bitwise_op_lookup::BitwiseOperationLookupBus, utils::not};
+use openvm_instructions::riscv::{
+    RV32_CELL_BITS, RV32_MEMORY_AS, RV32_REGISTER_AS, RV32_REGISTER_NUM_LIMBS,
+};
+use openvm_keccak256_transpiler::Rv32KeccakfOpcode;
+use openvm_rv32im_circuit::adapters::abstract_compose;
+use openvm_stark_backend::{
+    air_builders::sub::SubAirBuilder,
+    interaction::InteractionBuilder,
+    p3_air::{Air, AirBuilder, BaseAir},
+    p3_field::AbstractField,
+    p3_matrix::Matrix,
+    rap::{BaseAirWithPublicValues, PartitionedBaseAir},
+};
+use p3_keccak_air::{KeccakAir, NUM_KECCAK_COLS as NUM_KECCAK_PERM_COLS, U64_LIMBS};
+
+use super::{
+    columns::{KeccakfVmCols, NUM_KECCAKF_VM_COLS},
+    KECCAKF_REGISTER_READS, KECCAKF_STATE_WORDS, KECCAKF_TIMESTAMP_CHANGE,
+};
+use crate::{KECCAK_WIDTH_U16S, KECCAK_WORD_SIZE};
+
+#[derive(Clone, Copy, Debug, derive_new::new)]
+pub struct KeccakfVmAir {
+    pub execution_bridge: ExecutionBridge,
+    pub memory_bridge: MemoryBridge,
+    /// Bus to send 8-bit range checks to.
+    pub bitwise_lookup_bus: BitwiseOperationLookupBus,
+    /// Maximum number of bits allowed for an address pointer
+    pub ptr_max_bits: usize,
+    pub(super) offset: usize,
+}
+
+impl<F> BaseAirWithPublicValues<F> for KeccakfVmAir {}
+impl<F> PartitionedBaseAir<F> for KeccakfVmAir {}
+impl<F> BaseAir<F> for KeccakfVmAir {
+    fn width(&self) -> usize {
+        NUM_KECCAKF_VM_COLS
+    }
+}
+
+impl<AB: InteractionBuilder> Air<AB> for KeccakfVmAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let (local, next) = (main.row_slice(0), main.row_slice(1));
+        let local: &KeccakfVmCols<AB::Var> = (*local).borrow();
+        let next: &KeccakfVmCols<AB::Var> = (*next).borrow();
+
+        let instruction = local.instruction;
+        builder.assert_bool(instruction.is_enabled);
+        builder.assert_eq(
+            instruction.is_enabled_first_round,
+            instruction.is_enabled * local.is_first_round(),
+        );
+        // since keccak-f AIR has this column, we use it for the last round of an instruction
+        builder.assert_eq(
+            local.inner.export,
+            instruction.is_enabled * local.is_last_round(),
+        );
+
+        self.eval_keccak_f(builder);
+
+        // Instruction columns are the same on all rounds of the permutation
+        let mut transition_builder = builder.when_transition();
+        let mut round_builder = transition_builder.when(not(local.is_last_round()));
+        instruction.assert_eq(&mut round_builder, next.instruction);
+
+        self.eval_instruction(builder, local);
+    }
+}
+
+impl KeccakfVmAir {
+    /// Evaluate the keccak-f permutation constraints.
+    ///
+    /// WARNING: The keccak-f AIR columns **must** be the first columns in the main AIR.
+    #[inline]
+    pub fn eval_keccak_f<AB: AirBuilder>(&self, builder: &mut AB) {
+        let keccak_f_air = KeccakAir {};
+        let mut sub_builder =
+            SubAirBuilder::<AB, KeccakAir, AB::Var>::new(builder, 0..NUM_KECCAK_PERM_COLS);
+        keccak_f_air.eval(&mut sub_builder);
+    }
+
+    /// Receive the instruction on the first round, read the state pointer from `rd` and the
+    /// preimage from memory. Write the postimage to memory on the last round.
+    ///
+    /// The state is in `u16` limbs in the keccak-f AIR, and we decompose each limb into bytes
+    /// using `state_hi` as in [KeccakVmAir::constrain_absorb](crate::KeccakVmAir::constrain_absorb).
+    /// The preimage bytes are equal to bytes in memory, and the keccak-f AIR constrains the limbs
+    /// to be `u16`, so the decomposition is valid. The postimage bytes are range checked before
+    /// being written to memory.
+    pub fn eval_instruction<AB: InteractionBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &KeccakfVmCols<AB::Var>,
+    ) {
+        let instruction = local.instruction;
+        let is_first_round = instruction.is_enabled_first_round;
+        let is_last_round = local.inner.export;
+
+        let limb_bytes = |i: usize, limb: AB::Var| -> [AB::Expr; 2] {
+            let hi = local.state_hi[i];
+            // Conversion from bytes to u16 is little-endian
+            [limb - hi * AB::F::from_canonical_u32(1 << 8), hi.into()]
+        };
+        let preimage_bytes: Vec<_> = (0..KECCAK_WIDTH_U16S)
+            .flat_map(|i| {
+                let (y, x, limb) = limb_coords(i);
+                limb_bytes(i, local.inner.preimage[y][x][limb])
+            })
+            .collect();
+        let postimage_bytes: Vec<_> = (0..KECCAK_WIDTH_U16S)
+            .flat_map(|i| {
+                let (y, x, limb) = limb_coords(i);
+                limb_bytes(i, local.postimage(y, x, limb))
+            })
+            .collect();
+        for pair in postimage_bytes.chunks_exact(2) {
+            self.bitwise_lookup_bus
+                .send_range(pair[0].clone(), pair[1].clone())
+                .eval(builder, is_last_round);
+        }
+
+        self.execution_bridge
+            .execute_and_increment_pc(
+                AB::Expr::from_canonical_usize(Rv32KeccakfOpcode::KECCAKF as usize + self.offset),
+                [
+                    instruction.rd_ptr.into(),
+                    AB::Expr::ZERO,
+                    AB::Expr::ZERO,
+                    AB::Expr::from_canonical_u32(RV32_REGISTER_AS),
+                    AB::Expr::from_canonical_u32(RV32_MEMORY_AS),
+                ],
+                ExecutionState::new(instruction.pc, instruction.start_timestamp),
+                AB::Expr::from_canonical_usize(KECCAKF_TIMESTAMP_CHANGE),
+            )
+            .eval(builder, is_first_round);
+
+        self.memory_bridge
+            .read(
+                MemoryAddress::new(
+                    AB::F::from_canonical_u32(RV32_REGISTER_AS),
+                    instruction.rd_ptr,
+                ),
+                instruction.state_ptr,
+                instruction.start_timestamp,
+                &local.mem_oc.register_aux,
+            )
+            .eval(builder, is_first_round);
+        // Range check that the state pointer is less than 2^ptr_max_bits
+        let limb_shift = AB::F::from_canonical_usize(
+            1 << (RV32_CELL_BITS * RV32_REGISTER_NUM_LIMBS - self.ptr_max_bits),
+        );
+        self.bitwise_lookup_bus
+            .send_range(
+                instruction.state_ptr[RV32_REGISTER_NUM_LIMBS - 1] * limb_shift,
+                AB::Expr::ZERO,
+            )
+            .eval(builder, is_first_round);
+
+        let state_ptr = abstract_compose::<AB::Expr, _>(instruction.state_ptr);
+        let read_timestamp =
+            instruction.start_timestamp + AB::F::from_canonical_usize(KECCAKF_REGISTER_READS);
+        let write_timestamp =
+            read_timestamp.clone() + AB::F::from_canonical_usize(KECCAKF_STATE_WORDS);
+        for (i, (preimage_word, postimage_word, read_aux, write_aux)) in izip!(
+            preimage_bytes.chunks_exact(KECCAK_WORD_SIZE),
+            postimage_bytes.chunks_exact(KECCAK_WORD_SIZE),
+            &local.mem_oc.state_reads,
+            &local.mem_oc.state_writes,
+        )
+        .enumerate()
+        {
+            let address = MemoryAddress::new(
+                AB::F::from_canonical_u32(RV32_MEMORY_AS),
+                state_ptr.clone() + AB::F::from_canonical_usize(i * KECCAK_WORD_SIZE),
+            );
+            let offset = AB::F::from_canonical_usize(i);
+            self.memory_bridge
+                .read(
+                    address.clone(),
+                    from_fn::<_, KECCAK_WORD_SIZE, _>(|j| preimage_word[j].clone()),
+                    read_timestamp.clone() + offset,
+                    read_aux,
+                )
+                .eval(builder, is_first_round);
+            self.memory_bridge
+                .write(
+                    address,
+                    from_fn::<_, KECCAK_WORD_SIZE, _>(|j| postimage_word[j].clone()),
+                    write_timestamp.clone() + offset,
+                    write_aux,
+                )
+                .eval(builder, is_last_round);
+        }
+    }
+}
+
+/// The `(y, x, limb)` coordinates in the keccak-f AIR of the `i`-th `u16` limb of the state.
+pub(super) fn limb_coords(i: usize) -> (usize, usize, usize) {
+    let lane = i / U64_LIMBS;
+    (lane / 5, lane % 5, i % U64_LIMBS)
+}
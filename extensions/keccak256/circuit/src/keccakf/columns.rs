@@ -0,0 +1,87 @@
+use core::mem::size_of;
+
+use openvm_circuit::system::memory::offline_checker::{MemoryReadAuxCols, MemoryWriteAuxCols};
+use openvm_circuit_primitives::utils::assert_array_eq;
+use openvm_circuit_primitives_derive::AlignedBorrow;
+use openvm_instructions::riscv::RV32_REGISTER_NUM_LIMBS;
+use openvm_stark_backend::p3_air::AirBuilder;
+use p3_keccak_air::KeccakCols as KeccakPermCols;
+
+use super::KECCAKF_STATE_WORDS;
+use crate::{KECCAK_WIDTH_U16S, KECCAK_WORD_SIZE};
+
+/// Each KECCAKF instruction takes `NUM_ROUNDS` rows, one per round of the permutation. The state
+/// is read on the first round and written on the last round.
+#[repr(C)]
+#[derive(Debug, AlignedBorrow)]
+pub struct KeccakfVmCols<T> {
+    /// Columns for keccak-f permutation
+    pub inner: KeccakPermCols<T>,
+    /// Columns for instruction interface and register access
+    pub instruction: KeccakfInstructionCols<T>,
+    /// For each `u16` limb of the state, the most significant byte of the limb.
+    /// Here the state is the preimage on the first round and the postimage on the last round.
+    /// It is zero on the other rounds.
+    pub state_hi: [T; KECCAK_WIDTH_U16S],
+    /// Auxiliary columns for offline memory checking
+    pub mem_oc: KeccakfMemoryCols<T>,
+}
+
+/// Columns for KECCAKF instruction parsing. They are the same on all rounds of an instruction.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, AlignedBorrow)]
+pub struct KeccakfInstructionCols<T> {
+    /// Program counter
+    pub pc: T,
+    /// True for all rows that are part of opcode execution.
+    /// False on dummy rows only used to pad the height.
+    pub is_enabled: T,
+    /// Is enabled and first round. Used to lower constraint degree.
+    /// is_enabled * inner.step_flags[0]
+    pub is_enabled_first_round: T,
+    /// The timestamp of the register read. The state reads and writes follow it.
+    pub start_timestamp: T,
+    /// Pointer to address space 1 `rd` register
+    pub rd_ptr: T,
+    /// Pointer to the state, `state_ptr <- [rd_ptr:4]_1`
+    pub state_ptr: [T; RV32_REGISTER_NUM_LIMBS],
+}
+
+#[repr(C)]
+#[derive(Clone, Debug, AlignedBorrow)]
+pub struct KeccakfMemoryCols<T> {
+    pub register_aux: MemoryReadAuxCols<T, RV32_REGISTER_NUM_LIMBS>,
+    /// Only used on the first round
+    pub state_reads: [MemoryReadAuxCols<T, KECCAK_WORD_SIZE>; KECCAKF_STATE_WORDS],
+    /// Only used on the last round
+    pub state_writes: [MemoryWriteAuxCols<T, KECCAK_WORD_SIZE>; KECCAKF_STATE_WORDS],
+}
+
+impl<T: Copy> KeccakfVmCols<T> {
+    pub fn postimage(&self, y: usize, x: usize, limb: usize) -> T {
+        self.inner.a_prime_prime_prime(y, x, limb)
+    }
+
+    pub fn is_first_round(&self) -> T {
+        *self.inner.step_flags.first().unwrap()
+    }
+
+    pub fn is_last_round(&self) -> T {
+        *self.inner.step_flags.last().unwrap()
+    }
+}
+
+impl<T: Copy> KeccakfInstructionCols<T> {
+    pub fn assert_eq<AB: AirBuilder>(&self, builder: &mut AB, other: Self)
+    where
+        T: Into<AB::Expr>,
+    {
+        builder.assert_eq(self.pc, other.pc);
+        builder.assert_eq(self.is_enabled, other.is_enabled);
+        builder.assert_eq(self.start_timestamp, other.start_timestamp);
+        builder.assert_eq(self.rd_ptr, other.rd_ptr);
+        assert_array_eq(builder, self.state_ptr, other.state_ptr);
+    }
+}
+
+pub const NUM_KECCAKF_VM_COLS: usize = size_of::<KeccakfVmCols<u8>>();
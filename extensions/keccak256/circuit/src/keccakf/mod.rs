@@ -0,0 +1,146 @@
+//! Keccak-f[1600] permutation of a sponge state in VM memory. The guest absorbs and squeezes
+//! bytes itself, so it can hash streaming data one block at a time.
+use std::{array::from_fn, sync::Arc};
+
+use openvm_circuit::{
+    arch::{ExecutionBridge, ExecutionBus, ExecutionError, ExecutionState, InstructionExecutor},
+    system::{
+        memory::{MemoryControllerRef, MemoryReadRecord, MemoryWriteRecord},
+        program::ProgramBus,
+    },
+};
+use openvm_circuit_primitives::bitwise_op_lookup::BitwiseOperationLookupChip;
+use openvm_instructions::{
+    instruction::Instruction, program::DEFAULT_PC_STEP, riscv::RV32_REGISTER_NUM_LIMBS, UsizeOpcode,
+};
+use openvm_keccak256_transpiler::Rv32KeccakfOpcode;
+use openvm_rv32im_circuit::adapters::read_rv32_register;
+use openvm_stark_backend::p3_field::PrimeField32;
+use tiny_keccak::keccakf;
+
+use super::{KECCAK_WIDTH_BYTES, KECCAK_WORD_SIZE};
+
+mod air;
+mod columns;
+mod trace;
+
+pub use air::*;
+pub use columns::*;
+
+#[cfg(test)]
+mod tests;
+
+/// Register read to get the state pointer
+const KECCAKF_REGISTER_READS: usize = 1;
+/// Number of words of the state, each read on the first round and written on the last round
+const KECCAKF_STATE_WORDS: usize = KECCAK_WIDTH_BYTES / KECCAK_WORD_SIZE;
+/// Amount to advance the timestamp by after execution of one KECCAKF instruction
+const KECCAKF_TIMESTAMP_CHANGE: usize = KECCAKF_REGISTER_READS + 2 * KECCAKF_STATE_WORDS;
+
+#[derive(Debug)]
+pub struct KeccakfVmChip<F: PrimeField32> {
+    pub air: KeccakfVmAir,
+    pub records: Vec<KeccakfRecord<F>>,
+    pub memory_controller: MemoryControllerRef<F>,
+    pub bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<8>>,
+}
+
+#[derive(Clone, Debug)]
+pub struct KeccakfRecord<F> {
+    pub from_state: ExecutionState<u32>,
+    pub state_ptr_read: MemoryReadRecord<F, RV32_REGISTER_NUM_LIMBS>,
+    pub state_reads: [MemoryReadRecord<F, KECCAK_WORD_SIZE>; KECCAKF_STATE_WORDS],
+    pub state_writes: [MemoryWriteRecord<F, KECCAK_WORD_SIZE>; KECCAKF_STATE_WORDS],
+}
+
+impl<F: PrimeField32> KeccakfVmChip<F> {
+    pub fn new(
+        execution_bus: ExecutionBus,
+        program_bus: ProgramBus,
+        memory_controller: MemoryControllerRef<F>,
+        bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<8>>,
+        offset: usize,
+    ) -> Self {
+        let ptr_max_bits = memory_controller.borrow().mem_config().pointer_max_bits;
+        let memory_bridge = memory_controller.borrow().memory_bridge();
+        Self {
+            air: KeccakfVmAir::new(
+                ExecutionBridge::new(execution_bus, program_bus),
+                memory_bridge,
+                bitwise_lookup_chip.bus(),
+                ptr_max_bits,
+                offset,
+            ),
+            records: Vec::new(),
+            memory_controller,
+            bitwise_lookup_chip,
+        }
+    }
+}
+
+impl<F: PrimeField32> InstructionExecutor<F> for KeccakfVmChip<F> {
+    fn execute(
+        &mut self,
+        instruction: Instruction<F>,
+        from_state: ExecutionState<u32>,
+    ) -> Result<ExecutionState<u32>, ExecutionError> {
+        let Instruction {
+            opcode, a, d, e, ..
+        } = instruction;
+        let local_opcode = Rv32KeccakfOpcode::from_usize(opcode.local_opcode_idx(self.air.offset));
+        debug_assert_eq!(local_opcode, Rv32KeccakfOpcode::KECCAKF);
+
+        let mut memory = self.memory_controller.borrow_mut();
+        debug_assert_eq!(from_state.timestamp, memory.timestamp());
+
+        let (state_ptr_read, state_ptr) = read_rv32_register(&mut memory, d, a);
+        let state_ptr = state_ptr as usize;
+        assert!(state_ptr + KECCAK_WIDTH_BYTES <= (1 << self.air.ptr_max_bits));
+
+        let state_reads: [_; KECCAKF_STATE_WORDS] = from_fn(|i| {
+            memory.read::<KECCAK_WORD_SIZE>(
+                e,
+                F::from_canonical_usize(state_ptr + i * KECCAK_WORD_SIZE),
+            )
+        });
+        let mut state = [0u64; 25];
+        for (i, read) in state_reads.iter().enumerate() {
+            for (j, byte) in read.data.iter().enumerate() {
+                let byte: u8 = byte
+                    .as_canonical_u32()
+                    .try_into()
+                    .expect("Memory cell not a byte");
+                state[i / 2] |= (byte as u64) << (8 * ((i % 2) * KECCAK_WORD_SIZE + j));
+            }
+        }
+        keccakf(&mut state);
+        let output: Vec<u8> = state.iter().flat_map(|lane| lane.to_le_bytes()).collect();
+        let state_writes: [_; KECCAKF_STATE_WORDS] = from_fn(|i| {
+            memory.write::<KECCAK_WORD_SIZE>(
+                e,
+                F::from_canonical_usize(state_ptr + i * KECCAK_WORD_SIZE),
+                from_fn(|j| F::from_canonical_u8(output[i * KECCAK_WORD_SIZE + j])),
+            )
+        });
+
+        self.records.push(KeccakfRecord {
+            from_state,
+            state_ptr_read,
+            state_reads,
+            state_writes,
+        });
+
+        // NOTE: Check this is consistent with the timestamp change in KeccakfVmAir
+        let to_timestamp = from_state.timestamp + KECCAKF_TIMESTAMP_CHANGE as u32;
+        debug_assert_eq!(to_timestamp, memory.timestamp());
+
+        Ok(ExecutionState {
+            pc: from_state.pc + DEFAULT_PC_STEP,
+            timestamp: to_timestamp,
+        })
+    }
+
+    fn get_opcode_name(&self, _: usize) -> String {
+        "KECCAKF".to_string()
+    }
+}
@@ -0,0 +1,103 @@
+use std::{borrow::BorrowMut, sync::Arc};
+
+use openvm_circuit::arch::{
+    testing::{VmChipTestBuilder, VmChipTester},
+    BITWISE_OP_LOOKUP_BUS,
+};
+use openvm_circuit_primitives::bitwise_op_lookup::{
+    BitwiseOperationLookupBus, BitwiseOperationLookupChip,
+};
+use openvm_instructions::{instruction::Instruction, UsizeOpcode, VmOpcode};
+use openvm_keccak256_transpiler::Rv32KeccakfOpcode;
+use openvm_stark_backend::{
+    p3_field::AbstractField, utils::disable_debug_builder, verifier::VerificationError,
+};
+use openvm_stark_sdk::{
+    config::baby_bear_blake3::BabyBearBlake3Config, p3_baby_bear::BabyBear,
+    utils::create_seeded_rng,
+};
+use p3_keccak_air::NUM_ROUNDS;
+use rand::Rng;
+use tiny_keccak::keccakf;
+
+use super::{KeccakfVmChip, KeccakfVmCols};
+use crate::KECCAK_WIDTH_BYTES;
+
+type F = BabyBear;
+
+/// Permutes each of `states` in place in memory and checks the written state.
+fn build_keccakf_test(states: Vec<[u64; 25]>) -> VmChipTester<BabyBearBlake3Config> {
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = Arc::new(BitwiseOperationLookupChip::<8>::new(bitwise_bus));
+
+    let mut tester = VmChipTestBuilder::default();
+    let mut chip = KeccakfVmChip::new(
+        tester.execution_bus(),
+        tester.program_bus(),
+        tester.memory_controller(),
+        bitwise_chip.clone(),
+        Rv32KeccakfOpcode::default_offset(),
+    );
+
+    let [a, d, e] = [4, 1, 2];
+    for (k, mut state) in states.into_iter().enumerate() {
+        let state_ptr = k * KECCAK_WIDTH_BYTES;
+        tester.write(
+            d,
+            a,
+            (state_ptr as u32).to_le_bytes().map(F::from_canonical_u8),
+        );
+        let bytes: Vec<u8> = state.iter().flat_map(|lane| lane.to_le_bytes()).collect();
+        for (i, &byte) in bytes.iter().enumerate() {
+            tester.write_cell(e, state_ptr + i, F::from_canonical_u8(byte));
+        }
+
+        tester.execute(
+            &mut chip,
+            Instruction::from_isize(
+                VmOpcode::with_default_offset(Rv32KeccakfOpcode::KECCAKF),
+                a as isize,
+                0,
+                0,
+                d as isize,
+                e as isize,
+            ),
+        );
+
+        keccakf(&mut state);
+        let expected: Vec<u8> = state.iter().flat_map(|lane| lane.to_le_bytes()).collect();
+        for (i, &byte) in expected.iter().enumerate() {
+            assert_eq!(
+                tester.read_cell(e, state_ptr + i),
+                F::from_canonical_u8(byte)
+            );
+        }
+    }
+    tester.build().load(chip).load(bitwise_chip).finalize()
+}
+
+#[test]
+fn rand_keccakf_test() {
+    let mut rng = create_seeded_rng();
+    let mut states: Vec<[u64; 25]> = (0..5).map(|_| rng.gen()).collect();
+    states.push([0; 25]);
+    let tester = build_keccakf_test(states);
+    tester.simple_test().expect("Verification failed");
+}
+
+#[test]
+fn negative_keccakf_test() {
+    let mut rng = create_seeded_rng();
+    let mut tester = build_keccakf_test(vec![rng.gen()]);
+
+    // Change a limb of the postimage, which is written to memory
+    let keccakf_trace = tester.air_proof_inputs[2].raw.common_main.as_mut().unwrap();
+    let last_row: &mut KeccakfVmCols<F> = keccakf_trace.row_mut(NUM_ROUNDS - 1).borrow_mut();
+    last_row.inner.a_prime_prime_prime_0_0_limbs[0] += F::ONE;
+
+    disable_debug_builder();
+    assert_eq!(
+        tester.simple_test().err(),
+        Some(VerificationError::OodEvaluationMismatch)
+    );
+}
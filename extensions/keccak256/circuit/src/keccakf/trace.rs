@@ -0,0 +1,145 @@
+use std::{borrow::BorrowMut, sync::Arc};
+
+use openvm_instructions::riscv::{RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS};
+use openvm_stark_backend::{
+    config::{StarkGenericConfig, Val},
+    p3_air::BaseAir,
+    p3_field::{AbstractField, PrimeField32},
+    p3_matrix::{dense::RowMajorMatrix, Matrix},
+    p3_maybe_rayon::prelude::*,
+    prover::types::AirProofInput,
+    rap::{get_air_name, AnyRap},
+    Chip, ChipUsageGetter,
+};
+use p3_keccak_air::{generate_trace_rows, NUM_KECCAK_COLS as NUM_KECCAK_PERM_COLS, NUM_ROUNDS};
+
+use super::{
+    columns::{KeccakfInstructionCols, KeccakfVmCols},
+    KeccakfRecord, KeccakfVmChip, KECCAKF_STATE_WORDS,
+};
+use crate::{KECCAK_WIDTH_U16S, KECCAK_WORD_SIZE};
+
+impl<SC: StarkGenericConfig> Chip<SC> for KeccakfVmChip<Val<SC>>
+where
+    Val<SC>: PrimeField32,
+{
+    fn air(&self) -> Arc<dyn AnyRap<SC>> {
+        Arc::new(self.air)
+    }
+
+    fn generate_air_proof_input(self) -> AirProofInput<SC> {
+        let air = self.air();
+        let trace_width = self.trace_width();
+
+        let states = self
+            .records
+            .iter()
+            .map(|record| {
+                let mut state = [0u64; 25];
+                for (i, read) in record.state_reads.iter().enumerate() {
+                    for (j, byte) in read.data.iter().enumerate() {
+                        state[i / 2] |= (byte.as_canonical_u32() as u64)
+                            << (8 * ((i % 2) * KECCAK_WORD_SIZE + j));
+                    }
+                }
+                state
+            })
+            .collect();
+        let p3_keccak_trace: RowMajorMatrix<Val<SC>> = generate_trace_rows(states);
+        let num_rows = p3_keccak_trace.height();
+        // Every `NUM_ROUNDS` rows corresponds to one permutation. Dummy permutations have
+        // `is_enabled = 0`.
+        let mut records: Vec<Option<KeccakfRecord<Val<SC>>>> =
+            self.records.into_iter().map(Some).collect();
+        records.resize_with(num_rows.div_ceil(NUM_ROUNDS), || None);
+
+        let aux_cols_factory = self.memory_controller.borrow().aux_cols_factory();
+        let limb_shift_bits = RV32_CELL_BITS * RV32_REGISTER_NUM_LIMBS - self.air.ptr_max_bits;
+
+        let mut trace =
+            RowMajorMatrix::new(Val::<SC>::zero_vec(num_rows * trace_width), trace_width);
+        trace
+            .values
+            .par_chunks_mut(trace_width * NUM_ROUNDS)
+            .zip(
+                p3_keccak_trace
+                    .values
+                    .par_chunks(NUM_KECCAK_PERM_COLS * NUM_ROUNDS),
+            )
+            .zip(records.into_par_iter())
+            .for_each(|((rows, p3_keccak_mat), record)| {
+                let height = rows.len() / trace_width;
+                for (row, p3_keccak_row) in rows
+                    .chunks_exact_mut(trace_width)
+                    .zip(p3_keccak_mat.chunks_exact(NUM_KECCAK_PERM_COLS))
+                {
+                    // Safety: `KeccakPermCols` **must** be the first field in `KeccakfVmCols`
+                    row[..NUM_KECCAK_PERM_COLS].copy_from_slice(p3_keccak_row);
+                }
+                let Some(record) = record else {
+                    return;
+                };
+
+                let instruction = KeccakfInstructionCols {
+                    pc: Val::<SC>::from_canonical_u32(record.from_state.pc),
+                    is_enabled: Val::<SC>::ONE,
+                    is_enabled_first_round: Val::<SC>::ZERO,
+                    start_timestamp: Val::<SC>::from_canonical_u32(record.from_state.timestamp),
+                    rd_ptr: record.state_ptr_read.pointer,
+                    state_ptr: record.state_ptr_read.data,
+                };
+                for row in rows.chunks_exact_mut(trace_width) {
+                    let row_mut: &mut KeccakfVmCols<Val<SC>> = row.borrow_mut();
+                    row_mut.instruction = instruction;
+                }
+
+                let state_ptr_hi = record.state_ptr_read.data[RV32_REGISTER_NUM_LIMBS - 1];
+                self.bitwise_lookup_chip
+                    .request_range(state_ptr_hi.as_canonical_u32() << limb_shift_bits, 0);
+                let first_row: &mut KeccakfVmCols<Val<SC>> = rows[..trace_width].borrow_mut();
+                first_row.instruction.is_enabled_first_round = Val::<SC>::ONE;
+                first_row.state_hi = state_hi(&record.state_reads.map(|read| read.data));
+                first_row.mem_oc.register_aux =
+                    aux_cols_factory.make_read_aux_cols(record.state_ptr_read);
+                for (i, read) in record.state_reads.into_iter().enumerate() {
+                    first_row.mem_oc.state_reads[i] = aux_cols_factory.make_read_aux_cols(read);
+                }
+
+                let last_row: &mut KeccakfVmCols<Val<SC>> =
+                    rows[(height - 1) * trace_width..].borrow_mut();
+                last_row.inner.export = Val::<SC>::ONE;
+                let postimage = record.state_writes.map(|write| write.data);
+                for limb in postimage.iter().flat_map(|word| word.chunks_exact(2)) {
+                    self.bitwise_lookup_chip
+                        .request_range(limb[0].as_canonical_u32(), limb[1].as_canonical_u32());
+                }
+                last_row.state_hi = state_hi(&postimage);
+                for (i, write) in record.state_writes.into_iter().enumerate() {
+                    last_row.mem_oc.state_writes[i] = aux_cols_factory.make_write_aux_cols(write);
+                }
+            });
+
+        AirProofInput::simple_no_pis(air, trace)
+    }
+}
+
+/// The most significant byte of each `u16` limb of the state given as words of bytes.
+fn state_hi<F: Copy>(
+    words: &[[F; KECCAK_WORD_SIZE]; KECCAKF_STATE_WORDS],
+) -> [F; KECCAK_WIDTH_U16S] {
+    std::array::from_fn(|i| words[i / 2][(i % 2) * 2 + 1])
+}
+
+impl<F: PrimeField32> ChipUsageGetter for KeccakfVmChip<F> {
+    fn air_name(&self) -> String {
+        get_air_name(&self.air)
+    }
+
+    fn current_trace_height(&self) -> usize {
+        self.records.len() * NUM_ROUNDS
+    }
+
+    fn trace_width(&self) -> usize {
+        BaseAir::<F>::width(&self.air)
+    }
+}
@@ -9,6 +9,7 @@ use utils::num_keccak_f;
 
 pub mod air;
 pub mod columns;
+pub mod keccakf;
 pub mod trace;
 pub mod utils;
 
@@ -19,6 +20,7 @@ pub use extension::*;
 mod tests;
 
 pub use air::KeccakVmAir;
+pub use keccakf::KeccakfVmChip;
 use openvm_circuit::{
     arch::{ExecutionBridge, ExecutionBus, ExecutionError, ExecutionState, InstructionExecutor},
     system::{
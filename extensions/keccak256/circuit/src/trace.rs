@@ -208,9 +208,10 @@ where
                             aux_cols_factory.make_read_aux_cols(record);
                     }
                 }
+                let absorb_reads = first_row.mem_oc.absorb_reads_mut();
                 for (i, record) in block.reads.into_iter().enumerate() {
                     // TODO[jpw] make_read_aux_cols should directly write into slice
-                    first_row.mem_oc.absorb_reads[i] = aux_cols_factory.make_read_aux_cols(record);
+                    absorb_reads[i] = aux_cols_factory.make_read_aux_cols(record);
                 }
 
                 let last_row: &mut KeccakVmCols<Val<SC>> =
@@ -219,10 +220,10 @@ where
                 last_row.inner.export = instruction.is_enabled
                     * Val::<SC>::from_bool(block.remaining_len < KECCAK_RATE_BYTES);
                 if let Some(digest_writes) = diff.digest_writes {
+                    // Shares columns with the absorb reads, which are only used on the first row
+                    let digest_write_aux = last_row.mem_oc.digest_writes_mut();
                     for (i, record) in digest_writes.into_iter().enumerate() {
-                        // TODO: these aux columns are only used for the last row - can we share them with aux reads in first row?
-                        last_row.mem_oc.digest_writes[i] =
-                            aux_cols_factory.make_write_aux_cols(record);
+                        digest_write_aux[i] = aux_cols_factory.make_write_aux_cols(record);
                     }
                 }
             });
@@ -6,6 +6,11 @@ use core::mem::MaybeUninit;
 /// This is custom-0 defined in RISC-V spec document
 pub const OPCODE: u8 = 0x0b;
 pub const FUNCT3: u8 = 0b100;
+pub const KECCAK256_FUNCT7: u8 = 0x0;
+pub const KECCAKF_FUNCT7: u8 = 0x1;
+
+mod sponge;
+pub use sponge::*;
 
 /// The keccak256 cryptographic hash function.
 #[inline(always)]
@@ -40,7 +45,7 @@ pub fn keccak256(input: &[u8]) -> [u8; 32] {
 #[inline(always)]
 #[no_mangle]
 extern "C" fn native_keccak256(bytes: *const u8, len: usize, output: *mut u8) {
-    openvm_platform::custom_insn_r!(OPCODE, FUNCT3, 0x0, output, bytes, len);
+    openvm_platform::custom_insn_r!(OPCODE, FUNCT3, KECCAK256_FUNCT7, output, bytes, len);
 }
 
 /// Sets `output` to the keccak256 hash of `input`.
@@ -55,3 +60,34 @@ pub fn set_keccak256(input: &[u8], output: &mut [u8; 32]) {
     #[cfg(target_os = "zkvm")]
     native_keccak256(input.as_ptr(), input.len(), output.as_mut_ptr() as *mut u8);
 }
+
+/// Applies the keccak-f[1600] permutation to `state`, in the lane order of the Keccak
+/// reference, i.e., lane `(x, y)` is `state[x + 5 * y]`.
+#[inline(always)]
+pub fn keccakf(state: &mut [u64; 25]) {
+    #[cfg(not(target_os = "zkvm"))]
+    tiny_keccak::keccakf(state);
+    #[cfg(target_os = "zkvm")]
+    native_keccakf(state.as_mut_ptr());
+}
+
+/// Native hook for the keccak-f[1600] permutation.
+///
+/// # Safety
+///
+/// The VM permutes the 200-byte state in place.
+/// - `state` must point to a buffer of 25 little-endian `u64` lanes that is 4-byte aligned.
+#[cfg(target_os = "zkvm")]
+#[inline(always)]
+#[no_mangle]
+extern "C" fn native_keccakf(state: *mut u64) {
+    unsafe {
+        core::arch::asm!(
+            ".insn r {opcode}, {funct3}, {funct7}, {rd}, x0, x0",
+            opcode = const OPCODE,
+            funct3 = const FUNCT3,
+            funct7 = const KECCAKF_FUNCT7,
+            rd = in(reg) state,
+        )
+    }
+}
@@ -0,0 +1,85 @@
+use crate::keccakf;
+
+/// Number of bytes absorbed or squeezed per keccak-f permutation for keccak256.
+pub const KECCAK256_RATE_BYTES: usize = 136;
+
+/// Keccak sponge with the rate and padding of keccak256, for hashing data that is not available
+/// as one contiguous buffer.
+///
+/// Absorbing `a` and then `b` and squeezing 32 bytes gives `keccak256(a || b)`. The sponge may
+/// absorb again after squeezing, which makes it usable as a transcript: the state is permuted
+/// and the new input is absorbed from the start of the rate.
+#[derive(Clone, Debug)]
+pub struct KeccakSponge {
+    state: [u64; 25],
+    /// Byte position in the rate of the next byte to absorb or squeeze
+    pos: usize,
+    squeezing: bool,
+}
+
+impl Default for KeccakSponge {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl KeccakSponge {
+    /// Creates a sponge with the all zero state.
+    pub const fn new() -> Self {
+        Self {
+            state: [0; 25],
+            pos: 0,
+            squeezing: false,
+        }
+    }
+
+    /// Absorbs `input` into the state, permuting whenever the rate is full.
+    pub fn absorb(&mut self, input: &[u8]) {
+        if self.squeezing {
+            keccakf(&mut self.state);
+            self.pos = 0;
+            self.squeezing = false;
+        }
+        for &byte in input {
+            self.xor_byte(self.pos, byte);
+            self.pos += 1;
+            if self.pos == KECCAK256_RATE_BYTES {
+                keccakf(&mut self.state);
+                self.pos = 0;
+            }
+        }
+    }
+
+    /// Fills `output` with bytes squeezed from the state. The first squeeze after absorbing
+    /// pads the absorbed input.
+    pub fn squeeze(&mut self, output: &mut [u8]) {
+        if !self.squeezing {
+            // keccak 10*1 padding, little-endian
+            self.xor_byte(self.pos, 0x01);
+            self.xor_byte(KECCAK256_RATE_BYTES - 1, 0x80);
+            keccakf(&mut self.state);
+            self.pos = 0;
+            self.squeezing = true;
+        }
+        for byte in output {
+            if self.pos == KECCAK256_RATE_BYTES {
+                keccakf(&mut self.state);
+                self.pos = 0;
+            }
+            *byte = (self.state[self.pos / 8] >> (8 * (self.pos % 8))) as u8;
+            self.pos += 1;
+        }
+    }
+
+    /// Squeezes the 32-byte keccak256 digest of everything absorbed so far.
+    pub fn finalize(mut self) -> [u8; 32] {
+        let mut output = [0u8; 32];
+        self.squeeze(&mut output);
+        output
+    }
+
+    #[inline(always)]
+    fn xor_byte(&mut self, pos: usize, byte: u8) {
+        self.state[pos / 8] ^= (byte as u64) << (8 * (pos % 8));
+    }
+}
@@ -0,0 +1,41 @@
+#![cfg_attr(not(feature = "std"), no_main)]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use core::hint::black_box;
+
+use openvm_keccak256_guest::{keccak256, KeccakSponge};
+
+openvm::entry!(main);
+
+pub fn main() {
+    let input: Vec<u8> = (0..1000u32).map(|i| (i * 31 + 7) as u8).collect();
+    let input = black_box(input);
+    // Absorb in chunks that do not line up with the rate
+    for chunk_size in [1, 17, 136, 300] {
+        let mut sponge = KeccakSponge::new();
+        for chunk in input.chunks(chunk_size) {
+            sponge.absorb(chunk);
+        }
+        if sponge.finalize() != keccak256(&input) {
+            panic!();
+        }
+    }
+
+    // Squeezing in pieces gives the same bytes
+    let mut sponge = KeccakSponge::new();
+    sponge.absorb(&input);
+    let mut output = [0u8; 300];
+    let (first, second) = output.split_at_mut(100);
+    sponge.squeeze(first);
+    sponge.squeeze(second);
+    let mut sponge = KeccakSponge::new();
+    sponge.absorb(&input);
+    let mut expected = [0u8; 300];
+    sponge.squeeze(&mut expected);
+    if output != expected || output[..32] != keccak256(&input) {
+        panic!();
+    }
+}
@@ -28,4 +28,19 @@ mod tests {
         air_test(Keccak256Rv32Config::default(), openvm_exe);
         Ok(())
     }
+
+    #[test]
+    fn test_keccak_sponge() -> Result<()> {
+        let elf = build_example_program_at_path(get_programs_dir!(), "keccak-sponge")?;
+        let openvm_exe = VmExe::from_elf(
+            elf,
+            Transpiler::<F>::default()
+                .with_extension(Keccak256TranspilerExtension)
+                .with_extension(Rv32ITranspilerExtension)
+                .with_extension(Rv32MTranspilerExtension)
+                .with_extension(Rv32IoTranspilerExtension),
+        )?;
+        air_test(Keccak256Rv32Config::default(), openvm_exe);
+        Ok(())
+    }
 }
@@ -1,8 +1,12 @@
-use openvm_instructions::{instruction::Instruction, UsizeOpcode};
+use openvm_instructions::{
+    instruction::Instruction,
+    riscv::{RV32_MEMORY_AS, RV32_REGISTER_AS, RV32_REGISTER_NUM_LIMBS},
+    UsizeOpcode, VmOpcode,
+};
 use openvm_instructions_derive::UsizeOpcode;
-use openvm_keccak256_guest::{FUNCT3, OPCODE};
+use openvm_keccak256_guest::{FUNCT3, KECCAK256_FUNCT7, KECCAKF_FUNCT7, OPCODE};
 use openvm_stark_backend::p3_field::PrimeField32;
-use openvm_transpiler::{util::from_r_type, TranspilerExtension};
+use openvm_transpiler::{util::from_r_type, EncodingRange, TranspilerExtension};
 use rrs_lib::instruction_formats::RType;
 use strum::{EnumCount, EnumIter, FromRepr};
 
@@ -15,6 +19,16 @@ pub enum Rv32KeccakOpcode {
     KECCAK256,
 }
 
+/// Keccak-f[1600] permutation of the 200-byte sponge state at `[rd]`, in place.
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, EnumCount, EnumIter, FromRepr, UsizeOpcode,
+)]
+#[opcode_offset = 0x318]
+#[repr(usize)]
+pub enum Rv32KeccakfOpcode {
+    KECCAKF,
+}
+
 #[derive(Default)]
 pub struct Keccak256TranspilerExtension;
 
@@ -31,11 +45,29 @@ impl<F: PrimeField32> TranspilerExtension<F> for Keccak256TranspilerExtension {
             return None;
         }
         let dec_insn = RType::new(instruction_u32);
-        let instruction = from_r_type(
-            Rv32KeccakOpcode::KECCAK256.with_default_offset(),
-            2,
-            &dec_insn,
-        );
+        let instruction = match dec_insn.funct7 as u8 {
+            KECCAK256_FUNCT7 => from_r_type(
+                Rv32KeccakOpcode::KECCAK256.with_default_offset(),
+                2,
+                &dec_insn,
+            ),
+            // Only `rd` is used, `rs1` and `rs2` must be `x0`.
+            KECCAKF_FUNCT7 if dec_insn.rs1 == 0 && dec_insn.rs2 == 0 => Instruction::from_isize(
+                VmOpcode::with_default_offset(Rv32KeccakfOpcode::KECCAKF),
+                (RV32_REGISTER_NUM_LIMBS * dec_insn.rd) as isize,
+                0,
+                0,
+                RV32_REGISTER_AS as isize,
+                RV32_MEMORY_AS as isize,
+            ),
+            _ => return None,
+        };
         Some((instruction, 1))
     }
+
+    fn encodings(&self) -> Option<Vec<EncodingRange>> {
+        Some(vec![EncodingRange::opcode(OPCODE)
+            .with_funct3(FUNCT3)
+            .with_funct7(KECCAK256_FUNCT7..=KECCAKF_FUNCT7)])
+    }
 }
@@ -1,9 +1,9 @@
 use std::{
     borrow::{Borrow, BorrowMut},
-    cell::RefCell,
     marker::PhantomData,
 };
 
+use atomic_refcell::AtomicRefCell;
 use openvm_circuit::{
     arch::{
         AdapterAirContext, AdapterRuntimeContext, BasicAdapterInterface, ExecutionBridge,
@@ -53,7 +53,7 @@ impl<F: PrimeField32, const READ_SIZE: usize, const WRITE_SIZE: usize>
         program_bus: ProgramBus,
         memory_controller: MemoryControllerRef<F>,
     ) -> Self {
-        let memory_controller = RefCell::borrow(&memory_controller);
+        let memory_controller = AtomicRefCell::borrow(&memory_controller);
         let memory_bridge = memory_controller.memory_bridge();
         Self {
             air: ConvertAdapterAir {
@@ -1,9 +1,9 @@
 use std::{
     borrow::{Borrow, BorrowMut},
-    cell::RefCell,
     marker::PhantomData,
 };
 
+use atomic_refcell::AtomicRefCell;
 use openvm_circuit::{
     arch::{
         instructions::UsizeOpcode, AdapterAirContext, AdapterRuntimeContext, ExecutionBridge,
@@ -64,7 +64,7 @@ impl<F: PrimeField32, const NUM_CELLS: usize> NativeLoadStoreAdapterChip<F, NUM_
         memory_controller: MemoryControllerRef<F>,
         offset: usize,
     ) -> Self {
-        let memory_controller = RefCell::borrow(&memory_controller);
+        let memory_controller = AtomicRefCell::borrow(&memory_controller);
         let memory_bridge = memory_controller.memory_bridge();
         Self {
             air: NativeLoadStoreAdapterAir {
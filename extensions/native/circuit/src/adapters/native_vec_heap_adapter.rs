@@ -1,11 +1,11 @@
 use std::{
     array::from_fn,
     borrow::{Borrow, BorrowMut},
-    cell::RefCell,
     iter::{once, zip},
     marker::PhantomData,
 };
 
+use atomic_refcell::AtomicRefCell;
 use itertools::izip;
 use openvm_circuit::{
     arch::{
@@ -64,7 +64,7 @@ impl<
         memory_controller: MemoryControllerRef<F>,
     ) -> Self {
         assert!(R <= 2);
-        let memory_controller = RefCell::borrow(&memory_controller);
+        let memory_controller = AtomicRefCell::borrow(&memory_controller);
         let memory_bridge = memory_controller.memory_bridge();
         let address_bits = memory_controller.mem_config().pointer_max_bits;
         Self {
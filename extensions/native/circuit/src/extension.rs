@@ -231,7 +231,7 @@ pub(crate) mod phantom {
             _: F,
             _: u16,
         ) -> eyre::Result<()> {
-            let hint = match streams.input_stream.pop_front() {
+            let hint = match streams.next_input(&[]) {
                 Some(hint) => hint,
                 None => {
                     bail!("EndOfInputStream");
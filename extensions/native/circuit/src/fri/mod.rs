@@ -1,10 +1,10 @@
 use std::{
     array::from_fn,
     borrow::{Borrow, BorrowMut},
-    cell::RefCell,
     sync::Arc,
 };
 
+use atomic_refcell::AtomicRefCell;
 use openvm_circuit::{
     arch::{ExecutionBridge, ExecutionBus, ExecutionError, ExecutionState, InstructionExecutor},
     system::{
@@ -326,7 +326,7 @@ impl<F: PrimeField32> FriReducedOpeningChip<F> {
     ) -> Self {
         let air = FriReducedOpeningAir {
             execution_bridge: ExecutionBridge::new(execution_bus, program_bus),
-            memory_bridge: RefCell::borrow(&memory).memory_bridge(),
+            memory_bridge: AtomicRefCell::borrow(&memory).memory_bridge(),
             offset,
         };
         Self {
@@ -361,7 +361,7 @@ impl<F: PrimeField32> InstructionExecutor<F> for FriReducedOpeningChip<F> {
             ..
         } = instruction;
 
-        let mut memory = RefCell::borrow_mut(&self.memory);
+        let mut memory = AtomicRefCell::borrow_mut(&self.memory);
 
         let alpha_read = memory.read(addr_space, alpha_ptr);
         let length_read = memory.read_cell(addr_space, length_ptr);
@@ -538,7 +538,7 @@ impl<F: PrimeField32> FriReducedOpeningChip<F> {
         let width = self.trace_width();
         let height = next_power_of_two_or_zero(self.height);
         let mut flat_trace = F::zero_vec(width * height);
-        let aux_cols_factory = RefCell::borrow(&self.memory).aux_cols_factory();
+        let aux_cols_factory = AtomicRefCell::borrow(&self.memory).aux_cols_factory();
 
         let mut idx = 0;
         for record in self.records {
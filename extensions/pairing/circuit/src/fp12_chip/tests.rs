@@ -102,6 +102,7 @@ fn test_fp12_add_bn254() {
         modulus: BN254_MODULUS.clone(),
         num_limbs: BN254_NUM_LIMBS,
         limb_bits: BN254_LIMB_BITS,
+        ..Default::default()
     };
     let expr = fp12_add_expr(
         config,
@@ -130,6 +131,7 @@ fn test_fp12_sub_bn254() {
         modulus: BN254_MODULUS.clone(),
         num_limbs: BN254_NUM_LIMBS,
         limb_bits: BN254_LIMB_BITS,
+        ..Default::default()
     };
     let expr = fp12_sub_expr(
         config,
@@ -158,6 +160,7 @@ fn test_fp12_mul_bn254() {
         modulus: BN254_MODULUS.clone(),
         num_limbs: BN254_NUM_LIMBS,
         limb_bits: BN254_LIMB_BITS,
+        ..Default::default()
     };
     let xi = BN254_XI_ISIZE;
     let expr = fp12_mul_expr(
@@ -188,6 +191,7 @@ fn test_fp12_add_bls12381() {
         modulus: BLS12_381_MODULUS.clone(),
         num_limbs: BLS12_381_NUM_LIMBS,
         limb_bits: BLS12_381_LIMB_BITS,
+        ..Default::default()
     };
     let expr = fp12_add_expr(
         config,
@@ -216,6 +220,7 @@ fn test_fp12_sub_bls12381() {
         modulus: BLS12_381_MODULUS.clone(),
         num_limbs: BLS12_381_NUM_LIMBS,
         limb_bits: BLS12_381_LIMB_BITS,
+        ..Default::default()
     };
     let expr = fp12_sub_expr(
         config,
@@ -246,6 +251,7 @@ fn test_fp12_mul_bls12381() {
         modulus: BLS12_381_MODULUS.clone(),
         num_limbs: BLS12_381_NUM_LIMBS,
         limb_bits: BLS12_381_LIMB_BITS,
+        ..Default::default()
     };
     let xi = BLS12_381_XI_ISIZE;
     let expr = fp12_mul_expr(
@@ -57,6 +57,7 @@ fn test_mul_013_by_013() {
             modulus: BN254_MODULUS.clone(),
             num_limbs: NUM_LIMBS,
             limb_bits: LIMB_BITS,
+            ..Default::default()
         },
         BN254_XI_ISIZE,
         PairingOpcode::default_offset(),
@@ -153,6 +154,7 @@ fn test_mul_by_01234() {
             modulus: BN254_MODULUS.clone(),
             num_limbs: NUM_LIMBS,
             limb_bits: LIMB_BITS,
+            ..Default::default()
         },
         BN254_XI_ISIZE,
         PairingOpcode::default_offset(),
@@ -228,6 +230,7 @@ fn test_evaluate_line() {
         modulus: BN254_MODULUS.clone(),
         limb_bits: BN254_LIMB_BITS,
         num_limbs: BN254_NUM_LIMBS,
+        ..Default::default()
     };
     let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
     let bitwise_chip = Arc::new(BitwiseOperationLookupChip::<RV32_CELL_BITS>::new(
@@ -51,6 +51,7 @@ fn test_mul_023_by_023() {
             modulus: BLS12_381_MODULUS.clone(),
             num_limbs: BLS12_381_NUM_LIMBS,
             limb_bits: BLS12_381_LIMB_BITS,
+            ..Default::default()
         },
         BLS12_381_XI_ISIZE,
         PairingOpcode::default_offset(),
@@ -150,6 +151,7 @@ fn test_mul_by_02345() {
             modulus: BLS12_381_MODULUS.clone(),
             num_limbs: BLS12_381_NUM_LIMBS,
             limb_bits: BLS12_381_LIMB_BITS,
+            ..Default::default()
         },
         BLS12_381_XI_ISIZE,
         PairingOpcode::default_offset(),
@@ -146,6 +146,7 @@ mod tests {
                 modulus: BN254_MODULUS.clone(),
                 limb_bits: LIMB_BITS,
                 num_limbs: NUM_LIMBS,
+                ..Default::default()
             },
             PairingOpcode::default_offset(),
         );
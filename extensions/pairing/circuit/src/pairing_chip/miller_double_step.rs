@@ -125,6 +125,7 @@ mod tests {
             modulus: BN254_MODULUS.clone(),
             limb_bits: BN254_LIMB_BITS,
             num_limbs: BN254_NUM_LIMBS,
+            ..Default::default()
         };
         let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
         let bitwise_chip = Arc::new(BitwiseOperationLookupChip::<RV32_CELL_BITS>::new(
@@ -192,6 +193,7 @@ mod tests {
             modulus: BLS12_381_MODULUS.clone(),
             limb_bits: BLS12_381_LIMB_BITS,
             num_limbs: BLS12_381_NUM_LIMBS,
+            ..Default::default()
         };
         let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
         let bitwise_chip = Arc::new(BitwiseOperationLookupChip::<RV32_CELL_BITS>::new(
@@ -126,6 +126,7 @@ impl<F: PrimeField32> VmExtension<F> for PairingExtension {
                         modulus: curve.curve_config().modulus.clone(),
                         num_limbs: 32,
                         limb_bits: 8,
+                        ..Default::default()
                     };
                     let miller_double = MillerDoubleStepChip::new(
                         Rv32VecHeapAdapterChip::<F, 1, 4, 8, 32, 32>::new(
@@ -241,6 +242,7 @@ impl<F: PrimeField32> VmExtension<F> for PairingExtension {
                         modulus: curve.curve_config().modulus.clone(),
                         num_limbs: 48,
                         limb_bits: 8,
+                        ..Default::default()
                     };
                     let miller_double = MillerDoubleStepChip::new(
                         Rv32VecHeapAdapterChip::<F, 1, 12, 24, 16, 16>::new(
@@ -275,7 +275,48 @@ impl PairingCheck for Bls12_381 {
     ) -> (Self::Fp12, Self::Fp12) {
         #[cfg(not(target_os = "zkvm"))]
         {
-            todo!()
+            #[cfg(not(feature = "halo2curves"))]
+            panic!("pairing_check_hint outside of the zkVM requires the `halo2curves` feature");
+            #[cfg(feature = "halo2curves")]
+            {
+                use group::ff::PrimeField;
+                use halo2curves_axiom::bls12_381::{Fq, Fq12, Fq2};
+                use openvm_algebra_guest::IntMod;
+
+                use crate::{
+                    halo2curves_shims::bls12_381::Bls12_381 as Halo2Bls12_381, pairing::FinalExp,
+                };
+
+                // Same conversions as the hint phantom instruction of the VM
+                let to_fq = |x: &Fp| {
+                    let repr: [u8; 48] = x.as_le_bytes().try_into().unwrap();
+                    Fq::from_repr(repr.into()).unwrap()
+                };
+                let to_fq2 = |x: &Fp2| Fq2 {
+                    c0: to_fq(&x.c0),
+                    c1: to_fq(&x.c1),
+                };
+                let to_fp12 = |x: Fq12| Fp12 {
+                    c: x.to_coeffs().map(|fq2| {
+                        Fp2::new(
+                            Fp::from_le_bytes(&fq2.c0.to_bytes()),
+                            Fp::from_le_bytes(&fq2.c1.to_bytes()),
+                        )
+                    }),
+                };
+
+                let p = P
+                    .iter()
+                    .map(|p| AffinePoint::new(to_fq(&p.x), to_fq(&p.y)))
+                    .collect::<Vec<_>>();
+                let q = Q
+                    .iter()
+                    .map(|q| AffinePoint::new(to_fq2(&q.x), to_fq2(&q.y)))
+                    .collect::<Vec<_>>();
+                let f: Fq12 = Halo2Bls12_381::multi_miller_loop(&p, &q);
+                let (c, s) = Halo2Bls12_381::final_exp_hint(&f);
+                (to_fp12(c), to_fp12(s))
+            }
         }
         #[cfg(target_os = "zkvm")]
         {
@@ -12,7 +12,8 @@ use super::{Fp, Fp12, Fp2};
 use crate::{
     bls12_381::Bls12_381,
     pairing::{
-        fp2_invert_assign, fp6_invert_assign, fp6_square_assign, MultiMillerLoop, PairingIntrinsics,
+        fp2_invert_assign, fp6_invert_assign, fp6_square_assign, MultiMillerLoop, PairingCheck,
+        PairingIntrinsics,
     },
 };
 
@@ -259,3 +260,23 @@ fn test_bls12381_miller_loop_identity_2() {
         halo2curves_axiom::bls12_381::multi_miller_loop(&[(&h2c_p, &h2c_q_prepared)]);
     assert_miller_results_eq(compare_miller, f);
 }
+
+#[test]
+fn test_bls12381_pairing_check() {
+    let mut rng = StdRng::seed_from_u64(91);
+    let h2c_p = G1Affine::random(&mut rng);
+    let h2c_q = G2Affine::random(&mut rng);
+
+    let [p, neg_p] = [h2c_p, -h2c_p].map(|h2c_p| AffinePoint {
+        x: convert_bls12381_halo2_fq_to_fp(h2c_p.x),
+        y: convert_bls12381_halo2_fq_to_fp(h2c_p.y),
+    });
+    let q = AffinePoint {
+        x: convert_bls12381_halo2_fq2_to_fp2(h2c_q.x),
+        y: convert_bls12381_halo2_fq2_to_fp2(h2c_q.y),
+    };
+
+    // e(P, Q) * e(-P, Q) = 1
+    Bls12_381::pairing_check(&[p.clone(), neg_p], &[q.clone(), q.clone()]).unwrap();
+    assert!(Bls12_381::pairing_check(&[p.clone(), p], &[q.clone(), q]).is_err());
+}
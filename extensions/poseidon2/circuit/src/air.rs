@@ -0,0 +1,251 @@
+use std::{borrow::Borrow, sync::Arc};
+
+use itertools::izip;
+use openvm_circuit::{
+    arch::{ExecutionBridge, ExecutionState},
+    system::memory::{offline_checker::MemoryBridge, MemoryAddress},
+};
+use openvm_circuit_primitives::bitwise_op_lookup::BitwiseOperationLookupBus;
+use openvm_instructions::riscv::{
+    RV32_CELL_BITS, RV32_MEMORY_AS, RV32_REGISTER_AS, RV32_REGISTER_NUM_LIMBS,
+};
+use openvm_poseidon2_air::{Poseidon2SubAir, BABY_BEAR_POSEIDON2_HALF_FULL_ROUNDS};
+use openvm_poseidon2_guest::{POSEIDON2_CHUNK_SIZE, POSEIDON2_DIGEST_BYTES};
+use openvm_rv32im_circuit::adapters::abstract_compose;
+use openvm_stark_backend::{
+    air_builders::sub::SubAirBuilder,
+    interaction::InteractionBuilder,
+    p3_air::{Air, AirBuilder, BaseAir},
+    p3_field::{AbstractField, Field},
+    p3_matrix::Matrix,
+    rap::{BaseAirWithPublicValues, PartitionedBaseAir},
+};
+
+use super::{
+    columns::{Rv32Poseidon2VmCols, NUM_RV32_POSEIDON2_VM_COLS},
+    BABY_BEAR_TOP_BYTE, POSEIDON2_REGISTER_READS, POSEIDON2_SBOX_REGISTERS,
+    POSEIDON2_TIMESTAMP_CHANGE, POSEIDON2_WIDTH, POSEIDON2_WORD_SIZE,
+};
+
+#[derive(Debug, derive_new::new)]
+pub struct Rv32Poseidon2VmAir<F: Field> {
+    pub execution_bridge: ExecutionBridge,
+    pub memory_bridge: MemoryBridge,
+    /// Bus to send 8-bit range checks to.
+    pub bitwise_lookup_bus: BitwiseOperationLookupBus,
+    pub(super) subair: Arc<Poseidon2SubAir<F, POSEIDON2_SBOX_REGISTERS>>,
+    /// Maximum number of bits allowed for an address pointer
+    pub ptr_max_bits: usize,
+    pub(super) offset: usize,
+}
+
+impl<F: Field> BaseAirWithPublicValues<F> for Rv32Poseidon2VmAir<F> {}
+impl<F: Field> PartitionedBaseAir<F> for Rv32Poseidon2VmAir<F> {}
+impl<F: Field> BaseAir<F> for Rv32Poseidon2VmAir<F> {
+    fn width(&self) -> usize {
+        NUM_RV32_POSEIDON2_VM_COLS
+    }
+}
+
+impl<AB: InteractionBuilder> Air<AB> for Rv32Poseidon2VmAir<AB::F> {
+    fn eval(&self, builder: &mut AB) {
+        let mut sub_builder = SubAirBuilder::<
+            AB,
+            Poseidon2SubAir<AB::F, POSEIDON2_SBOX_REGISTERS>,
+            AB::F,
+        >::new(builder, 0..self.subair.width());
+        self.subair.eval(&mut sub_builder);
+
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let local: &Rv32Poseidon2VmCols<AB::Var> = (*local).borrow();
+
+        let instruction = local.instruction;
+        builder.assert_bool(instruction.is_permute);
+        builder.assert_bool(instruction.is_compress);
+        let is_valid = instruction.is_permute + instruction.is_compress;
+        builder.assert_bool(is_valid.clone());
+
+        self.eval_state(builder, local, is_valid.clone());
+        self.eval_instruction(builder, local, is_valid);
+    }
+}
+
+impl<F: Field> Rv32Poseidon2VmAir<F> {
+    /// Connect the permutation to the bytes in memory.
+    ///
+    /// The input elements are the composition of the input bytes, which are bytes in memory, so a
+    /// word that is not canonical is reduced modulo `p`. The output bytes are range checked and
+    /// constrained to compose to a canonical element, i.e. at most `p - 1 = 0x78000000`: the
+    /// most significant byte is at most `0x77`, or it is `0x78` and the other bytes are zero.
+    fn eval_state<AB: InteractionBuilder<F = F>>(
+        &self,
+        builder: &mut AB,
+        local: &Rv32Poseidon2VmCols<AB::Var>,
+        is_valid: AB::Expr,
+    ) {
+        let post = &local.inner.ending_full_rounds[BABY_BEAR_POSEIDON2_HALF_FULL_ROUNDS - 1].post;
+        for i in 0..POSEIDON2_WIDTH {
+            builder.assert_eq(
+                local.inner.inputs[i],
+                abstract_compose::<AB::Expr, _>(local.input_bytes[i]),
+            );
+            builder.when(is_valid.clone()).assert_eq(
+                post[i],
+                abstract_compose::<AB::Expr, _>(local.output_bytes[i]),
+            );
+
+            let [b0, b1, b2, b3] = local.output_bytes[i];
+            let flag = local.output_top_flag[i];
+            builder.assert_bool(flag);
+            builder.assert_zero(flag * (b0 + b1 + b2));
+        }
+
+        let range_checked: Vec<AB::Expr> = local
+            .output_bytes
+            .iter()
+            .flatten()
+            .map(|&byte| byte.into())
+            .chain(
+                local
+                    .output_bytes
+                    .iter()
+                    .zip(local.output_top_flag)
+                    .map(|(bytes, flag)| {
+                        AB::Expr::from_canonical_u32(BABY_BEAR_TOP_BYTE - 1) + flag
+                            - bytes[POSEIDON2_WORD_SIZE - 1]
+                    }),
+            )
+            .collect();
+        for pair in range_checked.chunks_exact(2) {
+            self.bitwise_lookup_bus
+                .send_range(pair[0].clone(), pair[1].clone())
+                .eval(builder, is_valid.clone());
+        }
+    }
+
+    /// Receive the instruction, read the pointers from the registers, read the input words and
+    /// write the output words.
+    fn eval_instruction<AB: InteractionBuilder<F = F>>(
+        &self,
+        builder: &mut AB,
+        local: &Rv32Poseidon2VmCols<AB::Var>,
+        is_valid: AB::Expr,
+    ) {
+        let instruction = local.instruction;
+        let is_permute = instruction.is_permute;
+        let is_compress = instruction.is_compress;
+
+        let left_ptr = abstract_compose::<AB::Expr, _>(instruction.left_ptr);
+        builder.when(is_permute).assert_eq(
+            instruction.right_ptr,
+            left_ptr.clone() + AB::F::from_canonical_usize(POSEIDON2_DIGEST_BYTES),
+        );
+        builder.when(is_compress).assert_eq(
+            instruction.right_ptr,
+            abstract_compose::<AB::Expr, _>(instruction.rs2_val),
+        );
+
+        self.execution_bridge
+            .execute_and_increment_pc(
+                AB::Expr::from_canonical_usize(self.offset) + is_compress,
+                [
+                    instruction.rd_ptr.into(),
+                    instruction.rs1_ptr.into(),
+                    instruction.rs2_ptr.into(),
+                    AB::Expr::from_canonical_u32(RV32_REGISTER_AS),
+                    AB::Expr::from_canonical_u32(RV32_MEMORY_AS),
+                ],
+                ExecutionState::new(instruction.pc, instruction.start_timestamp),
+                AB::Expr::from_canonical_usize(POSEIDON2_TIMESTAMP_CHANGE),
+            )
+            .eval(builder, is_valid.clone());
+
+        for (i, (ptr, data, aux, count)) in izip!(
+            [instruction.rd_ptr, instruction.rs1_ptr, instruction.rs2_ptr],
+            [
+                instruction.dst_ptr,
+                instruction.left_ptr,
+                instruction.rs2_val
+            ],
+            &local.mem_oc.register_aux,
+            [is_valid.clone(), is_valid.clone(), is_compress.into()],
+        )
+        .enumerate()
+        {
+            self.memory_bridge
+                .read(
+                    MemoryAddress::new(AB::F::from_canonical_u32(RV32_REGISTER_AS), ptr),
+                    data,
+                    instruction.start_timestamp + AB::F::from_canonical_usize(i),
+                    aux,
+                )
+                .eval(builder, count);
+        }
+        // Range check that the pointers are less than 2^ptr_max_bits
+        let limb_shift = AB::F::from_canonical_usize(
+            1 << (RV32_CELL_BITS * RV32_REGISTER_NUM_LIMBS - self.ptr_max_bits),
+        );
+        self.bitwise_lookup_bus
+            .send_range(
+                instruction.dst_ptr[RV32_REGISTER_NUM_LIMBS - 1] * limb_shift,
+                instruction.left_ptr[RV32_REGISTER_NUM_LIMBS - 1] * limb_shift,
+            )
+            .eval(builder, is_valid.clone());
+        self.bitwise_lookup_bus
+            .send_range(
+                instruction.rs2_val[RV32_REGISTER_NUM_LIMBS - 1] * limb_shift,
+                AB::Expr::ZERO,
+            )
+            .eval(builder, is_compress);
+
+        let dst_ptr = abstract_compose::<AB::Expr, _>(instruction.dst_ptr);
+        let read_timestamp =
+            instruction.start_timestamp + AB::F::from_canonical_usize(POSEIDON2_REGISTER_READS);
+        let write_timestamp = read_timestamp.clone() + AB::F::from_canonical_usize(POSEIDON2_WIDTH);
+        for (i, (input_word, output_word, read_aux, write_aux)) in izip!(
+            local.input_bytes,
+            local.output_bytes,
+            &local.mem_oc.input_reads,
+            &local.mem_oc.output_writes,
+        )
+        .enumerate()
+        {
+            let (read_ptr, offset) = if i < POSEIDON2_CHUNK_SIZE {
+                (left_ptr.clone(), i)
+            } else {
+                (instruction.right_ptr.into(), i - POSEIDON2_CHUNK_SIZE)
+            };
+            let timestamp_offset = AB::F::from_canonical_usize(i);
+            self.memory_bridge
+                .read(
+                    MemoryAddress::new(
+                        AB::F::from_canonical_u32(RV32_MEMORY_AS),
+                        read_ptr + AB::F::from_canonical_usize(offset * POSEIDON2_WORD_SIZE),
+                    ),
+                    input_word,
+                    read_timestamp.clone() + timestamp_offset,
+                    read_aux,
+                )
+                .eval(builder, is_valid.clone());
+
+            // Only the first half of the state is written on POSEIDON2_COMPRESS
+            let write_count = if i < POSEIDON2_CHUNK_SIZE {
+                is_valid.clone()
+            } else {
+                is_permute.into()
+            };
+            self.memory_bridge
+                .write(
+                    MemoryAddress::new(
+                        AB::F::from_canonical_u32(RV32_MEMORY_AS),
+                        dst_ptr.clone() + AB::F::from_canonical_usize(i * POSEIDON2_WORD_SIZE),
+                    ),
+                    output_word,
+                    write_timestamp.clone() + timestamp_offset,
+                    write_aux,
+                )
+                .eval(builder, write_count);
+        }
+    }
+}
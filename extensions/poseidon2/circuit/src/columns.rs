@@ -0,0 +1,68 @@
+use core::mem::size_of;
+
+use openvm_circuit::system::memory::offline_checker::{MemoryReadAuxCols, MemoryWriteAuxCols};
+use openvm_circuit_primitives_derive::AlignedBorrow;
+use openvm_instructions::riscv::RV32_REGISTER_NUM_LIMBS;
+use openvm_poseidon2_air::Poseidon2SubCols;
+
+use super::{
+    POSEIDON2_REGISTER_READS, POSEIDON2_SBOX_REGISTERS, POSEIDON2_WIDTH, POSEIDON2_WORD_SIZE,
+};
+
+/// Each instruction takes one row. Dummy rows have no flag set and permute the zero state.
+#[repr(C)]
+#[derive(AlignedBorrow)]
+pub struct Rv32Poseidon2VmCols<T> {
+    /// Columns for the permutation. They **must** be the first columns of the row.
+    pub inner: Poseidon2SubCols<T, POSEIDON2_SBOX_REGISTERS>,
+    /// Columns for instruction interface and register access
+    pub instruction: Rv32Poseidon2InstructionCols<T>,
+    /// Little-endian bytes of the input words as read from memory. The input elements of the
+    /// permutation are these words reduced modulo the BabyBear prime.
+    pub input_bytes: [[T; POSEIDON2_WORD_SIZE]; POSEIDON2_WIDTH],
+    /// Little-endian bytes of the canonical output elements. Range checked to 8 bits.
+    pub output_bytes: [[T; POSEIDON2_WORD_SIZE]; POSEIDON2_WIDTH],
+    /// Set if the most significant byte of an output element is that of `p - 1`, in which case
+    /// the other bytes must be zero.
+    pub output_top_flag: [T; POSEIDON2_WIDTH],
+    /// Auxiliary columns for offline memory checking
+    pub mem_oc: Rv32Poseidon2MemoryCols<T>,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, AlignedBorrow)]
+pub struct Rv32Poseidon2InstructionCols<T> {
+    /// Program counter
+    pub pc: T,
+    pub is_permute: T,
+    pub is_compress: T,
+    /// The timestamp of the first register read
+    pub start_timestamp: T,
+    /// Pointer to address space 1 `rd` register
+    pub rd_ptr: T,
+    /// Pointer to address space 1 `rs1` register
+    pub rs1_ptr: T,
+    /// Pointer to address space 1 `rs2` register. Zero on POSEIDON2_PERMUTE.
+    pub rs2_ptr: T,
+    /// `dst_ptr <- [rd_ptr:4]_1`
+    pub dst_ptr: [T; RV32_REGISTER_NUM_LIMBS],
+    /// `left_ptr <- [rs1_ptr:4]_1`
+    pub left_ptr: [T; RV32_REGISTER_NUM_LIMBS],
+    /// `[rs2_ptr:4]_1` on POSEIDON2_COMPRESS. Zero on POSEIDON2_PERMUTE.
+    pub rs2_val: [T; RV32_REGISTER_NUM_LIMBS],
+    /// Pointer to the right half of the input: `left_ptr + 32` on POSEIDON2_PERMUTE and the
+    /// composition of `rs2_val` on POSEIDON2_COMPRESS.
+    pub right_ptr: T,
+}
+
+#[repr(C)]
+#[derive(Clone, Debug, AlignedBorrow)]
+pub struct Rv32Poseidon2MemoryCols<T> {
+    /// The `rs2` read is only used on POSEIDON2_COMPRESS
+    pub register_aux: [MemoryReadAuxCols<T, RV32_REGISTER_NUM_LIMBS>; POSEIDON2_REGISTER_READS],
+    pub input_reads: [MemoryReadAuxCols<T, POSEIDON2_WORD_SIZE>; POSEIDON2_WIDTH],
+    /// The writes of the second half are only used on POSEIDON2_PERMUTE
+    pub output_writes: [MemoryWriteAuxCols<T, POSEIDON2_WORD_SIZE>; POSEIDON2_WIDTH],
+}
+
+pub const NUM_RV32_POSEIDON2_VM_COLS: usize = size_of::<Rv32Poseidon2VmCols<u8>>();
@@ -0,0 +1,108 @@
+use std::sync::Arc;
+
+use derive_more::derive::From;
+use openvm_circuit::{
+    arch::{
+        SystemConfig, SystemExecutor, SystemPeriphery, SystemPort, VmChipComplex, VmConfig,
+        VmExtension, VmInventory, VmInventoryBuilder, VmInventoryError,
+    },
+    system::phantom::PhantomChip,
+};
+use openvm_circuit_derive::{AnyEnum, InstructionExecutor, VmConfig};
+use openvm_circuit_primitives::bitwise_op_lookup::{
+    BitwiseOperationLookupBus, BitwiseOperationLookupChip,
+};
+use openvm_circuit_primitives_derive::{Chip, ChipUsageGetter};
+use openvm_instructions::*;
+use openvm_poseidon2_transpiler::Rv32Poseidon2Opcode;
+use openvm_rv32im_circuit::{
+    Rv32I, Rv32IExecutor, Rv32IPeriphery, Rv32Io, Rv32IoExecutor, Rv32IoPeriphery, Rv32M,
+    Rv32MExecutor, Rv32MPeriphery,
+};
+use openvm_stark_backend::p3_field::PrimeField32;
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+
+use crate::*;
+
+#[derive(Clone, Debug, VmConfig, derive_new::new, Serialize, Deserialize)]
+pub struct Rv32Poseidon2Config {
+    #[system]
+    pub system: SystemConfig,
+    #[extension]
+    pub rv32i: Rv32I,
+    #[extension]
+    pub rv32m: Rv32M,
+    #[extension]
+    pub io: Rv32Io,
+    #[extension]
+    pub poseidon2: Rv32Poseidon2,
+}
+
+impl Default for Rv32Poseidon2Config {
+    fn default() -> Self {
+        Self {
+            system: SystemConfig::default().with_continuations(),
+            rv32i: Rv32I,
+            rv32m: Rv32M::default(),
+            io: Rv32Io,
+            poseidon2: Rv32Poseidon2,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub struct Rv32Poseidon2;
+
+#[derive(ChipUsageGetter, Chip, InstructionExecutor, From, AnyEnum)]
+pub enum Rv32Poseidon2Executor<F: PrimeField32> {
+    Poseidon2(Rv32Poseidon2VmChip<F>),
+}
+
+#[derive(From, ChipUsageGetter, Chip, AnyEnum)]
+pub enum Rv32Poseidon2Periphery<F: PrimeField32> {
+    BitwiseOperationLookup(Arc<BitwiseOperationLookupChip<8>>),
+    Phantom(PhantomChip<F>),
+}
+
+impl<F: PrimeField32> VmExtension<F> for Rv32Poseidon2 {
+    type Executor = Rv32Poseidon2Executor<F>;
+    type Periphery = Rv32Poseidon2Periphery<F>;
+
+    fn build(
+        &self,
+        builder: &mut VmInventoryBuilder<F>,
+    ) -> Result<VmInventory<Self::Executor, Self::Periphery>, VmInventoryError> {
+        let mut inventory = VmInventory::new();
+        let SystemPort {
+            execution_bus,
+            program_bus,
+            memory_controller,
+        } = builder.system_port();
+        let bitwise_lu_chip = if let Some(chip) = builder
+            .find_chip::<Arc<BitwiseOperationLookupChip<8>>>()
+            .first()
+        {
+            Arc::clone(chip)
+        } else {
+            let bitwise_lu_bus = BitwiseOperationLookupBus::new(builder.new_bus_idx());
+            let chip = Arc::new(BitwiseOperationLookupChip::new(bitwise_lu_bus));
+            inventory.add_periphery_chip(chip.clone());
+            chip
+        };
+
+        let poseidon2_chip = Rv32Poseidon2VmChip::new(
+            execution_bus,
+            program_bus,
+            memory_controller,
+            bitwise_lu_chip,
+            Rv32Poseidon2Opcode::default_offset(),
+        );
+        inventory.add_executor(
+            poseidon2_chip,
+            Rv32Poseidon2Opcode::iter().map(VmOpcode::with_default_offset),
+        )?;
+
+        Ok(inventory)
+    }
+}
@@ -0,0 +1,227 @@
+//! Poseidon2 permutation and compression over BabyBear for RV32 guests, with each field element
+//! a little-endian `u32` in memory. This is the permutation the system uses for its memory
+//! commitments, so guests can verify them without implementing it in software.
+use std::{array::from_fn, sync::Arc};
+
+use openvm_circuit::{
+    arch::{
+        vm_poseidon2_config, ExecutionBridge, ExecutionBus, ExecutionError, ExecutionState,
+        InstructionExecutor,
+    },
+    system::{
+        memory::{MemoryControllerRef, MemoryReadRecord, MemoryWriteRecord},
+        program::ProgramBus,
+    },
+};
+use openvm_circuit_primitives::bitwise_op_lookup::BitwiseOperationLookupChip;
+use openvm_instructions::{
+    instruction::Instruction, program::DEFAULT_PC_STEP, riscv::RV32_REGISTER_NUM_LIMBS, UsizeOpcode,
+};
+use openvm_poseidon2_air::Poseidon2SubChip;
+use openvm_poseidon2_guest::{POSEIDON2_CHUNK_SIZE, POSEIDON2_DIGEST_BYTES, POSEIDON2_STATE_BYTES};
+use openvm_poseidon2_transpiler::Rv32Poseidon2Opcode;
+use openvm_rv32im_circuit::adapters::read_rv32_register;
+use openvm_stark_backend::p3_field::PrimeField32;
+
+pub mod air;
+pub mod columns;
+pub mod trace;
+
+mod extension;
+pub use extension::*;
+
+#[cfg(test)]
+mod tests;
+
+pub use air::Rv32Poseidon2VmAir;
+pub use openvm_poseidon2_guest::POSEIDON2_WIDTH;
+
+// ==== Constants for register/memory adapter ====
+/// Register reads to get the output pointer and the two input pointers
+const POSEIDON2_REGISTER_READS: usize = 3;
+/// Number of cells to read/write in a single memory access
+const POSEIDON2_WORD_SIZE: usize = 4;
+/// Amount to advance the timestamp by after execution of one instruction. All the words of the
+/// state are written on POSEIDON2_PERMUTE, and only the first half on POSEIDON2_COMPRESS, but
+/// both advance the timestamp as if the whole state is written.
+const POSEIDON2_TIMESTAMP_CHANGE: usize = POSEIDON2_REGISTER_READS + 2 * POSEIDON2_WIDTH;
+
+/// The most significant byte of `p - 1` for the BabyBear prime `p = 0x78000001`. An element is
+/// canonical if its most significant byte is smaller, or equal with all other bytes zero.
+const BABY_BEAR_TOP_BYTE: u32 = 0x78;
+
+/// The permutation uses one S-box register, which keeps the constraint degree at 3.
+pub const POSEIDON2_SBOX_REGISTERS: usize = 1;
+
+#[derive(Debug)]
+pub struct Rv32Poseidon2VmChip<F: PrimeField32> {
+    pub air: Arc<Rv32Poseidon2VmAir<F>>,
+    pub subchip: Poseidon2SubChip<F, POSEIDON2_SBOX_REGISTERS>,
+    /// IO and memory data necessary for each opcode call
+    pub records: Vec<Rv32Poseidon2Record<F>>,
+    pub memory_controller: MemoryControllerRef<F>,
+    pub bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<8>>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Rv32Poseidon2Record<F> {
+    pub from_state: ExecutionState<u32>,
+    pub opcode: Rv32Poseidon2Opcode,
+    pub dst_ptr_read: MemoryReadRecord<F, RV32_REGISTER_NUM_LIMBS>,
+    pub left_ptr_read: MemoryReadRecord<F, RV32_REGISTER_NUM_LIMBS>,
+    /// Only on POSEIDON2_COMPRESS. The right half of the state directly follows the left half
+    /// on POSEIDON2_PERMUTE.
+    pub right_ptr_read: Option<MemoryReadRecord<F, RV32_REGISTER_NUM_LIMBS>>,
+    pub right_ptr: u32,
+    pub input_reads: [MemoryReadRecord<F, POSEIDON2_WORD_SIZE>; POSEIDON2_WIDTH],
+    /// The permutation of the input elements.
+    pub output: [F; POSEIDON2_WIDTH],
+    pub output_writes: [MemoryWriteRecord<F, POSEIDON2_WORD_SIZE>; POSEIDON2_CHUNK_SIZE],
+    /// Only on POSEIDON2_PERMUTE
+    pub upper_output_writes:
+        Option<[MemoryWriteRecord<F, POSEIDON2_WORD_SIZE>; POSEIDON2_CHUNK_SIZE]>,
+}
+
+impl<F: PrimeField32> Rv32Poseidon2VmChip<F> {
+    pub fn new(
+        execution_bus: ExecutionBus,
+        program_bus: ProgramBus,
+        memory_controller: MemoryControllerRef<F>,
+        bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<8>>,
+        offset: usize,
+    ) -> Self {
+        let ptr_max_bits = memory_controller.borrow().mem_config().pointer_max_bits;
+        let memory_bridge = memory_controller.borrow().memory_bridge();
+        // The same permutation as the system uses for memory commitments
+        let subchip = Poseidon2SubChip::new(vm_poseidon2_config());
+        Self {
+            air: Arc::new(Rv32Poseidon2VmAir::new(
+                ExecutionBridge::new(execution_bus, program_bus),
+                memory_bridge,
+                bitwise_lookup_chip.bus(),
+                subchip.air.clone(),
+                ptr_max_bits,
+                offset,
+            )),
+            subchip,
+            records: Vec::new(),
+            memory_controller,
+            bitwise_lookup_chip,
+        }
+    }
+}
+
+impl<F: PrimeField32> InstructionExecutor<F> for Rv32Poseidon2VmChip<F> {
+    fn execute(
+        &mut self,
+        instruction: Instruction<F>,
+        from_state: ExecutionState<u32>,
+    ) -> Result<ExecutionState<u32>, ExecutionError> {
+        let Instruction {
+            opcode,
+            a,
+            b,
+            c,
+            d,
+            e,
+            ..
+        } = instruction;
+        let local_opcode =
+            Rv32Poseidon2Opcode::from_usize(opcode.local_opcode_idx(self.air.offset));
+
+        let mut memory = self.memory_controller.borrow_mut();
+        debug_assert_eq!(from_state.timestamp, memory.timestamp());
+
+        let (dst_ptr_read, dst_ptr) = read_rv32_register(&mut memory, d, a);
+        let (left_ptr_read, left_ptr) = read_rv32_register(&mut memory, d, b);
+        let (right_ptr_read, right_ptr) = match local_opcode {
+            Rv32Poseidon2Opcode::POSEIDON2_PERMUTE => {
+                memory.increment_timestamp();
+                (None, left_ptr + POSEIDON2_DIGEST_BYTES as u32)
+            }
+            Rv32Poseidon2Opcode::POSEIDON2_COMPRESS => {
+                let (read, ptr) = read_rv32_register(&mut memory, d, c);
+                (Some(read), ptr)
+            }
+        };
+        let output_bytes = match local_opcode {
+            Rv32Poseidon2Opcode::POSEIDON2_PERMUTE => POSEIDON2_STATE_BYTES,
+            Rv32Poseidon2Opcode::POSEIDON2_COMPRESS => POSEIDON2_DIGEST_BYTES,
+        };
+        let max_ptr = 1 << self.air.ptr_max_bits;
+        assert!(dst_ptr as usize + output_bytes <= max_ptr);
+        assert!(left_ptr as usize + POSEIDON2_DIGEST_BYTES <= max_ptr);
+        assert!(right_ptr as usize + POSEIDON2_DIGEST_BYTES <= max_ptr);
+
+        let input_reads: [_; POSEIDON2_WIDTH] = from_fn(|i| {
+            let ptr = if i < POSEIDON2_CHUNK_SIZE {
+                left_ptr as usize + i * POSEIDON2_WORD_SIZE
+            } else {
+                right_ptr as usize + (i - POSEIDON2_CHUNK_SIZE) * POSEIDON2_WORD_SIZE
+            };
+            memory.read::<POSEIDON2_WORD_SIZE>(e, F::from_canonical_usize(ptr))
+        });
+        let input = input_reads.map(|read| F::from_wrapped_u32(word_from_cells(read.data)));
+        let output = self.subchip.permute(input);
+        tracing::trace!("[runtime] poseidon2 output: {:?}", output);
+
+        let mut write_word = |i: usize| {
+            memory.write::<POSEIDON2_WORD_SIZE>(
+                e,
+                F::from_canonical_usize(dst_ptr as usize + i * POSEIDON2_WORD_SIZE),
+                output[i]
+                    .as_canonical_u32()
+                    .to_le_bytes()
+                    .map(F::from_canonical_u8),
+            )
+        };
+        let output_writes: [_; POSEIDON2_CHUNK_SIZE] = from_fn(&mut write_word);
+        let upper_output_writes = match local_opcode {
+            Rv32Poseidon2Opcode::POSEIDON2_PERMUTE => {
+                Some(from_fn(|i| write_word(POSEIDON2_CHUNK_SIZE + i)))
+            }
+            Rv32Poseidon2Opcode::POSEIDON2_COMPRESS => {
+                memory.increment_timestamp_by(POSEIDON2_CHUNK_SIZE as u32);
+                None
+            }
+        };
+
+        self.records.push(Rv32Poseidon2Record {
+            from_state,
+            opcode: local_opcode,
+            dst_ptr_read,
+            left_ptr_read,
+            right_ptr_read,
+            right_ptr,
+            input_reads,
+            output,
+            output_writes,
+            upper_output_writes,
+        });
+
+        // NOTE: Check this is consistent with the timestamp change in Rv32Poseidon2VmAir
+        let to_timestamp = from_state.timestamp + POSEIDON2_TIMESTAMP_CHANGE as u32;
+        debug_assert_eq!(to_timestamp, memory.timestamp());
+
+        Ok(ExecutionState {
+            pc: from_state.pc + DEFAULT_PC_STEP,
+            timestamp: to_timestamp,
+        })
+    }
+
+    fn get_opcode_name(&self, opcode: usize) -> String {
+        format!(
+            "{:?}",
+            Rv32Poseidon2Opcode::from_usize(opcode - self.air.offset)
+        )
+    }
+}
+
+/// The little-endian `u32` in the byte cells of a memory word.
+fn word_from_cells<F: PrimeField32>(cells: [F; POSEIDON2_WORD_SIZE]) -> u32 {
+    u32::from_le_bytes(cells.map(|cell| {
+        cell.as_canonical_u32()
+            .try_into()
+            .expect("Memory cell not a byte")
+    }))
+}
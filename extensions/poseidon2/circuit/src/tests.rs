@@ -0,0 +1,168 @@
+use std::{array::from_fn, borrow::BorrowMut, sync::Arc};
+
+use openvm_circuit::arch::{
+    hasher::{poseidon2::vm_poseidon2_hasher, Hasher},
+    testing::{VmChipTestBuilder, VmChipTester},
+    BITWISE_OP_LOOKUP_BUS,
+};
+use openvm_circuit_primitives::bitwise_op_lookup::{
+    BitwiseOperationLookupBus, BitwiseOperationLookupChip,
+};
+use openvm_instructions::{instruction::Instruction, UsizeOpcode, VmOpcode};
+use openvm_poseidon2_guest::{
+    poseidon2_permute_words, BABY_BEAR_MODULUS, POSEIDON2_CHUNK_SIZE, POSEIDON2_DIGEST_BYTES,
+    POSEIDON2_STATE_BYTES,
+};
+use openvm_poseidon2_transpiler::Rv32Poseidon2Opcode;
+use openvm_stark_backend::{
+    p3_field::{AbstractField, PrimeField32},
+    utils::disable_debug_builder,
+    verifier::VerificationError,
+};
+use openvm_stark_sdk::{
+    config::baby_bear_blake3::BabyBearBlake3Config, p3_baby_bear::BabyBear,
+    utils::create_seeded_rng,
+};
+use rand::Rng;
+
+use super::{columns::Rv32Poseidon2VmCols, Rv32Poseidon2VmChip, POSEIDON2_WIDTH};
+
+type F = BabyBear;
+
+/// Executes each opcode on its input words and checks the words written to memory. The output
+/// of each instruction is placed after its input.
+fn build_poseidon2_test(
+    inputs: Vec<(Rv32Poseidon2Opcode, [u32; POSEIDON2_WIDTH])>,
+) -> VmChipTester<BabyBearBlake3Config> {
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = Arc::new(BitwiseOperationLookupChip::<8>::new(bitwise_bus));
+
+    let mut tester = VmChipTestBuilder::default();
+    let mut chip = Rv32Poseidon2VmChip::new(
+        tester.execution_bus(),
+        tester.program_bus(),
+        tester.memory_controller(),
+        bitwise_chip.clone(),
+        Rv32Poseidon2Opcode::default_offset(),
+    );
+
+    let [a, b, c, d, e] = [4, 8, 12, 1, 2];
+    for (k, (opcode, mut state)) in inputs.into_iter().enumerate() {
+        // The right half is not contiguous with the left half on POSEIDON2_COMPRESS
+        let left_ptr = k * 3 * POSEIDON2_STATE_BYTES;
+        let right_ptr = match opcode {
+            Rv32Poseidon2Opcode::POSEIDON2_PERMUTE => left_ptr + POSEIDON2_DIGEST_BYTES,
+            Rv32Poseidon2Opcode::POSEIDON2_COMPRESS => left_ptr + POSEIDON2_STATE_BYTES,
+        };
+        let dst_ptr = left_ptr + 2 * POSEIDON2_STATE_BYTES;
+        for (reg, ptr) in [(a, dst_ptr), (b, left_ptr), (c, right_ptr)] {
+            tester.write(d, reg, (ptr as u32).to_le_bytes().map(F::from_canonical_u8));
+        }
+        for (i, word) in state.iter().enumerate() {
+            let ptr = if i < POSEIDON2_CHUNK_SIZE {
+                left_ptr + 4 * i
+            } else {
+                right_ptr + 4 * (i - POSEIDON2_CHUNK_SIZE)
+            };
+            tester.write(e, ptr, word.to_le_bytes().map(F::from_canonical_u8));
+        }
+
+        let rs2 = match opcode {
+            Rv32Poseidon2Opcode::POSEIDON2_PERMUTE => 0,
+            Rv32Poseidon2Opcode::POSEIDON2_COMPRESS => c,
+        };
+        tester.execute(
+            &mut chip,
+            Instruction::from_isize(
+                VmOpcode::with_default_offset(opcode),
+                a as isize,
+                b as isize,
+                rs2 as isize,
+                d as isize,
+                e as isize,
+            ),
+        );
+
+        poseidon2_permute_words(&mut state);
+        let num_words = match opcode {
+            Rv32Poseidon2Opcode::POSEIDON2_PERMUTE => POSEIDON2_WIDTH,
+            Rv32Poseidon2Opcode::POSEIDON2_COMPRESS => POSEIDON2_CHUNK_SIZE,
+        };
+        for (i, word) in state[..num_words].iter().enumerate() {
+            assert_eq!(
+                tester.read::<4>(e, dst_ptr + 4 * i),
+                word.to_le_bytes().map(F::from_canonical_u8)
+            );
+        }
+    }
+    tester.build().load(chip).load(bitwise_chip).finalize()
+}
+
+#[test]
+fn rand_poseidon2_test() {
+    let mut rng = create_seeded_rng();
+    let mut inputs: Vec<_> = (0..6)
+        .map(|k| {
+            let opcode = if k % 2 == 0 {
+                Rv32Poseidon2Opcode::POSEIDON2_PERMUTE
+            } else {
+                Rv32Poseidon2Opcode::POSEIDON2_COMPRESS
+            };
+            (opcode, from_fn(|_| rng.gen_range(0..BABY_BEAR_MODULUS)))
+        })
+        .collect();
+    // Words that are not canonical are reduced
+    inputs.push((
+        Rv32Poseidon2Opcode::POSEIDON2_PERMUTE,
+        [u32::MAX; POSEIDON2_WIDTH],
+    ));
+    inputs.push((
+        Rv32Poseidon2Opcode::POSEIDON2_COMPRESS,
+        [0; POSEIDON2_WIDTH],
+    ));
+    let tester = build_poseidon2_test(inputs);
+    tester.simple_test().expect("Verification failed");
+}
+
+#[test]
+fn poseidon2_matches_system_hasher_test() {
+    let mut rng = create_seeded_rng();
+    let left: [F; POSEIDON2_CHUNK_SIZE] =
+        from_fn(|_| F::from_canonical_u32(rng.gen_range(0..BABY_BEAR_MODULUS)));
+    let right: [F; POSEIDON2_CHUNK_SIZE] =
+        from_fn(|_| F::from_canonical_u32(rng.gen_range(0..BABY_BEAR_MODULUS)));
+    let expected = vm_poseidon2_hasher::<F>().compress(&left, &right);
+
+    let mut state: [u32; POSEIDON2_WIDTH] = from_fn(|i| {
+        if i < POSEIDON2_CHUNK_SIZE {
+            left[i].as_canonical_u32()
+        } else {
+            right[i - POSEIDON2_CHUNK_SIZE].as_canonical_u32()
+        }
+    });
+    poseidon2_permute_words(&mut state);
+    assert_eq!(
+        state[..POSEIDON2_CHUNK_SIZE],
+        expected.map(|x| x.as_canonical_u32())
+    );
+}
+
+#[test]
+fn negative_poseidon2_test() {
+    let mut rng = create_seeded_rng();
+    let mut tester = build_poseidon2_test(vec![(
+        Rv32Poseidon2Opcode::POSEIDON2_PERMUTE,
+        from_fn(|_| rng.gen_range(0..BABY_BEAR_MODULUS)),
+    )]);
+
+    // Change a byte of the output, which is written to memory
+    let poseidon2_trace = tester.air_proof_inputs[2].raw.common_main.as_mut().unwrap();
+    let row: &mut Rv32Poseidon2VmCols<F> = poseidon2_trace.row_mut(0).borrow_mut();
+    row.output_bytes[0][0] += F::ONE;
+
+    disable_debug_builder();
+    assert_eq!(
+        tester.simple_test().err(),
+        Some(VerificationError::OodEvaluationMismatch)
+    );
+}
@@ -0,0 +1,157 @@
+use std::{borrow::BorrowMut, sync::Arc};
+
+use openvm_circuit::system::memory::offline_checker::{MemoryReadAuxCols, MemoryWriteAuxCols};
+use openvm_instructions::riscv::{RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS};
+use openvm_poseidon2_transpiler::Rv32Poseidon2Opcode;
+use openvm_stark_backend::{
+    config::{StarkGenericConfig, Val},
+    p3_air::BaseAir,
+    p3_field::{AbstractField, PrimeField32},
+    p3_matrix::dense::RowMajorMatrix,
+    p3_maybe_rayon::prelude::*,
+    prover::types::AirProofInput,
+    rap::{get_air_name, AnyRap},
+    Chip, ChipUsageGetter,
+};
+
+use super::{
+    columns::{Rv32Poseidon2InstructionCols, Rv32Poseidon2VmCols},
+    word_from_cells, Rv32Poseidon2VmChip, BABY_BEAR_TOP_BYTE, POSEIDON2_CHUNK_SIZE,
+    POSEIDON2_WIDTH,
+};
+
+impl<SC: StarkGenericConfig> Chip<SC> for Rv32Poseidon2VmChip<Val<SC>>
+where
+    Val<SC>: PrimeField32,
+{
+    fn air(&self) -> Arc<dyn AnyRap<SC>> {
+        self.air.clone()
+    }
+
+    fn generate_air_proof_input(self) -> AirProofInput<SC> {
+        let air = self.air();
+        let trace_width = self.trace_width();
+        let num_rows = self.current_trace_height().next_power_of_two();
+
+        // Dummy rows permute the zero state
+        let mut inputs: Vec<[Val<SC>; POSEIDON2_WIDTH]> = self
+            .records
+            .par_iter()
+            .map(|record| {
+                record
+                    .input_reads
+                    .map(|read| Val::<SC>::from_wrapped_u32(word_from_cells(read.data)))
+            })
+            .collect();
+        inputs.resize(num_rows, [Val::<SC>::ZERO; POSEIDON2_WIDTH]);
+        let inner_trace = self.subchip.generate_trace(inputs);
+        let inner_width = self.air.subair.width();
+
+        let aux_cols_factory = self.memory_controller.borrow().aux_cols_factory();
+        let limb_shift_bits = RV32_CELL_BITS * RV32_REGISTER_NUM_LIMBS - self.air.ptr_max_bits;
+
+        let mut trace =
+            RowMajorMatrix::new(Val::<SC>::zero_vec(num_rows * trace_width), trace_width);
+        trace
+            .values
+            .par_chunks_mut(trace_width)
+            .zip(inner_trace.values.par_chunks(inner_width))
+            .enumerate()
+            .for_each(|(row_idx, (row, inner_row))| {
+                // Safety: `Poseidon2SubCols` **must** be the first field in `Rv32Poseidon2VmCols`
+                row[..inner_width].copy_from_slice(inner_row);
+                let Some(record) = self.records.get(row_idx) else {
+                    return;
+                };
+                let cols: &mut Rv32Poseidon2VmCols<Val<SC>> = row.borrow_mut();
+
+                let is_permute = record.opcode == Rv32Poseidon2Opcode::POSEIDON2_PERMUTE;
+                let rs2_val = record
+                    .right_ptr_read
+                    .map_or([Val::<SC>::ZERO; RV32_REGISTER_NUM_LIMBS], |read| read.data);
+                cols.instruction = Rv32Poseidon2InstructionCols {
+                    pc: Val::<SC>::from_canonical_u32(record.from_state.pc),
+                    is_permute: Val::<SC>::from_bool(is_permute),
+                    is_compress: Val::<SC>::from_bool(!is_permute),
+                    start_timestamp: Val::<SC>::from_canonical_u32(record.from_state.timestamp),
+                    rd_ptr: record.dst_ptr_read.pointer,
+                    rs1_ptr: record.left_ptr_read.pointer,
+                    rs2_ptr: record
+                        .right_ptr_read
+                        .map_or(Val::<SC>::ZERO, |read| read.pointer),
+                    dst_ptr: record.dst_ptr_read.data,
+                    left_ptr: record.left_ptr_read.data,
+                    rs2_val,
+                    right_ptr: Val::<SC>::from_canonical_u32(record.right_ptr),
+                };
+
+                let mut range_checked = Vec::with_capacity(5 * POSEIDON2_WIDTH);
+                let mut top_checked = Vec::with_capacity(POSEIDON2_WIDTH);
+                for (i, output) in record.output.iter().enumerate() {
+                    let bytes = output.as_canonical_u32().to_le_bytes();
+                    let is_top = bytes[3] as u32 == BABY_BEAR_TOP_BYTE;
+                    cols.input_bytes[i] = record.input_reads[i].data;
+                    cols.output_bytes[i] = bytes.map(Val::<SC>::from_canonical_u8);
+                    cols.output_top_flag[i] = Val::<SC>::from_bool(is_top);
+                    range_checked.extend(bytes.map(u32::from));
+                    top_checked.push(BABY_BEAR_TOP_BYTE - 1 + is_top as u32 - bytes[3] as u32);
+                }
+                // Same order as in the AIR: all the output bytes, then the top byte checks
+                range_checked.extend(top_checked);
+                for pair in range_checked.chunks_exact(2) {
+                    self.bitwise_lookup_chip.request_range(pair[0], pair[1]);
+                }
+
+                let hi_limb = |data: [Val<SC>; RV32_REGISTER_NUM_LIMBS]| {
+                    data[RV32_REGISTER_NUM_LIMBS - 1].as_canonical_u32() << limb_shift_bits
+                };
+                self.bitwise_lookup_chip.request_range(
+                    hi_limb(record.dst_ptr_read.data),
+                    hi_limb(record.left_ptr_read.data),
+                );
+                if let Some(read) = record.right_ptr_read {
+                    self.bitwise_lookup_chip
+                        .request_range(hi_limb(read.data), 0);
+                }
+
+                cols.mem_oc.register_aux = [
+                    aux_cols_factory.make_read_aux_cols(record.dst_ptr_read),
+                    aux_cols_factory.make_read_aux_cols(record.left_ptr_read),
+                    record
+                        .right_ptr_read
+                        .map_or(MemoryReadAuxCols::disabled(), |read| {
+                            aux_cols_factory.make_read_aux_cols(read)
+                        }),
+                ];
+                for (i, read) in record.input_reads.iter().enumerate() {
+                    cols.mem_oc.input_reads[i] = aux_cols_factory.make_read_aux_cols(*read);
+                }
+                for (i, write) in record.output_writes.iter().enumerate() {
+                    cols.mem_oc.output_writes[i] = aux_cols_factory.make_write_aux_cols(*write);
+                }
+                for i in 0..POSEIDON2_CHUNK_SIZE {
+                    cols.mem_oc.output_writes[POSEIDON2_CHUNK_SIZE + i] =
+                        match record.upper_output_writes {
+                            Some(writes) => aux_cols_factory.make_write_aux_cols(writes[i]),
+                            None => MemoryWriteAuxCols::disabled(),
+                        };
+                }
+            });
+
+        AirProofInput::simple_no_pis(air, trace)
+    }
+}
+
+impl<F: PrimeField32> ChipUsageGetter for Rv32Poseidon2VmChip<F> {
+    fn air_name(&self) -> String {
+        get_air_name(&self.air)
+    }
+
+    fn current_trace_height(&self) -> usize {
+        self.records.len()
+    }
+
+    fn trace_width(&self) -> usize {
+        BaseAir::<F>::width(self.air.as_ref())
+    }
+}
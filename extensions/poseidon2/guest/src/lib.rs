@@ -0,0 +1,154 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+/// This is custom-0 defined in RISC-V spec document
+pub const OPCODE: u8 = 0x0b;
+/// Shared with the keccak256 extension, which uses funct7 `0x0` and `0x1`.
+pub const POSEIDON2_FUNCT3: u8 = 0b100;
+pub const POSEIDON2_PERMUTE_FUNCT7: u8 = 0x2;
+pub const POSEIDON2_COMPRESS_FUNCT7: u8 = 0x3;
+
+/// Number of BabyBear elements in the state of the permutation.
+pub const POSEIDON2_WIDTH: usize = 16;
+/// Number of BabyBear elements in each input and in the output of the compression.
+pub const POSEIDON2_CHUNK_SIZE: usize = 8;
+/// Number of bytes in the state, with each element a little-endian `u32`.
+pub const POSEIDON2_STATE_BYTES: usize = POSEIDON2_WIDTH * 4;
+/// Number of bytes in a digest, with each element a little-endian `u32`.
+pub const POSEIDON2_DIGEST_BYTES: usize = POSEIDON2_CHUNK_SIZE * 4;
+/// The BabyBear prime `15 * 2^27 + 1`.
+pub const BABY_BEAR_MODULUS: u32 = 0x7800_0001;
+
+/// Applies the Poseidon2 permutation to `state`, given as 16 little-endian `u32` BabyBear
+/// elements. Input elements are reduced modulo [BABY_BEAR_MODULUS] and output elements are
+/// canonical.
+#[inline(always)]
+pub fn poseidon2_permute(state: &mut [u8; POSEIDON2_STATE_BYTES]) {
+    let mut words = bytes_to_words(state);
+    poseidon2_permute_words(&mut words);
+    words_to_bytes(&words, state);
+}
+
+/// The Poseidon2 compression function used by OpenVM for its memory commitments: the first 8
+/// elements of the permutation of `left || right`, without feed-forward.
+#[inline(always)]
+pub fn poseidon2_compress(
+    left: &[u8; POSEIDON2_DIGEST_BYTES],
+    right: &[u8; POSEIDON2_DIGEST_BYTES],
+) -> [u8; POSEIDON2_DIGEST_BYTES] {
+    let mut output = [0u8; POSEIDON2_DIGEST_BYTES];
+    set_poseidon2_compress(left, right, &mut output);
+    output
+}
+
+/// Sets `output` to the Poseidon2 compression of `left` and `right`.
+#[inline(always)]
+pub fn set_poseidon2_compress(
+    left: &[u8; POSEIDON2_DIGEST_BYTES],
+    right: &[u8; POSEIDON2_DIGEST_BYTES],
+    output: &mut [u8; POSEIDON2_DIGEST_BYTES],
+) {
+    let words = poseidon2_compress_words(&bytes_to_words(left), &bytes_to_words(right));
+    words_to_bytes(&words, output);
+}
+
+/// Applies the Poseidon2 permutation to `state` in place.
+#[inline(always)]
+pub fn poseidon2_permute_words(state: &mut [u32; POSEIDON2_WIDTH]) {
+    #[cfg(not(target_os = "zkvm"))]
+    {
+        *state = host::permute(*state);
+    }
+    #[cfg(target_os = "zkvm")]
+    native_poseidon2_permute(state.as_mut_ptr(), state.as_ptr());
+}
+
+/// Returns the Poseidon2 compression of `left` and `right`.
+#[inline(always)]
+pub fn poseidon2_compress_words(
+    left: &[u32; POSEIDON2_CHUNK_SIZE],
+    right: &[u32; POSEIDON2_CHUNK_SIZE],
+) -> [u32; POSEIDON2_CHUNK_SIZE] {
+    #[cfg(not(target_os = "zkvm"))]
+    {
+        let mut state = [0u32; POSEIDON2_WIDTH];
+        state[..POSEIDON2_CHUNK_SIZE].copy_from_slice(left);
+        state[POSEIDON2_CHUNK_SIZE..].copy_from_slice(right);
+        let state = host::permute(state);
+        core::array::from_fn(|i| state[i])
+    }
+    #[cfg(target_os = "zkvm")]
+    {
+        let mut output = [0u32; POSEIDON2_CHUNK_SIZE];
+        native_poseidon2_compress(output.as_mut_ptr(), left.as_ptr(), right.as_ptr());
+        output
+    }
+}
+
+fn bytes_to_words<const N: usize, const M: usize>(bytes: &[u8; N]) -> [u32; M] {
+    debug_assert_eq!(N, 4 * M);
+    core::array::from_fn(|i| u32::from_le_bytes(bytes[4 * i..4 * i + 4].try_into().unwrap()))
+}
+
+fn words_to_bytes<const N: usize, const M: usize>(words: &[u32; M], bytes: &mut [u8; N]) {
+    for (chunk, word) in bytes.chunks_exact_mut(4).zip(words) {
+        chunk.copy_from_slice(&word.to_le_bytes());
+    }
+}
+
+#[cfg(not(target_os = "zkvm"))]
+mod host {
+    use openvm_poseidon2_air::{p3_baby_bear::BabyBear, Poseidon2Config, Poseidon2SubChip};
+    use openvm_stark_backend::p3_field::{AbstractField, PrimeField32};
+
+    use super::POSEIDON2_WIDTH;
+
+    /// The same permutation as the VM, which uses the default BabyBear round constants.
+    pub(super) fn permute(state: [u32; POSEIDON2_WIDTH]) -> [u32; POSEIDON2_WIDTH] {
+        let subchip = Poseidon2SubChip::<BabyBear, 0>::new(Poseidon2Config::default());
+        subchip
+            .permute(state.map(BabyBear::from_wrapped_u32))
+            .map(|x| x.as_canonical_u32())
+    }
+}
+
+/// Native hook for the Poseidon2 permutation.
+///
+/// # Safety
+///
+/// The VM reads the whole input before writing the output, so they may overlap.
+/// - `input` must point to 16 little-endian `u32` words that are 4-byte aligned.
+/// - `output` must point to a buffer of 16 `u32` words that is 4-byte aligned.
+#[cfg(target_os = "zkvm")]
+#[inline(always)]
+#[no_mangle]
+extern "C" fn native_poseidon2_permute(output: *mut u32, input: *const u32) {
+    openvm_platform::custom_insn_r!(
+        OPCODE,
+        POSEIDON2_FUNCT3,
+        POSEIDON2_PERMUTE_FUNCT7,
+        output,
+        input,
+        "x0"
+    );
+}
+
+/// Native hook for the Poseidon2 compression function.
+///
+/// # Safety
+///
+/// The VM reads both inputs before writing the output, so they may overlap.
+/// - `left` and `right` must each point to 8 little-endian `u32` words that are 4-byte aligned.
+/// - `output` must point to a buffer of 8 `u32` words that is 4-byte aligned.
+#[cfg(target_os = "zkvm")]
+#[inline(always)]
+#[no_mangle]
+extern "C" fn native_poseidon2_compress(output: *mut u32, left: *const u32, right: *const u32) {
+    openvm_platform::custom_insn_r!(
+        OPCODE,
+        POSEIDON2_FUNCT3,
+        POSEIDON2_COMPRESS_FUNCT7,
+        output,
+        left,
+        right
+    );
+}
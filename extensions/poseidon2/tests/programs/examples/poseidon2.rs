@@ -0,0 +1,51 @@
+#![cfg_attr(not(feature = "std"), no_main)]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use core::{array::from_fn, hint::black_box};
+
+use openvm_poseidon2_guest::{
+    poseidon2_compress, poseidon2_permute, BABY_BEAR_MODULUS, POSEIDON2_DIGEST_BYTES,
+    POSEIDON2_STATE_BYTES,
+};
+
+openvm::entry!(main);
+
+fn is_canonical(bytes: &[u8]) -> bool {
+    bytes
+        .chunks_exact(4)
+        .all(|word| u32::from_le_bytes(word.try_into().unwrap()) < BABY_BEAR_MODULUS)
+}
+
+pub fn main() {
+    let left: [u8; POSEIDON2_DIGEST_BYTES] = black_box(from_fn(|i| i as u8));
+    let right: [u8; POSEIDON2_DIGEST_BYTES] = black_box(from_fn(|i| (3 * i + 1) as u8));
+
+    // The compression is the first half of the permutation of `left || right`
+    let digest = poseidon2_compress(&left, &right);
+    let mut state = [0u8; POSEIDON2_STATE_BYTES];
+    state[..POSEIDON2_DIGEST_BYTES].copy_from_slice(&left);
+    state[POSEIDON2_DIGEST_BYTES..].copy_from_slice(&right);
+    let input = state;
+    poseidon2_permute(&mut state);
+    if state[..POSEIDON2_DIGEST_BYTES] != digest || state == input {
+        panic!();
+    }
+    if !is_canonical(&state) {
+        panic!();
+    }
+
+    // Words that are not canonical are reduced modulo p
+    let mut reduced = black_box(input);
+    let word = u32::from_le_bytes(reduced[..4].try_into().unwrap()) + BABY_BEAR_MODULUS;
+    reduced[..4].copy_from_slice(&word.to_le_bytes());
+    poseidon2_permute(&mut reduced);
+    if reduced != state {
+        panic!();
+    }
+
+    // Compressions chain like the nodes of a Merkle tree
+    let parent = poseidon2_compress(&digest, &black_box(digest));
+    if parent == digest || !is_canonical(&parent) {
+        panic!();
+    }
+}
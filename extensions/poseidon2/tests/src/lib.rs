@@ -0,0 +1,31 @@
+#[cfg(test)]
+mod tests {
+    use eyre::Result;
+    use openvm_circuit::utils::air_test;
+    use openvm_instructions::exe::VmExe;
+    use openvm_poseidon2_circuit::Rv32Poseidon2Config;
+    use openvm_poseidon2_transpiler::Poseidon2TranspilerExtension;
+    use openvm_rv32im_transpiler::{
+        Rv32ITranspilerExtension, Rv32IoTranspilerExtension, Rv32MTranspilerExtension,
+    };
+    use openvm_stark_sdk::p3_baby_bear::BabyBear;
+    use openvm_toolchain_tests::{build_example_program_at_path, get_programs_dir};
+    use openvm_transpiler::{transpiler::Transpiler, FromElf};
+
+    type F = BabyBear;
+
+    #[test]
+    fn test_poseidon2() -> Result<()> {
+        let elf = build_example_program_at_path(get_programs_dir!(), "poseidon2")?;
+        let openvm_exe = VmExe::from_elf(
+            elf,
+            Transpiler::<F>::default()
+                .with_extension(Poseidon2TranspilerExtension)
+                .with_extension(Rv32ITranspilerExtension)
+                .with_extension(Rv32MTranspilerExtension)
+                .with_extension(Rv32IoTranspilerExtension),
+        )?;
+        air_test(Rv32Poseidon2Config::default(), openvm_exe);
+        Ok(())
+    }
+}
@@ -0,0 +1,60 @@
+use openvm_instructions::{instruction::Instruction, UsizeOpcode};
+use openvm_instructions_derive::UsizeOpcode;
+use openvm_poseidon2_guest::{
+    OPCODE, POSEIDON2_COMPRESS_FUNCT7, POSEIDON2_FUNCT3, POSEIDON2_PERMUTE_FUNCT7,
+};
+use openvm_stark_backend::p3_field::PrimeField32;
+use openvm_transpiler::{util::from_r_type, EncodingRange, TranspilerExtension};
+use rrs_lib::instruction_formats::RType;
+use strum::{EnumCount, EnumIter, FromRepr};
+
+/// Poseidon2 over BabyBear, with each field element a little-endian `u32` in memory.
+/// - `POSEIDON2_PERMUTE`: the permutation of the 16 elements at `[rs1]`, written to `[rd]`.
+/// - `POSEIDON2_COMPRESS`: the first 8 elements of the permutation of the 8 elements at `[rs1]`
+///   followed by the 8 elements at `[rs2]`, written to `[rd]`.
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, EnumCount, EnumIter, FromRepr, UsizeOpcode,
+)]
+#[opcode_offset = 0x338]
+#[repr(usize)]
+#[allow(non_camel_case_types)]
+pub enum Rv32Poseidon2Opcode {
+    POSEIDON2_PERMUTE,
+    POSEIDON2_COMPRESS,
+}
+
+#[derive(Default)]
+pub struct Poseidon2TranspilerExtension;
+
+impl<F: PrimeField32> TranspilerExtension<F> for Poseidon2TranspilerExtension {
+    fn process_custom(&self, instruction_stream: &[u32]) -> Option<(Instruction<F>, usize)> {
+        if instruction_stream.is_empty() {
+            return None;
+        }
+        let instruction_u32 = instruction_stream[0];
+        let opcode = (instruction_u32 & 0x7f) as u8;
+        let funct3 = ((instruction_u32 >> 12) & 0b111) as u8;
+
+        // The funct3 is shared with the keccak256 extension, so other funct7 are left to it.
+        if (opcode, funct3) != (OPCODE, POSEIDON2_FUNCT3) {
+            return None;
+        }
+        let dec_insn = RType::new(instruction_u32);
+        let local_opcode = match dec_insn.funct7 as u8 {
+            // `rs2` is not used and must be `x0`.
+            POSEIDON2_PERMUTE_FUNCT7 if dec_insn.rs2 == 0 => Rv32Poseidon2Opcode::POSEIDON2_PERMUTE,
+            POSEIDON2_COMPRESS_FUNCT7 => Rv32Poseidon2Opcode::POSEIDON2_COMPRESS,
+            _ => return None,
+        };
+        let instruction = from_r_type(local_opcode.with_default_offset(), 2, &dec_insn);
+        Some((instruction, 1))
+    }
+
+    fn encodings(&self) -> Option<Vec<EncodingRange>> {
+        Some(vec![EncodingRange::opcode(OPCODE)
+            .with_funct3(POSEIDON2_FUNCT3)
+            .with_funct7(
+                POSEIDON2_PERMUTE_FUNCT7..=POSEIDON2_COMPRESS_FUNCT7,
+            )])
+    }
+}
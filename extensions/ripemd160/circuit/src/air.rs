@@ -0,0 +1,440 @@
+use std::{array::from_fn, borrow::Borrow, iter::once};
+
+use openvm_circuit::{
+    arch::{ExecutionBridge, ExecutionState},
+    system::memory::{offline_checker::MemoryBridge, MemoryAddress},
+};
+use openvm_circuit_primitives::{bitwise_op_lookup::BitwiseOperationLookupBus, utils::not};
+use openvm_instructions::riscv::{
+    RV32_CELL_BITS, RV32_MEMORY_AS, RV32_REGISTER_AS, RV32_REGISTER_NUM_LIMBS,
+};
+use openvm_ripemd160_transpiler::Rv32Ripemd160Opcode;
+use openvm_rv32im_circuit::adapters::abstract_compose;
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    p3_air::{Air, AirBuilder, BaseAir},
+    p3_field::AbstractField,
+    p3_matrix::Matrix,
+    rap::{BaseAirWithPublicValues, PartitionedBaseAir},
+};
+
+use super::{
+    columns::{Ripemd160LineCols, Ripemd160VmCols, NUM_RIPEMD160_VM_COLS},
+    utils::Ripemd160Line,
+    RIPEMD160_BLOCK_WORDS, RIPEMD160_REGISTER_READS, RIPEMD160_STATE_WORDS, RIPEMD160_STEPS,
+    RIPEMD160_TIMESTAMP_CHANGE, RIPEMD160_WORD_BITS, RIPEMD160_WORD_BYTES, RIPEMD160_WORD_SIZE,
+    RIPEMD160_WORD_U16S,
+};
+
+/// Number of groups of steps with the same boolean function and constant.
+const RIPEMD160_GROUPS: usize = 5;
+
+#[derive(Clone, Copy, Debug, derive_new::new)]
+pub struct Ripemd160VmAir {
+    pub execution_bridge: ExecutionBridge,
+    pub memory_bridge: MemoryBridge,
+    /// Bus to send 8-bit range checks to.
+    pub bitwise_lookup_bus: BitwiseOperationLookupBus,
+    /// Maximum number of bits allowed for an address pointer
+    pub ptr_max_bits: usize,
+    pub(super) offset: usize,
+}
+
+impl<F> BaseAirWithPublicValues<F> for Ripemd160VmAir {}
+impl<F> PartitionedBaseAir<F> for Ripemd160VmAir {}
+impl<F> BaseAir<F> for Ripemd160VmAir {
+    fn width(&self) -> usize {
+        NUM_RIPEMD160_VM_COLS
+    }
+}
+
+impl<AB: InteractionBuilder> Air<AB> for Ripemd160VmAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let (local, next) = (main.row_slice(0), main.row_slice(1));
+        let local: &Ripemd160VmCols<AB::Var> = (*local).borrow();
+        let next: &Ripemd160VmCols<AB::Var> = (*next).borrow();
+
+        self.eval_flags(builder, local, next);
+        for (line, line_cols, next_line_cols) in [
+            (Ripemd160Line::Left, &local.left, &next.left),
+            (Ripemd160Line::Right, &local.right, &next.right),
+        ] {
+            self.eval_step(builder, local, line, line_cols, next_line_cols);
+        }
+        let is_step: AB::Expr = local.flags.is_step();
+        local
+            .instruction
+            .assert_eq(&mut builder.when(is_step), next.instruction);
+        self.eval_digest(builder, local);
+        self.eval_instruction(builder, local);
+    }
+}
+
+impl Ripemd160VmAir {
+    /// Constrain the rows of each block to be the steps in order followed by the digest row.
+    /// Rows without flags are dummy rows and may only be followed by the first step of a block.
+    #[inline]
+    pub fn eval_flags<AB: AirBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &Ripemd160VmCols<AB::Var>,
+        next: &Ripemd160VmCols<AB::Var>,
+    ) {
+        let flags = &local.flags;
+        for &flag in flags.step_flags.iter().chain(once(&flags.is_digest_row)) {
+            builder.assert_bool(flag);
+        }
+        let is_enabled: AB::Expr = flags.is_enabled();
+        builder.assert_bool(is_enabled.clone());
+        let is_step: AB::Expr = flags.is_step();
+
+        let mut transition_builder = builder.when_transition();
+        for j in 0..RIPEMD160_STEPS {
+            let next_flag = if j + 1 < RIPEMD160_STEPS {
+                next.flags.step_flags[j + 1]
+            } else {
+                next.flags.is_digest_row
+            };
+            transition_builder
+                .when(flags.step_flags[j])
+                .assert_one(next_flag);
+        }
+        transition_builder
+            .when(not::<AB::Expr>(is_step.clone()))
+            .assert_eq(
+                next.flags.is_enabled::<AB::Expr>(),
+                next.flags.step_flags[0],
+            );
+
+        builder
+            .when_first_row()
+            .assert_eq(is_enabled, flags.step_flags[0]);
+        // Every block is complete
+        builder.when_last_row().assert_zero(is_step);
+    }
+
+    /// Constrain one step of `line`, and the variables of the next row to be its output.
+    ///
+    /// The boolean function is computed on the bits of the variables, and the additions are
+    /// done on `u16` limbs with carries. The carries are range checked to 8 bits, which is
+    /// enough since at most 4 limbs are added. The rotation amount, message word and constant
+    /// of the step are selected by the step flags.
+    #[inline]
+    pub fn eval_step<AB: InteractionBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &Ripemd160VmCols<AB::Var>,
+        line: Ripemd160Line,
+        cols: &Ripemd160LineCols<AB::Var>,
+        next: &Ripemd160LineCols<AB::Var>,
+    ) {
+        let flags = &local.flags;
+        let is_step: AB::Expr = flags.is_step();
+
+        for &bit in cols.vars.iter().flatten().chain(&cols.sum) {
+            builder.assert_bool(bit);
+        }
+        let [a, b, c, d, e] = &cols.vars;
+        // The boolean functions have degree 2 in `b, c, d` and `b & c`, so selecting one by the
+        // group flags has degree 3
+        for i in 0..RIPEMD160_WORD_BITS {
+            builder.assert_eq(cols.bc[i], b[i] * c[i]);
+            let f = (0..RIPEMD160_GROUPS).fold(AB::Expr::ZERO, |acc, g| {
+                acc + flags.is_group::<AB::Expr>(g)
+                    * boolean_function::<AB::Expr>(line.function(g), b[i], c[i], d[i], cols.bc[i])
+            });
+            builder.assert_eq(cols.f[i], f);
+        }
+
+        // `rol(sum, s)`, with `s` selected by the step flags. Degree 2.
+        let rotated: [AB::Expr; RIPEMD160_WORD_BITS] = from_fn(|i| {
+            flags
+                .step_flags
+                .iter()
+                .enumerate()
+                .fold(AB::Expr::ZERO, |acc, (j, &flag)| {
+                    let s = line.rotation(j) as usize;
+                    acc + cols.sum[(i + RIPEMD160_WORD_BITS - s) % RIPEMD160_WORD_BITS] * flag
+                })
+        });
+
+        let mut step_builder = builder.when(is_step.clone());
+        let two_16 = AB::Expr::from_canonical_u32(1 << 16);
+        for k in 0..RIPEMD160_WORD_U16S {
+            // The message word `X[r]` selected by the step flags. Degree 2.
+            let message_limb =
+                flags
+                    .step_flags
+                    .iter()
+                    .enumerate()
+                    .fold(AB::Expr::ZERO, |acc, (j, &flag)| {
+                        let bytes = &local.instruction.message[line.message_index(j)];
+                        acc + (bytes[2 * k] + bytes[2 * k + 1] * AB::F::from_canonical_u32(1 << 8))
+                            * flag
+                    });
+            let constant_limb = (0..RIPEMD160_GROUPS).fold(AB::Expr::ZERO, |acc, g| {
+                acc + flags.is_group::<AB::Expr>(g)
+                    * AB::F::from_canonical_u32((line.constant(g) >> (16 * k)) & 0xffff)
+            });
+            let carry_in = |carries: &[AB::Var; RIPEMD160_WORD_U16S]| -> AB::Expr {
+                if k == 0 {
+                    AB::Expr::ZERO
+                } else {
+                    carries[k - 1].into()
+                }
+            };
+
+            step_builder.assert_eq(
+                compose_limb::<AB::Expr>(a, k)
+                    + compose_limb::<AB::Expr>(&cols.f, k)
+                    + message_limb
+                    + constant_limb
+                    + carry_in(&cols.carry_sum),
+                compose_limb::<AB::Expr>(&cols.sum, k) + cols.carry_sum[k] * two_16.clone(),
+            );
+            step_builder.assert_eq(
+                compose_limb::<AB::Expr>(&rotated, k)
+                    + compose_limb::<AB::Expr>(e, k)
+                    + carry_in(&cols.carry_b),
+                compose_limb::<AB::Expr>(&next.vars[1], k) + cols.carry_b[k] * two_16.clone(),
+            );
+        }
+
+        // `[a, b, c, d, e] <- [e, new_b, b, rol(c, 10), d]`
+        for i in 0..RIPEMD160_WORD_BITS {
+            step_builder.assert_eq(next.vars[0][i], e[i]);
+            step_builder.assert_eq(next.vars[2][i], b[i]);
+            step_builder.assert_eq(
+                next.vars[3][i],
+                c[(i + RIPEMD160_WORD_BITS - 10) % RIPEMD160_WORD_BITS],
+            );
+            step_builder.assert_eq(next.vars[4][i], d[i]);
+        }
+
+        for pair in [cols.carry_sum, cols.carry_b].concat().chunks_exact(2) {
+            self.bitwise_lookup_bus
+                .send_range(pair[0], pair[1])
+                .eval(builder, is_step.clone());
+        }
+
+        // The variables of the first step are the previous state
+        let mut first_step_builder = builder.when(flags.step_flags[0]);
+        for (var, prev_word) in cols.vars.iter().zip(local.instruction.prev_state) {
+            for (k, prev_limb) in prev_word.into_iter().enumerate() {
+                first_step_builder.assert_eq(compose_limb::<AB::Expr>(var, k), prev_limb);
+            }
+        }
+    }
+
+    /// Constrain word `i` of the new state to be `h[i + 1] + left[i + 2] + right[i + 3]`,
+    /// with indices modulo 5, and range check its bytes.
+    #[inline]
+    pub fn eval_digest<AB: InteractionBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &Ripemd160VmCols<AB::Var>,
+    ) {
+        let digest = &local.digest;
+        let is_digest_row = local.flags.is_digest_row;
+
+        let mut digest_builder = builder.when(is_digest_row);
+        for i in 0..RIPEMD160_STATE_WORDS {
+            for k in 0..RIPEMD160_WORD_U16S {
+                let carry_in = if k == 0 {
+                    AB::Expr::ZERO
+                } else {
+                    digest.carry[i][k - 1].into()
+                };
+                digest_builder.assert_eq(
+                    local.instruction.prev_state[(i + 1) % RIPEMD160_STATE_WORDS][k]
+                        + compose_limb::<AB::Expr>(
+                            &local.left.vars[(i + 2) % RIPEMD160_STATE_WORDS],
+                            k,
+                        )
+                        + compose_limb::<AB::Expr>(
+                            &local.right.vars[(i + 3) % RIPEMD160_STATE_WORDS],
+                            k,
+                        )
+                        + carry_in,
+                    digest.state_bytes[i][2 * k]
+                        + digest.state_bytes[i][2 * k + 1] * AB::F::from_canonical_u32(1 << 8)
+                        + digest.carry[i][k] * AB::F::from_canonical_u32(1 << 16),
+                );
+            }
+        }
+
+        for pair in digest
+            .state_bytes
+            .iter()
+            .flatten()
+            .chain(digest.carry.iter().flatten())
+            .collect::<Vec<_>>()
+            .chunks_exact(2)
+        {
+            self.bitwise_lookup_bus
+                .send_range(*pair[0], *pair[1])
+                .eval(builder, is_digest_row);
+        }
+    }
+
+    /// Receive the instruction and read the pointers, the previous state and the block on the
+    /// first step, and write the new state on the digest row.
+    pub fn eval_instruction<AB: InteractionBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &Ripemd160VmCols<AB::Var>,
+    ) {
+        let instruction = local.instruction;
+        let is_first_step = local.flags.step_flags[0];
+        let is_digest_row = local.flags.is_digest_row;
+
+        self.execution_bridge
+            .execute_and_increment_pc(
+                AB::Expr::from_canonical_usize(
+                    Rv32Ripemd160Opcode::RIPEMD160_COMPRESS as usize + self.offset,
+                ),
+                [
+                    instruction.rd_ptr.into(),
+                    instruction.rs1_ptr.into(),
+                    AB::Expr::ZERO,
+                    AB::Expr::from_canonical_u32(RV32_REGISTER_AS),
+                    AB::Expr::from_canonical_u32(RV32_MEMORY_AS),
+                ],
+                ExecutionState::new(instruction.pc, instruction.start_timestamp),
+                AB::Expr::from_canonical_usize(RIPEMD160_TIMESTAMP_CHANGE),
+            )
+            .eval(builder, is_first_step);
+
+        for (i, (reg_ptr, value)) in [
+            (instruction.rd_ptr, instruction.state_ptr),
+            (instruction.rs1_ptr, instruction.block_ptr),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            self.memory_bridge
+                .read(
+                    MemoryAddress::new(AB::F::from_canonical_u32(RV32_REGISTER_AS), reg_ptr),
+                    value,
+                    instruction.start_timestamp + AB::F::from_canonical_usize(i),
+                    &local.mem_oc.register_aux[i],
+                )
+                .eval(builder, is_first_step);
+        }
+        // Range check that the pointers are less than 2^ptr_max_bits
+        let limb_shift = AB::F::from_canonical_usize(
+            1 << (RV32_CELL_BITS * RV32_REGISTER_NUM_LIMBS - self.ptr_max_bits),
+        );
+        self.bitwise_lookup_bus
+            .send_range(
+                instruction.state_ptr[RV32_REGISTER_NUM_LIMBS - 1] * limb_shift,
+                instruction.block_ptr[RV32_REGISTER_NUM_LIMBS - 1] * limb_shift,
+            )
+            .eval(builder, is_first_step);
+        for pair in instruction
+            .message
+            .iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .chunks_exact(2)
+        {
+            self.bitwise_lookup_bus
+                .send_range(*pair[0], *pair[1])
+                .eval(builder, is_first_step);
+        }
+
+        let state_ptr = abstract_compose::<AB::Expr, _>(instruction.state_ptr);
+        let block_ptr = abstract_compose::<AB::Expr, _>(instruction.block_ptr);
+        let state_read_timestamp =
+            instruction.start_timestamp + AB::F::from_canonical_usize(RIPEMD160_REGISTER_READS);
+        let block_read_timestamp =
+            state_read_timestamp.clone() + AB::F::from_canonical_usize(RIPEMD160_STATE_WORDS);
+        let state_write_timestamp =
+            block_read_timestamp.clone() + AB::F::from_canonical_usize(RIPEMD160_BLOCK_WORDS);
+
+        // The variables of the left line on the first step are the previous state
+        for (i, (var, new_word)) in local
+            .left
+            .vars
+            .iter()
+            .zip(local.digest.state_bytes)
+            .enumerate()
+        {
+            let address = MemoryAddress::new(
+                AB::F::from_canonical_u32(RV32_MEMORY_AS),
+                state_ptr.clone() + AB::F::from_canonical_usize(i * RIPEMD160_WORD_SIZE),
+            );
+            let offset = AB::F::from_canonical_usize(i);
+            self.memory_bridge
+                .read(
+                    address.clone(),
+                    from_fn::<_, RIPEMD160_WORD_BYTES, _>(|m| compose_byte::<AB::Expr>(var, m)),
+                    state_read_timestamp.clone() + offset,
+                    &local.mem_oc.state_reads[i],
+                )
+                .eval(builder, is_first_step);
+            self.memory_bridge
+                .write(
+                    address,
+                    new_word,
+                    state_write_timestamp.clone() + offset,
+                    &local.mem_oc.state_writes[i],
+                )
+                .eval(builder, is_digest_row);
+        }
+
+        for (i, word) in instruction.message.into_iter().enumerate() {
+            self.memory_bridge
+                .read(
+                    MemoryAddress::new(
+                        AB::F::from_canonical_u32(RV32_MEMORY_AS),
+                        block_ptr.clone() + AB::F::from_canonical_usize(i * RIPEMD160_WORD_SIZE),
+                    ),
+                    word,
+                    block_read_timestamp.clone() + AB::F::from_canonical_usize(i),
+                    &local.mem_oc.block_reads[i],
+                )
+                .eval(builder, is_first_step);
+        }
+    }
+}
+
+/// The boolean function with index `function` on the bits `x, y, z`, given `xy = x * y`. The
+/// functions are, in order, `x ^ y ^ z`, `(x & y) | (!x & z)`, `(x | !y) ^ z`,
+/// `(x & z) | (y & !z)` and `x ^ (y | !z)`. Degree 2.
+fn boolean_function<E: AbstractField>(
+    function: usize,
+    x: impl Into<E>,
+    y: impl Into<E>,
+    z: impl Into<E>,
+    xy: impl Into<E>,
+) -> E {
+    let (x, y, z, xy) = (x.into(), y.into(), z.into(), xy.into());
+    let xz = x.clone() * z.clone();
+    let yz = y.clone() * z.clone();
+    let xyz = xy.clone() * z.clone();
+    match function {
+        0 => x + y + z - (xy + xz + yz).double() + xyz * E::from_canonical_u32(4),
+        1 => xy + z - xz,
+        2 => E::ONE - y + xy - z + (yz - xyz).double(),
+        3 => xz + y - yz,
+        _ => E::ONE - x - z + yz + (xz - xyz).double(),
+    }
+}
+
+/// The `k`-th `u16` limb of a word given as bits, least significant bit first.
+fn compose_limb<E: AbstractField>(bits: &[impl Into<E> + Clone], k: usize) -> E {
+    compose_bits(&bits[16 * k..16 * (k + 1)])
+}
+
+/// The `m`-th byte of a word given as bits, least significant bit first.
+fn compose_byte<E: AbstractField>(bits: &[impl Into<E> + Clone], m: usize) -> E {
+    compose_bits(&bits[8 * m..8 * (m + 1)])
+}
+
+fn compose_bits<E: AbstractField>(bits: &[impl Into<E> + Clone]) -> E {
+    bits.iter()
+        .rev()
+        .fold(E::ZERO, |acc, bit| acc.double() + bit.clone().into())
+}
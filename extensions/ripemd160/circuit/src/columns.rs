@@ -0,0 +1,151 @@
+use core::mem::size_of;
+
+use openvm_circuit::system::memory::offline_checker::{MemoryReadAuxCols, MemoryWriteAuxCols};
+use openvm_circuit_primitives::utils::assert_array_eq;
+use openvm_circuit_primitives_derive::AlignedBorrow;
+use openvm_instructions::riscv::RV32_REGISTER_NUM_LIMBS;
+use openvm_stark_backend::{p3_air::AirBuilder, p3_field::AbstractField};
+
+use super::{
+    RIPEMD160_BLOCK_WORDS, RIPEMD160_REGISTER_READS, RIPEMD160_STATE_WORDS, RIPEMD160_STEPS,
+    RIPEMD160_WORD_BITS, RIPEMD160_WORD_BYTES, RIPEMD160_WORD_SIZE, RIPEMD160_WORD_U16S,
+};
+
+/// Number of steps using the same boolean function and constant.
+pub const RIPEMD160_GROUP_STEPS: usize = 16;
+
+/// Each block takes [RIPEMD160_ROWS_PER_BLOCK](super::RIPEMD160_ROWS_PER_BLOCK) rows: one row
+/// per step of both lines, followed by a digest row which combines the final variables of the
+/// two lines with the previous state.
+#[repr(C)]
+#[derive(Debug, AlignedBorrow)]
+pub struct Ripemd160VmCols<T> {
+    pub flags: Ripemd160FlagCols<T>,
+    /// Columns for instruction interface and register access
+    pub instruction: Ripemd160InstructionCols<T>,
+    pub left: Ripemd160LineCols<T>,
+    pub right: Ripemd160LineCols<T>,
+    pub digest: Ripemd160DigestCols<T>,
+    /// Auxiliary columns for offline memory checking
+    pub mem_oc: Ripemd160MemoryCols<T>,
+}
+
+/// At most one flag is set on each row. No flag is set on dummy rows.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, AlignedBorrow)]
+pub struct Ripemd160FlagCols<T> {
+    /// `step_flags[j]` is set on the row of step `j`
+    pub step_flags: [T; RIPEMD160_STEPS],
+    pub is_digest_row: T,
+}
+
+/// Columns for RIPEMD160_COMPRESS instruction parsing. They are the same on all rows of a block.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, AlignedBorrow)]
+pub struct Ripemd160InstructionCols<T> {
+    /// Program counter
+    pub pc: T,
+    /// The timestamp of the first register read
+    pub start_timestamp: T,
+    /// Pointer to address space 1 `rd` register
+    pub rd_ptr: T,
+    /// Pointer to address space 1 `rs1` register
+    pub rs1_ptr: T,
+    /// `state_ptr <- [rd_ptr:4]_1`
+    pub state_ptr: [T; RV32_REGISTER_NUM_LIMBS],
+    /// `block_ptr <- [rs1_ptr:4]_1`
+    pub block_ptr: [T; RV32_REGISTER_NUM_LIMBS],
+    /// The state before the compression, as `u16` limbs of each word
+    pub prev_state: [[T; RIPEMD160_WORD_U16S]; RIPEMD160_STATE_WORDS],
+    /// Little-endian bytes of the message words. Range checked to 8 bits.
+    pub message: [[T; RIPEMD160_WORD_BYTES]; RIPEMD160_BLOCK_WORDS],
+}
+
+/// One step of a line:
+/// ```text
+/// sum = a + f(b, c, d) + X[r] + K
+/// [a, b, c, d, e] <- [e, rol(sum, s) + e, b, rol(c, 10), d]
+/// ```
+/// Bits are little-endian, i.e., least significant bit first.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, AlignedBorrow)]
+pub struct Ripemd160LineCols<T> {
+    /// Bits of the variables `a, b, c, d, e` before this step
+    pub vars: [[T; RIPEMD160_WORD_BITS]; RIPEMD160_STATE_WORDS],
+    /// Bits of `b & c`, to keep the degree of the boolean functions low
+    pub bc: [T; RIPEMD160_WORD_BITS],
+    /// Bits of the boolean function of this step on `b, c, d`
+    pub f: [T; RIPEMD160_WORD_BITS],
+    /// Bits of `sum` before the rotation
+    pub sum: [T; RIPEMD160_WORD_BITS],
+    /// Carries of the `u16` limb additions computing `sum` and the new `b`. Range checked to
+    /// 8 bits.
+    pub carry_sum: [T; RIPEMD160_WORD_U16S],
+    pub carry_b: [T; RIPEMD160_WORD_U16S],
+}
+
+/// Only used on the digest row.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, AlignedBorrow)]
+pub struct Ripemd160DigestCols<T> {
+    /// The new state as little-endian bytes of each word. Range checked to 8 bits.
+    pub state_bytes: [[T; RIPEMD160_WORD_BYTES]; RIPEMD160_STATE_WORDS],
+    /// Carries of the `u16` limb additions of the previous state and the variables of the two
+    /// lines. Range checked to 8 bits.
+    pub carry: [[T; RIPEMD160_WORD_U16S]; RIPEMD160_STATE_WORDS],
+}
+
+#[repr(C)]
+#[derive(Clone, Debug, AlignedBorrow)]
+pub struct Ripemd160MemoryCols<T> {
+    /// The memory columns other than the writes are only used on the first step
+    pub register_aux: [MemoryReadAuxCols<T, RV32_REGISTER_NUM_LIMBS>; RIPEMD160_REGISTER_READS],
+    pub state_reads: [MemoryReadAuxCols<T, RIPEMD160_WORD_SIZE>; RIPEMD160_STATE_WORDS],
+    pub block_reads: [MemoryReadAuxCols<T, RIPEMD160_WORD_SIZE>; RIPEMD160_BLOCK_WORDS],
+    /// Only used on the digest row
+    pub state_writes: [MemoryWriteAuxCols<T, RIPEMD160_WORD_SIZE>; RIPEMD160_STATE_WORDS],
+}
+
+impl<T: Copy> Ripemd160InstructionCols<T> {
+    pub fn assert_eq<AB: AirBuilder>(&self, builder: &mut AB, other: Self)
+    where
+        T: Into<AB::Expr>,
+    {
+        builder.assert_eq(self.pc, other.pc);
+        builder.assert_eq(self.start_timestamp, other.start_timestamp);
+        builder.assert_eq(self.rd_ptr, other.rd_ptr);
+        builder.assert_eq(self.rs1_ptr, other.rs1_ptr);
+        assert_array_eq(builder, self.state_ptr, other.state_ptr);
+        assert_array_eq(builder, self.block_ptr, other.block_ptr);
+        for (word, other_word) in self.prev_state.into_iter().zip(other.prev_state) {
+            assert_array_eq(builder, word, other_word);
+        }
+        for (word, other_word) in self.message.into_iter().zip(other.message) {
+            assert_array_eq(builder, word, other_word);
+        }
+    }
+}
+
+impl<T: Copy> Ripemd160FlagCols<T> {
+    /// Whether this row is a step row. Degree 1.
+    pub fn is_step<E: AbstractField + From<T>>(&self) -> E {
+        self.step_flags
+            .iter()
+            .fold(E::ZERO, |acc, &flag| acc + flag.into())
+    }
+
+    /// Whether this row belongs to a block. Degree 1.
+    pub fn is_enabled<E: AbstractField + From<T>>(&self) -> E {
+        self.is_step::<E>() + self.is_digest_row.into()
+    }
+
+    /// Whether this row is a step of group `g`, i.e., of steps `16 * g..16 * (g + 1)`.
+    /// Degree 1.
+    pub fn is_group<E: AbstractField + From<T>>(&self, g: usize) -> E {
+        self.step_flags[RIPEMD160_GROUP_STEPS * g..RIPEMD160_GROUP_STEPS * (g + 1)]
+            .iter()
+            .fold(E::ZERO, |acc, &flag| acc + flag.into())
+    }
+}
+
+pub const NUM_RIPEMD160_VM_COLS: usize = size_of::<Ripemd160VmCols<u8>>();
@@ -0,0 +1,198 @@
+//! RIPEMD-160 compression of one 64-byte block into a state in VM memory. The guest pads the
+//! message and chains the compressions itself.
+use std::{array::from_fn, sync::Arc};
+
+use openvm_circuit::{
+    arch::{ExecutionBridge, ExecutionBus, ExecutionError, ExecutionState, InstructionExecutor},
+    system::{
+        memory::{MemoryControllerRef, MemoryReadRecord, MemoryWriteRecord},
+        program::ProgramBus,
+    },
+};
+use openvm_circuit_primitives::bitwise_op_lookup::BitwiseOperationLookupChip;
+use openvm_instructions::{
+    instruction::Instruction, program::DEFAULT_PC_STEP, riscv::RV32_REGISTER_NUM_LIMBS, UsizeOpcode,
+};
+use openvm_ripemd160_guest::{ripemd160_compress, RIPEMD160_BLOCK_BYTES};
+use openvm_ripemd160_transpiler::Rv32Ripemd160Opcode;
+use openvm_rv32im_circuit::adapters::read_rv32_register;
+use openvm_stark_backend::p3_field::PrimeField32;
+
+pub mod air;
+pub mod columns;
+pub mod trace;
+pub mod utils;
+
+mod extension;
+pub use extension::*;
+
+#[cfg(test)]
+mod tests;
+
+pub use air::Ripemd160VmAir;
+
+// ==== Constants for register/memory adapter ====
+/// Register reads to get the state pointer and the block pointer
+const RIPEMD160_REGISTER_READS: usize = 2;
+/// Number of cells to read/write in a single memory access
+const RIPEMD160_WORD_SIZE: usize = 4;
+/// Amount to advance the timestamp by after execution of one RIPEMD160_COMPRESS instruction
+const RIPEMD160_TIMESTAMP_CHANGE: usize =
+    RIPEMD160_REGISTER_READS + 2 * RIPEMD160_STATE_WORDS + RIPEMD160_BLOCK_WORDS;
+
+// ==== Do not change these constants! ====
+/// Number of bits in a word.
+pub const RIPEMD160_WORD_BITS: usize = 32;
+/// Number of bytes in a word, which is also the number of cells of a memory access.
+pub const RIPEMD160_WORD_BYTES: usize = RIPEMD160_WORD_BITS / 8;
+/// Number of 16-bit limbs in a word.
+pub const RIPEMD160_WORD_U16S: usize = RIPEMD160_WORD_BITS / 16;
+/// Number of words in the state and in the variables of each line.
+pub const RIPEMD160_STATE_WORDS: usize = 5;
+/// Number of bytes in the state.
+pub const RIPEMD160_STATE_BYTES: usize = RIPEMD160_STATE_WORDS * RIPEMD160_WORD_BYTES;
+/// Number of words in a block.
+pub const RIPEMD160_BLOCK_WORDS: usize = RIPEMD160_BLOCK_BYTES / RIPEMD160_WORD_BYTES;
+/// Number of steps of each line of the compression function.
+pub const RIPEMD160_STEPS: usize = 80;
+/// Number of trace rows per block: one per step and a digest row.
+pub const RIPEMD160_ROWS_PER_BLOCK: usize = RIPEMD160_STEPS + 1;
+
+#[derive(Debug)]
+pub struct Ripemd160VmChip<F: PrimeField32> {
+    pub air: Ripemd160VmAir,
+    /// IO and memory data necessary for each opcode call
+    pub records: Vec<Ripemd160Record<F>>,
+    pub memory_controller: MemoryControllerRef<F>,
+    pub bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<8>>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Ripemd160Record<F> {
+    pub from_state: ExecutionState<u32>,
+    pub state_ptr_read: MemoryReadRecord<F, RV32_REGISTER_NUM_LIMBS>,
+    pub block_ptr_read: MemoryReadRecord<F, RV32_REGISTER_NUM_LIMBS>,
+    pub state_reads: [MemoryReadRecord<F, RIPEMD160_WORD_SIZE>; RIPEMD160_STATE_WORDS],
+    pub block_reads: [MemoryReadRecord<F, RIPEMD160_WORD_SIZE>; RIPEMD160_BLOCK_WORDS],
+    pub state_writes: [MemoryWriteRecord<F, RIPEMD160_WORD_SIZE>; RIPEMD160_STATE_WORDS],
+}
+
+impl<F: PrimeField32> Ripemd160VmChip<F> {
+    pub fn new(
+        execution_bus: ExecutionBus,
+        program_bus: ProgramBus,
+        memory_controller: MemoryControllerRef<F>,
+        bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<8>>,
+        offset: usize,
+    ) -> Self {
+        let ptr_max_bits = memory_controller.borrow().mem_config().pointer_max_bits;
+        let memory_bridge = memory_controller.borrow().memory_bridge();
+        Self {
+            air: Ripemd160VmAir::new(
+                ExecutionBridge::new(execution_bus, program_bus),
+                memory_bridge,
+                bitwise_lookup_chip.bus(),
+                ptr_max_bits,
+                offset,
+            ),
+            records: Vec::new(),
+            memory_controller,
+            bitwise_lookup_chip,
+        }
+    }
+}
+
+impl<F: PrimeField32> InstructionExecutor<F> for Ripemd160VmChip<F> {
+    fn execute(
+        &mut self,
+        instruction: Instruction<F>,
+        from_state: ExecutionState<u32>,
+    ) -> Result<ExecutionState<u32>, ExecutionError> {
+        let Instruction {
+            opcode, a, b, d, e, ..
+        } = instruction;
+        let local_opcode =
+            Rv32Ripemd160Opcode::from_usize(opcode.local_opcode_idx(self.air.offset));
+        debug_assert_eq!(local_opcode, Rv32Ripemd160Opcode::RIPEMD160_COMPRESS);
+
+        let mut memory = self.memory_controller.borrow_mut();
+        debug_assert_eq!(from_state.timestamp, memory.timestamp());
+
+        let (state_ptr_read, state_ptr) = read_rv32_register(&mut memory, d, a);
+        let (block_ptr_read, block_ptr) = read_rv32_register(&mut memory, d, b);
+        let (state_ptr, block_ptr) = (state_ptr as usize, block_ptr as usize);
+        assert!(state_ptr + RIPEMD160_STATE_BYTES <= (1 << self.air.ptr_max_bits));
+        assert!(block_ptr + RIPEMD160_BLOCK_BYTES <= (1 << self.air.ptr_max_bits));
+
+        let state_reads: [_; RIPEMD160_STATE_WORDS] = from_fn(|i| {
+            memory.read::<RIPEMD160_WORD_SIZE>(
+                e,
+                F::from_canonical_usize(state_ptr + i * RIPEMD160_WORD_SIZE),
+            )
+        });
+        let block_reads: [_; RIPEMD160_BLOCK_WORDS] = from_fn(|i| {
+            memory.read::<RIPEMD160_WORD_SIZE>(
+                e,
+                F::from_canonical_usize(block_ptr + i * RIPEMD160_WORD_SIZE),
+            )
+        });
+
+        let mut state = state_reads.map(|read| u32::from_le_bytes(read.data.map(to_byte)));
+        let mut block = [0u8; RIPEMD160_BLOCK_BYTES];
+        for (i, byte) in block_reads.iter().flat_map(|read| read.data).enumerate() {
+            block[i] = to_byte(byte);
+        }
+        ripemd160_compress(&mut state, &block);
+        tracing::trace!("[runtime] ripemd160 compress output: {:x?}", state);
+
+        let state_writes: [_; RIPEMD160_STATE_WORDS] = from_fn(|i| {
+            memory.write::<RIPEMD160_WORD_SIZE>(
+                e,
+                F::from_canonical_usize(state_ptr + i * RIPEMD160_WORD_SIZE),
+                state[i].to_le_bytes().map(F::from_canonical_u8),
+            )
+        });
+
+        self.records.push(Ripemd160Record {
+            from_state,
+            state_ptr_read,
+            block_ptr_read,
+            state_reads,
+            block_reads,
+            state_writes,
+        });
+
+        // NOTE: Check this is consistent with the timestamp change in Ripemd160VmAir
+        let to_timestamp = from_state.timestamp + RIPEMD160_TIMESTAMP_CHANGE as u32;
+        debug_assert_eq!(to_timestamp, memory.timestamp());
+
+        Ok(ExecutionState {
+            pc: from_state.pc + DEFAULT_PC_STEP,
+            timestamp: to_timestamp,
+        })
+    }
+
+    fn get_opcode_name(&self, _: usize) -> String {
+        "RIPEMD160_COMPRESS".to_string()
+    }
+}
+
+fn to_byte<F: PrimeField32>(cell: F) -> u8 {
+    cell.as_canonical_u32()
+        .try_into()
+        .expect("Memory cell not a byte")
+}
+
+impl<F: PrimeField32> Ripemd160Record<F> {
+    /// The state before the compression.
+    pub fn prev_state(&self) -> [u32; RIPEMD160_STATE_WORDS] {
+        self.state_reads
+            .map(|read| u32::from_le_bytes(read.data.map(to_byte)))
+    }
+
+    /// The message words of the block, which is little-endian.
+    pub fn message(&self) -> [u32; RIPEMD160_BLOCK_WORDS] {
+        self.block_reads
+            .map(|read| u32::from_le_bytes(read.data.map(to_byte)))
+    }
+}
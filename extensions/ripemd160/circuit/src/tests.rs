@@ -0,0 +1,136 @@
+use std::{borrow::BorrowMut, sync::Arc};
+
+use openvm_circuit::arch::{
+    testing::{VmChipTestBuilder, VmChipTester},
+    BITWISE_OP_LOOKUP_BUS,
+};
+use openvm_circuit_primitives::bitwise_op_lookup::{
+    BitwiseOperationLookupBus, BitwiseOperationLookupChip,
+};
+use openvm_instructions::{instruction::Instruction, UsizeOpcode, VmOpcode};
+use openvm_ripemd160_guest::{ripemd160_compress, RIPEMD160_BLOCK_BYTES, RIPEMD160_IV};
+use openvm_ripemd160_transpiler::Rv32Ripemd160Opcode;
+use openvm_stark_backend::{
+    p3_field::AbstractField, utils::disable_debug_builder, verifier::VerificationError,
+};
+use openvm_stark_sdk::{
+    config::baby_bear_blake3::BabyBearBlake3Config, p3_baby_bear::BabyBear,
+    utils::create_seeded_rng,
+};
+use rand::Rng;
+
+use super::{columns::Ripemd160VmCols, Ripemd160VmChip, RIPEMD160_STATE_BYTES, RIPEMD160_STEPS};
+
+type F = BabyBear;
+
+/// Compresses each block into its state in place in memory and checks the written state.
+fn build_ripemd160_test(
+    inputs: Vec<([u32; 5], [u8; RIPEMD160_BLOCK_BYTES])>,
+) -> VmChipTester<BabyBearBlake3Config> {
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = Arc::new(BitwiseOperationLookupChip::<8>::new(bitwise_bus));
+
+    let mut tester = VmChipTestBuilder::default();
+    let mut chip = Ripemd160VmChip::new(
+        tester.execution_bus(),
+        tester.program_bus(),
+        tester.memory_controller(),
+        bitwise_chip.clone(),
+        Rv32Ripemd160Opcode::default_offset(),
+    );
+
+    let [a, b, d, e] = [4, 8, 1, 2];
+    for (k, (mut state, block)) in inputs.into_iter().enumerate() {
+        let state_ptr = k * (RIPEMD160_STATE_BYTES + RIPEMD160_BLOCK_BYTES);
+        let block_ptr = state_ptr + RIPEMD160_STATE_BYTES;
+        tester.write(
+            d,
+            a,
+            (state_ptr as u32).to_le_bytes().map(F::from_canonical_u8),
+        );
+        tester.write(
+            d,
+            b,
+            (block_ptr as u32).to_le_bytes().map(F::from_canonical_u8),
+        );
+        let state_bytes: Vec<u8> = state.iter().flat_map(|word| word.to_le_bytes()).collect();
+        for (i, &byte) in state_bytes.iter().enumerate() {
+            tester.write_cell(e, state_ptr + i, F::from_canonical_u8(byte));
+        }
+        for (i, &byte) in block.iter().enumerate() {
+            tester.write_cell(e, block_ptr + i, F::from_canonical_u8(byte));
+        }
+
+        tester.execute(
+            &mut chip,
+            Instruction::from_isize(
+                VmOpcode::with_default_offset(Rv32Ripemd160Opcode::RIPEMD160_COMPRESS),
+                a as isize,
+                b as isize,
+                0,
+                d as isize,
+                e as isize,
+            ),
+        );
+
+        ripemd160_compress(&mut state, &block);
+        let expected: Vec<u8> = state.iter().flat_map(|word| word.to_le_bytes()).collect();
+        for (i, &byte) in expected.iter().enumerate() {
+            assert_eq!(
+                tester.read_cell(e, state_ptr + i),
+                F::from_canonical_u8(byte)
+            );
+        }
+    }
+    tester.build().load(chip).load(bitwise_chip).finalize()
+}
+
+#[test]
+fn rand_ripemd160_test() {
+    let mut rng = create_seeded_rng();
+    let mut inputs: Vec<_> = (0..3)
+        .map(|_| {
+            let mut block = [0u8; RIPEMD160_BLOCK_BYTES];
+            rng.fill(&mut block[..]);
+            (rng.gen(), block)
+        })
+        .collect();
+    // The padded empty message
+    let mut block = [0u8; RIPEMD160_BLOCK_BYTES];
+    block[0] = 0x80;
+    inputs.push((RIPEMD160_IV, block));
+    let tester = build_ripemd160_test(inputs);
+    tester.simple_test().expect("Verification failed");
+}
+
+#[test]
+fn ripemd160_empty_message_test() {
+    let mut state = RIPEMD160_IV;
+    let mut block = [0u8; RIPEMD160_BLOCK_BYTES];
+    block[0] = 0x80;
+    ripemd160_compress(&mut state, &block);
+    let digest: Vec<u8> = state.iter().flat_map(|word| word.to_le_bytes()).collect();
+    assert_eq!(
+        hex::encode(digest),
+        "9c1185a5c5e9fc54612808977ee8f548b2258d31"
+    );
+}
+
+#[test]
+fn negative_ripemd160_test() {
+    let mut rng = create_seeded_rng();
+    let mut block = [0u8; RIPEMD160_BLOCK_BYTES];
+    rng.fill(&mut block[..]);
+    let mut tester = build_ripemd160_test(vec![(rng.gen(), block)]);
+
+    // Change a byte of the new state, which is written to memory
+    let ripemd160_trace = tester.air_proof_inputs[2].raw.common_main.as_mut().unwrap();
+    let digest_row: &mut Ripemd160VmCols<F> = ripemd160_trace.row_mut(RIPEMD160_STEPS).borrow_mut();
+    digest_row.digest.state_bytes[0][0] += F::ONE;
+
+    disable_debug_builder();
+    assert_eq!(
+        tester.simple_test().err(),
+        Some(VerificationError::OodEvaluationMismatch)
+    );
+}
@@ -0,0 +1,181 @@
+use std::{borrow::BorrowMut, sync::Arc};
+
+use openvm_circuit_primitives::utils::next_power_of_two_or_zero;
+use openvm_instructions::riscv::{RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS};
+use openvm_stark_backend::{
+    config::{StarkGenericConfig, Val},
+    p3_air::BaseAir,
+    p3_field::{AbstractField, PrimeField32},
+    p3_matrix::dense::RowMajorMatrix,
+    p3_maybe_rayon::prelude::*,
+    prover::types::AirProofInput,
+    rap::{get_air_name, AnyRap},
+    Chip, ChipUsageGetter,
+};
+
+use super::{
+    columns::{
+        Ripemd160InstructionCols, Ripemd160LineCols, Ripemd160VmCols, RIPEMD160_GROUP_STEPS,
+    },
+    utils::*,
+    Ripemd160VmChip, RIPEMD160_ROWS_PER_BLOCK, RIPEMD160_STATE_WORDS, RIPEMD160_STEPS,
+};
+
+impl<SC: StarkGenericConfig> Chip<SC> for Ripemd160VmChip<Val<SC>>
+where
+    Val<SC>: PrimeField32,
+{
+    fn air(&self) -> Arc<dyn AnyRap<SC>> {
+        Arc::new(self.air)
+    }
+
+    fn generate_air_proof_input(self) -> AirProofInput<SC> {
+        let air = self.air();
+        let trace_width = self.trace_width();
+        let num_rows = next_power_of_two_or_zero(self.current_trace_height());
+        // Rows after the last block are dummy rows with no flags set.
+        let mut trace =
+            RowMajorMatrix::new(Val::<SC>::zero_vec(num_rows * trace_width), trace_width);
+
+        let aux_cols_factory = self.memory_controller.borrow().aux_cols_factory();
+        let limb_shift_bits = RV32_CELL_BITS * RV32_REGISTER_NUM_LIMBS - self.air.ptr_max_bits;
+        let block_width = trace_width * RIPEMD160_ROWS_PER_BLOCK;
+
+        trace.values[..self.records.len() * block_width]
+            .par_chunks_mut(block_width)
+            .zip(self.records.par_iter())
+            .for_each(|(rows, record)| {
+                let prev_state = record.prev_state();
+                let message = record.message();
+                let instruction = Ripemd160InstructionCols {
+                    pc: Val::<SC>::from_canonical_u32(record.from_state.pc),
+                    start_timestamp: Val::<SC>::from_canonical_u32(record.from_state.timestamp),
+                    rd_ptr: record.state_ptr_read.pointer,
+                    rs1_ptr: record.block_ptr_read.pointer,
+                    state_ptr: record.state_ptr_read.data,
+                    block_ptr: record.block_ptr_read.data,
+                    prev_state: prev_state.map(u32_to_u16_limbs),
+                    message: record.block_reads.map(|read| read.data),
+                };
+
+                for (j, (row, vars)) in rows
+                    .chunks_exact_mut(trace_width)
+                    .zip(ripemd160_rows(prev_state, &message))
+                    .enumerate()
+                {
+                    let cols: &mut Ripemd160VmCols<Val<SC>> = row.borrow_mut();
+                    cols.instruction = instruction;
+
+                    if j < RIPEMD160_STEPS {
+                        cols.flags.step_flags[j] = Val::<SC>::ONE;
+                        for (line, line_cols, line_vars) in [
+                            (Ripemd160Line::Left, &mut cols.left, vars[0]),
+                            (Ripemd160Line::Right, &mut cols.right, vars[1]),
+                        ] {
+                            let step = ripemd160_step(line, j, line_vars, &message);
+                            generate_line_cols(line_cols, line_vars, step.f, step.sum);
+
+                            let carry_sum = u16_limb_carries(&[
+                                line_vars[0],
+                                step.f,
+                                message[line.message_index(j)],
+                                line.constant(j / RIPEMD160_GROUP_STEPS),
+                            ]);
+                            let carry_b = u16_limb_carries(&[
+                                step.sum.rotate_left(line.rotation(j)),
+                                line_vars[4],
+                            ]);
+                            line_cols.carry_sum = carry_sum.map(Val::<SC>::from_canonical_u32);
+                            line_cols.carry_b = carry_b.map(Val::<SC>::from_canonical_u32);
+                            for pair in [carry_sum, carry_b] {
+                                self.bitwise_lookup_chip.request_range(pair[0], pair[1]);
+                            }
+                        }
+                    } else {
+                        cols.flags.is_digest_row = Val::<SC>::ONE;
+                        // Only the variables are used on the digest row, but `b & c` is
+                        // constrained on every row.
+                        generate_line_cols(&mut cols.left, vars[0], 0, 0);
+                        generate_line_cols(&mut cols.right, vars[1], 0, 0);
+
+                        let (mut range_checked, mut carries) = (Vec::new(), Vec::new());
+                        for (i, summands) in
+                            digest_summands(prev_state, vars).into_iter().enumerate()
+                        {
+                            let new_word = summands
+                                .iter()
+                                .fold(0u32, |acc, &summand| acc.wrapping_add(summand));
+                            let new_bytes = new_word.to_le_bytes();
+                            cols.digest.state_bytes[i] =
+                                new_bytes.map(Val::<SC>::from_canonical_u8);
+                            range_checked.extend(new_bytes.map(u32::from));
+                            let carry = u16_limb_carries(&summands);
+                            cols.digest.carry[i] = carry.map(Val::<SC>::from_canonical_u32);
+                            carries.extend(carry);
+                        }
+                        // Same order as in the AIR: all the bytes, then all the carries
+                        range_checked.extend(carries);
+                        for pair in range_checked.chunks_exact(2) {
+                            self.bitwise_lookup_chip.request_range(pair[0], pair[1]);
+                        }
+                        for (i, write) in record.state_writes.iter().enumerate() {
+                            cols.mem_oc.state_writes[i] =
+                                aux_cols_factory.make_write_aux_cols(*write);
+                        }
+                    }
+                }
+
+                let first_row: &mut Ripemd160VmCols<Val<SC>> = rows[..trace_width].borrow_mut();
+                first_row.mem_oc.register_aux = [record.state_ptr_read, record.block_ptr_read]
+                    .map(|read| aux_cols_factory.make_read_aux_cols(read));
+                for (i, read) in record.state_reads.iter().enumerate() {
+                    first_row.mem_oc.state_reads[i] = aux_cols_factory.make_read_aux_cols(*read);
+                }
+                for (i, read) in record.block_reads.iter().enumerate() {
+                    first_row.mem_oc.block_reads[i] = aux_cols_factory.make_read_aux_cols(*read);
+                }
+                self.bitwise_lookup_chip.request_range(
+                    record.state_ptr_read.data[RV32_REGISTER_NUM_LIMBS - 1].as_canonical_u32()
+                        << limb_shift_bits,
+                    record.block_ptr_read.data[RV32_REGISTER_NUM_LIMBS - 1].as_canonical_u32()
+                        << limb_shift_bits,
+                );
+                let message_bytes: Vec<u8> =
+                    message.iter().flat_map(|word| word.to_le_bytes()).collect();
+                for pair in message_bytes.chunks_exact(2) {
+                    self.bitwise_lookup_chip
+                        .request_range(pair[0] as u32, pair[1] as u32);
+                }
+            });
+
+        AirProofInput::simple_no_pis(air, trace)
+    }
+}
+
+/// Fills the bits of the variables, of `b & c`, of the boolean function and of the sum. The
+/// carries are filled separately.
+fn generate_line_cols<F: PrimeField32>(
+    line_cols: &mut Ripemd160LineCols<F>,
+    vars: [u32; RIPEMD160_STATE_WORDS],
+    f: u32,
+    sum: u32,
+) {
+    line_cols.vars = vars.map(u32_to_bits);
+    line_cols.bc = u32_to_bits(vars[1] & vars[2]);
+    line_cols.f = u32_to_bits(f);
+    line_cols.sum = u32_to_bits(sum);
+}
+
+impl<F: PrimeField32> ChipUsageGetter for Ripemd160VmChip<F> {
+    fn air_name(&self) -> String {
+        get_air_name(&self.air)
+    }
+
+    fn current_trace_height(&self) -> usize {
+        self.records.len() * RIPEMD160_ROWS_PER_BLOCK
+    }
+
+    fn trace_width(&self) -> usize {
+        BaseAir::<F>::width(&self.air)
+    }
+}
@@ -0,0 +1,138 @@
+use std::array::from_fn;
+
+use openvm_ripemd160_guest::{
+    ripemd160_f, RIPEMD160_K_LEFT, RIPEMD160_K_RIGHT, RIPEMD160_R_LEFT, RIPEMD160_R_RIGHT,
+    RIPEMD160_S_LEFT, RIPEMD160_S_RIGHT,
+};
+use openvm_stark_backend::p3_field::AbstractField;
+
+use crate::{
+    columns::RIPEMD160_GROUP_STEPS, RIPEMD160_BLOCK_WORDS, RIPEMD160_STATE_WORDS, RIPEMD160_STEPS,
+    RIPEMD160_WORD_BITS, RIPEMD160_WORD_U16S,
+};
+
+/// One of the two parallel lines of the compression function.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Ripemd160Line {
+    Left,
+    Right,
+}
+
+impl Ripemd160Line {
+    /// The index of the boolean function of group `g`, as taken by [ripemd160_f].
+    pub fn function(&self, g: usize) -> usize {
+        match self {
+            Ripemd160Line::Left => g,
+            Ripemd160Line::Right => 4 - g,
+        }
+    }
+
+    /// The constant of group `g`.
+    pub fn constant(&self, g: usize) -> u32 {
+        match self {
+            Ripemd160Line::Left => RIPEMD160_K_LEFT[g],
+            Ripemd160Line::Right => RIPEMD160_K_RIGHT[g],
+        }
+    }
+
+    /// The message word index of step `j`.
+    pub fn message_index(&self, j: usize) -> usize {
+        match self {
+            Ripemd160Line::Left => RIPEMD160_R_LEFT[j],
+            Ripemd160Line::Right => RIPEMD160_R_RIGHT[j],
+        }
+    }
+
+    /// The left rotation of step `j`.
+    pub fn rotation(&self, j: usize) -> u32 {
+        match self {
+            Ripemd160Line::Left => RIPEMD160_S_LEFT[j],
+            Ripemd160Line::Right => RIPEMD160_S_RIGHT[j],
+        }
+    }
+}
+
+/// The values computed by one step of a line.
+#[derive(Clone, Copy, Debug)]
+pub struct Ripemd160StepValues {
+    /// The boolean function of the step on `b, c, d`
+    pub f: u32,
+    /// `a + f + X[r] + K`, before the rotation
+    pub sum: u32,
+    /// The variables after the step
+    pub next_vars: [u32; RIPEMD160_STATE_WORDS],
+}
+
+/// Step `j` of `line` on the variables `vars`.
+pub fn ripemd160_step(
+    line: Ripemd160Line,
+    j: usize,
+    vars: [u32; RIPEMD160_STATE_WORDS],
+    message: &[u32; RIPEMD160_BLOCK_WORDS],
+) -> Ripemd160StepValues {
+    let g = j / RIPEMD160_GROUP_STEPS;
+    let [a, b, c, d, e] = vars;
+    let f = ripemd160_f(line.function(g), b, c, d);
+    let sum = a
+        .wrapping_add(f)
+        .wrapping_add(message[line.message_index(j)])
+        .wrapping_add(line.constant(g));
+    let new_b = sum.rotate_left(line.rotation(j)).wrapping_add(e);
+    Ripemd160StepValues {
+        f,
+        sum,
+        next_vars: [e, new_b, b, c.rotate_left(10), d],
+    }
+}
+
+/// The variables of the left and right lines on each row of a block, i.e., before step `j` for
+/// `j` in `0..=RIPEMD160_STEPS`.
+pub fn ripemd160_rows(
+    prev_state: [u32; RIPEMD160_STATE_WORDS],
+    message: &[u32; RIPEMD160_BLOCK_WORDS],
+) -> Vec<[[u32; RIPEMD160_STATE_WORDS]; 2]> {
+    let mut rows = Vec::with_capacity(RIPEMD160_STEPS + 1);
+    let (mut left, mut right) = (prev_state, prev_state);
+    for j in 0..RIPEMD160_STEPS {
+        rows.push([left, right]);
+        left = ripemd160_step(Ripemd160Line::Left, j, left, message).next_vars;
+        right = ripemd160_step(Ripemd160Line::Right, j, right, message).next_vars;
+    }
+    rows.push([left, right]);
+    rows
+}
+
+/// The three words added to get each word of the new state, given the previous state and the
+/// final variables of the two lines.
+pub fn digest_summands(
+    prev_state: [u32; RIPEMD160_STATE_WORDS],
+    [left, right]: [[u32; RIPEMD160_STATE_WORDS]; 2],
+) -> [[u32; 3]; RIPEMD160_STATE_WORDS] {
+    from_fn(|i| {
+        [
+            prev_state[(i + 1) % RIPEMD160_STATE_WORDS],
+            left[(i + 2) % RIPEMD160_STATE_WORDS],
+            right[(i + 3) % RIPEMD160_STATE_WORDS],
+        ]
+    })
+}
+
+/// The bits of `x`, least significant bit first.
+pub fn u32_to_bits<F: AbstractField>(x: u32) -> [F; RIPEMD160_WORD_BITS] {
+    from_fn(|i| F::from_canonical_u32((x >> i) & 1))
+}
+
+/// The `u16` limbs of `x`, least significant limb first.
+pub fn u32_to_u16_limbs<F: AbstractField>(x: u32) -> [F; RIPEMD160_WORD_U16S] {
+    from_fn(|k| F::from_canonical_u32((x >> (16 * k)) & 0xffff))
+}
+
+/// The carry out of each `u16` limb when adding `words` limb by limb.
+pub fn u16_limb_carries(words: &[u32]) -> [u32; RIPEMD160_WORD_U16S] {
+    let mut carry = 0;
+    from_fn(|i| {
+        let limb_sum: u32 = words.iter().map(|word| (word >> (16 * i)) & 0xffff).sum();
+        carry = (limb_sum + carry) >> 16;
+        carry
+    })
+}
@@ -0,0 +1,175 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+/// This is custom-1 defined in RISC-V spec document
+pub const OPCODE: u8 = 0x2b;
+pub const RIPEMD160_FUNCT3: u8 = 0b111;
+pub const RIPEMD160_COMPRESS_FUNCT7: u8 = 0x0;
+
+/// Number of bytes in a RIPEMD-160 message block.
+pub const RIPEMD160_BLOCK_BYTES: usize = 64;
+
+/// Initial hash value of RIPEMD-160.
+pub const RIPEMD160_IV: [u32; 5] = [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476, 0xc3d2e1f0];
+
+/// Constants of the left line, one per group of 16 steps.
+pub const RIPEMD160_K_LEFT: [u32; 5] = [0x00000000, 0x5a827999, 0x6ed9eba1, 0x8f1bbcdc, 0xa953fd4e];
+/// Constants of the right line, one per group of 16 steps.
+pub const RIPEMD160_K_RIGHT: [u32; 5] =
+    [0x50a28be6, 0x5c4dd124, 0x6d703ef3, 0x7a6d76e9, 0x00000000];
+
+/// Message word selected by each step of the left line.
+pub const RIPEMD160_R_LEFT: [usize; 80] = [
+    0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, //
+    7, 4, 13, 1, 10, 6, 15, 3, 12, 0, 9, 5, 2, 14, 11, 8, //
+    3, 10, 14, 4, 9, 15, 8, 1, 2, 7, 0, 6, 13, 11, 5, 12, //
+    1, 9, 11, 10, 0, 8, 12, 4, 13, 3, 7, 15, 14, 5, 6, 2, //
+    4, 0, 5, 9, 7, 12, 2, 10, 14, 1, 3, 8, 11, 6, 15, 13,
+];
+/// Message word selected by each step of the right line.
+pub const RIPEMD160_R_RIGHT: [usize; 80] = [
+    5, 14, 7, 0, 9, 2, 11, 4, 13, 6, 15, 8, 1, 10, 3, 12, //
+    6, 11, 3, 7, 0, 13, 5, 10, 14, 15, 8, 12, 4, 9, 1, 2, //
+    15, 5, 1, 3, 7, 14, 6, 9, 11, 8, 12, 2, 10, 0, 4, 13, //
+    8, 6, 4, 1, 3, 11, 15, 0, 5, 12, 2, 13, 9, 7, 10, 14, //
+    12, 15, 10, 4, 1, 5, 8, 7, 6, 2, 13, 14, 0, 3, 9, 11,
+];
+/// Left rotation of each step of the left line.
+pub const RIPEMD160_S_LEFT: [u32; 80] = [
+    11, 14, 15, 12, 5, 8, 7, 9, 11, 13, 14, 15, 6, 7, 9, 8, //
+    7, 6, 8, 13, 11, 9, 7, 15, 7, 12, 15, 9, 11, 7, 13, 12, //
+    11, 13, 6, 7, 14, 9, 13, 15, 14, 8, 13, 6, 5, 12, 7, 5, //
+    11, 12, 14, 15, 14, 15, 9, 8, 9, 14, 5, 6, 8, 6, 5, 12, //
+    9, 15, 5, 11, 6, 8, 13, 12, 5, 12, 13, 14, 11, 8, 5, 6,
+];
+/// Left rotation of each step of the right line.
+pub const RIPEMD160_S_RIGHT: [u32; 80] = [
+    8, 9, 9, 11, 13, 15, 15, 5, 7, 7, 8, 11, 14, 14, 12, 6, //
+    9, 13, 15, 7, 12, 8, 9, 11, 7, 7, 12, 7, 6, 15, 13, 11, //
+    9, 7, 15, 11, 8, 6, 6, 14, 12, 13, 5, 14, 13, 13, 7, 5, //
+    15, 5, 8, 11, 14, 14, 6, 14, 6, 9, 12, 9, 12, 5, 15, 8, //
+    8, 5, 12, 9, 12, 5, 14, 6, 8, 13, 6, 5, 15, 13, 11, 11,
+];
+
+/// The boolean function of group `g` of the left line. The right line uses group `4 - g`.
+#[inline(always)]
+pub fn ripemd160_f(g: usize, x: u32, y: u32, z: u32) -> u32 {
+    match g {
+        0 => x ^ y ^ z,
+        1 => (x & y) | (!x & z),
+        2 => (x | !y) ^ z,
+        3 => (x & z) | (y & !z),
+        _ => x ^ (y | !z),
+    }
+}
+
+/// The ripemd160 cryptographic hash function.
+#[inline(always)]
+pub fn ripemd160(input: &[u8]) -> [u8; 20] {
+    let mut output = [0u8; 20];
+    set_ripemd160(input, &mut output);
+    output
+}
+
+/// Sets `output` to the ripemd160 hash of `input`.
+pub fn set_ripemd160(input: &[u8], output: &mut [u8; 20]) {
+    let mut state = RIPEMD160_IV;
+    let mut blocks = input.chunks_exact(RIPEMD160_BLOCK_BYTES);
+    for block in blocks.by_ref() {
+        ripemd160_compress(&mut state, block.try_into().unwrap());
+    }
+    let rem = blocks.remainder();
+    // The padding is a 1 bit, zeros, and the 64-bit little-endian bit length, so it takes one
+    // or two more blocks.
+    let mut last = [0u8; 2 * RIPEMD160_BLOCK_BYTES];
+    last[..rem.len()].copy_from_slice(rem);
+    last[rem.len()] = 0x80;
+    let num_last = if rem.len() < RIPEMD160_BLOCK_BYTES - 8 {
+        1
+    } else {
+        2
+    };
+    let last_len = num_last * RIPEMD160_BLOCK_BYTES;
+    let bit_len = (input.len() as u64) * 8;
+    last[last_len - 8..last_len].copy_from_slice(&bit_len.to_le_bytes());
+    for block in last[..last_len].chunks_exact(RIPEMD160_BLOCK_BYTES) {
+        ripemd160_compress(&mut state, block.try_into().unwrap());
+    }
+    for (chunk, word) in output.chunks_exact_mut(4).zip(state) {
+        chunk.copy_from_slice(&word.to_le_bytes());
+    }
+}
+
+/// Applies the RIPEMD-160 compression function to `state` with the message `block`, including
+/// the combination with the previous state.
+#[inline(always)]
+pub fn ripemd160_compress(state: &mut [u32; 5], block: &[u8; RIPEMD160_BLOCK_BYTES]) {
+    #[cfg(not(target_os = "zkvm"))]
+    {
+        let mut x = [0u32; 16];
+        for (x, chunk) in x.iter_mut().zip(block.chunks_exact(4)) {
+            *x = u32::from_le_bytes(chunk.try_into().unwrap());
+        }
+        let mut left = *state;
+        let mut right = *state;
+        for j in 0..80 {
+            let g = j / 16;
+            left = ripemd160_step(
+                left,
+                ripemd160_f(g, left[1], left[2], left[3]),
+                x[RIPEMD160_R_LEFT[j]],
+                RIPEMD160_K_LEFT[g],
+                RIPEMD160_S_LEFT[j],
+            );
+            right = ripemd160_step(
+                right,
+                ripemd160_f(4 - g, right[1], right[2], right[3]),
+                x[RIPEMD160_R_RIGHT[j]],
+                RIPEMD160_K_RIGHT[g],
+                RIPEMD160_S_RIGHT[j],
+            );
+        }
+        let h = *state;
+        *state = core::array::from_fn(|i| {
+            h[(i + 1) % 5]
+                .wrapping_add(left[(i + 2) % 5])
+                .wrapping_add(right[(i + 3) % 5])
+        });
+    }
+    #[cfg(target_os = "zkvm")]
+    native_ripemd160_compress(state.as_mut_ptr(), block.as_ptr());
+}
+
+/// One step of a line on the variables `[a, b, c, d, e]`, given the value `f` of the boolean
+/// function on `b, c, d`. Returns the variables of the next step.
+#[cfg(not(target_os = "zkvm"))]
+#[inline(always)]
+fn ripemd160_step([a, b, c, d, e]: [u32; 5], f: u32, x: u32, k: u32, s: u32) -> [u32; 5] {
+    let t = a
+        .wrapping_add(f)
+        .wrapping_add(x)
+        .wrapping_add(k)
+        .rotate_left(s)
+        .wrapping_add(e);
+    [e, t, b, c.rotate_left(10), d]
+}
+
+/// Native hook for the RIPEMD-160 compression function.
+///
+/// # Safety
+///
+/// The VM updates the state in place.
+/// - `state` must point to 5 little-endian `u32` words that are 4-byte aligned.
+/// - `block` must point to a 64-byte message block.
+#[cfg(target_os = "zkvm")]
+#[inline(always)]
+#[no_mangle]
+extern "C" fn native_ripemd160_compress(state: *mut u32, block: *const u8) {
+    openvm_platform::custom_insn_r!(
+        OPCODE,
+        RIPEMD160_FUNCT3,
+        RIPEMD160_COMPRESS_FUNCT7,
+        state,
+        block,
+        "x0"
+    );
+}
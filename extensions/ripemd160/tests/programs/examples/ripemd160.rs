@@ -0,0 +1,35 @@
+#![cfg_attr(not(feature = "std"), no_main)]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use core::hint::black_box;
+
+use hex::FromHex;
+use openvm_ripemd160_guest::ripemd160;
+
+openvm::entry!(main);
+
+pub fn main() {
+    let test_vectors = [
+        ("", "9c1185a5c5e9fc54612808977ee8f548b2258d31"),
+        ("abc", "8eb208f7e05d987a9b044a8e98c6b087f15a0bfc"),
+        ("message digest", "5d0689ef49d2fae572b881b123a85ffa21595f36"),
+        (
+            "abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq",
+            "12a053384a9c0c88e405a06c27dcf49ada62eb2b",
+        ),
+        (
+            "12345678901234567890123456789012345678901234567890123456789012345678901234567890",
+            "9b752e45573d4b39f4dbd3323cab82bf63326bfb",
+        ),
+    ];
+    for (input, expected) in test_vectors.iter() {
+        let input = black_box(input.as_bytes());
+        let expected = Vec::from_hex(expected).unwrap();
+        if ripemd160(input) != *expected {
+            panic!();
+        }
+    }
+}
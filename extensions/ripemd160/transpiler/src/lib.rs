@@ -0,0 +1,55 @@
+use openvm_instructions::{instruction::Instruction, UsizeOpcode};
+use openvm_instructions_derive::UsizeOpcode;
+use openvm_ripemd160_guest::{OPCODE, RIPEMD160_COMPRESS_FUNCT7, RIPEMD160_FUNCT3};
+use openvm_stark_backend::p3_field::PrimeField32;
+use openvm_transpiler::{util::from_r_type, EncodingRange, TranspilerExtension};
+use rrs_lib::instruction_formats::RType;
+use strum::{EnumCount, EnumIter, FromRepr};
+
+/// RIPEMD-160 compression of the 64-byte block at `[rs1]` into the state at `[rd]`, in place.
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, EnumCount, EnumIter, FromRepr, UsizeOpcode,
+)]
+#[opcode_offset = 0x330]
+#[repr(usize)]
+#[allow(non_camel_case_types)]
+pub enum Rv32Ripemd160Opcode {
+    RIPEMD160_COMPRESS,
+}
+
+#[derive(Default)]
+pub struct Ripemd160TranspilerExtension;
+
+impl<F: PrimeField32> TranspilerExtension<F> for Ripemd160TranspilerExtension {
+    fn process_custom(&self, instruction_stream: &[u32]) -> Option<(Instruction<F>, usize)> {
+        if instruction_stream.is_empty() {
+            return None;
+        }
+        let instruction_u32 = instruction_stream[0];
+        let opcode = (instruction_u32 & 0x7f) as u8;
+        let funct3 = ((instruction_u32 >> 12) & 0b111) as u8;
+
+        if (opcode, funct3) != (OPCODE, RIPEMD160_FUNCT3) {
+            return None;
+        }
+        let dec_insn = RType::new(instruction_u32);
+        // `rs2` is not used and must be `x0`.
+        if dec_insn.funct7 as u8 != RIPEMD160_COMPRESS_FUNCT7 || dec_insn.rs2 != 0 {
+            return None;
+        }
+        let instruction = from_r_type(
+            Rv32Ripemd160Opcode::RIPEMD160_COMPRESS.with_default_offset(),
+            2,
+            &dec_insn,
+        );
+        Some((instruction, 1))
+    }
+
+    fn encodings(&self) -> Option<Vec<EncodingRange>> {
+        Some(vec![EncodingRange::opcode(OPCODE)
+            .with_funct3(RIPEMD160_FUNCT3)
+            .with_funct7(
+                RIPEMD160_COMPRESS_FUNCT7..=RIPEMD160_COMPRESS_FUNCT7,
+            )])
+    }
+}
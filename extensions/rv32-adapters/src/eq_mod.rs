@@ -1,11 +1,11 @@
 use std::{
     array::from_fn,
     borrow::{Borrow, BorrowMut},
-    cell::RefCell,
     marker::PhantomData,
     sync::Arc,
 };
 
+use atomic_refcell::AtomicRefCell;
 use itertools::izip;
 use openvm_circuit::{
     arch::{
@@ -256,7 +256,7 @@ impl<
     ) -> Self {
         assert!(NUM_READS <= 2);
         assert_eq!(TOTAL_READ_SIZE, BLOCKS_PER_READ * BLOCK_SIZE);
-        let memory_controller = RefCell::borrow(&memory_controller);
+        let memory_controller = AtomicRefCell::borrow(&memory_controller);
         let memory_bridge = memory_controller.memory_bridge();
         let address_bits = memory_controller.mem_config().pointer_max_bits;
         assert!(
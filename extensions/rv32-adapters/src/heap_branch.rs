@@ -1,12 +1,12 @@
 use std::{
     array::from_fn,
     borrow::{Borrow, BorrowMut},
-    cell::RefCell,
     iter::once,
     marker::PhantomData,
     sync::Arc,
 };
 
+use atomic_refcell::AtomicRefCell;
 use itertools::izip;
 use openvm_circuit::{
     arch::{
@@ -184,7 +184,7 @@ impl<F: PrimeField32, const NUM_READS: usize, const READ_SIZE: usize>
         bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<RV32_CELL_BITS>>,
     ) -> Self {
         assert!(NUM_READS <= 2);
-        let memory_controller = RefCell::borrow(&memory_controller);
+        let memory_controller = AtomicRefCell::borrow(&memory_controller);
         let address_bits = memory_controller.mem_config().pointer_max_bits;
         assert!(
             RV32_CELL_BITS * RV32_REGISTER_NUM_LIMBS - address_bits < RV32_CELL_BITS,
@@ -1,12 +1,12 @@
 use std::{
     array::from_fn,
     borrow::{Borrow, BorrowMut},
-    cell::RefCell,
     iter::{once, zip},
     marker::PhantomData,
     sync::Arc,
 };
 
+use atomic_refcell::AtomicRefCell;
 use itertools::izip;
 use openvm_circuit::{
     arch::{
@@ -79,7 +79,7 @@ impl<
         bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<RV32_CELL_BITS>>,
     ) -> Self {
         assert!(NUM_READS <= 2);
-        let memory_controller = RefCell::borrow(&memory_controller);
+        let memory_controller = AtomicRefCell::borrow(&memory_controller);
         let memory_bridge = memory_controller.memory_bridge();
         let address_bits = memory_controller.mem_config().pointer_max_bits;
         assert!(
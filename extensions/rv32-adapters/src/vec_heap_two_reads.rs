@@ -1,12 +1,12 @@
 use std::{
     array::from_fn,
     borrow::{Borrow, BorrowMut},
-    cell::RefCell,
     iter::zip,
     marker::PhantomData,
     sync::Arc,
 };
 
+use atomic_refcell::AtomicRefCell;
 use itertools::izip;
 use openvm_circuit::{
     arch::{
@@ -90,7 +90,7 @@ impl<
         memory_controller: MemoryControllerRef<F>,
         bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<RV32_CELL_BITS>>,
     ) -> Self {
-        let memory_controller = RefCell::borrow(&memory_controller);
+        let memory_controller = AtomicRefCell::borrow(&memory_controller);
         let memory_bridge = memory_controller.memory_bridge();
         let address_bits = memory_controller.mem_config().pointer_max_bits;
         assert!(
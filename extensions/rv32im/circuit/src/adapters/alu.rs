@@ -1,22 +1,17 @@
 use std::{
     borrow::{Borrow, BorrowMut},
-    cell::RefCell,
     marker::PhantomData,
 };
 
 use openvm_circuit::{
     arch::{
         AdapterAirContext, AdapterRuntimeContext, BasicAdapterInterface, ExecutionBridge,
-        ExecutionBus, ExecutionState, MinimalInstruction, Result, VmAdapterAir, VmAdapterChip,
+        ExecutionState, MinimalInstruction, Result, VmAdapterAir, VmAdapterChip,
         VmAdapterInterface,
     },
-    system::{
-        memory::{
-            offline_checker::{MemoryBridge, MemoryReadAuxCols, MemoryWriteAuxCols},
-            MemoryAddress, MemoryAuxColsFactory, MemoryController, MemoryControllerRef,
-            MemoryReadRecord, MemoryWriteRecord,
-        },
-        program::ProgramBus,
+    system::memory::{
+        offline_checker::{MemoryBridge, MemoryReadAuxCols, MemoryWriteAuxCols},
+        MemoryAddress, MemoryAuxColsFactory, MemoryController, MemoryReadRecord, MemoryWriteRecord,
     },
 };
 use openvm_circuit_primitives::utils::not;
@@ -27,7 +22,7 @@ use openvm_instructions::{
 };
 use openvm_stark_backend::{
     interaction::InteractionBuilder,
-    p3_air::{AirBuilder, BaseAir},
+    p3_air::AirBuilder,
     p3_field::{AbstractField, Field, PrimeField32},
 };
 
@@ -42,23 +37,11 @@ pub struct Rv32BaseAluAdapterChip<F: Field> {
     _marker: PhantomData<F>,
 }
 
-impl<F: PrimeField32> Rv32BaseAluAdapterChip<F> {
-    pub fn new(
-        execution_bus: ExecutionBus,
-        program_bus: ProgramBus,
-        memory_controller: MemoryControllerRef<F>,
-    ) -> Self {
-        let memory_controller = RefCell::borrow(&memory_controller);
-        let memory_bridge = memory_controller.memory_bridge();
-        Self {
-            air: Rv32BaseAluAdapterAir {
-                execution_bridge: ExecutionBridge::new(execution_bus, program_bus),
-                memory_bridge,
-            },
-            _marker: PhantomData,
-        }
-    }
-}
+impl_rv32_adapter!(
+    Rv32BaseAluAdapterChip,
+    Rv32BaseAluAdapterAir,
+    Rv32BaseAluAdapterCols
+);
 
 #[derive(Clone, Debug)]
 pub struct Rv32BaseAluReadRecord<F: Field> {
@@ -100,12 +83,6 @@ pub struct Rv32BaseAluAdapterAir {
     pub(super) memory_bridge: MemoryBridge,
 }
 
-impl<F: Field> BaseAir<F> for Rv32BaseAluAdapterAir {
-    fn width(&self) -> usize {
-        Rv32BaseAluAdapterCols::<F>::width()
-    }
-}
-
 impl<AB: InteractionBuilder> VmAdapterAir<AB> for Rv32BaseAluAdapterAir {
     type Interface = BasicAdapterInterface<
         AB::Expr,
@@ -0,0 +1,362 @@
+use std::{
+    borrow::{Borrow, BorrowMut},
+    marker::PhantomData,
+    sync::Arc,
+};
+
+use atomic_refcell::AtomicRefCell;
+use openvm_circuit::{
+    arch::{
+        AdapterAirContext, AdapterRuntimeContext, BasicAdapterInterface, ExecutionBridge,
+        ExecutionBus, ExecutionState, MinimalInstruction, Result, VmAdapterAir, VmAdapterChip,
+        VmAdapterInterface,
+    },
+    system::{
+        memory::{
+            offline_checker::{MemoryBridge, MemoryReadAuxCols, MemoryWriteAuxCols},
+            MemoryAddress, MemoryAuxColsFactory, MemoryController, MemoryControllerRef,
+            MemoryReadRecord, MemoryWriteRecord,
+        },
+        program::ProgramBus,
+    },
+};
+use openvm_circuit_primitives::{
+    utils::not,
+    var_range::{VariableRangeCheckerBus, VariableRangeCheckerChip},
+};
+use openvm_circuit_primitives_derive::AlignedBorrow;
+use openvm_instructions::{
+    instruction::Instruction,
+    riscv::{RV32_IMM_AS, RV32_REGISTER_AS},
+};
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    p3_air::{AirBuilder, BaseAir},
+    p3_field::{AbstractField, Field, PrimeField32},
+};
+
+use super::{compose, RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS};
+
+/// The runtime interface additionally passes the memory address to the core chip, which checks
+/// that it is aligned.
+pub struct Rv32AtomicAdapterRuntimeInterface<T>(PhantomData<T>);
+impl<T> VmAdapterInterface<T> for Rv32AtomicAdapterRuntimeInterface<T> {
+    type Reads = ([[T; RV32_REGISTER_NUM_LIMBS]; 2], T);
+    type Writes = [[T; RV32_REGISTER_NUM_LIMBS]; 2];
+    type ProcessedInstruction = ();
+}
+
+/// Reads instructions of the form OP a, b, c, d, e, f where the word [[b:4]_d:4]_e is read and
+/// overwritten, and its old value is written to [a:4]_d when f = 1. The source is [c:4]_d.
+/// Operand d can only be 1, and e is the memory address space.
+///
+/// The reads passed to the core chip are the source and the old word, and the writes are the
+/// value of rd and the new word.
+#[derive(Debug)]
+pub struct Rv32AtomicAdapterChip<F: Field> {
+    pub air: Rv32AtomicAdapterAir,
+    pub range_checker_chip: Arc<VariableRangeCheckerChip>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField32> Rv32AtomicAdapterChip<F> {
+    pub fn new(
+        execution_bus: ExecutionBus,
+        program_bus: ProgramBus,
+        memory_controller: MemoryControllerRef<F>,
+        range_checker_chip: Arc<VariableRangeCheckerChip>,
+    ) -> Self {
+        let memory_controller = AtomicRefCell::borrow(&memory_controller);
+        let memory_bridge = memory_controller.memory_bridge();
+        Self {
+            air: Rv32AtomicAdapterAir {
+                execution_bridge: ExecutionBridge::new(execution_bus, program_bus),
+                memory_bridge,
+                range_bus: range_checker_chip.bus(),
+                pointer_max_bits: memory_controller.mem_config().pointer_max_bits,
+            },
+            range_checker_chip,
+            _marker: PhantomData,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Rv32AtomicReadRecord<F: Field> {
+    pub rs1: MemoryReadRecord<F, RV32_REGISTER_NUM_LIMBS>,
+    pub rs2: MemoryReadRecord<F, RV32_REGISTER_NUM_LIMBS>,
+    pub mem: MemoryReadRecord<F, RV32_REGISTER_NUM_LIMBS>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Rv32AtomicWriteRecord<F: Field> {
+    pub from_state: ExecutionState<u32>,
+    pub rd_ptr: F,
+    /// Write to destination register, None if rd is x0
+    pub rd: Option<MemoryWriteRecord<F, RV32_REGISTER_NUM_LIMBS>>,
+    pub mem: MemoryWriteRecord<F, RV32_REGISTER_NUM_LIMBS>,
+}
+
+#[repr(C)]
+#[derive(AlignedBorrow)]
+pub struct Rv32AtomicAdapterCols<T> {
+    pub from_state: ExecutionState<T>,
+    pub rd_ptr: T,
+    pub rs1_ptr: T,
+    pub rs2_ptr: T,
+    /// The memory address
+    pub rs1_data: [T; RV32_REGISTER_NUM_LIMBS],
+    pub mem_as: T,
+    /// 1 if the old value of the word is written to rd
+    pub needs_write: T,
+    pub rs1_aux: MemoryReadAuxCols<T, RV32_REGISTER_NUM_LIMBS>,
+    pub reads_aux: [MemoryReadAuxCols<T, RV32_REGISTER_NUM_LIMBS>; 2],
+    pub writes_aux: [MemoryWriteAuxCols<T, RV32_REGISTER_NUM_LIMBS>; 2],
+}
+
+#[derive(Clone, Copy, Debug, derive_new::new)]
+pub struct Rv32AtomicAdapterAir {
+    pub(super) execution_bridge: ExecutionBridge,
+    pub(super) memory_bridge: MemoryBridge,
+    pub range_bus: VariableRangeCheckerBus,
+    pointer_max_bits: usize,
+}
+
+impl<F: Field> BaseAir<F> for Rv32AtomicAdapterAir {
+    fn width(&self) -> usize {
+        Rv32AtomicAdapterCols::<F>::width()
+    }
+}
+
+impl<AB: InteractionBuilder> VmAdapterAir<AB> for Rv32AtomicAdapterAir {
+    type Interface = BasicAdapterInterface<
+        AB::Expr,
+        MinimalInstruction<AB::Expr>,
+        2,
+        2,
+        RV32_REGISTER_NUM_LIMBS,
+        RV32_REGISTER_NUM_LIMBS,
+    >;
+
+    fn eval(
+        &self,
+        builder: &mut AB,
+        local: &[AB::Var],
+        ctx: AdapterAirContext<AB::Expr, Self::Interface>,
+    ) {
+        let local: &Rv32AtomicAdapterCols<_> = local.borrow();
+        let timestamp = local.from_state.timestamp;
+        let mut timestamp_delta: usize = 0;
+        let mut timestamp_pp = || {
+            timestamp_delta += 1;
+            timestamp + AB::F::from_canonical_usize(timestamp_delta - 1)
+        };
+        let is_valid = ctx.instruction.is_valid;
+
+        builder.assert_bool(local.needs_write);
+        builder
+            .when::<AB::Expr>(not(is_valid.clone()))
+            .assert_zero(local.needs_write);
+
+        self.memory_bridge
+            .read(
+                MemoryAddress::new(AB::F::from_canonical_u32(RV32_REGISTER_AS), local.rs1_ptr),
+                local.rs1_data,
+                timestamp_pp(),
+                &local.rs1_aux,
+            )
+            .eval(builder, is_valid.clone());
+
+        // The address is word aligned and less than 2^pointer_max_bits:
+        // limbs_01 / 4 < 2^14 and limbs_23 < 2^(pointer_max_bits - 16)
+        let limbs_01 =
+            local.rs1_data[0] + local.rs1_data[1] * AB::F::from_canonical_u32(1 << RV32_CELL_BITS);
+        let limbs_23 =
+            local.rs1_data[2] + local.rs1_data[3] * AB::F::from_canonical_u32(1 << RV32_CELL_BITS);
+        self.range_bus
+            .range_check(
+                limbs_01.clone() * AB::F::from_canonical_u32(4).inverse(),
+                RV32_CELL_BITS * 2 - 2,
+            )
+            .eval(builder, is_valid.clone());
+        self.range_bus
+            .range_check(limbs_23.clone(), self.pointer_max_bits - RV32_CELL_BITS * 2)
+            .eval(builder, is_valid.clone());
+        let mem_ptr = limbs_01 + limbs_23 * AB::F::from_canonical_u32(1 << (RV32_CELL_BITS * 2));
+
+        self.memory_bridge
+            .read(
+                MemoryAddress::new(AB::F::from_canonical_u32(RV32_REGISTER_AS), local.rs2_ptr),
+                ctx.reads[0].clone(),
+                timestamp_pp(),
+                &local.reads_aux[0],
+            )
+            .eval(builder, is_valid.clone());
+
+        self.memory_bridge
+            .read(
+                MemoryAddress::new(local.mem_as, mem_ptr.clone()),
+                ctx.reads[1].clone(),
+                timestamp_pp(),
+                &local.reads_aux[1],
+            )
+            .eval(builder, is_valid.clone());
+
+        self.memory_bridge
+            .write(
+                MemoryAddress::new(AB::F::from_canonical_u32(RV32_REGISTER_AS), local.rd_ptr),
+                ctx.writes[0].clone(),
+                timestamp_pp(),
+                &local.writes_aux[0],
+            )
+            .eval(builder, local.needs_write);
+
+        self.memory_bridge
+            .write(
+                MemoryAddress::new(local.mem_as, mem_ptr),
+                ctx.writes[1].clone(),
+                timestamp_pp(),
+                &local.writes_aux[1],
+            )
+            .eval(builder, is_valid.clone());
+
+        self.execution_bridge
+            .execute_and_increment_or_set_pc(
+                ctx.instruction.opcode,
+                [
+                    local.rd_ptr.into(),
+                    local.rs1_ptr.into(),
+                    local.rs2_ptr.into(),
+                    AB::Expr::from_canonical_u32(RV32_REGISTER_AS),
+                    local.mem_as.into(),
+                    local.needs_write.into(),
+                ],
+                local.from_state,
+                AB::F::from_canonical_usize(timestamp_delta),
+                (4, ctx.to_pc),
+            )
+            .eval(builder, is_valid);
+    }
+
+    fn get_from_pc(&self, local: &[AB::Var]) -> AB::Var {
+        let cols: &Rv32AtomicAdapterCols<_> = local.borrow();
+        cols.from_state.pc
+    }
+}
+
+impl<F: PrimeField32> VmAdapterChip<F> for Rv32AtomicAdapterChip<F> {
+    type ReadRecord = Rv32AtomicReadRecord<F>;
+    type WriteRecord = Rv32AtomicWriteRecord<F>;
+    type Air = Rv32AtomicAdapterAir;
+    type Interface = Rv32AtomicAdapterRuntimeInterface<F>;
+
+    fn preprocess(
+        &mut self,
+        memory: &mut MemoryController<F>,
+        instruction: &Instruction<F>,
+    ) -> Result<(
+        <Self::Interface as VmAdapterInterface<F>>::Reads,
+        Self::ReadRecord,
+    )> {
+        let Instruction { b, c, d, e, .. } = *instruction;
+
+        debug_assert_eq!(d.as_canonical_u32(), RV32_REGISTER_AS);
+        debug_assert!(e.as_canonical_u32() != RV32_IMM_AS);
+        assert!(self.range_checker_chip.range_max_bits() >= 14);
+
+        let rs1 = memory.read::<RV32_REGISTER_NUM_LIMBS>(d, b);
+        let ptr_val = compose(rs1.data);
+        assert!(
+            ptr_val < (1 << self.air.pointer_max_bits),
+            "ptr_val: {ptr_val} >= 2 ** {}",
+            self.air.pointer_max_bits
+        );
+        self.range_checker_chip
+            .add_count((ptr_val & 0xffff) / 4, RV32_CELL_BITS * 2 - 2);
+        self.range_checker_chip.add_count(
+            ptr_val >> (RV32_CELL_BITS * 2),
+            self.air.pointer_max_bits - RV32_CELL_BITS * 2,
+        );
+
+        let rs2 = memory.read::<RV32_REGISTER_NUM_LIMBS>(d, c);
+        // Memory is only accessed at aligned pointers. A misaligned address is rejected by the
+        // core chip before any row is generated.
+        let mem = memory.read::<RV32_REGISTER_NUM_LIMBS>(e, F::from_canonical_u32(ptr_val & !3));
+
+        Ok((
+            ([rs2.data, mem.data], F::from_canonical_u32(ptr_val)),
+            Self::ReadRecord { rs1, rs2, mem },
+        ))
+    }
+
+    fn postprocess(
+        &mut self,
+        memory: &mut MemoryController<F>,
+        instruction: &Instruction<F>,
+        from_state: ExecutionState<u32>,
+        output: AdapterRuntimeContext<F, Self::Interface>,
+        read_record: &Self::ReadRecord,
+    ) -> Result<(ExecutionState<u32>, Self::WriteRecord)> {
+        let Instruction { a, d, e, f, .. } = *instruction;
+        let rd = if f != F::ZERO {
+            Some(memory.write(d, a, output.writes[0]))
+        } else {
+            memory.increment_timestamp();
+            None
+        };
+        let mem = memory.write(e, read_record.mem.pointer, output.writes[1]);
+
+        let timestamp_delta = memory.timestamp() - from_state.timestamp;
+        debug_assert!(
+            timestamp_delta == 5,
+            "timestamp delta is {}, expected 5",
+            timestamp_delta
+        );
+
+        Ok((
+            ExecutionState {
+                pc: from_state.pc + 4,
+                timestamp: memory.timestamp(),
+            },
+            Self::WriteRecord {
+                from_state,
+                rd_ptr: a,
+                rd,
+                mem,
+            },
+        ))
+    }
+
+    fn generate_trace_row(
+        &self,
+        row_slice: &mut [F],
+        read_record: Self::ReadRecord,
+        write_record: Self::WriteRecord,
+        aux_cols_factory: &MemoryAuxColsFactory<F>,
+    ) {
+        let row_slice: &mut Rv32AtomicAdapterCols<_> = row_slice.borrow_mut();
+        row_slice.from_state = write_record.from_state.map(F::from_canonical_u32);
+        row_slice.rd_ptr = write_record.rd_ptr;
+        row_slice.rs1_ptr = read_record.rs1.pointer;
+        row_slice.rs2_ptr = read_record.rs2.pointer;
+        row_slice.rs1_data = read_record.rs1.data;
+        row_slice.mem_as = read_record.mem.address_space;
+        row_slice.needs_write = F::from_bool(write_record.rd.is_some());
+        row_slice.rs1_aux = aux_cols_factory.make_read_aux_cols(read_record.rs1);
+        row_slice.reads_aux = [
+            aux_cols_factory.make_read_aux_cols(read_record.rs2),
+            aux_cols_factory.make_read_aux_cols(read_record.mem),
+        ];
+        row_slice.writes_aux = [
+            match write_record.rd {
+                Some(rd_record) => aux_cols_factory.make_write_aux_cols(rd_record),
+                None => MemoryWriteAuxCols::<F, RV32_REGISTER_NUM_LIMBS>::disabled(),
+            },
+            aux_cols_factory.make_write_aux_cols(write_record.mem),
+        ];
+    }
+
+    fn air(&self) -> &Self::Air {
+        &self.air
+    }
+}
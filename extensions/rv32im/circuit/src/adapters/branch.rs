@@ -1,29 +1,22 @@
 use std::{
     borrow::{Borrow, BorrowMut},
-    cell::RefCell,
     marker::PhantomData,
 };
 
 use openvm_circuit::{
     arch::{
         AdapterAirContext, AdapterRuntimeContext, BasicAdapterInterface, ExecutionBridge,
-        ExecutionBus, ExecutionState, ImmInstruction, Result, VmAdapterAir, VmAdapterChip,
-        VmAdapterInterface,
+        ExecutionState, ImmInstruction, Result, VmAdapterAir, VmAdapterChip, VmAdapterInterface,
     },
-    system::{
-        memory::{
-            offline_checker::{MemoryBridge, MemoryReadAuxCols},
-            MemoryAddress, MemoryAuxColsFactory, MemoryController, MemoryControllerRef,
-            MemoryReadRecord,
-        },
-        program::ProgramBus,
+    system::memory::{
+        offline_checker::{MemoryBridge, MemoryReadAuxCols},
+        MemoryAddress, MemoryAuxColsFactory, MemoryController, MemoryReadRecord,
     },
 };
 use openvm_circuit_primitives_derive::AlignedBorrow;
 use openvm_instructions::{instruction::Instruction, riscv::RV32_REGISTER_AS};
 use openvm_stark_backend::{
     interaction::InteractionBuilder,
-    p3_air::BaseAir,
     p3_field::{AbstractField, Field, PrimeField32},
 };
 
@@ -37,23 +30,11 @@ pub struct Rv32BranchAdapterChip<F: Field> {
     _marker: PhantomData<F>,
 }
 
-impl<F: PrimeField32> Rv32BranchAdapterChip<F> {
-    pub fn new(
-        execution_bus: ExecutionBus,
-        program_bus: ProgramBus,
-        memory_controller: MemoryControllerRef<F>,
-    ) -> Self {
-        let memory_controller = RefCell::borrow(&memory_controller);
-        let memory_bridge = memory_controller.memory_bridge();
-        Self {
-            air: Rv32BranchAdapterAir {
-                execution_bridge: ExecutionBridge::new(execution_bus, program_bus),
-                memory_bridge,
-            },
-            _marker: PhantomData,
-        }
-    }
-}
+impl_rv32_adapter!(
+    Rv32BranchAdapterChip,
+    Rv32BranchAdapterAir,
+    Rv32BranchAdapterCols
+);
 
 #[derive(Debug)]
 pub struct Rv32BranchReadRecord<F: Field> {
@@ -83,12 +64,6 @@ pub struct Rv32BranchAdapterAir {
     pub(super) memory_bridge: MemoryBridge,
 }
 
-impl<F: Field> BaseAir<F> for Rv32BranchAdapterAir {
-    fn width(&self) -> usize {
-        Rv32BranchAdapterCols::<F>::width()
-    }
-}
-
 impl<AB: InteractionBuilder> VmAdapterAir<AB> for Rv32BranchAdapterAir {
     type Interface =
         BasicAdapterInterface<AB::Expr, ImmInstruction<AB::Expr>, 2, 0, RV32_REGISTER_NUM_LIMBS, 0>;
@@ -0,0 +1,330 @@
+use std::{
+    borrow::{Borrow, BorrowMut},
+    marker::PhantomData,
+};
+
+use atomic_refcell::AtomicRefCell;
+use openvm_circuit::{
+    arch::{
+        AdapterAirContext, AdapterRuntimeContext, BasicAdapterInterface, ExecutionBridge,
+        ExecutionBus, ExecutionState, MinimalInstruction, Result, VmAdapterAir, VmAdapterChip,
+        VmAdapterInterface,
+    },
+    system::{
+        memory::{
+            offline_checker::{MemoryBridge, MemoryReadAuxCols, MemoryWriteAuxCols},
+            MemoryAddress, MemoryAuxColsFactory, MemoryController, MemoryControllerRef,
+            MemoryReadRecord, MemoryWriteRecord,
+        },
+        program::ProgramBus,
+    },
+};
+use openvm_circuit_primitives::utils::not;
+use openvm_circuit_primitives_derive::AlignedBorrow;
+use openvm_instructions::{
+    instruction::Instruction,
+    riscv::{RV32_IMM_AS, RV32_REGISTER_AS},
+};
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    p3_air::{AirBuilder, BaseAir},
+    p3_field::{AbstractField, Field, PrimeField32},
+};
+
+use super::RV32_REGISTER_NUM_LIMBS;
+
+/// Reads instructions of the form OP a, b, c, d, e, f where the CSR [b:4]_d is read and
+/// overwritten, and its old value is written to [a:4]_d when f = 1. The source is [c:4]_e.
+/// Operand d can only be 1, and e can be either 1 (for register reads) or 0 (when c is an
+/// immediate, whose upper limbs are zero).
+#[derive(Debug)]
+pub struct Rv32CsrAdapterChip<F: Field> {
+    pub air: Rv32CsrAdapterAir,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField32> Rv32CsrAdapterChip<F> {
+    pub fn new(
+        execution_bus: ExecutionBus,
+        program_bus: ProgramBus,
+        memory_controller: MemoryControllerRef<F>,
+    ) -> Self {
+        let memory_controller = AtomicRefCell::borrow(&memory_controller);
+        let memory_bridge = memory_controller.memory_bridge();
+        Self {
+            air: Rv32CsrAdapterAir {
+                execution_bridge: ExecutionBridge::new(execution_bus, program_bus),
+                memory_bridge,
+            },
+            _marker: PhantomData,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Rv32CsrReadRecord<F: Field> {
+    /// Either
+    /// - read rs1 register value or
+    /// - if the source is an immediate, this is None
+    pub rs1: Option<MemoryReadRecord<F, RV32_REGISTER_NUM_LIMBS>>,
+    /// immediate value of the source or 0
+    pub rs1_imm: F,
+    /// Read CSR value from address space d=1
+    pub csr: MemoryReadRecord<F, RV32_REGISTER_NUM_LIMBS>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Rv32CsrWriteRecord<F: Field> {
+    pub from_state: ExecutionState<u32>,
+    pub rd_ptr: F,
+    /// Write to destination register, None if rd is x0
+    pub rd: Option<MemoryWriteRecord<F, RV32_REGISTER_NUM_LIMBS>>,
+    pub csr: MemoryWriteRecord<F, RV32_REGISTER_NUM_LIMBS>,
+}
+
+#[repr(C)]
+#[derive(AlignedBorrow)]
+pub struct Rv32CsrAdapterCols<T> {
+    pub from_state: ExecutionState<T>,
+    pub rd_ptr: T,
+    pub csr_ptr: T,
+    // Pointer if rs1 was a read, immediate value otherwise
+    pub rs1: T,
+    /// 1 if rs1 was a read, 0 if an immediate
+    pub rs1_as: T,
+    /// 1 if the old value of the CSR is written to rd
+    pub needs_write: T,
+    pub reads_aux: [MemoryReadAuxCols<T, RV32_REGISTER_NUM_LIMBS>; 2],
+    pub writes_aux: [MemoryWriteAuxCols<T, RV32_REGISTER_NUM_LIMBS>; 2],
+}
+
+#[derive(Clone, Copy, Debug, derive_new::new)]
+pub struct Rv32CsrAdapterAir {
+    pub(super) execution_bridge: ExecutionBridge,
+    pub(super) memory_bridge: MemoryBridge,
+}
+
+impl<F: Field> BaseAir<F> for Rv32CsrAdapterAir {
+    fn width(&self) -> usize {
+        Rv32CsrAdapterCols::<F>::width()
+    }
+}
+
+impl<AB: InteractionBuilder> VmAdapterAir<AB> for Rv32CsrAdapterAir {
+    /// Reads are the source and the old CSR value, writes are rd and the new CSR value.
+    type Interface = BasicAdapterInterface<
+        AB::Expr,
+        MinimalInstruction<AB::Expr>,
+        2,
+        2,
+        RV32_REGISTER_NUM_LIMBS,
+        RV32_REGISTER_NUM_LIMBS,
+    >;
+
+    fn eval(
+        &self,
+        builder: &mut AB,
+        local: &[AB::Var],
+        ctx: AdapterAirContext<AB::Expr, Self::Interface>,
+    ) {
+        let local: &Rv32CsrAdapterCols<_> = local.borrow();
+        let timestamp = local.from_state.timestamp;
+        let mut timestamp_delta: usize = 0;
+        let mut timestamp_pp = || {
+            timestamp_delta += 1;
+            timestamp + AB::F::from_canonical_usize(timestamp_delta - 1)
+        };
+
+        // if rs1 is an immediate value, constrain that its 4-byte representation is correct
+        let rs1_limbs = ctx.reads[0].clone();
+        builder.assert_bool(local.rs1_as);
+        let mut rs1_imm_when = builder.when(not(local.rs1_as));
+        rs1_imm_when.assert_eq(local.rs1, rs1_limbs[0].clone());
+        for limb in rs1_limbs.iter().skip(1) {
+            rs1_imm_when.assert_zero(limb.clone());
+        }
+
+        builder.assert_bool(local.needs_write);
+        builder
+            .when::<AB::Expr>(not(ctx.instruction.is_valid.clone()))
+            .assert_zero(local.needs_write);
+
+        self.memory_bridge
+            .read(
+                MemoryAddress::new(local.rs1_as, local.rs1),
+                ctx.reads[0].clone(),
+                timestamp_pp(),
+                &local.reads_aux[0],
+            )
+            .eval(builder, local.rs1_as);
+
+        self.memory_bridge
+            .read(
+                MemoryAddress::new(AB::F::from_canonical_u32(RV32_REGISTER_AS), local.csr_ptr),
+                ctx.reads[1].clone(),
+                timestamp_pp(),
+                &local.reads_aux[1],
+            )
+            .eval(builder, ctx.instruction.is_valid.clone());
+
+        self.memory_bridge
+            .write(
+                MemoryAddress::new(AB::F::from_canonical_u32(RV32_REGISTER_AS), local.rd_ptr),
+                ctx.writes[0].clone(),
+                timestamp_pp(),
+                &local.writes_aux[0],
+            )
+            .eval(builder, local.needs_write);
+
+        self.memory_bridge
+            .write(
+                MemoryAddress::new(AB::F::from_canonical_u32(RV32_REGISTER_AS), local.csr_ptr),
+                ctx.writes[1].clone(),
+                timestamp_pp(),
+                &local.writes_aux[1],
+            )
+            .eval(builder, ctx.instruction.is_valid.clone());
+
+        self.execution_bridge
+            .execute_and_increment_or_set_pc(
+                ctx.instruction.opcode,
+                [
+                    local.rd_ptr.into(),
+                    local.csr_ptr.into(),
+                    local.rs1.into(),
+                    AB::Expr::from_canonical_u32(RV32_REGISTER_AS),
+                    local.rs1_as.into(),
+                    local.needs_write.into(),
+                ],
+                local.from_state,
+                AB::F::from_canonical_usize(timestamp_delta),
+                (4, ctx.to_pc),
+            )
+            .eval(builder, ctx.instruction.is_valid);
+    }
+
+    fn get_from_pc(&self, local: &[AB::Var]) -> AB::Var {
+        let cols: &Rv32CsrAdapterCols<_> = local.borrow();
+        cols.from_state.pc
+    }
+}
+
+impl<F: PrimeField32> VmAdapterChip<F> for Rv32CsrAdapterChip<F> {
+    type ReadRecord = Rv32CsrReadRecord<F>;
+    type WriteRecord = Rv32CsrWriteRecord<F>;
+    type Air = Rv32CsrAdapterAir;
+    type Interface = BasicAdapterInterface<
+        F,
+        MinimalInstruction<F>,
+        2,
+        2,
+        RV32_REGISTER_NUM_LIMBS,
+        RV32_REGISTER_NUM_LIMBS,
+    >;
+
+    fn preprocess(
+        &mut self,
+        memory: &mut MemoryController<F>,
+        instruction: &Instruction<F>,
+    ) -> Result<(
+        <Self::Interface as VmAdapterInterface<F>>::Reads,
+        Self::ReadRecord,
+    )> {
+        let Instruction { b, c, d, e, .. } = *instruction;
+
+        debug_assert_eq!(d.as_canonical_u32(), RV32_REGISTER_AS);
+        debug_assert!(
+            e.as_canonical_u32() == RV32_IMM_AS || e.as_canonical_u32() == RV32_REGISTER_AS
+        );
+
+        let (rs1, rs1_data, rs1_imm) = if e.is_zero() {
+            debug_assert!(c.as_canonical_u32() < 1 << 5);
+            memory.increment_timestamp();
+            (None, [c, F::ZERO, F::ZERO, F::ZERO], c)
+        } else {
+            let rs1_read = memory.read::<RV32_REGISTER_NUM_LIMBS>(e, c);
+            (Some(rs1_read), rs1_read.data, F::ZERO)
+        };
+        let csr = memory.read::<RV32_REGISTER_NUM_LIMBS>(d, b);
+
+        Ok(([rs1_data, csr.data], Self::ReadRecord { rs1, rs1_imm, csr }))
+    }
+
+    fn postprocess(
+        &mut self,
+        memory: &mut MemoryController<F>,
+        instruction: &Instruction<F>,
+        from_state: ExecutionState<u32>,
+        output: AdapterRuntimeContext<F, Self::Interface>,
+        _read_record: &Self::ReadRecord,
+    ) -> Result<(ExecutionState<u32>, Self::WriteRecord)> {
+        let Instruction { a, b, d, f, .. } = *instruction;
+        let rd = if f != F::ZERO {
+            Some(memory.write(d, a, output.writes[0]))
+        } else {
+            memory.increment_timestamp();
+            None
+        };
+        let csr = memory.write(d, b, output.writes[1]);
+
+        let timestamp_delta = memory.timestamp() - from_state.timestamp;
+        debug_assert!(
+            timestamp_delta == 4,
+            "timestamp delta is {}, expected 4",
+            timestamp_delta
+        );
+
+        Ok((
+            ExecutionState {
+                pc: from_state.pc + 4,
+                timestamp: memory.timestamp(),
+            },
+            Self::WriteRecord {
+                from_state,
+                rd_ptr: a,
+                rd,
+                csr,
+            },
+        ))
+    }
+
+    fn generate_trace_row(
+        &self,
+        row_slice: &mut [F],
+        read_record: Self::ReadRecord,
+        write_record: Self::WriteRecord,
+        aux_cols_factory: &MemoryAuxColsFactory<F>,
+    ) {
+        let row_slice: &mut Rv32CsrAdapterCols<_> = row_slice.borrow_mut();
+        row_slice.from_state = write_record.from_state.map(F::from_canonical_u32);
+        row_slice.rd_ptr = write_record.rd_ptr;
+        row_slice.csr_ptr = read_record.csr.pointer;
+        row_slice.rs1 = read_record
+            .rs1
+            .map(|rs1| rs1.pointer)
+            .unwrap_or(read_record.rs1_imm);
+        row_slice.rs1_as = read_record
+            .rs1
+            .map(|rs1| rs1.address_space)
+            .unwrap_or(F::ZERO);
+        row_slice.needs_write = F::from_bool(write_record.rd.is_some());
+        row_slice.reads_aux = [
+            match read_record.rs1 {
+                Some(rs1_record) => aux_cols_factory.make_read_aux_cols(rs1_record),
+                None => MemoryReadAuxCols::<F, RV32_REGISTER_NUM_LIMBS>::disabled(),
+            },
+            aux_cols_factory.make_read_aux_cols(read_record.csr),
+        ];
+        row_slice.writes_aux = [
+            match write_record.rd {
+                Some(rd_record) => aux_cols_factory.make_write_aux_cols(rd_record),
+                None => MemoryWriteAuxCols::<F, RV32_REGISTER_NUM_LIMBS>::disabled(),
+            },
+            aux_cols_factory.make_write_aux_cols(write_record.csr),
+        ];
+    }
+
+    fn air(&self) -> &Self::Air {
+        &self.air
+    }
+}
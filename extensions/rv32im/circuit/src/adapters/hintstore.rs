@@ -1,11 +1,11 @@
 use std::{
     array,
     borrow::{Borrow, BorrowMut},
-    cell::RefCell,
     marker::PhantomData,
     sync::Arc,
 };
 
+use atomic_refcell::AtomicRefCell;
 use openvm_circuit::{
     arch::{
         AdapterAirContext, AdapterRuntimeContext, BasicAdapterInterface, ExecutionBridge,
@@ -52,7 +52,7 @@ impl<F: PrimeField32> Rv32HintStoreAdapterChip<F> {
         memory_controller: MemoryControllerRef<F>,
         range_checker_chip: Arc<VariableRangeCheckerChip>,
     ) -> Self {
-        let memory_controller = RefCell::borrow(&memory_controller);
+        let memory_controller = AtomicRefCell::borrow(&memory_controller);
         let memory_bridge = memory_controller.memory_bridge();
         Self {
             air: Rv32HintStoreAdapterAir {
@@ -1,22 +1,16 @@
 use std::{
     borrow::{Borrow, BorrowMut},
-    cell::RefCell,
     marker::PhantomData,
 };
 
 use openvm_circuit::{
     arch::{
         AdapterAirContext, AdapterRuntimeContext, BasicAdapterInterface, ExecutionBridge,
-        ExecutionBus, ExecutionState, ImmInstruction, Result, VmAdapterAir, VmAdapterChip,
-        VmAdapterInterface,
+        ExecutionState, ImmInstruction, Result, VmAdapterAir, VmAdapterChip, VmAdapterInterface,
     },
-    system::{
-        memory::{
-            offline_checker::{MemoryBridge, MemoryReadAuxCols, MemoryWriteAuxCols},
-            MemoryAddress, MemoryAuxColsFactory, MemoryController, MemoryControllerRef,
-            MemoryReadRecord, MemoryWriteRecord,
-        },
-        program::ProgramBus,
+    system::memory::{
+        offline_checker::{MemoryBridge, MemoryReadAuxCols, MemoryWriteAuxCols},
+        MemoryAddress, MemoryAuxColsFactory, MemoryController, MemoryReadRecord, MemoryWriteRecord,
     },
 };
 use openvm_circuit_primitives::utils::not;
@@ -24,7 +18,7 @@ use openvm_circuit_primitives_derive::AlignedBorrow;
 use openvm_instructions::{instruction::Instruction, riscv::RV32_REGISTER_AS};
 use openvm_stark_backend::{
     interaction::InteractionBuilder,
-    p3_air::{AirBuilder, BaseAir},
+    p3_air::AirBuilder,
     p3_field::{AbstractField, Field, PrimeField32},
 };
 
@@ -37,23 +31,7 @@ pub struct Rv32JalrAdapterChip<F: Field> {
     _marker: PhantomData<F>,
 }
 
-impl<F: PrimeField32> Rv32JalrAdapterChip<F> {
-    pub fn new(
-        execution_bus: ExecutionBus,
-        program_bus: ProgramBus,
-        memory_controller: MemoryControllerRef<F>,
-    ) -> Self {
-        let memory_controller = RefCell::borrow(&memory_controller);
-        let memory_bridge = memory_controller.memory_bridge();
-        Self {
-            air: Rv32JalrAdapterAir {
-                execution_bridge: ExecutionBridge::new(execution_bus, program_bus),
-                memory_bridge,
-            },
-            _marker: PhantomData,
-        }
-    }
-}
+impl_rv32_adapter!(Rv32JalrAdapterChip, Rv32JalrAdapterAir, Rv32JalrAdapterCols);
 #[derive(Debug, Clone)]
 pub struct Rv32JalrReadRecord<F: Field> {
     pub rs1: MemoryReadRecord<F, RV32_REGISTER_NUM_LIMBS>,
@@ -82,12 +60,6 @@ pub struct Rv32JalrAdapterAir {
     pub(super) execution_bridge: ExecutionBridge,
 }
 
-impl<F: Field> BaseAir<F> for Rv32JalrAdapterAir {
-    fn width(&self) -> usize {
-        Rv32JalrAdapterCols::<F>::width()
-    }
-}
-
 impl<AB: InteractionBuilder> VmAdapterAir<AB> for Rv32JalrAdapterAir {
     type Interface = BasicAdapterInterface<
         AB::Expr,
@@ -1,11 +1,11 @@
 use std::{
     array,
     borrow::{Borrow, BorrowMut},
-    cell::RefCell,
     marker::PhantomData,
     sync::Arc,
 };
 
+use atomic_refcell::AtomicRefCell;
 use openvm_circuit::{
     arch::{
         AdapterAirContext, AdapterRuntimeContext, ExecutionBridge, ExecutionBus, ExecutionState,
@@ -69,7 +69,8 @@ pub struct LoadStoreInstruction<T> {
 /// This method ensures that there are no modifications to the global interfaces.
 ///
 /// Here 2 reads represent read_data and prev_data,
-/// The second element of the tuple in Reads is the shift amount needed to be passed to the core chip
+/// The second element of the tuple in Reads is the unshifted memory pointer, from which the core
+/// chip gets the shift amount and checks that the access is aligned
 /// Getting the intermediate pointer is completely internal to the adapter and shouldn't be a part of the AdapterInterface
 pub struct Rv32LoadStoreAdapterRuntimeInterface<T>(PhantomData<T>);
 impl<T> VmAdapterInterface<T> for Rv32LoadStoreAdapterRuntimeInterface<T> {
@@ -108,7 +109,7 @@ impl<F: PrimeField32> Rv32LoadStoreAdapterChip<F> {
         range_checker_chip: Arc<VariableRangeCheckerChip>,
         offset: usize,
     ) -> Self {
-        let memory_controller = RefCell::borrow(&memory_controller);
+        let memory_controller = AtomicRefCell::borrow(&memory_controller);
         let memory_bridge = memory_controller.memory_bridge();
         Self {
             air: Rv32LoadStoreAdapterAir {
@@ -402,7 +403,7 @@ impl<F: PrimeField32> VmAdapterChip<F> for Rv32LoadStoreAdapterChip<F> {
         Ok((
             (
                 [prev_data, read_record.data],
-                F::from_canonical_u32(shift_amount),
+                F::from_canonical_u32(ptr_val + shift_amount),
             ),
             Self::ReadRecord {
                 rs1_record,
@@ -3,20 +3,66 @@ use std::ops::Mul;
 use openvm_circuit::system::memory::{MemoryController, MemoryReadRecord};
 use openvm_stark_backend::p3_field::{AbstractField, PrimeField32};
 
+/// Implements the constructor of an adapter chip whose AIR consists of an `execution_bridge` and
+/// a `memory_bridge`, and the width of the AIR from its columns.
+///
+/// The loadstore adapter is not built with this macro: its constructor also takes the range
+/// checker and the pointer offset, and its AIR is configured from the memory config.
+macro_rules! impl_rv32_adapter {
+    ($chip:ident, $air:ident, $cols:ident) => {
+        impl<F: ::openvm_stark_backend::p3_field::PrimeField32> $chip<F> {
+            pub fn new(
+                execution_bus: ::openvm_circuit::arch::ExecutionBus,
+                program_bus: ::openvm_circuit::system::program::ProgramBus,
+                memory_controller: ::openvm_circuit::system::memory::MemoryControllerRef<F>,
+            ) -> Self {
+                let memory_controller = ::atomic_refcell::AtomicRefCell::borrow(&memory_controller);
+                Self {
+                    air: $air {
+                        execution_bridge: ::openvm_circuit::arch::ExecutionBridge::new(
+                            execution_bus,
+                            program_bus,
+                        ),
+                        memory_bridge: memory_controller.memory_bridge(),
+                    },
+                    _marker: ::std::marker::PhantomData,
+                }
+            }
+        }
+
+        impl<F: ::openvm_stark_backend::p3_field::Field> ::openvm_stark_backend::p3_air::BaseAir<F>
+            for $air
+        {
+            fn width(&self) -> usize {
+                $cols::<F>::width()
+            }
+        }
+    };
+}
+
 mod alu;
+mod atomic;
 mod branch;
+mod csr;
 mod hintstore;
 mod jalr;
 mod loadstore;
 mod mul;
+mod mul_fused;
 mod rdwrite;
 
+#[cfg(test)]
+mod tests;
+
 pub use alu::*;
+pub use atomic::*;
 pub use branch::*;
+pub use csr::*;
 pub use hintstore::*;
 pub use jalr::*;
 pub use loadstore::*;
 pub use mul::*;
+pub use mul_fused::*;
 pub use openvm_instructions::riscv::{RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS};
 pub use rdwrite::*;
 
@@ -1,29 +1,23 @@
 use std::{
     borrow::{Borrow, BorrowMut},
-    cell::RefCell,
     marker::PhantomData,
 };
 
 use openvm_circuit::{
     arch::{
         AdapterAirContext, AdapterRuntimeContext, BasicAdapterInterface, ExecutionBridge,
-        ExecutionBus, ExecutionState, MinimalInstruction, Result, VmAdapterAir, VmAdapterChip,
+        ExecutionState, MinimalInstruction, Result, VmAdapterAir, VmAdapterChip,
         VmAdapterInterface,
     },
-    system::{
-        memory::{
-            offline_checker::{MemoryBridge, MemoryReadAuxCols, MemoryWriteAuxCols},
-            MemoryAddress, MemoryAuxColsFactory, MemoryController, MemoryControllerRef,
-            MemoryReadRecord, MemoryWriteRecord,
-        },
-        program::ProgramBus,
+    system::memory::{
+        offline_checker::{MemoryBridge, MemoryReadAuxCols, MemoryWriteAuxCols},
+        MemoryAddress, MemoryAuxColsFactory, MemoryController, MemoryReadRecord, MemoryWriteRecord,
     },
 };
 use openvm_circuit_primitives_derive::AlignedBorrow;
 use openvm_instructions::{instruction::Instruction, riscv::RV32_REGISTER_AS};
 use openvm_stark_backend::{
     interaction::InteractionBuilder,
-    p3_air::BaseAir,
     p3_field::{AbstractField, Field, PrimeField32},
 };
 
@@ -37,22 +31,7 @@ pub struct Rv32MultAdapterChip<F: Field> {
     _marker: PhantomData<F>,
 }
 
-impl<F: PrimeField32> Rv32MultAdapterChip<F> {
-    pub fn new(
-        execution_bus: ExecutionBus,
-        program_bus: ProgramBus,
-        memory_controller: MemoryControllerRef<F>,
-    ) -> Self {
-        let memory_controller = RefCell::borrow(&memory_controller);
-        Self {
-            air: Rv32MultAdapterAir {
-                execution_bridge: ExecutionBridge::new(execution_bus, program_bus),
-                memory_bridge: memory_controller.memory_bridge(),
-            },
-            _marker: PhantomData,
-        }
-    }
-}
+impl_rv32_adapter!(Rv32MultAdapterChip, Rv32MultAdapterAir, Rv32MultAdapterCols);
 
 #[derive(Debug)]
 pub struct Rv32MultReadRecord<F: Field> {
@@ -85,12 +64,6 @@ pub struct Rv32MultAdapterAir {
     pub(super) memory_bridge: MemoryBridge,
 }
 
-impl<F: Field> BaseAir<F> for Rv32MultAdapterAir {
-    fn width(&self) -> usize {
-        Rv32MultAdapterCols::<F>::width()
-    }
-}
-
 impl<AB: InteractionBuilder> VmAdapterAir<AB> for Rv32MultAdapterAir {
     type Interface = BasicAdapterInterface<
         AB::Expr,
@@ -0,0 +1,235 @@
+use std::{
+    borrow::{Borrow, BorrowMut},
+    marker::PhantomData,
+};
+
+use openvm_circuit::{
+    arch::{
+        AdapterAirContext, AdapterRuntimeContext, BasicAdapterInterface, ExecutionBridge,
+        ExecutionState, MinimalInstruction, Result, VmAdapterAir, VmAdapterChip,
+        VmAdapterInterface,
+    },
+    system::memory::{
+        offline_checker::{MemoryBridge, MemoryReadAuxCols, MemoryWriteAuxCols},
+        MemoryAddress, MemoryAuxColsFactory, MemoryController, MemoryWriteRecord,
+    },
+};
+use openvm_circuit_primitives_derive::AlignedBorrow;
+use openvm_instructions::{
+    instruction::Instruction, program::DEFAULT_PC_STEP, riscv::RV32_REGISTER_AS,
+};
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    p3_field::{AbstractField, Field, PrimeField32},
+};
+
+use super::{Rv32MultReadRecord, RV32_REGISTER_NUM_LIMBS};
+
+/// Reads instructions of the form OP a, b, c, d, e, f where [a:4]_d and [f:4]_d are the low and
+/// high words of [b:4]_d op [c:4]_d. Operand d can only be 1, and there is no immediate support.
+/// The instruction is fused with the one after it, so the pc is advanced past both.
+#[derive(Debug)]
+pub struct Rv32MultFusedAdapterChip<F: Field> {
+    pub air: Rv32MultFusedAdapterAir,
+    _marker: PhantomData<F>,
+}
+
+impl_rv32_adapter!(
+    Rv32MultFusedAdapterChip,
+    Rv32MultFusedAdapterAir,
+    Rv32MultFusedAdapterCols
+);
+
+#[derive(Debug)]
+pub struct Rv32MultFusedWriteRecord<F: Field> {
+    pub from_state: ExecutionState<u32>,
+    /// Write of the low word
+    pub rd: MemoryWriteRecord<F, RV32_REGISTER_NUM_LIMBS>,
+    /// Write of the high word
+    pub rd_hi: MemoryWriteRecord<F, RV32_REGISTER_NUM_LIMBS>,
+}
+
+#[repr(C)]
+#[derive(AlignedBorrow)]
+pub struct Rv32MultFusedAdapterCols<T> {
+    pub from_state: ExecutionState<T>,
+    pub rd_ptr: T,
+    pub rs1_ptr: T,
+    pub rs2_ptr: T,
+    pub rd_hi_ptr: T,
+    pub reads_aux: [MemoryReadAuxCols<T, RV32_REGISTER_NUM_LIMBS>; 2],
+    pub writes_aux: [MemoryWriteAuxCols<T, RV32_REGISTER_NUM_LIMBS>; 2],
+}
+
+#[derive(Clone, Copy, Debug, derive_new::new)]
+pub struct Rv32MultFusedAdapterAir {
+    pub(super) execution_bridge: ExecutionBridge,
+    pub(super) memory_bridge: MemoryBridge,
+}
+
+impl<AB: InteractionBuilder> VmAdapterAir<AB> for Rv32MultFusedAdapterAir {
+    type Interface = BasicAdapterInterface<
+        AB::Expr,
+        MinimalInstruction<AB::Expr>,
+        2,
+        2,
+        RV32_REGISTER_NUM_LIMBS,
+        RV32_REGISTER_NUM_LIMBS,
+    >;
+
+    fn eval(
+        &self,
+        builder: &mut AB,
+        local: &[AB::Var],
+        ctx: AdapterAirContext<AB::Expr, Self::Interface>,
+    ) {
+        let local: &Rv32MultFusedAdapterCols<_> = local.borrow();
+        let timestamp = local.from_state.timestamp;
+        let mut timestamp_delta: usize = 0;
+        let mut timestamp_pp = || {
+            timestamp_delta += 1;
+            timestamp + AB::F::from_canonical_usize(timestamp_delta - 1)
+        };
+
+        for (ptr, data, aux) in [
+            (local.rs1_ptr, &ctx.reads[0], &local.reads_aux[0]),
+            (local.rs2_ptr, &ctx.reads[1], &local.reads_aux[1]),
+        ] {
+            self.memory_bridge
+                .read(
+                    MemoryAddress::new(AB::F::from_canonical_u32(RV32_REGISTER_AS), ptr),
+                    data.clone(),
+                    timestamp_pp(),
+                    aux,
+                )
+                .eval(builder, ctx.instruction.is_valid.clone());
+        }
+
+        for (ptr, data, aux) in [
+            (local.rd_ptr, &ctx.writes[0], &local.writes_aux[0]),
+            (local.rd_hi_ptr, &ctx.writes[1], &local.writes_aux[1]),
+        ] {
+            self.memory_bridge
+                .write(
+                    MemoryAddress::new(AB::F::from_canonical_u32(RV32_REGISTER_AS), ptr),
+                    data.clone(),
+                    timestamp_pp(),
+                    aux,
+                )
+                .eval(builder, ctx.instruction.is_valid.clone());
+        }
+
+        self.execution_bridge
+            .execute_and_increment_or_set_pc(
+                ctx.instruction.opcode,
+                [
+                    local.rd_ptr.into(),
+                    local.rs1_ptr.into(),
+                    local.rs2_ptr.into(),
+                    AB::Expr::from_canonical_u32(RV32_REGISTER_AS),
+                    AB::Expr::ZERO,
+                    local.rd_hi_ptr.into(),
+                ],
+                local.from_state,
+                AB::F::from_canonical_usize(timestamp_delta),
+                (2 * DEFAULT_PC_STEP, ctx.to_pc),
+            )
+            .eval(builder, ctx.instruction.is_valid);
+    }
+
+    fn get_from_pc(&self, local: &[AB::Var]) -> AB::Var {
+        let cols: &Rv32MultFusedAdapterCols<_> = local.borrow();
+        cols.from_state.pc
+    }
+}
+
+impl<F: PrimeField32> VmAdapterChip<F> for Rv32MultFusedAdapterChip<F> {
+    type ReadRecord = Rv32MultReadRecord<F>;
+    type WriteRecord = Rv32MultFusedWriteRecord<F>;
+    type Air = Rv32MultFusedAdapterAir;
+    type Interface = BasicAdapterInterface<
+        F,
+        MinimalInstruction<F>,
+        2,
+        2,
+        RV32_REGISTER_NUM_LIMBS,
+        RV32_REGISTER_NUM_LIMBS,
+    >;
+
+    fn preprocess(
+        &mut self,
+        memory: &mut MemoryController<F>,
+        instruction: &Instruction<F>,
+    ) -> Result<(
+        <Self::Interface as VmAdapterInterface<F>>::Reads,
+        Self::ReadRecord,
+    )> {
+        let Instruction { b, c, d, .. } = *instruction;
+
+        debug_assert_eq!(d.as_canonical_u32(), RV32_REGISTER_AS);
+
+        let rs1 = memory.read::<RV32_REGISTER_NUM_LIMBS>(d, b);
+        let rs2 = memory.read::<RV32_REGISTER_NUM_LIMBS>(d, c);
+
+        Ok(([rs1.data, rs2.data], Self::ReadRecord { rs1, rs2 }))
+    }
+
+    fn postprocess(
+        &mut self,
+        memory: &mut MemoryController<F>,
+        instruction: &Instruction<F>,
+        from_state: ExecutionState<u32>,
+        output: AdapterRuntimeContext<F, Self::Interface>,
+        _read_record: &Self::ReadRecord,
+    ) -> Result<(ExecutionState<u32>, Self::WriteRecord)> {
+        let Instruction { a, d, f, .. } = *instruction;
+        let rd = memory.write(d, a, output.writes[0]);
+        let rd_hi = memory.write(d, f, output.writes[1]);
+
+        let timestamp_delta = memory.timestamp() - from_state.timestamp;
+        debug_assert!(
+            timestamp_delta == 4,
+            "timestamp delta is {}, expected 4",
+            timestamp_delta
+        );
+
+        Ok((
+            ExecutionState {
+                pc: from_state.pc + 2 * DEFAULT_PC_STEP,
+                timestamp: memory.timestamp(),
+            },
+            Self::WriteRecord {
+                from_state,
+                rd,
+                rd_hi,
+            },
+        ))
+    }
+
+    fn generate_trace_row(
+        &self,
+        row_slice: &mut [F],
+        read_record: Self::ReadRecord,
+        write_record: Self::WriteRecord,
+        aux_cols_factory: &MemoryAuxColsFactory<F>,
+    ) {
+        let row_slice: &mut Rv32MultFusedAdapterCols<_> = row_slice.borrow_mut();
+        row_slice.from_state = write_record.from_state.map(F::from_canonical_u32);
+        row_slice.rd_ptr = write_record.rd.pointer;
+        row_slice.rs1_ptr = read_record.rs1.pointer;
+        row_slice.rs2_ptr = read_record.rs2.pointer;
+        row_slice.rd_hi_ptr = write_record.rd_hi.pointer;
+        row_slice.reads_aux = [
+            aux_cols_factory.make_read_aux_cols(read_record.rs1),
+            aux_cols_factory.make_read_aux_cols(read_record.rs2),
+        ];
+        row_slice.writes_aux = [
+            aux_cols_factory.make_write_aux_cols(write_record.rd),
+            aux_cols_factory.make_write_aux_cols(write_record.rd_hi),
+        ];
+    }
+
+    fn air(&self) -> &Self::Air {
+        &self.air
+    }
+}
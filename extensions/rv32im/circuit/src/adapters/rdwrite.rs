@@ -1,6 +1,5 @@
 use std::{
     borrow::{Borrow, BorrowMut},
-    cell::RefCell,
     marker::PhantomData,
 };
 
@@ -45,23 +44,11 @@ pub struct Rv32CondRdWriteAdapterChip<F: Field> {
     pub air: Rv32CondRdWriteAdapterAir,
 }
 
-impl<F: PrimeField32> Rv32RdWriteAdapterChip<F> {
-    pub fn new(
-        execution_bus: ExecutionBus,
-        program_bus: ProgramBus,
-        memory_controller: MemoryControllerRef<F>,
-    ) -> Self {
-        let memory_controller = RefCell::borrow(&memory_controller);
-        let memory_bridge = memory_controller.memory_bridge();
-        Self {
-            air: Rv32RdWriteAdapterAir {
-                execution_bridge: ExecutionBridge::new(execution_bus, program_bus),
-                memory_bridge,
-            },
-            _marker: PhantomData,
-        }
-    }
-}
+impl_rv32_adapter!(
+    Rv32RdWriteAdapterChip,
+    Rv32RdWriteAdapterAir,
+    Rv32RdWriteAdapterCols
+);
 
 impl<F: PrimeField32> Rv32CondRdWriteAdapterChip<F> {
     pub fn new(
@@ -107,12 +94,6 @@ pub struct Rv32CondRdWriteAdapterAir {
     inner: Rv32RdWriteAdapterAir,
 }
 
-impl<F: Field> BaseAir<F> for Rv32RdWriteAdapterAir {
-    fn width(&self) -> usize {
-        Rv32RdWriteAdapterCols::<F>::width()
-    }
-}
-
 impl<F: Field> BaseAir<F> for Rv32CondRdWriteAdapterAir {
     fn width(&self) -> usize {
         Rv32CondRdWriteAdapterCols::<F>::width()
@@ -0,0 +1,456 @@
+use std::array;
+
+use openvm_circuit::arch::{
+    testing::{memory::gen_pointer, VmChipTestBuilder},
+    AdapterAirContext, AdapterRuntimeContext, DynArray, InstructionExecutor, Result, VmAdapterChip,
+    VmAdapterInterface, VmChipWrapper, VmCoreAir, VmCoreChip,
+};
+use openvm_instructions::{instruction::Instruction, riscv::RV32_REGISTER_AS, VmOpcode};
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    p3_air::{AirBuilder, BaseAir},
+    p3_field::{AbstractField, Field},
+    p3_matrix::dense::DenseMatrix,
+    rap::BaseAirWithPublicValues,
+    utils::disable_debug_builder,
+    verifier::VerificationError,
+    Chip,
+};
+use openvm_stark_sdk::{
+    config::baby_bear_blake3::BabyBearBlake3Config, p3_baby_bear::BabyBear,
+    utils::create_seeded_rng,
+};
+use rand::{rngs::StdRng, Rng};
+
+use super::{
+    Rv32BaseAluAdapterChip, Rv32BranchAdapterChip, Rv32JalrAdapterChip, Rv32MultAdapterChip,
+    Rv32MultFusedAdapterChip, Rv32RdWriteAdapterChip, RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS,
+};
+
+type F = BabyBear;
+type Rv32AdapterTestChip<A> = VmChipWrapper<F, A, PassthroughCoreChip>;
+
+/// The number of cells of the processed instruction: `is_valid`, `opcode` and `immediate`.
+const NUM_INSTRUCTION_CELLS: usize = 3;
+
+/// Core with no constraints other than `is_valid` being boolean, so that a chip built with it
+/// only exercises its adapter. A row is `[is_valid, opcode, immediate, reads.., writes..]`.
+#[derive(Clone, Copy, Debug)]
+struct PassthroughCoreAir {
+    num_read_cells: usize,
+    num_write_cells: usize,
+}
+
+impl<T: Field> BaseAir<T> for PassthroughCoreAir {
+    fn width(&self) -> usize {
+        NUM_INSTRUCTION_CELLS + self.num_read_cells + self.num_write_cells
+    }
+}
+impl<T: Field> BaseAirWithPublicValues<T> for PassthroughCoreAir {}
+
+impl<AB, I> VmCoreAir<AB, I> for PassthroughCoreAir
+where
+    AB: InteractionBuilder,
+    I: VmAdapterInterface<AB::Expr>,
+    I::Reads: From<DynArray<AB::Expr>>,
+    I::Writes: From<DynArray<AB::Expr>>,
+    I::ProcessedInstruction: From<DynArray<AB::Expr>>,
+{
+    fn eval(
+        &self,
+        builder: &mut AB,
+        local_core: &[AB::Var],
+        _from_pc: AB::Var,
+    ) -> AdapterAirContext<AB::Expr, I> {
+        builder.assert_bool(local_core[0]);
+        let (instruction, data) = local_core.split_at(NUM_INSTRUCTION_CELLS);
+        let (reads, writes) = data.split_at(self.num_read_cells);
+        let to_exprs = |cells: &[AB::Var]| -> DynArray<AB::Expr> {
+            DynArray(cells.iter().map(|&cell| cell.into()).collect())
+        };
+
+        AdapterAirContext {
+            to_pc: None,
+            reads: to_exprs(reads).into(),
+            writes: to_exprs(writes).into(),
+            instruction: to_exprs(instruction).into(),
+        }
+    }
+}
+
+/// Writes the read cells back in order, wrapping around, or counts up when nothing is read.
+#[derive(Debug)]
+struct PassthroughCoreChip {
+    air: PassthroughCoreAir,
+}
+
+impl<I: VmAdapterInterface<F>> VmCoreChip<F, I> for PassthroughCoreChip
+where
+    I::Reads: Into<DynArray<F>>,
+    I::Writes: From<DynArray<F>>,
+{
+    type Record = Vec<F>;
+    type Air = PassthroughCoreAir;
+
+    fn execute_instruction(
+        &self,
+        instruction: &Instruction<F>,
+        _from_pc: u32,
+        reads: I::Reads,
+    ) -> Result<(AdapterRuntimeContext<F, I>, Self::Record)> {
+        let reads: DynArray<F> = reads.into();
+        let reads = reads.0;
+        let writes: Vec<F> = (0..self.air.num_write_cells)
+            .map(|i| match reads.len() {
+                0 => F::from_canonical_usize(i),
+                len => reads[i % len],
+            })
+            .collect();
+        let row = [F::ONE, instruction.opcode.to_field(), instruction.c]
+            .into_iter()
+            .chain(reads)
+            .chain(writes.iter().copied())
+            .collect();
+        Ok((AdapterRuntimeContext::without_pc(DynArray(writes)), row))
+    }
+
+    fn get_opcode_name(&self, opcode: usize) -> String {
+        format!("PASSTHROUGH_{}", opcode)
+    }
+
+    fn generate_trace_row(&self, row_slice: &mut [F], record: Self::Record) {
+        row_slice.copy_from_slice(&record);
+    }
+
+    fn air(&self) -> &Self::Air {
+        &self.air
+    }
+}
+
+/// Adapter-level tests shared by the RV32 adapters with a [BasicAdapterInterface]. A new adapter
+/// is covered by implementing this trait and calling [run_rv32_adapter_rand_test] and
+/// [run_rv32_adapter_negative_tests] with it.
+///
+/// The loadstore adapter cannot be driven by [PassthroughCoreAir]: its AIR interface reads the
+/// previous data as variables and takes a `LoadStoreInstruction` rather than flat cells, so it
+/// is tested together with its core in the `loadstore` and `load_sign_extend` modules.
+///
+/// [BasicAdapterInterface]: openvm_circuit::arch::BasicAdapterInterface
+trait Rv32AdapterSuite: VmAdapterChip<F> + Sized {
+    const NUM_READ_CELLS: usize;
+    const NUM_WRITE_CELLS: usize;
+
+    fn new_for_test(tester: &VmChipTestBuilder<F>) -> Self;
+
+    /// Writes random values to the registers read by the returned instruction.
+    fn rand_instruction(tester: &mut VmChipTestBuilder<F>, rng: &mut StdRng) -> Instruction<F>;
+}
+
+fn rand_opcode(rng: &mut StdRng) -> VmOpcode {
+    VmOpcode::from_usize(rng.gen_range(0..(1 << 10)))
+}
+
+fn write_rand_register(tester: &mut VmChipTestBuilder<F>, rng: &mut StdRng, ptr: usize) {
+    let data: [F; RV32_REGISTER_NUM_LIMBS] =
+        array::from_fn(|_| F::from_canonical_u32(rng.gen_range(0..(1 << RV32_CELL_BITS))));
+    tester.write(RV32_REGISTER_AS as usize, ptr, data);
+}
+
+fn rand_register(rng: &mut StdRng) -> usize {
+    gen_pointer(rng, RV32_REGISTER_NUM_LIMBS)
+}
+
+impl Rv32AdapterSuite for Rv32BaseAluAdapterChip<F> {
+    const NUM_READ_CELLS: usize = 2 * RV32_REGISTER_NUM_LIMBS;
+    const NUM_WRITE_CELLS: usize = RV32_REGISTER_NUM_LIMBS;
+
+    fn new_for_test(tester: &VmChipTestBuilder<F>) -> Self {
+        Self::new(
+            tester.execution_bus(),
+            tester.program_bus(),
+            tester.memory_controller(),
+        )
+    }
+
+    fn rand_instruction(tester: &mut VmChipTestBuilder<F>, rng: &mut StdRng) -> Instruction<F> {
+        let [rd, rs1, rs2] = array::from_fn(|_| rand_register(rng));
+        write_rand_register(tester, rng, rs1);
+        if rng.gen_bool(0.5) {
+            write_rand_register(tester, rng, rs2);
+            Instruction::from_usize(rand_opcode(rng), [rd, rs1, rs2, 1, 1])
+        } else {
+            // Immediates are sign extended from 16 bits
+            let imm = rng.gen_range(0..(1 << 16)) | if rng.gen() { 0xff0000 } else { 0 };
+            Instruction::from_usize(rand_opcode(rng), [rd, rs1, imm, 1, 0])
+        }
+    }
+}
+
+impl Rv32AdapterSuite for Rv32MultAdapterChip<F> {
+    const NUM_READ_CELLS: usize = 2 * RV32_REGISTER_NUM_LIMBS;
+    const NUM_WRITE_CELLS: usize = RV32_REGISTER_NUM_LIMBS;
+
+    fn new_for_test(tester: &VmChipTestBuilder<F>) -> Self {
+        Self::new(
+            tester.execution_bus(),
+            tester.program_bus(),
+            tester.memory_controller(),
+        )
+    }
+
+    fn rand_instruction(tester: &mut VmChipTestBuilder<F>, rng: &mut StdRng) -> Instruction<F> {
+        let [rd, rs1, rs2] = array::from_fn(|_| rand_register(rng));
+        write_rand_register(tester, rng, rs1);
+        write_rand_register(tester, rng, rs2);
+        Instruction::from_usize(rand_opcode(rng), [rd, rs1, rs2, 1])
+    }
+}
+
+impl Rv32AdapterSuite for Rv32MultFusedAdapterChip<F> {
+    const NUM_READ_CELLS: usize = 2 * RV32_REGISTER_NUM_LIMBS;
+    const NUM_WRITE_CELLS: usize = 2 * RV32_REGISTER_NUM_LIMBS;
+
+    fn new_for_test(tester: &VmChipTestBuilder<F>) -> Self {
+        Self::new(
+            tester.execution_bus(),
+            tester.program_bus(),
+            tester.memory_controller(),
+        )
+    }
+
+    fn rand_instruction(tester: &mut VmChipTestBuilder<F>, rng: &mut StdRng) -> Instruction<F> {
+        let [rd, rs1, rs2] = array::from_fn(|_| rand_register(rng));
+        let rd_hi = loop {
+            let ptr = rand_register(rng);
+            if ptr != rd {
+                break ptr;
+            }
+        };
+        write_rand_register(tester, rng, rs1);
+        write_rand_register(tester, rng, rs2);
+        Instruction::from_usize(rand_opcode(rng), [rd, rs1, rs2, 1, 0, rd_hi])
+    }
+}
+
+impl Rv32AdapterSuite for Rv32BranchAdapterChip<F> {
+    const NUM_READ_CELLS: usize = 2 * RV32_REGISTER_NUM_LIMBS;
+    const NUM_WRITE_CELLS: usize = 0;
+
+    fn new_for_test(tester: &VmChipTestBuilder<F>) -> Self {
+        Self::new(
+            tester.execution_bus(),
+            tester.program_bus(),
+            tester.memory_controller(),
+        )
+    }
+
+    fn rand_instruction(tester: &mut VmChipTestBuilder<F>, rng: &mut StdRng) -> Instruction<F> {
+        let [rs1, rs2] = array::from_fn(|_| rand_register(rng));
+        write_rand_register(tester, rng, rs1);
+        write_rand_register(tester, rng, rs2);
+        let imm = rng.gen_range(0..(1 << 12)) * 2;
+        Instruction::from_usize(rand_opcode(rng), [rs1, rs2, imm, 1, 1])
+    }
+}
+
+impl Rv32AdapterSuite for Rv32JalrAdapterChip<F> {
+    const NUM_READ_CELLS: usize = RV32_REGISTER_NUM_LIMBS;
+    const NUM_WRITE_CELLS: usize = RV32_REGISTER_NUM_LIMBS;
+
+    fn new_for_test(tester: &VmChipTestBuilder<F>) -> Self {
+        Self::new(
+            tester.execution_bus(),
+            tester.program_bus(),
+            tester.memory_controller(),
+        )
+    }
+
+    fn rand_instruction(tester: &mut VmChipTestBuilder<F>, rng: &mut StdRng) -> Instruction<F> {
+        let [rd, rs1] = array::from_fn(|_| rand_register(rng));
+        write_rand_register(tester, rng, rs1);
+        let imm = rng.gen_range(0..(1 << 16));
+        Instruction::from_usize(rand_opcode(rng), [rd, rs1, imm, 1, 0, 1])
+    }
+}
+
+impl Rv32AdapterSuite for Rv32RdWriteAdapterChip<F> {
+    const NUM_READ_CELLS: usize = 0;
+    const NUM_WRITE_CELLS: usize = RV32_REGISTER_NUM_LIMBS;
+
+    fn new_for_test(tester: &VmChipTestBuilder<F>) -> Self {
+        Self::new(
+            tester.execution_bus(),
+            tester.program_bus(),
+            tester.memory_controller(),
+        )
+    }
+
+    fn rand_instruction(_tester: &mut VmChipTestBuilder<F>, rng: &mut StdRng) -> Instruction<F> {
+        let rd = rand_register(rng);
+        let imm = rng.gen_range(0..(1 << 20));
+        Instruction::from_usize(rand_opcode(rng), [rd, 0, imm, 1, 0])
+    }
+}
+
+fn setup<A: Rv32AdapterSuite>(num_ops: usize) -> (VmChipTestBuilder<F>, Rv32AdapterTestChip<A>)
+where
+    PassthroughCoreChip: VmCoreChip<F, A::Interface>,
+    Rv32AdapterTestChip<A>: InstructionExecutor<F>,
+{
+    let mut rng = create_seeded_rng();
+    let mut tester = VmChipTestBuilder::default();
+    let core = PassthroughCoreChip {
+        air: PassthroughCoreAir {
+            num_read_cells: A::NUM_READ_CELLS,
+            num_write_cells: A::NUM_WRITE_CELLS,
+        },
+    };
+    let mut chip =
+        Rv32AdapterTestChip::<A>::new(A::new_for_test(&tester), core, tester.memory_controller());
+
+    for _ in 0..num_ops {
+        let instruction = A::rand_instruction(&mut tester, &mut rng);
+        tester.execute(&mut chip, instruction);
+    }
+    (tester, chip)
+}
+
+///////////////////////////////////////////////////////////////////////////////////////
+/// POSITIVE TESTS
+///
+/// Execute random instructions, ensuring that the generated trace passes all constraints.
+///////////////////////////////////////////////////////////////////////////////////////
+
+fn run_rv32_adapter_rand_test<A: Rv32AdapterSuite>()
+where
+    PassthroughCoreChip: VmCoreChip<F, A::Interface>,
+    Rv32AdapterTestChip<A>: InstructionExecutor<F> + Chip<BabyBearBlake3Config>,
+{
+    let (tester, chip) = setup::<A>(100);
+    let tester = tester.build().load(chip).finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
+#[test]
+fn rv32_base_alu_adapter_rand_test() {
+    run_rv32_adapter_rand_test::<Rv32BaseAluAdapterChip<F>>();
+}
+
+#[test]
+fn rv32_mult_adapter_rand_test() {
+    run_rv32_adapter_rand_test::<Rv32MultAdapterChip<F>>();
+}
+
+#[test]
+fn rv32_mult_fused_adapter_rand_test() {
+    run_rv32_adapter_rand_test::<Rv32MultFusedAdapterChip<F>>();
+}
+
+#[test]
+fn rv32_branch_adapter_rand_test() {
+    run_rv32_adapter_rand_test::<Rv32BranchAdapterChip<F>>();
+}
+
+#[test]
+fn rv32_jalr_adapter_rand_test() {
+    run_rv32_adapter_rand_test::<Rv32JalrAdapterChip<F>>();
+}
+
+#[test]
+fn rv32_rdwrite_adapter_rand_test() {
+    run_rv32_adapter_rand_test::<Rv32RdWriteAdapterChip<F>>();
+}
+
+//////////////////////////////////////////////////////////////////////////////////////
+// NEGATIVE TESTS
+//
+// Execute a single instruction and change one cell of the trace that is sent on the
+// execution, program or memory bus, which must unbalance that bus.
+//////////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Clone, Copy, Debug)]
+enum AdapterPrank {
+    FromPc,
+    FromTimestamp,
+    Opcode,
+    ReadData,
+    WriteData,
+}
+
+fn run_rv32_adapter_negative_test<A: Rv32AdapterSuite>(prank: AdapterPrank)
+where
+    PassthroughCoreChip: VmCoreChip<F, A::Interface>,
+    Rv32AdapterTestChip<A>: InstructionExecutor<F> + Chip<BabyBearBlake3Config>,
+{
+    let (tester, chip) = setup::<A>(1);
+    // Every RV32 adapter starts with its `from_state`
+    let adapter_width = BaseAir::<F>::width(chip.adapter.air());
+    let col = match prank {
+        AdapterPrank::FromPc => 0,
+        AdapterPrank::FromTimestamp => 1,
+        AdapterPrank::Opcode => adapter_width + 1,
+        AdapterPrank::ReadData => adapter_width + NUM_INSTRUCTION_CELLS,
+        AdapterPrank::WriteData => adapter_width + NUM_INSTRUCTION_CELLS + A::NUM_READ_CELLS,
+    };
+    let modify_trace = |trace: &mut DenseMatrix<F>| {
+        trace.values[col] += F::ONE;
+    };
+
+    let tester = tester
+        .build()
+        .load_and_prank_trace(chip, modify_trace)
+        .finalize();
+    tester.simple_test_with_expected_error(VerificationError::ChallengePhaseError);
+}
+
+fn run_rv32_adapter_negative_tests<A: Rv32AdapterSuite>()
+where
+    PassthroughCoreChip: VmCoreChip<F, A::Interface>,
+    Rv32AdapterTestChip<A>: InstructionExecutor<F> + Chip<BabyBearBlake3Config>,
+{
+    disable_debug_builder();
+    let mut pranks = vec![
+        AdapterPrank::FromPc,
+        AdapterPrank::FromTimestamp,
+        AdapterPrank::Opcode,
+    ];
+    if A::NUM_READ_CELLS > 0 {
+        pranks.push(AdapterPrank::ReadData);
+    }
+    if A::NUM_WRITE_CELLS > 0 {
+        pranks.push(AdapterPrank::WriteData);
+    }
+    for prank in pranks {
+        run_rv32_adapter_negative_test::<A>(prank);
+    }
+}
+
+#[test]
+fn rv32_base_alu_adapter_negative_test() {
+    run_rv32_adapter_negative_tests::<Rv32BaseAluAdapterChip<F>>();
+}
+
+#[test]
+fn rv32_mult_adapter_negative_test() {
+    run_rv32_adapter_negative_tests::<Rv32MultAdapterChip<F>>();
+}
+
+#[test]
+fn rv32_mult_fused_adapter_negative_test() {
+    run_rv32_adapter_negative_tests::<Rv32MultFusedAdapterChip<F>>();
+}
+
+#[test]
+fn rv32_branch_adapter_negative_test() {
+    run_rv32_adapter_negative_tests::<Rv32BranchAdapterChip<F>>();
+}
+
+#[test]
+fn rv32_jalr_adapter_negative_test() {
+    run_rv32_adapter_negative_tests::<Rv32JalrAdapterChip<F>>();
+}
+
+#[test]
+fn rv32_rdwrite_adapter_negative_test() {
+    run_rv32_adapter_negative_tests::<Rv32RdWriteAdapterChip<F>>();
+}
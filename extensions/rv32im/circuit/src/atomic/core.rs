@@ -0,0 +1,457 @@
+use std::{
+    array,
+    borrow::{Borrow, BorrowMut},
+    sync::Arc,
+};
+
+use openvm_circuit::arch::{
+    AdapterAirContext, AdapterRuntimeContext, ExecutionError, MinimalInstruction, Result,
+    VmAdapterInterface, VmCoreAir, VmCoreChip,
+};
+use openvm_circuit_primitives::{
+    bitwise_op_lookup::{BitwiseOperationLookupBus, BitwiseOperationLookupChip},
+    utils::not,
+};
+use openvm_circuit_primitives_derive::AlignedBorrow;
+use openvm_instructions::{instruction::Instruction, UsizeOpcode};
+use openvm_rv32im_transpiler::{Rv32AtomicOpcode, Rv32MinMaxOpcode};
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    p3_air::{AirBuilder, BaseAir},
+    p3_field::{AbstractField, Field, PrimeField32},
+    rap::BaseAirWithPublicValues,
+};
+use strum::IntoEnumIterator;
+
+use crate::{
+    adapters::{compose, RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS},
+    run_min_max,
+};
+
+#[repr(C)]
+#[derive(AlignedBorrow)]
+pub struct Rv32AtomicCoreCols<T> {
+    /// rs2
+    pub src: [T; RV32_REGISTER_NUM_LIMBS],
+    /// Word in memory before the instruction
+    pub old: [T; RV32_REGISTER_NUM_LIMBS],
+    /// Word in memory after the instruction
+    pub new: [T; RV32_REGISTER_NUM_LIMBS],
+    /// `src ^ old`, for the bitwise operations
+    pub src_xor_old: [T; RV32_REGISTER_NUM_LIMBS],
+
+    /// Whether old < src, as signed integers for AMOMIN_W and AMOMAX_W and as unsigned integers
+    /// for the other opcodes
+    pub cmp_result: T,
+    /// Whether AMOMIN(U)_W or AMOMAX(U)_W store src rather than old
+    pub select_src: T,
+    // Most significant limb of old and src respectively as a field element, as in the MIN/MAX
+    // chip.
+    pub old_msb_f: T,
+    pub src_msb_f: T,
+    // 1 at the most significant index i such that old[i] != src[i], otherwise 0. If such an i
+    // exists, diff_val is the absolute difference of old[i] and src[i].
+    pub diff_marker: [T; RV32_REGISTER_NUM_LIMBS],
+    pub diff_val: T,
+
+    pub opcode_lr_flag: T,
+    pub opcode_sc_flag: T,
+    pub opcode_swap_flag: T,
+    pub opcode_add_flag: T,
+    pub opcode_xor_flag: T,
+    pub opcode_and_flag: T,
+    pub opcode_or_flag: T,
+    pub opcode_min_flag: T,
+    pub opcode_max_flag: T,
+    pub opcode_minu_flag: T,
+    pub opcode_maxu_flag: T,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct Rv32AtomicCoreAir {
+    pub bus: BitwiseOperationLookupBus,
+    offset: usize,
+}
+
+impl<F: Field> BaseAir<F> for Rv32AtomicCoreAir {
+    fn width(&self) -> usize {
+        Rv32AtomicCoreCols::<F>::width()
+    }
+}
+impl<F: Field> BaseAirWithPublicValues<F> for Rv32AtomicCoreAir {}
+
+impl<AB, I> VmCoreAir<AB, I> for Rv32AtomicCoreAir
+where
+    AB: InteractionBuilder,
+    I: VmAdapterInterface<AB::Expr>,
+    I::Reads: From<[[AB::Expr; RV32_REGISTER_NUM_LIMBS]; 2]>,
+    I::Writes: From<[[AB::Expr; RV32_REGISTER_NUM_LIMBS]; 2]>,
+    I::ProcessedInstruction: From<MinimalInstruction<AB::Expr>>,
+{
+    fn eval(
+        &self,
+        builder: &mut AB,
+        local_core: &[AB::Var],
+        _from_pc: AB::Var,
+    ) -> AdapterAirContext<AB::Expr, I> {
+        let cols: &Rv32AtomicCoreCols<_> = local_core.borrow();
+        // In the order of Rv32AtomicOpcode
+        let flags = [
+            cols.opcode_lr_flag,
+            cols.opcode_sc_flag,
+            cols.opcode_swap_flag,
+            cols.opcode_add_flag,
+            cols.opcode_xor_flag,
+            cols.opcode_and_flag,
+            cols.opcode_or_flag,
+            cols.opcode_min_flag,
+            cols.opcode_max_flag,
+            cols.opcode_minu_flag,
+            cols.opcode_maxu_flag,
+        ];
+
+        let is_valid = flags.iter().fold(AB::Expr::ZERO, |acc, &flag| {
+            builder.assert_bool(flag);
+            acc + flag.into()
+        });
+        builder.assert_bool(is_valid.clone());
+        builder.assert_bool(cols.cmp_result);
+
+        let (src, old, new, xor) = (&cols.src, &cols.old, &cols.new, &cols.src_xor_old);
+
+        // The lookup range checks src and old, and constrains src_xor_old. As src + old is
+        // (src ^ old) + 2 * (src & old), we have
+        // - src & old = (src + old - (src ^ old)) / 2
+        // - src | old = (src + old + (src ^ old)) / 2
+        for (((&src, &old), &new), &xor) in src.iter().zip(old).zip(new).zip(xor) {
+            self.bus
+                .send_xor(src, old, xor)
+                .eval(builder, is_valid.clone());
+            builder.when(cols.opcode_lr_flag).assert_eq(new, old);
+            builder
+                .when(cols.opcode_sc_flag + cols.opcode_swap_flag)
+                .assert_eq(new, src);
+            builder.when(cols.opcode_xor_flag).assert_eq(new, xor);
+            builder
+                .when(cols.opcode_and_flag)
+                .assert_eq(new * AB::F::TWO, src + old - xor);
+            builder
+                .when(cols.opcode_or_flag)
+                .assert_eq(new * AB::F::TWO, src + old + xor);
+        }
+
+        // AMOADD_W, as in the ALU chip
+        let carry_divide = AB::F::from_canonical_u32(1 << RV32_CELL_BITS).inverse();
+        let mut carry_add: [AB::Expr; RV32_REGISTER_NUM_LIMBS] = array::from_fn(|_| AB::Expr::ZERO);
+        for i in 0..RV32_REGISTER_NUM_LIMBS {
+            carry_add[i] = AB::Expr::from(carry_divide)
+                * (old[i] + src[i] - new[i]
+                    + if i > 0 {
+                        carry_add[i - 1].clone()
+                    } else {
+                        AB::Expr::ZERO
+                    });
+            builder
+                .when(cols.opcode_add_flag)
+                .assert_bool(carry_add[i].clone());
+        }
+        for i in 0..RV32_REGISTER_NUM_LIMBS / 2 {
+            self.bus
+                .send_range(new[i * 2], new[i * 2 + 1])
+                .eval(builder, cols.opcode_add_flag);
+        }
+
+        // Comparison of old and src, as in the MIN/MAX chip. It is signed for AMOMIN_W and
+        // AMOMAX_W and unsigned otherwise, and its result is only used by the MIN/MAX opcodes.
+        let is_signed = cols.opcode_min_flag + cols.opcode_max_flag;
+        let is_min = cols.opcode_min_flag + cols.opcode_minu_flag;
+        let is_max = cols.opcode_max_flag + cols.opcode_maxu_flag;
+        let marker = &cols.diff_marker;
+        let mut prefix_sum = AB::Expr::ZERO;
+
+        let old_diff = old[RV32_REGISTER_NUM_LIMBS - 1] - cols.old_msb_f;
+        let src_diff = src[RV32_REGISTER_NUM_LIMBS - 1] - cols.src_msb_f;
+        builder.assert_zero(
+            old_diff.clone() * (AB::Expr::from_canonical_u32(1 << RV32_CELL_BITS) - old_diff),
+        );
+        builder.assert_zero(
+            src_diff.clone() * (AB::Expr::from_canonical_u32(1 << RV32_CELL_BITS) - src_diff),
+        );
+
+        for i in (0..RV32_REGISTER_NUM_LIMBS).rev() {
+            let diff = (if i == RV32_REGISTER_NUM_LIMBS - 1 {
+                cols.src_msb_f - cols.old_msb_f
+            } else {
+                src[i] - old[i]
+            }) * (AB::Expr::from_canonical_u8(2) * cols.cmp_result - AB::Expr::ONE);
+            prefix_sum += marker[i].into();
+            builder.assert_bool(marker[i]);
+            builder.assert_zero(not::<AB::Expr>(prefix_sum.clone()) * diff.clone());
+            builder.when(marker[i]).assert_eq(cols.diff_val, diff);
+        }
+
+        builder.assert_bool(prefix_sum.clone());
+        builder
+            .when(not::<AB::Expr>(prefix_sum.clone()))
+            .assert_zero(cols.cmp_result);
+
+        self.bus
+            .send_range(
+                cols.old_msb_f
+                    + AB::Expr::from_canonical_u32(1 << (RV32_CELL_BITS - 1)) * is_signed.clone(),
+                cols.src_msb_f
+                    + AB::Expr::from_canonical_u32(1 << (RV32_CELL_BITS - 1)) * is_signed,
+            )
+            .eval(builder, is_valid.clone());
+        self.bus
+            .send_range(cols.diff_val - AB::Expr::ONE, AB::F::ZERO)
+            .eval(builder, prefix_sum);
+
+        // MAX(U) stores src when old < src, and MIN(U) stores src otherwise.
+        builder.assert_eq(
+            cols.select_src,
+            is_max.clone() * cols.cmp_result + is_min.clone() * not::<AB::Expr>(cols.cmp_result),
+        );
+        for ((&src, &old), &new) in src.iter().zip(old).zip(new) {
+            builder
+                .when(is_min.clone() + is_max.clone())
+                .assert_eq(new, old + cols.select_src * (src - old));
+        }
+
+        // rd is the old word, except for SC_W which always succeeds
+        let rd = old.map(|x| not::<AB::Expr>(cols.opcode_sc_flag) * x);
+
+        let expected_opcode = flags.iter().zip(Rv32AtomicOpcode::iter()).fold(
+            AB::Expr::ZERO,
+            |acc, (flag, local_opcode)| {
+                acc + (*flag).into() * AB::Expr::from_canonical_u8(local_opcode as u8)
+            },
+        ) + AB::Expr::from_canonical_usize(self.offset);
+
+        AdapterAirContext {
+            to_pc: None,
+            reads: [src.map(Into::into), old.map(Into::into)].into(),
+            writes: [rd, new.map(Into::into)].into(),
+            instruction: MinimalInstruction {
+                is_valid,
+                opcode: expected_opcode,
+            }
+            .into(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Rv32AtomicCoreRecord<T> {
+    pub opcode: Rv32AtomicOpcode,
+    pub src: [T; RV32_REGISTER_NUM_LIMBS],
+    pub old: [T; RV32_REGISTER_NUM_LIMBS],
+    pub new: [T; RV32_REGISTER_NUM_LIMBS],
+    pub src_xor_old: [T; RV32_REGISTER_NUM_LIMBS],
+    pub cmp_result: T,
+    pub select_src: T,
+    pub old_msb_f: T,
+    pub src_msb_f: T,
+    pub diff_val: T,
+    pub diff_idx: usize,
+}
+
+#[derive(Debug)]
+pub struct Rv32AtomicCoreChip {
+    pub air: Rv32AtomicCoreAir,
+    pub bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<RV32_CELL_BITS>>,
+}
+
+impl Rv32AtomicCoreChip {
+    pub fn new(
+        bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<RV32_CELL_BITS>>,
+        offset: usize,
+    ) -> Self {
+        Self {
+            air: Rv32AtomicCoreAir {
+                bus: bitwise_lookup_chip.bus(),
+                offset,
+            },
+            bitwise_lookup_chip,
+        }
+    }
+}
+
+impl<F, I> VmCoreChip<F, I> for Rv32AtomicCoreChip
+where
+    F: PrimeField32,
+    I: VmAdapterInterface<F>,
+    I::Reads: Into<([[F; RV32_REGISTER_NUM_LIMBS]; 2], F)>,
+    I::Writes: From<[[F; RV32_REGISTER_NUM_LIMBS]; 2]>,
+{
+    type Record = Rv32AtomicCoreRecord<F>;
+    type Air = Rv32AtomicCoreAir;
+
+    #[allow(clippy::type_complexity)]
+    fn execute_instruction(
+        &self,
+        instruction: &Instruction<F>,
+        from_pc: u32,
+        reads: I::Reads,
+    ) -> Result<(AdapterRuntimeContext<F, I>, Self::Record)> {
+        let Instruction { opcode, .. } = instruction;
+        let local_opcode = Rv32AtomicOpcode::from_usize(opcode.local_opcode_idx(self.air.offset));
+
+        let ([src, old], address) = reads.into();
+        let address = address.as_canonical_u32();
+        if address % 4 != 0 {
+            return Err(ExecutionError::MisalignedMemoryAccess {
+                pc: from_pc,
+                address,
+                alignment: 4,
+            });
+        }
+
+        let (rd, new) = run_atomic(local_opcode, compose(src), compose(old));
+        let new = new.to_le_bytes().map(F::from_canonical_u8);
+
+        let src_u32 = src.map(|x| x.as_canonical_u32());
+        let old_u32 = old.map(|x| x.as_canonical_u32());
+        let src_xor_old = array::from_fn(|i| {
+            F::from_canonical_u32(self.bitwise_lookup_chip.request_xor(src_u32[i], old_u32[i]))
+        });
+        if local_opcode == Rv32AtomicOpcode::AMOADD_W {
+            for i in 0..RV32_REGISTER_NUM_LIMBS / 2 {
+                self.bitwise_lookup_chip.request_range(
+                    new[i * 2].as_canonical_u32(),
+                    new[i * 2 + 1].as_canonical_u32(),
+                );
+            }
+        }
+
+        // The comparison is unsigned unless the opcode is AMOMIN_W or AMOMAX_W
+        let (cmp_opcode, is_min_max) = match local_opcode {
+            Rv32AtomicOpcode::AMOMIN_W => (Rv32MinMaxOpcode::MIN, true),
+            Rv32AtomicOpcode::AMOMAX_W => (Rv32MinMaxOpcode::MAX, true),
+            Rv32AtomicOpcode::AMOMINU_W => (Rv32MinMaxOpcode::MINU, true),
+            Rv32AtomicOpcode::AMOMAXU_W => (Rv32MinMaxOpcode::MAXU, true),
+            _ => (Rv32MinMaxOpcode::MINU, false),
+        };
+        let is_signed = matches!(cmp_opcode, Rv32MinMaxOpcode::MIN | Rv32MinMaxOpcode::MAX);
+        let is_max = matches!(cmp_opcode, Rv32MinMaxOpcode::MAX | Rv32MinMaxOpcode::MAXU);
+        let (_, cmp_result, diff_idx, old_sign, src_sign) =
+            run_min_max(cmp_opcode, &old_u32, &src_u32);
+
+        // We range check (old_msb_f + 128) and (src_msb_f + 128) if signed,
+        // old_msb_f and src_msb_f if not
+        let msb_f_and_range = |x: &[u32; RV32_REGISTER_NUM_LIMBS], sign: bool| {
+            let msb = x[RV32_REGISTER_NUM_LIMBS - 1];
+            if sign {
+                (
+                    -F::from_canonical_u32((1 << RV32_CELL_BITS) - msb),
+                    msb - (1 << (RV32_CELL_BITS - 1)),
+                )
+            } else {
+                (
+                    F::from_canonical_u32(msb),
+                    msb + ((is_signed as u32) << (RV32_CELL_BITS - 1)),
+                )
+            }
+        };
+        let (old_msb_f, old_msb_range) = msb_f_and_range(&old_u32, old_sign);
+        let (src_msb_f, src_msb_range) = msb_f_and_range(&src_u32, src_sign);
+        self.bitwise_lookup_chip
+            .request_range(old_msb_range, src_msb_range);
+
+        let diff_val = if diff_idx == RV32_REGISTER_NUM_LIMBS {
+            0
+        } else if diff_idx == (RV32_REGISTER_NUM_LIMBS - 1) {
+            if cmp_result {
+                src_msb_f - old_msb_f
+            } else {
+                old_msb_f - src_msb_f
+            }
+            .as_canonical_u32()
+        } else if cmp_result {
+            src_u32[diff_idx] - old_u32[diff_idx]
+        } else {
+            old_u32[diff_idx] - src_u32[diff_idx]
+        };
+
+        if diff_idx != RV32_REGISTER_NUM_LIMBS {
+            self.bitwise_lookup_chip.request_range(diff_val - 1, 0);
+        }
+
+        let output =
+            AdapterRuntimeContext::without_pc([rd.to_le_bytes().map(F::from_canonical_u8), new]);
+        let record = Self::Record {
+            opcode: local_opcode,
+            src,
+            old,
+            new,
+            src_xor_old,
+            cmp_result: F::from_bool(cmp_result),
+            select_src: F::from_bool(is_min_max && cmp_result == is_max),
+            old_msb_f,
+            src_msb_f,
+            diff_val: F::from_canonical_u32(diff_val),
+            diff_idx,
+        };
+
+        Ok((output, record))
+    }
+
+    fn get_opcode_name(&self, opcode: usize) -> String {
+        format!(
+            "{:?}",
+            Rv32AtomicOpcode::from_usize(opcode - self.air.offset)
+        )
+    }
+
+    fn generate_trace_row(&self, row_slice: &mut [F], record: Self::Record) {
+        let row_slice: &mut Rv32AtomicCoreCols<_> = row_slice.borrow_mut();
+        row_slice.src = record.src;
+        row_slice.old = record.old;
+        row_slice.new = record.new;
+        row_slice.src_xor_old = record.src_xor_old;
+        row_slice.cmp_result = record.cmp_result;
+        row_slice.select_src = record.select_src;
+        row_slice.old_msb_f = record.old_msb_f;
+        row_slice.src_msb_f = record.src_msb_f;
+        row_slice.diff_marker = array::from_fn(|i| F::from_bool(i == record.diff_idx));
+        row_slice.diff_val = record.diff_val;
+        row_slice.opcode_lr_flag = F::from_bool(record.opcode == Rv32AtomicOpcode::LR_W);
+        row_slice.opcode_sc_flag = F::from_bool(record.opcode == Rv32AtomicOpcode::SC_W);
+        row_slice.opcode_swap_flag = F::from_bool(record.opcode == Rv32AtomicOpcode::AMOSWAP_W);
+        row_slice.opcode_add_flag = F::from_bool(record.opcode == Rv32AtomicOpcode::AMOADD_W);
+        row_slice.opcode_xor_flag = F::from_bool(record.opcode == Rv32AtomicOpcode::AMOXOR_W);
+        row_slice.opcode_and_flag = F::from_bool(record.opcode == Rv32AtomicOpcode::AMOAND_W);
+        row_slice.opcode_or_flag = F::from_bool(record.opcode == Rv32AtomicOpcode::AMOOR_W);
+        row_slice.opcode_min_flag = F::from_bool(record.opcode == Rv32AtomicOpcode::AMOMIN_W);
+        row_slice.opcode_max_flag = F::from_bool(record.opcode == Rv32AtomicOpcode::AMOMAX_W);
+        row_slice.opcode_minu_flag = F::from_bool(record.opcode == Rv32AtomicOpcode::AMOMINU_W);
+        row_slice.opcode_maxu_flag = F::from_bool(record.opcode == Rv32AtomicOpcode::AMOMAXU_W);
+    }
+
+    fn air(&self) -> &Self::Air {
+        &self.air
+    }
+}
+
+/// Returns the value written to rd and the new word in memory, given the source and the old word.
+/// With a single hart, the reservation of `LR_W` is never lost, so `SC_W` always succeeds.
+pub fn run_atomic(opcode: Rv32AtomicOpcode, src: u32, old: u32) -> (u32, u32) {
+    let new = match opcode {
+        Rv32AtomicOpcode::LR_W => old,
+        Rv32AtomicOpcode::SC_W | Rv32AtomicOpcode::AMOSWAP_W => src,
+        Rv32AtomicOpcode::AMOADD_W => old.wrapping_add(src),
+        Rv32AtomicOpcode::AMOXOR_W => old ^ src,
+        Rv32AtomicOpcode::AMOAND_W => old & src,
+        Rv32AtomicOpcode::AMOOR_W => old | src,
+        Rv32AtomicOpcode::AMOMIN_W => (old as i32).min(src as i32) as u32,
+        Rv32AtomicOpcode::AMOMAX_W => (old as i32).max(src as i32) as u32,
+        Rv32AtomicOpcode::AMOMINU_W => old.min(src),
+        Rv32AtomicOpcode::AMOMAXU_W => old.max(src),
+    };
+    let rd = if opcode == Rv32AtomicOpcode::SC_W {
+        0
+    } else {
+        old
+    };
+    (rd, new)
+}
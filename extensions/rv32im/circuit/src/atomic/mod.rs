@@ -0,0 +1,11 @@
+use openvm_circuit::arch::VmChipWrapper;
+
+use crate::adapters::Rv32AtomicAdapterChip;
+
+mod core;
+pub use core::*;
+
+#[cfg(test)]
+mod tests;
+
+pub type Rv32AtomicChip<F> = VmChipWrapper<F, Rv32AtomicAdapterChip<F>, Rv32AtomicCoreChip>;
@@ -0,0 +1,177 @@
+use std::sync::Arc;
+
+use openvm_circuit::{
+    arch::{
+        testing::{memory::gen_pointer, VmChipTestBuilder},
+        ExecutionError, ExecutionState, InstructionExecutor, BITWISE_OP_LOOKUP_BUS,
+    },
+    utils::u32_into_limbs,
+};
+use openvm_circuit_primitives::bitwise_op_lookup::{
+    BitwiseOperationLookupBus, BitwiseOperationLookupChip,
+};
+use openvm_instructions::{instruction::Instruction, VmOpcode};
+use openvm_rv32im_transpiler::Rv32AtomicOpcode::{self, *};
+use openvm_stark_backend::p3_field::AbstractField;
+use openvm_stark_sdk::{p3_baby_bear::BabyBear, utils::create_seeded_rng};
+use rand::{rngs::StdRng, Rng};
+
+use super::{run_atomic, Rv32AtomicChip, Rv32AtomicCoreChip};
+use crate::adapters::{Rv32AtomicAdapterChip, RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS};
+
+type F = BabyBear;
+
+fn setup() -> (
+    VmChipTestBuilder<F>,
+    Rv32AtomicChip<F>,
+    Arc<BitwiseOperationLookupChip<RV32_CELL_BITS>>,
+) {
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = Arc::new(BitwiseOperationLookupChip::<RV32_CELL_BITS>::new(
+        bitwise_bus,
+    ));
+
+    let tester = VmChipTestBuilder::default();
+    let range_checker_chip = tester.memory_controller().borrow().range_checker.clone();
+    let chip = Rv32AtomicChip::<F>::new(
+        Rv32AtomicAdapterChip::new(
+            tester.execution_bus(),
+            tester.program_bus(),
+            tester.memory_controller(),
+            range_checker_chip,
+        ),
+        Rv32AtomicCoreChip::new(bitwise_chip.clone(), 0),
+        tester.memory_controller(),
+    );
+    (tester, chip, bitwise_chip)
+}
+
+fn to_limbs(x: u32) -> [F; RV32_REGISTER_NUM_LIMBS] {
+    u32_into_limbs::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(x).map(F::from_canonical_u32)
+}
+
+fn set_and_execute(
+    tester: &mut VmChipTestBuilder<F>,
+    chip: &mut Rv32AtomicChip<F>,
+    rng: &mut StdRng,
+    opcode: Rv32AtomicOpcode,
+    src: u32,
+    old: u32,
+) {
+    let needs_write = rng.gen_bool(0.8);
+    let ptr_val = rng.gen_range(0..1 << 10) * 4;
+    let rs1 = gen_pointer(rng, RV32_REGISTER_NUM_LIMBS);
+    let rs2 = loop {
+        let rs2 = gen_pointer(rng, RV32_REGISTER_NUM_LIMBS);
+        if rs2 != rs1 {
+            break rs2;
+        }
+    };
+    let rd = gen_pointer(rng, RV32_REGISTER_NUM_LIMBS);
+    // rd may alias rs1 or rs2, so it is written first
+    tester.write(1, rd, to_limbs(rng.gen()));
+    tester.write(1, rs1, to_limbs(ptr_val));
+    tester.write(1, rs2, to_limbs(src));
+    tester.write(2, ptr_val as usize, to_limbs(old));
+    let prev_rd = tester.read::<RV32_REGISTER_NUM_LIMBS>(1, rd);
+
+    tester.execute(
+        chip,
+        Instruction::from_usize(
+            VmOpcode::from_usize(opcode as usize),
+            [rd, rs1, rs2, 1, 2, needs_write as usize],
+        ),
+    );
+
+    let (expected_rd, expected_new) = run_atomic(opcode, src, old);
+    assert_eq!(
+        to_limbs(expected_new),
+        tester.read::<RV32_REGISTER_NUM_LIMBS>(2, ptr_val as usize)
+    );
+    assert_eq!(
+        if needs_write {
+            to_limbs(expected_rd)
+        } else {
+            prev_rd
+        },
+        tester.read::<RV32_REGISTER_NUM_LIMBS>(1, rd)
+    );
+}
+
+//////////////////////////////////////////////////////////////////////////////////////
+// POSITIVE TESTS
+//
+// Randomly generate computations and execute, ensuring that the generated trace
+// passes all constraints.
+//////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn rv32_atomic_rand_test() {
+    let mut rng = create_seeded_rng();
+    let (mut tester, mut chip, bitwise_chip) = setup();
+
+    for opcode in [
+        LR_W, SC_W, AMOSWAP_W, AMOADD_W, AMOXOR_W, AMOAND_W, AMOOR_W, AMOMIN_W, AMOMAX_W,
+        AMOMINU_W, AMOMAXU_W,
+    ] {
+        for _ in 0..20 {
+            let (src, old) = (rng.gen(), rng.gen());
+            set_and_execute(&mut tester, &mut chip, &mut rng, opcode, src, old);
+        }
+        // equal operands
+        let x = rng.gen();
+        set_and_execute(&mut tester, &mut chip, &mut rng, opcode, x, x);
+    }
+
+    let tester = tester.build().load(chip).load(bitwise_chip).finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
+//////////////////////////////////////////////////////////////////////////////////////
+// SANITY TESTS
+//
+// Ensure that the run_atomic function produces the expected values.
+//////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn run_atomic_sanity_test() {
+    let src = 0xffff_fff0; // -16
+    let old = 0x0000_0010; // 16
+    assert_eq!(run_atomic(LR_W, src, old), (old, old));
+    assert_eq!(run_atomic(SC_W, src, old), (0, src));
+    assert_eq!(run_atomic(AMOSWAP_W, src, old), (old, src));
+    assert_eq!(run_atomic(AMOADD_W, src, old), (old, 0));
+    assert_eq!(run_atomic(AMOXOR_W, src, old), (old, 0xffff_ffe0));
+    assert_eq!(run_atomic(AMOAND_W, src, old), (old, 0x10));
+    assert_eq!(run_atomic(AMOOR_W, src, old), (old, 0xffff_fff0));
+    assert_eq!(run_atomic(AMOMIN_W, src, old), (old, src));
+    assert_eq!(run_atomic(AMOMAX_W, src, old), (old, old));
+    assert_eq!(run_atomic(AMOMINU_W, src, old), (old, old));
+    assert_eq!(run_atomic(AMOMAXU_W, src, old), (old, src));
+}
+
+#[test]
+fn misaligned_atomic_test() {
+    let (mut tester, mut chip, _) = setup();
+
+    tester.write(1, 8, to_limbs(0x1002));
+    let from_state = ExecutionState {
+        pc: 4,
+        timestamp: tester.memory_controller().borrow().timestamp(),
+    };
+    let result = chip.execute(
+        Instruction::from_usize(
+            VmOpcode::from_usize(AMOADD_W as usize),
+            [16, 8, 12, 1, 2, 1],
+        ),
+        from_state,
+    );
+    assert!(matches!(
+        result,
+        Err(ExecutionError::MisalignedMemoryAccess {
+            pc: 4,
+            address: 0x1002,
+            alignment: 4,
+        })
+    ));
+}
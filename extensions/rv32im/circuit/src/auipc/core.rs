@@ -220,7 +220,7 @@ where
 }
 
 // returns rd_data
-pub(super) fn run_auipc(
+pub(crate) fn run_auipc(
     _opcode: Rv32AuipcOpcode,
     pc: u32,
     imm: u32,
@@ -258,7 +258,7 @@ where
     }
 }
 
-pub(super) fn run_alu<const NUM_LIMBS: usize, const LIMB_BITS: usize>(
+pub(crate) fn run_alu<const NUM_LIMBS: usize, const LIMB_BITS: usize>(
     opcode: BaseAluOpcode,
     x: &[u32; NUM_LIMBS],
     y: &[u32; NUM_LIMBS],
@@ -0,0 +1,224 @@
+use std::{
+    array,
+    borrow::{Borrow, BorrowMut},
+};
+
+use openvm_circuit::arch::{
+    AdapterAirContext, AdapterRuntimeContext, MinimalInstruction, Result, VmAdapterInterface,
+    VmCoreAir, VmCoreChip,
+};
+use openvm_circuit_primitives::utils::not;
+use openvm_circuit_primitives_derive::AlignedBorrow;
+use openvm_instructions::{instruction::Instruction, UsizeOpcode};
+use openvm_rv32im_transpiler::Rv32BitCountOpcode;
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    p3_air::{AirBuilder, BaseAir},
+    p3_field::{AbstractField, Field, PrimeField32},
+    rap::BaseAirWithPublicValues,
+};
+use strum::IntoEnumIterator;
+
+use crate::adapters::{RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS};
+
+const NUM_BITS: usize = RV32_REGISTER_NUM_LIMBS * RV32_CELL_BITS;
+
+#[repr(C)]
+#[derive(AlignedBorrow)]
+pub struct Rv32BitCountCoreCols<T> {
+    /// Bits of rs1, least significant first
+    pub bits: [T; NUM_BITS],
+    /// For CLZ (resp. CTZ), 1 at the indices of the run of zero bits starting from the
+    /// most (resp. least) significant bit, otherwise 0. Always 0 for CPOP.
+    pub zero_run: [T; NUM_BITS],
+
+    pub opcode_clz_flag: T,
+    pub opcode_ctz_flag: T,
+    pub opcode_cpop_flag: T,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct Rv32BitCountCoreAir {
+    offset: usize,
+}
+
+impl<F: Field> BaseAir<F> for Rv32BitCountCoreAir {
+    fn width(&self) -> usize {
+        Rv32BitCountCoreCols::<F>::width()
+    }
+}
+impl<F: Field> BaseAirWithPublicValues<F> for Rv32BitCountCoreAir {}
+
+impl<AB, I> VmCoreAir<AB, I> for Rv32BitCountCoreAir
+where
+    AB: InteractionBuilder,
+    I: VmAdapterInterface<AB::Expr>,
+    I::Reads: From<[[AB::Expr; RV32_REGISTER_NUM_LIMBS]; 2]>,
+    I::Writes: From<[[AB::Expr; RV32_REGISTER_NUM_LIMBS]; 1]>,
+    I::ProcessedInstruction: From<MinimalInstruction<AB::Expr>>,
+{
+    fn eval(
+        &self,
+        builder: &mut AB,
+        local_core: &[AB::Var],
+        _from_pc: AB::Var,
+    ) -> AdapterAirContext<AB::Expr, I> {
+        let cols: &Rv32BitCountCoreCols<_> = local_core.borrow();
+        let flags = [
+            cols.opcode_clz_flag,
+            cols.opcode_ctz_flag,
+            cols.opcode_cpop_flag,
+        ];
+
+        let is_valid = flags.iter().fold(AB::Expr::ZERO, |acc, &flag| {
+            builder.assert_bool(flag);
+            acc + flag.into()
+        });
+        builder.assert_bool(is_valid.clone());
+
+        let b: [AB::Expr; RV32_REGISTER_NUM_LIMBS] = array::from_fn(|i| {
+            (0..RV32_CELL_BITS).fold(AB::Expr::ZERO, |acc, j| {
+                let bit = cols.bits[i * RV32_CELL_BITS + j];
+                builder.assert_bool(bit);
+                acc + AB::Expr::from_canonical_u32(1 << j) * bit
+            })
+        });
+
+        // zero_run[i] is 1 iff bit i is 0 and so is its neighbour in the run, which is the
+        // next more significant bit for CLZ and the next less significant bit for CTZ. Out
+        // of range neighbours count as zero bits.
+        let mut num_ones = AB::Expr::ZERO;
+        let mut num_zeros = AB::Expr::ZERO;
+        for i in 0..NUM_BITS {
+            let next = if i == NUM_BITS - 1 {
+                AB::Expr::ONE
+            } else {
+                cols.zero_run[i + 1].into()
+            };
+            let prev = if i == 0 {
+                AB::Expr::ONE
+            } else {
+                cols.zero_run[i - 1].into()
+            };
+            builder.assert_eq(
+                cols.zero_run[i],
+                not::<AB::Expr>(cols.bits[i])
+                    * (cols.opcode_clz_flag * next + cols.opcode_ctz_flag * prev),
+            );
+            num_ones += cols.bits[i].into();
+            num_zeros += cols.zero_run[i].into();
+        }
+
+        let mut a: [AB::Expr; RV32_REGISTER_NUM_LIMBS] = array::from_fn(|_| AB::Expr::ZERO);
+        a[0] = cols.opcode_cpop_flag * num_ones + num_zeros;
+
+        let expected_opcode = flags.iter().zip(Rv32BitCountOpcode::iter()).fold(
+            AB::Expr::ZERO,
+            |acc, (flag, local_opcode)| {
+                acc + (*flag).into() * AB::Expr::from_canonical_u8(local_opcode as u8)
+            },
+        ) + AB::Expr::from_canonical_usize(self.offset);
+
+        AdapterAirContext {
+            to_pc: None,
+            reads: [b, array::from_fn(|_| AB::Expr::ZERO)].into(),
+            writes: [a].into(),
+            instruction: MinimalInstruction {
+                is_valid,
+                opcode: expected_opcode,
+            }
+            .into(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Rv32BitCountCoreRecord {
+    pub opcode: Rv32BitCountOpcode,
+    pub b: u32,
+    pub zero_run: u32,
+}
+
+#[derive(Debug)]
+pub struct Rv32BitCountCoreChip {
+    pub air: Rv32BitCountCoreAir,
+}
+
+impl Rv32BitCountCoreChip {
+    pub fn new(offset: usize) -> Self {
+        Self {
+            air: Rv32BitCountCoreAir { offset },
+        }
+    }
+}
+
+impl<F, I> VmCoreChip<F, I> for Rv32BitCountCoreChip
+where
+    F: PrimeField32,
+    I: VmAdapterInterface<F>,
+    I::Reads: Into<[[F; RV32_REGISTER_NUM_LIMBS]; 2]>,
+    I::Writes: From<[[F; RV32_REGISTER_NUM_LIMBS]; 1]>,
+{
+    type Record = Rv32BitCountCoreRecord;
+    type Air = Rv32BitCountCoreAir;
+
+    #[allow(clippy::type_complexity)]
+    fn execute_instruction(
+        &self,
+        instruction: &Instruction<F>,
+        _from_pc: u32,
+        reads: I::Reads,
+    ) -> Result<(AdapterRuntimeContext<F, I>, Self::Record)> {
+        let Instruction { opcode, .. } = instruction;
+        let local_opcode = Rv32BitCountOpcode::from_usize(opcode.local_opcode_idx(self.air.offset));
+
+        let data: [[F; RV32_REGISTER_NUM_LIMBS]; 2] = reads.into();
+        let b = u32::from_le_bytes(data[0].map(|x| x.as_canonical_u32() as u8));
+        let zero_run = match local_opcode {
+            Rv32BitCountOpcode::CLZ => (!0u32).checked_shl(NUM_BITS as u32 - b.leading_zeros()),
+            Rv32BitCountOpcode::CTZ => (!0u32).checked_shr(NUM_BITS as u32 - b.trailing_zeros()),
+            Rv32BitCountOpcode::CPOP => Some(0),
+        }
+        .unwrap_or(0);
+
+        let mut a = [0u32; RV32_REGISTER_NUM_LIMBS];
+        a[0] = run_bit_count(local_opcode, b);
+
+        let output = AdapterRuntimeContext::without_pc([a.map(F::from_canonical_u32)]);
+        let record = Self::Record {
+            opcode: local_opcode,
+            b,
+            zero_run,
+        };
+
+        Ok((output, record))
+    }
+
+    fn get_opcode_name(&self, opcode: usize) -> String {
+        format!(
+            "{:?}",
+            Rv32BitCountOpcode::from_usize(opcode - self.air.offset)
+        )
+    }
+
+    fn generate_trace_row(&self, row_slice: &mut [F], record: Self::Record) {
+        let row_slice: &mut Rv32BitCountCoreCols<_> = row_slice.borrow_mut();
+        row_slice.bits = array::from_fn(|i| F::from_bool((record.b >> i) & 1 == 1));
+        row_slice.zero_run = array::from_fn(|i| F::from_bool((record.zero_run >> i) & 1 == 1));
+        row_slice.opcode_clz_flag = F::from_bool(record.opcode == Rv32BitCountOpcode::CLZ);
+        row_slice.opcode_ctz_flag = F::from_bool(record.opcode == Rv32BitCountOpcode::CTZ);
+        row_slice.opcode_cpop_flag = F::from_bool(record.opcode == Rv32BitCountOpcode::CPOP);
+    }
+
+    fn air(&self) -> &Self::Air {
+        &self.air
+    }
+}
+
+pub fn run_bit_count(opcode: Rv32BitCountOpcode, b: u32) -> u32 {
+    match opcode {
+        Rv32BitCountOpcode::CLZ => b.leading_zeros(),
+        Rv32BitCountOpcode::CTZ => b.trailing_zeros(),
+        Rv32BitCountOpcode::CPOP => b.count_ones(),
+    }
+}
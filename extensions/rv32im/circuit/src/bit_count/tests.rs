@@ -0,0 +1,96 @@
+use std::sync::Arc;
+
+use openvm_circuit::{
+    arch::{testing::VmChipTestBuilder, BITWISE_OP_LOOKUP_BUS},
+    utils::generate_long_number,
+};
+use openvm_circuit_primitives::bitwise_op_lookup::{
+    BitwiseOperationLookupBus, BitwiseOperationLookupChip,
+};
+use openvm_rv32im_transpiler::Rv32BitCountOpcode;
+use openvm_stark_backend::p3_field::AbstractField;
+use openvm_stark_sdk::{p3_baby_bear::BabyBear, utils::create_seeded_rng};
+
+use super::{run_bit_count, Rv32BitCountChip, Rv32BitCountCoreChip};
+use crate::{
+    adapters::{Rv32BaseAluAdapterChip, RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS},
+    test_utils::rv32_rand_write_register_or_imm,
+};
+
+type F = BabyBear;
+
+//////////////////////////////////////////////////////////////////////////////////////
+// POSITIVE TESTS
+//
+// Randomly generate computations and execute, ensuring that the generated trace
+// passes all constraints.
+//////////////////////////////////////////////////////////////////////////////////////
+
+fn run_rv32_bit_count_rand_test(opcode: Rv32BitCountOpcode, num_ops: usize) {
+    let mut rng = create_seeded_rng();
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = Arc::new(BitwiseOperationLookupChip::<RV32_CELL_BITS>::new(
+        bitwise_bus,
+    ));
+
+    let mut tester = VmChipTestBuilder::default();
+    let mut chip = Rv32BitCountChip::<F>::new(
+        Rv32BaseAluAdapterChip::new(
+            tester.execution_bus(),
+            tester.program_bus(),
+            tester.memory_controller(),
+        ),
+        Rv32BitCountCoreChip::new(0),
+        tester.memory_controller(),
+    );
+
+    for _ in 0..num_ops {
+        let b = generate_long_number::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(&mut rng);
+        let (c_imm, c) = (Some(0), [0; RV32_REGISTER_NUM_LIMBS]);
+
+        let (instruction, rd) =
+            rv32_rand_write_register_or_imm(&mut tester, b, c, c_imm, opcode as usize, &mut rng);
+        tester.execute(&mut chip, instruction);
+
+        let mut a = [0; RV32_REGISTER_NUM_LIMBS];
+        a[0] = run_bit_count(opcode, u32::from_le_bytes(b.map(|x| x as u8)));
+        assert_eq!(
+            a.map(F::from_canonical_u32),
+            tester.read::<RV32_REGISTER_NUM_LIMBS>(1, rd)
+        )
+    }
+
+    let tester = tester.build().load(chip).load(bitwise_chip).finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
+#[test]
+fn rv32_bit_count_clz_rand_test() {
+    run_rv32_bit_count_rand_test(Rv32BitCountOpcode::CLZ, 100);
+}
+
+#[test]
+fn rv32_bit_count_ctz_rand_test() {
+    run_rv32_bit_count_rand_test(Rv32BitCountOpcode::CTZ, 100);
+}
+
+#[test]
+fn rv32_bit_count_cpop_rand_test() {
+    run_rv32_bit_count_rand_test(Rv32BitCountOpcode::CPOP, 100);
+}
+
+//////////////////////////////////////////////////////////////////////////////////////
+// SANITY TESTS
+//
+// Ensure that the run_* function produces the expected results.
+//////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn run_bit_count_sanity_test() {
+    let x = 0x0012_3400;
+    assert_eq!(run_bit_count(Rv32BitCountOpcode::CLZ, x), 11);
+    assert_eq!(run_bit_count(Rv32BitCountOpcode::CTZ, x), 10);
+    assert_eq!(run_bit_count(Rv32BitCountOpcode::CPOP, x), 5);
+    assert_eq!(run_bit_count(Rv32BitCountOpcode::CLZ, 0), 32);
+    assert_eq!(run_bit_count(Rv32BitCountOpcode::CTZ, 0), 32);
+}
@@ -219,7 +219,7 @@ where
 }
 
 // Returns (cmp_result, diff_idx, x[diff_idx] - y[diff_idx])
-pub(super) fn run_eq<F: PrimeField32, const NUM_LIMBS: usize>(
+pub(crate) fn run_eq<F: PrimeField32, const NUM_LIMBS: usize>(
     local_opcode: BranchEqualOpcode,
     x: &[u32; NUM_LIMBS],
     y: &[u32; NUM_LIMBS],
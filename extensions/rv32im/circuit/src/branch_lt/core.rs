@@ -340,7 +340,7 @@ where
 }
 
 // Returns (cmp_result, diff_idx, x_sign, y_sign)
-pub(super) fn run_cmp<const NUM_LIMBS: usize, const LIMB_BITS: usize>(
+pub(crate) fn run_cmp<const NUM_LIMBS: usize, const LIMB_BITS: usize>(
     local_opcode: BranchLessThanOpcode,
     x: &[u32; NUM_LIMBS],
     y: &[u32; NUM_LIMBS],
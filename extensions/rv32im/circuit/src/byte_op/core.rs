@@ -0,0 +1,261 @@
+use std::{
+    array,
+    borrow::{Borrow, BorrowMut},
+    sync::Arc,
+};
+
+use openvm_circuit::arch::{
+    AdapterAirContext, AdapterRuntimeContext, MinimalInstruction, Result, VmAdapterInterface,
+    VmCoreAir, VmCoreChip,
+};
+use openvm_circuit_primitives::{
+    bitwise_op_lookup::{BitwiseOperationLookupBus, BitwiseOperationLookupChip},
+    utils::not,
+};
+use openvm_circuit_primitives_derive::AlignedBorrow;
+use openvm_instructions::{instruction::Instruction, UsizeOpcode};
+use openvm_rv32im_transpiler::Rv32ByteOpOpcode;
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    p3_air::{AirBuilder, BaseAir},
+    p3_field::{AbstractField, Field, PrimeField32},
+    rap::BaseAirWithPublicValues,
+};
+use strum::IntoEnumIterator;
+
+use crate::adapters::{RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS};
+
+#[repr(C)]
+#[derive(AlignedBorrow)]
+pub struct Rv32ByteOpCoreCols<T> {
+    pub b: [T; RV32_REGISTER_NUM_LIMBS],
+
+    pub opcode_sext_b_flag: T,
+    pub opcode_sext_h_flag: T,
+    pub opcode_zext_h_flag: T,
+    pub opcode_rev8_flag: T,
+    pub opcode_orc_b_flag: T,
+
+    /// Sign bit of the byte or halfword being sign extended, 0 for other opcodes
+    pub sign: T,
+    /// Whether b[i] is nonzero, with b_inv[i] the inverse of b[i] when it is
+    pub nonzero: [T; RV32_REGISTER_NUM_LIMBS],
+    pub b_inv: [T; RV32_REGISTER_NUM_LIMBS],
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct Rv32ByteOpCoreAir {
+    pub bus: BitwiseOperationLookupBus,
+    offset: usize,
+}
+
+impl<F: Field> BaseAir<F> for Rv32ByteOpCoreAir {
+    fn width(&self) -> usize {
+        Rv32ByteOpCoreCols::<F>::width()
+    }
+}
+impl<F: Field> BaseAirWithPublicValues<F> for Rv32ByteOpCoreAir {}
+
+impl<AB, I> VmCoreAir<AB, I> for Rv32ByteOpCoreAir
+where
+    AB: InteractionBuilder,
+    I: VmAdapterInterface<AB::Expr>,
+    I::Reads: From<[[AB::Expr; RV32_REGISTER_NUM_LIMBS]; 2]>,
+    I::Writes: From<[[AB::Expr; RV32_REGISTER_NUM_LIMBS]; 1]>,
+    I::ProcessedInstruction: From<MinimalInstruction<AB::Expr>>,
+{
+    fn eval(
+        &self,
+        builder: &mut AB,
+        local_core: &[AB::Var],
+        _from_pc: AB::Var,
+    ) -> AdapterAirContext<AB::Expr, I> {
+        let cols: &Rv32ByteOpCoreCols<_> = local_core.borrow();
+        let flags = [
+            cols.opcode_sext_b_flag,
+            cols.opcode_sext_h_flag,
+            cols.opcode_zext_h_flag,
+            cols.opcode_rev8_flag,
+            cols.opcode_orc_b_flag,
+        ];
+
+        let is_valid = flags.iter().fold(AB::Expr::ZERO, |acc, &flag| {
+            builder.assert_bool(flag);
+            acc + flag.into()
+        });
+        builder.assert_bool(is_valid.clone());
+
+        let b = &cols.b;
+        let max = AB::Expr::from_canonical_u32((1 << RV32_CELL_BITS) - 1);
+
+        // The sign bit is the top bit of the most significant extended limb, i.e. the limb
+        // minus 2^(LIMB_BITS - 1) * sign is in [0, 2^(LIMB_BITS - 1)).
+        let is_sext = cols.opcode_sext_b_flag + cols.opcode_sext_h_flag;
+        builder.assert_bool(cols.sign);
+        builder
+            .when(not::<AB::Expr>(is_sext.clone()))
+            .assert_zero(cols.sign);
+        let msb = cols.opcode_sext_b_flag * b[0] + cols.opcode_sext_h_flag * b[1];
+        self.bus
+            .send_range(
+                (msb - AB::Expr::from_canonical_u32(1 << (RV32_CELL_BITS - 1)) * cols.sign)
+                    * AB::F::TWO,
+                AB::F::ZERO,
+            )
+            .eval(builder, is_sext.clone());
+        let sign_limb = cols.sign * max.clone();
+
+        for i in 0..RV32_REGISTER_NUM_LIMBS {
+            builder.assert_bool(cols.nonzero[i]);
+            builder.assert_eq(b[i] * cols.b_inv[i], cols.nonzero[i]);
+            builder.assert_zero(b[i] * not::<AB::Expr>(cols.nonzero[i]));
+        }
+
+        let a: [AB::Expr; RV32_REGISTER_NUM_LIMBS] = array::from_fn(|i| {
+            let extended = match i {
+                0 => (is_sext.clone() + cols.opcode_zext_h_flag) * b[0],
+                1 => {
+                    cols.opcode_sext_b_flag * sign_limb.clone()
+                        + (cols.opcode_sext_h_flag + cols.opcode_zext_h_flag) * b[1]
+                }
+                _ => is_sext.clone() * sign_limb.clone(),
+            };
+            extended
+                + cols.opcode_rev8_flag * b[RV32_REGISTER_NUM_LIMBS - 1 - i]
+                + cols.opcode_orc_b_flag * cols.nonzero[i] * max.clone()
+        });
+
+        let expected_opcode = flags.iter().zip(Rv32ByteOpOpcode::iter()).fold(
+            AB::Expr::ZERO,
+            |acc, (flag, local_opcode)| {
+                acc + (*flag).into() * AB::Expr::from_canonical_u8(local_opcode as u8)
+            },
+        ) + AB::Expr::from_canonical_usize(self.offset);
+
+        AdapterAirContext {
+            to_pc: None,
+            reads: [cols.b.map(Into::into), array::from_fn(|_| AB::Expr::ZERO)].into(),
+            writes: [a].into(),
+            instruction: MinimalInstruction {
+                is_valid,
+                opcode: expected_opcode,
+            }
+            .into(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Rv32ByteOpCoreRecord<T> {
+    pub opcode: Rv32ByteOpOpcode,
+    pub b: [T; RV32_REGISTER_NUM_LIMBS],
+    pub sign: T,
+}
+
+#[derive(Debug)]
+pub struct Rv32ByteOpCoreChip {
+    pub air: Rv32ByteOpCoreAir,
+    pub bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<RV32_CELL_BITS>>,
+}
+
+impl Rv32ByteOpCoreChip {
+    pub fn new(
+        bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<RV32_CELL_BITS>>,
+        offset: usize,
+    ) -> Self {
+        Self {
+            air: Rv32ByteOpCoreAir {
+                bus: bitwise_lookup_chip.bus(),
+                offset,
+            },
+            bitwise_lookup_chip,
+        }
+    }
+}
+
+impl<F, I> VmCoreChip<F, I> for Rv32ByteOpCoreChip
+where
+    F: PrimeField32,
+    I: VmAdapterInterface<F>,
+    I::Reads: Into<[[F; RV32_REGISTER_NUM_LIMBS]; 2]>,
+    I::Writes: From<[[F; RV32_REGISTER_NUM_LIMBS]; 1]>,
+{
+    type Record = Rv32ByteOpCoreRecord<F>;
+    type Air = Rv32ByteOpCoreAir;
+
+    #[allow(clippy::type_complexity)]
+    fn execute_instruction(
+        &self,
+        instruction: &Instruction<F>,
+        _from_pc: u32,
+        reads: I::Reads,
+    ) -> Result<(AdapterRuntimeContext<F, I>, Self::Record)> {
+        let Instruction { opcode, .. } = instruction;
+        let local_opcode = Rv32ByteOpOpcode::from_usize(opcode.local_opcode_idx(self.air.offset));
+
+        let data: [[F; RV32_REGISTER_NUM_LIMBS]; 2] = reads.into();
+        let b = data[0].map(|x| x.as_canonical_u32());
+        let a = run_byte_op(local_opcode, &b);
+
+        let msb = match local_opcode {
+            Rv32ByteOpOpcode::SEXT_B => Some(b[0]),
+            Rv32ByteOpOpcode::SEXT_H => Some(b[1]),
+            _ => None,
+        };
+        let sign = msb.map_or(0, |msb| msb >> (RV32_CELL_BITS - 1));
+        if let Some(msb) = msb {
+            self.bitwise_lookup_chip
+                .request_range((msb - (sign << (RV32_CELL_BITS - 1))) << 1, 0);
+        }
+
+        let output = AdapterRuntimeContext::without_pc([a.map(F::from_canonical_u32)]);
+        let record = Self::Record {
+            opcode: local_opcode,
+            b: data[0],
+            sign: F::from_canonical_u32(sign),
+        };
+
+        Ok((output, record))
+    }
+
+    fn get_opcode_name(&self, opcode: usize) -> String {
+        format!(
+            "{:?}",
+            Rv32ByteOpOpcode::from_usize(opcode - self.air.offset)
+        )
+    }
+
+    fn generate_trace_row(&self, row_slice: &mut [F], record: Self::Record) {
+        let row_slice: &mut Rv32ByteOpCoreCols<_> = row_slice.borrow_mut();
+        row_slice.b = record.b;
+        row_slice.sign = record.sign;
+        row_slice.nonzero = record.b.map(|x| F::from_bool(!x.is_zero()));
+        row_slice.b_inv = record.b.map(|x| x.try_inverse().unwrap_or(F::ZERO));
+        row_slice.opcode_sext_b_flag = F::from_bool(record.opcode == Rv32ByteOpOpcode::SEXT_B);
+        row_slice.opcode_sext_h_flag = F::from_bool(record.opcode == Rv32ByteOpOpcode::SEXT_H);
+        row_slice.opcode_zext_h_flag = F::from_bool(record.opcode == Rv32ByteOpOpcode::ZEXT_H);
+        row_slice.opcode_rev8_flag = F::from_bool(record.opcode == Rv32ByteOpOpcode::REV8);
+        row_slice.opcode_orc_b_flag = F::from_bool(record.opcode == Rv32ByteOpOpcode::ORC_B);
+    }
+
+    fn air(&self) -> &Self::Air {
+        &self.air
+    }
+}
+
+pub fn run_byte_op(
+    opcode: Rv32ByteOpOpcode,
+    b: &[u32; RV32_REGISTER_NUM_LIMBS],
+) -> [u32; RV32_REGISTER_NUM_LIMBS] {
+    let x = u32::from_le_bytes(b.map(|limb| limb as u8));
+    let a = match opcode {
+        Rv32ByteOpOpcode::SEXT_B => x as i8 as u32,
+        Rv32ByteOpOpcode::SEXT_H => x as i16 as u32,
+        Rv32ByteOpOpcode::ZEXT_H => x as u16 as u32,
+        Rv32ByteOpOpcode::REV8 => x.swap_bytes(),
+        Rv32ByteOpOpcode::ORC_B => {
+            u32::from_le_bytes(b.map(|limb| if limb == 0 { 0 } else { 0xff }))
+        }
+    };
+    a.to_le_bytes().map(u32::from)
+}
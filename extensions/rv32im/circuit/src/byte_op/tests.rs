@@ -0,0 +1,120 @@
+use std::sync::Arc;
+
+use openvm_circuit::{
+    arch::{testing::VmChipTestBuilder, BITWISE_OP_LOOKUP_BUS},
+    utils::generate_long_number,
+};
+use openvm_circuit_primitives::bitwise_op_lookup::{
+    BitwiseOperationLookupBus, BitwiseOperationLookupChip,
+};
+use openvm_rv32im_transpiler::Rv32ByteOpOpcode;
+use openvm_stark_backend::p3_field::AbstractField;
+use openvm_stark_sdk::{p3_baby_bear::BabyBear, utils::create_seeded_rng};
+
+use super::{run_byte_op, Rv32ByteOpChip, Rv32ByteOpCoreChip};
+use crate::{
+    adapters::{Rv32BaseAluAdapterChip, RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS},
+    test_utils::rv32_rand_write_register_or_imm,
+};
+
+type F = BabyBear;
+
+//////////////////////////////////////////////////////////////////////////////////////
+// POSITIVE TESTS
+//
+// Randomly generate computations and execute, ensuring that the generated trace
+// passes all constraints.
+//////////////////////////////////////////////////////////////////////////////////////
+
+fn run_rv32_byte_op_rand_test(opcode: Rv32ByteOpOpcode, num_ops: usize) {
+    let mut rng = create_seeded_rng();
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = Arc::new(BitwiseOperationLookupChip::<RV32_CELL_BITS>::new(
+        bitwise_bus,
+    ));
+
+    let mut tester = VmChipTestBuilder::default();
+    let mut chip = Rv32ByteOpChip::<F>::new(
+        Rv32BaseAluAdapterChip::new(
+            tester.execution_bus(),
+            tester.program_bus(),
+            tester.memory_controller(),
+        ),
+        Rv32ByteOpCoreChip::new(bitwise_chip.clone(), 0),
+        tester.memory_controller(),
+    );
+
+    for _ in 0..num_ops {
+        let b = generate_long_number::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(&mut rng);
+        let (c_imm, c) = (Some(0), [0; RV32_REGISTER_NUM_LIMBS]);
+
+        let (instruction, rd) =
+            rv32_rand_write_register_or_imm(&mut tester, b, c, c_imm, opcode as usize, &mut rng);
+        tester.execute(&mut chip, instruction);
+
+        let a = run_byte_op(opcode, &b);
+        assert_eq!(
+            a.map(F::from_canonical_u32),
+            tester.read::<RV32_REGISTER_NUM_LIMBS>(1, rd)
+        )
+    }
+
+    let tester = tester.build().load(chip).load(bitwise_chip).finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
+#[test]
+fn rv32_byte_op_sext_b_rand_test() {
+    run_rv32_byte_op_rand_test(Rv32ByteOpOpcode::SEXT_B, 100);
+}
+
+#[test]
+fn rv32_byte_op_sext_h_rand_test() {
+    run_rv32_byte_op_rand_test(Rv32ByteOpOpcode::SEXT_H, 100);
+}
+
+#[test]
+fn rv32_byte_op_zext_h_rand_test() {
+    run_rv32_byte_op_rand_test(Rv32ByteOpOpcode::ZEXT_H, 100);
+}
+
+#[test]
+fn rv32_byte_op_rev8_rand_test() {
+    run_rv32_byte_op_rand_test(Rv32ByteOpOpcode::REV8, 100);
+}
+
+#[test]
+fn rv32_byte_op_orc_b_rand_test() {
+    run_rv32_byte_op_rand_test(Rv32ByteOpOpcode::ORC_B, 100);
+}
+
+//////////////////////////////////////////////////////////////////////////////////////
+// SANITY TESTS
+//
+// Ensure that the run_* function produces the expected results.
+//////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn run_byte_op_sanity_test() {
+    let x: [u32; RV32_REGISTER_NUM_LIMBS] = [0x80, 0x7f, 0, 0x12];
+    assert_eq!(
+        run_byte_op(Rv32ByteOpOpcode::SEXT_B, &x),
+        [0x80, 0xff, 0xff, 0xff]
+    );
+    assert_eq!(
+        run_byte_op(Rv32ByteOpOpcode::SEXT_H, &x),
+        [0x80, 0x7f, 0, 0]
+    );
+    assert_eq!(
+        run_byte_op(Rv32ByteOpOpcode::ZEXT_H, &x),
+        [0x80, 0x7f, 0, 0]
+    );
+    assert_eq!(
+        run_byte_op(Rv32ByteOpOpcode::REV8, &x),
+        [0x12, 0, 0x7f, 0x80]
+    );
+    assert_eq!(
+        run_byte_op(Rv32ByteOpOpcode::ORC_B, &x),
+        [0xff, 0xff, 0, 0xff]
+    );
+}
@@ -0,0 +1,218 @@
+use std::{
+    array,
+    borrow::{Borrow, BorrowMut},
+    sync::Arc,
+};
+
+use openvm_circuit::arch::{
+    AdapterAirContext, AdapterRuntimeContext, MinimalInstruction, Result, VmAdapterInterface,
+    VmCoreAir, VmCoreChip,
+};
+use openvm_circuit_primitives::bitwise_op_lookup::{
+    BitwiseOperationLookupBus, BitwiseOperationLookupChip,
+};
+use openvm_circuit_primitives_derive::AlignedBorrow;
+use openvm_instructions::{instruction::Instruction, UsizeOpcode};
+use openvm_rv32im_transpiler::Rv32CsrOpcode;
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    p3_air::{AirBuilder, BaseAir},
+    p3_field::{AbstractField, Field, PrimeField32},
+    rap::BaseAirWithPublicValues,
+};
+use strum::IntoEnumIterator;
+
+use crate::adapters::{RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS};
+
+#[repr(C)]
+#[derive(AlignedBorrow)]
+pub struct Rv32CsrCoreCols<T> {
+    /// rs1 or the immediate
+    pub src: [T; RV32_REGISTER_NUM_LIMBS],
+    /// Value of the CSR before the instruction, which is written to rd
+    pub old: [T; RV32_REGISTER_NUM_LIMBS],
+    /// `src ^ old`, from which the new value of the CSR is computed
+    pub src_xor_old: [T; RV32_REGISTER_NUM_LIMBS],
+
+    pub opcode_csrrw_flag: T,
+    pub opcode_csrrs_flag: T,
+    pub opcode_csrrc_flag: T,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct Rv32CsrCoreAir {
+    pub bus: BitwiseOperationLookupBus,
+    offset: usize,
+}
+
+impl<F: Field> BaseAir<F> for Rv32CsrCoreAir {
+    fn width(&self) -> usize {
+        Rv32CsrCoreCols::<F>::width()
+    }
+}
+impl<F: Field> BaseAirWithPublicValues<F> for Rv32CsrCoreAir {}
+
+impl<AB, I> VmCoreAir<AB, I> for Rv32CsrCoreAir
+where
+    AB: InteractionBuilder,
+    I: VmAdapterInterface<AB::Expr>,
+    I::Reads: From<[[AB::Expr; RV32_REGISTER_NUM_LIMBS]; 2]>,
+    I::Writes: From<[[AB::Expr; RV32_REGISTER_NUM_LIMBS]; 2]>,
+    I::ProcessedInstruction: From<MinimalInstruction<AB::Expr>>,
+{
+    fn eval(
+        &self,
+        builder: &mut AB,
+        local_core: &[AB::Var],
+        _from_pc: AB::Var,
+    ) -> AdapterAirContext<AB::Expr, I> {
+        let cols: &Rv32CsrCoreCols<_> = local_core.borrow();
+        let flags = [
+            cols.opcode_csrrw_flag,
+            cols.opcode_csrrs_flag,
+            cols.opcode_csrrc_flag,
+        ];
+
+        let is_valid = flags.iter().fold(AB::Expr::ZERO, |acc, &flag| {
+            builder.assert_bool(flag);
+            acc + flag.into()
+        });
+        builder.assert_bool(is_valid.clone());
+
+        // The lookup range checks src and old, and constrains src_xor_old. As src + old is
+        // (src ^ old) + 2 * (src & old), we have
+        // - src | old = (src + old + (src ^ old)) / 2
+        // - old & !src = old - (src & old) = (old - src + (src ^ old)) / 2
+        let half = AB::F::from_canonical_u32(2).inverse();
+        let new: [AB::Expr; RV32_REGISTER_NUM_LIMBS] = array::from_fn(|i| {
+            let (src, old, xor) = (cols.src[i], cols.old[i], cols.src_xor_old[i]);
+            self.bus
+                .send_xor(src, old, xor)
+                .eval(builder, is_valid.clone());
+            cols.opcode_csrrw_flag * src
+                + cols.opcode_csrrs_flag * (src + old + xor) * half
+                + cols.opcode_csrrc_flag * (old + xor - src) * half
+        });
+
+        let expected_opcode = flags.iter().zip(Rv32CsrOpcode::iter()).fold(
+            AB::Expr::ZERO,
+            |acc, (flag, local_opcode)| {
+                acc + (*flag).into() * AB::Expr::from_canonical_u8(local_opcode as u8)
+            },
+        ) + AB::Expr::from_canonical_usize(self.offset);
+
+        AdapterAirContext {
+            to_pc: None,
+            reads: [cols.src.map(Into::into), cols.old.map(Into::into)].into(),
+            writes: [cols.old.map(Into::into), new].into(),
+            instruction: MinimalInstruction {
+                is_valid,
+                opcode: expected_opcode,
+            }
+            .into(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Rv32CsrCoreRecord<T> {
+    pub opcode: Rv32CsrOpcode,
+    pub src: [T; RV32_REGISTER_NUM_LIMBS],
+    pub old: [T; RV32_REGISTER_NUM_LIMBS],
+    pub src_xor_old: [T; RV32_REGISTER_NUM_LIMBS],
+}
+
+#[derive(Debug)]
+pub struct Rv32CsrCoreChip {
+    pub air: Rv32CsrCoreAir,
+    pub bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<RV32_CELL_BITS>>,
+}
+
+impl Rv32CsrCoreChip {
+    pub fn new(
+        bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<RV32_CELL_BITS>>,
+        offset: usize,
+    ) -> Self {
+        Self {
+            air: Rv32CsrCoreAir {
+                bus: bitwise_lookup_chip.bus(),
+                offset,
+            },
+            bitwise_lookup_chip,
+        }
+    }
+}
+
+impl<F, I> VmCoreChip<F, I> for Rv32CsrCoreChip
+where
+    F: PrimeField32,
+    I: VmAdapterInterface<F>,
+    I::Reads: Into<[[F; RV32_REGISTER_NUM_LIMBS]; 2]>,
+    I::Writes: From<[[F; RV32_REGISTER_NUM_LIMBS]; 2]>,
+{
+    type Record = Rv32CsrCoreRecord<F>;
+    type Air = Rv32CsrCoreAir;
+
+    #[allow(clippy::type_complexity)]
+    fn execute_instruction(
+        &self,
+        instruction: &Instruction<F>,
+        _from_pc: u32,
+        reads: I::Reads,
+    ) -> Result<(AdapterRuntimeContext<F, I>, Self::Record)> {
+        let Instruction { opcode, .. } = instruction;
+        let local_opcode = Rv32CsrOpcode::from_usize(opcode.local_opcode_idx(self.air.offset));
+
+        let [src, old]: [[F; RV32_REGISTER_NUM_LIMBS]; 2] = reads.into();
+        let src_u32 = src.map(|x| x.as_canonical_u32());
+        let old_u32 = old.map(|x| x.as_canonical_u32());
+        let new = run_csr(local_opcode, &src_u32, &old_u32);
+        let src_xor_old = array::from_fn(|i| {
+            F::from_canonical_u32(self.bitwise_lookup_chip.request_xor(src_u32[i], old_u32[i]))
+        });
+
+        let output = AdapterRuntimeContext {
+            to_pc: None,
+            writes: [old, new.map(F::from_canonical_u32)].into(),
+        };
+        let record = Self::Record {
+            opcode: local_opcode,
+            src,
+            old,
+            src_xor_old,
+        };
+
+        Ok((output, record))
+    }
+
+    fn get_opcode_name(&self, opcode: usize) -> String {
+        format!("{:?}", Rv32CsrOpcode::from_usize(opcode - self.air.offset))
+    }
+
+    fn generate_trace_row(&self, row_slice: &mut [F], record: Self::Record) {
+        let row_slice: &mut Rv32CsrCoreCols<_> = row_slice.borrow_mut();
+        row_slice.src = record.src;
+        row_slice.old = record.old;
+        row_slice.src_xor_old = record.src_xor_old;
+        row_slice.opcode_csrrw_flag = F::from_bool(record.opcode == Rv32CsrOpcode::CSRRW);
+        row_slice.opcode_csrrs_flag = F::from_bool(record.opcode == Rv32CsrOpcode::CSRRS);
+        row_slice.opcode_csrrc_flag = F::from_bool(record.opcode == Rv32CsrOpcode::CSRRC);
+    }
+
+    fn air(&self) -> &Self::Air {
+        &self.air
+    }
+}
+
+/// The new value of the CSR, limb by limb.
+pub fn run_csr(
+    opcode: Rv32CsrOpcode,
+    src: &[u32; RV32_REGISTER_NUM_LIMBS],
+    old: &[u32; RV32_REGISTER_NUM_LIMBS],
+) -> [u32; RV32_REGISTER_NUM_LIMBS] {
+    array::from_fn(|i| match opcode {
+        Rv32CsrOpcode::CSRRW => src[i],
+        Rv32CsrOpcode::CSRRS => old[i] | src[i],
+        Rv32CsrOpcode::CSRRC => old[i] & !src[i],
+    })
+}
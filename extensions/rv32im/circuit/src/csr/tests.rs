@@ -0,0 +1,188 @@
+use std::{borrow::BorrowMut, sync::Arc};
+
+use openvm_circuit::{
+    arch::{
+        testing::{memory::gen_pointer, VmChipTestBuilder},
+        VmAdapterChip, BITWISE_OP_LOOKUP_BUS,
+    },
+    utils::generate_long_number,
+};
+use openvm_circuit_primitives::bitwise_op_lookup::{
+    BitwiseOperationLookupBus, BitwiseOperationLookupChip,
+};
+use openvm_instructions::{instruction::Instruction, VmOpcode};
+use openvm_rv32im_transpiler::{Rv32CsrOpcode, RV32_CSR_PTR_START};
+use openvm_stark_backend::{
+    p3_air::BaseAir,
+    p3_field::AbstractField,
+    p3_matrix::{
+        dense::{DenseMatrix, RowMajorMatrix},
+        Matrix,
+    },
+    utils::disable_debug_builder,
+    verifier::VerificationError,
+    ChipUsageGetter,
+};
+use openvm_stark_sdk::{p3_baby_bear::BabyBear, utils::create_seeded_rng};
+use rand::Rng;
+
+use super::{run_csr, Rv32CsrChip, Rv32CsrCoreChip, Rv32CsrCoreCols};
+use crate::adapters::{Rv32CsrAdapterChip, RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS};
+
+type F = BabyBear;
+
+fn setup() -> (
+    VmChipTestBuilder<F>,
+    Rv32CsrChip<F>,
+    Arc<BitwiseOperationLookupChip<RV32_CELL_BITS>>,
+) {
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = Arc::new(BitwiseOperationLookupChip::<RV32_CELL_BITS>::new(
+        bitwise_bus,
+    ));
+
+    let tester = VmChipTestBuilder::default();
+    let chip = Rv32CsrChip::<F>::new(
+        Rv32CsrAdapterChip::new(
+            tester.execution_bus(),
+            tester.program_bus(),
+            tester.memory_controller(),
+        ),
+        Rv32CsrCoreChip::new(bitwise_chip.clone(), 0),
+        tester.memory_controller(),
+    );
+    (tester, chip, bitwise_chip)
+}
+
+//////////////////////////////////////////////////////////////////////////////////////
+// POSITIVE TESTS
+//
+// Randomly generate computations and execute, ensuring that the generated trace
+// passes all constraints.
+//////////////////////////////////////////////////////////////////////////////////////
+
+fn run_rv32_csr_rand_test(opcode: Rv32CsrOpcode, num_ops: usize) {
+    let mut rng = create_seeded_rng();
+    let (mut tester, mut chip, bitwise_chip) = setup();
+
+    let mut csr = generate_long_number::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(&mut rng);
+    tester.write(1, RV32_CSR_PTR_START, csr.map(F::from_canonical_u32));
+
+    for _ in 0..num_ops {
+        let rs1_is_imm = rng.gen_bool(0.5);
+        let needs_write = rng.gen_bool(0.5);
+        let rd = gen_pointer(&mut rng, RV32_REGISTER_NUM_LIMBS);
+        let (rs1, src) = if rs1_is_imm {
+            let imm = rng.gen_range(0..32);
+            (imm, [imm as u32, 0, 0, 0])
+        } else {
+            let rs1 = gen_pointer(&mut rng, RV32_REGISTER_NUM_LIMBS);
+            let src = generate_long_number::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(&mut rng);
+            tester.write(1, rs1, src.map(F::from_canonical_u32));
+            (rs1, src)
+        };
+
+        tester.execute(
+            &mut chip,
+            Instruction::from_usize(
+                VmOpcode::from_usize(opcode as usize),
+                [
+                    rd,
+                    RV32_CSR_PTR_START,
+                    rs1,
+                    1,
+                    !rs1_is_imm as usize,
+                    needs_write as usize,
+                ],
+            ),
+        );
+
+        if needs_write {
+            assert_eq!(
+                csr.map(F::from_canonical_u32),
+                tester.read::<RV32_REGISTER_NUM_LIMBS>(1, rd)
+            );
+        }
+        csr = run_csr(opcode, &src, &csr);
+        assert_eq!(
+            csr.map(F::from_canonical_u32),
+            tester.read::<RV32_REGISTER_NUM_LIMBS>(1, RV32_CSR_PTR_START)
+        );
+    }
+
+    let tester = tester.build().load(chip).load(bitwise_chip).finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
+#[test]
+fn rv32_csrrw_rand_test() {
+    run_rv32_csr_rand_test(Rv32CsrOpcode::CSRRW, 100);
+}
+
+#[test]
+fn rv32_csrrs_rand_test() {
+    run_rv32_csr_rand_test(Rv32CsrOpcode::CSRRS, 100);
+}
+
+#[test]
+fn rv32_csrrc_rand_test() {
+    run_rv32_csr_rand_test(Rv32CsrOpcode::CSRRC, 100);
+}
+
+//////////////////////////////////////////////////////////////////////////////////////
+// NEGATIVE TESTS
+//
+// Given a fake trace of a single operation, setup a chip and run the test. We replace
+// the xor of the inputs, from which the new CSR value is computed, and check that the
+// bitwise lookup fails.
+//////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn rv32_csrrs_wrong_xor_negative_test() {
+    let (mut tester, mut chip, bitwise_chip) = setup();
+    tester.write(
+        1,
+        RV32_CSR_PTR_START,
+        [0b1100, 0, 0, 0].map(F::from_canonical_u32),
+    );
+    tester.write(1, 0, [0b1010, 0, 0, 0].map(F::from_canonical_u32));
+    tester.execute(
+        &mut chip,
+        Instruction::from_usize(
+            VmOpcode::from_usize(Rv32CsrOpcode::CSRRS as usize),
+            [4, RV32_CSR_PTR_START, 0, 1, 1, 1],
+        ),
+    );
+
+    let trace_width = chip.trace_width();
+    let adapter_width = BaseAir::<F>::width(chip.adapter.air());
+    let modify_trace = |trace: &mut DenseMatrix<BabyBear>| {
+        let mut values = trace.row_slice(0).to_vec();
+        let cols: &mut Rv32CsrCoreCols<F> = values.split_at_mut(adapter_width).1.borrow_mut();
+        cols.src_xor_old[0] = F::from_canonical_u32(0b1000);
+        *trace = RowMajorMatrix::new(values, trace_width);
+    };
+
+    disable_debug_builder();
+    let tester = tester
+        .build()
+        .load_and_prank_trace(chip, modify_trace)
+        .load(bitwise_chip)
+        .finalize();
+    tester.simple_test_with_expected_error(VerificationError::ChallengePhaseError);
+}
+
+#[test]
+fn run_csr_sanity_test() {
+    let src = [0b1010, 0, 0xff, 0];
+    let old = [0b1100, 0xff, 0, 0];
+    assert_eq!(run_csr(Rv32CsrOpcode::CSRRW, &src, &old), src);
+    assert_eq!(
+        run_csr(Rv32CsrOpcode::CSRRS, &src, &old),
+        [0b1110, 0xff, 0xff, 0]
+    );
+    assert_eq!(
+        run_csr(Rv32CsrOpcode::CSRRC, &src, &old),
+        [0b0100, 0xff, 0, 0]
+    );
+}
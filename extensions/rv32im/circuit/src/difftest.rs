@@ -0,0 +1,407 @@
+//! Differential testing of the RV32IM executors against a plain RISC-V interpreter.
+//!
+//! [difftest] runs a program through an [ExecutionSegment] and through the
+//! [Rv32ReferenceInterpreter] in lockstep, and reports the first instruction after which the pc,
+//! a register or a stored memory cell differ. This catches executor bugs before they turn into
+//! traces that cannot be proven.
+
+use std::{collections::BTreeMap, sync::Arc};
+
+use openvm_circuit::{
+    arch::{ExecutionError, ExecutionSegment, Streams, VmConfig},
+    system::memory::memory_image_to_equipartition,
+};
+use openvm_instructions::{
+    exe::VmExe,
+    riscv::{RV32_MEMORY_AS, RV32_REGISTER_AS},
+};
+use openvm_stark_backend::p3_field::{AbstractField, PrimeField32};
+
+use crate::adapters::RV32_REGISTER_NUM_LIMBS;
+
+/// A minimal RV32IM interpreter, used as the reference of [difftest].
+///
+/// Instructions outside of RV32IM, such as the OpenVM intrinsics, are not supported.
+#[derive(Clone, Debug)]
+pub struct Rv32ReferenceInterpreter {
+    pub pc: u32,
+    pub registers: [u32; 32],
+    /// Sparse byte-addressed memory. Missing bytes are zero.
+    pub memory: BTreeMap<u32, u8>,
+}
+
+/// Memory range written by the last instruction of the [Rv32ReferenceInterpreter].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Store {
+    pub address: u32,
+    pub len: u32,
+}
+
+impl Rv32ReferenceInterpreter {
+    /// Starts at the entry point of `exe`, with its initial memory, which includes the program
+    /// text.
+    pub fn from_exe<F: PrimeField32>(exe: &VmExe<F>) -> Self {
+        let memory = exe
+            .init_memory
+            .iter()
+            .filter(|((address_space, _), _)| *address_space == RV32_MEMORY_AS)
+            .map(|(&(_, address), value)| (address, value.as_canonical_u32() as u8))
+            .collect();
+        Self {
+            pc: exe.pc_start,
+            registers: [0; 32],
+            memory,
+        }
+    }
+
+    pub fn read_memory(&self, address: u32, len: u32) -> u32 {
+        (0..len).fold(0, |value, i| {
+            let byte = self.memory.get(&address.wrapping_add(i)).copied();
+            value | (byte.unwrap_or(0) as u32) << (8 * i)
+        })
+    }
+
+    fn write_memory(&mut self, address: u32, len: u32, value: u32) {
+        for i in 0..len {
+            self.memory
+                .insert(address.wrapping_add(i), (value >> (8 * i)) as u8);
+        }
+    }
+
+    fn write_register(&mut self, reg: u32, value: u32) {
+        if reg != 0 {
+            self.registers[reg as usize] = value;
+        }
+    }
+
+    /// Executes the instruction at the pc. Returns the memory it wrote, if any, or `Err` with the
+    /// instruction word if it is not an RV32IM instruction.
+    pub fn step(&mut self) -> Result<Option<Store>, u32> {
+        let word = self.read_memory(self.pc, 4);
+        let opcode = word & 0x7f;
+        let rd = (word >> 7) & 0x1f;
+        let funct3 = (word >> 12) & 0x7;
+        let rs1 = self.registers[((word >> 15) & 0x1f) as usize];
+        let rs2 = self.registers[((word >> 20) & 0x1f) as usize];
+        let funct7 = word >> 25;
+        let imm_i = ((word as i32) >> 20) as u32;
+        let imm_s = (((word as i32) >> 25 << 5) as u32) | ((word >> 7) & 0x1f);
+        let imm_b = (((word as i32) >> 31 << 12) as u32)
+            | ((word >> 7) & 0x1) << 11
+            | ((word >> 25) & 0x3f) << 5
+            | ((word >> 8) & 0xf) << 1;
+        let imm_j = (((word as i32) >> 31 << 20) as u32)
+            | ((word >> 12) & 0xff) << 12
+            | ((word >> 20) & 0x1) << 11
+            | ((word >> 21) & 0x3ff) << 1;
+
+        let mut next_pc = self.pc.wrapping_add(4);
+        let mut store = None;
+        match opcode {
+            // LUI
+            0x37 => self.write_register(rd, word & 0xfffff000),
+            // AUIPC
+            0x17 => self.write_register(rd, self.pc.wrapping_add(word & 0xfffff000)),
+            // JAL
+            0x6f => {
+                self.write_register(rd, next_pc);
+                next_pc = self.pc.wrapping_add(imm_j);
+            }
+            // JALR
+            0x67 if funct3 == 0 => {
+                let target = rs1.wrapping_add(imm_i) & !1;
+                self.write_register(rd, next_pc);
+                next_pc = target;
+            }
+            0x63 => {
+                let taken = match funct3 {
+                    0 => rs1 == rs2,
+                    1 => rs1 != rs2,
+                    4 => (rs1 as i32) < (rs2 as i32),
+                    5 => (rs1 as i32) >= (rs2 as i32),
+                    6 => rs1 < rs2,
+                    7 => rs1 >= rs2,
+                    _ => return Err(word),
+                };
+                if taken {
+                    next_pc = self.pc.wrapping_add(imm_b);
+                }
+            }
+            // Loads
+            0x03 => {
+                let address = rs1.wrapping_add(imm_i);
+                let value = match funct3 {
+                    0 => self.read_memory(address, 1) as i8 as u32,
+                    1 => self.read_memory(address, 2) as i16 as u32,
+                    2 => self.read_memory(address, 4),
+                    4 => self.read_memory(address, 1),
+                    5 => self.read_memory(address, 2),
+                    _ => return Err(word),
+                };
+                self.write_register(rd, value);
+            }
+            // Stores
+            0x23 => {
+                let address = rs1.wrapping_add(imm_s);
+                let len = match funct3 {
+                    0 => 1,
+                    1 => 2,
+                    2 => 4,
+                    _ => return Err(word),
+                };
+                self.write_memory(address, len, rs2);
+                store = Some(Store { address, len });
+            }
+            // OP-IMM
+            0x13 => {
+                let shamt = imm_i & 0x1f;
+                let value = match (funct3, funct7) {
+                    (0, _) => rs1.wrapping_add(imm_i),
+                    (2, _) => ((rs1 as i32) < (imm_i as i32)) as u32,
+                    (3, _) => (rs1 < imm_i) as u32,
+                    (4, _) => rs1 ^ imm_i,
+                    (6, _) => rs1 | imm_i,
+                    (7, _) => rs1 & imm_i,
+                    (1, 0x00) => rs1 << shamt,
+                    (5, 0x00) => rs1 >> shamt,
+                    (5, 0x20) => ((rs1 as i32) >> shamt) as u32,
+                    _ => return Err(word),
+                };
+                self.write_register(rd, value);
+            }
+            // OP
+            0x33 => {
+                let value = match (funct7, funct3) {
+                    (0x00, 0) => rs1.wrapping_add(rs2),
+                    (0x20, 0) => rs1.wrapping_sub(rs2),
+                    (0x00, 1) => rs1 << (rs2 & 0x1f),
+                    (0x00, 2) => ((rs1 as i32) < (rs2 as i32)) as u32,
+                    (0x00, 3) => (rs1 < rs2) as u32,
+                    (0x00, 4) => rs1 ^ rs2,
+                    (0x00, 5) => rs1 >> (rs2 & 0x1f),
+                    (0x20, 5) => ((rs1 as i32) >> (rs2 & 0x1f)) as u32,
+                    (0x00, 6) => rs1 | rs2,
+                    (0x00, 7) => rs1 & rs2,
+                    (0x01, _) => mul_div(funct3, rs1, rs2),
+                    _ => return Err(word),
+                };
+                self.write_register(rd, value);
+            }
+            // FENCE
+            0x0f => {}
+            _ => return Err(word),
+        }
+        self.pc = next_pc;
+        Ok(store)
+    }
+}
+
+/// Instructions of the M extension.
+fn mul_div(funct3: u32, rs1: u32, rs2: u32) -> u32 {
+    let (signed1, signed2) = (rs1 as i32 as i64, rs2 as i32 as i64);
+    match funct3 {
+        0 => rs1.wrapping_mul(rs2),
+        1 => ((signed1 * signed2) >> 32) as u32,
+        2 => ((signed1 * rs2 as i64) >> 32) as u32,
+        3 => ((rs1 as u64 * rs2 as u64) >> 32) as u32,
+        4 => match rs2 {
+            0 => u32::MAX,
+            _ => (rs1 as i32).wrapping_div(rs2 as i32) as u32,
+        },
+        5 => rs1.checked_div(rs2).unwrap_or(u32::MAX),
+        6 => match rs2 {
+            0 => rs1,
+            _ => (rs1 as i32).wrapping_rem(rs2 as i32) as u32,
+        },
+        _ => rs1.checked_rem(rs2).unwrap_or(rs1),
+    }
+}
+
+/// What differs after an instruction, as `(OpenVM, reference)` values.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Mismatch {
+    Pc(u32, u32),
+    Register { reg: usize, values: (u32, u32) },
+    Memory { address: u32, values: (u8, u8) },
+}
+
+/// The first instruction after which OpenVM and the reference disagree.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Divergence {
+    /// Number of instructions executed before the diverging one.
+    pub step: u64,
+    pub pc: u32,
+    pub mismatch: Mismatch,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DifftestReport {
+    /// Number of instructions compared.
+    pub steps: u64,
+    /// Number of instructions the reference does not support, e.g. intrinsics. Their effects
+    /// are copied from OpenVM to the reference.
+    pub skipped: u64,
+    /// Set if the program terminated within the step limit.
+    pub exit_code: Option<u32>,
+    pub divergence: Option<Divergence>,
+}
+
+/// Executes `exe` with `config` and the [Rv32ReferenceInterpreter] side by side for at most
+/// `max_steps` instructions, stopping at the first divergence.
+///
+/// The architectural state is compared after every instruction: the pc, all registers, and the
+/// memory written by the instruction.
+pub fn difftest<F: PrimeField32, VC: VmConfig<F>>(
+    config: &VC,
+    exe: &VmExe<F>,
+    input: impl Into<Streams<F>>,
+    max_steps: u64,
+) -> Result<DifftestReport, ExecutionError> {
+    let mut reference = Rv32ReferenceInterpreter::from_exe(exe);
+    let mut segment = ExecutionSegment::new(
+        config,
+        exe.program.clone(),
+        input.into(),
+        Some(Arc::new(memory_image_to_equipartition(
+            exe.init_memory.clone(),
+        ))),
+        exe.fn_bounds.clone(),
+    );
+    let mut report = DifftestReport::default();
+    let mut pc = exe.pc_start;
+    while report.steps < max_steps {
+        let record = segment.step(pc)?;
+        if record.exit_code.is_some() {
+            report.exit_code = record.exit_code;
+            break;
+        }
+        let memory = segment.chip_complex.memory_controller().borrow();
+        let vm_register = |reg: usize| {
+            let limbs = memory.unsafe_read::<RV32_REGISTER_NUM_LIMBS>(
+                F::from_canonical_u32(RV32_REGISTER_AS),
+                F::from_canonical_usize(RV32_REGISTER_NUM_LIMBS * reg),
+            );
+            u32::from_le_bytes(limbs.map(|limb| limb.as_canonical_u32() as u8))
+        };
+        let vm_byte = |address: u32| {
+            memory
+                .unsafe_read_cell(
+                    F::from_canonical_u32(RV32_MEMORY_AS),
+                    F::from_canonical_u32(address),
+                )
+                .as_canonical_u32() as u8
+        };
+
+        let mut written: Vec<u32> = record
+            .writes_to(RV32_MEMORY_AS)
+            .flat_map(|write| write.pointer..write.pointer + write.data.len() as u32)
+            .collect();
+        let mismatch = match reference.step() {
+            Ok(store) => {
+                written.extend(store.into_iter().flat_map(|s| s.address..s.address + s.len));
+                if reference.pc != record.next_pc {
+                    Some(Mismatch::Pc(record.next_pc, reference.pc))
+                } else if let Some(reg) =
+                    (0..32).find(|&reg| vm_register(reg) != reference.registers[reg])
+                {
+                    Some(Mismatch::Register {
+                        reg,
+                        values: (vm_register(reg), reference.registers[reg]),
+                    })
+                } else {
+                    written.into_iter().find_map(|address| {
+                        let values = (vm_byte(address), reference.read_memory(address, 1) as u8);
+                        (values.0 != values.1).then_some(Mismatch::Memory { address, values })
+                    })
+                }
+            }
+            Err(_) => {
+                report.skipped += 1;
+                reference.pc = record.next_pc;
+                for reg in 1..32 {
+                    reference.registers[reg] = vm_register(reg);
+                }
+                for address in written {
+                    reference.memory.insert(address, vm_byte(address));
+                }
+                None
+            }
+        };
+        if let Some(mismatch) = mismatch {
+            report.divergence = Some(Divergence {
+                step: report.steps,
+                pc,
+                mismatch,
+            });
+            break;
+        }
+        report.steps += 1;
+        pc = record.next_pc;
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use openvm_circuit::arch::Streams;
+    use openvm_instructions::{exe::VmExe, program::Program, riscv::RV32_MEMORY_AS};
+    use openvm_rv32im_transpiler::{
+        Rv32ITranspilerExtension, Rv32IoTranspilerExtension, Rv32MTranspilerExtension,
+    };
+    use openvm_stark_backend::p3_field::AbstractField;
+    use openvm_stark_sdk::p3_baby_bear::BabyBear;
+    use openvm_transpiler::transpiler::Transpiler;
+
+    use super::{difftest, Rv32ReferenceInterpreter};
+    use crate::Rv32ImConfig;
+
+    type F = BabyBear;
+
+    const PC_BASE: u32 = 0x1000;
+
+    fn exe(words: &[u32]) -> VmExe<F> {
+        let transpiler = Transpiler::<F>::default()
+            .with_extension(Rv32ITranspilerExtension)
+            .with_extension(Rv32MTranspilerExtension)
+            .with_extension(Rv32IoTranspilerExtension);
+        let instructions = transpiler.transpile(words).unwrap();
+        let program = Program::new_without_debug_infos(&instructions, 4, PC_BASE, 0);
+        let mut exe = VmExe::new(program).with_pc_start(PC_BASE);
+        for (i, word) in words.iter().enumerate() {
+            for (j, byte) in word.to_le_bytes().into_iter().enumerate() {
+                let address = PC_BASE + 4 * i as u32 + j as u32;
+                exe.init_memory
+                    .insert((RV32_MEMORY_AS, address), F::from_canonical_u8(byte));
+            }
+        }
+        exe
+    }
+
+    #[test]
+    fn test_difftest() {
+        let exe = exe(&[
+            0x00500093, // addi x1, x0, 5
+            0xff900113, // addi x2, x0, -7
+            0x022081b3, // mul x3, x1, x2
+            0x00002237, // lui x4, 2
+            0x00322223, // sw x3, 4(x4)
+            0x00424283, // lbu x5, 4(x4)
+            0x0220c333, // div x6, x1, x2
+            0x0000000b, // terminate
+        ]);
+        let report = difftest(&Rv32ImConfig::default(), &exe, Streams::default(), 100).unwrap();
+        assert_eq!(report.divergence, None);
+        assert_eq!(report.steps, 7);
+        assert_eq!(report.exit_code, Some(0));
+
+        let mut reference = Rv32ReferenceInterpreter::from_exe(&exe);
+        for _ in 0..7 {
+            reference.step().unwrap();
+        }
+        assert_eq!(reference.registers[3], -35i32 as u32);
+        assert_eq!(reference.registers[5], (-35i32 as u32) & 0xff);
+        assert_eq!(reference.registers[6], 0);
+        assert_eq!(reference.read_memory(0x2004, 4), -35i32 as u32);
+        assert_eq!(reference.step(), Err(0x0000000b));
+    }
+}
@@ -7,8 +7,8 @@ use std::{
 use num_bigint::BigUint;
 use num_integer::Integer;
 use openvm_circuit::arch::{
-    AdapterAirContext, AdapterRuntimeContext, MinimalInstruction, Result, VmAdapterInterface,
-    VmCoreAir, VmCoreChip,
+    AdapterAirContext, AdapterRuntimeContext, ExecutionError, MinimalInstruction, Result,
+    VmAdapterInterface, VmCoreAir, VmCoreChip,
 };
 use openvm_circuit_primitives::{
     bitwise_op_lookup::{BitwiseOperationLookupBus, BitwiseOperationLookupChip},
@@ -304,11 +304,16 @@ where
     }
 }
 
+/// Division by zero and signed overflow follow the RISC-V spec: `x / 0` gives all ones with
+/// remainder `x`, and `MIN / -1` gives `MIN` with remainder 0. Both cases are constrained by the
+/// AIR. If `trap` is set, execution instead stops with [ExecutionError::DivisionByZero] or
+/// [ExecutionError::DivisionOverflow].
 #[derive(Debug)]
 pub struct DivRemCoreChip<const NUM_LIMBS: usize, const LIMB_BITS: usize> {
     pub air: DivRemCoreAir<NUM_LIMBS, LIMB_BITS>,
     pub bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<LIMB_BITS>>,
     pub range_tuple_chip: Arc<RangeTupleCheckerChip<2>>,
+    pub trap: bool,
 }
 
 impl<const NUM_LIMBS: usize, const LIMB_BITS: usize> DivRemCoreChip<NUM_LIMBS, LIMB_BITS> {
@@ -339,8 +344,15 @@ impl<const NUM_LIMBS: usize, const LIMB_BITS: usize> DivRemCoreChip<NUM_LIMBS, L
             },
             bitwise_lookup_chip,
             range_tuple_chip,
+            trap: false,
         }
     }
+
+    /// Returns an error on division by zero and signed overflow instead of the spec values.
+    pub fn with_trap(mut self) -> Self {
+        self.trap = true;
+        self
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -364,7 +376,7 @@ pub struct DivRemCoreRecord<T, const NUM_LIMBS: usize, const LIMB_BITS: usize> {
 
 #[derive(Debug, Eq, PartialEq)]
 #[repr(u8)]
-pub(super) enum DivRemCoreSpecialCase {
+pub(crate) enum DivRemCoreSpecialCase {
     None,
     ZeroDivisor,
     SignedOverflow,
@@ -383,7 +395,7 @@ where
     fn execute_instruction(
         &self,
         instruction: &Instruction<F>,
-        _from_pc: u32,
+        from_pc: u32,
         reads: I::Reads,
     ) -> Result<(AdapterRuntimeContext<F, I>, Self::Record)> {
         let Instruction { opcode, .. } = instruction;
@@ -397,6 +409,17 @@ where
         let c = data[1].map(|y| y.as_canonical_u32());
         let (q, r, b_sign, c_sign, q_sign, case) =
             run_divrem::<NUM_LIMBS, LIMB_BITS>(is_signed, &b, &c);
+        if self.trap {
+            match case {
+                DivRemCoreSpecialCase::None => {}
+                DivRemCoreSpecialCase::ZeroDivisor => {
+                    return Err(ExecutionError::DivisionByZero { pc: from_pc });
+                }
+                DivRemCoreSpecialCase::SignedOverflow => {
+                    return Err(ExecutionError::DivisionOverflow { pc: from_pc });
+                }
+            }
+        }
 
         let carries = run_mul_carries::<NUM_LIMBS, LIMB_BITS>(is_signed, &c, &q, &r, q_sign);
         for i in 0..NUM_LIMBS {
@@ -493,7 +516,7 @@ where
 
 // Returns (quotient, remainder, x_sign, y_sign, q_sign, case) where case = 0 for normal, 1
 // for zero divisor, and 2 for signed overflow
-pub(super) fn run_divrem<const NUM_LIMBS: usize, const LIMB_BITS: usize>(
+pub(crate) fn run_divrem<const NUM_LIMBS: usize, const LIMB_BITS: usize>(
     signed: bool,
     x: &[u32; NUM_LIMBS],
     y: &[u32; NUM_LIMBS],
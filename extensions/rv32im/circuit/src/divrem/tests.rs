@@ -3,8 +3,8 @@ use std::{array, borrow::BorrowMut, sync::Arc};
 use openvm_circuit::{
     arch::{
         testing::{memory::gen_pointer, TestAdapterChip, VmChipTestBuilder},
-        ExecutionBridge, InstructionExecutor, VmAdapterChip, VmChipWrapper, BITWISE_OP_LOOKUP_BUS,
-        RANGE_TUPLE_CHECKER_BUS,
+        ExecutionBridge, ExecutionError, ExecutionState, InstructionExecutor, VmAdapterChip,
+        VmChipWrapper, BITWISE_OP_LOOKUP_BUS, RANGE_TUPLE_CHECKER_BUS,
     },
     utils::generate_long_number,
 };
@@ -204,6 +204,60 @@ fn rv32_remu_rand_test() {
     run_rv32_divrem_rand_test(DivRemOpcode::REMU, 100);
 }
 
+#[test]
+fn rv32_divrem_trap_test() {
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let range_tuple_bus = RangeTupleCheckerBus::new(
+        RANGE_TUPLE_CHECKER_BUS,
+        [1 << RV32_CELL_BITS, 8 * (1 << RV32_CELL_BITS)],
+    );
+    let bitwise_chip = Arc::new(BitwiseOperationLookupChip::<RV32_CELL_BITS>::new(
+        bitwise_bus,
+    ));
+    let range_tuple_checker = Arc::new(RangeTupleCheckerChip::new(range_tuple_bus));
+
+    let mut tester = VmChipTestBuilder::default();
+    let mut chip = Rv32DivRemChip::<F>::new(
+        Rv32MultAdapterChip::new(
+            tester.execution_bus(),
+            tester.program_bus(),
+            tester.memory_controller(),
+        ),
+        DivRemCoreChip::new(bitwise_chip, range_tuple_checker, 0).with_trap(),
+        tester.memory_controller(),
+    );
+
+    let mut execute = |opcode: DivRemOpcode, b: [u32; 4], c: [u32; 4]| {
+        tester.write::<RV32_REGISTER_NUM_LIMBS>(1, 4, b.map(F::from_canonical_u32));
+        tester.write::<RV32_REGISTER_NUM_LIMBS>(1, 8, c.map(F::from_canonical_u32));
+        let from_state = ExecutionState {
+            pc: 12,
+            timestamp: tester.memory_controller().borrow().timestamp(),
+        };
+        chip.execute(
+            Instruction::from_usize(VmOpcode::from_usize(opcode as usize), [0, 4, 8, 1]),
+            from_state,
+        )
+    };
+
+    let min = [0, 0, 0, 1 << (RV32_CELL_BITS - 1)];
+    let neg_one = [(1 << RV32_CELL_BITS) - 1; RV32_REGISTER_NUM_LIMBS];
+    assert!(matches!(
+        execute(DivRemOpcode::DIV, [7, 0, 0, 0], [0; 4]),
+        Err(ExecutionError::DivisionByZero { pc: 12 })
+    ));
+    assert!(matches!(
+        execute(DivRemOpcode::REMU, [7, 0, 0, 0], [0; 4]),
+        Err(ExecutionError::DivisionByZero { pc: 12 })
+    ));
+    assert!(matches!(
+        execute(DivRemOpcode::REM, min, neg_one),
+        Err(ExecutionError::DivisionOverflow { pc: 12 })
+    ));
+    // Signed overflow is only a special case for the signed opcodes
+    assert!(execute(DivRemOpcode::DIVU, min, neg_one).is_ok());
+}
+
 //////////////////////////////////////////////////////////////////////////////////////
 // NEGATIVE TESTS
 //
@@ -17,8 +17,11 @@ use openvm_circuit_primitives_derive::{Chip, ChipUsageGetter};
 use openvm_instructions::{program::DEFAULT_PC_STEP, PhantomDiscriminant, UsizeOpcode, VmOpcode};
 use openvm_rv32im_transpiler::{
     BaseAluOpcode, BranchEqualOpcode, BranchLessThanOpcode, DivRemOpcode, LessThanOpcode,
-    MulHOpcode, MulOpcode, Rv32AuipcOpcode, Rv32HintStoreOpcode, Rv32JalLuiOpcode, Rv32JalrOpcode,
-    Rv32LoadStoreOpcode, Rv32Phantom, ShiftOpcode,
+    MulHMulOpcode, MulHOpcode, MulOpcode, Rv32AtomicOpcode, Rv32AuipcOpcode, Rv32BitCountOpcode,
+    Rv32ByteOpOpcode, Rv32CsrOpcode, Rv32FloatAddOpcode, Rv32FloatCmpOpcode, Rv32FloatMiscOpcode,
+    Rv32FloatMulOpcode, Rv32FloatToIntOpcode, Rv32HintStoreOpcode, Rv32IntToFloatOpcode,
+    Rv32JalLuiOpcode, Rv32JalrOpcode, Rv32LoadStoreOpcode, Rv32LogicNotOpcode, Rv32MemcpyOpcode,
+    Rv32MinMaxOpcode, Rv32Phantom, Rv32RotateOpcode, Rv32ShAddOpcode, ShiftOpcode,
 };
 use openvm_stark_backend::p3_field::PrimeField32;
 use serde::{Deserialize, Serialize};
@@ -135,16 +138,58 @@ pub struct Rv32Io;
 pub struct Rv32M {
     #[serde(default = "default_range_tuple_checker_sizes")]
     pub range_tuple_checker_sizes: [u32; 2],
+    /// Adds a chip for the [MulHMulOpcode] instructions, which prove a `mulh[[s]u]` and a `mul`
+    /// on the same operands in a single row. Programs must then be transpiled with
+    /// [Rv32MFusedTranspilerExtension](openvm_rv32im_transpiler::Rv32MFusedTranspilerExtension).
+    #[serde(default)]
+    pub fuse_mulh_mul: bool,
+    /// Stops execution with a [DivisionByZero](openvm_circuit::arch::ExecutionError::DivisionByZero)
+    /// or [DivisionOverflow](openvm_circuit::arch::ExecutionError::DivisionOverflow) error instead
+    /// of returning the RISC-V values for `x / 0` and `i32::MIN / -1`.
+    #[serde(default)]
+    pub trap_div_special_cases: bool,
 }
 
 impl Default for Rv32M {
     fn default() -> Self {
         Self {
             range_tuple_checker_sizes: default_range_tuple_checker_sizes(),
+            fuse_mulh_mul: false,
+            trap_div_special_cases: false,
         }
     }
 }
 
+/// RISC-V Extension for the Zicsr instructions on the CSRs of
+/// [Rv32Csr](openvm_rv32im_transpiler::Rv32Csr). Programs using them must be transpiled with
+/// [Rv32ZicsrTranspilerExtension](openvm_rv32im_transpiler::Rv32ZicsrTranspilerExtension).
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub struct Rv32Zicsr;
+
+/// RISC-V Extension for the Zba address generation instructions. Programs using them
+/// must be transpiled with
+/// [Rv32ZbaTranspilerExtension](openvm_rv32im_transpiler::Rv32ZbaTranspilerExtension).
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub struct Rv32Zba;
+
+/// RISC-V Extension for the Zbb basic bit-manipulation instructions. Programs using them
+/// must be transpiled with
+/// [Rv32ZbbTranspilerExtension](openvm_rv32im_transpiler::Rv32ZbbTranspilerExtension).
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub struct Rv32Zbb;
+
+/// RISC-V Extension for the word instructions of the A (atomic) extension, with the semantics
+/// of a single hart. Programs using them must be transpiled with
+/// [Rv32ATranspilerExtension](openvm_rv32im_transpiler::Rv32ATranspilerExtension).
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub struct Rv32A;
+
+/// RISC-V Extension for the single-precision instructions of the F extension, with the
+/// restrictions of [Rv32FTranspilerExtension](openvm_rv32im_transpiler::Rv32FTranspilerExtension).
+/// Float loads, stores and moves are transpiled to instructions of [Rv32I].
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub struct Rv32F;
+
 fn default_range_tuple_checker_sizes() -> [u32; 2] {
     [1 << 8, 8 * (1 << 8)]
 }
@@ -172,6 +217,7 @@ pub enum Rv32IExecutor<F: PrimeField32> {
 pub enum Rv32MExecutor<F: PrimeField32> {
     Multiplication(Rv32MultiplicationChip<F>),
     MultiplicationHigh(Rv32MulHChip<F>),
+    MultiplicationFused(Rv32MulHMulChip<F>),
     DivRem(Rv32DivRemChip<F>),
 }
 
@@ -179,6 +225,48 @@ pub enum Rv32MExecutor<F: PrimeField32> {
 #[derive(ChipUsageGetter, Chip, InstructionExecutor, From, AnyEnum)]
 pub enum Rv32IoExecutor<F: PrimeField32> {
     HintStore(Rv32HintStoreChip<F>),
+    HintBuffer(Rv32HintBufferChip<F>),
+    HintLoadPage(Rv32HintLoadPageChip<F>),
+    Memcpy(Rv32MemcpyChip<F>),
+}
+
+/// RISC-V Zicsr Instruction Executors
+#[derive(ChipUsageGetter, Chip, InstructionExecutor, From, AnyEnum)]
+pub enum Rv32ZicsrExecutor<F: PrimeField32> {
+    Csr(Rv32CsrChip<F>),
+}
+
+/// RISC-V Zba Instruction Executors
+#[derive(ChipUsageGetter, Chip, InstructionExecutor, From, AnyEnum)]
+pub enum Rv32ZbaExecutor<F: PrimeField32> {
+    ShAdd(Rv32ShAddChip<F>),
+}
+
+/// RISC-V Zbb Instruction Executors
+#[derive(ChipUsageGetter, Chip, InstructionExecutor, From, AnyEnum)]
+pub enum Rv32ZbbExecutor<F: PrimeField32> {
+    LogicNot(Rv32LogicNotChip<F>),
+    MinMax(Rv32MinMaxChip<F>),
+    Rotate(Rv32RotateChip<F>),
+    BitCount(Rv32BitCountChip<F>),
+    ByteOp(Rv32ByteOpChip<F>),
+}
+
+/// RISC-V A Instruction Executors
+#[derive(ChipUsageGetter, Chip, InstructionExecutor, From, AnyEnum)]
+pub enum Rv32AExecutor<F: PrimeField32> {
+    Atomic(Rv32AtomicChip<F>),
+}
+
+/// RISC-V F Instruction Executors
+#[derive(ChipUsageGetter, Chip, InstructionExecutor, From, AnyEnum)]
+pub enum Rv32FExecutor<F: PrimeField32> {
+    FloatMisc(Rv32FloatMiscChip<F>),
+    FloatCmp(Rv32FloatCmpChip<F>),
+    FloatAdd(Rv32FloatAddChip<F>),
+    FloatMul(Rv32FloatMulChip<F>),
+    FloatToInt(Rv32FloatToIntChip<F>),
+    IntToFloat(Rv32IntToFloatChip<F>),
 }
 
 #[derive(From, ChipUsageGetter, Chip, AnyEnum)]
@@ -204,6 +292,41 @@ pub enum Rv32IoPeriphery<F: PrimeField32> {
     Phantom(PhantomChip<F>),
 }
 
+#[derive(From, ChipUsageGetter, Chip, AnyEnum)]
+pub enum Rv32ZicsrPeriphery<F: PrimeField32> {
+    BitwiseOperationLookup(Arc<BitwiseOperationLookupChip<8>>),
+    // We put this only to get the <F> generic to work
+    Phantom(PhantomChip<F>),
+}
+
+#[derive(From, ChipUsageGetter, Chip, AnyEnum)]
+pub enum Rv32ZbaPeriphery<F: PrimeField32> {
+    BitwiseOperationLookup(Arc<BitwiseOperationLookupChip<8>>),
+    // We put this only to get the <F> generic to work
+    Phantom(PhantomChip<F>),
+}
+
+#[derive(From, ChipUsageGetter, Chip, AnyEnum)]
+pub enum Rv32ZbbPeriphery<F: PrimeField32> {
+    BitwiseOperationLookup(Arc<BitwiseOperationLookupChip<8>>),
+    // We put this only to get the <F> generic to work
+    Phantom(PhantomChip<F>),
+}
+
+#[derive(From, ChipUsageGetter, Chip, AnyEnum)]
+pub enum Rv32APeriphery<F: PrimeField32> {
+    BitwiseOperationLookup(Arc<BitwiseOperationLookupChip<8>>),
+    // We put this only to get the <F> generic to work
+    Phantom(PhantomChip<F>),
+}
+
+#[derive(From, ChipUsageGetter, Chip, AnyEnum)]
+pub enum Rv32FPeriphery<F: PrimeField32> {
+    BitwiseOperationLookup(Arc<BitwiseOperationLookupChip<8>>),
+    // We put this only to get the <F> generic to work
+    Phantom(PhantomChip<F>),
+}
+
 // ============ VmExtension Implementations ============
 
 impl<F: PrimeField32> VmExtension<F> for Rv32I {
@@ -371,6 +494,10 @@ impl<F: PrimeField32> VmExtension<F> for Rv32I {
             phantom::Rv32PrintStrSubEx,
             PhantomDiscriminant(Rv32Phantom::PrintStr as u16),
         )?;
+        builder.add_phantom_sub_executor(
+            phantom::Rv32HintStreamSubEx,
+            PhantomDiscriminant(Rv32Phantom::HintStream as u16),
+        )?;
 
         Ok(inventory)
     }
@@ -443,13 +570,37 @@ impl<F: PrimeField32> VmExtension<F> for Rv32M {
             MulHOpcode::iter().map(VmOpcode::with_default_offset),
         )?;
 
+        if self.fuse_mulh_mul {
+            let mul_h_mul_chip = Rv32MulHMulChip::new(
+                Rv32MultFusedAdapterChip::new(
+                    execution_bus,
+                    program_bus,
+                    memory_controller.clone(),
+                ),
+                MulHMulCoreChip::new(
+                    bitwise_lu_chip.clone(),
+                    range_tuple_checker.clone(),
+                    MulHMulOpcode::default_offset(),
+                ),
+                memory_controller.clone(),
+            );
+            inventory.add_executor(
+                mul_h_mul_chip,
+                MulHMulOpcode::iter().map(VmOpcode::with_default_offset),
+            )?;
+        }
+
+        let mut div_rem_core = DivRemCoreChip::new(
+            bitwise_lu_chip.clone(),
+            range_tuple_checker.clone(),
+            DivRemOpcode::default_offset(),
+        );
+        if self.trap_div_special_cases {
+            div_rem_core = div_rem_core.with_trap();
+        }
         let div_rem_chip = Rv32DivRemChip::new(
             Rv32MultAdapterChip::new(execution_bus, program_bus, memory_controller.clone()),
-            DivRemCoreChip::new(
-                bitwise_lu_chip.clone(),
-                range_tuple_checker.clone(),
-                DivRemOpcode::default_offset(),
-            ),
+            div_rem_core,
             memory_controller.clone(),
         );
         inventory.add_executor(
@@ -505,7 +656,381 @@ impl<F: PrimeField32> VmExtension<F> for Rv32Io {
 
         inventory.add_executor(
             hintstore_chip,
-            Rv32HintStoreOpcode::iter().map(VmOpcode::with_default_offset),
+            [Rv32HintStoreOpcode::HINT_STOREW].map(VmOpcode::with_default_offset),
+        )?;
+
+        let mut hint_buffer_chip = Rv32HintBufferChip::new(
+            execution_bus,
+            program_bus,
+            memory_controller.clone(),
+            range_checker.clone(),
+            bitwise_lu_chip.clone(),
+            Rv32HintStoreOpcode::default_offset(),
+        );
+        hint_buffer_chip.set_streams(builder.streams().clone());
+
+        inventory.add_executor(
+            hint_buffer_chip,
+            [Rv32HintStoreOpcode::HINT_BUFFER].map(VmOpcode::with_default_offset),
+        )?;
+
+        let mut hint_load_page_chip = Rv32HintLoadPageChip::new(
+            execution_bus,
+            program_bus,
+            memory_controller.clone(),
+            range_checker.clone(),
+            bitwise_lu_chip.clone(),
+            Rv32HintStoreOpcode::default_offset(),
+        );
+        hint_load_page_chip.set_streams(builder.streams().clone());
+
+        inventory.add_executor(
+            hint_load_page_chip,
+            [Rv32HintStoreOpcode::HINT_LOAD_PAGE].map(VmOpcode::with_default_offset),
+        )?;
+
+        let memcpy_chip = Rv32MemcpyChip::new(
+            execution_bus,
+            program_bus,
+            memory_controller.clone(),
+            bitwise_lu_chip.clone(),
+            Rv32MemcpyOpcode::default_offset(),
+        );
+        inventory.add_executor(
+            memcpy_chip,
+            Rv32MemcpyOpcode::iter().map(VmOpcode::with_default_offset),
+        )?;
+
+        Ok(inventory)
+    }
+}
+
+impl<F: PrimeField32> VmExtension<F> for Rv32Zicsr {
+    type Executor = Rv32ZicsrExecutor<F>;
+    type Periphery = Rv32ZicsrPeriphery<F>;
+
+    fn build(
+        &self,
+        builder: &mut VmInventoryBuilder<F>,
+    ) -> Result<VmInventory<Self::Executor, Self::Periphery>, VmInventoryError> {
+        let mut inventory = VmInventory::new();
+        let SystemPort {
+            execution_bus,
+            program_bus,
+            memory_controller,
+        } = builder.system_port();
+        let bitwise_lu_chip = if let Some(chip) = builder
+            .find_chip::<Arc<BitwiseOperationLookupChip<8>>>()
+            .first()
+        {
+            Arc::clone(chip)
+        } else {
+            let bitwise_lu_bus = BitwiseOperationLookupBus::new(builder.new_bus_idx());
+            let chip = Arc::new(BitwiseOperationLookupChip::new(bitwise_lu_bus));
+            inventory.add_periphery_chip(chip.clone());
+            chip
+        };
+
+        let csr_chip = Rv32CsrChip::new(
+            Rv32CsrAdapterChip::new(execution_bus, program_bus, memory_controller.clone()),
+            Rv32CsrCoreChip::new(bitwise_lu_chip.clone(), Rv32CsrOpcode::default_offset()),
+            memory_controller.clone(),
+        );
+        inventory.add_executor(
+            csr_chip,
+            Rv32CsrOpcode::iter().map(VmOpcode::with_default_offset),
+        )?;
+
+        Ok(inventory)
+    }
+}
+
+impl<F: PrimeField32> VmExtension<F> for Rv32Zba {
+    type Executor = Rv32ZbaExecutor<F>;
+    type Periphery = Rv32ZbaPeriphery<F>;
+
+    fn build(
+        &self,
+        builder: &mut VmInventoryBuilder<F>,
+    ) -> Result<VmInventory<Self::Executor, Self::Periphery>, VmInventoryError> {
+        let mut inventory = VmInventory::new();
+        let SystemPort {
+            execution_bus,
+            program_bus,
+            memory_controller,
+        } = builder.system_port();
+        let range_checker = builder.system_base().range_checker_chip.clone();
+        let bitwise_lu_chip = if let Some(chip) = builder
+            .find_chip::<Arc<BitwiseOperationLookupChip<8>>>()
+            .first()
+        {
+            Arc::clone(chip)
+        } else {
+            let bitwise_lu_bus = BitwiseOperationLookupBus::new(builder.new_bus_idx());
+            let chip = Arc::new(BitwiseOperationLookupChip::new(bitwise_lu_bus));
+            inventory.add_periphery_chip(chip.clone());
+            chip
+        };
+
+        let sh_add_chip = Rv32ShAddChip::new(
+            Rv32BaseAluAdapterChip::new(execution_bus, program_bus, memory_controller.clone()),
+            Rv32ShAddCoreChip::new(
+                bitwise_lu_chip.clone(),
+                range_checker.clone(),
+                Rv32ShAddOpcode::default_offset(),
+            ),
+            memory_controller.clone(),
+        );
+        inventory.add_executor(
+            sh_add_chip,
+            Rv32ShAddOpcode::iter().map(VmOpcode::with_default_offset),
+        )?;
+
+        Ok(inventory)
+    }
+}
+
+impl<F: PrimeField32> VmExtension<F> for Rv32Zbb {
+    type Executor = Rv32ZbbExecutor<F>;
+    type Periphery = Rv32ZbbPeriphery<F>;
+
+    fn build(
+        &self,
+        builder: &mut VmInventoryBuilder<F>,
+    ) -> Result<VmInventory<Self::Executor, Self::Periphery>, VmInventoryError> {
+        let mut inventory = VmInventory::new();
+        let SystemPort {
+            execution_bus,
+            program_bus,
+            memory_controller,
+        } = builder.system_port();
+        let range_checker = builder.system_base().range_checker_chip.clone();
+        let bitwise_lu_chip = if let Some(chip) = builder
+            .find_chip::<Arc<BitwiseOperationLookupChip<8>>>()
+            .first()
+        {
+            Arc::clone(chip)
+        } else {
+            let bitwise_lu_bus = BitwiseOperationLookupBus::new(builder.new_bus_idx());
+            let chip = Arc::new(BitwiseOperationLookupChip::new(bitwise_lu_bus));
+            inventory.add_periphery_chip(chip.clone());
+            chip
+        };
+
+        let logic_not_chip = Rv32LogicNotChip::new(
+            Rv32BaseAluAdapterChip::new(execution_bus, program_bus, memory_controller.clone()),
+            Rv32LogicNotCoreChip::new(
+                bitwise_lu_chip.clone(),
+                Rv32LogicNotOpcode::default_offset(),
+            ),
+            memory_controller.clone(),
+        );
+        inventory.add_executor(
+            logic_not_chip,
+            Rv32LogicNotOpcode::iter().map(VmOpcode::with_default_offset),
+        )?;
+
+        let min_max_chip = Rv32MinMaxChip::new(
+            Rv32BaseAluAdapterChip::new(execution_bus, program_bus, memory_controller.clone()),
+            Rv32MinMaxCoreChip::new(bitwise_lu_chip.clone(), Rv32MinMaxOpcode::default_offset()),
+            memory_controller.clone(),
+        );
+        inventory.add_executor(
+            min_max_chip,
+            Rv32MinMaxOpcode::iter().map(VmOpcode::with_default_offset),
+        )?;
+
+        let rotate_chip = Rv32RotateChip::new(
+            Rv32BaseAluAdapterChip::new(execution_bus, program_bus, memory_controller.clone()),
+            Rv32RotateCoreChip::new(
+                bitwise_lu_chip.clone(),
+                range_checker.clone(),
+                Rv32RotateOpcode::default_offset(),
+            ),
+            memory_controller.clone(),
+        );
+        inventory.add_executor(
+            rotate_chip,
+            Rv32RotateOpcode::iter().map(VmOpcode::with_default_offset),
+        )?;
+
+        let bit_count_chip = Rv32BitCountChip::new(
+            Rv32BaseAluAdapterChip::new(execution_bus, program_bus, memory_controller.clone()),
+            Rv32BitCountCoreChip::new(Rv32BitCountOpcode::default_offset()),
+            memory_controller.clone(),
+        );
+        inventory.add_executor(
+            bit_count_chip,
+            Rv32BitCountOpcode::iter().map(VmOpcode::with_default_offset),
+        )?;
+
+        let byte_op_chip = Rv32ByteOpChip::new(
+            Rv32BaseAluAdapterChip::new(execution_bus, program_bus, memory_controller.clone()),
+            Rv32ByteOpCoreChip::new(bitwise_lu_chip.clone(), Rv32ByteOpOpcode::default_offset()),
+            memory_controller.clone(),
+        );
+        inventory.add_executor(
+            byte_op_chip,
+            Rv32ByteOpOpcode::iter().map(VmOpcode::with_default_offset),
+        )?;
+
+        Ok(inventory)
+    }
+}
+
+impl<F: PrimeField32> VmExtension<F> for Rv32A {
+    type Executor = Rv32AExecutor<F>;
+    type Periphery = Rv32APeriphery<F>;
+
+    fn build(
+        &self,
+        builder: &mut VmInventoryBuilder<F>,
+    ) -> Result<VmInventory<Self::Executor, Self::Periphery>, VmInventoryError> {
+        let mut inventory = VmInventory::new();
+        let SystemPort {
+            execution_bus,
+            program_bus,
+            memory_controller,
+        } = builder.system_port();
+        let range_checker = builder.system_base().range_checker_chip.clone();
+        let bitwise_lu_chip = if let Some(chip) = builder
+            .find_chip::<Arc<BitwiseOperationLookupChip<8>>>()
+            .first()
+        {
+            Arc::clone(chip)
+        } else {
+            let bitwise_lu_bus = BitwiseOperationLookupBus::new(builder.new_bus_idx());
+            let chip = Arc::new(BitwiseOperationLookupChip::new(bitwise_lu_bus));
+            inventory.add_periphery_chip(chip.clone());
+            chip
+        };
+
+        let atomic_chip = Rv32AtomicChip::new(
+            Rv32AtomicAdapterChip::new(
+                execution_bus,
+                program_bus,
+                memory_controller.clone(),
+                range_checker.clone(),
+            ),
+            Rv32AtomicCoreChip::new(bitwise_lu_chip.clone(), Rv32AtomicOpcode::default_offset()),
+            memory_controller.clone(),
+        );
+        inventory.add_executor(
+            atomic_chip,
+            Rv32AtomicOpcode::iter().map(VmOpcode::with_default_offset),
+        )?;
+
+        Ok(inventory)
+    }
+}
+
+impl<F: PrimeField32> VmExtension<F> for Rv32F {
+    type Executor = Rv32FExecutor<F>;
+    type Periphery = Rv32FPeriphery<F>;
+
+    fn build(
+        &self,
+        builder: &mut VmInventoryBuilder<F>,
+    ) -> Result<VmInventory<Self::Executor, Self::Periphery>, VmInventoryError> {
+        let mut inventory = VmInventory::new();
+        let SystemPort {
+            execution_bus,
+            program_bus,
+            memory_controller,
+        } = builder.system_port();
+        let range_checker = builder.system_base().range_checker_chip.clone();
+        let bitwise_lu_chip = if let Some(chip) = builder
+            .find_chip::<Arc<BitwiseOperationLookupChip<8>>>()
+            .first()
+        {
+            Arc::clone(chip)
+        } else {
+            let bitwise_lu_bus = BitwiseOperationLookupBus::new(builder.new_bus_idx());
+            let chip = Arc::new(BitwiseOperationLookupChip::new(bitwise_lu_bus));
+            inventory.add_periphery_chip(chip.clone());
+            chip
+        };
+
+        let float_misc_chip = Rv32FloatMiscChip::new(
+            Rv32BaseAluAdapterChip::new(execution_bus, program_bus, memory_controller.clone()),
+            Rv32FloatMiscCoreChip::new(
+                bitwise_lu_chip.clone(),
+                Rv32FloatMiscOpcode::default_offset(),
+            ),
+            memory_controller.clone(),
+        );
+        inventory.add_executor(
+            float_misc_chip,
+            Rv32FloatMiscOpcode::iter().map(VmOpcode::with_default_offset),
+        )?;
+
+        let float_cmp_chip = Rv32FloatCmpChip::new(
+            Rv32BaseAluAdapterChip::new(execution_bus, program_bus, memory_controller.clone()),
+            Rv32FloatCmpCoreChip::new(
+                bitwise_lu_chip.clone(),
+                Rv32FloatCmpOpcode::default_offset(),
+            ),
+            memory_controller.clone(),
+        );
+        inventory.add_executor(
+            float_cmp_chip,
+            Rv32FloatCmpOpcode::iter().map(VmOpcode::with_default_offset),
+        )?;
+
+        let float_add_chip = Rv32FloatAddChip::new(
+            Rv32BaseAluAdapterChip::new(execution_bus, program_bus, memory_controller.clone()),
+            Rv32FloatAddCoreChip::new(
+                bitwise_lu_chip.clone(),
+                range_checker.clone(),
+                Rv32FloatAddOpcode::default_offset(),
+            ),
+            memory_controller.clone(),
+        );
+        inventory.add_executor(
+            float_add_chip,
+            Rv32FloatAddOpcode::iter().map(VmOpcode::with_default_offset),
+        )?;
+
+        let float_mul_chip = Rv32FloatMulChip::new(
+            Rv32BaseAluAdapterChip::new(execution_bus, program_bus, memory_controller.clone()),
+            Rv32FloatMulCoreChip::new(
+                bitwise_lu_chip.clone(),
+                range_checker.clone(),
+                Rv32FloatMulOpcode::default_offset(),
+            ),
+            memory_controller.clone(),
+        );
+        inventory.add_executor(
+            float_mul_chip,
+            Rv32FloatMulOpcode::iter().map(VmOpcode::with_default_offset),
+        )?;
+
+        let float_to_int_chip = Rv32FloatToIntChip::new(
+            Rv32BaseAluAdapterChip::new(execution_bus, program_bus, memory_controller.clone()),
+            Rv32FloatToIntCoreChip::new(
+                bitwise_lu_chip.clone(),
+                range_checker.clone(),
+                Rv32FloatToIntOpcode::default_offset(),
+            ),
+            memory_controller.clone(),
+        );
+        inventory.add_executor(
+            float_to_int_chip,
+            Rv32FloatToIntOpcode::iter().map(VmOpcode::with_default_offset),
+        )?;
+
+        let int_to_float_chip = Rv32IntToFloatChip::new(
+            Rv32BaseAluAdapterChip::new(execution_bus, program_bus, memory_controller.clone()),
+            Rv32IntToFloatCoreChip::new(
+                bitwise_lu_chip.clone(),
+                range_checker.clone(),
+                Rv32IntToFloatOpcode::default_offset(),
+            ),
+            memory_controller.clone(),
+        );
+        inventory.add_executor(
+            int_to_float_chip,
+            Rv32IntToFloatOpcode::iter().map(VmOpcode::with_default_offset),
         )?;
 
         Ok(inventory)
@@ -513,7 +1038,7 @@ impl<F: PrimeField32> VmExtension<F> for Rv32Io {
 }
 
 /// Phantom sub-executors
-mod phantom {
+pub mod phantom {
     use eyre::bail;
     use openvm_circuit::{
         arch::{PhantomSubExecutor, Streams},
@@ -522,10 +1047,27 @@ mod phantom {
     use openvm_instructions::PhantomDiscriminant;
     use openvm_stark_backend::p3_field::{Field, PrimeField32};
 
-    use crate::adapters::unsafe_read_rv32_register;
+    use crate::adapters::{compose, unsafe_read_rv32_register};
 
     pub struct Rv32HintInputSubEx;
     pub struct Rv32PrintStrSubEx;
+    pub struct Rv32HintStreamSubEx;
+
+    /// Replaces the hint stream with `hint`, prepended with its length as 4 bytes and padded to
+    /// a multiple of 4 bytes.
+    fn set_hint_stream<F: Field>(streams: &mut Streams<F>, mut hint: Vec<F>) {
+        streams.hint_stream.clear();
+        streams.hint_stream.extend(
+            (hint.len() as u32)
+                .to_le_bytes()
+                .iter()
+                .map(|b| F::from_canonical_u8(*b)),
+        );
+        // Extend by 0 for 4 byte alignment
+        let capacity = hint.len().div_ceil(4) * 4;
+        hint.resize(capacity, F::ZERO);
+        streams.hint_stream.extend(hint);
+    }
 
     impl<F: Field> PhantomSubExecutor<F> for Rv32HintInputSubEx {
         fn phantom_execute(
@@ -537,23 +1079,44 @@ mod phantom {
             _: F,
             _: u16,
         ) -> eyre::Result<()> {
-            let mut hint = match streams.input_stream.pop_front() {
+            let hint = match streams.next_input(&[]) {
                 Some(hint) => hint,
                 None => {
                     bail!("EndOfInputStream");
                 }
             };
-            streams.hint_stream.clear();
-            streams.hint_stream.extend(
-                (hint.len() as u32)
-                    .to_le_bytes()
-                    .iter()
-                    .map(|b| F::from_canonical_u8(*b)),
-            );
-            // Extend by 0 for 4 byte alignment
-            let capacity = hint.len().div_ceil(4) * 4;
-            hint.resize(capacity, F::ZERO);
-            streams.hint_stream.extend(hint);
+            set_hint_stream(streams, hint);
+            Ok(())
+        }
+    }
+
+    impl<F: PrimeField32> PhantomSubExecutor<F> for Rv32HintStreamSubEx {
+        fn phantom_execute(
+            &mut self,
+            memory: &MemoryController<F>,
+            streams: &mut Streams<F>,
+            _: PhantomDiscriminant,
+            a: F,
+            b: F,
+            _: u16,
+        ) -> eyre::Result<()> {
+            let stream = unsafe_read_rv32_register(memory, a) as usize;
+            if stream >= streams.num_streams() {
+                bail!("Unknown input stream {stream}");
+            }
+            let key_ref = unsafe_read_rv32_register(memory, b);
+            let [key_ptr, key_len] = [key_ref, key_ref + 4]
+                .map(|ptr| compose(memory.unsafe_read(F::TWO, F::from_canonical_u32(ptr))));
+            let key: Vec<F> = (key_ptr..key_ptr + key_len)
+                .map(|ptr| memory.unsafe_read_cell(F::TWO, F::from_canonical_u32(ptr)))
+                .collect();
+            let hint = match streams.next_input_from(stream, &key) {
+                Some(hint) => hint,
+                None => {
+                    bail!("EndOfInputStream {stream}");
+                }
+            };
+            set_hint_stream(streams, hint);
             Ok(())
         }
     }
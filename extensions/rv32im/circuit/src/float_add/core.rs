@@ -0,0 +1,563 @@
+use std::{
+    array,
+    borrow::{Borrow, BorrowMut},
+    sync::Arc,
+};
+
+use openvm_circuit::arch::{
+    AdapterAirContext, AdapterRuntimeContext, MinimalInstruction, Result, VmAdapterInterface,
+    VmCoreAir, VmCoreChip,
+};
+use openvm_circuit_primitives::{
+    bitwise_op_lookup::{BitwiseOperationLookupBus, BitwiseOperationLookupChip},
+    is_zero::{IsZeroIo, IsZeroSubAir},
+    utils::not,
+    var_range::{VariableRangeCheckerBus, VariableRangeCheckerChip},
+    SubAir, TraceSubRowGenerator,
+};
+use openvm_circuit_primitives_derive::AlignedBorrow;
+use openvm_instructions::{instruction::Instruction, UsizeOpcode};
+use openvm_rv32im_transpiler::Rv32FloatAddOpcode;
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    p3_air::{AirBuilder, BaseAir},
+    p3_field::{AbstractField, Field, PrimeField32},
+    rap::BaseAirWithPublicValues,
+};
+use strum::IntoEnumIterator;
+
+use crate::{
+    adapters::{compose, RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS},
+    float_utils::{
+        bits_to_expr, canonicalize_nan, effective_exp, eval_float_class, eval_float_decomp,
+        eval_float_result, f32_fields, f32_is_nan, f32_significand, request_float_decomp_range,
+        request_float_result_range, run_float_round, significand, FloatClassCols, FloatDecompCols,
+        FloatRoundAir, FloatRoundCols, FloatRoundRecord, F32_CANONICAL_NAN, F32_EXP_MAX,
+        F32_FRAC_BITS,
+    },
+};
+
+/// Number of bits of the significand of the smaller operand.
+const SMALL_SIG_BITS: usize = F32_FRAC_BITS + 1;
+/// Number of bits kept below the significand of the smaller operand when aligning it, the
+/// lowest of which is sticky.
+const ALIGN_BITS: usize = 4;
+/// Shifts of the smaller operand from 0 up to this one, which also stands for all larger
+/// shifts since they leave nothing but the sticky bit.
+const MAX_SHIFT: usize = SMALL_SIG_BITS + ALIGN_BITS - 1;
+/// Number of bits of the range check on shifts of at least [MAX_SHIFT].
+const SHIFT_BITS: usize = 8;
+
+/// Number of bits of the sum of the aligned significands.
+pub const FLOAT_ADD_SUM_BITS: usize = SMALL_SIG_BITS + ALIGN_BITS + 1;
+const FLOAT_ADD_ROUND_P_MIN: isize = -(F32_FRAC_BITS as isize);
+/// Positions of the rounded significand in the sum, from -23 up to one above any normal
+/// position.
+pub const FLOAT_ADD_ROUND_POSITIONS: usize = FLOAT_ADD_SUM_BITS + 1;
+
+#[repr(C)]
+#[derive(AlignedBorrow)]
+pub struct Rv32FloatAddCoreCols<T> {
+    pub a: [T; RV32_REGISTER_NUM_LIMBS],
+    pub b: [T; RV32_REGISTER_NUM_LIMBS],
+    pub c: [T; RV32_REGISTER_NUM_LIMBS],
+
+    pub a_decomp: FloatDecompCols<T>,
+    pub b_decomp: FloatDecompCols<T>,
+    pub b_class: FloatClassCols<T>,
+    pub c_decomp: FloatDecompCols<T>,
+    pub c_class: FloatClassCols<T>,
+
+    /// Sign of c, flipped for FSUB.S
+    pub c_sign: T,
+    /// Whether the magnitudes are subtracted
+    pub eff_sub: T,
+    /// Whether c has the larger magnitude
+    pub swap: T,
+    pub big_exp: T,
+    pub big_sig: T,
+    pub small_bits: [T; SMALL_SIG_BITS],
+    pub shift_marker: [T; MAX_SHIFT + 1],
+    /// Whether any bit of the smaller significand is shifted out
+    pub shift_sticky: T,
+    pub shift_sticky_inv: T,
+    /// The smaller significand times 2^4 shifted right by the difference of the exponents,
+    /// with its lowest bit being sticky
+    pub aligned: T,
+
+    pub b_is_nan: T,
+    pub c_is_nan: T,
+    pub b_is_inf: T,
+    pub c_is_inf: T,
+    pub any_nan: T,
+    /// Whether infinities of opposite signs are added
+    pub inf_inf: T,
+    /// Whether either operand is infinite or NaN
+    pub is_special: T,
+    pub sum_is_zero: T,
+    pub sum_is_zero_inv: T,
+    /// Whether the result is zero and no operand is special
+    pub is_zero: T,
+
+    /// Rounding of the sum, whose least significant bit has exponent big_exp - 4
+    pub round: FloatRoundCols<T, FLOAT_ADD_SUM_BITS, FLOAT_ADD_ROUND_POSITIONS>,
+
+    pub opcode_fadd_flag: T,
+    pub opcode_fsub_flag: T,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct Rv32FloatAddCoreAir {
+    pub bus: BitwiseOperationLookupBus,
+    pub range_bus: VariableRangeCheckerBus,
+    pub round: FloatRoundAir<FLOAT_ADD_SUM_BITS, FLOAT_ADD_ROUND_POSITIONS>,
+    offset: usize,
+}
+
+impl<F: Field> BaseAir<F> for Rv32FloatAddCoreAir {
+    fn width(&self) -> usize {
+        Rv32FloatAddCoreCols::<F>::width()
+    }
+}
+impl<F: Field> BaseAirWithPublicValues<F> for Rv32FloatAddCoreAir {}
+
+impl<AB, I> VmCoreAir<AB, I> for Rv32FloatAddCoreAir
+where
+    AB: InteractionBuilder,
+    I: VmAdapterInterface<AB::Expr>,
+    I::Reads: From<[[AB::Expr; RV32_REGISTER_NUM_LIMBS]; 2]>,
+    I::Writes: From<[[AB::Expr; RV32_REGISTER_NUM_LIMBS]; 1]>,
+    I::ProcessedInstruction: From<MinimalInstruction<AB::Expr>>,
+{
+    fn eval(
+        &self,
+        builder: &mut AB,
+        local_core: &[AB::Var],
+        _from_pc: AB::Var,
+    ) -> AdapterAirContext<AB::Expr, I> {
+        let cols: &Rv32FloatAddCoreCols<_> = local_core.borrow();
+        let flags = [cols.opcode_fadd_flag, cols.opcode_fsub_flag];
+
+        let is_valid = flags.iter().fold(AB::Expr::ZERO, |acc, &flag| {
+            builder.assert_bool(flag);
+            acc + flag.into()
+        });
+        builder.assert_bool(is_valid.clone());
+
+        let a = eval_float_result(builder, self.bus, &cols.a, &cols.a_decomp, is_valid.clone());
+        let b = eval_float_decomp(builder, self.bus, &cols.b, &cols.b_decomp, is_valid.clone());
+        eval_float_class(builder, &b, &cols.b_class, is_valid.clone());
+        let c = eval_float_decomp(builder, self.bus, &cols.c, &cols.c_decomp, is_valid.clone());
+        eval_float_class(builder, &c, &cols.c_class, is_valid.clone());
+
+        let xor = |x: AB::Expr, y: AB::Expr| x.clone() + y.clone() - AB::Expr::TWO * x * y;
+        builder.assert_eq(
+            cols.c_sign,
+            xor(c.sign.clone(), cols.opcode_fsub_flag.into()),
+        );
+        builder.assert_eq(cols.eff_sub, xor(b.sign.clone(), cols.c_sign.into()));
+
+        // The operand with the larger magnitude is the big one. The sum below is negative if
+        // swap is chosen the wrong way, which its decomposition into bits rules out.
+        builder.assert_bool(cols.swap);
+        let (b_exp, c_exp) = (
+            effective_exp::<AB>(&b, &cols.b_class),
+            effective_exp::<AB>(&c, &cols.c_class),
+        );
+        let (b_sig, c_sig) = (
+            significand::<AB>(&b, &cols.b_class),
+            significand::<AB>(&c, &cols.c_class),
+        );
+        builder.assert_eq(
+            cols.big_exp,
+            b_exp.clone() + cols.swap * (c_exp.clone() - b_exp.clone()),
+        );
+        builder.assert_eq(
+            cols.big_sig,
+            b_sig.clone() + cols.swap * (c_sig.clone() - b_sig.clone()),
+        );
+        for &bit in cols.small_bits.iter() {
+            builder.assert_bool(bit);
+        }
+        builder.assert_eq(
+            bits_to_expr::<AB>(&cols.small_bits),
+            b_sig + c_sig - cols.big_sig,
+        );
+
+        // The shift is the difference of the exponents, and the last marker stands for all
+        // shifts of at least MAX_SHIFT.
+        let shift = AB::Expr::TWO * cols.big_exp - b_exp - c_exp;
+        let mut marker_sum = AB::Expr::ZERO;
+        let mut aligned = AB::Expr::ZERO;
+        let mut below = AB::Expr::ZERO;
+        for (i, &marker) in cols.shift_marker.iter().enumerate() {
+            builder.assert_bool(marker);
+            marker_sum += marker.into();
+            if i < MAX_SHIFT {
+                builder.assert_zero(marker * (shift.clone() - AB::Expr::from_canonical_usize(i)));
+            }
+            let mut marker_aligned = AB::Expr::ZERO;
+            let mut marker_below = AB::Expr::ZERO;
+            for (j, &bit) in cols.small_bits.iter().enumerate() {
+                // Bit j of the smaller significand lands at position j + 3 - i of aligned / 2.
+                let pos = (j + ALIGN_BITS - 1) as isize - i as isize;
+                if pos >= 0 {
+                    marker_aligned += AB::Expr::from_canonical_u32(1 << (pos + 1)) * bit;
+                } else {
+                    marker_below += bit.into();
+                }
+            }
+            aligned += marker_aligned * marker;
+            below += marker_below * marker;
+        }
+        builder.assert_eq(marker_sum, is_valid.clone());
+        self.range_bus
+            .range_check(
+                shift - AB::Expr::from_canonical_usize(MAX_SHIFT),
+                SHIFT_BITS,
+            )
+            .eval(builder, cols.shift_marker[MAX_SHIFT]);
+        builder.assert_eq(cols.shift_sticky, below.clone() * cols.shift_sticky_inv);
+        builder.assert_zero(not::<AB::Expr>(cols.shift_sticky) * below);
+        builder.assert_eq(cols.aligned, aligned + cols.shift_sticky);
+
+        let sum = bits_to_expr::<AB>(&cols.round.bits);
+        builder.assert_eq(
+            sum.clone(),
+            AB::Expr::from_canonical_u32(1 << ALIGN_BITS) * cols.big_sig
+                + cols.aligned * (AB::Expr::ONE - AB::Expr::TWO * cols.eff_sub),
+        );
+
+        // Special operands and results.
+        let (b_is_max, c_is_max) = (cols.b_class.exp_is_max, cols.c_class.exp_is_max);
+        builder.assert_eq(
+            cols.b_is_nan,
+            b_is_max * not::<AB::Expr>(cols.b_class.frac_is_zero),
+        );
+        builder.assert_eq(
+            cols.c_is_nan,
+            c_is_max * not::<AB::Expr>(cols.c_class.frac_is_zero),
+        );
+        builder.assert_eq(cols.b_is_inf, b_is_max * cols.b_class.frac_is_zero);
+        builder.assert_eq(cols.c_is_inf, c_is_max * cols.c_class.frac_is_zero);
+        builder.assert_eq(
+            cols.any_nan,
+            cols.b_is_nan + cols.c_is_nan - cols.b_is_nan * cols.c_is_nan,
+        );
+        builder.assert_eq(cols.inf_inf, cols.b_is_inf * cols.c_is_inf * cols.eff_sub);
+        builder.assert_eq(cols.is_special, b_is_max + c_is_max - b_is_max * c_is_max);
+        IsZeroSubAir.eval(
+            builder,
+            (
+                IsZeroIo::new(sum, cols.sum_is_zero.into(), is_valid.clone()),
+                cols.sum_is_zero_inv,
+            ),
+        );
+        builder.assert_eq(
+            cols.is_zero,
+            cols.sum_is_zero * not::<AB::Expr>(cols.is_special),
+        );
+
+        let nan = F32_CANONICAL_NAN.to_le_bytes();
+        let is_nan = cols.any_nan + cols.inf_inf;
+        for i in 0..RV32_REGISTER_NUM_LIMBS {
+            builder
+                .when(is_nan.clone())
+                .assert_eq(cols.a[i], AB::Expr::from_canonical_u8(nan[i]));
+        }
+
+        // An infinite result has the sign of the infinite operand.
+        let is_inf = cols.is_special - is_nan;
+        let inf_sign =
+            cols.b_is_inf * b.sign.clone() + not::<AB::Expr>(cols.b_is_inf) * cols.c_sign;
+        let mut when_inf = builder.when(is_inf);
+        when_inf.assert_eq(a.exp.clone(), AB::Expr::from_canonical_u32(F32_EXP_MAX));
+        when_inf.assert_zero(a.frac.clone());
+        when_inf.assert_eq(a.sign.clone(), inf_sign);
+
+        // An exact zero is negative only if both operands are.
+        let mut when_zero = builder.when(cols.is_zero);
+        when_zero.assert_zero(a.exp.clone());
+        when_zero.assert_zero(a.frac.clone());
+        when_zero.assert_eq(a.sign.clone(), b.sign.clone() * cols.c_sign);
+
+        // Otherwise the result is the rounded sum with the sign of the big operand.
+        let is_finite = is_valid.clone() - cols.is_special - cols.is_zero;
+        builder
+            .when(is_finite.clone())
+            .assert_eq(a.sign, b.sign.clone() + cols.swap * (cols.c_sign - b.sign));
+        self.round.eval(
+            builder,
+            &cols.round,
+            is_finite,
+            cols.big_exp - AB::Expr::from_canonical_usize(ALIGN_BITS),
+            a.exp,
+            a.frac,
+        );
+
+        let expected_opcode = flags.iter().zip(Rv32FloatAddOpcode::iter()).fold(
+            AB::Expr::ZERO,
+            |acc, (flag, local_opcode)| {
+                acc + (*flag).into() * AB::Expr::from_canonical_u8(local_opcode as u8)
+            },
+        ) + AB::Expr::from_canonical_usize(self.offset);
+
+        AdapterAirContext {
+            to_pc: None,
+            reads: [cols.b.map(Into::into), cols.c.map(Into::into)].into(),
+            writes: [cols.a.map(Into::into)].into(),
+            instruction: MinimalInstruction {
+                is_valid,
+                opcode: expected_opcode,
+            }
+            .into(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Rv32FloatAddCoreRecord<T> {
+    pub opcode: Rv32FloatAddOpcode,
+    pub a: [T; RV32_REGISTER_NUM_LIMBS],
+    pub b: [T; RV32_REGISTER_NUM_LIMBS],
+    pub c: [T; RV32_REGISTER_NUM_LIMBS],
+}
+
+#[derive(Debug)]
+pub struct Rv32FloatAddCoreChip {
+    pub air: Rv32FloatAddCoreAir,
+    pub bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<RV32_CELL_BITS>>,
+    pub range_checker_chip: Arc<VariableRangeCheckerChip>,
+}
+
+impl Rv32FloatAddCoreChip {
+    pub fn new(
+        bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<RV32_CELL_BITS>>,
+        range_checker_chip: Arc<VariableRangeCheckerChip>,
+        offset: usize,
+    ) -> Self {
+        Self {
+            air: Rv32FloatAddCoreAir {
+                bus: bitwise_lookup_chip.bus(),
+                range_bus: range_checker_chip.bus(),
+                round: FloatRoundAir {
+                    bitwise_lookup_bus: bitwise_lookup_chip.bus(),
+                    range_bus: range_checker_chip.bus(),
+                    p_min: FLOAT_ADD_ROUND_P_MIN,
+                },
+                offset,
+            },
+            bitwise_lookup_chip,
+            range_checker_chip,
+        }
+    }
+}
+
+impl<F, I> VmCoreChip<F, I> for Rv32FloatAddCoreChip
+where
+    F: PrimeField32,
+    I: VmAdapterInterface<F>,
+    I::Reads: Into<[[F; RV32_REGISTER_NUM_LIMBS]; 2]>,
+    I::Writes: From<[[F; RV32_REGISTER_NUM_LIMBS]; 1]>,
+{
+    type Record = Rv32FloatAddCoreRecord<F>;
+    type Air = Rv32FloatAddCoreAir;
+
+    #[allow(clippy::type_complexity)]
+    fn execute_instruction(
+        &self,
+        instruction: &Instruction<F>,
+        _from_pc: u32,
+        reads: I::Reads,
+    ) -> Result<(AdapterRuntimeContext<F, I>, Self::Record)> {
+        let Instruction { opcode, .. } = instruction;
+        let local_opcode = Rv32FloatAddOpcode::from_usize(opcode.local_opcode_idx(self.air.offset));
+
+        let data: [[F; RV32_REGISTER_NUM_LIMBS]; 2] = reads.into();
+        let b = compose(data[0]);
+        let c = compose(data[1]);
+        let a = run_float_add(local_opcode, b, c);
+
+        request_float_result_range(&self.bitwise_lookup_chip, a);
+        request_float_decomp_range(&self.bitwise_lookup_chip, b);
+        request_float_decomp_range(&self.bitwise_lookup_chip, c);
+        let witness = FloatAddWitness::new(local_opcode, b, c);
+        if witness.shift >= MAX_SHIFT as u32 {
+            self.range_checker_chip
+                .add_count(witness.shift - MAX_SHIFT as u32, SHIFT_BITS);
+        }
+        if let Some(round) = witness.round {
+            round.request_range_checks(
+                self.air.round.p_max(),
+                &self.bitwise_lookup_chip,
+                &self.range_checker_chip,
+            );
+        }
+
+        let a = a.to_le_bytes().map(F::from_canonical_u8);
+        let output = AdapterRuntimeContext::without_pc([a]);
+        let record = Self::Record {
+            opcode: local_opcode,
+            a,
+            b: data[0],
+            c: data[1],
+        };
+
+        Ok((output, record))
+    }
+
+    fn get_opcode_name(&self, opcode: usize) -> String {
+        format!(
+            "{:?}",
+            Rv32FloatAddOpcode::from_usize(opcode - self.air.offset)
+        )
+    }
+
+    fn generate_trace_row(&self, row_slice: &mut [F], record: Self::Record) {
+        let row_slice: &mut Rv32FloatAddCoreCols<_> = row_slice.borrow_mut();
+        let a = compose(record.a);
+        let b = compose(record.b);
+        let c = compose(record.c);
+        let witness = FloatAddWitness::new(record.opcode, b, c);
+        let c_eff = witness.c;
+        let (_, b_exp, _) = f32_fields(b);
+        let (_, c_exp, _) = f32_fields(c);
+        let (b_is_max, c_is_max) = (b_exp == F32_EXP_MAX, c_exp == F32_EXP_MAX);
+        let (b_is_nan, c_is_nan) = (f32_is_nan(b), f32_is_nan(c));
+        let (b_is_inf, c_is_inf) = (b_is_max && !b_is_nan, c_is_max && !c_is_nan);
+        let is_special = b_is_max || c_is_max;
+
+        row_slice.a = record.a;
+        row_slice.b = record.b;
+        row_slice.c = record.c;
+        row_slice.a_decomp = FloatDecompCols::new(a);
+        row_slice.b_decomp = FloatDecompCols::new(b);
+        row_slice.b_class = FloatClassCols::new(b);
+        row_slice.c_decomp = FloatDecompCols::new(c);
+        row_slice.c_class = FloatClassCols::new(c);
+
+        row_slice.c_sign = F::from_bool(c_eff >> 31 == 1);
+        row_slice.eff_sub = F::from_bool(witness.eff_sub);
+        row_slice.swap = F::from_bool(witness.swap);
+        row_slice.big_exp = F::from_canonical_u32(witness.big_exp);
+        row_slice.big_sig = F::from_canonical_u32(witness.big_sig);
+        row_slice.small_bits = array::from_fn(|i| F::from_bool((witness.small_sig >> i) & 1 == 1));
+        row_slice.shift_marker =
+            array::from_fn(|i| F::from_bool(i as u32 == witness.shift.min(MAX_SHIFT as u32)));
+        row_slice.shift_sticky = F::from_bool(witness.shift_below != 0);
+        row_slice.shift_sticky_inv = F::from_canonical_u32(witness.shift_below)
+            .try_inverse()
+            .unwrap_or(F::ZERO);
+        row_slice.aligned = F::from_canonical_u32(witness.aligned);
+
+        row_slice.b_is_nan = F::from_bool(b_is_nan);
+        row_slice.c_is_nan = F::from_bool(c_is_nan);
+        row_slice.b_is_inf = F::from_bool(b_is_inf);
+        row_slice.c_is_inf = F::from_bool(c_is_inf);
+        row_slice.any_nan = F::from_bool(b_is_nan || c_is_nan);
+        row_slice.inf_inf = F::from_bool(b_is_inf && c_is_inf && witness.eff_sub);
+        row_slice.is_special = F::from_bool(is_special);
+        IsZeroSubAir.generate_subrow(
+            F::from_canonical_u32(witness.sum),
+            (&mut row_slice.sum_is_zero_inv, &mut row_slice.sum_is_zero),
+        );
+        row_slice.is_zero = F::from_bool(witness.sum == 0 && !is_special);
+        self.air.round.generate_subrow(
+            witness.sum as u64,
+            witness.round.as_ref(),
+            &mut row_slice.round,
+        );
+
+        row_slice.opcode_fadd_flag = F::from_bool(record.opcode == Rv32FloatAddOpcode::FADD_S);
+        row_slice.opcode_fsub_flag = F::from_bool(record.opcode == Rv32FloatAddOpcode::FSUB_S);
+    }
+
+    fn air(&self) -> &Self::Air {
+        &self.air
+    }
+}
+
+/// Intermediate values of the addition of b and c, with the sign of c flipped for FSUB.S.
+struct FloatAddWitness {
+    c: u32,
+    eff_sub: bool,
+    swap: bool,
+    big_exp: u32,
+    big_sig: u32,
+    small_sig: u32,
+    shift: u32,
+    /// Number of set bits of the smaller significand that are shifted out
+    shift_below: u32,
+    aligned: u32,
+    sum: u32,
+    /// Rounding of the sum, unless an operand is special or the sum is zero
+    round: Option<FloatRoundRecord>,
+}
+
+impl FloatAddWitness {
+    fn new(opcode: Rv32FloatAddOpcode, b: u32, c: u32) -> Self {
+        let c = match opcode {
+            Rv32FloatAddOpcode::FADD_S => c,
+            Rv32FloatAddOpcode::FSUB_S => c ^ (1 << 31),
+        };
+        let eff_sub = (b ^ c) >> 31 == 1;
+        let swap = c << 1 > b << 1;
+        let (big, small) = if swap { (c, b) } else { (b, c) };
+        let (big_sig, big_exp) = f32_significand(big);
+        let (small_sig, small_exp) = f32_significand(small);
+        let shift = big_exp - small_exp;
+
+        // Shifting by at least MAX_SHIFT leaves no bit above the sticky one.
+        let kept = ALIGN_BITS as u32 - 1;
+        let (aligned, shift_below) = if shift >= MAX_SHIFT as u32 {
+            (0, small_sig.count_ones())
+        } else if shift <= kept {
+            (small_sig << (kept - shift), 0)
+        } else {
+            (
+                small_sig >> (shift - kept),
+                (small_sig & ((1 << (shift - kept)) - 1)).count_ones(),
+            )
+        };
+        let aligned = 2 * aligned + (shift_below != 0) as u32;
+        let big_part = big_sig << ALIGN_BITS;
+        let sum = if eff_sub {
+            big_part - aligned
+        } else {
+            big_part + aligned
+        };
+
+        let (_, b_exp, _) = f32_fields(b);
+        let (_, c_exp, _) = f32_fields(c);
+        let is_special = b_exp == F32_EXP_MAX || c_exp == F32_EXP_MAX;
+        let round = (!is_special && sum != 0).then(|| {
+            run_float_round(
+                sum as u64,
+                big_exp as isize - ALIGN_BITS as isize,
+                FLOAT_ADD_ROUND_P_MIN + FLOAT_ADD_ROUND_POSITIONS as isize - 1,
+            )
+        });
+
+        Self {
+            c,
+            eff_sub,
+            swap,
+            big_exp,
+            big_sig,
+            small_sig,
+            shift,
+            shift_below,
+            aligned,
+            sum,
+            round,
+        }
+    }
+}
+
+pub fn run_float_add(opcode: Rv32FloatAddOpcode, b: u32, c: u32) -> u32 {
+    let (x, y) = (f32::from_bits(b), f32::from_bits(c));
+    canonicalize_nan(match opcode {
+        Rv32FloatAddOpcode::FADD_S => x + y,
+        Rv32FloatAddOpcode::FSUB_S => x - y,
+    })
+}
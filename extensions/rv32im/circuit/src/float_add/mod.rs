@@ -0,0 +1,11 @@
+use openvm_circuit::arch::VmChipWrapper;
+
+use super::adapters::Rv32BaseAluAdapterChip;
+
+mod core;
+pub use core::*;
+
+#[cfg(test)]
+mod tests;
+
+pub type Rv32FloatAddChip<F> = VmChipWrapper<F, Rv32BaseAluAdapterChip<F>, Rv32FloatAddCoreChip>;
@@ -0,0 +1,131 @@
+use std::sync::Arc;
+
+use openvm_circuit::arch::{
+    testing::{memory::gen_pointer, VmChipTestBuilder},
+    BITWISE_OP_LOOKUP_BUS,
+};
+use openvm_circuit_primitives::bitwise_op_lookup::{
+    BitwiseOperationLookupBus, BitwiseOperationLookupChip,
+};
+use openvm_instructions::{instruction::Instruction, VmOpcode};
+use openvm_rv32im_transpiler::Rv32FloatAddOpcode::{self, *};
+use openvm_stark_backend::p3_field::AbstractField;
+use openvm_stark_sdk::{p3_baby_bear::BabyBear, utils::create_seeded_rng};
+use rand::{rngs::StdRng, Rng};
+
+use super::{run_float_add, Rv32FloatAddChip, Rv32FloatAddCoreChip};
+use crate::{
+    adapters::{Rv32BaseAluAdapterChip, RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS},
+    float_utils::F32_CANONICAL_NAN,
+    test_utils::generate_f32,
+};
+
+type F = BabyBear;
+
+fn set_and_execute(
+    tester: &mut VmChipTestBuilder<F>,
+    chip: &mut Rv32FloatAddChip<F>,
+    rng: &mut StdRng,
+    opcode: Rv32FloatAddOpcode,
+    b: u32,
+    c: u32,
+) {
+    let rs1 = gen_pointer(rng, RV32_REGISTER_NUM_LIMBS);
+    let rs2 = loop {
+        let rs2 = gen_pointer(rng, RV32_REGISTER_NUM_LIMBS);
+        if rs2 != rs1 {
+            break rs2;
+        }
+    };
+    let rd = gen_pointer(rng, RV32_REGISTER_NUM_LIMBS);
+    tester.write(1, rs1, b.to_le_bytes().map(F::from_canonical_u8));
+    tester.write(1, rs2, c.to_le_bytes().map(F::from_canonical_u8));
+
+    tester.execute(
+        chip,
+        Instruction::from_usize(VmOpcode::from_usize(opcode as usize), [rd, rs1, rs2, 1, 1]),
+    );
+    assert_eq!(
+        run_float_add(opcode, b, c)
+            .to_le_bytes()
+            .map(F::from_canonical_u8),
+        tester.read::<RV32_REGISTER_NUM_LIMBS>(1, rd)
+    );
+}
+
+//////////////////////////////////////////////////////////////////////////////////////
+// POSITIVE TESTS
+//
+// Randomly generate computations and execute, ensuring that the generated trace
+// passes all constraints.
+//////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn rv32_float_add_rand_test() {
+    let mut rng = create_seeded_rng();
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = Arc::new(BitwiseOperationLookupChip::<RV32_CELL_BITS>::new(
+        bitwise_bus,
+    ));
+
+    let mut tester = VmChipTestBuilder::default();
+    let mut chip = Rv32FloatAddChip::<F>::new(
+        Rv32BaseAluAdapterChip::new(
+            tester.execution_bus(),
+            tester.program_bus(),
+            tester.memory_controller(),
+        ),
+        Rv32FloatAddCoreChip::new(
+            bitwise_chip.clone(),
+            tester.memory_controller().borrow().range_checker.clone(),
+            0,
+        ),
+        tester.memory_controller(),
+    );
+
+    for opcode in [FADD_S, FSUB_S] {
+        for _ in 0..100 {
+            let b = generate_f32(&mut rng);
+            // Operands of close magnitudes exercise cancellation.
+            let c = match rng.gen_range(0..4) {
+                0 => b ^ (1 << 31),
+                1 => (b ^ (1 << 31)).wrapping_add(rng.gen_range(0..4)),
+                _ => generate_f32(&mut rng),
+            };
+            set_and_execute(&mut tester, &mut chip, &mut rng, opcode, b, c);
+        }
+    }
+
+    let tester = tester.build().load(chip).load(bitwise_chip).finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
+//////////////////////////////////////////////////////////////////////////////////////
+// SANITY TESTS
+//
+// Ensure that the run_* function produces the expected results.
+//////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn run_float_add_sanity_test() {
+    let one = 1.0f32.to_bits();
+    let inf = f32::INFINITY.to_bits();
+    assert_eq!(
+        run_float_add(FADD_S, one, 2.0f32.to_bits()),
+        3.0f32.to_bits()
+    );
+    assert_eq!(run_float_add(FSUB_S, one, one), 0.0f32.to_bits());
+    assert_eq!(
+        run_float_add(FADD_S, (-0.0f32).to_bits(), (-0.0f32).to_bits()),
+        (-0.0f32).to_bits()
+    );
+    // 1 + 2^-24 is a tie that rounds to the even 1.
+    assert_eq!(run_float_add(FADD_S, one, 0x3380_0000), one);
+    assert_eq!(
+        run_float_add(FADD_S, f32::MAX.to_bits(), f32::MAX.to_bits()),
+        inf
+    );
+    assert_eq!(run_float_add(FSUB_S, inf, inf), F32_CANONICAL_NAN);
+    assert_eq!(run_float_add(FADD_S, 0x7f80_0001, one), F32_CANONICAL_NAN);
+    assert_eq!(run_float_add(FADD_S, 0x0000_0001, 0x0000_0001), 0x0000_0002);
+}
@@ -0,0 +1,436 @@
+use std::{
+    array,
+    borrow::{Borrow, BorrowMut},
+    sync::Arc,
+};
+
+use openvm_circuit::arch::{
+    AdapterAirContext, AdapterRuntimeContext, MinimalInstruction, Result, VmAdapterInterface,
+    VmCoreAir, VmCoreChip,
+};
+use openvm_circuit_primitives::{
+    bitwise_op_lookup::{BitwiseOperationLookupBus, BitwiseOperationLookupChip},
+    utils::not,
+};
+use openvm_circuit_primitives_derive::AlignedBorrow;
+use openvm_instructions::{instruction::Instruction, UsizeOpcode};
+use openvm_rv32im_transpiler::Rv32FloatCmpOpcode;
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    p3_air::{AirBuilder, BaseAir},
+    p3_field::{AbstractField, Field, PrimeField32},
+    rap::BaseAirWithPublicValues,
+};
+use strum::IntoEnumIterator;
+
+use crate::{
+    adapters::{compose, RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS},
+    float_utils::{
+        eval_float_class, eval_float_decomp, f32_fields, f32_is_nan, request_float_decomp_range,
+        FloatClassCols, FloatDecompCols, F32_CANONICAL_NAN,
+    },
+};
+
+#[repr(C)]
+#[derive(AlignedBorrow)]
+pub struct Rv32FloatCmpCoreCols<T> {
+    pub a: [T; RV32_REGISTER_NUM_LIMBS],
+    pub b: [T; RV32_REGISTER_NUM_LIMBS],
+    pub c: [T; RV32_REGISTER_NUM_LIMBS],
+
+    pub b_decomp: FloatDecompCols<T>,
+    pub b_class: FloatClassCols<T>,
+    pub c_decomp: FloatDecompCols<T>,
+    pub c_class: FloatClassCols<T>,
+
+    /// Whether |b| < |c|, comparing the bytes without the sign bit as in the less than chip
+    pub cmp_result: T,
+    pub diff_marker: [T; RV32_REGISTER_NUM_LIMBS],
+    pub diff_val: T,
+
+    pub b_is_nan: T,
+    pub c_is_nan: T,
+    pub any_nan: T,
+    pub both_nan: T,
+    pub b_is_zero: T,
+    pub c_is_zero: T,
+    pub both_zero: T,
+    /// Whether b and c have the same sign and magnitude
+    pub eq_total: T,
+    /// Whether b < c in the order where -0 < +0, ignoring NaNs
+    pub lt_total: T,
+    /// Whether b == c, ignoring NaNs
+    pub eq: T,
+    /// Whether b < c, ignoring NaNs
+    pub lt: T,
+    /// Whether the result of FMIN.S or FMAX.S is c rather than b
+    pub select_c: T,
+
+    pub opcode_feq_flag: T,
+    pub opcode_flt_flag: T,
+    pub opcode_fle_flag: T,
+    pub opcode_fmin_flag: T,
+    pub opcode_fmax_flag: T,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct Rv32FloatCmpCoreAir {
+    pub bus: BitwiseOperationLookupBus,
+    offset: usize,
+}
+
+impl<F: Field> BaseAir<F> for Rv32FloatCmpCoreAir {
+    fn width(&self) -> usize {
+        Rv32FloatCmpCoreCols::<F>::width()
+    }
+}
+impl<F: Field> BaseAirWithPublicValues<F> for Rv32FloatCmpCoreAir {}
+
+impl<AB, I> VmCoreAir<AB, I> for Rv32FloatCmpCoreAir
+where
+    AB: InteractionBuilder,
+    I: VmAdapterInterface<AB::Expr>,
+    I::Reads: From<[[AB::Expr; RV32_REGISTER_NUM_LIMBS]; 2]>,
+    I::Writes: From<[[AB::Expr; RV32_REGISTER_NUM_LIMBS]; 1]>,
+    I::ProcessedInstruction: From<MinimalInstruction<AB::Expr>>,
+{
+    fn eval(
+        &self,
+        builder: &mut AB,
+        local_core: &[AB::Var],
+        _from_pc: AB::Var,
+    ) -> AdapterAirContext<AB::Expr, I> {
+        let cols: &Rv32FloatCmpCoreCols<_> = local_core.borrow();
+        let flags = [
+            cols.opcode_feq_flag,
+            cols.opcode_flt_flag,
+            cols.opcode_fle_flag,
+            cols.opcode_fmin_flag,
+            cols.opcode_fmax_flag,
+        ];
+
+        let is_valid = flags.iter().fold(AB::Expr::ZERO, |acc, &flag| {
+            builder.assert_bool(flag);
+            acc + flag.into()
+        });
+        builder.assert_bool(is_valid.clone());
+
+        let b = eval_float_decomp(builder, self.bus, &cols.b, &cols.b_decomp, is_valid.clone());
+        eval_float_class(builder, &b, &cols.b_class, is_valid.clone());
+        let c = eval_float_decomp(builder, self.bus, &cols.c, &cols.c_decomp, is_valid.clone());
+        eval_float_class(builder, &c, &cols.c_class, is_valid.clone());
+
+        // Comparison of the magnitudes, whose most significant byte is range checked to 7 bits
+        // by the decompositions.
+        builder.assert_bool(cols.cmp_result);
+        let marker = &cols.diff_marker;
+        let mut prefix_sum = AB::Expr::ZERO;
+        for i in (0..RV32_REGISTER_NUM_LIMBS).rev() {
+            let diff = (if i == RV32_REGISTER_NUM_LIMBS - 1 {
+                cols.c[i] - AB::Expr::from_canonical_u32(1 << 7) * c.sign.clone() - cols.b[i]
+                    + AB::Expr::from_canonical_u32(1 << 7) * b.sign.clone()
+            } else {
+                cols.c[i] - cols.b[i]
+            }) * (AB::Expr::TWO * cols.cmp_result - AB::Expr::ONE);
+            prefix_sum += marker[i].into();
+            builder.assert_bool(marker[i]);
+            builder.assert_zero(not::<AB::Expr>(prefix_sum.clone()) * diff.clone());
+            builder.when(marker[i]).assert_eq(cols.diff_val, diff);
+        }
+        builder.assert_bool(prefix_sum.clone());
+        builder
+            .when(not::<AB::Expr>(prefix_sum.clone()))
+            .assert_zero(cols.cmp_result);
+        self.bus
+            .send_range(cols.diff_val - AB::Expr::ONE, AB::F::ZERO)
+            .eval(builder, prefix_sum.clone());
+
+        builder.assert_eq(
+            cols.b_is_nan,
+            cols.b_class.exp_is_max * not::<AB::Expr>(cols.b_class.frac_is_zero),
+        );
+        builder.assert_eq(
+            cols.c_is_nan,
+            cols.c_class.exp_is_max * not::<AB::Expr>(cols.c_class.frac_is_zero),
+        );
+        builder.assert_eq(
+            cols.any_nan,
+            cols.b_is_nan + cols.c_is_nan - cols.b_is_nan * cols.c_is_nan,
+        );
+        builder.assert_eq(cols.both_nan, cols.b_is_nan * cols.c_is_nan);
+        builder.assert_eq(
+            cols.b_is_zero,
+            cols.b_class.exp_is_zero * cols.b_class.frac_is_zero,
+        );
+        builder.assert_eq(
+            cols.c_is_zero,
+            cols.c_class.exp_is_zero * cols.c_class.frac_is_zero,
+        );
+        builder.assert_eq(cols.both_zero, cols.b_is_zero * cols.c_is_zero);
+
+        let b_sign = b.sign;
+        let c_sign = c.sign;
+        let signs_differ =
+            b_sign.clone() + c_sign.clone() - AB::Expr::TWO * b_sign.clone() * c_sign.clone();
+        builder.assert_eq(
+            cols.eq_total,
+            (is_valid.clone() - prefix_sum.clone()) * not::<AB::Expr>(signs_differ.clone()),
+        );
+        builder.assert_eq(cols.eq, cols.eq_total + cols.both_zero * signs_differ);
+        // A negative number is below a positive one, and of two negative numbers the one with
+        // the larger magnitude is smaller.
+        builder.assert_eq(
+            cols.lt_total,
+            not::<AB::Expr>(b_sign.clone()) * not::<AB::Expr>(c_sign.clone()) * cols.cmp_result
+                + b_sign.clone() * c_sign.clone() * (prefix_sum - cols.cmp_result)
+                + b_sign.clone() * not::<AB::Expr>(c_sign.clone()),
+        );
+        builder.assert_eq(
+            cols.lt,
+            cols.lt_total - b_sign * not::<AB::Expr>(c_sign) * cols.both_zero,
+        );
+
+        // Comparisons are false if either operand is NaN.
+        let is_ordered = not::<AB::Expr>(cols.any_nan);
+        builder
+            .when(cols.opcode_feq_flag)
+            .assert_eq(cols.a[0], cols.eq * is_ordered.clone());
+        builder
+            .when(cols.opcode_flt_flag)
+            .assert_eq(cols.a[0], cols.lt * is_ordered.clone());
+        builder
+            .when(cols.opcode_fle_flag)
+            .assert_eq(cols.a[0], (cols.lt + cols.eq) * is_ordered.clone());
+        let is_cmp = cols.opcode_feq_flag + cols.opcode_flt_flag + cols.opcode_fle_flag;
+        for i in 1..RV32_REGISTER_NUM_LIMBS {
+            builder.when(is_cmp.clone()).assert_zero(cols.a[i]);
+        }
+
+        // FMIN.S and FMAX.S return the operand that is not NaN if there is one, and the
+        // canonical NaN if both are NaN.
+        let only_b_nan = cols.b_is_nan * not::<AB::Expr>(cols.c_is_nan);
+        builder.when(cols.opcode_fmin_flag).assert_eq(
+            cols.select_c,
+            only_b_nan.clone()
+                + is_ordered.clone() * (AB::Expr::ONE - cols.lt_total - cols.eq_total),
+        );
+        builder
+            .when(cols.opcode_fmax_flag)
+            .assert_eq(cols.select_c, only_b_nan + is_ordered * cols.lt_total);
+        let nan = F32_CANONICAL_NAN.to_le_bytes();
+        for i in 0..RV32_REGISTER_NUM_LIMBS {
+            builder
+                .when(cols.opcode_fmin_flag + cols.opcode_fmax_flag)
+                .assert_eq(
+                    cols.a[i],
+                    cols.b[i]
+                        + cols.select_c * (cols.c[i] - cols.b[i])
+                        + cols.both_nan * (AB::Expr::from_canonical_u8(nan[i]) - cols.b[i]),
+                );
+        }
+
+        let expected_opcode = flags.iter().zip(Rv32FloatCmpOpcode::iter()).fold(
+            AB::Expr::ZERO,
+            |acc, (flag, local_opcode)| {
+                acc + (*flag).into() * AB::Expr::from_canonical_u8(local_opcode as u8)
+            },
+        ) + AB::Expr::from_canonical_usize(self.offset);
+
+        AdapterAirContext {
+            to_pc: None,
+            reads: [cols.b.map(Into::into), cols.c.map(Into::into)].into(),
+            writes: [cols.a.map(Into::into)].into(),
+            instruction: MinimalInstruction {
+                is_valid,
+                opcode: expected_opcode,
+            }
+            .into(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Rv32FloatCmpCoreRecord<T> {
+    pub opcode: Rv32FloatCmpOpcode,
+    pub a: [T; RV32_REGISTER_NUM_LIMBS],
+    pub b: [T; RV32_REGISTER_NUM_LIMBS],
+    pub c: [T; RV32_REGISTER_NUM_LIMBS],
+}
+
+#[derive(Debug)]
+pub struct Rv32FloatCmpCoreChip {
+    pub air: Rv32FloatCmpCoreAir,
+    pub bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<RV32_CELL_BITS>>,
+}
+
+impl Rv32FloatCmpCoreChip {
+    pub fn new(
+        bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<RV32_CELL_BITS>>,
+        offset: usize,
+    ) -> Self {
+        Self {
+            air: Rv32FloatCmpCoreAir {
+                bus: bitwise_lookup_chip.bus(),
+                offset,
+            },
+            bitwise_lookup_chip,
+        }
+    }
+}
+
+impl<F, I> VmCoreChip<F, I> for Rv32FloatCmpCoreChip
+where
+    F: PrimeField32,
+    I: VmAdapterInterface<F>,
+    I::Reads: Into<[[F; RV32_REGISTER_NUM_LIMBS]; 2]>,
+    I::Writes: From<[[F; RV32_REGISTER_NUM_LIMBS]; 1]>,
+{
+    type Record = Rv32FloatCmpCoreRecord<F>;
+    type Air = Rv32FloatCmpCoreAir;
+
+    #[allow(clippy::type_complexity)]
+    fn execute_instruction(
+        &self,
+        instruction: &Instruction<F>,
+        _from_pc: u32,
+        reads: I::Reads,
+    ) -> Result<(AdapterRuntimeContext<F, I>, Self::Record)> {
+        let Instruction { opcode, .. } = instruction;
+        let local_opcode = Rv32FloatCmpOpcode::from_usize(opcode.local_opcode_idx(self.air.offset));
+
+        let data: [[F; RV32_REGISTER_NUM_LIMBS]; 2] = reads.into();
+        let b = compose(data[0]);
+        let c = compose(data[1]);
+        let a = run_float_cmp(local_opcode, b, c)
+            .to_le_bytes()
+            .map(F::from_canonical_u8);
+
+        request_float_decomp_range(&self.bitwise_lookup_chip, b);
+        request_float_decomp_range(&self.bitwise_lookup_chip, c);
+        let (_, diff_idx, diff_val) = run_magnitude_cmp(b, c);
+        if diff_idx != RV32_REGISTER_NUM_LIMBS {
+            self.bitwise_lookup_chip.request_range(diff_val - 1, 0);
+        }
+
+        let output = AdapterRuntimeContext::without_pc([a]);
+        let record = Self::Record {
+            opcode: local_opcode,
+            a,
+            b: data[0],
+            c: data[1],
+        };
+
+        Ok((output, record))
+    }
+
+    fn get_opcode_name(&self, opcode: usize) -> String {
+        format!(
+            "{:?}",
+            Rv32FloatCmpOpcode::from_usize(opcode - self.air.offset)
+        )
+    }
+
+    fn generate_trace_row(&self, row_slice: &mut [F], record: Self::Record) {
+        let row_slice: &mut Rv32FloatCmpCoreCols<_> = row_slice.borrow_mut();
+        let b = compose(record.b);
+        let c = compose(record.c);
+        let (cmp_result, diff_idx, diff_val) = run_magnitude_cmp(b, c);
+        let (b_sign, _, _) = f32_fields(b);
+        let (c_sign, _, _) = f32_fields(c);
+        let (b_is_nan, c_is_nan) = (f32_is_nan(b), f32_is_nan(c));
+        let (b_is_zero, c_is_zero) = (b << 1 == 0, c << 1 == 0);
+        let both_zero = b_is_zero && c_is_zero;
+        let eq_total = b == c;
+        let lt_total = match (b_sign, c_sign) {
+            (false, false) => cmp_result,
+            (true, true) => !cmp_result && !eq_total,
+            (b_sign, _) => b_sign,
+        };
+
+        row_slice.a = record.a;
+        row_slice.b = record.b;
+        row_slice.c = record.c;
+        row_slice.b_decomp = FloatDecompCols::new(b);
+        row_slice.b_class = FloatClassCols::new(b);
+        row_slice.c_decomp = FloatDecompCols::new(c);
+        row_slice.c_class = FloatClassCols::new(c);
+        row_slice.cmp_result = F::from_bool(cmp_result);
+        row_slice.diff_marker = array::from_fn(|i| F::from_bool(i == diff_idx));
+        row_slice.diff_val = F::from_canonical_u32(diff_val);
+        row_slice.b_is_nan = F::from_bool(b_is_nan);
+        row_slice.c_is_nan = F::from_bool(c_is_nan);
+        row_slice.any_nan = F::from_bool(b_is_nan || c_is_nan);
+        row_slice.both_nan = F::from_bool(b_is_nan && c_is_nan);
+        row_slice.b_is_zero = F::from_bool(b_is_zero);
+        row_slice.c_is_zero = F::from_bool(c_is_zero);
+        row_slice.both_zero = F::from_bool(both_zero);
+        row_slice.eq_total = F::from_bool(eq_total);
+        row_slice.lt_total = F::from_bool(lt_total);
+        row_slice.eq = F::from_bool(eq_total || both_zero);
+        row_slice.lt = F::from_bool(lt_total && !both_zero);
+        let is_ordered = !b_is_nan && !c_is_nan;
+        row_slice.select_c = F::from_bool(match record.opcode {
+            Rv32FloatCmpOpcode::FMIN_S => {
+                (b_is_nan && !c_is_nan) || (is_ordered && !lt_total && !eq_total)
+            }
+            Rv32FloatCmpOpcode::FMAX_S => (b_is_nan && !c_is_nan) || (is_ordered && lt_total),
+            _ => false,
+        });
+        row_slice.opcode_feq_flag = F::from_bool(record.opcode == Rv32FloatCmpOpcode::FEQ_S);
+        row_slice.opcode_flt_flag = F::from_bool(record.opcode == Rv32FloatCmpOpcode::FLT_S);
+        row_slice.opcode_fle_flag = F::from_bool(record.opcode == Rv32FloatCmpOpcode::FLE_S);
+        row_slice.opcode_fmin_flag = F::from_bool(record.opcode == Rv32FloatCmpOpcode::FMIN_S);
+        row_slice.opcode_fmax_flag = F::from_bool(record.opcode == Rv32FloatCmpOpcode::FMAX_S);
+    }
+
+    fn air(&self) -> &Self::Air {
+        &self.air
+    }
+}
+
+// Returns (|b| < |c|, diff_idx, diff_val) as in the less than chip, for the bytes of b and c
+// without their sign bit.
+fn run_magnitude_cmp(b: u32, c: u32) -> (bool, usize, u32) {
+    let b = (b & !(1 << 31)).to_le_bytes();
+    let c = (c & !(1 << 31)).to_le_bytes();
+    (0..RV32_REGISTER_NUM_LIMBS)
+        .rev()
+        .find(|&i| b[i] != c[i])
+        .map_or((false, RV32_REGISTER_NUM_LIMBS, 0), |i| {
+            (b[i] < c[i], i, b[i].abs_diff(c[i]) as u32)
+        })
+}
+
+/// Whether b < c in the order where -0 < +0, for b and c that are not NaN.
+fn lt_total(b: u32, c: u32) -> bool {
+    let (x, y) = (f32::from_bits(b), f32::from_bits(c));
+    x < y || (x == y && b >> 31 > c >> 31)
+}
+
+pub fn run_float_cmp(opcode: Rv32FloatCmpOpcode, b: u32, c: u32) -> u32 {
+    let (x, y) = (f32::from_bits(b), f32::from_bits(c));
+    match opcode {
+        Rv32FloatCmpOpcode::FEQ_S => (x == y) as u32,
+        Rv32FloatCmpOpcode::FLT_S => (x < y) as u32,
+        Rv32FloatCmpOpcode::FLE_S => (x <= y) as u32,
+        Rv32FloatCmpOpcode::FMIN_S | Rv32FloatCmpOpcode::FMAX_S => {
+            match (f32_is_nan(b), f32_is_nan(c)) {
+                (true, true) => F32_CANONICAL_NAN,
+                (true, false) => c,
+                (false, true) => b,
+                (false, false) => {
+                    let select_c = if opcode == Rv32FloatCmpOpcode::FMIN_S {
+                        lt_total(c, b)
+                    } else {
+                        lt_total(b, c)
+                    };
+                    if select_c {
+                        c
+                    } else {
+                        b
+                    }
+                }
+            }
+        }
+    }
+}
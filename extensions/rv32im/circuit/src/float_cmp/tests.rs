@@ -0,0 +1,130 @@
+use std::sync::Arc;
+
+use openvm_circuit::arch::{
+    testing::{memory::gen_pointer, VmChipTestBuilder},
+    BITWISE_OP_LOOKUP_BUS,
+};
+use openvm_circuit_primitives::bitwise_op_lookup::{
+    BitwiseOperationLookupBus, BitwiseOperationLookupChip,
+};
+use openvm_instructions::{instruction::Instruction, VmOpcode};
+use openvm_rv32im_transpiler::Rv32FloatCmpOpcode::{self, *};
+use openvm_stark_backend::p3_field::AbstractField;
+use openvm_stark_sdk::{p3_baby_bear::BabyBear, utils::create_seeded_rng};
+use rand::{rngs::StdRng, Rng};
+
+use super::{run_float_cmp, Rv32FloatCmpChip, Rv32FloatCmpCoreChip};
+use crate::{
+    adapters::{Rv32BaseAluAdapterChip, RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS},
+    float_utils::F32_CANONICAL_NAN,
+    test_utils::generate_f32,
+};
+
+type F = BabyBear;
+
+fn set_and_execute(
+    tester: &mut VmChipTestBuilder<F>,
+    chip: &mut Rv32FloatCmpChip<F>,
+    rng: &mut StdRng,
+    opcode: Rv32FloatCmpOpcode,
+    b: u32,
+    c: u32,
+) {
+    let rs1 = gen_pointer(rng, RV32_REGISTER_NUM_LIMBS);
+    let rs2 = loop {
+        let rs2 = gen_pointer(rng, RV32_REGISTER_NUM_LIMBS);
+        if rs2 != rs1 {
+            break rs2;
+        }
+    };
+    let rd = gen_pointer(rng, RV32_REGISTER_NUM_LIMBS);
+    tester.write(1, rs1, b.to_le_bytes().map(F::from_canonical_u8));
+    tester.write(1, rs2, c.to_le_bytes().map(F::from_canonical_u8));
+
+    tester.execute(
+        chip,
+        Instruction::from_usize(VmOpcode::from_usize(opcode as usize), [rd, rs1, rs2, 1, 1]),
+    );
+    assert_eq!(
+        run_float_cmp(opcode, b, c)
+            .to_le_bytes()
+            .map(F::from_canonical_u8),
+        tester.read::<RV32_REGISTER_NUM_LIMBS>(1, rd)
+    );
+}
+
+//////////////////////////////////////////////////////////////////////////////////////
+// POSITIVE TESTS
+//
+// Randomly generate computations and execute, ensuring that the generated trace
+// passes all constraints.
+//////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn rv32_float_cmp_rand_test() {
+    let mut rng = create_seeded_rng();
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = Arc::new(BitwiseOperationLookupChip::<RV32_CELL_BITS>::new(
+        bitwise_bus,
+    ));
+
+    let mut tester = VmChipTestBuilder::default();
+    let mut chip = Rv32FloatCmpChip::<F>::new(
+        Rv32BaseAluAdapterChip::new(
+            tester.execution_bus(),
+            tester.program_bus(),
+            tester.memory_controller(),
+        ),
+        Rv32FloatCmpCoreChip::new(bitwise_chip.clone(), 0),
+        tester.memory_controller(),
+    );
+
+    for opcode in [FEQ_S, FLT_S, FLE_S, FMIN_S, FMAX_S] {
+        for _ in 0..50 {
+            let b = generate_f32(&mut rng);
+            // Equal operands and operands differing only in their sign are worth covering.
+            let c = match rng.gen_range(0..4) {
+                0 => b,
+                1 => b ^ (1 << 31),
+                _ => generate_f32(&mut rng),
+            };
+            set_and_execute(&mut tester, &mut chip, &mut rng, opcode, b, c);
+        }
+    }
+
+    let tester = tester.build().load(chip).load(bitwise_chip).finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
+//////////////////////////////////////////////////////////////////////////////////////
+// SANITY TESTS
+//
+// Ensure that the run_* function produces the expected results.
+//////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn run_float_cmp_sanity_test() {
+    let pos_zero = 0.0f32.to_bits();
+    let neg_zero = (-0.0f32).to_bits();
+    let one = 1.0f32.to_bits();
+    let neg_two = (-2.0f32).to_bits();
+    let nan = F32_CANONICAL_NAN;
+    let snan = 0x7f80_0001;
+
+    assert_eq!(run_float_cmp(FEQ_S, pos_zero, neg_zero), 1);
+    assert_eq!(run_float_cmp(FLT_S, neg_zero, pos_zero), 0);
+    assert_eq!(run_float_cmp(FLE_S, neg_zero, pos_zero), 1);
+    assert_eq!(run_float_cmp(FLT_S, neg_two, one), 1);
+    assert_eq!(run_float_cmp(FLE_S, one, neg_two), 0);
+    assert_eq!(run_float_cmp(FEQ_S, nan, nan), 0);
+    assert_eq!(run_float_cmp(FLE_S, one, nan), 0);
+
+    assert_eq!(run_float_cmp(FMIN_S, pos_zero, neg_zero), neg_zero);
+    assert_eq!(run_float_cmp(FMIN_S, neg_zero, pos_zero), neg_zero);
+    assert_eq!(run_float_cmp(FMAX_S, neg_zero, pos_zero), pos_zero);
+    assert_eq!(run_float_cmp(FMIN_S, one, neg_two), neg_two);
+    assert_eq!(run_float_cmp(FMAX_S, one, neg_two), one);
+    assert_eq!(run_float_cmp(FMIN_S, snan, one), one);
+    assert_eq!(run_float_cmp(FMAX_S, one, nan), one);
+    assert_eq!(run_float_cmp(FMAX_S, snan, snan), F32_CANONICAL_NAN);
+}
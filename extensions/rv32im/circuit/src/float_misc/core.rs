@@ -0,0 +1,316 @@
+use std::{
+    borrow::{Borrow, BorrowMut},
+    sync::Arc,
+};
+
+use openvm_circuit::arch::{
+    AdapterAirContext, AdapterRuntimeContext, MinimalInstruction, Result, VmAdapterInterface,
+    VmCoreAir, VmCoreChip,
+};
+use openvm_circuit_primitives::{
+    bitwise_op_lookup::{BitwiseOperationLookupBus, BitwiseOperationLookupChip},
+    utils::not,
+};
+use openvm_circuit_primitives_derive::AlignedBorrow;
+use openvm_instructions::{instruction::Instruction, UsizeOpcode};
+use openvm_rv32im_transpiler::Rv32FloatMiscOpcode;
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    p3_air::{AirBuilder, BaseAir},
+    p3_field::{AbstractField, Field, PrimeField32},
+    rap::BaseAirWithPublicValues,
+};
+use strum::IntoEnumIterator;
+
+use crate::{
+    adapters::{compose, RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS},
+    float_utils::{
+        eval_float_class, eval_float_decomp, f32_fields, request_float_decomp_range,
+        FloatClassCols, FloatDecompCols, F32_EXP_MAX,
+    },
+};
+
+/// Number of classes distinguished by `FCLASS.S`.
+pub const F32_NUM_CLASSES: usize = 10;
+
+#[repr(C)]
+#[derive(AlignedBorrow)]
+pub struct Rv32FloatMiscCoreCols<T> {
+    pub a: [T; RV32_REGISTER_NUM_LIMBS],
+    pub b: [T; RV32_REGISTER_NUM_LIMBS],
+    pub c: [T; RV32_REGISTER_NUM_LIMBS],
+
+    pub b_decomp: FloatDecompCols<T>,
+    pub b_class: FloatClassCols<T>,
+    pub c_sign: T,
+    /// One-hot class of b, in the bit order of the `FCLASS.S` result
+    pub class: [T; F32_NUM_CLASSES],
+
+    pub opcode_fsgnj_flag: T,
+    pub opcode_fsgnjn_flag: T,
+    pub opcode_fsgnjx_flag: T,
+    pub opcode_fclass_flag: T,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct Rv32FloatMiscCoreAir {
+    pub bus: BitwiseOperationLookupBus,
+    offset: usize,
+}
+
+impl<F: Field> BaseAir<F> for Rv32FloatMiscCoreAir {
+    fn width(&self) -> usize {
+        Rv32FloatMiscCoreCols::<F>::width()
+    }
+}
+impl<F: Field> BaseAirWithPublicValues<F> for Rv32FloatMiscCoreAir {}
+
+impl<AB, I> VmCoreAir<AB, I> for Rv32FloatMiscCoreAir
+where
+    AB: InteractionBuilder,
+    I: VmAdapterInterface<AB::Expr>,
+    I::Reads: From<[[AB::Expr; RV32_REGISTER_NUM_LIMBS]; 2]>,
+    I::Writes: From<[[AB::Expr; RV32_REGISTER_NUM_LIMBS]; 1]>,
+    I::ProcessedInstruction: From<MinimalInstruction<AB::Expr>>,
+{
+    fn eval(
+        &self,
+        builder: &mut AB,
+        local_core: &[AB::Var],
+        _from_pc: AB::Var,
+    ) -> AdapterAirContext<AB::Expr, I> {
+        let cols: &Rv32FloatMiscCoreCols<_> = local_core.borrow();
+        let flags = [
+            cols.opcode_fsgnj_flag,
+            cols.opcode_fsgnjn_flag,
+            cols.opcode_fsgnjx_flag,
+            cols.opcode_fclass_flag,
+        ];
+
+        let is_valid = flags.iter().fold(AB::Expr::ZERO, |acc, &flag| {
+            builder.assert_bool(flag);
+            acc + flag.into()
+        });
+        builder.assert_bool(is_valid.clone());
+
+        let b = eval_float_decomp(builder, self.bus, &cols.b, &cols.b_decomp, is_valid.clone());
+        eval_float_class(builder, &b, &cols.b_class, is_valid.clone());
+
+        // c_sign is the top bit of c as long as c[3] - 128 * c_sign < 2^7.
+        builder.assert_bool(cols.c_sign);
+        self.bus
+            .send_range(
+                AB::Expr::TWO
+                    * (cols.c[RV32_REGISTER_NUM_LIMBS - 1]
+                        - AB::Expr::from_canonical_u32(1 << 7) * cols.c_sign),
+                AB::Expr::ZERO,
+            )
+            .eval(builder, is_valid.clone());
+
+        // Sign injection keeps all bits of b but the sign.
+        let is_sign_injection =
+            cols.opcode_fsgnj_flag + cols.opcode_fsgnjn_flag + cols.opcode_fsgnjx_flag;
+        for i in 0..RV32_REGISTER_NUM_LIMBS - 1 {
+            builder
+                .when(is_sign_injection.clone())
+                .assert_eq(cols.a[i], cols.b[i]);
+        }
+        let b_sign = cols.b_decomp.sign;
+        let sign = cols.opcode_fsgnj_flag * cols.c_sign
+            + cols.opcode_fsgnjn_flag * not::<AB::Expr>(cols.c_sign)
+            + cols.opcode_fsgnjx_flag
+                * (b_sign + cols.c_sign - AB::Expr::TWO * b_sign * cols.c_sign);
+        builder.assert_zero(
+            is_sign_injection
+                * (cols.a[RV32_REGISTER_NUM_LIMBS - 1] - cols.b[RV32_REGISTER_NUM_LIMBS - 1]
+                    + AB::Expr::from_canonical_u32(1 << 7) * b_sign)
+                - AB::Expr::from_canonical_u32(1 << 7) * sign,
+        );
+
+        // The class of b follows from its sign, whether its exponent is 0 or 255 and whether
+        // its fraction is 0.
+        let neg = AB::Expr::from(b_sign);
+        let pos = not::<AB::Expr>(b_sign);
+        let exp_is_zero = cols.b_class.exp_is_zero;
+        let exp_is_max = cols.b_class.exp_is_max;
+        let frac_is_zero = cols.b_class.frac_is_zero;
+        let is_inf = exp_is_max * frac_is_zero;
+        let is_normal = AB::Expr::ONE - exp_is_zero - exp_is_max;
+        let is_subnormal = exp_is_zero * not::<AB::Expr>(frac_is_zero);
+        let is_zero = exp_is_zero * frac_is_zero;
+        let is_nan = exp_is_max * not::<AB::Expr>(frac_is_zero);
+        let class = [
+            neg.clone() * is_inf.clone(),
+            neg.clone() * is_normal.clone(),
+            neg.clone() * is_subnormal.clone(),
+            neg * is_zero.clone(),
+            pos.clone() * is_zero,
+            pos.clone() * is_subnormal,
+            pos.clone() * is_normal,
+            pos * is_inf,
+            is_nan.clone() * not::<AB::Expr>(cols.b_decomp.quiet),
+            is_nan * cols.b_decomp.quiet,
+        ];
+        for (&col, expr) in cols.class.iter().zip(class) {
+            builder.assert_eq(col, expr);
+        }
+        let mut when_fclass = builder.when(cols.opcode_fclass_flag);
+        when_fclass.assert_eq(
+            cols.a[0],
+            cols.class[..RV32_CELL_BITS]
+                .iter()
+                .rev()
+                .fold(AB::Expr::ZERO, |acc, &bit| acc * AB::Expr::TWO + bit),
+        );
+        when_fclass.assert_eq(
+            cols.a[1],
+            cols.class[RV32_CELL_BITS] + AB::Expr::TWO * cols.class[RV32_CELL_BITS + 1],
+        );
+        when_fclass.assert_zero(cols.a[2]);
+        when_fclass.assert_zero(cols.a[3]);
+
+        let expected_opcode = flags.iter().zip(Rv32FloatMiscOpcode::iter()).fold(
+            AB::Expr::ZERO,
+            |acc, (flag, local_opcode)| {
+                acc + (*flag).into() * AB::Expr::from_canonical_u8(local_opcode as u8)
+            },
+        ) + AB::Expr::from_canonical_usize(self.offset);
+
+        AdapterAirContext {
+            to_pc: None,
+            reads: [cols.b.map(Into::into), cols.c.map(Into::into)].into(),
+            writes: [cols.a.map(Into::into)].into(),
+            instruction: MinimalInstruction {
+                is_valid,
+                opcode: expected_opcode,
+            }
+            .into(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Rv32FloatMiscCoreRecord<T> {
+    pub opcode: Rv32FloatMiscOpcode,
+    pub a: [T; RV32_REGISTER_NUM_LIMBS],
+    pub b: [T; RV32_REGISTER_NUM_LIMBS],
+    pub c: [T; RV32_REGISTER_NUM_LIMBS],
+}
+
+#[derive(Debug)]
+pub struct Rv32FloatMiscCoreChip {
+    pub air: Rv32FloatMiscCoreAir,
+    pub bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<RV32_CELL_BITS>>,
+}
+
+impl Rv32FloatMiscCoreChip {
+    pub fn new(
+        bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<RV32_CELL_BITS>>,
+        offset: usize,
+    ) -> Self {
+        Self {
+            air: Rv32FloatMiscCoreAir {
+                bus: bitwise_lookup_chip.bus(),
+                offset,
+            },
+            bitwise_lookup_chip,
+        }
+    }
+}
+
+impl<F, I> VmCoreChip<F, I> for Rv32FloatMiscCoreChip
+where
+    F: PrimeField32,
+    I: VmAdapterInterface<F>,
+    I::Reads: Into<[[F; RV32_REGISTER_NUM_LIMBS]; 2]>,
+    I::Writes: From<[[F; RV32_REGISTER_NUM_LIMBS]; 1]>,
+{
+    type Record = Rv32FloatMiscCoreRecord<F>;
+    type Air = Rv32FloatMiscCoreAir;
+
+    #[allow(clippy::type_complexity)]
+    fn execute_instruction(
+        &self,
+        instruction: &Instruction<F>,
+        _from_pc: u32,
+        reads: I::Reads,
+    ) -> Result<(AdapterRuntimeContext<F, I>, Self::Record)> {
+        let Instruction { opcode, .. } = instruction;
+        let local_opcode =
+            Rv32FloatMiscOpcode::from_usize(opcode.local_opcode_idx(self.air.offset));
+
+        let data: [[F; RV32_REGISTER_NUM_LIMBS]; 2] = reads.into();
+        let b = compose(data[0]);
+        let c = compose(data[1]);
+        let a = run_float_misc(local_opcode, b, c)
+            .to_le_bytes()
+            .map(F::from_canonical_u8);
+
+        request_float_decomp_range(&self.bitwise_lookup_chip, b);
+        self.bitwise_lookup_chip
+            .request_range(2 * ((c >> 24) & 0x7f), 0);
+
+        let output = AdapterRuntimeContext::without_pc([a]);
+        let record = Self::Record {
+            opcode: local_opcode,
+            a,
+            b: data[0],
+            c: data[1],
+        };
+
+        Ok((output, record))
+    }
+
+    fn get_opcode_name(&self, opcode: usize) -> String {
+        format!(
+            "{:?}",
+            Rv32FloatMiscOpcode::from_usize(opcode - self.air.offset)
+        )
+    }
+
+    fn generate_trace_row(&self, row_slice: &mut [F], record: Self::Record) {
+        let row_slice: &mut Rv32FloatMiscCoreCols<_> = row_slice.borrow_mut();
+        let b = compose(record.b);
+        let c = compose(record.c);
+        row_slice.a = record.a;
+        row_slice.b = record.b;
+        row_slice.c = record.c;
+        row_slice.b_decomp = FloatDecompCols::new(b);
+        row_slice.b_class = FloatClassCols::new(b);
+        row_slice.c_sign = F::from_bool(c >> 31 == 1);
+        let class = f32_class(b);
+        row_slice.class = std::array::from_fn(|i| F::from_bool(i == class));
+        row_slice.opcode_fsgnj_flag = F::from_bool(record.opcode == Rv32FloatMiscOpcode::FSGNJ_S);
+        row_slice.opcode_fsgnjn_flag = F::from_bool(record.opcode == Rv32FloatMiscOpcode::FSGNJN_S);
+        row_slice.opcode_fsgnjx_flag = F::from_bool(record.opcode == Rv32FloatMiscOpcode::FSGNJX_S);
+        row_slice.opcode_fclass_flag = F::from_bool(record.opcode == Rv32FloatMiscOpcode::FCLASS_S);
+    }
+
+    fn air(&self) -> &Self::Air {
+        &self.air
+    }
+}
+
+/// Returns the index of the class of the float `x`, which is the bit set in the result of
+/// `FCLASS.S`.
+pub fn f32_class(x: u32) -> usize {
+    let (sign, exp, frac) = f32_fields(x);
+    let pos = if sign { 0 } else { 1 };
+    match (exp, frac) {
+        (F32_EXP_MAX, 0) => 7 * pos,
+        (F32_EXP_MAX, frac) => 8 + (frac >> 22) as usize,
+        (0, 0) => 3 + pos,
+        (0, _) => 2 + 3 * pos,
+        _ => 1 + 5 * pos,
+    }
+}
+
+pub fn run_float_misc(opcode: Rv32FloatMiscOpcode, b: u32, c: u32) -> u32 {
+    const SIGN: u32 = 1 << 31;
+    match opcode {
+        Rv32FloatMiscOpcode::FSGNJ_S => (b & !SIGN) | (c & SIGN),
+        Rv32FloatMiscOpcode::FSGNJN_S => (b & !SIGN) | (!c & SIGN),
+        Rv32FloatMiscOpcode::FSGNJX_S => b ^ (c & SIGN),
+        Rv32FloatMiscOpcode::FCLASS_S => 1 << f32_class(b),
+    }
+}
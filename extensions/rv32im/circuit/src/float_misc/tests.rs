@@ -0,0 +1,130 @@
+use std::sync::Arc;
+
+use openvm_circuit::arch::{
+    testing::{memory::gen_pointer, VmChipTestBuilder},
+    BITWISE_OP_LOOKUP_BUS,
+};
+use openvm_circuit_primitives::bitwise_op_lookup::{
+    BitwiseOperationLookupBus, BitwiseOperationLookupChip,
+};
+use openvm_instructions::{instruction::Instruction, VmOpcode};
+use openvm_rv32im_transpiler::Rv32FloatMiscOpcode::{self, *};
+use openvm_stark_backend::p3_field::AbstractField;
+use openvm_stark_sdk::{p3_baby_bear::BabyBear, utils::create_seeded_rng};
+use rand::rngs::StdRng;
+
+use super::{f32_class, run_float_misc, Rv32FloatMiscChip, Rv32FloatMiscCoreChip};
+use crate::{
+    adapters::{Rv32BaseAluAdapterChip, RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS},
+    test_utils::generate_f32,
+};
+
+type F = BabyBear;
+
+fn set_and_execute(
+    tester: &mut VmChipTestBuilder<F>,
+    chip: &mut Rv32FloatMiscChip<F>,
+    rng: &mut StdRng,
+    opcode: Rv32FloatMiscOpcode,
+    b: u32,
+    c: u32,
+) {
+    let rs1 = gen_pointer(rng, RV32_REGISTER_NUM_LIMBS);
+    let rd = gen_pointer(rng, RV32_REGISTER_NUM_LIMBS);
+    tester.write(1, rs1, b.to_le_bytes().map(F::from_canonical_u8));
+
+    // FCLASS.S has the immediate 0 as its second operand.
+    let (rs2, e, c) = if opcode == FCLASS_S {
+        (0, 0, 0)
+    } else {
+        let rs2 = loop {
+            let rs2 = gen_pointer(rng, RV32_REGISTER_NUM_LIMBS);
+            if rs2 != rs1 {
+                break rs2;
+            }
+        };
+        tester.write(1, rs2, c.to_le_bytes().map(F::from_canonical_u8));
+        (rs2, 1, c)
+    };
+
+    tester.execute(
+        chip,
+        Instruction::from_usize(VmOpcode::from_usize(opcode as usize), [rd, rs1, rs2, 1, e]),
+    );
+    assert_eq!(
+        run_float_misc(opcode, b, c)
+            .to_le_bytes()
+            .map(F::from_canonical_u8),
+        tester.read::<RV32_REGISTER_NUM_LIMBS>(1, rd)
+    );
+}
+
+//////////////////////////////////////////////////////////////////////////////////////
+// POSITIVE TESTS
+//
+// Randomly generate computations and execute, ensuring that the generated trace
+// passes all constraints.
+//////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn rv32_float_misc_rand_test() {
+    let mut rng = create_seeded_rng();
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = Arc::new(BitwiseOperationLookupChip::<RV32_CELL_BITS>::new(
+        bitwise_bus,
+    ));
+
+    let mut tester = VmChipTestBuilder::default();
+    let mut chip = Rv32FloatMiscChip::<F>::new(
+        Rv32BaseAluAdapterChip::new(
+            tester.execution_bus(),
+            tester.program_bus(),
+            tester.memory_controller(),
+        ),
+        Rv32FloatMiscCoreChip::new(bitwise_chip.clone(), 0),
+        tester.memory_controller(),
+    );
+
+    for opcode in [FSGNJ_S, FSGNJN_S, FSGNJX_S, FCLASS_S] {
+        for _ in 0..50 {
+            let b = generate_f32(&mut rng);
+            let c = generate_f32(&mut rng);
+            set_and_execute(&mut tester, &mut chip, &mut rng, opcode, b, c);
+        }
+    }
+
+    let tester = tester.build().load(chip).load(bitwise_chip).finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
+//////////////////////////////////////////////////////////////////////////////////////
+// SANITY TESTS
+//
+// Ensure that the run_* function produces the expected results.
+//////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn run_float_misc_sanity_test() {
+    let x = 1.5f32.to_bits();
+    let y = (-2.0f32).to_bits();
+    assert_eq!(run_float_misc(FSGNJ_S, x, y), (-1.5f32).to_bits());
+    assert_eq!(run_float_misc(FSGNJN_S, x, y), 1.5f32.to_bits());
+    assert_eq!(run_float_misc(FSGNJX_S, y, y), 2.0f32.to_bits());
+
+    let classes = [
+        f32::NEG_INFINITY.to_bits(),
+        (-1.0f32).to_bits(),
+        0x8000_0001,
+        (-0.0f32).to_bits(),
+        0.0f32.to_bits(),
+        0x0000_0001,
+        1.0f32.to_bits(),
+        f32::INFINITY.to_bits(),
+        0x7f80_0001,
+        0x7fc0_0000,
+    ];
+    for (i, x) in classes.into_iter().enumerate() {
+        assert_eq!(f32_class(x), i);
+        assert_eq!(run_float_misc(FCLASS_S, x, 0), 1 << i);
+    }
+}
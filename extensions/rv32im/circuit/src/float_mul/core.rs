@@ -0,0 +1,488 @@
+use std::{
+    array,
+    borrow::{Borrow, BorrowMut},
+    sync::Arc,
+};
+
+use openvm_circuit::arch::{
+    AdapterAirContext, AdapterRuntimeContext, MinimalInstruction, Result, VmAdapterInterface,
+    VmCoreAir, VmCoreChip,
+};
+use openvm_circuit_primitives::{
+    bitwise_op_lookup::{BitwiseOperationLookupBus, BitwiseOperationLookupChip},
+    utils::not,
+    var_range::{VariableRangeCheckerBus, VariableRangeCheckerChip},
+};
+use openvm_circuit_primitives_derive::AlignedBorrow;
+use openvm_instructions::{instruction::Instruction, UsizeOpcode};
+use openvm_rv32im_transpiler::Rv32FloatMulOpcode;
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    p3_air::{AirBuilder, BaseAir},
+    p3_field::{AbstractField, Field, PrimeField32},
+    rap::BaseAirWithPublicValues,
+};
+use strum::IntoEnumIterator;
+
+use crate::{
+    adapters::{compose, RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS},
+    float_utils::{
+        bits_to_expr, canonicalize_nan, effective_exp, eval_float_class, eval_float_decomp,
+        eval_float_result, f32_fields, f32_is_nan, f32_significand, request_float_decomp_range,
+        request_float_result_range, run_float_round, FloatClassCols, FloatDecompCols, FloatFields,
+        FloatRoundAir, FloatRoundCols, FloatRoundRecord, F32_CANONICAL_NAN, F32_EXP_MAX,
+        F32_FRAC_BITS, F32_LSB_EXP_BIAS,
+    },
+};
+
+/// The significands are multiplied as two limbs of this many bits.
+const LIMB_BITS: usize = 12;
+/// Number of bits of the carries of the product limbs.
+const CARRY_BITS: [usize; 2] = [LIMB_BITS, LIMB_BITS + 2];
+
+/// Number of bits of the product of the significands.
+pub const FLOAT_MUL_PRODUCT_BITS: usize = 4 * LIMB_BITS;
+/// Positions of the rounded significand in the product, from 0 up to one above its most
+/// significant bit, below which a subnormal result is zero.
+pub const FLOAT_MUL_ROUND_POSITIONS: usize = FLOAT_MUL_PRODUCT_BITS + 2;
+
+#[repr(C)]
+#[derive(AlignedBorrow)]
+pub struct Rv32FloatMulCoreCols<T> {
+    pub a: [T; RV32_REGISTER_NUM_LIMBS],
+    pub b: [T; RV32_REGISTER_NUM_LIMBS],
+    pub c: [T; RV32_REGISTER_NUM_LIMBS],
+
+    pub a_decomp: FloatDecompCols<T>,
+    pub b_decomp: FloatDecompCols<T>,
+    pub b_class: FloatClassCols<T>,
+    pub c_decomp: FloatDecompCols<T>,
+    pub c_class: FloatClassCols<T>,
+
+    /// Lower limb of the significand of b
+    pub b_sig_lo: T,
+    /// Upper limb of the fraction of b
+    pub b_frac_hi: T,
+    pub c_sig_lo: T,
+    pub c_frac_hi: T,
+    pub carry: [T; 2],
+
+    pub b_is_nan: T,
+    pub c_is_nan: T,
+    pub b_is_inf: T,
+    pub c_is_inf: T,
+    pub b_is_zero: T,
+    pub c_is_zero: T,
+    pub any_nan: T,
+    /// Whether an infinity is multiplied by zero
+    pub inf_zero: T,
+    /// Whether either operand is infinite or NaN
+    pub is_special: T,
+    /// Whether either operand is zero and no operand is special
+    pub is_zero: T,
+
+    /// Rounding of the product, whose least significant bit has exponent E_b + E_c - 300
+    pub round: FloatRoundCols<T, FLOAT_MUL_PRODUCT_BITS, FLOAT_MUL_ROUND_POSITIONS>,
+
+    pub opcode_fmul_flag: T,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct Rv32FloatMulCoreAir {
+    pub bus: BitwiseOperationLookupBus,
+    pub range_bus: VariableRangeCheckerBus,
+    pub round: FloatRoundAir<FLOAT_MUL_PRODUCT_BITS, FLOAT_MUL_ROUND_POSITIONS>,
+    offset: usize,
+}
+
+impl<F: Field> BaseAir<F> for Rv32FloatMulCoreAir {
+    fn width(&self) -> usize {
+        Rv32FloatMulCoreCols::<F>::width()
+    }
+}
+impl<F: Field> BaseAirWithPublicValues<F> for Rv32FloatMulCoreAir {}
+
+impl Rv32FloatMulCoreAir {
+    /// Constrains the limbs of the significand of a float, and returns them.
+    fn eval_sig_limbs<AB: InteractionBuilder>(
+        &self,
+        builder: &mut AB,
+        fields: &FloatFields<AB::Expr>,
+        class: &FloatClassCols<AB::Var>,
+        sig_lo: AB::Var,
+        frac_hi: AB::Var,
+        is_valid: AB::Expr,
+    ) -> [AB::Expr; 2] {
+        self.range_bus
+            .range_check(sig_lo, LIMB_BITS)
+            .eval(builder, is_valid.clone());
+        self.range_bus
+            .range_check(frac_hi, F32_FRAC_BITS - LIMB_BITS)
+            .eval(builder, is_valid);
+        builder.assert_eq(
+            fields.frac.clone(),
+            sig_lo + AB::Expr::from_canonical_u32(1 << LIMB_BITS) * frac_hi,
+        );
+        [
+            sig_lo.into(),
+            frac_hi
+                + AB::Expr::from_canonical_u32(1 << (F32_FRAC_BITS - LIMB_BITS))
+                    * not::<AB::Expr>(class.exp_is_zero),
+        ]
+    }
+}
+
+impl<AB, I> VmCoreAir<AB, I> for Rv32FloatMulCoreAir
+where
+    AB: InteractionBuilder,
+    I: VmAdapterInterface<AB::Expr>,
+    I::Reads: From<[[AB::Expr; RV32_REGISTER_NUM_LIMBS]; 2]>,
+    I::Writes: From<[[AB::Expr; RV32_REGISTER_NUM_LIMBS]; 1]>,
+    I::ProcessedInstruction: From<MinimalInstruction<AB::Expr>>,
+{
+    fn eval(
+        &self,
+        builder: &mut AB,
+        local_core: &[AB::Var],
+        _from_pc: AB::Var,
+    ) -> AdapterAirContext<AB::Expr, I> {
+        let cols: &Rv32FloatMulCoreCols<_> = local_core.borrow();
+        let flags = [cols.opcode_fmul_flag];
+
+        let is_valid = flags.iter().fold(AB::Expr::ZERO, |acc, &flag| {
+            builder.assert_bool(flag);
+            acc + flag.into()
+        });
+        builder.assert_bool(is_valid.clone());
+
+        let a = eval_float_result(builder, self.bus, &cols.a, &cols.a_decomp, is_valid.clone());
+        let b = eval_float_decomp(builder, self.bus, &cols.b, &cols.b_decomp, is_valid.clone());
+        eval_float_class(builder, &b, &cols.b_class, is_valid.clone());
+        let c = eval_float_decomp(builder, self.bus, &cols.c, &cols.c_decomp, is_valid.clone());
+        eval_float_class(builder, &c, &cols.c_class, is_valid.clone());
+
+        // The product of the significands, whose limbs are made of the bits being rounded.
+        let [b_lo, b_hi] = self.eval_sig_limbs(
+            builder,
+            &b,
+            &cols.b_class,
+            cols.b_sig_lo,
+            cols.b_frac_hi,
+            is_valid.clone(),
+        );
+        let [c_lo, c_hi] = self.eval_sig_limbs(
+            builder,
+            &c,
+            &cols.c_class,
+            cols.c_sig_lo,
+            cols.c_frac_hi,
+            is_valid.clone(),
+        );
+        let limbs: [AB::Expr; 4] = array::from_fn(|k| {
+            bits_to_expr::<AB>(&cols.round.bits[k * LIMB_BITS..(k + 1) * LIMB_BITS])
+        });
+        let radix = AB::Expr::from_canonical_u32(1 << LIMB_BITS);
+        builder.assert_eq(
+            limbs[0].clone() + radix.clone() * cols.carry[0],
+            b_lo.clone() * c_lo.clone(),
+        );
+        builder.assert_eq(
+            limbs[1].clone() + radix.clone() * cols.carry[1],
+            b_lo * c_hi.clone() + b_hi.clone() * c_lo + cols.carry[0],
+        );
+        builder.assert_eq(
+            limbs[2].clone() + radix * limbs[3].clone(),
+            b_hi * c_hi + cols.carry[1],
+        );
+        for (&carry, bits) in cols.carry.iter().zip(CARRY_BITS) {
+            self.range_bus
+                .range_check(carry, bits)
+                .eval(builder, is_valid.clone());
+        }
+
+        // Special operands and results.
+        let (b_is_max, c_is_max) = (cols.b_class.exp_is_max, cols.c_class.exp_is_max);
+        builder.assert_eq(
+            cols.b_is_nan,
+            b_is_max * not::<AB::Expr>(cols.b_class.frac_is_zero),
+        );
+        builder.assert_eq(
+            cols.c_is_nan,
+            c_is_max * not::<AB::Expr>(cols.c_class.frac_is_zero),
+        );
+        builder.assert_eq(cols.b_is_inf, b_is_max * cols.b_class.frac_is_zero);
+        builder.assert_eq(cols.c_is_inf, c_is_max * cols.c_class.frac_is_zero);
+        builder.assert_eq(
+            cols.b_is_zero,
+            cols.b_class.exp_is_zero * cols.b_class.frac_is_zero,
+        );
+        builder.assert_eq(
+            cols.c_is_zero,
+            cols.c_class.exp_is_zero * cols.c_class.frac_is_zero,
+        );
+        builder.assert_eq(
+            cols.any_nan,
+            cols.b_is_nan + cols.c_is_nan - cols.b_is_nan * cols.c_is_nan,
+        );
+        builder.assert_eq(
+            cols.inf_zero,
+            cols.b_is_inf * cols.c_is_zero + cols.b_is_zero * cols.c_is_inf,
+        );
+        builder.assert_eq(cols.is_special, b_is_max + c_is_max - b_is_max * c_is_max);
+        builder.assert_eq(
+            cols.is_zero,
+            (cols.b_is_zero + cols.c_is_zero - cols.b_is_zero * cols.c_is_zero)
+                * not::<AB::Expr>(cols.is_special),
+        );
+
+        let nan = F32_CANONICAL_NAN.to_le_bytes();
+        let is_nan = cols.any_nan + cols.inf_zero;
+        for i in 0..RV32_REGISTER_NUM_LIMBS {
+            builder
+                .when(is_nan.clone())
+                .assert_eq(cols.a[i], AB::Expr::from_canonical_u8(nan[i]));
+        }
+
+        // Any other result has the sign of the product.
+        builder.when(is_valid.clone() - is_nan.clone()).assert_eq(
+            a.sign.clone(),
+            b.sign.clone() + c.sign.clone() - AB::Expr::TWO * b.sign * c.sign,
+        );
+        let mut when_inf = builder.when(cols.is_special - is_nan);
+        when_inf.assert_eq(a.exp.clone(), AB::Expr::from_canonical_u32(F32_EXP_MAX));
+        when_inf.assert_zero(a.frac.clone());
+        let mut when_zero = builder.when(cols.is_zero);
+        when_zero.assert_zero(a.exp.clone());
+        when_zero.assert_zero(a.frac.clone());
+
+        let lsb_exp = effective_exp::<AB>(&b, &cols.b_class)
+            + effective_exp::<AB>(&c, &cols.c_class)
+            - AB::Expr::from_canonical_usize(F32_LSB_EXP_BIAS as usize);
+        self.round.eval(
+            builder,
+            &cols.round,
+            is_valid.clone() - cols.is_special - cols.is_zero,
+            lsb_exp,
+            a.exp,
+            a.frac,
+        );
+
+        let expected_opcode = flags.iter().zip(Rv32FloatMulOpcode::iter()).fold(
+            AB::Expr::ZERO,
+            |acc, (flag, local_opcode)| {
+                acc + (*flag).into() * AB::Expr::from_canonical_u8(local_opcode as u8)
+            },
+        ) + AB::Expr::from_canonical_usize(self.offset);
+
+        AdapterAirContext {
+            to_pc: None,
+            reads: [cols.b.map(Into::into), cols.c.map(Into::into)].into(),
+            writes: [cols.a.map(Into::into)].into(),
+            instruction: MinimalInstruction {
+                is_valid,
+                opcode: expected_opcode,
+            }
+            .into(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Rv32FloatMulCoreRecord<T> {
+    pub opcode: Rv32FloatMulOpcode,
+    pub a: [T; RV32_REGISTER_NUM_LIMBS],
+    pub b: [T; RV32_REGISTER_NUM_LIMBS],
+    pub c: [T; RV32_REGISTER_NUM_LIMBS],
+}
+
+#[derive(Debug)]
+pub struct Rv32FloatMulCoreChip {
+    pub air: Rv32FloatMulCoreAir,
+    pub bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<RV32_CELL_BITS>>,
+    pub range_checker_chip: Arc<VariableRangeCheckerChip>,
+}
+
+impl Rv32FloatMulCoreChip {
+    pub fn new(
+        bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<RV32_CELL_BITS>>,
+        range_checker_chip: Arc<VariableRangeCheckerChip>,
+        offset: usize,
+    ) -> Self {
+        Self {
+            air: Rv32FloatMulCoreAir {
+                bus: bitwise_lookup_chip.bus(),
+                range_bus: range_checker_chip.bus(),
+                round: FloatRoundAir {
+                    bitwise_lookup_bus: bitwise_lookup_chip.bus(),
+                    range_bus: range_checker_chip.bus(),
+                    p_min: 0,
+                },
+                offset,
+            },
+            bitwise_lookup_chip,
+            range_checker_chip,
+        }
+    }
+}
+
+impl<F, I> VmCoreChip<F, I> for Rv32FloatMulCoreChip
+where
+    F: PrimeField32,
+    I: VmAdapterInterface<F>,
+    I::Reads: Into<[[F; RV32_REGISTER_NUM_LIMBS]; 2]>,
+    I::Writes: From<[[F; RV32_REGISTER_NUM_LIMBS]; 1]>,
+{
+    type Record = Rv32FloatMulCoreRecord<F>;
+    type Air = Rv32FloatMulCoreAir;
+
+    #[allow(clippy::type_complexity)]
+    fn execute_instruction(
+        &self,
+        instruction: &Instruction<F>,
+        _from_pc: u32,
+        reads: I::Reads,
+    ) -> Result<(AdapterRuntimeContext<F, I>, Self::Record)> {
+        let Instruction { opcode, .. } = instruction;
+        let local_opcode = Rv32FloatMulOpcode::from_usize(opcode.local_opcode_idx(self.air.offset));
+
+        let data: [[F; RV32_REGISTER_NUM_LIMBS]; 2] = reads.into();
+        let b = compose(data[0]);
+        let c = compose(data[1]);
+        let a = run_float_mul(b, c);
+
+        request_float_result_range(&self.bitwise_lookup_chip, a);
+        for x in [b, c] {
+            request_float_decomp_range(&self.bitwise_lookup_chip, x);
+            let (_, _, frac) = f32_fields(x);
+            self.range_checker_chip
+                .add_count(frac & ((1 << LIMB_BITS) - 1), LIMB_BITS);
+            self.range_checker_chip
+                .add_count(frac >> LIMB_BITS, F32_FRAC_BITS - LIMB_BITS);
+        }
+        let witness = FloatMulWitness::new(b, c, self.air.round.p_max());
+        for (carry, bits) in witness.carry.into_iter().zip(CARRY_BITS) {
+            self.range_checker_chip.add_count(carry, bits);
+        }
+        if let Some(round) = witness.round {
+            round.request_range_checks(
+                self.air.round.p_max(),
+                &self.bitwise_lookup_chip,
+                &self.range_checker_chip,
+            );
+        }
+
+        let a = a.to_le_bytes().map(F::from_canonical_u8);
+        let output = AdapterRuntimeContext::without_pc([a]);
+        let record = Self::Record {
+            opcode: local_opcode,
+            a,
+            b: data[0],
+            c: data[1],
+        };
+
+        Ok((output, record))
+    }
+
+    fn get_opcode_name(&self, opcode: usize) -> String {
+        format!(
+            "{:?}",
+            Rv32FloatMulOpcode::from_usize(opcode - self.air.offset)
+        )
+    }
+
+    fn generate_trace_row(&self, row_slice: &mut [F], record: Self::Record) {
+        let row_slice: &mut Rv32FloatMulCoreCols<_> = row_slice.borrow_mut();
+        let a = compose(record.a);
+        let b = compose(record.b);
+        let c = compose(record.c);
+        let witness = FloatMulWitness::new(b, c, self.air.round.p_max());
+        let (_, b_exp, b_frac) = f32_fields(b);
+        let (_, c_exp, c_frac) = f32_fields(c);
+        let (b_is_nan, c_is_nan) = (f32_is_nan(b), f32_is_nan(c));
+        let (b_is_inf, c_is_inf) = (
+            b_exp == F32_EXP_MAX && !b_is_nan,
+            c_exp == F32_EXP_MAX && !c_is_nan,
+        );
+        let (b_is_zero, c_is_zero) = (b << 1 == 0, c << 1 == 0);
+        let is_special = b_exp == F32_EXP_MAX || c_exp == F32_EXP_MAX;
+
+        row_slice.a = record.a;
+        row_slice.b = record.b;
+        row_slice.c = record.c;
+        row_slice.a_decomp = FloatDecompCols::new(a);
+        row_slice.b_decomp = FloatDecompCols::new(b);
+        row_slice.b_class = FloatClassCols::new(b);
+        row_slice.c_decomp = FloatDecompCols::new(c);
+        row_slice.c_class = FloatClassCols::new(c);
+
+        row_slice.b_sig_lo = F::from_canonical_u32(b_frac & ((1 << LIMB_BITS) - 1));
+        row_slice.b_frac_hi = F::from_canonical_u32(b_frac >> LIMB_BITS);
+        row_slice.c_sig_lo = F::from_canonical_u32(c_frac & ((1 << LIMB_BITS) - 1));
+        row_slice.c_frac_hi = F::from_canonical_u32(c_frac >> LIMB_BITS);
+        row_slice.carry = witness.carry.map(F::from_canonical_u32);
+
+        row_slice.b_is_nan = F::from_bool(b_is_nan);
+        row_slice.c_is_nan = F::from_bool(c_is_nan);
+        row_slice.b_is_inf = F::from_bool(b_is_inf);
+        row_slice.c_is_inf = F::from_bool(c_is_inf);
+        row_slice.b_is_zero = F::from_bool(b_is_zero);
+        row_slice.c_is_zero = F::from_bool(c_is_zero);
+        row_slice.any_nan = F::from_bool(b_is_nan || c_is_nan);
+        row_slice.inf_zero = F::from_bool((b_is_inf && c_is_zero) || (b_is_zero && c_is_inf));
+        row_slice.is_special = F::from_bool(is_special);
+        row_slice.is_zero = F::from_bool((b_is_zero || c_is_zero) && !is_special);
+        self.air.round.generate_subrow(
+            witness.product,
+            witness.round.as_ref(),
+            &mut row_slice.round,
+        );
+
+        row_slice.opcode_fmul_flag = F::from_bool(record.opcode == Rv32FloatMulOpcode::FMUL_S);
+    }
+
+    fn air(&self) -> &Self::Air {
+        &self.air
+    }
+}
+
+/// Intermediate values of the multiplication of b and c.
+struct FloatMulWitness {
+    product: u64,
+    carry: [u32; 2],
+    /// Rounding of the product, unless an operand is special or zero
+    round: Option<FloatRoundRecord>,
+}
+
+impl FloatMulWitness {
+    fn new(b: u32, c: u32, p_max: isize) -> Self {
+        let (b_sig, b_exp) = f32_significand(b);
+        let (c_sig, c_exp) = f32_significand(c);
+        let mask = (1 << LIMB_BITS) - 1;
+        let (b_lo, b_hi) = (b_sig & mask, b_sig >> LIMB_BITS);
+        let (c_lo, c_hi) = (c_sig & mask, c_sig >> LIMB_BITS);
+        let carry0 = (b_lo * c_lo) >> LIMB_BITS;
+        let carry1 = (b_lo * c_hi + b_hi * c_lo + carry0) >> LIMB_BITS;
+        let product = b_sig as u64 * c_sig as u64;
+
+        let (_, b_fexp, _) = f32_fields(b);
+        let (_, c_fexp, _) = f32_fields(c);
+        let is_special = b_fexp == F32_EXP_MAX || c_fexp == F32_EXP_MAX;
+        let round = (!is_special && product != 0).then(|| {
+            run_float_round(
+                product,
+                b_exp as isize + c_exp as isize - F32_LSB_EXP_BIAS,
+                p_max,
+            )
+        });
+
+        Self {
+            product,
+            carry: [carry0, carry1],
+            round,
+        }
+    }
+}
+
+pub fn run_float_mul(b: u32, c: u32) -> u32 {
+    canonicalize_nan(f32::from_bits(b) * f32::from_bits(c))
+}
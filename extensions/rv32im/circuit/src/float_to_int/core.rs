@@ -0,0 +1,423 @@
+use std::{
+    array,
+    borrow::{Borrow, BorrowMut},
+    sync::Arc,
+};
+
+use openvm_circuit::arch::{
+    AdapterAirContext, AdapterRuntimeContext, MinimalInstruction, Result, VmAdapterInterface,
+    VmCoreAir, VmCoreChip,
+};
+use openvm_circuit_primitives::{
+    bitwise_op_lookup::{BitwiseOperationLookupBus, BitwiseOperationLookupChip},
+    utils::not,
+    var_range::{VariableRangeCheckerBus, VariableRangeCheckerChip},
+};
+use openvm_circuit_primitives_derive::AlignedBorrow;
+use openvm_instructions::{instruction::Instruction, utils::isize_to_field, UsizeOpcode};
+use openvm_rv32im_transpiler::Rv32FloatToIntOpcode;
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    p3_air::{AirBuilder, BaseAir},
+    p3_field::{AbstractField, Field, PrimeField32},
+    rap::BaseAirWithPublicValues,
+};
+use strum::IntoEnumIterator;
+
+use crate::{
+    adapters::{compose, RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS},
+    float_utils::{
+        bits_to_expr, effective_exp, eval_float_class, eval_float_decomp, f32_fields, f32_is_nan,
+        f32_significand, request_float_decomp_range, significand, FloatClassCols, FloatDecompCols,
+        F32_FRAC_BITS, F32_LSB_EXP_BIAS,
+    },
+};
+
+const SIG_BITS: usize = F32_FRAC_BITS + 1;
+/// Smallest shift of the significand that keeps any of its bits.
+const MIN_SHIFT: isize = -(SIG_BITS as isize);
+/// Largest shift of the significand whose result fits in 32 bits.
+const MAX_SHIFT: isize = (RV32_REGISTER_NUM_LIMBS * RV32_CELL_BITS - SIG_BITS) as isize;
+const NUM_SHIFTS: usize = (MAX_SHIFT - MIN_SHIFT + 1) as usize;
+/// Number of bits of the range checks on effective exponents out of the range of shifts.
+const EXP_BITS: usize = 8;
+
+#[repr(C)]
+#[derive(AlignedBorrow)]
+pub struct Rv32FloatToIntCoreCols<T> {
+    pub a: [T; RV32_REGISTER_NUM_LIMBS],
+    pub b: [T; RV32_REGISTER_NUM_LIMBS],
+    pub c: [T; RV32_REGISTER_NUM_LIMBS],
+
+    pub b_decomp: FloatDecompCols<T>,
+    pub b_class: FloatClassCols<T>,
+    pub sig_bits: [T; SIG_BITS],
+    /// Marks the shift E - 150 of the significand, if it is between -24 and 8
+    pub shift_marker: [T; NUM_SHIFTS],
+    /// Whether the shift is below -24, so that b truncates to zero
+    pub is_tiny: T,
+    /// Whether the shift is above 8, so that b is out of range of any result
+    pub is_huge: T,
+    /// Bytes of the magnitude of b truncated toward zero
+    pub mag: [T; RV32_REGISTER_NUM_LIMBS],
+    /// Carries of the negation of the magnitude
+    pub neg_carry: [T; RV32_REGISTER_NUM_LIMBS],
+
+    pub is_nan: T,
+    /// Whether b is positive or NaN, which saturates like a positive number
+    pub is_pos: T,
+    /// Whether the result is the largest integer of the output type
+    pub sat_pos: T,
+    /// Whether the result is the smallest signed integer
+    pub sat_neg: T,
+    /// Whether the result is the magnitude
+    pub out_mag: T,
+    /// Whether the result is the negated magnitude
+    pub out_neg: T,
+    /// Whether the result is zero because b is negative and the output is unsigned
+    pub out_zero: T,
+
+    pub opcode_fcvt_w_flag: T,
+    pub opcode_fcvt_wu_flag: T,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct Rv32FloatToIntCoreAir {
+    pub bus: BitwiseOperationLookupBus,
+    pub range_bus: VariableRangeCheckerBus,
+    offset: usize,
+}
+
+impl<F: Field> BaseAir<F> for Rv32FloatToIntCoreAir {
+    fn width(&self) -> usize {
+        Rv32FloatToIntCoreCols::<F>::width()
+    }
+}
+impl<F: Field> BaseAirWithPublicValues<F> for Rv32FloatToIntCoreAir {}
+
+impl<AB, I> VmCoreAir<AB, I> for Rv32FloatToIntCoreAir
+where
+    AB: InteractionBuilder,
+    I: VmAdapterInterface<AB::Expr>,
+    I::Reads: From<[[AB::Expr; RV32_REGISTER_NUM_LIMBS]; 2]>,
+    I::Writes: From<[[AB::Expr; RV32_REGISTER_NUM_LIMBS]; 1]>,
+    I::ProcessedInstruction: From<MinimalInstruction<AB::Expr>>,
+{
+    fn eval(
+        &self,
+        builder: &mut AB,
+        local_core: &[AB::Var],
+        _from_pc: AB::Var,
+    ) -> AdapterAirContext<AB::Expr, I> {
+        let cols: &Rv32FloatToIntCoreCols<_> = local_core.borrow();
+        let flags = [cols.opcode_fcvt_w_flag, cols.opcode_fcvt_wu_flag];
+
+        let is_valid = flags.iter().fold(AB::Expr::ZERO, |acc, &flag| {
+            builder.assert_bool(flag);
+            acc + flag.into()
+        });
+        builder.assert_bool(is_valid.clone());
+
+        let b = eval_float_decomp(builder, self.bus, &cols.b, &cols.b_decomp, is_valid.clone());
+        eval_float_class(builder, &b, &cols.b_class, is_valid.clone());
+        for &bit in cols.sig_bits.iter() {
+            builder.assert_bool(bit);
+        }
+        builder.assert_eq(
+            bits_to_expr::<AB>(&cols.sig_bits),
+            significand::<AB>(&b, &cols.b_class),
+        );
+
+        // The magnitude is the significand shifted by E - 150, truncating the bits shifted out.
+        let exp = effective_exp::<AB>(&b, &cols.b_class);
+        let shift = exp.clone() - AB::Expr::from_canonical_usize(F32_LSB_EXP_BIAS as usize);
+        let mut marker_sum = cols.is_tiny + cols.is_huge;
+        let mut mag: [AB::Expr; RV32_REGISTER_NUM_LIMBS] = array::from_fn(|_| AB::Expr::ZERO);
+        builder.assert_bool(cols.is_tiny);
+        builder.assert_bool(cols.is_huge);
+        for (i, &marker) in cols.shift_marker.iter().enumerate() {
+            builder.assert_bool(marker);
+            marker_sum += marker.into();
+            let k = MIN_SHIFT + i as isize;
+            builder
+                .assert_zero(marker * (shift.clone() - AB::Expr::from(isize_to_field::<AB::F>(k))));
+            let mut marker_mag: [AB::Expr; RV32_REGISTER_NUM_LIMBS] =
+                array::from_fn(|_| AB::Expr::ZERO);
+            for (j, &bit) in cols.sig_bits.iter().enumerate() {
+                let pos = j as isize + k;
+                if pos >= 0 {
+                    let pos = pos as usize;
+                    marker_mag[pos / RV32_CELL_BITS] +=
+                        AB::Expr::from_canonical_u32(1 << (pos % RV32_CELL_BITS)) * bit;
+                }
+            }
+            for (limb, marker_limb) in mag.iter_mut().zip(marker_mag) {
+                *limb += marker_limb * marker;
+            }
+        }
+        builder.assert_eq(marker_sum, is_valid.clone());
+        self.range_bus
+            .range_check(
+                AB::Expr::from_canonical_usize((F32_LSB_EXP_BIAS + MIN_SHIFT - 1) as usize)
+                    - exp.clone(),
+                EXP_BITS,
+            )
+            .eval(builder, cols.is_tiny);
+        self.range_bus
+            .range_check(
+                exp - AB::Expr::from_canonical_usize((F32_LSB_EXP_BIAS + MAX_SHIFT + 1) as usize),
+                EXP_BITS,
+            )
+            .eval(builder, cols.is_huge);
+        for (&col, limb) in cols.mag.iter().zip(mag) {
+            builder.assert_eq(col, limb);
+        }
+
+        // Out of range inputs saturate, with NaN saturating like a positive number.
+        let sign = b.sign;
+        builder.assert_eq(
+            cols.is_nan,
+            cols.b_class.exp_is_max * not::<AB::Expr>(cols.b_class.frac_is_zero),
+        );
+        builder.assert_eq(
+            cols.is_pos,
+            is_valid.clone() - sign.clone() * not::<AB::Expr>(cols.is_nan),
+        );
+        let (is_w, is_wu) = (cols.opcode_fcvt_w_flag, cols.opcode_fcvt_wu_flag);
+        // A signed result saturates once the shift is 8, since only -2^31 is then in range and
+        // it is also the saturated value.
+        let w_sat = cols.shift_marker[NUM_SHIFTS - 1] + cols.is_huge;
+        builder.assert_eq(
+            cols.sat_pos,
+            (is_w * w_sat.clone() + is_wu * cols.is_huge) * cols.is_pos,
+        );
+        builder.assert_eq(
+            cols.sat_neg,
+            is_w * w_sat.clone() * not::<AB::Expr>(cols.is_pos),
+        );
+        builder.assert_eq(
+            cols.out_mag,
+            (is_w * not::<AB::Expr>(w_sat.clone()) + is_wu * not::<AB::Expr>(cols.is_huge))
+                * not::<AB::Expr>(sign.clone()),
+        );
+        builder.assert_eq(cols.out_neg, is_w * not::<AB::Expr>(w_sat) * sign.clone());
+        builder.assert_eq(cols.out_zero, is_wu * sign * not::<AB::Expr>(cols.is_nan));
+
+        for i in 0..RV32_REGISTER_NUM_LIMBS {
+            let is_msb = i == RV32_REGISTER_NUM_LIMBS - 1;
+            let max_limb = AB::Expr::from_canonical_u32((1 << RV32_CELL_BITS) - 1);
+            let sat_pos_limb = if is_msb {
+                max_limb.clone() - AB::Expr::from_canonical_u32(1 << (RV32_CELL_BITS - 1)) * is_w
+            } else {
+                max_limb.clone()
+            };
+            builder
+                .when(cols.sat_pos)
+                .assert_eq(cols.a[i], sat_pos_limb);
+            builder.when(cols.sat_neg).assert_eq(
+                cols.a[i],
+                AB::Expr::from_canonical_u32(if is_msb { 1 << (RV32_CELL_BITS - 1) } else { 0 }),
+            );
+            builder.when(cols.out_mag).assert_eq(cols.a[i], cols.mag[i]);
+            builder.when(cols.out_zero).assert_zero(cols.a[i]);
+
+            // a + mag is 0 or 2^32, which the range checks on the bytes of a make unique.
+            builder.assert_bool(cols.neg_carry[i]);
+            let carry_in = if i == 0 {
+                AB::Expr::ZERO
+            } else {
+                cols.neg_carry[i - 1].into()
+            };
+            builder.when(cols.out_neg).assert_eq(
+                cols.a[i] + cols.mag[i] + carry_in,
+                AB::Expr::from_canonical_u32(1 << RV32_CELL_BITS) * cols.neg_carry[i],
+            );
+        }
+        for i in (0..RV32_REGISTER_NUM_LIMBS).step_by(2) {
+            self.bus
+                .send_range(cols.a[i], cols.a[i + 1])
+                .eval(builder, is_valid.clone());
+        }
+
+        let expected_opcode = flags.iter().zip(Rv32FloatToIntOpcode::iter()).fold(
+            AB::Expr::ZERO,
+            |acc, (flag, local_opcode)| {
+                acc + (*flag).into() * AB::Expr::from_canonical_u8(local_opcode as u8)
+            },
+        ) + AB::Expr::from_canonical_usize(self.offset);
+
+        AdapterAirContext {
+            to_pc: None,
+            reads: [cols.b.map(Into::into), cols.c.map(Into::into)].into(),
+            writes: [cols.a.map(Into::into)].into(),
+            instruction: MinimalInstruction {
+                is_valid,
+                opcode: expected_opcode,
+            }
+            .into(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Rv32FloatToIntCoreRecord<T> {
+    pub opcode: Rv32FloatToIntOpcode,
+    pub a: [T; RV32_REGISTER_NUM_LIMBS],
+    pub b: [T; RV32_REGISTER_NUM_LIMBS],
+    pub c: [T; RV32_REGISTER_NUM_LIMBS],
+}
+
+#[derive(Debug)]
+pub struct Rv32FloatToIntCoreChip {
+    pub air: Rv32FloatToIntCoreAir,
+    pub bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<RV32_CELL_BITS>>,
+    pub range_checker_chip: Arc<VariableRangeCheckerChip>,
+}
+
+impl Rv32FloatToIntCoreChip {
+    pub fn new(
+        bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<RV32_CELL_BITS>>,
+        range_checker_chip: Arc<VariableRangeCheckerChip>,
+        offset: usize,
+    ) -> Self {
+        Self {
+            air: Rv32FloatToIntCoreAir {
+                bus: bitwise_lookup_chip.bus(),
+                range_bus: range_checker_chip.bus(),
+                offset,
+            },
+            bitwise_lookup_chip,
+            range_checker_chip,
+        }
+    }
+}
+
+impl<F, I> VmCoreChip<F, I> for Rv32FloatToIntCoreChip
+where
+    F: PrimeField32,
+    I: VmAdapterInterface<F>,
+    I::Reads: Into<[[F; RV32_REGISTER_NUM_LIMBS]; 2]>,
+    I::Writes: From<[[F; RV32_REGISTER_NUM_LIMBS]; 1]>,
+{
+    type Record = Rv32FloatToIntCoreRecord<F>;
+    type Air = Rv32FloatToIntCoreAir;
+
+    #[allow(clippy::type_complexity)]
+    fn execute_instruction(
+        &self,
+        instruction: &Instruction<F>,
+        _from_pc: u32,
+        reads: I::Reads,
+    ) -> Result<(AdapterRuntimeContext<F, I>, Self::Record)> {
+        let Instruction { opcode, .. } = instruction;
+        let local_opcode =
+            Rv32FloatToIntOpcode::from_usize(opcode.local_opcode_idx(self.air.offset));
+
+        let data: [[F; RV32_REGISTER_NUM_LIMBS]; 2] = reads.into();
+        let b = compose(data[0]);
+        let a = run_float_to_int(local_opcode, b);
+
+        request_float_decomp_range(&self.bitwise_lookup_chip, b);
+        let a_bytes = a.to_le_bytes();
+        for i in (0..RV32_REGISTER_NUM_LIMBS).step_by(2) {
+            self.bitwise_lookup_chip
+                .request_range(a_bytes[i] as u32, a_bytes[i + 1] as u32);
+        }
+        let (_, exp) = f32_significand(b);
+        let shift = exp as isize - F32_LSB_EXP_BIAS;
+        if shift < MIN_SHIFT {
+            self.range_checker_chip.add_count(
+                (F32_LSB_EXP_BIAS + MIN_SHIFT - 1 - exp as isize) as u32,
+                EXP_BITS,
+            );
+        } else if shift > MAX_SHIFT {
+            self.range_checker_chip.add_count(
+                (exp as isize - F32_LSB_EXP_BIAS - MAX_SHIFT - 1) as u32,
+                EXP_BITS,
+            );
+        }
+
+        let a = a_bytes.map(F::from_canonical_u8);
+        let output = AdapterRuntimeContext::without_pc([a]);
+        let record = Self::Record {
+            opcode: local_opcode,
+            a,
+            b: data[0],
+            c: data[1],
+        };
+
+        Ok((output, record))
+    }
+
+    fn get_opcode_name(&self, opcode: usize) -> String {
+        format!(
+            "{:?}",
+            Rv32FloatToIntOpcode::from_usize(opcode - self.air.offset)
+        )
+    }
+
+    fn generate_trace_row(&self, row_slice: &mut [F], record: Self::Record) {
+        let row_slice: &mut Rv32FloatToIntCoreCols<_> = row_slice.borrow_mut();
+        let b = compose(record.b);
+        let (sign, _, _) = f32_fields(b);
+        let (sig, exp) = f32_significand(b);
+        let shift = exp as isize - F32_LSB_EXP_BIAS;
+        let in_range = (MIN_SHIFT..=MAX_SHIFT).contains(&shift);
+        let mag = if !in_range {
+            0
+        } else if shift >= 0 {
+            sig << shift
+        } else {
+            sig >> -shift
+        };
+        let is_nan = f32_is_nan(b);
+        let is_pos = !sign || is_nan;
+        let is_huge = shift > MAX_SHIFT;
+        let w_sat = shift >= MAX_SHIFT;
+        let is_w = record.opcode == Rv32FloatToIntOpcode::FCVT_W_S;
+
+        row_slice.a = record.a;
+        row_slice.b = record.b;
+        row_slice.c = record.c;
+        row_slice.b_decomp = FloatDecompCols::new(b);
+        row_slice.b_class = FloatClassCols::new(b);
+        row_slice.sig_bits = array::from_fn(|i| F::from_bool((sig >> i) & 1 == 1));
+        row_slice.shift_marker =
+            array::from_fn(|i| F::from_bool(in_range && MIN_SHIFT + i as isize == shift));
+        row_slice.is_tiny = F::from_bool(shift < MIN_SHIFT);
+        row_slice.is_huge = F::from_bool(is_huge);
+        row_slice.mag = mag.to_le_bytes().map(F::from_canonical_u8);
+        // The carry out of byte i is set exactly when some byte up to i of mag is nonzero.
+        let mag_bytes = mag.to_le_bytes();
+        row_slice.neg_carry = array::from_fn(|i| {
+            F::from_bool(is_w && sign && mag_bytes[..=i].iter().any(|&x| x != 0))
+        });
+
+        row_slice.is_nan = F::from_bool(is_nan);
+        row_slice.is_pos = F::from_bool(is_pos);
+        row_slice.sat_pos = F::from_bool((if is_w { w_sat } else { is_huge }) && is_pos);
+        row_slice.sat_neg = F::from_bool(is_w && w_sat && !is_pos);
+        row_slice.out_mag = F::from_bool((if is_w { !w_sat } else { !is_huge }) && !sign);
+        row_slice.out_neg = F::from_bool(is_w && !w_sat && sign);
+        row_slice.out_zero = F::from_bool(!is_w && sign && !is_nan);
+
+        row_slice.opcode_fcvt_w_flag = F::from_bool(is_w);
+        row_slice.opcode_fcvt_wu_flag = F::from_bool(!is_w);
+    }
+
+    fn air(&self) -> &Self::Air {
+        &self.air
+    }
+}
+
+pub fn run_float_to_int(opcode: Rv32FloatToIntOpcode, b: u32) -> u32 {
+    let x = f32::from_bits(b);
+    // Casts truncate toward zero and saturate, but map NaN to zero rather than to the largest
+    // integer.
+    match opcode {
+        Rv32FloatToIntOpcode::FCVT_W_S if x.is_nan() => i32::MAX as u32,
+        Rv32FloatToIntOpcode::FCVT_W_S => x as i32 as u32,
+        Rv32FloatToIntOpcode::FCVT_WU_S if x.is_nan() => u32::MAX,
+        Rv32FloatToIntOpcode::FCVT_WU_S => x as u32,
+    }
+}
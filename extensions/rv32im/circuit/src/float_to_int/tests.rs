@@ -0,0 +1,126 @@
+use std::sync::Arc;
+
+use openvm_circuit::arch::{
+    testing::{memory::gen_pointer, VmChipTestBuilder},
+    BITWISE_OP_LOOKUP_BUS,
+};
+use openvm_circuit_primitives::bitwise_op_lookup::{
+    BitwiseOperationLookupBus, BitwiseOperationLookupChip,
+};
+use openvm_instructions::{instruction::Instruction, VmOpcode};
+use openvm_rv32im_transpiler::Rv32FloatToIntOpcode::{self, *};
+use openvm_stark_backend::p3_field::AbstractField;
+use openvm_stark_sdk::{p3_baby_bear::BabyBear, utils::create_seeded_rng};
+use rand::{rngs::StdRng, Rng};
+
+use super::{run_float_to_int, Rv32FloatToIntChip, Rv32FloatToIntCoreChip};
+use crate::{
+    adapters::{Rv32BaseAluAdapterChip, RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS},
+    test_utils::generate_f32,
+};
+
+type F = BabyBear;
+
+fn set_and_execute(
+    tester: &mut VmChipTestBuilder<F>,
+    chip: &mut Rv32FloatToIntChip<F>,
+    rng: &mut StdRng,
+    opcode: Rv32FloatToIntOpcode,
+    b: u32,
+) {
+    let rs1 = gen_pointer(rng, RV32_REGISTER_NUM_LIMBS);
+    let rd = gen_pointer(rng, RV32_REGISTER_NUM_LIMBS);
+    tester.write(1, rs1, b.to_le_bytes().map(F::from_canonical_u8));
+
+    tester.execute(
+        chip,
+        Instruction::from_usize(VmOpcode::from_usize(opcode as usize), [rd, rs1, 0, 1, 0]),
+    );
+    assert_eq!(
+        run_float_to_int(opcode, b)
+            .to_le_bytes()
+            .map(F::from_canonical_u8),
+        tester.read::<RV32_REGISTER_NUM_LIMBS>(1, rd)
+    );
+}
+
+//////////////////////////////////////////////////////////////////////////////////////
+// POSITIVE TESTS
+//
+// Randomly generate computations and execute, ensuring that the generated trace
+// passes all constraints.
+//////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn rv32_float_to_int_rand_test() {
+    let mut rng = create_seeded_rng();
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = Arc::new(BitwiseOperationLookupChip::<RV32_CELL_BITS>::new(
+        bitwise_bus,
+    ));
+
+    let mut tester = VmChipTestBuilder::default();
+    let mut chip = Rv32FloatToIntChip::<F>::new(
+        Rv32BaseAluAdapterChip::new(
+            tester.execution_bus(),
+            tester.program_bus(),
+            tester.memory_controller(),
+        ),
+        Rv32FloatToIntCoreChip::new(
+            bitwise_chip.clone(),
+            tester.memory_controller().borrow().range_checker.clone(),
+            0,
+        ),
+        tester.memory_controller(),
+    );
+
+    for opcode in [FCVT_W_S, FCVT_WU_S] {
+        for _ in 0..100 {
+            // Exponents around the range of 32-bit integers are the interesting ones.
+            let b = if rng.gen_bool(0.5) {
+                (generate_f32(&mut rng) & 0x807f_ffff) | ((rng.gen_range(120..162)) << 23)
+            } else {
+                generate_f32(&mut rng)
+            };
+            set_and_execute(&mut tester, &mut chip, &mut rng, opcode, b);
+        }
+    }
+
+    let tester = tester.build().load(chip).load(bitwise_chip).finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
+//////////////////////////////////////////////////////////////////////////////////////
+// SANITY TESTS
+//
+// Ensure that the run_* function produces the expected results.
+//////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn run_float_to_int_sanity_test() {
+    let nan = 0x7fc0_0000;
+    assert_eq!(
+        run_float_to_int(FCVT_W_S, (-2.75f32).to_bits()),
+        -2i32 as u32
+    );
+    assert_eq!(run_float_to_int(FCVT_W_S, (-0.5f32).to_bits()), 0);
+    assert_eq!(
+        run_float_to_int(FCVT_W_S, 2147483648.0f32.to_bits()),
+        i32::MAX as u32
+    );
+    assert_eq!(
+        run_float_to_int(FCVT_W_S, (-2147483648.0f32).to_bits()),
+        i32::MIN as u32
+    );
+    assert_eq!(run_float_to_int(FCVT_W_S, nan), i32::MAX as u32);
+    assert_eq!(
+        run_float_to_int(FCVT_WU_S, 4294967040.0f32.to_bits()),
+        4294967040
+    );
+    assert_eq!(run_float_to_int(FCVT_WU_S, (-3.0f32).to_bits()), 0);
+    assert_eq!(
+        run_float_to_int(FCVT_WU_S, f32::INFINITY.to_bits()),
+        u32::MAX
+    );
+    assert_eq!(run_float_to_int(FCVT_WU_S, nan), u32::MAX);
+}
@@ -0,0 +1,547 @@
+//! Gadgets shared by the chips of the F extension.
+//!
+//! A single-precision float is handled as its sign, its 8-bit biased exponent `exp` and its
+//! 23-bit fraction `frac`. Its magnitude is `M * 2^(E - 150)`, where the significand `M` is
+//! `frac + 2^23` and the effective exponent `E` is `exp` for normal floats, while `M = frac` and
+//! `E = 1` for subnormal floats and zeros.
+
+use openvm_circuit_primitives::{
+    bitwise_op_lookup::{BitwiseOperationLookupBus, BitwiseOperationLookupChip},
+    is_zero::{IsZeroIo, IsZeroSubAir},
+    utils::not,
+    var_range::{VariableRangeCheckerBus, VariableRangeCheckerChip},
+    SubAir, TraceSubRowGenerator,
+};
+use openvm_circuit_primitives_derive::AlignedBorrow;
+use openvm_instructions::utils::isize_to_field;
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    p3_air::AirBuilder,
+    p3_field::{AbstractField, Field},
+};
+
+use crate::adapters::{RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS};
+
+pub const F32_FRAC_BITS: usize = 23;
+pub const F32_EXP_MAX: u32 = (1 << 8) - 1;
+/// Exponent of the least significant bit of the significand is `E - F32_LSB_EXP_BIAS`.
+pub const F32_LSB_EXP_BIAS: isize = 150;
+/// The NaN returned by all operations that produce a NaN.
+pub const F32_CANONICAL_NAN: u32 = 0x7fc0_0000;
+
+/// Number of bits of the range checks on differences of exponents in [FloatRoundAir].
+const EXP_DIFF_BITS: usize = 10;
+
+/// Returns the sign, biased exponent and fraction of the float `x`.
+pub fn f32_fields(x: u32) -> (bool, u32, u32) {
+    (
+        x >> 31 == 1,
+        (x >> F32_FRAC_BITS) & F32_EXP_MAX,
+        x & ((1 << F32_FRAC_BITS) - 1),
+    )
+}
+
+/// Returns the significand and effective exponent of the float `x`.
+pub fn f32_significand(x: u32) -> (u32, u32) {
+    let (_, exp, frac) = f32_fields(x);
+    if exp == 0 {
+        (frac, 1)
+    } else {
+        (frac + (1 << F32_FRAC_BITS), exp)
+    }
+}
+
+pub fn f32_is_nan(x: u32) -> bool {
+    let (_, exp, frac) = f32_fields(x);
+    exp == F32_EXP_MAX && frac != 0
+}
+
+/// Replaces NaN results of host float operations by [F32_CANONICAL_NAN].
+pub fn canonicalize_nan(x: f32) -> u32 {
+    if x.is_nan() {
+        F32_CANONICAL_NAN
+    } else {
+        x.to_bits()
+    }
+}
+
+/// The sign, biased exponent and fraction of a float.
+#[derive(Clone, Debug)]
+pub struct FloatFields<T> {
+    pub sign: T,
+    pub exp: T,
+    pub frac: T,
+}
+
+/// Decomposition of the bytes of a float. Its exponent and fraction are expressions in these
+/// columns and its bytes.
+#[repr(C)]
+#[derive(AlignedBorrow, Clone, Copy, Debug)]
+pub struct FloatDecompCols<T> {
+    pub sign: T,
+    /// Least significant bit of the exponent
+    pub exp_lo: T,
+    /// Most significant bit of the fraction, which is set in quiet NaNs
+    pub quiet: T,
+}
+
+impl<F: Field> FloatDecompCols<F> {
+    pub fn new(x: u32) -> Self {
+        Self {
+            sign: F::from_bool(x >> 31 == 1),
+            exp_lo: F::from_bool((x >> F32_FRAC_BITS) & 1 == 1),
+            quiet: F::from_bool((x >> (F32_FRAC_BITS - 1)) & 1 == 1),
+        }
+    }
+}
+
+/// Constrains `cols` to be the decomposition of the float with bytes `x`, and returns its fields.
+pub fn eval_float_decomp<AB: InteractionBuilder>(
+    builder: &mut AB,
+    bus: BitwiseOperationLookupBus,
+    x: &[AB::Var; RV32_REGISTER_NUM_LIMBS],
+    cols: &FloatDecompCols<AB::Var>,
+    is_valid: AB::Expr,
+) -> FloatFields<AB::Expr> {
+    builder.assert_bool(cols.sign);
+    builder.assert_bool(cols.exp_lo);
+    builder.assert_bool(cols.quiet);
+
+    // The bits are those of the bytes as long as exp_hi < 2^7 and frac_hi < 2^6.
+    let exp_hi = x[3] - AB::Expr::from_canonical_u32(1 << 7) * cols.sign;
+    let frac_hi = x[2]
+        - AB::Expr::from_canonical_u32(1 << 7) * cols.exp_lo
+        - AB::Expr::from_canonical_u32(1 << 6) * cols.quiet;
+    bus.send_range(
+        AB::Expr::TWO * exp_hi.clone(),
+        AB::Expr::from_canonical_u32(4) * frac_hi,
+    )
+    .eval(builder, is_valid);
+
+    FloatFields {
+        sign: cols.sign.into(),
+        exp: AB::Expr::TWO * exp_hi + cols.exp_lo,
+        frac: x[0]
+            + AB::Expr::from_canonical_u32(1 << RV32_CELL_BITS) * x[1]
+            + AB::Expr::from_canonical_u32(1 << (2 * RV32_CELL_BITS))
+                * (x[2] - AB::Expr::from_canonical_u32(1 << 7) * cols.exp_lo),
+    }
+}
+
+/// Requests the range check of [eval_float_decomp] for the float `x`.
+pub fn request_float_decomp_range(bitwise: &BitwiseOperationLookupChip<RV32_CELL_BITS>, x: u32) {
+    bitwise.request_range(2 * ((x >> 24) & 0x7f), 4 * ((x >> 16) & 0x3f));
+}
+
+/// Constrains the bytes `x` of a float computed by a chip to be bytes, and returns its fields as
+/// [eval_float_decomp] does.
+pub fn eval_float_result<AB: InteractionBuilder>(
+    builder: &mut AB,
+    bus: BitwiseOperationLookupBus,
+    x: &[AB::Var; RV32_REGISTER_NUM_LIMBS],
+    cols: &FloatDecompCols<AB::Var>,
+    is_valid: AB::Expr,
+) -> FloatFields<AB::Expr> {
+    bus.send_range(x[0], x[1]).eval(builder, is_valid.clone());
+    eval_float_decomp(builder, bus, x, cols, is_valid)
+}
+
+/// Requests the range checks of [eval_float_result] for the float `x`.
+pub fn request_float_result_range(bitwise: &BitwiseOperationLookupChip<RV32_CELL_BITS>, x: u32) {
+    bitwise.request_range(x & 0xff, (x >> 8) & 0xff);
+    request_float_decomp_range(bitwise, x);
+}
+
+/// Whether the exponent of a float is 0 or 255 and whether its fraction is 0, which together
+/// with its sign determine its class.
+#[repr(C)]
+#[derive(AlignedBorrow, Clone, Copy, Debug)]
+pub struct FloatClassCols<T> {
+    pub exp_is_zero: T,
+    pub exp_is_zero_inv: T,
+    pub exp_is_max: T,
+    pub exp_is_max_inv: T,
+    pub frac_is_zero: T,
+    pub frac_is_zero_inv: T,
+}
+
+impl<F: Field> FloatClassCols<F> {
+    pub fn new(x: u32) -> Self {
+        let (_, exp, frac) = f32_fields(x);
+        let mut cols = Self {
+            exp_is_zero: F::ZERO,
+            exp_is_zero_inv: F::ZERO,
+            exp_is_max: F::ZERO,
+            exp_is_max_inv: F::ZERO,
+            frac_is_zero: F::ZERO,
+            frac_is_zero_inv: F::ZERO,
+        };
+        IsZeroSubAir.generate_subrow(
+            F::from_canonical_u32(exp),
+            (&mut cols.exp_is_zero_inv, &mut cols.exp_is_zero),
+        );
+        IsZeroSubAir.generate_subrow(
+            F::from_canonical_u32(exp) - F::from_canonical_u32(F32_EXP_MAX),
+            (&mut cols.exp_is_max_inv, &mut cols.exp_is_max),
+        );
+        IsZeroSubAir.generate_subrow(
+            F::from_canonical_u32(frac),
+            (&mut cols.frac_is_zero_inv, &mut cols.frac_is_zero),
+        );
+        cols
+    }
+}
+
+pub fn eval_float_class<AB: AirBuilder>(
+    builder: &mut AB,
+    fields: &FloatFields<AB::Expr>,
+    cols: &FloatClassCols<AB::Var>,
+    is_valid: AB::Expr,
+) {
+    IsZeroSubAir.eval(
+        builder,
+        (
+            IsZeroIo::new(
+                fields.exp.clone(),
+                cols.exp_is_zero.into(),
+                is_valid.clone(),
+            ),
+            cols.exp_is_zero_inv,
+        ),
+    );
+    IsZeroSubAir.eval(
+        builder,
+        (
+            IsZeroIo::new(
+                fields.exp.clone() - AB::Expr::from_canonical_u32(F32_EXP_MAX),
+                cols.exp_is_max.into(),
+                is_valid.clone(),
+            ),
+            cols.exp_is_max_inv,
+        ),
+    );
+    IsZeroSubAir.eval(
+        builder,
+        (
+            IsZeroIo::new(fields.frac.clone(), cols.frac_is_zero.into(), is_valid),
+            cols.frac_is_zero_inv,
+        ),
+    );
+}
+
+/// The significand `M` of a float, of degree 1.
+pub fn significand<AB: AirBuilder>(
+    fields: &FloatFields<AB::Expr>,
+    class: &FloatClassCols<AB::Var>,
+) -> AB::Expr {
+    fields.frac.clone()
+        + AB::Expr::from_canonical_u32(1 << F32_FRAC_BITS) * not::<AB::Expr>(class.exp_is_zero)
+}
+
+/// The effective exponent `E` of a float, of degree 1.
+pub fn effective_exp<AB: AirBuilder>(
+    fields: &FloatFields<AB::Expr>,
+    class: &FloatClassCols<AB::Var>,
+) -> AB::Expr {
+    fields.exp.clone() + class.exp_is_zero
+}
+
+/// The little-endian number with bits `bits`.
+pub fn bits_to_expr<AB: AirBuilder>(bits: &[AB::Var]) -> AB::Expr {
+    bits.iter()
+        .rev()
+        .fold(AB::Expr::ZERO, |acc, &bit| acc * AB::Expr::TWO + bit)
+}
+
+/// Columns proving that a float is the rounding to nearest, ties to even, of the positive number
+/// `W * 2^(lsb_exp - 150)`, for an integer `W` given by its `N` bits.
+///
+/// The rounded significand is `W` shifted right by `p`, where `p` is 23 less than the position
+/// of the leading one of `W`, or `1 - lsb_exp` if the result is subnormal. `p` is marked among
+/// `P` consecutive positions starting at [FloatRoundAir::p_min], and the last of them also
+/// stands for all larger positions, below which the number rounds to zero.
+#[repr(C)]
+#[derive(AlignedBorrow)]
+pub struct FloatRoundCols<T, const N: usize, const P: usize> {
+    pub bits: [T; N],
+    pub lsb_marker: [T; P],
+    /// Bit of `W` at position `p - 1`
+    pub guard: T,
+    /// Bit of `W` at position `p`
+    pub lsb: T,
+    /// Whether any bit of `W` below position `p - 1` is set
+    pub sticky: T,
+    pub sticky_inv: T,
+    pub round_up: T,
+    pub is_subnormal: T,
+    /// Whether the exponent is too large even before rounding, so that the result is infinite
+    pub is_overflow: T,
+    /// Whether rounding up carries into the exponent
+    pub carry: T,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct FloatRoundAir<const N: usize, const P: usize> {
+    pub bitwise_lookup_bus: BitwiseOperationLookupBus,
+    pub range_bus: VariableRangeCheckerBus,
+    /// The smallest position of the least significant bit of the rounded significand. The
+    /// largest one, `p_min + P - 1`, must be above any position of a normal result.
+    pub p_min: isize,
+}
+
+impl<const N: usize, const P: usize> FloatRoundAir<N, P> {
+    /// Constrains the float with biased exponent `exp` and fraction `frac` to be the rounding of
+    /// `W * 2^(lsb_exp - 150)` if `enable` is set, in which case `W` must be nonzero. The bits of
+    /// `W` are always constrained to be boolean. `lsb_exp`, `exp` and `frac` must be of degree at
+    /// most 1.
+    pub fn eval<AB: InteractionBuilder>(
+        &self,
+        builder: &mut AB,
+        cols: &FloatRoundCols<AB::Var, N, P>,
+        enable: AB::Expr,
+        lsb_exp: AB::Expr,
+        exp: AB::Expr,
+        frac: AB::Expr,
+    ) {
+        for &bit in cols.bits.iter() {
+            builder.assert_bool(bit);
+        }
+        let bit = |j: isize| (0..N as isize).contains(&j).then(|| cols.bits[j as usize]);
+
+        // Every quantity below depends on p, and is the sum over the markers of the marker
+        // times its value for the marked position.
+        let mut marker_sum = AB::Expr::ZERO;
+        let mut position = AB::Expr::ZERO;
+        let mut above = AB::Expr::ZERO;
+        let mut significand = AB::Expr::ZERO;
+        let mut below = AB::Expr::ZERO;
+        let mut lead = AB::Expr::ZERO;
+        let mut guard = AB::Expr::ZERO;
+        let mut lsb = AB::Expr::ZERO;
+        for (i, &marker) in cols.lsb_marker.iter().enumerate() {
+            builder.assert_bool(marker);
+            marker_sum += marker.into();
+            let p = self.p_min + i as isize;
+            position += AB::Expr::from(isize_to_field::<AB::F>(p)) * marker;
+
+            let mut marker_above = AB::Expr::ZERO;
+            let mut marker_significand = AB::Expr::ZERO;
+            let mut marker_below = AB::Expr::ZERO;
+            for (j, &b) in cols.bits.iter().enumerate() {
+                let j = j as isize;
+                if j >= p + F32_FRAC_BITS as isize + 1 {
+                    marker_above += b.into();
+                } else if j >= p {
+                    marker_significand += AB::Expr::from_canonical_u32(1 << (j - p)) * b;
+                } else if j < p - 1 {
+                    marker_below += b.into();
+                }
+            }
+            above += marker_above * marker;
+            significand += marker_significand * marker;
+            below += marker_below * marker;
+            if let Some(b) = bit(p + F32_FRAC_BITS as isize) {
+                lead += b * marker;
+            }
+            if let Some(b) = bit(p - 1) {
+                guard += b * marker;
+            }
+            if let Some(b) = bit(p) {
+                lsb += b * marker;
+            }
+        }
+        builder.assert_eq(marker_sum, enable.clone());
+
+        // The significand has at most 24 bits, and exactly 24 unless the result is subnormal.
+        builder.assert_zero(above);
+        builder.assert_bool(cols.is_subnormal);
+        builder.assert_eq(lead, enable.clone() - cols.is_subnormal);
+
+        builder.assert_eq(cols.guard, guard);
+        builder.assert_eq(cols.lsb, lsb);
+        builder.assert_eq(cols.sticky, below.clone() * cols.sticky_inv);
+        builder.assert_zero(not::<AB::Expr>(cols.sticky) * below);
+        builder.assert_eq(
+            cols.round_up,
+            cols.guard * (cols.sticky + cols.lsb - cols.sticky * cols.lsb),
+        );
+
+        // The exponent of the least significant bit of the significand is e - 150. A subnormal
+        // result has e = 1, or e <= 1 if p is the largest position.
+        let e = position + lsb_exp;
+        let cap = cols.lsb_marker[P - 1];
+        builder.assert_zero(cols.is_subnormal * not::<AB::Expr>(cap) * (e.clone() - AB::Expr::ONE));
+        self.range_bus
+            .range_check(AB::Expr::ONE - e.clone(), EXP_DIFF_BITS)
+            .eval(builder, cap);
+
+        // A normal result has 1 <= e <= 254, unless it overflows with e >= 255.
+        builder.assert_bool(cols.is_overflow);
+        builder
+            .assert_zero(cols.is_overflow * (not::<AB::Expr>(enable.clone()) + cols.is_subnormal));
+        let is_normal = enable.clone() - cols.is_subnormal;
+        self.bitwise_lookup_bus
+            .send_range(
+                e.clone() - AB::Expr::ONE,
+                AB::Expr::from_canonical_u32(F32_EXP_MAX - 1) - e.clone(),
+            )
+            .eval(builder, is_normal.clone() - cols.is_overflow);
+        self.range_bus
+            .range_check(
+                e.clone() - AB::Expr::from_canonical_u32(F32_EXP_MAX),
+                EXP_DIFF_BITS,
+            )
+            .eval(builder, cols.is_overflow);
+
+        // Rounding up may carry into the exponent, possibly up to infinity.
+        builder.assert_bool(cols.carry);
+        let is_finite = enable - cols.is_overflow;
+        let hidden_bit = AB::Expr::from_canonical_u32(1 << F32_FRAC_BITS);
+        builder.assert_zero(
+            is_finite.clone()
+                * (frac.clone() + hidden_bit.clone() * cols.carry + hidden_bit * is_normal.clone()
+                    - significand
+                    - cols.round_up),
+        );
+        builder.assert_zero(is_finite * (exp.clone() - cols.carry - is_normal * e));
+        builder
+            .when(cols.is_overflow)
+            .assert_eq(exp, AB::Expr::from_canonical_u32(F32_EXP_MAX));
+        builder.when(cols.is_overflow).assert_zero(frac);
+    }
+
+    /// The largest position of the least significant bit of the rounded significand.
+    pub fn p_max(&self) -> isize {
+        self.p_min + P as isize - 1
+    }
+
+    pub fn generate_subrow<F: Field>(
+        &self,
+        w: u64,
+        record: Option<&FloatRoundRecord>,
+        cols: &mut FloatRoundCols<F, N, P>,
+    ) {
+        for (j, bit) in cols.bits.iter_mut().enumerate() {
+            *bit = F::from_bool((w >> j) & 1 == 1);
+        }
+        cols.lsb_marker = [F::ZERO; P];
+        let Some(record) = record else {
+            cols.guard = F::ZERO;
+            cols.lsb = F::ZERO;
+            cols.sticky = F::ZERO;
+            cols.sticky_inv = F::ZERO;
+            cols.round_up = F::ZERO;
+            cols.is_subnormal = F::ZERO;
+            cols.is_overflow = F::ZERO;
+            cols.carry = F::ZERO;
+            return;
+        };
+        cols.lsb_marker[(record.p - self.p_min) as usize] = F::ONE;
+        cols.guard = F::from_bool(record.guard);
+        cols.lsb = F::from_bool(record.lsb);
+        cols.sticky = F::from_bool(record.below != 0);
+        cols.sticky_inv = F::from_canonical_u32(record.below)
+            .try_inverse()
+            .unwrap_or(F::ZERO);
+        cols.round_up = F::from_bool(record.round_up);
+        cols.is_subnormal = F::from_bool(record.is_subnormal);
+        cols.is_overflow = F::from_bool(record.is_overflow);
+        cols.carry = F::from_bool(record.carry);
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct FloatRoundRecord {
+    /// Position in `W` of the least significant bit of the rounded significand
+    pub p: isize,
+    /// Exponent of that bit plus 150
+    pub e: isize,
+    pub guard: bool,
+    pub lsb: bool,
+    /// Number of set bits of `W` below the guard bit
+    pub below: u32,
+    pub round_up: bool,
+    pub is_subnormal: bool,
+    pub is_overflow: bool,
+    pub carry: bool,
+    /// Biased exponent of the result
+    pub exp: u32,
+    /// Fraction of the result
+    pub frac: u32,
+}
+
+/// Rounds the nonzero `W * 2^(lsb_exp - 150)` to nearest, ties to even, as constrained by
+/// [FloatRoundAir] with positions up to `p_max`.
+pub fn run_float_round(w: u64, lsb_exp: isize, p_max: isize) -> FloatRoundRecord {
+    debug_assert_ne!(w, 0);
+    let frac_bits = F32_FRAC_BITS as isize;
+    let lead = 63 - w.leading_zeros() as isize;
+    let (p, is_subnormal) = if lead - frac_bits + lsb_exp >= 1 {
+        (lead - frac_bits, false)
+    } else {
+        ((1 - lsb_exp).min(p_max), true)
+    };
+    let e = p + lsb_exp;
+    let bit = |j: isize| (0..64).contains(&j) && (w >> j) & 1 == 1;
+
+    let significand = (if p >= 0 { w >> p } else { w << -p }) as u32;
+    let guard = bit(p - 1);
+    let lsb = bit(p);
+    let below = if p >= 2 {
+        (w & ((1 << (p - 1)) - 1)).count_ones()
+    } else {
+        0
+    };
+    let round_up = guard && (below != 0 || lsb);
+    let is_overflow = !is_subnormal && e >= F32_EXP_MAX as isize;
+
+    let (exp, frac, carry) = if is_overflow {
+        (F32_EXP_MAX, 0, false)
+    } else {
+        let hidden_bit = if is_subnormal { 0 } else { 1 << F32_FRAC_BITS };
+        let rounded = significand - hidden_bit + round_up as u32;
+        let carry = rounded >> F32_FRAC_BITS == 1;
+        let exp = if is_subnormal { 0 } else { e as u32 };
+        (
+            exp + carry as u32,
+            rounded & ((1 << F32_FRAC_BITS) - 1),
+            carry,
+        )
+    };
+
+    FloatRoundRecord {
+        p,
+        e,
+        guard,
+        lsb,
+        below,
+        round_up,
+        is_subnormal,
+        is_overflow,
+        carry,
+        exp,
+        frac,
+    }
+}
+
+impl FloatRoundRecord {
+    /// Requests the range checks of [FloatRoundAir::eval] with positions up to `p_max`.
+    pub fn request_range_checks(
+        &self,
+        p_max: isize,
+        bitwise: &BitwiseOperationLookupChip<RV32_CELL_BITS>,
+        range_checker: &VariableRangeCheckerChip,
+    ) {
+        if self.p == p_max {
+            range_checker.add_count((1 - self.e) as u32, EXP_DIFF_BITS);
+        }
+        if self.is_overflow {
+            range_checker.add_count((self.e - F32_EXP_MAX as isize) as u32, EXP_DIFF_BITS);
+        } else if !self.is_subnormal {
+            bitwise.request_range(
+                (self.e - 1) as u32,
+                (F32_EXP_MAX as isize - 1 - self.e) as u32,
+            );
+        }
+    }
+}
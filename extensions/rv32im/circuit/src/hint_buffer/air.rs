@@ -0,0 +1,211 @@
+use std::borrow::Borrow;
+
+use openvm_circuit::{
+    arch::{ExecutionBridge, ExecutionState},
+    system::memory::{
+        offline_checker::{MemoryBridge, MemoryReadAuxCols, MemoryWriteAuxCols},
+        MemoryAddress,
+    },
+};
+use openvm_circuit_primitives::{
+    bitwise_op_lookup::BitwiseOperationLookupBus, utils::not, var_range::VariableRangeCheckerBus,
+};
+use openvm_circuit_primitives_derive::AlignedBorrow;
+use openvm_instructions::riscv::{RV32_MEMORY_AS, RV32_REGISTER_AS};
+use openvm_rv32im_transpiler::Rv32HintStoreOpcode;
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    p3_air::{Air, AirBuilder, BaseAir},
+    p3_field::{AbstractField, Field},
+    p3_matrix::Matrix,
+    rap::{BaseAirWithPublicValues, PartitionedBaseAir},
+};
+
+use crate::adapters::{abstract_compose, RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS};
+
+/// One row per word written by a HINT_BUFFER instruction. The first row of an instruction reads
+/// rs1 and writes the length word, and each following row writes the next data word.
+#[repr(C)]
+#[derive(Debug, Clone, AlignedBorrow)]
+pub struct Rv32HintBufferCols<T> {
+    pub is_valid: T,
+    /// Whether this row is the first row of an instruction
+    pub is_start: T,
+    /// Only set on the first row
+    pub pc: T,
+    /// The timestamp of the rs1 read on the first row, and one less than the timestamp of the
+    /// write of each row
+    pub timestamp: T,
+
+    // Only set on the first row:
+    pub rs1_ptr: T,
+    pub rs1_data: [T; RV32_REGISTER_NUM_LIMBS],
+    pub rs1_aux_cols: MemoryReadAuxCols<T, RV32_REGISTER_NUM_LIMBS>,
+    pub imm: T,
+    pub imm_sign: T,
+    /// Bits of the padding `4 * rem_words - len` of the buffer
+    pub pad_bits: [T; 2],
+
+    /// The memory pointer of this row's write, as two 16-bit limbs
+    pub mem_ptr_limbs: [T; 2],
+    pub data: [T; RV32_REGISTER_NUM_LIMBS],
+    pub write_aux: MemoryWriteAuxCols<T, RV32_REGISTER_NUM_LIMBS>,
+    /// Number of data words left to write after this row
+    pub rem_words: T,
+}
+
+#[derive(Clone, Copy, Debug, derive_new::new)]
+pub struct Rv32HintBufferAir {
+    pub execution_bridge: ExecutionBridge,
+    pub memory_bridge: MemoryBridge,
+    pub bitwise_lookup_bus: BitwiseOperationLookupBus,
+    pub range_bus: VariableRangeCheckerBus,
+    /// Maximum number of bits allowed for an address pointer
+    pub pointer_max_bits: usize,
+    pub(super) offset: usize,
+}
+
+impl<F: Field> BaseAirWithPublicValues<F> for Rv32HintBufferAir {}
+impl<F: Field> PartitionedBaseAir<F> for Rv32HintBufferAir {}
+impl<F: Field> BaseAir<F> for Rv32HintBufferAir {
+    fn width(&self) -> usize {
+        Rv32HintBufferCols::<F>::width()
+    }
+}
+
+impl<AB: InteractionBuilder> Air<AB> for Rv32HintBufferAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let (local, next) = (main.row_slice(0), main.row_slice(1));
+        let local: &Rv32HintBufferCols<AB::Var> = (*local).borrow();
+        let next: &Rv32HintBufferCols<AB::Var> = (*next).borrow();
+
+        builder.assert_bool(local.is_valid);
+        builder.assert_bool(local.is_start);
+        builder.when(local.is_start).assert_one(local.is_valid);
+        builder
+            .when_first_row()
+            .assert_eq(local.is_valid, local.is_start);
+
+        let mem_ptr = local.mem_ptr_limbs[0]
+            + local.mem_ptr_limbs[1] * AB::F::from_canonical_u32(1 << (RV32_CELL_BITS * 2));
+
+        // A valid row that does not start an instruction continues the instruction of the row
+        // before it, writing the next word.
+        let next_is_continue = next.is_valid - next.is_start;
+        let next_mem_ptr = next.mem_ptr_limbs[0]
+            + next.mem_ptr_limbs[1] * AB::F::from_canonical_u32(1 << (RV32_CELL_BITS * 2));
+        let mut continue_builder = builder.when(next_is_continue.clone());
+        continue_builder.assert_one(local.is_valid);
+        continue_builder.assert_eq(next.rem_words, local.rem_words - AB::Expr::ONE);
+        continue_builder.assert_eq(
+            next_mem_ptr,
+            mem_ptr.clone() + AB::F::from_canonical_usize(RV32_REGISTER_NUM_LIMBS),
+        );
+        continue_builder.assert_eq(next.timestamp, local.timestamp + AB::Expr::ONE);
+        // An instruction ends exactly when it has no words left to write.
+        builder
+            .when_transition()
+            .assert_zero(local.rem_words * not::<AB::Expr>(next_is_continue));
+        builder.when_last_row().assert_zero(local.rem_words);
+
+        self.eval_start(builder, local);
+
+        for i in 0..RV32_REGISTER_NUM_LIMBS / 2 {
+            self.bitwise_lookup_bus
+                .send_range(local.data[i * 2], local.data[i * 2 + 1])
+                .eval(builder, local.is_valid);
+        }
+        self.range_bus
+            .range_check(local.mem_ptr_limbs[0], RV32_CELL_BITS * 2)
+            .eval(builder, local.is_valid);
+        self.range_bus
+            .range_check(
+                local.mem_ptr_limbs[1],
+                self.pointer_max_bits - RV32_CELL_BITS * 2,
+            )
+            .eval(builder, local.is_valid);
+
+        self.memory_bridge
+            .write(
+                MemoryAddress::new(AB::F::from_canonical_u32(RV32_MEMORY_AS), mem_ptr),
+                local.data,
+                local.timestamp + AB::Expr::ONE,
+                &local.write_aux,
+            )
+            .eval(builder, local.is_valid);
+    }
+}
+
+impl Rv32HintBufferAir {
+    /// Constrains the first row of an instruction: the rs1 read, `mem_ptr = rs1 + imm` and that
+    /// the length word written on this row rounds up to `rem_words` words.
+    fn eval_start<AB: InteractionBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &Rv32HintBufferCols<AB::Var>,
+    ) {
+        let is_start = local.is_start;
+        self.memory_bridge
+            .read(
+                MemoryAddress::new(AB::F::from_canonical_u32(RV32_REGISTER_AS), local.rs1_ptr),
+                local.rs1_data,
+                local.timestamp,
+                &local.rs1_aux_cols,
+            )
+            .eval(builder, is_start);
+
+        // constrain mem_ptr = rs1 + imm as a u32 addition with 2 limbs
+        let limbs_01 =
+            local.rs1_data[0] + local.rs1_data[1] * AB::F::from_canonical_u32(1 << RV32_CELL_BITS);
+        let limbs_23 =
+            local.rs1_data[2] + local.rs1_data[3] * AB::F::from_canonical_u32(1 << RV32_CELL_BITS);
+
+        let inv = AB::F::from_canonical_u32(1 << (RV32_CELL_BITS * 2)).inverse();
+        let carry = (limbs_01 + local.imm - local.mem_ptr_limbs[0]) * inv;
+        builder.when(is_start).assert_bool(carry.clone());
+
+        builder.assert_bool(local.imm_sign);
+        let imm_extend_limb =
+            local.imm_sign * AB::F::from_canonical_u32((1 << (RV32_CELL_BITS * 2)) - 1);
+        let carry = (limbs_23 + imm_extend_limb + carry - local.mem_ptr_limbs[1]) * inv;
+        builder.when(is_start).assert_bool(carry);
+
+        // len < 2^pointer_max_bits, so that len + pad = 4 * rem_words does not wrap around
+        self.range_bus
+            .range_check(
+                local.data[2] + local.data[3] * AB::F::from_canonical_u32(1 << RV32_CELL_BITS),
+                self.pointer_max_bits - RV32_CELL_BITS * 2,
+            )
+            .eval(builder, is_start);
+        builder.assert_bool(local.pad_bits[0]);
+        builder.assert_bool(local.pad_bits[1]);
+        let len = abstract_compose::<AB::Expr, _>(local.data);
+        let pad = local.pad_bits[0] + local.pad_bits[1] * AB::F::TWO;
+        builder.when(is_start).assert_eq(
+            len + pad,
+            local.rem_words * AB::F::from_canonical_usize(RV32_REGISTER_NUM_LIMBS),
+        );
+
+        // The rs1 read and the writes of the length word and the rem_words data words
+        self.execution_bridge
+            .execute_and_increment_pc(
+                AB::Expr::from_canonical_usize(
+                    Rv32HintStoreOpcode::HINT_BUFFER as usize + self.offset,
+                ),
+                [
+                    AB::Expr::ZERO,
+                    local.rs1_ptr.into(),
+                    local.imm.into(),
+                    AB::Expr::from_canonical_u32(RV32_REGISTER_AS),
+                    AB::Expr::from_canonical_u32(RV32_MEMORY_AS),
+                ],
+                ExecutionState {
+                    pc: local.pc,
+                    timestamp: local.timestamp,
+                },
+                local.rem_words + AB::F::TWO,
+            )
+            .eval(builder, is_start);
+    }
+}
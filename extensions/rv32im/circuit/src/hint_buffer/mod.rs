@@ -0,0 +1,181 @@
+use std::{
+    array,
+    sync::{Arc, OnceLock},
+};
+
+use openvm_circuit::{
+    arch::{
+        ExecutionBridge, ExecutionBus, ExecutionError, ExecutionState, InstructionExecutor, Streams,
+    },
+    system::{
+        memory::{MemoryControllerRef, MemoryReadRecord, MemoryWriteRecord},
+        program::ProgramBus,
+    },
+};
+use openvm_circuit_primitives::{
+    bitwise_op_lookup::BitwiseOperationLookupChip, var_range::VariableRangeCheckerChip,
+};
+use openvm_instructions::{
+    instruction::Instruction,
+    program::DEFAULT_PC_STEP,
+    riscv::{RV32_MEMORY_AS, RV32_REGISTER_AS},
+    UsizeOpcode,
+};
+use openvm_rv32im_transpiler::Rv32HintStoreOpcode;
+use openvm_stark_backend::p3_field::{AbstractField, Field, PrimeField32};
+use parking_lot::Mutex;
+
+use crate::adapters::{compose, RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS};
+
+mod air;
+mod trace;
+
+pub use air::*;
+
+#[cfg(test)]
+mod tests;
+
+/// Executes HINT_BUFFER, which writes a length word `len` from the hint stream to memory at
+/// `rs1 + imm`, followed by the next `len` bytes of the hint stream padded to whole words. Each
+/// written word takes one row of the trace.
+#[derive(Debug)]
+pub struct Rv32HintBufferChip<F: PrimeField32> {
+    pub air: Rv32HintBufferAir,
+    pub records: Vec<Rv32HintBufferRecord<F>>,
+    pub streams: OnceLock<Arc<Mutex<Streams<F>>>>,
+    pub memory_controller: MemoryControllerRef<F>,
+    pub range_checker_chip: Arc<VariableRangeCheckerChip>,
+    pub bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<RV32_CELL_BITS>>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Rv32HintBufferRecord<F: Field> {
+    pub from_state: ExecutionState<u32>,
+    pub rs1_read: MemoryReadRecord<F, RV32_REGISTER_NUM_LIMBS>,
+    pub imm: F,
+    pub imm_sign: bool,
+    pub mem_ptr: u32,
+    /// The write of the length word followed by the writes of the data words
+    pub writes: Vec<MemoryWriteRecord<F, RV32_REGISTER_NUM_LIMBS>>,
+}
+
+impl<F: PrimeField32> Rv32HintBufferChip<F> {
+    pub fn new(
+        execution_bus: ExecutionBus,
+        program_bus: ProgramBus,
+        memory_controller: MemoryControllerRef<F>,
+        range_checker_chip: Arc<VariableRangeCheckerChip>,
+        bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<RV32_CELL_BITS>>,
+        offset: usize,
+    ) -> Self {
+        let pointer_max_bits = memory_controller.borrow().mem_config().pointer_max_bits;
+        let memory_bridge = memory_controller.borrow().memory_bridge();
+        Self {
+            air: Rv32HintBufferAir::new(
+                ExecutionBridge::new(execution_bus, program_bus),
+                memory_bridge,
+                bitwise_lookup_chip.bus(),
+                range_checker_chip.bus(),
+                pointer_max_bits,
+                offset,
+            ),
+            records: Vec::new(),
+            streams: OnceLock::new(),
+            memory_controller,
+            range_checker_chip,
+            bitwise_lookup_chip,
+        }
+    }
+
+    pub fn set_streams(&mut self, streams: Arc<Mutex<Streams<F>>>) {
+        self.streams.set(streams).unwrap();
+    }
+}
+
+impl<F: PrimeField32> InstructionExecutor<F> for Rv32HintBufferChip<F> {
+    fn execute(
+        &mut self,
+        instruction: Instruction<F>,
+        from_state: ExecutionState<u32>,
+    ) -> Result<ExecutionState<u32>, ExecutionError> {
+        let Instruction {
+            opcode, b, c, d, e, ..
+        } = instruction;
+        let local_opcode =
+            Rv32HintStoreOpcode::from_usize(opcode.local_opcode_idx(self.air.offset));
+        debug_assert_eq!(local_opcode, Rv32HintStoreOpcode::HINT_BUFFER);
+        debug_assert_eq!(d.as_canonical_u32(), RV32_REGISTER_AS);
+        debug_assert_eq!(e.as_canonical_u32(), RV32_MEMORY_AS);
+
+        // Check the whole buffer is hinted before touching memory.
+        let mut streams = self.streams.get().unwrap().lock();
+        if streams.hint_stream.len() < RV32_REGISTER_NUM_LIMBS {
+            return Err(ExecutionError::HintOutOfBounds { pc: from_state.pc });
+        }
+        let len = compose(array::from_fn(|i| streams.hint_stream[i]));
+        let num_words = len.div_ceil(RV32_REGISTER_NUM_LIMBS as u32) as usize;
+        if streams.hint_stream.len() < (num_words + 1) * RV32_REGISTER_NUM_LIMBS {
+            return Err(ExecutionError::HintOutOfBounds { pc: from_state.pc });
+        }
+
+        let mut memory = self.memory_controller.borrow_mut();
+        debug_assert_eq!(from_state.timestamp, memory.timestamp());
+
+        let rs1_read = memory.read::<RV32_REGISTER_NUM_LIMBS>(d, b);
+        let imm = c.as_canonical_u32();
+        let imm_sign = (imm & 0x8000) >> 15;
+        let imm_extended = imm + imm_sign * 0xffff0000;
+        let mem_ptr = compose(rs1_read.data).wrapping_add(imm_extended);
+        // The last word is written at `mem_ptr + 4 * num_words`.
+        assert!(
+            (mem_ptr as usize) + num_words * RV32_REGISTER_NUM_LIMBS
+                < (1 << self.air.pointer_max_bits)
+        );
+
+        let writes = (0..=num_words)
+            .map(|k| {
+                let data: [F; RV32_REGISTER_NUM_LIMBS] =
+                    array::from_fn(|_| streams.hint_stream.pop_front().unwrap());
+                for i in 0..(RV32_REGISTER_NUM_LIMBS / 2) {
+                    self.bitwise_lookup_chip.request_range(
+                        data[2 * i].as_canonical_u32(),
+                        data[2 * i + 1].as_canonical_u32(),
+                    );
+                }
+                let ptr = mem_ptr + (k * RV32_REGISTER_NUM_LIMBS) as u32;
+                self.range_checker_chip
+                    .add_count(ptr & 0xffff, RV32_CELL_BITS * 2);
+                self.range_checker_chip.add_count(
+                    ptr >> (RV32_CELL_BITS * 2),
+                    self.air.pointer_max_bits - RV32_CELL_BITS * 2,
+                );
+                memory.write(e, F::from_canonical_u32(ptr), data)
+            })
+            .collect();
+        self.range_checker_chip.add_count(
+            len >> (RV32_CELL_BITS * 2),
+            self.air.pointer_max_bits - RV32_CELL_BITS * 2,
+        );
+
+        self.records.push(Rv32HintBufferRecord {
+            from_state,
+            rs1_read,
+            imm: c,
+            imm_sign: imm_sign == 1,
+            mem_ptr,
+            writes,
+        });
+
+        Ok(ExecutionState {
+            pc: from_state.pc + DEFAULT_PC_STEP,
+            timestamp: memory.timestamp(),
+        })
+    }
+
+    fn get_opcode_name(&self, opcode: usize) -> String {
+        format!(
+            "{:?}",
+            Rv32HintStoreOpcode::from_usize(opcode - self.air.offset)
+        )
+    }
+}
@@ -0,0 +1,150 @@
+use std::{array, borrow::BorrowMut, sync::Arc};
+
+use openvm_circuit::arch::{
+    testing::{VmChipTestBuilder, VmChipTester},
+    Streams, BITWISE_OP_LOOKUP_BUS,
+};
+use openvm_circuit_primitives::bitwise_op_lookup::{
+    BitwiseOperationLookupBus, BitwiseOperationLookupChip,
+};
+use openvm_instructions::{instruction::Instruction, UsizeOpcode, VmOpcode};
+use openvm_rv32im_transpiler::Rv32HintStoreOpcode::{self, *};
+use openvm_stark_backend::{
+    p3_field::AbstractField, p3_matrix::dense::DenseMatrix, utils::disable_debug_builder,
+    verifier::VerificationError,
+};
+use openvm_stark_sdk::{
+    config::baby_bear_blake3::BabyBearBlake3Config, p3_baby_bear::BabyBear,
+    utils::create_seeded_rng,
+};
+use parking_lot::Mutex;
+use rand::Rng;
+
+use super::{Rv32HintBufferChip, Rv32HintBufferCols};
+use crate::adapters::{RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS};
+
+type F = BabyBear;
+
+/// Executes HINT_BUFFER on each of `buffers`, with the buffers laid out one after the other in
+/// memory, and checks the written memory.
+fn build_hint_buffer_test(buffers: Vec<Vec<u8>>) -> VmChipTester<BabyBearBlake3Config> {
+    let mut rng = create_seeded_rng();
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = Arc::new(BitwiseOperationLookupChip::<RV32_CELL_BITS>::new(
+        bitwise_bus,
+    ));
+
+    let mut tester = VmChipTestBuilder::default();
+    let range_checker_chip = tester.memory_controller().borrow().range_checker.clone();
+    let mut chip = Rv32HintBufferChip::<F>::new(
+        tester.execution_bus(),
+        tester.program_bus(),
+        tester.memory_controller(),
+        range_checker_chip.clone(),
+        bitwise_chip.clone(),
+        Rv32HintStoreOpcode::default_offset(),
+    );
+    let streams = Arc::new(Mutex::new(Streams::default()));
+    chip.set_streams(streams.clone());
+
+    let [b, d, e] = [4, 1, 2];
+    let mut mem_ptr = 0;
+    for buffer in buffers {
+        let len = buffer.len();
+        let mut words = (len as u32).to_le_bytes().to_vec();
+        words.extend(&buffer);
+        words.resize((len.div_ceil(4) + 1) * 4, 0);
+        streams
+            .lock()
+            .hint_stream
+            .extend(words.iter().map(|&byte| F::from_canonical_u8(byte)));
+
+        // Reach mem_ptr with an immediate of either sign
+        let imm: u32 = rng.gen_range(0..(1 << 16));
+        let imm_ext = imm + ((imm & 0x8000) >> 15) * 0xffff0000;
+        let rs1 = (mem_ptr as u32).wrapping_sub(imm_ext);
+        tester.write(d, b, rs1.to_le_bytes().map(F::from_canonical_u8));
+
+        tester.execute(
+            &mut chip,
+            Instruction::from_usize(
+                VmOpcode::with_default_offset(HINT_BUFFER),
+                [0, b, imm as usize, d, e],
+            ),
+        );
+        for (i, word) in words.chunks_exact(RV32_REGISTER_NUM_LIMBS).enumerate() {
+            assert_eq!(
+                tester.read::<RV32_REGISTER_NUM_LIMBS>(e, mem_ptr + 4 * i),
+                array::from_fn(|j| F::from_canonical_u8(word[j]))
+            );
+        }
+        mem_ptr += words.len();
+    }
+    assert!(streams.lock().hint_stream.is_empty());
+
+    drop(range_checker_chip);
+    tester.build().load(chip).load(bitwise_chip).finalize()
+}
+
+///////////////////////////////////////////////////////////////////////////////////////
+/// POSITIVE TESTS
+///
+/// Randomly generate computations and execute, ensuring that the generated trace
+/// passes all constraints.
+///////////////////////////////////////////////////////////////////////////////////////
+#[test]
+fn rand_hint_buffer_test() {
+    let mut rng = create_seeded_rng();
+    let mut buffers = vec![vec![], vec![rng.gen()], vec![rng.gen(); 4]];
+    for _ in 0..20 {
+        let len = rng.gen_range(0..64);
+        buffers.push((0..len).map(|_| rng.gen()).collect());
+    }
+    let tester = build_hint_buffer_test(buffers);
+    tester.simple_test().expect("Verification failed");
+}
+
+//////////////////////////////////////////////////////////////////////////////////////
+// NEGATIVE TESTS
+//
+// Execute a valid buffer, then replace parts of the generated trace and check that
+// the constraints reject it.
+//////////////////////////////////////////////////////////////////////////////////////
+
+fn run_negative_hint_buffer_test(
+    len: usize,
+    prank: impl Fn(&mut DenseMatrix<F>),
+    expected_error: VerificationError,
+) {
+    let mut tester = build_hint_buffer_test(vec![vec![0xab; len]]);
+    let trace = tester.air_proof_inputs[2].raw.common_main.as_mut().unwrap();
+    prank(trace);
+    disable_debug_builder();
+    assert_eq!(tester.simple_test().err(), Some(expected_error));
+}
+
+#[test]
+fn negative_hint_buffer_wrong_padding_test() {
+    // 5 bytes are padded by 3 to 2 words.
+    run_negative_hint_buffer_test(
+        5,
+        |trace| {
+            let cols: &mut Rv32HintBufferCols<F> = trace.row_mut(0).borrow_mut();
+            cols.pad_bits = [F::ZERO, F::ZERO];
+        },
+        VerificationError::OodEvaluationMismatch,
+    );
+}
+
+#[test]
+fn negative_hint_buffer_words_left_test() {
+    // The last word of the buffer cannot leave words to write.
+    run_negative_hint_buffer_test(
+        8,
+        |trace| {
+            let cols: &mut Rv32HintBufferCols<F> = trace.row_mut(2).borrow_mut();
+            cols.rem_words = F::ONE;
+        },
+        VerificationError::OodEvaluationMismatch,
+    );
+}
@@ -0,0 +1,77 @@
+use std::{borrow::BorrowMut, sync::Arc};
+
+use openvm_stark_backend::{
+    config::{StarkGenericConfig, Val},
+    p3_air::BaseAir,
+    p3_field::{AbstractField, PrimeField32},
+    p3_matrix::dense::RowMajorMatrix,
+    prover::types::AirProofInput,
+    rap::{get_air_name, AnyRap},
+    Chip, ChipUsageGetter,
+};
+
+use super::{Rv32HintBufferChip, Rv32HintBufferCols};
+use crate::adapters::{compose, RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS};
+
+impl<SC: StarkGenericConfig> Chip<SC> for Rv32HintBufferChip<Val<SC>>
+where
+    Val<SC>: PrimeField32,
+{
+    fn air(&self) -> Arc<dyn AnyRap<SC>> {
+        Arc::new(self.air)
+    }
+
+    fn generate_air_proof_input(self) -> AirProofInput<SC> {
+        let air = self.air();
+        let trace_width = self.trace_width();
+        let height = self.current_trace_height().next_power_of_two();
+        let aux_cols_factory = self.memory_controller.borrow().aux_cols_factory();
+
+        // Padding rows are all zero
+        let mut trace = RowMajorMatrix::new(Val::<SC>::zero_vec(height * trace_width), trace_width);
+        let mut rows = trace.values.chunks_exact_mut(trace_width);
+        for record in self.records {
+            let num_words = record.writes.len() - 1;
+            for (k, write) in record.writes.into_iter().enumerate() {
+                let cols: &mut Rv32HintBufferCols<Val<SC>> = rows.next().unwrap().borrow_mut();
+                cols.is_valid = Val::<SC>::ONE;
+                cols.timestamp =
+                    Val::<SC>::from_canonical_u32(record.from_state.timestamp + k as u32);
+                if k == 0 {
+                    let len = compose(write.data) as usize;
+                    let pad = num_words * RV32_REGISTER_NUM_LIMBS - len;
+                    cols.is_start = Val::<SC>::ONE;
+                    cols.pc = Val::<SC>::from_canonical_u32(record.from_state.pc);
+                    cols.rs1_ptr = record.rs1_read.pointer;
+                    cols.rs1_data = record.rs1_read.data;
+                    cols.rs1_aux_cols = aux_cols_factory.make_read_aux_cols(record.rs1_read);
+                    cols.imm = record.imm;
+                    cols.imm_sign = Val::<SC>::from_bool(record.imm_sign);
+                    cols.pad_bits = [pad & 1, pad >> 1].map(Val::<SC>::from_canonical_usize);
+                }
+                let mem_ptr = record.mem_ptr + (k * RV32_REGISTER_NUM_LIMBS) as u32;
+                cols.mem_ptr_limbs = [mem_ptr & 0xffff, mem_ptr >> (RV32_CELL_BITS * 2)]
+                    .map(Val::<SC>::from_canonical_u32);
+                cols.data = write.data;
+                cols.write_aux = aux_cols_factory.make_write_aux_cols(write);
+                cols.rem_words = Val::<SC>::from_canonical_usize(num_words - k);
+            }
+        }
+
+        AirProofInput::simple_no_pis(air, trace)
+    }
+}
+
+impl<F: PrimeField32> ChipUsageGetter for Rv32HintBufferChip<F> {
+    fn air_name(&self) -> String {
+        get_air_name(&self.air)
+    }
+
+    fn current_trace_height(&self) -> usize {
+        self.records.iter().map(|record| record.writes.len()).sum()
+    }
+
+    fn trace_width(&self) -> usize {
+        BaseAir::<F>::width(&self.air)
+    }
+}
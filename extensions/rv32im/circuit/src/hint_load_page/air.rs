@@ -0,0 +1,210 @@
+use std::borrow::Borrow;
+
+use openvm_circuit::{
+    arch::{ExecutionBridge, ExecutionState},
+    system::memory::{
+        offline_checker::{MemoryBridge, MemoryReadAuxCols, MemoryWriteAuxCols},
+        MemoryAddress,
+    },
+};
+use openvm_circuit_primitives::{
+    bitwise_op_lookup::BitwiseOperationLookupBus, utils::not, var_range::VariableRangeCheckerBus,
+};
+use openvm_circuit_primitives_derive::AlignedBorrow;
+use openvm_instructions::riscv::{RV32_MEMORY_AS, RV32_REGISTER_AS};
+use openvm_rv32im_transpiler::{Rv32HintStoreOpcode, HINT_PAGE_ALIGN, HINT_PAGE_BYTES};
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    p3_air::{Air, AirBuilder, BaseAir},
+    p3_field::{AbstractField, Field},
+    p3_matrix::Matrix,
+    rap::{BaseAirWithPublicValues, PartitionedBaseAir},
+};
+
+use crate::adapters::{RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS};
+
+/// Number of blocks, and rows, of one HINT_LOAD_PAGE instruction.
+pub const HINT_PAGE_BLOCKS: usize = HINT_PAGE_BYTES / HINT_PAGE_ALIGN;
+/// Bits of the low limb of `mem_ptr / HINT_PAGE_ALIGN`, so that the low limb times
+/// `HINT_PAGE_ALIGN` covers the low 16 bits of `mem_ptr`.
+pub(super) const MEM_PTR_LO_BITS: usize = RV32_CELL_BITS * 2 - HINT_PAGE_ALIGN.ilog2() as usize;
+
+/// One row per block written by a HINT_LOAD_PAGE instruction. The first row of an instruction
+/// also reads rs1.
+#[repr(C)]
+#[derive(Debug, Clone, AlignedBorrow)]
+pub struct Rv32HintLoadPageCols<T> {
+    pub is_valid: T,
+    /// Whether this row is the first row of an instruction
+    pub is_start: T,
+    /// Only set on the first row
+    pub pc: T,
+    /// The timestamp of the rs1 read on the first row, and one less than the timestamp of the
+    /// write of each row
+    pub timestamp: T,
+
+    // Only set on the first row:
+    pub rs1_ptr: T,
+    pub rs1_data: [T; RV32_REGISTER_NUM_LIMBS],
+    pub rs1_aux_cols: MemoryReadAuxCols<T, RV32_REGISTER_NUM_LIMBS>,
+    pub imm: T,
+    pub imm_sign: T,
+
+    /// The memory pointer of this row's write is
+    /// `mem_ptr_limbs[0] * HINT_PAGE_ALIGN + mem_ptr_limbs[1] * 2^16`
+    pub mem_ptr_limbs: [T; 2],
+    pub data: [T; HINT_PAGE_ALIGN],
+    pub write_aux: MemoryWriteAuxCols<T, HINT_PAGE_ALIGN>,
+    /// Number of blocks left to write after this row
+    pub rem_blocks: T,
+}
+
+#[derive(Clone, Copy, Debug, derive_new::new)]
+pub struct Rv32HintLoadPageAir {
+    pub execution_bridge: ExecutionBridge,
+    pub memory_bridge: MemoryBridge,
+    pub bitwise_lookup_bus: BitwiseOperationLookupBus,
+    pub range_bus: VariableRangeCheckerBus,
+    /// Maximum number of bits allowed for an address pointer
+    pub pointer_max_bits: usize,
+    pub(super) offset: usize,
+}
+
+impl<F: Field> BaseAirWithPublicValues<F> for Rv32HintLoadPageAir {}
+impl<F: Field> PartitionedBaseAir<F> for Rv32HintLoadPageAir {}
+impl<F: Field> BaseAir<F> for Rv32HintLoadPageAir {
+    fn width(&self) -> usize {
+        Rv32HintLoadPageCols::<F>::width()
+    }
+}
+
+impl<AB: InteractionBuilder> Air<AB> for Rv32HintLoadPageAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let (local, next) = (main.row_slice(0), main.row_slice(1));
+        let local: &Rv32HintLoadPageCols<AB::Var> = (*local).borrow();
+        let next: &Rv32HintLoadPageCols<AB::Var> = (*next).borrow();
+
+        builder.assert_bool(local.is_valid);
+        builder.assert_bool(local.is_start);
+        builder.when(local.is_start).assert_one(local.is_valid);
+        builder
+            .when_first_row()
+            .assert_eq(local.is_valid, local.is_start);
+
+        let mem_ptr = self.mem_ptr::<AB>(local);
+
+        // A valid row that does not start an instruction continues the instruction of the row
+        // before it, writing the next block.
+        let next_is_continue = next.is_valid - next.is_start;
+        let mut continue_builder = builder.when(next_is_continue.clone());
+        continue_builder.assert_one(local.is_valid);
+        continue_builder.assert_eq(next.rem_blocks, local.rem_blocks - AB::Expr::ONE);
+        continue_builder.assert_eq(
+            self.mem_ptr::<AB>(next),
+            mem_ptr.clone() + AB::F::from_canonical_usize(HINT_PAGE_ALIGN),
+        );
+        continue_builder.assert_eq(next.timestamp, local.timestamp + AB::Expr::ONE);
+        // An instruction ends exactly when it has no blocks left to write.
+        builder
+            .when_transition()
+            .assert_zero(local.rem_blocks * not::<AB::Expr>(next_is_continue));
+        builder.when_last_row().assert_zero(local.rem_blocks);
+
+        self.eval_start(builder, local);
+
+        // The hinted bytes are range checked like the ones of HINT_STOREW.
+        for i in 0..HINT_PAGE_ALIGN / 2 {
+            self.bitwise_lookup_bus
+                .send_range(local.data[i * 2], local.data[i * 2 + 1])
+                .eval(builder, local.is_valid);
+        }
+        self.range_bus
+            .range_check(local.mem_ptr_limbs[0], MEM_PTR_LO_BITS)
+            .eval(builder, local.is_valid);
+        self.range_bus
+            .range_check(
+                local.mem_ptr_limbs[1],
+                self.pointer_max_bits - RV32_CELL_BITS * 2,
+            )
+            .eval(builder, local.is_valid);
+
+        self.memory_bridge
+            .write(
+                MemoryAddress::new(AB::F::from_canonical_u32(RV32_MEMORY_AS), mem_ptr),
+                local.data,
+                local.timestamp + AB::Expr::ONE,
+                &local.write_aux,
+            )
+            .eval(builder, local.is_valid);
+    }
+}
+
+impl Rv32HintLoadPageAir {
+    fn mem_ptr<AB: AirBuilder>(&self, cols: &Rv32HintLoadPageCols<AB::Var>) -> AB::Expr {
+        cols.mem_ptr_limbs[0] * AB::F::from_canonical_usize(HINT_PAGE_ALIGN)
+            + cols.mem_ptr_limbs[1] * AB::F::from_canonical_u32(1 << (RV32_CELL_BITS * 2))
+    }
+
+    /// Constrains the first row of an instruction: the rs1 read, `mem_ptr = rs1 + imm` and that
+    /// the instruction writes [HINT_PAGE_BLOCKS] blocks.
+    fn eval_start<AB: InteractionBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &Rv32HintLoadPageCols<AB::Var>,
+    ) {
+        let is_start = local.is_start;
+        self.memory_bridge
+            .read(
+                MemoryAddress::new(AB::F::from_canonical_u32(RV32_REGISTER_AS), local.rs1_ptr),
+                local.rs1_data,
+                local.timestamp,
+                &local.rs1_aux_cols,
+            )
+            .eval(builder, is_start);
+
+        // constrain mem_ptr = rs1 + imm as a u32 addition with 2 limbs, where the low limb of
+        // mem_ptr is a multiple of HINT_PAGE_ALIGN
+        let limbs_01 =
+            local.rs1_data[0] + local.rs1_data[1] * AB::F::from_canonical_u32(1 << RV32_CELL_BITS);
+        let limbs_23 =
+            local.rs1_data[2] + local.rs1_data[3] * AB::F::from_canonical_u32(1 << RV32_CELL_BITS);
+        let mem_ptr_lo = local.mem_ptr_limbs[0] * AB::F::from_canonical_usize(HINT_PAGE_ALIGN);
+
+        let inv = AB::F::from_canonical_u32(1 << (RV32_CELL_BITS * 2)).inverse();
+        let carry = (limbs_01 + local.imm - mem_ptr_lo) * inv;
+        builder.when(is_start).assert_bool(carry.clone());
+
+        builder.assert_bool(local.imm_sign);
+        let imm_extend_limb =
+            local.imm_sign * AB::F::from_canonical_u32((1 << (RV32_CELL_BITS * 2)) - 1);
+        let carry = (limbs_23 + imm_extend_limb + carry - local.mem_ptr_limbs[1]) * inv;
+        builder.when(is_start).assert_bool(carry);
+
+        builder.when(is_start).assert_eq(
+            local.rem_blocks,
+            AB::F::from_canonical_usize(HINT_PAGE_BLOCKS - 1),
+        );
+
+        // The rs1 read and the writes of the blocks
+        self.execution_bridge
+            .execute_and_increment_pc(
+                AB::Expr::from_canonical_usize(
+                    Rv32HintStoreOpcode::HINT_LOAD_PAGE as usize + self.offset,
+                ),
+                [
+                    AB::Expr::ZERO,
+                    local.rs1_ptr.into(),
+                    local.imm.into(),
+                    AB::Expr::from_canonical_u32(RV32_REGISTER_AS),
+                    AB::Expr::from_canonical_u32(RV32_MEMORY_AS),
+                ],
+                ExecutionState {
+                    pc: local.pc,
+                    timestamp: local.timestamp,
+                },
+                AB::F::from_canonical_usize(HINT_PAGE_BLOCKS + 1),
+            )
+            .eval(builder, is_start);
+    }
+}
@@ -0,0 +1,175 @@
+use std::sync::{Arc, OnceLock};
+
+use openvm_circuit::{
+    arch::{
+        ExecutionBridge, ExecutionBus, ExecutionError, ExecutionState, InstructionExecutor, Streams,
+    },
+    system::{
+        memory::{MemoryControllerRef, MemoryReadRecord, MemoryWriteRangeRecord},
+        program::ProgramBus,
+    },
+};
+use openvm_circuit_primitives::{
+    bitwise_op_lookup::BitwiseOperationLookupChip, var_range::VariableRangeCheckerChip,
+};
+use openvm_instructions::{
+    instruction::Instruction,
+    program::DEFAULT_PC_STEP,
+    riscv::{RV32_MEMORY_AS, RV32_REGISTER_AS},
+    UsizeOpcode,
+};
+use openvm_rv32im_transpiler::{Rv32HintStoreOpcode, HINT_PAGE_ALIGN, HINT_PAGE_BYTES};
+use openvm_stark_backend::p3_field::{AbstractField, Field, PrimeField32};
+use parking_lot::Mutex;
+
+use crate::adapters::{compose, RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS};
+
+mod air;
+mod trace;
+
+use air::MEM_PTR_LO_BITS;
+pub use air::*;
+
+#[cfg(test)]
+mod tests;
+
+/// Executes HINT_LOAD_PAGE, which writes the next [HINT_PAGE_BYTES] bytes of the hint stream to
+/// memory at `rs1 + imm`, in blocks of [HINT_PAGE_ALIGN] bytes. Each block takes one row of the
+/// trace and one memory write, instead of one HINT_STOREW per word.
+#[derive(Debug)]
+pub struct Rv32HintLoadPageChip<F: PrimeField32> {
+    pub air: Rv32HintLoadPageAir,
+    pub records: Vec<Rv32HintLoadPageRecord<F>>,
+    pub streams: OnceLock<Arc<Mutex<Streams<F>>>>,
+    pub memory_controller: MemoryControllerRef<F>,
+    pub range_checker_chip: Arc<VariableRangeCheckerChip>,
+    pub bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<RV32_CELL_BITS>>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Rv32HintLoadPageRecord<F: Field> {
+    pub from_state: ExecutionState<u32>,
+    pub rs1_read: MemoryReadRecord<F, RV32_REGISTER_NUM_LIMBS>,
+    pub imm: F,
+    pub imm_sign: bool,
+    pub mem_ptr: u32,
+    pub writes: MemoryWriteRangeRecord<F, HINT_PAGE_ALIGN>,
+}
+
+impl<F: PrimeField32> Rv32HintLoadPageChip<F> {
+    pub fn new(
+        execution_bus: ExecutionBus,
+        program_bus: ProgramBus,
+        memory_controller: MemoryControllerRef<F>,
+        range_checker_chip: Arc<VariableRangeCheckerChip>,
+        bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<RV32_CELL_BITS>>,
+        offset: usize,
+    ) -> Self {
+        let pointer_max_bits = memory_controller.borrow().mem_config().pointer_max_bits;
+        let memory_bridge = memory_controller.borrow().memory_bridge();
+        Self {
+            air: Rv32HintLoadPageAir::new(
+                ExecutionBridge::new(execution_bus, program_bus),
+                memory_bridge,
+                bitwise_lookup_chip.bus(),
+                range_checker_chip.bus(),
+                pointer_max_bits,
+                offset,
+            ),
+            records: Vec::new(),
+            streams: OnceLock::new(),
+            memory_controller,
+            range_checker_chip,
+            bitwise_lookup_chip,
+        }
+    }
+
+    pub fn set_streams(&mut self, streams: Arc<Mutex<Streams<F>>>) {
+        self.streams.set(streams).unwrap();
+    }
+}
+
+impl<F: PrimeField32> InstructionExecutor<F> for Rv32HintLoadPageChip<F> {
+    fn execute(
+        &mut self,
+        instruction: Instruction<F>,
+        from_state: ExecutionState<u32>,
+    ) -> Result<ExecutionState<u32>, ExecutionError> {
+        let Instruction {
+            opcode, b, c, d, e, ..
+        } = instruction;
+        let local_opcode =
+            Rv32HintStoreOpcode::from_usize(opcode.local_opcode_idx(self.air.offset));
+        debug_assert_eq!(local_opcode, Rv32HintStoreOpcode::HINT_LOAD_PAGE);
+        debug_assert_eq!(d.as_canonical_u32(), RV32_REGISTER_AS);
+        debug_assert_eq!(e.as_canonical_u32(), RV32_MEMORY_AS);
+
+        let imm = c.as_canonical_u32();
+        let imm_sign = (imm & 0x8000) >> 15;
+        let imm_extended = imm + imm_sign * 0xffff0000;
+
+        // Check the page is hinted and fits in memory before touching memory.
+        let mut streams = self.streams.get().unwrap().lock();
+        if streams.hint_stream.len() < HINT_PAGE_BYTES {
+            return Err(ExecutionError::HintOutOfBounds { pc: from_state.pc });
+        }
+        let mut memory = self.memory_controller.borrow_mut();
+        debug_assert_eq!(from_state.timestamp, memory.timestamp());
+        let mem_ptr =
+            compose(memory.unsafe_read::<RV32_REGISTER_NUM_LIMBS>(d, b)).wrapping_add(imm_extended);
+        if mem_ptr % HINT_PAGE_ALIGN as u32 != 0 {
+            return Err(ExecutionError::MisalignedMemoryAccess {
+                pc: from_state.pc,
+                address: mem_ptr,
+                alignment: HINT_PAGE_ALIGN as u32,
+            });
+        }
+        if mem_ptr as usize + HINT_PAGE_BYTES > 1 << self.air.pointer_max_bits {
+            return Err(ExecutionError::Fail { pc: from_state.pc });
+        }
+
+        let rs1_read = memory.read::<RV32_REGISTER_NUM_LIMBS>(d, b);
+        let writes = memory
+            .write_hints::<HINT_PAGE_ALIGN>(
+                e,
+                F::from_canonical_u32(mem_ptr),
+                HINT_PAGE_BYTES,
+                &mut streams.hint_stream,
+            )
+            .unwrap();
+        for pair in writes.data.chunks_exact(2) {
+            self.bitwise_lookup_chip
+                .request_range(pair[0].as_canonical_u32(), pair[1].as_canonical_u32());
+        }
+        for block in 0..HINT_PAGE_BLOCKS as u32 {
+            let ptr = mem_ptr + block * HINT_PAGE_ALIGN as u32;
+            self.range_checker_chip
+                .add_count((ptr & 0xffff) / HINT_PAGE_ALIGN as u32, MEM_PTR_LO_BITS);
+            self.range_checker_chip.add_count(
+                ptr >> (RV32_CELL_BITS * 2),
+                self.air.pointer_max_bits - RV32_CELL_BITS * 2,
+            );
+        }
+
+        self.records.push(Rv32HintLoadPageRecord {
+            from_state,
+            rs1_read,
+            imm: c,
+            imm_sign: imm_sign == 1,
+            mem_ptr,
+            writes,
+        });
+
+        Ok(ExecutionState {
+            pc: from_state.pc + DEFAULT_PC_STEP,
+            timestamp: memory.timestamp(),
+        })
+    }
+
+    fn get_opcode_name(&self, opcode: usize) -> String {
+        format!(
+            "{:?}",
+            Rv32HintStoreOpcode::from_usize(opcode - self.air.offset)
+        )
+    }
+}
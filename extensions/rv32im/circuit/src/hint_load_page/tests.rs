@@ -0,0 +1,189 @@
+use std::{array, borrow::BorrowMut, sync::Arc};
+
+use openvm_circuit::arch::{
+    testing::{VmChipTestBuilder, VmChipTester},
+    ExecutionError, ExecutionState, InstructionExecutor, Streams, BITWISE_OP_LOOKUP_BUS,
+};
+use openvm_circuit_primitives::bitwise_op_lookup::{
+    BitwiseOperationLookupBus, BitwiseOperationLookupChip,
+};
+use openvm_instructions::{instruction::Instruction, UsizeOpcode, VmOpcode};
+use openvm_rv32im_transpiler::{
+    Rv32HintStoreOpcode::{self, *},
+    HINT_PAGE_ALIGN, HINT_PAGE_BYTES,
+};
+use openvm_stark_backend::{
+    p3_field::AbstractField, p3_matrix::dense::DenseMatrix, utils::disable_debug_builder,
+    verifier::VerificationError,
+};
+use openvm_stark_sdk::{
+    config::baby_bear_blake3::BabyBearBlake3Config, p3_baby_bear::BabyBear,
+    utils::create_seeded_rng,
+};
+use parking_lot::Mutex;
+use rand::Rng;
+
+use super::{Rv32HintLoadPageChip, Rv32HintLoadPageCols, HINT_PAGE_BLOCKS};
+use crate::adapters::{RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS};
+
+type F = BabyBear;
+
+fn setup() -> (
+    VmChipTestBuilder<F>,
+    Rv32HintLoadPageChip<F>,
+    Arc<BitwiseOperationLookupChip<RV32_CELL_BITS>>,
+    Arc<Mutex<Streams<F>>>,
+) {
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = Arc::new(BitwiseOperationLookupChip::<RV32_CELL_BITS>::new(
+        bitwise_bus,
+    ));
+
+    let tester = VmChipTestBuilder::default();
+    let range_checker_chip = tester.memory_controller().borrow().range_checker.clone();
+    let mut chip = Rv32HintLoadPageChip::<F>::new(
+        tester.execution_bus(),
+        tester.program_bus(),
+        tester.memory_controller(),
+        range_checker_chip,
+        bitwise_chip.clone(),
+        Rv32HintStoreOpcode::default_offset(),
+    );
+    let streams = Arc::new(Mutex::new(Streams::default()));
+    chip.set_streams(streams.clone());
+    (tester, chip, bitwise_chip, streams)
+}
+
+/// Sets rs1 so that `rs1 + imm = mem_ptr`, and returns the HINT_LOAD_PAGE instruction.
+fn load_page_instruction(
+    tester: &mut VmChipTestBuilder<F>,
+    mem_ptr: u32,
+    imm: u32,
+) -> Instruction<F> {
+    let [b, d, e] = [4, 1, 2];
+    let imm_ext = imm + ((imm & 0x8000) >> 15) * 0xffff0000;
+    let rs1 = mem_ptr.wrapping_sub(imm_ext);
+    tester.write(d, b, rs1.to_le_bytes().map(F::from_canonical_u8));
+    Instruction::from_usize(
+        VmOpcode::with_default_offset(HINT_LOAD_PAGE),
+        [0, b, imm as usize, d, e],
+    )
+}
+
+/// Loads one page of random bytes at each of `num_pages` consecutive pages, and checks the
+/// written memory.
+fn build_hint_load_page_test(num_pages: usize) -> VmChipTester<BabyBearBlake3Config> {
+    let mut rng = create_seeded_rng();
+    let (mut tester, mut chip, bitwise_chip, streams) = setup();
+
+    for page in 0..num_pages {
+        let bytes: Vec<u8> = (0..HINT_PAGE_BYTES).map(|_| rng.gen()).collect();
+        streams
+            .lock()
+            .hint_stream
+            .extend(bytes.iter().map(|&byte| F::from_canonical_u8(byte)));
+
+        // Reach mem_ptr with an immediate of either sign
+        let mem_ptr = (page * HINT_PAGE_BYTES) as u32;
+        let imm = rng.gen_range(0..(1 << 16));
+        let instruction = load_page_instruction(&mut tester, mem_ptr, imm);
+        tester.execute(&mut chip, instruction);
+        for (i, word) in bytes.chunks_exact(RV32_REGISTER_NUM_LIMBS).enumerate() {
+            assert_eq!(
+                tester.read::<RV32_REGISTER_NUM_LIMBS>(2, mem_ptr as usize + 4 * i),
+                array::from_fn(|j| F::from_canonical_u8(word[j]))
+            );
+        }
+    }
+    assert!(streams.lock().hint_stream.is_empty());
+
+    tester.build().load(chip).load(bitwise_chip).finalize()
+}
+
+///////////////////////////////////////////////////////////////////////////////////////
+/// POSITIVE TESTS
+///
+/// Randomly generate computations and execute, ensuring that the generated trace
+/// passes all constraints.
+///////////////////////////////////////////////////////////////////////////////////////
+#[test]
+fn rand_hint_load_page_test() {
+    let tester = build_hint_load_page_test(3);
+    tester.simple_test().expect("Verification failed");
+}
+
+#[test]
+fn hint_load_page_errors_test() {
+    let (mut tester, mut chip, _, streams) = setup();
+    let mut execute = |tester: &mut VmChipTestBuilder<F>, mem_ptr: u32| {
+        let instruction = load_page_instruction(tester, mem_ptr, 0);
+        let from_state = ExecutionState {
+            pc: 4,
+            timestamp: tester.memory_controller().borrow().timestamp(),
+        };
+        chip.execute(instruction, from_state)
+    };
+
+    streams
+        .lock()
+        .hint_stream
+        .extend((0..HINT_PAGE_BYTES - 1).map(|_| F::ZERO));
+    assert!(matches!(
+        execute(&mut tester, 0),
+        Err(ExecutionError::HintOutOfBounds { pc: 4 })
+    ));
+
+    streams.lock().hint_stream.push_back(F::ZERO);
+    let address = HINT_PAGE_ALIGN as u32 / 2;
+    assert!(matches!(
+        execute(&mut tester, address),
+        Err(ExecutionError::MisalignedMemoryAccess {
+            pc: 4,
+            address: a,
+            alignment,
+        }) if a == address && alignment == HINT_PAGE_ALIGN as u32
+    ));
+    assert_eq!(streams.lock().hint_stream.len(), HINT_PAGE_BYTES);
+}
+
+//////////////////////////////////////////////////////////////////////////////////////
+// NEGATIVE TESTS
+//
+// Execute a valid page, then replace parts of the generated trace and check that
+// the constraints reject it.
+//////////////////////////////////////////////////////////////////////////////////////
+
+fn run_negative_hint_load_page_test(
+    prank: impl Fn(&mut DenseMatrix<F>),
+    expected_error: VerificationError,
+) {
+    let mut tester = build_hint_load_page_test(1);
+    let trace = tester.air_proof_inputs[2].raw.common_main.as_mut().unwrap();
+    prank(trace);
+    disable_debug_builder();
+    assert_eq!(tester.simple_test().err(), Some(expected_error));
+}
+
+#[test]
+fn negative_hint_load_page_short_page_test() {
+    // A page cannot end before all of its blocks are written.
+    run_negative_hint_load_page_test(
+        |trace| {
+            let cols: &mut Rv32HintLoadPageCols<F> = trace.row_mut(0).borrow_mut();
+            cols.rem_blocks = F::from_canonical_usize(HINT_PAGE_BLOCKS - 2);
+        },
+        VerificationError::OodEvaluationMismatch,
+    );
+}
+
+#[test]
+fn negative_hint_load_page_wrong_byte_test() {
+    // The hinted bytes are range checked.
+    run_negative_hint_load_page_test(
+        |trace| {
+            let cols: &mut Rv32HintLoadPageCols<F> = trace.row_mut(1).borrow_mut();
+            cols.data[0] = F::from_canonical_u32(1 << RV32_CELL_BITS);
+        },
+        VerificationError::ChallengePhaseError,
+    );
+}
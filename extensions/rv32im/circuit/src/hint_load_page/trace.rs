@@ -0,0 +1,78 @@
+use std::{borrow::BorrowMut, sync::Arc};
+
+use openvm_rv32im_transpiler::HINT_PAGE_ALIGN;
+use openvm_stark_backend::{
+    config::{StarkGenericConfig, Val},
+    p3_air::BaseAir,
+    p3_field::{AbstractField, PrimeField32},
+    p3_matrix::dense::RowMajorMatrix,
+    prover::types::AirProofInput,
+    rap::{get_air_name, AnyRap},
+    Chip, ChipUsageGetter,
+};
+
+use super::{Rv32HintLoadPageChip, Rv32HintLoadPageCols, HINT_PAGE_BLOCKS};
+use crate::adapters::RV32_CELL_BITS;
+
+impl<SC: StarkGenericConfig> Chip<SC> for Rv32HintLoadPageChip<Val<SC>>
+where
+    Val<SC>: PrimeField32,
+{
+    fn air(&self) -> Arc<dyn AnyRap<SC>> {
+        Arc::new(self.air)
+    }
+
+    fn generate_air_proof_input(self) -> AirProofInput<SC> {
+        let air = self.air();
+        let trace_width = self.trace_width();
+        let height = self.current_trace_height().next_power_of_two();
+        let aux_cols_factory = self.memory_controller.borrow().aux_cols_factory();
+
+        // Padding rows are all zero
+        let mut trace = RowMajorMatrix::new(Val::<SC>::zero_vec(height * trace_width), trace_width);
+        let mut rows = trace.values.chunks_exact_mut(trace_width);
+        for record in self.records {
+            let write_aux_cols = aux_cols_factory.make_write_range_aux_cols(&record.writes);
+            for (k, (write, write_aux)) in record.writes.blocks().zip(write_aux_cols).enumerate() {
+                let cols: &mut Rv32HintLoadPageCols<Val<SC>> = rows.next().unwrap().borrow_mut();
+                cols.is_valid = Val::<SC>::ONE;
+                cols.timestamp =
+                    Val::<SC>::from_canonical_u32(record.from_state.timestamp + k as u32);
+                if k == 0 {
+                    cols.is_start = Val::<SC>::ONE;
+                    cols.pc = Val::<SC>::from_canonical_u32(record.from_state.pc);
+                    cols.rs1_ptr = record.rs1_read.pointer;
+                    cols.rs1_data = record.rs1_read.data;
+                    cols.rs1_aux_cols = aux_cols_factory.make_read_aux_cols(record.rs1_read);
+                    cols.imm = record.imm;
+                    cols.imm_sign = Val::<SC>::from_bool(record.imm_sign);
+                }
+                let mem_ptr = record.mem_ptr + (k * HINT_PAGE_ALIGN) as u32;
+                cols.mem_ptr_limbs = [
+                    (mem_ptr & 0xffff) / HINT_PAGE_ALIGN as u32,
+                    mem_ptr >> (RV32_CELL_BITS * 2),
+                ]
+                .map(Val::<SC>::from_canonical_u32);
+                cols.data = write.data;
+                cols.write_aux = write_aux;
+                cols.rem_blocks = Val::<SC>::from_canonical_usize(HINT_PAGE_BLOCKS - 1 - k);
+            }
+        }
+
+        AirProofInput::simple_no_pis(air, trace)
+    }
+}
+
+impl<F: PrimeField32> ChipUsageGetter for Rv32HintLoadPageChip<F> {
+    fn air_name(&self) -> String {
+        get_air_name(&self.air)
+    }
+
+    fn current_trace_height(&self) -> usize {
+        self.records.len() * HINT_PAGE_BLOCKS
+    }
+
+    fn trace_width(&self) -> usize {
+        BaseAir::<F>::width(&self.air)
+    }
+}
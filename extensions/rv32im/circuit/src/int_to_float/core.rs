@@ -0,0 +1,330 @@
+use std::{
+    array,
+    borrow::{Borrow, BorrowMut},
+    sync::Arc,
+};
+
+use openvm_circuit::arch::{
+    AdapterAirContext, AdapterRuntimeContext, MinimalInstruction, Result, VmAdapterInterface,
+    VmCoreAir, VmCoreChip,
+};
+use openvm_circuit_primitives::{
+    bitwise_op_lookup::{BitwiseOperationLookupBus, BitwiseOperationLookupChip},
+    is_zero::{IsZeroIo, IsZeroSubAir},
+    utils::not,
+    var_range::VariableRangeCheckerChip,
+    SubAir, TraceSubRowGenerator,
+};
+use openvm_circuit_primitives_derive::AlignedBorrow;
+use openvm_instructions::{instruction::Instruction, UsizeOpcode};
+use openvm_rv32im_transpiler::Rv32IntToFloatOpcode;
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    p3_air::{AirBuilder, BaseAir},
+    p3_field::{AbstractField, Field, PrimeField32},
+    rap::BaseAirWithPublicValues,
+};
+use strum::IntoEnumIterator;
+
+use crate::{
+    adapters::{compose, RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS},
+    float_utils::{
+        bits_to_expr, eval_float_result, request_float_result_range, run_float_round,
+        FloatDecompCols, FloatRoundAir, FloatRoundCols, F32_FRAC_BITS, F32_LSB_EXP_BIAS,
+    },
+};
+
+pub const INT_TO_FLOAT_BITS: usize = RV32_REGISTER_NUM_LIMBS * RV32_CELL_BITS;
+const INT_TO_FLOAT_ROUND_P_MIN: isize = -(F32_FRAC_BITS as isize);
+/// Positions of the rounded significand in the integer, from -23 up to one above any normal
+/// position.
+pub const INT_TO_FLOAT_ROUND_POSITIONS: usize = INT_TO_FLOAT_BITS + 1;
+
+#[repr(C)]
+#[derive(AlignedBorrow)]
+pub struct Rv32IntToFloatCoreCols<T> {
+    pub a: [T; RV32_REGISTER_NUM_LIMBS],
+    pub b: [T; RV32_REGISTER_NUM_LIMBS],
+    pub c: [T; RV32_REGISTER_NUM_LIMBS],
+
+    pub a_decomp: FloatDecompCols<T>,
+    /// Whether b is a negative signed integer
+    pub b_sign: T,
+    /// Carries of the negation of b
+    pub neg_carry: [T; RV32_REGISTER_NUM_LIMBS],
+    pub is_zero: T,
+    pub is_zero_inv: T,
+
+    /// Rounding of the magnitude of b, whose least significant bit has exponent 0
+    pub round: FloatRoundCols<T, INT_TO_FLOAT_BITS, INT_TO_FLOAT_ROUND_POSITIONS>,
+
+    pub opcode_fcvt_s_w_flag: T,
+    pub opcode_fcvt_s_wu_flag: T,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct Rv32IntToFloatCoreAir {
+    pub bus: BitwiseOperationLookupBus,
+    pub round: FloatRoundAir<INT_TO_FLOAT_BITS, INT_TO_FLOAT_ROUND_POSITIONS>,
+    offset: usize,
+}
+
+impl<F: Field> BaseAir<F> for Rv32IntToFloatCoreAir {
+    fn width(&self) -> usize {
+        Rv32IntToFloatCoreCols::<F>::width()
+    }
+}
+impl<F: Field> BaseAirWithPublicValues<F> for Rv32IntToFloatCoreAir {}
+
+impl<AB, I> VmCoreAir<AB, I> for Rv32IntToFloatCoreAir
+where
+    AB: InteractionBuilder,
+    I: VmAdapterInterface<AB::Expr>,
+    I::Reads: From<[[AB::Expr; RV32_REGISTER_NUM_LIMBS]; 2]>,
+    I::Writes: From<[[AB::Expr; RV32_REGISTER_NUM_LIMBS]; 1]>,
+    I::ProcessedInstruction: From<MinimalInstruction<AB::Expr>>,
+{
+    fn eval(
+        &self,
+        builder: &mut AB,
+        local_core: &[AB::Var],
+        _from_pc: AB::Var,
+    ) -> AdapterAirContext<AB::Expr, I> {
+        let cols: &Rv32IntToFloatCoreCols<_> = local_core.borrow();
+        let flags = [cols.opcode_fcvt_s_w_flag, cols.opcode_fcvt_s_wu_flag];
+
+        let is_valid = flags.iter().fold(AB::Expr::ZERO, |acc, &flag| {
+            builder.assert_bool(flag);
+            acc + flag.into()
+        });
+        builder.assert_bool(is_valid.clone());
+
+        let a = eval_float_result(builder, self.bus, &cols.a, &cols.a_decomp, is_valid.clone());
+
+        // b_sign is the top bit of b as long as b[3] - 128 * b_sign < 2^7, and unsigned
+        // integers are never negative.
+        builder.assert_bool(cols.b_sign);
+        builder
+            .when(cols.opcode_fcvt_s_wu_flag)
+            .assert_zero(cols.b_sign);
+        self.bus
+            .send_range(
+                AB::Expr::TWO
+                    * (cols.b[RV32_REGISTER_NUM_LIMBS - 1]
+                        - AB::Expr::from_canonical_u32(1 << 7) * cols.b_sign),
+                AB::Expr::ZERO,
+            )
+            .eval(builder, cols.opcode_fcvt_s_w_flag);
+
+        // The bits being rounded are those of b, or of -b if b is negative, in which case
+        // b + mag = 2^32.
+        let bits = &cols.round.bits;
+        for i in 0..RV32_REGISTER_NUM_LIMBS {
+            let mag = bits_to_expr::<AB>(&bits[i * RV32_CELL_BITS..(i + 1) * RV32_CELL_BITS]);
+            builder
+                .when(not::<AB::Expr>(cols.b_sign))
+                .assert_eq(cols.b[i], mag.clone());
+            builder.assert_bool(cols.neg_carry[i]);
+            let carry_in = if i == 0 {
+                AB::Expr::ZERO
+            } else {
+                cols.neg_carry[i - 1].into()
+            };
+            builder.when(cols.b_sign).assert_eq(
+                cols.b[i] + mag + carry_in,
+                AB::Expr::from_canonical_u32(1 << RV32_CELL_BITS) * cols.neg_carry[i],
+            );
+        }
+
+        // Zero converts to +0, and any other integer is rounded.
+        let bit_count = bits
+            .iter()
+            .fold(AB::Expr::ZERO, |acc, &bit| acc + bit.into());
+        IsZeroSubAir.eval(
+            builder,
+            (
+                IsZeroIo::new(bit_count, cols.is_zero.into(), is_valid.clone()),
+                cols.is_zero_inv,
+            ),
+        );
+        builder
+            .when(is_valid.clone())
+            .assert_eq(a.sign, cols.b_sign);
+        builder.when(cols.is_zero).assert_zero(a.exp.clone());
+        builder.when(cols.is_zero).assert_zero(a.frac.clone());
+        self.round.eval(
+            builder,
+            &cols.round,
+            is_valid.clone() - cols.is_zero,
+            AB::Expr::from_canonical_usize(F32_LSB_EXP_BIAS as usize),
+            a.exp,
+            a.frac,
+        );
+
+        let expected_opcode = flags.iter().zip(Rv32IntToFloatOpcode::iter()).fold(
+            AB::Expr::ZERO,
+            |acc, (flag, local_opcode)| {
+                acc + (*flag).into() * AB::Expr::from_canonical_u8(local_opcode as u8)
+            },
+        ) + AB::Expr::from_canonical_usize(self.offset);
+
+        AdapterAirContext {
+            to_pc: None,
+            reads: [cols.b.map(Into::into), cols.c.map(Into::into)].into(),
+            writes: [cols.a.map(Into::into)].into(),
+            instruction: MinimalInstruction {
+                is_valid,
+                opcode: expected_opcode,
+            }
+            .into(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Rv32IntToFloatCoreRecord<T> {
+    pub opcode: Rv32IntToFloatOpcode,
+    pub a: [T; RV32_REGISTER_NUM_LIMBS],
+    pub b: [T; RV32_REGISTER_NUM_LIMBS],
+    pub c: [T; RV32_REGISTER_NUM_LIMBS],
+}
+
+#[derive(Debug)]
+pub struct Rv32IntToFloatCoreChip {
+    pub air: Rv32IntToFloatCoreAir,
+    pub bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<RV32_CELL_BITS>>,
+    pub range_checker_chip: Arc<VariableRangeCheckerChip>,
+}
+
+impl Rv32IntToFloatCoreChip {
+    pub fn new(
+        bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<RV32_CELL_BITS>>,
+        range_checker_chip: Arc<VariableRangeCheckerChip>,
+        offset: usize,
+    ) -> Self {
+        Self {
+            air: Rv32IntToFloatCoreAir {
+                bus: bitwise_lookup_chip.bus(),
+                round: FloatRoundAir {
+                    bitwise_lookup_bus: bitwise_lookup_chip.bus(),
+                    range_bus: range_checker_chip.bus(),
+                    p_min: INT_TO_FLOAT_ROUND_P_MIN,
+                },
+                offset,
+            },
+            bitwise_lookup_chip,
+            range_checker_chip,
+        }
+    }
+}
+
+impl<F, I> VmCoreChip<F, I> for Rv32IntToFloatCoreChip
+where
+    F: PrimeField32,
+    I: VmAdapterInterface<F>,
+    I::Reads: Into<[[F; RV32_REGISTER_NUM_LIMBS]; 2]>,
+    I::Writes: From<[[F; RV32_REGISTER_NUM_LIMBS]; 1]>,
+{
+    type Record = Rv32IntToFloatCoreRecord<F>;
+    type Air = Rv32IntToFloatCoreAir;
+
+    #[allow(clippy::type_complexity)]
+    fn execute_instruction(
+        &self,
+        instruction: &Instruction<F>,
+        _from_pc: u32,
+        reads: I::Reads,
+    ) -> Result<(AdapterRuntimeContext<F, I>, Self::Record)> {
+        let Instruction { opcode, .. } = instruction;
+        let local_opcode =
+            Rv32IntToFloatOpcode::from_usize(opcode.local_opcode_idx(self.air.offset));
+
+        let data: [[F; RV32_REGISTER_NUM_LIMBS]; 2] = reads.into();
+        let b = compose(data[0]);
+        let a = run_int_to_float(local_opcode, b);
+
+        request_float_result_range(&self.bitwise_lookup_chip, a);
+        if local_opcode == Rv32IntToFloatOpcode::FCVT_S_W {
+            self.bitwise_lookup_chip
+                .request_range(2 * ((b >> 24) & 0x7f), 0);
+        }
+        let mag = int_magnitude(local_opcode, b);
+        if mag != 0 {
+            run_float_round(mag as u64, F32_LSB_EXP_BIAS, self.air.round.p_max())
+                .request_range_checks(
+                    self.air.round.p_max(),
+                    &self.bitwise_lookup_chip,
+                    &self.range_checker_chip,
+                );
+        }
+
+        let a = a.to_le_bytes().map(F::from_canonical_u8);
+        let output = AdapterRuntimeContext::without_pc([a]);
+        let record = Self::Record {
+            opcode: local_opcode,
+            a,
+            b: data[0],
+            c: data[1],
+        };
+
+        Ok((output, record))
+    }
+
+    fn get_opcode_name(&self, opcode: usize) -> String {
+        format!(
+            "{:?}",
+            Rv32IntToFloatOpcode::from_usize(opcode - self.air.offset)
+        )
+    }
+
+    fn generate_trace_row(&self, row_slice: &mut [F], record: Self::Record) {
+        let row_slice: &mut Rv32IntToFloatCoreCols<_> = row_slice.borrow_mut();
+        let a = compose(record.a);
+        let b = compose(record.b);
+        let mag = int_magnitude(record.opcode, b);
+        let b_sign = record.opcode == Rv32IntToFloatOpcode::FCVT_S_W && b >> 31 == 1;
+        let round = (mag != 0)
+            .then(|| run_float_round(mag as u64, F32_LSB_EXP_BIAS, self.air.round.p_max()));
+
+        row_slice.a = record.a;
+        row_slice.b = record.b;
+        row_slice.c = record.c;
+        row_slice.a_decomp = FloatDecompCols::new(a);
+        row_slice.b_sign = F::from_bool(b_sign);
+        // The carry out of byte i is set exactly when some byte up to i of mag is nonzero.
+        let mag_bytes = mag.to_le_bytes();
+        row_slice.neg_carry =
+            array::from_fn(|i| F::from_bool(b_sign && mag_bytes[..=i].iter().any(|&x| x != 0)));
+        IsZeroSubAir.generate_subrow(
+            F::from_canonical_u32(mag.count_ones()),
+            (&mut row_slice.is_zero_inv, &mut row_slice.is_zero),
+        );
+        self.air
+            .round
+            .generate_subrow(mag as u64, round.as_ref(), &mut row_slice.round);
+
+        row_slice.opcode_fcvt_s_w_flag =
+            F::from_bool(record.opcode == Rv32IntToFloatOpcode::FCVT_S_W);
+        row_slice.opcode_fcvt_s_wu_flag =
+            F::from_bool(record.opcode == Rv32IntToFloatOpcode::FCVT_S_WU);
+    }
+
+    fn air(&self) -> &Self::Air {
+        &self.air
+    }
+}
+
+fn int_magnitude(opcode: Rv32IntToFloatOpcode, b: u32) -> u32 {
+    match opcode {
+        Rv32IntToFloatOpcode::FCVT_S_W => (b as i32).unsigned_abs(),
+        Rv32IntToFloatOpcode::FCVT_S_WU => b,
+    }
+}
+
+pub fn run_int_to_float(opcode: Rv32IntToFloatOpcode, b: u32) -> u32 {
+    // Integer to float casts round to nearest, ties to even.
+    match opcode {
+        Rv32IntToFloatOpcode::FCVT_S_W => (b as i32 as f32).to_bits(),
+        Rv32IntToFloatOpcode::FCVT_S_WU => (b as f32).to_bits(),
+    }
+}
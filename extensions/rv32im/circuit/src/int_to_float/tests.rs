@@ -0,0 +1,119 @@
+use std::sync::Arc;
+
+use openvm_circuit::arch::{
+    testing::{memory::gen_pointer, VmChipTestBuilder},
+    BITWISE_OP_LOOKUP_BUS,
+};
+use openvm_circuit_primitives::bitwise_op_lookup::{
+    BitwiseOperationLookupBus, BitwiseOperationLookupChip,
+};
+use openvm_instructions::{instruction::Instruction, VmOpcode};
+use openvm_rv32im_transpiler::Rv32IntToFloatOpcode::{self, *};
+use openvm_stark_backend::p3_field::AbstractField;
+use openvm_stark_sdk::{p3_baby_bear::BabyBear, utils::create_seeded_rng};
+use rand::{rngs::StdRng, Rng};
+
+use super::{run_int_to_float, Rv32IntToFloatChip, Rv32IntToFloatCoreChip};
+use crate::adapters::{Rv32BaseAluAdapterChip, RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS};
+
+type F = BabyBear;
+
+fn set_and_execute(
+    tester: &mut VmChipTestBuilder<F>,
+    chip: &mut Rv32IntToFloatChip<F>,
+    rng: &mut StdRng,
+    opcode: Rv32IntToFloatOpcode,
+    b: u32,
+) {
+    let rs1 = gen_pointer(rng, RV32_REGISTER_NUM_LIMBS);
+    let rd = gen_pointer(rng, RV32_REGISTER_NUM_LIMBS);
+    tester.write(1, rs1, b.to_le_bytes().map(F::from_canonical_u8));
+
+    tester.execute(
+        chip,
+        Instruction::from_usize(VmOpcode::from_usize(opcode as usize), [rd, rs1, 0, 1, 0]),
+    );
+    assert_eq!(
+        run_int_to_float(opcode, b)
+            .to_le_bytes()
+            .map(F::from_canonical_u8),
+        tester.read::<RV32_REGISTER_NUM_LIMBS>(1, rd)
+    );
+}
+
+//////////////////////////////////////////////////////////////////////////////////////
+// POSITIVE TESTS
+//
+// Randomly generate computations and execute, ensuring that the generated trace
+// passes all constraints.
+//////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn rv32_int_to_float_rand_test() {
+    let mut rng = create_seeded_rng();
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = Arc::new(BitwiseOperationLookupChip::<RV32_CELL_BITS>::new(
+        bitwise_bus,
+    ));
+
+    let mut tester = VmChipTestBuilder::default();
+    let mut chip = Rv32IntToFloatChip::<F>::new(
+        Rv32BaseAluAdapterChip::new(
+            tester.execution_bus(),
+            tester.program_bus(),
+            tester.memory_controller(),
+        ),
+        Rv32IntToFloatCoreChip::new(
+            bitwise_chip.clone(),
+            tester.memory_controller().borrow().range_checker.clone(),
+            0,
+        ),
+        tester.memory_controller(),
+    );
+
+    for opcode in [FCVT_S_W, FCVT_S_WU] {
+        for b in [0, 1, u32::MAX, 1 << 31, (1 << 24) + 1, (1 << 25) + 3] {
+            set_and_execute(&mut tester, &mut chip, &mut rng, opcode, b);
+        }
+        for _ in 0..50 {
+            // Integers of all sizes, most of which need rounding.
+            let b = rng.gen::<u32>() >> rng.gen_range(0..32);
+            set_and_execute(&mut tester, &mut chip, &mut rng, opcode, b);
+        }
+    }
+
+    let tester = tester.build().load(chip).load(bitwise_chip).finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
+//////////////////////////////////////////////////////////////////////////////////////
+// SANITY TESTS
+//
+// Ensure that the run_* function produces the expected results.
+//////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn run_int_to_float_sanity_test() {
+    assert_eq!(
+        run_int_to_float(FCVT_S_W, -3i32 as u32),
+        (-3.0f32).to_bits()
+    );
+    assert_eq!(
+        run_int_to_float(FCVT_S_WU, -3i32 as u32),
+        4294967296.0f32.to_bits()
+    );
+    assert_eq!(run_int_to_float(FCVT_S_W, 0), 0);
+    assert_eq!(
+        run_int_to_float(FCVT_S_W, 1 << 31),
+        (-2147483648.0f32).to_bits()
+    );
+    // 2^24 + 1 is a tie that rounds to the even 2^24, and 2^24 + 3 rounds up.
+    assert_eq!(
+        run_int_to_float(FCVT_S_WU, (1 << 24) + 1),
+        16777216.0f32.to_bits()
+    );
+    assert_eq!(
+        run_int_to_float(FCVT_S_WU, (1 << 24) + 3),
+        16777220.0f32.to_bits()
+    );
+}
@@ -236,7 +236,7 @@ where
 }
 
 // returns (to_pc, rd_data)
-pub(super) fn run_jal_lui(
+pub(crate) fn run_jal_lui(
     opcode: Rv32JalLuiOpcode,
     pc: u32,
     imm: i32,
@@ -298,7 +298,7 @@ where
 }
 
 // Returns (cmp_result, diff_idx, x_sign, y_sign)
-pub(super) fn run_less_than<const NUM_LIMBS: usize, const LIMB_BITS: usize>(
+pub(crate) fn run_less_than<const NUM_LIMBS: usize, const LIMB_BITS: usize>(
     opcode: LessThanOpcode,
     x: &[u32; NUM_LIMBS],
     y: &[u32; NUM_LIMBS],
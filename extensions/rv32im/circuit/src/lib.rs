@@ -1,37 +1,78 @@
 pub mod adapters;
 
+mod atomic;
 mod auipc;
 mod base_alu;
+mod bit_count;
 mod branch_eq;
 mod branch_lt;
+mod byte_op;
+mod csr;
 mod divrem;
+mod float_add;
+mod float_cmp;
+mod float_misc;
+mod float_mul;
+mod float_to_int;
+mod float_utils;
+mod hint_buffer;
+mod hint_load_page;
 mod hintstore;
+mod int_to_float;
 mod jal_lui;
 mod jalr;
 mod less_than;
 mod load_sign_extend;
 mod loadstore;
+mod logic_not;
+mod memcpy;
+mod min_max;
 mod mul;
 mod mulh;
+mod mulh_mul;
+mod rotate;
+mod sh_add;
 mod shift;
 
+pub use atomic::*;
 pub use auipc::*;
 pub use base_alu::*;
+pub use bit_count::*;
 pub use branch_eq::*;
 pub use branch_lt::*;
+pub use byte_op::*;
+pub use csr::*;
 pub use divrem::*;
+pub use float_add::*;
+pub use float_cmp::*;
+pub use float_misc::*;
+pub use float_mul::*;
+pub use float_to_int::*;
+pub use float_utils::*;
+pub use hint_buffer::*;
+pub use hint_load_page::*;
 pub use hintstore::*;
+pub use int_to_float::*;
 pub use jal_lui::*;
 pub use jalr::*;
 pub use less_than::*;
 pub use load_sign_extend::*;
 pub use loadstore::*;
+pub use logic_not::*;
+pub use memcpy::*;
+pub use min_max::*;
 pub use mul::*;
 pub use mulh::*;
+pub use mulh_mul::*;
+pub use rotate::*;
+pub use sh_add::*;
 pub use shift::*;
 
 mod extension;
 pub use extension::*;
 
+pub mod difftest;
+pub mod rv32im_model;
+
 #[cfg(any(test, feature = "test-utils"))]
 mod test_utils;
@@ -21,7 +21,7 @@ use openvm_stark_backend::{
     rap::BaseAirWithPublicValues,
 };
 
-use crate::adapters::LoadStoreInstruction;
+use crate::{adapters::LoadStoreInstruction, loadstore::check_alignment};
 
 /// LoadSignExtend Core Chip handles byte/halfword into word conversions through sign extend
 /// This chip uses read_data to construct write_data
@@ -200,14 +200,16 @@ where
     fn execute_instruction(
         &self,
         instruction: &Instruction<F>,
-        _from_pc: u32,
+        from_pc: u32,
         reads: I::Reads,
     ) -> Result<(AdapterRuntimeContext<F, I>, Self::Record)> {
         let local_opcode =
             Rv32LoadStoreOpcode::from_usize(instruction.opcode.local_opcode_idx(self.air.offset));
 
-        let (data, shift_amount) = reads.into();
-        let shift_amount = shift_amount.as_canonical_u32();
+        let (data, ptr) = reads.into();
+        let ptr = ptr.as_canonical_u32();
+        check_alignment(local_opcode, ptr, from_pc)?;
+        let shift_amount = ptr % 4;
         let write_data: [F; NUM_CELLS] = run_write_data_sign_extend::<_, NUM_CELLS, LIMB_BITS>(
             local_opcode,
             data[1],
@@ -268,7 +270,7 @@ where
     }
 }
 
-pub(super) fn run_write_data_sign_extend<
+pub(crate) fn run_write_data_sign_extend<
     F: PrimeField32,
     const NUM_CELLS: usize,
     const LIMB_BITS: usize,
@@ -1,7 +1,8 @@
 use std::borrow::{Borrow, BorrowMut};
 
 use openvm_circuit::arch::{
-    AdapterAirContext, AdapterRuntimeContext, Result, VmAdapterInterface, VmCoreAir, VmCoreChip,
+    AdapterAirContext, AdapterRuntimeContext, ExecutionError, Result, VmAdapterInterface,
+    VmCoreAir, VmCoreChip,
 };
 use openvm_circuit_primitives_derive::AlignedBorrow;
 use openvm_instructions::{instruction::Instruction, UsizeOpcode};
@@ -259,14 +260,16 @@ where
     fn execute_instruction(
         &self,
         instruction: &Instruction<F>,
-        _from_pc: u32,
+        from_pc: u32,
         reads: I::Reads,
     ) -> Result<(AdapterRuntimeContext<F, I>, Self::Record)> {
         let local_opcode =
             Rv32LoadStoreOpcode::from_usize(instruction.opcode.local_opcode_idx(self.air.offset));
 
-        let (reads, shift_amount) = reads.into();
-        let shift = shift_amount.as_canonical_u32();
+        let (reads, ptr) = reads.into();
+        let ptr = ptr.as_canonical_u32();
+        check_alignment(local_opcode, ptr, from_pc)?;
+        let shift = ptr % 4;
         let prev_data = reads[0];
         let read_data = reads[1];
         let write_data = run_write_data(local_opcode, read_data, prev_data, shift);
@@ -327,7 +330,24 @@ where
     }
 }
 
-pub(super) fn run_write_data<F: PrimeField32, const NUM_CELLS: usize>(
+/// Returns an error if the memory access of `opcode` at `address` is not aligned to its width.
+pub(crate) fn check_alignment(opcode: Rv32LoadStoreOpcode, address: u32, pc: u32) -> Result<()> {
+    let alignment = match opcode {
+        LOADW | STOREW => 4,
+        LOADH | LOADHU | STOREH => 2,
+        LOADB | LOADBU | STOREB => 1,
+    };
+    if address % alignment != 0 {
+        return Err(ExecutionError::MisalignedMemoryAccess {
+            pc,
+            address,
+            alignment,
+        });
+    }
+    Ok(())
+}
+
+pub(crate) fn run_write_data<F: PrimeField32, const NUM_CELLS: usize>(
     opcode: Rv32LoadStoreOpcode,
     read_data: [F; NUM_CELLS],
     prev_data: [F; NUM_CELLS],
@@ -3,7 +3,7 @@ use std::{array, borrow::BorrowMut};
 use openvm_circuit::{
     arch::{
         testing::{memory::gen_pointer, VmChipTestBuilder},
-        VmAdapterChip,
+        ExecutionError, ExecutionState, InstructionExecutor, VmAdapterChip,
     },
     utils::{u32_into_limbs, u32_sign_extend},
 };
@@ -278,6 +278,46 @@ fn negative_write_data_tests() {
     );
 }
 
+#[test]
+fn misaligned_access_test() {
+    let mut tester = VmChipTestBuilder::default();
+    let range_checker_chip = tester.memory_controller().borrow().range_checker.clone();
+    let adapter = Rv32LoadStoreAdapterChip::<F>::new(
+        tester.execution_bus(),
+        tester.program_bus(),
+        tester.memory_controller(),
+        range_checker_chip,
+        Rv32LoadStoreOpcode::default_offset(),
+    );
+    let core = LoadStoreCoreChip::new(Rv32LoadStoreOpcode::default_offset());
+    let mut chip = Rv32LoadStoreChip::<F>::new(adapter, core, tester.memory_controller());
+
+    for (opcode, address, alignment) in [(LOADW, 102, 4), (STOREW, 101, 4), (STOREH, 103, 2)] {
+        tester.write(
+            1,
+            4,
+            u32_into_limbs::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(100)
+                .map(F::from_canonical_u32),
+        );
+        let from_state = ExecutionState {
+            pc: 8,
+            timestamp: tester.memory_controller().borrow().timestamp(),
+        };
+        let result = chip.execute(
+            Instruction::from_usize(
+                VmOpcode::with_default_offset(opcode),
+                [8, 4, address - 100, 1, 2],
+            ),
+            from_state,
+        );
+        assert!(matches!(
+            result,
+            Err(ExecutionError::MisalignedMemoryAccess { pc: 8, address: a, alignment: b })
+                if a == address as u32 && b == alignment
+        ));
+    }
+}
+
 ///////////////////////////////////////////////////////////////////////////////////////
 /// SANITY TESTS
 ///
@@ -0,0 +1,217 @@
+use std::{
+    array,
+    borrow::{Borrow, BorrowMut},
+    sync::Arc,
+};
+
+use openvm_circuit::arch::{
+    AdapterAirContext, AdapterRuntimeContext, MinimalInstruction, Result, VmAdapterInterface,
+    VmCoreAir, VmCoreChip,
+};
+use openvm_circuit_primitives::bitwise_op_lookup::{
+    BitwiseOperationLookupBus, BitwiseOperationLookupChip,
+};
+use openvm_circuit_primitives_derive::AlignedBorrow;
+use openvm_instructions::{instruction::Instruction, UsizeOpcode};
+use openvm_rv32im_transpiler::Rv32LogicNotOpcode;
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    p3_air::{AirBuilder, BaseAir},
+    p3_field::{AbstractField, Field, PrimeField32},
+    rap::BaseAirWithPublicValues,
+};
+use strum::IntoEnumIterator;
+
+use crate::adapters::{RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS};
+
+#[repr(C)]
+#[derive(AlignedBorrow)]
+pub struct Rv32LogicNotCoreCols<T> {
+    pub a: [T; RV32_REGISTER_NUM_LIMBS],
+    pub b: [T; RV32_REGISTER_NUM_LIMBS],
+    pub c: [T; RV32_REGISTER_NUM_LIMBS],
+
+    pub opcode_andn_flag: T,
+    pub opcode_orn_flag: T,
+    pub opcode_xnor_flag: T,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct Rv32LogicNotCoreAir {
+    pub bus: BitwiseOperationLookupBus,
+    offset: usize,
+}
+
+impl<F: Field> BaseAir<F> for Rv32LogicNotCoreAir {
+    fn width(&self) -> usize {
+        Rv32LogicNotCoreCols::<F>::width()
+    }
+}
+impl<F: Field> BaseAirWithPublicValues<F> for Rv32LogicNotCoreAir {}
+
+impl<AB, I> VmCoreAir<AB, I> for Rv32LogicNotCoreAir
+where
+    AB: InteractionBuilder,
+    I: VmAdapterInterface<AB::Expr>,
+    I::Reads: From<[[AB::Expr; RV32_REGISTER_NUM_LIMBS]; 2]>,
+    I::Writes: From<[[AB::Expr; RV32_REGISTER_NUM_LIMBS]; 1]>,
+    I::ProcessedInstruction: From<MinimalInstruction<AB::Expr>>,
+{
+    fn eval(
+        &self,
+        builder: &mut AB,
+        local_core: &[AB::Var],
+        _from_pc: AB::Var,
+    ) -> AdapterAirContext<AB::Expr, I> {
+        let cols: &Rv32LogicNotCoreCols<_> = local_core.borrow();
+        let flags = [
+            cols.opcode_andn_flag,
+            cols.opcode_orn_flag,
+            cols.opcode_xnor_flag,
+        ];
+
+        let is_valid = flags.iter().fold(AB::Expr::ZERO, |acc, &flag| {
+            builder.assert_bool(flag);
+            acc + flag.into()
+        });
+        builder.assert_bool(is_valid.clone());
+
+        // As b + c = (b ^ c) + 2 * (b & c), we have
+        // - b & !c = b - (b & c) = (b - c + (b ^ c)) / 2
+        // - b | !c = !(!b & c) = max - (c - b + (b ^ c)) / 2
+        // - !(b ^ c) = max - (b ^ c)
+        // where max = 2^LIMB_BITS - 1, so the XOR lookup constrains a.
+        let max = AB::Expr::from_canonical_u32((1 << RV32_CELL_BITS) - 1);
+        let two = AB::Expr::from_canonical_u32(2);
+        for i in 0..RV32_REGISTER_NUM_LIMBS {
+            let (a, b, c) = (cols.a[i], cols.b[i], cols.c[i]);
+            let b_xor_c = cols.opcode_andn_flag * (two.clone() * a - b + c)
+                + cols.opcode_orn_flag * (two.clone() * (max.clone() - a) - c + b)
+                + cols.opcode_xnor_flag * (max.clone() - a);
+            self.bus
+                .send_xor(b, c, b_xor_c)
+                .eval(builder, is_valid.clone());
+        }
+
+        let expected_opcode = flags.iter().zip(Rv32LogicNotOpcode::iter()).fold(
+            AB::Expr::ZERO,
+            |acc, (flag, local_opcode)| {
+                acc + (*flag).into() * AB::Expr::from_canonical_u8(local_opcode as u8)
+            },
+        ) + AB::Expr::from_canonical_usize(self.offset);
+
+        AdapterAirContext {
+            to_pc: None,
+            reads: [cols.b.map(Into::into), cols.c.map(Into::into)].into(),
+            writes: [cols.a.map(Into::into)].into(),
+            instruction: MinimalInstruction {
+                is_valid,
+                opcode: expected_opcode,
+            }
+            .into(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Rv32LogicNotCoreRecord<T> {
+    pub opcode: Rv32LogicNotOpcode,
+    pub a: [T; RV32_REGISTER_NUM_LIMBS],
+    pub b: [T; RV32_REGISTER_NUM_LIMBS],
+    pub c: [T; RV32_REGISTER_NUM_LIMBS],
+}
+
+#[derive(Debug)]
+pub struct Rv32LogicNotCoreChip {
+    pub air: Rv32LogicNotCoreAir,
+    pub bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<RV32_CELL_BITS>>,
+}
+
+impl Rv32LogicNotCoreChip {
+    pub fn new(
+        bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<RV32_CELL_BITS>>,
+        offset: usize,
+    ) -> Self {
+        Self {
+            air: Rv32LogicNotCoreAir {
+                bus: bitwise_lookup_chip.bus(),
+                offset,
+            },
+            bitwise_lookup_chip,
+        }
+    }
+}
+
+impl<F, I> VmCoreChip<F, I> for Rv32LogicNotCoreChip
+where
+    F: PrimeField32,
+    I: VmAdapterInterface<F>,
+    I::Reads: Into<[[F; RV32_REGISTER_NUM_LIMBS]; 2]>,
+    I::Writes: From<[[F; RV32_REGISTER_NUM_LIMBS]; 1]>,
+{
+    type Record = Rv32LogicNotCoreRecord<F>;
+    type Air = Rv32LogicNotCoreAir;
+
+    #[allow(clippy::type_complexity)]
+    fn execute_instruction(
+        &self,
+        instruction: &Instruction<F>,
+        _from_pc: u32,
+        reads: I::Reads,
+    ) -> Result<(AdapterRuntimeContext<F, I>, Self::Record)> {
+        let Instruction { opcode, .. } = instruction;
+        let local_opcode = Rv32LogicNotOpcode::from_usize(opcode.local_opcode_idx(self.air.offset));
+
+        let data: [[F; RV32_REGISTER_NUM_LIMBS]; 2] = reads.into();
+        let b = data[0].map(|x| x.as_canonical_u32());
+        let c = data[1].map(|y| y.as_canonical_u32());
+        let a = run_logic_not(local_opcode, &b, &c);
+        for (b_val, c_val) in b.iter().zip(c.iter()) {
+            self.bitwise_lookup_chip.request_xor(*b_val, *c_val);
+        }
+
+        let output = AdapterRuntimeContext::without_pc([a.map(F::from_canonical_u32)]);
+        let record = Self::Record {
+            opcode: local_opcode,
+            a: a.map(F::from_canonical_u32),
+            b: data[0],
+            c: data[1],
+        };
+
+        Ok((output, record))
+    }
+
+    fn get_opcode_name(&self, opcode: usize) -> String {
+        format!(
+            "{:?}",
+            Rv32LogicNotOpcode::from_usize(opcode - self.air.offset)
+        )
+    }
+
+    fn generate_trace_row(&self, row_slice: &mut [F], record: Self::Record) {
+        let row_slice: &mut Rv32LogicNotCoreCols<_> = row_slice.borrow_mut();
+        row_slice.a = record.a;
+        row_slice.b = record.b;
+        row_slice.c = record.c;
+        row_slice.opcode_andn_flag = F::from_bool(record.opcode == Rv32LogicNotOpcode::ANDN);
+        row_slice.opcode_orn_flag = F::from_bool(record.opcode == Rv32LogicNotOpcode::ORN);
+        row_slice.opcode_xnor_flag = F::from_bool(record.opcode == Rv32LogicNotOpcode::XNOR);
+    }
+
+    fn air(&self) -> &Self::Air {
+        &self.air
+    }
+}
+
+pub fn run_logic_not(
+    opcode: Rv32LogicNotOpcode,
+    b: &[u32; RV32_REGISTER_NUM_LIMBS],
+    c: &[u32; RV32_REGISTER_NUM_LIMBS],
+) -> [u32; RV32_REGISTER_NUM_LIMBS] {
+    let max = (1 << RV32_CELL_BITS) - 1;
+    array::from_fn(|i| match opcode {
+        Rv32LogicNotOpcode::ANDN => b[i] & !c[i] & max,
+        Rv32LogicNotOpcode::ORN => (b[i] | !c[i]) & max,
+        Rv32LogicNotOpcode::XNOR => !(b[i] ^ c[i]) & max,
+    })
+}
@@ -0,0 +1,112 @@
+use std::sync::Arc;
+
+use openvm_circuit::{
+    arch::{testing::VmChipTestBuilder, BITWISE_OP_LOOKUP_BUS},
+    utils::generate_long_number,
+};
+use openvm_circuit_primitives::bitwise_op_lookup::{
+    BitwiseOperationLookupBus, BitwiseOperationLookupChip,
+};
+use openvm_rv32im_transpiler::Rv32LogicNotOpcode;
+use openvm_stark_backend::p3_field::AbstractField;
+use openvm_stark_sdk::{p3_baby_bear::BabyBear, utils::create_seeded_rng};
+use rand::Rng;
+
+use super::{run_logic_not, Rv32LogicNotChip, Rv32LogicNotCoreChip};
+use crate::{
+    adapters::{Rv32BaseAluAdapterChip, RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS},
+    test_utils::{generate_rv32_is_type_immediate, rv32_rand_write_register_or_imm},
+};
+
+type F = BabyBear;
+
+//////////////////////////////////////////////////////////////////////////////////////
+// POSITIVE TESTS
+//
+// Randomly generate computations and execute, ensuring that the generated trace
+// passes all constraints.
+//////////////////////////////////////////////////////////////////////////////////////
+
+fn run_rv32_logic_not_rand_test(opcode: Rv32LogicNotOpcode, num_ops: usize) {
+    let mut rng = create_seeded_rng();
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = Arc::new(BitwiseOperationLookupChip::<RV32_CELL_BITS>::new(
+        bitwise_bus,
+    ));
+
+    let mut tester = VmChipTestBuilder::default();
+    let mut chip = Rv32LogicNotChip::<F>::new(
+        Rv32BaseAluAdapterChip::new(
+            tester.execution_bus(),
+            tester.program_bus(),
+            tester.memory_controller(),
+        ),
+        Rv32LogicNotCoreChip::new(bitwise_chip.clone(), 0),
+        tester.memory_controller(),
+    );
+
+    for _ in 0..num_ops {
+        let b = generate_long_number::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(&mut rng);
+        let (c_imm, c) = if rng.gen_bool(0.5) {
+            (
+                None,
+                generate_long_number::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(&mut rng),
+            )
+        } else {
+            let (imm, c) = generate_rv32_is_type_immediate(&mut rng);
+            (Some(imm), c)
+        };
+
+        let (instruction, rd) =
+            rv32_rand_write_register_or_imm(&mut tester, b, c, c_imm, opcode as usize, &mut rng);
+        tester.execute(&mut chip, instruction);
+
+        let a = run_logic_not(opcode, &b, &c);
+        assert_eq!(
+            a.map(F::from_canonical_u32),
+            tester.read::<RV32_REGISTER_NUM_LIMBS>(1, rd)
+        )
+    }
+
+    let tester = tester.build().load(chip).load(bitwise_chip).finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
+#[test]
+fn rv32_logic_not_andn_rand_test() {
+    run_rv32_logic_not_rand_test(Rv32LogicNotOpcode::ANDN, 100);
+}
+
+#[test]
+fn rv32_logic_not_orn_rand_test() {
+    run_rv32_logic_not_rand_test(Rv32LogicNotOpcode::ORN, 100);
+}
+
+#[test]
+fn rv32_logic_not_xnor_rand_test() {
+    run_rv32_logic_not_rand_test(Rv32LogicNotOpcode::XNOR, 100);
+}
+
+//////////////////////////////////////////////////////////////////////////////////////
+// SANITY TESTS
+//
+// Ensure that the run_* function produces the expected results.
+//////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn run_logic_not_sanity_test() {
+    let x: [u32; RV32_REGISTER_NUM_LIMBS] = [0b1100, 0xff, 0, 0x5a];
+    let y: [u32; RV32_REGISTER_NUM_LIMBS] = [0b1010, 0, 0xff, 0xa5];
+    assert_eq!(
+        run_logic_not(Rv32LogicNotOpcode::ANDN, &x, &y),
+        [0b0100, 0xff, 0, 0x5a]
+    );
+    assert_eq!(
+        run_logic_not(Rv32LogicNotOpcode::ORN, &x, &y),
+        [0xfd, 0xff, 0, 0x5a]
+    );
+    assert_eq!(
+        run_logic_not(Rv32LogicNotOpcode::XNOR, &x, &y),
+        [0xf9, 0, 0, 0]
+    );
+}
@@ -0,0 +1,212 @@
+use std::borrow::Borrow;
+
+use openvm_circuit::{
+    arch::{ExecutionBridge, ExecutionState},
+    system::memory::{
+        offline_checker::{MemoryBridge, MemoryReadAuxCols, MemoryWriteAuxCols},
+        MemoryAddress,
+    },
+};
+use openvm_circuit_primitives::{bitwise_op_lookup::BitwiseOperationLookupBus, utils::not};
+use openvm_circuit_primitives_derive::AlignedBorrow;
+use openvm_instructions::riscv::{RV32_MEMORY_AS, RV32_REGISTER_AS};
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    p3_air::{Air, AirBuilder, BaseAir},
+    p3_field::{AbstractField, Field},
+    p3_matrix::Matrix,
+    rap::{BaseAirWithPublicValues, PartitionedBaseAir},
+};
+
+use super::{MEMCPY_REGISTER_READS, MEMCPY_WORD_TIMESTAMPS};
+use crate::adapters::{abstract_compose, RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS};
+
+/// The first row of a MEMCPY or MEMSET instruction reads the registers, and each following row
+/// copies one word.
+#[repr(C)]
+#[derive(Debug, Clone, AlignedBorrow)]
+pub struct Rv32MemcpyCols<T> {
+    pub is_valid: T,
+    /// Whether this row is the first row of an instruction
+    pub is_start: T,
+    pub is_memset: T,
+    /// Only set on the first row
+    pub pc: T,
+    /// The timestamp of the first register read on the first row, and of the read of the
+    /// source word on the other rows
+    pub timestamp: T,
+
+    // Only set on the first row:
+    pub rd_ptr: T,
+    pub rs1_ptr: T,
+    pub rs2_ptr: T,
+    pub dst_data: [T; RV32_REGISTER_NUM_LIMBS],
+    pub len_data: [T; RV32_REGISTER_NUM_LIMBS],
+    pub register_aux: [MemoryReadAuxCols<T, RV32_REGISTER_NUM_LIMBS>; MEMCPY_REGISTER_READS],
+
+    /// Destination and source pointers of the word copied on this row. On the first row, the
+    /// pointers of the first word.
+    pub dst: T,
+    pub src: T,
+    /// The word copied on this row. On the first row, the value of `rs1`, which is the fill
+    /// value for MEMSET.
+    pub data: [T; RV32_REGISTER_NUM_LIMBS],
+    pub read_aux: MemoryReadAuxCols<T, RV32_REGISTER_NUM_LIMBS>,
+    pub write_aux: MemoryWriteAuxCols<T, RV32_REGISTER_NUM_LIMBS>,
+    /// Number of words left to copy after this row
+    pub rem_words: T,
+}
+
+#[derive(Clone, Copy, Debug, derive_new::new)]
+pub struct Rv32MemcpyAir {
+    pub execution_bridge: ExecutionBridge,
+    pub memory_bridge: MemoryBridge,
+    pub bitwise_lookup_bus: BitwiseOperationLookupBus,
+    /// Maximum number of bits allowed for an address pointer
+    pub pointer_max_bits: usize,
+    pub(super) offset: usize,
+}
+
+impl<F: Field> BaseAirWithPublicValues<F> for Rv32MemcpyAir {}
+impl<F: Field> PartitionedBaseAir<F> for Rv32MemcpyAir {}
+impl<F: Field> BaseAir<F> for Rv32MemcpyAir {
+    fn width(&self) -> usize {
+        Rv32MemcpyCols::<F>::width()
+    }
+}
+
+impl<AB: InteractionBuilder> Air<AB> for Rv32MemcpyAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let (local, next) = (main.row_slice(0), main.row_slice(1));
+        let local: &Rv32MemcpyCols<AB::Var> = (*local).borrow();
+        let next: &Rv32MemcpyCols<AB::Var> = (*next).borrow();
+
+        builder.assert_bool(local.is_valid);
+        builder.assert_bool(local.is_start);
+        builder.assert_bool(local.is_memset);
+        builder.when(local.is_start).assert_one(local.is_valid);
+        builder
+            .when_first_row()
+            .assert_eq(local.is_valid, local.is_start);
+
+        // A valid row that does not start an instruction copies the next word of the instruction
+        // of the row before it.
+        let next_is_continue = next.is_valid - next.is_start;
+        let word_step =
+            AB::Expr::from_canonical_usize(RV32_REGISTER_NUM_LIMBS) * not(local.is_start);
+        let mut continue_builder = builder.when(next_is_continue.clone());
+        continue_builder.assert_one(local.is_valid);
+        continue_builder.assert_eq(next.is_memset, local.is_memset);
+        continue_builder.assert_eq(next.rem_words, local.rem_words - AB::Expr::ONE);
+        continue_builder.assert_eq(next.dst, local.dst + word_step.clone());
+        continue_builder.assert_eq(next.src, local.src + word_step);
+        continue_builder.assert_eq(
+            next.timestamp,
+            local.timestamp
+                + AB::Expr::from_canonical_usize(MEMCPY_WORD_TIMESTAMPS)
+                + local.is_start,
+        );
+        // MEMSET writes the fill value, which is in `data` on the first row, to every word.
+        let mut memset_builder = builder.when(next_is_continue.clone() * next.is_memset);
+        for (&next_data, &local_data) in next.data.iter().zip(local.data.iter()) {
+            memset_builder.assert_eq(next_data, local_data);
+        }
+        // An instruction ends exactly when it has no words left to copy.
+        builder
+            .when_transition()
+            .assert_zero(local.rem_words * not::<AB::Expr>(next_is_continue));
+        builder.when_last_row().assert_zero(local.rem_words);
+
+        self.eval_start(builder, local);
+
+        let is_word = local.is_valid - local.is_start;
+        self.memory_bridge
+            .read(
+                MemoryAddress::new(AB::F::from_canonical_u32(RV32_MEMORY_AS), local.src),
+                local.data,
+                local.timestamp,
+                &local.read_aux,
+            )
+            .eval(builder, is_word.clone() * not(local.is_memset));
+        self.memory_bridge
+            .write(
+                MemoryAddress::new(AB::F::from_canonical_u32(RV32_MEMORY_AS), local.dst),
+                local.data,
+                local.timestamp + AB::Expr::ONE,
+                &local.write_aux,
+            )
+            .eval(builder, is_word);
+    }
+}
+
+impl Rv32MemcpyAir {
+    /// Constrains the first row of an instruction: the register reads, and that the pointers and
+    /// number of words of the row are the register values.
+    fn eval_start<AB: InteractionBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &Rv32MemcpyCols<AB::Var>,
+    ) {
+        let is_start = local.is_start;
+
+        for (i, (ptr, data)) in [
+            (local.rd_ptr, local.dst_data),
+            (local.rs1_ptr, local.data),
+            (local.rs2_ptr, local.len_data),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            self.memory_bridge
+                .read(
+                    MemoryAddress::new(AB::F::from_canonical_u32(RV32_REGISTER_AS), ptr),
+                    data,
+                    local.timestamp + AB::F::from_canonical_usize(i),
+                    &local.register_aux[i],
+                )
+                .eval(builder, is_start);
+        }
+
+        let mut start_builder = builder.when(is_start);
+        start_builder.assert_eq(local.dst, abstract_compose::<AB::Expr, _>(local.dst_data));
+        start_builder.assert_eq(local.src, abstract_compose::<AB::Expr, _>(local.data));
+        start_builder.assert_eq(
+            local.rem_words,
+            abstract_compose::<AB::Expr, _>(local.len_data),
+        );
+
+        // Range check that the pointers are less than 2^pointer_max_bits. The fill value of
+        // MEMSET is not a pointer.
+        let limb_shift = AB::F::from_canonical_usize(
+            1 << (RV32_CELL_BITS * RV32_REGISTER_NUM_LIMBS - self.pointer_max_bits),
+        );
+        self.bitwise_lookup_bus
+            .send_range(
+                local.dst_data[RV32_REGISTER_NUM_LIMBS - 1] * limb_shift,
+                local.data[RV32_REGISTER_NUM_LIMBS - 1]
+                    * not::<AB::Expr>(local.is_memset)
+                    * limb_shift,
+            )
+            .eval(builder, is_start);
+
+        self.execution_bridge
+            .execute_and_increment_pc(
+                AB::Expr::from_canonical_usize(self.offset) + local.is_memset,
+                [
+                    local.rd_ptr.into(),
+                    local.rs1_ptr.into(),
+                    local.rs2_ptr.into(),
+                    AB::Expr::from_canonical_u32(RV32_REGISTER_AS),
+                    AB::Expr::from_canonical_u32(RV32_MEMORY_AS),
+                ],
+                ExecutionState {
+                    pc: local.pc,
+                    timestamp: local.timestamp,
+                },
+                AB::Expr::from_canonical_usize(MEMCPY_REGISTER_READS)
+                    + local.rem_words * AB::F::from_canonical_usize(MEMCPY_WORD_TIMESTAMPS),
+            )
+            .eval(builder, is_start);
+    }
+}
@@ -0,0 +1,182 @@
+use std::sync::Arc;
+
+use openvm_circuit::{
+    arch::{ExecutionBridge, ExecutionBus, ExecutionError, ExecutionState, InstructionExecutor},
+    system::{
+        memory::{MemoryControllerRef, MemoryReadRecord, MemoryWriteRecord},
+        program::ProgramBus,
+    },
+};
+use openvm_circuit_primitives::bitwise_op_lookup::BitwiseOperationLookupChip;
+use openvm_instructions::{
+    instruction::Instruction, program::DEFAULT_PC_STEP, riscv::RV32_MEMORY_AS, UsizeOpcode,
+};
+use openvm_rv32im_transpiler::Rv32MemcpyOpcode;
+use openvm_stark_backend::p3_field::{AbstractField, Field, PrimeField32};
+
+use crate::adapters::{read_rv32_register, RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS};
+
+mod air;
+mod trace;
+
+pub use air::*;
+
+#[cfg(test)]
+mod tests;
+
+/// Register reads to get dst, src or the fill value, and the number of words
+const MEMCPY_REGISTER_READS: usize = 3;
+/// Timestamps taken by each word: one read and one write. MEMSET skips the read.
+const MEMCPY_WORD_TIMESTAMPS: usize = 2;
+
+/// Executes MEMCPY, which copies `[rs2]` words from `[rs1]` to `[rd]` in increasing address
+/// order, and MEMSET, which sets `[rs2]` words at `[rd]` to the word `[rs1]`. The instruction
+/// takes one row of the trace for the register reads, and one more row per word.
+#[derive(Debug)]
+pub struct Rv32MemcpyChip<F: PrimeField32> {
+    pub air: Rv32MemcpyAir,
+    pub records: Vec<Rv32MemcpyRecord<F>>,
+    pub memory_controller: MemoryControllerRef<F>,
+    pub bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<RV32_CELL_BITS>>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Rv32MemcpyRecord<F: Field> {
+    pub from_state: ExecutionState<u32>,
+    pub opcode: Rv32MemcpyOpcode,
+    pub dst_read: MemoryReadRecord<F, RV32_REGISTER_NUM_LIMBS>,
+    pub src_read: MemoryReadRecord<F, RV32_REGISTER_NUM_LIMBS>,
+    pub len_read: MemoryReadRecord<F, RV32_REGISTER_NUM_LIMBS>,
+    /// For each word, the read of the source word for MEMCPY, and the write of the word
+    pub words: Vec<(
+        Option<MemoryReadRecord<F, RV32_REGISTER_NUM_LIMBS>>,
+        MemoryWriteRecord<F, RV32_REGISTER_NUM_LIMBS>,
+    )>,
+}
+
+impl<F: PrimeField32> Rv32MemcpyChip<F> {
+    pub fn new(
+        execution_bus: ExecutionBus,
+        program_bus: ProgramBus,
+        memory_controller: MemoryControllerRef<F>,
+        bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<RV32_CELL_BITS>>,
+        offset: usize,
+    ) -> Self {
+        let pointer_max_bits = memory_controller.borrow().mem_config().pointer_max_bits;
+        let memory_bridge = memory_controller.borrow().memory_bridge();
+        Self {
+            air: Rv32MemcpyAir::new(
+                ExecutionBridge::new(execution_bus, program_bus),
+                memory_bridge,
+                bitwise_lookup_chip.bus(),
+                pointer_max_bits,
+                offset,
+            ),
+            records: Vec::new(),
+            memory_controller,
+            bitwise_lookup_chip,
+        }
+    }
+}
+
+impl<F: PrimeField32> InstructionExecutor<F> for Rv32MemcpyChip<F> {
+    fn execute(
+        &mut self,
+        instruction: Instruction<F>,
+        from_state: ExecutionState<u32>,
+    ) -> Result<ExecutionState<u32>, ExecutionError> {
+        let Instruction {
+            opcode,
+            a,
+            b,
+            c,
+            d,
+            e,
+            ..
+        } = instruction;
+        let local_opcode = Rv32MemcpyOpcode::from_usize(opcode.local_opcode_idx(self.air.offset));
+        debug_assert_eq!(e.as_canonical_u32(), RV32_MEMORY_AS);
+
+        let mut memory = self.memory_controller.borrow_mut();
+        debug_assert_eq!(from_state.timestamp, memory.timestamp());
+
+        let (dst_read, dst) = read_rv32_register(&mut memory, d, a);
+        let (src_read, src) = read_rv32_register(&mut memory, d, b);
+        let (len_read, len) = read_rv32_register(&mut memory, d, c);
+        let is_memset = local_opcode == Rv32MemcpyOpcode::MEMSET;
+        let pointers = if is_memset {
+            &[dst][..]
+        } else {
+            &[dst, src][..]
+        };
+        for &address in pointers {
+            if address % RV32_REGISTER_NUM_LIMBS as u32 != 0 {
+                return Err(ExecutionError::MisalignedMemoryAccess {
+                    pc: from_state.pc,
+                    address,
+                    alignment: RV32_REGISTER_NUM_LIMBS as u32,
+                });
+            }
+        }
+        let len_bytes = len as usize * RV32_REGISTER_NUM_LIMBS;
+        assert!(dst as usize + len_bytes <= (1 << self.air.pointer_max_bits));
+        if !is_memset {
+            assert!(src as usize + len_bytes <= (1 << self.air.pointer_max_bits));
+        }
+
+        let words = (0..len as usize * RV32_REGISTER_NUM_LIMBS)
+            .step_by(RV32_REGISTER_NUM_LIMBS)
+            .map(|offset| {
+                let (read, data) = if is_memset {
+                    memory.increment_timestamp();
+                    (None, src_read.data)
+                } else {
+                    let read = memory.read::<RV32_REGISTER_NUM_LIMBS>(
+                        e,
+                        F::from_canonical_usize(src as usize + offset),
+                    );
+                    let data = read.data;
+                    (Some(read), data)
+                };
+                let write = memory.write(e, F::from_canonical_usize(dst as usize + offset), data);
+                (read, write)
+            })
+            .collect();
+
+        let limb_shift_bits = RV32_CELL_BITS * RV32_REGISTER_NUM_LIMBS - self.air.pointer_max_bits;
+        self.bitwise_lookup_chip.request_range(
+            (dst >> (RV32_CELL_BITS * (RV32_REGISTER_NUM_LIMBS - 1))) << limb_shift_bits,
+            if is_memset {
+                0
+            } else {
+                (src >> (RV32_CELL_BITS * (RV32_REGISTER_NUM_LIMBS - 1))) << limb_shift_bits
+            },
+        );
+
+        self.records.push(Rv32MemcpyRecord {
+            from_state,
+            opcode: local_opcode,
+            dst_read,
+            src_read,
+            len_read,
+            words,
+        });
+
+        // NOTE: Check this is consistent with the timestamp change in Rv32MemcpyAir
+        let to_timestamp = from_state.timestamp
+            + (MEMCPY_REGISTER_READS + MEMCPY_WORD_TIMESTAMPS * len as usize) as u32;
+        debug_assert_eq!(to_timestamp, memory.timestamp());
+
+        Ok(ExecutionState {
+            pc: from_state.pc + DEFAULT_PC_STEP,
+            timestamp: to_timestamp,
+        })
+    }
+
+    fn get_opcode_name(&self, opcode: usize) -> String {
+        format!(
+            "{:?}",
+            Rv32MemcpyOpcode::from_usize(opcode - self.air.offset)
+        )
+    }
+}
@@ -0,0 +1,156 @@
+use std::{borrow::BorrowMut, sync::Arc};
+
+use openvm_circuit::arch::{
+    testing::{VmChipTestBuilder, VmChipTester},
+    BITWISE_OP_LOOKUP_BUS,
+};
+use openvm_circuit_primitives::bitwise_op_lookup::{
+    BitwiseOperationLookupBus, BitwiseOperationLookupChip,
+};
+use openvm_instructions::{instruction::Instruction, UsizeOpcode, VmOpcode};
+use openvm_rv32im_transpiler::Rv32MemcpyOpcode::{self, *};
+use openvm_stark_backend::{
+    p3_field::AbstractField, p3_matrix::dense::DenseMatrix, utils::disable_debug_builder,
+    verifier::VerificationError,
+};
+use openvm_stark_sdk::{
+    config::baby_bear_blake3::BabyBearBlake3Config, p3_baby_bear::BabyBear,
+    utils::create_seeded_rng,
+};
+use rand::Rng;
+
+use super::{Rv32MemcpyChip, Rv32MemcpyCols};
+use crate::adapters::{RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS};
+
+type F = BabyBear;
+
+/// Executes each of `(opcode, num_words)` on a fresh region of memory and checks the written
+/// memory.
+fn build_memcpy_test(
+    instructions: Vec<(Rv32MemcpyOpcode, usize)>,
+) -> VmChipTester<BabyBearBlake3Config> {
+    let mut rng = create_seeded_rng();
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = Arc::new(BitwiseOperationLookupChip::<RV32_CELL_BITS>::new(
+        bitwise_bus,
+    ));
+
+    let mut tester = VmChipTestBuilder::default();
+    let mut chip = Rv32MemcpyChip::<F>::new(
+        tester.execution_bus(),
+        tester.program_bus(),
+        tester.memory_controller(),
+        bitwise_chip.clone(),
+        Rv32MemcpyOpcode::default_offset(),
+    );
+
+    let [a, b, c, d, e] = [4, 8, 12, 1, 2];
+    let mut mem_ptr = 0;
+    for (opcode, num_words) in instructions {
+        let src = mem_ptr;
+        let dst = mem_ptr + num_words * RV32_REGISTER_NUM_LIMBS;
+        mem_ptr = dst + num_words * RV32_REGISTER_NUM_LIMBS;
+
+        let fill: u32 = rng.gen();
+        let words: Vec<[F; RV32_REGISTER_NUM_LIMBS]> = (0..num_words)
+            .map(|_| rng.gen::<u32>().to_le_bytes().map(F::from_canonical_u8))
+            .collect();
+        for (i, &word) in words.iter().enumerate() {
+            tester.write(e, src + i * RV32_REGISTER_NUM_LIMBS, word);
+        }
+        let rs1 = match opcode {
+            MEMCPY => src as u32,
+            MEMSET => fill,
+        };
+        tester.write(d, a, (dst as u32).to_le_bytes().map(F::from_canonical_u8));
+        tester.write(d, b, rs1.to_le_bytes().map(F::from_canonical_u8));
+        tester.write(
+            d,
+            c,
+            (num_words as u32).to_le_bytes().map(F::from_canonical_u8),
+        );
+
+        tester.execute(
+            &mut chip,
+            Instruction::from_usize(VmOpcode::with_default_offset(opcode), [a, b, c, d, e]),
+        );
+        for (i, &word) in words.iter().enumerate() {
+            let expected = match opcode {
+                MEMCPY => word,
+                MEMSET => fill.to_le_bytes().map(F::from_canonical_u8),
+            };
+            assert_eq!(
+                tester.read::<RV32_REGISTER_NUM_LIMBS>(e, dst + i * RV32_REGISTER_NUM_LIMBS),
+                expected
+            );
+        }
+    }
+
+    tester.build().load(chip).load(bitwise_chip).finalize()
+}
+
+///////////////////////////////////////////////////////////////////////////////////////
+/// POSITIVE TESTS
+///
+/// Randomly generate computations and execute, ensuring that the generated trace
+/// passes all constraints.
+///////////////////////////////////////////////////////////////////////////////////////
+#[test]
+fn rand_memcpy_test() {
+    let mut rng = create_seeded_rng();
+    let mut instructions = vec![(MEMCPY, 0), (MEMSET, 0), (MEMCPY, 1), (MEMSET, 1)];
+    for _ in 0..20 {
+        let opcode = if rng.gen() { MEMCPY } else { MEMSET };
+        instructions.push((opcode, rng.gen_range(0..32)));
+    }
+    let tester = build_memcpy_test(instructions);
+    tester.simple_test().expect("Verification failed");
+}
+
+//////////////////////////////////////////////////////////////////////////////////////
+// NEGATIVE TESTS
+//
+// Execute a valid instruction, then replace parts of the generated trace and check
+// that the constraints reject it.
+//////////////////////////////////////////////////////////////////////////////////////
+
+fn run_negative_memcpy_test(
+    opcode: Rv32MemcpyOpcode,
+    num_words: usize,
+    prank: impl Fn(&mut DenseMatrix<F>),
+    expected_error: VerificationError,
+) {
+    let mut tester = build_memcpy_test(vec![(opcode, num_words)]);
+    let trace = tester.air_proof_inputs[2].raw.common_main.as_mut().unwrap();
+    prank(trace);
+    disable_debug_builder();
+    assert_eq!(tester.simple_test().err(), Some(expected_error));
+}
+
+#[test]
+fn negative_memcpy_words_left_test() {
+    // The last word of the instruction cannot leave words to copy.
+    run_negative_memcpy_test(
+        MEMCPY,
+        3,
+        |trace| {
+            let cols: &mut Rv32MemcpyCols<F> = trace.row_mut(3).borrow_mut();
+            cols.rem_words = F::ONE;
+        },
+        VerificationError::OodEvaluationMismatch,
+    );
+}
+
+#[test]
+fn negative_memset_skip_word_test() {
+    // Each row copies exactly one word.
+    run_negative_memcpy_test(
+        MEMSET,
+        3,
+        |trace| {
+            let cols: &mut Rv32MemcpyCols<F> = trace.row_mut(2).borrow_mut();
+            cols.rem_words = F::ZERO;
+        },
+        VerificationError::OodEvaluationMismatch,
+    );
+}
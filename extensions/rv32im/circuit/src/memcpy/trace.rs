@@ -0,0 +1,99 @@
+use std::{borrow::BorrowMut, sync::Arc};
+
+use openvm_rv32im_transpiler::Rv32MemcpyOpcode;
+use openvm_stark_backend::{
+    config::{StarkGenericConfig, Val},
+    p3_air::BaseAir,
+    p3_field::{AbstractField, PrimeField32},
+    p3_matrix::dense::RowMajorMatrix,
+    prover::types::AirProofInput,
+    rap::{get_air_name, AnyRap},
+    Chip, ChipUsageGetter,
+};
+
+use super::{Rv32MemcpyChip, Rv32MemcpyCols, MEMCPY_REGISTER_READS, MEMCPY_WORD_TIMESTAMPS};
+use crate::adapters::{abstract_compose, compose, RV32_REGISTER_NUM_LIMBS};
+
+impl<SC: StarkGenericConfig> Chip<SC> for Rv32MemcpyChip<Val<SC>>
+where
+    Val<SC>: PrimeField32,
+{
+    fn air(&self) -> Arc<dyn AnyRap<SC>> {
+        Arc::new(self.air)
+    }
+
+    fn generate_air_proof_input(self) -> AirProofInput<SC> {
+        let air = self.air();
+        let trace_width = self.trace_width();
+        let height = self.current_trace_height().next_power_of_two();
+        let aux_cols_factory = self.memory_controller.borrow().aux_cols_factory();
+
+        // Padding rows are all zero
+        let mut trace = RowMajorMatrix::new(Val::<SC>::zero_vec(height * trace_width), trace_width);
+        let mut rows = trace.values.chunks_exact_mut(trace_width);
+        for record in self.records {
+            let is_memset = Val::<SC>::from_bool(record.opcode == Rv32MemcpyOpcode::MEMSET);
+            let dst = compose(record.dst_read.data);
+            // The fill value of MEMSET may not fit in a field element as a u32.
+            let src: Val<SC> = abstract_compose(record.src_read.data);
+            let num_words = record.words.len();
+
+            let cols: &mut Rv32MemcpyCols<Val<SC>> = rows.next().unwrap().borrow_mut();
+            cols.is_valid = Val::<SC>::ONE;
+            cols.is_start = Val::<SC>::ONE;
+            cols.is_memset = is_memset;
+            cols.pc = Val::<SC>::from_canonical_u32(record.from_state.pc);
+            cols.timestamp = Val::<SC>::from_canonical_u32(record.from_state.timestamp);
+            cols.rd_ptr = record.dst_read.pointer;
+            cols.rs1_ptr = record.src_read.pointer;
+            cols.rs2_ptr = record.len_read.pointer;
+            cols.dst_data = record.dst_read.data;
+            cols.len_data = record.len_read.data;
+            cols.register_aux = [record.dst_read, record.src_read, record.len_read]
+                .map(|read| aux_cols_factory.make_read_aux_cols(read));
+            cols.dst = Val::<SC>::from_canonical_u32(dst);
+            cols.src = src;
+            cols.data = record.src_read.data;
+            cols.rem_words = Val::<SC>::from_canonical_usize(num_words);
+
+            for (k, (read, write)) in record.words.into_iter().enumerate() {
+                let cols: &mut Rv32MemcpyCols<Val<SC>> = rows.next().unwrap().borrow_mut();
+                let offset = (k * RV32_REGISTER_NUM_LIMBS) as u32;
+                cols.is_valid = Val::<SC>::ONE;
+                cols.is_memset = is_memset;
+                cols.timestamp = Val::<SC>::from_canonical_usize(
+                    record.from_state.timestamp as usize
+                        + MEMCPY_REGISTER_READS
+                        + MEMCPY_WORD_TIMESTAMPS * k,
+                );
+                cols.dst = Val::<SC>::from_canonical_u32(dst + offset);
+                cols.src = src + Val::<SC>::from_canonical_u32(offset);
+                cols.data = write.data;
+                if let Some(read) = read {
+                    cols.read_aux = aux_cols_factory.make_read_aux_cols(read);
+                }
+                cols.write_aux = aux_cols_factory.make_write_aux_cols(write);
+                cols.rem_words = Val::<SC>::from_canonical_usize(num_words - k - 1);
+            }
+        }
+
+        AirProofInput::simple_no_pis(air, trace)
+    }
+}
+
+impl<F: PrimeField32> ChipUsageGetter for Rv32MemcpyChip<F> {
+    fn air_name(&self) -> String {
+        get_air_name(&self.air)
+    }
+
+    fn current_trace_height(&self) -> usize {
+        self.records
+            .iter()
+            .map(|record| 1 + record.words.len())
+            .sum()
+    }
+
+    fn trace_width(&self) -> usize {
+        BaseAir::<F>::width(&self.air)
+    }
+}
@@ -0,0 +1,334 @@
+use std::{
+    array,
+    borrow::{Borrow, BorrowMut},
+    sync::Arc,
+};
+
+use openvm_circuit::arch::{
+    AdapterAirContext, AdapterRuntimeContext, MinimalInstruction, Result, VmAdapterInterface,
+    VmCoreAir, VmCoreChip,
+};
+use openvm_circuit_primitives::{
+    bitwise_op_lookup::{BitwiseOperationLookupBus, BitwiseOperationLookupChip},
+    utils::not,
+};
+use openvm_circuit_primitives_derive::AlignedBorrow;
+use openvm_instructions::{instruction::Instruction, UsizeOpcode};
+use openvm_rv32im_transpiler::Rv32MinMaxOpcode;
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    p3_air::{AirBuilder, BaseAir},
+    p3_field::{AbstractField, Field, PrimeField32},
+    rap::BaseAirWithPublicValues,
+};
+use strum::IntoEnumIterator;
+
+use crate::adapters::{RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS};
+
+#[repr(C)]
+#[derive(AlignedBorrow)]
+pub struct Rv32MinMaxCoreCols<T> {
+    pub b: [T; RV32_REGISTER_NUM_LIMBS],
+    pub c: [T; RV32_REGISTER_NUM_LIMBS],
+    /// Whether b < c
+    pub cmp_result: T,
+    /// Whether the result is c rather than b
+    pub select_c: T,
+
+    pub opcode_min_flag: T,
+    pub opcode_minu_flag: T,
+    pub opcode_max_flag: T,
+    pub opcode_maxu_flag: T,
+
+    // Most significant limb of b and c respectively as a field element, will be range
+    // checked to be within [-128, 127) if signed, [0, 256) if unsigned.
+    pub b_msb_f: T,
+    pub c_msb_f: T,
+
+    // 1 at the most significant index i such that b[i] != c[i], otherwise 0. If such
+    // an i exists, diff_val = c[i] - b[i] if c[i] > b[i] or b[i] - c[i] else.
+    pub diff_marker: [T; RV32_REGISTER_NUM_LIMBS],
+    pub diff_val: T,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct Rv32MinMaxCoreAir {
+    pub bus: BitwiseOperationLookupBus,
+    offset: usize,
+}
+
+impl<F: Field> BaseAir<F> for Rv32MinMaxCoreAir {
+    fn width(&self) -> usize {
+        Rv32MinMaxCoreCols::<F>::width()
+    }
+}
+impl<F: Field> BaseAirWithPublicValues<F> for Rv32MinMaxCoreAir {}
+
+impl<AB, I> VmCoreAir<AB, I> for Rv32MinMaxCoreAir
+where
+    AB: InteractionBuilder,
+    I: VmAdapterInterface<AB::Expr>,
+    I::Reads: From<[[AB::Expr; RV32_REGISTER_NUM_LIMBS]; 2]>,
+    I::Writes: From<[[AB::Expr; RV32_REGISTER_NUM_LIMBS]; 1]>,
+    I::ProcessedInstruction: From<MinimalInstruction<AB::Expr>>,
+{
+    fn eval(
+        &self,
+        builder: &mut AB,
+        local_core: &[AB::Var],
+        _from_pc: AB::Var,
+    ) -> AdapterAirContext<AB::Expr, I> {
+        let cols: &Rv32MinMaxCoreCols<_> = local_core.borrow();
+        let flags = [
+            cols.opcode_min_flag,
+            cols.opcode_minu_flag,
+            cols.opcode_max_flag,
+            cols.opcode_maxu_flag,
+        ];
+
+        let is_valid = flags.iter().fold(AB::Expr::ZERO, |acc, &flag| {
+            builder.assert_bool(flag);
+            acc + flag.into()
+        });
+        builder.assert_bool(is_valid.clone());
+        builder.assert_bool(cols.cmp_result);
+
+        let is_signed = cols.opcode_min_flag + cols.opcode_max_flag;
+        let is_max = cols.opcode_max_flag + cols.opcode_maxu_flag;
+        let is_min = cols.opcode_min_flag + cols.opcode_minu_flag;
+
+        // Comparison of b and c, as in the less than chip.
+        let b = &cols.b;
+        let c = &cols.c;
+        let marker = &cols.diff_marker;
+        let mut prefix_sum = AB::Expr::ZERO;
+
+        let b_diff = b[RV32_REGISTER_NUM_LIMBS - 1] - cols.b_msb_f;
+        let c_diff = c[RV32_REGISTER_NUM_LIMBS - 1] - cols.c_msb_f;
+        builder.assert_zero(
+            b_diff.clone() * (AB::Expr::from_canonical_u32(1 << RV32_CELL_BITS) - b_diff),
+        );
+        builder.assert_zero(
+            c_diff.clone() * (AB::Expr::from_canonical_u32(1 << RV32_CELL_BITS) - c_diff),
+        );
+
+        for i in (0..RV32_REGISTER_NUM_LIMBS).rev() {
+            let diff = (if i == RV32_REGISTER_NUM_LIMBS - 1 {
+                cols.c_msb_f - cols.b_msb_f
+            } else {
+                c[i] - b[i]
+            }) * (AB::Expr::from_canonical_u8(2) * cols.cmp_result - AB::Expr::ONE);
+            prefix_sum += marker[i].into();
+            builder.assert_bool(marker[i]);
+            builder.assert_zero(not::<AB::Expr>(prefix_sum.clone()) * diff.clone());
+            builder.when(marker[i]).assert_eq(cols.diff_val, diff);
+        }
+
+        builder.assert_bool(prefix_sum.clone());
+        builder
+            .when(not::<AB::Expr>(prefix_sum.clone()))
+            .assert_zero(cols.cmp_result);
+
+        self.bus
+            .send_range(
+                cols.b_msb_f
+                    + AB::Expr::from_canonical_u32(1 << (RV32_CELL_BITS - 1)) * is_signed.clone(),
+                cols.c_msb_f + AB::Expr::from_canonical_u32(1 << (RV32_CELL_BITS - 1)) * is_signed,
+            )
+            .eval(builder, is_valid.clone());
+        self.bus
+            .send_range(cols.diff_val - AB::Expr::ONE, AB::F::ZERO)
+            .eval(builder, prefix_sum);
+
+        // MAX(U) picks c when b < c, and MIN(U) picks c otherwise.
+        builder.assert_eq(
+            cols.select_c,
+            is_max * cols.cmp_result + is_min * not::<AB::Expr>(cols.cmp_result),
+        );
+        let a: [AB::Expr; RV32_REGISTER_NUM_LIMBS] =
+            array::from_fn(|i| b[i] + cols.select_c * (c[i] - b[i]));
+
+        let expected_opcode = flags.iter().zip(Rv32MinMaxOpcode::iter()).fold(
+            AB::Expr::ZERO,
+            |acc, (flag, local_opcode)| {
+                acc + (*flag).into() * AB::Expr::from_canonical_u8(local_opcode as u8)
+            },
+        ) + AB::Expr::from_canonical_usize(self.offset);
+
+        AdapterAirContext {
+            to_pc: None,
+            reads: [cols.b.map(Into::into), cols.c.map(Into::into)].into(),
+            writes: [a].into(),
+            instruction: MinimalInstruction {
+                is_valid,
+                opcode: expected_opcode,
+            }
+            .into(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Rv32MinMaxCoreRecord<T> {
+    pub opcode: Rv32MinMaxOpcode,
+    pub b: [T; RV32_REGISTER_NUM_LIMBS],
+    pub c: [T; RV32_REGISTER_NUM_LIMBS],
+    pub cmp_result: T,
+    pub select_c: T,
+    pub b_msb_f: T,
+    pub c_msb_f: T,
+    pub diff_val: T,
+    pub diff_idx: usize,
+}
+
+#[derive(Debug)]
+pub struct Rv32MinMaxCoreChip {
+    pub air: Rv32MinMaxCoreAir,
+    pub bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<RV32_CELL_BITS>>,
+}
+
+impl Rv32MinMaxCoreChip {
+    pub fn new(
+        bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<RV32_CELL_BITS>>,
+        offset: usize,
+    ) -> Self {
+        Self {
+            air: Rv32MinMaxCoreAir {
+                bus: bitwise_lookup_chip.bus(),
+                offset,
+            },
+            bitwise_lookup_chip,
+        }
+    }
+}
+
+impl<F, I> VmCoreChip<F, I> for Rv32MinMaxCoreChip
+where
+    F: PrimeField32,
+    I: VmAdapterInterface<F>,
+    I::Reads: Into<[[F; RV32_REGISTER_NUM_LIMBS]; 2]>,
+    I::Writes: From<[[F; RV32_REGISTER_NUM_LIMBS]; 1]>,
+{
+    type Record = Rv32MinMaxCoreRecord<F>;
+    type Air = Rv32MinMaxCoreAir;
+
+    #[allow(clippy::type_complexity)]
+    fn execute_instruction(
+        &self,
+        instruction: &Instruction<F>,
+        _from_pc: u32,
+        reads: I::Reads,
+    ) -> Result<(AdapterRuntimeContext<F, I>, Self::Record)> {
+        let Instruction { opcode, .. } = instruction;
+        let local_opcode = Rv32MinMaxOpcode::from_usize(opcode.local_opcode_idx(self.air.offset));
+        let is_signed = matches!(local_opcode, Rv32MinMaxOpcode::MIN | Rv32MinMaxOpcode::MAX);
+
+        let data: [[F; RV32_REGISTER_NUM_LIMBS]; 2] = reads.into();
+        let b = data[0].map(|x| x.as_canonical_u32());
+        let c = data[1].map(|y| y.as_canonical_u32());
+        let (a, cmp_result, diff_idx, b_sign, c_sign) = run_min_max(local_opcode, &b, &c);
+
+        // We range check (b_msb_f + 128) and (c_msb_f + 128) if signed,
+        // b_msb_f and c_msb_f if not
+        let msb_f_and_range = |x: &[u32; RV32_REGISTER_NUM_LIMBS], sign: bool| {
+            let msb = x[RV32_REGISTER_NUM_LIMBS - 1];
+            if sign {
+                (
+                    -F::from_canonical_u32((1 << RV32_CELL_BITS) - msb),
+                    msb - (1 << (RV32_CELL_BITS - 1)),
+                )
+            } else {
+                (
+                    F::from_canonical_u32(msb),
+                    msb + ((is_signed as u32) << (RV32_CELL_BITS - 1)),
+                )
+            }
+        };
+        let (b_msb_f, b_msb_range) = msb_f_and_range(&b, b_sign);
+        let (c_msb_f, c_msb_range) = msb_f_and_range(&c, c_sign);
+        self.bitwise_lookup_chip
+            .request_range(b_msb_range, c_msb_range);
+
+        let diff_val = if diff_idx == RV32_REGISTER_NUM_LIMBS {
+            0
+        } else if diff_idx == (RV32_REGISTER_NUM_LIMBS - 1) {
+            if cmp_result {
+                c_msb_f - b_msb_f
+            } else {
+                b_msb_f - c_msb_f
+            }
+            .as_canonical_u32()
+        } else if cmp_result {
+            c[diff_idx] - b[diff_idx]
+        } else {
+            b[diff_idx] - c[diff_idx]
+        };
+
+        if diff_idx != RV32_REGISTER_NUM_LIMBS {
+            self.bitwise_lookup_chip.request_range(diff_val - 1, 0);
+        }
+
+        let is_max = matches!(local_opcode, Rv32MinMaxOpcode::MAX | Rv32MinMaxOpcode::MAXU);
+        let output = AdapterRuntimeContext::without_pc([a.map(F::from_canonical_u32)]);
+        let record = Self::Record {
+            opcode: local_opcode,
+            b: data[0],
+            c: data[1],
+            cmp_result: F::from_bool(cmp_result),
+            select_c: F::from_bool(cmp_result == is_max),
+            b_msb_f,
+            c_msb_f,
+            diff_val: F::from_canonical_u32(diff_val),
+            diff_idx,
+        };
+
+        Ok((output, record))
+    }
+
+    fn get_opcode_name(&self, opcode: usize) -> String {
+        format!(
+            "{:?}",
+            Rv32MinMaxOpcode::from_usize(opcode - self.air.offset)
+        )
+    }
+
+    fn generate_trace_row(&self, row_slice: &mut [F], record: Self::Record) {
+        let row_slice: &mut Rv32MinMaxCoreCols<_> = row_slice.borrow_mut();
+        row_slice.b = record.b;
+        row_slice.c = record.c;
+        row_slice.cmp_result = record.cmp_result;
+        row_slice.select_c = record.select_c;
+        row_slice.b_msb_f = record.b_msb_f;
+        row_slice.c_msb_f = record.c_msb_f;
+        row_slice.diff_val = record.diff_val;
+        row_slice.opcode_min_flag = F::from_bool(record.opcode == Rv32MinMaxOpcode::MIN);
+        row_slice.opcode_minu_flag = F::from_bool(record.opcode == Rv32MinMaxOpcode::MINU);
+        row_slice.opcode_max_flag = F::from_bool(record.opcode == Rv32MinMaxOpcode::MAX);
+        row_slice.opcode_maxu_flag = F::from_bool(record.opcode == Rv32MinMaxOpcode::MAXU);
+        row_slice.diff_marker = array::from_fn(|i| F::from_bool(i == record.diff_idx));
+    }
+
+    fn air(&self) -> &Self::Air {
+        &self.air
+    }
+}
+
+// Returns (a, cmp_result, diff_idx, b_sign, c_sign)
+pub fn run_min_max(
+    opcode: Rv32MinMaxOpcode,
+    b: &[u32; RV32_REGISTER_NUM_LIMBS],
+    c: &[u32; RV32_REGISTER_NUM_LIMBS],
+) -> ([u32; RV32_REGISTER_NUM_LIMBS], bool, usize, bool, bool) {
+    let is_signed = matches!(opcode, Rv32MinMaxOpcode::MIN | Rv32MinMaxOpcode::MAX);
+    let is_max = matches!(opcode, Rv32MinMaxOpcode::MAX | Rv32MinMaxOpcode::MAXU);
+    let b_sign = (b[RV32_REGISTER_NUM_LIMBS - 1] >> (RV32_CELL_BITS - 1) == 1) && is_signed;
+    let c_sign = (c[RV32_REGISTER_NUM_LIMBS - 1] >> (RV32_CELL_BITS - 1) == 1) && is_signed;
+    let (cmp_result, diff_idx) = (0..RV32_REGISTER_NUM_LIMBS)
+        .rev()
+        .find(|&i| b[i] != c[i])
+        .map_or((false, RV32_REGISTER_NUM_LIMBS), |i| {
+            ((b[i] < c[i]) ^ b_sign ^ c_sign, i)
+        });
+    let a = if cmp_result == is_max { *c } else { *b };
+    (a, cmp_result, diff_idx, b_sign, c_sign)
+}
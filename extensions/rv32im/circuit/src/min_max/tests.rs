@@ -0,0 +1,115 @@
+use std::sync::Arc;
+
+use openvm_circuit::{
+    arch::{testing::VmChipTestBuilder, BITWISE_OP_LOOKUP_BUS},
+    utils::generate_long_number,
+};
+use openvm_circuit_primitives::bitwise_op_lookup::{
+    BitwiseOperationLookupBus, BitwiseOperationLookupChip,
+};
+use openvm_rv32im_transpiler::Rv32MinMaxOpcode;
+use openvm_stark_backend::p3_field::AbstractField;
+use openvm_stark_sdk::{p3_baby_bear::BabyBear, utils::create_seeded_rng};
+use rand::Rng;
+
+use super::{run_min_max, Rv32MinMaxChip, Rv32MinMaxCoreChip};
+use crate::{
+    adapters::{Rv32BaseAluAdapterChip, RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS},
+    test_utils::{generate_rv32_is_type_immediate, rv32_rand_write_register_or_imm},
+};
+
+type F = BabyBear;
+
+//////////////////////////////////////////////////////////////////////////////////////
+// POSITIVE TESTS
+//
+// Randomly generate computations and execute, ensuring that the generated trace
+// passes all constraints.
+//////////////////////////////////////////////////////////////////////////////////////
+
+fn run_rv32_min_max_rand_test(opcode: Rv32MinMaxOpcode, num_ops: usize) {
+    let mut rng = create_seeded_rng();
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = Arc::new(BitwiseOperationLookupChip::<RV32_CELL_BITS>::new(
+        bitwise_bus,
+    ));
+
+    let mut tester = VmChipTestBuilder::default();
+    let mut chip = Rv32MinMaxChip::<F>::new(
+        Rv32BaseAluAdapterChip::new(
+            tester.execution_bus(),
+            tester.program_bus(),
+            tester.memory_controller(),
+        ),
+        Rv32MinMaxCoreChip::new(bitwise_chip.clone(), 0),
+        tester.memory_controller(),
+    );
+
+    for _ in 0..num_ops {
+        let b = generate_long_number::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(&mut rng);
+        let (c_imm, c) = if rng.gen_bool(0.5) {
+            (
+                None,
+                generate_long_number::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(&mut rng),
+            )
+        } else {
+            let (imm, c) = generate_rv32_is_type_immediate(&mut rng);
+            (Some(imm), c)
+        };
+
+        let (instruction, rd) =
+            rv32_rand_write_register_or_imm(&mut tester, b, c, c_imm, opcode as usize, &mut rng);
+        tester.execute(&mut chip, instruction);
+
+        let (a, _, _, _, _) = run_min_max(opcode, &b, &c);
+        assert_eq!(
+            a.map(F::from_canonical_u32),
+            tester.read::<RV32_REGISTER_NUM_LIMBS>(1, rd)
+        )
+    }
+
+    let tester = tester.build().load(chip).load(bitwise_chip).finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
+#[test]
+fn rv32_min_max_min_rand_test() {
+    run_rv32_min_max_rand_test(Rv32MinMaxOpcode::MIN, 100);
+}
+
+#[test]
+fn rv32_min_max_minu_rand_test() {
+    run_rv32_min_max_rand_test(Rv32MinMaxOpcode::MINU, 100);
+}
+
+#[test]
+fn rv32_min_max_max_rand_test() {
+    run_rv32_min_max_rand_test(Rv32MinMaxOpcode::MAX, 100);
+}
+
+#[test]
+fn rv32_min_max_maxu_rand_test() {
+    run_rv32_min_max_rand_test(Rv32MinMaxOpcode::MAXU, 100);
+}
+
+//////////////////////////////////////////////////////////////////////////////////////
+// SANITY TESTS
+//
+// Ensure that the run_* function produces the expected results.
+//////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn run_min_max_sanity_test() {
+    let x: [u32; RV32_REGISTER_NUM_LIMBS] = [145, 34, 25, 205];
+    let y: [u32; RV32_REGISTER_NUM_LIMBS] = [73, 35, 25, 5];
+    let (min, cmp_result, diff_idx, x_sign, y_sign) = run_min_max(Rv32MinMaxOpcode::MIN, &x, &y);
+    assert_eq!(min, x);
+    assert!(cmp_result);
+    assert_eq!(diff_idx, RV32_REGISTER_NUM_LIMBS - 1);
+    assert!(x_sign);
+    assert!(!y_sign);
+    assert_eq!(run_min_max(Rv32MinMaxOpcode::MINU, &x, &y).0, y);
+    assert_eq!(run_min_max(Rv32MinMaxOpcode::MAX, &x, &y).0, y);
+    assert_eq!(run_min_max(Rv32MinMaxOpcode::MAXU, &x, &y).0, x);
+    assert_eq!(run_min_max(Rv32MinMaxOpcode::MAX, &x, &x).0, x);
+}
@@ -201,7 +201,7 @@ where
 }
 
 // returns mul, carry
-pub(super) fn run_mul<const NUM_LIMBS: usize, const LIMB_BITS: usize>(
+pub(crate) fn run_mul<const NUM_LIMBS: usize, const LIMB_BITS: usize>(
     x: &[u32; NUM_LIMBS],
     y: &[u32; NUM_LIMBS],
 ) -> ([u32; NUM_LIMBS], [u32; NUM_LIMBS]) {
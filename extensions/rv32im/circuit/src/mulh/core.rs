@@ -43,7 +43,7 @@ pub struct MulHCoreCols<T, const NUM_LIMBS: usize, const LIMB_BITS: usize> {
 pub struct MulHCoreAir<const NUM_LIMBS: usize, const LIMB_BITS: usize> {
     pub bitwise_lookup_bus: BitwiseOperationLookupBus,
     pub range_tuple_bus: RangeTupleCheckerBus<2>,
-    offset: usize,
+    pub(crate) offset: usize,
 }
 
 impl<F: Field, const NUM_LIMBS: usize, const LIMB_BITS: usize> BaseAir<F>
@@ -74,6 +74,25 @@ where
         _from_pc: AB::Var,
     ) -> AdapterAirContext<AB::Expr, I> {
         let cols: &MulHCoreCols<_, NUM_LIMBS, LIMB_BITS> = local_core.borrow();
+        let instruction = self.eval_mul(builder, cols);
+
+        AdapterAirContext {
+            to_pc: None,
+            reads: [cols.b.map(Into::into), cols.c.map(Into::into)].into(),
+            writes: [cols.a.map(Into::into)].into(),
+            instruction: instruction.into(),
+        }
+    }
+}
+
+impl<const NUM_LIMBS: usize, const LIMB_BITS: usize> MulHCoreAir<NUM_LIMBS, LIMB_BITS> {
+    /// Constrains `a` and `a_mul` to be the high and low limbs of the product of `b` and `c`,
+    /// sign extended according to the opcode flags.
+    pub(crate) fn eval_mul<AB: InteractionBuilder>(
+        &self,
+        builder: &mut AB,
+        cols: &MulHCoreCols<AB::Var, NUM_LIMBS, LIMB_BITS>,
+    ) -> MinimalInstruction<AB::Expr> {
         let flags = [
             cols.opcode_mulh_flag,
             cols.opcode_mulhsu_flag,
@@ -163,15 +182,9 @@ where
             },
         ) + AB::Expr::from_canonical_usize(self.offset);
 
-        AdapterAirContext {
-            to_pc: None,
-            reads: [cols.b.map(Into::into), cols.c.map(Into::into)].into(),
-            writes: [cols.a.map(Into::into)].into(),
-            instruction: MinimalInstruction {
-                is_valid,
-                opcode: expected_opcode,
-            }
-            .into(),
+        MinimalInstruction {
+            is_valid,
+            opcode: expected_opcode,
         }
     }
 }
@@ -213,39 +226,17 @@ impl<const NUM_LIMBS: usize, const LIMB_BITS: usize> MulHCoreChip<NUM_LIMBS, LIM
             range_tuple_chip,
         }
     }
-}
 
-#[derive(Clone, Debug)]
-pub struct MulHCoreRecord<T, const NUM_LIMBS: usize, const LIMB_BITS: usize> {
-    pub opcode: MulHOpcode,
-    pub a: [T; NUM_LIMBS],
-    pub b: [T; NUM_LIMBS],
-    pub c: [T; NUM_LIMBS],
-    pub a_mul: [T; NUM_LIMBS],
-    pub b_ext: T,
-    pub c_ext: T,
-}
-
-impl<F: PrimeField32, I: VmAdapterInterface<F>, const NUM_LIMBS: usize, const LIMB_BITS: usize>
-    VmCoreChip<F, I> for MulHCoreChip<NUM_LIMBS, LIMB_BITS>
-where
-    I::Reads: Into<[[F; NUM_LIMBS]; 2]>,
-    I::Writes: From<[[F; NUM_LIMBS]; 1]>,
-{
-    type Record = MulHCoreRecord<F, NUM_LIMBS, LIMB_BITS>;
-    type Air = MulHCoreAir<NUM_LIMBS, LIMB_BITS>;
-
-    #[allow(clippy::type_complexity)]
-    fn execute_instruction(
+    /// Computes the high and low limbs of the product of the two reads and requests the range
+    /// checks that [MulHCoreAir::eval_mul] sends.
+    pub(crate) fn execute_mul<F: PrimeField32>(
         &self,
         instruction: &Instruction<F>,
-        _from_pc: u32,
-        reads: I::Reads,
-    ) -> Result<(AdapterRuntimeContext<F, I>, Self::Record)> {
+        data: [[F; NUM_LIMBS]; 2],
+    ) -> MulHCoreRecord<F, NUM_LIMBS, LIMB_BITS> {
         let Instruction { opcode, .. } = instruction;
         let mulh_opcode = MulHOpcode::from_usize(opcode.local_opcode_idx(self.air.offset));
 
-        let data: [[F; NUM_LIMBS]; 2] = reads.into();
         let b = data[0].map(|x| x.as_canonical_u32());
         let c = data[1].map(|y| y.as_canonical_u32());
         let (a, a_mul, carry, b_ext, c_ext) = run_mulh::<NUM_LIMBS, LIMB_BITS>(mulh_opcode, &b, &c);
@@ -265,8 +256,7 @@ where
             );
         }
 
-        let output = AdapterRuntimeContext::without_pc([a.map(F::from_canonical_u32)]);
-        let record = MulHCoreRecord {
+        MulHCoreRecord {
             opcode: mulh_opcode,
             a: a.map(F::from_canonical_u32),
             b: data[0],
@@ -274,16 +264,14 @@ where
             a_mul: a_mul.map(F::from_canonical_u32),
             b_ext: F::from_canonical_u32(b_ext),
             c_ext: F::from_canonical_u32(c_ext),
-        };
-
-        Ok((output, record))
-    }
-
-    fn get_opcode_name(&self, opcode: usize) -> String {
-        format!("{:?}", MulHOpcode::from_usize(opcode - self.air.offset))
+        }
     }
 
-    fn generate_trace_row(&self, row_slice: &mut [F], record: Self::Record) {
+    pub(crate) fn generate_mul_trace_row<F: PrimeField32>(
+        &self,
+        row_slice: &mut [F],
+        record: MulHCoreRecord<F, NUM_LIMBS, LIMB_BITS>,
+    ) {
         let row_slice: &mut MulHCoreCols<_, NUM_LIMBS, LIMB_BITS> = row_slice.borrow_mut();
         row_slice.a = record.a;
         row_slice.b = record.b;
@@ -295,6 +283,47 @@ where
         row_slice.opcode_mulhsu_flag = F::from_bool(record.opcode == MulHOpcode::MULHSU);
         row_slice.opcode_mulhu_flag = F::from_bool(record.opcode == MulHOpcode::MULHU);
     }
+}
+
+#[derive(Clone, Debug)]
+pub struct MulHCoreRecord<T, const NUM_LIMBS: usize, const LIMB_BITS: usize> {
+    pub opcode: MulHOpcode,
+    pub a: [T; NUM_LIMBS],
+    pub b: [T; NUM_LIMBS],
+    pub c: [T; NUM_LIMBS],
+    pub a_mul: [T; NUM_LIMBS],
+    pub b_ext: T,
+    pub c_ext: T,
+}
+
+impl<F: PrimeField32, I: VmAdapterInterface<F>, const NUM_LIMBS: usize, const LIMB_BITS: usize>
+    VmCoreChip<F, I> for MulHCoreChip<NUM_LIMBS, LIMB_BITS>
+where
+    I::Reads: Into<[[F; NUM_LIMBS]; 2]>,
+    I::Writes: From<[[F; NUM_LIMBS]; 1]>,
+{
+    type Record = MulHCoreRecord<F, NUM_LIMBS, LIMB_BITS>;
+    type Air = MulHCoreAir<NUM_LIMBS, LIMB_BITS>;
+
+    #[allow(clippy::type_complexity)]
+    fn execute_instruction(
+        &self,
+        instruction: &Instruction<F>,
+        _from_pc: u32,
+        reads: I::Reads,
+    ) -> Result<(AdapterRuntimeContext<F, I>, Self::Record)> {
+        let record = self.execute_mul(instruction, reads.into());
+        let output = AdapterRuntimeContext::without_pc([record.a]);
+        Ok((output, record))
+    }
+
+    fn get_opcode_name(&self, opcode: usize) -> String {
+        format!("{:?}", MulHOpcode::from_usize(opcode - self.air.offset))
+    }
+
+    fn generate_trace_row(&self, row_slice: &mut [F], record: Self::Record) {
+        self.generate_mul_trace_row(row_slice, record);
+    }
 
     fn air(&self) -> &Self::Air {
         &self.air
@@ -302,7 +331,7 @@ where
 }
 
 // returns mulh[[s]u], mul, carry, x_ext, y_ext
-pub(super) fn run_mulh<const NUM_LIMBS: usize, const LIMB_BITS: usize>(
+pub(crate) fn run_mulh<const NUM_LIMBS: usize, const LIMB_BITS: usize>(
     opcode: MulHOpcode,
     x: &[u32; NUM_LIMBS],
     y: &[u32; NUM_LIMBS],
@@ -325,6 +325,49 @@ fn rv32_mulhsu_wrong_c_ext_negative_test() {
     );
 }
 
+#[test]
+fn rv32_mulhsu_invalid_b_ext_negative_test() {
+    run_rv32_mulh_negative_test(
+        MulHOpcode::MULHSU,
+        [3, 2, 2, 2],
+        [0, 0, 0, 128],
+        [2, 0, 0, 0],
+        [0, 0, 0, 0],
+        1,
+        0,
+        false,
+    );
+}
+
+#[test]
+fn rv32_mulhsu_invalid_c_ext_negative_test() {
+    run_rv32_mulh_negative_test(
+        MulHOpcode::MULHSU,
+        [3, 2, 2, 2],
+        [2, 0, 0, 0],
+        [0, 0, 0, 128],
+        [0, 0, 0, 0],
+        0,
+        1,
+        false,
+    );
+}
+
+#[test]
+fn rv32_mulhsu_unsigned_b_ext_negative_test() {
+    // b is negative, but a is the unsigned product as if the opcode were MULHU
+    run_rv32_mulh_negative_test(
+        MulHOpcode::MULHSU,
+        [225, 149, 68, 89],
+        [197, 85, 150, 160],
+        [51, 109, 78, 142],
+        [63, 247, 125, 104],
+        0,
+        0,
+        true,
+    );
+}
+
 #[test]
 fn rv32_mulhu_wrong_a_mul_negative_test() {
     run_rv32_mulh_negative_test(
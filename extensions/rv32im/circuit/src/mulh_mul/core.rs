@@ -0,0 +1,124 @@
+use std::{borrow::Borrow, sync::Arc};
+
+use openvm_circuit::arch::{
+    AdapterAirContext, AdapterRuntimeContext, MinimalInstruction, Result, VmAdapterInterface,
+    VmCoreAir, VmCoreChip,
+};
+use openvm_circuit_primitives::{
+    bitwise_op_lookup::BitwiseOperationLookupChip, range_tuple::RangeTupleCheckerChip,
+};
+use openvm_instructions::{instruction::Instruction, UsizeOpcode};
+use openvm_rv32im_transpiler::MulHMulOpcode;
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    p3_air::BaseAir,
+    p3_field::{Field, PrimeField32},
+    rap::BaseAirWithPublicValues,
+};
+
+use crate::{MulHCoreAir, MulHCoreChip, MulHCoreCols, MulHCoreRecord};
+
+/// The constraints are those of [MulHCoreAir], which already has the low limbs of the product
+/// in `a_mul`. They are written out as the first word, and the high limbs `a` as the second.
+#[derive(Copy, Clone, Debug)]
+pub struct MulHMulCoreAir<const NUM_LIMBS: usize, const LIMB_BITS: usize> {
+    pub mulh: MulHCoreAir<NUM_LIMBS, LIMB_BITS>,
+}
+
+impl<F: Field, const NUM_LIMBS: usize, const LIMB_BITS: usize> BaseAir<F>
+    for MulHMulCoreAir<NUM_LIMBS, LIMB_BITS>
+{
+    fn width(&self) -> usize {
+        MulHCoreCols::<F, NUM_LIMBS, LIMB_BITS>::width()
+    }
+}
+impl<F: Field, const NUM_LIMBS: usize, const LIMB_BITS: usize> BaseAirWithPublicValues<F>
+    for MulHMulCoreAir<NUM_LIMBS, LIMB_BITS>
+{
+}
+
+impl<AB, I, const NUM_LIMBS: usize, const LIMB_BITS: usize> VmCoreAir<AB, I>
+    for MulHMulCoreAir<NUM_LIMBS, LIMB_BITS>
+where
+    AB: InteractionBuilder,
+    I: VmAdapterInterface<AB::Expr>,
+    I::Reads: From<[[AB::Expr; NUM_LIMBS]; 2]>,
+    I::Writes: From<[[AB::Expr; NUM_LIMBS]; 2]>,
+    I::ProcessedInstruction: From<MinimalInstruction<AB::Expr>>,
+{
+    fn eval(
+        &self,
+        builder: &mut AB,
+        local_core: &[AB::Var],
+        _from_pc: AB::Var,
+    ) -> AdapterAirContext<AB::Expr, I> {
+        let cols: &MulHCoreCols<_, NUM_LIMBS, LIMB_BITS> = local_core.borrow();
+        // The variants of MulHMulOpcode are in the same order as MulHOpcode, so the expected
+        // opcode is the same with the offset of MulHMulOpcode
+        let instruction = self.mulh.eval_mul(builder, cols);
+
+        AdapterAirContext {
+            to_pc: None,
+            reads: [cols.b.map(Into::into), cols.c.map(Into::into)].into(),
+            writes: [cols.a_mul.map(Into::into), cols.a.map(Into::into)].into(),
+            instruction: instruction.into(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct MulHMulCoreChip<const NUM_LIMBS: usize, const LIMB_BITS: usize> {
+    pub air: MulHMulCoreAir<NUM_LIMBS, LIMB_BITS>,
+    pub mulh: MulHCoreChip<NUM_LIMBS, LIMB_BITS>,
+}
+
+impl<const NUM_LIMBS: usize, const LIMB_BITS: usize> MulHMulCoreChip<NUM_LIMBS, LIMB_BITS> {
+    pub fn new(
+        bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<LIMB_BITS>>,
+        range_tuple_chip: Arc<RangeTupleCheckerChip<2>>,
+        offset: usize,
+    ) -> Self {
+        let mulh = MulHCoreChip::new(bitwise_lookup_chip, range_tuple_chip, offset);
+        Self {
+            air: MulHMulCoreAir { mulh: mulh.air },
+            mulh,
+        }
+    }
+}
+
+impl<F: PrimeField32, I: VmAdapterInterface<F>, const NUM_LIMBS: usize, const LIMB_BITS: usize>
+    VmCoreChip<F, I> for MulHMulCoreChip<NUM_LIMBS, LIMB_BITS>
+where
+    I::Reads: Into<[[F; NUM_LIMBS]; 2]>,
+    I::Writes: From<[[F; NUM_LIMBS]; 2]>,
+{
+    type Record = MulHCoreRecord<F, NUM_LIMBS, LIMB_BITS>;
+    type Air = MulHMulCoreAir<NUM_LIMBS, LIMB_BITS>;
+
+    #[allow(clippy::type_complexity)]
+    fn execute_instruction(
+        &self,
+        instruction: &Instruction<F>,
+        _from_pc: u32,
+        reads: I::Reads,
+    ) -> Result<(AdapterRuntimeContext<F, I>, Self::Record)> {
+        let record = self.mulh.execute_mul(instruction, reads.into());
+        let output = AdapterRuntimeContext::without_pc([record.a_mul, record.a]);
+        Ok((output, record))
+    }
+
+    fn get_opcode_name(&self, opcode: usize) -> String {
+        format!(
+            "{:?}",
+            MulHMulOpcode::from_usize(opcode - self.air.mulh.offset)
+        )
+    }
+
+    fn generate_trace_row(&self, row_slice: &mut [F], record: Self::Record) {
+        self.mulh.generate_mul_trace_row(row_slice, record);
+    }
+
+    fn air(&self) -> &Self::Air {
+        &self.air
+    }
+}
@@ -0,0 +1,15 @@
+use openvm_circuit::arch::VmChipWrapper;
+
+use super::adapters::{Rv32MultFusedAdapterChip, RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS};
+
+mod core;
+pub use core::*;
+
+#[cfg(test)]
+mod tests;
+
+pub type Rv32MulHMulChip<F> = VmChipWrapper<
+    F,
+    Rv32MultFusedAdapterChip<F>,
+    MulHMulCoreChip<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>,
+>;
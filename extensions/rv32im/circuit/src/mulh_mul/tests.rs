@@ -0,0 +1,182 @@
+use std::{borrow::BorrowMut, sync::Arc};
+
+use openvm_circuit::{
+    arch::{
+        testing::{memory::gen_pointer, VmChipTestBuilder},
+        VmAdapterChip, BITWISE_OP_LOOKUP_BUS, RANGE_TUPLE_CHECKER_BUS,
+    },
+    utils::generate_long_number,
+};
+use openvm_circuit_primitives::{
+    bitwise_op_lookup::{BitwiseOperationLookupBus, BitwiseOperationLookupChip},
+    range_tuple::{RangeTupleCheckerBus, RangeTupleCheckerChip},
+};
+use openvm_instructions::{instruction::Instruction, UsizeOpcode, VmOpcode};
+use openvm_rv32im_transpiler::{MulHMulOpcode, MulHOpcode};
+use openvm_stark_backend::{
+    p3_air::BaseAir,
+    p3_field::AbstractField,
+    p3_matrix::{
+        dense::{DenseMatrix, RowMajorMatrix},
+        Matrix,
+    },
+    utils::disable_debug_builder,
+    verifier::VerificationError,
+    ChipUsageGetter,
+};
+use openvm_stark_sdk::{p3_baby_bear::BabyBear, utils::create_seeded_rng};
+use rand::{rngs::StdRng, Rng};
+
+use crate::{
+    adapters::{Rv32MultFusedAdapterChip, RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS},
+    mulh::run_mulh,
+    mulh_mul::{MulHMulCoreChip, Rv32MulHMulChip},
+    MulHCoreCols,
+};
+
+type F = BabyBear;
+
+fn mulh_opcode(opcode: MulHMulOpcode) -> MulHOpcode {
+    MulHOpcode::from_usize(opcode as usize)
+}
+
+fn setup() -> (
+    VmChipTestBuilder<F>,
+    Rv32MulHMulChip<F>,
+    Arc<BitwiseOperationLookupChip<RV32_CELL_BITS>>,
+    Arc<RangeTupleCheckerChip<2>>,
+) {
+    const MAX_NUM_LIMBS: u32 = 32;
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let range_tuple_bus = RangeTupleCheckerBus::new(
+        RANGE_TUPLE_CHECKER_BUS,
+        [1 << RV32_CELL_BITS, MAX_NUM_LIMBS * (1 << RV32_CELL_BITS)],
+    );
+    let bitwise_chip = Arc::new(BitwiseOperationLookupChip::<RV32_CELL_BITS>::new(
+        bitwise_bus,
+    ));
+    let range_tuple_chip = Arc::new(RangeTupleCheckerChip::new(range_tuple_bus));
+
+    let tester = VmChipTestBuilder::default();
+    let chip = Rv32MulHMulChip::<F>::new(
+        Rv32MultFusedAdapterChip::new(
+            tester.execution_bus(),
+            tester.program_bus(),
+            tester.memory_controller(),
+        ),
+        MulHMulCoreChip::new(bitwise_chip.clone(), range_tuple_chip.clone(), 0),
+        tester.memory_controller(),
+    );
+    (tester, chip, bitwise_chip, range_tuple_chip)
+}
+
+fn run_rv32_mulh_mul_rand_write_execute(
+    opcode: MulHMulOpcode,
+    tester: &mut VmChipTestBuilder<F>,
+    chip: &mut Rv32MulHMulChip<F>,
+    b: [u32; RV32_REGISTER_NUM_LIMBS],
+    c: [u32; RV32_REGISTER_NUM_LIMBS],
+    rng: &mut StdRng,
+) {
+    let rs1 = gen_pointer(rng, 4);
+    let rs2 = gen_pointer(rng, 4);
+    let rd = gen_pointer(rng, 4);
+    let rd_hi = loop {
+        let ptr = gen_pointer(rng, 4);
+        if ptr != rd {
+            break ptr;
+        }
+    };
+
+    tester.write::<RV32_REGISTER_NUM_LIMBS>(1, rs1, b.map(F::from_canonical_u32));
+    tester.write::<RV32_REGISTER_NUM_LIMBS>(1, rs2, c.map(F::from_canonical_u32));
+
+    let (hi, lo, _, _, _) =
+        run_mulh::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(mulh_opcode(opcode), &b, &c);
+    tester.execute(
+        chip,
+        Instruction::from_usize(
+            VmOpcode::from_usize(opcode as usize),
+            [rd, rs1, rs2, 1, 0, rd_hi],
+        ),
+    );
+
+    assert_eq!(
+        lo.map(F::from_canonical_u32),
+        tester.read::<RV32_REGISTER_NUM_LIMBS>(1, rd)
+    );
+    assert_eq!(
+        hi.map(F::from_canonical_u32),
+        tester.read::<RV32_REGISTER_NUM_LIMBS>(1, rd_hi)
+    );
+}
+
+fn run_rv32_mulh_mul_rand_test(opcode: MulHMulOpcode, num_ops: usize) {
+    let mut rng = create_seeded_rng();
+    let (mut tester, mut chip, bitwise_chip, range_tuple_chip) = setup();
+
+    for _ in 0..num_ops {
+        let b = generate_long_number::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(&mut rng);
+        let c = generate_long_number::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(&mut rng);
+        run_rv32_mulh_mul_rand_write_execute(opcode, &mut tester, &mut chip, b, c, &mut rng);
+    }
+
+    let tester = tester
+        .build()
+        .load(chip)
+        .load(bitwise_chip)
+        .load(range_tuple_chip)
+        .finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
+#[test]
+fn rv32_mulh_mul_rand_test() {
+    run_rv32_mulh_mul_rand_test(MulHMulOpcode::MULH_MUL, 100);
+}
+
+#[test]
+fn rv32_mulhsu_mul_rand_test() {
+    run_rv32_mulh_mul_rand_test(MulHMulOpcode::MULHSU_MUL, 100);
+}
+
+#[test]
+fn rv32_mulhu_mul_rand_test() {
+    run_rv32_mulh_mul_rand_test(MulHMulOpcode::MULHU_MUL, 100);
+}
+
+#[test]
+fn rv32_mulh_mul_wrong_low_word_negative_test() {
+    let mut rng = create_seeded_rng();
+    let (mut tester, mut chip, bitwise_chip, range_tuple_chip) = setup();
+    let b = generate_long_number::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(&mut rng);
+    let c = generate_long_number::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(&mut rng);
+    run_rv32_mulh_mul_rand_write_execute(
+        MulHMulOpcode::MULH_MUL,
+        &mut tester,
+        &mut chip,
+        b,
+        c,
+        &mut rng,
+    );
+
+    let trace_width = chip.trace_width();
+    let adapter_width = BaseAir::<F>::width(chip.adapter.air());
+    let limb = rng.gen_range(0..RV32_REGISTER_NUM_LIMBS);
+    let modify_trace = |trace: &mut DenseMatrix<BabyBear>| {
+        let mut values = trace.row_slice(0).to_vec();
+        let cols: &mut MulHCoreCols<F, RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS> =
+            values.split_at_mut(adapter_width).1.borrow_mut();
+        cols.a_mul[limb] += F::ONE;
+        *trace = RowMajorMatrix::new(values, trace_width);
+    };
+
+    disable_debug_builder();
+    let tester = tester
+        .build()
+        .load_and_prank_trace(chip, modify_trace)
+        .load(bitwise_chip)
+        .load(range_tuple_chip)
+        .finalize();
+    tester.simple_test_with_expected_error(VerificationError::ChallengePhaseError);
+}
@@ -0,0 +1,314 @@
+use std::{
+    array,
+    borrow::{Borrow, BorrowMut},
+    sync::Arc,
+};
+
+use openvm_circuit::arch::{
+    AdapterAirContext, AdapterRuntimeContext, MinimalInstruction, Result, VmAdapterInterface,
+    VmCoreAir, VmCoreChip,
+};
+use openvm_circuit_primitives::{
+    bitwise_op_lookup::{BitwiseOperationLookupBus, BitwiseOperationLookupChip},
+    var_range::{VariableRangeCheckerBus, VariableRangeCheckerChip},
+};
+use openvm_circuit_primitives_derive::AlignedBorrow;
+use openvm_instructions::{instruction::Instruction, UsizeOpcode};
+use openvm_rv32im_transpiler::Rv32RotateOpcode;
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    p3_air::{AirBuilder, BaseAir},
+    p3_field::{AbstractField, Field, PrimeField32},
+    rap::BaseAirWithPublicValues,
+};
+use strum::IntoEnumIterator;
+
+use crate::adapters::{RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS};
+
+const NUM_BITS: usize = RV32_REGISTER_NUM_LIMBS * RV32_CELL_BITS;
+const NUM_BITS_LOG: usize = NUM_BITS.ilog2() as usize;
+
+#[repr(C)]
+#[derive(AlignedBorrow, Clone, Copy, Debug)]
+pub struct Rv32RotateCoreCols<T> {
+    pub a: [T; RV32_REGISTER_NUM_LIMBS],
+    pub b: [T; RV32_REGISTER_NUM_LIMBS],
+    pub c: [T; RV32_REGISTER_NUM_LIMBS],
+
+    pub opcode_rol_flag: T,
+    pub opcode_ror_flag: T,
+
+    // bit_multiplier = 2^bit_shift
+    pub bit_multiplier: T,
+
+    // Boolean columns that are 1 exactly at the index of the bit/limb part of the left
+    // rotation amount, which is the right rotation amount negated for ROR
+    pub bit_shift_marker: [T; RV32_CELL_BITS],
+    pub limb_shift_marker: [T; RV32_REGISTER_NUM_LIMBS],
+
+    // Part of each b[i] that gets bit shifted to the next limb
+    pub bit_shift_carry: [T; RV32_REGISTER_NUM_LIMBS],
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct Rv32RotateCoreAir {
+    pub bitwise_lookup_bus: BitwiseOperationLookupBus,
+    pub range_bus: VariableRangeCheckerBus,
+    offset: usize,
+}
+
+impl<F: Field> BaseAir<F> for Rv32RotateCoreAir {
+    fn width(&self) -> usize {
+        Rv32RotateCoreCols::<F>::width()
+    }
+}
+impl<F: Field> BaseAirWithPublicValues<F> for Rv32RotateCoreAir {}
+
+impl<AB, I> VmCoreAir<AB, I> for Rv32RotateCoreAir
+where
+    AB: InteractionBuilder,
+    I: VmAdapterInterface<AB::Expr>,
+    I::Reads: From<[[AB::Expr; RV32_REGISTER_NUM_LIMBS]; 2]>,
+    I::Writes: From<[[AB::Expr; RV32_REGISTER_NUM_LIMBS]; 1]>,
+    I::ProcessedInstruction: From<MinimalInstruction<AB::Expr>>,
+{
+    fn eval(
+        &self,
+        builder: &mut AB,
+        local_core: &[AB::Var],
+        _from_pc: AB::Var,
+    ) -> AdapterAirContext<AB::Expr, I> {
+        let cols: &Rv32RotateCoreCols<_> = local_core.borrow();
+        let flags = [cols.opcode_rol_flag, cols.opcode_ror_flag];
+
+        let is_valid = flags.iter().fold(AB::Expr::ZERO, |acc, &flag| {
+            builder.assert_bool(flag);
+            acc + flag.into()
+        });
+        builder.assert_bool(is_valid.clone());
+
+        let a = &cols.a;
+        let b = &cols.b;
+        let c = &cols.c;
+
+        // Constrain that bit_shift, bit_multiplier are correct, i.e. that bit_multiplier =
+        // 1 << bit_shift. Because the sum of all bit_shift_marker[i] is constrained to be
+        // 1, bit_shift is guaranteed to be in range.
+        let mut bit_marker_sum = AB::Expr::ZERO;
+        let mut bit_shift = AB::Expr::ZERO;
+        for i in 0..RV32_CELL_BITS {
+            builder.assert_bool(cols.bit_shift_marker[i]);
+            bit_marker_sum += cols.bit_shift_marker[i].into();
+            bit_shift += AB::Expr::from_canonical_usize(i) * cols.bit_shift_marker[i];
+            builder
+                .when(cols.bit_shift_marker[i])
+                .assert_eq(cols.bit_multiplier, AB::Expr::from_canonical_usize(1 << i));
+        }
+        builder.when(is_valid.clone()).assert_one(bit_marker_sum);
+
+        // Rotating left by limb_shift limbs and bit_shift bits moves b[j] * 2^bit_shift,
+        // minus the carry it hands over to the next limb, to a[j + limb_shift], where
+        // indices wrap around.
+        let mut limb_marker_sum = AB::Expr::ZERO;
+        let mut limb_shift = AB::Expr::ZERO;
+        for i in 0..RV32_REGISTER_NUM_LIMBS {
+            builder.assert_bool(cols.limb_shift_marker[i]);
+            limb_marker_sum += cols.limb_shift_marker[i].into();
+            limb_shift += AB::Expr::from_canonical_usize(i) * cols.limb_shift_marker[i];
+
+            let mut when_limb_shift = builder.when(cols.limb_shift_marker[i]);
+            for j in 0..RV32_REGISTER_NUM_LIMBS {
+                let src = (j + RV32_REGISTER_NUM_LIMBS - i) % RV32_REGISTER_NUM_LIMBS;
+                let prev = (src + RV32_REGISTER_NUM_LIMBS - 1) % RV32_REGISTER_NUM_LIMBS;
+                when_limb_shift.assert_eq(
+                    a[j],
+                    b[src] * cols.bit_multiplier
+                        - AB::Expr::from_canonical_usize(1 << RV32_CELL_BITS)
+                            * cols.bit_shift_carry[src]
+                        + cols.bit_shift_carry[prev],
+                );
+            }
+        }
+        builder.when(is_valid.clone()).assert_one(limb_marker_sum);
+
+        // Check that the left rotation amount is congruent to c[0] for ROL and to -c[0] for
+        // ROR modulo NUM_BITS. The quotient is at most (2^LIMB_BITS + NUM_BITS) / NUM_BITS.
+        let rotate = limb_shift * AB::F::from_canonical_usize(RV32_CELL_BITS) + bit_shift.clone();
+        self.range_bus
+            .range_check(
+                (c[0] + (cols.opcode_ror_flag - cols.opcode_rol_flag) * rotate)
+                    * AB::F::from_canonical_usize(NUM_BITS).inverse(),
+                RV32_CELL_BITS - NUM_BITS_LOG + 1,
+            )
+            .eval(builder, is_valid.clone());
+
+        for i in 0..(RV32_REGISTER_NUM_LIMBS / 2) {
+            self.bitwise_lookup_bus
+                .send_range(a[i * 2], a[i * 2 + 1])
+                .eval(builder, is_valid.clone());
+        }
+
+        for carry in cols.bit_shift_carry {
+            self.range_bus
+                .send(carry, bit_shift.clone())
+                .eval(builder, is_valid.clone());
+        }
+
+        let expected_opcode = flags.iter().zip(Rv32RotateOpcode::iter()).fold(
+            AB::Expr::ZERO,
+            |acc, (flag, local_opcode)| {
+                acc + (*flag).into() * AB::Expr::from_canonical_u8(local_opcode as u8)
+            },
+        ) + AB::Expr::from_canonical_usize(self.offset);
+
+        AdapterAirContext {
+            to_pc: None,
+            reads: [cols.b.map(Into::into), cols.c.map(Into::into)].into(),
+            writes: [cols.a.map(Into::into)].into(),
+            instruction: MinimalInstruction {
+                is_valid,
+                opcode: expected_opcode,
+            }
+            .into(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Rv32RotateCoreRecord<T> {
+    pub opcode: Rv32RotateOpcode,
+    pub a: [T; RV32_REGISTER_NUM_LIMBS],
+    pub b: [T; RV32_REGISTER_NUM_LIMBS],
+    pub c: [T; RV32_REGISTER_NUM_LIMBS],
+    pub bit_shift_carry: [T; RV32_REGISTER_NUM_LIMBS],
+    pub bit_shift: usize,
+    pub limb_shift: usize,
+}
+
+#[derive(Debug)]
+pub struct Rv32RotateCoreChip {
+    pub air: Rv32RotateCoreAir,
+    pub bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<RV32_CELL_BITS>>,
+    pub range_checker_chip: Arc<VariableRangeCheckerChip>,
+}
+
+impl Rv32RotateCoreChip {
+    pub fn new(
+        bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<RV32_CELL_BITS>>,
+        range_checker_chip: Arc<VariableRangeCheckerChip>,
+        offset: usize,
+    ) -> Self {
+        Self {
+            air: Rv32RotateCoreAir {
+                bitwise_lookup_bus: bitwise_lookup_chip.bus(),
+                range_bus: range_checker_chip.bus(),
+                offset,
+            },
+            bitwise_lookup_chip,
+            range_checker_chip,
+        }
+    }
+}
+
+impl<F, I> VmCoreChip<F, I> for Rv32RotateCoreChip
+where
+    F: PrimeField32,
+    I: VmAdapterInterface<F>,
+    I::Reads: Into<[[F; RV32_REGISTER_NUM_LIMBS]; 2]>,
+    I::Writes: From<[[F; RV32_REGISTER_NUM_LIMBS]; 1]>,
+{
+    type Record = Rv32RotateCoreRecord<F>;
+    type Air = Rv32RotateCoreAir;
+
+    #[allow(clippy::type_complexity)]
+    fn execute_instruction(
+        &self,
+        instruction: &Instruction<F>,
+        _from_pc: u32,
+        reads: I::Reads,
+    ) -> Result<(AdapterRuntimeContext<F, I>, Self::Record)> {
+        let Instruction { opcode, .. } = instruction;
+        let local_opcode = Rv32RotateOpcode::from_usize(opcode.local_opcode_idx(self.air.offset));
+
+        let data: [[F; RV32_REGISTER_NUM_LIMBS]; 2] = reads.into();
+        let b = data[0].map(|x| x.as_canonical_u32());
+        let c = data[1].map(|y| y.as_canonical_u32());
+        let (a, limb_shift, bit_shift) = run_rotate(local_opcode, &b, &c);
+
+        let bit_shift_carry = b.map(|x| x >> (RV32_CELL_BITS - bit_shift));
+
+        let rotate = limb_shift * RV32_CELL_BITS + bit_shift;
+        let quotient = match local_opcode {
+            Rv32RotateOpcode::ROL => c[0] as usize - rotate,
+            Rv32RotateOpcode::ROR => c[0] as usize + rotate,
+        } >> NUM_BITS_LOG;
+        self.range_checker_chip
+            .add_count(quotient as u32, RV32_CELL_BITS - NUM_BITS_LOG + 1);
+
+        for i in 0..(RV32_REGISTER_NUM_LIMBS / 2) {
+            self.bitwise_lookup_chip
+                .request_range(a[i * 2], a[i * 2 + 1]);
+        }
+        for carry_val in bit_shift_carry {
+            self.range_checker_chip.add_count(carry_val, bit_shift);
+        }
+
+        let output = AdapterRuntimeContext::without_pc([a.map(F::from_canonical_u32)]);
+        let record = Self::Record {
+            opcode: local_opcode,
+            a: a.map(F::from_canonical_u32),
+            b: data[0],
+            c: data[1],
+            bit_shift_carry: bit_shift_carry.map(F::from_canonical_u32),
+            bit_shift,
+            limb_shift,
+        };
+
+        Ok((output, record))
+    }
+
+    fn get_opcode_name(&self, opcode: usize) -> String {
+        format!(
+            "{:?}",
+            Rv32RotateOpcode::from_usize(opcode - self.air.offset)
+        )
+    }
+
+    fn generate_trace_row(&self, row_slice: &mut [F], record: Self::Record) {
+        let row_slice: &mut Rv32RotateCoreCols<_> = row_slice.borrow_mut();
+        row_slice.a = record.a;
+        row_slice.b = record.b;
+        row_slice.c = record.c;
+        row_slice.bit_multiplier = F::from_canonical_usize(1 << record.bit_shift);
+        row_slice.bit_shift_marker = array::from_fn(|i| F::from_bool(i == record.bit_shift));
+        row_slice.limb_shift_marker = array::from_fn(|i| F::from_bool(i == record.limb_shift));
+        row_slice.bit_shift_carry = record.bit_shift_carry;
+        row_slice.opcode_rol_flag = F::from_bool(record.opcode == Rv32RotateOpcode::ROL);
+        row_slice.opcode_ror_flag = F::from_bool(record.opcode == Rv32RotateOpcode::ROR);
+    }
+
+    fn air(&self) -> &Self::Air {
+        &self.air
+    }
+}
+
+// Returns (a, limb_shift, bit_shift) where the limb and bit shift make up the equivalent
+// left rotation amount
+pub fn run_rotate(
+    opcode: Rv32RotateOpcode,
+    b: &[u32; RV32_REGISTER_NUM_LIMBS],
+    c: &[u32; RV32_REGISTER_NUM_LIMBS],
+) -> ([u32; RV32_REGISTER_NUM_LIMBS], usize, usize) {
+    let amount = c[0] as usize % NUM_BITS;
+    let rotate = match opcode {
+        Rv32RotateOpcode::ROL => amount,
+        Rv32RotateOpcode::ROR => (NUM_BITS - amount) % NUM_BITS,
+    };
+    let (limb_shift, bit_shift) = (rotate / RV32_CELL_BITS, rotate % RV32_CELL_BITS);
+    let a = array::from_fn(|j| {
+        let src = (j + RV32_REGISTER_NUM_LIMBS - limb_shift) % RV32_REGISTER_NUM_LIMBS;
+        let prev = (src + RV32_REGISTER_NUM_LIMBS - 1) % RV32_REGISTER_NUM_LIMBS;
+        ((b[src] << bit_shift) + (b[prev] >> (RV32_CELL_BITS - bit_shift))) % (1 << RV32_CELL_BITS)
+    });
+    (a, limb_shift, bit_shift)
+}
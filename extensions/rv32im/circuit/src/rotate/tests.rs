@@ -0,0 +1,107 @@
+use std::sync::Arc;
+
+use openvm_circuit::{
+    arch::{testing::VmChipTestBuilder, BITWISE_OP_LOOKUP_BUS},
+    utils::generate_long_number,
+};
+use openvm_circuit_primitives::bitwise_op_lookup::{
+    BitwiseOperationLookupBus, BitwiseOperationLookupChip,
+};
+use openvm_rv32im_transpiler::Rv32RotateOpcode;
+use openvm_stark_backend::p3_field::AbstractField;
+use openvm_stark_sdk::{p3_baby_bear::BabyBear, utils::create_seeded_rng};
+use rand::Rng;
+
+use super::{run_rotate, Rv32RotateChip, Rv32RotateCoreChip};
+use crate::{
+    adapters::{Rv32BaseAluAdapterChip, RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS},
+    test_utils::{generate_rv32_is_type_immediate, rv32_rand_write_register_or_imm},
+};
+
+type F = BabyBear;
+
+//////////////////////////////////////////////////////////////////////////////////////
+// POSITIVE TESTS
+//
+// Randomly generate computations and execute, ensuring that the generated trace
+// passes all constraints.
+//////////////////////////////////////////////////////////////////////////////////////
+
+fn run_rv32_rotate_rand_test(opcode: Rv32RotateOpcode, num_ops: usize) {
+    let mut rng = create_seeded_rng();
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = Arc::new(BitwiseOperationLookupChip::<RV32_CELL_BITS>::new(
+        bitwise_bus,
+    ));
+
+    let mut tester = VmChipTestBuilder::default();
+    let mut chip = Rv32RotateChip::<F>::new(
+        Rv32BaseAluAdapterChip::new(
+            tester.execution_bus(),
+            tester.program_bus(),
+            tester.memory_controller(),
+        ),
+        Rv32RotateCoreChip::new(
+            bitwise_chip.clone(),
+            tester.memory_controller().borrow().range_checker.clone(),
+            0,
+        ),
+        tester.memory_controller(),
+    );
+
+    for _ in 0..num_ops {
+        let b = generate_long_number::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(&mut rng);
+        let (c_imm, c) = if rng.gen_bool(0.5) {
+            (
+                None,
+                generate_long_number::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(&mut rng),
+            )
+        } else {
+            let (imm, c) = generate_rv32_is_type_immediate(&mut rng);
+            (Some(imm), c)
+        };
+
+        let (instruction, rd) =
+            rv32_rand_write_register_or_imm(&mut tester, b, c, c_imm, opcode as usize, &mut rng);
+        tester.execute(&mut chip, instruction);
+
+        let (a, _, _) = run_rotate(opcode, &b, &c);
+        assert_eq!(
+            a.map(F::from_canonical_u32),
+            tester.read::<RV32_REGISTER_NUM_LIMBS>(1, rd)
+        )
+    }
+
+    let tester = tester.build().load(chip).load(bitwise_chip).finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
+#[test]
+fn rv32_rotate_rol_rand_test() {
+    run_rv32_rotate_rand_test(Rv32RotateOpcode::ROL, 100);
+}
+
+#[test]
+fn rv32_rotate_ror_rand_test() {
+    run_rv32_rotate_rand_test(Rv32RotateOpcode::ROR, 100);
+}
+
+//////////////////////////////////////////////////////////////////////////////////////
+// SANITY TESTS
+//
+// Ensure that the run_* function produces the expected results.
+//////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn run_rotate_sanity_test() {
+    let x: [u32; RV32_REGISTER_NUM_LIMBS] = [0x78, 0x56, 0x34, 0x12];
+    let y: [u32; RV32_REGISTER_NUM_LIMBS] = [44, 190, 0, 0];
+    let (result, limb_shift, bit_shift) = run_rotate(Rv32RotateOpcode::ROL, &x, &y);
+    assert_eq!(result, [0x23, 0x81, 0x67, 0x45]);
+    assert_eq!((limb_shift, bit_shift), (1, 4));
+    let (result, limb_shift, bit_shift) = run_rotate(Rv32RotateOpcode::ROR, &x, &y);
+    assert_eq!(result, [0x45, 0x23, 0x81, 0x67]);
+    assert_eq!((limb_shift, bit_shift), (2, 4));
+    let (result, _, _) = run_rotate(Rv32RotateOpcode::ROR, &x, &[32, 0, 0, 0]);
+    assert_eq!(result, x);
+}
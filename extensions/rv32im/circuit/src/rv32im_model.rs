@@ -0,0 +1,362 @@
+//! The semantics of every RV32IM opcode and of the extensions implemented in this crate, as pure
+//! functions of the decoded operands.
+//!
+//! Each function calls the same `run_*` helper that the corresponding core chip uses to compute
+//! its output, so this is exactly the model the circuits implement, including its deviations
+//! from RISC-V such as the canonical NaN of the F extension. Operands and results are whole
+//! words rather than limbs, which makes the model suitable for fuzzers, differential testers and
+//! documentation.
+//!
+//! Immediates are the `c` operand of the transpiled instruction, decoded as described for each
+//! function. Functions that take a field `F` only use it to carry
+//! limbs through the chip's helper, so the result does not depend on the choice of field.
+
+use std::array;
+
+use openvm_instructions::program::DEFAULT_PC_STEP;
+use openvm_rv32im_transpiler::{
+    BaseAluOpcode, BranchEqualOpcode, BranchLessThanOpcode, DivRemOpcode, LessThanOpcode,
+    MulHOpcode, Rv32AtomicOpcode, Rv32AuipcOpcode, Rv32BitCountOpcode, Rv32ByteOpOpcode,
+    Rv32CsrOpcode, Rv32FloatAddOpcode, Rv32FloatCmpOpcode, Rv32FloatMiscOpcode,
+    Rv32FloatToIntOpcode, Rv32IntToFloatOpcode, Rv32JalLuiOpcode, Rv32JalrOpcode,
+    Rv32LoadStoreOpcode, Rv32LogicNotOpcode, Rv32MinMaxOpcode, Rv32RotateOpcode, Rv32ShAddOpcode,
+    ShiftOpcode,
+};
+use openvm_stark_backend::p3_field::PrimeField32;
+
+use crate::{
+    adapters::{RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS},
+    atomic::run_atomic,
+    auipc::run_auipc,
+    base_alu::run_alu,
+    bit_count::run_bit_count,
+    branch_eq::run_eq,
+    branch_lt::run_cmp,
+    byte_op::run_byte_op,
+    csr::run_csr,
+    divrem::run_divrem,
+    float_add::run_float_add,
+    float_cmp::run_float_cmp,
+    float_misc::run_float_misc,
+    float_mul::run_float_mul,
+    float_to_int::run_float_to_int,
+    int_to_float::run_int_to_float,
+    jal_lui::run_jal_lui,
+    jalr::run_jalr,
+    less_than::run_less_than,
+    load_sign_extend::run_write_data_sign_extend,
+    loadstore::run_write_data,
+    logic_not::run_logic_not,
+    min_max::run_min_max,
+    mul::run_mul,
+    mulh::run_mulh,
+    rotate::run_rotate,
+    sh_add::run_sh_add,
+    shift::run_shift,
+};
+
+type Limbs = [u32; RV32_REGISTER_NUM_LIMBS];
+
+fn to_limbs(x: u32) -> Limbs {
+    array::from_fn(|i| (x >> (RV32_CELL_BITS * i)) & ((1 << RV32_CELL_BITS) - 1))
+}
+
+fn from_limbs(limbs: Limbs) -> u32 {
+    limbs
+        .iter()
+        .enumerate()
+        .fold(0, |acc, (i, &limb)| acc | limb << (RV32_CELL_BITS * i))
+}
+
+fn to_cells<F: PrimeField32>(x: u32) -> [F; RV32_REGISTER_NUM_LIMBS] {
+    to_limbs(x).map(F::from_canonical_u32)
+}
+
+fn from_cells<F: PrimeField32>(cells: [F; RV32_REGISTER_NUM_LIMBS]) -> u32 {
+    from_limbs(cells.map(|cell| cell.as_canonical_u32()))
+}
+
+/// `rd` of `ADD`, `SUB`, `XOR`, `OR` and `AND`, and of their immediate forms with `rs2` the
+/// sign-extended immediate.
+pub fn alu(opcode: BaseAluOpcode, rs1: u32, rs2: u32) -> u32 {
+    from_limbs(run_alu::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(
+        opcode,
+        &to_limbs(rs1),
+        &to_limbs(rs2),
+    ))
+}
+
+/// `rd` of `SLL`, `SRL` and `SRA`. Only the low 5 bits of `rs2` are used.
+pub fn shift(opcode: ShiftOpcode, rs1: u32, rs2: u32) -> u32 {
+    let (rd, _, _) = run_shift::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(
+        opcode,
+        &to_limbs(rs1),
+        &to_limbs(rs2),
+    );
+    from_limbs(rd)
+}
+
+/// `rd` of `SLT` and `SLTU`.
+pub fn less_than(opcode: LessThanOpcode, rs1: u32, rs2: u32) -> u32 {
+    let (cmp_result, _, _, _) = run_less_than::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(
+        opcode,
+        &to_limbs(rs1),
+        &to_limbs(rs2),
+    );
+    cmp_result as u32
+}
+
+/// `rd` of `MUL`.
+pub fn mul(rs1: u32, rs2: u32) -> u32 {
+    let (rd, _) =
+        run_mul::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(&to_limbs(rs1), &to_limbs(rs2));
+    from_limbs(rd)
+}
+
+/// `rd` of `MULH`, `MULHSU` and `MULHU`.
+pub fn mulh(opcode: MulHOpcode, rs1: u32, rs2: u32) -> u32 {
+    let (rd, _, _, _, _) =
+        run_mulh::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(opcode, &to_limbs(rs1), &to_limbs(rs2));
+    from_limbs(rd)
+}
+
+/// `rd` of `DIV`, `DIVU`, `REM` and `REMU`. Division by zero and signed overflow follow the
+/// RISC-V specification.
+pub fn divrem(opcode: DivRemOpcode, rs1: u32, rs2: u32) -> u32 {
+    let signed = matches!(opcode, DivRemOpcode::DIV | DivRemOpcode::REM);
+    let (q, r, _, _, _, _) = run_divrem::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(
+        signed,
+        &to_limbs(rs1),
+        &to_limbs(rs2),
+    );
+    match opcode {
+        DivRemOpcode::DIV | DivRemOpcode::DIVU => from_limbs(q),
+        DivRemOpcode::REM | DivRemOpcode::REMU => from_limbs(r),
+    }
+}
+
+/// The next pc of `BEQ` and `BNE`, where `imm` is the signed branch offset.
+pub fn branch_eq<F: PrimeField32>(
+    opcode: BranchEqualOpcode,
+    pc: u32,
+    imm: i32,
+    rs1: u32,
+    rs2: u32,
+) -> u32 {
+    let (cmp_result, _, _) =
+        run_eq::<F, RV32_REGISTER_NUM_LIMBS>(opcode, &to_limbs(rs1), &to_limbs(rs2));
+    branch_target(cmp_result, pc, imm)
+}
+
+/// The next pc of `BLT`, `BLTU`, `BGE` and `BGEU`, where `imm` is the signed branch offset.
+pub fn branch_lt(opcode: BranchLessThanOpcode, pc: u32, imm: i32, rs1: u32, rs2: u32) -> u32 {
+    let (cmp_result, _, _, _) =
+        run_cmp::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(opcode, &to_limbs(rs1), &to_limbs(rs2));
+    branch_target(cmp_result, pc, imm)
+}
+
+fn branch_target(taken: bool, pc: u32, imm: i32) -> u32 {
+    if taken {
+        pc.wrapping_add(imm as u32)
+    } else {
+        pc + DEFAULT_PC_STEP
+    }
+}
+
+/// The next pc and `rd` of `JAL` and `LUI`. For `JAL`, `imm` is the signed jump offset, and for
+/// `LUI` it is the upper 20 bits of `rd`.
+pub fn jal_lui(opcode: Rv32JalLuiOpcode, pc: u32, imm: i32) -> (u32, u32) {
+    let (to_pc, rd) = run_jal_lui(opcode, pc, imm);
+    (to_pc, from_limbs(rd))
+}
+
+/// The next pc and `rd` of `JALR`, where `imm` is the sign-extended 12-bit offset.
+pub fn jalr(pc: u32, imm: u32, rs1: u32) -> (u32, u32) {
+    let (to_pc, rd) = run_jalr(Rv32JalrOpcode::JALR, pc, imm, rs1);
+    (to_pc, from_limbs(rd))
+}
+
+/// `rd` of `AUIPC`, where `imm` is the offset shifted right by [RV32_CELL_BITS], as in the
+/// transpiled instruction.
+pub fn auipc(pc: u32, imm: u32) -> u32 {
+    from_limbs(run_auipc(Rv32AuipcOpcode::AUIPC, pc, imm))
+}
+
+/// `rd` of `LOADW`, `LOADBU`, `LOADHU`, `LOADB` and `LOADH`, given the aligned word containing
+/// the address and the offset `shift` of the address in it.
+///
+/// # Panics
+/// If `shift` is not aligned to the size of the access.
+pub fn load<F: PrimeField32>(opcode: Rv32LoadStoreOpcode, word: u32, shift: u32) -> u32 {
+    let (read, prev) = (to_cells::<F>(word), to_cells::<F>(0));
+    from_cells(match opcode {
+        Rv32LoadStoreOpcode::LOADB | Rv32LoadStoreOpcode::LOADH => {
+            run_write_data_sign_extend::<F, RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(
+                opcode, read, prev, shift,
+            )
+        }
+        _ => run_write_data(opcode, read, prev, shift),
+    })
+}
+
+/// The new aligned word of `STOREW`, `STOREH` and `STOREB`, given the previous word containing
+/// the address and the offset `shift` of the address in it.
+///
+/// # Panics
+/// If `shift` is not aligned to the size of the access.
+pub fn store<F: PrimeField32>(opcode: Rv32LoadStoreOpcode, rs2: u32, word: u32, shift: u32) -> u32 {
+    from_cells(run_write_data(
+        opcode,
+        to_cells::<F>(rs2),
+        to_cells::<F>(word),
+        shift,
+    ))
+}
+
+/// `rd` and the new value of the CSR of `CSRRW`, `CSRRS` and `CSRRC`, given the source operand
+/// and the old value of the CSR.
+pub fn csr(opcode: Rv32CsrOpcode, src: u32, old: u32) -> (u32, u32) {
+    (
+        old,
+        from_limbs(run_csr(opcode, &to_limbs(src), &to_limbs(old))),
+    )
+}
+
+/// `rd` of `SH1ADD`, `SH2ADD` and `SH3ADD`.
+pub fn sh_add(opcode: Rv32ShAddOpcode, rs1: u32, rs2: u32) -> u32 {
+    let (rd, _) = run_sh_add(opcode, &to_limbs(rs1), &to_limbs(rs2));
+    from_limbs(rd)
+}
+
+/// `rd` of `ANDN`, `ORN` and `XNOR`.
+pub fn logic_not(opcode: Rv32LogicNotOpcode, rs1: u32, rs2: u32) -> u32 {
+    from_limbs(run_logic_not(opcode, &to_limbs(rs1), &to_limbs(rs2)))
+}
+
+/// `rd` of `MIN`, `MINU`, `MAX` and `MAXU`.
+pub fn min_max(opcode: Rv32MinMaxOpcode, rs1: u32, rs2: u32) -> u32 {
+    let (rd, _, _, _, _) = run_min_max(opcode, &to_limbs(rs1), &to_limbs(rs2));
+    from_limbs(rd)
+}
+
+/// `rd` of `ROL` and `ROR`, and of `RORI` with `rs2` the shift amount.
+pub fn rotate(opcode: Rv32RotateOpcode, rs1: u32, rs2: u32) -> u32 {
+    let (rd, _, _) = run_rotate(opcode, &to_limbs(rs1), &to_limbs(rs2));
+    from_limbs(rd)
+}
+
+/// `rd` of `CLZ`, `CTZ` and `CPOP`.
+pub fn bit_count(opcode: Rv32BitCountOpcode, rs1: u32) -> u32 {
+    run_bit_count(opcode, rs1)
+}
+
+/// `rd` of `SEXT.B`, `SEXT.H`, `ZEXT.H`, `REV8` and `ORC.B`.
+pub fn byte_op(opcode: Rv32ByteOpOpcode, rs1: u32) -> u32 {
+    from_limbs(run_byte_op(opcode, &to_limbs(rs1)))
+}
+
+/// `rd` and the new word in memory of the A instructions, given `rs2` and the old word.
+pub fn atomic(opcode: Rv32AtomicOpcode, rs2: u32, old: u32) -> (u32, u32) {
+    run_atomic(opcode, rs2, old)
+}
+
+/// `rd` of `FSGNJ.S`, `FSGNJN.S`, `FSGNJX.S` and `FCLASS.S`.
+pub fn float_misc(opcode: Rv32FloatMiscOpcode, rs1: u32, rs2: u32) -> u32 {
+    run_float_misc(opcode, rs1, rs2)
+}
+
+/// `rd` of `FEQ.S`, `FLT.S`, `FLE.S`, `FMIN.S` and `FMAX.S`.
+pub fn float_cmp(opcode: Rv32FloatCmpOpcode, rs1: u32, rs2: u32) -> u32 {
+    run_float_cmp(opcode, rs1, rs2)
+}
+
+/// `rd` of `FADD.S` and `FSUB.S`, rounding to nearest, ties to even.
+pub fn float_add(opcode: Rv32FloatAddOpcode, rs1: u32, rs2: u32) -> u32 {
+    run_float_add(opcode, rs1, rs2)
+}
+
+/// `rd` of `FMUL.S`, rounding to nearest, ties to even.
+pub fn float_mul(rs1: u32, rs2: u32) -> u32 {
+    run_float_mul(rs1, rs2)
+}
+
+/// `rd` of `FCVT.W.S` and `FCVT.WU.S`, rounding toward zero.
+pub fn float_to_int(opcode: Rv32FloatToIntOpcode, rs1: u32) -> u32 {
+    run_float_to_int(opcode, rs1)
+}
+
+/// `rd` of `FCVT.S.W` and `FCVT.S.WU`, rounding to nearest, ties to even.
+pub fn int_to_float(opcode: Rv32IntToFloatOpcode, rs1: u32) -> u32 {
+    run_int_to_float(opcode, rs1)
+}
+
+#[cfg(test)]
+mod tests {
+    use openvm_stark_sdk::p3_baby_bear::BabyBear;
+
+    use super::*;
+
+    type F = BabyBear;
+
+    #[test]
+    fn test_rv32im_model() {
+        assert_eq!(alu(BaseAluOpcode::SUB, 3, 5), -2i32 as u32);
+        assert_eq!(shift(ShiftOpcode::SRA, 0x8000_0000, 33), 0xc000_0000);
+        assert_eq!(less_than(LessThanOpcode::SLT, -1i32 as u32, 0), 1);
+        assert_eq!(less_than(LessThanOpcode::SLTU, -1i32 as u32, 0), 0);
+        assert_eq!(mul(0xffff_ffff, 3), 0xffff_fffd);
+        assert_eq!(mulh(MulHOpcode::MULH, -2i32 as u32, 3), u32::MAX);
+        assert_eq!(mulh(MulHOpcode::MULHU, -2i32 as u32, 3), 2);
+        assert_eq!(divrem(DivRemOpcode::DIV, 7, 0), u32::MAX);
+        assert_eq!(divrem(DivRemOpcode::REMU, 7, 0), 7);
+        assert_eq!(
+            divrem(DivRemOpcode::DIV, i32::MIN as u32, -1i32 as u32),
+            i32::MIN as u32
+        );
+        assert_eq!(divrem(DivRemOpcode::REM, -7i32 as u32, 2), -1i32 as u32);
+
+        assert_eq!(branch_eq::<F>(BranchEqualOpcode::BNE, 100, -8, 1, 2), 92);
+        assert_eq!(branch_lt(BranchLessThanOpcode::BGEU, 100, -8, 1, 2), 104);
+        assert_eq!(jal_lui(Rv32JalLuiOpcode::JAL, 100, 16), (116, 104));
+        assert_eq!(
+            jal_lui(Rv32JalLuiOpcode::LUI, 100, 0x12345),
+            (104, 0x1234_5000)
+        );
+        assert_eq!(jalr(100, -3i32 as u32, 20), (16, 104));
+        assert_eq!(auipc(100, 0x12345 << 4), 0x1234_5000 + 100);
+
+        assert_eq!(
+            load::<F>(Rv32LoadStoreOpcode::LOADB, 0x1280_ff00, 1),
+            u32::MAX
+        );
+        assert_eq!(
+            load::<F>(Rv32LoadStoreOpcode::LOADHU, 0x1280_ff00, 2),
+            0x1280
+        );
+        assert_eq!(
+            store::<F>(Rv32LoadStoreOpcode::STOREB, 0xab, 0x1234_5678, 3),
+            0xab34_5678
+        );
+
+        assert_eq!(csr(Rv32CsrOpcode::CSRRC, 0b0110, 0b1100), (0b1100, 0b1000));
+        assert_eq!(
+            min_max(Rv32MinMaxOpcode::MIN, -1i32 as u32, 1),
+            -1i32 as u32
+        );
+        assert_eq!(rotate(Rv32RotateOpcode::ROR, 1, 1), 0x8000_0000);
+        assert_eq!(byte_op(Rv32ByteOpOpcode::REV8, 0x1234_5678), 0x7856_3412);
+        assert_eq!(atomic(Rv32AtomicOpcode::SC_W, 5, 9), (0, 5));
+        assert_eq!(
+            float_add(
+                Rv32FloatAddOpcode::FADD_S,
+                1.5f32.to_bits(),
+                2.25f32.to_bits()
+            ),
+            3.75f32.to_bits()
+        );
+        assert_eq!(
+            float_to_int(Rv32FloatToIntOpcode::FCVT_W_S, (-2.75f32).to_bits()),
+            -2i32 as u32
+        );
+    }
+}
@@ -0,0 +1,245 @@
+use std::{
+    borrow::{Borrow, BorrowMut},
+    sync::Arc,
+};
+
+use openvm_circuit::arch::{
+    AdapterAirContext, AdapterRuntimeContext, MinimalInstruction, Result, VmAdapterInterface,
+    VmCoreAir, VmCoreChip,
+};
+use openvm_circuit_primitives::{
+    bitwise_op_lookup::{BitwiseOperationLookupBus, BitwiseOperationLookupChip},
+    var_range::{VariableRangeCheckerBus, VariableRangeCheckerChip},
+};
+use openvm_circuit_primitives_derive::AlignedBorrow;
+use openvm_instructions::{instruction::Instruction, UsizeOpcode};
+use openvm_rv32im_transpiler::Rv32ShAddOpcode;
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    p3_air::{AirBuilder, BaseAir},
+    p3_field::{AbstractField, Field, PrimeField32},
+    rap::BaseAirWithPublicValues,
+};
+use strum::IntoEnumIterator;
+
+use crate::adapters::{RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS};
+
+/// Number of bits of the carries, which are at most `(255 * 8 + 255 + 9) / 256 = 9`.
+const CARRY_BITS: usize = 4;
+
+#[repr(C)]
+#[derive(AlignedBorrow)]
+pub struct Rv32ShAddCoreCols<T> {
+    pub a: [T; RV32_REGISTER_NUM_LIMBS],
+    pub b: [T; RV32_REGISTER_NUM_LIMBS],
+    pub c: [T; RV32_REGISTER_NUM_LIMBS],
+
+    pub opcode_sh1add_flag: T,
+    pub opcode_sh2add_flag: T,
+    pub opcode_sh3add_flag: T,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct Rv32ShAddCoreAir {
+    pub bitwise_lookup_bus: BitwiseOperationLookupBus,
+    pub range_bus: VariableRangeCheckerBus,
+    offset: usize,
+}
+
+impl<F: Field> BaseAir<F> for Rv32ShAddCoreAir {
+    fn width(&self) -> usize {
+        Rv32ShAddCoreCols::<F>::width()
+    }
+}
+impl<F: Field> BaseAirWithPublicValues<F> for Rv32ShAddCoreAir {}
+
+impl<AB, I> VmCoreAir<AB, I> for Rv32ShAddCoreAir
+where
+    AB: InteractionBuilder,
+    I: VmAdapterInterface<AB::Expr>,
+    I::Reads: From<[[AB::Expr; RV32_REGISTER_NUM_LIMBS]; 2]>,
+    I::Writes: From<[[AB::Expr; RV32_REGISTER_NUM_LIMBS]; 1]>,
+    I::ProcessedInstruction: From<MinimalInstruction<AB::Expr>>,
+{
+    fn eval(
+        &self,
+        builder: &mut AB,
+        local_core: &[AB::Var],
+        _from_pc: AB::Var,
+    ) -> AdapterAirContext<AB::Expr, I> {
+        let cols: &Rv32ShAddCoreCols<_> = local_core.borrow();
+        let flags = [
+            cols.opcode_sh1add_flag,
+            cols.opcode_sh2add_flag,
+            cols.opcode_sh3add_flag,
+        ];
+
+        let is_valid = flags.iter().fold(AB::Expr::ZERO, |acc, &flag| {
+            builder.assert_bool(flag);
+            acc + flag.into()
+        });
+        builder.assert_bool(is_valid.clone());
+
+        let multiplier = flags
+            .iter()
+            .enumerate()
+            .fold(AB::Expr::ZERO, |acc, (i, &flag)| {
+                acc + AB::Expr::from_canonical_u32(2 << i) * flag
+            });
+
+        // The carry of each limb is carry[i] = (b[i] * multiplier + c[i] + carry[i - 1] - a[i])
+        // / 2^LIMB_BITS. Since a[i] is range checked, a[i] = (b[i] * multiplier + c[i] +
+        // carry[i - 1]) % 2^LIMB_BITS as long as carry[i] is small enough not to wrap around.
+        let carry_divide = AB::F::from_canonical_usize(1 << RV32_CELL_BITS).inverse();
+        let mut carry = AB::Expr::ZERO;
+        for i in 0..RV32_REGISTER_NUM_LIMBS {
+            carry = AB::Expr::from(carry_divide)
+                * (cols.b[i] * multiplier.clone() + cols.c[i] - cols.a[i] + carry);
+            self.range_bus
+                .range_check(carry.clone(), CARRY_BITS)
+                .eval(builder, is_valid.clone());
+        }
+        for i in 0..(RV32_REGISTER_NUM_LIMBS / 2) {
+            self.bitwise_lookup_bus
+                .send_range(cols.a[i * 2], cols.a[i * 2 + 1])
+                .eval(builder, is_valid.clone());
+        }
+
+        let expected_opcode = flags.iter().zip(Rv32ShAddOpcode::iter()).fold(
+            AB::Expr::ZERO,
+            |acc, (flag, local_opcode)| {
+                acc + (*flag).into() * AB::Expr::from_canonical_u8(local_opcode as u8)
+            },
+        ) + AB::Expr::from_canonical_usize(self.offset);
+
+        AdapterAirContext {
+            to_pc: None,
+            reads: [cols.b.map(Into::into), cols.c.map(Into::into)].into(),
+            writes: [cols.a.map(Into::into)].into(),
+            instruction: MinimalInstruction {
+                is_valid,
+                opcode: expected_opcode,
+            }
+            .into(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Rv32ShAddCoreRecord<T> {
+    pub opcode: Rv32ShAddOpcode,
+    pub a: [T; RV32_REGISTER_NUM_LIMBS],
+    pub b: [T; RV32_REGISTER_NUM_LIMBS],
+    pub c: [T; RV32_REGISTER_NUM_LIMBS],
+}
+
+#[derive(Debug)]
+pub struct Rv32ShAddCoreChip {
+    pub air: Rv32ShAddCoreAir,
+    pub bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<RV32_CELL_BITS>>,
+    pub range_checker_chip: Arc<VariableRangeCheckerChip>,
+}
+
+impl Rv32ShAddCoreChip {
+    pub fn new(
+        bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<RV32_CELL_BITS>>,
+        range_checker_chip: Arc<VariableRangeCheckerChip>,
+        offset: usize,
+    ) -> Self {
+        Self {
+            air: Rv32ShAddCoreAir {
+                bitwise_lookup_bus: bitwise_lookup_chip.bus(),
+                range_bus: range_checker_chip.bus(),
+                offset,
+            },
+            bitwise_lookup_chip,
+            range_checker_chip,
+        }
+    }
+}
+
+impl<F, I> VmCoreChip<F, I> for Rv32ShAddCoreChip
+where
+    F: PrimeField32,
+    I: VmAdapterInterface<F>,
+    I::Reads: Into<[[F; RV32_REGISTER_NUM_LIMBS]; 2]>,
+    I::Writes: From<[[F; RV32_REGISTER_NUM_LIMBS]; 1]>,
+{
+    type Record = Rv32ShAddCoreRecord<F>;
+    type Air = Rv32ShAddCoreAir;
+
+    #[allow(clippy::type_complexity)]
+    fn execute_instruction(
+        &self,
+        instruction: &Instruction<F>,
+        _from_pc: u32,
+        reads: I::Reads,
+    ) -> Result<(AdapterRuntimeContext<F, I>, Self::Record)> {
+        let Instruction { opcode, .. } = instruction;
+        let local_opcode = Rv32ShAddOpcode::from_usize(opcode.local_opcode_idx(self.air.offset));
+
+        let data: [[F; RV32_REGISTER_NUM_LIMBS]; 2] = reads.into();
+        let b = data[0].map(|x| x.as_canonical_u32());
+        let c = data[1].map(|y| y.as_canonical_u32());
+        let (a, carry) = run_sh_add(local_opcode, &b, &c);
+
+        for carry_val in carry {
+            self.range_checker_chip.add_count(carry_val, CARRY_BITS);
+        }
+        for i in 0..(RV32_REGISTER_NUM_LIMBS / 2) {
+            self.bitwise_lookup_chip
+                .request_range(a[i * 2], a[i * 2 + 1]);
+        }
+
+        let output = AdapterRuntimeContext::without_pc([a.map(F::from_canonical_u32)]);
+        let record = Self::Record {
+            opcode: local_opcode,
+            a: a.map(F::from_canonical_u32),
+            b: data[0],
+            c: data[1],
+        };
+
+        Ok((output, record))
+    }
+
+    fn get_opcode_name(&self, opcode: usize) -> String {
+        format!(
+            "{:?}",
+            Rv32ShAddOpcode::from_usize(opcode - self.air.offset)
+        )
+    }
+
+    fn generate_trace_row(&self, row_slice: &mut [F], record: Self::Record) {
+        let row_slice: &mut Rv32ShAddCoreCols<_> = row_slice.borrow_mut();
+        row_slice.a = record.a;
+        row_slice.b = record.b;
+        row_slice.c = record.c;
+        row_slice.opcode_sh1add_flag = F::from_bool(record.opcode == Rv32ShAddOpcode::SH1ADD);
+        row_slice.opcode_sh2add_flag = F::from_bool(record.opcode == Rv32ShAddOpcode::SH2ADD);
+        row_slice.opcode_sh3add_flag = F::from_bool(record.opcode == Rv32ShAddOpcode::SH3ADD);
+    }
+
+    fn air(&self) -> &Self::Air {
+        &self.air
+    }
+}
+
+// Returns (a, carry)
+pub fn run_sh_add(
+    opcode: Rv32ShAddOpcode,
+    b: &[u32; RV32_REGISTER_NUM_LIMBS],
+    c: &[u32; RV32_REGISTER_NUM_LIMBS],
+) -> (
+    [u32; RV32_REGISTER_NUM_LIMBS],
+    [u32; RV32_REGISTER_NUM_LIMBS],
+) {
+    let shift = opcode as usize + 1;
+    let mut a = [0u32; RV32_REGISTER_NUM_LIMBS];
+    let mut carry = [0u32; RV32_REGISTER_NUM_LIMBS];
+    for i in 0..RV32_REGISTER_NUM_LIMBS {
+        let sum = (b[i] << shift) + c[i] + if i > 0 { carry[i - 1] } else { 0 };
+        a[i] = sum & ((1 << RV32_CELL_BITS) - 1);
+        carry[i] = sum >> RV32_CELL_BITS;
+    }
+    (a, carry)
+}
@@ -0,0 +1,100 @@
+use std::sync::Arc;
+
+use openvm_circuit::{
+    arch::{testing::VmChipTestBuilder, BITWISE_OP_LOOKUP_BUS},
+    utils::generate_long_number,
+};
+use openvm_circuit_primitives::bitwise_op_lookup::{
+    BitwiseOperationLookupBus, BitwiseOperationLookupChip,
+};
+use openvm_rv32im_transpiler::Rv32ShAddOpcode;
+use openvm_stark_backend::p3_field::AbstractField;
+use openvm_stark_sdk::{p3_baby_bear::BabyBear, utils::create_seeded_rng};
+
+use super::{run_sh_add, Rv32ShAddChip, Rv32ShAddCoreChip};
+use crate::{
+    adapters::{Rv32BaseAluAdapterChip, RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS},
+    test_utils::rv32_rand_write_register_or_imm,
+};
+
+type F = BabyBear;
+
+//////////////////////////////////////////////////////////////////////////////////////
+// POSITIVE TESTS
+//
+// Randomly generate computations and execute, ensuring that the generated trace
+// passes all constraints.
+//////////////////////////////////////////////////////////////////////////////////////
+
+fn run_rv32_sh_add_rand_test(opcode: Rv32ShAddOpcode, num_ops: usize) {
+    let mut rng = create_seeded_rng();
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = Arc::new(BitwiseOperationLookupChip::<RV32_CELL_BITS>::new(
+        bitwise_bus,
+    ));
+
+    let mut tester = VmChipTestBuilder::default();
+    let mut chip = Rv32ShAddChip::<F>::new(
+        Rv32BaseAluAdapterChip::new(
+            tester.execution_bus(),
+            tester.program_bus(),
+            tester.memory_controller(),
+        ),
+        Rv32ShAddCoreChip::new(
+            bitwise_chip.clone(),
+            tester.memory_controller().borrow().range_checker.clone(),
+            0,
+        ),
+        tester.memory_controller(),
+    );
+
+    for _ in 0..num_ops {
+        let b = generate_long_number::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(&mut rng);
+        let c = generate_long_number::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(&mut rng);
+        let c_imm = None;
+
+        let (instruction, rd) =
+            rv32_rand_write_register_or_imm(&mut tester, b, c, c_imm, opcode as usize, &mut rng);
+        tester.execute(&mut chip, instruction);
+
+        let (a, _) = run_sh_add(opcode, &b, &c);
+        assert_eq!(
+            a.map(F::from_canonical_u32),
+            tester.read::<RV32_REGISTER_NUM_LIMBS>(1, rd)
+        )
+    }
+
+    let tester = tester.build().load(chip).load(bitwise_chip).finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
+#[test]
+fn rv32_sh_add_sh1add_rand_test() {
+    run_rv32_sh_add_rand_test(Rv32ShAddOpcode::SH1ADD, 100);
+}
+
+#[test]
+fn rv32_sh_add_sh2add_rand_test() {
+    run_rv32_sh_add_rand_test(Rv32ShAddOpcode::SH2ADD, 100);
+}
+
+#[test]
+fn rv32_sh_add_sh3add_rand_test() {
+    run_rv32_sh_add_rand_test(Rv32ShAddOpcode::SH3ADD, 100);
+}
+
+//////////////////////////////////////////////////////////////////////////////////////
+// SANITY TESTS
+//
+// Ensure that the run_* function produces the expected results.
+//////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn run_sh_add_sanity_test() {
+    let x: [u32; RV32_REGISTER_NUM_LIMBS] = [229, 33, 29, 111];
+    let y: [u32; RV32_REGISTER_NUM_LIMBS] = [50, 171, 44, 194];
+    let z: [u32; RV32_REGISTER_NUM_LIMBS] = [198, 50, 161, 126];
+    let (result, carry) = run_sh_add(Rv32ShAddOpcode::SH2ADD, &x, &y);
+    assert_eq!(z, result);
+    assert_eq!([3, 1, 0, 2], carry);
+}
@@ -364,7 +364,7 @@ where
     }
 }
 
-pub(super) fn run_shift<const NUM_LIMBS: usize, const LIMB_BITS: usize>(
+pub(crate) fn run_shift<const NUM_LIMBS: usize, const LIMB_BITS: usize>(
     opcode: ShiftOpcode,
     x: &[u32; NUM_LIMBS],
     y: &[u32; NUM_LIMBS],
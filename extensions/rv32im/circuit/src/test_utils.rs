@@ -55,3 +55,27 @@ pub fn generate_rv32_is_type_immediate(
         .map(|x| x as u32),
     )
 }
+
+/// Returns a random float, which is more often a special value, a subnormal or of moderate
+/// magnitude than random bits would be.
+#[cfg_attr(all(feature = "test-utils", not(test)), allow(dead_code))]
+pub fn generate_f32(rng: &mut StdRng) -> u32 {
+    const SPECIAL: [u32; 9] = [
+        0x0000_0000, // zero
+        0x7f80_0000, // infinity
+        0x7fc0_0000, // quiet NaN
+        0x7f80_0001, // signaling NaN
+        0x0000_0001, // smallest subnormal
+        0x007f_ffff, // largest subnormal
+        0x0080_0000, // smallest normal
+        0x7f7f_ffff, // largest normal
+        0x3f80_0000, // one
+    ];
+    let sign = rng.gen::<u32>() & (1 << 31);
+    sign | match rng.gen_range(0..4) {
+        0 => SPECIAL[rng.gen_range(0..SPECIAL.len())],
+        1 => rng.gen_range(0..1 << 23),
+        2 => (rng.gen_range(100..156) << 23) | rng.gen_range(0..1 << 23),
+        _ => rng.gen::<u32>() & !(1 << 31),
+    }
+}
@@ -15,6 +15,22 @@ macro_rules! hint_store_u32 {
     };
 }
 
+/// Store the next 4 bytes from the hint stream, read as a length `len`, to [[rd] + imm]_2,
+/// followed by the next `len` bytes padded to a multiple of 4. The buffer at [rd] + imm must have
+/// room for `4 + len.div_ceil(4) * 4` bytes.
+#[macro_export]
+macro_rules! hint_buffer {
+    ($x:expr, $imm:expr) => {
+        openvm_platform::custom_insn_i!(
+            openvm_rv32im_guest::SYSTEM_OPCODE,
+            openvm_rv32im_guest::HINT_BUFFER_FUNCT3,
+            $x,
+            "x0",
+            $imm
+        )
+    };
+}
+
 /// Reset the hint stream with the next hint.
 #[inline(always)]
 pub fn hint_input() {
@@ -27,6 +43,33 @@ pub fn hint_input() {
     );
 }
 
+/// Reset the hint stream with the next input of input stream `stream`. Key-value stores look the
+/// input up by `key`, other streams ignore it.
+#[inline(always)]
+pub fn hint_input_from(stream: u32, key: &[u8]) {
+    let key_ref = [key.as_ptr() as u32, key.len() as u32];
+    openvm_platform::custom_insn_i!(
+        SYSTEM_OPCODE,
+        PHANTOM_FUNCT3,
+        stream,
+        key_ref.as_ptr(),
+        PhantomImm::HintStream as u16
+    );
+}
+
+/// Put the performance counters of the current segment in front of the hint stream, as five
+/// little-endian `u32`s. Hints already in the stream are read after the counters.
+#[inline(always)]
+pub fn hint_perf_counters() {
+    openvm_platform::custom_insn_i!(
+        SYSTEM_OPCODE,
+        PHANTOM_FUNCT3,
+        "x0",
+        "x0",
+        PhantomImm::HintPerfCounters as u16
+    );
+}
+
 /// Store rs1 to [[rd] + imm]_2.
 #[macro_export]
 macro_rules! reveal {
@@ -57,3 +100,20 @@ pub fn raw_print_str_from_bytes(msg_ptr: *const u8, len: usize) {
         PhantomImm::PrintStr as u16
     );
 }
+
+/// Log a UTF-8 string encoded as bytes to the host log, annotated with the current cycle.
+#[inline(always)]
+pub fn log_str_from_bytes(str_as_bytes: &[u8]) {
+    raw_log_str_from_bytes(str_as_bytes.as_ptr(), str_as_bytes.len());
+}
+
+#[inline(always)]
+pub fn raw_log_str_from_bytes(msg_ptr: *const u8, len: usize) {
+    openvm_platform::custom_insn_i!(
+        SYSTEM_OPCODE,
+        PHANTOM_FUNCT3,
+        msg_ptr,
+        len,
+        PhantomImm::DebugLog as u16
+    );
+}
@@ -5,6 +5,11 @@
 mod io;
 #[cfg(target_os = "zkvm")]
 pub use io::*;
+/// Word-level memory copy and fill intrinsics, and hinted page loads.
+#[cfg(target_os = "zkvm")]
+mod mem;
+#[cfg(target_os = "zkvm")]
+pub use mem::*;
 use strum_macros::FromRepr;
 
 /// This is custom-0 defined in RISC-V spec document
@@ -17,7 +22,37 @@ pub const TERMINATE_FUNCT3: u8 = 0b000;
 pub const HINT_STORE_W_FUNCT3: u8 = 0b001;
 pub const REVEAL_FUNCT3: u8 = 0b010;
 pub const PHANTOM_FUNCT3: u8 = 0b011;
+pub const HINT_BUFFER_FUNCT3: u8 = 0b111;
 pub const CSRRW_FUNCT3: u8 = 0b001;
+pub const CSRRS_FUNCT3: u8 = 0b010;
+pub const CSRRC_FUNCT3: u8 = 0b011;
+/// Set in the funct3 of CSR instructions whose source is the 5-bit immediate in the rs1 field.
+pub const CSR_IMM_FUNCT3_BIT: u8 = 0b100;
+
+/// This is custom-1 defined in RISC-V spec document
+pub const MEM_OPCODE: u8 = 0x2b;
+pub const MEM_FUNCT3: u8 = 0b100;
+pub const MEMCPY_FUNCT7: u8 = 0x0;
+pub const MEMSET_FUNCT7: u8 = 0x1;
+pub const HINT_LOAD_PAGE_FUNCT7: u8 = 0x2;
+
+/// Number of bytes written by one HINT_LOAD_PAGE instruction.
+pub const HINT_PAGE_BYTES: usize = 1024;
+/// HINT_LOAD_PAGE writes its page in blocks of this many bytes, so the page must be aligned to it.
+pub const HINT_PAGE_ALIGN: usize = 8;
+
+/// A page of bytes with the alignment required by HINT_LOAD_PAGE.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(C, align(8))]
+pub struct HintPage(pub [u8; HINT_PAGE_BYTES]);
+
+const _: () = assert!(core::mem::align_of::<HintPage>() == HINT_PAGE_ALIGN);
+
+impl Default for HintPage {
+    fn default() -> Self {
+        Self([0; HINT_PAGE_BYTES])
+    }
+}
 
 /// imm options for system phantom instructions
 #[derive(Debug, Copy, Clone, PartialEq, Eq, FromRepr)]
@@ -25,4 +60,7 @@ pub const CSRRW_FUNCT3: u8 = 0b001;
 pub enum PhantomImm {
     HintInput = 0,
     PrintStr,
+    DebugLog,
+    HintStream,
+    HintPerfCounters,
 }
@@ -0,0 +1,39 @@
+use crate::{
+    HintPage, HINT_LOAD_PAGE_FUNCT7, MEMCPY_FUNCT7, MEMSET_FUNCT7, MEM_FUNCT3, MEM_OPCODE,
+};
+
+/// Copy `num_words` words from `src` to `dst`, one word at a time in increasing address order.
+///
+/// # Safety
+/// `src` must be valid for reads and `dst` valid for writes of `num_words` words, and both must
+/// be 4-byte aligned. The two regions may only overlap if `dst <= src`.
+#[inline(always)]
+pub unsafe fn memcpy_words(dst: *mut u32, src: *const u32, num_words: usize) {
+    openvm_platform::custom_insn_r!(MEM_OPCODE, MEM_FUNCT3, MEMCPY_FUNCT7, dst, src, num_words);
+}
+
+/// Set each of the `num_words` words at `dst` to `value`.
+///
+/// # Safety
+/// `dst` must be valid for writes of `num_words` words and 4-byte aligned.
+#[inline(always)]
+pub unsafe fn memset_words(dst: *mut u32, value: u32, num_words: usize) {
+    openvm_platform::custom_insn_r!(MEM_OPCODE, MEM_FUNCT3, MEMSET_FUNCT7, dst, value, num_words);
+}
+
+/// Store the next [HINT_PAGE_BYTES](crate::HINT_PAGE_BYTES) bytes from the hint stream to `page`.
+///
+/// # Safety
+/// `page` must be valid for writes of a [HintPage]. Its alignment guarantees the alignment
+/// HINT_LOAD_PAGE requires.
+#[inline(always)]
+pub unsafe fn hint_load_page(page: *mut HintPage) {
+    openvm_platform::custom_insn_r!(
+        MEM_OPCODE,
+        MEM_FUNCT3,
+        HINT_LOAD_PAGE_FUNCT7,
+        "x0",
+        page,
+        "x0"
+    );
+}
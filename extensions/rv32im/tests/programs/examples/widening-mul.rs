@@ -0,0 +1,21 @@
+#![cfg_attr(not(feature = "std"), no_main)]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use core::hint::black_box;
+
+openvm::entry!(main);
+
+pub fn main() {
+    let x: u32 = black_box(0xdead_beef);
+    let y: u32 = black_box(0x1234_5678);
+    // rustc computes widening multiplications with a `mulh*` and a `mul` on the same operands
+    if (x as u64) * (y as u64) != 0x0fd5_bdee_5621_ca08 {
+        openvm::process::panic();
+    }
+    if (x as i32 as i64) * (y as i32 as i64) != -0x025e_9889_a9de_35f8 {
+        openvm::process::panic();
+    }
+    if (x as i32 as i64) * (y as i64) != -0x025e_9889_a9de_35f8 {
+        openvm::process::panic();
+    }
+}
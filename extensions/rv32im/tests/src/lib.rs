@@ -7,9 +7,10 @@ mod tests {
         utils::{air_test, air_test_with_min_segments},
     };
     use openvm_instructions::exe::VmExe;
-    use openvm_rv32im_circuit::{Rv32IConfig, Rv32ImConfig};
+    use openvm_rv32im_circuit::{Rv32IConfig, Rv32ImConfig, Rv32M};
     use openvm_rv32im_transpiler::{
-        Rv32ITranspilerExtension, Rv32IoTranspilerExtension, Rv32MTranspilerExtension,
+        Rv32ITranspilerExtension, Rv32IoTranspilerExtension, Rv32MFusedTranspilerExtension,
+        Rv32MTranspilerExtension,
     };
     use openvm_stark_sdk::{openvm_stark_backend::p3_field::AbstractField, p3_baby_bear::BabyBear};
     use openvm_toolchain_tests::{
@@ -53,6 +54,28 @@ mod tests {
         Ok(())
     }
 
+    #[test_case("widening-mul", 1)]
+    #[test_case("collatz", 1)]
+    fn test_rv32im_fused_mul(example_name: &str, min_segments: usize) -> Result<()> {
+        let elf = build_example_program_at_path(get_programs_dir!(), example_name)?;
+        let exe = VmExe::from_elf(
+            elf,
+            Transpiler::<F>::default()
+                .with_extension(Rv32ITranspilerExtension)
+                .with_extension(Rv32IoTranspilerExtension)
+                .with_extension(Rv32MFusedTranspilerExtension),
+        )?;
+        let config = Rv32ImConfig {
+            mul: Rv32M {
+                fuse_mulh_mul: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        air_test_with_min_segments(config, exe, vec![], min_segments);
+        Ok(())
+    }
+
     // #[test_case("fibonacci", 1)]
     #[test_case("collatz", 1)]
     fn test_rv32im_std(example_name: &str, min_segments: usize) -> Result<()> {
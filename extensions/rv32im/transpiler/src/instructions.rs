@@ -154,6 +154,242 @@ pub enum DivRemOpcode {
     REMU,
 }
 
+/// A `mulh[[s]u]` fused with a `mul` on the same operands, which together compute the full
+/// 64-bit product. Writes the low word to `[a:4]_1` and the high word to `[f:4]_1`, then skips
+/// the instruction it was fused with. The variants are in the same order as [MulHOpcode].
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, EnumCount, EnumIter, FromRepr, UsizeOpcode,
+)]
+#[opcode_offset = 0x258]
+#[repr(usize)]
+#[allow(non_camel_case_types)]
+pub enum MulHMulOpcode {
+    MULH_MUL,
+    MULHSU_MUL,
+    MULHU_MUL,
+}
+
+// =================================================================================================
+// Zicsr Instructions
+// =================================================================================================
+
+/// Read-modify-write of a CSR. The immediate forms use operand `e = 0`.
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, EnumCount, EnumIter, FromRepr, UsizeOpcode,
+)]
+#[opcode_offset = 0x260]
+#[repr(usize)]
+#[allow(non_camel_case_types)]
+pub enum Rv32CsrOpcode {
+    CSRRW,
+    CSRRS,
+    CSRRC,
+}
+
+// =================================================================================================
+// Zba and Zbb Instructions
+// =================================================================================================
+
+/// `rd = (rs1 << n) + rs2` for n = 1, 2, 3.
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, EnumCount, EnumIter, FromRepr, UsizeOpcode,
+)]
+#[opcode_offset = 0x270]
+#[repr(usize)]
+#[allow(non_camel_case_types)]
+pub enum Rv32ShAddOpcode {
+    SH1ADD,
+    SH2ADD,
+    SH3ADD,
+}
+
+/// Bitwise operations with the second operand inverted.
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, EnumCount, EnumIter, FromRepr, UsizeOpcode,
+)]
+#[opcode_offset = 0x273]
+#[repr(usize)]
+#[allow(non_camel_case_types)]
+pub enum Rv32LogicNotOpcode {
+    ANDN,
+    ORN,
+    XNOR,
+}
+
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, EnumCount, EnumIter, FromRepr, UsizeOpcode,
+)]
+#[opcode_offset = 0x276]
+#[repr(usize)]
+#[allow(non_camel_case_types)]
+pub enum Rv32MinMaxOpcode {
+    MIN,
+    MINU,
+    MAX,
+    MAXU,
+}
+
+/// Rotations by the lowest 5 bits of the second operand.
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, EnumCount, EnumIter, FromRepr, UsizeOpcode,
+)]
+#[opcode_offset = 0x27a]
+#[repr(usize)]
+#[allow(non_camel_case_types)]
+pub enum Rv32RotateOpcode {
+    ROL,
+    ROR,
+}
+
+/// Unary: the second operand is the immediate 0.
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, EnumCount, EnumIter, FromRepr, UsizeOpcode,
+)]
+#[opcode_offset = 0x27c]
+#[repr(usize)]
+#[allow(non_camel_case_types)]
+pub enum Rv32BitCountOpcode {
+    CLZ,
+    CTZ,
+    CPOP,
+}
+
+/// Unary: the second operand is the immediate 0.
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, EnumCount, EnumIter, FromRepr, UsizeOpcode,
+)]
+#[opcode_offset = 0x280]
+#[repr(usize)]
+#[allow(non_camel_case_types)]
+pub enum Rv32ByteOpOpcode {
+    SEXT_B,
+    SEXT_H,
+    ZEXT_H,
+    REV8,
+    ORC_B,
+}
+
+// =================================================================================================
+// A Instructions
+// =================================================================================================
+
+/// Atomic memory operations on words, with single-hart semantics: `LR_W` is a load and `SC_W`
+/// is a store that always succeeds.
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, EnumCount, EnumIter, FromRepr, UsizeOpcode,
+)]
+#[opcode_offset = 0x290]
+#[repr(usize)]
+#[allow(non_camel_case_types)]
+pub enum Rv32AtomicOpcode {
+    LR_W,
+    SC_W,
+    AMOSWAP_W,
+    AMOADD_W,
+    AMOXOR_W,
+    AMOAND_W,
+    AMOOR_W,
+    AMOMIN_W,
+    AMOMAX_W,
+    AMOMINU_W,
+    AMOMAXU_W,
+}
+
+// =================================================================================================
+// F Instructions
+// =================================================================================================
+
+/// Sign injection, and classification whose second operand is the immediate 0.
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, EnumCount, EnumIter, FromRepr, UsizeOpcode,
+)]
+#[opcode_offset = 0x2a0]
+#[repr(usize)]
+#[allow(non_camel_case_types)]
+pub enum Rv32FloatMiscOpcode {
+    FSGNJ_S,
+    FSGNJN_S,
+    FSGNJX_S,
+    FCLASS_S,
+}
+
+/// Comparisons writing 0 or 1 to an integer register, and minimum and maximum.
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, EnumCount, EnumIter, FromRepr, UsizeOpcode,
+)]
+#[opcode_offset = 0x2a8]
+#[repr(usize)]
+#[allow(non_camel_case_types)]
+pub enum Rv32FloatCmpOpcode {
+    FEQ_S,
+    FLT_S,
+    FLE_S,
+    FMIN_S,
+    FMAX_S,
+}
+
+/// Rounds to nearest, ties to even.
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, EnumCount, EnumIter, FromRepr, UsizeOpcode,
+)]
+#[opcode_offset = 0x2b0]
+#[repr(usize)]
+#[allow(non_camel_case_types)]
+pub enum Rv32FloatAddOpcode {
+    FADD_S,
+    FSUB_S,
+}
+
+/// Rounds to nearest, ties to even.
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, EnumCount, EnumIter, FromRepr, UsizeOpcode,
+)]
+#[opcode_offset = 0x2b4]
+#[repr(usize)]
+#[allow(non_camel_case_types)]
+pub enum Rv32FloatMulOpcode {
+    FMUL_S,
+}
+
+/// Rounds toward zero. The second operand is the immediate 0.
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, EnumCount, EnumIter, FromRepr, UsizeOpcode,
+)]
+#[opcode_offset = 0x2b8]
+#[repr(usize)]
+#[allow(non_camel_case_types)]
+pub enum Rv32FloatToIntOpcode {
+    FCVT_W_S,
+    FCVT_WU_S,
+}
+
+/// Rounds to nearest, ties to even. The second operand is the immediate 0.
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, EnumCount, EnumIter, FromRepr, UsizeOpcode,
+)]
+#[opcode_offset = 0x2bc]
+#[repr(usize)]
+#[allow(non_camel_case_types)]
+pub enum Rv32IntToFloatOpcode {
+    FCVT_S_W,
+    FCVT_S_WU,
+}
+
+// =================================================================================================
+// Rv32Memcpy Instructions
+// =================================================================================================
+
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, EnumCount, EnumIter, FromRepr, UsizeOpcode,
+)]
+#[opcode_offset = 0x2c0]
+#[repr(usize)]
+#[allow(non_camel_case_types)]
+pub enum Rv32MemcpyOpcode {
+    MEMCPY,
+    MEMSET,
+}
+
 // =================================================================================================
 // Rv32HintStore Instruction
 // =================================================================================================
@@ -166,6 +402,11 @@ pub enum DivRemOpcode {
 #[allow(non_camel_case_types)]
 pub enum Rv32HintStoreOpcode {
     HINT_STOREW,
+    /// Stores a length word from the hint stream followed by that many bytes, padded to a
+    /// multiple of 4.
+    HINT_BUFFER,
+    /// Stores a page of [HINT_PAGE_BYTES](crate::HINT_PAGE_BYTES) bytes from the hint stream.
+    HINT_LOAD_PAGE,
 }
 
 // =================================================================================================
@@ -179,4 +420,7 @@ pub enum Rv32Phantom {
     HintInput = 0x20,
     /// Peek string from memory and print it to stdout.
     PrintStr,
+    /// Like [Self::HintInput], from the input stream whose index is in register `a`. Register `b`
+    /// points to the pointer and length of the key for key-value streams.
+    HintStream,
 }
@@ -1,27 +1,31 @@
 use std::marker::PhantomData;
 
 use openvm_instructions::{
-    instruction::Instruction, riscv::RV32_REGISTER_NUM_LIMBS, PhantomDiscriminant, SystemOpcode,
-    VmOpcode,
+    instruction::Instruction,
+    riscv::{RV32_IMM_AS, RV32_MEMORY_AS, RV32_REGISTER_AS, RV32_REGISTER_NUM_LIMBS},
+    PhantomDiscriminant, SysPhantom, SystemOpcode, UsizeOpcode, VmOpcode,
 };
 use openvm_rv32im_guest::{
-    PhantomImm, CSRRW_FUNCT3, CSR_OPCODE, HINT_STORE_W_FUNCT3, PHANTOM_FUNCT3, REVEAL_FUNCT3,
-    RV32M_FUNCT7, RV32_ALU_OPCODE, SYSTEM_OPCODE, TERMINATE_FUNCT3,
+    PhantomImm, CSRRC_FUNCT3, CSRRS_FUNCT3, CSRRW_FUNCT3, CSR_IMM_FUNCT3_BIT, CSR_OPCODE,
+    HINT_BUFFER_FUNCT3, HINT_LOAD_PAGE_FUNCT7, HINT_STORE_W_FUNCT3, MEMCPY_FUNCT7, MEMSET_FUNCT7,
+    MEM_FUNCT3, MEM_OPCODE, PHANTOM_FUNCT3, REVEAL_FUNCT3, RV32M_FUNCT7, RV32_ALU_OPCODE,
+    SYSTEM_OPCODE, TERMINATE_FUNCT3,
 };
 use openvm_stark_backend::p3_field::PrimeField32;
 use openvm_transpiler::{
-    util::{nop, unimp},
+    util::{from_i_type_shamt, from_r_type, from_s_type, nop, unimp},
     TranspilerExtension,
 };
 use rrs::InstructionTranspiler;
 use rrs_lib::{
-    instruction_formats::{IType, RType},
+    instruction_formats::{IType, ITypeShamt, RType, SType},
     process_instruction,
 };
 
 mod instructions;
 pub mod rrs;
 pub use instructions::*;
+pub use openvm_rv32im_guest::{HINT_PAGE_ALIGN, HINT_PAGE_BYTES};
 
 #[derive(Default)]
 pub struct Rv32ITranspilerExtension;
@@ -29,9 +33,122 @@ pub struct Rv32ITranspilerExtension;
 #[derive(Default)]
 pub struct Rv32MTranspilerExtension;
 
+/// Transpiles the M extension like [Rv32MTranspilerExtension], but fuses a `mulh[[s]u]` and a
+/// `mul` on the same operands, in either order, into one [MulHMulOpcode] instruction. This is
+/// how rustc computes widening multiplications. The fused instruction takes the place of the
+/// first one and skips the second, which is still transpiled so that it can be jumped to. Use it
+/// instead of [Rv32MTranspilerExtension], not together with it.
+#[derive(Default)]
+pub struct Rv32MFusedTranspilerExtension;
+
 #[derive(Default)]
 pub struct Rv32IoTranspilerExtension;
 
+/// Transpiles the Zicsr instructions on the CSRs of [Rv32Csr]. Other CSR instructions are left
+/// to [Rv32ITranspilerExtension], which transpiles them to `unimp`.
+#[derive(Default)]
+pub struct Rv32ZicsrTranspilerExtension;
+
+/// Transpiles the Zba instructions `sh1add`, `sh2add` and `sh3add`.
+#[derive(Default)]
+pub struct Rv32ZbaTranspilerExtension;
+
+/// Transpiles the Zbb instructions.
+#[derive(Default)]
+pub struct Rv32ZbbTranspilerExtension;
+
+/// Transpiles the word instructions of the A extension: `lr.w`, `sc.w` and the `amo*.w`
+/// instructions. The `aq` and `rl` ordering bits are ignored, as there is a single hart.
+#[derive(Default)]
+pub struct Rv32ATranspilerExtension;
+
+/// Transpiles the single-precision instructions of the F extension, except `fdiv.s`,
+/// `fsqrt.s` and the fused multiply-add instructions. The float registers are stored after the
+/// integer registers, from [RV32_FLOAT_REG_PTR_START]. Arithmetic rounds to nearest, ties to
+/// even, so only that rounding mode and the dynamic one are accepted, and conversions to
+/// integers only accept rounding toward zero. The accrued exception flags are not tracked.
+#[derive(Default)]
+pub struct Rv32FTranspilerExtension;
+
+const RV32_ALU_IMM_OPCODE: u8 = 0b0010011;
+const ZBA_FUNCT7: u8 = 0b0010000;
+const ZBB_INVERTED_FUNCT7: u8 = 0b0100000;
+const ZBB_MIN_MAX_FUNCT7: u8 = 0b0000101;
+const ZBB_ROTATE_FUNCT7: u8 = 0b0110000;
+const ZBB_ZEXT_H_FUNCT7: u8 = 0b0000100;
+const RV32_AMO_OPCODE: u8 = 0b0101111;
+const AMO_W_FUNCT3: u8 = 0b010;
+const RV32_LOAD_FP_OPCODE: u8 = 0b0000111;
+const RV32_STORE_FP_OPCODE: u8 = 0b0100111;
+const RV32_OP_FP_OPCODE: u8 = 0b1010011;
+const FP_W_FUNCT3: u8 = 0b010;
+const FP_RM_RNE: u8 = 0b000;
+const FP_RM_RTZ: u8 = 0b001;
+const FP_RM_DYN: u8 = 0b111;
+const MUL_FUNCT3: u8 = 0b000;
+const MULH_FUNCT3: u8 = 0b001;
+const MULHSU_FUNCT3: u8 = 0b010;
+const MULHU_FUNCT3: u8 = 0b011;
+
+/// Pointer of the first float register in the register address space, after the 32 registers
+/// and the CSRs.
+pub const RV32_FLOAT_REG_PTR_START: usize = 64 * RV32_REGISTER_NUM_LIMBS;
+
+/// Pointer of the first CSR in the register address space, right after the 32 registers.
+pub const RV32_CSR_PTR_START: usize = 32 * RV32_REGISTER_NUM_LIMBS;
+
+/// The CSRs supported by [Rv32ZicsrTranspilerExtension].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Rv32Csr {
+    /// `cycle`, `time`, `instret` or one of their upper halves. They are read-only and always
+    /// read as zero.
+    Counter,
+    /// `sscratch` or `mscratch`, a read-write register stored as 4 bytes at `pointer` in the
+    /// register address space. It is zero at the start of execution.
+    Scratch { pointer: usize },
+}
+
+impl Rv32Csr {
+    pub const SSCRATCH: u32 = 0x140;
+    pub const MSCRATCH: u32 = 0x340;
+
+    pub fn from_csr(csr: u32) -> Option<Self> {
+        match csr {
+            0xc00..=0xc02 | 0xc80..=0xc82 => Some(Self::Counter),
+            Self::SSCRATCH => Some(Self::Scratch {
+                pointer: RV32_CSR_PTR_START,
+            }),
+            Self::MSCRATCH => Some(Self::Scratch {
+                pointer: RV32_CSR_PTR_START + RV32_REGISTER_NUM_LIMBS,
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// Decodes a Zicsr instruction on a supported CSR into its opcode, its CSR, its operands and
+/// whether the source is the immediate in the rs1 field.
+fn decode_zicsr(instruction_u32: u32) -> Option<(Rv32CsrOpcode, Rv32Csr, IType, bool)> {
+    let opcode = (instruction_u32 & 0x7f) as u8;
+    let funct3 = ((instruction_u32 >> 12) & 0b111) as u8;
+    if opcode != CSR_OPCODE {
+        return None;
+    }
+    let csr_opcode = match funct3 & !CSR_IMM_FUNCT3_BIT {
+        CSRRW_FUNCT3 => Rv32CsrOpcode::CSRRW,
+        CSRRS_FUNCT3 => Rv32CsrOpcode::CSRRS,
+        CSRRC_FUNCT3 => Rv32CsrOpcode::CSRRC,
+        _ => return None,
+    };
+    let csr = Rv32Csr::from_csr(instruction_u32 >> 20)?;
+    Some((
+        csr_opcode,
+        csr,
+        IType::new(instruction_u32),
+        funct3 & CSR_IMM_FUNCT3_BIT != 0,
+    ))
+}
+
 impl<F: PrimeField32> TranspilerExtension<F> for Rv32ITranspilerExtension {
     fn process_custom(&self, instruction_stream: &[u32]) -> Option<(Instruction<F>, usize)> {
         let mut transpiler = InstructionTranspiler::<F>(PhantomData);
@@ -45,6 +162,10 @@ impl<F: PrimeField32> TranspilerExtension<F> for Rv32ITranspilerExtension {
 
         let instruction = match (opcode, funct3) {
             (CSR_OPCODE, _) => {
+                if decode_zicsr(instruction_u32).is_some() {
+                    // Handled by Rv32ZicsrTranspilerExtension
+                    return None;
+                }
                 let dec_insn = IType::new(instruction_u32);
                 if dec_insn.funct3 as u8 == CSRRW_FUNCT3 {
                     // CSRRW
@@ -84,6 +205,24 @@ impl<F: PrimeField32> TranspilerExtension<F> for Rv32ITranspilerExtension {
                         F::from_canonical_usize(RV32_REGISTER_NUM_LIMBS * dec_insn.rs1),
                         0,
                     ),
+                    PhantomImm::HintStream => Instruction::phantom(
+                        PhantomDiscriminant(Rv32Phantom::HintStream as u16),
+                        F::from_canonical_usize(RV32_REGISTER_NUM_LIMBS * dec_insn.rd),
+                        F::from_canonical_usize(RV32_REGISTER_NUM_LIMBS * dec_insn.rs1),
+                        0,
+                    ),
+                    PhantomImm::DebugLog => Instruction::phantom(
+                        PhantomDiscriminant(SysPhantom::DebugLog as u16),
+                        F::from_canonical_usize(RV32_REGISTER_NUM_LIMBS * dec_insn.rd),
+                        F::from_canonical_usize(RV32_REGISTER_NUM_LIMBS * dec_insn.rs1),
+                        0,
+                    ),
+                    PhantomImm::HintPerfCounters => Instruction::phantom(
+                        PhantomDiscriminant(SysPhantom::HintPerfCounters as u16),
+                        F::ZERO,
+                        F::ZERO,
+                        0,
+                    ),
                 })
             }
             (RV32_ALU_OPCODE, _) => {
@@ -129,6 +268,69 @@ impl<F: PrimeField32> TranspilerExtension<F> for Rv32MTranspilerExtension {
     }
 }
 
+impl<F: PrimeField32> TranspilerExtension<F> for Rv32MFusedTranspilerExtension {
+    fn process_custom(&self, instruction_stream: &[u32]) -> Option<(Instruction<F>, usize)> {
+        if let [first, second, ..] = *instruction_stream {
+            if let Some(instruction) = fuse_mulh_mul(first, second) {
+                return Some((instruction, 1));
+            }
+        }
+        Rv32MTranspilerExtension.process_custom(instruction_stream)
+    }
+}
+
+/// Decodes an M extension multiplication into its `funct3` and operands.
+fn decode_rv32m_mul(instruction_u32: u32) -> Option<(u8, RType)> {
+    let dec_insn = RType::new(instruction_u32);
+    let funct3 = dec_insn.funct3 as u8;
+    ((instruction_u32 & 0x7f) as u8 == RV32_ALU_OPCODE
+        && dec_insn.funct7 as u8 == RV32M_FUNCT7
+        && funct3 <= MULHU_FUNCT3)
+        .then_some((funct3, dec_insn))
+}
+
+/// Fuses `first` and `second` if one is a `mul` and the other a `mulh[[s]u]` with the same
+/// source registers (in either order for the `mul`, which is commutative). The destination of
+/// `first` must not be one of the sources, since `second` reads them after it is written.
+fn fuse_mulh_mul<F: PrimeField32>(first: u32, second: u32) -> Option<Instruction<F>> {
+    let (first_funct3, first) = decode_rv32m_mul(first)?;
+    let (second_funct3, second) = decode_rv32m_mul(second)?;
+    let (mulh_funct3, mulh, mul) = match (first_funct3, second_funct3) {
+        (MUL_FUNCT3, MUL_FUNCT3) => return None,
+        (MUL_FUNCT3, funct3) => (funct3, &second, &first),
+        (funct3, MUL_FUNCT3) => (funct3, &first, &second),
+        _ => return None,
+    };
+    let same_sources =
+        (mul.rs1, mul.rs2) == (mulh.rs1, mulh.rs2) || (mul.rs1, mul.rs2) == (mulh.rs2, mulh.rs1);
+    if !same_sources
+        || first.rd == 0
+        || second.rd == 0
+        || first.rd == second.rd
+        || first.rd == mulh.rs1
+        || first.rd == mulh.rs2
+    {
+        return None;
+    }
+    let local_opcode = match mulh_funct3 {
+        MULH_FUNCT3 => MulHMulOpcode::MULH_MUL,
+        MULHSU_FUNCT3 => MulHMulOpcode::MULHSU_MUL,
+        MULHU_FUNCT3 => MulHMulOpcode::MULHU_MUL,
+        _ => unreachable!(),
+    };
+    Some(Instruction::from_usize(
+        VmOpcode::with_default_offset(local_opcode),
+        [
+            RV32_REGISTER_NUM_LIMBS * mul.rd,
+            RV32_REGISTER_NUM_LIMBS * mulh.rs1,
+            RV32_REGISTER_NUM_LIMBS * mulh.rs2,
+            RV32_REGISTER_AS as usize,
+            0,
+            RV32_REGISTER_NUM_LIMBS * mulh.rd,
+        ],
+    ))
+}
+
 impl<F: PrimeField32> TranspilerExtension<F> for Rv32IoTranspilerExtension {
     fn process_custom(&self, instruction_stream: &[u32]) -> Option<(Instruction<F>, usize)> {
         if instruction_stream.is_empty() {
@@ -139,19 +341,51 @@ impl<F: PrimeField32> TranspilerExtension<F> for Rv32IoTranspilerExtension {
         let opcode = (instruction_u32 & 0x7f) as u8;
         let funct3 = ((instruction_u32 >> 12) & 0b111) as u8; // All our instructions are R-, I- or B-type
 
+        if (opcode, funct3) == (MEM_OPCODE, MEM_FUNCT3) {
+            let dec_insn = RType::new(instruction_u32);
+            if dec_insn.funct7 as u8 == HINT_LOAD_PAGE_FUNCT7 {
+                // Same operands as HINT_STOREW, with the page pointer in rs1 and no immediate
+                return Some((
+                    Instruction::from_isize(
+                        VmOpcode::with_default_offset(Rv32HintStoreOpcode::HINT_LOAD_PAGE),
+                        0,
+                        (RV32_REGISTER_NUM_LIMBS * dec_insn.rs1) as isize,
+                        0,
+                        1,
+                        2,
+                    ),
+                    1,
+                ));
+            }
+            let local_opcode = match dec_insn.funct7 as u8 {
+                MEMCPY_FUNCT7 => Rv32MemcpyOpcode::MEMCPY,
+                MEMSET_FUNCT7 => Rv32MemcpyOpcode::MEMSET,
+                _ => return None,
+            };
+            return Some((
+                from_r_type(local_opcode.with_default_offset(), 2, &dec_insn),
+                1,
+            ));
+        }
         if opcode != SYSTEM_OPCODE {
             return None;
         }
-        if funct3 != HINT_STORE_W_FUNCT3 && funct3 != REVEAL_FUNCT3 {
+        if funct3 != HINT_STORE_W_FUNCT3 && funct3 != HINT_BUFFER_FUNCT3 && funct3 != REVEAL_FUNCT3
+        {
             return None;
         }
 
         let instruction = match funct3 {
-            HINT_STORE_W_FUNCT3 => {
+            HINT_STORE_W_FUNCT3 | HINT_BUFFER_FUNCT3 => {
                 let dec_insn = IType::new(instruction_u32);
                 let imm_u16 = (dec_insn.imm as u32) & 0xffff;
+                let opcode = if funct3 == HINT_STORE_W_FUNCT3 {
+                    Rv32HintStoreOpcode::HINT_STOREW
+                } else {
+                    Rv32HintStoreOpcode::HINT_BUFFER
+                };
                 Some(Instruction::from_isize(
-                    VmOpcode::with_default_offset(Rv32HintStoreOpcode::HINT_STOREW),
+                    VmOpcode::with_default_offset(opcode),
                     0,
                     (RV32_REGISTER_NUM_LIMBS * dec_insn.rd) as isize,
                     imm_u16 as isize,
@@ -178,3 +412,380 @@ impl<F: PrimeField32> TranspilerExtension<F> for Rv32IoTranspilerExtension {
         instruction.map(|instruction| (instruction, 1))
     }
 }
+
+impl<F: PrimeField32> TranspilerExtension<F> for Rv32ZicsrTranspilerExtension {
+    fn process_custom(&self, instruction_stream: &[u32]) -> Option<(Instruction<F>, usize)> {
+        if instruction_stream.is_empty() {
+            return None;
+        }
+        let (opcode, csr, dec_insn, is_imm) = decode_zicsr(instruction_stream[0])?;
+
+        let instruction = match csr {
+            Rv32Csr::Counter => {
+                // Only `csrrs`/`csrrc` with x0 or a zero immediate are reads, anything else is a
+                // write to a read-only CSR.
+                if opcode == Rv32CsrOpcode::CSRRW || dec_insn.rs1 != 0 {
+                    unimp()
+                } else if dec_insn.rd == 0 {
+                    nop()
+                } else {
+                    // ADD rd, x0, 0
+                    Instruction::from_isize(
+                        VmOpcode::with_default_offset(BaseAluOpcode::ADD),
+                        (RV32_REGISTER_NUM_LIMBS * dec_insn.rd) as isize,
+                        0,
+                        0,
+                        1,
+                        0,
+                    )
+                }
+            }
+            // OP rd, csr, rs1/zimm, 1, e, f where `f` disables the write to rd when it is x0
+            Rv32Csr::Scratch { pointer } => Instruction::from_usize(
+                VmOpcode::with_default_offset(opcode),
+                [
+                    RV32_REGISTER_NUM_LIMBS * dec_insn.rd,
+                    pointer,
+                    if is_imm {
+                        dec_insn.rs1
+                    } else {
+                        RV32_REGISTER_NUM_LIMBS * dec_insn.rs1
+                    },
+                    RV32_REGISTER_AS as usize,
+                    if is_imm {
+                        RV32_IMM_AS
+                    } else {
+                        RV32_REGISTER_AS
+                    } as usize,
+                    (dec_insn.rd != 0) as usize,
+                ],
+            ),
+        };
+
+        Some((instruction, 1))
+    }
+}
+
+impl<F: PrimeField32> TranspilerExtension<F> for Rv32ZbaTranspilerExtension {
+    fn process_custom(&self, instruction_stream: &[u32]) -> Option<(Instruction<F>, usize)> {
+        if instruction_stream.is_empty() {
+            return None;
+        }
+        let instruction_u32 = instruction_stream[0];
+
+        let opcode = (instruction_u32 & 0x7f) as u8;
+        let dec_insn = RType::new(instruction_u32);
+        if opcode != RV32_ALU_OPCODE || dec_insn.funct7 as u8 != ZBA_FUNCT7 {
+            return None;
+        }
+        let sh_add_opcode = match dec_insn.funct3 {
+            0b010 => Rv32ShAddOpcode::SH1ADD,
+            0b100 => Rv32ShAddOpcode::SH2ADD,
+            0b110 => Rv32ShAddOpcode::SH3ADD,
+            _ => return None,
+        };
+
+        Some((
+            from_r_type(sh_add_opcode.with_default_offset(), 1, &dec_insn),
+            1,
+        ))
+    }
+}
+
+impl<F: PrimeField32> TranspilerExtension<F> for Rv32ZbbTranspilerExtension {
+    fn process_custom(&self, instruction_stream: &[u32]) -> Option<(Instruction<F>, usize)> {
+        if instruction_stream.is_empty() {
+            return None;
+        }
+        let instruction_u32 = instruction_stream[0];
+
+        let opcode = (instruction_u32 & 0x7f) as u8;
+        let dec_insn = RType::new(instruction_u32);
+        let funct7 = dec_insn.funct7 as u8;
+
+        let instruction = match opcode {
+            RV32_ALU_OPCODE => {
+                let local_opcode = match (funct7, dec_insn.funct3) {
+                    (ZBB_INVERTED_FUNCT7, 0b111) => Rv32LogicNotOpcode::ANDN.with_default_offset(),
+                    (ZBB_INVERTED_FUNCT7, 0b110) => Rv32LogicNotOpcode::ORN.with_default_offset(),
+                    (ZBB_INVERTED_FUNCT7, 0b100) => Rv32LogicNotOpcode::XNOR.with_default_offset(),
+                    (ZBB_MIN_MAX_FUNCT7, 0b100) => Rv32MinMaxOpcode::MIN.with_default_offset(),
+                    (ZBB_MIN_MAX_FUNCT7, 0b101) => Rv32MinMaxOpcode::MINU.with_default_offset(),
+                    (ZBB_MIN_MAX_FUNCT7, 0b110) => Rv32MinMaxOpcode::MAX.with_default_offset(),
+                    (ZBB_MIN_MAX_FUNCT7, 0b111) => Rv32MinMaxOpcode::MAXU.with_default_offset(),
+                    (ZBB_ROTATE_FUNCT7, 0b001) => Rv32RotateOpcode::ROL.with_default_offset(),
+                    (ZBB_ROTATE_FUNCT7, 0b101) => Rv32RotateOpcode::ROR.with_default_offset(),
+                    (ZBB_ZEXT_H_FUNCT7, 0b100) if dec_insn.rs2 == 0 => {
+                        return Some((
+                            from_unary(Rv32ByteOpOpcode::ZEXT_H.with_default_offset(), &dec_insn),
+                            1,
+                        ));
+                    }
+                    _ => return None,
+                };
+                from_r_type(local_opcode, 1, &dec_insn)
+            }
+            RV32_ALU_IMM_OPCODE => {
+                // The instructions are I-type, with the operation in the immediate.
+                let imm = instruction_u32 >> 20;
+                let local_opcode = match (dec_insn.funct3, imm) {
+                    (0b001, 0x600) => Rv32BitCountOpcode::CLZ.with_default_offset(),
+                    (0b001, 0x601) => Rv32BitCountOpcode::CTZ.with_default_offset(),
+                    (0b001, 0x602) => Rv32BitCountOpcode::CPOP.with_default_offset(),
+                    (0b001, 0x604) => Rv32ByteOpOpcode::SEXT_B.with_default_offset(),
+                    (0b001, 0x605) => Rv32ByteOpOpcode::SEXT_H.with_default_offset(),
+                    (0b101, 0x698) => Rv32ByteOpOpcode::REV8.with_default_offset(),
+                    (0b101, 0x287) => Rv32ByteOpOpcode::ORC_B.with_default_offset(),
+                    (0b101, _) if funct7 == ZBB_ROTATE_FUNCT7 => {
+                        // rori
+                        return Some((
+                            from_i_type_shamt(
+                                Rv32RotateOpcode::ROR.with_default_offset(),
+                                &ITypeShamt::new(instruction_u32),
+                            ),
+                            1,
+                        ));
+                    }
+                    _ => return None,
+                };
+                from_unary(local_opcode, &dec_insn)
+            }
+            _ => return None,
+        };
+
+        Some((instruction, 1))
+    }
+}
+
+impl<F: PrimeField32> TranspilerExtension<F> for Rv32ATranspilerExtension {
+    fn process_custom(&self, instruction_stream: &[u32]) -> Option<(Instruction<F>, usize)> {
+        if instruction_stream.is_empty() {
+            return None;
+        }
+        let instruction_u32 = instruction_stream[0];
+
+        let opcode = (instruction_u32 & 0x7f) as u8;
+        let dec_insn = RType::new(instruction_u32);
+        if opcode != RV32_AMO_OPCODE || dec_insn.funct3 as u8 != AMO_W_FUNCT3 {
+            return None;
+        }
+        // funct7 is funct5 followed by the aq and rl bits
+        let atomic_opcode = match dec_insn.funct7 >> 2 {
+            0b00010 if dec_insn.rs2 == 0 => Rv32AtomicOpcode::LR_W,
+            0b00011 => Rv32AtomicOpcode::SC_W,
+            0b00001 => Rv32AtomicOpcode::AMOSWAP_W,
+            0b00000 => Rv32AtomicOpcode::AMOADD_W,
+            0b00100 => Rv32AtomicOpcode::AMOXOR_W,
+            0b01100 => Rv32AtomicOpcode::AMOAND_W,
+            0b01000 => Rv32AtomicOpcode::AMOOR_W,
+            0b10000 => Rv32AtomicOpcode::AMOMIN_W,
+            0b10100 => Rv32AtomicOpcode::AMOMAX_W,
+            0b11000 => Rv32AtomicOpcode::AMOMINU_W,
+            0b11100 => Rv32AtomicOpcode::AMOMAXU_W,
+            _ => return None,
+        };
+
+        // OP rd, rs1, rs2, 1, 2, f where `f` disables the write to rd when it is x0. The
+        // instruction is kept even then, as the memory write must still happen.
+        Some((
+            Instruction::from_usize(
+                VmOpcode::with_default_offset(atomic_opcode),
+                [
+                    RV32_REGISTER_NUM_LIMBS * dec_insn.rd,
+                    RV32_REGISTER_NUM_LIMBS * dec_insn.rs1,
+                    RV32_REGISTER_NUM_LIMBS * dec_insn.rs2,
+                    RV32_REGISTER_AS as usize,
+                    RV32_MEMORY_AS as usize,
+                    (dec_insn.rd != 0) as usize,
+                ],
+            ),
+            1,
+        ))
+    }
+}
+
+impl<F: PrimeField32> TranspilerExtension<F> for Rv32FTranspilerExtension {
+    fn process_custom(&self, instruction_stream: &[u32]) -> Option<(Instruction<F>, usize)> {
+        if instruction_stream.is_empty() {
+            return None;
+        }
+        let instruction_u32 = instruction_stream[0];
+
+        let opcode = (instruction_u32 & 0x7f) as u8;
+        let instruction = match opcode {
+            RV32_LOAD_FP_OPCODE => {
+                let dec_insn = IType::new(instruction_u32);
+                if dec_insn.funct3 as u8 != FP_W_FUNCT3 {
+                    return None;
+                }
+                // Unlike from_load, the destination is never x0.
+                Instruction::from_usize(
+                    VmOpcode::with_default_offset(Rv32LoadStoreOpcode::LOADW),
+                    [
+                        float_reg(dec_insn.rd),
+                        RV32_REGISTER_NUM_LIMBS * dec_insn.rs1,
+                        (dec_insn.imm as u32 & 0xffff) as usize,
+                        RV32_REGISTER_AS as usize,
+                        RV32_MEMORY_AS as usize,
+                    ],
+                )
+            }
+            RV32_STORE_FP_OPCODE => {
+                let dec_insn = SType::new(instruction_u32);
+                if dec_insn.funct3 as u8 != FP_W_FUNCT3 {
+                    return None;
+                }
+                let mut instruction =
+                    from_s_type(Rv32LoadStoreOpcode::STOREW.with_default_offset(), &dec_insn);
+                instruction.a = F::from_canonical_usize(float_reg(dec_insn.rs2));
+                instruction
+            }
+            RV32_OP_FP_OPCODE => {
+                let dec_insn = RType::new(instruction_u32);
+                let rm = dec_insn.funct3 as u8;
+                let is_rne = rm == FP_RM_RNE || rm == FP_RM_DYN;
+                let (rd, rs1, rs2) = (dec_insn.rd, dec_insn.rs1, dec_insn.rs2);
+                match (dec_insn.funct7, rm) {
+                    (0b0000000, _) if is_rne => from_float_op(
+                        Rv32FloatAddOpcode::FADD_S.with_default_offset(),
+                        float_reg(rd),
+                        float_reg(rs1),
+                        Some(float_reg(rs2)),
+                    ),
+                    (0b0000100, _) if is_rne => from_float_op(
+                        Rv32FloatAddOpcode::FSUB_S.with_default_offset(),
+                        float_reg(rd),
+                        float_reg(rs1),
+                        Some(float_reg(rs2)),
+                    ),
+                    (0b0001000, _) if is_rne => from_float_op(
+                        Rv32FloatMulOpcode::FMUL_S.with_default_offset(),
+                        float_reg(rd),
+                        float_reg(rs1),
+                        Some(float_reg(rs2)),
+                    ),
+                    (0b0010000, 0b000..=0b010) => from_float_op(
+                        [
+                            Rv32FloatMiscOpcode::FSGNJ_S,
+                            Rv32FloatMiscOpcode::FSGNJN_S,
+                            Rv32FloatMiscOpcode::FSGNJX_S,
+                        ][rm as usize]
+                            .with_default_offset(),
+                        float_reg(rd),
+                        float_reg(rs1),
+                        Some(float_reg(rs2)),
+                    ),
+                    (0b0010100, 0b000..=0b001) => from_float_op(
+                        [Rv32FloatCmpOpcode::FMIN_S, Rv32FloatCmpOpcode::FMAX_S][rm as usize]
+                            .with_default_offset(),
+                        float_reg(rd),
+                        float_reg(rs1),
+                        Some(float_reg(rs2)),
+                    ),
+                    (0b1010000, 0b000..=0b010) if rd != 0 => from_float_op(
+                        [
+                            Rv32FloatCmpOpcode::FLE_S,
+                            Rv32FloatCmpOpcode::FLT_S,
+                            Rv32FloatCmpOpcode::FEQ_S,
+                        ][rm as usize]
+                            .with_default_offset(),
+                        RV32_REGISTER_NUM_LIMBS * rd,
+                        float_reg(rs1),
+                        Some(float_reg(rs2)),
+                    ),
+                    (0b1100000, FP_RM_RTZ) if rs2 <= 1 && rd != 0 => from_float_op(
+                        [
+                            Rv32FloatToIntOpcode::FCVT_W_S,
+                            Rv32FloatToIntOpcode::FCVT_WU_S,
+                        ][rs2]
+                            .with_default_offset(),
+                        RV32_REGISTER_NUM_LIMBS * rd,
+                        float_reg(rs1),
+                        None,
+                    ),
+                    (0b1101000, _) if rs2 <= 1 && is_rne => from_float_op(
+                        [
+                            Rv32IntToFloatOpcode::FCVT_S_W,
+                            Rv32IntToFloatOpcode::FCVT_S_WU,
+                        ][rs2]
+                            .with_default_offset(),
+                        float_reg(rd),
+                        RV32_REGISTER_NUM_LIMBS * rs1,
+                        None,
+                    ),
+                    (0b1110000, 0b001) if rs2 == 0 && rd != 0 => from_float_op(
+                        Rv32FloatMiscOpcode::FCLASS_S.with_default_offset(),
+                        RV32_REGISTER_NUM_LIMBS * rd,
+                        float_reg(rs1),
+                        None,
+                    ),
+                    // fmv.x.w and fmv.w.x move the bits, as an addition of the immediate 0.
+                    (0b1110000, 0b000) if rs2 == 0 && rd != 0 => from_float_op(
+                        BaseAluOpcode::ADD.with_default_offset(),
+                        RV32_REGISTER_NUM_LIMBS * rd,
+                        float_reg(rs1),
+                        None,
+                    ),
+                    (0b1111000, 0b000) if rs2 == 0 => from_float_op(
+                        BaseAluOpcode::ADD.with_default_offset(),
+                        float_reg(rd),
+                        RV32_REGISTER_NUM_LIMBS * rs1,
+                        None,
+                    ),
+                    // Writes of comparisons, classifications, conversions and moves to x0.
+                    (0b1010000, 0b000..=0b010)
+                    | (0b1110000, 0b000..=0b001)
+                    | (0b1100000, FP_RM_RTZ)
+                        if rs2 <= 1 && rd == 0 =>
+                    {
+                        nop()
+                    }
+                    _ => return None,
+                }
+            }
+            _ => return None,
+        };
+
+        Some((instruction, 1))
+    }
+}
+
+/// Pointer of the float register `f{reg}`.
+fn float_reg(reg: usize) -> usize {
+    RV32_FLOAT_REG_PTR_START + RV32_REGISTER_NUM_LIMBS * reg
+}
+
+/// OP a, b, c, 1, e for the F instructions, with the register pointers `a` and `b` and either
+/// the register pointer `c` or the immediate 0.
+fn from_float_op<F: PrimeField32>(
+    opcode: usize,
+    a: usize,
+    b: usize,
+    c: Option<usize>,
+) -> Instruction<F> {
+    let (c, e) = match c {
+        Some(c) => (c, RV32_REGISTER_AS),
+        None => (0, RV32_IMM_AS),
+    };
+    Instruction::from_usize(
+        VmOpcode::from_usize(opcode),
+        [a, b, c, RV32_REGISTER_AS as usize, e as usize],
+    )
+}
+
+/// OP rd, rs1, 0, 1, 0 for the unary Zbb instructions, whose second operand is the immediate 0.
+fn from_unary<F: PrimeField32>(opcode: usize, dec_insn: &RType) -> Instruction<F> {
+    if dec_insn.rd == 0 {
+        return nop();
+    }
+    Instruction::from_usize(
+        VmOpcode::from_usize(opcode),
+        [
+            RV32_REGISTER_NUM_LIMBS * dec_insn.rd,
+            RV32_REGISTER_NUM_LIMBS * dec_insn.rs1,
+            0,
+            RV32_REGISTER_AS as usize,
+            RV32_IMM_AS as usize,
+        ],
+    )
+}
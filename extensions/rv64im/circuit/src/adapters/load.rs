@@ -0,0 +1,413 @@
+use std::{
+    array,
+    borrow::{Borrow, BorrowMut},
+    marker::PhantomData,
+    sync::Arc,
+};
+
+use atomic_refcell::AtomicRefCell;
+use openvm_circuit::{
+    arch::{
+        AdapterAirContext, AdapterRuntimeContext, ExecutionBridge, ExecutionBus, ExecutionState,
+        Result, VmAdapterAir, VmAdapterChip, VmAdapterInterface,
+    },
+    system::{
+        memory::{
+            offline_checker::{
+                MemoryBaseAuxCols, MemoryBridge, MemoryReadAuxCols, MemoryWriteAuxCols,
+            },
+            MemoryAddress, MemoryAuxColsFactory, MemoryController, MemoryControllerRef,
+            MemoryReadRecord, MemoryWriteRecord,
+        },
+        program::ProgramBus,
+    },
+};
+use openvm_circuit_primitives::{
+    bitwise_op_lookup::{BitwiseOperationLookupBus, BitwiseOperationLookupChip},
+    var_range::{VariableRangeCheckerBus, VariableRangeCheckerChip},
+};
+use openvm_circuit_primitives_derive::AlignedBorrow;
+use openvm_instructions::{
+    instruction::Instruction,
+    riscv::{RV32_IMM_AS, RV32_REGISTER_AS},
+};
+use openvm_rv32im_circuit::adapters::{
+    compose, Rv32LoadStoreAdapterAirInterface, Rv32LoadStoreAdapterRuntimeInterface,
+};
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    p3_air::{AirBuilder, BaseAir},
+    p3_field::{AbstractField, Field, PrimeField32},
+};
+
+use super::{
+    eval_sign_extend, sign_extend_word, RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS,
+    RV64_REGISTER_NUM_LIMBS,
+};
+
+/// Adapter for the byte, halfword and word loads of RV64.
+///
+/// This chip reads the low word of rs1 and gets an intermediate memory pointer address with
+/// rs1 + imm. It reads the aligned word at the intermediate pointer and writes the loaded word,
+/// sign extended to 64 bits, to rd. The core chip is responsible for the zero or sign extension
+/// of bytes and halfwords to a word, so that the unsigned loads always have a zero sign bit.
+///
+/// It uses the same interfaces as the RV32 load/store adapter, so it can be paired with the RV32
+/// load cores. Only loads are supported: stores of at most 4 bytes only touch the low word of
+/// rs2 and can use the RV32 load/store adapter directly.
+#[derive(Debug)]
+pub struct Rv64LoadAdapterChip<F: Field> {
+    pub air: Rv64LoadAdapterAir,
+    pub range_checker_chip: Arc<VariableRangeCheckerChip>,
+    pub bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<RV32_CELL_BITS>>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField32> Rv64LoadAdapterChip<F> {
+    pub fn new(
+        execution_bus: ExecutionBus,
+        program_bus: ProgramBus,
+        memory_controller: MemoryControllerRef<F>,
+        range_checker_chip: Arc<VariableRangeCheckerChip>,
+        bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<RV32_CELL_BITS>>,
+    ) -> Self {
+        let memory_controller = AtomicRefCell::borrow(&memory_controller);
+        let memory_bridge = memory_controller.memory_bridge();
+        Self {
+            air: Rv64LoadAdapterAir {
+                execution_bridge: ExecutionBridge::new(execution_bus, program_bus),
+                memory_bridge,
+                range_bus: range_checker_chip.bus(),
+                bitwise_lookup_bus: bitwise_lookup_chip.bus(),
+                pointer_max_bits: memory_controller.mem_config().pointer_max_bits,
+            },
+            range_checker_chip,
+            bitwise_lookup_chip,
+            _marker: PhantomData,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Rv64LoadReadRecord<F: Field> {
+    pub rs1_record: MemoryReadRecord<F, RV32_REGISTER_NUM_LIMBS>,
+    pub rs1_ptr: F,
+    /// Read of the aligned word from RISC-V memory
+    pub read: MemoryReadRecord<F, RV32_REGISTER_NUM_LIMBS>,
+
+    pub imm: F,
+    pub imm_sign: bool,
+    pub mem_ptr_limbs: [F; 2],
+    pub mem_as: F,
+}
+
+#[derive(Debug, Clone)]
+pub struct Rv64LoadWriteRecord<F: Field> {
+    pub from_state: ExecutionState<u32>,
+    pub rd: MemoryWriteRecord<F, RV64_REGISTER_NUM_LIMBS>,
+    pub rd_sign: bool,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, AlignedBorrow)]
+pub struct Rv64LoadAdapterCols<T> {
+    pub from_state: ExecutionState<T>,
+    pub rs1_ptr: T,
+    pub rs1_data: [T; RV32_REGISTER_NUM_LIMBS],
+    pub rs1_aux_cols: MemoryReadAuxCols<T, RV32_REGISTER_NUM_LIMBS>,
+
+    pub rd_ptr: T,
+    pub read_data_aux: MemoryReadAuxCols<T, RV32_REGISTER_NUM_LIMBS>,
+    pub imm: T,
+    pub imm_sign: T,
+    /// mem_ptr is the intermediate memory pointer limbs, needed to check the correct addition
+    pub mem_ptr_limbs: [T; 2],
+    pub mem_as: T,
+    /// Sign bit of the loaded word, which fills the upper half of rd
+    pub rd_sign: T,
+    /// The core chip provides the previous data of the low word of rd, the previous data of
+    /// the upper word is kept here to make a complete MemoryWriteAuxCols
+    pub rd_prev_data_hi: [T; RV64_REGISTER_NUM_LIMBS - RV32_REGISTER_NUM_LIMBS],
+    pub write_base_aux: MemoryBaseAuxCols<T>,
+}
+
+#[derive(Clone, Copy, Debug, derive_new::new)]
+pub struct Rv64LoadAdapterAir {
+    pub(super) memory_bridge: MemoryBridge,
+    pub(super) execution_bridge: ExecutionBridge,
+    pub range_bus: VariableRangeCheckerBus,
+    pub bitwise_lookup_bus: BitwiseOperationLookupBus,
+    pointer_max_bits: usize,
+}
+
+impl<F: Field> BaseAir<F> for Rv64LoadAdapterAir {
+    fn width(&self) -> usize {
+        Rv64LoadAdapterCols::<F>::width()
+    }
+}
+
+impl<AB: InteractionBuilder> VmAdapterAir<AB> for Rv64LoadAdapterAir {
+    type Interface = Rv32LoadStoreAdapterAirInterface<AB>;
+
+    fn eval(
+        &self,
+        builder: &mut AB,
+        local: &[AB::Var],
+        ctx: AdapterAirContext<AB::Expr, Self::Interface>,
+    ) {
+        let local_cols: &Rv64LoadAdapterCols<AB::Var> = local.borrow();
+
+        let timestamp: AB::Var = local_cols.from_state.timestamp;
+        let mut timestamp_delta: usize = 0;
+        let mut timestamp_pp = || {
+            timestamp_delta += 1;
+            timestamp + AB::Expr::from_canonical_usize(timestamp_delta - 1)
+        };
+
+        let is_valid = ctx.instruction.is_valid;
+        let load_shift_amount = ctx.instruction.load_shift_amount;
+        // only loads are handled by this adapter
+        builder.assert_eq(ctx.instruction.is_load, is_valid.clone());
+
+        // read the low word of rs1
+        self.memory_bridge
+            .read(
+                MemoryAddress::new(
+                    AB::F::from_canonical_u32(RV32_REGISTER_AS),
+                    local_cols.rs1_ptr,
+                ),
+                local_cols.rs1_data,
+                timestamp_pp(),
+                &local_cols.rs1_aux_cols,
+            )
+            .eval(builder, is_valid.clone());
+
+        // constrain mem_ptr = rs1 + imm as a u32 addition with 2 limbs
+        let limbs_01 = local_cols.rs1_data[0]
+            + local_cols.rs1_data[1] * AB::F::from_canonical_u32(1 << RV32_CELL_BITS);
+        let limbs_23 = local_cols.rs1_data[2]
+            + local_cols.rs1_data[3] * AB::F::from_canonical_u32(1 << RV32_CELL_BITS);
+
+        let inv = AB::F::from_canonical_u32(1 << (RV32_CELL_BITS * 2)).inverse();
+        let carry = (limbs_01 + local_cols.imm - local_cols.mem_ptr_limbs[0]) * inv;
+
+        builder.when(is_valid.clone()).assert_bool(carry.clone());
+
+        builder
+            .when(is_valid.clone())
+            .assert_bool(local_cols.imm_sign);
+        let imm_extend_limb =
+            local_cols.imm_sign * AB::F::from_canonical_u32((1 << (RV32_CELL_BITS * 2)) - 1);
+        let carry = (limbs_23 + imm_extend_limb + carry - local_cols.mem_ptr_limbs[1]) * inv;
+        builder.when(is_valid.clone()).assert_bool(carry.clone());
+
+        // preventing mem_ptr overflow
+        self.range_bus
+            .range_check(
+                // (limb[0] - shift_amount) / 4 < 2^14 => limb[0] - shift_amount < 2^16
+                (local_cols.mem_ptr_limbs[0] - load_shift_amount.clone())
+                    * AB::F::from_canonical_u32(4).inverse(),
+                RV32_CELL_BITS * 2 - 2,
+            )
+            .eval(builder, is_valid.clone());
+        self.range_bus
+            .range_check(
+                local_cols.mem_ptr_limbs[1],
+                self.pointer_max_bits - RV32_CELL_BITS * 2,
+            )
+            .eval(builder, is_valid.clone());
+
+        let mem_ptr = local_cols.mem_ptr_limbs[0]
+            + local_cols.mem_ptr_limbs[1] * AB::F::from_canonical_u32(1 << (RV32_CELL_BITS * 2));
+
+        self.memory_bridge
+            .read(
+                MemoryAddress::new(local_cols.mem_as, mem_ptr - load_shift_amount),
+                ctx.reads.1,
+                timestamp_pp(),
+                &local_cols.read_data_aux,
+            )
+            .eval(builder, is_valid.clone());
+
+        let rd_prev_data = array::from_fn(|i| {
+            if i < RV32_REGISTER_NUM_LIMBS {
+                ctx.reads.0[i]
+            } else {
+                local_cols.rd_prev_data_hi[i - RV32_REGISTER_NUM_LIMBS]
+            }
+        });
+        let write_aux_cols = MemoryWriteAuxCols::from_base(local_cols.write_base_aux, rd_prev_data);
+        let [write_data] = ctx.writes;
+        let rd_data = eval_sign_extend(
+            builder,
+            self.bitwise_lookup_bus,
+            write_data,
+            local_cols.rd_sign,
+            is_valid.clone(),
+        );
+
+        self.memory_bridge
+            .write(
+                MemoryAddress::new(
+                    AB::F::from_canonical_u32(RV32_REGISTER_AS),
+                    local_cols.rd_ptr,
+                ),
+                rd_data,
+                timestamp_pp(),
+                &write_aux_cols,
+            )
+            .eval(builder, is_valid.clone());
+
+        let to_pc = ctx
+            .to_pc
+            .unwrap_or(local_cols.from_state.pc + AB::F::from_canonical_u32(4));
+        self.execution_bridge
+            .execute(
+                ctx.instruction.opcode,
+                [
+                    local_cols.rd_ptr.into(),
+                    local_cols.rs1_ptr.into(),
+                    local_cols.imm.into(),
+                    AB::Expr::from_canonical_u32(RV32_REGISTER_AS),
+                    local_cols.mem_as.into(),
+                ],
+                local_cols.from_state,
+                ExecutionState {
+                    pc: to_pc,
+                    timestamp: timestamp + AB::F::from_canonical_usize(timestamp_delta),
+                },
+            )
+            .eval(builder, is_valid);
+    }
+
+    fn get_from_pc(&self, local: &[AB::Var]) -> AB::Var {
+        let local_cols: &Rv64LoadAdapterCols<AB::Var> = local.borrow();
+        local_cols.from_state.pc
+    }
+}
+
+impl<F: PrimeField32> VmAdapterChip<F> for Rv64LoadAdapterChip<F> {
+    type ReadRecord = Rv64LoadReadRecord<F>;
+    type WriteRecord = Rv64LoadWriteRecord<F>;
+    type Air = Rv64LoadAdapterAir;
+    type Interface = Rv32LoadStoreAdapterRuntimeInterface<F>;
+
+    #[allow(clippy::type_complexity)]
+    fn preprocess(
+        &mut self,
+        memory: &mut MemoryController<F>,
+        instruction: &Instruction<F>,
+    ) -> Result<(
+        <Self::Interface as VmAdapterInterface<F>>::Reads,
+        Self::ReadRecord,
+    )> {
+        let Instruction { a, b, c, d, e, .. } = *instruction;
+        debug_assert_eq!(d.as_canonical_u32(), RV32_REGISTER_AS);
+        debug_assert!(e.as_canonical_u32() != RV32_IMM_AS);
+        assert!(self.range_checker_chip.range_max_bits() >= 15);
+
+        let rs1_record = memory.read::<RV32_REGISTER_NUM_LIMBS>(d, b);
+
+        let rs1_val = compose(rs1_record.data);
+        let imm = c.as_canonical_u32();
+        let imm_sign = (imm & 0x8000) >> 15;
+        let imm_extended = imm + imm_sign * 0xffff0000;
+
+        let ptr_val = rs1_val.wrapping_add(imm_extended);
+        let shift_amount = ptr_val % 4;
+        assert!(
+            ptr_val < (1 << self.air.pointer_max_bits),
+            "ptr_val: {ptr_val} = rs1_val: {rs1_val} + imm_extended: {imm_extended} >= 2 ** {}",
+            self.air.pointer_max_bits
+        );
+
+        let mem_ptr_limbs = array::from_fn(|i| ((ptr_val >> (i * (RV32_CELL_BITS * 2))) & 0xffff));
+        self.range_checker_chip.add_count(
+            (mem_ptr_limbs[0] - shift_amount) / 4,
+            RV32_CELL_BITS * 2 - 2,
+        );
+        self.range_checker_chip.add_count(
+            mem_ptr_limbs[1],
+            self.air.pointer_max_bits - RV32_CELL_BITS * 2,
+        );
+
+        let ptr_val = ptr_val - shift_amount;
+        let read_record = memory.read::<RV32_REGISTER_NUM_LIMBS>(e, F::from_canonical_u32(ptr_val));
+
+        // The core chip keeps the previous data of the low word of rd
+        let prev_data =
+            array::from_fn(|i| memory.unsafe_read_cell(d, a + F::from_canonical_usize(i)));
+
+        Ok((
+            (
+                [prev_data, read_record.data],
+                F::from_canonical_u32(ptr_val + shift_amount),
+            ),
+            Self::ReadRecord {
+                rs1_record,
+                rs1_ptr: b,
+                read: read_record,
+                imm: c,
+                imm_sign: imm_sign == 1,
+                mem_ptr_limbs: mem_ptr_limbs.map(F::from_canonical_u32),
+                mem_as: e,
+            },
+        ))
+    }
+
+    fn postprocess(
+        &mut self,
+        memory: &mut MemoryController<F>,
+        instruction: &Instruction<F>,
+        from_state: ExecutionState<u32>,
+        output: AdapterRuntimeContext<F, Self::Interface>,
+        _read_record: &Self::ReadRecord,
+    ) -> Result<(ExecutionState<u32>, Self::WriteRecord)> {
+        let Instruction { a, d, .. } = *instruction;
+        let (rd_data, rd_sign) = sign_extend_word(&self.bitwise_lookup_chip, output.writes[0]);
+        let rd = memory.write(d, a, rd_data);
+
+        Ok((
+            ExecutionState {
+                pc: output.to_pc.unwrap_or(from_state.pc + 4),
+                timestamp: memory.timestamp(),
+            },
+            Self::WriteRecord {
+                from_state,
+                rd,
+                rd_sign,
+            },
+        ))
+    }
+
+    fn generate_trace_row(
+        &self,
+        row_slice: &mut [F],
+        read_record: Self::ReadRecord,
+        write_record: Self::WriteRecord,
+        aux_cols_factory: &MemoryAuxColsFactory<F>,
+    ) {
+        let adapter_cols: &mut Rv64LoadAdapterCols<_> = row_slice.borrow_mut();
+        adapter_cols.from_state = write_record.from_state.map(F::from_canonical_u32);
+        adapter_cols.rs1_data = read_record.rs1_record.data;
+        adapter_cols.rs1_aux_cols = aux_cols_factory.make_read_aux_cols(read_record.rs1_record);
+        adapter_cols.rs1_ptr = read_record.rs1_ptr;
+        adapter_cols.rd_ptr = write_record.rd.pointer;
+        adapter_cols.read_data_aux = aux_cols_factory.make_read_aux_cols(read_record.read);
+        adapter_cols.imm = read_record.imm;
+        adapter_cols.imm_sign = F::from_bool(read_record.imm_sign);
+        adapter_cols.mem_ptr_limbs = read_record.mem_ptr_limbs;
+        adapter_cols.mem_as = read_record.mem_as;
+        adapter_cols.rd_sign = F::from_bool(write_record.rd_sign);
+        adapter_cols
+            .rd_prev_data_hi
+            .copy_from_slice(&write_record.rd.prev_data[RV32_REGISTER_NUM_LIMBS..]);
+        adapter_cols.write_base_aux = aux_cols_factory
+            .make_write_aux_cols(write_record.rd)
+            .get_base();
+    }
+
+    fn air(&self) -> &Self::Air {
+        &self.air
+    }
+}
@@ -0,0 +1,82 @@
+use std::array;
+
+use openvm_circuit_primitives::bitwise_op_lookup::{
+    BitwiseOperationLookupBus, BitwiseOperationLookupChip,
+};
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    p3_air::AirBuilder,
+    p3_field::{AbstractField, PrimeField32},
+};
+
+mod alu;
+mod branch;
+mod doubleword;
+mod jalr;
+mod load;
+mod mul;
+mod rdwrite;
+mod word;
+
+pub use alu::*;
+pub use branch::*;
+pub use doubleword::*;
+pub use jalr::*;
+pub use load::*;
+pub use mul::*;
+pub use openvm_instructions::riscv::{
+    RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS, RV64_REGISTER_NUM_LIMBS,
+};
+pub use rdwrite::*;
+pub use word::*;
+
+/// Sign extends the 32-bit `word` to the limbs of a 64-bit register, and returns them with the
+/// sign bit. The range check constraining the sign bit in [eval_sign_extend] is requested from
+/// `bitwise_lookup_chip`.
+pub fn sign_extend_word<F: PrimeField32>(
+    bitwise_lookup_chip: &BitwiseOperationLookupChip<RV32_CELL_BITS>,
+    word: [F; RV32_REGISTER_NUM_LIMBS],
+) -> ([F; RV64_REGISTER_NUM_LIMBS], bool) {
+    let msl = word[RV32_REGISTER_NUM_LIMBS - 1].as_canonical_u32();
+    let sign = msl >> (RV32_CELL_BITS - 1);
+    bitwise_lookup_chip.request_range((msl - (sign << (RV32_CELL_BITS - 1))) << 1, 0);
+    let extension = F::from_canonical_u32(sign * ((1 << RV32_CELL_BITS) - 1));
+    let limbs = array::from_fn(|i| {
+        if i < RV32_REGISTER_NUM_LIMBS {
+            word[i]
+        } else {
+            extension
+        }
+    });
+    (limbs, sign == 1)
+}
+
+/// Returns the limbs of the 64-bit register holding the 32-bit `word` sign extended, where
+/// `sign` is constrained to be the sign bit of `word` whenever `count` is nonzero.
+///
+/// `word` must be range checked to bytes. The sign bit is checked by range checking
+/// `2 * (msl - 2^7 * sign)` to 8 bits, where `msl` is the most significant limb of `word`.
+pub fn eval_sign_extend<AB: InteractionBuilder>(
+    builder: &mut AB,
+    bitwise_lookup_bus: BitwiseOperationLookupBus,
+    word: [AB::Expr; RV32_REGISTER_NUM_LIMBS],
+    sign: AB::Var,
+    count: impl Into<AB::Expr>,
+) -> [AB::Expr; RV64_REGISTER_NUM_LIMBS] {
+    builder.assert_bool(sign);
+    let msl = word[RV32_REGISTER_NUM_LIMBS - 1].clone();
+    bitwise_lookup_bus
+        .send_range(
+            (msl - AB::Expr::from_canonical_u32(1 << (RV32_CELL_BITS - 1)) * sign) * AB::Expr::TWO,
+            AB::Expr::ZERO,
+        )
+        .eval(builder, count);
+    let extension = AB::Expr::from_canonical_u32((1 << RV32_CELL_BITS) - 1) * sign;
+    array::from_fn(|i| {
+        if i < RV32_REGISTER_NUM_LIMBS {
+            word[i].clone()
+        } else {
+            extension.clone()
+        }
+    })
+}
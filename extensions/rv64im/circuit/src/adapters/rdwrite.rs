@@ -0,0 +1,409 @@
+use std::{
+    borrow::{Borrow, BorrowMut},
+    marker::PhantomData,
+    sync::Arc,
+};
+
+use atomic_refcell::AtomicRefCell;
+use openvm_circuit::{
+    arch::{
+        AdapterAirContext, AdapterRuntimeContext, BasicAdapterInterface, ExecutionBridge,
+        ExecutionBus, ExecutionState, ImmInstruction, Result, VmAdapterAir, VmAdapterChip,
+        VmAdapterInterface,
+    },
+    system::{
+        memory::{
+            offline_checker::{MemoryBridge, MemoryWriteAuxCols},
+            MemoryAddress, MemoryAuxColsFactory, MemoryController, MemoryControllerRef,
+            MemoryWriteRecord,
+        },
+        program::ProgramBus,
+    },
+};
+use openvm_circuit_primitives::{
+    bitwise_op_lookup::{BitwiseOperationLookupBus, BitwiseOperationLookupChip},
+    utils::not,
+};
+use openvm_circuit_primitives_derive::AlignedBorrow;
+use openvm_instructions::{instruction::Instruction, riscv::RV32_REGISTER_AS};
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    p3_air::{AirBuilder, BaseAir},
+    p3_field::{AbstractField, Field, PrimeField32},
+};
+
+use super::{
+    eval_sign_extend, sign_extend_word, RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS,
+    RV64_REGISTER_NUM_LIMBS,
+};
+
+/// This adapter doesn't read anything, and writes the sign extension of a 32-bit result to
+/// [a:8]_d, where d == 1
+#[derive(Debug)]
+pub struct Rv64RdWriteAdapterChip<F: Field> {
+    pub air: Rv64RdWriteAdapterAir,
+    pub bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<RV32_CELL_BITS>>,
+    _marker: PhantomData<F>,
+}
+
+/// This adapter doesn't read anything, and **maybe** writes the sign extension of a 32-bit
+/// result to [a:8]_d, where d == 1
+#[derive(Debug)]
+pub struct Rv64CondRdWriteAdapterChip<F: Field> {
+    /// Do not use the inner air directly, use `air` instead.
+    inner: Rv64RdWriteAdapterChip<F>,
+    pub air: Rv64CondRdWriteAdapterAir,
+}
+
+impl<F: PrimeField32> Rv64RdWriteAdapterChip<F> {
+    pub fn new(
+        execution_bus: ExecutionBus,
+        program_bus: ProgramBus,
+        memory_controller: MemoryControllerRef<F>,
+        bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<RV32_CELL_BITS>>,
+    ) -> Self {
+        let memory_controller = AtomicRefCell::borrow(&memory_controller);
+        let memory_bridge = memory_controller.memory_bridge();
+        Self {
+            air: Rv64RdWriteAdapterAir {
+                execution_bridge: ExecutionBridge::new(execution_bus, program_bus),
+                memory_bridge,
+                bitwise_lookup_bus: bitwise_lookup_chip.bus(),
+            },
+            bitwise_lookup_chip,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F: PrimeField32> Rv64CondRdWriteAdapterChip<F> {
+    pub fn new(
+        execution_bus: ExecutionBus,
+        program_bus: ProgramBus,
+        memory_controller: MemoryControllerRef<F>,
+        bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<RV32_CELL_BITS>>,
+    ) -> Self {
+        let inner = Rv64RdWriteAdapterChip::new(
+            execution_bus,
+            program_bus,
+            memory_controller,
+            bitwise_lookup_chip,
+        );
+        let air = Rv64CondRdWriteAdapterAir { inner: inner.air };
+        Self { inner, air }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Rv64RdWriteWriteRecord<F: Field> {
+    pub from_state: ExecutionState<u32>,
+    pub rd: Option<MemoryWriteRecord<F, RV64_REGISTER_NUM_LIMBS>>,
+    pub rd_sign: bool,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, AlignedBorrow)]
+pub struct Rv64RdWriteAdapterCols<T> {
+    pub from_state: ExecutionState<T>,
+    pub rd_ptr: T,
+    /// Sign bit of the 32-bit result, which fills the upper half of rd
+    pub rd_sign: T,
+    pub rd_aux_cols: MemoryWriteAuxCols<T, RV64_REGISTER_NUM_LIMBS>,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, AlignedBorrow)]
+pub struct Rv64CondRdWriteAdapterCols<T> {
+    inner: Rv64RdWriteAdapterCols<T>,
+    pub needs_write: T,
+}
+
+#[derive(Clone, Copy, Debug, derive_new::new)]
+pub struct Rv64RdWriteAdapterAir {
+    pub(super) memory_bridge: MemoryBridge,
+    pub(super) execution_bridge: ExecutionBridge,
+    pub bitwise_lookup_bus: BitwiseOperationLookupBus,
+}
+
+#[derive(Clone, Copy, Debug, derive_new::new)]
+pub struct Rv64CondRdWriteAdapterAir {
+    inner: Rv64RdWriteAdapterAir,
+}
+
+impl<F: Field> BaseAir<F> for Rv64RdWriteAdapterAir {
+    fn width(&self) -> usize {
+        Rv64RdWriteAdapterCols::<F>::width()
+    }
+}
+
+impl<F: Field> BaseAir<F> for Rv64CondRdWriteAdapterAir {
+    fn width(&self) -> usize {
+        Rv64CondRdWriteAdapterCols::<F>::width()
+    }
+}
+
+impl Rv64RdWriteAdapterAir {
+    /// If `needs_write` is provided:
+    /// - Only writes if `needs_write`.
+    /// - Sets operand `f = needs_write` in the instruction.
+    /// - Does not put any other constraints on `needs_write`
+    ///
+    /// Otherwise:
+    /// - Writes if `ctx.instruction.is_valid`.
+    /// - Sets operand `f` to default value of `0` in the instruction.
+    #[allow(clippy::type_complexity)]
+    fn conditional_eval<AB: InteractionBuilder>(
+        &self,
+        builder: &mut AB,
+        local_cols: &Rv64RdWriteAdapterCols<AB::Var>,
+        ctx: AdapterAirContext<
+            AB::Expr,
+            BasicAdapterInterface<
+                AB::Expr,
+                ImmInstruction<AB::Expr>,
+                0,
+                1,
+                0,
+                RV32_REGISTER_NUM_LIMBS,
+            >,
+        >,
+        needs_write: Option<AB::Expr>,
+    ) {
+        let timestamp: AB::Var = local_cols.from_state.timestamp;
+        let timestamp_delta = 1;
+        let (write_count, f) = if let Some(needs_write) = needs_write {
+            (needs_write.clone(), needs_write)
+        } else {
+            (ctx.instruction.is_valid.clone(), AB::Expr::ZERO)
+        };
+        let rd_data = eval_sign_extend(
+            builder,
+            self.bitwise_lookup_bus,
+            ctx.writes[0].clone(),
+            local_cols.rd_sign,
+            write_count.clone(),
+        );
+        self.memory_bridge
+            .write(
+                MemoryAddress::new(
+                    AB::F::from_canonical_u32(RV32_REGISTER_AS),
+                    local_cols.rd_ptr,
+                ),
+                rd_data,
+                timestamp,
+                &local_cols.rd_aux_cols,
+            )
+            .eval(builder, write_count);
+
+        let to_pc = ctx
+            .to_pc
+            .unwrap_or(local_cols.from_state.pc + AB::F::from_canonical_u32(4));
+        // regardless of `needs_write`, must always execute instruction when `is_valid`.
+        self.execution_bridge
+            .execute(
+                ctx.instruction.opcode,
+                [
+                    local_cols.rd_ptr.into(),
+                    AB::Expr::ZERO,
+                    ctx.instruction.immediate,
+                    AB::Expr::from_canonical_u32(RV32_REGISTER_AS),
+                    AB::Expr::ZERO,
+                    f,
+                ],
+                local_cols.from_state,
+                ExecutionState {
+                    pc: to_pc,
+                    timestamp: timestamp + AB::F::from_canonical_usize(timestamp_delta),
+                },
+            )
+            .eval(builder, ctx.instruction.is_valid);
+    }
+}
+
+impl<AB: InteractionBuilder> VmAdapterAir<AB> for Rv64RdWriteAdapterAir {
+    type Interface =
+        BasicAdapterInterface<AB::Expr, ImmInstruction<AB::Expr>, 0, 1, 0, RV32_REGISTER_NUM_LIMBS>;
+
+    fn eval(
+        &self,
+        builder: &mut AB,
+        local: &[AB::Var],
+        ctx: AdapterAirContext<AB::Expr, Self::Interface>,
+    ) {
+        let local_cols: &Rv64RdWriteAdapterCols<AB::Var> = (*local).borrow();
+        self.conditional_eval(builder, local_cols, ctx, None);
+    }
+
+    fn get_from_pc(&self, local: &[AB::Var]) -> AB::Var {
+        let cols: &Rv64RdWriteAdapterCols<_> = local.borrow();
+        cols.from_state.pc
+    }
+}
+
+impl<AB: InteractionBuilder> VmAdapterAir<AB> for Rv64CondRdWriteAdapterAir {
+    type Interface =
+        BasicAdapterInterface<AB::Expr, ImmInstruction<AB::Expr>, 0, 1, 0, RV32_REGISTER_NUM_LIMBS>;
+
+    fn eval(
+        &self,
+        builder: &mut AB,
+        local: &[AB::Var],
+        ctx: AdapterAirContext<AB::Expr, Self::Interface>,
+    ) {
+        let local_cols: &Rv64CondRdWriteAdapterCols<AB::Var> = (*local).borrow();
+
+        builder.assert_bool(local_cols.needs_write);
+        builder
+            .when::<AB::Expr>(not(ctx.instruction.is_valid.clone()))
+            .assert_zero(local_cols.needs_write);
+
+        self.inner.conditional_eval(
+            builder,
+            &local_cols.inner,
+            ctx,
+            Some(local_cols.needs_write.into()),
+        );
+    }
+
+    fn get_from_pc(&self, local: &[AB::Var]) -> AB::Var {
+        let cols: &Rv64CondRdWriteAdapterCols<_> = local.borrow();
+        cols.inner.from_state.pc
+    }
+}
+
+impl<F: PrimeField32> VmAdapterChip<F> for Rv64RdWriteAdapterChip<F> {
+    type ReadRecord = ();
+    type WriteRecord = Rv64RdWriteWriteRecord<F>;
+    type Air = Rv64RdWriteAdapterAir;
+    type Interface = BasicAdapterInterface<F, ImmInstruction<F>, 0, 1, 0, RV32_REGISTER_NUM_LIMBS>;
+
+    fn preprocess(
+        &mut self,
+        _memory: &mut MemoryController<F>,
+        instruction: &Instruction<F>,
+    ) -> Result<(
+        <Self::Interface as VmAdapterInterface<F>>::Reads,
+        Self::ReadRecord,
+    )> {
+        let d = instruction.d;
+        debug_assert_eq!(d.as_canonical_u32(), RV32_REGISTER_AS);
+
+        Ok(([], ()))
+    }
+
+    fn postprocess(
+        &mut self,
+        memory: &mut MemoryController<F>,
+        instruction: &Instruction<F>,
+        from_state: ExecutionState<u32>,
+        output: AdapterRuntimeContext<F, Self::Interface>,
+        _read_record: &Self::ReadRecord,
+    ) -> Result<(ExecutionState<u32>, Self::WriteRecord)> {
+        let Instruction { a, d, .. } = *instruction;
+        let (rd_data, rd_sign) = sign_extend_word(&self.bitwise_lookup_chip, output.writes[0]);
+        let rd = memory.write(d, a, rd_data);
+
+        Ok((
+            ExecutionState {
+                pc: output.to_pc.unwrap_or(from_state.pc + 4),
+                timestamp: memory.timestamp(),
+            },
+            Self::WriteRecord {
+                from_state,
+                rd: Some(rd),
+                rd_sign,
+            },
+        ))
+    }
+
+    fn generate_trace_row(
+        &self,
+        row_slice: &mut [F],
+        _read_record: Self::ReadRecord,
+        write_record: Self::WriteRecord,
+        aux_cols_factory: &MemoryAuxColsFactory<F>,
+    ) {
+        let adapter_cols: &mut Rv64RdWriteAdapterCols<F> = row_slice.borrow_mut();
+        adapter_cols.from_state = write_record.from_state.map(F::from_canonical_u32);
+        let rd = write_record.rd.unwrap();
+        adapter_cols.rd_ptr = rd.pointer;
+        adapter_cols.rd_sign = F::from_bool(write_record.rd_sign);
+        adapter_cols.rd_aux_cols = aux_cols_factory.make_write_aux_cols(rd);
+    }
+
+    fn air(&self) -> &Self::Air {
+        &self.air
+    }
+}
+
+impl<F: PrimeField32> VmAdapterChip<F> for Rv64CondRdWriteAdapterChip<F> {
+    type ReadRecord = ();
+    type WriteRecord = Rv64RdWriteWriteRecord<F>;
+    type Air = Rv64CondRdWriteAdapterAir;
+    type Interface = BasicAdapterInterface<F, ImmInstruction<F>, 0, 1, 0, RV32_REGISTER_NUM_LIMBS>;
+
+    fn preprocess(
+        &mut self,
+        memory: &mut MemoryController<F>,
+        instruction: &Instruction<F>,
+    ) -> Result<(
+        <Self::Interface as VmAdapterInterface<F>>::Reads,
+        Self::ReadRecord,
+    )> {
+        self.inner.preprocess(memory, instruction)
+    }
+
+    fn postprocess(
+        &mut self,
+        memory: &mut MemoryController<F>,
+        instruction: &Instruction<F>,
+        from_state: ExecutionState<u32>,
+        output: AdapterRuntimeContext<F, Self::Interface>,
+        _read_record: &Self::ReadRecord,
+    ) -> Result<(ExecutionState<u32>, Self::WriteRecord)> {
+        let Instruction { a, d, .. } = *instruction;
+        let (rd, rd_sign) = if instruction.f != F::ZERO {
+            let (rd_data, rd_sign) =
+                sign_extend_word(&self.inner.bitwise_lookup_chip, output.writes[0]);
+            (Some(memory.write(d, a, rd_data)), rd_sign)
+        } else {
+            memory.increment_timestamp();
+            (None, false)
+        };
+
+        Ok((
+            ExecutionState {
+                pc: output.to_pc.unwrap_or(from_state.pc + 4),
+                timestamp: memory.timestamp(),
+            },
+            Self::WriteRecord {
+                from_state,
+                rd,
+                rd_sign,
+            },
+        ))
+    }
+
+    fn generate_trace_row(
+        &self,
+        row_slice: &mut [F],
+        _read_record: Self::ReadRecord,
+        write_record: Self::WriteRecord,
+        aux_cols_factory: &MemoryAuxColsFactory<F>,
+    ) {
+        let adapter_cols: &mut Rv64CondRdWriteAdapterCols<F> = row_slice.borrow_mut();
+        adapter_cols.inner.from_state = write_record.from_state.map(F::from_canonical_u32);
+        if let Some(rd) = write_record.rd {
+            adapter_cols.inner.rd_ptr = rd.pointer;
+            adapter_cols.inner.rd_sign = F::from_bool(write_record.rd_sign);
+            adapter_cols.inner.rd_aux_cols = aux_cols_factory.make_write_aux_cols(rd);
+            adapter_cols.needs_write = F::ONE;
+        } else {
+            adapter_cols.needs_write = F::ZERO;
+        }
+    }
+
+    fn air(&self) -> &Self::Air {
+        &self.air
+    }
+}
@@ -0,0 +1,229 @@
+use std::borrow::{Borrow, BorrowMut};
+
+use openvm_circuit::arch::{
+    AdapterAirContext, AdapterRuntimeContext, ExecutionError, Result, VmAdapterInterface,
+    VmCoreAir, VmCoreChip,
+};
+use openvm_circuit_primitives_derive::AlignedBorrow;
+use openvm_instructions::{instruction::Instruction, UsizeOpcode};
+use openvm_rv32im_circuit::adapters::LoadStoreInstruction;
+use openvm_rv64im_transpiler::Rv64DoublewordOpcode::{self, *};
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    p3_air::{AirBuilder, BaseAir},
+    p3_field::{AbstractField, Field, PrimeField32},
+    rap::BaseAirWithPublicValues,
+};
+
+use crate::adapters::{RV32_REGISTER_NUM_LIMBS, RV64_REGISTER_NUM_LIMBS};
+
+/// Doubleword Core Chip handles `ld`, `sd` and `lwu`.
+/// Doublewords are always moved unchanged, while `lwu` takes the low or high word of the 8 byte
+/// aligned doubleword read by the adapter, depending on the shift, and zero extends it.
+#[repr(C)]
+#[derive(Debug, Clone, AlignedBorrow)]
+pub struct Rv64DoublewordCoreCols<T> {
+    pub is_loadd: T,
+    /// `lwu` of the low word of the aligned doubleword
+    pub is_loadwu0: T,
+    /// `lwu` of the high word of the aligned doubleword
+    pub is_loadwu4: T,
+    pub is_stored: T,
+
+    pub read_data: [T; RV64_REGISTER_NUM_LIMBS],
+    pub prev_data: [T; RV64_REGISTER_NUM_LIMBS],
+    pub write_data: [T; RV64_REGISTER_NUM_LIMBS],
+}
+
+#[derive(Debug, Clone)]
+pub struct Rv64DoublewordCoreRecord<F> {
+    pub opcode: Rv64DoublewordOpcode,
+    pub shift: u32,
+    pub read_data: [F; RV64_REGISTER_NUM_LIMBS],
+    pub prev_data: [F; RV64_REGISTER_NUM_LIMBS],
+    pub write_data: [F; RV64_REGISTER_NUM_LIMBS],
+}
+
+#[derive(Debug, Clone)]
+pub struct Rv64DoublewordCoreAir {
+    pub offset: usize,
+}
+
+impl<F: Field> BaseAir<F> for Rv64DoublewordCoreAir {
+    fn width(&self) -> usize {
+        Rv64DoublewordCoreCols::<F>::width()
+    }
+}
+
+impl<F: Field> BaseAirWithPublicValues<F> for Rv64DoublewordCoreAir {}
+
+impl<AB, I> VmCoreAir<AB, I> for Rv64DoublewordCoreAir
+where
+    AB: InteractionBuilder,
+    I: VmAdapterInterface<AB::Expr>,
+    I::Reads: From<(
+        [AB::Var; RV64_REGISTER_NUM_LIMBS],
+        [AB::Expr; RV64_REGISTER_NUM_LIMBS],
+    )>,
+    I::Writes: From<[[AB::Expr; RV64_REGISTER_NUM_LIMBS]; 1]>,
+    I::ProcessedInstruction: From<LoadStoreInstruction<AB::Expr>>,
+{
+    fn eval(
+        &self,
+        builder: &mut AB,
+        local_core: &[AB::Var],
+        _from_pc: AB::Var,
+    ) -> AdapterAirContext<AB::Expr, I> {
+        let cols: &Rv64DoublewordCoreCols<AB::Var> = (*local_core).borrow();
+        let flags = [
+            cols.is_loadd,
+            cols.is_loadwu0,
+            cols.is_loadwu4,
+            cols.is_stored,
+        ];
+
+        let is_valid = flags.iter().fold(AB::Expr::ZERO, |acc, &flag| {
+            builder.assert_bool(flag);
+            acc + flag.into()
+        });
+        builder.assert_bool(is_valid.clone());
+
+        for (i, cell) in cols.write_data.iter().enumerate() {
+            let expected_val = if i < RV32_REGISTER_NUM_LIMBS {
+                (cols.is_loadd + cols.is_stored + cols.is_loadwu0) * cols.read_data[i]
+                    + cols.is_loadwu4 * cols.read_data[i + RV32_REGISTER_NUM_LIMBS]
+            } else {
+                (cols.is_loadd + cols.is_stored) * cols.read_data[i]
+            };
+            builder.assert_eq(*cell, expected_val);
+        }
+
+        let expected_opcode = cols.is_loadd * AB::Expr::from_canonical_u8(LOADD as u8)
+            + (cols.is_loadwu0 + cols.is_loadwu4) * AB::Expr::from_canonical_u8(LOADWU as u8)
+            + cols.is_stored * AB::Expr::from_canonical_u8(STORED as u8)
+            + AB::Expr::from_canonical_usize(self.offset);
+
+        AdapterAirContext {
+            to_pc: None,
+            reads: (cols.prev_data, cols.read_data.map(|x| x.into())).into(),
+            writes: [cols.write_data.map(|x| x.into())].into(),
+            instruction: LoadStoreInstruction {
+                is_valid,
+                opcode: expected_opcode,
+                is_load: cols.is_loadd + cols.is_loadwu0 + cols.is_loadwu4,
+                load_shift_amount: cols.is_loadwu4
+                    * AB::Expr::from_canonical_usize(RV32_REGISTER_NUM_LIMBS),
+                store_shift_amount: AB::Expr::ZERO,
+            }
+            .into(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Rv64DoublewordCoreChip {
+    pub air: Rv64DoublewordCoreAir,
+}
+
+impl Rv64DoublewordCoreChip {
+    pub fn new(offset: usize) -> Self {
+        Self {
+            air: Rv64DoublewordCoreAir { offset },
+        }
+    }
+}
+
+impl<F: PrimeField32, I: VmAdapterInterface<F>> VmCoreChip<F, I> for Rv64DoublewordCoreChip
+where
+    I::Reads: Into<([[F; RV64_REGISTER_NUM_LIMBS]; 2], F)>,
+    I::Writes: From<[[F; RV64_REGISTER_NUM_LIMBS]; 1]>,
+{
+    type Record = Rv64DoublewordCoreRecord<F>;
+    type Air = Rv64DoublewordCoreAir;
+
+    #[allow(clippy::type_complexity)]
+    fn execute_instruction(
+        &self,
+        instruction: &Instruction<F>,
+        from_pc: u32,
+        reads: I::Reads,
+    ) -> Result<(AdapterRuntimeContext<F, I>, Self::Record)> {
+        let local_opcode =
+            Rv64DoublewordOpcode::from_usize(instruction.opcode.local_opcode_idx(self.air.offset));
+
+        let (reads, ptr) = reads.into();
+        let ptr = ptr.as_canonical_u32();
+        check_alignment(local_opcode, ptr, from_pc)?;
+        let shift = ptr % RV64_REGISTER_NUM_LIMBS as u32;
+        let prev_data = reads[0];
+        let read_data = reads[1];
+        let write_data = run_write_data(local_opcode, read_data, shift);
+        let output = AdapterRuntimeContext::without_pc([write_data]);
+
+        Ok((
+            output,
+            Rv64DoublewordCoreRecord {
+                opcode: local_opcode,
+                shift,
+                prev_data,
+                read_data,
+                write_data,
+            },
+        ))
+    }
+
+    fn get_opcode_name(&self, opcode: usize) -> String {
+        format!(
+            "{:?}",
+            Rv64DoublewordOpcode::from_usize(opcode - self.air.offset)
+        )
+    }
+
+    fn generate_trace_row(&self, row_slice: &mut [F], record: Self::Record) {
+        let core_cols: &mut Rv64DoublewordCoreCols<F> = row_slice.borrow_mut();
+        core_cols.is_loadd = F::from_bool(record.opcode == LOADD);
+        core_cols.is_loadwu0 = F::from_bool(record.opcode == LOADWU && record.shift == 0);
+        core_cols.is_loadwu4 = F::from_bool(record.opcode == LOADWU && record.shift != 0);
+        core_cols.is_stored = F::from_bool(record.opcode == STORED);
+        core_cols.read_data = record.read_data;
+        core_cols.prev_data = record.prev_data;
+        core_cols.write_data = record.write_data;
+    }
+
+    fn air(&self) -> &Self::Air {
+        &self.air
+    }
+}
+
+/// Returns an error if the memory access of `opcode` at `address` is not aligned to its width.
+pub(crate) fn check_alignment(opcode: Rv64DoublewordOpcode, address: u32, pc: u32) -> Result<()> {
+    let alignment = match opcode {
+        LOADD | STORED => 8,
+        LOADWU => 4,
+    };
+    if address % alignment != 0 {
+        return Err(ExecutionError::MisalignedMemoryAccess {
+            pc,
+            address,
+            alignment,
+        });
+    }
+    Ok(())
+}
+
+pub(super) fn run_write_data<F: PrimeField32>(
+    opcode: Rv64DoublewordOpcode,
+    read_data: [F; RV64_REGISTER_NUM_LIMBS],
+    shift: u32,
+) -> [F; RV64_REGISTER_NUM_LIMBS] {
+    match opcode {
+        LOADD | STORED => read_data,
+        LOADWU => {
+            let shift = shift as usize;
+            let mut write_data = [F::ZERO; RV64_REGISTER_NUM_LIMBS];
+            write_data[..RV32_REGISTER_NUM_LIMBS]
+                .copy_from_slice(&read_data[shift..shift + RV32_REGISTER_NUM_LIMBS]);
+            write_data
+        }
+    }
+}
@@ -0,0 +1,13 @@
+mod core;
+
+pub use core::*;
+
+use openvm_circuit::arch::VmChipWrapper;
+
+use super::adapters::Rv64DoublewordAdapterChip;
+
+#[cfg(test)]
+mod tests;
+
+pub type Rv64DoublewordChip<F> =
+    VmChipWrapper<F, Rv64DoublewordAdapterChip<F>, Rv64DoublewordCoreChip>;
@@ -0,0 +1,169 @@
+use std::array;
+
+use openvm_circuit::{
+    arch::{
+        testing::{memory::gen_pointer, VmChipTestBuilder},
+        ExecutionError, ExecutionState, InstructionExecutor,
+    },
+    utils::{u32_into_limbs, u32_sign_extend},
+};
+use openvm_instructions::{instruction::Instruction, UsizeOpcode, VmOpcode};
+use openvm_rv32im_circuit::adapters::compose;
+use openvm_rv64im_transpiler::Rv64DoublewordOpcode::{self, *};
+use openvm_stark_backend::p3_field::AbstractField;
+use openvm_stark_sdk::{config::setup_tracing, p3_baby_bear::BabyBear, utils::create_seeded_rng};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng};
+
+use super::{run_write_data, Rv64DoublewordChip, Rv64DoublewordCoreChip};
+use crate::adapters::{
+    Rv64DoublewordAdapterChip, RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS, RV64_REGISTER_NUM_LIMBS,
+};
+
+const IMM_BITS: usize = 16;
+
+type F = BabyBear;
+
+fn create_chip(tester: &mut VmChipTestBuilder<F>) -> Rv64DoublewordChip<F> {
+    let range_checker_chip = tester.memory_controller().borrow().range_checker.clone();
+    let adapter = Rv64DoublewordAdapterChip::<F>::new(
+        tester.execution_bus(),
+        tester.program_bus(),
+        tester.memory_controller(),
+        range_checker_chip,
+        Rv64DoublewordOpcode::default_offset(),
+    );
+    let core = Rv64DoublewordCoreChip::new(Rv64DoublewordOpcode::default_offset());
+    Rv64DoublewordChip::<F>::new(adapter, core, tester.memory_controller())
+}
+
+fn set_and_execute(
+    tester: &mut VmChipTestBuilder<F>,
+    chip: &mut Rv64DoublewordChip<F>,
+    rng: &mut StdRng,
+    opcode: Rv64DoublewordOpcode,
+) {
+    let imm = rng.gen_range(0..(1 << IMM_BITS));
+    let imm_ext = u32_sign_extend::<IMM_BITS>(imm);
+
+    let alignment = match opcode {
+        LOADD | STORED => 3,
+        LOADWU => 2,
+    };
+    let ptr_val = rng.gen_range(
+        0..(1
+            << (tester
+                .memory_controller()
+                .borrow()
+                .mem_config()
+                .pointer_max_bits
+                - alignment)),
+    ) << alignment;
+
+    let rs1 = u32_into_limbs::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(
+        (ptr_val as u32).wrapping_sub(imm_ext),
+    )
+    .map(F::from_canonical_u32);
+    let a = gen_pointer(rng, RV64_REGISTER_NUM_LIMBS);
+    let b = gen_pointer(rng, RV64_REGISTER_NUM_LIMBS);
+    let mem_as = *[2, 3].choose(rng).unwrap();
+
+    let ptr_val = imm_ext.wrapping_add(compose(rs1));
+    let shift_amount = ptr_val % 8;
+    tester.write(1, b, rs1);
+
+    let is_load = opcode != STORED;
+    let some_prev_data: [F; RV64_REGISTER_NUM_LIMBS] =
+        array::from_fn(|_| F::from_canonical_u32(rng.gen_range(0..(1 << RV32_CELL_BITS))));
+    let read_data: [F; RV64_REGISTER_NUM_LIMBS] =
+        array::from_fn(|_| F::from_canonical_u32(rng.gen_range(0..(1 << RV32_CELL_BITS))));
+
+    if is_load {
+        tester.write(1, a, some_prev_data);
+        tester.write(mem_as, (ptr_val - shift_amount) as usize, read_data);
+    } else {
+        tester.write(mem_as, (ptr_val - shift_amount) as usize, some_prev_data);
+        tester.write(1, a, read_data);
+    }
+
+    tester.execute(
+        chip,
+        Instruction::from_usize(
+            VmOpcode::with_default_offset(opcode),
+            [a, b, imm as usize, 1, mem_as],
+        ),
+    );
+
+    let write_data = run_write_data(opcode, read_data, shift_amount);
+    if is_load {
+        assert_eq!(write_data, tester.read::<8>(1, a));
+    } else {
+        assert_eq!(
+            write_data,
+            tester.read::<8>(mem_as, (ptr_val - shift_amount) as usize)
+        );
+    }
+}
+
+#[test]
+fn rand_doubleword_test() {
+    setup_tracing();
+    let mut rng = create_seeded_rng();
+    let mut tester = VmChipTestBuilder::default();
+    let mut chip = create_chip(&mut tester);
+
+    let num_tests: usize = 100;
+    for _ in 0..num_tests {
+        set_and_execute(&mut tester, &mut chip, &mut rng, LOADD);
+        set_and_execute(&mut tester, &mut chip, &mut rng, LOADWU);
+        set_and_execute(&mut tester, &mut chip, &mut rng, STORED);
+    }
+
+    let tester = tester.build().load(chip).finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
+#[test]
+fn run_loadwu_sanity_test() {
+    let read_data = [138, 45, 202, 76, 131, 74, 186, 29].map(F::from_canonical_u32);
+    let low = run_write_data(LOADWU, read_data, 0);
+    let high = run_write_data(LOADWU, read_data, 4);
+    assert_eq!(
+        low,
+        [138, 45, 202, 76, 0, 0, 0, 0].map(F::from_canonical_u32)
+    );
+    assert_eq!(
+        high,
+        [131, 74, 186, 29, 0, 0, 0, 0].map(F::from_canonical_u32)
+    );
+    assert_eq!(run_write_data(LOADD, read_data, 0), read_data);
+}
+
+#[test]
+fn misaligned_doubleword_test() {
+    let mut tester = VmChipTestBuilder::default();
+    let mut chip = create_chip(&mut tester);
+
+    // rs1 = 0x1004 and imm = 0, so the address is only 4 byte aligned
+    tester.write(
+        1,
+        8,
+        u32_into_limbs::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(0x1004)
+            .map(F::from_canonical_u32),
+    );
+    let from_state = ExecutionState {
+        pc: 8,
+        timestamp: tester.memory_controller().borrow().timestamp(),
+    };
+    let result = chip.execute(
+        Instruction::from_usize(VmOpcode::with_default_offset(LOADD), [16, 8, 0, 1, 2]),
+        from_state,
+    );
+    assert!(matches!(
+        result,
+        Err(ExecutionError::MisalignedMemoryAccess {
+            pc: 8,
+            address: 0x1004,
+            alignment: 8,
+        })
+    ));
+}
@@ -0,0 +1,534 @@
+use std::sync::Arc;
+
+use derive_more::derive::From;
+use openvm_circuit::{
+    arch::{
+        SystemConfig, SystemExecutor, SystemPeriphery, SystemPort, VmChipComplex, VmConfig,
+        VmExtension, VmInventory, VmInventoryBuilder, VmInventoryError,
+    },
+    system::phantom::PhantomChip,
+};
+use openvm_circuit_derive::{AnyEnum, InstructionExecutor, VmConfig};
+use openvm_circuit_primitives::{
+    bitwise_op_lookup::{BitwiseOperationLookupBus, BitwiseOperationLookupChip},
+    range_tuple::{RangeTupleCheckerBus, RangeTupleCheckerChip},
+};
+use openvm_circuit_primitives_derive::{Chip, ChipUsageGetter};
+use openvm_instructions::{program::DEFAULT_PC_STEP, PhantomDiscriminant, UsizeOpcode, VmOpcode};
+use openvm_rv32im_circuit::{
+    adapters::Rv32LoadStoreAdapterChip, phantom, BaseAluCoreChip, BranchEqualCoreChip,
+    BranchLessThanCoreChip, DivRemCoreChip, LessThanCoreChip, LoadSignExtendCoreChip,
+    LoadStoreCoreChip, MulHCoreChip, MultiplicationCoreChip, Rv32AuipcCoreChip, Rv32Io,
+    Rv32IoExecutor, Rv32IoPeriphery, Rv32JalLuiCoreChip, Rv32JalrCoreChip, ShiftCoreChip,
+};
+use openvm_rv32im_transpiler::{Rv32LoadStoreOpcode, Rv32Phantom};
+use openvm_rv64im_transpiler::{
+    Rv64AuipcOpcode, Rv64BaseAluOpcode, Rv64BaseAluWOpcode, Rv64BranchEqualOpcode,
+    Rv64BranchLessThanOpcode, Rv64DivRemOpcode, Rv64DivRemWOpcode, Rv64DoublewordOpcode,
+    Rv64JalLuiOpcode, Rv64JalrOpcode, Rv64LessThanOpcode, Rv64LoadStoreOpcode, Rv64MulHOpcode,
+    Rv64MulOpcode, Rv64MulWOpcode, Rv64ShiftOpcode, Rv64ShiftWOpcode,
+};
+use openvm_stark_backend::p3_field::PrimeField32;
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+
+use crate::{adapters::*, *};
+
+/// Config for a VM with the RV64I base extension, the RV64M multiplication extension and the IO
+/// extension
+#[derive(Clone, Debug, VmConfig, derive_new::new, Serialize, Deserialize)]
+pub struct Rv64ImConfig {
+    #[system]
+    pub system: SystemConfig,
+    #[extension]
+    pub base: Rv64I,
+    #[extension]
+    pub mul: Rv64M,
+    #[extension]
+    pub io: Rv32Io,
+}
+
+impl Default for Rv64ImConfig {
+    fn default() -> Self {
+        let system = SystemConfig::default().with_continuations();
+        Self {
+            system,
+            base: Default::default(),
+            mul: Default::default(),
+            io: Default::default(),
+        }
+    }
+}
+
+impl Rv64ImConfig {
+    pub fn with_public_values(public_values: usize) -> Self {
+        let system = SystemConfig::default()
+            .with_continuations()
+            .with_public_values(public_values);
+        Self {
+            system,
+            base: Default::default(),
+            mul: Default::default(),
+            io: Default::default(),
+        }
+    }
+}
+
+// ============ Extension Implementations ============
+
+/// RISC-V 64-bit Base (RV64I) Extension
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub struct Rv64I;
+
+/// RISC-V 64-bit Multiplication Extension (RV64M) Extension
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct Rv64M {
+    #[serde(default = "default_range_tuple_checker_sizes")]
+    pub range_tuple_checker_sizes: [u32; 2],
+}
+
+impl Default for Rv64M {
+    fn default() -> Self {
+        Self {
+            range_tuple_checker_sizes: default_range_tuple_checker_sizes(),
+        }
+    }
+}
+
+fn default_range_tuple_checker_sizes() -> [u32; 2] {
+    [1 << 8, 2 * RV64_REGISTER_NUM_LIMBS as u32 * (1 << 8)]
+}
+
+// ============ Executor and Periphery Enums for Extension ============
+
+/// RISC-V 64-bit Base (RV64I) Instruction Executors
+#[derive(ChipUsageGetter, Chip, InstructionExecutor, From, AnyEnum)]
+pub enum Rv64IExecutor<F: PrimeField32> {
+    BaseAlu(Rv64BaseAluChip<F>),
+    LessThan(Rv64LessThanChip<F>),
+    Shift(Rv64ShiftChip<F>),
+    BaseAluW(Rv64BaseAluWChip<F>),
+    ShiftW(Rv64ShiftWChip<F>),
+    Store(Rv64StoreChip<F>),
+    Load(Rv64LoadChip<F>),
+    LoadSignExtend(Rv64LoadSignExtendChip<F>),
+    Doubleword(Rv64DoublewordChip<F>),
+    BranchEqual(Rv64BranchEqualChip<F>),
+    BranchLessThan(Rv64BranchLessThanChip<F>),
+    JalLui(Rv64JalLuiChip<F>),
+    Jalr(Rv64JalrChip<F>),
+    Auipc(Rv64AuipcChip<F>),
+}
+
+/// RISC-V 64-bit Multiplication Extension (RV64M) Instruction Executors
+#[derive(ChipUsageGetter, Chip, InstructionExecutor, From, AnyEnum)]
+pub enum Rv64MExecutor<F: PrimeField32> {
+    Multiplication(Rv64MultiplicationChip<F>),
+    MultiplicationHigh(Rv64MulHChip<F>),
+    DivRem(Rv64DivRemChip<F>),
+    MultiplicationW(Rv64MultiplicationWChip<F>),
+    DivRemW(Rv64DivRemWChip<F>),
+}
+
+#[derive(From, ChipUsageGetter, Chip, AnyEnum)]
+pub enum Rv64IPeriphery<F: PrimeField32> {
+    BitwiseOperationLookup(Arc<BitwiseOperationLookupChip<8>>),
+    // We put this only to get the <F> generic to work
+    Phantom(PhantomChip<F>),
+}
+
+#[derive(From, ChipUsageGetter, Chip, AnyEnum)]
+pub enum Rv64MPeriphery<F: PrimeField32> {
+    BitwiseOperationLookup(Arc<BitwiseOperationLookupChip<8>>),
+    /// Only needed for multiplication extension
+    RangeTupleChecker(Arc<RangeTupleCheckerChip<2>>),
+    // We put this only to get the <F> generic to work
+    Phantom(PhantomChip<F>),
+}
+
+// ============ VmExtension Implementations ============
+
+impl<F: PrimeField32> VmExtension<F> for Rv64I {
+    type Executor = Rv64IExecutor<F>;
+    type Periphery = Rv64IPeriphery<F>;
+
+    fn build(
+        &self,
+        builder: &mut VmInventoryBuilder<F>,
+    ) -> Result<VmInventory<Rv64IExecutor<F>, Rv64IPeriphery<F>>, VmInventoryError> {
+        let mut inventory = VmInventory::new();
+        let SystemPort {
+            execution_bus,
+            program_bus,
+            memory_controller,
+        } = builder.system_port();
+        let range_checker = builder.system_base().range_checker_chip.clone();
+        let bitwise_lu_chip = if let Some(chip) = builder
+            .find_chip::<Arc<BitwiseOperationLookupChip<8>>>()
+            .first()
+        {
+            Arc::clone(chip)
+        } else {
+            let bitwise_lu_bus = BitwiseOperationLookupBus::new(builder.new_bus_idx());
+            let chip = Arc::new(BitwiseOperationLookupChip::new(bitwise_lu_bus));
+            inventory.add_periphery_chip(chip.clone());
+            chip
+        };
+
+        let base_alu_chip = Rv64BaseAluChip::new(
+            Rv64BaseAluAdapterChip::new(execution_bus, program_bus, memory_controller.clone()),
+            BaseAluCoreChip::new(bitwise_lu_chip.clone(), Rv64BaseAluOpcode::default_offset()),
+            memory_controller.clone(),
+        );
+        inventory.add_executor(
+            base_alu_chip,
+            Rv64BaseAluOpcode::iter().map(VmOpcode::with_default_offset),
+        )?;
+
+        let lt_chip = Rv64LessThanChip::new(
+            Rv64BaseAluAdapterChip::new(execution_bus, program_bus, memory_controller.clone()),
+            LessThanCoreChip::new(
+                bitwise_lu_chip.clone(),
+                Rv64LessThanOpcode::default_offset(),
+            ),
+            memory_controller.clone(),
+        );
+        inventory.add_executor(
+            lt_chip,
+            Rv64LessThanOpcode::iter().map(VmOpcode::with_default_offset),
+        )?;
+
+        let shift_chip = Rv64ShiftChip::new(
+            Rv64BaseAluAdapterChip::new(execution_bus, program_bus, memory_controller.clone()),
+            ShiftCoreChip::new(
+                bitwise_lu_chip.clone(),
+                range_checker.clone(),
+                Rv64ShiftOpcode::default_offset(),
+            ),
+            memory_controller.clone(),
+        );
+        inventory.add_executor(
+            shift_chip,
+            Rv64ShiftOpcode::iter().map(VmOpcode::with_default_offset),
+        )?;
+
+        let base_alu_w_chip = Rv64BaseAluWChip::new(
+            Rv64WordAdapterChip::new(
+                execution_bus,
+                program_bus,
+                memory_controller.clone(),
+                bitwise_lu_chip.clone(),
+            ),
+            BaseAluCoreChip::new(
+                bitwise_lu_chip.clone(),
+                Rv64BaseAluWOpcode::default_offset(),
+            ),
+            memory_controller.clone(),
+        );
+        inventory.add_executor(
+            base_alu_w_chip,
+            Rv64BaseAluWOpcode::iter().map(VmOpcode::with_default_offset),
+        )?;
+
+        let shift_w_chip = Rv64ShiftWChip::new(
+            Rv64WordAdapterChip::new(
+                execution_bus,
+                program_bus,
+                memory_controller.clone(),
+                bitwise_lu_chip.clone(),
+            ),
+            ShiftCoreChip::new(
+                bitwise_lu_chip.clone(),
+                range_checker.clone(),
+                Rv64ShiftWOpcode::default_offset(),
+            ),
+            memory_controller.clone(),
+        );
+        inventory.add_executor(
+            shift_w_chip,
+            Rv64ShiftWOpcode::iter().map(VmOpcode::with_default_offset),
+        )?;
+
+        let store_chip = Rv64StoreChip::new(
+            Rv32LoadStoreAdapterChip::new(
+                execution_bus,
+                program_bus,
+                memory_controller.clone(),
+                range_checker.clone(),
+                Rv64LoadStoreOpcode::default_offset(),
+            ),
+            LoadStoreCoreChip::new(Rv64LoadStoreOpcode::default_offset()),
+            memory_controller.clone(),
+        );
+        inventory.add_executor(
+            store_chip,
+            [
+                Rv32LoadStoreOpcode::STOREW,
+                Rv32LoadStoreOpcode::STOREH,
+                Rv32LoadStoreOpcode::STOREB,
+            ]
+            .map(|opcode| VmOpcode::with_default_offset(Rv64LoadStoreOpcode(opcode))),
+        )?;
+
+        let load_chip = Rv64LoadChip::new(
+            Rv64LoadAdapterChip::new(
+                execution_bus,
+                program_bus,
+                memory_controller.clone(),
+                range_checker.clone(),
+                bitwise_lu_chip.clone(),
+            ),
+            LoadStoreCoreChip::new(Rv64LoadStoreOpcode::default_offset()),
+            memory_controller.clone(),
+        );
+        inventory.add_executor(
+            load_chip,
+            [
+                Rv32LoadStoreOpcode::LOADW,
+                Rv32LoadStoreOpcode::LOADBU,
+                Rv32LoadStoreOpcode::LOADHU,
+            ]
+            .map(|opcode| VmOpcode::with_default_offset(Rv64LoadStoreOpcode(opcode))),
+        )?;
+
+        let load_sign_extend_chip = Rv64LoadSignExtendChip::new(
+            Rv64LoadAdapterChip::new(
+                execution_bus,
+                program_bus,
+                memory_controller.clone(),
+                range_checker.clone(),
+                bitwise_lu_chip.clone(),
+            ),
+            LoadSignExtendCoreChip::new(
+                range_checker.clone(),
+                Rv64LoadStoreOpcode::default_offset(),
+            ),
+            memory_controller.clone(),
+        );
+        inventory.add_executor(
+            load_sign_extend_chip,
+            [Rv32LoadStoreOpcode::LOADB, Rv32LoadStoreOpcode::LOADH]
+                .map(|opcode| VmOpcode::with_default_offset(Rv64LoadStoreOpcode(opcode))),
+        )?;
+
+        let doubleword_chip = Rv64DoublewordChip::new(
+            Rv64DoublewordAdapterChip::new(
+                execution_bus,
+                program_bus,
+                memory_controller.clone(),
+                range_checker.clone(),
+                Rv64DoublewordOpcode::default_offset(),
+            ),
+            Rv64DoublewordCoreChip::new(Rv64DoublewordOpcode::default_offset()),
+            memory_controller.clone(),
+        );
+        inventory.add_executor(
+            doubleword_chip,
+            Rv64DoublewordOpcode::iter().map(VmOpcode::with_default_offset),
+        )?;
+
+        let beq_chip = Rv64BranchEqualChip::new(
+            Rv64BranchAdapterChip::new(execution_bus, program_bus, memory_controller.clone()),
+            BranchEqualCoreChip::new(Rv64BranchEqualOpcode::default_offset(), DEFAULT_PC_STEP),
+            memory_controller.clone(),
+        );
+        inventory.add_executor(
+            beq_chip,
+            Rv64BranchEqualOpcode::iter().map(VmOpcode::with_default_offset),
+        )?;
+
+        let blt_chip = Rv64BranchLessThanChip::new(
+            Rv64BranchAdapterChip::new(execution_bus, program_bus, memory_controller.clone()),
+            BranchLessThanCoreChip::new(
+                bitwise_lu_chip.clone(),
+                Rv64BranchLessThanOpcode::default_offset(),
+            ),
+            memory_controller.clone(),
+        );
+        inventory.add_executor(
+            blt_chip,
+            Rv64BranchLessThanOpcode::iter().map(VmOpcode::with_default_offset),
+        )?;
+
+        let jal_lui_chip = Rv64JalLuiChip::new(
+            Rv64CondRdWriteAdapterChip::new(
+                execution_bus,
+                program_bus,
+                memory_controller.clone(),
+                bitwise_lu_chip.clone(),
+            ),
+            Rv32JalLuiCoreChip::new(bitwise_lu_chip.clone(), Rv64JalLuiOpcode::default_offset()),
+            memory_controller.clone(),
+        );
+        inventory.add_executor(
+            jal_lui_chip,
+            Rv64JalLuiOpcode::iter().map(VmOpcode::with_default_offset),
+        )?;
+
+        let jalr_chip = Rv64JalrChip::new(
+            Rv64JalrAdapterChip::new(execution_bus, program_bus, memory_controller.clone()),
+            Rv32JalrCoreChip::new(
+                bitwise_lu_chip.clone(),
+                range_checker.clone(),
+                Rv64JalrOpcode::default_offset(),
+            ),
+            memory_controller.clone(),
+        );
+        inventory.add_executor(
+            jalr_chip,
+            Rv64JalrOpcode::iter().map(VmOpcode::with_default_offset),
+        )?;
+
+        let auipc_chip = Rv64AuipcChip::new(
+            Rv64RdWriteAdapterChip::new(
+                execution_bus,
+                program_bus,
+                memory_controller.clone(),
+                bitwise_lu_chip.clone(),
+            ),
+            Rv32AuipcCoreChip::new(bitwise_lu_chip.clone(), Rv64AuipcOpcode::default_offset()),
+            memory_controller.clone(),
+        );
+        inventory.add_executor(
+            auipc_chip,
+            Rv64AuipcOpcode::iter().map(VmOpcode::with_default_offset),
+        )?;
+
+        // The hint stream phantom reads 32-bit pointers from memory, so it is not supported.
+        builder.add_phantom_sub_executor(
+            phantom::Rv32HintInputSubEx,
+            PhantomDiscriminant(Rv32Phantom::HintInput as u16),
+        )?;
+        builder.add_phantom_sub_executor(
+            phantom::Rv32PrintStrSubEx,
+            PhantomDiscriminant(Rv32Phantom::PrintStr as u16),
+        )?;
+
+        Ok(inventory)
+    }
+}
+
+impl<F: PrimeField32> VmExtension<F> for Rv64M {
+    type Executor = Rv64MExecutor<F>;
+    type Periphery = Rv64MPeriphery<F>;
+
+    fn build(
+        &self,
+        builder: &mut VmInventoryBuilder<F>,
+    ) -> Result<VmInventory<Rv64MExecutor<F>, Rv64MPeriphery<F>>, VmInventoryError> {
+        let mut inventory = VmInventory::new();
+        let SystemPort {
+            execution_bus,
+            program_bus,
+            memory_controller,
+        } = builder.system_port();
+
+        let bitwise_lu_chip = if let Some(chip) = builder
+            .find_chip::<Arc<BitwiseOperationLookupChip<8>>>()
+            .first()
+        {
+            Arc::clone(chip)
+        } else {
+            let bitwise_lu_bus = BitwiseOperationLookupBus::new(builder.new_bus_idx());
+            let chip = Arc::new(BitwiseOperationLookupChip::new(bitwise_lu_bus));
+            inventory.add_periphery_chip(chip.clone());
+            chip
+        };
+
+        let range_tuple_checker = if let Some(chip) = builder
+            .find_chip::<Arc<RangeTupleCheckerChip<2>>>()
+            .into_iter()
+            .find(|c| {
+                c.bus().sizes[0] >= self.range_tuple_checker_sizes[0]
+                    && c.bus().sizes[1] >= self.range_tuple_checker_sizes[1]
+            }) {
+            chip.clone()
+        } else {
+            let range_tuple_bus =
+                RangeTupleCheckerBus::new(builder.new_bus_idx(), self.range_tuple_checker_sizes);
+            let chip = Arc::new(RangeTupleCheckerChip::new(range_tuple_bus));
+            inventory.add_periphery_chip(chip.clone());
+            chip
+        };
+
+        let mul_chip = Rv64MultiplicationChip::new(
+            Rv64MultAdapterChip::new(execution_bus, program_bus, memory_controller.clone()),
+            MultiplicationCoreChip::new(
+                range_tuple_checker.clone(),
+                Rv64MulOpcode::default_offset(),
+            ),
+            memory_controller.clone(),
+        );
+        inventory.add_executor(
+            mul_chip,
+            Rv64MulOpcode::iter().map(VmOpcode::with_default_offset),
+        )?;
+
+        let mul_h_chip = Rv64MulHChip::new(
+            Rv64MultAdapterChip::new(execution_bus, program_bus, memory_controller.clone()),
+            MulHCoreChip::new(
+                bitwise_lu_chip.clone(),
+                range_tuple_checker.clone(),
+                Rv64MulHOpcode::default_offset(),
+            ),
+            memory_controller.clone(),
+        );
+        inventory.add_executor(
+            mul_h_chip,
+            Rv64MulHOpcode::iter().map(VmOpcode::with_default_offset),
+        )?;
+
+        let div_rem_chip = Rv64DivRemChip::new(
+            Rv64MultAdapterChip::new(execution_bus, program_bus, memory_controller.clone()),
+            DivRemCoreChip::new(
+                bitwise_lu_chip.clone(),
+                range_tuple_checker.clone(),
+                Rv64DivRemOpcode::default_offset(),
+            ),
+            memory_controller.clone(),
+        );
+        inventory.add_executor(
+            div_rem_chip,
+            Rv64DivRemOpcode::iter().map(VmOpcode::with_default_offset),
+        )?;
+
+        let mul_w_chip = Rv64MultiplicationWChip::new(
+            Rv64WordAdapterChip::new(
+                execution_bus,
+                program_bus,
+                memory_controller.clone(),
+                bitwise_lu_chip.clone(),
+            ),
+            MultiplicationCoreChip::new(
+                range_tuple_checker.clone(),
+                Rv64MulWOpcode::default_offset(),
+            ),
+            memory_controller.clone(),
+        );
+        inventory.add_executor(
+            mul_w_chip,
+            Rv64MulWOpcode::iter().map(VmOpcode::with_default_offset),
+        )?;
+
+        let div_rem_w_chip = Rv64DivRemWChip::new(
+            Rv64WordAdapterChip::new(
+                execution_bus,
+                program_bus,
+                memory_controller.clone(),
+                bitwise_lu_chip.clone(),
+            ),
+            DivRemCoreChip::new(
+                bitwise_lu_chip.clone(),
+                range_tuple_checker.clone(),
+                Rv64DivRemWOpcode::default_offset(),
+            ),
+            memory_controller.clone(),
+        );
+        inventory.add_executor(
+            div_rem_w_chip,
+            Rv64DivRemWOpcode::iter().map(VmOpcode::with_default_offset),
+        )?;
+
+        Ok(inventory)
+    }
+}
@@ -0,0 +1,105 @@
+//! Circuit extension for the RISC-V 64-bit IM instruction set.
+//!
+//! Registers are 64 bits wide and stored as 8 byte limbs at pointer `8 * i` of the register
+//! address space. The 64-bit instructions reuse the limb-generic core chips of
+//! [openvm_rv32im_circuit] with 8 limbs, while the `W` instructions run the 4 limb cores on the
+//! low words of the registers and sign extend the result.
+//!
+//! Memory addresses and the program counter are still 32 bits wide, so only the low word of the
+//! base register of a load or store is used.
+
+use openvm_circuit::arch::VmChipWrapper;
+use openvm_rv32im_circuit::{
+    adapters::Rv32LoadStoreAdapterChip, BaseAluCoreChip, BranchEqualCoreChip,
+    BranchLessThanCoreChip, DivRemCoreChip, LessThanCoreChip, LoadSignExtendCoreChip,
+    LoadStoreCoreChip, MulHCoreChip, MultiplicationCoreChip, Rv32AuipcCoreChip, Rv32JalLuiCoreChip,
+    Rv32JalrCoreChip, ShiftCoreChip,
+};
+
+pub mod adapters;
+
+mod doubleword;
+mod extension;
+
+pub use doubleword::*;
+pub use extension::*;
+
+use crate::adapters::*;
+
+#[cfg(test)]
+mod tests;
+
+pub type Rv64BaseAluChip<F> = VmChipWrapper<
+    F,
+    Rv64BaseAluAdapterChip<F>,
+    BaseAluCoreChip<RV64_REGISTER_NUM_LIMBS, RV32_CELL_BITS>,
+>;
+pub type Rv64LessThanChip<F> = VmChipWrapper<
+    F,
+    Rv64BaseAluAdapterChip<F>,
+    LessThanCoreChip<RV64_REGISTER_NUM_LIMBS, RV32_CELL_BITS>,
+>;
+pub type Rv64ShiftChip<F> = VmChipWrapper<
+    F,
+    Rv64BaseAluAdapterChip<F>,
+    ShiftCoreChip<RV64_REGISTER_NUM_LIMBS, RV32_CELL_BITS>,
+>;
+/// `ADDW` and `SUBW`
+pub type Rv64BaseAluWChip<F> = VmChipWrapper<
+    F,
+    Rv64WordAdapterChip<F>,
+    BaseAluCoreChip<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>,
+>;
+/// `SLLW`, `SRLW` and `SRAW`
+pub type Rv64ShiftWChip<F> = VmChipWrapper<
+    F,
+    Rv64WordAdapterChip<F>,
+    ShiftCoreChip<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>,
+>;
+/// Byte, halfword and word stores, which only use the low word of rs2.
+pub type Rv64StoreChip<F> =
+    VmChipWrapper<F, Rv32LoadStoreAdapterChip<F>, LoadStoreCoreChip<RV32_REGISTER_NUM_LIMBS>>;
+/// `LW`, `LBU` and `LHU`
+pub type Rv64LoadChip<F> =
+    VmChipWrapper<F, Rv64LoadAdapterChip<F>, LoadStoreCoreChip<RV32_REGISTER_NUM_LIMBS>>;
+/// `LB` and `LH`
+pub type Rv64LoadSignExtendChip<F> = VmChipWrapper<
+    F,
+    Rv64LoadAdapterChip<F>,
+    LoadSignExtendCoreChip<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>,
+>;
+pub type Rv64BranchEqualChip<F> =
+    VmChipWrapper<F, Rv64BranchAdapterChip<F>, BranchEqualCoreChip<RV64_REGISTER_NUM_LIMBS>>;
+pub type Rv64BranchLessThanChip<F> = VmChipWrapper<
+    F,
+    Rv64BranchAdapterChip<F>,
+    BranchLessThanCoreChip<RV64_REGISTER_NUM_LIMBS, RV32_CELL_BITS>,
+>;
+pub type Rv64JalLuiChip<F> = VmChipWrapper<F, Rv64CondRdWriteAdapterChip<F>, Rv32JalLuiCoreChip>;
+pub type Rv64JalrChip<F> = VmChipWrapper<F, Rv64JalrAdapterChip<F>, Rv32JalrCoreChip>;
+pub type Rv64AuipcChip<F> = VmChipWrapper<F, Rv64RdWriteAdapterChip<F>, Rv32AuipcCoreChip>;
+
+pub type Rv64MultiplicationChip<F> = VmChipWrapper<
+    F,
+    Rv64MultAdapterChip<F>,
+    MultiplicationCoreChip<RV64_REGISTER_NUM_LIMBS, RV32_CELL_BITS>,
+>;
+pub type Rv64MulHChip<F> =
+    VmChipWrapper<F, Rv64MultAdapterChip<F>, MulHCoreChip<RV64_REGISTER_NUM_LIMBS, RV32_CELL_BITS>>;
+pub type Rv64DivRemChip<F> = VmChipWrapper<
+    F,
+    Rv64MultAdapterChip<F>,
+    DivRemCoreChip<RV64_REGISTER_NUM_LIMBS, RV32_CELL_BITS>,
+>;
+/// `MULW`
+pub type Rv64MultiplicationWChip<F> = VmChipWrapper<
+    F,
+    Rv64WordAdapterChip<F>,
+    MultiplicationCoreChip<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>,
+>;
+/// `DIVW`, `DIVUW`, `REMW` and `REMUW`
+pub type Rv64DivRemWChip<F> = VmChipWrapper<
+    F,
+    Rv64WordAdapterChip<F>,
+    DivRemCoreChip<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>,
+>;
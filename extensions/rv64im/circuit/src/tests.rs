@@ -0,0 +1,150 @@
+use std::sync::Arc;
+
+use openvm_circuit::{
+    arch::{
+        testing::{memory::gen_pointer, VmChipTestBuilder},
+        BITWISE_OP_LOOKUP_BUS,
+    },
+    system::memory::{u64_from_limbs, u64_to_limbs},
+};
+use openvm_circuit_primitives::bitwise_op_lookup::{
+    BitwiseOperationLookupBus, BitwiseOperationLookupChip,
+};
+use openvm_instructions::{instruction::Instruction, UsizeOpcode, VmOpcode};
+use openvm_rv32im_circuit::{BaseAluCoreChip, LoadStoreCoreChip};
+use openvm_rv32im_transpiler::{BaseAluOpcode, Rv32LoadStoreOpcode};
+use openvm_rv64im_transpiler::{Rv64BaseAluWOpcode, Rv64LoadStoreOpcode};
+use openvm_stark_backend::p3_field::AbstractField;
+use openvm_stark_sdk::{p3_baby_bear::BabyBear, utils::create_seeded_rng};
+use rand::Rng;
+
+use crate::{
+    adapters::{Rv64LoadAdapterChip, Rv64WordAdapterChip, RV32_CELL_BITS, RV64_REGISTER_NUM_LIMBS},
+    Rv64BaseAluWChip, Rv64LoadChip,
+};
+
+type F = BabyBear;
+
+fn run_word_alu_rand_test(opcode: BaseAluOpcode, num_ops: usize) {
+    let mut rng = create_seeded_rng();
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = Arc::new(BitwiseOperationLookupChip::<RV32_CELL_BITS>::new(
+        bitwise_bus,
+    ));
+
+    let mut tester = VmChipTestBuilder::default();
+    let mut chip = Rv64BaseAluWChip::<F>::new(
+        Rv64WordAdapterChip::new(
+            tester.execution_bus(),
+            tester.program_bus(),
+            tester.memory_controller(),
+            bitwise_chip.clone(),
+        ),
+        BaseAluCoreChip::new(bitwise_chip.clone(), Rv64BaseAluWOpcode::default_offset()),
+        tester.memory_controller(),
+    );
+
+    for _ in 0..num_ops {
+        // the upper words of the operands must be ignored
+        let b = rng.gen::<u64>();
+        let c = rng.gen::<u64>();
+        let rs1 = gen_pointer(&mut rng, RV64_REGISTER_NUM_LIMBS);
+        let rs2 = gen_pointer(&mut rng, RV64_REGISTER_NUM_LIMBS);
+        let rd = gen_pointer(&mut rng, RV64_REGISTER_NUM_LIMBS);
+        tester.write(1, rs1, u64_to_limbs::<F>(b));
+        tester.write(1, rs2, u64_to_limbs::<F>(c));
+
+        tester.execute(
+            &mut chip,
+            Instruction::from_usize(
+                VmOpcode::with_default_offset(Rv64BaseAluWOpcode(opcode)),
+                [rd, rs1, rs2, 1, 1],
+            ),
+        );
+
+        let word = match opcode {
+            BaseAluOpcode::ADD => (b as u32).wrapping_add(c as u32),
+            BaseAluOpcode::SUB => (b as u32).wrapping_sub(c as u32),
+            _ => unreachable!(),
+        };
+        let expected = word as i32 as i64 as u64;
+        assert_eq!(
+            expected,
+            u64_from_limbs(&tester.read::<RV64_REGISTER_NUM_LIMBS>(1, rd)).unwrap()
+        );
+    }
+
+    let tester = tester.build().load(chip).load(bitwise_chip).finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
+#[test]
+fn rv64_addw_rand_test() {
+    run_word_alu_rand_test(BaseAluOpcode::ADD, 100);
+}
+
+#[test]
+fn rv64_subw_rand_test() {
+    run_word_alu_rand_test(BaseAluOpcode::SUB, 100);
+}
+
+#[test]
+fn rv64_load_word_sign_extend_test() {
+    let mut rng = create_seeded_rng();
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = Arc::new(BitwiseOperationLookupChip::<RV32_CELL_BITS>::new(
+        bitwise_bus,
+    ));
+
+    let mut tester = VmChipTestBuilder::default();
+    let range_checker_chip = tester.memory_controller().borrow().range_checker.clone();
+    let mut chip = Rv64LoadChip::<F>::new(
+        Rv64LoadAdapterChip::new(
+            tester.execution_bus(),
+            tester.program_bus(),
+            tester.memory_controller(),
+            range_checker_chip,
+            bitwise_chip.clone(),
+        ),
+        LoadStoreCoreChip::new(Rv64LoadStoreOpcode::default_offset()),
+        tester.memory_controller(),
+    );
+
+    for (opcode, word, expected) in [
+        (
+            Rv32LoadStoreOpcode::LOADW,
+            0x8000_0001u32,
+            0xffff_ffff_8000_0001u64,
+        ),
+        (Rv32LoadStoreOpcode::LOADW, 0x7fff_ffff, 0x7fff_ffff),
+        (Rv32LoadStoreOpcode::LOADBU, 0x0000_00ff, 0xff),
+        (Rv32LoadStoreOpcode::LOADHU, 0xffff_ffff, 0xffff),
+    ] {
+        let ptr = rng.gen_range(0..1 << 10) * 4;
+        let rs1 = gen_pointer(&mut rng, RV64_REGISTER_NUM_LIMBS);
+        let rd = gen_pointer(&mut rng, RV64_REGISTER_NUM_LIMBS);
+        tester.write(1, rs1, u64_to_limbs::<F>(ptr as u64));
+        tester.write(
+            2,
+            ptr,
+            word.to_le_bytes().map(|byte| F::from_canonical_u8(byte)),
+        );
+        // rd holds garbage in its upper word before the load
+        tester.write(1, rd, u64_to_limbs::<F>(rng.gen::<u64>()));
+
+        tester.execute(
+            &mut chip,
+            Instruction::from_usize(
+                VmOpcode::with_default_offset(Rv64LoadStoreOpcode(opcode)),
+                [rd, rs1, 0, 1, 2],
+            ),
+        );
+        assert_eq!(
+            expected,
+            u64_from_limbs(&tester.read::<RV64_REGISTER_NUM_LIMBS>(1, rd)).unwrap()
+        );
+    }
+
+    let tester = tester.build().load(chip).load(bitwise_chip).finalize();
+    tester.simple_test().expect("Verification failed");
+}
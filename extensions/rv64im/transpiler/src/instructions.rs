@@ -0,0 +1,192 @@
+// =================================================================================================
+// RV64IM support opcodes.
+// The 64-bit chips reuse the RV32IM core chips with 8 limbs, so most opcodes are wrappers of the
+// RV32IM opcode enums at a different offset. The `W` opcodes operate on the low 32 bits of the
+// registers and sign extend the result to 64 bits.
+// =================================================================================================
+
+use openvm_instructions::UsizeOpcode;
+use openvm_instructions_derive::UsizeOpcode;
+use openvm_rv32im_transpiler::{
+    BaseAluOpcode, BranchEqualOpcode, BranchLessThanOpcode, DivRemOpcode, LessThanOpcode,
+    MulHOpcode, MulOpcode, Rv32AuipcOpcode, Rv32JalLuiOpcode, Rv32JalrOpcode, Rv32LoadStoreOpcode,
+    ShiftOpcode,
+};
+use strum::{EnumCount, EnumIter, FromRepr, IntoEnumIterator};
+
+#[derive(Copy, Clone, Debug, UsizeOpcode)]
+#[opcode_offset = 0x800]
+pub struct Rv64BaseAluOpcode(pub BaseAluOpcode);
+
+impl Rv64BaseAluOpcode {
+    pub fn iter() -> impl Iterator<Item = Self> {
+        BaseAluOpcode::iter().map(Self)
+    }
+}
+
+#[derive(Copy, Clone, Debug, UsizeOpcode)]
+#[opcode_offset = 0x805]
+pub struct Rv64ShiftOpcode(pub ShiftOpcode);
+
+impl Rv64ShiftOpcode {
+    pub fn iter() -> impl Iterator<Item = Self> {
+        ShiftOpcode::iter().map(Self)
+    }
+}
+
+#[derive(Copy, Clone, Debug, UsizeOpcode)]
+#[opcode_offset = 0x808]
+pub struct Rv64LessThanOpcode(pub LessThanOpcode);
+
+impl Rv64LessThanOpcode {
+    pub fn iter() -> impl Iterator<Item = Self> {
+        LessThanOpcode::iter().map(Self)
+    }
+}
+
+/// Byte, halfword and word loads and stores. Loads sign extend (or zero extend for the
+/// unsigned variants) the loaded word to 64 bits.
+#[derive(Copy, Clone, Debug, UsizeOpcode)]
+#[opcode_offset = 0x810]
+pub struct Rv64LoadStoreOpcode(pub Rv32LoadStoreOpcode);
+
+impl Rv64LoadStoreOpcode {
+    pub fn iter() -> impl Iterator<Item = Self> {
+        Rv32LoadStoreOpcode::iter().map(Self)
+    }
+}
+
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, EnumCount, EnumIter, FromRepr, UsizeOpcode,
+)]
+#[opcode_offset = 0x818]
+#[repr(usize)]
+pub enum Rv64DoublewordOpcode {
+    LOADD,
+    /// Loads a word and zero extends it to 64 bits
+    LOADWU,
+    STORED,
+}
+
+#[derive(Copy, Clone, Debug, UsizeOpcode)]
+#[opcode_offset = 0x820]
+pub struct Rv64BranchEqualOpcode(pub BranchEqualOpcode);
+
+impl Rv64BranchEqualOpcode {
+    pub fn iter() -> impl Iterator<Item = Self> {
+        BranchEqualOpcode::iter().map(Self)
+    }
+}
+
+#[derive(Copy, Clone, Debug, UsizeOpcode)]
+#[opcode_offset = 0x825]
+pub struct Rv64BranchLessThanOpcode(pub BranchLessThanOpcode);
+
+impl Rv64BranchLessThanOpcode {
+    pub fn iter() -> impl Iterator<Item = Self> {
+        BranchLessThanOpcode::iter().map(Self)
+    }
+}
+
+#[derive(Copy, Clone, Debug, UsizeOpcode)]
+#[opcode_offset = 0x830]
+pub struct Rv64JalLuiOpcode(pub Rv32JalLuiOpcode);
+
+impl Rv64JalLuiOpcode {
+    pub fn iter() -> impl Iterator<Item = Self> {
+        Rv32JalLuiOpcode::iter().map(Self)
+    }
+}
+
+#[derive(Copy, Clone, Debug, UsizeOpcode)]
+#[opcode_offset = 0x835]
+pub struct Rv64JalrOpcode(pub Rv32JalrOpcode);
+
+impl Rv64JalrOpcode {
+    pub fn iter() -> impl Iterator<Item = Self> {
+        Rv32JalrOpcode::iter().map(Self)
+    }
+}
+
+#[derive(Copy, Clone, Debug, UsizeOpcode)]
+#[opcode_offset = 0x838]
+pub struct Rv64AuipcOpcode(pub Rv32AuipcOpcode);
+
+impl Rv64AuipcOpcode {
+    pub fn iter() -> impl Iterator<Item = Self> {
+        Rv32AuipcOpcode::iter().map(Self)
+    }
+}
+
+/// `ADDW` and `SUBW`. Only [BaseAluOpcode::ADD] and [BaseAluOpcode::SUB] are used.
+#[derive(Copy, Clone, Debug, UsizeOpcode)]
+#[opcode_offset = 0x840]
+pub struct Rv64BaseAluWOpcode(pub BaseAluOpcode);
+
+impl Rv64BaseAluWOpcode {
+    pub fn iter() -> impl Iterator<Item = Self> {
+        [BaseAluOpcode::ADD, BaseAluOpcode::SUB]
+            .into_iter()
+            .map(Self)
+    }
+}
+
+#[derive(Copy, Clone, Debug, UsizeOpcode)]
+#[opcode_offset = 0x845]
+pub struct Rv64ShiftWOpcode(pub ShiftOpcode);
+
+impl Rv64ShiftWOpcode {
+    pub fn iter() -> impl Iterator<Item = Self> {
+        ShiftOpcode::iter().map(Self)
+    }
+}
+
+#[derive(Copy, Clone, Debug, UsizeOpcode)]
+#[opcode_offset = 0x850]
+pub struct Rv64MulOpcode(pub MulOpcode);
+
+impl Rv64MulOpcode {
+    pub fn iter() -> impl Iterator<Item = Self> {
+        MulOpcode::iter().map(Self)
+    }
+}
+
+#[derive(Copy, Clone, Debug, UsizeOpcode)]
+#[opcode_offset = 0x851]
+pub struct Rv64MulHOpcode(pub MulHOpcode);
+
+impl Rv64MulHOpcode {
+    pub fn iter() -> impl Iterator<Item = Self> {
+        MulHOpcode::iter().map(Self)
+    }
+}
+
+#[derive(Copy, Clone, Debug, UsizeOpcode)]
+#[opcode_offset = 0x854]
+pub struct Rv64DivRemOpcode(pub DivRemOpcode);
+
+impl Rv64DivRemOpcode {
+    pub fn iter() -> impl Iterator<Item = Self> {
+        DivRemOpcode::iter().map(Self)
+    }
+}
+
+#[derive(Copy, Clone, Debug, UsizeOpcode)]
+#[opcode_offset = 0x858]
+pub struct Rv64MulWOpcode(pub MulOpcode);
+
+impl Rv64MulWOpcode {
+    pub fn iter() -> impl Iterator<Item = Self> {
+        MulOpcode::iter().map(Self)
+    }
+}
+
+#[derive(Copy, Clone, Debug, UsizeOpcode)]
+#[opcode_offset = 0x859]
+pub struct Rv64DivRemWOpcode(pub DivRemOpcode);
+
+impl Rv64DivRemWOpcode {
+    pub fn iter() -> impl Iterator<Item = Self> {
+        DivRemOpcode::iter().map(Self)
+    }
+}
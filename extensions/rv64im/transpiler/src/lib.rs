@@ -0,0 +1,395 @@
+use openvm_instructions::{
+    instruction::Instruction, riscv::RV64_REGISTER_NUM_LIMBS, PhantomDiscriminant, SysPhantom,
+    SystemOpcode, UsizeOpcode, VmOpcode,
+};
+use openvm_rv32im_guest::{
+    PhantomImm, CSRRW_FUNCT3, CSR_OPCODE, HINT_BUFFER_FUNCT3, HINT_STORE_W_FUNCT3, PHANTOM_FUNCT3,
+    REVEAL_FUNCT3, RV32M_FUNCT7, SYSTEM_OPCODE, TERMINATE_FUNCT3,
+};
+use openvm_rv32im_transpiler::{
+    BaseAluOpcode, BranchEqualOpcode, BranchLessThanOpcode, DivRemOpcode, LessThanOpcode,
+    MulHOpcode, MulOpcode, Rv32AuipcOpcode, Rv32HintStoreOpcode, Rv32JalLuiOpcode, Rv32JalrOpcode,
+    Rv32LoadStoreOpcode, Rv32Phantom, ShiftOpcode,
+};
+use openvm_stark_backend::p3_field::PrimeField32;
+use openvm_transpiler::{
+    util::{nop, unimp},
+    TranspilerExtension,
+};
+use rrs_lib::instruction_formats::{BType, IType, JType, RType, SType, UType};
+use util::*;
+
+mod instructions;
+mod util;
+pub use instructions::*;
+
+/// Transpiles the RV64I instructions, including the system and phantom instructions.
+#[derive(Default)]
+pub struct Rv64ITranspilerExtension;
+
+/// Transpiles the RV64M instructions.
+#[derive(Default)]
+pub struct Rv64MTranspilerExtension;
+
+/// Transpiles the hint store and reveal instructions for the 64-bit register layout. They are
+/// executed by the chips of the RV32 IO extension.
+#[derive(Default)]
+pub struct Rv64IoTranspilerExtension;
+
+const LOAD_OPCODE: u8 = 0b0000011;
+const FENCE_OPCODE: u8 = 0b0001111;
+const ALU_IMM_OPCODE: u8 = 0b0010011;
+const AUIPC_OPCODE: u8 = 0b0010111;
+const ALU_IMM_W_OPCODE: u8 = 0b0011011;
+const STORE_OPCODE: u8 = 0b0100011;
+const ALU_OPCODE: u8 = 0b0110011;
+const LUI_OPCODE: u8 = 0b0110111;
+const ALU_W_OPCODE: u8 = 0b0111011;
+const BRANCH_OPCODE: u8 = 0b1100011;
+const JALR_OPCODE: u8 = 0b1100111;
+const JAL_OPCODE: u8 = 0b1101111;
+
+/// funct7 of SUB, SRA and their variants
+const ALT_FUNCT7: u8 = 0b0100000;
+
+impl<F: PrimeField32> TranspilerExtension<F> for Rv64ITranspilerExtension {
+    fn process_custom(&self, instruction_stream: &[u32]) -> Option<(Instruction<F>, usize)> {
+        if instruction_stream.is_empty() {
+            return None;
+        }
+        let instruction_u32 = instruction_stream[0];
+
+        let opcode = (instruction_u32 & 0x7f) as u8;
+        let funct3 = ((instruction_u32 >> 12) & 0b111) as u8;
+
+        let instruction = match opcode {
+            CSR_OPCODE => {
+                let dec_insn = IType::new(instruction_u32);
+                if funct3 == CSRRW_FUNCT3 && dec_insn.rs1 == 0 && dec_insn.rd == 0 {
+                    // This resets the CSR counter to zero. Since we don't have any CSR registers, this is a nop.
+                    return Some((nop(), 1));
+                }
+                eprintln!(
+                    "Transpiling system / CSR instruction: {:b} (opcode = {:07b}, funct3 = {:03b}) to unimp",
+                    instruction_u32, opcode, funct3
+                );
+                return Some((unimp(), 1));
+            }
+            SYSTEM_OPCODE => match funct3 {
+                TERMINATE_FUNCT3 => {
+                    let dec_insn = IType::new(instruction_u32);
+                    Some(Instruction {
+                        opcode: VmOpcode::with_default_offset(SystemOpcode::TERMINATE),
+                        c: F::from_canonical_u8(
+                            dec_insn.imm.try_into().expect("exit code must be byte"),
+                        ),
+                        ..Default::default()
+                    })
+                }
+                PHANTOM_FUNCT3 => process_phantom(&IType::new(instruction_u32)),
+                _ => None,
+            },
+            ALU_OPCODE => {
+                let dec_insn = RType::new(instruction_u32);
+                let local_opcode = match (dec_insn.funct7 as u8, funct3) {
+                    (0, 0b000) => Rv64BaseAluOpcode(BaseAluOpcode::ADD).with_default_offset(),
+                    (ALT_FUNCT7, 0b000) => {
+                        Rv64BaseAluOpcode(BaseAluOpcode::SUB).with_default_offset()
+                    }
+                    (0, 0b001) => Rv64ShiftOpcode(ShiftOpcode::SLL).with_default_offset(),
+                    (0, 0b010) => Rv64LessThanOpcode(LessThanOpcode::SLT).with_default_offset(),
+                    (0, 0b011) => Rv64LessThanOpcode(LessThanOpcode::SLTU).with_default_offset(),
+                    (0, 0b100) => Rv64BaseAluOpcode(BaseAluOpcode::XOR).with_default_offset(),
+                    (0, 0b101) => Rv64ShiftOpcode(ShiftOpcode::SRL).with_default_offset(),
+                    (ALT_FUNCT7, 0b101) => Rv64ShiftOpcode(ShiftOpcode::SRA).with_default_offset(),
+                    (0, 0b110) => Rv64BaseAluOpcode(BaseAluOpcode::OR).with_default_offset(),
+                    (0, 0b111) => Rv64BaseAluOpcode(BaseAluOpcode::AND).with_default_offset(),
+                    _ => return None,
+                };
+                Some(from_r_type(local_opcode, 1, &dec_insn))
+            }
+            ALU_IMM_OPCODE => {
+                let dec_insn = IType::new(instruction_u32);
+                // The shifts by an immediate take a 6-bit shamt, with the operation in the upper
+                // 6 bits of the immediate.
+                let shamt = (instruction_u32 >> 20) & 0x3f;
+                let funct6 = instruction_u32 >> 26;
+                let shift = |shift_opcode: ShiftOpcode| {
+                    from_i_type_shamt(
+                        Rv64ShiftOpcode(shift_opcode).with_default_offset(),
+                        dec_insn.rd,
+                        dec_insn.rs1,
+                        shamt,
+                    )
+                };
+                match (funct3, funct6) {
+                    (0b000, _) => Some(from_i_type(
+                        Rv64BaseAluOpcode(BaseAluOpcode::ADD).with_default_offset(),
+                        &dec_insn,
+                    )),
+                    (0b010, _) => Some(from_i_type(
+                        Rv64LessThanOpcode(LessThanOpcode::SLT).with_default_offset(),
+                        &dec_insn,
+                    )),
+                    (0b011, _) => Some(from_i_type(
+                        Rv64LessThanOpcode(LessThanOpcode::SLTU).with_default_offset(),
+                        &dec_insn,
+                    )),
+                    (0b100, _) => Some(from_i_type(
+                        Rv64BaseAluOpcode(BaseAluOpcode::XOR).with_default_offset(),
+                        &dec_insn,
+                    )),
+                    (0b110, _) => Some(from_i_type(
+                        Rv64BaseAluOpcode(BaseAluOpcode::OR).with_default_offset(),
+                        &dec_insn,
+                    )),
+                    (0b111, _) => Some(from_i_type(
+                        Rv64BaseAluOpcode(BaseAluOpcode::AND).with_default_offset(),
+                        &dec_insn,
+                    )),
+                    (0b001, 0b000000) => Some(shift(ShiftOpcode::SLL)),
+                    (0b101, 0b000000) => Some(shift(ShiftOpcode::SRL)),
+                    (0b101, 0b010000) => Some(shift(ShiftOpcode::SRA)),
+                    _ => None,
+                }
+            }
+            ALU_W_OPCODE => {
+                let dec_insn = RType::new(instruction_u32);
+                let local_opcode = match (dec_insn.funct7 as u8, funct3) {
+                    (0, 0b000) => Rv64BaseAluWOpcode(BaseAluOpcode::ADD).with_default_offset(),
+                    (ALT_FUNCT7, 0b000) => {
+                        Rv64BaseAluWOpcode(BaseAluOpcode::SUB).with_default_offset()
+                    }
+                    (0, 0b001) => Rv64ShiftWOpcode(ShiftOpcode::SLL).with_default_offset(),
+                    (0, 0b101) => Rv64ShiftWOpcode(ShiftOpcode::SRL).with_default_offset(),
+                    (ALT_FUNCT7, 0b101) => Rv64ShiftWOpcode(ShiftOpcode::SRA).with_default_offset(),
+                    _ => return None,
+                };
+                Some(from_r_type(local_opcode, 1, &dec_insn))
+            }
+            ALU_IMM_W_OPCODE => {
+                let dec_insn = IType::new(instruction_u32);
+                let shamt = (instruction_u32 >> 20) & 0x1f;
+                let funct7 = (instruction_u32 >> 25) as u8;
+                let shift = |shift_opcode: ShiftOpcode| {
+                    from_i_type_shamt(
+                        Rv64ShiftWOpcode(shift_opcode).with_default_offset(),
+                        dec_insn.rd,
+                        dec_insn.rs1,
+                        shamt,
+                    )
+                };
+                match (funct3, funct7) {
+                    (0b000, _) => Some(from_i_type(
+                        Rv64BaseAluWOpcode(BaseAluOpcode::ADD).with_default_offset(),
+                        &dec_insn,
+                    )),
+                    (0b001, 0) => Some(shift(ShiftOpcode::SLL)),
+                    (0b101, 0) => Some(shift(ShiftOpcode::SRL)),
+                    (0b101, ALT_FUNCT7) => Some(shift(ShiftOpcode::SRA)),
+                    _ => None,
+                }
+            }
+            LOAD_OPCODE => {
+                let dec_insn = IType::new(instruction_u32);
+                let local_opcode = match funct3 {
+                    0b000 => Rv64LoadStoreOpcode(Rv32LoadStoreOpcode::LOADB).with_default_offset(),
+                    0b001 => Rv64LoadStoreOpcode(Rv32LoadStoreOpcode::LOADH).with_default_offset(),
+                    0b010 => Rv64LoadStoreOpcode(Rv32LoadStoreOpcode::LOADW).with_default_offset(),
+                    0b011 => Rv64DoublewordOpcode::LOADD.with_default_offset(),
+                    0b100 => Rv64LoadStoreOpcode(Rv32LoadStoreOpcode::LOADBU).with_default_offset(),
+                    0b101 => Rv64LoadStoreOpcode(Rv32LoadStoreOpcode::LOADHU).with_default_offset(),
+                    0b110 => Rv64DoublewordOpcode::LOADWU.with_default_offset(),
+                    _ => return None,
+                };
+                Some(from_load(local_opcode, &dec_insn))
+            }
+            STORE_OPCODE => {
+                let dec_insn = SType::new(instruction_u32);
+                let local_opcode = match funct3 {
+                    0b000 => Rv64LoadStoreOpcode(Rv32LoadStoreOpcode::STOREB).with_default_offset(),
+                    0b001 => Rv64LoadStoreOpcode(Rv32LoadStoreOpcode::STOREH).with_default_offset(),
+                    0b010 => Rv64LoadStoreOpcode(Rv32LoadStoreOpcode::STOREW).with_default_offset(),
+                    0b011 => Rv64DoublewordOpcode::STORED.with_default_offset(),
+                    _ => return None,
+                };
+                Some(from_s_type(local_opcode, &dec_insn))
+            }
+            BRANCH_OPCODE => {
+                let dec_insn = BType::new(instruction_u32);
+                let local_opcode = match funct3 {
+                    0b000 => Rv64BranchEqualOpcode(BranchEqualOpcode::BEQ).with_default_offset(),
+                    0b001 => Rv64BranchEqualOpcode(BranchEqualOpcode::BNE).with_default_offset(),
+                    0b100 => {
+                        Rv64BranchLessThanOpcode(BranchLessThanOpcode::BLT).with_default_offset()
+                    }
+                    0b101 => {
+                        Rv64BranchLessThanOpcode(BranchLessThanOpcode::BGE).with_default_offset()
+                    }
+                    0b110 => {
+                        Rv64BranchLessThanOpcode(BranchLessThanOpcode::BLTU).with_default_offset()
+                    }
+                    0b111 => {
+                        Rv64BranchLessThanOpcode(BranchLessThanOpcode::BGEU).with_default_offset()
+                    }
+                    _ => return None,
+                };
+                Some(from_b_type(local_opcode, &dec_insn))
+            }
+            JAL_OPCODE => Some(from_j_type(
+                Rv64JalLuiOpcode(Rv32JalLuiOpcode::JAL).with_default_offset(),
+                &JType::new(instruction_u32),
+            )),
+            JALR_OPCODE if funct3 == 0 => Some(from_jalr(
+                Rv64JalrOpcode(Rv32JalrOpcode::JALR).with_default_offset(),
+                &IType::new(instruction_u32),
+            )),
+            LUI_OPCODE => Some(from_lui(
+                Rv64JalLuiOpcode(Rv32JalLuiOpcode::LUI).with_default_offset(),
+                &UType::new(instruction_u32),
+            )),
+            AUIPC_OPCODE => Some(from_auipc(
+                Rv64AuipcOpcode(Rv32AuipcOpcode::AUIPC).with_default_offset(),
+                &UType::new(instruction_u32),
+            )),
+            FENCE_OPCODE => {
+                tracing::debug!("Transpiling fence ({:b}) to nop", instruction_u32);
+                Some(nop())
+            }
+            _ => None,
+        };
+
+        instruction.map(|ret| (ret, 1))
+    }
+}
+
+/// The phantom instructions of [Rv32Phantom] and the debug log, with the register pointers
+/// scaled for 64-bit registers. The hint stream phantom is not supported, since its key is laid
+/// out with 32-bit pointers.
+fn process_phantom<F: PrimeField32>(dec_insn: &IType) -> Option<Instruction<F>> {
+    let rd = F::from_canonical_usize(RV64_REGISTER_NUM_LIMBS * dec_insn.rd);
+    let rs1 = F::from_canonical_usize(RV64_REGISTER_NUM_LIMBS * dec_insn.rs1);
+    match PhantomImm::from_repr(dec_insn.imm as u16)? {
+        PhantomImm::HintInput => Some(Instruction::phantom(
+            PhantomDiscriminant(Rv32Phantom::HintInput as u16),
+            F::ZERO,
+            F::ZERO,
+            0,
+        )),
+        PhantomImm::PrintStr => Some(Instruction::phantom(
+            PhantomDiscriminant(Rv32Phantom::PrintStr as u16),
+            rd,
+            rs1,
+            0,
+        )),
+        PhantomImm::DebugLog => Some(Instruction::phantom(
+            PhantomDiscriminant(SysPhantom::DebugLog as u16),
+            rd,
+            rs1,
+            0,
+        )),
+        PhantomImm::HintPerfCounters => Some(Instruction::phantom(
+            PhantomDiscriminant(SysPhantom::HintPerfCounters as u16),
+            F::ZERO,
+            F::ZERO,
+            0,
+        )),
+        PhantomImm::HintStream => None,
+    }
+}
+
+impl<F: PrimeField32> TranspilerExtension<F> for Rv64MTranspilerExtension {
+    fn process_custom(&self, instruction_stream: &[u32]) -> Option<(Instruction<F>, usize)> {
+        if instruction_stream.is_empty() {
+            return None;
+        }
+        let instruction_u32 = instruction_stream[0];
+
+        let opcode = (instruction_u32 & 0x7f) as u8;
+        let dec_insn = RType::new(instruction_u32);
+        if dec_insn.funct7 as u8 != RV32M_FUNCT7 {
+            return None;
+        }
+
+        let instruction = match opcode {
+            ALU_OPCODE => {
+                let local_opcode = match dec_insn.funct3 {
+                    0b000 => Rv64MulOpcode(MulOpcode::MUL).with_default_offset(),
+                    0b001 => Rv64MulHOpcode(MulHOpcode::MULH).with_default_offset(),
+                    0b010 => Rv64MulHOpcode(MulHOpcode::MULHSU).with_default_offset(),
+                    0b011 => Rv64MulHOpcode(MulHOpcode::MULHU).with_default_offset(),
+                    0b100 => Rv64DivRemOpcode(DivRemOpcode::DIV).with_default_offset(),
+                    0b101 => Rv64DivRemOpcode(DivRemOpcode::DIVU).with_default_offset(),
+                    0b110 => Rv64DivRemOpcode(DivRemOpcode::REM).with_default_offset(),
+                    0b111 => Rv64DivRemOpcode(DivRemOpcode::REMU).with_default_offset(),
+                    _ => return None,
+                };
+                from_r_type(local_opcode, 0, &dec_insn)
+            }
+            ALU_W_OPCODE => {
+                let local_opcode = match dec_insn.funct3 {
+                    0b000 => Rv64MulWOpcode(MulOpcode::MUL).with_default_offset(),
+                    0b100 => Rv64DivRemWOpcode(DivRemOpcode::DIV).with_default_offset(),
+                    0b101 => Rv64DivRemWOpcode(DivRemOpcode::DIVU).with_default_offset(),
+                    0b110 => Rv64DivRemWOpcode(DivRemOpcode::REM).with_default_offset(),
+                    0b111 => Rv64DivRemWOpcode(DivRemOpcode::REMU).with_default_offset(),
+                    _ => return None,
+                };
+                // The W chips share their adapter with ADDW/SUBW, which reads rs2 when e = 1
+                from_r_type(local_opcode, 1, &dec_insn)
+            }
+            _ => return None,
+        };
+
+        Some((instruction, 1))
+    }
+}
+
+impl<F: PrimeField32> TranspilerExtension<F> for Rv64IoTranspilerExtension {
+    fn process_custom(&self, instruction_stream: &[u32]) -> Option<(Instruction<F>, usize)> {
+        if instruction_stream.is_empty() {
+            return None;
+        }
+        let instruction_u32 = instruction_stream[0];
+
+        let opcode = (instruction_u32 & 0x7f) as u8;
+        let funct3 = ((instruction_u32 >> 12) & 0b111) as u8;
+        if opcode != SYSTEM_OPCODE {
+            return None;
+        }
+
+        let dec_insn = IType::new(instruction_u32);
+        let imm_u16 = (dec_insn.imm as u32) & 0xffff;
+        let instruction = match funct3 {
+            // The hint store chip reads the low word of rd as the memory pointer
+            HINT_STORE_W_FUNCT3 => Instruction::from_isize(
+                VmOpcode::with_default_offset(Rv32HintStoreOpcode::HINT_STOREW),
+                0,
+                (RV64_REGISTER_NUM_LIMBS * dec_insn.rd) as isize,
+                imm_u16 as isize,
+                1,
+                2,
+            ),
+            HINT_BUFFER_FUNCT3 => Instruction::from_isize(
+                VmOpcode::with_default_offset(Rv32HintStoreOpcode::HINT_BUFFER),
+                0,
+                (RV64_REGISTER_NUM_LIMBS * dec_insn.rd) as isize,
+                imm_u16 as isize,
+                1,
+                2,
+            ),
+            // REVEAL is a pseudo-instruction for STOREW a,b,c,1,3
+            REVEAL_FUNCT3 => Instruction::from_isize(
+                VmOpcode::with_default_offset(Rv64LoadStoreOpcode(Rv32LoadStoreOpcode::STOREW)),
+                (RV64_REGISTER_NUM_LIMBS * dec_insn.rs1) as isize,
+                (RV64_REGISTER_NUM_LIMBS * dec_insn.rd) as isize,
+                imm_u16 as isize,
+                1,
+                3,
+            ),
+            _ => return None,
+        };
+
+        Some((instruction, 1))
+    }
+}
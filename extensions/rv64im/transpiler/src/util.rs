@@ -0,0 +1,186 @@
+use openvm_instructions::{
+    instruction::Instruction, riscv::RV64_REGISTER_NUM_LIMBS, utils::isize_to_field, VmOpcode,
+};
+use openvm_stark_backend::p3_field::PrimeField32;
+use openvm_transpiler::util::nop;
+use rrs_lib::instruction_formats::{BType, IType, JType, RType, SType, UType};
+
+// The helpers below mirror the ones in `openvm_transpiler::util`, with register pointers scaled
+// by the 8 bytes of a 64-bit register.
+
+fn reg(idx: usize) -> usize {
+    RV64_REGISTER_NUM_LIMBS * idx
+}
+
+fn i12_to_u24(imm: i32) -> u32 {
+    (imm as u32) & 0xffffff
+}
+
+/// Create a new [`Instruction`] from an R-type instruction.
+pub(crate) fn from_r_type<F: PrimeField32>(
+    opcode: usize,
+    e_as: usize,
+    dec_insn: &RType,
+) -> Instruction<F> {
+    if dec_insn.rd == 0 {
+        return nop();
+    }
+    Instruction::from_usize(
+        VmOpcode::from_usize(opcode),
+        [
+            reg(dec_insn.rd),
+            reg(dec_insn.rs1),
+            reg(dec_insn.rs2),
+            1,
+            e_as,
+        ],
+    )
+}
+
+/// Create a new [`Instruction`] from an I-type ALU instruction, with the immediate sign extended
+/// to 24 bits.
+pub(crate) fn from_i_type<F: PrimeField32>(opcode: usize, dec_insn: &IType) -> Instruction<F> {
+    if dec_insn.rd == 0 {
+        return nop();
+    }
+    Instruction::new(
+        VmOpcode::from_usize(opcode),
+        F::from_canonical_usize(reg(dec_insn.rd)),
+        F::from_canonical_usize(reg(dec_insn.rs1)),
+        F::from_canonical_u32(i12_to_u24(dec_insn.imm)),
+        F::ONE,  // rd and rs1 are registers
+        F::ZERO, // rs2 is an immediate
+        F::ZERO,
+        F::ZERO,
+    )
+}
+
+/// Create a new [`Instruction`] from a shift by an immediate `shamt`.
+pub(crate) fn from_i_type_shamt<F: PrimeField32>(
+    opcode: usize,
+    rd: usize,
+    rs1: usize,
+    shamt: u32,
+) -> Instruction<F> {
+    if rd == 0 {
+        return nop();
+    }
+    Instruction::new(
+        VmOpcode::from_usize(opcode),
+        F::from_canonical_usize(reg(rd)),
+        F::from_canonical_usize(reg(rs1)),
+        F::from_canonical_u32(shamt),
+        F::ONE,  // rd and rs1 are registers
+        F::ZERO, // rs2 is an immediate
+        F::ZERO,
+        F::ZERO,
+    )
+}
+
+/// Create a new [`Instruction`] from a load operation
+pub(crate) fn from_load<F: PrimeField32>(opcode: usize, dec_insn: &IType) -> Instruction<F> {
+    if dec_insn.rd == 0 {
+        return nop();
+    }
+    Instruction::new(
+        VmOpcode::from_usize(opcode),
+        F::from_canonical_usize(reg(dec_insn.rd)),
+        F::from_canonical_usize(reg(dec_insn.rs1)),
+        F::from_canonical_u32((dec_insn.imm as u32) & 0xffff),
+        F::ONE, // rd is a register
+        F::TWO, // we load from memory
+        F::ZERO,
+        F::ZERO,
+    )
+}
+
+/// Create a new [`Instruction`] from an S-type instruction.
+pub(crate) fn from_s_type<F: PrimeField32>(opcode: usize, dec_insn: &SType) -> Instruction<F> {
+    Instruction::new(
+        VmOpcode::from_usize(opcode),
+        F::from_canonical_usize(reg(dec_insn.rs2)),
+        F::from_canonical_usize(reg(dec_insn.rs1)),
+        F::from_canonical_u32((dec_insn.imm as u32) & 0xffff),
+        F::ONE,
+        F::TWO,
+        F::ZERO,
+        F::ZERO,
+    )
+}
+
+/// Create a new [`Instruction`] from a B-type instruction.
+pub(crate) fn from_b_type<F: PrimeField32>(opcode: usize, dec_insn: &BType) -> Instruction<F> {
+    Instruction::new(
+        VmOpcode::from_usize(opcode),
+        F::from_canonical_usize(reg(dec_insn.rs1)),
+        F::from_canonical_usize(reg(dec_insn.rs2)),
+        isize_to_field(dec_insn.imm as isize),
+        F::ONE, // rs1 is a register
+        F::ONE, // rs2 is a register
+        F::ZERO,
+        F::ZERO,
+    )
+}
+
+/// Create a new [`Instruction`] from a J-type instruction.
+pub(crate) fn from_j_type<F: PrimeField32>(opcode: usize, dec_insn: &JType) -> Instruction<F> {
+    Instruction::new(
+        VmOpcode::from_usize(opcode),
+        F::from_canonical_usize(reg(dec_insn.rd)),
+        F::ZERO,
+        isize_to_field(dec_insn.imm as isize),
+        F::ONE, // rd is a register
+        F::ZERO,
+        F::from_bool(dec_insn.rd != 0), // we may need to use this flag in the operation
+        F::ZERO,
+    )
+}
+
+/// Create a new [`Instruction`] from a JALR instruction.
+pub(crate) fn from_jalr<F: PrimeField32>(opcode: usize, dec_insn: &IType) -> Instruction<F> {
+    Instruction::new(
+        VmOpcode::from_usize(opcode),
+        F::from_canonical_usize(reg(dec_insn.rd)),
+        F::from_canonical_usize(reg(dec_insn.rs1)),
+        F::from_canonical_u32((dec_insn.imm as u32) & 0xffff),
+        F::ONE,
+        F::ZERO,
+        F::from_bool(dec_insn.rd != 0),
+        F::ZERO,
+    )
+}
+
+/// Create a new [`Instruction`] from a LUI instruction.
+pub(crate) fn from_lui<F: PrimeField32>(opcode: usize, dec_insn: &UType) -> Instruction<F> {
+    if dec_insn.rd == 0 {
+        return nop();
+    }
+    Instruction::new(
+        VmOpcode::from_usize(opcode),
+        F::from_canonical_usize(reg(dec_insn.rd)),
+        F::ZERO,
+        F::from_canonical_u32((dec_insn.imm as u32 >> 12) & 0xfffff),
+        F::ONE, // rd is a register
+        F::ZERO,
+        // we need to set f to 1 because this is handled by the same chip as jal
+        F::ONE,
+        F::ZERO,
+    )
+}
+
+/// Create a new [`Instruction`] from an AUIPC instruction.
+pub(crate) fn from_auipc<F: PrimeField32>(opcode: usize, dec_insn: &UType) -> Instruction<F> {
+    if dec_insn.rd == 0 {
+        return nop();
+    }
+    Instruction::new(
+        VmOpcode::from_usize(opcode),
+        F::from_canonical_usize(reg(dec_insn.rd)),
+        F::ZERO,
+        F::from_canonical_u32(((dec_insn.imm as u32) & 0xfffff000) >> 8),
+        F::ONE, // rd is a register
+        F::ZERO,
+        F::ZERO,
+        F::ZERO,
+    )
+}
@@ -0,0 +1,479 @@
+use std::{array::from_fn, borrow::Borrow, iter::once};
+
+use openvm_circuit::{
+    arch::{ExecutionBridge, ExecutionState},
+    system::memory::{offline_checker::MemoryBridge, MemoryAddress},
+};
+use openvm_circuit_primitives::{bitwise_op_lookup::BitwiseOperationLookupBus, utils::not};
+use openvm_instructions::riscv::{
+    RV32_CELL_BITS, RV32_MEMORY_AS, RV32_REGISTER_AS, RV32_REGISTER_NUM_LIMBS,
+};
+use openvm_rv32im_circuit::adapters::abstract_compose;
+use openvm_sha2_guest::SHA512_K;
+use openvm_sha2_transpiler::Rv32Sha512Opcode;
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    p3_air::{Air, AirBuilder, BaseAir},
+    p3_field::AbstractField,
+    p3_matrix::Matrix,
+    rap::{BaseAirWithPublicValues, PartitionedBaseAir},
+};
+
+use super::{
+    columns::{Sha512VmCols, NUM_SHA512_VM_COLS},
+    SHA512_BLOCK_WORDS, SHA512_BLOCK_WORD_READS, SHA512_REGISTER_READS, SHA512_ROUNDS,
+    SHA512_STATE_WORDS, SHA512_STATE_WORD_ACCESSES, SHA512_TIMESTAMP_CHANGE, SHA512_WORD_BITS,
+    SHA512_WORD_BYTES, SHA512_WORD_SIZE, SHA512_WORD_U16S,
+};
+
+#[derive(Clone, Copy, Debug, derive_new::new)]
+pub struct Sha512VmAir {
+    pub execution_bridge: ExecutionBridge,
+    pub memory_bridge: MemoryBridge,
+    /// Bus to send 8-bit range checks to.
+    pub bitwise_lookup_bus: BitwiseOperationLookupBus,
+    /// Maximum number of bits allowed for an address pointer
+    pub ptr_max_bits: usize,
+    pub(super) offset: usize,
+}
+
+impl<F> BaseAirWithPublicValues<F> for Sha512VmAir {}
+impl<F> PartitionedBaseAir<F> for Sha512VmAir {}
+impl<F> BaseAir<F> for Sha512VmAir {
+    fn width(&self) -> usize {
+        NUM_SHA512_VM_COLS
+    }
+}
+
+impl<AB: InteractionBuilder> Air<AB> for Sha512VmAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let (local, next) = (main.row_slice(0), main.row_slice(1));
+        let local: &Sha512VmCols<AB::Var> = (*local).borrow();
+        let next: &Sha512VmCols<AB::Var> = (*next).borrow();
+
+        self.eval_flags(builder, local, next);
+        self.eval_round(builder, local, next);
+        self.eval_digest(builder, local);
+        self.eval_instruction(builder, local);
+    }
+}
+
+impl Sha512VmAir {
+    /// Constrain the rows of each block to be the rounds in order followed by the digest row.
+    /// Rows without flags are dummy rows and may only be followed by the first round of a block.
+    #[inline]
+    pub fn eval_flags<AB: AirBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &Sha512VmCols<AB::Var>,
+        next: &Sha512VmCols<AB::Var>,
+    ) {
+        let flags = &local.flags;
+        for &flag in flags.round_flags.iter().chain(once(&flags.is_digest_row)) {
+            builder.assert_bool(flag);
+        }
+        let is_enabled: AB::Expr = flags.is_enabled();
+        builder.assert_bool(is_enabled.clone());
+        let is_round: AB::Expr = flags.is_round();
+
+        let mut transition_builder = builder.when_transition();
+        for t in 0..SHA512_ROUNDS {
+            let next_flag = if t + 1 < SHA512_ROUNDS {
+                next.flags.round_flags[t + 1]
+            } else {
+                next.flags.is_digest_row
+            };
+            transition_builder
+                .when(flags.round_flags[t])
+                .assert_one(next_flag);
+        }
+        transition_builder
+            .when(not::<AB::Expr>(is_round.clone()))
+            .assert_eq(
+                next.flags.is_enabled::<AB::Expr>(),
+                next.flags.round_flags[0],
+            );
+
+        builder
+            .when_first_row()
+            .assert_eq(is_enabled, flags.round_flags[0]);
+        // Every block is complete
+        builder.when_last_row().assert_zero(is_round);
+    }
+
+    /// Constrain one round of the compression function and one step of the message schedule.
+    ///
+    /// The boolean functions are computed on the bits of the working variables, and the
+    /// additions are done on `u16` limbs with carries. The carries are range checked to 8 bits,
+    /// which is enough since at most 7 limbs are added.
+    #[inline]
+    pub fn eval_round<AB: InteractionBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &Sha512VmCols<AB::Var>,
+        next: &Sha512VmCols<AB::Var>,
+    ) {
+        let round = &local.round;
+        let next_round = &next.round;
+        let is_round: AB::Expr = local.flags.is_round();
+
+        for &bit in round
+            .work_vars
+            .iter()
+            .flatten()
+            .chain(&round.w0_bits)
+            .chain(&round.w1_bits)
+            .chain(&round.w14_bits)
+        {
+            builder.assert_bool(bit);
+        }
+        let [a, b, c, d, e, f, g, h] = &round.work_vars;
+        let rotr =
+            |x: &[AB::Var; SHA512_WORD_BITS], n: usize, i: usize| x[(i + n) % SHA512_WORD_BITS];
+        let shr = |x: &[AB::Var; SHA512_WORD_BITS], n: usize, i: usize| -> AB::Expr {
+            if i + n < SHA512_WORD_BITS {
+                x[i + n].into()
+            } else {
+                AB::Expr::ZERO
+            }
+        };
+        for i in 0..SHA512_WORD_BITS {
+            builder.assert_eq(
+                round.big_sig0[i],
+                xor3::<AB::Expr>(rotr(a, 28, i), rotr(a, 34, i), rotr(a, 39, i)),
+            );
+            builder.assert_eq(
+                round.big_sig1[i],
+                xor3::<AB::Expr>(rotr(e, 14, i), rotr(e, 18, i), rotr(e, 41, i)),
+            );
+            builder.assert_eq(round.maj[i], maj::<AB::Expr>(a[i], b[i], c[i]));
+            let w1 = &round.w1_bits;
+            builder.assert_eq(
+                round.small_sig0[i],
+                xor3::<AB::Expr>(rotr(w1, 1, i), rotr(w1, 8, i), shr(w1, 7, i)),
+            );
+            let w14 = &round.w14_bits;
+            builder.assert_eq(
+                round.small_sig1[i],
+                xor3::<AB::Expr>(rotr(w14, 19, i), rotr(w14, 61, i), shr(w14, 6, i)),
+            );
+        }
+        for (limbs, bits) in [
+            (round.w[0], &round.w0_bits),
+            (round.w[1], &round.w1_bits),
+            (round.w[14], &round.w14_bits),
+        ] {
+            for (k, limb) in limbs.into_iter().enumerate() {
+                builder.assert_eq(limb, compose_limb::<AB::Expr>(bits, k));
+            }
+        }
+
+        let mut round_builder = builder.when(is_round.clone());
+        // Shift the working variables other than `a` and `e`
+        for (next_var, var) in [(1, 0), (2, 1), (3, 2), (5, 4), (6, 5), (7, 6)] {
+            for (&next_bit, &bit) in next_round.work_vars[next_var]
+                .iter()
+                .zip(&round.work_vars[var])
+            {
+                round_builder.assert_eq(next_bit, bit);
+            }
+        }
+        // Shift the message schedule window
+        for (next_word, word) in next_round.w.iter().zip(&round.w[1..]) {
+            for (&next_limb, &limb) in next_word.iter().zip(word) {
+                round_builder.assert_eq(next_limb, limb);
+            }
+        }
+        local
+            .instruction
+            .assert_eq(&mut round_builder, next.instruction);
+
+        let two_16 = AB::Expr::from_canonical_u32(1 << 16);
+        for k in 0..SHA512_WORD_U16S {
+            let round_constant = local.flags.round_flags.iter().zip(SHA512_K).fold(
+                AB::Expr::ZERO,
+                |acc, (&flag, k_t)| {
+                    acc + flag * AB::F::from_canonical_u64((k_t >> (16 * k)) & 0xffff)
+                },
+            );
+            let ch = (0..16).fold(AB::Expr::ZERO, |acc, j| {
+                let i = 16 * k + j;
+                acc + ch::<AB::Expr>(e[i], f[i], g[i]) * AB::F::from_canonical_u32(1 << j)
+            });
+            let t1 = compose_limb::<AB::Expr>(h, k)
+                + compose_limb::<AB::Expr>(&round.big_sig1, k)
+                + ch
+                + round_constant
+                + round.w[0][k];
+            let t2 = compose_limb::<AB::Expr>(&round.big_sig0, k)
+                + compose_limb::<AB::Expr>(&round.maj, k);
+            let carry_in = |carries: &[AB::Var; SHA512_WORD_U16S]| -> AB::Expr {
+                if k == 0 {
+                    AB::Expr::ZERO
+                } else {
+                    carries[k - 1].into()
+                }
+            };
+
+            round_builder.assert_eq(
+                t1.clone() + t2 + carry_in(&round.carry_a),
+                compose_limb::<AB::Expr>(&next_round.work_vars[0], k)
+                    + round.carry_a[k] * two_16.clone(),
+            );
+            round_builder.assert_eq(
+                compose_limb::<AB::Expr>(d, k) + t1 + carry_in(&round.carry_e),
+                compose_limb::<AB::Expr>(&next_round.work_vars[4], k)
+                    + round.carry_e[k] * two_16.clone(),
+            );
+            round_builder.assert_eq(
+                compose_limb::<AB::Expr>(&round.small_sig1, k)
+                    + round.w[9][k]
+                    + compose_limb::<AB::Expr>(&round.small_sig0, k)
+                    + round.w[0][k]
+                    + carry_in(&round.carry_w),
+                next_round.w[SHA512_BLOCK_WORDS - 1][k] + round.carry_w[k] * two_16.clone(),
+            );
+        }
+
+        let carries: Vec<_> = round
+            .carry_a
+            .iter()
+            .chain(&round.carry_e)
+            .chain(&round.carry_w)
+            .collect();
+        for pair in carries.chunks_exact(2) {
+            self.bitwise_lookup_bus
+                .send_range(*pair[0], *pair[1])
+                .eval(builder, is_round.clone());
+        }
+
+        // The working variables of the first round are the previous state
+        let mut first_round_builder = builder.when(local.flags.round_flags[0]);
+        for (var, prev_word) in round.work_vars.iter().zip(local.instruction.prev_state) {
+            for (k, prev_limb) in prev_word.into_iter().enumerate() {
+                first_round_builder.assert_eq(compose_limb::<AB::Expr>(var, k), prev_limb);
+            }
+        }
+    }
+
+    /// Constrain the new state to be the previous state plus the final working variables, and
+    /// range check its bytes.
+    #[inline]
+    pub fn eval_digest<AB: InteractionBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &Sha512VmCols<AB::Var>,
+    ) {
+        let digest = &local.digest;
+        let is_digest_row = local.flags.is_digest_row;
+        for &carry in digest.carry.iter().flatten() {
+            builder.assert_bool(carry);
+        }
+
+        let mut digest_builder = builder.when(is_digest_row);
+        for j in 0..SHA512_STATE_WORDS {
+            for k in 0..SHA512_WORD_U16S {
+                let carry_in = if k == 0 {
+                    AB::Expr::ZERO
+                } else {
+                    digest.carry[j][k - 1].into()
+                };
+                digest_builder.assert_eq(
+                    local.instruction.prev_state[j][k]
+                        + compose_limb::<AB::Expr>(&local.round.work_vars[j], k)
+                        + carry_in,
+                    digest.state_bytes[j][2 * k]
+                        + digest.state_bytes[j][2 * k + 1] * AB::F::from_canonical_u32(1 << 8)
+                        + digest.carry[j][k] * AB::F::from_canonical_u32(1 << 16),
+                );
+            }
+        }
+
+        for pair in digest
+            .state_bytes
+            .iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .chunks_exact(2)
+        {
+            self.bitwise_lookup_bus
+                .send_range(*pair[0], *pair[1])
+                .eval(builder, is_digest_row);
+        }
+    }
+
+    /// Receive the instruction and read the pointers and the previous state on the first round,
+    /// read the message word `W[t]` on each of the first 16 rounds, and write the new state on
+    /// the digest row.
+    ///
+    /// The state is little-endian in memory while the block is big-endian, as in the guest.
+    pub fn eval_instruction<AB: InteractionBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &Sha512VmCols<AB::Var>,
+    ) {
+        let instruction = local.instruction;
+        let is_first_round = local.flags.round_flags[0];
+        let is_digest_row = local.flags.is_digest_row;
+
+        self.execution_bridge
+            .execute_and_increment_pc(
+                AB::Expr::from_canonical_usize(
+                    Rv32Sha512Opcode::SHA512_COMPRESS as usize + self.offset,
+                ),
+                [
+                    instruction.rd_ptr.into(),
+                    instruction.rs1_ptr.into(),
+                    AB::Expr::ZERO,
+                    AB::Expr::from_canonical_u32(RV32_REGISTER_AS),
+                    AB::Expr::from_canonical_u32(RV32_MEMORY_AS),
+                ],
+                ExecutionState::new(instruction.pc, instruction.start_timestamp),
+                AB::Expr::from_canonical_usize(SHA512_TIMESTAMP_CHANGE),
+            )
+            .eval(builder, is_first_round);
+
+        for (i, (reg_ptr, value)) in [
+            (instruction.rd_ptr, instruction.state_ptr),
+            (instruction.rs1_ptr, instruction.block_ptr),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            self.memory_bridge
+                .read(
+                    MemoryAddress::new(AB::F::from_canonical_u32(RV32_REGISTER_AS), reg_ptr),
+                    value,
+                    instruction.start_timestamp + AB::F::from_canonical_usize(i),
+                    &local.mem_oc.register_aux[i],
+                )
+                .eval(builder, is_first_round);
+        }
+        // Range check that the pointers are less than 2^ptr_max_bits
+        let limb_shift = AB::F::from_canonical_usize(
+            1 << (RV32_CELL_BITS * RV32_REGISTER_NUM_LIMBS - self.ptr_max_bits),
+        );
+        self.bitwise_lookup_bus
+            .send_range(
+                instruction.state_ptr[RV32_REGISTER_NUM_LIMBS - 1] * limb_shift,
+                instruction.block_ptr[RV32_REGISTER_NUM_LIMBS - 1] * limb_shift,
+            )
+            .eval(builder, is_first_round);
+
+        let state_ptr = abstract_compose::<AB::Expr, _>(instruction.state_ptr);
+        let block_ptr = abstract_compose::<AB::Expr, _>(instruction.block_ptr);
+        let state_read_timestamp =
+            instruction.start_timestamp + AB::F::from_canonical_usize(SHA512_REGISTER_READS);
+        let block_read_timestamp =
+            state_read_timestamp.clone() + AB::F::from_canonical_usize(SHA512_STATE_WORD_ACCESSES);
+        let state_write_timestamp = block_read_timestamp.clone()
+            + AB::F::from_canonical_usize(SHA512_BLOCK_WORDS * SHA512_BLOCK_WORD_READS);
+
+        let prev_state_bytes: Vec<AB::Expr> = local
+            .round
+            .work_vars
+            .iter()
+            .flat_map(|var| (0..SHA512_WORD_BYTES).map(|m| compose_byte::<AB::Expr>(var, m)))
+            .collect();
+        let new_state_bytes: Vec<AB::Expr> = local
+            .digest
+            .state_bytes
+            .iter()
+            .flatten()
+            .map(|&byte| byte.into())
+            .collect();
+        for (i, (prev_word, new_word)) in prev_state_bytes
+            .chunks_exact(SHA512_WORD_SIZE)
+            .zip(new_state_bytes.chunks_exact(SHA512_WORD_SIZE))
+            .enumerate()
+        {
+            let address = MemoryAddress::new(
+                AB::F::from_canonical_u32(RV32_MEMORY_AS),
+                state_ptr.clone() + AB::F::from_canonical_usize(i * SHA512_WORD_SIZE),
+            );
+            let offset = AB::F::from_canonical_usize(i);
+            self.memory_bridge
+                .read(
+                    address.clone(),
+                    from_fn::<_, SHA512_WORD_SIZE, _>(|j| prev_word[j].clone()),
+                    state_read_timestamp.clone() + offset,
+                    &local.mem_oc.state_reads[i],
+                )
+                .eval(builder, is_first_round);
+            self.memory_bridge
+                .write(
+                    address,
+                    from_fn::<_, SHA512_WORD_SIZE, _>(|j| new_word[j].clone()),
+                    state_write_timestamp.clone() + offset,
+                    &local.mem_oc.state_writes[i],
+                )
+                .eval(builder, is_digest_row);
+        }
+
+        // Round `t` reads `W[t]` for `t < 16`, which is big-endian in memory
+        let is_block_read = local.flags.round_flags[..SHA512_BLOCK_WORDS]
+            .iter()
+            .fold(AB::Expr::ZERO, |acc, &flag| acc + flag);
+        let round_idx: AB::Expr = local.flags.round_idx();
+        let w0_be_bytes: Vec<AB::Expr> = (0..SHA512_WORD_BYTES)
+            .rev()
+            .map(|m| compose_byte::<AB::Expr>(&local.round.w0_bits, m))
+            .collect();
+        for (i, word) in w0_be_bytes.chunks_exact(SHA512_WORD_SIZE).enumerate() {
+            let offset = AB::F::from_canonical_usize(i);
+            self.memory_bridge
+                .read(
+                    MemoryAddress::new(
+                        AB::F::from_canonical_u32(RV32_MEMORY_AS),
+                        block_ptr.clone()
+                            + round_idx.clone() * AB::F::from_canonical_usize(SHA512_WORD_BYTES)
+                            + offset * AB::F::from_canonical_usize(SHA512_WORD_SIZE),
+                    ),
+                    from_fn::<_, SHA512_WORD_SIZE, _>(|j| word[j].clone()),
+                    block_read_timestamp.clone()
+                        + round_idx.clone() * AB::F::from_canonical_usize(SHA512_BLOCK_WORD_READS)
+                        + offset,
+                    &local.mem_oc.block_reads[i],
+                )
+                .eval(builder, is_block_read.clone());
+        }
+    }
+}
+
+/// The `k`-th `u16` limb of a word given as bits, least significant bit first.
+fn compose_limb<E: AbstractField>(bits: &[impl Into<E> + Copy], k: usize) -> E {
+    compose_bits(&bits[16 * k..16 * (k + 1)])
+}
+
+/// The `m`-th byte of a word given as bits, least significant bit first.
+fn compose_byte<E: AbstractField>(bits: &[impl Into<E> + Copy], m: usize) -> E {
+    compose_bits(&bits[8 * m..8 * (m + 1)])
+}
+
+fn compose_bits<E: AbstractField>(bits: &[impl Into<E> + Copy]) -> E {
+    bits.iter()
+        .rev()
+        .fold(E::ZERO, |acc, &bit| acc.double() + bit.into())
+}
+
+/// `x ^ y ^ z` for booleans. Degree 3.
+fn xor3<E: AbstractField>(x: impl Into<E>, y: impl Into<E>, z: impl Into<E>) -> E {
+    let (x, y, z) = (x.into(), y.into(), z.into());
+    let xy = x.clone() * y.clone();
+    x.clone() + y.clone() + z.clone() - (xy.clone() + (x + y) * z.clone()).double()
+        + xy * z * E::from_canonical_u32(4)
+}
+
+/// `Maj(x, y, z)` for booleans. Degree 3.
+fn maj<E: AbstractField>(x: impl Into<E>, y: impl Into<E>, z: impl Into<E>) -> E {
+    let (x, y, z) = (x.into(), y.into(), z.into());
+    let xy = x.clone() * y.clone();
+    xy.clone() + (x + y) * z.clone() - (xy * z).double()
+}
+
+/// `Ch(x, y, z)` for booleans. Degree 2.
+fn ch<E: AbstractField>(x: impl Into<E>, y: impl Into<E>, z: impl Into<E>) -> E {
+    let (x, y, z) = (x.into(), y.into(), z.into());
+    x.clone() * y + z.clone() - x * z
+}
@@ -0,0 +1,154 @@
+use core::mem::size_of;
+
+use openvm_circuit::system::memory::offline_checker::{MemoryReadAuxCols, MemoryWriteAuxCols};
+use openvm_circuit_primitives::utils::assert_array_eq;
+use openvm_circuit_primitives_derive::AlignedBorrow;
+use openvm_instructions::riscv::RV32_REGISTER_NUM_LIMBS;
+use openvm_stark_backend::{p3_air::AirBuilder, p3_field::AbstractField};
+
+use super::{
+    SHA512_BLOCK_WORDS, SHA512_BLOCK_WORD_READS, SHA512_REGISTER_READS, SHA512_ROUNDS,
+    SHA512_STATE_WORDS, SHA512_STATE_WORD_ACCESSES, SHA512_WORD_BITS, SHA512_WORD_BYTES,
+    SHA512_WORD_SIZE, SHA512_WORD_U16S,
+};
+
+/// Each block takes [SHA512_ROWS_PER_BLOCK](super::SHA512_ROWS_PER_BLOCK) rows: one row per
+/// round, followed by a digest row which adds the working variables to the previous state.
+#[repr(C)]
+#[derive(Debug, AlignedBorrow)]
+pub struct Sha512VmCols<T> {
+    pub flags: Sha512FlagCols<T>,
+    /// Columns for instruction interface and register access
+    pub instruction: Sha512InstructionCols<T>,
+    pub round: Sha512RoundCols<T>,
+    pub digest: Sha512DigestCols<T>,
+    /// Auxiliary columns for offline memory checking
+    pub mem_oc: Sha512MemoryCols<T>,
+}
+
+/// At most one flag is set on each row. No flag is set on dummy rows.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, AlignedBorrow)]
+pub struct Sha512FlagCols<T> {
+    /// `round_flags[t]` is set on the row of round `t`
+    pub round_flags: [T; SHA512_ROUNDS],
+    pub is_digest_row: T,
+}
+
+/// Columns for SHA512_COMPRESS instruction parsing. They are the same on all rows of a block.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, AlignedBorrow)]
+pub struct Sha512InstructionCols<T> {
+    /// Program counter
+    pub pc: T,
+    /// The timestamp of the first register read
+    pub start_timestamp: T,
+    /// Pointer to address space 1 `rd` register
+    pub rd_ptr: T,
+    /// Pointer to address space 1 `rs1` register
+    pub rs1_ptr: T,
+    /// `state_ptr <- [rd_ptr:4]_1`
+    pub state_ptr: [T; RV32_REGISTER_NUM_LIMBS],
+    /// `block_ptr <- [rs1_ptr:4]_1`
+    pub block_ptr: [T; RV32_REGISTER_NUM_LIMBS],
+    /// The state before the compression, as `u16` limbs of each word
+    pub prev_state: [[T; SHA512_WORD_U16S]; SHA512_STATE_WORDS],
+}
+
+/// Bits are little-endian, i.e., least significant bit first.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, AlignedBorrow)]
+pub struct Sha512RoundCols<T> {
+    /// Bits of the working variables `a, ..., h` before this round
+    pub work_vars: [[T; SHA512_WORD_BITS]; SHA512_STATE_WORDS],
+    /// Bits of `Σ0(a)`
+    pub big_sig0: [T; SHA512_WORD_BITS],
+    /// Bits of `Σ1(e)`
+    pub big_sig1: [T; SHA512_WORD_BITS],
+    /// Bits of `Maj(a, b, c)`
+    pub maj: [T; SHA512_WORD_BITS],
+    /// The message schedule words `W[t], ..., W[t + 15]` as `u16` limbs, where `t` is the round
+    pub w: [[T; SHA512_WORD_U16S]; SHA512_BLOCK_WORDS],
+    /// Bits of `W[t]`
+    pub w0_bits: [T; SHA512_WORD_BITS],
+    /// Bits of `W[t + 1]`
+    pub w1_bits: [T; SHA512_WORD_BITS],
+    /// Bits of `W[t + 14]`
+    pub w14_bits: [T; SHA512_WORD_BITS],
+    /// Bits of `σ0(W[t + 1])`
+    pub small_sig0: [T; SHA512_WORD_BITS],
+    /// Bits of `σ1(W[t + 14])`
+    pub small_sig1: [T; SHA512_WORD_BITS],
+    /// Carries of the `u16` limb additions computing `a` and `e` of the next round and
+    /// `W[t + 16]`. Range checked to 8 bits.
+    pub carry_a: [T; SHA512_WORD_U16S],
+    pub carry_e: [T; SHA512_WORD_U16S],
+    pub carry_w: [T; SHA512_WORD_U16S],
+}
+
+/// Only used on the digest row.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, AlignedBorrow)]
+pub struct Sha512DigestCols<T> {
+    /// The new state as little-endian bytes of each word. Range checked to 8 bits.
+    pub state_bytes: [[T; SHA512_WORD_BYTES]; SHA512_STATE_WORDS],
+    /// Boolean carries of the `u16` limb additions of the previous state and the working
+    /// variables
+    pub carry: [[T; SHA512_WORD_U16S]; SHA512_STATE_WORDS],
+}
+
+#[repr(C)]
+#[derive(Clone, Debug, AlignedBorrow)]
+pub struct Sha512MemoryCols<T> {
+    /// Only used on the first round
+    pub register_aux: [MemoryReadAuxCols<T, RV32_REGISTER_NUM_LIMBS>; SHA512_REGISTER_READS],
+    /// Only used on the first round
+    pub state_reads: [MemoryReadAuxCols<T, SHA512_WORD_SIZE>; SHA512_STATE_WORD_ACCESSES],
+    /// Reads of `W[t]` on the first 16 rounds
+    pub block_reads: [MemoryReadAuxCols<T, SHA512_WORD_SIZE>; SHA512_BLOCK_WORD_READS],
+    /// Only used on the digest row
+    pub state_writes: [MemoryWriteAuxCols<T, SHA512_WORD_SIZE>; SHA512_STATE_WORD_ACCESSES],
+}
+
+impl<T: Copy> Sha512InstructionCols<T> {
+    pub fn assert_eq<AB: AirBuilder>(&self, builder: &mut AB, other: Self)
+    where
+        T: Into<AB::Expr>,
+    {
+        builder.assert_eq(self.pc, other.pc);
+        builder.assert_eq(self.start_timestamp, other.start_timestamp);
+        builder.assert_eq(self.rd_ptr, other.rd_ptr);
+        builder.assert_eq(self.rs1_ptr, other.rs1_ptr);
+        assert_array_eq(builder, self.state_ptr, other.state_ptr);
+        assert_array_eq(builder, self.block_ptr, other.block_ptr);
+        for (word, other_word) in self.prev_state.into_iter().zip(other.prev_state) {
+            assert_array_eq(builder, word, other_word);
+        }
+    }
+}
+
+impl<T: Copy> Sha512FlagCols<T> {
+    /// Whether this row is a round row. Degree 1.
+    pub fn is_round<E: AbstractField + From<T>>(&self) -> E {
+        self.round_flags
+            .iter()
+            .fold(E::ZERO, |acc, &flag| acc + flag.into())
+    }
+
+    /// Whether this row belongs to a block. Degree 1.
+    pub fn is_enabled<E: AbstractField + From<T>>(&self) -> E {
+        self.is_round::<E>() + self.is_digest_row.into()
+    }
+
+    /// The round index on round rows and 0 otherwise. Degree 1.
+    pub fn round_idx<E: AbstractField + From<T>>(&self) -> E {
+        self.round_flags
+            .iter()
+            .enumerate()
+            .fold(E::ZERO, |acc, (t, &flag)| {
+                acc + E::from(flag) * E::from_canonical_usize(t)
+            })
+    }
+}
+
+pub const NUM_SHA512_VM_COLS: usize = size_of::<Sha512VmCols<u8>>();
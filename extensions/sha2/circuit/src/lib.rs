@@ -0,0 +1,226 @@
+//! SHA-512 compression of one 128-byte block into a state in VM memory. The guest pads the
+//! message and chains the compressions itself, which gives both SHA-512 and SHA-384.
+use std::{array::from_fn, sync::Arc};
+
+use openvm_circuit::{
+    arch::{ExecutionBridge, ExecutionBus, ExecutionError, ExecutionState, InstructionExecutor},
+    system::{
+        memory::{MemoryControllerRef, MemoryReadRecord, MemoryWriteRecord},
+        program::ProgramBus,
+    },
+};
+use openvm_circuit_primitives::bitwise_op_lookup::BitwiseOperationLookupChip;
+use openvm_instructions::{
+    instruction::Instruction, program::DEFAULT_PC_STEP, riscv::RV32_REGISTER_NUM_LIMBS, UsizeOpcode,
+};
+use openvm_rv32im_circuit::adapters::read_rv32_register;
+use openvm_sha2_guest::{sha512_compress, SHA512_BLOCK_BYTES};
+use openvm_sha2_transpiler::Rv32Sha512Opcode;
+use openvm_stark_backend::p3_field::PrimeField32;
+
+pub mod air;
+pub mod columns;
+pub mod trace;
+pub mod utils;
+
+mod extension;
+pub use extension::*;
+
+#[cfg(test)]
+mod tests;
+
+pub use air::Sha512VmAir;
+
+// ==== Constants for register/memory adapter ====
+/// Register reads to get the state pointer and the block pointer
+const SHA512_REGISTER_READS: usize = 2;
+/// Number of cells to read/write in a single memory access
+const SHA512_WORD_SIZE: usize = 4;
+/// Memory accesses to read or write the state
+const SHA512_STATE_WORD_ACCESSES: usize = SHA512_STATE_BYTES / SHA512_WORD_SIZE;
+/// Memory reads of a message word per round, on the first 16 rounds
+const SHA512_BLOCK_WORD_READS: usize = SHA512_WORD_BYTES / SHA512_WORD_SIZE;
+/// Amount to advance the timestamp by after execution of one SHA512_COMPRESS instruction
+const SHA512_TIMESTAMP_CHANGE: usize =
+    SHA512_REGISTER_READS + 2 * SHA512_STATE_WORD_ACCESSES + SHA512_BLOCK_BYTES / SHA512_WORD_SIZE;
+
+// ==== Do not change these constants! ====
+/// Number of bits in a word.
+pub const SHA512_WORD_BITS: usize = 64;
+/// Number of bytes in a word.
+pub const SHA512_WORD_BYTES: usize = SHA512_WORD_BITS / 8;
+/// Number of 16-bit limbs in a word.
+pub const SHA512_WORD_U16S: usize = SHA512_WORD_BITS / 16;
+/// Number of words in the state.
+pub const SHA512_STATE_WORDS: usize = 8;
+/// Number of bytes in the state.
+pub const SHA512_STATE_BYTES: usize = SHA512_STATE_WORDS * SHA512_WORD_BYTES;
+/// Number of words in a block.
+pub const SHA512_BLOCK_WORDS: usize = SHA512_BLOCK_BYTES / SHA512_WORD_BYTES;
+/// Number of rounds of the compression function.
+pub const SHA512_ROUNDS: usize = 80;
+/// Number of trace rows per block: one per round and a digest row.
+pub const SHA512_ROWS_PER_BLOCK: usize = SHA512_ROUNDS + 1;
+
+#[derive(Debug)]
+pub struct Sha512VmChip<F: PrimeField32> {
+    pub air: Sha512VmAir,
+    /// IO and memory data necessary for each opcode call
+    pub records: Vec<Sha512Record<F>>,
+    pub memory_controller: MemoryControllerRef<F>,
+    pub bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<8>>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Sha512Record<F> {
+    pub from_state: ExecutionState<u32>,
+    pub state_ptr_read: MemoryReadRecord<F, RV32_REGISTER_NUM_LIMBS>,
+    pub block_ptr_read: MemoryReadRecord<F, RV32_REGISTER_NUM_LIMBS>,
+    pub state_reads: [MemoryReadRecord<F, SHA512_WORD_SIZE>; SHA512_STATE_WORD_ACCESSES],
+    /// Reads of the block in order, [SHA512_BLOCK_WORD_READS] per message word
+    pub block_reads:
+        [MemoryReadRecord<F, SHA512_WORD_SIZE>; SHA512_BLOCK_WORDS * SHA512_BLOCK_WORD_READS],
+    pub state_writes: [MemoryWriteRecord<F, SHA512_WORD_SIZE>; SHA512_STATE_WORD_ACCESSES],
+}
+
+impl<F: PrimeField32> Sha512VmChip<F> {
+    pub fn new(
+        execution_bus: ExecutionBus,
+        program_bus: ProgramBus,
+        memory_controller: MemoryControllerRef<F>,
+        bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<8>>,
+        offset: usize,
+    ) -> Self {
+        let ptr_max_bits = memory_controller.borrow().mem_config().pointer_max_bits;
+        let memory_bridge = memory_controller.borrow().memory_bridge();
+        Self {
+            air: Sha512VmAir::new(
+                ExecutionBridge::new(execution_bus, program_bus),
+                memory_bridge,
+                bitwise_lookup_chip.bus(),
+                ptr_max_bits,
+                offset,
+            ),
+            records: Vec::new(),
+            memory_controller,
+            bitwise_lookup_chip,
+        }
+    }
+}
+
+impl<F: PrimeField32> InstructionExecutor<F> for Sha512VmChip<F> {
+    fn execute(
+        &mut self,
+        instruction: Instruction<F>,
+        from_state: ExecutionState<u32>,
+    ) -> Result<ExecutionState<u32>, ExecutionError> {
+        let Instruction {
+            opcode, a, b, d, e, ..
+        } = instruction;
+        let local_opcode = Rv32Sha512Opcode::from_usize(opcode.local_opcode_idx(self.air.offset));
+        debug_assert_eq!(local_opcode, Rv32Sha512Opcode::SHA512_COMPRESS);
+
+        let mut memory = self.memory_controller.borrow_mut();
+        debug_assert_eq!(from_state.timestamp, memory.timestamp());
+
+        let (state_ptr_read, state_ptr) = read_rv32_register(&mut memory, d, a);
+        let (block_ptr_read, block_ptr) = read_rv32_register(&mut memory, d, b);
+        let (state_ptr, block_ptr) = (state_ptr as usize, block_ptr as usize);
+        assert!(state_ptr + SHA512_STATE_BYTES <= (1 << self.air.ptr_max_bits));
+        assert!(block_ptr + SHA512_BLOCK_BYTES <= (1 << self.air.ptr_max_bits));
+
+        let state_reads: [_; SHA512_STATE_WORD_ACCESSES] = from_fn(|i| {
+            memory.read::<SHA512_WORD_SIZE>(
+                e,
+                F::from_canonical_usize(state_ptr + i * SHA512_WORD_SIZE),
+            )
+        });
+        let block_reads: [_; SHA512_BLOCK_WORDS * SHA512_BLOCK_WORD_READS] = from_fn(|i| {
+            memory.read::<SHA512_WORD_SIZE>(
+                e,
+                F::from_canonical_usize(block_ptr + i * SHA512_WORD_SIZE),
+            )
+        });
+
+        let mut state = [0u64; SHA512_STATE_WORDS];
+        for (i, byte) in state_reads.iter().flat_map(|read| read.data).enumerate() {
+            state[i / SHA512_WORD_BYTES] |= (to_byte(byte) as u64) << (8 * (i % SHA512_WORD_BYTES));
+        }
+        let mut block = [0u8; SHA512_BLOCK_BYTES];
+        for (i, byte) in block_reads.iter().flat_map(|read| read.data).enumerate() {
+            block[i] = to_byte(byte);
+        }
+        sha512_compress(&mut state, &block);
+        tracing::trace!("[runtime] sha512 compress output: {:x?}", state);
+
+        let output: Vec<u8> = state.iter().flat_map(|word| word.to_le_bytes()).collect();
+        let state_writes: [_; SHA512_STATE_WORD_ACCESSES] = from_fn(|i| {
+            memory.write::<SHA512_WORD_SIZE>(
+                e,
+                F::from_canonical_usize(state_ptr + i * SHA512_WORD_SIZE),
+                from_fn(|j| F::from_canonical_u8(output[i * SHA512_WORD_SIZE + j])),
+            )
+        });
+
+        self.records.push(Sha512Record {
+            from_state,
+            state_ptr_read,
+            block_ptr_read,
+            state_reads,
+            block_reads,
+            state_writes,
+        });
+
+        // NOTE: Check this is consistent with the timestamp change in Sha512VmAir
+        let to_timestamp = from_state.timestamp + SHA512_TIMESTAMP_CHANGE as u32;
+        debug_assert_eq!(to_timestamp, memory.timestamp());
+
+        Ok(ExecutionState {
+            pc: from_state.pc + DEFAULT_PC_STEP,
+            timestamp: to_timestamp,
+        })
+    }
+
+    fn get_opcode_name(&self, _: usize) -> String {
+        "SHA512_COMPRESS".to_string()
+    }
+}
+
+fn to_byte<F: PrimeField32>(cell: F) -> u8 {
+    cell.as_canonical_u32()
+        .try_into()
+        .expect("Memory cell not a byte")
+}
+
+impl<F: PrimeField32> Sha512Record<F> {
+    /// The state before the compression.
+    pub fn prev_state(&self) -> [u64; SHA512_STATE_WORDS] {
+        let mut state = [0u64; SHA512_STATE_WORDS];
+        for (i, byte) in self
+            .state_reads
+            .iter()
+            .flat_map(|read| read.data)
+            .enumerate()
+        {
+            state[i / SHA512_WORD_BYTES] |=
+                (byte.as_canonical_u32() as u64) << (8 * (i % SHA512_WORD_BYTES));
+        }
+        state
+    }
+
+    /// The message words of the block, which is big-endian.
+    pub fn message(&self) -> [u64; SHA512_BLOCK_WORDS] {
+        let bytes: Vec<u8> = self
+            .block_reads
+            .iter()
+            .flat_map(|read| read.data.map(|byte| byte.as_canonical_u32() as u8))
+            .collect();
+        from_fn(|i| {
+            u64::from_be_bytes(
+                bytes[i * SHA512_WORD_BYTES..(i + 1) * SHA512_WORD_BYTES]
+                    .try_into()
+                    .unwrap(),
+            )
+        })
+    }
+}
@@ -0,0 +1,169 @@
+use std::{borrow::BorrowMut, sync::Arc};
+
+use openvm_circuit_primitives::utils::next_power_of_two_or_zero;
+use openvm_instructions::riscv::{RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS};
+use openvm_sha2_guest::SHA512_K;
+use openvm_stark_backend::{
+    config::{StarkGenericConfig, Val},
+    p3_air::BaseAir,
+    p3_field::{AbstractField, PrimeField32},
+    p3_matrix::dense::RowMajorMatrix,
+    p3_maybe_rayon::prelude::*,
+    prover::types::AirProofInput,
+    rap::{get_air_name, AnyRap},
+    Chip, ChipUsageGetter,
+};
+
+use super::{
+    columns::{Sha512InstructionCols, Sha512RoundCols, Sha512VmCols},
+    utils::*,
+    Sha512VmChip, SHA512_BLOCK_WORDS, SHA512_BLOCK_WORD_READS, SHA512_ROUNDS,
+    SHA512_ROWS_PER_BLOCK, SHA512_STATE_WORDS,
+};
+
+impl<SC: StarkGenericConfig> Chip<SC> for Sha512VmChip<Val<SC>>
+where
+    Val<SC>: PrimeField32,
+{
+    fn air(&self) -> Arc<dyn AnyRap<SC>> {
+        Arc::new(self.air)
+    }
+
+    fn generate_air_proof_input(self) -> AirProofInput<SC> {
+        let air = self.air();
+        let trace_width = self.trace_width();
+        let num_rows = next_power_of_two_or_zero(self.current_trace_height());
+        // Rows after the last block are dummy rows with no flags set.
+        let mut trace =
+            RowMajorMatrix::new(Val::<SC>::zero_vec(num_rows * trace_width), trace_width);
+
+        let aux_cols_factory = self.memory_controller.borrow().aux_cols_factory();
+        let limb_shift_bits = RV32_CELL_BITS * RV32_REGISTER_NUM_LIMBS - self.air.ptr_max_bits;
+        let block_width = trace_width * SHA512_ROWS_PER_BLOCK;
+
+        trace.values[..self.records.len() * block_width]
+            .par_chunks_mut(block_width)
+            .zip(self.records.par_iter())
+            .for_each(|(rows, record)| {
+                let prev_state = record.prev_state();
+                let instruction = Sha512InstructionCols {
+                    pc: Val::<SC>::from_canonical_u32(record.from_state.pc),
+                    start_timestamp: Val::<SC>::from_canonical_u32(record.from_state.timestamp),
+                    rd_ptr: record.state_ptr_read.pointer,
+                    rs1_ptr: record.block_ptr_read.pointer,
+                    state_ptr: record.state_ptr_read.data,
+                    block_ptr: record.block_ptr_read.data,
+                    prev_state: prev_state.map(u64_to_u16_limbs),
+                };
+
+                for (t, (row, (vars, w))) in rows
+                    .chunks_exact_mut(trace_width)
+                    .zip(sha512_rows(prev_state, record.message()))
+                    .enumerate()
+                {
+                    let cols: &mut Sha512VmCols<Val<SC>> = row.borrow_mut();
+                    cols.instruction = instruction;
+                    generate_round_cols(&mut cols.round, vars, w);
+
+                    if t < SHA512_ROUNDS {
+                        cols.flags.round_flags[t] = Val::<SC>::ONE;
+
+                        let [a, b, c, d, e, f, g, h] = vars;
+                        let t1 = [h, big_sigma1(e), ch(e, f, g), SHA512_K[t], w[0]];
+                        let t2 = [big_sigma0(a), maj(a, b, c)];
+                        let carry_a = u16_limb_carries(&[&t1[..], &t2[..]].concat());
+                        let carry_e = u16_limb_carries(&[&[d][..], &t1[..]].concat());
+                        let carry_w = u16_limb_carries(&[
+                            small_sigma1(w[14]),
+                            w[9],
+                            small_sigma0(w[1]),
+                            w[0],
+                        ]);
+                        cols.round.carry_a = carry_a.map(Val::<SC>::from_canonical_u64);
+                        cols.round.carry_e = carry_e.map(Val::<SC>::from_canonical_u64);
+                        cols.round.carry_w = carry_w.map(Val::<SC>::from_canonical_u64);
+                        let carries: Vec<_> = [carry_a, carry_e, carry_w].concat();
+                        for pair in carries.chunks_exact(2) {
+                            self.bitwise_lookup_chip
+                                .request_range(pair[0] as u32, pair[1] as u32);
+                        }
+                    } else {
+                        cols.flags.is_digest_row = Val::<SC>::ONE;
+
+                        for (j, (&prev_word, &var)) in prev_state.iter().zip(&vars).enumerate() {
+                            let new_bytes = prev_word.wrapping_add(var).to_le_bytes();
+                            cols.digest.state_bytes[j] =
+                                new_bytes.map(Val::<SC>::from_canonical_u8);
+                            cols.digest.carry[j] = u16_limb_carries(&[prev_word, var])
+                                .map(Val::<SC>::from_canonical_u64);
+                            for pair in new_bytes.chunks_exact(2) {
+                                self.bitwise_lookup_chip
+                                    .request_range(pair[0] as u32, pair[1] as u32);
+                            }
+                        }
+                        for (i, write) in record.state_writes.iter().enumerate() {
+                            cols.mem_oc.state_writes[i] =
+                                aux_cols_factory.make_write_aux_cols(*write);
+                        }
+                    }
+
+                    if t < SHA512_BLOCK_WORDS {
+                        let reads = &record.block_reads
+                            [t * SHA512_BLOCK_WORD_READS..(t + 1) * SHA512_BLOCK_WORD_READS];
+                        for (aux, read) in cols.mem_oc.block_reads.iter_mut().zip(reads) {
+                            *aux = aux_cols_factory.make_read_aux_cols(*read);
+                        }
+                    }
+                }
+
+                let first_row: &mut Sha512VmCols<Val<SC>> = rows[..trace_width].borrow_mut();
+                first_row.mem_oc.register_aux = [record.state_ptr_read, record.block_ptr_read]
+                    .map(|read| aux_cols_factory.make_read_aux_cols(read));
+                for (i, read) in record.state_reads.iter().enumerate() {
+                    first_row.mem_oc.state_reads[i] = aux_cols_factory.make_read_aux_cols(*read);
+                }
+                self.bitwise_lookup_chip.request_range(
+                    record.state_ptr_read.data[RV32_REGISTER_NUM_LIMBS - 1].as_canonical_u32()
+                        << limb_shift_bits,
+                    record.block_ptr_read.data[RV32_REGISTER_NUM_LIMBS - 1].as_canonical_u32()
+                        << limb_shift_bits,
+                );
+            });
+
+        AirProofInput::simple_no_pis(air, trace)
+    }
+}
+
+/// Fills the bits of the working variables and the message schedule window, and the values of
+/// the functions computed from them. The carries are filled separately.
+fn generate_round_cols<F: PrimeField32>(
+    round: &mut Sha512RoundCols<F>,
+    vars: [u64; SHA512_STATE_WORDS],
+    w: [u64; SHA512_BLOCK_WORDS],
+) {
+    let [a, b, c, _, e, ..] = vars;
+    round.work_vars = vars.map(u64_to_bits);
+    round.big_sig0 = u64_to_bits(big_sigma0(a));
+    round.big_sig1 = u64_to_bits(big_sigma1(e));
+    round.maj = u64_to_bits(maj(a, b, c));
+    round.w = w.map(u64_to_u16_limbs);
+    round.w0_bits = u64_to_bits(w[0]);
+    round.w1_bits = u64_to_bits(w[1]);
+    round.w14_bits = u64_to_bits(w[14]);
+    round.small_sig0 = u64_to_bits(small_sigma0(w[1]));
+    round.small_sig1 = u64_to_bits(small_sigma1(w[14]));
+}
+
+impl<F: PrimeField32> ChipUsageGetter for Sha512VmChip<F> {
+    fn air_name(&self) -> String {
+        get_air_name(&self.air)
+    }
+
+    fn current_trace_height(&self) -> usize {
+        self.records.len() * SHA512_ROWS_PER_BLOCK
+    }
+
+    fn trace_width(&self) -> usize {
+        BaseAir::<F>::width(&self.air)
+    }
+}
@@ -0,0 +1,81 @@
+use std::array::from_fn;
+
+use openvm_sha2_guest::SHA512_K;
+use openvm_stark_backend::p3_field::AbstractField;
+
+use crate::{
+    SHA512_BLOCK_WORDS, SHA512_ROUNDS, SHA512_STATE_WORDS, SHA512_WORD_BITS, SHA512_WORD_U16S,
+};
+
+pub fn big_sigma0(x: u64) -> u64 {
+    x.rotate_right(28) ^ x.rotate_right(34) ^ x.rotate_right(39)
+}
+
+pub fn big_sigma1(x: u64) -> u64 {
+    x.rotate_right(14) ^ x.rotate_right(18) ^ x.rotate_right(41)
+}
+
+pub fn small_sigma0(x: u64) -> u64 {
+    x.rotate_right(1) ^ x.rotate_right(8) ^ (x >> 7)
+}
+
+pub fn small_sigma1(x: u64) -> u64 {
+    x.rotate_right(19) ^ x.rotate_right(61) ^ (x >> 6)
+}
+
+pub fn ch(x: u64, y: u64, z: u64) -> u64 {
+    (x & y) ^ (!x & z)
+}
+
+pub fn maj(x: u64, y: u64, z: u64) -> u64 {
+    (x & y) ^ (x & z) ^ (y & z)
+}
+
+/// The bits of `x`, least significant bit first.
+pub fn u64_to_bits<F: AbstractField>(x: u64) -> [F; SHA512_WORD_BITS] {
+    from_fn(|i| F::from_canonical_u64((x >> i) & 1))
+}
+
+/// The `u16` limbs of `x`, least significant limb first.
+pub fn u64_to_u16_limbs<F: AbstractField>(x: u64) -> [F; SHA512_WORD_U16S] {
+    from_fn(|k| F::from_canonical_u64((x >> (16 * k)) & 0xffff))
+}
+
+/// The state of each row of a block: the working variables before round `t` and the message
+/// schedule window `W[t], ..., W[t + 15]`, for `t` in `0..=SHA512_ROUNDS`.
+pub fn sha512_rows(
+    prev_state: [u64; SHA512_STATE_WORDS],
+    message: [u64; SHA512_BLOCK_WORDS],
+) -> Vec<([u64; SHA512_STATE_WORDS], [u64; SHA512_BLOCK_WORDS])> {
+    let mut rows = Vec::with_capacity(SHA512_ROUNDS + 1);
+    let (mut vars, mut w) = (prev_state, message);
+    for k in SHA512_K {
+        rows.push((vars, w));
+        let [a, b, c, d, e, f, g, h] = vars;
+        let t1 = h
+            .wrapping_add(big_sigma1(e))
+            .wrapping_add(ch(e, f, g))
+            .wrapping_add(k)
+            .wrapping_add(w[0]);
+        let t2 = big_sigma0(a).wrapping_add(maj(a, b, c));
+        vars = [t1.wrapping_add(t2), a, b, c, d.wrapping_add(t1), e, f, g];
+        let w16 = small_sigma1(w[14])
+            .wrapping_add(w[9])
+            .wrapping_add(small_sigma0(w[1]))
+            .wrapping_add(w[0]);
+        w.rotate_left(1);
+        w[SHA512_BLOCK_WORDS - 1] = w16;
+    }
+    rows.push((vars, w));
+    rows
+}
+
+/// The carry out of each `u16` limb when adding `words` limb by limb.
+pub fn u16_limb_carries(words: &[u64]) -> [u64; SHA512_WORD_U16S] {
+    let mut carry = 0;
+    from_fn(|i| {
+        let limb_sum: u64 = words.iter().map(|word| (word >> (16 * i)) & 0xffff).sum();
+        carry = (limb_sum + carry) >> 16;
+        carry
+    })
+}
@@ -0,0 +1,246 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+/// This is custom-1 defined in RISC-V spec document
+pub const OPCODE: u8 = 0x2b;
+pub const SHA2_FUNCT3: u8 = 0b101;
+pub const SHA512_COMPRESS_FUNCT7: u8 = 0x0;
+
+/// Number of bytes in a SHA-512 message block.
+pub const SHA512_BLOCK_BYTES: usize = 128;
+
+/// Initial hash value of SHA-512.
+pub const SHA512_IV: [u64; 8] = [
+    0x6a09e667f3bcc908,
+    0xbb67ae8584caa73b,
+    0x3c6ef372fe94f82b,
+    0xa54ff53a5f1d36f1,
+    0x510e527fade682d1,
+    0x9b05688c2b3e6c1f,
+    0x1f83d9abfb41bd6b,
+    0x5be0cd19137e2179,
+];
+
+/// Initial hash value of SHA-384.
+pub const SHA384_IV: [u64; 8] = [
+    0xcbbb9d5dc1059ed8,
+    0x629a292a367cd507,
+    0x9159015a3070dd17,
+    0x152fecd8f70e5939,
+    0x67332667ffc00b31,
+    0x8eb44a8768581511,
+    0xdb0c2e0d64f98fa7,
+    0x47b5481dbefa4fa4,
+];
+
+/// Round constants of SHA-512 and SHA-384.
+pub const SHA512_K: [u64; 80] = [
+    0x428a2f98d728ae22,
+    0x7137449123ef65cd,
+    0xb5c0fbcfec4d3b2f,
+    0xe9b5dba58189dbbc,
+    0x3956c25bf348b538,
+    0x59f111f1b605d019,
+    0x923f82a4af194f9b,
+    0xab1c5ed5da6d8118,
+    0xd807aa98a3030242,
+    0x12835b0145706fbe,
+    0x243185be4ee4b28c,
+    0x550c7dc3d5ffb4e2,
+    0x72be5d74f27b896f,
+    0x80deb1fe3b1696b1,
+    0x9bdc06a725c71235,
+    0xc19bf174cf692694,
+    0xe49b69c19ef14ad2,
+    0xefbe4786384f25e3,
+    0x0fc19dc68b8cd5b5,
+    0x240ca1cc77ac9c65,
+    0x2de92c6f592b0275,
+    0x4a7484aa6ea6e483,
+    0x5cb0a9dcbd41fbd4,
+    0x76f988da831153b5,
+    0x983e5152ee66dfab,
+    0xa831c66d2db43210,
+    0xb00327c898fb213f,
+    0xbf597fc7beef0ee4,
+    0xc6e00bf33da88fc2,
+    0xd5a79147930aa725,
+    0x06ca6351e003826f,
+    0x142929670a0e6e70,
+    0x27b70a8546d22ffc,
+    0x2e1b21385c26c926,
+    0x4d2c6dfc5ac42aed,
+    0x53380d139d95b3df,
+    0x650a73548baf63de,
+    0x766a0abb3c77b2a8,
+    0x81c2c92e47edaee6,
+    0x92722c851482353b,
+    0xa2bfe8a14cf10364,
+    0xa81a664bbc423001,
+    0xc24b8b70d0f89791,
+    0xc76c51a30654be30,
+    0xd192e819d6ef5218,
+    0xd69906245565a910,
+    0xf40e35855771202a,
+    0x106aa07032bbd1b8,
+    0x19a4c116b8d2d0c8,
+    0x1e376c085141ab53,
+    0x2748774cdf8eeb99,
+    0x34b0bcb5e19b48a8,
+    0x391c0cb3c5c95a63,
+    0x4ed8aa4ae3418acb,
+    0x5b9cca4f7763e373,
+    0x682e6ff3d6b2b8a3,
+    0x748f82ee5defb2fc,
+    0x78a5636f43172f60,
+    0x84c87814a1f0ab72,
+    0x8cc702081a6439ec,
+    0x90befffa23631e28,
+    0xa4506cebde82bde9,
+    0xbef9a3f7b2c67915,
+    0xc67178f2e372532b,
+    0xca273eceea26619c,
+    0xd186b8c721c0c207,
+    0xeada7dd6cde0eb1e,
+    0xf57d4f7fee6ed178,
+    0x06f067aa72176fba,
+    0x0a637dc5a2c898a6,
+    0x113f9804bef90dae,
+    0x1b710b35131c471b,
+    0x28db77f523047d84,
+    0x32caab7b40c72493,
+    0x3c9ebe0a15c9bebc,
+    0x431d67c49c100d4c,
+    0x4cc5d4becb3e42b6,
+    0x597f299cfc657e2a,
+    0x5fcb6fab3ad6faec,
+    0x6c44198c4a475817,
+];
+
+/// The sha512 cryptographic hash function.
+#[inline(always)]
+pub fn sha512(input: &[u8]) -> [u8; 64] {
+    let mut output = [0u8; 64];
+    set_sha512(input, &mut output);
+    output
+}
+
+/// The sha384 cryptographic hash function.
+#[inline(always)]
+pub fn sha384(input: &[u8]) -> [u8; 48] {
+    let mut output = [0u8; 48];
+    set_sha384(input, &mut output);
+    output
+}
+
+/// Sets `output` to the sha512 hash of `input`.
+pub fn set_sha512(input: &[u8], output: &mut [u8; 64]) {
+    let state = sha512_digest(SHA512_IV, input);
+    for (chunk, word) in output.chunks_exact_mut(8).zip(state) {
+        chunk.copy_from_slice(&word.to_be_bytes());
+    }
+}
+
+/// Sets `output` to the sha384 hash of `input`.
+pub fn set_sha384(input: &[u8], output: &mut [u8; 48]) {
+    let state = sha512_digest(SHA384_IV, input);
+    for (chunk, word) in output.chunks_exact_mut(8).zip(state) {
+        chunk.copy_from_slice(&word.to_be_bytes());
+    }
+}
+
+/// Pads `input` and compresses it block by block starting from `iv`.
+fn sha512_digest(iv: [u64; 8], input: &[u8]) -> [u64; 8] {
+    let mut state = iv;
+    let mut blocks = input.chunks_exact(SHA512_BLOCK_BYTES);
+    for block in blocks.by_ref() {
+        sha512_compress(&mut state, block.try_into().unwrap());
+    }
+    let rem = blocks.remainder();
+    // The padding is a 1 bit, zeros, and the 128-bit big-endian bit length, so it takes one
+    // or two more blocks.
+    let mut last = [0u8; 2 * SHA512_BLOCK_BYTES];
+    last[..rem.len()].copy_from_slice(rem);
+    last[rem.len()] = 0x80;
+    let num_last = if rem.len() < SHA512_BLOCK_BYTES - 16 {
+        1
+    } else {
+        2
+    };
+    let last_len = num_last * SHA512_BLOCK_BYTES;
+    let bit_len = (input.len() as u128) * 8;
+    last[last_len - 16..last_len].copy_from_slice(&bit_len.to_be_bytes());
+    for block in last[..last_len].chunks_exact(SHA512_BLOCK_BYTES) {
+        sha512_compress(&mut state, block.try_into().unwrap());
+    }
+    state
+}
+
+/// Applies the SHA-512 compression function to `state` with the message `block`, including
+/// the addition of the previous state.
+#[inline(always)]
+pub fn sha512_compress(state: &mut [u64; 8], block: &[u8; SHA512_BLOCK_BYTES]) {
+    #[cfg(not(target_os = "zkvm"))]
+    {
+        let mut w = [0u64; 16];
+        for (w, chunk) in w.iter_mut().zip(block.chunks_exact(8)) {
+            *w = u64::from_be_bytes(chunk.try_into().unwrap());
+        }
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = *state;
+        for (t, k) in SHA512_K.iter().enumerate() {
+            if t >= 16 {
+                let w15 = w[(t + 1) % 16];
+                let w2 = w[(t + 14) % 16];
+                let s0 = w15.rotate_right(1) ^ w15.rotate_right(8) ^ (w15 >> 7);
+                let s1 = w2.rotate_right(19) ^ w2.rotate_right(61) ^ (w2 >> 6);
+                w[t % 16] = s1
+                    .wrapping_add(w[(t + 9) % 16])
+                    .wrapping_add(s0)
+                    .wrapping_add(w[t % 16]);
+            }
+            let s1 = e.rotate_right(14) ^ e.rotate_right(18) ^ e.rotate_right(41);
+            let ch = (e & f) ^ (!e & g);
+            let t1 = h
+                .wrapping_add(s1)
+                .wrapping_add(ch)
+                .wrapping_add(*k)
+                .wrapping_add(w[t % 16]);
+            let s0 = a.rotate_right(28) ^ a.rotate_right(34) ^ a.rotate_right(39);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            let t2 = s0.wrapping_add(maj);
+            h = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(t1);
+            d = c;
+            c = b;
+            b = a;
+            a = t1.wrapping_add(t2);
+        }
+        for (s, v) in state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+            *s = s.wrapping_add(v);
+        }
+    }
+    #[cfg(target_os = "zkvm")]
+    native_sha512_compress(state.as_mut_ptr(), block.as_ptr());
+}
+
+/// Native hook for the SHA-512 compression function.
+///
+/// # Safety
+///
+/// The VM updates the state in place.
+/// - `state` must point to 8 little-endian `u64` words that are 4-byte aligned.
+/// - `block` must point to a 128-byte message block.
+#[cfg(target_os = "zkvm")]
+#[inline(always)]
+#[no_mangle]
+extern "C" fn native_sha512_compress(state: *mut u64, block: *const u8) {
+    openvm_platform::custom_insn_r!(
+        OPCODE,
+        SHA2_FUNCT3,
+        SHA512_COMPRESS_FUNCT7,
+        state,
+        block,
+        "x0"
+    );
+}